@@ -85,6 +85,7 @@ async fn main() -> Result<()> {
             per_tool_timeout_ms: 30_000,
             refine_on_tool_result: true,
             max_tools_exposed: 64,
+            cache: true,
         };
 
         match orchestrator
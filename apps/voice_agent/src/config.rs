@@ -1,8 +1,11 @@
-use std::collections::HashMap;
 use std::fs;
 use std::path::{Path, PathBuf};
 
-use loom_audio::{MicConfig, SttConfig, VadConfig, WakeWordConfig};
+use loom_audio::{
+    BargeInConfig, MicConfig, PlaybackCaptureConfig, ResponseSpeakerConfig, SttConfig, VadConfig,
+    WakeMode, WakeWordConfig,
+};
+use loom_core::LlmClientConfig;
 
 /// High-level configuration for the Voice Agent demo
 #[derive(Clone, Debug)]
@@ -13,11 +16,14 @@ pub struct VoiceAgentConfig {
     pub wake: WakeWordConfig,
     pub llm: LlmConfig,
     pub tts: TtsConfig,
+    pub barge_in: BargeInSettings,
     /// Topic where user queries are published by Wake module
     pub query_topic: String,
+    /// Topic the assistant streams its replies to, spoken by the response speaker
+    pub reply_topic: String,
 }
 
-/// LLM client configuration (used to fill headers for llm.generate)
+/// LLM client configuration of the voice assistant agent
 #[derive(Clone, Debug)]
 pub struct LlmConfig {
     pub base_url: String,
@@ -28,7 +34,7 @@ pub struct LlmConfig {
     pub system_prompt: String,
 }
 
-/// Local TTS preferences (mapped to tts.speak headers)
+/// Local TTS preferences (mapped to tts.speak arguments)
 #[derive(Clone, Debug, Default)]
 pub struct TtsConfig {
     pub piper_bin: Option<String>,
@@ -37,6 +43,30 @@ pub struct TtsConfig {
     pub volume: Option<f32>,
     pub sample_rate: Option<u32>,
     pub player: Option<String>,
+    /// Shortest text spoken as a sentence while replies stream in
+    pub min_sentence_chars: Option<usize>,
+}
+
+/// Full-duplex settings: capture the agent's own playback and stop speaking when the
+/// user talks over it
+#[derive(Clone, Debug)]
+pub struct BargeInSettings {
+    /// Off by default; needs a loopback device (`VOICE_BARGE_IN=1`)
+    pub enabled: bool,
+    pub playback: PlaybackCaptureConfig,
+    pub detector: BargeInConfig,
+}
+
+impl Default for BargeInSettings {
+    fn default() -> Self {
+        Self {
+            enabled: std::env::var("VOICE_BARGE_IN")
+                .map(|v| matches!(v.trim(), "1" | "true" | "yes" | "on"))
+                .unwrap_or(false),
+            playback: PlaybackCaptureConfig::default(),
+            detector: BargeInConfig::default(),
+        }
+    }
 }
 
 impl Default for LlmConfig {
@@ -85,7 +115,9 @@ impl Default for VoiceAgentConfig {
             wake: WakeWordConfig::default(),
             llm: LlmConfig::default(),
             tts: TtsConfig::default(),
+            barge_in: BargeInSettings::default(),
             query_topic: std::env::var("QUERY_TOPIC").unwrap_or_else(|_| "query".to_string()),
+            reply_topic: ResponseSpeakerConfig::default().topic,
         }
     }
 }
@@ -132,48 +164,53 @@ impl VoiceAgentConfig {
         );
         default
     }
-    /// Build LLM headers for the `llm.generate` ActionCall based on config
-    pub fn llm_headers(&self) -> HashMap<String, String> {
-        let mut h = HashMap::new();
-        if !self.llm.model.is_empty() {
-            h.insert("model".into(), self.llm.model.clone());
-        }
-        if !self.llm.base_url.is_empty() {
-            h.insert("base_url".into(), self.llm.base_url.clone());
-        }
-        h.insert(
-            "request_timeout_ms".into(),
-            self.llm.request_timeout_ms.to_string(),
-        );
-        h.insert("temperature".into(), format!("{}", self.llm.temperature));
-        if let Some(key) = &self.llm.api_key {
-            if !key.is_empty() {
-                // Some backends accept Authorization separately; for simplicity we keep it here
-                h.insert("authorization".into(), format!("Bearer {}", key));
-            }
+    /// LLM client settings for the voice assistant agent
+    pub fn llm_client_config(&self) -> LlmClientConfig {
+        LlmClientConfig {
+            base_url: self.llm.base_url.clone(),
+            model: self.llm.model.clone(),
+            api_key: self.llm.api_key.clone().filter(|key| !key.is_empty()),
+            request_timeout_ms: self.llm.request_timeout_ms,
+            temperature: self.llm.temperature,
         }
-        h
     }
 
-    /// Build TTS headers for the `tts.speak` ActionCall based on config
-    pub fn tts_headers(&self) -> HashMap<String, String> {
-        let mut h = HashMap::new();
-        if let Some(v) = &self.tts.voice {
-            h.insert("voice".into(), v.clone());
+    /// Response speaker settings, with `tts.speak` preferences from the [tts] section
+    pub fn speaker_config(&self) -> ResponseSpeakerConfig {
+        let mut cfg = ResponseSpeakerConfig {
+            topic: self.reply_topic.clone(),
+            voice: self.tts.voice.clone(),
+            rate: self.tts.rate,
+            volume: self.tts.volume,
+            ..ResponseSpeakerConfig::default()
+        };
+        if let Some(chars) = self.tts.min_sentence_chars {
+            cfg.min_sentence_chars = chars;
+        }
+        if self.barge_in.enabled {
+            cfg.barge_in_topic = Some(self.barge_in.detector.topic.clone());
         }
-        if let Some(v) = self.tts.rate {
-            h.insert("rate".into(), format!("{}", v));
-        }
-        if let Some(v) = self.tts.volume {
-            h.insert("volume".into(), format!("{}", v));
-        }
-        if let Some(v) = self.tts.sample_rate {
-            h.insert("sample_rate".into(), v.to_string());
+        cfg
+    }
+
+    /// Barge-in detector settings, listening to the configured mic and playback topics
+    pub fn barge_in_config(&self) -> BargeInConfig {
+        BargeInConfig {
+            mic_topic: self.mic.topic.clone(),
+            playback_topic: self.barge_in.playback.topic.clone(),
+            ..self.barge_in.detector.clone()
         }
-        if let Some(v) = &self.tts.player {
-            h.insert("player".into(), v.clone());
+    }
+
+    /// VAD settings; with barge-in on and a clean topic set, VAD hears the echo-gated mic
+    pub fn vad_config(&self) -> VadConfig {
+        let mut cfg = self.vad.clone();
+        if self.barge_in.enabled {
+            if let Some(clean_topic) = &self.barge_in.detector.clean_topic {
+                cfg.input_topic = clean_topic.clone();
+            }
         }
-        h
+        cfg
     }
 
     /// Build TtsSpeakProviderConfig from voice_agent.toml [tts] section
@@ -215,12 +252,14 @@ impl VoiceAgentConfig {
 #[derive(Debug, Clone, Default, serde::Deserialize)]
 struct VoiceAgentToml {
     pub query_topic: Option<String>,
+    pub reply_topic: Option<String>,
     pub mic: Option<MicToml>,
     pub vad: Option<VadToml>,
     pub stt: Option<SttToml>,
     pub wake: Option<WakeToml>,
     pub llm: Option<LlmToml>,
     pub tts: Option<TtsToml>,
+    pub barge_in: Option<BargeInToml>,
 }
 
 impl VoiceAgentToml {
@@ -228,6 +267,9 @@ impl VoiceAgentToml {
         if let Some(q) = self.query_topic {
             base.query_topic = q;
         }
+        if let Some(r) = self.reply_topic {
+            base.reply_topic = r;
+        }
         if let Some(m) = self.mic {
             m.apply(&mut base.mic);
         }
@@ -246,6 +288,9 @@ impl VoiceAgentToml {
         if let Some(t) = self.tts {
             t.apply(&mut base.tts);
         }
+        if let Some(b) = self.barge_in {
+            b.apply(&mut base.barge_in);
+        }
         base
     }
 }
@@ -328,6 +373,7 @@ struct SttToml {
     pub language: Option<String>,
     pub temp_dir: Option<PathBuf>,
     pub extra_args: Option<Vec<String>>, // e.g., ["--threads", "4"]
+    pub partial_interval_ms: Option<u64>,
 }
 impl SttToml {
     fn apply(self, s: &mut SttConfig) {
@@ -355,6 +401,9 @@ impl SttToml {
         if let Some(mut x) = self.extra_args {
             s.extra_args = x.drain(..).filter(|a| !a.is_empty()).collect();
         }
+        if let Some(x) = self.partial_interval_ms {
+            s.partial_interval_ms = x;
+        }
     }
 }
 
@@ -368,6 +417,10 @@ struct WakeToml {
     pub match_anywhere: Option<bool>,
     pub jaro_winkler_threshold: Option<f64>,
     pub min_query_chars: Option<usize>,
+    pub mode: Option<String>,
+    pub model_path: Option<PathBuf>,
+    pub acoustic_threshold: Option<f32>,
+    pub wake_on_partial: Option<bool>,
 }
 impl WakeToml {
     fn apply(self, w: &mut WakeWordConfig) {
@@ -394,6 +447,18 @@ impl WakeToml {
         if let Some(x) = self.min_query_chars {
             w.min_query_chars = x;
         }
+        if let Some(mode) = self.mode.as_deref().and_then(WakeMode::parse) {
+            w.mode = mode;
+        }
+        if let Some(x) = self.model_path {
+            w.model_path = Some(x);
+        }
+        if let Some(x) = self.acoustic_threshold {
+            w.acoustic_threshold = x;
+        }
+        if let Some(x) = self.wake_on_partial {
+            w.wake_on_partial = x;
+        }
     }
 }
 
@@ -437,6 +502,7 @@ struct TtsToml {
     pub volume: Option<f32>,
     pub sample_rate: Option<u32>,
     pub player: Option<String>,
+    pub min_sentence_chars: Option<usize>,
 }
 impl TtsToml {
     fn apply(self, t: &mut TtsConfig) {
@@ -458,5 +524,48 @@ impl TtsToml {
         if let Some(x) = self.player {
             t.player = Some(x);
         }
+        if let Some(x) = self.min_sentence_chars {
+            t.min_sentence_chars = Some(x);
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, serde::Deserialize)]
+struct BargeInToml {
+    pub enabled: Option<bool>,
+    pub playback_device: Option<String>,
+    pub playback_topic: Option<String>,
+    pub topic: Option<String>,
+    pub clean_topic: Option<String>,
+    pub speech_rms: Option<f32>,
+    pub echo_margin: Option<f32>,
+    pub min_speech_ms: Option<u32>,
+}
+impl BargeInToml {
+    fn apply(self, b: &mut BargeInSettings) {
+        if let Some(x) = self.enabled {
+            b.enabled = x;
+        }
+        if let Some(x) = self.playback_device {
+            b.playback.device_name = Some(x);
+        }
+        if let Some(x) = self.playback_topic {
+            b.playback.topic = x;
+        }
+        if let Some(x) = self.topic {
+            b.detector.topic = x;
+        }
+        if let Some(x) = self.clean_topic {
+            b.detector.clean_topic = Some(x);
+        }
+        if let Some(x) = self.speech_rms {
+            b.detector.speech_rms = x;
+        }
+        if let Some(x) = self.echo_margin {
+            b.detector.echo_margin = x;
+        }
+        if let Some(x) = self.min_speech_ms {
+            b.detector.min_speech_ms = x;
+        }
     }
 }
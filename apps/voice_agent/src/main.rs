@@ -1,13 +1,15 @@
 mod config;
 use config::VoiceAgentConfig;
-use loom_audio::{MicSource, SttEngine, VadGate, WakeWordDetector};
-use loom_core::context::{PromptBundle, TokenBudget};
-use loom_core::proto::QoSLevel;
-use loom_core::Loom;
-use serde_json::json;
+use loom_audio::{
+    BargeInDetector, MicSource, PlaybackCaptureSource, ResponseSpeaker, SttEngine, VadGate,
+    WakeMode, WakeWordDetector,
+};
+use loom_core::cognitive::{CognitiveAgent, CognitiveConfig, SimpleCognitiveLoop};
+use loom_core::{AgentConfig, LlmClient, Loom};
+use std::collections::HashMap;
 use std::sync::Arc;
 use tokio::signal;
-use tracing::{error, info};
+use tracing::info;
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
@@ -39,101 +41,107 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let mic_handle = mic.start().await?;
 
     // 2) VAD gating → vad (speech_start/end) and audio.voiced (audio_voiced)
-    let vad = VadGate::new(Arc::clone(&bus), cfg.vad.clone());
+    let vad = VadGate::new(Arc::clone(&bus), cfg.vad_config());
     let vad_handle = vad.start().await?;
 
+    // Acoustic wake (WAKE_MODE=acoustic + WAKE_MODEL_PATH) lets STT idle until woken
+    let wake_model = match cfg.wake.mode {
+        WakeMode::Acoustic => loom_audio::wake::load_model(&cfg.wake),
+        WakeMode::Transcript => None,
+    };
+
     // 3) STT utterance segmentation via whisper.cpp → transcript (transcript.final)
-    let stt = SttEngine::new(Arc::clone(&bus), cfg.stt.clone());
+    let mut stt_cfg = cfg.stt.clone();
+    stt_cfg.pool = Some(Arc::clone(&loom.pools.audio));
+    if wake_model.is_some() && stt_cfg.wake_topic.is_none() {
+        stt_cfg.wake_topic = Some(cfg.wake.wake_topic.clone());
+    }
+    let stt = SttEngine::new(Arc::clone(&bus), stt_cfg);
     let stt_handle = stt.start().await?;
 
-    // 4) Wake word on transcripts → wake (wake_word_detected) + query (user.query)
-    let wake = WakeWordDetector::new(Arc::clone(&bus), cfg.wake.clone());
+    // 4) Wake word on audio or transcripts → wake (wake_word_detected) + query (user.query),
+    //    each query asking for its reply on the speaker's topic
+    let mut wake_cfg = cfg.wake.clone();
+    if wake_cfg.reply_topic.is_none() {
+        wake_cfg.reply_topic = Some(cfg.reply_topic.clone());
+    }
+    let mut wake = WakeWordDetector::new(Arc::clone(&bus), wake_cfg);
+    if let Some(model) = wake_model {
+        wake = wake.with_model(model);
+    }
     let wake_handle = wake.start().await?;
 
     // Register local TTS capability as a Tool (moved to loom-audio)
     {
-        let tts_cfg = cfg.build_tts_config();
+        let mut tts_cfg = cfg.build_tts_config();
+        tts_cfg.pool = Some(Arc::clone(&loom.pools.audio));
         let tts = loom_audio::TtsSpeakProvider::new(Arc::clone(&bus), Some(tts_cfg));
+        registry.register(Arc::new(tts.stop_tool())).await;
         registry.register(Arc::new(tts)).await;
     }
 
-    // 5) Subscribe to user queries → call LLM → TTS
-    let (_sub_id, mut query_rx) = bus
-        .subscribe(
-            cfg.query_topic.clone(),
-            vec!["user.query".to_string()],
-            QoSLevel::QosBatched,
-        )
-        .await?;
-    let llm_system = cfg.llm.system_prompt.clone();
-
-    // Spawn task to process queries
-    let broker_task = tokio::spawn(async move {
-        while let Some(ev) = query_rx.recv().await {
-            let text = ev
-                .metadata
-                .get("text")
-                .cloned()
-                .unwrap_or_else(|| String::from_utf8_lossy(&ev.payload).to_string());
-            if text.trim().is_empty() {
-                continue;
-            }
-            info!(target = "voice_agent", user_query = %text, "➡️  Received user.query");
-
-            // Assemble a minimal PromptBundle
-            let bundle = PromptBundle {
-                system: llm_system.clone(),
-                instructions: text.clone(),
-                tools_json_schema: None,
-                context_docs: vec![],
-                history: vec![],
-            };
-            let budget = TokenBudget {
-                max_input_tokens: 2048,
-                max_output_tokens: 256,
-            };
-
-            // Invoke LLM via tool registry (llm.generate)
-            let args = json!({
-                "bundle": bundle,
-                "budget": budget,
-                "headers": cfg.llm_headers(),
-            });
-
-            let res = registry.call("llm.generate", args).await;
-            let reply_text = match res {
-                Ok(val) => val
-                    .get("text")
-                    .and_then(|x| x.as_str())
-                    .unwrap_or("")
-                    .to_string(),
-                Err(e) => {
-                    error!(target = "voice_agent", error = %e, "llm.generate call failed");
-                    String::new()
-                }
-            };
+    // 5) Assistant agent answers user queries, streaming its reply to the reply topic
+    {
+        let mut llm = LlmClient::new(cfg.llm_client_config())?;
+        if let Some(cache) = &loom.llm_cache {
+            llm = llm.with_cache(Arc::clone(cache));
+        }
+        let llm = Arc::new(llm);
+        let config =
+            CognitiveConfig::single_shot().with_system_prompt(cfg.llm.system_prompt.clone());
+        let loop_impl = SimpleCognitiveLoop::new(config, llm, Arc::clone(&registry));
+        let assistant = CognitiveAgent::new(loop_impl).with_response_streaming(Arc::clone(&bus));
+        let agent_config = AgentConfig {
+            agent_id: "voice_assistant".to_string(),
+            agent_type: "cognitive".to_string(),
+            subscribed_topics: vec![cfg.query_topic.clone()],
+            capabilities: vec![],
+            parameters: HashMap::new(),
+        };
+        loom.agent_runtime
+            .create_agent(agent_config, Box::new(assistant))
+            .await?;
+    }
 
-            if reply_text.is_empty() {
-                continue;
-            }
+    // 6) Speak the reply sentence by sentence as it streams in (tts.speak)
+    let speaker = ResponseSpeaker::new(
+        Arc::clone(&bus),
+        Arc::clone(&registry),
+        cfg.speaker_config(),
+    );
+    let speaker_handle = speaker.start().await?;
+    let mut handles = vec![
+        mic_handle,
+        vad_handle,
+        stt_handle,
+        wake_handle,
+        speaker_handle,
+    ];
+
+    // 7) Full duplex (VOICE_BARGE_IN=1): capture playback → audio.playback, and stop
+    //    speaking when the user talks over it (barge_in → tts.stop)
+    if cfg.barge_in.enabled {
+        let playback = PlaybackCaptureSource::new(Arc::clone(&bus), cfg.barge_in.playback.clone());
+        handles.push(playback.start().await?);
+        let detector = BargeInDetector::new(Arc::clone(&bus), cfg.barge_in_config());
+        handles.push(detector.start().await?);
+    }
 
-            info!(target = "voice_agent", assistant = %reply_text, "🗣️  Speaking reply");
-
-            // Invoke local TTS (tts.speak) via tool registry
-            let tts_args = json!({
-                "text": reply_text,
-                "voice": cfg.tts_headers().get("voice").cloned().unwrap_or_default(),
-                "rate": cfg.tts_headers().get("rate").and_then(|s| s.parse::<f32>().ok()),
-                "volume": cfg.tts_headers().get("volume").and_then(|s| s.parse::<f32>().ok()),
-            });
-            match registry.call("tts.speak", tts_args).await {
-                Ok(_) => {}
-                Err(e) => {
-                    error!(target = "voice_agent", error = %e, "TTS invocation failed");
+    // Stop the pipeline before the tools and bus it uses
+    let pipeline = std::sync::Mutex::new(Some(handles));
+    loom.shutdown_registry.register_fn(
+        "voice_pipeline",
+        &["event_bus", "tool_registry"],
+        move |_deadline| {
+            let tasks = pipeline.lock().unwrap().take();
+            async move {
+                for handle in tasks.into_iter().flatten() {
+                    handle.abort();
                 }
+                Ok(())
             }
-        }
-    });
+        },
+    )?;
 
     // Ctrl+C handler to shutdown gracefully
     let shutdown = async {
@@ -148,13 +156,6 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         }
     }
 
-    // Stop background tasks (they are long-running but will exit on process end)
-    let _ = mic_handle.abort();
-    let _ = vad_handle.abort();
-    let _ = stt_handle.abort();
-    let _ = wake_handle.abort();
-    let _ = broker_task.abort();
-
     loom.shutdown().await.ok();
     Ok(())
 }
@@ -0,0 +1,175 @@
+//! Topic publish authorization for Bridge agents.
+//!
+//! A `TopicAcl` is a list of rules, each scoped to one agent id, one bearer token, or
+//! every agent, with `allow` and `deny` topic patterns. Patterns follow EventBus
+//! subscriptions (`topic_matches`): an exact topic, or a prefix ending in `.*`
+//! (`system.*` matches `system.shutdown` and `system.a.b`). `*` alone matches any topic.
+//!
+//! A publish is decided by the most specific rules with a matching pattern: agent rules
+//! first, then token rules, then rules for every agent. Within one level `deny` beats
+//! `allow`. If no pattern matches at any level, the publish is denied when an applicable
+//! rule has an `allow` list and allowed otherwise. Patterns match the topic the agent
+//! published to, before tenant namespacing.
+//!
+//! `TopicAcl::from_env()` loads rules from the TOML file named by `LOOM_BRIDGE_ACL_FILE`:
+//!
+//! ```toml
+//! [[rules]]
+//! deny = ["system.*"]
+//!
+//! [[rules]]
+//! agent = "ops-agent"
+//! allow = ["system.*", "ops.*"]
+//! ```
+
+use loom_core::topic_matches;
+use serde::Deserialize;
+use tracing::info;
+
+use crate::{BridgeError, Result};
+
+/// Allow and deny patterns for one agent, one token, or every agent
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+pub struct AclRule {
+    /// Agent id the rule applies to
+    #[serde(default)]
+    pub agent: Option<String>,
+    /// Bearer token the rule applies to
+    #[serde(default)]
+    pub token: Option<String>,
+    #[serde(default)]
+    pub allow: Vec<String>,
+    #[serde(default)]
+    pub deny: Vec<String>,
+}
+
+impl AclRule {
+    /// Rule applying to every agent
+    pub fn any() -> Self {
+        Self::default()
+    }
+
+    pub fn for_agent(agent_id: impl Into<String>) -> Self {
+        Self {
+            agent: Some(agent_id.into()),
+            ..Self::default()
+        }
+    }
+
+    pub fn for_token(token: impl Into<String>) -> Self {
+        Self {
+            token: Some(token.into()),
+            ..Self::default()
+        }
+    }
+
+    pub fn with_allow(mut self, pattern: impl Into<String>) -> Self {
+        self.allow.push(pattern.into());
+        self
+    }
+
+    pub fn with_deny(mut self, pattern: impl Into<String>) -> Self {
+        self.deny.push(pattern.into());
+        self
+    }
+
+    /// 0 for agent rules, 1 for token rules, 2 for rules applying to every agent
+    fn level(&self) -> usize {
+        match (&self.agent, &self.token) {
+            (Some(_), _) => 0,
+            (None, Some(_)) => 1,
+            (None, None) => 2,
+        }
+    }
+
+    fn applies_to(&self, agent_id: &str, token: Option<&str>) -> bool {
+        self.agent.as_deref().is_none_or(|a| a == agent_id)
+            && self.token.as_deref().is_none_or(|t| Some(t) == token)
+    }
+}
+
+/// Topic publish rules enforced on `ClientEvent::Publish`
+#[derive(Debug, Clone, Default)]
+pub struct TopicAcl {
+    rules: Vec<AclRule>,
+}
+
+impl TopicAcl {
+    /// ACL without rules: every publish is allowed
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_rule(mut self, rule: AclRule) -> Self {
+        self.rules.push(rule);
+        self
+    }
+
+    /// Parse a TOML document with a `[[rules]]` array
+    pub fn from_toml(source: &str) -> Result<Self> {
+        #[derive(Deserialize)]
+        struct File {
+            #[serde(default)]
+            rules: Vec<AclRule>,
+        }
+        let file: File = toml::from_str(source)
+            .map_err(|e| BridgeError::Config(format!("invalid topic ACL file: {e}")))?;
+        Ok(Self { rules: file.rules })
+    }
+
+    /// Load the file named by `LOOM_BRIDGE_ACL_FILE`; `Ok(None)` if it is unset
+    pub fn from_env() -> Result<Option<Self>> {
+        let Ok(path) = std::env::var("LOOM_BRIDGE_ACL_FILE") else {
+            return Ok(None);
+        };
+        let source = std::fs::read_to_string(&path)
+            .map_err(|e| BridgeError::Config(format!("failed to read {path}: {e}")))?;
+        let acl = Self::from_toml(&source)?;
+        info!(path = %path, rules = acl.rules.len(), "Loaded topic ACL");
+        Ok(Some(acl))
+    }
+
+    pub fn rules(&self) -> &[AclRule] {
+        &self.rules
+    }
+
+    /// Whether `agent_id`, authenticated with `token`, may publish to `topic`
+    pub fn is_allowed(&self, agent_id: &str, token: Option<&str>, topic: &str) -> bool {
+        let applicable: Vec<&AclRule> = self
+            .rules
+            .iter()
+            .filter(|r| r.applies_to(agent_id, token))
+            .collect();
+
+        for level in 0..3 {
+            let rules = applicable.iter().filter(|r| r.level() == level);
+            let (mut allowed, mut denied) = (false, false);
+            for rule in rules {
+                denied |= rule.deny.iter().any(|p| pattern_matches(p, topic));
+                allowed |= rule.allow.iter().any(|p| pattern_matches(p, topic));
+            }
+            if denied {
+                return false;
+            }
+            if allowed {
+                return true;
+            }
+        }
+        applicable.iter().all(|r| r.allow.is_empty())
+    }
+
+    /// `Err(BridgeError::PublishDenied)` unless `is_allowed`
+    pub fn check(&self, agent_id: &str, token: Option<&str>, topic: &str) -> Result<()> {
+        if self.is_allowed(agent_id, token, topic) {
+            Ok(())
+        } else {
+            Err(BridgeError::PublishDenied(format!(
+                "agent '{agent_id}' may not publish to '{topic}'"
+            )))
+        }
+    }
+}
+
+fn pattern_matches(pattern: &str, topic: &str) -> bool {
+    pattern == "*" || topic_matches(pattern, topic)
+}
@@ -0,0 +1,90 @@
+//! Load generator for sizing deployments.
+//!
+//! ```text
+//! # In-process EventBus
+//! LOOM_LOADGEN_RATE=20000 cargo run -p loom-bridge --release --bin loom-loadgen
+//!
+//! # A running Bridge
+//! LOOM_LOADGEN_TARGET=bridge LOOM_LOADGEN_BRIDGE_ADDR=127.0.0.1:50051 \
+//!     cargo run -p loom-bridge --release --bin loom-loadgen
+//! ```
+//!
+//! See `loom_bridge::loadgen` for the workload variables. `loom loadgen` in the Python
+//! CLI wraps this binary.
+
+use std::net::SocketAddr;
+use std::process::ExitCode;
+use std::sync::Arc;
+
+use loom_bridge::loadgen::{run_bridge, run_bus, LoadgenConfig};
+use loom_core::EventBus;
+use tracing_subscriber::EnvFilter;
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let _ = dotenvy::dotenv();
+    tracing_subscriber::fmt()
+        .with_env_filter(
+            EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("warn")),
+        )
+        .init();
+
+    let config = LoadgenConfig::from_env();
+    let target = std::env::var("LOOM_LOADGEN_TARGET").unwrap_or_else(|_| "bus".into());
+    let result = match target.as_str() {
+        "bus" => {
+            let event_bus = match EventBus::new().await {
+                Ok(bus) => Arc::new(bus),
+                Err(e) => {
+                    eprintln!("[loom-loadgen] failed to create EventBus: {e}");
+                    return ExitCode::FAILURE;
+                }
+            };
+            let _ = event_bus.start().await;
+            println!("[loom-loadgen] target: in-process EventBus");
+            run_bus(event_bus, &config).await
+        }
+        "bridge" => {
+            let addr = std::env::var("LOOM_LOADGEN_BRIDGE_ADDR")
+                .or_else(|_| std::env::var("LOOM_BRIDGE_ADDR"))
+                .unwrap_or_else(|_| "127.0.0.1:50051".into());
+            let addr: SocketAddr = match addr.parse() {
+                Ok(addr) => addr,
+                Err(e) => {
+                    eprintln!("[loom-loadgen] invalid bridge address {addr}: {e}");
+                    return ExitCode::FAILURE;
+                }
+            };
+            println!("[loom-loadgen] target: Bridge at {addr}");
+            run_bridge(addr, &config).await
+        }
+        other => {
+            eprintln!(
+                "[loom-loadgen] unknown LOOM_LOADGEN_TARGET '{other}' (expected bus or bridge)"
+            );
+            return ExitCode::FAILURE;
+        }
+    };
+
+    println!(
+        "[loom-loadgen] {} topics, rate {}/s, {} publishers, {} B payload, pattern {:?}, burst {:?}, {} personas, {}s",
+        config.topic_names().len(),
+        config.rate,
+        config.publishers,
+        config.payload_bytes,
+        config.pattern,
+        config.burst,
+        config.personas.as_ref().map_or(0, |p| p.personas),
+        config.duration.as_secs()
+    );
+    match result {
+        Ok(report) => {
+            println!("[loom-loadgen] {report}");
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("[loom-loadgen] run failed: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
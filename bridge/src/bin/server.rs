@@ -1,9 +1,9 @@
 use std::net::SocketAddr;
 use std::sync::Arc;
 
-use loom_bridge::start_server_with_dashboard;
+use loom_bridge::{start_server_with_dashboard, BridgePeer, PeerConfig};
 use loom_core::dashboard::{DashboardConfig, DashboardServer, EventBroadcaster, FlowTracker};
-use loom_core::Loom;
+use loom_core::{A2aConfig, A2aServer, Loom, LoomConfig, OpenAiConfig, OpenAiServer};
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
@@ -17,6 +17,18 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         }
     }
 
+    // `config export` writes the manifest equivalent to the environment and exits
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.first().map(String::as_str) == Some("config") {
+        return config_command(&args[1..]);
+    }
+
+    // Settings from the LOOM_CONFIG manifest; variables already set win
+    let config = LoomConfig::load_startup()?;
+    if let Ok(path) = std::env::var(loom_core::config::CONFIG_PATH_ENV) {
+        eprintln!("[loom-bridge] Applied config manifest: {}", path);
+    }
+
     // Debug: print BRAVE_API_KEY status
     match std::env::var("BRAVE_API_KEY") {
         Ok(key) => eprintln!(
@@ -39,6 +51,8 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         }
     };
 
+    config.warn_undeclared()?;
+
     let mut loom = Loom::new().await?;
 
     // Check if Dashboard is enabled
@@ -60,6 +74,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             (*event_bus_ptr).set_dashboard_broadcaster(broadcaster.clone());
             (*event_bus_ptr).set_flow_tracker(flow_tracker.clone());
         }
+        loom.memory_governor.register(flow_tracker.clone());
 
         // Get agent directory
         let agent_directory = loom.agent_directory.clone();
@@ -71,7 +86,11 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             agent_directory,
             span_collector.clone(),
         )
-        .with_flow_tracker(flow_tracker.clone());
+        .with_flow_tracker(flow_tracker.clone())
+        .with_tool_registry(loom.tool_registry.clone())
+        .with_agent_runtime(loom.agent_runtime.clone())
+        .with_stats(loom.stats_collector.clone())
+        .with_event_bus(loom.event_bus.clone());
 
         tracing::info!(
             "Dashboard enabled at http://{}:{}",
@@ -97,6 +116,46 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     loom.start().await?;
 
+    // Federate selected topics with a remote Loom's Bridge
+    if let Some(config) = PeerConfig::from_env()? {
+        tracing::info!(
+            remote = %config.remote_addr,
+            export = ?config.export,
+            import = ?config.import,
+            tools = ?config.tools,
+            "Peering with remote Loom"
+        );
+        let peer = Arc::new(
+            BridgePeer::new(loom.event_bus.clone(), config)?
+                .with_tool_registry(loom.tool_registry.clone()),
+        );
+        peer.start().await?;
+    }
+
+    // OpenAI-compatible chat completions facade for one agent
+    if OpenAiConfig::enabled() {
+        let openai = OpenAiServer::new(OpenAiConfig::from_env(), loom.event_bus.clone());
+        tokio::spawn(async move {
+            if let Err(e) = openai.serve().await {
+                tracing::error!("OpenAI facade error: {}", e);
+            }
+        });
+    }
+
+    // A2A agent cards and task endpoints for the registered agents
+    if A2aConfig::enabled() {
+        let a2a = A2aServer::new(
+            A2aConfig::from_env(),
+            loom.event_bus.clone(),
+            loom.agent_directory.clone(),
+        );
+        tokio::spawn(async move {
+            if let Err(e) = a2a.serve().await {
+                tracing::error!("A2A server error: {}", e);
+            }
+        });
+    }
+
     let addr: SocketAddr = std::env::var("LOOM_BRIDGE_ADDR")
         .unwrap_or_else(|_| "0.0.0.0:50051".into())
         .parse()?;
@@ -109,6 +168,8 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         loom.agent_directory.clone(),
         broadcaster_opt,
         flow_tracker_opt,
+        Some(loom.memory_governor.clone()),
+        Some(loom.stats_collector.clone()),
     )
     .await;
 
@@ -119,3 +180,31 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     server_result.map_err(|e| e.into())
 }
+
+/// `config export [--include-secrets] [PATH]`; prints to stdout without a path
+fn config_command(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let usage = "usage: loom-bridge-server config export [--include-secrets] [PATH]";
+    let (command, rest) = args.split_first().ok_or(usage)?;
+    if command != "export" {
+        return Err(usage.into());
+    }
+    let mut include_secrets = false;
+    let mut path = None;
+    for arg in rest {
+        match arg.as_str() {
+            "--include-secrets" => include_secrets = true,
+            _ if arg.starts_with("--") || path.is_some() => return Err(usage.into()),
+            _ => path = Some(arg),
+        }
+    }
+
+    let config = LoomConfig::export_env(include_secrets)?;
+    match path {
+        Some(path) => {
+            config.save(path)?;
+            eprintln!("[loom-bridge] Wrote config manifest to {}", path);
+        }
+        None => print!("{}", config.to_toml()?),
+    }
+    Ok(())
+}
@@ -0,0 +1,62 @@
+//! Long-haul soak run of the full pipeline with leak detection.
+//!
+//! ```text
+//! LOOM_SOAK_DURATION_SECS=14400 cargo run -p loom-bridge --release --features soak --bin loom-soak
+//! ```
+//!
+//! See `loom_bridge::soak` for the configuration variables. Exits non-zero if any gauge
+//! grew past its baseline.
+
+use std::process::ExitCode;
+
+use loom_bridge::soak::{run_soak, SoakConfig};
+use tracing_subscriber::EnvFilter;
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let _ = dotenvy::dotenv();
+    tracing_subscriber::fmt()
+        .with_env_filter(
+            EnvFilter::try_from_default_env()
+                .unwrap_or_else(|_| EnvFilter::new("warn,loom_bridge::soak=info")),
+        )
+        .init();
+
+    let report = match run_soak(SoakConfig::from_env()).await {
+        Ok(report) => report,
+        Err(e) => {
+            eprintln!("[loom-soak] run failed: {e}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    println!(
+        "[loom-soak] {}s, {} samples: published={} handled={} deliveries={} client_sessions={} tool_calls ok={} failed={}",
+        report.elapsed.as_secs(),
+        report.samples,
+        report.events_published,
+        report.events_handled,
+        report.deliveries,
+        report.client_sessions,
+        report.tool_calls_ok,
+        report.tool_calls_failed,
+    );
+    println!("[loom-soak] baseline: {:?}", report.baseline);
+    println!("[loom-soak] peak:     {:?}", report.peak);
+
+    if report.passed() {
+        println!("[loom-soak] PASS: no unbounded growth detected");
+        ExitCode::SUCCESS
+    } else {
+        for (at, v) in report.violations.iter() {
+            println!(
+                "[loom-soak] LEAK at {}s: {} grew from {} to {}",
+                at.as_secs(),
+                v.gauge,
+                v.baseline,
+                v.current
+            );
+        }
+        ExitCode::FAILURE
+    }
+}
@@ -0,0 +1,148 @@
+//! Blob references for events crossing the Bridge (see `loom_core::blob`).
+//!
+//! With a `BlobStore` set (`BridgeState::set_blob_store`, or `LOOM_BLOB_*` for
+//! `start_server_with_dashboard`), a payload an agent publishes that is larger than the
+//! store's `inline_max_bytes` is moved into the store before the event reaches the
+//! EventBus, so subscriber queues only carry the reference. The blob is owned by the
+//! publishing agent, confined to its namespace and readable by the agents listed under
+//! `blob.readers` in the event's metadata (any agent when none are listed). Signed events
+//! are published as they are: stashing would change the payload they were signed with.
+//!
+//! Deliveries are resolved per agent: an agent that may read the blob gets its content
+//! back as the payload, others get the reference alone. Agents that register with
+//! `blobs: ref` metadata always get the reference and fetch what they need themselves
+//! (e.g. with the `blob:get` tool). Redeliveries and replayed deliveries carry the
+//! reference.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+use dashmap::{DashMap, DashSet};
+use serde::Serialize;
+use tracing::{debug, warn};
+
+use loom_core::blob::{BlobAccess, BlobRef, BlobStore};
+use loom_core::messaging::envelope::keys;
+use loom_core::Namespace;
+use loom_proto::Event;
+
+/// Registration metadata key: `ref` to receive blob references instead of content
+pub const BLOBS_KEY: &str = "blobs";
+
+/// Blob relay statistics
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct BlobRelayStats {
+    /// Agents that asked for references
+    pub by_ref_agents: usize,
+    /// Published payloads moved into the store
+    pub stashed: u64,
+    /// Events delivered with the blob's content inlined (once per event)
+    pub resolved: u64,
+    /// Blobs that could not be stashed or loaded
+    pub failed: u64,
+}
+
+/// Stashes large publishes in a `BlobStore` and resolves references on delivery
+pub struct BlobRelay {
+    store: Arc<BlobStore>,
+    namespaces: Arc<DashMap<String, Namespace>>,
+    by_ref: DashSet<String>,
+    stashed: AtomicU64,
+    resolved: AtomicU64,
+    failed: AtomicU64,
+}
+
+impl BlobRelay {
+    /// Relay over `store`, confining blobs to the agents' `namespaces`
+    pub fn new(store: Arc<BlobStore>, namespaces: Arc<DashMap<String, Namespace>>) -> Self {
+        Self {
+            store,
+            namespaces,
+            by_ref: DashSet::new(),
+            stashed: AtomicU64::new(0),
+            resolved: AtomicU64::new(0),
+            failed: AtomicU64::new(0),
+        }
+    }
+
+    pub fn store(&self) -> &Arc<BlobStore> {
+        &self.store
+    }
+
+    /// Record whether `agent_id` asked for references with `blobs` registration metadata
+    pub fn negotiate(&self, agent_id: &str, metadata: &HashMap<String, String>) {
+        let by_ref = metadata
+            .get(BLOBS_KEY)
+            .is_some_and(|v| v.trim().eq_ignore_ascii_case("ref"));
+        if by_ref {
+            debug!(agent_id = %agent_id, "Negotiated blob references");
+            self.by_ref.insert(agent_id.to_string());
+        } else {
+            self.by_ref.remove(agent_id);
+        }
+    }
+
+    /// Whether deliveries to `agent_id` get blob content inlined
+    pub fn inlines(&self, agent_id: &str) -> bool {
+        !self.by_ref.contains(agent_id)
+    }
+
+    /// Move the payload of `event`, published by `agent_id`, into the store if it is
+    /// large. Returns whether it stashed; on error the event must not be published.
+    pub async fn stash(&self, agent_id: &str, event: &mut Event) -> loom_core::Result<bool> {
+        if event.metadata.contains_key(keys::SIGNATURE) {
+            return Ok(false);
+        }
+        let mut access = BlobAccess::owned_by(agent_id).with_readers_of(event);
+        if let Some(ns) = self.namespaces.get(agent_id) {
+            access = access.with_namespace(ns.as_str());
+        }
+        match self.store.stash(event, access).await {
+            Ok(Some(blob)) => {
+                debug!(agent_id = %agent_id, blob_ref = %blob.id, size = blob.size, "Stashed published payload");
+                self.stashed.fetch_add(1, Ordering::Relaxed);
+                Ok(true)
+            }
+            Ok(None) => Ok(false),
+            Err(e) => {
+                self.failed.fetch_add(1, Ordering::Relaxed);
+                Err(e)
+            }
+        }
+    }
+
+    /// `event` with the content of the blob it references inlined, and who may read it.
+    /// `None` if it carries no reference or the blob cannot be loaded.
+    pub async fn fetch(&self, event: &Event) -> Option<(Event, BlobAccess)> {
+        let blob = BlobRef::from_event(event)?;
+        match self.store.load(&blob).await {
+            Ok((content, access)) => {
+                self.resolved.fetch_add(1, Ordering::Relaxed);
+                let mut resolved = event.clone();
+                BlobRef::inline(&mut resolved, content);
+                Some((resolved, access))
+            }
+            Err(e) => {
+                self.failed.fetch_add(1, Ordering::Relaxed);
+                warn!(event_id = %event.id, blob_ref = %blob.id, error = %e, "Delivering blob reference unresolved");
+                None
+            }
+        }
+    }
+
+    /// Whether `agent_id`, in its namespace, may read a blob with `access`
+    pub fn allows(&self, agent_id: &str, access: &BlobAccess) -> bool {
+        let namespace = self.namespaces.get(agent_id).map(|ns| ns.to_string());
+        access.allows(Some(agent_id), namespace.as_deref())
+    }
+
+    pub fn stats(&self) -> BlobRelayStats {
+        BlobRelayStats {
+            by_ref_agents: self.by_ref.len(),
+            stashed: self.stashed.load(Ordering::Relaxed),
+            resolved: self.resolved.load(Ordering::Relaxed),
+            failed: self.failed.load(Ordering::Relaxed),
+        }
+    }
+}
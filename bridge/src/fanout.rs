@@ -0,0 +1,545 @@
+//! Shared per-topic EventBus subscriptions with fanout to connected agents.
+//!
+//! Instead of one EventBus subscription per agent per topic, the Bridge keeps a single
+//! subscription per topic and a forwarding task that delivers each event to every
+//! attached agent stream. The subscription is created when the first agent attaches
+//! and released when the last one detaches.
+//!
+//! Delivery to agents uses `try_send`: an agent whose outbound queue is full misses
+//! the event instead of stalling delivery for everyone else on the topic.
+//!
+//! With tenant namespaces enabled, deliveries carry the tenant-relative topic
+//! (`tenant.<id>.` stripped) so agents see the topics they subscribed to.
+//!
+//! With a `PayloadCodec`, large payloads are compressed and chunked per agent, encoding
+//! each event once per distinct set of negotiated encodings. A chunked event is only
+//! queued if all of its chunks fit into the agent's outbound queue.
+//!
+//! On critical topics the shared subscription's delivery receipt is handed off to every
+//! attached agent before forwarding, so each agent acks (`ClientEvent::Ack` carrying the
+//! event id) for itself. Unacked events are redelivered to the agent's current stream.
+//! Topics matching `GuaranteedTopics` get a `QosGuaranteed` subscription, whose every
+//! event carries such a receipt.
+//!
+//! Agents that register with `accept-batch: <n>` metadata get the events that were
+//! already waiting on the topic packed into `DeliveryBatch` frames of up to `n` events.
+//! An idle topic still delivers each event as soon as it arrives; chunked events always
+//! go out as individual `Delivery` frames.
+//!
+//! With a `BlobRelay`, an event that references a blob is loaded from the store once and
+//! delivered with its content inlined to the agents that may read it (see `blobs`).
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+use dashmap::DashMap;
+use serde::Serialize;
+use tokio::sync::{mpsc, Mutex};
+use tokio::task::JoinHandle;
+use tracing::{debug, warn};
+
+use loom_core::dashboard::FlowTracker;
+use loom_core::messaging::receipts::RedeliverFn;
+use loom_core::tenancy::{strip_namespace, topic_tenant};
+use loom_core::EventBus;
+use loom_proto::{server_event, Delivery, DeliveryBatch, ServerEvent};
+
+use crate::blobs::BlobRelay;
+use crate::guaranteed::GuaranteedTopics;
+use crate::payload::{PayloadAccept, PayloadCodec};
+
+/// Registration metadata key: the most events an agent accepts per `DeliveryBatch`
+pub const ACCEPT_BATCH_KEY: &str = "accept-batch";
+
+/// Most events drained from a topic's subscription in one round of deliveries
+const MAX_BATCH: usize = 256;
+
+type AgentSenders = Arc<DashMap<String, mpsc::Sender<ServerEvent>>>;
+
+struct TopicEntry {
+    subscription_id: String,
+    agents: AgentSenders,
+    task: JoinHandle<()>,
+    guaranteed: bool,
+}
+
+/// Fanout statistics
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct FanoutStats {
+    /// Topics with an active shared subscription
+    pub topics: usize,
+    /// Sum of attached agents across topics
+    pub attachments: usize,
+    /// Deliveries skipped because an agent's outbound queue was full
+    pub dropped: u64,
+    /// Agents that negotiated batched deliveries
+    pub batching_agents: usize,
+    /// Topics subscribed with `QosGuaranteed`
+    pub guaranteed_topics: usize,
+}
+
+/// Table of shared topic subscriptions
+pub struct TopicFanout {
+    event_bus: Arc<EventBus>,
+    flow_tracker: Option<Arc<FlowTracker>>,
+    topics: Mutex<HashMap<String, TopicEntry>>,
+    dropped: Arc<AtomicU64>,
+    tenant_namespaces: bool,
+    payloads: Option<Arc<PayloadCodec>>,
+    batch_limits: Arc<DashMap<String, usize>>,
+    guaranteed: Option<Arc<GuaranteedTopics>>,
+    blobs: Option<Arc<BlobRelay>>,
+}
+
+impl TopicFanout {
+    pub fn new(event_bus: Arc<EventBus>, flow_tracker: Option<Arc<FlowTracker>>) -> Self {
+        Self {
+            event_bus,
+            flow_tracker,
+            topics: Mutex::new(HashMap::new()),
+            dropped: Arc::new(AtomicU64::new(0)),
+            tenant_namespaces: false,
+            payloads: None,
+            batch_limits: Arc::new(DashMap::new()),
+            guaranteed: None,
+            blobs: None,
+        }
+    }
+
+    /// Strip the tenant namespace from the topic of each delivery
+    pub fn with_tenant_namespaces(mut self) -> Self {
+        self.tenant_namespaces = true;
+        self
+    }
+
+    /// Encode large payloads for each agent with `payloads`
+    pub fn with_payloads(mut self, payloads: Arc<PayloadCodec>) -> Self {
+        self.payloads = Some(payloads);
+        self
+    }
+
+    /// Require agents to ack each delivery on topics matching `guaranteed`
+    pub fn with_guaranteed(mut self, guaranteed: Arc<GuaranteedTopics>) -> Self {
+        self.guaranteed = Some(guaranteed);
+        self
+    }
+
+    /// Resolve blob references per agent with `blobs`
+    pub fn with_blobs(mut self, blobs: Arc<BlobRelay>) -> Self {
+        self.blobs = Some(blobs);
+        self
+    }
+
+    /// Record the batch size `agent_id` asked for with `accept-batch` registration
+    /// metadata. Missing, invalid or `1` values turn batching off for the agent.
+    pub fn negotiate_batching(&self, agent_id: &str, metadata: &HashMap<String, String>) {
+        let limit = metadata
+            .get(ACCEPT_BATCH_KEY)
+            .and_then(|v| v.trim().parse::<usize>().ok())
+            .unwrap_or(1)
+            .min(MAX_BATCH);
+        if limit > 1 {
+            debug!(agent_id = %agent_id, limit, "Negotiated batched deliveries");
+            self.batch_limits.insert(agent_id.to_string(), limit);
+        } else {
+            self.batch_limits.remove(agent_id);
+        }
+    }
+
+    /// Most events per delivery frame for `agent_id` (1 without batching)
+    pub fn batch_limit_of(&self, agent_id: &str) -> usize {
+        self.batch_limits.get(agent_id).map(|l| *l).unwrap_or(1)
+    }
+
+    /// Attach an agent's outbound stream to `topic`, subscribing on the EventBus if
+    /// this is the first agent for the topic. Re-attaching replaces the old sender.
+    pub async fn attach(
+        &self,
+        agent_id: &str,
+        topic: &str,
+        tx: mpsc::Sender<ServerEvent>,
+    ) -> loom_core::Result<()> {
+        let mut topics = self.topics.lock().await;
+        if let Some(entry) = topics.get(topic) {
+            if !entry.task.is_finished() {
+                entry.agents.insert(agent_id.to_string(), tx);
+                return Ok(());
+            }
+        }
+
+        // No live subscription for this topic (or its task ended): create one
+        if let Some(stale) = topics.remove(topic) {
+            let _ = self.event_bus.unsubscribe(&stale.subscription_id).await;
+        }
+        let guaranteed = self
+            .guaranteed
+            .as_ref()
+            .filter(|g| g.matches(delivered_topic(topic, self.tenant_namespaces)));
+        let (subscription_id, rx_bus) = match guaranteed {
+            Some(guaranteed) => {
+                self.event_bus
+                    .subscribe_guaranteed(topic.to_string(), vec![], guaranteed.delivery.clone())
+                    .await?
+            }
+            None => {
+                self.event_bus
+                    .subscribe(topic.to_string(), vec![], loom_proto::QoSLevel::QosBatched)
+                    .await?
+            }
+        };
+        let agents: AgentSenders = Arc::new(DashMap::new());
+        agents.insert(agent_id.to_string(), tx);
+
+        let task = tokio::spawn(forward_loop(
+            Arc::clone(&self.event_bus),
+            topic.to_string(),
+            subscription_id.clone(),
+            rx_bus,
+            Arc::clone(&agents),
+            self.flow_tracker.clone(),
+            Arc::clone(&self.dropped),
+            self.tenant_namespaces,
+            self.payloads.clone(),
+            Arc::clone(&self.batch_limits),
+            self.blobs.clone(),
+        ));
+        debug!(topic = %topic, sub_id = %subscription_id, guaranteed = guaranteed.is_some(), "Created shared topic subscription");
+        topics.insert(
+            topic.to_string(),
+            TopicEntry {
+                subscription_id,
+                agents,
+                task,
+                guaranteed: guaranteed.is_some(),
+            },
+        );
+        Ok(())
+    }
+
+    /// Detach an agent from `topic`. Only removes the agent if `tx` is the sender
+    /// currently attached, so a stale stream cannot detach a reconnected agent.
+    /// Releases the EventBus subscription when no agents remain.
+    pub async fn detach(&self, agent_id: &str, topic: &str, tx: &mpsc::Sender<ServerEvent>) {
+        let mut topics = self.topics.lock().await;
+        let Some(entry) = topics.get(topic) else {
+            return;
+        };
+        entry
+            .agents
+            .remove_if(agent_id, |_, current| current.same_channel(tx));
+        if entry.agents.is_empty() {
+            if let Some(entry) = topics.remove(topic) {
+                entry.task.abort();
+                let _ = self.event_bus.unsubscribe(&entry.subscription_id).await;
+                debug!(topic = %topic, "Released shared topic subscription");
+            }
+        }
+    }
+
+    /// Swap the sender attached for `agent_id` on `topic` to `new`, but only while `old`
+    /// is still the attached sender. Returns false if the topic has no live subscription
+    /// or the agent has already re-attached with a different stream.
+    pub async fn replace(
+        &self,
+        agent_id: &str,
+        topic: &str,
+        old: &mpsc::Sender<ServerEvent>,
+        new: mpsc::Sender<ServerEvent>,
+    ) -> bool {
+        let topics = self.topics.lock().await;
+        let Some(entry) = topics.get(topic) else {
+            return false;
+        };
+        let replaced = match entry.agents.get_mut(agent_id) {
+            Some(mut current) if current.same_channel(old) => {
+                *current = new;
+                true
+            }
+            _ => false,
+        };
+        replaced
+    }
+
+    /// Number of agents attached to `topic`
+    pub async fn subscriber_count(&self, topic: &str) -> usize {
+        self.topics
+            .lock()
+            .await
+            .get(topic)
+            .map(|e| e.agents.len())
+            .unwrap_or(0)
+    }
+
+    pub async fn stats(&self) -> FanoutStats {
+        let topics = self.topics.lock().await;
+        FanoutStats {
+            topics: topics.len(),
+            attachments: topics.values().map(|e| e.agents.len()).sum(),
+            dropped: self.dropped.load(Ordering::Relaxed),
+            batching_agents: self.batch_limits.len(),
+            guaranteed_topics: topics.values().filter(|e| e.guaranteed).count(),
+        }
+    }
+}
+
+/// Topic as delivered to agents: tenant-relative when tenant namespaces are stripped
+fn delivered_topic(topic: &str, tenant_namespaces: bool) -> &str {
+    if !tenant_namespaces {
+        return topic;
+    }
+    topic_tenant(topic)
+        .and_then(|tenant| strip_namespace(tenant, topic))
+        .unwrap_or(topic)
+}
+
+/// Redelivers an unacked critical event to whatever stream `agent_id` has attached now
+fn redeliver_to(agent_id: String, agents: AgentSenders, topic: String) -> RedeliverFn {
+    Arc::new(move |event| {
+        // Redeliveries skip payload encoding; an agent that is away keeps its receipt
+        // and may reattach before the next attempt
+        if let Some(tx) = agents.get(&agent_id) {
+            let _ = tx.try_send(ServerEvent {
+                msg: Some(server_event::Msg::Delivery(Delivery {
+                    topic: topic.clone(),
+                    event: Some(event),
+                })),
+            });
+        }
+        true
+    })
+}
+
+/// One agent's deliveries for a round of drained events
+struct Outbox {
+    agent_id: String,
+    tx: mpsc::Sender<ServerEvent>,
+    batch_limit: usize,
+    pending: Vec<loom_proto::Event>,
+    closed: bool,
+}
+
+impl Outbox {
+    fn new(agent_id: String, tx: mpsc::Sender<ServerEvent>, batch_limit: usize) -> Self {
+        Self {
+            agent_id,
+            tx,
+            batch_limit,
+            pending: Vec::new(),
+            closed: false,
+        }
+    }
+
+    /// Queue `event`, sending a frame once the agent's batch is full
+    fn push(&mut self, event: loom_proto::Event, topic: &str, dropped: &AtomicU64) {
+        self.pending.push(event);
+        if self.pending.len() >= self.batch_limit {
+            self.flush(topic, dropped);
+        }
+    }
+
+    /// Send the chunks of one event as individual frames, after anything queued before
+    /// it. Never queues part of a chunked event.
+    fn push_chunks(&mut self, chunks: &[loom_proto::Event], topic: &str, dropped: &AtomicU64) {
+        self.flush(topic, dropped);
+        if self.closed {
+            return;
+        }
+        if self.tx.capacity() < chunks.len() {
+            dropped.fetch_add(1, Ordering::Relaxed);
+            warn!(topic = %topic, agent_id = %self.agent_id, chunks = chunks.len(), "Agent stream full; dropping chunked delivery");
+            return;
+        }
+        for chunk in chunks {
+            let delivery = server_event::Msg::Delivery(Delivery {
+                topic: topic.to_string(),
+                event: Some(chunk.clone()),
+            });
+            if !self.send(delivery, 1, topic, dropped) {
+                break;
+            }
+        }
+    }
+
+    /// Send queued events: one as a `Delivery`, more as a `DeliveryBatch`
+    fn flush(&mut self, topic: &str, dropped: &AtomicU64) {
+        let mut events = std::mem::take(&mut self.pending);
+        let count = events.len();
+        let msg = match count {
+            0 => return,
+            1 => server_event::Msg::Delivery(Delivery {
+                topic: topic.to_string(),
+                event: events.pop(),
+            }),
+            _ => server_event::Msg::DeliveryBatch(DeliveryBatch {
+                topic: topic.to_string(),
+                events,
+            }),
+        };
+        self.send(msg, count, topic, dropped);
+    }
+
+    fn send(
+        &mut self,
+        msg: server_event::Msg,
+        count: usize,
+        topic: &str,
+        dropped: &AtomicU64,
+    ) -> bool {
+        match self.tx.try_send(ServerEvent { msg: Some(msg) }) {
+            Ok(()) => true,
+            Err(mpsc::error::TrySendError::Full(_)) => {
+                dropped.fetch_add(count as u64, Ordering::Relaxed);
+                warn!(topic = %topic, agent_id = %self.agent_id, events = count, "Agent stream full; dropping delivery");
+                false
+            }
+            Err(mpsc::error::TrySendError::Closed(_)) => {
+                self.closed = true;
+                false
+            }
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn forward_loop(
+    event_bus: Arc<EventBus>,
+    topic: String,
+    subscription_id: String,
+    mut rx_bus: mpsc::Receiver<loom_proto::Event>,
+    agents: AgentSenders,
+    flow_tracker: Option<Arc<FlowTracker>>,
+    dropped: Arc<AtomicU64>,
+    tenant_namespaces: bool,
+    payloads: Option<Arc<PayloadCodec>>,
+    batch_limits: Arc<DashMap<String, usize>>,
+    blobs: Option<Arc<BlobRelay>>,
+) {
+    let delivered_topic = delivered_topic(&topic, tenant_namespaces).to_string();
+    while let Some(first) = rx_bus.recv().await {
+        // Whatever is already waiting goes out in the same round, batched per agent
+        let mut ready = vec![first];
+        while ready.len() < MAX_BATCH {
+            match rx_bus.try_recv() {
+                Ok(ev) => ready.push(ev),
+                Err(_) => break,
+            }
+        }
+
+        // Snapshot targets so no DashMap guard is held across awaits
+        let mut outboxes: Vec<Outbox> = agents
+            .iter()
+            .map(|e| {
+                let limit = batch_limits.get(e.key()).map(|l| *l).unwrap_or(1);
+                Outbox::new(e.key().clone(), e.value().clone(), limit)
+            })
+            .collect();
+
+        for ev in ready {
+            // Agents owe their own acks on critical and guaranteed topics; with nobody
+            // attached the receipt stays with the subscription and the event comes back
+            // on redelivery
+            if !outboxes.is_empty() && event_bus.receipts().is_pending(&subscription_id, &ev.id) {
+                let handed = outboxes
+                    .iter()
+                    .map(|outbox| {
+                        let redeliver = redeliver_to(
+                            outbox.agent_id.clone(),
+                            Arc::clone(&agents),
+                            delivered_topic.clone(),
+                        );
+                        (outbox.agent_id.clone(), redeliver)
+                    })
+                    .collect();
+                event_bus
+                    .receipts()
+                    .hand_off(&subscription_id, &ev.id, handed);
+            }
+
+            // Create a span for fanning this event out to agent streams
+            let fwd_span = tracing::info_span!(
+                "bridge.forward",
+                topic = %topic,
+                agents = outboxes.len(),
+                event_id = %ev.id,
+                trace_id = tracing::field::Empty,
+                span_id = tracing::field::Empty
+            );
+            let _fwd_guard = fwd_span.enter();
+
+            // Apply remote parent if present
+            let env = loom_core::Envelope::from_event(&ev);
+            if env.extract_trace_context() {
+                tracing::Span::current().record("trace_id", tracing::field::display(&env.trace_id));
+                tracing::Span::current().record("span_id", tracing::field::display(&env.span_id));
+            }
+
+            // The blob this event references, loaded once for the agents that inline it
+            let resolved = match blobs.as_deref() {
+                Some(relay) if outboxes.iter().any(|o| relay.inlines(&o.agent_id)) => {
+                    relay.fetch(&ev).await
+                }
+                _ => None,
+            };
+
+            // Encoded forms of this event, keyed by whether the blob is inlined and the
+            // agents' negotiated encodings
+            let mut encoded: Vec<((bool, PayloadAccept), Vec<loom_proto::Event>)> = Vec::new();
+
+            for outbox in outboxes.iter_mut().filter(|o| !o.closed) {
+                // Record flow: subscription -> agent
+                if let Some(ref tracker) = flow_tracker {
+                    tracker
+                        .record_flow(&subscription_id, &outbox.agent_id, &topic)
+                        .await;
+                }
+
+                let inlined = match (&resolved, blobs.as_deref()) {
+                    (Some((_, access)), Some(relay)) => {
+                        relay.inlines(&outbox.agent_id) && relay.allows(&outbox.agent_id, access)
+                    }
+                    _ => false,
+                };
+                let source = match &resolved {
+                    Some((event, _)) if inlined => event,
+                    _ => &ev,
+                };
+                let codec = payloads.as_deref().filter(|p| p.needs_encoding(source));
+                let events = match codec {
+                    Some(codec) => {
+                        let accept = codec.accept_of(&outbox.agent_id);
+                        if accept.is_empty() {
+                            std::slice::from_ref(source)
+                        } else {
+                            let key = (inlined, accept);
+                            let pos = match encoded.iter().position(|(k, _)| *k == key) {
+                                Some(pos) => pos,
+                                None => {
+                                    let events = codec.encode(source, &key.1);
+                                    encoded.push((key, events));
+                                    encoded.len() - 1
+                                }
+                            };
+                            encoded[pos].1.as_slice()
+                        }
+                    }
+                    None => std::slice::from_ref(source),
+                };
+                match events {
+                    [event] => outbox.push(event.clone(), &delivered_topic, &dropped),
+                    chunks => outbox.push_chunks(chunks, &delivered_topic, &dropped),
+                }
+            }
+        }
+
+        for mut outbox in outboxes {
+            outbox.flush(&delivered_topic, &dropped);
+            if outbox.closed {
+                // stream dropped; disconnect cleanup will detach it
+                agents.remove_if(&outbox.agent_id, |_, current| {
+                    current.same_channel(&outbox.tx)
+                });
+            }
+        }
+    }
+}
@@ -0,0 +1,274 @@
+//! Payload formats negotiated by Bridge agents.
+//!
+//! Agents name the format they want `Event.payload` in with `payload-format` registration
+//! metadata: `raw` (the bytes as published, the default), `json` or `msgpack`. The Bridge
+//! transcodes deliveries to that format and publishes from that format back to the form
+//! the EventBus carries, using the `SchemaRegistry` (see `schema`) to map event types to
+//! protobuf messages or JSON schemas.
+//!
+//! Every delivery transcoded for an agent carries `payload-format` metadata naming the
+//! format its payload is in: a payload that cannot be transcoded (e.g. audio bytes of an
+//! unregistered type) is delivered as-is and marked `raw`. A publish may carry the same
+//! key to override the agent's format for one event.
+//!
+//! MessagePack is converted through the JSON data model: `bin` values become base64
+//! strings and extension types are rejected.
+
+use base64::Engine;
+use serde_json::{Map, Number, Value};
+
+use crate::{BridgeError, Result};
+
+/// Registration and event metadata key naming a payload format
+pub const PAYLOAD_FORMAT_KEY: &str = "payload-format";
+
+/// Deepest nesting accepted when decoding a MessagePack payload
+const MAX_DEPTH: usize = 128;
+
+/// Encoding of `Event.payload` on an agent's stream
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PayloadFormat {
+    /// Payload bytes as carried by the EventBus
+    #[default]
+    Raw,
+    /// UTF-8 JSON
+    Json,
+    /// MessagePack
+    Msgpack,
+}
+
+impl PayloadFormat {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PayloadFormat::Raw => "raw",
+            PayloadFormat::Json => "json",
+            PayloadFormat::Msgpack => "msgpack",
+        }
+    }
+
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "raw" => Some(PayloadFormat::Raw),
+            "json" => Some(PayloadFormat::Json),
+            "msgpack" | "messagepack" => Some(PayloadFormat::Msgpack),
+            _ => None,
+        }
+    }
+
+    /// Payload bytes for `value`; `Raw` has no JSON form and fails
+    pub fn encode(&self, value: &Value) -> Result<Vec<u8>> {
+        match self {
+            PayloadFormat::Raw => Err(BridgeError::Payload(
+                "raw payloads have no JSON form".into(),
+            )),
+            PayloadFormat::Json => Ok(serde_json::to_vec(value)
+                .map_err(|e| BridgeError::Payload(format!("cannot encode JSON: {e}")))?),
+            PayloadFormat::Msgpack => {
+                let mut out = Vec::new();
+                write_msgpack(value, &mut out);
+                Ok(out)
+            }
+        }
+    }
+
+    /// JSON value of a payload in this format
+    pub fn decode(&self, payload: &[u8]) -> Result<Value> {
+        match self {
+            PayloadFormat::Raw => Err(BridgeError::Payload(
+                "raw payloads have no JSON form".into(),
+            )),
+            PayloadFormat::Json => serde_json::from_slice(payload)
+                .map_err(|e| BridgeError::Payload(format!("payload is not JSON: {e}"))),
+            PayloadFormat::Msgpack => {
+                let mut buf = payload;
+                let value = read_msgpack(&mut buf, 0)?;
+                if !buf.is_empty() {
+                    return Err(msgpack_error("trailing bytes after value"));
+                }
+                Ok(value)
+            }
+        }
+    }
+}
+
+fn write_msgpack(value: &Value, out: &mut Vec<u8>) {
+    match value {
+        Value::Null => out.push(0xc0),
+        Value::Bool(b) => out.push(if *b { 0xc3 } else { 0xc2 }),
+        Value::Number(n) => {
+            if let Some(u) = n.as_u64() {
+                match u {
+                    0..=0x7f => out.push(u as u8),
+                    0x80..=0xff => out.extend([0xcc, u as u8]),
+                    0x100..=0xffff => {
+                        out.push(0xcd);
+                        out.extend((u as u16).to_be_bytes());
+                    }
+                    0x1_0000..=0xffff_ffff => {
+                        out.push(0xce);
+                        out.extend((u as u32).to_be_bytes());
+                    }
+                    _ => {
+                        out.push(0xcf);
+                        out.extend(u.to_be_bytes());
+                    }
+                }
+            } else if let Some(i) = n.as_i64() {
+                // Negative, as non-negative integers fit `as_u64`
+                match i {
+                    -32..=-1 => out.push(i as u8),
+                    -0x80..=-33 => out.extend([0xd0, i as u8]),
+                    -0x8000..=-0x81 => {
+                        out.push(0xd1);
+                        out.extend((i as i16).to_be_bytes());
+                    }
+                    -0x8000_0000..=-0x8001 => {
+                        out.push(0xd2);
+                        out.extend((i as i32).to_be_bytes());
+                    }
+                    _ => {
+                        out.push(0xd3);
+                        out.extend(i.to_be_bytes());
+                    }
+                }
+            } else {
+                out.push(0xcb);
+                out.extend(n.as_f64().unwrap_or_default().to_be_bytes());
+            }
+        }
+        Value::String(s) => {
+            write_len(out, s.len(), 0xa0, 32, [0xd9, 0xda, 0xdb]);
+            out.extend(s.as_bytes());
+        }
+        Value::Array(items) => {
+            write_len(out, items.len(), 0x90, 16, [0, 0xdc, 0xdd]);
+            for item in items {
+                write_msgpack(item, out);
+            }
+        }
+        Value::Object(map) => {
+            write_len(out, map.len(), 0x80, 16, [0, 0xde, 0xdf]);
+            for (key, item) in map {
+                write_msgpack(&Value::String(key.clone()), out);
+                write_msgpack(item, out);
+            }
+        }
+    }
+}
+
+/// Header of a string, array or map of `len` items: the `fix` form below `fix_limit`,
+/// then the 8-bit (strings only), 16-bit and 32-bit forms
+fn write_len(out: &mut Vec<u8>, len: usize, fix: u8, fix_limit: usize, markers: [u8; 3]) {
+    if len < fix_limit {
+        out.push(fix | len as u8);
+    } else if len <= 0xff && markers[0] != 0 {
+        out.extend([markers[0], len as u8]);
+    } else if len <= 0xffff {
+        out.push(markers[1]);
+        out.extend((len as u16).to_be_bytes());
+    } else {
+        out.push(markers[2]);
+        out.extend((len as u32).to_be_bytes());
+    }
+}
+
+fn read_msgpack(buf: &mut &[u8], depth: usize) -> Result<Value> {
+    if depth > MAX_DEPTH {
+        return Err(msgpack_error("nested too deeply"));
+    }
+    let marker = take(buf, 1)?[0];
+    Ok(match marker {
+        0x00..=0x7f => Value::from(marker),
+        0x80..=0x8f => read_map(buf, (marker & 0x0f) as usize, depth)?,
+        0x90..=0x9f => read_array(buf, (marker & 0x0f) as usize, depth)?,
+        0xa0..=0xbf => read_str(buf, (marker & 0x1f) as usize)?,
+        0xc0 => Value::Null,
+        0xc2 => Value::Bool(false),
+        0xc3 => Value::Bool(true),
+        0xc4..=0xc6 => {
+            let len = read_uint(buf, 1 << (marker - 0xc4))? as usize;
+            Value::String(base64::engine::general_purpose::STANDARD.encode(take(buf, len)?))
+        }
+        0xca => float(f32::from_be_bytes(array(buf)?) as f64)?,
+        0xcb => float(f64::from_be_bytes(array(buf)?))?,
+        0xcc..=0xcf => Value::from(read_uint(buf, 1 << (marker - 0xcc))?),
+        0xd0 => Value::from(i8::from_be_bytes(array(buf)?)),
+        0xd1 => Value::from(i16::from_be_bytes(array(buf)?)),
+        0xd2 => Value::from(i32::from_be_bytes(array(buf)?)),
+        0xd3 => Value::from(i64::from_be_bytes(array(buf)?)),
+        0xd9..=0xdb => {
+            let len = read_uint(buf, 1 << (marker - 0xd9))? as usize;
+            read_str(buf, len)?
+        }
+        0xdc | 0xdd => {
+            let len = read_uint(buf, 2 << (marker - 0xdc))? as usize;
+            read_array(buf, len, depth)?
+        }
+        0xde | 0xdf => {
+            let len = read_uint(buf, 2 << (marker - 0xde))? as usize;
+            read_map(buf, len, depth)?
+        }
+        0xe0..=0xff => Value::from(marker as i8),
+        0xc7..=0xc9 | 0xd4..=0xd8 => {
+            return Err(msgpack_error("extension types are not supported"))
+        }
+        _ => return Err(msgpack_error(&format!("invalid marker 0x{marker:02x}"))),
+    })
+}
+
+fn read_array(buf: &mut &[u8], len: usize, depth: usize) -> Result<Value> {
+    // Every item takes at least one byte, so a bogus length cannot force a large allocation
+    let mut items = Vec::with_capacity(len.min(buf.len()));
+    for _ in 0..len {
+        items.push(read_msgpack(buf, depth + 1)?);
+    }
+    Ok(Value::Array(items))
+}
+
+fn read_map(buf: &mut &[u8], len: usize, depth: usize) -> Result<Value> {
+    let mut map = Map::new();
+    for _ in 0..len {
+        let key = match read_msgpack(buf, depth + 1)? {
+            Value::String(s) => s,
+            other => other.to_string(),
+        };
+        map.insert(key, read_msgpack(buf, depth + 1)?);
+    }
+    Ok(Value::Object(map))
+}
+
+fn read_str(buf: &mut &[u8], len: usize) -> Result<Value> {
+    let bytes = take(buf, len)?;
+    String::from_utf8(bytes.to_vec())
+        .map(Value::String)
+        .map_err(|_| msgpack_error("string is not UTF-8"))
+}
+
+fn read_uint(buf: &mut &[u8], width: usize) -> Result<u64> {
+    Ok(take(buf, width)?
+        .iter()
+        .fold(0, |acc, b| (acc << 8) | *b as u64))
+}
+
+fn array<const N: usize>(buf: &mut &[u8]) -> Result<[u8; N]> {
+    Ok(take(buf, N)?.try_into().expect("length checked"))
+}
+
+fn take<'a>(buf: &mut &'a [u8], len: usize) -> Result<&'a [u8]> {
+    if buf.len() < len {
+        return Err(msgpack_error("truncated value"));
+    }
+    let (head, rest) = buf.split_at(len);
+    *buf = rest;
+    Ok(head)
+}
+
+fn float(f: f64) -> Result<Value> {
+    Number::from_f64(f)
+        .map(Value::Number)
+        .ok_or_else(|| msgpack_error("NaN and infinite floats have no JSON form"))
+}
+
+fn msgpack_error(reason: &str) -> BridgeError {
+    BridgeError::Payload(format!("invalid MessagePack payload: {reason}"))
+}
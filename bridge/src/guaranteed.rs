@@ -0,0 +1,85 @@
+//! Guaranteed topics: at-least-once delivery to Bridge agents.
+//!
+//! The Bridge subscribes to guaranteed topics with `QosGuaranteed`
+//! (`EventBus::subscribe_guaranteed`), so every event it receives on them carries a
+//! delivery receipt. The topic fanout hands the receipt off to each attached agent, which
+//! must ack the `Delivery` with `ClientEvent::Ack { message_id: <event id> }`. An unacked
+//! delivery is redelivered to the agent's current stream after the ack timeout, with a
+//! `receipt.attempt` counter (2, 3, ...), and published to the dead-letter topic
+//! (`dead_letter.<topic>`) once `max_deliveries` attempts are used up.
+//!
+//! Unlike critical topics (`EventBus::mark_critical`), only the Bridge's deliveries need
+//! acks; in-process subscribers of a guaranteed topic are unaffected.
+//!
+//! A subscription is guaranteed when the topic the agent subscribed to matches one of the
+//! patterns (`topic_matches`: an exact topic, or a prefix ending in `.*`), before tenant
+//! namespacing.
+//!
+//! Env for `GuaranteedTopics::from_env()`:
+//! - LOOM_BRIDGE_GUARANTEED_TOPICS (comma-separated patterns; unset disables)
+//! - LOOM_BRIDGE_ACK_TIMEOUT_MS (default 30000)
+//! - LOOM_BRIDGE_MAX_DELIVERIES (default 5)
+
+use std::time::Duration;
+
+use loom_core::{topic_matches, ReliableConfig};
+
+/// Topic patterns whose deliveries agents must ack, and the redelivery settings
+#[derive(Debug, Clone, Default)]
+pub struct GuaranteedTopics {
+    pub patterns: Vec<String>,
+    /// Ack timeout, attempts before dead-lettering and dead-letter topic
+    pub delivery: ReliableConfig,
+}
+
+impl GuaranteedTopics {
+    pub fn new<I, S>(patterns: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self {
+            patterns: patterns.into_iter().map(Into::into).collect(),
+            delivery: ReliableConfig::default(),
+        }
+    }
+
+    pub fn with_delivery(mut self, delivery: ReliableConfig) -> Self {
+        self.delivery = delivery;
+        self
+    }
+
+    pub fn from_env() -> Option<Self> {
+        let patterns: Vec<String> = std::env::var("LOOM_BRIDGE_GUARANTEED_TOPICS")
+            .ok()?
+            .split(',')
+            .map(str::trim)
+            .filter(|p| !p.is_empty())
+            .map(str::to_string)
+            .collect();
+        if patterns.is_empty() {
+            return None;
+        }
+        let mut delivery = ReliableConfig::default();
+        if let Some(ms) = std::env::var("LOOM_BRIDGE_ACK_TIMEOUT_MS")
+            .ok()
+            .and_then(|v| v.parse().ok())
+        {
+            delivery = delivery.with_ack_timeout(Duration::from_millis(ms));
+        }
+        if let Some(max) = std::env::var("LOOM_BRIDGE_MAX_DELIVERIES")
+            .ok()
+            .and_then(|v| v.parse().ok())
+        {
+            delivery = delivery.with_max_deliveries(max);
+        }
+        Some(Self { patterns, delivery })
+    }
+
+    /// Whether subscriptions on `topic` are guaranteed
+    pub fn matches(&self, topic: &str) -> bool {
+        self.patterns
+            .iter()
+            .any(|pattern| topic_matches(pattern, topic))
+    }
+}
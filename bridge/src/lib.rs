@@ -1,27 +1,74 @@
 //! Loom Bridge - gRPC gateway for external agents
 //!
 //! Provides registration, bidirectional event streaming, tool forwarding, and heartbeat
-//! for agents connecting via gRPC (Python, TypeScript, etc.)
+//! for agents connecting via gRPC (Python, TypeScript, etc.). `BridgePeer` (see `peer`)
+//! runs the other way, linking this process's EventBus to a remote Loom's Bridge.
+//!
+//! The protocol and the optional features (tenancy and namespaces, topic ACLs, payload
+//! formats, blobs, guaranteed topics, liveness, signing, peering) are described in
+//! `docs/BRIDGE.md`; each module documents its own part.
 
 use std::net::SocketAddr;
 use std::sync::Arc;
 
+pub mod acl;
+pub mod blobs;
+pub mod fanout;
+pub mod format;
+pub mod guaranteed;
+pub mod liveness;
+pub mod loadgen;
 pub mod memory_handler;
+pub mod payload;
+pub mod peer;
+pub mod peer_tools;
+pub mod remote_tools;
+pub mod replay;
+pub mod schema;
+#[cfg(feature = "soak")]
+pub mod soak;
 pub mod trading_memory;
 
+pub use acl::{AclRule, TopicAcl};
+pub use blobs::{BlobRelay, BlobRelayStats};
+pub use fanout::{FanoutStats, TopicFanout};
+pub use format::PayloadFormat;
+pub use guaranteed::GuaranteedTopics;
+pub use liveness::{LivenessChange, LivenessConfig, LivenessStats, LivenessTracker};
+pub use payload::{Codec, PayloadAccept, PayloadCodec, PayloadConfig};
+pub use peer::{BridgePeer, PeerConfig, PeerStats, ORIGIN_KEY};
+pub use peer_tools::PeerTool;
+pub use remote_tools::{RemoteAgentTool, RemoteTools};
+pub use replay::{ReplayConfig, ReplayOverflow, ReplayQueues, ReplayStats};
+pub use schema::{PayloadSchema, SchemaRegistry};
+
 use dashmap::DashMap;
-use tokio::{sync::mpsc, task::JoinHandle};
+use prost::Message;
+use std::time::Duration;
+
+use tokio::sync::{mpsc, oneshot};
+use tokio::task::JoinHandle;
 use tokio_stream::{wrappers::ReceiverStream, StreamExt};
 use tonic::{Request, Response, Status};
-use tracing::info;
+use tracing::{debug, info, warn, Instrument};
 
-use loom_core::{AgentDirectory, AgentInfo, AgentStatus, EventBus, ToolRegistry};
+use loom_core::blob::BlobStore;
+use loom_core::messaging::rate_limit::PUBLISHER_KEY;
+use loom_core::messaging::signing::{BRIDGE_INGRESS, INGRESS_KEY};
+use loom_core::namespace::NAMESPACE_KEY;
+use loom_core::tenancy::{namespace_topic, strip_namespace, TENANT_KEY};
+use loom_core::{
+    topic_matches, AgentDirectory, AgentInfo, AgentStatus, CancellationToken, EventBus, LoomError,
+    MemoryComponent, MemoryGovernor, Namespace, PressureLevel, StatsCollector, StatsSource,
+    TenantRegistry, ToolRegistry,
+};
 use loom_proto::{
     bridge_server::{Bridge, BridgeServer},
     client_event,
     memory_service_server::MemoryServiceServer,
-    server_event, AgentRegisterRequest, AgentRegisterResponse, ClientEvent, Delivery,
-    HeartbeatRequest, HeartbeatResponse, ServerEvent, ToolCall, ToolDescriptor, ToolResult,
+    server_event, AgentDescriptor, AgentRegisterRequest, AgentRegisterResponse, ClientEvent,
+    HeartbeatRequest, HeartbeatResponse, ListAgentsRequest, ListAgentsResponse, ListToolsRequest,
+    ListToolsResponse, ProviderKind, ServerEvent, ToolCall, ToolCancel, ToolDescriptor, ToolResult,
     ToolStatus,
 };
 
@@ -31,6 +78,18 @@ pub enum BridgeError {
     Registration(String),
     #[error("internal error: {0}")]
     Internal(String),
+    #[error("timed out waiting for tool result: {0}")]
+    Timeout(String),
+    #[error("agent disconnected: {0}")]
+    AgentDisconnected(String),
+    #[error("tool call cancelled: {0}")]
+    Cancelled(String),
+    #[error("publish denied: {0}")]
+    PublishDenied(String),
+    #[error("invalid configuration: {0}")]
+    Config(String),
+    #[error("invalid payload: {0}")]
+    Payload(String),
 }
 
 pub type Result<T> = std::result::Result<T, BridgeError>;
@@ -48,25 +107,58 @@ pub struct BridgeState {
     pub subscriptions: Arc<DashMap<String, Vec<String>>>,
     // agent_id -> tools provided by the agent
     pub agent_tools: Arc<DashMap<String, Vec<ToolDescriptor>>>,
+    // Agents' tools registered in `tool_registry` for local callers (see `remote_tools`)
+    pub remote_tools: Arc<RemoteTools>,
     // agent_id -> sender to push ServerEvent into gRPC stream task
     pub streams: Arc<DashMap<String, mpsc::Sender<ServerEvent>>>,
     // tool_call_id -> ToolResult received from agent (server-push correlation)
     pub tool_results: Arc<DashMap<String, ToolResult>>,
-    // agent_id -> event bus subscription ids for cleanup
-    pub subscription_ids: Arc<DashMap<String, Vec<String>>>,
-    // agent_id -> task handles for forwarding loops (abort on disconnect)
-    pub forwarding_tasks: Arc<DashMap<String, Vec<JoinHandle<()>>>>,
+    // topic -> shared EventBus subscription fanned out to agent streams
+    pub fanout: Arc<TopicFanout>,
     // agent_id -> list of tool_result ids for cleanup
     pub tool_result_index: Arc<DashMap<String, Vec<String>>>,
+    // tool_call_id -> agent_id the call was pushed to (until its result arrives)
+    pub pending_tool_calls: Arc<DashMap<String, String>>,
+    // tool_call_id -> waiter registered by await_tool_result
+    pub tool_waiters: Arc<DashMap<String, ToolResultWaiter>>,
+    // agent_id -> deliveries buffered while the agent's stream is down
+    pub replay: Arc<ReplayQueues>,
+    // Tenants authenticating agents; None disables tenancy
+    pub tenants: Option<Arc<TenantRegistry>>,
+    // agent_id -> namespace assigned at registration (the owning tenant under tenancy)
+    pub agent_namespaces: Arc<DashMap<String, Namespace>>,
+    // Topic publish rules; None allows every publish
+    pub topic_acl: Option<Arc<TopicAcl>>,
+    // Payload compression/chunking and format negotiated per agent
+    pub payloads: Arc<PayloadCodec>,
+    // Event type -> payload schema, for transcoding payload formats
+    pub schemas: Arc<SchemaRegistry>,
+    // agent_id -> when it was last heard from, and its stream's cancellation
+    pub liveness: Arc<LivenessTracker>,
+    // Topics whose deliveries agents must ack; None requires acks on critical topics only
+    pub guaranteed: Option<Arc<GuaranteedTopics>>,
+    // Blob store large publishes are stashed in; None passes payloads through
+    pub blobs: Option<Arc<BlobRelay>>,
 }
 
+/// Resolves an `await_tool_result` call with the result or a disconnect error
+pub type ToolResultWaiter = oneshot::Sender<Result<ToolResult>>;
+
 impl BridgeState {
     pub fn new(
         event_bus: Arc<EventBus>,
         tool_registry: Arc<ToolRegistry>,
         agent_directory: Arc<AgentDirectory>,
     ) -> Self {
+        let schemas = Arc::new(SchemaRegistry::new());
+        let payloads = Arc::new(PayloadCodec::default().with_schemas(Arc::clone(&schemas)));
         Self {
+            fanout: Arc::new(
+                TopicFanout::new(Arc::clone(&event_bus), None)
+                    .with_payloads(Arc::clone(&payloads))
+                    .with_tenant_namespaces(),
+            ),
+            remote_tools: Arc::new(RemoteTools::new(Arc::clone(&tool_registry))),
             event_bus,
             tool_registry,
             agent_directory,
@@ -76,12 +168,32 @@ impl BridgeState {
             agent_tools: Arc::new(DashMap::new()),
             streams: Arc::new(DashMap::new()),
             tool_results: Arc::new(DashMap::new()),
-            subscription_ids: Arc::new(DashMap::new()),
-            forwarding_tasks: Arc::new(DashMap::new()),
             tool_result_index: Arc::new(DashMap::new()),
+            pending_tool_calls: Arc::new(DashMap::new()),
+            tool_waiters: Arc::new(DashMap::new()),
+            replay: Arc::new(ReplayQueues::new(ReplayConfig::default())),
+            tenants: None,
+            agent_namespaces: Arc::new(DashMap::new()),
+            topic_acl: None,
+            payloads,
+            schemas,
+            liveness: Arc::new(LivenessTracker::new(LivenessConfig::default())),
+            guaranteed: None,
+            blobs: None,
         }
     }
 
+    /// Buffer deliveries for disconnected agents and replay them when they reconnect
+    /// within `config.ttl` (disabled by default)
+    pub fn set_replay_config(&mut self, config: ReplayConfig) {
+        self.replay = Arc::new(ReplayQueues::new(config));
+    }
+
+    /// Heartbeat expectations applied by the liveness monitor (see `liveness`)
+    pub fn set_liveness_config(&mut self, config: LivenessConfig) {
+        self.liveness = Arc::new(LivenessTracker::new(config));
+    }
+
     /// Set dashboard broadcaster for event notifications
     pub fn set_dashboard_broadcaster(
         &mut self,
@@ -93,6 +205,204 @@ impl BridgeState {
     /// Set flow tracker for event flow visualization
     pub fn set_flow_tracker(&mut self, flow_tracker: Arc<loom_core::dashboard::FlowTracker>) {
         self.flow_tracker = Some(flow_tracker);
+        self.rebuild_fanout();
+    }
+
+    /// Require bearer-token authentication and isolate agents by tenant
+    pub fn set_tenants(&mut self, tenants: Arc<TenantRegistry>) {
+        self.tenants = Some(tenants);
+        self.rebuild_fanout();
+    }
+
+    /// Enforce `acl` on every publish from an agent's event stream
+    pub fn set_topic_acl(&mut self, acl: Arc<TopicAcl>) {
+        self.topic_acl = Some(acl);
+    }
+
+    /// Require agents to ack every delivery on `topics`, redelivering and dead-lettering
+    /// unacked ones (see `guaranteed`). Applies to topics agents attach to from now on.
+    pub fn set_guaranteed_topics(&mut self, topics: GuaranteedTopics) {
+        self.guaranteed = Some(Arc::new(topics));
+        self.rebuild_fanout();
+    }
+
+    /// Compress and chunk large payloads per `config` (see `payload`)
+    pub fn set_payload_config(&mut self, config: PayloadConfig) {
+        self.payloads = Arc::new(PayloadCodec::new(config).with_schemas(Arc::clone(&self.schemas)));
+        self.rebuild_fanout();
+    }
+
+    /// Stash large publishes in `store` and resolve the references on delivery (see
+    /// `blobs`)
+    pub fn set_blob_store(&mut self, store: Arc<BlobStore>) {
+        self.blobs = Some(Arc::new(BlobRelay::new(
+            store,
+            Arc::clone(&self.agent_namespaces),
+        )));
+        self.rebuild_fanout();
+    }
+
+    /// Transcode payload formats with the event types registered in `schemas` (see `schema`)
+    pub fn set_schema_registry(&mut self, schemas: Arc<SchemaRegistry>) {
+        self.schemas = schemas;
+        self.set_payload_config(self.payloads.config().clone());
+    }
+
+    /// Apply the liveness changes due now: degraded agents are marked `Degraded`; dead
+    /// agents have their stream closed (its cleanup marks them Disconnected) or, without
+    /// a stream, are marked Disconnected here. Returns the changes applied.
+    pub fn check_liveness(&self) -> Vec<LivenessChange> {
+        let changes = self.liveness.check(tokio::time::Instant::now());
+        for change in &changes {
+            match change {
+                LivenessChange::Degraded { agent_id, missed } => {
+                    warn!(agent_id=%agent_id, missed=missed, "Agent missed heartbeats, marking degraded");
+                    self.agent_directory
+                        .update_status(agent_id, AgentStatus::Degraded);
+                    self.notify_dashboard(liveness::liveness_event(
+                        loom_core::dashboard::DashboardEventType::AgentDegraded,
+                        agent_id,
+                        format!("Agent {} missed {} heartbeats", agent_id, missed),
+                    ));
+                }
+                LivenessChange::Dead { agent_id, missed } => {
+                    warn!(agent_id=%agent_id, missed=missed, "Agent missed heartbeats, disconnecting");
+                    if self.liveness.close_stream(agent_id) {
+                        continue;
+                    }
+                    self.agent_directory.mark_disconnected(agent_id);
+                    if let (Some(tenants), Some(ns)) =
+                        (&self.tenants, self.agent_namespaces.get(agent_id))
+                    {
+                        tenants.release_agent(ns.as_str(), agent_id);
+                    }
+                    self.notify_dashboard(liveness::liveness_event(
+                        loom_core::dashboard::DashboardEventType::AgentUnregistered,
+                        agent_id,
+                        format!("Agent {} missed {} heartbeats", agent_id, missed),
+                    ));
+                    if self.liveness.config().unregister {
+                        unregister_dead_agent(
+                            &self.agent_directory,
+                            &self.subscriptions,
+                            &self.agent_tools,
+                            &self.remote_tools,
+                            agent_id,
+                        );
+                    }
+                }
+            }
+        }
+        changes
+    }
+
+    /// Run `check_liveness` twice per heartbeat interval until the task is aborted;
+    /// `None` when liveness checks are disabled
+    pub fn spawn_liveness_monitor(&self) -> Option<JoinHandle<()>> {
+        let config = self.liveness.config().clone();
+        if !config.enabled() {
+            return None;
+        }
+        let state = self.clone();
+        info!(
+            interval_ms = config.heartbeat_interval.as_millis() as u64,
+            degraded_after = config.degraded_after,
+            disconnect_after = config.disconnect_after,
+            "Starting Bridge liveness monitor"
+        );
+        Some(tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(config.heartbeat_interval / 2);
+            ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            loop {
+                ticker.tick().await;
+                state.check_liveness();
+            }
+        }))
+    }
+
+    fn notify_dashboard(&self, event: loom_core::dashboard::DashboardEvent) {
+        if let Some(ref broadcaster) = self.dashboard_broadcaster {
+            broadcaster.broadcast(event);
+        }
+    }
+
+    fn rebuild_fanout(&mut self) {
+        // Namespaced agents are delivered namespace-relative topics
+        let mut fanout = TopicFanout::new(Arc::clone(&self.event_bus), self.flow_tracker.clone())
+            .with_payloads(Arc::clone(&self.payloads))
+            .with_tenant_namespaces();
+        if let Some(ref guaranteed) = self.guaranteed {
+            fanout = fanout.with_guaranteed(Arc::clone(guaranteed));
+        }
+        if let Some(ref blobs) = self.blobs {
+            fanout = fanout.with_blobs(Arc::clone(blobs));
+        }
+        self.fanout = Arc::new(fanout);
+    }
+}
+
+/// Stored tool results are the only state the Bridge grows without bound (results
+/// nobody has awaited yet). Under pressure they are dropped; a later
+/// `await_tool_result` for a dropped result times out.
+#[tonic::async_trait]
+impl MemoryComponent for BridgeState {
+    fn name(&self) -> String {
+        "bridge.tool_results".to_string()
+    }
+
+    fn shed_priority(&self) -> u8 {
+        50
+    }
+
+    async fn memory_usage(&self) -> usize {
+        self.tool_results
+            .iter()
+            .map(|e| e.key().len() + e.value().encoded_len() + 32)
+            .sum()
+    }
+
+    async fn shed(&self, bytes: usize, _level: PressureLevel) -> usize {
+        let ids: Vec<String> = self.tool_results.iter().map(|e| e.key().clone()).collect();
+        let mut freed = 0;
+        for id in ids {
+            if freed >= bytes {
+                break;
+            }
+            if let Some((id, result)) = self.tool_results.remove(&id) {
+                freed += id.len() + result.encoded_len() + 32;
+            }
+        }
+        if freed > 0 {
+            warn!(
+                freed_bytes = freed,
+                "Dropped unclaimed tool results under memory pressure"
+            );
+        }
+        freed
+    }
+}
+
+/// The `bridge` section of `Loom::stats()`: connections and delivery counters
+#[tonic::async_trait]
+impl StatsSource for BridgeState {
+    fn name(&self) -> String {
+        "bridge".to_string()
+    }
+
+    async fn stats(&self) -> serde_json::Value {
+        let mut connected: Vec<String> = self.streams.iter().map(|e| e.key().clone()).collect();
+        connected.sort();
+        serde_json::json!({
+            "registered_agents": self.subscriptions.len(),
+            "connections": connected.len(),
+            "connected_agents": connected,
+            "pending_tool_calls": self.pending_tool_calls.len(),
+            "unclaimed_tool_results": self.tool_results.len(),
+            "fanout": self.fanout.stats().await,
+            "replay": self.replay.stats(),
+            "liveness": self.liveness.stats(),
+            "blobs": self.blobs.as_ref().map(|b| b.stats()),
+        })
     }
 }
 
@@ -108,19 +418,120 @@ impl BridgeService {
 
     /// Push a ToolCall to an agent's active stream; returns Ok(true) if delivered.
     pub async fn push_tool_call(&self, agent_id: &str, call: ToolCall) -> Result<bool> {
-        if let Some(sender) = self.state.streams.get(agent_id) {
-            let server_event = ServerEvent {
-                msg: Some(server_event::Msg::ToolCall(call)),
-            };
-            match sender.send(server_event).await {
-                Ok(_) => Ok(true),
-                Err(e) => Err(BridgeError::Internal(format!(
+        // Clone the sender so no map guard is held across the send
+        let Some(sender) = self.state.streams.get(agent_id).map(|s| s.clone()) else {
+            return Ok(false);
+        };
+        let call_id = call.id.clone();
+        self.state
+            .pending_tool_calls
+            .insert(call_id.clone(), agent_id.to_string());
+        let server_event = ServerEvent {
+            msg: Some(server_event::Msg::ToolCall(call)),
+        };
+        match sender.send(server_event).await {
+            Ok(_) => Ok(true),
+            Err(e) => {
+                self.state.pending_tool_calls.remove(&call_id);
+                Err(BridgeError::Internal(format!(
                     "failed to send tool_call: {}",
                     e
-                ))),
+                )))
+            }
+        }
+    }
+
+    /// Cancel a call previously sent with `push_tool_call`.
+    ///
+    /// The agent gets a `ToolCancel` on its stream (skipped if the stream is gone or
+    /// full; the call is cancelled on the Bridge side regardless) and a pending
+    /// `await_tool_result` fails with `BridgeError::Cancelled`. A result the agent sends
+    /// anyway is kept like any late result. Returns false if the call was not pending.
+    pub fn cancel_tool_call(&self, call_id: &str, reason: &str) -> bool {
+        let Some((_, agent_id)) = self.state.pending_tool_calls.remove(call_id) else {
+            return false;
+        };
+        if let Some(sender) = self.state.streams.get(&agent_id).map(|s| s.clone()) {
+            let cancel = ServerEvent {
+                msg: Some(server_event::Msg::ToolCancel(ToolCancel {
+                    id: call_id.to_string(),
+                    reason: reason.to_string(),
+                })),
+            };
+            if let Err(e) = sender.try_send(cancel) {
+                warn!(agent_id = %agent_id, call_id = %call_id, error = %e, "Failed to send tool_cancel");
+            }
+        }
+        if let Some((_, waiter)) = self.state.tool_waiters.remove(call_id) {
+            let _ = waiter.send(Err(BridgeError::Cancelled(call_id.to_string())));
+        }
+        info!(agent_id = %agent_id, call_id = %call_id, reason = %reason, "Cancelled tool call");
+        true
+    }
+
+    /// `await_tool_result` that cancels the call (see `cancel_tool_call`) and fails with
+    /// `BridgeError::Cancelled` once `cancel` fires
+    pub async fn await_tool_result_with_cancel(
+        &self,
+        call_id: &str,
+        timeout: Duration,
+        cancel: &CancellationToken,
+    ) -> Result<ToolResult> {
+        tokio::select! {
+            biased;
+            result = self.await_tool_result(call_id, timeout) => result,
+            _ = cancel.cancelled() => {
+                self.cancel_tool_call(call_id, "cancelled by caller");
+                Err(BridgeError::Cancelled(call_id.to_string()))
+            }
+        }
+    }
+
+    /// Wait for the ToolResult of a call previously sent with `push_tool_call`.
+    ///
+    /// Resolves as soon as the agent replies on its stream. Fails with
+    /// `BridgeError::Timeout` if no result arrives within `timeout`, with
+    /// `BridgeError::AgentDisconnected` if the agent's stream ends first, and with
+    /// `BridgeError::Cancelled` if the call is cancelled.
+    /// A result returned here is consumed and no longer visible via `get_tool_result`.
+    pub async fn await_tool_result(&self, call_id: &str, timeout: Duration) -> Result<ToolResult> {
+        if let Some((_, result)) = self.state.tool_results.remove(call_id) {
+            return Ok(result);
+        }
+
+        let (tx, rx) = oneshot::channel();
+        self.state.tool_waiters.insert(call_id.to_string(), tx);
+
+        // The result may have landed between the first check and registering the waiter
+        if let Some((_, result)) = self.state.tool_results.remove(call_id) {
+            self.state.tool_waiters.remove(call_id);
+            return Ok(result);
+        }
+
+        // Agent already gone: the call can never complete
+        if let Some(agent_id) = self
+            .state
+            .pending_tool_calls
+            .get(call_id)
+            .map(|a| a.clone())
+        {
+            if !self.state.streams.contains_key(&agent_id) {
+                self.state.tool_waiters.remove(call_id);
+                self.state.pending_tool_calls.remove(call_id);
+                return Err(BridgeError::AgentDisconnected(agent_id));
+            }
+        }
+
+        match tokio::time::timeout(timeout, rx).await {
+            Ok(Ok(outcome)) => outcome,
+            Ok(Err(_)) => Err(BridgeError::Internal(format!(
+                "waiter for tool call {} dropped",
+                call_id
+            ))),
+            Err(_) => {
+                self.state.tool_waiters.remove(call_id);
+                Err(BridgeError::Timeout(call_id.to_string()))
             }
-        } else {
-            Ok(false)
         }
     }
 
@@ -128,6 +539,18 @@ impl BridgeService {
     pub fn get_tool_result(&self, call_id: &str) -> Option<ToolResult> {
         self.state.tool_results.get(call_id).map(|e| e.clone())
     }
+
+    /// Tenant selected by the request's bearer token; `None` when tenancy is disabled
+    #[allow(clippy::result_large_err)]
+    fn authenticate<T>(&self, request: &Request<T>) -> std::result::Result<Option<String>, Status> {
+        let Some(ref tenants) = self.state.tenants else {
+            return Ok(None);
+        };
+        bearer_token(request)
+            .and_then(|token| tenants.authenticate(token))
+            .map(Some)
+            .ok_or_else(|| Status::unauthenticated("missing or invalid bearer token"))
+    }
 }
 
 #[tonic::async_trait]
@@ -136,7 +559,8 @@ impl Bridge for BridgeService {
         &self,
         request: Request<AgentRegisterRequest>,
     ) -> std::result::Result<Response<AgentRegisterResponse>, Status> {
-        let req = request.into_inner();
+        let tenant = self.authenticate(&request)?;
+        let mut req = request.into_inner();
         let agent_id = req.agent_id.clone();
         if agent_id.is_empty() {
             return Ok(Response::new(AgentRegisterResponse {
@@ -144,12 +568,60 @@ impl Bridge for BridgeService {
                 error_message: "agent_id cannot be empty".into(),
             }));
         }
+        let namespace = assign_namespace(tenant.as_deref(), req.metadata.get(NAMESPACE_KEY))?;
+        if let (Some(tenants), Some(tenant)) = (&self.state.tenants, &tenant) {
+            // An agent id stays bound to the first tenant that registered it
+            let owner = self
+                .state
+                .agent_namespaces
+                .get(&agent_id)
+                .map(|ns| ns.to_string());
+            if owner.is_some_and(|owner| owner != *tenant) {
+                return Err(Status::permission_denied(format!(
+                    "agent_id {} belongs to another tenant",
+                    agent_id
+                )));
+            }
+            if let Err(e) = tenants.admit_agent(tenant, &agent_id) {
+                return Ok(Response::new(AgentRegisterResponse {
+                    success: false,
+                    error_message: e.to_string(),
+                }));
+            }
+        }
+        match &namespace {
+            Some(ns) => {
+                self.state
+                    .agent_namespaces
+                    .insert(agent_id.clone(), ns.clone());
+                req.subscribed_topics = req
+                    .subscribed_topics
+                    .iter()
+                    .map(|topic| ns.topic(topic))
+                    .collect();
+                req.metadata.insert(TENANT_KEY.to_string(), ns.to_string());
+            }
+            None => {
+                self.state.agent_namespaces.remove(&agent_id);
+            }
+        }
         self.state
             .subscriptions
             .insert(agent_id.clone(), req.subscribed_topics.clone());
         self.state
             .agent_tools
             .insert(agent_id.clone(), req.tools.clone());
+        self.state
+            .remote_tools
+            .expose(self, &agent_id, namespace.as_ref(), &req.tools)
+            .await;
+        self.state.payloads.negotiate(&agent_id, &req.metadata);
+        self.state
+            .fanout
+            .negotiate_batching(&agent_id, &req.metadata);
+        if let Some(ref blobs) = self.state.blobs {
+            blobs.negotiate(&agent_id, &req.metadata);
+        }
 
         // Register agent in AgentDirectory for Dashboard visibility
         let tool_names: Vec<String> = req.tools.iter().map(|t| t.name.clone()).collect();
@@ -158,10 +630,11 @@ impl Bridge for BridgeService {
             agent_id: agent_id.clone(),
             subscribed_topics: req.subscribed_topics.clone(),
             capabilities: tool_names,
-            metadata: std::collections::HashMap::new(),
+            metadata: req.metadata.clone(),
             last_heartbeat: Some(now),
             status: AgentStatus::Active,
         });
+        self.state.liveness.register(&agent_id);
 
         // Broadcast AgentRegistered event to Dashboard
         if let Some(ref broadcaster) = self.state.dashboard_broadcaster {
@@ -199,6 +672,12 @@ impl Bridge for BridgeService {
         &self,
         request: Request<tonic::Streaming<ClientEvent>>,
     ) -> std::result::Result<Response<Self::EventStreamStream>, Status> {
+        let tenant = self.authenticate(&request)?;
+        // Token ACL rules only apply to tokens the tenant registry has verified
+        let token = tenant
+            .as_ref()
+            .and(bearer_token(&request))
+            .map(str::to_string);
         let mut inbound = request.into_inner();
 
         // Expect first message to be an Ack containing agent_id in message_id for simplicity (lightweight handshake)
@@ -218,127 +697,168 @@ impl Bridge for BridgeService {
         // Record agent_id in span
         tracing::Span::current().record("agent_id", &agent_id);
 
+        let namespace = self
+            .state
+            .agent_namespaces
+            .get(&agent_id)
+            .map(|ns| ns.clone());
+        if tenant.is_some() && namespace.as_ref().map(Namespace::to_string) != tenant {
+            return Err(Status::permission_denied(
+                "agent is not registered for this tenant",
+            ));
+        }
+
         // Create outbound channel
         let (tx, rx) = mpsc::channel::<ServerEvent>(512);
         self.state.streams.insert(agent_id.clone(), tx.clone());
+        // Cancelled by the liveness monitor if the agent stops heartbeating
+        let (stream_id, dead) = self.state.liveness.watch_stream(&agent_id);
         let agent_id_for_inbound = agent_id.clone();
 
-        // For each subscribed topic, subscribe and spawn a forwarding task, tracking ids and handles
-        if let Some(topics) = self.state.subscriptions.get(&agent_id).map(|v| v.clone()) {
-            let mut sub_ids: Vec<String> = Vec::new();
-            let mut handles: Vec<JoinHandle<()>> = Vec::new();
-            for topic in topics.iter() {
-                let topic_clone = topic.clone();
-                let event_bus_local = Arc::clone(&self.state.event_bus);
-                let flow_tracker = self.state.flow_tracker.clone();
-                let agent_id_for_flow = agent_id.clone();
-                // subscribe first to capture subscription id and receiver
-                if let Ok((sub_id, mut rx_bus)) = event_bus_local
-                    .subscribe(
-                        topic_clone.clone(),
-                        vec![],
-                        loom_proto::QoSLevel::QosBatched,
-                    )
-                    .await
-                {
-                    sub_ids.push(sub_id.clone());
-                    let tx_clone = tx.clone();
-                    let handle: JoinHandle<()> = tokio::spawn(async move {
-                        while let Some(ev) = rx_bus.recv().await {
-                            // Record flow: subscription -> agent
-                            if let Some(ref tracker) = flow_tracker {
-                                tracker
-                                    .record_flow(&sub_id, &agent_id_for_flow, &topic_clone)
-                                    .await;
-                            }
-
-                            // Create a span for forwarding this event to the agent stream
-                            let fwd_span = tracing::info_span!(
-                                "bridge.forward",
-                                topic = %topic_clone,
-                                agent_id = %agent_id_for_flow,
-                                event_id = %ev.id,
-                                trace_id = tracing::field::Empty,
-                                span_id = tracing::field::Empty
-                            );
-                            let _fwd_guard = fwd_span.enter();
-
-                            // Apply remote parent if present
-                            let env = loom_core::Envelope::from_event(&ev);
-                            if env.extract_trace_context() {
-                                tracing::Span::current()
-                                    .record("trace_id", &tracing::field::display(&env.trace_id));
-                                tracing::Span::current()
-                                    .record("span_id", &tracing::field::display(&env.span_id));
-                            }
-
-                            if tx_clone
-                                .send(ServerEvent {
-                                    msg: Some(server_event::Msg::Delivery(Delivery {
-                                        topic: topic_clone.clone(),
-                                        event: Some(ev),
-                                    })),
-                                })
-                                .await
-                                .is_err()
-                            {
-                                break; // stream dropped
-                            }
-                        }
-                        // Ensure unsubscribe to release EventBus resources on normal end
-                        let _ = event_bus_local.unsubscribe(&sub_id).await;
-                    });
-                    handles.push(handle);
-                }
+        // Attach this stream to the shared subscription of each subscribed topic
+        let topics = self
+            .state
+            .subscriptions
+            .get(&agent_id)
+            .map(|v| v.clone())
+            .unwrap_or_default();
+        for topic in topics.iter() {
+            if let Err(e) = self.state.fanout.attach(&agent_id, topic, tx.clone()).await {
+                warn!(agent_id=%agent_id, topic=%topic, error=%e, "Failed to attach agent to topic");
             }
-            // record for cleanup
-            self.state
-                .subscription_ids
-                .insert(agent_id.clone(), sub_ids);
-            self.state
-                .forwarding_tasks
-                .insert(agent_id.clone(), handles);
         }
 
+        // Deliveries missed while the previous stream was down go out ahead of live ones
+        let replayed = self
+            .state
+            .replay
+            .resume(&agent_id, &self.state.fanout)
+            .await;
+
         // Spawn task handling inbound messages
         let event_bus = Arc::clone(&self.state.event_bus);
         let tx_in = tx.clone();
         let streams_map = self.state.streams.clone();
         let tool_results = self.state.tool_results.clone();
-        let subscription_ids = self.state.subscription_ids.clone();
-        let forwarding_tasks = self.state.forwarding_tasks.clone();
+        let fanout = Arc::clone(&self.state.fanout);
+        let pending_tool_calls = self.state.pending_tool_calls.clone();
+        let tool_waiters = self.state.tool_waiters.clone();
         let tool_result_index = self.state.tool_result_index.clone();
         let agent_directory = Arc::clone(&self.state.agent_directory);
         let dashboard_broadcaster = self.state.dashboard_broadcaster.clone();
+        let replay = Arc::clone(&self.state.replay);
+        let tenants = self.state.tenants.clone();
+        let topic_acl = self.state.topic_acl.clone();
+        let payloads = Arc::clone(&self.state.payloads);
+        let blobs = self.state.blobs.clone();
+        let liveness = Arc::clone(&self.state.liveness);
+        let subscriptions = self.state.subscriptions.clone();
+        let agent_tools = self.state.agent_tools.clone();
+        let remote_tools = Arc::clone(&self.state.remote_tools);
         tokio::spawn(async move {
-            while let Some(Ok(msg)) = inbound.message().await.transpose() {
+            loop {
+                let msg = tokio::select! {
+                    msg = inbound.message() => match msg {
+                        Ok(Some(msg)) => msg,
+                        _ => break,
+                    },
+                    _ = dead.cancelled() => break,
+                };
+                // Any message counts as a heartbeat
+                if liveness.touch(&agent_id_for_inbound) == Some(true) {
+                    info!(agent_id=%agent_id_for_inbound, "Degraded agent recovered");
+                    agent_directory.update_status(&agent_id_for_inbound, AgentStatus::Active);
+                    if let Some(ref broadcaster) = dashboard_broadcaster {
+                        broadcaster.broadcast(liveness::liveness_event(
+                            loom_core::dashboard::DashboardEventType::AgentRecovered,
+                            &agent_id_for_inbound,
+                            format!("Agent {} recovered", agent_id_for_inbound),
+                        ));
+                    }
+                }
                 match msg.msg {
                     Some(client_event::Msg::Publish(p)) => {
-                        if let (Some(ev), topic) = (p.event, p.topic) {
-                            // Build span first, then enter and set remote parent on THIS span
-                            let span = tracing::info_span!(
-                                "bridge.publish",
-                                agent_id = %agent_id_for_inbound,
-                                topic = %topic,
-                                event_id = %ev.id,
-                                trace_id = tracing::field::Empty,
-                                span_id = tracing::field::Empty
-                            );
-                            let _guard = span.enter();
-
-                            // Extract trace context from event and set as parent of current span
-                            let envelope = loom_core::Envelope::from_event(&ev);
-                            if envelope.extract_trace_context() {
-                                // Record extracted identifiers on the span for debugging/visibility
-                                tracing::Span::current().record(
-                                    "trace_id",
-                                    &tracing::field::display(&envelope.trace_id),
-                                );
-                                tracing::Span::current()
-                                    .record("span_id", &tracing::field::display(&envelope.span_id));
-                            }
+                        let Some(ev) = p.event else { continue };
+                        let Some((topic, mut events)) = admit_publish(
+                            &agent_id_for_inbound,
+                            p.topic,
+                            vec![ev],
+                            &payloads,
+                            topic_acl.as_deref(),
+                            token.as_deref(),
+                            tenants.as_deref().zip(tenant.as_deref()),
+                            namespace.as_ref(),
+                            &tx_in,
+                        ) else {
+                            continue;
+                        };
+                        if let Some(ref blobs) = blobs {
+                            events =
+                                stash_blobs(blobs, &agent_id_for_inbound, events, &tx_in).await;
+                        }
+                        let Some(ev) = events.pop() else { continue };
+
+                        // Build span first, then enter and set remote parent on THIS span
+                        let span = tracing::info_span!(
+                            "bridge.publish",
+                            agent_id = %agent_id_for_inbound,
+                            topic = %topic,
+                            event_id = %ev.id,
+                            trace_id = tracing::field::Empty,
+                            span_id = tracing::field::Empty
+                        );
+                        let _guard = span.enter();
+
+                        // Extract trace context from event and set as parent of current span
+                        let envelope = loom_core::Envelope::from_event(&ev);
+                        if envelope.extract_trace_context() {
+                            // Record extracted identifiers on the span for debugging/visibility
+                            tracing::Span::current()
+                                .record("trace_id", tracing::field::display(&envelope.trace_id));
+                            tracing::Span::current()
+                                .record("span_id", tracing::field::display(&envelope.span_id));
+                        }
 
-                            let _ = event_bus.publish(&topic, ev).await;
+                        if let Err(LoomError::SignatureError(e)) =
+                            event_bus.publish(&topic, ev).await
+                        {
+                            reject(&tx_in, "SIGNATURE_INVALID", e);
+                        }
+                    }
+                    Some(client_event::Msg::PublishBatch(batch)) => {
+                        let Some((topic, mut events)) = admit_publish(
+                            &agent_id_for_inbound,
+                            batch.topic,
+                            batch.events,
+                            &payloads,
+                            topic_acl.as_deref(),
+                            token.as_deref(),
+                            tenants.as_deref().zip(tenant.as_deref()),
+                            namespace.as_ref(),
+                            &tx_in,
+                        ) else {
+                            continue;
+                        };
+                        if let Some(ref blobs) = blobs {
+                            events =
+                                stash_blobs(blobs, &agent_id_for_inbound, events, &tx_in).await;
+                            if events.is_empty() {
+                                continue;
+                            }
+                        }
+                        // Each event continues its own trace inside `publish_batch`
+                        let span = tracing::info_span!(
+                            "bridge.publish_batch",
+                            agent_id = %agent_id_for_inbound,
+                            topic = %topic,
+                            events = events.len()
+                        );
+                        if let Err(LoomError::SignatureError(e)) = event_bus
+                            .publish_batch(&topic, events)
+                            .instrument(span)
+                            .await
+                        {
+                            reject(&tx_in, "SIGNATURE_INVALID", e);
                         }
                     }
                     Some(client_event::Msg::Ping(_hb)) => {
@@ -356,27 +876,54 @@ impl Bridge for BridgeService {
                     }
                     Some(client_event::Msg::ToolResult(tr)) => {
                         info!(tool_id=%tr.id, "Received tool result from agent");
+                        pending_tool_calls.remove(&tr.id);
+                        // Hand the result to a waiter if one is registered, otherwise store it
+                        let tr = match tool_waiters.remove(&tr.id) {
+                            Some((_, waiter)) => match waiter.send(Ok(tr)) {
+                                Ok(()) => continue,
+                                // waiter gave up (timed out); keep the late result
+                                Err(Ok(tr)) => tr,
+                                Err(Err(_)) => continue,
+                            },
+                            None => tr,
+                        };
                         tool_results.insert(tr.id.clone(), tr.clone());
                         // index this result under agent for cleanup
                         tool_result_index
                             .entry(agent_id_for_inbound.clone())
-                            .or_insert_with(Vec::new)
+                            .or_default()
                             .push(tr.id);
                     }
-                    Some(client_event::Msg::Ack(_)) => { /* ignore */ }
+                    Some(client_event::Msg::Ack(ack)) => {
+                        // Receipt for a delivery on a critical or guaranteed topic (message_id = event id)
+                        if !event_bus.ack(&agent_id_for_inbound, &ack.message_id) {
+                            debug!(agent_id=%agent_id_for_inbound, message_id=%ack.message_id, "Ack for no outstanding delivery");
+                        }
+                    }
                     None => {}
                 }
             }
-            info!(agent_id=%agent_id_for_inbound, "EventStream inbound ended");
+            let timed_out = dead.is_cancelled();
+            if timed_out {
+                warn!(agent_id=%agent_id_for_inbound, "EventStream closed after missed heartbeats");
+            } else {
+                info!(agent_id=%agent_id_for_inbound, "EventStream inbound ended");
+            }
+            liveness.stream_closed(&agent_id_for_inbound, stream_id);
 
-            // Update agent status to Disconnected
-            agent_directory.update_status(&agent_id_for_inbound, AgentStatus::Disconnected);
+            // Keep the agent's last-known state in the directory, marked Disconnected
+            agent_directory.mark_disconnected(&agent_id_for_inbound);
 
             // Cleanup stream sender on disconnect
             streams_map.remove(&agent_id_for_inbound);
 
-            // Unregister agent from directory
-            agent_directory.unregister_agent(&agent_id_for_inbound);
+            // Chunked publishes cut off by the disconnect can never complete
+            payloads.discard(&agent_id_for_inbound);
+
+            // Free the agent's slot in its tenant's quota
+            if let (Some(tenants), Some(tenant)) = (&tenants, &tenant) {
+                tenants.release_agent(tenant, &agent_id_for_inbound);
+            }
 
             // Broadcast AgentUnregistered event to Dashboard
             if let Some(ref broadcaster) = dashboard_broadcaster {
@@ -388,21 +935,37 @@ impl Bridge for BridgeService {
                     sender: Some(agent_id_for_inbound.clone()),
                     thread_id: None,
                     correlation_id: None,
-                    payload_preview: format!("Agent {} disconnected", agent_id_for_inbound),
+                    payload_preview: if timed_out {
+                        format!("Agent {} missed heartbeats", agent_id_for_inbound)
+                    } else {
+                        format!("Agent {} disconnected", agent_id_for_inbound)
+                    },
                     trace_id: String::new(),
                 });
             }
 
-            // Unsubscribe all topic subscriptions for this agent
-            if let Some(ids) = subscription_ids.remove(&agent_id_for_inbound) {
-                for sid in ids.1.iter() {
-                    let _ = event_bus.unsubscribe(sid).await;
+            // Park the agent to buffer deliveries until it reconnects; otherwise detach
+            // from shared topic subscriptions (released when no agents remain)
+            if !replay
+                .park(&agent_id_for_inbound, &topics, &tx_in, &fanout)
+                .await
+            {
+                for topic in topics.iter() {
+                    fanout.detach(&agent_id_for_inbound, topic, &tx_in).await;
                 }
             }
-            // Abort forwarding tasks to stop background loops promptly
-            if let Some(handles) = forwarding_tasks.remove(&agent_id_for_inbound) {
-                for h in handles.1.into_iter() {
-                    h.abort();
+            // Fail calls still awaiting a result from this agent
+            let orphaned: Vec<String> = pending_tool_calls
+                .iter()
+                .filter(|e| e.value() == &agent_id_for_inbound)
+                .map(|e| e.key().clone())
+                .collect();
+            for call_id in orphaned {
+                pending_tool_calls.remove(&call_id);
+                if let Some((_, waiter)) = tool_waiters.remove(&call_id) {
+                    let _ = waiter.send(Err(BridgeError::AgentDisconnected(
+                        agent_id_for_inbound.clone(),
+                    )));
                 }
             }
             // Drop any stored tool results indexed for this agent to avoid leaks
@@ -411,10 +974,21 @@ impl Bridge for BridgeService {
                     tool_results.remove(&rid);
                 }
             }
+            if timed_out && liveness.config().unregister {
+                unregister_dead_agent(
+                    &agent_directory,
+                    &subscriptions,
+                    &agent_tools,
+                    &remote_tools,
+                    &agent_id_for_inbound,
+                );
+            }
         });
 
         let id_for_log = agent_id;
-        let outbound = ReceiverStream::new(rx).map(|ev| Ok(ev));
+        let outbound = tokio_stream::iter(replayed)
+            .chain(ReceiverStream::new(rx))
+            .map(Ok::<_, Status>);
         info!(agent_id=%id_for_log, "EventStream outbound established");
         Ok(Response::new(Box::pin(outbound) as Self::EventStreamStream))
     }
@@ -428,17 +1002,18 @@ impl Bridge for BridgeService {
         &self,
         request: Request<ToolCall>,
     ) -> std::result::Result<Response<ToolResult>, Status> {
+        self.authenticate(&request)?;
         let call = request.into_inner();
 
         // Record call details in span
-        tracing::Span::current().record("tool_name", &call.name.as_str());
-        tracing::Span::current().record("call_id", &call.id.as_str());
+        tracing::Span::current().record("tool_name", call.name.as_str());
+        tracing::Span::current().record("call_id", call.id.as_str());
 
         // Extract trace context from tool call
         let envelope = loom_core::Envelope::from_metadata(&call.headers, &call.id);
         if envelope.extract_trace_context() {
             tracing::Span::current()
-                .record("trace_id", &tracing::field::display(&envelope.trace_id));
+                .record("trace_id", tracing::field::display(&envelope.trace_id));
         }
 
         // Parse arguments from JSON string
@@ -481,6 +1056,11 @@ impl Bridge for BridgeService {
                         (ToolStatus::ToolInvalidArguments, "INVALID_ARGUMENTS")
                     }
                     loom_core::ToolError::Timeout => (ToolStatus::ToolTimeout, "TIMEOUT"),
+                    loom_core::ToolError::Cancelled => (ToolStatus::ToolCancelled, "CANCELLED"),
+                    loom_core::ToolError::CircuitOpen(_) => (ToolStatus::ToolError, "CIRCUIT_OPEN"),
+                    loom_core::ToolError::InvalidOutput(_) => {
+                        (ToolStatus::ToolError, "INVALID_OUTPUT")
+                    }
                     _ => (ToolStatus::ToolError, "EXECUTION_ERROR"),
                 };
                 Ok(Response::new(ToolResult {
@@ -501,6 +1081,47 @@ impl Bridge for BridgeService {
         &self,
         request: Request<HeartbeatRequest>,
     ) -> std::result::Result<Response<HeartbeatResponse>, Status> {
+        // Heartbeats naming an agent keep it alive; anonymous ones just echo
+        if let Some(agent_id) = request
+            .metadata()
+            .get(liveness::AGENT_ID_HEADER)
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_string)
+        {
+            let tenant = self.authenticate(&request)?;
+            if tenant.is_some()
+                && self
+                    .state
+                    .agent_namespaces
+                    .get(&agent_id)
+                    .map(|ns| ns.to_string())
+                    != tenant
+            {
+                return Err(Status::permission_denied(
+                    "agent is not registered for this tenant",
+                ));
+            }
+            match self.state.liveness.touch(&agent_id) {
+                // Dead or unknown agents are told to register again
+                None => {
+                    return Err(Status::not_found(format!(
+                        "agent {} is not registered",
+                        agent_id
+                    )))
+                }
+                Some(recovered) => {
+                    self.state.agent_directory.update_heartbeat(&agent_id);
+                    if recovered {
+                        info!(agent_id=%agent_id, "Degraded agent recovered");
+                        self.state.notify_dashboard(liveness::liveness_event(
+                            loom_core::dashboard::DashboardEventType::AgentRecovered,
+                            &agent_id,
+                            format!("Agent {} recovered", agent_id),
+                        ));
+                    }
+                }
+            }
+        }
         let req = request.into_inner();
 
         Ok(Response::new(HeartbeatResponse {
@@ -508,6 +1129,272 @@ impl Bridge for BridgeService {
             status: "ok".into(),
         }))
     }
+
+    async fn list_tools(
+        &self,
+        request: Request<ListToolsRequest>,
+    ) -> std::result::Result<Response<ListToolsResponse>, Status> {
+        let tenant = self.authenticate(&request)?;
+        let prefix = request.into_inner().name_prefix;
+        // Tenants see the global tools plus their namespace's own
+        let registry = match tenant.as_deref().map(Namespace::new) {
+            Some(Ok(ns)) => self.state.tool_registry.for_namespace(&ns),
+            _ => (*self.state.tool_registry).clone(),
+        };
+        let mut tools: Vec<ToolDescriptor> = registry
+            .list_tools()
+            .into_iter()
+            .map(|tool| tool_descriptor(tool.as_ref()))
+            .filter(|descriptor| descriptor.name.starts_with(&prefix))
+            .collect();
+        tools.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(Response::new(ListToolsResponse { tools }))
+    }
+
+    async fn list_agents(
+        &self,
+        request: Request<ListAgentsRequest>,
+    ) -> std::result::Result<Response<ListAgentsResponse>, Status> {
+        let tenant = self.authenticate(&request)?;
+        let req = request.into_inner();
+        let directory = &self.state.agent_directory;
+
+        let candidates = if req.capability.is_empty() {
+            directory.all()
+        } else {
+            directory
+                .by_capability(&req.capability)
+                .into_iter()
+                .filter_map(|agent_id| directory.get(&agent_id))
+                .collect()
+        };
+        // Tenants only see their own agents, with the topics they registered
+        let topic = match &tenant {
+            Some(tenant) if !req.topic.is_empty() => namespace_topic(tenant, &req.topic),
+            _ => req.topic,
+        };
+        let mut agents: Vec<AgentDescriptor> = candidates
+            .into_iter()
+            .filter(|info| match &tenant {
+                Some(tenant) => self
+                    .state
+                    .agent_namespaces
+                    .get(&info.agent_id)
+                    .is_some_and(|owner| owner.as_str() == tenant),
+                None => true,
+            })
+            .filter(|info| req.include_disconnected || info.status != AgentStatus::Disconnected)
+            .filter(|info| {
+                topic.is_empty()
+                    || info
+                        .subscribed_topics
+                        .iter()
+                        .any(|pattern| topic_matches(pattern, &topic))
+            })
+            .map(|info| agent_descriptor(info, tenant.as_deref()))
+            .collect();
+        agents.sort_by(|a, b| a.agent_id.cmp(&b.agent_id));
+        Ok(Response::new(ListAgentsResponse { agents }))
+    }
+}
+
+/// Descriptor of a ToolRegistry tool for `ListTools`
+fn tool_descriptor(tool: &dyn loom_core::Tool) -> ToolDescriptor {
+    let mut metadata = std::collections::HashMap::new();
+    if let Some(schema) = tool.output_schema() {
+        metadata.insert(
+            remote_tools::OUTPUT_SCHEMA_KEY.to_string(),
+            schema.to_string(),
+        );
+    }
+    ToolDescriptor {
+        name: tool.name(),
+        description: tool.description(),
+        parameters_schema: tool.parameters().to_string(),
+        // The registry does not record where a tool comes from (MCP adapters included)
+        provider: ProviderKind::ProviderNative as i32,
+        metadata,
+    }
+}
+
+/// `ListAgents` entry for `info`; a tenant's topics are shown without its namespace
+fn agent_descriptor(info: AgentInfo, tenant: Option<&str>) -> AgentDescriptor {
+    let subscribed_topics = match tenant {
+        Some(tenant) => info
+            .subscribed_topics
+            .iter()
+            .map(|topic| strip_namespace(tenant, topic).unwrap_or(topic).to_string())
+            .collect(),
+        None => info.subscribed_topics,
+    };
+    let status = match info.status {
+        AgentStatus::Active => "active",
+        AgentStatus::Idle => "idle",
+        AgentStatus::Inactive => "inactive",
+        AgentStatus::Degraded => "degraded",
+        AgentStatus::Paused => "paused",
+        AgentStatus::Draining => "draining",
+        AgentStatus::Disconnected => "disconnected",
+    };
+    AgentDescriptor {
+        agent_id: info.agent_id,
+        subscribed_topics,
+        capabilities: info.capabilities,
+        metadata: info.metadata,
+        last_heartbeat_ms: info.last_heartbeat.unwrap_or_default(),
+        status: status.to_string(),
+    }
+}
+
+/// Report a rejected client message on the agent's stream (best-effort)
+fn reject(tx: &mpsc::Sender<ServerEvent>, code: &str, message: String) {
+    let _ = tx.try_send(ServerEvent {
+        msg: Some(server_event::Msg::Err(loom_proto::Error {
+            code: code.into(),
+            message,
+        })),
+    });
+}
+
+/// Checks shared by `Publish` and `PublishBatch`: payload decoding, topic ACL and
+/// tenant quota. Returns the topic (inside the agent's namespace) and the events to
+/// publish, stamped with the namespace, the Bridge ingress marker (so the EventBus
+/// verifies their signatures) and the agent as publisher (so its rate limit applies
+/// whatever sender the events claim), in order; rejections are reported on `tx`.
+///
+/// Chunks of a still incomplete event are buffered and left out. A batch stops at the
+/// first event over the tenant's quota.
+#[allow(clippy::too_many_arguments)]
+fn admit_publish(
+    agent_id: &str,
+    topic: String,
+    events: Vec<loom_proto::Event>,
+    payloads: &PayloadCodec,
+    topic_acl: Option<&TopicAcl>,
+    token: Option<&str>,
+    tenant: Option<(&TenantRegistry, &str)>,
+    namespace: Option<&Namespace>,
+    tx: &mpsc::Sender<ServerEvent>,
+) -> Option<(String, Vec<loom_proto::Event>)> {
+    // Chunks are buffered until the whole event has arrived
+    let mut decoded = Vec::with_capacity(events.len());
+    for ev in events {
+        match payloads.decode(agent_id, ev) {
+            Ok(Some(ev)) => decoded.push(ev),
+            Ok(None) => {}
+            Err(e) => {
+                warn!(agent_id=%agent_id, topic=%topic, error=%e, "Rejecting publish with invalid payload");
+                reject(tx, "PAYLOAD_INVALID", e.to_string());
+            }
+        }
+    }
+    if decoded.is_empty() {
+        return None;
+    }
+    if let Some(acl) = topic_acl {
+        if let Err(e) = acl.check(agent_id, token, &topic) {
+            warn!(agent_id=%agent_id, topic=%topic, "Rejecting publish denied by topic ACL");
+            reject(tx, "PUBLISH_DENIED", e.to_string());
+            return None;
+        }
+    }
+    let mut admitted = Vec::with_capacity(decoded.len());
+    for mut ev in decoded {
+        if let Some((tenants, tenant)) = tenant {
+            if let Err(e) = tenants.admit_event(tenant) {
+                warn!(agent_id=%agent_id, topic=%topic, error=%e, "Dropping publish over tenant quota");
+                reject(tx, "QUOTA_EXCEEDED", e.to_string());
+                break;
+            }
+        }
+        if let Some(ns) = namespace {
+            ev.metadata.insert(TENANT_KEY.to_string(), ns.to_string());
+        }
+        ev.metadata
+            .insert(INGRESS_KEY.to_string(), BRIDGE_INGRESS.to_string());
+        ev.metadata
+            .insert(PUBLISHER_KEY.to_string(), agent_id.to_string());
+        admitted.push(ev);
+    }
+    if admitted.is_empty() {
+        return None;
+    }
+    let topic = match namespace {
+        Some(ns) => ns.topic(&topic),
+        None => topic,
+    };
+    Some((topic, admitted))
+}
+
+/// Admitted events with large payloads stashed in the blob store; events that could not
+/// be stashed are left out and rejected on `tx`
+async fn stash_blobs(
+    blobs: &BlobRelay,
+    agent_id: &str,
+    events: Vec<loom_proto::Event>,
+    tx: &mpsc::Sender<ServerEvent>,
+) -> Vec<loom_proto::Event> {
+    let mut stashed = Vec::with_capacity(events.len());
+    for mut ev in events {
+        match blobs.stash(agent_id, &mut ev).await {
+            Ok(_) => stashed.push(ev),
+            Err(e) => {
+                warn!(agent_id=%agent_id, event_id=%ev.id, error=%e, "Rejecting publish whose payload could not be stashed");
+                reject(tx, "BLOB_FAILED", e.to_string());
+            }
+        }
+    }
+    stashed
+}
+
+/// Namespace of an agent registering with `requested` namespace metadata: its tenant
+/// under tenancy (requesting another one is denied), otherwise the requested one
+#[allow(clippy::result_large_err)]
+fn assign_namespace(
+    tenant: Option<&str>,
+    requested: Option<&String>,
+) -> std::result::Result<Option<Namespace>, Status> {
+    let requested = requested
+        .map(|id| Namespace::new(id.as_str()))
+        .transpose()
+        .map_err(|e| Status::invalid_argument(e.to_string()))?;
+    let Some(tenant) = tenant else {
+        return Ok(requested);
+    };
+    match requested {
+        Some(ns) if ns.as_str() != tenant => Err(Status::permission_denied(format!(
+            "namespace {} belongs to another tenant",
+            ns
+        ))),
+        _ => Namespace::new(tenant)
+            .map(Some)
+            .map_err(|e| Status::internal(e.to_string())),
+    }
+}
+
+/// Token from the request's `authorization: Bearer <token>` header
+fn bearer_token<T>(request: &Request<T>) -> Option<&str> {
+    request
+        .metadata()
+        .get("authorization")
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .map(str::trim)
+}
+
+/// Remove an agent declared dead from the directory along with its registration
+fn unregister_dead_agent(
+    directory: &AgentDirectory,
+    subscriptions: &DashMap<String, Vec<String>>,
+    agent_tools: &DashMap<String, Vec<ToolDescriptor>>,
+    remote_tools: &RemoteTools,
+    agent_id: &str,
+) {
+    directory.unregister_agent(agent_id);
+    subscriptions.remove(agent_id);
+    agent_tools.remove(agent_id);
+    remote_tools.withdraw(agent_id);
+    info!(agent_id=%agent_id, "Unregistered agent after missed heartbeats");
 }
 
 pub async fn start_server(
@@ -520,8 +1407,9 @@ pub async fn start_server(
 
     let svc = BridgeService::new(BridgeState::new(event_bus, tool_registry, agent_directory));
 
-    // Create memory store and handler
-    let memory_store = trading_memory::InMemoryMemory::new();
+    // Create memory store (persistent when LOOM_TRADING_MEMORY_PATH is set) and handler
+    let memory_store =
+        trading_memory::from_env().map_err(|e| BridgeError::Internal(e.to_string()))?;
     let memory_handler = memory_handler::MemoryHandler::new(memory_store);
 
     tonic::transport::Server::builder()
@@ -533,6 +1421,7 @@ pub async fn start_server(
 }
 
 /// Start server with dashboard integration
+#[allow(clippy::too_many_arguments)]
 pub async fn start_server_with_dashboard(
     addr: SocketAddr,
     event_bus: Arc<EventBus>,
@@ -540,10 +1429,46 @@ pub async fn start_server_with_dashboard(
     agent_directory: Arc<AgentDirectory>,
     dashboard_broadcaster: Option<loom_core::dashboard::EventBroadcaster>,
     flow_tracker: Option<Arc<loom_core::dashboard::FlowTracker>>,
+    memory_governor: Option<Arc<MemoryGovernor>>,
+    stats: Option<Arc<StatsCollector>>,
 ) -> Result<()> {
     info!(addr = %addr, "Starting Loom Bridge gRPC server with Dashboard integration");
 
     let mut state = BridgeState::new(event_bus, tool_registry, agent_directory);
+    state.set_replay_config(ReplayConfig::from_env());
+    if let Some(schemas) = SchemaRegistry::from_env()? {
+        state.set_schema_registry(Arc::new(schemas));
+    }
+    state.set_payload_config(PayloadConfig::from_env()?);
+    state.set_liveness_config(LivenessConfig::from_env());
+
+    if let Some(tenants) =
+        TenantRegistry::from_env().map_err(|e| BridgeError::Internal(e.to_string()))?
+    {
+        state.set_tenants(Arc::new(tenants));
+    }
+
+    if let Some(acl) = TopicAcl::from_env()? {
+        state.set_topic_acl(Arc::new(acl));
+    }
+
+    if let Some(store) = BlobStore::from_env().map_err(|e| BridgeError::Internal(e.to_string()))? {
+        info!(store = ?store, "Blob references enabled");
+        state.set_blob_store(Arc::new(store));
+    }
+
+    if let Some(topics) = GuaranteedTopics::from_env() {
+        info!(patterns = ?topics.patterns, ack_timeout_ms = topics.delivery.ack_timeout.as_millis() as u64, "Guaranteed delivery enabled");
+        state.set_guaranteed_topics(topics);
+    }
+
+    if let Some(ref governor) = memory_governor {
+        governor.register(Arc::new(state.clone()));
+    }
+
+    if let Some(ref stats) = stats {
+        stats.register(Arc::new(state.clone()));
+    }
 
     if let Some(broadcaster) = dashboard_broadcaster {
         state.set_dashboard_broadcaster(broadcaster);
@@ -553,16 +1478,23 @@ pub async fn start_server_with_dashboard(
         state.set_flow_tracker(tracker);
     }
 
-    let svc = BridgeService::new(state);
-
-    // Create memory store and handler
-    let memory_store = trading_memory::InMemoryMemory::new();
+    // Create memory store (persistent when LOOM_TRADING_MEMORY_PATH is set) and handler
+    let memory_store =
+        trading_memory::from_env().map_err(|e| BridgeError::Internal(e.to_string()))?;
     let memory_handler = memory_handler::MemoryHandler::new(memory_store);
 
-    tonic::transport::Server::builder()
+    // Started after the broadcaster is set so liveness changes reach the Dashboard
+    let monitor = state.spawn_liveness_monitor();
+    let svc = BridgeService::new(state);
+
+    let served = tonic::transport::Server::builder()
         .add_service(BridgeServer::new(svc))
         .add_service(MemoryServiceServer::new(memory_handler))
         .serve(addr)
         .await
-        .map_err(|e| BridgeError::Internal(e.to_string()))
+        .map_err(|e| BridgeError::Internal(e.to_string()));
+    if let Some(monitor) = monitor {
+        monitor.abort();
+    }
+    served
 }
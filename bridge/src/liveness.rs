@@ -0,0 +1,310 @@
+//! Heartbeat-driven agent liveness.
+//!
+//! Every message on an agent's event stream (inline pings included) and every unary
+//! `Heartbeat` carrying an `x-loom-agent-id` header counts as a sign of life. An agent not
+//! heard from for `degraded_after` heartbeat intervals is marked `Degraded` in the
+//! `AgentDirectory`; after `disconnect_after` intervals its event stream is closed as if
+//! the agent had hung up, which marks it Disconnected and runs the usual stream cleanup
+//! (topic forwarding, pending tool calls, tenant slot). With `unregister` set, dead agents
+//! are also removed from the directory.
+//!
+//! The tracker only records what it hears; `BridgeState::spawn_liveness_monitor` runs the
+//! periodic check and applies its transitions.
+//!
+//! Env overrides for `LivenessConfig::from_env()`:
+//! - LOOM_BRIDGE_HEARTBEAT_INTERVAL_MS (default 15000; 0 disables the monitor)
+//! - LOOM_BRIDGE_DEGRADED_AFTER_MISSED (default 2)
+//! - LOOM_BRIDGE_DISCONNECT_AFTER_MISSED (default 4)
+//! - LOOM_BRIDGE_UNREGISTER_DEAD (`true` removes dead agents from the directory)
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+use dashmap::DashMap;
+use serde::Serialize;
+use tokio::time::Instant;
+
+use loom_core::dashboard::{DashboardEvent, DashboardEventType};
+use loom_core::CancellationToken;
+
+/// Metadata header naming the agent a unary `Heartbeat` is sent for
+pub const AGENT_ID_HEADER: &str = "x-loom-agent-id";
+
+/// Liveness monitor configuration
+#[derive(Debug, Clone)]
+pub struct LivenessConfig {
+    /// Interval agents are expected to heartbeat at; zero disables the monitor
+    pub heartbeat_interval: Duration,
+    /// Missed intervals before an agent is marked Degraded
+    pub degraded_after: u32,
+    /// Missed intervals before an agent's stream is closed and it is marked Disconnected
+    pub disconnect_after: u32,
+    /// Remove dead agents from the directory instead of keeping them as Disconnected
+    pub unregister: bool,
+}
+
+impl Default for LivenessConfig {
+    fn default() -> Self {
+        Self {
+            heartbeat_interval: Duration::from_secs(15),
+            degraded_after: 2,
+            disconnect_after: 4,
+            unregister: false,
+        }
+    }
+}
+
+impl LivenessConfig {
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        let interval_ms = std::env::var("LOOM_BRIDGE_HEARTBEAT_INTERVAL_MS")
+            .ok()
+            .and_then(|v| v.parse::<u64>().ok());
+        let degraded_after = std::env::var("LOOM_BRIDGE_DEGRADED_AFTER_MISSED")
+            .ok()
+            .and_then(|v| v.parse::<u32>().ok());
+        let disconnect_after = std::env::var("LOOM_BRIDGE_DISCONNECT_AFTER_MISSED")
+            .ok()
+            .and_then(|v| v.parse::<u32>().ok());
+        let unregister = matches!(
+            std::env::var("LOOM_BRIDGE_UNREGISTER_DEAD").as_deref(),
+            Ok("1") | Ok("true")
+        );
+        Self {
+            heartbeat_interval: interval_ms
+                .map(Duration::from_millis)
+                .unwrap_or(defaults.heartbeat_interval),
+            degraded_after: degraded_after.unwrap_or(defaults.degraded_after),
+            disconnect_after: disconnect_after.unwrap_or(defaults.disconnect_after),
+            unregister,
+        }
+    }
+
+    pub fn enabled(&self) -> bool {
+        !self.heartbeat_interval.is_zero() && self.disconnect_after > 0
+    }
+}
+
+/// A change in an agent's liveness found by `LivenessTracker::check`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LivenessChange {
+    /// The agent missed `degraded_after` heartbeats
+    Degraded { agent_id: String, missed: u32 },
+    /// The agent missed `disconnect_after` heartbeats; it is no longer tracked
+    Dead { agent_id: String, missed: u32 },
+}
+
+impl LivenessChange {
+    pub fn agent_id(&self) -> &str {
+        match self {
+            Self::Degraded { agent_id, .. } | Self::Dead { agent_id, .. } => agent_id,
+        }
+    }
+}
+
+/// Liveness statistics
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct LivenessStats {
+    /// Agents currently tracked
+    pub tracked_agents: usize,
+    /// Tracked agents currently degraded
+    pub degraded_agents: usize,
+    /// Times an agent was marked Degraded
+    pub degraded: u64,
+    /// Times a degraded agent was heard from again
+    pub recovered: u64,
+    /// Agents declared dead after missing heartbeats
+    pub dead: u64,
+}
+
+#[derive(Default)]
+struct Counters {
+    degraded: AtomicU64,
+    recovered: AtomicU64,
+    dead: AtomicU64,
+}
+
+struct Liveness {
+    last_seen: Instant,
+    degraded: bool,
+}
+
+struct WatchedStream {
+    id: u64,
+    cancel: CancellationToken,
+}
+
+/// When each agent was last heard from, and a handle to close its event stream
+pub struct LivenessTracker {
+    config: LivenessConfig,
+    agents: DashMap<String, Liveness>,
+    streams: DashMap<String, WatchedStream>,
+    counters: Counters,
+    next_stream: AtomicU64,
+}
+
+impl LivenessTracker {
+    pub fn new(config: LivenessConfig) -> Self {
+        Self {
+            config,
+            agents: DashMap::new(),
+            streams: DashMap::new(),
+            counters: Counters::default(),
+            next_stream: AtomicU64::new(0),
+        }
+    }
+
+    pub fn config(&self) -> &LivenessConfig {
+        &self.config
+    }
+
+    /// Start (or restart) tracking `agent_id` as just heard from
+    pub fn register(&self, agent_id: &str) {
+        self.agents.insert(
+            agent_id.to_string(),
+            Liveness {
+                last_seen: Instant::now(),
+                degraded: false,
+            },
+        );
+    }
+
+    /// Record a sign of life from `agent_id`.
+    ///
+    /// Returns `Some(true)` if the agent was degraded and has now recovered, and `None`
+    /// if it is not tracked (never registered, or already declared dead).
+    pub fn touch(&self, agent_id: &str) -> Option<bool> {
+        let mut liveness = self.agents.get_mut(agent_id)?;
+        liveness.last_seen = Instant::now();
+        let recovered = std::mem::replace(&mut liveness.degraded, false);
+        if recovered {
+            self.counters.recovered.fetch_add(1, Ordering::Relaxed);
+        }
+        Some(recovered)
+    }
+
+    /// Track a newly opened event stream of `agent_id`; the returned token is cancelled
+    /// when the agent is declared dead. Replaces the agent's previous stream, if any.
+    pub fn watch_stream(&self, agent_id: &str) -> (u64, CancellationToken) {
+        self.register(agent_id);
+        let id = self.next_stream.fetch_add(1, Ordering::Relaxed);
+        let cancel = CancellationToken::new();
+        self.streams.insert(
+            agent_id.to_string(),
+            WatchedStream {
+                id,
+                cancel: cancel.clone(),
+            },
+        );
+        (id, cancel)
+    }
+
+    /// Stop tracking `agent_id` once its stream `stream_id` has ended; a no-op if the
+    /// agent has opened a newer stream since
+    pub fn stream_closed(&self, agent_id: &str, stream_id: u64) {
+        if self
+            .streams
+            .remove_if(agent_id, |_, stream| stream.id == stream_id)
+            .is_some()
+        {
+            self.agents.remove(agent_id);
+        }
+    }
+
+    /// Cancel the event stream of `agent_id`; returns false if it has none
+    pub fn close_stream(&self, agent_id: &str) -> bool {
+        match self.streams.get(agent_id) {
+            Some(stream) => {
+                stream.cancel.cancel();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Stop tracking `agent_id`
+    pub fn forget(&self, agent_id: &str) {
+        self.agents.remove(agent_id);
+    }
+
+    pub fn is_tracked(&self, agent_id: &str) -> bool {
+        self.agents.contains_key(agent_id)
+    }
+
+    pub fn is_degraded(&self, agent_id: &str) -> bool {
+        self.agents.get(agent_id).is_some_and(|l| l.degraded)
+    }
+
+    /// Changes due at `now`, in agent id order. Dead agents stop being tracked, so each
+    /// change is reported once; a disabled config reports nothing.
+    pub fn check(&self, now: Instant) -> Vec<LivenessChange> {
+        if !self.config.enabled() {
+            return Vec::new();
+        }
+        let interval = self.config.heartbeat_interval.as_millis();
+        let mut changes = Vec::new();
+        for mut entry in self.agents.iter_mut() {
+            let elapsed = now.saturating_duration_since(entry.last_seen).as_millis();
+            let missed = u32::try_from(elapsed / interval).unwrap_or(u32::MAX);
+            if missed >= self.config.disconnect_after {
+                changes.push(LivenessChange::Dead {
+                    agent_id: entry.key().clone(),
+                    missed,
+                });
+            } else if missed >= self.config.degraded_after && !entry.degraded {
+                entry.degraded = true;
+                changes.push(LivenessChange::Degraded {
+                    agent_id: entry.key().clone(),
+                    missed,
+                });
+            }
+        }
+        for change in &changes {
+            match change {
+                LivenessChange::Degraded { .. } => {
+                    self.counters.degraded.fetch_add(1, Ordering::Relaxed);
+                }
+                LivenessChange::Dead { agent_id, .. } => {
+                    self.agents.remove(agent_id);
+                    self.counters.dead.fetch_add(1, Ordering::Relaxed);
+                }
+            }
+        }
+        changes.sort_by(|a, b| a.agent_id().cmp(b.agent_id()));
+        changes
+    }
+
+    pub fn stats(&self) -> LivenessStats {
+        LivenessStats {
+            tracked_agents: self.agents.len(),
+            degraded_agents: self.agents.iter().filter(|l| l.degraded).count(),
+            degraded: self.counters.degraded.load(Ordering::Relaxed),
+            recovered: self.counters.recovered.load(Ordering::Relaxed),
+            dead: self.counters.dead.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// Dashboard notification of a liveness change of `agent_id`
+pub(crate) fn liveness_event(
+    event_type: DashboardEventType,
+    agent_id: &str,
+    preview: String,
+) -> DashboardEvent {
+    let (id_prefix, topic) = match event_type {
+        DashboardEventType::AgentDegraded => ("degraded", "system.agent.degraded"),
+        DashboardEventType::AgentRecovered => ("recovered", "system.agent.recovered"),
+        _ => ("unregister", "system.agent.unregister"),
+    };
+    DashboardEvent {
+        timestamp: chrono::Utc::now().to_rfc3339(),
+        event_type,
+        event_id: format!("{}_{}", id_prefix, agent_id),
+        topic: topic.to_string(),
+        sender: Some(agent_id.to_string()),
+        thread_id: None,
+        correlation_id: None,
+        payload_preview: preview,
+        trace_id: String::new(),
+    }
+}
@@ -0,0 +1,803 @@
+//! Synthetic event workloads for sizing deployments.
+//!
+//! The load generator publishes events at a target rate across a set of topics and
+//! measures how many come back and how long they took. It can drive an `EventBus`
+//! in-process (`run_bus`) or a running Bridge over gRPC (`run_bridge`), in which case
+//! publishers and the measuring consumer are ordinary Bridge agents.
+//!
+//! Every event carries its send time (`loadgen.sent_ns` metadata, relative to the start
+//! of the run), so end-to-end latency is measured by the consumer in the same process.
+//! With `CorrelationPattern::RequestReply`, a responder echoes each request on its
+//! thread's reply topic and the latency is the full round trip.
+//!
+//! With `LoadgenConfig::personas`, requests are answered by scripted personas
+//! (`loom_core::simulation`) instead of an echo: one per topic, each with its own reply
+//! latency and error rate, so the report shows how a fleet of slow or flaky agents
+//! behaves under load. Personas run in-process and are only used by `run_bus`.
+//!
+//! Env overrides for `LoadgenConfig::from_env()`:
+//! - LOOM_LOADGEN_TOPICS (default 4)
+//! - LOOM_LOADGEN_TOPIC_PREFIX (default `loadgen`)
+//! - LOOM_LOADGEN_RATE (events/s across all publishers, 0 = unthrottled, default 1000)
+//! - LOOM_LOADGEN_DURATION_SECS (default 10)
+//! - LOOM_LOADGEN_PUBLISHERS (default 4)
+//! - LOOM_LOADGEN_PAYLOAD_BYTES (default 256)
+//! - LOOM_LOADGEN_BURST_SIZE / LOOM_LOADGEN_BURST_EVERY_MS (extra back-to-back events; off by default)
+//! - LOOM_LOADGEN_PATTERN (`none`, `threads` or `request_reply`, default `none`)
+//! - LOOM_LOADGEN_THREADS (thread ids cycled by `threads` / `request_reply`, default 16)
+//! - LOOM_LOADGEN_PERSONAS (scripted personas answering requests, one topic each; implies
+//!   `request_reply`, off by default)
+//! - LOOM_LOADGEN_PERSONA_LATENCY_MS (`min-max` reply latency of personas, default 0)
+//! - LOOM_LOADGEN_PERSONA_ERROR_RATE (share of requests personas leave unanswered, default 0)
+
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use tokio::sync::{mpsc, watch};
+use tokio::time::{sleep, sleep_until, Instant};
+use tokio_stream::wrappers::ReceiverStream;
+use tracing::{debug, info, warn};
+
+use loom_core::proto::{Event, QoSLevel};
+use loom_core::{Envelope, EventBus, Simulation, SimulationConfig, ThreadTopicKind};
+use loom_proto::{
+    bridge_client::BridgeClient, client_event, server_event, Ack, AgentRegisterRequest,
+    ClientEvent, Publish,
+};
+
+use crate::{BridgeError, Result};
+
+/// Metadata key carrying the send time in nanoseconds since the run started
+pub const SENT_NS_KEY: &str = "loadgen.sent_ns";
+
+/// Event type of generated requests and their echoed replies
+const REQUEST_TYPE: &str = "loadgen.event";
+const REPLY_TYPE: &str = "loadgen.reply";
+
+/// How long to wait for in-flight events after publishing stops
+const DRAIN_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Latency samples kept for percentiles; later samples replace random earlier ones
+const MAX_LATENCY_SAMPLES: usize = 1_000_000;
+
+/// Extra events published back-to-back on top of the steady rate
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BurstConfig {
+    pub size: usize,
+    pub every: Duration,
+}
+
+/// How generated events relate to each other
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CorrelationPattern {
+    /// Independent events without an envelope
+    #[default]
+    None,
+    /// Events carry an envelope cycling through `threads` thread ids
+    Threads(usize),
+    /// Like `Threads`, and each event is answered on its thread's reply topic
+    RequestReply(usize),
+}
+
+impl CorrelationPattern {
+    fn threads(&self) -> usize {
+        match self {
+            Self::None => 0,
+            Self::Threads(n) | Self::RequestReply(n) => (*n).max(1),
+        }
+    }
+}
+
+/// Workload description
+#[derive(Debug, Clone)]
+pub struct LoadgenConfig {
+    pub topics: usize,
+    pub topic_prefix: String,
+    /// Target events per second across all publishers; 0 publishes as fast as possible
+    pub rate: u64,
+    pub duration: Duration,
+    /// Concurrent publishers sharing the rate
+    pub publishers: usize,
+    pub payload_bytes: usize,
+    pub burst: Option<BurstConfig>,
+    pub pattern: CorrelationPattern,
+    /// Personas answering requests in place of the echo responder (with
+    /// `CorrelationPattern::RequestReply`); there is one topic per persona
+    pub personas: Option<SimulationConfig>,
+}
+
+impl Default for LoadgenConfig {
+    fn default() -> Self {
+        Self {
+            topics: 4,
+            topic_prefix: "loadgen".to_string(),
+            rate: 1000,
+            duration: Duration::from_secs(10),
+            publishers: 4,
+            payload_bytes: 256,
+            burst: None,
+            pattern: CorrelationPattern::None,
+            personas: None,
+        }
+    }
+}
+
+impl LoadgenConfig {
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        let burst = match (
+            env_parse::<usize>("LOOM_LOADGEN_BURST_SIZE"),
+            env_parse::<u64>("LOOM_LOADGEN_BURST_EVERY_MS"),
+        ) {
+            (Some(size), Some(every_ms)) if size > 0 && every_ms > 0 => Some(BurstConfig {
+                size,
+                every: Duration::from_millis(every_ms),
+            }),
+            _ => None,
+        };
+        let threads = env_parse("LOOM_LOADGEN_THREADS").unwrap_or(16);
+        let personas = env_parse::<usize>("LOOM_LOADGEN_PERSONAS")
+            .filter(|&n| n > 0)
+            .map(|n| {
+                let mut personas = SimulationConfig::new(n);
+                if let Some((min, max)) = std::env::var("LOOM_LOADGEN_PERSONA_LATENCY_MS")
+                    .ok()
+                    .and_then(|v| parse_range(&v))
+                {
+                    personas = personas
+                        .with_reply_latency(Duration::from_millis(min), Duration::from_millis(max));
+                }
+                if let Some(rate) = env_parse("LOOM_LOADGEN_PERSONA_ERROR_RATE") {
+                    personas = personas.with_error_rate(rate);
+                }
+                personas
+            });
+        let pattern = match std::env::var("LOOM_LOADGEN_PATTERN").as_deref() {
+            _ if personas.is_some() => CorrelationPattern::RequestReply(threads),
+            Ok("threads") => CorrelationPattern::Threads(threads),
+            Ok("request_reply") => CorrelationPattern::RequestReply(threads),
+            _ => CorrelationPattern::None,
+        };
+        Self {
+            topics: env_parse("LOOM_LOADGEN_TOPICS").unwrap_or(defaults.topics),
+            topic_prefix: std::env::var("LOOM_LOADGEN_TOPIC_PREFIX")
+                .unwrap_or(defaults.topic_prefix),
+            rate: env_parse("LOOM_LOADGEN_RATE").unwrap_or(defaults.rate),
+            duration: env_parse::<u64>("LOOM_LOADGEN_DURATION_SECS")
+                .map(Duration::from_secs)
+                .unwrap_or(defaults.duration),
+            publishers: env_parse("LOOM_LOADGEN_PUBLISHERS").unwrap_or(defaults.publishers),
+            payload_bytes: env_parse("LOOM_LOADGEN_PAYLOAD_BYTES")
+                .unwrap_or(defaults.payload_bytes),
+            burst,
+            pattern,
+            personas,
+        }
+    }
+
+    /// Topics events are published to
+    pub fn topic_names(&self) -> Vec<String> {
+        let topics = match self.simulation() {
+            Some(personas) => personas.personas,
+            None => self.topics,
+        };
+        (0..topics.max(1))
+            .map(|i| format!("{}.{}", self.topic_prefix, i))
+            .collect()
+    }
+
+    /// Personas answering requests, listening on the load topics
+    fn simulation(&self) -> Option<SimulationConfig> {
+        let personas = self.personas.as_ref()?;
+        matches!(self.pattern, CorrelationPattern::RequestReply(_)).then(|| {
+            personas
+                .clone()
+                .with_prefix(self.topic_prefix.clone())
+                .with_request_type(REQUEST_TYPE, REPLY_TYPE)
+        })
+    }
+
+    /// Topics the measuring consumer listens on
+    fn measured_topics(&self) -> Vec<String> {
+        match self.pattern {
+            CorrelationPattern::RequestReply(_) => (0..self.pattern.threads())
+                .map(|i| ThreadTopicKind::Reply.topic(&self.thread_id(i)))
+                .collect(),
+            _ => self.topic_names(),
+        }
+    }
+
+    fn thread_id(&self, i: usize) -> String {
+        format!("{}-thread-{}", self.topic_prefix, i)
+    }
+}
+
+fn env_parse<T: std::str::FromStr>(name: &str) -> Option<T> {
+    std::env::var(name).ok().and_then(|v| v.parse().ok())
+}
+
+/// `min-max`, or a single value for both
+fn parse_range(value: &str) -> Option<(u64, u64)> {
+    match value.split_once('-') {
+        Some((min, max)) => Some((min.trim().parse().ok()?, max.trim().parse().ok()?)),
+        None => value.trim().parse().ok().map(|v| (v, v)),
+    }
+}
+
+/// End-to-end latency percentiles
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct LatencySummary {
+    pub samples: usize,
+    pub mean: Duration,
+    pub p50: Duration,
+    pub p90: Duration,
+    pub p99: Duration,
+    pub p999: Duration,
+    pub max: Duration,
+}
+
+impl LatencySummary {
+    /// Summarize latency samples given in nanoseconds
+    pub fn from_nanos(mut samples: Vec<u64>) -> Self {
+        if samples.is_empty() {
+            return Self::default();
+        }
+        samples.sort_unstable();
+        let pct = |p: f64| {
+            let idx = ((samples.len() as f64 * p).ceil() as usize).clamp(1, samples.len()) - 1;
+            Duration::from_nanos(samples[idx])
+        };
+        let sum: u128 = samples.iter().map(|&n| n as u128).sum();
+        Self {
+            samples: samples.len(),
+            mean: Duration::from_nanos((sum / samples.len() as u128) as u64),
+            p50: pct(0.50),
+            p90: pct(0.90),
+            p99: pct(0.99),
+            p999: pct(0.999),
+            max: Duration::from_nanos(*samples.last().unwrap()),
+        }
+    }
+}
+
+/// Outcome of a load run
+#[derive(Debug, Clone, Default)]
+pub struct LoadReport {
+    /// Publishing window (excludes the drain)
+    pub elapsed: Duration,
+    pub sent: u64,
+    pub received: u64,
+    pub publish_errors: u64,
+    pub latency: LatencySummary,
+}
+
+impl LoadReport {
+    pub fn send_rate(&self) -> f64 {
+        per_second(self.sent, self.elapsed)
+    }
+
+    pub fn receive_rate(&self) -> f64 {
+        per_second(self.received, self.elapsed)
+    }
+
+    /// Fraction of sent events that were never received
+    pub fn loss(&self) -> f64 {
+        if self.sent == 0 {
+            0.0
+        } else {
+            1.0 - (self.received.min(self.sent) as f64 / self.sent as f64)
+        }
+    }
+}
+
+impl std::fmt::Display for LoadReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "sent {} ({:.0}/s), received {} ({:.0}/s), loss {:.2}%, publish errors {}",
+            self.sent,
+            self.send_rate(),
+            self.received,
+            self.receive_rate(),
+            self.loss() * 100.0,
+            self.publish_errors
+        )?;
+        let l = &self.latency;
+        write!(
+            f,
+            "latency p50 {:?}, p90 {:?}, p99 {:?}, p99.9 {:?}, max {:?}, mean {:?} ({} samples)",
+            l.p50, l.p90, l.p99, l.p999, l.max, l.mean, l.samples
+        )
+    }
+}
+
+fn per_second(count: u64, elapsed: Duration) -> f64 {
+    if elapsed.is_zero() {
+        0.0
+    } else {
+        count as f64 / elapsed.as_secs_f64()
+    }
+}
+
+/// Where generated events go
+#[derive(Clone)]
+enum Sink {
+    Bus(Arc<EventBus>),
+    Bridge(mpsc::Sender<ClientEvent>),
+}
+
+impl Sink {
+    async fn publish(&self, topic: &str, event: Event) -> Result<()> {
+        match self {
+            Sink::Bus(bus) => bus
+                .publish(topic, event)
+                .await
+                .map(|_| ())
+                .map_err(|e| BridgeError::Internal(e.to_string())),
+            Sink::Bridge(tx) => tx
+                .send(ClientEvent {
+                    msg: Some(client_event::Msg::Publish(Publish {
+                        topic: topic.to_string(),
+                        event: Some(event),
+                    })),
+                })
+                .await
+                .map_err(|_| BridgeError::AgentDisconnected("loadgen publisher".into())),
+        }
+    }
+}
+
+/// Shared state of one run
+struct Run {
+    config: LoadgenConfig,
+    topics: Vec<String>,
+    origin: Instant,
+    seq: AtomicU64,
+    sent: AtomicU64,
+    publish_errors: AtomicU64,
+    payload: Vec<u8>,
+}
+
+impl Run {
+    fn new(config: &LoadgenConfig) -> Arc<Self> {
+        Arc::new(Self {
+            topics: config.topic_names(),
+            config: config.clone(),
+            origin: Instant::now(),
+            seq: AtomicU64::new(0),
+            sent: AtomicU64::new(0),
+            publish_errors: AtomicU64::new(0),
+            payload: vec![0x5a; config.payload_bytes],
+        })
+    }
+
+    fn next_event(&self) -> (String, Event) {
+        let seq = self.seq.fetch_add(1, Ordering::Relaxed);
+        let topic = self.topics[seq as usize % self.topics.len()].clone();
+        let mut event = Event {
+            id: format!("loadgen-{seq}"),
+            r#type: REQUEST_TYPE.to_string(),
+            timestamp_ms: chrono::Utc::now().timestamp_millis(),
+            source: "loadgen".to_string(),
+            metadata: HashMap::new(),
+            payload: self.payload.clone(),
+            confidence: 1.0,
+            tags: vec![],
+            priority: 50,
+        };
+        let threads = self.config.pattern.threads();
+        if threads > 0 {
+            let mut env = Envelope::new(
+                self.config.thread_id(seq as usize % threads),
+                "agent.loadgen",
+            );
+            env.correlation_id = event.id.clone();
+            env.attach_to_event(&mut event);
+        }
+        event.metadata.insert(
+            SENT_NS_KEY.to_string(),
+            (self.origin.elapsed().as_nanos() as u64).to_string(),
+        );
+        (topic, event)
+    }
+
+    async fn send_one(&self, sink: &Sink) {
+        let (topic, event) = self.next_event();
+        match sink.publish(&topic, event).await {
+            Ok(()) => {
+                self.sent.fetch_add(1, Ordering::Relaxed);
+            }
+            Err(e) => {
+                debug!(error = %e, "loadgen publish failed");
+                self.publish_errors.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+    }
+}
+
+/// Paced publisher: sends on a fixed schedule and catches up without sleeping when behind
+async fn publish_loop(run: Arc<Run>, sink: Sink, per_publisher_rate: f64, deadline: Instant) {
+    let period =
+        (per_publisher_rate > 0.0).then(|| Duration::from_secs_f64(1.0 / per_publisher_rate));
+    let start = Instant::now();
+    let mut n: u32 = 0;
+    loop {
+        if let Some(period) = period {
+            let due = start + period * n;
+            if due >= deadline {
+                return;
+            }
+            if due > Instant::now() {
+                sleep_until(due).await;
+            }
+        } else if Instant::now() >= deadline {
+            return;
+        } else if n % 256 == 0 {
+            // Unthrottled: still let the consumer and the runtime make progress
+            tokio::task::yield_now().await;
+        }
+        run.send_one(&sink).await;
+        n = n.wrapping_add(1);
+    }
+}
+
+async fn burst_loop(run: Arc<Run>, sink: Sink, burst: BurstConfig, deadline: Instant) {
+    loop {
+        let next = Instant::now() + burst.every;
+        if next >= deadline {
+            return;
+        }
+        sleep_until(next).await;
+        for _ in 0..burst.size {
+            run.send_one(&sink).await;
+        }
+    }
+}
+
+/// Receives measured events and records their latency
+struct Collector {
+    origin: Instant,
+    received: AtomicU64,
+    samples: Mutex<Reservoir>,
+}
+
+struct Reservoir {
+    samples: Vec<u64>,
+    seen: u64,
+    rng: u64,
+}
+
+impl Collector {
+    fn new(origin: Instant) -> Arc<Self> {
+        Arc::new(Self {
+            origin,
+            received: AtomicU64::new(0),
+            samples: Mutex::new(Reservoir {
+                samples: Vec::new(),
+                seen: 0,
+                rng: 0x9e37_79b9_7f4a_7c15,
+            }),
+        })
+    }
+
+    fn record(&self, event: &Event) {
+        let Some(sent_ns) = event
+            .metadata
+            .get(SENT_NS_KEY)
+            .and_then(|s| s.parse::<u64>().ok())
+        else {
+            return;
+        };
+        self.received.fetch_add(1, Ordering::Relaxed);
+        let latency = (self.origin.elapsed().as_nanos() as u64).saturating_sub(sent_ns);
+
+        let mut r = self.samples.lock().unwrap();
+        r.seen += 1;
+        if r.samples.len() < MAX_LATENCY_SAMPLES {
+            r.samples.push(latency);
+        } else {
+            // xorshift; keep each sample with probability MAX / seen
+            r.rng ^= r.rng << 13;
+            r.rng ^= r.rng >> 7;
+            r.rng ^= r.rng << 17;
+            let slot = (r.rng % r.seen) as usize;
+            if slot < MAX_LATENCY_SAMPLES {
+                r.samples[slot] = latency;
+            }
+        }
+    }
+
+    fn received(&self) -> u64 {
+        self.received.load(Ordering::Relaxed)
+    }
+}
+
+/// Build the reply to a request: same metadata (and send time), addressed to its reply topic
+fn echo_reply(request: &Event) -> (String, Event) {
+    let env = Envelope::from_event(request);
+    let mut reply = request.clone();
+    reply.id = format!("{}-reply", request.id);
+    reply.r#type = REPLY_TYPE.to_string();
+    reply.source = "loadgen.responder".to_string();
+    (env.reply_to, reply)
+}
+
+/// Run a workload against an `EventBus` in this process
+pub async fn run_bus(event_bus: Arc<EventBus>, config: &LoadgenConfig) -> Result<LoadReport> {
+    let run = Run::new(config);
+    let collector = Collector::new(run.origin);
+    let (stop_tx, stop_rx) = watch::channel(false);
+    let mut background = Vec::new();
+    let mut subscriptions = Vec::new();
+
+    for topic in config.measured_topics() {
+        let (sub_id, rx) = event_bus
+            .subscribe(topic, vec![], QoSLevel::QosBatched)
+            .await
+            .map_err(|e| BridgeError::Internal(e.to_string()))?;
+        subscriptions.push(sub_id);
+        background.push(tokio::spawn(consume(
+            rx,
+            Arc::clone(&collector),
+            stop_rx.clone(),
+        )));
+    }
+    let mut simulation = None;
+    if let Some(personas) = config.simulation() {
+        simulation = Some(
+            Simulation::spawn(Arc::clone(&event_bus), personas)
+                .await
+                .map_err(|e| BridgeError::Internal(e.to_string()))?,
+        );
+    } else if matches!(config.pattern, CorrelationPattern::RequestReply(_)) {
+        for topic in run.topics.iter() {
+            let (sub_id, mut rx) = event_bus
+                .subscribe(
+                    topic.clone(),
+                    vec![REQUEST_TYPE.to_string()],
+                    QoSLevel::QosBatched,
+                )
+                .await
+                .map_err(|e| BridgeError::Internal(e.to_string()))?;
+            subscriptions.push(sub_id);
+            let bus = Arc::clone(&event_bus);
+            let mut stop = stop_rx.clone();
+            background.push(tokio::spawn(async move {
+                loop {
+                    tokio::select! {
+                        _ = stop.changed() => return,
+                        ev = rx.recv() => match ev {
+                            Some(ev) => {
+                                let (reply_topic, reply) = echo_reply(&ev);
+                                let _ = bus.publish(&reply_topic, reply).await;
+                            }
+                            None => return,
+                        },
+                    }
+                }
+            }));
+        }
+    }
+
+    let report = drive(&run, &collector, Sink::Bus(Arc::clone(&event_bus)), config).await;
+    let _ = stop_tx.send(true);
+    for task in background {
+        task.abort();
+    }
+    if let Some(simulation) = simulation {
+        let stats = simulation.stats();
+        info!(
+            requests = stats.requests,
+            replies = stats.replies,
+            errors = stats.errors,
+            "Personas finished"
+        );
+        simulation.stop().await;
+    }
+    // Leave a live bus as we found it
+    for sub_id in subscriptions {
+        let _ = event_bus.unsubscribe(&sub_id).await;
+    }
+    Ok(report)
+}
+
+/// Run a workload through a Bridge at `addr`; publishers, consumer and responder are
+/// Bridge agents named `loadgen-*`
+pub async fn run_bridge(addr: SocketAddr, config: &LoadgenConfig) -> Result<LoadReport> {
+    let run = Run::new(config);
+    let collector = Collector::new(run.origin);
+    let mut connections = Vec::new();
+
+    let (_consumer_tx, mut consumer_rx) =
+        connect(addr, "loadgen-consumer", config.measured_topics()).await?;
+    {
+        let collector = Arc::clone(&collector);
+        connections.push(tokio::spawn(async move {
+            while let Ok(Some(msg)) = consumer_rx.message().await {
+                if let Some(server_event::Msg::Delivery(d)) = msg.msg {
+                    if let Some(ev) = d.event {
+                        collector.record(&ev);
+                    }
+                }
+            }
+        }));
+    }
+
+    if config.personas.is_some() {
+        warn!("Personas only run against an in-process EventBus; the Bridge run echoes instead");
+    }
+    let mut responder_tx = None;
+    if matches!(config.pattern, CorrelationPattern::RequestReply(_)) {
+        let (tx, mut rx) = connect(addr, "loadgen-responder", run.topics.clone()).await?;
+        let reply_tx = tx.clone();
+        connections.push(tokio::spawn(async move {
+            while let Ok(Some(msg)) = rx.message().await {
+                let Some(server_event::Msg::Delivery(d)) = msg.msg else {
+                    continue;
+                };
+                let Some(ev) = d.event.filter(|ev| ev.r#type == REQUEST_TYPE) else {
+                    continue;
+                };
+                let (topic, reply) = echo_reply(&ev);
+                let publish = ClientEvent {
+                    msg: Some(client_event::Msg::Publish(Publish {
+                        topic,
+                        event: Some(reply),
+                    })),
+                };
+                if reply_tx.send(publish).await.is_err() {
+                    return;
+                }
+            }
+        }));
+        responder_tx = Some(tx);
+    }
+
+    // One Bridge agent per publisher so each has its own stream
+    let mut sinks = Vec::new();
+    let mut publisher_streams = Vec::new();
+    for i in 0..config.publishers.max(1) {
+        let (tx, rx) = connect(addr, &format!("loadgen-publisher-{i}"), vec![]).await?;
+        sinks.push(Sink::Bridge(tx));
+        publisher_streams.push(rx);
+    }
+
+    // Give the Bridge a moment to attach the consumer's topics before publishing
+    sleep(Duration::from_millis(200)).await;
+    let report = drive_sinks(&run, &collector, sinks, config).await;
+    drop(responder_tx);
+    drop(publisher_streams);
+    for task in connections {
+        task.abort();
+    }
+    Ok(report)
+}
+
+async fn connect(
+    addr: SocketAddr,
+    agent_id: &str,
+    topics: Vec<String>,
+) -> Result<(
+    mpsc::Sender<ClientEvent>,
+    tonic::Streaming<loom_proto::ServerEvent>,
+)> {
+    let mut client = BridgeClient::connect(format!("http://{addr}"))
+        .await
+        .map_err(|e| BridgeError::Internal(format!("connect {addr}: {e}")))?;
+    let resp = client
+        .register_agent(AgentRegisterRequest {
+            agent_id: agent_id.to_string(),
+            subscribed_topics: topics,
+            tools: vec![],
+            metadata: Default::default(),
+        })
+        .await
+        .map_err(|e| BridgeError::Registration(e.to_string()))?
+        .into_inner();
+    if !resp.success {
+        return Err(BridgeError::Registration(resp.error_message));
+    }
+
+    let (tx, rx) = mpsc::channel(1024);
+    tx.send(ClientEvent {
+        msg: Some(client_event::Msg::Ack(Ack {
+            message_id: agent_id.to_string(),
+        })),
+    })
+    .await
+    .map_err(|_| BridgeError::Internal("handshake channel closed".into()))?;
+    let stream = client
+        .event_stream(ReceiverStream::new(rx))
+        .await
+        .map_err(|e| BridgeError::Internal(e.to_string()))?
+        .into_inner();
+    Ok((tx, stream))
+}
+
+async fn consume(
+    mut rx: mpsc::Receiver<Event>,
+    collector: Arc<Collector>,
+    mut stop: watch::Receiver<bool>,
+) {
+    loop {
+        tokio::select! {
+            _ = stop.changed() => return,
+            ev = rx.recv() => match ev {
+                Some(ev) => collector.record(&ev),
+                None => return,
+            },
+        }
+    }
+}
+
+async fn drive(
+    run: &Arc<Run>,
+    collector: &Collector,
+    sink: Sink,
+    config: &LoadgenConfig,
+) -> LoadReport {
+    let sinks = vec![sink; config.publishers.max(1)];
+    drive_sinks(run, collector, sinks, config).await
+}
+
+/// Publish for `config.duration`, then wait for in-flight events and summarize
+async fn drive_sinks(
+    run: &Arc<Run>,
+    collector: &Collector,
+    sinks: Vec<Sink>,
+    config: &LoadgenConfig,
+) -> LoadReport {
+    info!(?config, "Starting load run");
+    let started = Instant::now();
+    let deadline = started + config.duration;
+    let per_publisher_rate = config.rate as f64 / sinks.len() as f64;
+
+    let mut publishers = Vec::new();
+    for sink in sinks.iter() {
+        publishers.push(tokio::spawn(publish_loop(
+            Arc::clone(run),
+            sink.clone(),
+            per_publisher_rate,
+            deadline,
+        )));
+    }
+    if let Some(burst) = config.burst {
+        publishers.push(tokio::spawn(burst_loop(
+            Arc::clone(run),
+            sinks[0].clone(),
+            burst,
+            deadline,
+        )));
+    }
+    for p in publishers {
+        if let Err(e) = p.await {
+            warn!(error = %e, "loadgen publisher task failed");
+        }
+    }
+    let elapsed = started.elapsed();
+
+    // Drain: wait for in-flight events, bounded by DRAIN_TIMEOUT
+    let sent = run.sent.load(Ordering::Relaxed);
+    let drain_deadline = Instant::now() + DRAIN_TIMEOUT;
+    while collector.received() < sent && Instant::now() < drain_deadline {
+        sleep(Duration::from_millis(20)).await;
+    }
+
+    let samples = collector.samples.lock().unwrap().samples.clone();
+    let report = LoadReport {
+        elapsed,
+        sent,
+        received: collector.received(),
+        publish_errors: run.publish_errors.load(Ordering::Relaxed),
+        latency: LatencySummary::from_nanos(samples),
+    };
+    info!(
+        sent = report.sent,
+        received = report.received,
+        "Load run finished"
+    );
+    report
+}
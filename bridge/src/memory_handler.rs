@@ -1,11 +1,12 @@
-use crate::trading_memory::InMemoryMemory;
+use crate::trading_memory::{GroupBy, MemoryError, RecordFilter, TradingMemory};
 use loom_proto::{
-    memory_service_server::MemoryService, CheckDuplicateRequest, CheckDuplicateResponse,
-    CheckExecutedRequest, CheckExecutedResponse, GetExecutionStatsRequest,
-    GetExecutionStatsResponse, GetRecentPlansRequest, GetRecentPlansResponse, MarkExecutedRequest,
-    MarkExecutedResponse, MemoryRetrieveRequest, MemoryRetrieveResponse, MemorySummarizeRequest,
-    MemorySummarizeResponse, MemoryWriteRequest, MemoryWriteResponse, SavePlanRequest,
-    SavePlanResponse,
+    memory_service_server::MemoryService, AggregateExecutionsRequest, AggregateExecutionsResponse,
+    CheckDuplicateRequest, CheckDuplicateResponse, CheckExecutedRequest, CheckExecutedResponse,
+    GetExecutionStatsRequest, GetExecutionStatsResponse, GetRecentPlansRequest,
+    GetRecentPlansResponse, MarkExecutedRequest, MarkExecutedResponse, MemoryRetrieveRequest,
+    MemoryRetrieveResponse, MemorySummarizeRequest, MemorySummarizeResponse, MemoryWriteRequest,
+    MemoryWriteResponse, QueryExecutionsResponse, QueryPlansResponse, SavePlanRequest,
+    SavePlanResponse, TradingQuery,
 };
 use std::sync::Arc;
 use tonic::{Request, Response, Status};
@@ -14,15 +15,23 @@ use tracing::debug;
 /// Memory handler service exposed via gRPC
 #[derive(Clone)]
 pub struct MemoryHandler {
-    store: Arc<InMemoryMemory>,
+    store: Arc<dyn TradingMemory>,
 }
 
 impl MemoryHandler {
-    pub fn new(store: Arc<InMemoryMemory>) -> Self {
+    pub fn new(store: Arc<dyn TradingMemory>) -> Self {
         Self { store }
     }
 }
 
+/// Bad queries are the caller's fault; anything else is ours
+fn query_status(context: &str, e: MemoryError) -> Status {
+    match e {
+        MemoryError::InvalidQuery(_) => Status::invalid_argument(format!("{}: {}", context, e)),
+        _ => Status::internal(format!("{}: {}", context, e)),
+    }
+}
+
 #[tonic::async_trait]
 impl MemoryService for MemoryHandler {
     async fn save_plan(
@@ -144,7 +153,6 @@ impl MemoryService for MemoryHandler {
         debug!(session_id = %req.session_id, "Appending event via Bridge");
 
         if let Some(event) = req.event {
-            use loom_core::context::MemoryWriter;
             match self.store.append_event(&req.session_id, event).await {
                 Ok(_) => Ok(Response::new(MemoryWriteResponse {
                     success: true,
@@ -169,7 +177,6 @@ impl MemoryService for MemoryHandler {
             "Retrieving from memory via Bridge"
         );
 
-        use loom_core::context::MemoryReader;
         match self.store.retrieve(&req.query, req.k as usize, None).await {
             Ok(results) => Ok(Response::new(MemoryRetrieveResponse {
                 results,
@@ -190,7 +197,6 @@ impl MemoryService for MemoryHandler {
             "Summarizing episode via Bridge"
         );
 
-        use loom_core::context::MemoryWriter;
         match self.store.summarize_episode(&req.session_id).await {
             Ok(summary) => Ok(Response::new(MemorySummarizeResponse {
                 summary: summary.unwrap_or_default(),
@@ -204,4 +210,72 @@ impl MemoryService for MemoryHandler {
             ))),
         }
     }
+
+    async fn query_plans(
+        &self,
+        request: Request<TradingQuery>,
+    ) -> Result<Response<QueryPlansResponse>, Status> {
+        let req = request.into_inner();
+        debug!(
+            session_id = %req.session_id,
+            query = %req.query,
+            "Querying plans via Bridge"
+        );
+
+        let filter =
+            RecordFilter::from_proto(&req).map_err(|e| query_status("Failed to query plans", e))?;
+        match self.store.query_plans(&filter) {
+            Ok(plans) => Ok(Response::new(QueryPlansResponse {
+                plans,
+                success: true,
+                error_message: String::new(),
+            })),
+            Err(e) => Err(query_status("Failed to query plans", e)),
+        }
+    }
+
+    async fn query_executions(
+        &self,
+        request: Request<TradingQuery>,
+    ) -> Result<Response<QueryExecutionsResponse>, Status> {
+        let req = request.into_inner();
+        debug!(
+            session_id = %req.session_id,
+            query = %req.query,
+            "Querying executions via Bridge"
+        );
+
+        let filter = RecordFilter::from_proto(&req)
+            .map_err(|e| query_status("Failed to query executions", e))?;
+        match self.store.query_executions(&filter) {
+            Ok(executions) => Ok(Response::new(QueryExecutionsResponse {
+                executions,
+                success: true,
+                error_message: String::new(),
+            })),
+            Err(e) => Err(query_status("Failed to query executions", e)),
+        }
+    }
+
+    async fn aggregate_executions(
+        &self,
+        request: Request<AggregateExecutionsRequest>,
+    ) -> Result<Response<AggregateExecutionsResponse>, Status> {
+        let req = request.into_inner();
+        let query = req.query.unwrap_or_default();
+        debug!(
+            session_id = %query.session_id,
+            query = %query.query,
+            group_by = %req.group_by,
+            "Aggregating executions via Bridge"
+        );
+
+        let context = "Failed to aggregate executions";
+        let filter = RecordFilter::from_proto(&query).map_err(|e| query_status(context, e))?;
+        let group_by = GroupBy::parse(&req.group_by).map_err(|e| query_status(context, e))?;
+        match self.store.aggregate_executions(&filter, group_by) {
+            Ok(resp) => Ok(Response::new(resp)),
+            Err(e) => Err(query_status(context, e)),
+        }
+    }
 }
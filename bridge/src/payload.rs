@@ -0,0 +1,511 @@
+//! Payload compression and chunking for events crossing the Bridge.
+//!
+//! Audio and document events can carry megabytes in `Event.payload`. To keep them from
+//! bloating agent channels and the gRPC stream, the Bridge can compress large payloads
+//! and split them into chunks. Both directions use event metadata:
+//!
+//! - `content-encoding` names the codec (`zstd` or `lz4`) a payload is compressed with.
+//! - `chunk-id`, `chunk-index` and `chunk-count` mark one part of a chunked event. Chunks
+//!   carry the id `<chunk-id>:<index>`; the reassembled event gets `chunk-id` back as id.
+//!
+//! Senders compress first and then chunk; receivers reassemble first and then decompress.
+//!
+//! Inbound publishes are always decoded: the EventBus only ever sees whole, uncompressed
+//! events. Outbound deliveries are encoded per agent, depending on what the agent listed
+//! under `accept-encoding` in its registration metadata (e.g. `zstd, lz4, chunked`).
+//! Agents that list nothing receive events unchanged.
+//!
+//! Agents registered with a `payload-format` other than `raw` (see `format`) also have
+//! their deliveries transcoded, before compression, and their publishes transcoded back,
+//! after decompression, through the codec's `SchemaRegistry`.
+//!
+//! Env overrides for `PayloadConfig::from_env()`:
+//! - LOOM_BRIDGE_COMPRESSION (`zstd`, `lz4` or `none`, default `zstd`)
+//! - LOOM_BRIDGE_COMPRESS_MIN_BYTES (default 65536)
+//! - LOOM_BRIDGE_CHUNK_BYTES (default 1048576, 0 disables chunking)
+//! - LOOM_BRIDGE_MAX_PAYLOAD_BYTES (default 67108864)
+//! - LOOM_BRIDGE_REASSEMBLY_TIMEOUT_MS (default 30000)
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use dashmap::DashMap;
+use loom_proto::Event;
+use tracing::{debug, warn};
+
+use crate::format::{PayloadFormat, PAYLOAD_FORMAT_KEY};
+use crate::schema::SchemaRegistry;
+use crate::{BridgeError, Result};
+
+/// Event metadata key naming the codec of a compressed payload
+pub const CONTENT_ENCODING_KEY: &str = "content-encoding";
+/// Registration metadata key listing the codecs (and `chunked`) an agent can decode
+pub const ACCEPT_ENCODING_KEY: &str = "accept-encoding";
+/// `accept-encoding` token for agents that reassemble chunked deliveries
+pub const CHUNKED: &str = "chunked";
+/// Event metadata key holding the id of the event a chunk belongs to
+pub const CHUNK_ID_KEY: &str = "chunk-id";
+/// Event metadata key holding the zero-based position of a chunk
+pub const CHUNK_INDEX_KEY: &str = "chunk-index";
+/// Event metadata key holding the number of chunks of an event
+pub const CHUNK_COUNT_KEY: &str = "chunk-count";
+
+/// Upper bound on `chunk-count`, so a bogus count cannot force a large allocation
+const MAX_CHUNKS: usize = 4096;
+
+/// Payload compression codec
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Codec {
+    Zstd,
+    Lz4,
+}
+
+impl Codec {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Codec::Zstd => "zstd",
+            Codec::Lz4 => "lz4",
+        }
+    }
+
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "zstd" => Some(Codec::Zstd),
+            "lz4" => Some(Codec::Lz4),
+            _ => None,
+        }
+    }
+
+    pub fn compress(&self, data: &[u8]) -> Result<Vec<u8>> {
+        match self {
+            Codec::Zstd => zstd::bulk::compress(data, 3)
+                .map_err(|e| BridgeError::Payload(format!("zstd compression failed: {e}"))),
+            Codec::Lz4 => Ok(lz4_flex::compress_prepend_size(data)),
+        }
+    }
+
+    /// Decompress `data`, failing if the result would exceed `max_len` bytes
+    pub fn decompress(&self, data: &[u8], max_len: usize) -> Result<Vec<u8>> {
+        match self {
+            Codec::Zstd => zstd::bulk::decompress(data, max_len)
+                .map_err(|e| BridgeError::Payload(format!("zstd decompression failed: {e}"))),
+            Codec::Lz4 => {
+                // lz4_flex prepends the uncompressed size as a little-endian u32
+                let size = data
+                    .get(..4)
+                    .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]) as usize)
+                    .ok_or_else(|| BridgeError::Payload("truncated lz4 payload".into()))?;
+                if size > max_len {
+                    return Err(BridgeError::Payload(format!(
+                        "lz4 payload of {size} bytes exceeds limit of {max_len}"
+                    )));
+                }
+                lz4_flex::decompress_size_prepended(data)
+                    .map_err(|e| BridgeError::Payload(format!("lz4 decompression failed: {e}")))
+            }
+        }
+    }
+}
+
+/// Payload encoding configuration
+#[derive(Debug, Clone)]
+pub struct PayloadConfig {
+    /// Codec for outbound deliveries; None disables compression
+    pub compression: Option<Codec>,
+    /// Payloads smaller than this are delivered uncompressed
+    pub compress_min_bytes: usize,
+    /// Payloads larger than this are delivered in chunks of this size; zero disables
+    pub chunk_bytes: usize,
+    /// Largest payload accepted after reassembly and decompression
+    pub max_payload_bytes: usize,
+    /// How long the chunks of an incomplete inbound event are kept
+    pub reassembly_timeout: Duration,
+}
+
+impl Default for PayloadConfig {
+    fn default() -> Self {
+        Self {
+            compression: Some(Codec::Zstd),
+            compress_min_bytes: 64 * 1024,
+            chunk_bytes: 1024 * 1024,
+            max_payload_bytes: 64 * 1024 * 1024,
+            reassembly_timeout: Duration::from_secs(30),
+        }
+    }
+}
+
+impl PayloadConfig {
+    pub fn from_env() -> Result<Self> {
+        let defaults = Self::default();
+        let compression = match std::env::var("LOOM_BRIDGE_COMPRESSION") {
+            Ok(name) if name.eq_ignore_ascii_case("none") => None,
+            Ok(name) => Some(Codec::parse(&name).ok_or_else(|| {
+                BridgeError::Config(format!("unknown LOOM_BRIDGE_COMPRESSION codec: {name}"))
+            })?),
+            Err(_) => defaults.compression,
+        };
+        let env_usize = |key: &str| {
+            std::env::var(key)
+                .ok()
+                .and_then(|v| v.parse::<usize>().ok())
+        };
+        let timeout_ms = std::env::var("LOOM_BRIDGE_REASSEMBLY_TIMEOUT_MS")
+            .ok()
+            .and_then(|v| v.parse::<u64>().ok());
+        Ok(Self {
+            compression,
+            compress_min_bytes: env_usize("LOOM_BRIDGE_COMPRESS_MIN_BYTES")
+                .unwrap_or(defaults.compress_min_bytes),
+            chunk_bytes: env_usize("LOOM_BRIDGE_CHUNK_BYTES").unwrap_or(defaults.chunk_bytes),
+            max_payload_bytes: env_usize("LOOM_BRIDGE_MAX_PAYLOAD_BYTES")
+                .unwrap_or(defaults.max_payload_bytes),
+            reassembly_timeout: timeout_ms
+                .map(Duration::from_millis)
+                .unwrap_or(defaults.reassembly_timeout),
+        })
+    }
+}
+
+/// Encodings an agent can decode, from its `accept-encoding` and `payload-format`
+/// registration metadata
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PayloadAccept {
+    pub codecs: Vec<Codec>,
+    pub chunked: bool,
+    pub format: PayloadFormat,
+}
+
+impl PayloadAccept {
+    pub fn parse(value: &str) -> Self {
+        let mut accept = Self::default();
+        for token in value.split(',').map(str::trim) {
+            if token.eq_ignore_ascii_case(CHUNKED) {
+                accept.chunked = true;
+            } else if let Some(codec) = Codec::parse(token) {
+                accept.codecs.push(codec);
+            }
+        }
+        accept
+    }
+
+    pub fn from_metadata(metadata: &HashMap<String, String>) -> Self {
+        let mut accept: Self = metadata
+            .get(ACCEPT_ENCODING_KEY)
+            .map(|v| Self::parse(v))
+            .unwrap_or_default();
+        if let Some(name) = metadata.get(PAYLOAD_FORMAT_KEY) {
+            match PayloadFormat::parse(name) {
+                Some(format) => accept.format = format,
+                None => warn!(format = %name, "Ignoring unknown payload-format"),
+            }
+        }
+        accept
+    }
+
+    pub fn with_format(mut self, format: PayloadFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.codecs.is_empty() && !self.chunked && self.format == PayloadFormat::Raw
+    }
+}
+
+struct PartialEvent {
+    chunks: Vec<Option<Event>>,
+    received: usize,
+    bytes: usize,
+    started: Instant,
+}
+
+/// Encodes outbound deliveries per agent and decodes inbound publishes
+pub struct PayloadCodec {
+    config: PayloadConfig,
+    // agent_id -> encodings negotiated at registration
+    accepts: DashMap<String, PayloadAccept>,
+    // Agents in `accepts` with a payload format other than raw
+    transcoding: AtomicUsize,
+    schemas: Arc<SchemaRegistry>,
+    // (agent_id, chunk id) -> chunks received so far
+    partial: Mutex<HashMap<(String, String), PartialEvent>>,
+}
+
+impl Default for PayloadCodec {
+    fn default() -> Self {
+        Self::new(PayloadConfig::default())
+    }
+}
+
+impl PayloadCodec {
+    pub fn new(config: PayloadConfig) -> Self {
+        Self {
+            config,
+            accepts: DashMap::new(),
+            transcoding: AtomicUsize::new(0),
+            schemas: Arc::new(SchemaRegistry::new()),
+            partial: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Transcode payloads with the event types registered in `schemas`
+    pub fn with_schemas(mut self, schemas: Arc<SchemaRegistry>) -> Self {
+        self.schemas = schemas;
+        self
+    }
+
+    pub fn config(&self) -> &PayloadConfig {
+        &self.config
+    }
+
+    pub fn schemas(&self) -> &Arc<SchemaRegistry> {
+        &self.schemas
+    }
+
+    /// Record the encodings listed in an agent's registration metadata
+    pub fn negotiate(&self, agent_id: &str, metadata: &HashMap<String, String>) {
+        let accept = PayloadAccept::from_metadata(metadata);
+        let transcodes = accept.format != PayloadFormat::Raw;
+        let previous = if accept.is_empty() {
+            self.accepts.remove(agent_id).map(|(_, a)| a)
+        } else {
+            debug!(agent_id = %agent_id, accept = ?accept, "Negotiated payload encoding");
+            self.accepts.insert(agent_id.to_string(), accept)
+        };
+        let transcoded = previous.is_some_and(|a| a.format != PayloadFormat::Raw);
+        if transcodes && !transcoded {
+            self.transcoding.fetch_add(1, Ordering::Relaxed);
+        } else if transcoded && !transcodes {
+            self.transcoding.fetch_sub(1, Ordering::Relaxed);
+        }
+    }
+
+    /// Encodings negotiated for `agent_id` (empty if it registered none)
+    pub fn accept_of(&self, agent_id: &str) -> PayloadAccept {
+        self.accepts
+            .get(agent_id)
+            .map(|a| a.clone())
+            .unwrap_or_default()
+    }
+
+    /// Whether some agent may get `event` encoded: it is large, or an agent negotiated a
+    /// payload format
+    pub fn needs_encoding(&self, event: &Event) -> bool {
+        let len = event.payload.len();
+        self.transcoding.load(Ordering::Relaxed) > 0
+            || (self.config.compression.is_some() && len >= self.config.compress_min_bytes)
+            || (self.config.chunk_bytes > 0 && len > self.config.chunk_bytes)
+    }
+
+    /// Events to deliver in place of `event` to an agent accepting `accept`: the event
+    /// itself, possibly transcoded and compressed, or its chunks in order
+    pub fn encode(&self, event: &Event, accept: &PayloadAccept) -> Vec<Event> {
+        let mut event = event.clone();
+        if accept.format != PayloadFormat::Raw {
+            self.transcode_out(&mut event, accept.format);
+        }
+        if let Some(codec) = self
+            .config
+            .compression
+            .filter(|c| accept.codecs.contains(c))
+        {
+            if event.payload.len() >= self.config.compress_min_bytes
+                && !event.metadata.contains_key(CONTENT_ENCODING_KEY)
+            {
+                match codec.compress(&event.payload) {
+                    // Incompressible payloads (already encoded media) are sent as-is
+                    Ok(compressed) if compressed.len() < event.payload.len() => {
+                        event.payload = compressed;
+                        event
+                            .metadata
+                            .insert(CONTENT_ENCODING_KEY.to_string(), codec.as_str().into());
+                    }
+                    Ok(_) => {}
+                    Err(e) => {
+                        warn!(event_id = %event.id, error = %e, "Delivering payload uncompressed")
+                    }
+                }
+            }
+        }
+
+        let chunk_bytes = self.config.chunk_bytes;
+        if !accept.chunked || chunk_bytes == 0 || event.payload.len() <= chunk_bytes {
+            return vec![event];
+        }
+        let payload = std::mem::take(&mut event.payload);
+        let count = payload.len().div_ceil(chunk_bytes);
+        payload
+            .chunks(chunk_bytes)
+            .enumerate()
+            .map(|(index, part)| {
+                let mut chunk = event.clone();
+                chunk.id = format!("{}:{}", event.id, index);
+                chunk.payload = part.to_vec();
+                chunk
+                    .metadata
+                    .insert(CHUNK_ID_KEY.to_string(), event.id.clone());
+                chunk
+                    .metadata
+                    .insert(CHUNK_INDEX_KEY.to_string(), index.to_string());
+                chunk
+                    .metadata
+                    .insert(CHUNK_COUNT_KEY.to_string(), count.to_string());
+                chunk
+            })
+            .collect()
+    }
+
+    /// Decode an event published by `agent_id`. Returns `None` while a chunked event is
+    /// incomplete, and the whole, decompressed event once its last chunk arrives.
+    pub fn decode(&self, agent_id: &str, event: Event) -> Result<Option<Event>> {
+        let Some(mut event) = self.reassemble(agent_id, event)? else {
+            return Ok(None);
+        };
+        if let Some(name) = event.metadata.remove(CONTENT_ENCODING_KEY) {
+            let codec = Codec::parse(&name).ok_or_else(|| {
+                BridgeError::Payload(format!("unsupported content-encoding: {name}"))
+            })?;
+            event.payload = codec.decompress(&event.payload, self.config.max_payload_bytes)?;
+        } else if event.payload.len() > self.config.max_payload_bytes {
+            return Err(self.too_large(event.payload.len()));
+        }
+
+        // The event's own payload-format wins over the agent's
+        let format = match event.metadata.remove(PAYLOAD_FORMAT_KEY) {
+            Some(name) => PayloadFormat::parse(&name).ok_or_else(|| {
+                BridgeError::Payload(format!("unsupported payload-format: {name}"))
+            })?,
+            None => self
+                .accepts
+                .get(agent_id)
+                .map(|a| a.format)
+                .unwrap_or_default(),
+        };
+        if format != PayloadFormat::Raw {
+            let value = format.decode(&event.payload)?;
+            event.payload = self.schemas.from_json(&event.r#type, &value)?;
+        }
+        Ok(Some(event))
+    }
+
+    /// Transcode the payload of `event` to `format`, marking the format it ends up in;
+    /// payloads without a JSON form stay raw
+    fn transcode_out(&self, event: &mut Event, format: PayloadFormat) {
+        // Already encoded on the bus; only the publisher knows what is inside
+        if event.metadata.contains_key(CONTENT_ENCODING_KEY) {
+            event.metadata.insert(
+                PAYLOAD_FORMAT_KEY.to_string(),
+                PayloadFormat::Raw.as_str().into(),
+            );
+            return;
+        }
+        let transcoded = self
+            .schemas
+            .to_json(&event.r#type, &event.payload)
+            .and_then(|value| format.encode(&value));
+        let delivered = match transcoded {
+            Ok(payload) => {
+                event.payload = payload;
+                format
+            }
+            Err(e) => {
+                debug!(event_id = %event.id, event_type = %event.r#type, error = %e, "Delivering payload raw");
+                PayloadFormat::Raw
+            }
+        };
+        event
+            .metadata
+            .insert(PAYLOAD_FORMAT_KEY.to_string(), delivered.as_str().into());
+    }
+
+    /// Drop the incomplete inbound events of `agent_id`
+    pub fn discard(&self, agent_id: &str) {
+        self.partial
+            .lock()
+            .unwrap()
+            .retain(|(agent, _), _| agent != agent_id);
+    }
+
+    /// Incomplete inbound events currently buffered
+    pub fn pending(&self) -> usize {
+        self.partial.lock().unwrap().len()
+    }
+
+    fn reassemble(&self, agent_id: &str, mut event: Event) -> Result<Option<Event>> {
+        let Some(chunk_id) = event.metadata.remove(CHUNK_ID_KEY) else {
+            return Ok(Some(event));
+        };
+        let index = chunk_field(&mut event, CHUNK_INDEX_KEY)?;
+        let count = chunk_field(&mut event, CHUNK_COUNT_KEY)?;
+        if count == 0 || count > MAX_CHUNKS || index >= count {
+            return Err(BridgeError::Payload(format!(
+                "chunk {index} of {count} is out of range for {chunk_id}"
+            )));
+        }
+
+        let mut partial = self.partial.lock().unwrap();
+        let timeout = self.config.reassembly_timeout;
+        partial.retain(|(agent, id), p| {
+            let live = p.started.elapsed() < timeout;
+            if !live {
+                warn!(agent_id = %agent, chunk_id = %id, received = p.received, "Discarding incomplete chunked event");
+            }
+            live
+        });
+
+        let key = (agent_id.to_string(), chunk_id.clone());
+        let entry = partial.entry(key.clone()).or_insert_with(|| PartialEvent {
+            chunks: vec![None; count],
+            received: 0,
+            bytes: 0,
+            started: Instant::now(),
+        });
+        if entry.chunks.len() != count {
+            partial.remove(&key);
+            return Err(BridgeError::Payload(format!(
+                "inconsistent chunk-count for {chunk_id}"
+            )));
+        }
+        let len = event.payload.len();
+        if entry.bytes + len > self.config.max_payload_bytes {
+            let total = entry.bytes + len;
+            partial.remove(&key);
+            return Err(self.too_large(total));
+        }
+        if entry.chunks[index].is_none() {
+            entry.received += 1;
+            entry.bytes += len;
+        }
+        entry.chunks[index] = Some(event);
+        if entry.received < count {
+            return Ok(None);
+        }
+
+        let entry = partial.remove(&key).expect("entry present");
+        let mut chunks = entry.chunks.into_iter().flatten();
+        let mut whole = chunks.next().expect("chunk 0 present");
+        whole.id = chunk_id;
+        whole.payload.reserve(entry.bytes - whole.payload.len());
+        for chunk in chunks {
+            whole.payload.extend_from_slice(&chunk.payload);
+        }
+        Ok(Some(whole))
+    }
+
+    fn too_large(&self, len: usize) -> BridgeError {
+        BridgeError::Payload(format!(
+            "payload of {len} bytes exceeds limit of {}",
+            self.config.max_payload_bytes
+        ))
+    }
+}
+
+fn chunk_field(event: &mut Event, key: &str) -> Result<usize> {
+    event
+        .metadata
+        .remove(key)
+        .and_then(|v| v.parse().ok())
+        .ok_or_else(|| BridgeError::Payload(format!("chunk is missing a valid {key}")))
+}
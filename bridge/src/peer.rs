@@ -0,0 +1,588 @@
+//! Bridge peering: federate this process's EventBus with a remote Loom.
+//!
+//! A `BridgePeer` connects to the remote instance's Bridge like any SDK agent: it
+//! registers with the `import` topics as its subscriptions and opens the event stream.
+//! Events published locally on `export` topics are published on the remote bus;
+//! deliveries on `import` topics are published on the local bus. Only one side of a
+//! pair runs a peer; the other only needs its Bridge server.
+//!
+//! Loop prevention: every event crossing the link carries `loom.origin`, the id of the
+//! instance it was first published on. A peer exports only events that originated
+//! locally (so imported events are never sent back or relayed onwards) and drops
+//! deliveries that originated locally (its own exports echoed by the remote bus).
+//!
+//! Reconnection: when the stream fails or a pong is late, the peer re-registers and
+//! reopens it with exponential backoff. Exports published meanwhile wait in the local
+//! subscriptions; imports missed meanwhile are replayed by the remote Bridge when its
+//! replay queues are enabled.
+//!
+//! Topics are exact: the Bridge delivers pattern subscriptions under the pattern, so a
+//! `prefix.*` import could not be republished under its real topic.
+//!
+//! Tools: with `tools` prefixes and a local `ToolRegistry`, the peer also imports the
+//! remote Bridge's matching tools whenever the link comes up and forwards calls to them
+//! over its channel (see `peer_tools`).
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+use tokio::time::{sleep, sleep_until, Instant, MissedTickBehavior};
+use tokio_stream::wrappers::ReceiverStream;
+use tokio_stream::{StreamExt, StreamMap};
+use tonic::metadata::{Ascii, MetadataValue};
+use tonic::service::interceptor::InterceptedService;
+use tonic::service::Interceptor;
+use tonic::transport::{Channel, Endpoint};
+use tracing::{debug, info, warn};
+
+use loom_core::messaging::RECEIPT_SUBSCRIBER_KEY;
+use loom_core::{EventBus, ToolRegistry};
+use loom_proto::{
+    bridge_client::BridgeClient, client_event, server_event, Ack, AgentRegisterRequest,
+    ClientEvent, Event, HeartbeatRequest, ListToolsRequest, Publish, QoSLevel, ServerEvent,
+};
+
+use crate::peer_tools::PeerTool;
+use crate::remote_tools::DEFAULT_REMOTE_TOOL_TIMEOUT;
+use crate::{BridgeError, Result};
+
+/// Metadata key naming the instance an event was first published on
+pub const ORIGIN_KEY: &str = "loom.origin";
+
+/// Frames queued on the outbound half of the stream
+const STREAM_CAPACITY: usize = 1024;
+
+/// Settings of a `BridgePeer`
+#[derive(Debug, Clone, PartialEq)]
+pub struct PeerConfig {
+    /// This instance's id: the origin of exported events and the agent id registered
+    /// with the remote Bridge
+    pub peer_id: String,
+    /// Remote Bridge address (`host:port` or an `http://` URL)
+    pub remote_addr: String,
+    /// Local topics published on the remote bus
+    pub export: Vec<String>,
+    /// Remote topics published on the local bus
+    pub import: Vec<String>,
+    /// Name prefixes of remote tools registered locally (`""` imports every tool)
+    pub tools: Vec<String>,
+    /// Deadline of a call to an imported tool
+    pub tool_timeout: Duration,
+    /// Bearer token for a multi-tenant remote Bridge
+    pub token: Option<String>,
+    pub heartbeat_interval: Duration,
+    pub heartbeat_timeout: Duration,
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+}
+
+impl PeerConfig {
+    pub fn new(peer_id: impl Into<String>, remote_addr: impl Into<String>) -> Self {
+        Self {
+            peer_id: peer_id.into(),
+            remote_addr: remote_addr.into(),
+            export: Vec::new(),
+            import: Vec::new(),
+            tools: Vec::new(),
+            tool_timeout: DEFAULT_REMOTE_TOOL_TIMEOUT,
+            token: None,
+            heartbeat_interval: Duration::from_secs(15),
+            heartbeat_timeout: Duration::from_secs(5),
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(10),
+        }
+    }
+
+    pub fn with_export<I, S>(mut self, topics: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.export.extend(topics.into_iter().map(Into::into));
+        self
+    }
+
+    pub fn with_import<I, S>(mut self, topics: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.import.extend(topics.into_iter().map(Into::into));
+        self
+    }
+
+    pub fn with_tools<I, S>(mut self, prefixes: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.tools.extend(prefixes.into_iter().map(Into::into));
+        self
+    }
+
+    pub fn with_tool_timeout(mut self, timeout: Duration) -> Self {
+        self.tool_timeout = timeout;
+        self
+    }
+
+    pub fn with_token(mut self, token: impl Into<String>) -> Self {
+        self.token = Some(token.into());
+        self
+    }
+
+    pub fn with_heartbeat(mut self, interval: Duration, timeout: Duration) -> Self {
+        self.heartbeat_interval = interval;
+        self.heartbeat_timeout = timeout;
+        self
+    }
+
+    pub fn with_backoff(mut self, initial: Duration, max: Duration) -> Self {
+        self.initial_backoff = initial;
+        self.max_backoff = max;
+        self
+    }
+
+    /// Peering configured by `LOOM_PEER_ADDR`; `None` when it is not set.
+    ///
+    /// `LOOM_PEER_ID` (default `peer-<pid>`), `LOOM_PEER_EXPORT` and `LOOM_PEER_IMPORT`
+    /// (comma-separated topics), `LOOM_PEER_TOOLS` (comma-separated tool name prefixes),
+    /// `LOOM_PEER_TOOL_TIMEOUT_MS` and `LOOM_PEER_TOKEN` complete it.
+    pub fn from_env() -> Result<Option<Self>> {
+        let Ok(addr) = std::env::var("LOOM_PEER_ADDR") else {
+            return Ok(None);
+        };
+        let peer_id = std::env::var("LOOM_PEER_ID")
+            .unwrap_or_else(|_| format!("peer-{}", std::process::id()));
+        let topics = |key: &str| -> Vec<String> {
+            std::env::var(key)
+                .unwrap_or_default()
+                .split(',')
+                .map(str::trim)
+                .filter(|t| !t.is_empty())
+                .map(str::to_string)
+                .collect()
+        };
+        let mut config = Self::new(peer_id, addr.trim())
+            .with_export(topics("LOOM_PEER_EXPORT"))
+            .with_import(topics("LOOM_PEER_IMPORT"))
+            .with_tools(topics("LOOM_PEER_TOOLS"));
+        if let Ok(ms) = std::env::var("LOOM_PEER_TOOL_TIMEOUT_MS") {
+            let ms = ms.trim().parse().map_err(|_| {
+                BridgeError::Config(format!("invalid LOOM_PEER_TOOL_TIMEOUT_MS '{ms}'"))
+            })?;
+            config.tool_timeout = Duration::from_millis(ms);
+        }
+        config.token = std::env::var("LOOM_PEER_TOKEN")
+            .ok()
+            .filter(|t| !t.is_empty());
+        config.validate()?;
+        Ok(Some(config))
+    }
+
+    fn validate(&self) -> Result<()> {
+        if self.peer_id.is_empty() || self.remote_addr.is_empty() {
+            return Err(BridgeError::Config(
+                "peer id and remote address must be set".into(),
+            ));
+        }
+        if self.export.is_empty() && self.import.is_empty() && self.tools.is_empty() {
+            return Err(BridgeError::Config(
+                "peer has no topics to export or import and no tools to import".into(),
+            ));
+        }
+        if let Some(topic) = self
+            .export
+            .iter()
+            .chain(&self.import)
+            .find(|t| t.contains('*'))
+        {
+            return Err(BridgeError::Config(format!(
+                "peer topics must be exact, got pattern '{topic}'"
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Counters of a `BridgePeer`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PeerStats {
+    pub connected: bool,
+    /// Local events published on the remote bus
+    pub exported: u64,
+    /// Remote events published on the local bus
+    pub imported: u64,
+    /// Events not forwarded because they would have looped back
+    pub loops_dropped: u64,
+    /// Times the stream was re-established after a failure
+    pub reconnects: u64,
+    /// Remote tools currently registered locally
+    pub tools: u64,
+}
+
+#[derive(Default)]
+struct Counters {
+    connected: AtomicBool,
+    exported: AtomicU64,
+    imported: AtomicU64,
+    loops_dropped: AtomicU64,
+    reconnects: AtomicU64,
+    tools: AtomicU64,
+}
+
+pub(crate) type Client = BridgeClient<InterceptedService<Channel, BearerToken>>;
+
+/// Adds `authorization: Bearer <token>` to every RPC when a token is set
+#[derive(Clone)]
+pub(crate) struct BearerToken(Option<MetadataValue<Ascii>>);
+
+impl Interceptor for BearerToken {
+    fn call(
+        &mut self,
+        mut request: tonic::Request<()>,
+    ) -> std::result::Result<tonic::Request<()>, tonic::Status> {
+        if let Some(ref value) = self.0 {
+            request
+                .metadata_mut()
+                .insert("authorization", value.clone());
+        }
+        Ok(request)
+    }
+}
+
+/// Federates the local EventBus with a remote Loom through its Bridge
+pub struct BridgePeer {
+    event_bus: Arc<EventBus>,
+    config: PeerConfig,
+    counters: Arc<Counters>,
+    tool_registry: Option<Arc<ToolRegistry>>,
+    /// Names of the imported tools registered in `tool_registry`
+    imported_tools: Mutex<Vec<String>>,
+}
+
+impl BridgePeer {
+    pub fn new(event_bus: Arc<EventBus>, config: PeerConfig) -> Result<Self> {
+        config.validate()?;
+        Ok(Self {
+            event_bus,
+            config,
+            counters: Arc::new(Counters::default()),
+            tool_registry: None,
+            imported_tools: Mutex::new(Vec::new()),
+        })
+    }
+
+    /// Register the remote tools matching `PeerConfig::tools` in `registry`
+    pub fn with_tool_registry(mut self, registry: Arc<ToolRegistry>) -> Self {
+        self.tool_registry = Some(registry);
+        self
+    }
+
+    pub fn config(&self) -> &PeerConfig {
+        &self.config
+    }
+
+    pub fn stats(&self) -> PeerStats {
+        PeerStats {
+            connected: self.counters.connected.load(Ordering::Relaxed),
+            exported: self.counters.exported.load(Ordering::Relaxed),
+            imported: self.counters.imported.load(Ordering::Relaxed),
+            loops_dropped: self.counters.loops_dropped.load(Ordering::Relaxed),
+            reconnects: self.counters.reconnects.load(Ordering::Relaxed),
+            tools: self.counters.tools.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Subscribe to the export topics and keep the link up in the background. The task
+    /// ends when the local export subscriptions close (the EventBus shut down); abort it
+    /// to disconnect.
+    pub async fn start(self: &Arc<Self>) -> Result<JoinHandle<()>> {
+        if !self.config.tools.is_empty() && self.tool_registry.is_none() {
+            return Err(BridgeError::Config(
+                "peer imports tools but has no tool registry".into(),
+            ));
+        }
+        let endpoint = if self.config.remote_addr.contains("://") {
+            self.config.remote_addr.clone()
+        } else {
+            format!("http://{}", self.config.remote_addr)
+        };
+        let token = match &self.config.token {
+            Some(token) => Some(
+                MetadataValue::try_from(format!("Bearer {token}"))
+                    .map_err(|_| BridgeError::Config("invalid peer token".into()))?,
+            ),
+            None => None,
+        };
+        // Lazy: the first connection attempt happens in the supervisor loop
+        let channel = Endpoint::from_shared(endpoint)
+            .map_err(|e| BridgeError::Config(format!("invalid peer address: {e}")))?
+            .connect_lazy();
+        let client = BridgeClient::with_interceptor(channel, BearerToken(token));
+
+        // Subscribed up front so exports published before the first connection are kept
+        let mut exports = StreamMap::new();
+        for topic in &self.config.export {
+            let (_sub_id, rx) = self
+                .event_bus
+                .subscribe(topic.clone(), vec![], QoSLevel::QosBatched)
+                .await
+                .map_err(|e| BridgeError::Internal(e.to_string()))?;
+            exports.insert(topic.clone(), ReceiverStream::new(rx));
+        }
+
+        let peer = Arc::clone(self);
+        Ok(tokio::spawn(async move {
+            peer.supervise(client, exports).await;
+        }))
+    }
+
+    async fn supervise(
+        &self,
+        client: Client,
+        mut exports: StreamMap<String, ReceiverStream<Event>>,
+    ) {
+        let peer_id = &self.config.peer_id;
+        let mut backoff = self.config.initial_backoff;
+        let mut connected_once = false;
+        loop {
+            match self.open(client.clone()).await {
+                Ok((stream_tx, inbound)) => {
+                    if connected_once {
+                        self.counters.reconnects.fetch_add(1, Ordering::Relaxed);
+                    }
+                    connected_once = true;
+                    backoff = self.config.initial_backoff;
+                    self.counters.connected.store(true, Ordering::Relaxed);
+                    info!(peer_id = %peer_id, remote = %self.config.remote_addr, "Peer link up");
+                    self.import_tools(&client).await;
+                    let end = self.run_session(stream_tx, inbound, &mut exports).await;
+                    self.counters.connected.store(false, Ordering::Relaxed);
+                    self.withdraw_tools();
+                    match end {
+                        None => {
+                            info!(peer_id = %peer_id, "Local subscriptions closed; peer stopped");
+                            return;
+                        }
+                        Some(reason) => {
+                            warn!(peer_id = %peer_id, %reason, "Peer link lost; reconnecting")
+                        }
+                    }
+                }
+                Err(e) => {
+                    debug!(peer_id = %peer_id, error = %e, backoff_ms = backoff.as_millis() as u64, "Peer connect failed");
+                }
+            }
+            sleep(backoff).await;
+            backoff = (backoff * 2).min(self.config.max_backoff);
+        }
+    }
+
+    /// Register with the remote Bridge and open the event stream
+    async fn open(
+        &self,
+        mut client: Client,
+    ) -> Result<(mpsc::Sender<ClientEvent>, tonic::Streaming<ServerEvent>)> {
+        let registration = AgentRegisterRequest {
+            agent_id: self.config.peer_id.clone(),
+            subscribed_topics: self.config.import.clone(),
+            tools: vec![],
+            metadata: HashMap::from([("role".to_string(), "peer".to_string())]),
+        };
+        let resp = client
+            .register_agent(registration)
+            .await
+            .map_err(|s| BridgeError::Registration(s.to_string()))?
+            .into_inner();
+        if !resp.success {
+            return Err(BridgeError::Registration(resp.error_message));
+        }
+
+        // The Ack must be queued before awaiting the RPC, or the handshake deadlocks
+        let (stream_tx, stream_rx) = mpsc::channel(STREAM_CAPACITY);
+        let _ = stream_tx
+            .send(ClientEvent {
+                msg: Some(client_event::Msg::Ack(Ack {
+                    message_id: self.config.peer_id.clone(),
+                })),
+            })
+            .await;
+        let inbound = client
+            .event_stream(ReceiverStream::new(stream_rx))
+            .await
+            .map_err(|s| BridgeError::Registration(s.to_string()))?
+            .into_inner();
+        Ok((stream_tx, inbound))
+    }
+
+    /// Register the remote tools matching the configured prefixes, skipping names that
+    /// are already registered locally
+    async fn import_tools(&self, client: &Client) {
+        let Some(registry) = &self.tool_registry else {
+            return;
+        };
+        let mut imported = Vec::new();
+        for prefix in &self.config.tools {
+            let request = ListToolsRequest {
+                name_prefix: prefix.clone(),
+            };
+            let tools = match client.clone().list_tools(request).await {
+                Ok(resp) => resp.into_inner().tools,
+                Err(status) => {
+                    warn!(peer_id = %self.config.peer_id, prefix = %prefix, error = %status, "Failed to list remote tools");
+                    continue;
+                }
+            };
+            for descriptor in tools {
+                let name = descriptor.name.clone();
+                if imported.contains(&name) {
+                    continue;
+                }
+                if registry.get(&name).is_some() {
+                    warn!(peer_id = %self.config.peer_id, tool = %name, "Tool name already registered; not importing the remote tool");
+                    continue;
+                }
+                let tool = PeerTool::new(client.clone(), &self.config, descriptor);
+                registry.register(Arc::new(tool)).await;
+                imported.push(name);
+            }
+        }
+        info!(peer_id = %self.config.peer_id, tools = imported.len(), "Imported remote tools");
+        self.counters
+            .tools
+            .store(imported.len() as u64, Ordering::Relaxed);
+        *self.imported_tools.lock().unwrap() = imported;
+    }
+
+    /// Unregister the imported tools while the link is down
+    fn withdraw_tools(&self) {
+        let Some(registry) = &self.tool_registry else {
+            return;
+        };
+        for name in std::mem::take(&mut *self.imported_tools.lock().unwrap()) {
+            registry.unregister(&name);
+        }
+        self.counters.tools.store(0, Ordering::Relaxed);
+    }
+
+    /// Forward in both directions until the link fails (`Some(reason)`) or the local
+    /// subscriptions close (`None`)
+    async fn run_session(
+        &self,
+        stream_tx: mpsc::Sender<ClientEvent>,
+        mut inbound: tonic::Streaming<ServerEvent>,
+        exports: &mut StreamMap<String, ReceiverStream<Event>>,
+    ) -> Option<String> {
+        let mut heartbeat = tokio::time::interval(self.config.heartbeat_interval);
+        heartbeat.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        heartbeat.tick().await;
+        let mut pong_deadline: Option<Instant> = None;
+
+        loop {
+            tokio::select! {
+                export = exports.next(), if !exports.is_empty() => {
+                    let (topic, event) = export?;
+                    if let Some(event) = self.outbound(event) {
+                        let publish = ClientEvent {
+                            msg: Some(client_event::Msg::Publish(Publish { topic, event: Some(event) })),
+                        };
+                        if stream_tx.send(publish).await.is_err() {
+                            return Some("outbound stream closed".into());
+                        }
+                        self.counters.exported.fetch_add(1, Ordering::Relaxed);
+                    }
+                }
+                msg = inbound.message() => match msg {
+                    Ok(Some(msg)) => match msg.msg {
+                        Some(server_event::Msg::Delivery(d)) => {
+                            if let Some(event) = d.event {
+                                self.inbound(&d.topic, event, &stream_tx).await;
+                            }
+                        }
+                        Some(server_event::Msg::DeliveryBatch(batch)) => {
+                            for event in batch.events {
+                                self.inbound(&batch.topic, event, &stream_tx).await;
+                            }
+                        }
+                        Some(server_event::Msg::Pong(_)) => pong_deadline = None,
+                        Some(server_event::Msg::Err(err)) => {
+                            warn!(peer_id = %self.config.peer_id, code = %err.code, message = %err.message, "Remote error on peer stream");
+                        }
+                        Some(server_event::Msg::ToolCall(_))
+                        | Some(server_event::Msg::ToolCancel(_))
+                        | None => {}
+                    },
+                    Ok(None) => return Some("stream closed by remote".into()),
+                    Err(status) => return Some(status.to_string()),
+                },
+                _ = heartbeat.tick() => {
+                    let ping = ClientEvent {
+                        msg: Some(client_event::Msg::Ping(HeartbeatRequest {
+                            timestamp_ms: chrono::Utc::now().timestamp_millis(),
+                        })),
+                    };
+                    if stream_tx.send(ping).await.is_err() {
+                        return Some("outbound stream closed".into());
+                    }
+                    pong_deadline.get_or_insert(Instant::now() + self.config.heartbeat_timeout);
+                }
+                _ = sleep_until(pong_deadline.unwrap_or_else(Instant::now)), if pong_deadline.is_some() => {
+                    return Some("heartbeat timed out".into());
+                }
+            }
+        }
+    }
+
+    /// A local event ready for the remote bus, or `None` if it came from elsewhere
+    fn outbound(&self, mut event: Event) -> Option<Event> {
+        // The local receipt is settled once the event is handed to the link
+        self.event_bus.ack_event(&event);
+        event.metadata.remove(RECEIPT_SUBSCRIBER_KEY);
+        match event.metadata.get(ORIGIN_KEY) {
+            Some(origin) if *origin != self.config.peer_id => {
+                self.counters.loops_dropped.fetch_add(1, Ordering::Relaxed);
+                None
+            }
+            Some(_) => Some(event),
+            None => {
+                event
+                    .metadata
+                    .insert(ORIGIN_KEY.to_string(), self.config.peer_id.clone());
+                Some(event)
+            }
+        }
+    }
+
+    /// Publish a remote delivery locally unless it is one of our own exports
+    async fn inbound(&self, topic: &str, mut event: Event, stream_tx: &mpsc::Sender<ClientEvent>) {
+        if event.metadata.remove(RECEIPT_SUBSCRIBER_KEY).is_some() {
+            let _ = stream_tx
+                .send(ClientEvent {
+                    msg: Some(client_event::Msg::Ack(Ack {
+                        message_id: event.id.clone(),
+                    })),
+                })
+                .await;
+        }
+        let origin = event
+            .metadata
+            .entry(ORIGIN_KEY.to_string())
+            .or_insert_with(|| self.config.remote_addr.clone());
+        if *origin == self.config.peer_id {
+            self.counters.loops_dropped.fetch_add(1, Ordering::Relaxed);
+            return;
+        }
+        match self.event_bus.publish(topic, event).await {
+            Ok(_) => {
+                self.counters.imported.fetch_add(1, Ordering::Relaxed);
+            }
+            Err(e) => {
+                warn!(peer_id = %self.config.peer_id, topic = %topic, error = %e, "Failed to publish imported event");
+            }
+        }
+    }
+}
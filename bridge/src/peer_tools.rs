@@ -0,0 +1,153 @@
+//! Tools hosted on a peered Loom, called over the peer link.
+//!
+//! A `BridgePeer` with tool prefixes (`PeerConfig::with_tools`) looks the matching tools
+//! up with the remote Bridge's `ListTools` each time its link comes up and registers them
+//! in the local `ToolRegistry` as `PeerTool`s, so registry lookups and LLM tool use find
+//! them like local tools, e.g. an edge node using the models of a GPU node. A call is
+//! forwarded with `ForwardToolCall` on the peer's gRPC channel:
+//!
+//! - Deadline: the call carries `PeerConfig::tool_timeout` as its gRPC timeout,
+//!   `timeout_ms` and envelope deadline; once it passes the RPC is dropped and the caller
+//!   gets `ToolError::Timeout`
+//! - Tracing: the call runs in a `peer_tool_call` span whose trace context travels in
+//!   `ToolCall.headers`, so the remote execution joins the caller's trace
+//! - Cancellation: cancelling the call drops the RPC, which ends it remotely
+//!
+//! Imported tools never replace a tool that is already registered under the same name,
+//! and are unregistered while the link is down. Only import in one direction of a pair:
+//! two peers importing each other's tools could forward a call back and forth.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use serde_json::{json, Value};
+use tonic::Code;
+use tracing::Instrument;
+
+use loom_core::tools::ToolResult;
+use loom_core::{CancellationToken, Envelope, Tool, ToolError};
+use loom_proto::{ToolCall, ToolDescriptor};
+
+use crate::peer::{Client, PeerConfig};
+use crate::remote_tools::{tool_output, OUTPUT_SCHEMA_KEY};
+
+static CALL_SEQ: AtomicU64 = AtomicU64::new(0);
+
+/// A `Tool` executed by a remote Loom, forwarded through a `BridgePeer`'s channel
+pub struct PeerTool {
+    client: Client,
+    peer_id: String,
+    remote_addr: String,
+    descriptor: ToolDescriptor,
+    timeout: Duration,
+}
+
+impl PeerTool {
+    pub(crate) fn new(client: Client, config: &PeerConfig, descriptor: ToolDescriptor) -> Self {
+        Self {
+            client,
+            peer_id: config.peer_id.clone(),
+            remote_addr: config.remote_addr.clone(),
+            descriptor,
+            timeout: config.tool_timeout,
+        }
+    }
+
+    /// Address of the Bridge that executes the tool
+    pub fn remote_addr(&self) -> &str {
+        &self.remote_addr
+    }
+
+    async fn forward(&self, arguments: Value, cancel: CancellationToken) -> ToolResult<Value> {
+        let call_id = format!(
+            "peer-{}-{}",
+            self.peer_id,
+            CALL_SEQ.fetch_add(1, Ordering::Relaxed)
+        );
+        let mut envelope =
+            Envelope::new(call_id.clone(), self.peer_id.clone()).with_timeout(self.timeout);
+        envelope.inject_trace_context();
+        let mut headers = HashMap::new();
+        envelope.apply_to_metadata(&mut headers);
+        let mut request = tonic::Request::new(ToolCall {
+            id: call_id,
+            name: self.descriptor.name.clone(),
+            arguments: arguments.to_string(),
+            headers,
+            timeout_ms: self.timeout.as_millis() as i64,
+            correlation_id: envelope.correlation_id.clone(),
+            qos: 0,
+        });
+        request.set_timeout(self.timeout);
+
+        let mut client = self.client.clone();
+        let started = Instant::now();
+        let response = tokio::select! {
+            response = tokio::time::timeout(self.timeout, client.forward_tool_call(request)) => {
+                response.map_err(|_| ToolError::Timeout)?
+            }
+            _ = cancel.cancelled() => return Err(ToolError::Cancelled),
+        };
+        match response {
+            Ok(result) => tool_output(&self.descriptor.name, result.into_inner()),
+            Err(status) => Err(match status.code() {
+                Code::DeadlineExceeded => ToolError::Timeout,
+                // tonic reports the expiry of the request timeout as `Cancelled`
+                Code::Cancelled if started.elapsed() >= self.timeout => ToolError::Timeout,
+                Code::Cancelled => ToolError::Cancelled,
+                Code::Unauthenticated | Code::PermissionDenied => {
+                    ToolError::PermissionDenied(status.message().to_string())
+                }
+                _ => ToolError::ExecutionFailed(format!(
+                    "peer {} failed: {}",
+                    self.remote_addr,
+                    status.message()
+                )),
+            }),
+        }
+    }
+}
+
+#[async_trait]
+impl Tool for PeerTool {
+    fn name(&self) -> String {
+        self.descriptor.name.clone()
+    }
+
+    fn description(&self) -> String {
+        self.descriptor.description.clone()
+    }
+
+    fn parameters(&self) -> Value {
+        serde_json::from_str(&self.descriptor.parameters_schema)
+            .unwrap_or_else(|_| json!({ "type": "object" }))
+    }
+
+    fn output_schema(&self) -> Option<Value> {
+        self.descriptor
+            .metadata
+            .get(OUTPUT_SCHEMA_KEY)
+            .and_then(|schema| serde_json::from_str(schema).ok())
+    }
+
+    async fn call(&self, arguments: Value) -> ToolResult<Value> {
+        self.call_with_cancel(arguments, CancellationToken::new())
+            .await
+    }
+
+    async fn call_with_cancel(
+        &self,
+        arguments: Value,
+        cancel: CancellationToken,
+    ) -> ToolResult<Value> {
+        let span = tracing::info_span!(
+            "peer_tool_call",
+            peer_id = %self.peer_id,
+            remote = %self.remote_addr,
+            tool = %self.descriptor.name,
+        );
+        self.forward(arguments, cancel).instrument(span).await
+    }
+}
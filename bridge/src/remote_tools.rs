@@ -0,0 +1,281 @@
+//! Agent-provided tools exposed through the `ToolRegistry`.
+//!
+//! The tools an agent lists in its registration become `RemoteAgentTool`s in the
+//! Bridge's registry, so LLM tool use (`ToolOrchestrator`) and any other registry caller
+//! can choose them like local tools. A call is pushed down the agent's event stream with
+//! `push_tool_call` and resolved by the `ToolResult` the agent sends back; cancelling the
+//! call, or running out of time, sends the agent a `ToolCancel`.
+//!
+//! A namespaced agent's tools are registered in its namespace (`register_in`). Remote
+//! tools never replace a tool that is already registered under the same name, whether
+//! local or provided by another agent; such tools are skipped with a warning. An agent's
+//! tools are withdrawn when it registers again and when it is unregistered.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use dashmap::DashMap;
+use serde_json::{json, Value};
+use tracing::{debug, warn};
+
+use loom_core::tools::ToolResult;
+use loom_core::{CancellationToken, Namespace, Tool, ToolError, ToolRegistry};
+use loom_proto::{ToolCall, ToolDescriptor, ToolStatus};
+
+use crate::{BridgeError, BridgeService};
+
+/// How long a remote tool call may take unless its descriptor sets `timeout_ms`
+pub const DEFAULT_REMOTE_TOOL_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Descriptor metadata key: the call timeout in milliseconds
+pub const TIMEOUT_MS_KEY: &str = "timeout_ms";
+
+/// Descriptor metadata key: JSON Schema of the tool's output
+pub const OUTPUT_SCHEMA_KEY: &str = "output_schema";
+
+static CALL_SEQ: AtomicU64 = AtomicU64::new(0);
+
+/// A `Tool` executed by an external agent over its Bridge stream
+pub struct RemoteAgentTool {
+    service: BridgeService,
+    agent_id: String,
+    descriptor: ToolDescriptor,
+    timeout: Duration,
+}
+
+impl RemoteAgentTool {
+    /// `descriptor` as registered by `agent_id`, called through `service`
+    pub fn new(
+        service: BridgeService,
+        agent_id: impl Into<String>,
+        descriptor: ToolDescriptor,
+    ) -> Self {
+        let timeout = descriptor
+            .metadata
+            .get(TIMEOUT_MS_KEY)
+            .and_then(|ms| ms.parse().ok())
+            .map(Duration::from_millis)
+            .unwrap_or(DEFAULT_REMOTE_TOOL_TIMEOUT);
+        Self {
+            service,
+            agent_id: agent_id.into(),
+            descriptor,
+            timeout,
+        }
+    }
+
+    /// Give up on calls after `timeout`
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Agent that executes the tool
+    pub fn agent_id(&self) -> &str {
+        &self.agent_id
+    }
+}
+
+#[async_trait]
+impl Tool for RemoteAgentTool {
+    fn name(&self) -> String {
+        self.descriptor.name.clone()
+    }
+
+    fn description(&self) -> String {
+        self.descriptor.description.clone()
+    }
+
+    fn parameters(&self) -> Value {
+        serde_json::from_str(&self.descriptor.parameters_schema)
+            .unwrap_or_else(|_| json!({ "type": "object" }))
+    }
+
+    fn output_schema(&self) -> Option<Value> {
+        self.descriptor
+            .metadata
+            .get(OUTPUT_SCHEMA_KEY)
+            .and_then(|schema| serde_json::from_str(schema).ok())
+    }
+
+    async fn call(&self, arguments: Value) -> ToolResult<Value> {
+        self.call_with_cancel(arguments, CancellationToken::new())
+            .await
+    }
+
+    async fn call_with_cancel(
+        &self,
+        arguments: Value,
+        cancel: CancellationToken,
+    ) -> ToolResult<Value> {
+        let seq = CALL_SEQ.fetch_add(1, Ordering::Relaxed);
+        let call_id = format!("remote-{}-{}", self.agent_id, seq);
+        let call = ToolCall {
+            id: call_id.clone(),
+            name: self.descriptor.name.clone(),
+            arguments: arguments.to_string(),
+            headers: Default::default(),
+            timeout_ms: self.timeout.as_millis() as i64,
+            correlation_id: String::new(),
+            qos: 0,
+        };
+        match self.service.push_tool_call(&self.agent_id, call).await {
+            Ok(true) => {}
+            Ok(false) => {
+                return Err(ToolError::ExecutionFailed(format!(
+                    "agent {} is not connected",
+                    self.agent_id
+                )))
+            }
+            Err(e) => return Err(ToolError::ExecutionFailed(e.to_string())),
+        }
+        debug!(agent_id = %self.agent_id, call_id = %call_id, tool = %self.descriptor.name, "Pushed remote tool call");
+
+        let pending = PendingCall {
+            service: &self.service,
+            call_id: &call_id,
+        };
+        let outcome = self
+            .service
+            .await_tool_result_with_cancel(&call_id, self.timeout, &cancel)
+            .await;
+        std::mem::forget(pending);
+        let result = outcome.map_err(|e| match e {
+            BridgeError::Timeout(_) => {
+                self.service.cancel_tool_call(&call_id, "timed out");
+                ToolError::Timeout
+            }
+            BridgeError::Cancelled(_) => ToolError::Cancelled,
+            other => ToolError::ExecutionFailed(other.to_string()),
+        })?;
+
+        tool_output(&self.descriptor.name, result)
+    }
+}
+
+/// The outcome a `ToolResult` of tool `name` reports
+pub(crate) fn tool_output(name: &str, result: loom_proto::ToolResult) -> ToolResult<Value> {
+    let message = || {
+        result
+            .error
+            .as_ref()
+            .map(|e| e.message.clone())
+            .unwrap_or_default()
+    };
+    match ToolStatus::try_from(result.status).unwrap_or(ToolStatus::ToolError) {
+        // Output that is not JSON is passed on as a string
+        ToolStatus::ToolOk => Ok(serde_json::from_str(&result.output)
+            .unwrap_or_else(|_| Value::String(result.output.clone()))),
+        ToolStatus::ToolNotFound => Err(ToolError::NotFound(name.to_string())),
+        ToolStatus::ToolInvalidArguments => Err(ToolError::InvalidArguments(message())),
+        ToolStatus::ToolTimeout => Err(ToolError::Timeout),
+        ToolStatus::ToolCancelled => Err(ToolError::Cancelled),
+        ToolStatus::ToolError => Err(ToolError::ExecutionFailed(message())),
+    }
+}
+
+/// Cancels a pushed call whose caller stops waiting for it, e.g. when the registry's
+/// own timeout drops the call
+struct PendingCall<'a> {
+    service: &'a BridgeService,
+    call_id: &'a str,
+}
+
+impl Drop for PendingCall<'_> {
+    fn drop(&mut self) {
+        self.service
+            .cancel_tool_call(self.call_id, "abandoned by caller");
+    }
+}
+
+/// Tools one agent has in the registry
+struct Exposed {
+    namespace: Option<Namespace>,
+    names: Vec<String>,
+}
+
+/// Registers agents' tools in a `ToolRegistry` and withdraws them again
+pub struct RemoteTools {
+    registry: Arc<ToolRegistry>,
+    exposed: DashMap<String, Exposed>,
+}
+
+impl RemoteTools {
+    pub fn new(registry: Arc<ToolRegistry>) -> Self {
+        Self {
+            registry,
+            exposed: DashMap::new(),
+        }
+    }
+
+    /// Register `tools` of `agent_id` (in `namespace`, if any), replacing the ones it
+    /// registered before. Returns the names registered.
+    pub async fn expose(
+        &self,
+        service: &BridgeService,
+        agent_id: &str,
+        namespace: Option<&Namespace>,
+        tools: &[ToolDescriptor],
+    ) -> Vec<String> {
+        self.withdraw(agent_id);
+        let visible = match namespace {
+            Some(ns) => self.registry.for_namespace(ns),
+            None => self.registry.as_ref().clone(),
+        };
+        let mut names = Vec::new();
+        for descriptor in tools {
+            let name = descriptor.name.clone();
+            if name.is_empty() || names.contains(&name) {
+                continue;
+            }
+            if visible.get(&name).is_some() {
+                warn!(agent_id = %agent_id, tool = %name, "Tool name already registered; not exposing the agent's tool");
+                continue;
+            }
+            let tool = Arc::new(RemoteAgentTool::new(
+                service.clone(),
+                agent_id,
+                descriptor.clone(),
+            ));
+            match namespace {
+                Some(ns) => self.registry.register_in(ns, tool).await,
+                None => self.registry.register(tool).await,
+            }
+            names.push(name);
+        }
+        if !names.is_empty() {
+            self.exposed.insert(
+                agent_id.to_string(),
+                Exposed {
+                    namespace: namespace.cloned(),
+                    names: names.clone(),
+                },
+            );
+        }
+        names
+    }
+
+    /// Unregister the tools `agent_id` exposed; returns how many there were
+    pub fn withdraw(&self, agent_id: &str) -> usize {
+        let Some((_, exposed)) = self.exposed.remove(agent_id) else {
+            return 0;
+        };
+        for name in &exposed.names {
+            match &exposed.namespace {
+                Some(ns) => self.registry.unregister_in(ns, name),
+                None => self.registry.unregister(name),
+            };
+        }
+        exposed.names.len()
+    }
+
+    /// Names of the tools `agent_id` exposed
+    pub fn exposed(&self, agent_id: &str) -> Vec<String> {
+        self.exposed
+            .get(agent_id)
+            .map(|e| e.names.clone())
+            .unwrap_or_default()
+    }
+}
@@ -0,0 +1,327 @@
+//! Per-agent replay queues covering gaps between an agent's event streams.
+//!
+//! When an agent's stream ends, the Bridge parks the agent instead of detaching it from
+//! its topics: each topic attachment is swapped to a parking channel whose deliveries
+//! are buffered in a bounded queue. If the agent opens a new stream within the TTL, the
+//! buffered `Delivery` messages are replayed ahead of live traffic. Otherwise the queue
+//! is discarded and the agent is detached as if it had never been parked.
+//!
+//! Queues live in the Bridge process; they cover dropped connections, not Bridge
+//! restarts. Parking is disabled unless a non-zero TTL is configured.
+//!
+//! Env overrides for `ReplayConfig::from_env()`:
+//! - LOOM_BRIDGE_REPLAY_TTL_MS (default 0, disabled)
+//! - LOOM_BRIDGE_REPLAY_CAPACITY (default 1024)
+//! - LOOM_BRIDGE_REPLAY_OVERFLOW (`drop_oldest` or `drop_newest`, default `drop_oldest`)
+
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use dashmap::DashMap;
+use serde::Serialize;
+use tokio::sync::{mpsc, oneshot};
+use tokio::task::JoinHandle;
+use tokio::time::Instant;
+use tracing::{debug, info, warn};
+
+use loom_proto::ServerEvent;
+
+use crate::fanout::TopicFanout;
+
+/// Capacity of the channel between the topic fanout and a parked agent's queue
+const PARKING_CHANNEL_CAPACITY: usize = 512;
+
+/// What to do when a parked agent's queue is full
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ReplayOverflow {
+    /// Evict the oldest buffered delivery to make room
+    #[default]
+    DropOldest,
+    /// Keep the buffered deliveries and discard the new one
+    DropNewest,
+}
+
+/// Replay queue configuration
+#[derive(Debug, Clone)]
+pub struct ReplayConfig {
+    /// How long a disconnected agent stays parked; zero disables parking
+    pub ttl: Duration,
+    /// Maximum deliveries buffered per agent
+    pub capacity: usize,
+    pub overflow: ReplayOverflow,
+}
+
+impl Default for ReplayConfig {
+    fn default() -> Self {
+        Self {
+            ttl: Duration::ZERO,
+            capacity: 1024,
+            overflow: ReplayOverflow::DropOldest,
+        }
+    }
+}
+
+impl ReplayConfig {
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        let ttl_ms = std::env::var("LOOM_BRIDGE_REPLAY_TTL_MS")
+            .ok()
+            .and_then(|v| v.parse::<u64>().ok());
+        let capacity = std::env::var("LOOM_BRIDGE_REPLAY_CAPACITY")
+            .ok()
+            .and_then(|v| v.parse::<usize>().ok());
+        let overflow = match std::env::var("LOOM_BRIDGE_REPLAY_OVERFLOW").as_deref() {
+            Ok("drop_newest") => ReplayOverflow::DropNewest,
+            _ => ReplayOverflow::DropOldest,
+        };
+        Self {
+            ttl: ttl_ms.map(Duration::from_millis).unwrap_or(defaults.ttl),
+            capacity: capacity.unwrap_or(defaults.capacity),
+            overflow,
+        }
+    }
+
+    pub fn enabled(&self) -> bool {
+        !self.ttl.is_zero() && self.capacity > 0
+    }
+}
+
+/// Replay statistics
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ReplayStats {
+    /// Agents currently parked awaiting reconnection
+    pub parked_agents: usize,
+    /// Deliveries currently buffered across parked agents
+    pub buffered: usize,
+    /// Deliveries replayed to reconnected agents
+    pub replayed: u64,
+    /// Deliveries discarded by the overflow policy or on TTL expiry
+    pub dropped: u64,
+    /// Parked agents that did not reconnect within the TTL
+    pub expired: u64,
+}
+
+#[derive(Default)]
+struct Counters {
+    replayed: AtomicU64,
+    dropped: AtomicU64,
+    expired: AtomicU64,
+}
+
+struct ParkedAgent {
+    generation: u64,
+    sender: mpsc::Sender<ServerEvent>,
+    topics: Vec<String>,
+    buffered: Arc<AtomicUsize>,
+    resume: oneshot::Sender<()>,
+    task: JoinHandle<VecDeque<ServerEvent>>,
+}
+
+/// Table of parked agents and their buffered deliveries
+pub struct ReplayQueues {
+    config: ReplayConfig,
+    parked: Arc<DashMap<String, ParkedAgent>>,
+    counters: Arc<Counters>,
+    next_generation: AtomicU64,
+}
+
+impl ReplayQueues {
+    pub fn new(config: ReplayConfig) -> Self {
+        Self {
+            config,
+            parked: Arc::new(DashMap::new()),
+            counters: Arc::new(Counters::default()),
+            next_generation: AtomicU64::new(0),
+        }
+    }
+
+    pub fn config(&self) -> &ReplayConfig {
+        &self.config
+    }
+
+    /// Park `agent_id` after its stream `stream_tx` ended: its attachments on `topics`
+    /// are moved to a buffering queue until `resume` or TTL expiry.
+    ///
+    /// Returns false if parking is disabled or no topic attachment still belonged to
+    /// `stream_tx` (e.g. the agent already reconnected); the caller then detaches as usual.
+    pub async fn park(
+        &self,
+        agent_id: &str,
+        topics: &[String],
+        stream_tx: &mpsc::Sender<ServerEvent>,
+        fanout: &Arc<TopicFanout>,
+    ) -> bool {
+        if !self.config.enabled() {
+            return false;
+        }
+
+        let (tx, rx) = mpsc::channel(PARKING_CHANNEL_CAPACITY);
+        let mut parked_topics = Vec::new();
+        for topic in topics {
+            if fanout.replace(agent_id, topic, stream_tx, tx.clone()).await {
+                parked_topics.push(topic.clone());
+            }
+        }
+        if parked_topics.is_empty() {
+            return false;
+        }
+
+        let generation = self.next_generation.fetch_add(1, Ordering::Relaxed);
+        let buffered = Arc::new(AtomicUsize::new(0));
+        let (resume_tx, resume_rx) = oneshot::channel();
+        let task = tokio::spawn(park_loop(
+            ParkLoop {
+                agent_id: agent_id.to_string(),
+                generation,
+                deadline: Instant::now() + self.config.ttl,
+                capacity: self.config.capacity,
+                overflow: self.config.overflow,
+                buffered: Arc::clone(&buffered),
+                counters: Arc::clone(&self.counters),
+                parked: Arc::clone(&self.parked),
+                fanout: Arc::clone(fanout),
+            },
+            rx,
+            resume_rx,
+        ));
+
+        info!(agent_id = %agent_id, topics = ?parked_topics, ttl_ms = self.config.ttl.as_millis() as u64, "Agent parked awaiting reconnection");
+        if let Some(previous) = self.parked.insert(
+            agent_id.to_string(),
+            ParkedAgent {
+                generation,
+                sender: tx,
+                topics: parked_topics,
+                buffered,
+                resume: resume_tx,
+                task,
+            },
+        ) {
+            // Should not happen (one stream per agent), but don't leak the old queue
+            previous.task.abort();
+        }
+        true
+    }
+
+    /// Take the deliveries buffered for `agent_id` while it was parked, in arrival order.
+    ///
+    /// Call after the agent's new stream is attached to its topics, so that every
+    /// delivery not returned here goes to the new stream.
+    pub async fn resume(&self, agent_id: &str, fanout: &TopicFanout) -> Vec<ServerEvent> {
+        let Some((_, parked)) = self.parked.remove(agent_id) else {
+            return Vec::new();
+        };
+        // Release parking attachments on topics the new stream did not take over
+        for topic in parked.topics.iter() {
+            fanout.detach(agent_id, topic, &parked.sender).await;
+        }
+        let _ = parked.resume.send(());
+        let queue = parked.task.await.unwrap_or_default();
+        self.counters
+            .replayed
+            .fetch_add(queue.len() as u64, Ordering::Relaxed);
+        info!(agent_id = %agent_id, replayed = queue.len(), "Replaying deliveries to reconnected agent");
+        queue.into()
+    }
+
+    pub fn is_parked(&self, agent_id: &str) -> bool {
+        self.parked.contains_key(agent_id)
+    }
+
+    pub fn stats(&self) -> ReplayStats {
+        ReplayStats {
+            parked_agents: self.parked.len(),
+            buffered: self
+                .parked
+                .iter()
+                .map(|p| p.buffered.load(Ordering::Relaxed))
+                .sum(),
+            replayed: self.counters.replayed.load(Ordering::Relaxed),
+            dropped: self.counters.dropped.load(Ordering::Relaxed),
+            expired: self.counters.expired.load(Ordering::Relaxed),
+        }
+    }
+}
+
+struct ParkLoop {
+    agent_id: String,
+    generation: u64,
+    deadline: Instant,
+    capacity: usize,
+    overflow: ReplayOverflow,
+    buffered: Arc<AtomicUsize>,
+    counters: Arc<Counters>,
+    parked: Arc<DashMap<String, ParkedAgent>>,
+    fanout: Arc<TopicFanout>,
+}
+
+impl ParkLoop {
+    /// Append `ev`, applying the overflow policy when the queue is at capacity
+    fn push(&self, queue: &mut VecDeque<ServerEvent>, ev: ServerEvent) {
+        if queue.len() >= self.capacity {
+            self.counters.dropped.fetch_add(1, Ordering::Relaxed);
+            match self.overflow {
+                ReplayOverflow::DropOldest => {
+                    queue.pop_front();
+                }
+                ReplayOverflow::DropNewest => return,
+            }
+        }
+        queue.push_back(ev);
+        self.buffered.store(queue.len(), Ordering::Relaxed);
+    }
+}
+
+async fn park_loop(
+    ctx: ParkLoop,
+    mut rx: mpsc::Receiver<ServerEvent>,
+    mut resume_rx: oneshot::Receiver<()>,
+) -> VecDeque<ServerEvent> {
+    let mut queue = VecDeque::new();
+    let expire = tokio::time::sleep_until(ctx.deadline);
+    tokio::pin!(expire);
+
+    let resumed = loop {
+        tokio::select! {
+            _ = &mut resume_rx => break true,
+            _ = &mut expire => break false,
+            ev = rx.recv() => match ev {
+                Some(ev) => ctx.push(&mut queue, ev),
+                // Every parking sender was detached; nothing more can arrive
+                None => break true,
+            },
+        }
+    };
+
+    if !resumed {
+        match ctx
+            .parked
+            .remove_if(&ctx.agent_id, |_, p| p.generation == ctx.generation)
+        {
+            Some((_, parked)) => {
+                for topic in parked.topics.iter() {
+                    ctx.fanout
+                        .detach(&ctx.agent_id, topic, &parked.sender)
+                        .await;
+                }
+                ctx.counters.expired.fetch_add(1, Ordering::Relaxed);
+                ctx.counters
+                    .dropped
+                    .fetch_add(queue.len() as u64, Ordering::Relaxed);
+                warn!(agent_id = %ctx.agent_id, dropped = queue.len(), "Parked agent did not reconnect; discarding queued deliveries");
+                return VecDeque::new();
+            }
+            // Lost the race with `resume`: it is waiting for this queue
+            None => debug!(agent_id = %ctx.agent_id, "Parked agent resumed at expiry"),
+        }
+    }
+
+    // Keep deliveries already in flight to the parking channel
+    rx.close();
+    while let Some(ev) = rx.recv().await {
+        ctx.push(&mut queue, ev);
+    }
+    queue
+}
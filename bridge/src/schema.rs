@@ -0,0 +1,601 @@
+//! Payload schemas of event types, for transcoding Bridge payloads (see `format`).
+//!
+//! A `SchemaRegistry` maps an `Event.type` to the schema of its payload on the EventBus:
+//!
+//! - `PayloadSchema::Protobuf(message)`: the payload is the named protobuf message,
+//!   transcoded to and from its proto3 JSON mapping (lowerCamelCase field names, 64-bit
+//!   integers as strings, enums by name, bytes as base64). Messages are resolved from the
+//!   `loom.v1` descriptors and any descriptor sets added with `add_descriptor_set`.
+//! - `PayloadSchema::Json(schema)`: the payload is JSON; publishes are validated against
+//!   the JSON schema (the subset understood by `loom_core::tools::schema`).
+//!
+//! Unregistered types are JSON if their payload parses as JSON, and raw otherwise.
+//!
+//! `SchemaRegistry::from_env()` loads the TOML file named by `LOOM_BRIDGE_SCHEMA_FILE`:
+//!
+//! ```toml
+//! # FileDescriptorSets written by `protoc --include_imports --descriptor_set_out`
+//! descriptor_sets = ["schemas/sensors.bin"]
+//!
+//! [types."sensor.reading"]
+//! protobuf = "acme.sensors.v1.Reading"
+//!
+//! [types."task.created"]
+//! json_schema = { type = "object", required = ["id"] }
+//! ```
+
+use std::collections::HashMap;
+
+use base64::Engine;
+use prost::Message;
+use prost_types::field_descriptor_proto::{Label, Type};
+use prost_types::{DescriptorProto, EnumDescriptorProto, FieldDescriptorProto, FileDescriptorSet};
+use serde::Deserialize;
+use serde_json::{Map, Number, Value};
+use tracing::info;
+
+use crate::{BridgeError, Result};
+
+/// Deepest message nesting accepted when decoding a protobuf payload
+const MAX_DEPTH: usize = 64;
+
+/// Schema of the payloads of one event type
+#[derive(Debug, Clone, PartialEq)]
+pub enum PayloadSchema {
+    /// JSON payloads matching a JSON schema
+    Json(Value),
+    /// Payloads encoded as the protobuf message with this full name, e.g. `loom.v1.Event`
+    Protobuf(String),
+}
+
+/// Event type → payload schema, with the protobuf descriptors the schemas refer to
+#[derive(Debug, Clone)]
+pub struct SchemaRegistry {
+    messages: HashMap<String, DescriptorProto>,
+    enums: HashMap<String, EnumDescriptorProto>,
+    types: HashMap<String, PayloadSchema>,
+}
+
+impl Default for SchemaRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SchemaRegistry {
+    /// Registry without event types, resolving the `loom.v1` messages
+    pub fn new() -> Self {
+        let mut registry = Self {
+            messages: HashMap::new(),
+            enums: HashMap::new(),
+            types: HashMap::new(),
+        };
+        registry
+            .add_descriptor_set(loom_proto::FILE_DESCRIPTOR_SET)
+            .expect("loom.v1 descriptors are valid");
+        registry
+    }
+
+    /// Make the messages and enums of an encoded `FileDescriptorSet` available
+    pub fn add_descriptor_set(&mut self, bytes: &[u8]) -> Result<()> {
+        let set = FileDescriptorSet::decode(bytes)
+            .map_err(|e| BridgeError::Config(format!("invalid descriptor set: {e}")))?;
+        for file in set.file {
+            let package = file.package().to_string();
+            for message in file.message_type {
+                self.add_message(&package, message);
+            }
+            for e in file.enum_type {
+                self.enums.insert(qualify(&package, e.name()), e);
+            }
+        }
+        Ok(())
+    }
+
+    fn add_message(&mut self, scope: &str, mut message: DescriptorProto) {
+        let name = qualify(scope, message.name());
+        for nested in std::mem::take(&mut message.nested_type) {
+            self.add_message(&name, nested);
+        }
+        for e in std::mem::take(&mut message.enum_type) {
+            self.enums.insert(qualify(&name, e.name()), e);
+        }
+        self.messages.insert(name, message);
+    }
+
+    /// Set the payload schema of `event_type`; protobuf messages must be known
+    pub fn register(&mut self, event_type: impl Into<String>, schema: PayloadSchema) -> Result<()> {
+        if let PayloadSchema::Protobuf(ref message) = schema {
+            if !self.messages.contains_key(message) {
+                return Err(BridgeError::Config(format!(
+                    "unknown protobuf message: {message}"
+                )));
+            }
+        }
+        self.types.insert(event_type.into(), schema);
+        Ok(())
+    }
+
+    pub fn schema_of(&self, event_type: &str) -> Option<&PayloadSchema> {
+        self.types.get(event_type)
+    }
+
+    /// Parse a TOML document with `descriptor_sets` paths and a `types` table
+    pub fn from_toml(source: &str) -> Result<Self> {
+        #[derive(Deserialize)]
+        #[serde(deny_unknown_fields)]
+        struct TypeEntry {
+            protobuf: Option<String>,
+            json_schema: Option<toml::Value>,
+        }
+        #[derive(Deserialize)]
+        struct File {
+            #[serde(default)]
+            descriptor_sets: Vec<String>,
+            #[serde(default)]
+            types: HashMap<String, TypeEntry>,
+        }
+        let file: File = toml::from_str(source)
+            .map_err(|e| BridgeError::Config(format!("invalid schema file: {e}")))?;
+
+        let mut registry = Self::new();
+        for path in &file.descriptor_sets {
+            let bytes = std::fs::read(path)
+                .map_err(|e| BridgeError::Config(format!("failed to read {path}: {e}")))?;
+            registry.add_descriptor_set(&bytes)?;
+        }
+        for (event_type, entry) in file.types {
+            let schema = match (entry.protobuf, entry.json_schema) {
+                (Some(message), None) => PayloadSchema::Protobuf(message),
+                (None, Some(schema)) => PayloadSchema::Json(
+                    serde_json::to_value(schema)
+                        .map_err(|e| BridgeError::Config(format!("invalid JSON schema: {e}")))?,
+                ),
+                _ => {
+                    return Err(BridgeError::Config(format!(
+                        "type '{event_type}' needs exactly one of protobuf and json_schema"
+                    )))
+                }
+            };
+            registry.register(event_type, schema)?;
+        }
+        Ok(registry)
+    }
+
+    /// Load the file named by `LOOM_BRIDGE_SCHEMA_FILE`; `Ok(None)` if it is unset
+    pub fn from_env() -> Result<Option<Self>> {
+        let Ok(path) = std::env::var("LOOM_BRIDGE_SCHEMA_FILE") else {
+            return Ok(None);
+        };
+        let source = std::fs::read_to_string(&path)
+            .map_err(|e| BridgeError::Config(format!("failed to read {path}: {e}")))?;
+        let registry = Self::from_toml(&source)?;
+        info!(path = %path, types = registry.types.len(), "Loaded payload schemas");
+        Ok(Some(registry))
+    }
+
+    /// JSON form of an EventBus payload of `event_type`
+    pub fn to_json(&self, event_type: &str, payload: &[u8]) -> Result<Value> {
+        match self.types.get(event_type) {
+            Some(PayloadSchema::Protobuf(message)) => self
+                .decode_message(message, payload, 0)
+                .map_err(|e| BridgeError::Payload(format!("invalid {message} payload: {e}"))),
+            _ => serde_json::from_slice(payload)
+                .map_err(|e| BridgeError::Payload(format!("payload is not JSON: {e}"))),
+        }
+    }
+
+    /// EventBus payload of `event_type` from its JSON form, validated against the
+    /// registered schema
+    pub fn from_json(&self, event_type: &str, value: &Value) -> Result<Vec<u8>> {
+        match self.types.get(event_type) {
+            Some(PayloadSchema::Protobuf(message)) => {
+                let mut out = Vec::new();
+                self.encode_message(message, value, &mut out)
+                    .map_err(|e| BridgeError::Payload(format!("invalid {message} payload: {e}")))?;
+                Ok(out)
+            }
+            Some(PayloadSchema::Json(schema)) => {
+                loom_core::tools::schema::validate(schema, value).map_err(|e| {
+                    BridgeError::Payload(format!(
+                        "{event_type} payload does not match its schema: {e}"
+                    ))
+                })?;
+                Ok(value.to_string().into_bytes())
+            }
+            None => Ok(value.to_string().into_bytes()),
+        }
+    }
+
+    fn message(&self, name: &str) -> std::result::Result<&DescriptorProto, String> {
+        self.messages
+            .get(name.trim_start_matches('.'))
+            .ok_or_else(|| format!("unknown message {name}"))
+    }
+
+    fn is_map(&self, field: &FieldDescriptorProto) -> bool {
+        field.r#type() == Type::Message
+            && self
+                .message(field.type_name())
+                .is_ok_and(|m| m.options.as_ref().is_some_and(|o| o.map_entry()))
+    }
+
+    fn decode_message(
+        &self,
+        name: &str,
+        mut buf: &[u8],
+        depth: usize,
+    ) -> std::result::Result<Value, String> {
+        if depth > MAX_DEPTH {
+            return Err("nested too deeply".into());
+        }
+        let message = self.message(name)?;
+        let mut out = Map::new();
+        while !buf.is_empty() {
+            let key = read_varint(&mut buf)?;
+            let (number, wire) = ((key >> 3) as i32, (key & 7) as u8);
+            let Some(field) = message.field.iter().find(|f| f.number() == number) else {
+                skip_field(wire, &mut buf)?;
+                continue;
+            };
+            let mut values = Vec::new();
+            match packed_wire(field.r#type()) {
+                Some(element_wire) if wire == 2 => {
+                    let mut data = read_delimited(&mut buf)?;
+                    while !data.is_empty() {
+                        values.push(self.decode_value(field, element_wire, &mut data, depth)?);
+                    }
+                }
+                _ => values.push(self.decode_value(field, wire, &mut buf, depth)?),
+            }
+
+            let key = json_name(field);
+            if self.is_map(field) {
+                let entries = out.entry(key).or_insert_with(|| Value::Object(Map::new()));
+                for entry in values {
+                    let key = match entry.get("key") {
+                        Some(Value::String(s)) => s.clone(),
+                        Some(other) => other.to_string(),
+                        None => String::new(),
+                    };
+                    let value = entry.get("value").cloned().unwrap_or(Value::Null);
+                    if let Value::Object(map) = entries {
+                        map.insert(key, value);
+                    }
+                }
+            } else if field.label() == Label::Repeated {
+                if let Value::Array(items) = out.entry(key).or_insert_with(|| Value::Array(vec![]))
+                {
+                    items.extend(values);
+                }
+            } else if let Some(value) = values.pop() {
+                out.insert(key, value);
+            }
+        }
+        Ok(Value::Object(out))
+    }
+
+    fn decode_value(
+        &self,
+        field: &FieldDescriptorProto,
+        wire: u8,
+        buf: &mut &[u8],
+        depth: usize,
+    ) -> std::result::Result<Value, String> {
+        let ty = field.r#type();
+        let expected = packed_wire(ty).unwrap_or(2);
+        if wire != expected {
+            return Err(format!(
+                "field {} has wire type {wire}, expected {expected}",
+                field.name()
+            ));
+        }
+        Ok(match ty {
+            Type::Double => float(f64::from_le_bytes(fixed(buf)?)),
+            Type::Float => float(f32::from_le_bytes(fixed(buf)?) as f64),
+            Type::Int64 => Value::String((read_varint(buf)? as i64).to_string()),
+            Type::Uint64 => Value::String(read_varint(buf)?.to_string()),
+            Type::Sint64 => Value::String(unzigzag(read_varint(buf)?).to_string()),
+            Type::Fixed64 => Value::String(u64::from_le_bytes(fixed(buf)?).to_string()),
+            Type::Sfixed64 => Value::String(i64::from_le_bytes(fixed(buf)?).to_string()),
+            Type::Int32 => Value::from(read_varint(buf)? as i32),
+            Type::Uint32 => Value::from(read_varint(buf)? as u32),
+            Type::Sint32 => Value::from(unzigzag(read_varint(buf)?) as i32),
+            Type::Fixed32 => Value::from(u32::from_le_bytes(fixed(buf)?)),
+            Type::Sfixed32 => Value::from(i32::from_le_bytes(fixed(buf)?)),
+            Type::Bool => Value::Bool(read_varint(buf)? != 0),
+            Type::Enum => {
+                let number = read_varint(buf)? as i32;
+                self.enums
+                    .get(field.type_name().trim_start_matches('.'))
+                    .and_then(|e| e.value.iter().find(|v| v.number() == number))
+                    .map(|v| Value::String(v.name().to_string()))
+                    .unwrap_or_else(|| Value::from(number))
+            }
+            Type::String => Value::String(
+                String::from_utf8(read_delimited(buf)?.to_vec())
+                    .map_err(|_| format!("field {} is not UTF-8", field.name()))?,
+            ),
+            Type::Bytes => Value::String(
+                base64::engine::general_purpose::STANDARD.encode(read_delimited(buf)?),
+            ),
+            Type::Message => {
+                self.decode_message(field.type_name(), read_delimited(buf)?, depth + 1)?
+            }
+            Type::Group => return Err("groups are not supported".into()),
+        })
+    }
+
+    fn encode_message(
+        &self,
+        name: &str,
+        value: &Value,
+        out: &mut Vec<u8>,
+    ) -> std::result::Result<(), String> {
+        let message = self.message(name)?;
+        let object = value
+            .as_object()
+            .ok_or_else(|| format!("expected an object for {name}"))?;
+        if let Some(key) = object.keys().find(|key| {
+            !message
+                .field
+                .iter()
+                .any(|f| json_name(f) == **key || f.name() == key.as_str())
+        }) {
+            return Err(format!("unknown field {key}"));
+        }
+        // Fields go out in field-number order, as protobuf encoders write them
+        let mut fields: Vec<&FieldDescriptorProto> = message.field.iter().collect();
+        fields.sort_by_key(|f| f.number());
+        for field in fields {
+            let json_key = json_name(field);
+            let Some((key, value)) = object
+                .get_key_value(&json_key)
+                .or_else(|| object.get_key_value(field.name()))
+            else {
+                continue;
+            };
+            if value.is_null() {
+                continue;
+            }
+            if self.is_map(field) {
+                let entries = value
+                    .as_object()
+                    .ok_or_else(|| format!("expected an object for {key}"))?;
+                for (k, v) in entries {
+                    let entry = serde_json::json!({ "key": k, "value": v });
+                    let mut bytes = Vec::new();
+                    self.encode_message(field.type_name(), &entry, &mut bytes)?;
+                    write_key(out, field.number(), 2);
+                    write_delimited(out, &bytes);
+                }
+            } else if field.label() == Label::Repeated {
+                let items = value
+                    .as_array()
+                    .ok_or_else(|| format!("expected an array for {key}"))?;
+                for item in items {
+                    self.encode_value(field, item, out)?;
+                }
+            } else {
+                self.encode_value(field, value, out)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn encode_value(
+        &self,
+        field: &FieldDescriptorProto,
+        value: &Value,
+        out: &mut Vec<u8>,
+    ) -> std::result::Result<(), String> {
+        let name = field.name();
+        let ty = field.r#type();
+        write_key(out, field.number(), packed_wire(ty).unwrap_or(2));
+        match ty {
+            Type::Double => out.extend(as_f64(value, name)?.to_le_bytes()),
+            Type::Float => out.extend((as_f64(value, name)? as f32).to_le_bytes()),
+            Type::Int64 => write_varint(out, as_i64(value, name)? as u64),
+            Type::Uint64 => write_varint(out, as_u64(value, name)?),
+            Type::Sint64 => write_varint(out, zigzag(as_i64(value, name)?)),
+            Type::Fixed64 => out.extend(as_u64(value, name)?.to_le_bytes()),
+            Type::Sfixed64 => out.extend(as_i64(value, name)?.to_le_bytes()),
+            Type::Int32 => write_varint(out, as_i32(value, name)? as i64 as u64),
+            Type::Uint32 => write_varint(out, as_u32(value, name)? as u64),
+            Type::Sint32 => write_varint(out, zigzag(as_i32(value, name)? as i64)),
+            Type::Fixed32 => out.extend(as_u32(value, name)?.to_le_bytes()),
+            Type::Sfixed32 => out.extend(as_i32(value, name)?.to_le_bytes()),
+            Type::Bool => match value {
+                Value::Bool(b) => write_varint(out, *b as u64),
+                // Map keys arrive as strings
+                Value::String(s) if s == "true" || s == "false" => {
+                    write_varint(out, (s == "true") as u64)
+                }
+                _ => return Err(format!("field {name} expects a boolean")),
+            },
+            Type::Enum => {
+                let number = match value {
+                    Value::String(s) => self
+                        .enums
+                        .get(field.type_name().trim_start_matches('.'))
+                        .and_then(|e| e.value.iter().find(|v| v.name() == s))
+                        .map(|v| v.number())
+                        .ok_or_else(|| format!("field {name} has no enum value {s}"))?,
+                    _ => as_i32(value, name)?,
+                };
+                write_varint(out, number as i64 as u64);
+            }
+            Type::String => {
+                let s = value
+                    .as_str()
+                    .ok_or_else(|| format!("field {name} expects a string"))?;
+                write_delimited(out, s.as_bytes());
+            }
+            Type::Bytes => {
+                let bytes = value
+                    .as_str()
+                    .and_then(|s| base64::engine::general_purpose::STANDARD.decode(s).ok())
+                    .ok_or_else(|| format!("field {name} expects a base64 string"))?;
+                write_delimited(out, &bytes);
+            }
+            Type::Message => {
+                let mut bytes = Vec::new();
+                self.encode_message(field.type_name(), value, &mut bytes)?;
+                write_delimited(out, &bytes);
+            }
+            Type::Group => return Err("groups are not supported".into()),
+        }
+        Ok(())
+    }
+}
+
+fn qualify(scope: &str, name: &str) -> String {
+    if scope.is_empty() {
+        name.to_string()
+    } else {
+        format!("{scope}.{name}")
+    }
+}
+
+/// The field's proto3 JSON name: `json_name` as set by protoc, else lowerCamelCase
+fn json_name(field: &FieldDescriptorProto) -> String {
+    if let Some(name) = field.json_name.as_deref() {
+        return name.to_string();
+    }
+    let mut out = String::new();
+    let mut upper = false;
+    for c in field.name().chars() {
+        if c == '_' {
+            upper = true;
+        } else if upper {
+            out.push(c.to_ascii_uppercase());
+            upper = false;
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+/// Wire type of a scalar that repeated fields may pack; `None` for length-delimited types
+fn packed_wire(ty: Type) -> Option<u8> {
+    match ty {
+        Type::Double | Type::Fixed64 | Type::Sfixed64 => Some(1),
+        Type::Float | Type::Fixed32 | Type::Sfixed32 => Some(5),
+        Type::String | Type::Bytes | Type::Message | Type::Group => None,
+        _ => Some(0),
+    }
+}
+
+fn read_varint(buf: &mut &[u8]) -> std::result::Result<u64, String> {
+    let mut value = 0u64;
+    for shift in (0..64).step_by(7) {
+        let (&byte, rest) = buf.split_first().ok_or("truncated varint")?;
+        *buf = rest;
+        value |= ((byte & 0x7f) as u64) << shift;
+        if byte & 0x80 == 0 {
+            return Ok(value);
+        }
+    }
+    Err("varint is too long".into())
+}
+
+fn read_delimited<'a>(buf: &mut &'a [u8]) -> std::result::Result<&'a [u8], String> {
+    let len = read_varint(buf)? as usize;
+    if buf.len() < len {
+        return Err("truncated field".into());
+    }
+    let (head, rest) = buf.split_at(len);
+    *buf = rest;
+    Ok(head)
+}
+
+fn fixed<const N: usize>(buf: &mut &[u8]) -> std::result::Result<[u8; N], String> {
+    if buf.len() < N {
+        return Err("truncated field".into());
+    }
+    let (head, rest) = buf.split_at(N);
+    *buf = rest;
+    Ok(head.try_into().expect("length checked"))
+}
+
+fn skip_field(wire: u8, buf: &mut &[u8]) -> std::result::Result<(), String> {
+    match wire {
+        0 => read_varint(buf).map(drop),
+        1 => fixed::<8>(buf).map(drop),
+        2 => read_delimited(buf).map(drop),
+        5 => fixed::<4>(buf).map(drop),
+        _ => Err(format!("unsupported wire type {wire}")),
+    }
+}
+
+fn write_varint(out: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        out.push((value as u8) | 0x80);
+        value >>= 7;
+    }
+    out.push(value as u8);
+}
+
+fn write_key(out: &mut Vec<u8>, number: i32, wire: u8) {
+    write_varint(out, ((number as u64) << 3) | wire as u64);
+}
+
+fn write_delimited(out: &mut Vec<u8>, bytes: &[u8]) {
+    write_varint(out, bytes.len() as u64);
+    out.extend_from_slice(bytes);
+}
+
+fn zigzag(n: i64) -> u64 {
+    ((n << 1) ^ (n >> 63)) as u64
+}
+
+fn unzigzag(n: u64) -> i64 {
+    ((n >> 1) as i64) ^ -((n & 1) as i64)
+}
+
+/// Non-finite floats have no JSON number; proto3 JSON writes them as strings
+fn float(f: f64) -> Value {
+    match Number::from_f64(f) {
+        Some(n) => Value::Number(n),
+        None if f.is_nan() => Value::String("NaN".into()),
+        None if f > 0.0 => Value::String("Infinity".into()),
+        None => Value::String("-Infinity".into()),
+    }
+}
+
+fn as_f64(value: &Value, field: &str) -> std::result::Result<f64, String> {
+    match value {
+        Value::Number(n) => n.as_f64(),
+        Value::String(s) => s.parse().ok(),
+        _ => None,
+    }
+    .ok_or_else(|| format!("field {field} expects a number"))
+}
+
+/// Integers may be written as numbers or, as proto3 JSON does for 64-bit ones, strings
+fn as_i64(value: &Value, field: &str) -> std::result::Result<i64, String> {
+    match value {
+        Value::Number(n) => n.as_i64(),
+        Value::String(s) => s.parse().ok(),
+        _ => None,
+    }
+    .ok_or_else(|| format!("field {field} expects an integer"))
+}
+
+fn as_u64(value: &Value, field: &str) -> std::result::Result<u64, String> {
+    match value {
+        Value::Number(n) => n.as_u64(),
+        Value::String(s) => s.parse().ok(),
+        _ => None,
+    }
+    .ok_or_else(|| format!("field {field} expects an unsigned integer"))
+}
+
+fn as_i32(value: &Value, field: &str) -> std::result::Result<i32, String> {
+    i32::try_from(as_i64(value, field)?).map_err(|_| format!("field {field} is out of range"))
+}
+
+fn as_u32(value: &Value, field: &str) -> std::result::Result<u32, String> {
+    u32::try_from(as_u64(value, field)?).map_err(|_| format!("field {field} is out of range"))
+}
@@ -0,0 +1,754 @@
+//! Long-haul soak testing with leak detection (feature `soak`).
+//!
+//! `run_soak` runs the full pipeline in one process for a configurable duration:
+//! - Event generators publishing to a set of soak topics on the EventBus
+//! - Core agents (`AgentRuntime`) subscribed to those topics, periodically deleted and
+//!   recreated
+//! - Bridge clients connecting over gRPC, receiving deliveries, publishing, answering
+//!   server-pushed tool calls, then disconnecting and starting over
+//!
+//! A sampler snapshots resource gauges (EventBus topics and subscriptions, Bridge maps,
+//! fanout attachments, parked agents, live tokio tasks, process RSS) at a fixed interval.
+//! The per-gauge maxima seen during warmup become the baseline; a later sample above
+//! baseline + slack is reported as a leak. Churn keeps every gauge oscillating within a
+//! fixed range, so steady growth points at state nobody cleans up, such as an orphaned
+//! forwarding task.
+//!
+//! Env overrides for `SoakConfig::from_env()`:
+//! - LOOM_SOAK_DURATION_SECS (default 3600)
+//! - LOOM_SOAK_WARMUP_SECS (default 60)
+//! - LOOM_SOAK_SAMPLE_INTERVAL_SECS (default 10)
+//! - LOOM_SOAK_AGENTS (default 4)
+//! - LOOM_SOAK_CLIENTS (default 8)
+//! - LOOM_SOAK_TOPICS (default 4)
+//! - LOOM_SOAK_PUBLISH_RATE (events/s, default 200)
+//! - LOOM_SOAK_COUNT_SLACK (default 16)
+//! - LOOM_SOAK_RSS_GROWTH_MB (default 64)
+//! - LOOM_SOAK_FAIL_FAST (`1`/`true` stops at the first leak)
+
+use std::net::{Ipv4Addr, SocketAddr};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use tokio::net::TcpListener;
+use tokio::sync::watch;
+use tokio::time::{sleep, Instant, MissedTickBehavior};
+use tokio_stream::wrappers::{ReceiverStream, TcpListenerStream};
+use tracing::{debug, info, warn};
+
+use loom_core::proto::{Action, AgentConfig, AgentState, Event};
+use loom_core::{AgentBehavior, AgentDirectory, AgentRuntime, EventBus, ModelRouter, ToolRegistry};
+use loom_proto::{
+    bridge_client::BridgeClient, bridge_server::BridgeServer, client_event, server_event, Ack,
+    AgentRegisterRequest, ClientEvent, ProviderKind, Publish, ToolCall, ToolDescriptor, ToolResult,
+    ToolStatus,
+};
+
+use crate::{BridgeError, BridgeService, BridgeState, ReplayConfig, Result};
+
+/// How often one core agent is deleted and recreated
+const AGENT_CHURN_INTERVAL: Duration = Duration::from_secs(5);
+
+/// How often a server-push tool call is sent to one of the clients
+const TOOL_CALL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// How often a connected client publishes through its stream
+const CLIENT_PUBLISH_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Tool every soak client registers and answers
+const SOAK_TOOL: &str = "soak.echo";
+
+/// Soak run configuration
+#[derive(Debug, Clone)]
+pub struct SoakConfig {
+    /// Total run time, including warmup
+    pub duration: Duration,
+    /// Samples taken during warmup only establish the baseline
+    pub warmup: Duration,
+    pub sample_interval: Duration,
+    /// Core agents subscribed to every soak topic
+    pub agents: usize,
+    /// Bridge clients repeatedly connecting and disconnecting
+    pub clients: usize,
+    pub topics: usize,
+    /// Events per second published across all soak topics
+    pub publish_rate: u32,
+    /// Allowed growth of each count gauge over its baseline
+    pub count_slack: usize,
+    /// Allowed RSS growth over the baseline
+    pub rss_growth_bytes: u64,
+    /// Stop at the first leak instead of running to completion
+    pub fail_fast: bool,
+}
+
+impl Default for SoakConfig {
+    fn default() -> Self {
+        Self {
+            duration: Duration::from_secs(3600),
+            warmup: Duration::from_secs(60),
+            sample_interval: Duration::from_secs(10),
+            agents: 4,
+            clients: 8,
+            topics: 4,
+            publish_rate: 200,
+            count_slack: 16,
+            rss_growth_bytes: 64 * 1024 * 1024,
+            fail_fast: false,
+        }
+    }
+}
+
+impl SoakConfig {
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        let secs = |name: &str, default: Duration| {
+            env_parse::<u64>(name)
+                .map(Duration::from_secs)
+                .unwrap_or(default)
+        };
+        Self {
+            duration: secs("LOOM_SOAK_DURATION_SECS", defaults.duration),
+            warmup: secs("LOOM_SOAK_WARMUP_SECS", defaults.warmup),
+            sample_interval: secs("LOOM_SOAK_SAMPLE_INTERVAL_SECS", defaults.sample_interval),
+            agents: env_parse("LOOM_SOAK_AGENTS").unwrap_or(defaults.agents),
+            clients: env_parse("LOOM_SOAK_CLIENTS").unwrap_or(defaults.clients),
+            topics: env_parse("LOOM_SOAK_TOPICS").unwrap_or(defaults.topics),
+            publish_rate: env_parse("LOOM_SOAK_PUBLISH_RATE").unwrap_or(defaults.publish_rate),
+            count_slack: env_parse("LOOM_SOAK_COUNT_SLACK").unwrap_or(defaults.count_slack),
+            rss_growth_bytes: env_parse::<u64>("LOOM_SOAK_RSS_GROWTH_MB")
+                .map(|mb| mb * 1024 * 1024)
+                .unwrap_or(defaults.rss_growth_bytes),
+            fail_fast: matches!(
+                std::env::var("LOOM_SOAK_FAIL_FAST").as_deref(),
+                Ok("1") | Ok("true")
+            ),
+        }
+    }
+}
+
+fn env_parse<T: std::str::FromStr>(name: &str) -> Option<T> {
+    std::env::var(name).ok().and_then(|v| v.parse().ok())
+}
+
+/// Point-in-time resource gauges
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ResourceSnapshot {
+    pub bus_topics: usize,
+    pub bus_subscriptions: usize,
+    pub agents: usize,
+    pub bridge_streams: usize,
+    pub bridge_subscriptions: usize,
+    pub agent_tools: usize,
+    pub tool_results: usize,
+    pub tool_result_index: usize,
+    pub tool_waiters: usize,
+    pub pending_tool_calls: usize,
+    pub fanout_topics: usize,
+    pub fanout_attachments: usize,
+    pub parked_agents: usize,
+    /// Live tokio tasks on the soak runtime
+    pub tasks: usize,
+    /// Resident set size; `None` where `/proc/self/statm` is unavailable
+    pub rss_bytes: Option<u64>,
+}
+
+impl ResourceSnapshot {
+    /// Count gauges by name (RSS is checked separately)
+    pub fn counts(&self) -> [(&'static str, usize); 14] {
+        [
+            ("bus_topics", self.bus_topics),
+            ("bus_subscriptions", self.bus_subscriptions),
+            ("agents", self.agents),
+            ("bridge_streams", self.bridge_streams),
+            ("bridge_subscriptions", self.bridge_subscriptions),
+            ("agent_tools", self.agent_tools),
+            ("tool_results", self.tool_results),
+            ("tool_result_index", self.tool_result_index),
+            ("tool_waiters", self.tool_waiters),
+            ("pending_tool_calls", self.pending_tool_calls),
+            ("fanout_topics", self.fanout_topics),
+            ("fanout_attachments", self.fanout_attachments),
+            ("parked_agents", self.parked_agents),
+            ("tasks", self.tasks),
+        ]
+    }
+
+    /// Per-gauge maximum of two snapshots
+    pub fn max(&self, other: &Self) -> Self {
+        Self {
+            bus_topics: self.bus_topics.max(other.bus_topics),
+            bus_subscriptions: self.bus_subscriptions.max(other.bus_subscriptions),
+            agents: self.agents.max(other.agents),
+            bridge_streams: self.bridge_streams.max(other.bridge_streams),
+            bridge_subscriptions: self.bridge_subscriptions.max(other.bridge_subscriptions),
+            agent_tools: self.agent_tools.max(other.agent_tools),
+            tool_results: self.tool_results.max(other.tool_results),
+            tool_result_index: self.tool_result_index.max(other.tool_result_index),
+            tool_waiters: self.tool_waiters.max(other.tool_waiters),
+            pending_tool_calls: self.pending_tool_calls.max(other.pending_tool_calls),
+            fanout_topics: self.fanout_topics.max(other.fanout_topics),
+            fanout_attachments: self.fanout_attachments.max(other.fanout_attachments),
+            parked_agents: self.parked_agents.max(other.parked_agents),
+            tasks: self.tasks.max(other.tasks),
+            rss_bytes: self.rss_bytes.max(other.rss_bytes),
+        }
+    }
+
+    /// Take a snapshot of the pipeline's gauges
+    pub async fn capture(state: &BridgeState, runtime: &AgentRuntime) -> Self {
+        let fanout = state.fanout.stats().await;
+        Self {
+            bus_topics: state.event_bus.topic_count(),
+            bus_subscriptions: state.event_bus.subscription_count(),
+            agents: runtime.agent_count(),
+            bridge_streams: state.streams.len(),
+            bridge_subscriptions: state.subscriptions.len(),
+            agent_tools: state.agent_tools.len(),
+            tool_results: state.tool_results.len(),
+            tool_result_index: state.tool_result_index.len(),
+            tool_waiters: state.tool_waiters.len(),
+            pending_tool_calls: state.pending_tool_calls.len(),
+            fanout_topics: fanout.topics,
+            fanout_attachments: fanout.attachments,
+            parked_agents: state.replay.stats().parked_agents,
+            tasks: tokio::runtime::Handle::current()
+                .metrics()
+                .num_alive_tasks(),
+            rss_bytes: process_rss_bytes(),
+        }
+    }
+}
+
+/// Resident set size from `/proc/self/statm` (Linux; assumes 4 KiB pages)
+fn process_rss_bytes() -> Option<u64> {
+    let statm = std::fs::read_to_string("/proc/self/statm").ok()?;
+    let pages: u64 = statm.split_whitespace().nth(1)?.parse().ok()?;
+    Some(pages * 4096)
+}
+
+/// A gauge that grew past its allowance
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LeakViolation {
+    pub gauge: &'static str,
+    pub baseline: u64,
+    pub current: u64,
+}
+
+/// Compares samples against a baseline taken during warmup
+#[derive(Debug, Clone)]
+pub struct LeakDetector {
+    count_slack: usize,
+    rss_growth_bytes: u64,
+    baseline: Option<ResourceSnapshot>,
+}
+
+impl LeakDetector {
+    pub fn new(count_slack: usize, rss_growth_bytes: u64) -> Self {
+        Self {
+            count_slack,
+            rss_growth_bytes,
+            baseline: None,
+        }
+    }
+
+    /// Fold a warmup sample into the baseline (per-gauge maximum)
+    pub fn observe_warmup(&mut self, snapshot: &ResourceSnapshot) {
+        self.baseline = Some(match &self.baseline {
+            Some(baseline) => baseline.max(snapshot),
+            None => snapshot.clone(),
+        });
+    }
+
+    pub fn baseline(&self) -> Option<&ResourceSnapshot> {
+        self.baseline.as_ref()
+    }
+
+    /// Gauges in `snapshot` above baseline + slack; empty before any warmup sample
+    pub fn check(&self, snapshot: &ResourceSnapshot) -> Vec<LeakViolation> {
+        let Some(baseline) = &self.baseline else {
+            return Vec::new();
+        };
+        let mut violations: Vec<LeakViolation> = baseline
+            .counts()
+            .iter()
+            .zip(snapshot.counts().iter())
+            .filter(|((_, base), (_, current))| *current > base + self.count_slack)
+            .map(|((gauge, base), (_, current))| LeakViolation {
+                gauge: *gauge,
+                baseline: *base as u64,
+                current: *current as u64,
+            })
+            .collect();
+        if let (Some(base), Some(current)) = (baseline.rss_bytes, snapshot.rss_bytes) {
+            if current > base + self.rss_growth_bytes {
+                violations.push(LeakViolation {
+                    gauge: "rss_bytes",
+                    baseline: base,
+                    current,
+                });
+            }
+        }
+        violations
+    }
+}
+
+/// Outcome of a soak run
+#[derive(Debug, Clone, Default)]
+pub struct SoakReport {
+    pub elapsed: Duration,
+    pub samples: usize,
+    pub baseline: ResourceSnapshot,
+    pub peak: ResourceSnapshot,
+    pub last: ResourceSnapshot,
+    /// Leaks with the run time at which they were observed
+    pub violations: Vec<(Duration, LeakViolation)>,
+    pub events_published: u64,
+    pub events_handled: u64,
+    pub deliveries: u64,
+    pub client_sessions: u64,
+    pub tool_calls_ok: u64,
+    pub tool_calls_failed: u64,
+}
+
+impl SoakReport {
+    pub fn passed(&self) -> bool {
+        self.violations.is_empty()
+    }
+}
+
+#[derive(Default)]
+struct Counters {
+    published: AtomicU64,
+    handled: AtomicU64,
+    deliveries: AtomicU64,
+    sessions: AtomicU64,
+    tool_ok: AtomicU64,
+    tool_failed: AtomicU64,
+}
+
+/// Core agent that counts the events it handles
+struct CountingBehavior {
+    counters: Arc<Counters>,
+}
+
+#[async_trait]
+impl AgentBehavior for CountingBehavior {
+    async fn on_event(
+        &mut self,
+        _event: Event,
+        _state: &mut AgentState,
+    ) -> loom_core::Result<Vec<Action>> {
+        self.counters.handled.fetch_add(1, Ordering::Relaxed);
+        Ok(vec![])
+    }
+
+    async fn on_init(&mut self, _config: &AgentConfig) -> loom_core::Result<()> {
+        Ok(())
+    }
+
+    async fn on_shutdown(&mut self) -> loom_core::Result<()> {
+        Ok(())
+    }
+}
+
+fn internal(e: impl std::fmt::Display) -> BridgeError {
+    BridgeError::Internal(e.to_string())
+}
+
+/// Run the soak pipeline for `config.duration`, sampling and checking for leaks
+pub async fn run_soak(config: SoakConfig) -> Result<SoakReport> {
+    info!(?config, "Starting soak run");
+
+    let event_bus = Arc::new(EventBus::new().await.map_err(internal)?);
+    event_bus.start().await.map_err(internal)?;
+    let mut state = BridgeState::new(
+        Arc::clone(&event_bus),
+        Arc::new(ToolRegistry::new()),
+        Arc::new(AgentDirectory::new()),
+    );
+    state.set_replay_config(ReplayConfig::from_env());
+    let svc = BridgeService::new(state.clone());
+
+    let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0))
+        .await
+        .map_err(internal)?;
+    let addr = listener.local_addr().map_err(internal)?;
+    let server_svc = svc.clone();
+    let server = tokio::spawn(async move {
+        if let Err(e) = tonic::transport::Server::builder()
+            .add_service(BridgeServer::new(server_svc))
+            .serve_with_incoming(TcpListenerStream::new(listener))
+            .await
+        {
+            warn!(error = %e, "Soak bridge server exited");
+        }
+    });
+
+    let runtime = AgentRuntime::new(
+        Arc::clone(&event_bus),
+        Arc::new(ToolRegistry::new()),
+        ModelRouter::new().await.map_err(internal)?,
+    )
+    .await
+    .map_err(internal)?;
+
+    let topics: Vec<String> = (0..config.topics.max(1))
+        .map(|i| format!("soak.events.{i}"))
+        .collect();
+    let counters = Arc::new(Counters::default());
+    for i in 0..config.agents {
+        create_agent(&runtime, i, &topics, &counters).await?;
+    }
+
+    let (stop_tx, stop_rx) = watch::channel(false);
+    let mut workers = vec![
+        tokio::spawn(generate_events(
+            Arc::clone(&event_bus),
+            topics.clone(),
+            config.publish_rate,
+            Arc::clone(&counters),
+            stop_rx.clone(),
+        )),
+        tokio::spawn(churn_agents(
+            runtime.clone(),
+            config.agents,
+            topics.clone(),
+            Arc::clone(&counters),
+            stop_rx.clone(),
+        )),
+        tokio::spawn(push_tool_calls(
+            svc.clone(),
+            config.clients,
+            Arc::clone(&counters),
+            stop_rx.clone(),
+        )),
+    ];
+    for i in 0..config.clients {
+        workers.push(tokio::spawn(run_client(
+            addr,
+            format!("soak-client-{i}"),
+            topics.clone(),
+            Arc::clone(&counters),
+            stop_rx.clone(),
+        )));
+    }
+
+    let started = Instant::now();
+    let mut detector = LeakDetector::new(config.count_slack, config.rss_growth_bytes);
+    let mut report = SoakReport::default();
+    let mut ticker = tokio::time::interval(config.sample_interval);
+    ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+    ticker.tick().await;
+    loop {
+        ticker.tick().await;
+        let elapsed = started.elapsed();
+        let snapshot = ResourceSnapshot::capture(&state, &runtime).await;
+        report.samples += 1;
+        report.peak = report.peak.max(&snapshot);
+
+        if elapsed <= config.warmup {
+            detector.observe_warmup(&snapshot);
+            info!(
+                elapsed_s = elapsed.as_secs(),
+                ?snapshot,
+                "Soak warmup sample"
+            );
+        } else {
+            let violations = detector.check(&snapshot);
+            for v in violations.iter() {
+                warn!(
+                    elapsed_s = elapsed.as_secs(),
+                    gauge = v.gauge,
+                    baseline = v.baseline,
+                    current = v.current,
+                    "Soak gauge grew past its baseline"
+                );
+            }
+            if violations.is_empty() {
+                info!(elapsed_s = elapsed.as_secs(), ?snapshot, "Soak sample");
+            }
+            report
+                .violations
+                .extend(violations.into_iter().map(|v| (elapsed, v)));
+        }
+        report.last = snapshot;
+
+        if elapsed >= config.duration || (config.fail_fast && !report.passed()) {
+            break;
+        }
+    }
+
+    let _ = stop_tx.send(true);
+    for worker in workers {
+        let _ = tokio::time::timeout(Duration::from_secs(5), worker).await;
+    }
+    for i in 0..config.agents {
+        let _ = runtime.delete_agent(&agent_id(i)).await;
+    }
+    server.abort();
+    let _ = event_bus.shutdown().await;
+
+    report.elapsed = started.elapsed();
+    report.baseline = detector.baseline().cloned().unwrap_or_default();
+    report.events_published = counters.published.load(Ordering::Relaxed);
+    report.events_handled = counters.handled.load(Ordering::Relaxed);
+    report.deliveries = counters.deliveries.load(Ordering::Relaxed);
+    report.client_sessions = counters.sessions.load(Ordering::Relaxed);
+    report.tool_calls_ok = counters.tool_ok.load(Ordering::Relaxed);
+    report.tool_calls_failed = counters.tool_failed.load(Ordering::Relaxed);
+    info!(
+        elapsed_s = report.elapsed.as_secs(),
+        samples = report.samples,
+        violations = report.violations.len(),
+        "Soak run finished"
+    );
+    Ok(report)
+}
+
+fn agent_id(i: usize) -> String {
+    format!("soak-agent-{i}")
+}
+
+async fn create_agent(
+    runtime: &AgentRuntime,
+    i: usize,
+    topics: &[String],
+    counters: &Arc<Counters>,
+) -> Result<()> {
+    runtime
+        .create_agent(
+            AgentConfig {
+                agent_id: agent_id(i),
+                agent_type: "soak".into(),
+                subscribed_topics: topics.to_vec(),
+                capabilities: vec![],
+                parameters: Default::default(),
+            },
+            Box::new(CountingBehavior {
+                counters: Arc::clone(counters),
+            }),
+        )
+        .await
+        .map_err(internal)?;
+    Ok(())
+}
+
+fn soak_event(id: String, source: &str) -> Event {
+    Event {
+        id,
+        r#type: "soak.tick".into(),
+        timestamp_ms: chrono::Utc::now().timestamp_millis(),
+        source: source.into(),
+        metadata: Default::default(),
+        payload: vec![0u8; 64],
+        confidence: 1.0,
+        tags: vec![],
+        priority: 50,
+    }
+}
+
+async fn generate_events(
+    event_bus: Arc<EventBus>,
+    topics: Vec<String>,
+    rate: u32,
+    counters: Arc<Counters>,
+    mut stop: watch::Receiver<bool>,
+) {
+    if rate == 0 {
+        return;
+    }
+    let mut ticker = tokio::time::interval(Duration::from_secs_f64(1.0 / rate as f64));
+    ticker.set_missed_tick_behavior(MissedTickBehavior::Skip);
+    let mut seq: u64 = 0;
+    loop {
+        tokio::select! {
+            _ = stop.changed() => return,
+            _ = ticker.tick() => {}
+        }
+        let topic = &topics[seq as usize % topics.len()];
+        if event_bus
+            .publish(topic, soak_event(format!("soak-{seq}"), "soak.generator"))
+            .await
+            .is_ok()
+        {
+            counters.published.fetch_add(1, Ordering::Relaxed);
+        }
+        seq += 1;
+    }
+}
+
+/// Delete and recreate core agents round-robin
+async fn churn_agents(
+    runtime: AgentRuntime,
+    agents: usize,
+    topics: Vec<String>,
+    counters: Arc<Counters>,
+    mut stop: watch::Receiver<bool>,
+) {
+    if agents == 0 {
+        return;
+    }
+    let mut next = 0;
+    loop {
+        tokio::select! {
+            _ = stop.changed() => return,
+            _ = sleep(AGENT_CHURN_INTERVAL) => {}
+        }
+        let i = next % agents;
+        next += 1;
+        if let Err(e) = runtime.delete_agent(&agent_id(i)).await {
+            debug!(error = %e, "Soak agent delete failed");
+        }
+        if let Err(e) = create_agent(&runtime, i, &topics, &counters).await {
+            warn!(error = %e, "Soak agent recreate failed");
+        }
+    }
+}
+
+/// Push a tool call to one client at a time and wait for its result
+async fn push_tool_calls(
+    svc: BridgeService,
+    clients: usize,
+    counters: Arc<Counters>,
+    mut stop: watch::Receiver<bool>,
+) {
+    if clients == 0 {
+        return;
+    }
+    let mut seq: u64 = 0;
+    loop {
+        tokio::select! {
+            _ = stop.changed() => return,
+            _ = sleep(TOOL_CALL_INTERVAL) => {}
+        }
+        let agent_id = format!("soak-client-{}", seq as usize % clients);
+        let call_id = format!("soak-call-{seq}");
+        seq += 1;
+        let call = ToolCall {
+            id: call_id.clone(),
+            name: SOAK_TOOL.into(),
+            arguments: format!(r#"{{"seq":{seq}}}"#),
+            headers: Default::default(),
+            timeout_ms: 2_000,
+            correlation_id: String::new(),
+            qos: 0,
+        };
+        // Clients come and go; only count calls that reached a connected agent
+        if !matches!(svc.push_tool_call(&agent_id, call).await, Ok(true)) {
+            continue;
+        }
+        match svc
+            .await_tool_result(&call_id, Duration::from_secs(2))
+            .await
+        {
+            Ok(result) if result.status == ToolStatus::ToolOk as i32 => {
+                counters.tool_ok.fetch_add(1, Ordering::Relaxed);
+            }
+            _ => {
+                counters.tool_failed.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+    }
+}
+
+/// Connect, hold the stream for a varying time, disconnect, repeat
+async fn run_client(
+    addr: SocketAddr,
+    agent_id: String,
+    topics: Vec<String>,
+    counters: Arc<Counters>,
+    mut stop: watch::Receiver<bool>,
+) {
+    let mut session: u64 = 0;
+    while !*stop.borrow() {
+        session += 1;
+        // Spread hold times over 0.5-2.5s so clients don't reconnect in lockstep
+        let hold = Duration::from_millis(500 + (session * 331 + agent_id.len() as u64 * 97) % 2000);
+        if let Err(e) = client_session(addr, &agent_id, &topics, hold, &counters, &mut stop).await {
+            debug!(agent_id = %agent_id, error = %e, "Soak client session failed");
+            sleep(Duration::from_millis(200)).await;
+        }
+    }
+}
+
+async fn client_session(
+    addr: SocketAddr,
+    agent_id: &str,
+    topics: &[String],
+    hold: Duration,
+    counters: &Counters,
+    stop: &mut watch::Receiver<bool>,
+) -> std::result::Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let mut client = BridgeClient::connect(format!("http://{addr}")).await?;
+    client
+        .register_agent(AgentRegisterRequest {
+            agent_id: agent_id.into(),
+            subscribed_topics: topics.to_vec(),
+            tools: vec![ToolDescriptor {
+                name: SOAK_TOOL.into(),
+                description: "Echo the call arguments".into(),
+                parameters_schema: r#"{"type":"object"}"#.into(),
+                provider: ProviderKind::ProviderGrpc as i32,
+                metadata: Default::default(),
+            }],
+            metadata: Default::default(),
+        })
+        .await?;
+
+    let (tx, rx) = tokio::sync::mpsc::channel(64);
+    tx.send(ClientEvent {
+        msg: Some(client_event::Msg::Ack(Ack {
+            message_id: agent_id.into(),
+        })),
+    })
+    .await?;
+    let mut inbound = client
+        .event_stream(ReceiverStream::new(rx))
+        .await?
+        .into_inner();
+    counters.sessions.fetch_add(1, Ordering::Relaxed);
+
+    let deadline = sleep(hold);
+    tokio::pin!(deadline);
+    let mut publish = tokio::time::interval(CLIENT_PUBLISH_INTERVAL);
+    let mut published: u64 = 0;
+    loop {
+        tokio::select! {
+            _ = &mut deadline => break,
+            _ = stop.changed() => break,
+            _ = publish.tick() => {
+                published += 1;
+                let topic = &topics[published as usize % topics.len()];
+                tx.send(ClientEvent {
+                    msg: Some(client_event::Msg::Publish(Publish {
+                        topic: topic.clone(),
+                        event: Some(soak_event(format!("{agent_id}-{published}"), agent_id)),
+                    })),
+                })
+                .await?;
+            }
+            msg = inbound.message() => match msg?.and_then(|m| m.msg) {
+                Some(server_event::Msg::Delivery(_)) => {
+                    counters.deliveries.fetch_add(1, Ordering::Relaxed);
+                }
+                Some(server_event::Msg::ToolCall(call)) => {
+                    tx.send(ClientEvent {
+                        msg: Some(client_event::Msg::ToolResult(ToolResult {
+                            id: call.id,
+                            status: ToolStatus::ToolOk as i32,
+                            output: call.arguments,
+                            error: None,
+                        })),
+                    })
+                    .await?;
+                }
+                Some(_) => {}
+                None => break,
+            },
+        }
+    }
+    // Dropping `tx` and `inbound` ends the stream; the Bridge detaches (or parks) the agent
+    Ok(())
+}
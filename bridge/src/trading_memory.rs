@@ -1,27 +1,40 @@
-//! In-Memory Trading Memory Store
+//! Trading Memory Store
 //!
 //! Provides memory storage for market-analyst agents, including:
 //! - Trading plan storage and retrieval
 //! - Execution record tracking
 //! - Duplicate detection
 //! - Event history
+//! - Time-range and symbol queries, and aggregations such as PnL per strategy
+//!
+//! `TradingMemory` is implemented by `InMemoryMemory` (bounded, lost on restart) and
+//! `RocksDbMemory` (persistent). `from_env` picks RocksDB when
+//! `LOOM_TRADING_MEMORY_PATH` is set.
 //!
 //! This is a specialized memory store for the market-analyst demo,
 //! separate from the general-purpose context memory in loom-core.
 
+mod persistent;
+mod query;
+
+pub use persistent::RocksDbMemory;
+pub use query::{
+    aggregate, plan_strategy, GroupBy, RecordFilter, DEFAULT_QUERY_LIMIT, MAX_QUERY_LIMIT,
+};
+
 use async_trait::async_trait;
 use dashmap::DashMap;
 use loom_core::context::{MemoryReader, MemoryWriter};
 use loom_proto::{
-    CheckDuplicateRequest, CheckDuplicateResponse, CheckExecutedRequest, CheckExecutedResponse,
-    Event, ExecutionRecord, GetExecutionStatsRequest, GetExecutionStatsResponse,
-    GetRecentPlansRequest, GetRecentPlansResponse, MarkExecutedRequest, MarkExecutedResponse,
-    PlanRecord, SavePlanRequest, SavePlanResponse,
+    AggregateExecutionsResponse, CheckDuplicateRequest, CheckDuplicateResponse,
+    CheckExecutedRequest, CheckExecutedResponse, Event, ExecutionRecord, GetExecutionStatsRequest,
+    GetExecutionStatsResponse, GetRecentPlansRequest, GetRecentPlansResponse, MarkExecutedRequest,
+    MarkExecutedResponse, PlanRecord, SavePlanRequest, SavePlanResponse,
 };
 use std::sync::Arc;
 use tracing::debug;
 
-/// Error type for InMemoryMemory operations
+/// Error type for trading memory operations
 #[derive(thiserror::Error, Debug)]
 pub enum MemoryError {
     #[error("Plan is required")]
@@ -30,6 +43,12 @@ pub enum MemoryError {
     #[error("Session not found: {0}")]
     SessionNotFound(String),
 
+    #[error("Invalid query: {0}")]
+    InvalidQuery(String),
+
+    #[error("Storage error: {0}")]
+    Storage(String),
+
     #[error("Internal error: {0}")]
     Internal(String),
 }
@@ -40,44 +59,83 @@ impl From<MemoryError> for loom_core::LoomError {
     }
 }
 
-/// A simple in-memory memory store for demo/testing.
-/// Stores textual summaries of events keyed by session id.
-/// Also stores structured trading plans and execution records for market-analyst agents.
-#[derive(Default)]
-pub struct InMemoryMemory {
-    // session -> list of event summary lines (legacy)
-    store: DashMap<String, Vec<String>>,
-
-    // session_id -> list of trading plans
-    plans: DashMap<String, Vec<PlanRecord>>,
+/// Store configured by `LOOM_TRADING_MEMORY_PATH`: RocksDB at that path, in memory if unset
+pub fn from_env() -> Result<Arc<dyn TradingMemory>, MemoryError> {
+    let store: Arc<dyn TradingMemory> = match std::env::var("LOOM_TRADING_MEMORY_PATH") {
+        Ok(path) if !path.trim().is_empty() => RocksDbMemory::open(path.trim())?,
+        _ => InMemoryMemory::new(),
+    };
+    Ok(store)
+}
 
-    // session_id -> list of execution records
-    executed_plans: DashMap<String, Vec<ExecutionRecord>>,
+fn summarize_event(event: &Event) -> String {
+    // Minimal summary without parsing payload
+    format!(
+        "[{ts}] {ty} from {src}",
+        ts = event.timestamp_ms,
+        ty = event.r#type,
+        src = event.source
+    )
 }
 
-impl InMemoryMemory {
-    pub fn new() -> Arc<Self> {
-        Arc::new(Self {
-            store: DashMap::new(),
-            plans: DashMap::new(),
-            executed_plans: DashMap::new(),
-        })
+/// Storage for trading plans and executions
+///
+/// Backends implement the storage primitives; the operations behind `MemoryService`
+/// are provided on top of them.
+pub trait TradingMemory: MemoryWriter + MemoryReader + Send + Sync {
+    /// Store `plan` for `session_id`
+    fn insert_plan(&self, session_id: &str, plan: PlanRecord) -> Result<(), MemoryError>;
+
+    /// Store `record` for `session_id`
+    fn insert_execution(
+        &self,
+        session_id: &str,
+        record: ExecutionRecord,
+    ) -> Result<(), MemoryError>;
+
+    /// Plan saved in the session with `plan_hash`
+    fn find_plan(
+        &self,
+        session_id: &str,
+        plan_hash: &str,
+    ) -> Result<Option<PlanRecord>, MemoryError>;
+
+    /// First execution recorded in the session for `plan_hash`
+    fn find_execution(
+        &self,
+        session_id: &str,
+        plan_hash: &str,
+    ) -> Result<Option<ExecutionRecord>, MemoryError>;
+
+    /// Plans matching `filter`, oldest first; the filter's order and limit are ignored
+    fn scan_plans(&self, filter: &RecordFilter) -> Result<Vec<PlanRecord>, MemoryError>;
+
+    /// Executions matching `filter`, oldest first; the filter's order and limit are ignored
+    fn scan_executions(&self, filter: &RecordFilter) -> Result<Vec<ExecutionRecord>, MemoryError>;
+
+    /// Plans matching `filter`, in its order and up to its limit
+    fn query_plans(&self, filter: &RecordFilter) -> Result<Vec<PlanRecord>, MemoryError> {
+        Ok(filter.finish(self.scan_plans(filter)?))
+    }
+
+    /// Executions matching `filter`, in its order and up to its limit
+    fn query_executions(&self, filter: &RecordFilter) -> Result<Vec<ExecutionRecord>, MemoryError> {
+        Ok(filter.finish(self.scan_executions(filter)?))
     }
 
-    fn summarize_event(event: &Event) -> String {
-        // Minimal summary without parsing payload
-        format!(
-            "[{ts}] {ty} from {src}",
-            ts = event.timestamp_ms,
-            ty = event.r#type,
-            src = event.source
-        )
+    /// Totals of every execution matching `filter` (its limit is ignored), per group
+    fn aggregate_executions(
+        &self,
+        filter: &RecordFilter,
+        group_by: GroupBy,
+    ) -> Result<AggregateExecutionsResponse, MemoryError> {
+        Ok(aggregate(&self.scan_executions(filter)?, group_by))
     }
 
     // === Trading Plan Management (for market-analyst agents) ===
 
     /// Save a trading plan to memory
-    pub fn save_plan(&self, req: SavePlanRequest) -> Result<SavePlanResponse, MemoryError> {
+    fn save_plan(&self, req: SavePlanRequest) -> Result<SavePlanResponse, MemoryError> {
         debug!(
             session_id = %req.session_id,
             symbol = %req.plan.as_ref().map(|p| p.symbol.as_str()).unwrap_or("unknown"),
@@ -85,29 +143,18 @@ impl InMemoryMemory {
         );
 
         let plan = req.plan.ok_or(MemoryError::PlanRequired)?;
-
-        self.plans
-            .entry(req.session_id.clone())
-            .or_default()
-            .push(plan.clone());
-
-        // Keep only last 100 plans per session to avoid memory bloat
-        if let Some(mut plans) = self.plans.get_mut(&req.session_id) {
-            if plans.len() > 100 {
-                let drain_count = plans.len() - 100;
-                plans.drain(0..drain_count);
-            }
-        }
+        let plan_hash = plan.plan_hash.clone();
+        self.insert_plan(&req.session_id, plan)?;
 
         Ok(SavePlanResponse {
             success: true,
-            plan_hash: plan.plan_hash.clone(),
+            plan_hash,
             error_message: String::new(),
         })
     }
 
     /// Get recent plans for a symbol
-    pub fn get_recent_plans(
+    fn get_recent_plans(
         &self,
         req: GetRecentPlansRequest,
     ) -> Result<GetRecentPlansResponse, MemoryError> {
@@ -118,31 +165,19 @@ impl InMemoryMemory {
             "Retrieving recent plans"
         );
 
-        let limit = req.limit.clamp(1, 100) as usize;
-
-        let plans = self
-            .plans
-            .get(&req.session_id)
-            .map(|entry| {
-                entry
-                    .iter()
-                    .filter(|p| p.symbol == req.symbol)
-                    .rev()
-                    .take(limit)
-                    .cloned()
-                    .collect::<Vec<_>>()
-            })
-            .unwrap_or_default();
+        let filter = RecordFilter::session(req.session_id)
+            .with_symbol(req.symbol)
+            .with_limit(req.limit.clamp(1, 100) as usize);
 
         Ok(GetRecentPlansResponse {
-            plans,
+            plans: self.query_plans(&filter)?,
             success: true,
             error_message: String::new(),
         })
     }
 
     /// Check if a plan is a duplicate
-    pub fn check_duplicate(
+    fn check_duplicate(
         &self,
         req: CheckDuplicateRequest,
     ) -> Result<CheckDuplicateResponse, MemoryError> {
@@ -160,19 +195,22 @@ impl InMemoryMemory {
             .map(|p| p.timestamp_ms)
             .unwrap_or_else(|| chrono::Utc::now().timestamp_millis());
 
-        // Find a duplicate plan if it exists
-        let duplicate = req.plan.as_ref().and_then(|plan| {
-            self.plans.get(&req.session_id).and_then(|entry| {
-                entry
-                    .iter()
-                    .find(|p| {
-                        p.symbol == plan.symbol
-                            && p.action == plan.action
-                            && (check_ts - p.timestamp_ms).abs() < time_window_ms
-                    })
-                    .cloned()
-            })
-        });
+        // Same symbol and action strictly within the window on either side
+        let duplicate = match req.plan {
+            Some(ref plan) => {
+                let filter = RecordFilter::session(req.session_id.clone())
+                    .with_symbol(plan.symbol.clone())
+                    .with_action(plan.action.clone())
+                    .with_time_range(
+                        Some(check_ts - time_window_ms + 1),
+                        Some(check_ts + time_window_ms),
+                    )
+                    .oldest_first()
+                    .with_limit(1);
+                self.query_plans(&filter)?.into_iter().next()
+            }
+            None => None,
+        };
 
         let time_since = duplicate
             .as_ref()
@@ -187,10 +225,7 @@ impl InMemoryMemory {
     }
 
     /// Mark a plan as executed
-    pub fn mark_executed(
-        &self,
-        req: MarkExecutedRequest,
-    ) -> Result<MarkExecutedResponse, MemoryError> {
+    fn mark_executed(&self, req: MarkExecutedRequest) -> Result<MarkExecutedResponse, MemoryError> {
         debug!(
             session_id = %req.session_id,
             plan_hash = %req.plan_hash,
@@ -198,7 +233,7 @@ impl InMemoryMemory {
         );
 
         // Use provided execution record or create a minimal one
-        let record = req.execution.unwrap_or_else(|| ExecutionRecord {
+        let mut record = req.execution.unwrap_or_else(|| ExecutionRecord {
             timestamp_ms: chrono::Utc::now().timestamp_millis(),
             plan_hash: req.plan_hash.clone(),
             symbol: String::new(),
@@ -209,21 +244,19 @@ impl InMemoryMemory {
             order_id: String::new(),
             order_size_usdt: 0.0,
             error_message: String::new(),
+            pnl_usdt: 0.0,
+            strategy: String::new(),
         });
 
-        self.executed_plans
-            .entry(req.session_id.clone())
-            .or_default()
-            .push(record);
-
-        // Keep only last 1000 execution records per session
-        if let Some(mut records) = self.executed_plans.get_mut(&req.session_id) {
-            if records.len() > 1000 {
-                let drain_count = records.len() - 1000;
-                records.drain(0..drain_count);
+        // Executions inherit the strategy of the plan they carry out
+        if record.strategy.is_empty() {
+            if let Some(plan) = self.find_plan(&req.session_id, &record.plan_hash)? {
+                record.strategy = plan_strategy(&plan).to_string();
             }
         }
 
+        self.insert_execution(&req.session_id, record)?;
+
         Ok(MarkExecutedResponse {
             success: true,
             error_message: String::new(),
@@ -231,7 +264,7 @@ impl InMemoryMemory {
     }
 
     /// Check if a plan was executed
-    pub fn check_executed(
+    fn check_executed(
         &self,
         req: CheckExecutedRequest,
     ) -> Result<CheckExecutedResponse, MemoryError> {
@@ -241,10 +274,7 @@ impl InMemoryMemory {
             "Checking if plan was executed"
         );
 
-        let execution = self
-            .executed_plans
-            .get(&req.session_id)
-            .and_then(|entry| entry.iter().find(|r| r.plan_hash == req.plan_hash).cloned());
+        let execution = self.find_execution(&req.session_id, &req.plan_hash)?;
 
         Ok(CheckExecutedResponse {
             is_executed: execution.is_some(),
@@ -253,7 +283,7 @@ impl InMemoryMemory {
     }
 
     /// Get execution statistics for a symbol
-    pub fn get_execution_stats(
+    fn get_execution_stats(
         &self,
         req: GetExecutionStatsRequest,
     ) -> Result<GetExecutionStatsResponse, MemoryError> {
@@ -263,17 +293,11 @@ impl InMemoryMemory {
             "Getting execution stats"
         );
 
-        let executions: Vec<ExecutionRecord> = self
-            .executed_plans
-            .get(&req.session_id)
-            .map(|entry| {
-                entry
-                    .iter()
-                    .filter(|r| req.symbol.is_empty() || r.symbol == req.symbol)
-                    .cloned()
-                    .collect()
-            })
-            .unwrap_or_default();
+        let mut filter = RecordFilter::session(req.session_id);
+        if !req.symbol.is_empty() {
+            filter = filter.with_symbol(req.symbol);
+        }
+        let executions = self.scan_executions(&filter)?;
 
         let total_executions = executions.len() as i32;
         let successful_executions =
@@ -303,10 +327,132 @@ impl InMemoryMemory {
     }
 }
 
+/// A simple in-memory memory store for demo/testing.
+/// Stores textual summaries of events keyed by session id.
+/// Also stores structured trading plans and execution records for market-analyst agents.
+#[derive(Default)]
+pub struct InMemoryMemory {
+    // session -> list of event summary lines (legacy)
+    store: DashMap<String, Vec<String>>,
+
+    // session_id -> list of trading plans
+    plans: DashMap<String, Vec<PlanRecord>>,
+
+    // session_id -> list of execution records
+    executed_plans: DashMap<String, Vec<ExecutionRecord>>,
+}
+
+impl InMemoryMemory {
+    pub fn new() -> Arc<Self> {
+        Arc::new(Self {
+            store: DashMap::new(),
+            plans: DashMap::new(),
+            executed_plans: DashMap::new(),
+        })
+    }
+
+    /// Records of every session `filter` allows that match `keep`, oldest first
+    fn scan<T: Clone>(
+        map: &DashMap<String, Vec<T>>,
+        filter: &RecordFilter,
+        keep: impl Fn(&T) -> bool,
+        timestamp: impl Fn(&T) -> i64,
+    ) -> Vec<T> {
+        let mut records: Vec<T> = map
+            .iter()
+            .filter(|entry| filter.matches_session(entry.key()))
+            .flat_map(|entry| {
+                entry
+                    .value()
+                    .iter()
+                    .filter(|r| keep(r))
+                    .cloned()
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+        // Stable: records with equal timestamps keep insertion order
+        records.sort_by_key(|r| timestamp(r));
+        records
+    }
+}
+
+impl TradingMemory for InMemoryMemory {
+    fn insert_plan(&self, session_id: &str, plan: PlanRecord) -> Result<(), MemoryError> {
+        let mut plans = self.plans.entry(session_id.to_string()).or_default();
+        plans.push(plan);
+
+        // Keep only last 100 plans per session to avoid memory bloat
+        if plans.len() > 100 {
+            let drain_count = plans.len() - 100;
+            plans.drain(0..drain_count);
+        }
+        Ok(())
+    }
+
+    fn insert_execution(
+        &self,
+        session_id: &str,
+        record: ExecutionRecord,
+    ) -> Result<(), MemoryError> {
+        let mut records = self
+            .executed_plans
+            .entry(session_id.to_string())
+            .or_default();
+        records.push(record);
+
+        // Keep only last 1000 execution records per session
+        if records.len() > 1000 {
+            let drain_count = records.len() - 1000;
+            records.drain(0..drain_count);
+        }
+        Ok(())
+    }
+
+    fn find_plan(
+        &self,
+        session_id: &str,
+        plan_hash: &str,
+    ) -> Result<Option<PlanRecord>, MemoryError> {
+        Ok(self
+            .plans
+            .get(session_id)
+            .and_then(|entry| entry.iter().find(|p| p.plan_hash == plan_hash).cloned()))
+    }
+
+    fn find_execution(
+        &self,
+        session_id: &str,
+        plan_hash: &str,
+    ) -> Result<Option<ExecutionRecord>, MemoryError> {
+        Ok(self
+            .executed_plans
+            .get(session_id)
+            .and_then(|entry| entry.iter().find(|r| r.plan_hash == plan_hash).cloned()))
+    }
+
+    fn scan_plans(&self, filter: &RecordFilter) -> Result<Vec<PlanRecord>, MemoryError> {
+        Ok(Self::scan(
+            &self.plans,
+            filter,
+            |p| filter.matches_plan(p),
+            |p| p.timestamp_ms,
+        ))
+    }
+
+    fn scan_executions(&self, filter: &RecordFilter) -> Result<Vec<ExecutionRecord>, MemoryError> {
+        Ok(Self::scan(
+            &self.executed_plans,
+            filter,
+            |r| filter.matches_execution(r),
+            |r| r.timestamp_ms,
+        ))
+    }
+}
+
 #[async_trait]
 impl MemoryWriter for InMemoryMemory {
     async fn append_event(&self, session: &str, event: Event) -> loom_core::Result<()> {
-        let summary = Self::summarize_event(&event);
+        let summary = summarize_event(&event);
         self.store
             .entry(session.to_string())
             .or_default()
@@ -483,6 +629,8 @@ mod tests {
             order_id: "order-123".to_string(),
             order_size_usdt: 100.0,
             error_message: String::new(),
+            pnl_usdt: 12.5,
+            strategy: String::new(),
         };
 
         let mark_req = MarkExecutedRequest {
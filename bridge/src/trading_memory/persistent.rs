@@ -0,0 +1,384 @@
+//! RocksDB backing for trading memory.
+//!
+//! Plans, executions and event summaries live in one column family each, keyed by
+//! `session \0 timestamp seq` so a session's records are contiguous and ordered by time
+//! (the timestamp is big-endian with its sign bit flipped, `seq` breaks ties in insertion
+//! order). Records are stored as their protobuf encoding. `plan_index` and
+//! `execution_index` map `session \0 plan_hash` to the first plan and execution saved
+//! for that hash.
+
+use super::{summarize_event, MemoryError, RecordFilter, TradingMemory};
+use async_trait::async_trait;
+use loom_core::context::{MemoryReader, MemoryWriter};
+use loom_proto::{Event, ExecutionRecord, PlanRecord};
+use prost::Message;
+use rocksdb::{ColumnFamilyDescriptor, Direction, IteratorMode, Options, WriteBatch, DB};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use tracing::info;
+
+const CF_PLANS: &str = "plans";
+const CF_EXECUTIONS: &str = "executions";
+const CF_PLAN_INDEX: &str = "plan_index";
+const CF_EXECUTION_INDEX: &str = "execution_index";
+const CF_EVENTS: &str = "events";
+
+/// Default column family key holding the next sequence number
+const SEQ_KEY: &[u8] = b"meta:seq";
+
+/// Events considered by `summarize_episode`, matching `InMemoryMemory`'s retention
+const SUMMARY_WINDOW: usize = 500;
+/// Event lines in one summary
+const SUMMARY_LINES: usize = 50;
+
+fn storage(e: rocksdb::Error) -> MemoryError {
+    MemoryError::Storage(e.to_string())
+}
+
+fn session_prefix(session_id: &str) -> Result<Vec<u8>, MemoryError> {
+    if session_id.contains('\0') {
+        return Err(MemoryError::InvalidQuery(
+            "session id must not contain NUL".to_string(),
+        ));
+    }
+    let mut prefix = Vec::with_capacity(session_id.len() + 1);
+    prefix.extend_from_slice(session_id.as_bytes());
+    prefix.push(0);
+    Ok(prefix)
+}
+
+/// Big-endian with the sign bit flipped, so byte order is numeric order
+fn encode_timestamp(timestamp_ms: i64) -> [u8; 8] {
+    ((timestamp_ms as u64) ^ (1 << 63)).to_be_bytes()
+}
+
+fn decode_timestamp(bytes: &[u8]) -> Option<i64> {
+    let raw: [u8; 8] = bytes.get(..8)?.try_into().ok()?;
+    Some((u64::from_be_bytes(raw) ^ (1 << 63)) as i64)
+}
+
+fn record_key(prefix: &[u8], timestamp_ms: i64, seq: u64) -> Vec<u8> {
+    let mut key = prefix.to_vec();
+    key.extend_from_slice(&encode_timestamp(timestamp_ms));
+    key.extend_from_slice(&seq.to_be_bytes());
+    key
+}
+
+fn index_key(session_id: &str, plan_hash: &str) -> Result<Vec<u8>, MemoryError> {
+    let mut key = session_prefix(session_id)?;
+    key.extend_from_slice(plan_hash.as_bytes());
+    Ok(key)
+}
+
+/// Persistent trading memory; survives restarts and keeps every record
+pub struct RocksDbMemory {
+    db: DB,
+    /// Next sequence number; held while a record is written so it is persisted in order
+    seq: Mutex<u64>,
+}
+
+impl std::fmt::Debug for RocksDbMemory {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RocksDbMemory")
+            .field("path", &self.db.path())
+            .finish()
+    }
+}
+
+impl RocksDbMemory {
+    /// Open (or create) the store at `path`
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Arc<Self>, MemoryError> {
+        let mut opts = Options::default();
+        opts.create_if_missing(true);
+        opts.create_missing_column_families(true);
+
+        let cf_descriptors = [
+            CF_PLANS,
+            CF_EXECUTIONS,
+            CF_PLAN_INDEX,
+            CF_EXECUTION_INDEX,
+            CF_EVENTS,
+        ]
+        .into_iter()
+        .map(|name| ColumnFamilyDescriptor::new(name, Options::default()))
+        .collect::<Vec<_>>();
+        let db = DB::open_cf_descriptors(&opts, path, cf_descriptors).map_err(storage)?;
+
+        let seq = match db.get(SEQ_KEY).map_err(storage)? {
+            Some(bytes) => {
+                let raw: [u8; 8] = bytes
+                    .as_slice()
+                    .try_into()
+                    .map_err(|_| MemoryError::Storage("corrupt sequence number".to_string()))?;
+                u64::from_be_bytes(raw)
+            }
+            None => 0,
+        };
+
+        info!(path = %db.path().display(), "Trading memory persistence initialized");
+        Ok(Arc::new(Self {
+            db,
+            seq: Mutex::new(seq),
+        }))
+    }
+
+    fn cf(&self, name: &str) -> Result<&rocksdb::ColumnFamily, MemoryError> {
+        self.db
+            .cf_handle(name)
+            .ok_or_else(|| MemoryError::Storage(format!("Missing CF: {}", name)))
+    }
+
+    /// Write `value` under a fresh key in `cf_name`, and under the `(column family, key)`
+    /// of `index` unless that key is already set
+    fn insert(
+        &self,
+        cf_name: &str,
+        session_id: &str,
+        timestamp_ms: i64,
+        value: Vec<u8>,
+        index: Option<(&str, Vec<u8>)>,
+    ) -> Result<(), MemoryError> {
+        let prefix = session_prefix(session_id)?;
+        let mut seq = self
+            .seq
+            .lock()
+            .map_err(|_| MemoryError::Internal("sequence lock poisoned".to_string()))?;
+
+        let mut batch = WriteBatch::default();
+        batch.put_cf(
+            self.cf(cf_name)?,
+            record_key(&prefix, timestamp_ms, *seq),
+            &value,
+        );
+        if let Some((index_cf, key)) = index {
+            let index_cf = self.cf(index_cf)?;
+            if self.db.get_cf(index_cf, &key).map_err(storage)?.is_none() {
+                batch.put_cf(index_cf, key, &value);
+            }
+        }
+        batch.put(SEQ_KEY, (*seq + 1).to_be_bytes());
+        self.db.write(batch).map_err(storage)?;
+        *seq += 1;
+        Ok(())
+    }
+
+    fn get_indexed<T: Message + Default>(
+        &self,
+        cf_name: &str,
+        session_id: &str,
+        plan_hash: &str,
+    ) -> Result<Option<T>, MemoryError> {
+        let key = index_key(session_id, plan_hash)?;
+        match self.db.get_cf(self.cf(cf_name)?, key).map_err(storage)? {
+            Some(bytes) => Ok(Some(decode(&bytes)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Records in `cf_name` from sessions `filter` allows, within its time range and
+    /// accepted by `keep`, oldest first
+    fn scan<T: Message + Default>(
+        &self,
+        cf_name: &str,
+        filter: &RecordFilter,
+        keep: impl Fn(&T) -> bool,
+        timestamp: impl Fn(&T) -> i64,
+    ) -> Result<Vec<T>, MemoryError> {
+        let cf = self.cf(cf_name)?;
+        let mut records = Vec::new();
+        match filter.session_id {
+            Some(ref session_id) => {
+                // Seek straight to the start of the range and stop at its end
+                let prefix = session_prefix(session_id)?;
+                let start = record_key(&prefix, filter.start_ms.unwrap_or(i64::MIN), 0);
+                let iter = self
+                    .db
+                    .iterator_cf(cf, IteratorMode::From(&start, Direction::Forward));
+                for entry in iter {
+                    let (key, value) = entry.map_err(storage)?;
+                    let Some(rest) = key.strip_prefix(prefix.as_slice()) else {
+                        break;
+                    };
+                    let Some(timestamp_ms) = decode_timestamp(rest) else {
+                        continue;
+                    };
+                    if matches!(filter.end_ms, Some(end) if timestamp_ms >= end) {
+                        break;
+                    }
+                    let record: T = decode(&value)?;
+                    if keep(&record) {
+                        records.push(record);
+                    }
+                }
+            }
+            None => {
+                for entry in self.db.iterator_cf(cf, IteratorMode::Start) {
+                    let (key, value) = entry.map_err(storage)?;
+                    let in_range = key
+                        .iter()
+                        .position(|b| *b == 0)
+                        .and_then(|nul| decode_timestamp(&key[nul + 1..]))
+                        .map(|ts| filter.in_range(ts))
+                        .unwrap_or(false);
+                    if !in_range {
+                        continue;
+                    }
+                    let record: T = decode(&value)?;
+                    if keep(&record) {
+                        records.push(record);
+                    }
+                }
+                // Sessions are iterated one after another; merge them by time
+                records.sort_by_key(|r| timestamp(r));
+            }
+        }
+        Ok(records)
+    }
+
+    /// The session's last `SUMMARY_WINDOW` event summaries, oldest first
+    fn recent_events(&self, session: &str) -> Result<Vec<String>, MemoryError> {
+        let cf = self.cf(CF_EVENTS)?;
+        let prefix = session_prefix(session)?;
+        // The byte after the NUL separator sorts past every key of the session
+        let mut end = prefix.clone();
+        if let Some(last) = end.last_mut() {
+            *last = 1;
+        }
+        let mut lines = Vec::new();
+        let iter = self
+            .db
+            .iterator_cf(cf, IteratorMode::From(&end, Direction::Reverse));
+        for entry in iter {
+            let (key, value) = entry.map_err(storage)?;
+            if !key.starts_with(&prefix) || lines.len() >= SUMMARY_WINDOW {
+                break;
+            }
+            lines.push(String::from_utf8_lossy(&value).into_owned());
+        }
+        lines.reverse();
+        Ok(lines)
+    }
+}
+
+fn decode<T: Message + Default>(bytes: &[u8]) -> Result<T, MemoryError> {
+    T::decode(bytes).map_err(|e| MemoryError::Storage(format!("undecodable record: {}", e)))
+}
+
+impl TradingMemory for RocksDbMemory {
+    fn insert_plan(&self, session_id: &str, plan: PlanRecord) -> Result<(), MemoryError> {
+        let index = index_key(session_id, &plan.plan_hash)?;
+        self.insert(
+            CF_PLANS,
+            session_id,
+            plan.timestamp_ms,
+            plan.encode_to_vec(),
+            Some((CF_PLAN_INDEX, index)),
+        )
+    }
+
+    fn insert_execution(
+        &self,
+        session_id: &str,
+        record: ExecutionRecord,
+    ) -> Result<(), MemoryError> {
+        let index = index_key(session_id, &record.plan_hash)?;
+        self.insert(
+            CF_EXECUTIONS,
+            session_id,
+            record.timestamp_ms,
+            record.encode_to_vec(),
+            Some((CF_EXECUTION_INDEX, index)),
+        )
+    }
+
+    fn find_plan(
+        &self,
+        session_id: &str,
+        plan_hash: &str,
+    ) -> Result<Option<PlanRecord>, MemoryError> {
+        self.get_indexed(CF_PLAN_INDEX, session_id, plan_hash)
+    }
+
+    fn find_execution(
+        &self,
+        session_id: &str,
+        plan_hash: &str,
+    ) -> Result<Option<ExecutionRecord>, MemoryError> {
+        self.get_indexed(CF_EXECUTION_INDEX, session_id, plan_hash)
+    }
+
+    fn scan_plans(&self, filter: &RecordFilter) -> Result<Vec<PlanRecord>, MemoryError> {
+        self.scan(
+            CF_PLANS,
+            filter,
+            |p| filter.matches_plan(p),
+            |p: &PlanRecord| p.timestamp_ms,
+        )
+    }
+
+    fn scan_executions(&self, filter: &RecordFilter) -> Result<Vec<ExecutionRecord>, MemoryError> {
+        self.scan(
+            CF_EXECUTIONS,
+            filter,
+            |r| filter.matches_execution(r),
+            |r: &ExecutionRecord| r.timestamp_ms,
+        )
+    }
+}
+
+#[async_trait]
+impl MemoryWriter for RocksDbMemory {
+    async fn append_event(&self, session: &str, event: Event) -> loom_core::Result<()> {
+        let summary = summarize_event(&event);
+        self.insert(
+            CF_EVENTS,
+            session,
+            event.timestamp_ms,
+            summary.into_bytes(),
+            None,
+        )?;
+        Ok(())
+    }
+
+    async fn summarize_episode(&self, session: &str) -> loom_core::Result<Option<String>> {
+        let lines = self.recent_events(session)?;
+        if lines.is_empty() {
+            return Ok(None);
+        }
+        Ok(Some(
+            lines
+                .into_iter()
+                .take(SUMMARY_LINES)
+                .collect::<Vec<_>>()
+                .join("\n"),
+        ))
+    }
+}
+
+#[async_trait]
+impl MemoryReader for RocksDbMemory {
+    async fn retrieve(
+        &self,
+        query: &str,
+        k: usize,
+        _filters: Option<serde_json::Value>,
+    ) -> loom_core::Result<Vec<String>> {
+        // Simple substring search across all sessions
+        let query_lower = query.to_lowercase();
+        let mut results = Vec::new();
+
+        let cf = self.cf(CF_EVENTS)?;
+        for entry in self.db.iterator_cf(cf, IteratorMode::Start) {
+            let (_, value) = entry.map_err(storage)?;
+            let event_summary = String::from_utf8_lossy(&value);
+            if event_summary.to_lowercase().contains(&query_lower) {
+                results.push(event_summary.into_owned());
+                if results.len() >= k {
+                    break;
+                }
+            }
+        }
+
+        Ok(results)
+    }
+}
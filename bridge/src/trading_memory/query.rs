@@ -0,0 +1,349 @@
+//! Filters and aggregations over stored plans and executions.
+//!
+//! A `RecordFilter` comes from a `TradingQuery` message, from the query language, or
+//! both (the conditions are ANDed). The language is whitespace-separated `field:value`
+//! terms; commas list alternatives:
+//!
+//! ```text
+//! symbol:BTCUSDT,ETHUSDT action:buy status:success strategy:momentum
+//! since:-24h until:2024-03-01T00:00:00Z session:alpha limit:20 order:asc
+//! ```
+//!
+//! `since`/`until` take milliseconds since the epoch, RFC 3339 times, or offsets from now
+//! (`-30m`, `-24h`, `-7d`). Values compare case-insensitively.
+
+use std::collections::BTreeMap;
+
+use loom_proto::{
+    AggregateExecutionsResponse, ExecutionGroup, ExecutionRecord, PlanRecord, TradingQuery,
+};
+
+use super::MemoryError;
+
+/// Records returned when a query sets no limit
+pub const DEFAULT_QUERY_LIMIT: usize = 100;
+
+/// Most records one query returns
+pub const MAX_QUERY_LIMIT: usize = 1000;
+
+/// Conditions a plan or execution must meet
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RecordFilter {
+    /// `None`: every session
+    pub session_id: Option<String>,
+    pub symbols: Vec<String>,
+    pub actions: Vec<String>,
+    /// Executions only
+    pub statuses: Vec<String>,
+    pub strategies: Vec<String>,
+    /// Inclusive
+    pub start_ms: Option<i64>,
+    /// Exclusive
+    pub end_ms: Option<i64>,
+    /// `None`: every match
+    pub limit: Option<usize>,
+    pub oldest_first: bool,
+}
+
+impl RecordFilter {
+    /// Filter for one session
+    pub fn session(session_id: impl Into<String>) -> Self {
+        Self {
+            session_id: Some(session_id.into()),
+            ..Self::default()
+        }
+    }
+
+    /// Parse the query language
+    pub fn parse(query: &str) -> Result<Self, MemoryError> {
+        let mut filter = Self::default();
+        filter.apply_query(query)?;
+        Ok(filter)
+    }
+
+    /// Filter described by `query`: its fields, then its `query` string
+    pub fn from_proto(query: &TradingQuery) -> Result<Self, MemoryError> {
+        let mut filter = Self {
+            session_id: (!query.session_id.is_empty()).then(|| query.session_id.clone()),
+            symbols: query.symbols.clone(),
+            actions: query.actions.clone(),
+            statuses: query.statuses.clone(),
+            strategies: query.strategies.clone(),
+            start_ms: (query.start_ms != 0).then_some(query.start_ms),
+            end_ms: (query.end_ms != 0).then_some(query.end_ms),
+            limit: None,
+            oldest_first: query.oldest_first,
+        };
+        if query.limit < 0 {
+            return Err(MemoryError::InvalidQuery(format!(
+                "limit must not be negative, got {}",
+                query.limit
+            )));
+        }
+        if query.limit > 0 {
+            filter.limit = Some(query.limit as usize);
+        }
+        filter.apply_query(&query.query)?;
+        if filter.limit.is_none() {
+            filter.limit = Some(DEFAULT_QUERY_LIMIT);
+        }
+        filter.limit = filter.limit.map(|limit| limit.min(MAX_QUERY_LIMIT));
+        Ok(filter)
+    }
+
+    fn apply_query(&mut self, query: &str) -> Result<(), MemoryError> {
+        for term in query.split_whitespace() {
+            let (field, value) = term.split_once(':').ok_or_else(|| {
+                MemoryError::InvalidQuery(format!("expected field:value, got '{}'", term))
+            })?;
+            let values = || {
+                value
+                    .split(',')
+                    .filter(|v| !v.is_empty())
+                    .map(str::to_string)
+            };
+            match field.to_ascii_lowercase().as_str() {
+                "session" => self.session_id = Some(value.to_string()),
+                "symbol" => self.symbols.extend(values()),
+                "action" => self.actions.extend(values()),
+                "status" => self.statuses.extend(values()),
+                "strategy" => self.strategies.extend(values()),
+                "since" | "from" => self.start_ms = Some(parse_time(value)?),
+                "until" | "to" => self.end_ms = Some(parse_time(value)?),
+                "limit" => {
+                    let limit = value.parse().map_err(|_| {
+                        MemoryError::InvalidQuery(format!("invalid limit '{}'", value))
+                    })?;
+                    self.limit = Some(limit);
+                }
+                "order" => {
+                    self.oldest_first = match value.to_ascii_lowercase().as_str() {
+                        "asc" | "oldest" => true,
+                        "desc" | "newest" => false,
+                        _ => {
+                            return Err(MemoryError::InvalidQuery(format!(
+                                "order must be asc or desc, got '{}'",
+                                value
+                            )))
+                        }
+                    }
+                }
+                _ => {
+                    return Err(MemoryError::InvalidQuery(format!(
+                        "unknown field '{}'",
+                        field
+                    )))
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub fn with_symbol(mut self, symbol: impl Into<String>) -> Self {
+        self.symbols.push(symbol.into());
+        self
+    }
+
+    pub fn with_action(mut self, action: impl Into<String>) -> Self {
+        self.actions.push(action.into());
+        self
+    }
+
+    pub fn with_time_range(mut self, start_ms: Option<i64>, end_ms: Option<i64>) -> Self {
+        self.start_ms = start_ms;
+        self.end_ms = end_ms;
+        self
+    }
+
+    pub fn with_limit(mut self, limit: usize) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    pub fn oldest_first(mut self) -> Self {
+        self.oldest_first = true;
+        self
+    }
+
+    /// Whether `session_id` may hold matches
+    pub fn matches_session(&self, session_id: &str) -> bool {
+        match self.session_id {
+            Some(ref s) => s == session_id,
+            None => true,
+        }
+    }
+
+    /// Whether `timestamp_ms` is inside the time range
+    pub fn in_range(&self, timestamp_ms: i64) -> bool {
+        !matches!(self.start_ms, Some(start) if timestamp_ms < start)
+            && !matches!(self.end_ms, Some(end) if timestamp_ms >= end)
+    }
+
+    pub fn matches_plan(&self, plan: &PlanRecord) -> bool {
+        self.in_range(plan.timestamp_ms)
+            && any_of(&self.symbols, &plan.symbol)
+            && any_of(&self.actions, &plan.action)
+            && any_of(&self.strategies, plan_strategy(plan))
+    }
+
+    pub fn matches_execution(&self, execution: &ExecutionRecord) -> bool {
+        self.in_range(execution.timestamp_ms)
+            && any_of(&self.symbols, &execution.symbol)
+            && any_of(&self.actions, &execution.action)
+            && any_of(&self.statuses, &execution.status)
+            && any_of(&self.strategies, &execution.strategy)
+    }
+
+    /// Order `records` (oldest first on input) and apply the limit
+    pub fn finish<T>(&self, mut records: Vec<T>) -> Vec<T> {
+        if !self.oldest_first {
+            records.reverse();
+        }
+        if let Some(limit) = self.limit {
+            records.truncate(limit);
+        }
+        records
+    }
+}
+
+fn any_of(allowed: &[String], value: &str) -> bool {
+    allowed.is_empty() || allowed.iter().any(|a| a.eq_ignore_ascii_case(value))
+}
+
+/// Strategy of a plan: its `strategy` metadata, else its method
+pub fn plan_strategy(plan: &PlanRecord) -> &str {
+    plan.metadata
+        .get("strategy")
+        .map(String::as_str)
+        .filter(|s| !s.is_empty())
+        .unwrap_or(&plan.method)
+}
+
+fn parse_time(value: &str) -> Result<i64, MemoryError> {
+    let invalid = || MemoryError::InvalidQuery(format!("invalid time '{}'", value));
+    if let Some(offset) = value.strip_prefix('-') {
+        let unit = offset.chars().last().ok_or_else(invalid)?;
+        let amount: i64 = offset[..offset.len() - unit.len_utf8()]
+            .parse()
+            .map_err(|_| invalid())?;
+        let unit_ms = match unit {
+            's' => 1_000,
+            'm' => 60_000,
+            'h' => 3_600_000,
+            'd' => 86_400_000,
+            _ => return Err(invalid()),
+        };
+        return Ok(chrono::Utc::now().timestamp_millis() - amount * unit_ms);
+    }
+    if let Ok(ms) = value.parse::<i64>() {
+        return Ok(ms);
+    }
+    chrono::DateTime::parse_from_rfc3339(value)
+        .map(|dt| dt.timestamp_millis())
+        .map_err(|_| invalid())
+}
+
+/// Field executions are grouped by
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum GroupBy {
+    #[default]
+    Strategy,
+    Symbol,
+    Action,
+    Status,
+    /// UTC date of the execution
+    Day,
+}
+
+impl GroupBy {
+    /// `strategy` (also the default for an empty name), `symbol`, `action`, `status`, `day`
+    pub fn parse(name: &str) -> Result<Self, MemoryError> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "" | "strategy" => Ok(Self::Strategy),
+            "symbol" => Ok(Self::Symbol),
+            "action" => Ok(Self::Action),
+            "status" => Ok(Self::Status),
+            "day" | "date" => Ok(Self::Day),
+            other => Err(MemoryError::InvalidQuery(format!(
+                "cannot group by '{}'",
+                other
+            ))),
+        }
+    }
+
+    fn key(self, execution: &ExecutionRecord) -> String {
+        let key = match self {
+            Self::Strategy => execution.strategy.clone(),
+            Self::Symbol => execution.symbol.clone(),
+            Self::Action => execution.action.clone(),
+            Self::Status => execution.status.clone(),
+            Self::Day => chrono::DateTime::from_timestamp_millis(execution.timestamp_ms)
+                .map(|dt| dt.format("%Y-%m-%d").to_string())
+                .unwrap_or_default(),
+        };
+        if key.is_empty() {
+            "unknown".to_string()
+        } else {
+            key
+        }
+    }
+}
+
+/// Totals per group of `executions`, plus the overall total
+pub fn aggregate(executions: &[ExecutionRecord], group_by: GroupBy) -> AggregateExecutionsResponse {
+    let mut groups: BTreeMap<String, ExecutionGroup> = BTreeMap::new();
+    let mut total = empty_group("total");
+    for execution in executions {
+        let key = group_by.key(execution);
+        let group = groups
+            .entry(key.clone())
+            .or_insert_with(|| empty_group(&key));
+        add_to_group(group, execution);
+        add_to_group(&mut total, execution);
+    }
+    let mut groups: Vec<ExecutionGroup> = groups.into_values().collect();
+    for group in groups.iter_mut().chain(std::iter::once(&mut total)) {
+        if group.executions > 0 {
+            group.avg_confidence /= group.executions as f32;
+        }
+    }
+    AggregateExecutionsResponse {
+        groups,
+        total: Some(total),
+        success: true,
+        error_message: String::new(),
+    }
+}
+
+fn empty_group(key: &str) -> ExecutionGroup {
+    ExecutionGroup {
+        key: key.to_string(),
+        executions: 0,
+        successful: 0,
+        failed: 0,
+        pnl_usdt: 0.0,
+        volume_usdt: 0.0,
+        avg_confidence: 0.0,
+        first_ms: 0,
+        last_ms: 0,
+    }
+}
+
+fn add_to_group(group: &mut ExecutionGroup, execution: &ExecutionRecord) {
+    if group.executions == 0 || execution.timestamp_ms < group.first_ms {
+        group.first_ms = execution.timestamp_ms;
+    }
+    if group.executions == 0 || execution.timestamp_ms > group.last_ms {
+        group.last_ms = execution.timestamp_ms;
+    }
+    group.executions += 1;
+    match execution.status.as_str() {
+        "success" => group.successful += 1,
+        "error" => group.failed += 1,
+        _ => {}
+    }
+    group.pnl_usdt += execution.pnl_usdt;
+    group.volume_usdt += execution.order_size_usdt as f64;
+    // Summed here, divided by the count once every execution is added
+    group.avg_confidence += execution.confidence;
+}
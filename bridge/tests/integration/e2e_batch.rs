@@ -0,0 +1,137 @@
+use super::*;
+use loom_bridge::fanout::ACCEPT_BATCH_KEY;
+use loom_proto::{PublishBatch, QoSLevel};
+use std::collections::HashMap;
+use tokio::time::{timeout, Duration};
+
+fn event(id: &str) -> Event {
+    Event {
+        id: id.into(),
+        r#type: "tick".into(),
+        timestamp_ms: 0,
+        source: "tester".into(),
+        metadata: HashMap::new(),
+        payload: vec![],
+        confidence: 1.0,
+        tags: vec![],
+        priority: 50,
+    }
+}
+
+/// Event ids of the next frame, and whether it was a `DeliveryBatch`
+async fn next_frame(rx: &mut tonic::Streaming<loom_proto::ServerEvent>) -> (Vec<String>, bool) {
+    let msg = timeout(Duration::from_secs(2), rx.message())
+        .await
+        .expect("recv timed out")
+        .unwrap()
+        .unwrap()
+        .msg
+        .unwrap();
+    match msg {
+        server_event::Msg::Delivery(del) => (vec![del.event.unwrap().id], false),
+        server_event::Msg::DeliveryBatch(batch) => {
+            assert_eq!(batch.topic, "ticks");
+            (batch.events.into_iter().map(|e| e.id).collect(), true)
+        }
+        other => panic!("Expected a delivery, got {:?}", other),
+    }
+}
+
+async fn connect_batching_agent(
+    addr: SocketAddr,
+    agent_id: &str,
+    max_events: usize,
+) -> (
+    tokio::sync::mpsc::Sender<ClientEvent>,
+    tonic::Streaming<loom_proto::ServerEvent>,
+) {
+    let mut client = new_client(addr).await;
+    let resp = client
+        .register_agent(AgentRegisterRequest {
+            agent_id: agent_id.into(),
+            subscribed_topics: vec!["ticks".into()],
+            tools: vec![],
+            metadata: HashMap::from([(ACCEPT_BATCH_KEY.to_string(), max_events.to_string())]),
+        })
+        .await
+        .unwrap()
+        .into_inner();
+    assert!(resp.success);
+    let (tx, rx_stream) = tokio::sync::mpsc::channel(16);
+    tx.send(ClientEvent {
+        msg: Some(client_event::Msg::Ack(Ack {
+            message_id: agent_id.into(),
+        })),
+    })
+    .await
+    .unwrap();
+    let rx = client
+        .event_stream(tokio_stream::wrappers::ReceiverStream::new(rx_stream))
+        .await
+        .unwrap()
+        .into_inner();
+    (tx, rx)
+}
+
+#[tokio::test]
+async fn test_publish_batch_reaches_the_event_bus_in_order() {
+    let event_bus = Arc::new(EventBus::new().await.unwrap());
+    event_bus.start().await.unwrap();
+    let (addr, _handle, _svc) =
+        start_test_server(Arc::clone(&event_bus), Arc::new(ToolRegistry::new())).await;
+    let (_sub, mut bus_rx) = event_bus
+        .subscribe("ticks".into(), vec![], QoSLevel::QosBatched)
+        .await
+        .unwrap();
+
+    let (tx, _rx) = connect_agent(addr, "producer", vec![]).await;
+    tx.send(ClientEvent {
+        msg: Some(client_event::Msg::PublishBatch(PublishBatch {
+            topic: "ticks".into(),
+            events: (0..5).map(|i| event(&format!("t{i}"))).collect(),
+        })),
+    })
+    .await
+    .unwrap();
+
+    for i in 0..5 {
+        let ev = timeout(Duration::from_secs(2), bus_rx.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(ev.id, format!("t{i}"));
+    }
+    assert_eq!(event_bus.get_stats("ticks").unwrap().total_published, 5);
+}
+
+#[tokio::test]
+async fn test_waiting_events_are_delivered_in_batches() {
+    let event_bus = Arc::new(EventBus::new().await.unwrap());
+    event_bus.start().await.unwrap();
+    let (addr, _handle, _svc) =
+        start_test_server(Arc::clone(&event_bus), Arc::new(ToolRegistry::new())).await;
+
+    let (_tx_batched, mut rx_batched) = connect_batching_agent(addr, "batched", 8).await;
+    let (_tx_plain, mut rx_plain) = connect_agent(addr, "plain", vec!["ticks".into()]).await;
+
+    // All 20 events are queued on the topic before the forwarding task runs
+    let events = (0..20).map(|i| event(&format!("t{i}"))).collect();
+    event_bus.publish_batch("ticks", events).await.unwrap();
+
+    let mut ids = Vec::new();
+    let mut batches = 0;
+    while ids.len() < 20 {
+        let (frame, batched) = next_frame(&mut rx_batched).await;
+        assert!(frame.len() <= 8);
+        batches += batched as usize;
+        ids.extend(frame);
+    }
+    let expected: Vec<String> = (0..20).map(|i| format!("t{i}")).collect();
+    assert_eq!(ids, expected);
+    assert!(batches > 0);
+
+    // Agents that did not ask for batching get one frame per event
+    for id in &expected {
+        assert_eq!(next_frame(&mut rx_plain).await, (vec![id.clone()], false));
+    }
+}
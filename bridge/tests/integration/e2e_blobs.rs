@@ -0,0 +1,161 @@
+use super::*;
+use loom_bridge::blobs::BLOBS_KEY;
+use loom_core::blob::{keys, BlobRef, BlobStore, DiskBlobBackend};
+use loom_core::ToolRegistry;
+use loom_proto::QoSLevel;
+use std::collections::HashMap;
+use tokio::time::{timeout, Duration};
+
+fn event(id: &str, payload: Vec<u8>, readers: &str) -> Event {
+    Event {
+        id: id.into(),
+        r#type: "image.captured".into(),
+        timestamp_ms: 0,
+        source: "camera".into(),
+        metadata: HashMap::from([
+            (keys::READERS.to_string(), readers.to_string()),
+            (keys::CONTENT_TYPE.to_string(), "image/png".to_string()),
+        ]),
+        payload,
+        confidence: 1.0,
+        tags: vec![],
+        priority: 50,
+    }
+}
+
+/// Register `agent_id` with `metadata` and open its event stream
+async fn connect_with(
+    addr: SocketAddr,
+    agent_id: &str,
+    topics: Vec<String>,
+    metadata: HashMap<String, String>,
+) -> (
+    tokio::sync::mpsc::Sender<ClientEvent>,
+    tonic::Streaming<loom_proto::ServerEvent>,
+) {
+    let mut client = new_client(addr).await;
+    let resp = client
+        .register_agent(AgentRegisterRequest {
+            agent_id: agent_id.into(),
+            subscribed_topics: topics,
+            tools: vec![],
+            metadata,
+        })
+        .await
+        .unwrap()
+        .into_inner();
+    assert!(resp.success);
+    let (tx, rx_stream) = tokio::sync::mpsc::channel(16);
+    tx.send(ClientEvent {
+        msg: Some(client_event::Msg::Ack(Ack {
+            message_id: agent_id.into(),
+        })),
+    })
+    .await
+    .unwrap();
+    let rx = client
+        .event_stream(tokio_stream::wrappers::ReceiverStream::new(rx_stream))
+        .await
+        .unwrap()
+        .into_inner();
+    (tx, rx)
+}
+
+async fn next_delivery(rx: &mut tonic::Streaming<loom_proto::ServerEvent>) -> Event {
+    let msg = timeout(Duration::from_secs(2), rx.message())
+        .await
+        .expect("recv timed out")
+        .unwrap()
+        .unwrap()
+        .msg
+        .unwrap();
+    match msg {
+        server_event::Msg::Delivery(del) => del.event.unwrap(),
+        other => panic!("Expected Delivery, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn test_large_publishes_travel_by_reference() {
+    let dir = tempfile::tempdir().unwrap();
+    let store = Arc::new(
+        BlobStore::new(Arc::new(DiskBlobBackend::new(dir.path()))).with_inline_max_bytes(1024),
+    );
+    let event_bus = Arc::new(EventBus::new().await.unwrap());
+    event_bus.start().await.unwrap();
+    let mut state = loom_bridge::BridgeState::new(
+        Arc::clone(&event_bus),
+        Arc::new(ToolRegistry::new()),
+        Arc::new(AgentDirectory::new()),
+    );
+    state.set_blob_store(Arc::clone(&store));
+    let blobs = state.blobs.clone().unwrap();
+    let (addr, _handle, _svc) = start_test_server_with_state(state).await;
+
+    let topics = vec!["frames".to_string()];
+    let (tx_camera, _rx_camera) = connect_agent(addr, "camera", vec![]).await;
+    let (_tx_vision, mut rx_vision) = connect_agent(addr, "vision", topics.clone()).await;
+    let (_tx_stranger, mut rx_stranger) = connect_agent(addr, "stranger", topics.clone()).await;
+    let by_ref = HashMap::from([(BLOBS_KEY.to_string(), "ref".to_string())]);
+    let (_tx_archiver, mut rx_archiver) = connect_with(addr, "archiver", topics, by_ref).await;
+    let (_sub, mut bus_rx) = event_bus
+        .subscribe("frames".into(), vec![], QoSLevel::QosBatched)
+        .await
+        .unwrap();
+
+    let image = vec![7u8; 4096];
+    tx_camera
+        .send(ClientEvent {
+            msg: Some(client_event::Msg::Publish(Publish {
+                topic: "frames".into(),
+                event: Some(event("ev-big", image.clone(), "vision, archiver")),
+            })),
+        })
+        .await
+        .unwrap();
+
+    // The EventBus carries the reference only
+    let on_bus = timeout(Duration::from_secs(2), bus_rx.recv())
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(on_bus.payload.is_empty());
+    let blob = BlobRef::from_event(&on_bus).unwrap();
+    assert_eq!(blob.size, 4096);
+    assert_eq!(blob.content_type, "image/png");
+
+    // A reader gets the content back; an agent asking for references and an agent that
+    // may not read the blob get the reference
+    let delivered = next_delivery(&mut rx_vision).await;
+    assert_eq!(delivered.payload, image);
+    assert!(!delivered.metadata.contains_key(keys::REF));
+    let delivered = next_delivery(&mut rx_archiver).await;
+    assert_eq!(BlobRef::from_event(&delivered), Some(blob.clone()));
+    assert!(delivered.payload.is_empty());
+    let delivered = next_delivery(&mut rx_stranger).await;
+    assert_eq!(BlobRef::from_event(&delivered), Some(blob.clone()));
+    assert!(delivered.payload.is_empty());
+    assert_eq!(
+        store.get(&blob, Some("archiver"), None).await.unwrap(),
+        image
+    );
+
+    // Small payloads pass through unchanged
+    tx_camera
+        .send(ClientEvent {
+            msg: Some(client_event::Msg::Publish(Publish {
+                topic: "frames".into(),
+                event: Some(event("ev-small", b"thumb".to_vec(), "")),
+            })),
+        })
+        .await
+        .unwrap();
+    let delivered = next_delivery(&mut rx_stranger).await;
+    assert_eq!(delivered.id, "ev-small");
+    assert_eq!(delivered.payload, b"thumb");
+
+    let stats = blobs.stats();
+    assert_eq!(stats.stashed, 1);
+    assert_eq!(stats.resolved, 1);
+    assert_eq!(stats.by_ref_agents, 1);
+}
@@ -0,0 +1,226 @@
+use super::*;
+use loom_core::{TenantRegistry, ToolRegistry};
+use loom_proto::{ListAgentsRequest, ListToolsRequest, ToolDescriptor};
+use serde_json::{json, Value};
+use tonic::{Code, Request};
+
+/// A registry tool that only has a name
+struct NamedTool(&'static str);
+
+#[async_trait::async_trait]
+impl loom_core::Tool for NamedTool {
+    fn name(&self) -> String {
+        self.0.to_string()
+    }
+
+    fn description(&self) -> String {
+        format!("The {} tool", self.0)
+    }
+
+    fn parameters(&self) -> Value {
+        json!({"type": "object", "properties": {"text": {"type": "string"}}})
+    }
+
+    async fn call(&self, arguments: Value) -> loom_core::tools::ToolResult<Value> {
+        Ok(arguments)
+    }
+}
+
+fn registration(agent_id: &str, topics: &[&str], tools: &[&str]) -> AgentRegisterRequest {
+    AgentRegisterRequest {
+        agent_id: agent_id.into(),
+        subscribed_topics: topics.iter().map(|t| t.to_string()).collect(),
+        tools: tools
+            .iter()
+            .map(|name| ToolDescriptor {
+                name: name.to_string(),
+                description: String::new(),
+                parameters_schema: "{}".into(),
+                provider: loom_proto::ProviderKind::ProviderGrpc as i32,
+                metadata: Default::default(),
+            })
+            .collect(),
+        metadata: Default::default(),
+    }
+}
+
+fn authed<T>(message: T, token: &str) -> Request<T> {
+    let mut request = Request::new(message);
+    request
+        .metadata_mut()
+        .insert("authorization", format!("Bearer {token}").parse().unwrap());
+    request
+}
+
+#[tokio::test]
+async fn test_list_tools_and_agents() {
+    let event_bus = Arc::new(EventBus::new().await.unwrap());
+    let tools = Arc::new(ToolRegistry::new());
+    tools.register(Arc::new(NamedTool("web:search"))).await;
+    tools.register(Arc::new(NamedTool("math:eval"))).await;
+    tools.register(Arc::new(NamedTool("web:fetch"))).await;
+    let state = loom_bridge::BridgeState::new(event_bus, tools, Arc::new(AgentDirectory::new()));
+    let directory = Arc::clone(&state.agent_directory);
+    let (addr, _handle, _svc) = start_test_server_with_state(state).await;
+    let mut client = new_client(addr).await;
+
+    let listed = client
+        .list_tools(ListToolsRequest::default())
+        .await
+        .unwrap()
+        .into_inner()
+        .tools;
+    let names: Vec<&str> = listed.iter().map(|t| t.name.as_str()).collect();
+    assert_eq!(names, ["math:eval", "web:fetch", "web:search"]);
+    let schema: Value = serde_json::from_str(&listed[0].parameters_schema).unwrap();
+    assert_eq!(schema["properties"]["text"]["type"], "string");
+    assert_eq!(listed[0].description, "The math:eval tool");
+
+    let web = client
+        .list_tools(ListToolsRequest {
+            name_prefix: "web:".into(),
+        })
+        .await
+        .unwrap()
+        .into_inner()
+        .tools;
+    assert_eq!(web.len(), 2);
+
+    for (agent_id, topics, tools) in [
+        (
+            "translator",
+            &["jobs.translate"][..],
+            &["translate:text"][..],
+        ),
+        ("summarizer", &["jobs.*"][..], &["summarize:text"][..]),
+        (
+            "polyglot",
+            &["chat"][..],
+            &["translate:text", "detect:lang"][..],
+        ),
+    ] {
+        let resp = client
+            .register_agent(registration(agent_id, topics, tools))
+            .await
+            .unwrap()
+            .into_inner();
+        assert!(resp.success);
+    }
+
+    let list = |request: ListAgentsRequest| {
+        let mut client = client.clone();
+        async move {
+            client
+                .list_agents(request)
+                .await
+                .unwrap()
+                .into_inner()
+                .agents
+        }
+    };
+    let ids = |agents: &[loom_proto::AgentDescriptor]| -> Vec<String> {
+        agents.iter().map(|a| a.agent_id.clone()).collect()
+    };
+
+    let all = list(ListAgentsRequest::default()).await;
+    assert_eq!(ids(&all), ["polyglot", "summarizer", "translator"]);
+    assert_eq!(all[0].capabilities, ["translate:text", "detect:lang"]);
+    assert_eq!(all[0].status, "active");
+    assert!(all[0].last_heartbeat_ms > 0);
+
+    let translators = list(ListAgentsRequest {
+        capability: "translate:text".into(),
+        ..Default::default()
+    })
+    .await;
+    assert_eq!(ids(&translators), ["polyglot", "translator"]);
+
+    // Topic filters honour wildcard subscriptions
+    let jobs = list(ListAgentsRequest {
+        topic: "jobs.summarize".into(),
+        ..Default::default()
+    })
+    .await;
+    assert_eq!(ids(&jobs), ["summarizer"]);
+
+    // Disconnected agents are only listed on request
+    directory.mark_disconnected("translator");
+    let live = list(ListAgentsRequest {
+        capability: "translate:text".into(),
+        ..Default::default()
+    })
+    .await;
+    assert_eq!(ids(&live), ["polyglot"]);
+    let everyone = list(ListAgentsRequest {
+        capability: "translate:text".into(),
+        include_disconnected: true,
+        ..Default::default()
+    })
+    .await;
+    assert_eq!(ids(&everyone), ["polyglot", "translator"]);
+    assert_eq!(everyone[1].status, "disconnected");
+}
+
+#[tokio::test]
+async fn test_tenants_only_discover_their_own_agents() {
+    let event_bus = Arc::new(EventBus::new().await.unwrap());
+    let tenants = TenantRegistry::from_toml(
+        r#"
+        [[tenants]]
+        id = "acme"
+        tokens = ["acme-secret"]
+
+        [[tenants]]
+        id = "globex"
+        tokens = ["globex-secret"]
+        "#,
+    )
+    .unwrap();
+    let mut state = loom_bridge::BridgeState::new(
+        event_bus,
+        Arc::new(ToolRegistry::new()),
+        Arc::new(AgentDirectory::new()),
+    );
+    state.set_tenants(Arc::new(tenants));
+    let (addr, _handle, _svc) = start_test_server_with_state(state).await;
+    let mut client = new_client(addr).await;
+
+    for (agent_id, token) in [("acme-1", "acme-secret"), ("globex-1", "globex-secret")] {
+        let resp = client
+            .register_agent(authed(
+                registration(agent_id, &["orders"], &["orders:lookup"]),
+                token,
+            ))
+            .await
+            .unwrap()
+            .into_inner();
+        assert!(resp.success, "{}", resp.error_message);
+    }
+
+    let agents = client
+        .list_agents(authed(
+            ListAgentsRequest {
+                topic: "orders".into(),
+                ..Default::default()
+            },
+            "acme-secret",
+        ))
+        .await
+        .unwrap()
+        .into_inner()
+        .agents;
+    assert_eq!(agents.len(), 1);
+    assert_eq!(agents[0].agent_id, "acme-1");
+    assert_eq!(agents[0].subscribed_topics, ["orders"]);
+
+    let err = client
+        .list_agents(ListAgentsRequest::default())
+        .await
+        .unwrap_err();
+    assert_eq!(err.code(), Code::Unauthenticated);
+    let err = client
+        .list_tools(ListToolsRequest::default())
+        .await
+        .unwrap_err();
+    assert_eq!(err.code(), Code::Unauthenticated);
+}
@@ -0,0 +1,76 @@
+use super::*;
+use loom_core::{EventBus, ToolRegistry};
+use tokio::time::{sleep, timeout, Duration};
+
+async fn wait_for_subscriptions(event_bus: &EventBus, topic: &str, expected: usize) {
+    for _ in 0..100 {
+        let active = event_bus
+            .get_stats(topic)
+            .map(|s| s.active_subscriptions)
+            .unwrap_or(0);
+        if active == expected {
+            return;
+        }
+        sleep(Duration::from_millis(20)).await;
+    }
+    panic!("expected {expected} subscriptions on {topic}");
+}
+
+#[tokio::test]
+async fn test_agents_share_one_subscription_per_topic() {
+    let event_bus = Arc::new(EventBus::new().await.unwrap());
+    let tool_registry = Arc::new(ToolRegistry::new());
+    event_bus.start().await.unwrap();
+
+    let (addr, _handle, _svc) = start_test_server(event_bus.clone(), tool_registry.clone()).await;
+
+    let mut agents = Vec::new();
+    for i in 0..3 {
+        agents.push(connect_agent(addr, &format!("fan{i}"), vec!["topic.shared".into()]).await);
+    }
+    wait_for_subscriptions(&event_bus, "topic.shared", 1).await;
+
+    event_bus
+        .publish(
+            "topic.shared",
+            Event {
+                id: "ev-shared".into(),
+                r#type: "test".into(),
+                timestamp_ms: 0,
+                source: "tester".into(),
+                metadata: Default::default(),
+                payload: vec![],
+                confidence: 1.0,
+                tags: vec![],
+                priority: 50,
+            },
+        )
+        .await
+        .unwrap();
+
+    for (_, rx) in agents.iter_mut() {
+        let msg = timeout(Duration::from_secs(2), rx.message())
+            .await
+            .expect("recv timed out")
+            .unwrap()
+            .unwrap();
+        match msg.msg {
+            Some(server_event::Msg::Delivery(del)) => {
+                assert_eq!(del.topic, "topic.shared");
+                assert_eq!(del.event.unwrap().id, "ev-shared");
+            }
+            other => panic!("Expected Delivery, got {:?}", other),
+        }
+    }
+
+    // One agent leaves: the shared subscription stays for the others
+    let (tx0, rx0) = agents.remove(0);
+    drop(tx0);
+    drop(rx0);
+    sleep(Duration::from_millis(100)).await;
+    wait_for_subscriptions(&event_bus, "topic.shared", 1).await;
+
+    // Last agents leave: the subscription is released
+    agents.clear();
+    wait_for_subscriptions(&event_bus, "topic.shared", 0).await;
+}
@@ -0,0 +1,289 @@
+use super::*;
+use loom_bridge::format::PAYLOAD_FORMAT_KEY;
+use loom_bridge::{PayloadAccept, PayloadCodec, PayloadFormat, PayloadSchema, SchemaRegistry};
+use loom_core::ToolRegistry;
+use loom_proto::QoSLevel;
+use prost::Message;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use tokio::time::{timeout, Duration};
+
+fn event(id: &str, event_type: &str, payload: Vec<u8>) -> Event {
+    Event {
+        id: id.into(),
+        r#type: event_type.into(),
+        timestamp_ms: 0,
+        source: "tester".into(),
+        metadata: HashMap::new(),
+        payload,
+        confidence: 1.0,
+        tags: vec![],
+        priority: 50,
+    }
+}
+
+/// A `loom.v1.ToolCall`, used as a protobuf payload
+fn tool_call() -> loom_proto::ToolCall {
+    loom_proto::ToolCall {
+        id: "call-1".into(),
+        name: "weather.get".into(),
+        arguments: r#"{"city":"Oslo"}"#.into(),
+        headers: HashMap::from([("x-trace".to_string(), "abc".to_string())]),
+        timeout_ms: 1500,
+        correlation_id: String::new(),
+        qos: 2,
+    }
+}
+
+fn registry() -> SchemaRegistry {
+    SchemaRegistry::from_toml(
+        r#"
+        [types."tool.call"]
+        protobuf = "loom.v1.ToolCall"
+
+        [types."task.created"]
+        json_schema = { type = "object", required = ["id"], properties = { id = { type = "string" } } }
+        "#,
+    )
+    .unwrap()
+}
+
+async fn connect_with_format(
+    addr: SocketAddr,
+    agent_id: &str,
+    topics: Vec<String>,
+    format: &str,
+) -> (
+    tokio::sync::mpsc::Sender<ClientEvent>,
+    tonic::Streaming<loom_proto::ServerEvent>,
+) {
+    let mut client = new_client(addr).await;
+    let resp = client
+        .register_agent(AgentRegisterRequest {
+            agent_id: agent_id.into(),
+            subscribed_topics: topics,
+            tools: vec![],
+            metadata: HashMap::from([(PAYLOAD_FORMAT_KEY.to_string(), format.to_string())]),
+        })
+        .await
+        .unwrap()
+        .into_inner();
+    assert!(resp.success);
+    let (tx, rx_stream) = tokio::sync::mpsc::channel(16);
+    tx.send(ClientEvent {
+        msg: Some(client_event::Msg::Ack(Ack {
+            message_id: agent_id.into(),
+        })),
+    })
+    .await
+    .unwrap();
+    let rx = client
+        .event_stream(tokio_stream::wrappers::ReceiverStream::new(rx_stream))
+        .await
+        .unwrap()
+        .into_inner();
+    (tx, rx)
+}
+
+async fn next_message(rx: &mut tonic::Streaming<loom_proto::ServerEvent>) -> server_event::Msg {
+    timeout(Duration::from_secs(2), rx.message())
+        .await
+        .expect("recv timed out")
+        .unwrap()
+        .unwrap()
+        .msg
+        .unwrap()
+}
+
+async fn next_delivery(rx: &mut tonic::Streaming<loom_proto::ServerEvent>) -> Event {
+    match next_message(rx).await {
+        server_event::Msg::Delivery(del) => del.event.unwrap(),
+        other => panic!("Expected Delivery, got {:?}", other),
+    }
+}
+
+async fn publish(tx: &tokio::sync::mpsc::Sender<ClientEvent>, topic: &str, ev: Event) {
+    tx.send(ClientEvent {
+        msg: Some(client_event::Msg::Publish(Publish {
+            topic: topic.into(),
+            event: Some(ev),
+        })),
+    })
+    .await
+    .unwrap();
+}
+
+#[test]
+fn test_schema_registry_transcodes_protobuf_and_validates_json() {
+    let registry = registry();
+    let bytes = tool_call().encode_to_vec();
+
+    // proto3 JSON mapping: camelCase names, 64-bit integers as strings
+    let value = registry.to_json("tool.call", &bytes).unwrap();
+    assert_eq!(value["name"], "weather.get");
+    assert_eq!(value["timeoutMs"], "1500");
+    assert_eq!(value["headers"]["x-trace"], "abc");
+    assert_eq!(value["qos"], "QOS_BACKGROUND");
+    let back = registry.from_json("tool.call", &value).unwrap();
+    assert_eq!(
+        loom_proto::ToolCall::decode(back.as_slice()).unwrap(),
+        tool_call()
+    );
+
+    // Field names as declared and plain numbers are accepted too
+    let loose = json!({ "id": "call-1", "timeout_ms": 1500 });
+    let call =
+        loom_proto::ToolCall::decode(registry.from_json("tool.call", &loose).unwrap().as_slice())
+            .unwrap();
+    assert_eq!(call.timeout_ms, 1500);
+    assert!(registry
+        .from_json("tool.call", &json!({ "nope": 1 }))
+        .is_err());
+
+    assert!(registry
+        .from_json("task.created", &json!({ "id": 7 }))
+        .is_err());
+    assert_eq!(
+        registry
+            .from_json("task.created", &json!({ "id": "t1" }))
+            .unwrap(),
+        br#"{"id":"t1"}"#
+    );
+    // Unregistered types are JSON if they parse as JSON
+    assert!(registry.to_json("audio.clip", &[0xff, 0x00]).is_err());
+    assert!(registry.schema_of("audio.clip").is_none());
+    assert!(matches!(
+        registry.schema_of("tool.call"),
+        Some(PayloadSchema::Protobuf(_))
+    ));
+
+    assert!(SchemaRegistry::from_toml("[types.x]\nprotobuf = \"acme.Missing\"\n").is_err());
+    assert!(SchemaRegistry::from_toml("[types.x]\n").is_err());
+}
+
+#[test]
+fn test_msgpack_round_trips_json_values() {
+    let value = json!({
+        "small": [0, 127, -1, -32],
+        "wide": [200, -200, 70000, -70000, 5_000_000_000u64, -5_000_000_000i64],
+        "float": 1.5,
+        "text": "x".repeat(300),
+        "flags": [true, false, null],
+        "nested": { "a": { "b": [] } }
+    });
+    let bytes = PayloadFormat::Msgpack.encode(&value).unwrap();
+    assert_eq!(PayloadFormat::Msgpack.decode(&bytes).unwrap(), value);
+
+    // { "a": 1 } packs into a fixmap, fixstr and positive fixint
+    assert_eq!(
+        PayloadFormat::Msgpack.encode(&json!({ "a": 1 })).unwrap(),
+        [0x81, 0xa1, b'a', 0x01]
+    );
+    // bin 8 becomes base64
+    assert_eq!(
+        PayloadFormat::Msgpack.decode(&[0xc4, 2, 1, 2]).unwrap(),
+        json!("AQI=")
+    );
+    assert!(PayloadFormat::Msgpack.decode(&[0x92, 0x01]).is_err());
+    assert!(PayloadFormat::Msgpack.decode(&[0xd4, 1, 0]).is_err());
+    assert_eq!(
+        PayloadFormat::parse("MsgPack"),
+        Some(PayloadFormat::Msgpack)
+    );
+}
+
+#[test]
+fn test_codec_transcodes_per_negotiated_format() {
+    let codec = PayloadCodec::default().with_schemas(Arc::new(registry()));
+    let original = event("ev-1", "tool.call", tool_call().encode_to_vec());
+
+    let json_accept = PayloadAccept::default().with_format(PayloadFormat::Json);
+    let [delivered] = codec.encode(&original, &json_accept).try_into().unwrap();
+    assert_eq!(delivered.metadata[PAYLOAD_FORMAT_KEY], "json");
+    let value: Value = serde_json::from_slice(&delivered.payload).unwrap();
+    assert_eq!(value["arguments"], r#"{"city":"Oslo"}"#);
+
+    // Publishing the delivery back yields the protobuf payload
+    assert_eq!(
+        codec.decode("a1", delivered).unwrap().unwrap().payload,
+        original.payload
+    );
+
+    // Payloads without a JSON form are delivered raw
+    let audio = event("ev-2", "audio.clip", vec![0xff, 0x00]);
+    let [delivered] = codec.encode(&audio, &json_accept).try_into().unwrap();
+    assert_eq!(delivered.metadata[PAYLOAD_FORMAT_KEY], "raw");
+    assert_eq!(delivered.payload, audio.payload);
+
+    let mut unknown = event("ev-3", "task.created", b"{}".to_vec());
+    unknown
+        .metadata
+        .insert(PAYLOAD_FORMAT_KEY.into(), "yaml".into());
+    assert!(codec.decode("a1", unknown).is_err());
+}
+
+#[tokio::test]
+async fn test_agents_receive_and_publish_in_their_format() {
+    let event_bus = Arc::new(EventBus::new().await.unwrap());
+    event_bus.start().await.unwrap();
+    let mut state = loom_bridge::BridgeState::new(
+        Arc::clone(&event_bus),
+        Arc::new(ToolRegistry::new()),
+        Arc::new(AgentDirectory::new()),
+    );
+    state.set_schema_registry(Arc::new(registry()));
+    let (addr, _handle, _svc) = start_test_server_with_state(state).await;
+
+    let (tx_json, mut rx_json) = connect_with_format(addr, "py", vec!["jobs".into()], "json").await;
+    let (_tx_mp, mut rx_mp) = connect_with_format(addr, "js", vec!["jobs".into()], "msgpack").await;
+    let (_tx_raw, mut rx_raw) = connect_agent(addr, "rs", vec!["jobs".into()]).await;
+    let (_sub, mut bus_rx) = event_bus
+        .subscribe("jobs".into(), vec![], QoSLevel::QosBatched)
+        .await
+        .unwrap();
+
+    // An in-process protobuf payload reaches each agent in its format
+    let original = event("ev-1", "tool.call", tool_call().encode_to_vec());
+    event_bus.publish("jobs", original.clone()).await.unwrap();
+    let as_json = next_delivery(&mut rx_json).await;
+    let value: Value = serde_json::from_slice(&as_json.payload).unwrap();
+    assert_eq!(value["name"], "weather.get");
+    let as_msgpack = next_delivery(&mut rx_mp).await;
+    assert_eq!(as_msgpack.metadata[PAYLOAD_FORMAT_KEY], "msgpack");
+    assert_eq!(
+        PayloadFormat::Msgpack.decode(&as_msgpack.payload).unwrap(),
+        value
+    );
+    assert_eq!(
+        as_published(next_delivery(&mut rx_raw).await, &original),
+        original
+    );
+    bus_rx.recv().await.unwrap();
+
+    // A JSON publish reaches the EventBus as protobuf
+    let mut from_json = event("ev-2", "tool.call", as_json.payload.clone());
+    from_json.source = "py".into();
+    publish(&tx_json, "jobs", from_json).await;
+    let on_bus = timeout(Duration::from_secs(2), bus_rx.recv())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        loom_proto::ToolCall::decode(on_bus.payload.as_slice()).unwrap(),
+        tool_call()
+    );
+    assert!(!on_bus.metadata.contains_key(PAYLOAD_FORMAT_KEY));
+    let _ = next_delivery(&mut rx_json).await;
+
+    // A publish that breaks its type's schema is rejected
+    publish(
+        &tx_json,
+        "jobs",
+        event("ev-3", "task.created", br#"{"id": 7}"#.to_vec()),
+    )
+    .await;
+    match next_message(&mut rx_json).await {
+        server_event::Msg::Err(err) => assert_eq!(err.code, "PAYLOAD_INVALID"),
+        other => panic!("Expected Err, got {:?}", other),
+    }
+}
@@ -0,0 +1,134 @@
+use super::*;
+use loom_bridge::{BridgeState, GuaranteedTopics};
+use loom_core::messaging::receipts::{RECEIPT_ATTEMPT_KEY, RECEIPT_SUBSCRIBER_KEY};
+use loom_core::messaging::reliable::dead_letter_keys;
+use loom_core::{AgentDirectory, EventBus, ReliableConfig, ToolRegistry};
+use loom_proto::QoSLevel;
+use tokio::time::{sleep, timeout, Duration};
+
+fn event(id: &str) -> Event {
+    Event {
+        id: id.into(),
+        r#type: "order".into(),
+        timestamp_ms: 0,
+        source: "tester".into(),
+        metadata: Default::default(),
+        payload: vec![],
+        confidence: 1.0,
+        tags: vec![],
+        priority: 50,
+    }
+}
+
+async fn start_guaranteed(max_deliveries: u32) -> (SocketAddr, Arc<EventBus>, BridgeState) {
+    let event_bus = Arc::new(EventBus::new().await.unwrap());
+    event_bus.start().await.unwrap();
+    let mut state = BridgeState::new(
+        Arc::clone(&event_bus),
+        Arc::new(ToolRegistry::new()),
+        Arc::new(AgentDirectory::new()),
+    );
+    state.set_guaranteed_topics(
+        GuaranteedTopics::new(["orders.*"]).with_delivery(
+            ReliableConfig::default()
+                .with_ack_timeout(Duration::from_millis(150))
+                .with_max_deliveries(max_deliveries),
+        ),
+    );
+    let (addr, _handle, _svc) = start_test_server_with_state(state.clone()).await;
+    (addr, event_bus, state)
+}
+
+async fn recv_delivery(rx: &mut tonic::Streaming<loom_proto::ServerEvent>) -> Event {
+    let msg = timeout(Duration::from_secs(2), rx.message())
+        .await
+        .expect("recv timed out")
+        .unwrap()
+        .unwrap();
+    match msg.msg {
+        Some(server_event::Msg::Delivery(del)) => del.event.unwrap(),
+        other => panic!("Expected Delivery, got {:?}", other),
+    }
+}
+
+fn ack(id: &str) -> ClientEvent {
+    ClientEvent {
+        msg: Some(client_event::Msg::Ack(Ack {
+            message_id: id.into(),
+        })),
+    }
+}
+
+#[tokio::test]
+async fn test_unacked_delivery_is_redelivered_until_acked() {
+    let (addr, event_bus, state) = start_guaranteed(5).await;
+    let (tx, mut rx) =
+        connect_agent(addr, "clerk", vec!["orders.eu".into(), "news.daily".into()]).await;
+    assert_eq!(state.fanout.stats().await.guaranteed_topics, 1);
+
+    event_bus.publish("orders.eu", event("o1")).await.unwrap();
+    let first = recv_delivery(&mut rx).await;
+    assert_eq!(first.id, "o1");
+    assert!(first.metadata.contains_key(RECEIPT_SUBSCRIBER_KEY));
+    assert!(!first.metadata.contains_key(RECEIPT_ATTEMPT_KEY));
+
+    // Not acked: it comes back with a redelivery counter
+    let second = recv_delivery(&mut rx).await;
+    assert_eq!(second.id, "o1");
+    assert_eq!(
+        second.metadata.get(RECEIPT_ATTEMPT_KEY).map(String::as_str),
+        Some("2")
+    );
+    let outstanding = event_bus.outstanding_acks();
+    assert_eq!(outstanding.len(), 1);
+    assert_eq!(outstanding[0].subscriber, "clerk");
+
+    tx.send(ack("o1")).await.unwrap();
+    assert!(
+        timeout(Duration::from_millis(400), rx.message())
+            .await
+            .is_err(),
+        "acked delivery must not be redelivered"
+    );
+    assert!(event_bus.outstanding_acks().is_empty());
+
+    // Other topics need no acks
+    event_bus.publish("news.daily", event("n1")).await.unwrap();
+    let news = recv_delivery(&mut rx).await;
+    assert!(!news.metadata.contains_key(RECEIPT_SUBSCRIBER_KEY));
+}
+
+#[tokio::test]
+async fn test_exhausted_delivery_is_dead_lettered() {
+    let (addr, event_bus, _state) = start_guaranteed(2).await;
+    let (_dl_id, mut dead_letters) = event_bus
+        .subscribe("dead_letter.orders.eu".into(), vec![], QoSLevel::QosBatched)
+        .await
+        .unwrap();
+    // In-process subscribers of a guaranteed topic owe no acks
+    let (_local_id, mut local) = event_bus
+        .subscribe("orders.eu".into(), vec![], QoSLevel::QosBatched)
+        .await
+        .unwrap();
+    let (_tx, mut rx) = connect_agent(addr, "clerk", vec!["orders.eu".into()]).await;
+
+    event_bus.publish("orders.eu", event("o2")).await.unwrap();
+    let local_event = local.recv().await.unwrap();
+    assert!(!local_event.metadata.contains_key(RECEIPT_SUBSCRIBER_KEY));
+
+    assert_eq!(recv_delivery(&mut rx).await.id, "o2");
+    assert_eq!(recv_delivery(&mut rx).await.id, "o2");
+
+    let dead = timeout(Duration::from_secs(2), dead_letters.recv())
+        .await
+        .expect("dead letter timed out")
+        .unwrap();
+    assert_eq!(dead.id, "o2");
+    assert_eq!(dead.metadata[dead_letter_keys::REASON], "ack_timeout");
+    assert_eq!(dead.metadata[dead_letter_keys::ATTEMPTS], "2");
+    assert_eq!(dead.metadata[dead_letter_keys::TOPIC], "orders.eu");
+    assert_eq!(dead.metadata[dead_letter_keys::SUBSCRIPTION], "clerk");
+
+    sleep(Duration::from_millis(300)).await;
+    assert!(event_bus.outstanding_acks().is_empty());
+}
@@ -0,0 +1,89 @@
+use super::*;
+use loom_bridge::{BridgeState, LivenessConfig};
+use loom_core::{AgentDirectory, AgentStatus, EventBus, ToolRegistry};
+use loom_proto::HeartbeatRequest;
+use tokio::time::{sleep, timeout, Duration};
+
+async fn start_with_liveness(disconnect_after: u32) -> (SocketAddr, Arc<EventBus>, BridgeState) {
+    let event_bus = Arc::new(EventBus::new().await.unwrap());
+    event_bus.start().await.unwrap();
+    let mut state = BridgeState::new(
+        Arc::clone(&event_bus),
+        Arc::new(ToolRegistry::new()),
+        Arc::new(AgentDirectory::new()),
+    );
+    state.set_liveness_config(LivenessConfig {
+        heartbeat_interval: Duration::from_millis(50),
+        degraded_after: 2,
+        disconnect_after,
+        unregister: false,
+    });
+    let _monitor = state.spawn_liveness_monitor().unwrap();
+    let (addr, _handle, _svc) = start_test_server_with_state(state.clone()).await;
+    (addr, event_bus, state)
+}
+
+async fn wait_for_status(directory: &AgentDirectory, agent_id: &str, status: AgentStatus) {
+    for _ in 0..100 {
+        if directory.get(agent_id).map(|a| a.status) == Some(status.clone()) {
+            return;
+        }
+        sleep(Duration::from_millis(20)).await;
+    }
+    panic!("expected {agent_id} to be {status:?}");
+}
+
+#[tokio::test]
+async fn test_silent_agent_stream_is_closed() {
+    let (addr, event_bus, state) = start_with_liveness(4).await;
+    let (_tx, mut rx) = connect_agent(addr, "silent", vec!["topic.silent".into()]).await;
+    wait_for_status(&state.agent_directory, "silent", AgentStatus::Degraded).await;
+    wait_for_status(&state.agent_directory, "silent", AgentStatus::Disconnected).await;
+
+    // The agent's stream ends although the agent never hung up
+    let end = timeout(Duration::from_secs(2), rx.message())
+        .await
+        .expect("stream not closed");
+    assert!(!matches!(end, Ok(Some(_))));
+    assert!(!state.streams.contains_key("silent"));
+    let stats = state.liveness.stats();
+    assert_eq!(stats.degraded, 1);
+    assert_eq!(stats.dead, 1);
+
+    // Its topic forwarding is released
+    for _ in 0..100 {
+        let active = event_bus
+            .get_stats("topic.silent")
+            .map(|s| s.active_subscriptions)
+            .unwrap_or(0);
+        if active == 0 {
+            return;
+        }
+        sleep(Duration::from_millis(20)).await;
+    }
+    panic!("subscription not released after missed heartbeats");
+}
+
+#[tokio::test]
+async fn test_pinging_agent_stays_active() {
+    let (addr, _event_bus, state) = start_with_liveness(4).await;
+    let (tx, _rx) = connect_agent(addr, "pinger", vec!["topic.pinger".into()]).await;
+
+    for _ in 0..15 {
+        tx.send(ClientEvent {
+            msg: Some(client_event::Msg::Ping(HeartbeatRequest {
+                timestamp_ms: chrono::Utc::now().timestamp_millis(),
+            })),
+        })
+        .await
+        .unwrap();
+        sleep(Duration::from_millis(30)).await;
+    }
+
+    assert_eq!(
+        state.agent_directory.get("pinger").unwrap().status,
+        AgentStatus::Active
+    );
+    assert!(state.streams.contains_key("pinger"));
+    assert_eq!(state.liveness.stats().dead, 0);
+}
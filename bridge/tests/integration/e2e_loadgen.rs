@@ -0,0 +1,102 @@
+use super::*;
+use loom_bridge::loadgen::{
+    run_bridge, run_bus, BurstConfig, CorrelationPattern, LatencySummary, LoadgenConfig,
+};
+use loom_core::SimulationConfig;
+use std::time::Duration;
+
+fn short_config(pattern: CorrelationPattern) -> LoadgenConfig {
+    LoadgenConfig {
+        topics: 2,
+        topic_prefix: "loadgen.test".into(),
+        rate: 500,
+        duration: Duration::from_millis(600),
+        publishers: 2,
+        payload_bytes: 128,
+        burst: None,
+        pattern,
+        personas: None,
+    }
+}
+
+#[test]
+fn test_latency_percentiles() {
+    let summary = LatencySummary::from_nanos((1..=1000).rev().collect());
+    assert_eq!(summary.samples, 1000);
+    assert_eq!(summary.p50, Duration::from_nanos(500));
+    assert_eq!(summary.p99, Duration::from_nanos(990));
+    assert_eq!(summary.max, Duration::from_nanos(1000));
+    assert_eq!(
+        LatencySummary::from_nanos(vec![]),
+        LatencySummary::default()
+    );
+}
+
+#[tokio::test]
+async fn test_loadgen_on_event_bus_with_bursts() {
+    let event_bus = Arc::new(EventBus::new().await.unwrap());
+    event_bus.start().await.unwrap();
+    let config = LoadgenConfig {
+        burst: Some(BurstConfig {
+            size: 50,
+            every: Duration::from_millis(200),
+        }),
+        ..short_config(CorrelationPattern::Threads(4))
+    };
+
+    let report = run_bus(Arc::clone(&event_bus), &config).await.unwrap();
+
+    // ~300 paced events plus two or three bursts of 50
+    assert!(report.sent >= 300, "sent {}", report.sent);
+    assert_eq!(report.publish_errors, 0);
+    assert_eq!(report.received, report.sent);
+    assert_eq!(report.latency.samples as u64, report.received);
+    assert!(report.latency.p50 <= report.latency.p99);
+    // Subscriptions are released after the run
+    assert_eq!(event_bus.subscription_count(), 0);
+}
+
+#[tokio::test]
+async fn test_loadgen_request_reply_through_bridge() {
+    let event_bus = Arc::new(EventBus::new().await.unwrap());
+    event_bus.start().await.unwrap();
+    let (addr, _handle, _svc) = start_test_server(event_bus, Arc::new(ToolRegistry::new())).await;
+
+    let report = run_bridge(addr, &short_config(CorrelationPattern::RequestReply(3)))
+        .await
+        .unwrap();
+
+    assert!(report.sent > 0);
+    assert_eq!(report.publish_errors, 0);
+    assert_eq!(
+        report.received, report.sent,
+        "every request should be answered: {report}"
+    );
+    assert!(report.latency.max > Duration::ZERO);
+}
+
+#[tokio::test]
+async fn test_loadgen_with_slow_and_flaky_personas() {
+    let event_bus = Arc::new(EventBus::new().await.unwrap());
+    event_bus.start().await.unwrap();
+    let personas = SimulationConfig::new(3)
+        .with_reply_latency(Duration::from_millis(2), Duration::from_millis(10))
+        .with_error_rate(0.25);
+    let config = LoadgenConfig {
+        personas: Some(personas),
+        ..short_config(CorrelationPattern::RequestReply(3))
+    };
+    assert_eq!(config.topic_names().len(), 3);
+
+    let report = run_bus(Arc::clone(&event_bus), &config).await.unwrap();
+
+    // About a quarter of the requests go unanswered; the rest take at least 2ms
+    assert!(report.sent >= 250, "sent {}", report.sent);
+    assert!(
+        report.received > 0 && report.received < report.sent,
+        "{report}"
+    );
+    assert!(report.loss() > 0.1 && report.loss() < 0.4, "{report}");
+    assert!(report.latency.p50 >= Duration::from_millis(2));
+    assert_eq!(event_bus.subscription_count(), 0);
+}
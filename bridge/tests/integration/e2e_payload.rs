@@ -0,0 +1,252 @@
+use super::*;
+use loom_bridge::payload::{
+    ACCEPT_ENCODING_KEY, CHUNK_COUNT_KEY, CHUNK_ID_KEY, CHUNK_INDEX_KEY, CONTENT_ENCODING_KEY,
+};
+use loom_bridge::{Codec, PayloadAccept, PayloadCodec, PayloadConfig};
+use loom_core::ToolRegistry;
+use loom_proto::QoSLevel;
+use std::collections::HashMap;
+use tokio::time::{timeout, Duration};
+
+fn config() -> PayloadConfig {
+    PayloadConfig {
+        compression: Some(Codec::Zstd),
+        compress_min_bytes: 1024,
+        chunk_bytes: 4096,
+        max_payload_bytes: 1024 * 1024,
+        reassembly_timeout: Duration::from_secs(5),
+    }
+}
+
+fn event(id: &str, payload: Vec<u8>) -> Event {
+    Event {
+        id: id.into(),
+        r#type: "audio.clip".into(),
+        timestamp_ms: 0,
+        source: "tester".into(),
+        metadata: HashMap::from([("thread_id".to_string(), "t1".to_string())]),
+        payload,
+        confidence: 1.0,
+        tags: vec![],
+        priority: 50,
+    }
+}
+
+/// Pseudo-random bytes from a 16-symbol alphabet: compresses to about half, so a
+/// compressed payload still spans several chunks
+fn payload(len: usize) -> Vec<u8> {
+    let mut state: u32 = 0x2545_f491;
+    (0..len)
+        .map(|_| {
+            state = state.wrapping_mul(1_664_525).wrapping_add(1_013_904_223);
+            (state >> 28) as u8
+        })
+        .collect()
+}
+
+async fn next_delivery(rx: &mut tonic::Streaming<loom_proto::ServerEvent>) -> Event {
+    let msg = timeout(Duration::from_secs(2), rx.message())
+        .await
+        .expect("recv timed out")
+        .unwrap()
+        .unwrap()
+        .msg
+        .unwrap();
+    match msg {
+        server_event::Msg::Delivery(del) => del.event.unwrap(),
+        other => panic!("Expected Delivery, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_codec_round_trips_compressed_chunks() {
+    let codec = PayloadCodec::new(PayloadConfig {
+        chunk_bytes: 1000,
+        ..config()
+    });
+    let original = event("ev-1", payload(64 * 1024));
+
+    for accept in ["zstd, chunked", "lz4,chunked"] {
+        let accept = PayloadAccept::parse(accept);
+        let codec = PayloadCodec::new(PayloadConfig {
+            compression: accept.codecs.first().copied(),
+            ..codec.config().clone()
+        });
+        let chunks = codec.encode(&original, &accept);
+        assert!(chunks.len() > 1);
+        assert_eq!(chunks[1].id, "ev-1:1");
+        assert_eq!(chunks[1].metadata[CHUNK_INDEX_KEY], "1");
+        assert_eq!(
+            chunks[0].metadata[CONTENT_ENCODING_KEY],
+            accept.codecs[0].as_str()
+        );
+
+        // Chunks may arrive in any order
+        let mut decoded = None;
+        for chunk in chunks.into_iter().rev() {
+            decoded = codec.decode("a1", chunk).unwrap();
+        }
+        assert_eq!(decoded.unwrap(), original);
+        assert_eq!(codec.pending(), 0);
+    }
+
+    // Agents that negotiated nothing get the event unchanged
+    let plain = codec.encode(&original, &PayloadAccept::default());
+    assert_eq!(plain, [original.clone()]);
+    assert_eq!(
+        codec.decode("a1", original.clone()).unwrap().unwrap(),
+        original
+    );
+}
+
+#[test]
+fn test_codec_rejects_bogus_payloads() {
+    let codec = PayloadCodec::new(config());
+
+    let mut bomb = event(
+        "ev-bomb",
+        Codec::Lz4.compress(&vec![0; 2 * 1024 * 1024]).unwrap(),
+    );
+    bomb.metadata
+        .insert(CONTENT_ENCODING_KEY.into(), "lz4".into());
+    assert!(codec.decode("a1", bomb).is_err());
+
+    let mut unknown = event("ev-br", vec![1, 2, 3]);
+    unknown
+        .metadata
+        .insert(CONTENT_ENCODING_KEY.into(), "br".into());
+    assert!(codec.decode("a1", unknown).is_err());
+
+    let mut chunk = event("ev-2:5", vec![1]);
+    chunk.metadata.insert(CHUNK_ID_KEY.into(), "ev-2".into());
+    chunk.metadata.insert(CHUNK_INDEX_KEY.into(), "5".into());
+    chunk.metadata.insert(CHUNK_COUNT_KEY.into(), "2".into());
+    assert!(codec.decode("a1", chunk.clone()).is_err());
+
+    // Incomplete events are dropped when their agent disconnects
+    chunk.metadata.insert(CHUNK_ID_KEY.into(), "ev-2".into());
+    chunk.metadata.insert(CHUNK_INDEX_KEY.into(), "0".into());
+    chunk.metadata.insert(CHUNK_COUNT_KEY.into(), "2".into());
+    assert!(codec.decode("a1", chunk).unwrap().is_none());
+    assert_eq!(codec.pending(), 1);
+    codec.discard("a1");
+    assert_eq!(codec.pending(), 0);
+}
+
+#[tokio::test]
+async fn test_large_payloads_are_negotiated_per_agent() {
+    let event_bus = Arc::new(EventBus::new().await.unwrap());
+    event_bus.start().await.unwrap();
+    let mut state = loom_bridge::BridgeState::new(
+        Arc::clone(&event_bus),
+        Arc::new(ToolRegistry::new()),
+        Arc::new(AgentDirectory::new()),
+    );
+    state.set_payload_config(config());
+    let payloads = Arc::clone(&state.payloads);
+    let (addr, _handle, _svc) = start_test_server_with_state(state).await;
+
+    // One agent negotiates compression and chunking, the other nothing
+    let mut client = new_client(addr).await;
+    let resp = client
+        .register_agent(AgentRegisterRequest {
+            agent_id: "modern".into(),
+            subscribed_topics: vec!["media".into()],
+            tools: vec![],
+            metadata: HashMap::from([(ACCEPT_ENCODING_KEY.to_string(), "zstd, chunked".into())]),
+        })
+        .await
+        .unwrap()
+        .into_inner();
+    assert!(resp.success);
+    let (tx_modern, rx_stream) = tokio::sync::mpsc::channel(16);
+    tx_modern
+        .send(ClientEvent {
+            msg: Some(client_event::Msg::Ack(Ack {
+                message_id: "modern".into(),
+            })),
+        })
+        .await
+        .unwrap();
+    let mut rx_modern = client
+        .event_stream(tokio_stream::wrappers::ReceiverStream::new(rx_stream))
+        .await
+        .unwrap()
+        .into_inner();
+    let (_tx_legacy, mut rx_legacy) = connect_agent(addr, "legacy", vec!["media".into()]).await;
+
+    let (_sub, mut bus_rx) = event_bus
+        .subscribe("media".into(), vec![], QoSLevel::QosBatched)
+        .await
+        .unwrap();
+
+    // The modern agent publishes a chunked, compressed event
+    let original = event("ev-big", payload(200 * 1024));
+    let encoder = PayloadCodec::new(config());
+    let chunks = encoder.encode(&original, &PayloadAccept::parse("zstd,chunked"));
+    assert!(chunks.len() > 1);
+    for chunk in chunks {
+        tx_modern
+            .send(ClientEvent {
+                msg: Some(client_event::Msg::Publish(Publish {
+                    topic: "media".into(),
+                    event: Some(chunk),
+                })),
+            })
+            .await
+            .unwrap();
+    }
+
+    // The EventBus sees the whole, decoded event exactly once
+    let on_bus = timeout(Duration::from_secs(2), bus_rx.recv())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(as_published(on_bus, &original), original);
+
+    // The legacy agent receives it unchanged
+    assert_eq!(
+        as_published(next_delivery(&mut rx_legacy).await, &original),
+        original
+    );
+
+    // The modern agent receives compressed chunks and reassembles them
+    let first = next_delivery(&mut rx_modern).await;
+    assert_eq!(first.metadata[CONTENT_ENCODING_KEY], "zstd");
+    let count: usize = first.metadata[CHUNK_COUNT_KEY].parse().unwrap();
+    let decoder = PayloadCodec::new(config());
+    let mut decoded = decoder.decode("bridge", first).unwrap();
+    for _ in 1..count {
+        decoded = decoder
+            .decode("bridge", next_delivery(&mut rx_modern).await)
+            .unwrap();
+    }
+    assert_eq!(as_published(decoded.unwrap(), &original), original);
+    assert_eq!(payloads.pending(), 0);
+
+    // A corrupt payload is rejected with an error event
+    let mut corrupt = event("ev-bad", vec![0xff; 16]);
+    corrupt
+        .metadata
+        .insert(CONTENT_ENCODING_KEY.into(), "zstd".into());
+    tx_modern
+        .send(ClientEvent {
+            msg: Some(client_event::Msg::Publish(Publish {
+                topic: "media".into(),
+                event: Some(corrupt),
+            })),
+        })
+        .await
+        .unwrap();
+    let msg = timeout(Duration::from_secs(2), rx_modern.message())
+        .await
+        .unwrap()
+        .unwrap()
+        .unwrap()
+        .msg
+        .unwrap();
+    match msg {
+        server_event::Msg::Err(err) => assert_eq!(err.code, "PAYLOAD_INVALID"),
+        other => panic!("Expected Err, got {:?}", other),
+    }
+}
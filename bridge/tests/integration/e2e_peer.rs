@@ -0,0 +1,281 @@
+use super::*;
+use loom_bridge::{BridgePeer, PeerConfig, ORIGIN_KEY};
+use loom_core::tools::ToolResult as CoreToolResult;
+use loom_core::{EventBus, QoSLevel, Tool, ToolError, ToolRegistry};
+use tokio::time::{sleep, timeout, Duration};
+
+fn event(id: &str) -> Event {
+    Event {
+        id: id.into(),
+        r#type: "test".into(),
+        timestamp_ms: 0,
+        source: "tester".into(),
+        metadata: Default::default(),
+        payload: vec![],
+        confidence: 1.0,
+        tags: vec![],
+        priority: 50,
+    }
+}
+
+async fn started_bus() -> Arc<EventBus> {
+    let bus = Arc::new(EventBus::new().await.unwrap());
+    bus.start().await.unwrap();
+    bus
+}
+
+async fn wait_connected(peer: &BridgePeer) {
+    for _ in 0..100 {
+        if peer.stats().connected {
+            return;
+        }
+        sleep(Duration::from_millis(20)).await;
+    }
+    panic!("peer never connected");
+}
+
+/// Echoes its arguments after `delay`
+struct DelayedEcho {
+    name: &'static str,
+    delay: Duration,
+}
+
+#[async_trait::async_trait]
+impl Tool for DelayedEcho {
+    fn name(&self) -> String {
+        self.name.to_string()
+    }
+
+    fn description(&self) -> String {
+        format!("Echoes its arguments after {:?}", self.delay)
+    }
+
+    fn parameters(&self) -> serde_json::Value {
+        serde_json::json!({ "type": "object" })
+    }
+
+    async fn call(&self, arguments: serde_json::Value) -> CoreToolResult<serde_json::Value> {
+        sleep(self.delay).await;
+        Ok(arguments)
+    }
+}
+
+fn echo(name: &'static str, delay_ms: u64) -> Arc<DelayedEcho> {
+    Arc::new(DelayedEcho {
+        name,
+        delay: Duration::from_millis(delay_ms),
+    })
+}
+
+async fn recv(rx: &mut tokio::sync::mpsc::Receiver<Event>) -> Event {
+    timeout(Duration::from_secs(2), rx.recv())
+        .await
+        .expect("recv timed out")
+        .expect("subscription open")
+}
+
+#[tokio::test]
+async fn test_peer_forwards_both_ways_without_loops() {
+    // "server" runs the cognitive agents behind its Bridge; "frontend" peers with it
+    let server = started_bus().await;
+    let (addr, _handle, _svc) =
+        start_test_server(Arc::clone(&server), Arc::new(ToolRegistry::new())).await;
+    let frontend = started_bus().await;
+
+    // Both directions share a topic too, which would ping-pong without origin tags
+    let peer = Arc::new(
+        BridgePeer::new(
+            Arc::clone(&frontend),
+            PeerConfig::new("frontend", addr.to_string())
+                .with_export(["audio.transcript", "shared.state"])
+                .with_import(["voice.reply", "shared.state"]),
+        )
+        .unwrap(),
+    );
+    let task = peer.start().await.unwrap();
+    wait_connected(&peer).await;
+
+    let (_sub, mut server_transcripts) = server
+        .subscribe("audio.transcript".into(), vec![], QoSLevel::QosBatched)
+        .await
+        .unwrap();
+    let (_sub, mut frontend_replies) = frontend
+        .subscribe("voice.reply".into(), vec![], QoSLevel::QosBatched)
+        .await
+        .unwrap();
+    let (_sub, mut server_shared) = server
+        .subscribe("shared.state".into(), vec![], QoSLevel::QosBatched)
+        .await
+        .unwrap();
+    let (_sub, mut frontend_shared) = frontend
+        .subscribe("shared.state".into(), vec![], QoSLevel::QosBatched)
+        .await
+        .unwrap();
+
+    // Export: frontend -> server, tagged with its origin
+    frontend
+        .publish("audio.transcript", event("t1"))
+        .await
+        .unwrap();
+    let got = recv(&mut server_transcripts).await;
+    assert_eq!(got.id, "t1");
+    assert_eq!(
+        got.metadata.get(ORIGIN_KEY).map(String::as_str),
+        Some("frontend")
+    );
+
+    // Import: server -> frontend
+    server.publish("voice.reply", event("r1")).await.unwrap();
+    assert_eq!(recv(&mut frontend_replies).await.id, "r1");
+
+    // A shared topic event crosses once in each direction and never comes back
+    frontend.publish("shared.state", event("f1")).await.unwrap();
+    server.publish("shared.state", event("s1")).await.unwrap();
+    assert_eq!(recv(&mut frontend_shared).await.id, "f1");
+    assert_eq!(recv(&mut frontend_shared).await.id, "s1");
+    let mut on_server = vec![recv(&mut server_shared).await.id];
+    on_server.push(recv(&mut server_shared).await.id);
+    on_server.sort();
+    assert_eq!(on_server, vec!["f1", "s1"]);
+
+    sleep(Duration::from_millis(300)).await;
+    assert!(frontend_shared.try_recv().is_err());
+    assert!(server_shared.try_recv().is_err());
+
+    let stats = peer.stats();
+    assert_eq!(stats.exported, 2);
+    assert_eq!(stats.imported, 2);
+    // The echo of f1 from the server and the re-export of s1 on the frontend
+    assert_eq!(stats.loops_dropped, 2);
+
+    task.abort();
+}
+
+#[tokio::test]
+async fn test_peer_retries_until_remote_is_up() {
+    // Reserve a port nobody listens on yet
+    let listener = tokio::net::TcpListener::bind((std::net::Ipv4Addr::LOCALHOST, 0))
+        .await
+        .unwrap();
+    let addr = listener.local_addr().unwrap();
+    drop(listener);
+
+    let frontend = started_bus().await;
+    let peer = Arc::new(
+        BridgePeer::new(
+            Arc::clone(&frontend),
+            PeerConfig::new("frontend", addr.to_string())
+                .with_export(["audio.transcript"])
+                .with_backoff(Duration::from_millis(50), Duration::from_millis(200)),
+        )
+        .unwrap(),
+    );
+    let task = peer.start().await.unwrap();
+
+    // Published while the remote is down; waits in the local subscription
+    frontend
+        .publish("audio.transcript", event("queued"))
+        .await
+        .unwrap();
+    sleep(Duration::from_millis(200)).await;
+    assert!(!peer.stats().connected);
+
+    let server = started_bus().await;
+    let (_sub, mut server_transcripts) = server
+        .subscribe("audio.transcript".into(), vec![], QoSLevel::QosBatched)
+        .await
+        .unwrap();
+    let state = loom_bridge::BridgeState::new(
+        Arc::clone(&server),
+        Arc::new(ToolRegistry::new()),
+        Arc::new(loom_core::AgentDirectory::new()),
+    );
+    let listener = tokio::net::TcpListener::bind(addr).await.expect("rebind");
+    tokio::spawn(async move {
+        tonic::transport::Server::builder()
+            .add_service(loom_proto::bridge_server::BridgeServer::new(
+                loom_bridge::BridgeService::new(state),
+            ))
+            .serve_with_incoming(tokio_stream::wrappers::TcpListenerStream::new(listener))
+            .await
+            .unwrap();
+    });
+
+    assert_eq!(recv(&mut server_transcripts).await.id, "queued");
+    assert!(peer.stats().connected);
+
+    task.abort();
+}
+
+#[tokio::test]
+async fn test_peer_config_rejects_patterns_and_empty_topics() {
+    let bus = started_bus().await;
+    let config = PeerConfig::new("frontend", "127.0.0.1:50051");
+    assert!(BridgePeer::new(Arc::clone(&bus), config.clone()).is_err());
+    assert!(BridgePeer::new(Arc::clone(&bus), config.clone().with_import(["audio.*"])).is_err());
+    assert!(BridgePeer::new(bus, config.with_import(["voice.reply"])).is_ok());
+}
+
+#[tokio::test]
+async fn test_peer_imports_remote_tools_and_forwards_calls() {
+    // The "gpu" node serves heavy tools; the edge node peers with it to use them
+    let gpu_tools = Arc::new(ToolRegistry::new());
+    gpu_tools.register(echo("gpu:caption", 0)).await;
+    gpu_tools.register(echo("gpu:slow", 2000)).await;
+    gpu_tools.register(echo("gpu:shared", 0)).await;
+    gpu_tools.register(echo("cpu:resize", 0)).await;
+    let (addr, _server, _svc) = start_test_server(started_bus().await, gpu_tools).await;
+
+    let edge_tools = Arc::new(ToolRegistry::new());
+    edge_tools.register(echo("gpu:shared", 1)).await;
+    let peer = Arc::new(
+        BridgePeer::new(
+            started_bus().await,
+            PeerConfig::new("edge", addr.to_string())
+                .with_tools(["gpu:"])
+                .with_tool_timeout(Duration::from_millis(300)),
+        )
+        .unwrap()
+        .with_tool_registry(Arc::clone(&edge_tools)),
+    );
+    let task = peer.start().await.unwrap();
+    wait_connected(&peer).await;
+    for _ in 0..100 {
+        if peer.stats().tools > 0 {
+            break;
+        }
+        sleep(Duration::from_millis(20)).await;
+    }
+
+    // Only the prefix is imported, and the local gpu:shared is not replaced
+    assert_eq!(peer.stats().tools, 2);
+    assert!(edge_tools.get("gpu:caption").is_some());
+    assert!(edge_tools.get("cpu:resize").is_none());
+    assert!(edge_tools
+        .get("gpu:shared")
+        .unwrap()
+        .description()
+        .ends_with("1ms"));
+
+    let output = edge_tools
+        .call("gpu:caption", serde_json::json!({ "image": "blob:1" }))
+        .await
+        .unwrap();
+    assert_eq!(output, serde_json::json!({ "image": "blob:1" }));
+
+    // The deadline ends calls the remote node does not finish in time
+    let err = edge_tools
+        .call("gpu:slow", serde_json::json!({}))
+        .await
+        .unwrap_err();
+    assert!(matches!(err, ToolError::Timeout), "{err:?}");
+
+    task.abort();
+}
+
+#[tokio::test]
+async fn test_peer_importing_tools_requires_a_registry() {
+    let config = PeerConfig::new("edge", "127.0.0.1:50051").with_tools(["gpu:"]);
+    let peer = Arc::new(BridgePeer::new(started_bus().await, config).unwrap());
+    assert!(peer.start().await.is_err());
+}
@@ -0,0 +1,70 @@
+use super::*;
+use loom_core::RateLimit;
+use loom_proto::conventions::keys;
+use loom_proto::QoSLevel;
+use std::collections::HashMap;
+use tokio::time::{sleep, timeout, Duration};
+
+fn event(id: &str, sender: &str) -> Event {
+    Event {
+        id: id.into(),
+        r#type: "chat".into(),
+        timestamp_ms: 0,
+        source: sender.into(),
+        metadata: HashMap::from([(keys::SENDER.to_string(), sender.to_string())]),
+        payload: vec![],
+        confidence: 1.0,
+        tags: vec![],
+        priority: 50,
+    }
+}
+
+async fn publish(tx: &tokio::sync::mpsc::Sender<ClientEvent>, event: Event) {
+    tx.send(ClientEvent {
+        msg: Some(client_event::Msg::Publish(Publish {
+            topic: "chat".into(),
+            event: Some(event),
+        })),
+    })
+    .await
+    .unwrap();
+}
+
+#[tokio::test]
+async fn test_publisher_limit_follows_the_connection_not_the_sender() {
+    let event_bus = Arc::new(EventBus::new().await.unwrap());
+    event_bus.start().await.unwrap();
+    event_bus
+        .rate_limits()
+        .set_default_publisher_limit(Some(RateLimit::per_second(0.01).with_burst(2)));
+    let (addr, _handle, _svc) =
+        start_test_server(Arc::clone(&event_bus), Arc::new(ToolRegistry::new())).await;
+    let (_sub, mut bus_rx) = event_bus
+        .subscribe("chat".into(), vec![], QoSLevel::QosBatched)
+        .await
+        .unwrap();
+
+    // Claiming a new sender per event neither escapes the limit nor spends the
+    // budget of the agent named
+    let (sly, _sly_rx) = connect_agent(addr, "sly", vec![]).await;
+    publish(&sly, event("s0", "fake-0")).await;
+    publish(&sly, event("s1", "victim")).await;
+    publish(&sly, event("s2", "fake-2")).await;
+    sleep(Duration::from_millis(200)).await;
+    let (victim, _victim_rx) = connect_agent(addr, "victim", vec![]).await;
+    publish(&victim, event("v0", "victim")).await;
+    publish(&victim, event("v1", "victim")).await;
+
+    let mut received = Vec::new();
+    for _ in 0..4 {
+        let ev = timeout(Duration::from_secs(2), bus_rx.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        received.push(ev.id);
+    }
+    assert_eq!(received, ["s0", "s1", "v0", "v1"]);
+    let stats = event_bus.rate_limits().stats();
+    assert_eq!(stats.len(), 1);
+    assert_eq!((stats[0].key.as_str(), stats[0].rejected), ("sly", 1));
+}
@@ -0,0 +1,336 @@
+//! End-to-end: an external agent's tool, registered over the Bridge, shows up in the
+//! Bridge's ToolRegistry, is chosen by a CognitiveAgent's (mock) LLM and executed via
+//! push_tool_call / ToolResult.
+
+use super::*;
+use loom_bridge::BridgeState;
+use loom_core::agent::AgentBehavior;
+use loom_core::cognitive::{
+    CognitiveAgent, CognitiveConfig, LlmClient, LlmClientConfig, SimpleCognitiveLoop,
+    ThinkingStrategy,
+};
+use loom_core::proto::{AgentConfig, AgentState};
+use loom_proto::{ToolDescriptor, ToolError as ProtoToolError};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+use tokio_stream::wrappers::ReceiverStream;
+
+const TOOL_NAME: &str = "remote.quote";
+
+fn quote_descriptor() -> ToolDescriptor {
+    ToolDescriptor {
+        name: TOOL_NAME.into(),
+        description: "Look up a price quote".into(),
+        parameters_schema: r#"{"type":"object","properties":{"symbol":{"type":"string"}}}"#.into(),
+        provider: loom_proto::ProviderKind::ProviderGrpc as i32,
+        metadata: Default::default(),
+    }
+}
+
+/// The mock LLM asks for the remote tool, then answers from the tool observation
+fn scripted_reply(input: &str) -> String {
+    match input.find("Observation:") {
+        Some(idx) => {
+            let observation = input[idx + "Observation:".len()..]
+                .split("\n\n")
+                .next()
+                .unwrap_or("")
+                .trim();
+            format!("The remote quote tool reported: {}", observation)
+        }
+        None => format!(
+            "I need a quote. {{\"tool\": \"{}\", \"args\": {{\"symbol\": \"LOOM\"}}}}",
+            TOOL_NAME
+        ),
+    }
+}
+
+/// Register `agent_id` with the quote tool and answer its tool calls with `respond`
+async fn spawn_tool_agent<F>(
+    addr: SocketAddr,
+    agent_id: &str,
+    respond: F,
+) -> (Arc<AtomicUsize>, tokio::task::JoinHandle<()>)
+where
+    F: Fn(&ToolCall) -> ToolResult + Send + 'static,
+{
+    let mut client = new_client(addr).await;
+    let resp = client
+        .register_agent(AgentRegisterRequest {
+            agent_id: agent_id.into(),
+            subscribed_topics: vec![],
+            tools: vec![quote_descriptor()],
+            metadata: Default::default(),
+        })
+        .await
+        .unwrap()
+        .into_inner();
+    assert!(resp.success);
+
+    let (tx_client, rx_stream) = tokio::sync::mpsc::channel(16);
+    tx_client
+        .send(ClientEvent {
+            msg: Some(client_event::Msg::Ack(Ack {
+                message_id: agent_id.into(),
+            })),
+        })
+        .await
+        .unwrap();
+    let mut inbound = client
+        .event_stream(ReceiverStream::new(rx_stream))
+        .await
+        .unwrap()
+        .into_inner();
+
+    let calls = Arc::new(AtomicUsize::new(0));
+    let calls_seen = Arc::clone(&calls);
+    let handle = tokio::spawn(async move {
+        while let Some(Ok(msg)) = inbound.message().await.transpose() {
+            if let Some(server_event::Msg::ToolCall(call)) = msg.msg {
+                calls_seen.fetch_add(1, Ordering::SeqCst);
+                let result = respond(&call);
+                let _ = tx_client
+                    .send(ClientEvent {
+                        msg: Some(client_event::Msg::ToolResult(result)),
+                    })
+                    .await;
+            }
+        }
+    });
+    (calls, handle)
+}
+
+/// Start the Bridge, whose ToolRegistry picks up the tools `agent_id` registers
+async fn setup(
+    agent_id: &str,
+    respond: impl Fn(&ToolCall) -> ToolResult + Send + 'static,
+) -> (Arc<ToolRegistry>, Arc<AtomicUsize>) {
+    let event_bus = Arc::new(EventBus::new().await.unwrap());
+    event_bus.start().await.unwrap();
+    let tools = Arc::new(ToolRegistry::new());
+    let (addr, _handle, _svc) = start_test_server(event_bus, Arc::clone(&tools)).await;
+
+    let (calls, _agent) = spawn_tool_agent(addr, agent_id, respond).await;
+    (tools, calls)
+}
+
+async fn run_cognitive_agent(tools: Arc<ToolRegistry>, question: &str) -> String {
+    let base_url = start_mock_llm(scripted_reply).await;
+    let llm = Arc::new(
+        LlmClient::new(LlmClientConfig {
+            base_url,
+            model: "mock".into(),
+            api_key: None,
+            request_timeout_ms: 5_000,
+            temperature: 0.0,
+        })
+        .unwrap(),
+    );
+    let config = CognitiveConfig {
+        thinking_strategy: ThinkingStrategy::ReAct,
+        max_iterations: 3,
+        refine_after_tools: true,
+        ..Default::default()
+    };
+    let mut agent = CognitiveAgent::new(SimpleCognitiveLoop::new(config, llm, tools));
+    agent
+        .on_init(&AgentConfig {
+            agent_id: "thinker".into(),
+            agent_type: "cognitive".into(),
+            subscribed_topics: vec![],
+            capabilities: vec![],
+            parameters: Default::default(),
+        })
+        .await
+        .unwrap();
+
+    let mut state = AgentState {
+        agent_id: "thinker".into(),
+        persistent_state: vec![],
+        ephemeral_context: vec![],
+        last_update_ms: 0,
+        metadata: Default::default(),
+    };
+    let event = Event {
+        id: "question-1".into(),
+        r#type: "user.question".into(),
+        timestamp_ms: 0,
+        source: "tester".into(),
+        metadata: Default::default(),
+        payload: question.as_bytes().to_vec(),
+        confidence: 1.0,
+        tags: vec![],
+        priority: 50,
+    };
+    tokio::time::timeout(Duration::from_secs(10), agent.on_event(event, &mut state))
+        .await
+        .expect("cognitive cycle timed out")
+        .unwrap();
+
+    agent
+        .memory_buffer()
+        .recent(10)
+        .into_iter()
+        .find(|item| item.item_type == loom_core::cognitive::MemoryItemType::AgentResponse)
+        .map(|item| item.content.clone())
+        .expect("agent produced no answer")
+}
+
+#[tokio::test]
+async fn test_cognitive_agent_answers_with_remote_tool_result() {
+    let (tools, calls) = setup("quote-agent", |call| {
+        let args: serde_json::Value = serde_json::from_str(&call.arguments).unwrap();
+        assert_eq!(call.name, TOOL_NAME);
+        assert_eq!(args["symbol"], "LOOM");
+        ToolResult {
+            id: call.id.clone(),
+            status: ToolStatus::ToolOk as i32,
+            output: r#"{"symbol":"LOOM","price":42.5}"#.into(),
+            error: None,
+        }
+    })
+    .await;
+    assert_eq!(tools.list_tools().len(), 1);
+
+    let answer = run_cognitive_agent(tools, "What is the LOOM price?").await;
+
+    assert_eq!(calls.load(Ordering::SeqCst), 1);
+    assert!(
+        answer.contains("42.5"),
+        "answer should reference the remote result: {answer}"
+    );
+}
+
+#[tokio::test]
+async fn test_remote_tool_error_reaches_the_loop_as_failed_observation() {
+    let (tools, calls) = setup("failing-agent", |call| ToolResult {
+        id: call.id.clone(),
+        status: ToolStatus::ToolError as i32,
+        output: String::new(),
+        error: Some(ProtoToolError {
+            code: "UPSTREAM".into(),
+            message: "quote service unavailable".into(),
+            details: Default::default(),
+        }),
+    })
+    .await;
+
+    let answer = run_cognitive_agent(tools, "What is the LOOM price?").await;
+
+    assert_eq!(calls.load(Ordering::SeqCst), 1);
+    assert!(
+        answer.contains("quote service unavailable"),
+        "answer should reference the remote error: {answer}"
+    );
+}
+
+/// A local tool that answers with its own name
+struct LocalTool(&'static str);
+
+#[async_trait::async_trait]
+impl loom_core::Tool for LocalTool {
+    fn name(&self) -> String {
+        self.0.to_string()
+    }
+
+    fn description(&self) -> String {
+        "A local tool".into()
+    }
+
+    fn parameters(&self) -> serde_json::Value {
+        serde_json::json!({"type": "object"})
+    }
+
+    async fn call(
+        &self,
+        _arguments: serde_json::Value,
+    ) -> loom_core::tools::ToolResult<serde_json::Value> {
+        Ok(serde_json::json!("local"))
+    }
+}
+
+async fn register(
+    client: &mut BridgeClient<tonic::transport::Channel>,
+    agent_id: &str,
+    tools: Vec<ToolDescriptor>,
+    metadata: &[(&str, &str)],
+) {
+    let resp = client
+        .register_agent(AgentRegisterRequest {
+            agent_id: agent_id.into(),
+            subscribed_topics: vec![],
+            tools,
+            metadata: metadata
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect(),
+        })
+        .await
+        .unwrap()
+        .into_inner();
+    assert!(resp.success);
+}
+
+#[tokio::test]
+async fn test_agent_tools_never_shadow_and_are_withdrawn_on_reregistration() {
+    let event_bus = Arc::new(EventBus::new().await.unwrap());
+    let tools = Arc::new(ToolRegistry::new());
+    tools.register(Arc::new(LocalTool("local.echo"))).await;
+    let state = BridgeState::new(
+        event_bus,
+        Arc::clone(&tools),
+        Arc::new(AgentDirectory::new()),
+    );
+    let remote_tools = Arc::clone(&state.remote_tools);
+    let (addr, _handle, _svc) = start_test_server_with_state(state).await;
+    let mut client = new_client(addr).await;
+
+    let mut echo = quote_descriptor();
+    echo.name = "local.echo".into();
+    register(&mut client, "agent-a", vec![quote_descriptor(), echo], &[]).await;
+    assert_eq!(remote_tools.exposed("agent-a"), [TOOL_NAME]);
+    assert_eq!(
+        tools
+            .call("local.echo", serde_json::json!({}))
+            .await
+            .unwrap(),
+        "local"
+    );
+
+    // Another agent cannot take over the first agent's tool
+    register(&mut client, "agent-b", vec![quote_descriptor()], &[]).await;
+    assert!(remote_tools.exposed("agent-b").is_empty());
+
+    // Not connected: the call fails instead of waiting out the timeout
+    let err = tools
+        .call(TOOL_NAME, serde_json::json!({"symbol": "LOOM"}))
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("not connected"), "{err}");
+
+    register(&mut client, "agent-a", vec![], &[]).await;
+    assert!(tools.get(TOOL_NAME).is_none());
+    assert_eq!(tools.list_tools().len(), 1);
+}
+
+#[tokio::test]
+async fn test_namespaced_agent_tools_stay_in_their_namespace() {
+    let event_bus = Arc::new(EventBus::new().await.unwrap());
+    let tools = Arc::new(ToolRegistry::new());
+    let (addr, _handle, _svc) = start_test_server(event_bus, Arc::clone(&tools)).await;
+    let mut client = new_client(addr).await;
+
+    register(
+        &mut client,
+        "shop-agent",
+        vec![quote_descriptor()],
+        &[("namespace", "shop")],
+    )
+    .await;
+    let shop = loom_core::Namespace::new("shop").unwrap();
+    assert!(tools.get(TOOL_NAME).is_none());
+    assert!(tools.for_namespace(&shop).get(TOOL_NAME).is_some());
+
+    register(&mut client, "shop-agent", vec![], &[("namespace", "shop")]).await;
+    assert!(tools.for_namespace(&shop).get(TOOL_NAME).is_none());
+}
@@ -0,0 +1,161 @@
+use super::*;
+use loom_bridge::{BridgeState, ReplayConfig, ReplayOverflow, ReplayQueues};
+use loom_core::{AgentDirectory, EventBus, ToolRegistry};
+use tokio::time::{sleep, timeout, Duration};
+
+fn event(id: &str) -> Event {
+    Event {
+        id: id.into(),
+        r#type: "test".into(),
+        timestamp_ms: 0,
+        source: "tester".into(),
+        metadata: Default::default(),
+        payload: vec![],
+        confidence: 1.0,
+        tags: vec![],
+        priority: 50,
+    }
+}
+
+async fn start_with_replay(config: ReplayConfig) -> (SocketAddr, Arc<EventBus>, Arc<ReplayQueues>) {
+    let event_bus = Arc::new(EventBus::new().await.unwrap());
+    event_bus.start().await.unwrap();
+    let mut state = BridgeState::new(
+        Arc::clone(&event_bus),
+        Arc::new(ToolRegistry::new()),
+        Arc::new(AgentDirectory::new()),
+    );
+    state.set_replay_config(config);
+    let replay = Arc::clone(&state.replay);
+    let (addr, _handle, _svc) = start_test_server_with_state(state).await;
+    (addr, event_bus, replay)
+}
+
+async fn wait_for_parked(replay: &ReplayQueues, agent_id: &str, parked: bool) {
+    for _ in 0..100 {
+        if replay.is_parked(agent_id) == parked {
+            return;
+        }
+        sleep(Duration::from_millis(20)).await;
+    }
+    panic!("expected {agent_id} parked={parked}");
+}
+
+async fn recv_delivery_id(rx: &mut tonic::Streaming<loom_proto::ServerEvent>) -> String {
+    let msg = timeout(Duration::from_secs(2), rx.message())
+        .await
+        .expect("recv timed out")
+        .unwrap()
+        .unwrap();
+    match msg.msg {
+        Some(server_event::Msg::Delivery(del)) => del.event.unwrap().id,
+        other => panic!("Expected Delivery, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn test_reconnect_replays_missed_deliveries_in_order() {
+    let (addr, event_bus, replay) = start_with_replay(ReplayConfig {
+        ttl: Duration::from_secs(5),
+        ..Default::default()
+    })
+    .await;
+
+    let (tx, rx) = connect_agent(addr, "replayer", vec!["topic.replay".into()]).await;
+    drop(tx);
+    drop(rx);
+    wait_for_parked(&replay, "replayer", true).await;
+
+    for id in ["ev1", "ev2", "ev3"] {
+        event_bus.publish("topic.replay", event(id)).await.unwrap();
+    }
+    for _ in 0..100 {
+        if replay.stats().buffered == 3 {
+            break;
+        }
+        sleep(Duration::from_millis(20)).await;
+    }
+
+    let (_tx, mut rx) = connect_agent(addr, "replayer", vec!["topic.replay".into()]).await;
+    event_bus
+        .publish("topic.replay", event("live"))
+        .await
+        .unwrap();
+
+    for expected in ["ev1", "ev2", "ev3", "live"] {
+        assert_eq!(recv_delivery_id(&mut rx).await, expected);
+    }
+    assert!(!replay.is_parked("replayer"));
+    let stats = replay.stats();
+    assert_eq!(stats.replayed, 3);
+    assert_eq!(stats.dropped, 0);
+}
+
+#[tokio::test]
+async fn test_overflow_drops_oldest() {
+    let (addr, event_bus, replay) = start_with_replay(ReplayConfig {
+        ttl: Duration::from_secs(5),
+        capacity: 2,
+        overflow: ReplayOverflow::DropOldest,
+    })
+    .await;
+
+    let (tx, rx) = connect_agent(addr, "overflow", vec!["topic.overflow".into()]).await;
+    drop(tx);
+    drop(rx);
+    wait_for_parked(&replay, "overflow", true).await;
+
+    for id in ["ev1", "ev2", "ev3"] {
+        event_bus
+            .publish("topic.overflow", event(id))
+            .await
+            .unwrap();
+    }
+    for _ in 0..100 {
+        if replay.stats().dropped == 1 {
+            break;
+        }
+        sleep(Duration::from_millis(20)).await;
+    }
+
+    let (_tx, mut rx) = connect_agent(addr, "overflow", vec!["topic.overflow".into()]).await;
+    assert_eq!(recv_delivery_id(&mut rx).await, "ev2");
+    assert_eq!(recv_delivery_id(&mut rx).await, "ev3");
+    assert_eq!(replay.stats().dropped, 1);
+}
+
+#[tokio::test]
+async fn test_parked_agent_expires_after_ttl() {
+    let (addr, event_bus, replay) = start_with_replay(ReplayConfig {
+        ttl: Duration::from_millis(200),
+        ..Default::default()
+    })
+    .await;
+
+    let (tx, rx) = connect_agent(addr, "expiring", vec!["topic.expire".into()]).await;
+    drop(tx);
+    drop(rx);
+    wait_for_parked(&replay, "expiring", true).await;
+    event_bus
+        .publish("topic.expire", event("missed"))
+        .await
+        .unwrap();
+
+    wait_for_parked(&replay, "expiring", false).await;
+    let stats = replay.stats();
+    assert_eq!(stats.expired, 1);
+    assert_eq!(stats.replayed, 0);
+
+    // Expiry detaches the agent, releasing the shared subscription
+    for _ in 0..100 {
+        let active = event_bus
+            .get_stats("topic.expire")
+            .map(|s| s.active_subscriptions)
+            .unwrap_or(0);
+        if active == 0 {
+            return;
+        }
+        sleep(Duration::from_millis(20)).await;
+    }
+    panic!("subscription not released after expiry");
+}
@@ -87,3 +87,190 @@ async fn test_server_push_tool_call_and_client_reply() {
     let r = result.unwrap();
     assert_eq!(r.status, ToolStatus::ToolOk as i32);
 }
+
+fn pushed_call(id: &str) -> ToolCall {
+    ToolCall {
+        id: id.into(),
+        name: "agent.action".into(),
+        arguments: "{}".into(),
+        headers: Default::default(),
+        timeout_ms: 1000,
+        correlation_id: String::new(),
+        qos: 0,
+    }
+}
+
+#[tokio::test]
+async fn test_await_tool_result_resolves_on_reply() {
+    use tokio::time::Duration;
+
+    let event_bus = Arc::new(EventBus::new().await.unwrap());
+    let tool_registry = Arc::new(ToolRegistry::new());
+    event_bus.start().await.unwrap();
+    let (addr, _handle, svc) = start_test_server(event_bus.clone(), tool_registry.clone()).await;
+
+    let (tx_client, mut inbound) = connect_agent(addr, "agentAwait", vec![]).await;
+
+    // Agent replies after a short delay
+    tokio::spawn(async move {
+        while let Some(Ok(msg)) = inbound.message().await.transpose() {
+            if let Some(server_event::Msg::ToolCall(call)) = msg.msg {
+                tokio::time::sleep(Duration::from_millis(50)).await;
+                let _ = tx_client
+                    .send(ClientEvent {
+                        msg: Some(client_event::Msg::ToolResult(ToolResult {
+                            id: call.id,
+                            status: ToolStatus::ToolOk as i32,
+                            output: r#"{"result":"awaited"}"#.into(),
+                            error: None,
+                        })),
+                    })
+                    .await;
+            }
+        }
+    });
+
+    assert!(svc
+        .push_tool_call("agentAwait", pushed_call("await1"))
+        .await
+        .unwrap());
+    let result = svc
+        .await_tool_result("await1", Duration::from_secs(2))
+        .await
+        .expect("tool result");
+    assert_eq!(result.status, ToolStatus::ToolOk as i32);
+    assert_eq!(result.output, r#"{"result":"awaited"}"#);
+
+    // Consumed by the waiter, not left in the polling map
+    assert!(svc.get_tool_result("await1").is_none());
+}
+
+#[tokio::test]
+async fn test_await_tool_result_times_out() {
+    use tokio::time::Duration;
+
+    let event_bus = Arc::new(EventBus::new().await.unwrap());
+    let tool_registry = Arc::new(ToolRegistry::new());
+    event_bus.start().await.unwrap();
+    let (addr, _handle, svc) = start_test_server(event_bus.clone(), tool_registry.clone()).await;
+
+    // Agent connected but never replies
+    let (_tx_client, _inbound) = connect_agent(addr, "agentSilent", vec![]).await;
+
+    assert!(svc
+        .push_tool_call("agentSilent", pushed_call("silent1"))
+        .await
+        .unwrap());
+    let err = svc
+        .await_tool_result("silent1", Duration::from_millis(100))
+        .await
+        .unwrap_err();
+    assert!(matches!(err, loom_bridge::BridgeError::Timeout(_)));
+}
+
+#[tokio::test]
+async fn test_await_tool_result_fails_on_agent_disconnect() {
+    use tokio::time::Duration;
+
+    let event_bus = Arc::new(EventBus::new().await.unwrap());
+    let tool_registry = Arc::new(ToolRegistry::new());
+    event_bus.start().await.unwrap();
+    let (addr, _handle, svc) = start_test_server(event_bus.clone(), tool_registry.clone()).await;
+
+    let (tx_client, inbound) = connect_agent(addr, "agentGone", vec![]).await;
+
+    assert!(svc
+        .push_tool_call("agentGone", pushed_call("gone1"))
+        .await
+        .unwrap());
+
+    // Close the client stream shortly after the call was pushed
+    tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        drop(tx_client);
+        drop(inbound);
+    });
+
+    let err = svc
+        .await_tool_result("gone1", Duration::from_secs(5))
+        .await
+        .unwrap_err();
+    match err {
+        loom_bridge::BridgeError::AgentDisconnected(agent) => assert_eq!(agent, "agentGone"),
+        other => panic!("expected AgentDisconnected, got {other:?}"),
+    }
+}
+
+async fn next_push(inbound: &mut tonic::Streaming<loom_proto::ServerEvent>) -> server_event::Msg {
+    tokio::time::timeout(std::time::Duration::from_secs(2), inbound.message())
+        .await
+        .expect("recv timed out")
+        .unwrap()
+        .unwrap()
+        .msg
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_cancelled_tool_call_reaches_the_agent() {
+    use loom_core::{CancellationToken, ToolError};
+    use loom_proto::ToolDescriptor;
+    use tokio::time::{timeout, Duration};
+
+    let event_bus = Arc::new(EventBus::new().await.unwrap());
+    let tool_registry = Arc::new(ToolRegistry::new());
+    event_bus.start().await.unwrap();
+    let (addr, _handle, svc) = start_test_server(event_bus.clone(), tool_registry.clone()).await;
+
+    // Agent accepts the call but never replies
+    let (_tx_client, mut inbound) = connect_agent(addr, "agentSlow", vec![]).await;
+
+    let remote = ToolRegistry::new();
+    remote
+        .register(Arc::new(
+            loom_bridge::RemoteAgentTool::new(
+                svc.clone(),
+                "agentSlow",
+                ToolDescriptor {
+                    name: "slow.search".into(),
+                    description: "Never finishes".into(),
+                    parameters_schema: "{}".into(),
+                    ..Default::default()
+                },
+            )
+            .with_timeout(Duration::from_secs(10)),
+        ))
+        .await;
+
+    let cancel = CancellationToken::new();
+    let call = {
+        let cancel = cancel.clone();
+        tokio::spawn(async move {
+            remote
+                .call_with_cancel("slow.search", serde_json::json!({}), cancel)
+                .await
+        })
+    };
+
+    let call_id = match next_push(&mut inbound).await {
+        server_event::Msg::ToolCall(call) => call.id,
+        other => panic!("Expected ToolCall, got {:?}", other),
+    };
+
+    cancel.cancel();
+    let result = timeout(Duration::from_secs(1), call)
+        .await
+        .expect("call not cancelled")
+        .unwrap();
+    assert!(matches!(result, Err(ToolError::Cancelled)));
+
+    match next_push(&mut inbound).await {
+        server_event::Msg::ToolCancel(cancel) => {
+            assert_eq!(cancel.id, call_id);
+            assert_eq!(cancel.reason, "cancelled by caller");
+        }
+        other => panic!("Expected ToolCancel, got {:?}", other),
+    }
+    // Nothing left pending: a second cancel is a no-op
+    assert!(!svc.cancel_tool_call(&call_id, "again"));
+}
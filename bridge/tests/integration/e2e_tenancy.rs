@@ -0,0 +1,258 @@
+use super::*;
+use loom_core::messaging::envelope::keys;
+use loom_core::tenancy::TENANT_KEY;
+use loom_core::{EnvelopeSigner, TenantRegistry, ToolRegistry};
+use loom_proto::QoSLevel;
+use tokio::time::{sleep, timeout, Duration};
+use tonic::{Code, Request};
+
+fn authed<T>(message: T, token: &str) -> Request<T> {
+    let mut request = Request::new(message);
+    request
+        .metadata_mut()
+        .insert("authorization", format!("Bearer {token}").parse().unwrap());
+    request
+}
+
+fn registration(agent_id: &str, topics: &[&str]) -> AgentRegisterRequest {
+    AgentRegisterRequest {
+        agent_id: agent_id.into(),
+        subscribed_topics: topics.iter().map(|t| t.to_string()).collect(),
+        tools: vec![],
+        metadata: Default::default(),
+    }
+}
+
+/// Register with `token` and open the event stream
+async fn connect_tenant_agent(
+    addr: SocketAddr,
+    token: &str,
+    agent_id: &str,
+    topics: &[&str],
+) -> (
+    tokio::sync::mpsc::Sender<ClientEvent>,
+    tonic::Streaming<loom_proto::ServerEvent>,
+) {
+    let mut client = new_client(addr).await;
+    let resp = client
+        .register_agent(authed(registration(agent_id, topics), token))
+        .await
+        .unwrap()
+        .into_inner();
+    assert!(resp.success, "{}", resp.error_message);
+
+    let (tx_client, rx_stream) = tokio::sync::mpsc::channel(16);
+    tx_client
+        .send(ClientEvent {
+            msg: Some(client_event::Msg::Ack(Ack {
+                message_id: agent_id.into(),
+            })),
+        })
+        .await
+        .unwrap();
+    let rx = client
+        .event_stream(authed(
+            tokio_stream::wrappers::ReceiverStream::new(rx_stream),
+            token,
+        ))
+        .await
+        .unwrap()
+        .into_inner();
+    (tx_client, rx)
+}
+
+#[tokio::test]
+async fn test_tenants_are_authenticated_and_isolated() {
+    let event_bus = Arc::new(EventBus::new().await.unwrap());
+    event_bus.start().await.unwrap();
+    let tenants = TenantRegistry::from_toml(
+        r#"
+        [[tenants]]
+        id = "acme"
+        tokens = ["acme-secret"]
+        max_agents = 1
+
+        [[tenants]]
+        id = "globex"
+        tokens = ["globex-secret"]
+        "#,
+    )
+    .unwrap();
+    let mut state = loom_bridge::BridgeState::new(
+        Arc::clone(&event_bus),
+        Arc::new(ToolRegistry::new()),
+        Arc::new(AgentDirectory::new()),
+    );
+    state.set_tenants(Arc::new(tenants));
+    let (addr, _handle, _svc) = start_test_server_with_state(state.clone()).await;
+
+    // No or unknown token
+    let mut client = new_client(addr).await;
+    let err = client
+        .register_agent(registration("anon", &["chat"]))
+        .await
+        .unwrap_err();
+    assert_eq!(err.code(), Code::Unauthenticated);
+    let err = client
+        .register_agent(authed(registration("anon", &["chat"]), "wrong"))
+        .await
+        .unwrap_err();
+    assert_eq!(err.code(), Code::Unauthenticated);
+
+    let (tx_acme, mut rx_acme) = connect_tenant_agent(addr, "acme-secret", "a1", &["chat"]).await;
+    let (_tx_globex, mut rx_globex) =
+        connect_tenant_agent(addr, "globex-secret", "g1", &["chat"]).await;
+    assert_eq!(
+        state.subscriptions.get("a1").unwrap().clone(),
+        vec!["tenant.acme.chat".to_string()]
+    );
+    let info = state.agent_directory.get("a1").unwrap();
+    assert_eq!(
+        info.metadata.get(TENANT_KEY).map(String::as_str),
+        Some("acme")
+    );
+
+    // Agent ids stay with their tenant; acme is at its agent quota
+    let err = client
+        .register_agent(authed(registration("a1", &["chat"]), "globex-secret"))
+        .await
+        .unwrap_err();
+    assert_eq!(err.code(), Code::PermissionDenied);
+    let resp = client
+        .register_agent(authed(registration("a2", &["chat"]), "acme-secret"))
+        .await
+        .unwrap()
+        .into_inner();
+    assert!(!resp.success);
+
+    // A publish on "chat" reaches acme's agents only, with the plain topic
+    for _ in 0..100 {
+        if event_bus
+            .get_stats("tenant.globex.chat")
+            .is_some_and(|s| s.active_subscriptions == 1)
+        {
+            break;
+        }
+        sleep(Duration::from_millis(20)).await;
+    }
+    tx_acme
+        .send(ClientEvent {
+            msg: Some(client_event::Msg::Publish(Publish {
+                topic: "chat".into(),
+                event: Some(Event {
+                    id: "ev-acme".into(),
+                    r#type: "test".into(),
+                    timestamp_ms: 0,
+                    source: "a1".into(),
+                    metadata: Default::default(),
+                    payload: vec![],
+                    confidence: 1.0,
+                    tags: vec![],
+                    priority: 50,
+                }),
+            })),
+        })
+        .await
+        .unwrap();
+
+    let msg = timeout(Duration::from_secs(2), rx_acme.message())
+        .await
+        .expect("recv timed out")
+        .unwrap()
+        .unwrap();
+    match msg.msg {
+        Some(server_event::Msg::Delivery(del)) => {
+            assert_eq!(del.topic, "chat");
+            let event = del.event.unwrap();
+            assert_eq!(event.id, "ev-acme");
+            assert_eq!(
+                event.metadata.get(TENANT_KEY).map(String::as_str),
+                Some("acme")
+            );
+        }
+        other => panic!("Expected Delivery, got {:?}", other),
+    }
+    assert!(
+        timeout(Duration::from_millis(300), rx_globex.message())
+            .await
+            .is_err(),
+        "globex must not see acme's events"
+    );
+}
+
+#[tokio::test]
+async fn test_namespaced_agents_sign_their_relative_topics() {
+    let signer = Arc::new(EnvelopeSigner::new("deployment-key"));
+    let mut event_bus = EventBus::new().await.unwrap();
+    event_bus.set_signer(Arc::clone(&signer));
+    let event_bus = Arc::new(event_bus);
+    event_bus.start().await.unwrap();
+    let tenants = TenantRegistry::from_toml(
+        r#"
+        [[tenants]]
+        id = "acme"
+        tokens = ["acme-secret"]
+        "#,
+    )
+    .unwrap();
+    let mut state = loom_bridge::BridgeState::new(
+        Arc::clone(&event_bus),
+        Arc::new(ToolRegistry::new()),
+        Arc::new(AgentDirectory::new()),
+    );
+    state.set_tenants(Arc::new(tenants));
+    let (addr, _handle, _svc) = start_test_server_with_state(state).await;
+    let (_sub, mut bus_rx) = event_bus
+        .subscribe(
+            "tenant.acme.system.control".into(),
+            vec![],
+            QoSLevel::QosBatched,
+        )
+        .await
+        .unwrap();
+    let (tx_acme, _rx_acme) = connect_tenant_agent(addr, "acme-secret", "a1", &[]).await;
+
+    let event = |id: &str| Event {
+        id: id.into(),
+        r#type: "system.shutdown".into(),
+        timestamp_ms: 0,
+        source: "a1".into(),
+        metadata: Default::default(),
+        payload: b"now".to_vec(),
+        confidence: 1.0,
+        tags: vec![],
+        priority: 50,
+    };
+    let publish = |event: Event| ClientEvent {
+        msg: Some(client_event::Msg::Publish(Publish {
+            topic: "system.control".into(),
+            event: Some(event),
+        })),
+    };
+    // The agent signs the topic it publishes to; `system.*` still requires a signature
+    // inside its namespace
+    let mut signed = event("signed");
+    signer.sign("system.control", &mut signed);
+    tx_acme.send(publish(event("unsigned"))).await.unwrap();
+    tx_acme.send(publish(signed)).await.unwrap();
+
+    let delivered = timeout(Duration::from_secs(2), bus_rx.recv())
+        .await
+        .expect("recv timed out")
+        .unwrap();
+    assert_eq!(delivered.id, "signed");
+    assert_eq!(
+        delivered
+            .metadata
+            .get(keys::SIGNATURE_VERIFIED)
+            .map(String::as_str),
+        Some("true")
+    );
+    assert_eq!(
+        event_bus
+            .get_stats("tenant.acme.system.control")
+            .unwrap()
+            .signature_rejected,
+        1
+    );
+}
@@ -0,0 +1,122 @@
+use super::*;
+use loom_bridge::{AclRule, TopicAcl};
+use loom_core::ToolRegistry;
+use tokio::time::{timeout, Duration};
+
+fn publish(topic: &str, id: &str) -> ClientEvent {
+    ClientEvent {
+        msg: Some(client_event::Msg::Publish(Publish {
+            topic: topic.into(),
+            event: Some(Event {
+                id: id.into(),
+                r#type: "test".into(),
+                timestamp_ms: 0,
+                source: "tester".into(),
+                metadata: Default::default(),
+                payload: vec![],
+                confidence: 1.0,
+                tags: vec![],
+                priority: 50,
+            }),
+        })),
+    }
+}
+
+async fn next_message(rx: &mut tonic::Streaming<loom_proto::ServerEvent>) -> server_event::Msg {
+    timeout(Duration::from_secs(2), rx.message())
+        .await
+        .expect("recv timed out")
+        .unwrap()
+        .unwrap()
+        .msg
+        .unwrap()
+}
+
+#[test]
+fn test_acl_rules_prefer_the_most_specific_match() {
+    let acl = TopicAcl::from_toml(
+        r#"
+        [[rules]]
+        deny = ["system.*"]
+
+        [[rules]]
+        agent = "ops"
+        allow = ["system.*"]
+
+        [[rules]]
+        token = "sandbox-token"
+        allow = ["sandbox.*"]
+        "#,
+    )
+    .unwrap();
+    assert_eq!(acl.rules().len(), 3);
+
+    assert!(acl.is_allowed("worker", None, "chat"));
+    assert!(!acl.is_allowed("worker", None, "system.shutdown"));
+    assert!(acl.is_allowed("ops", None, "system.shutdown"));
+
+    // The token's allow list restricts it to its own topics
+    assert!(acl.is_allowed("worker", Some("sandbox-token"), "sandbox.run"));
+    assert!(!acl.is_allowed("worker", Some("sandbox-token"), "chat"));
+    assert!(!acl.is_allowed("worker", Some("sandbox-token"), "system.shutdown"));
+
+    // Within one level deny beats allow
+    let acl =
+        TopicAcl::new().with_rule(AclRule::for_agent("a1").with_allow("*").with_deny("secret"));
+    assert!(acl.is_allowed("a1", None, "public"));
+    assert!(acl.check("a1", None, "secret").is_err());
+    assert!(TopicAcl::from_toml("[[rules]]\nallow = 1\n").is_err());
+}
+
+#[tokio::test]
+async fn test_denied_publish_returns_error_event() {
+    let event_bus = Arc::new(EventBus::new().await.unwrap());
+    event_bus.start().await.unwrap();
+    let mut state = loom_bridge::BridgeState::new(
+        Arc::clone(&event_bus),
+        Arc::new(ToolRegistry::new()),
+        Arc::new(AgentDirectory::new()),
+    );
+    state.set_topic_acl(Arc::new(
+        TopicAcl::new()
+            .with_rule(AclRule::any().with_deny("system.*"))
+            .with_rule(AclRule::for_agent("ops").with_allow("system.*")),
+    ));
+    let (addr, _handle, _svc) = start_test_server_with_state(state).await;
+
+    let (tx_worker, mut rx_worker) =
+        connect_agent(addr, "worker", vec!["system.alerts".into(), "work".into()]).await;
+    let (tx_ops, _rx_ops) = connect_agent(addr, "ops", vec![]).await;
+
+    // Rejected publish: the agent gets an error and nothing is delivered
+    tx_worker
+        .send(publish("system.alerts", "ev-denied"))
+        .await
+        .unwrap();
+    match next_message(&mut rx_worker).await {
+        server_event::Msg::Err(err) => {
+            assert_eq!(err.code, "PUBLISH_DENIED");
+            assert!(err.message.contains("system.alerts"));
+        }
+        other => panic!("Expected Err, got {:?}", other),
+    }
+
+    tx_worker.send(publish("work", "ev-work")).await.unwrap();
+    match next_message(&mut rx_worker).await {
+        server_event::Msg::Delivery(del) => assert_eq!(del.event.unwrap().id, "ev-work"),
+        other => panic!("Expected Delivery, got {:?}", other),
+    }
+
+    // The agent-specific allow overrides the deny for everyone
+    tx_ops
+        .send(publish("system.alerts", "ev-ops"))
+        .await
+        .unwrap();
+    match next_message(&mut rx_worker).await {
+        server_event::Msg::Delivery(del) => {
+            assert_eq!(del.topic, "system.alerts");
+            assert_eq!(del.event.unwrap().id, "ev-ops");
+        }
+        other => panic!("Expected Delivery, got {:?}", other),
+    }
+}
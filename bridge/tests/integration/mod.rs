@@ -3,7 +3,6 @@
 use std::net::SocketAddr;
 use std::sync::Arc;
 
-use loom_bridge::BridgeService;
 use loom_core::{AgentDirectory, EventBus, ToolRegistry};
 use loom_proto::bridge_server::BridgeServer;
 use tokio::net::TcpListener;
@@ -11,8 +10,7 @@ use tokio_stream::wrappers::TcpListenerStream;
 
 pub use loom_proto::{
     bridge_client::BridgeClient, client_event, server_event, Ack, AgentRegisterRequest,
-    ClientEvent, Delivery, Event, HeartbeatRequest, HeartbeatResponse, Publish, ToolCall,
-    ToolResult, ToolStatus,
+    ClientEvent, Event, Publish, ToolCall, ToolResult, ToolStatus,
 };
 
 /// Start a Bridge gRPC server on an ephemeral localhost port and return the bound address
@@ -25,11 +23,23 @@ pub async fn start_test_server(
     loom_bridge::BridgeService,
 ) {
     let agent_directory = Arc::new(AgentDirectory::new());
-    let svc = loom_bridge::BridgeService::new(loom_bridge::BridgeState::new(
+    start_test_server_with_state(loom_bridge::BridgeState::new(
         Arc::clone(&event_bus),
         Arc::clone(&tool_registry),
         agent_directory,
-    ));
+    ))
+    .await
+}
+
+/// Start a Bridge gRPC server for a pre-configured `BridgeState`
+pub async fn start_test_server_with_state(
+    state: loom_bridge::BridgeState,
+) -> (
+    SocketAddr,
+    tokio::task::JoinHandle<()>,
+    loom_bridge::BridgeService,
+) {
+    let svc = loom_bridge::BridgeService::new(state);
     let svc_for_return = svc.clone();
 
     // Bind to 127.0.0.1:0 for an ephemeral port
@@ -58,6 +68,102 @@ pub async fn new_client(addr: SocketAddr) -> BridgeClient<tonic::transport::Chan
         .expect("connect client")
 }
 
+/// Register `agent_id` with `topics` and open its event stream (Ack handshake queued first)
+pub async fn connect_agent(
+    addr: SocketAddr,
+    agent_id: &str,
+    topics: Vec<String>,
+) -> (
+    tokio::sync::mpsc::Sender<ClientEvent>,
+    tonic::Streaming<loom_proto::ServerEvent>,
+) {
+    let mut client = new_client(addr).await;
+    let resp = client
+        .register_agent(AgentRegisterRequest {
+            agent_id: agent_id.into(),
+            subscribed_topics: topics,
+            tools: vec![],
+            metadata: Default::default(),
+        })
+        .await
+        .unwrap()
+        .into_inner();
+    assert!(resp.success);
+
+    let (tx_client, rx_stream) = tokio::sync::mpsc::channel(16);
+    tx_client
+        .send(ClientEvent {
+            msg: Some(client_event::Msg::Ack(Ack {
+                message_id: agent_id.into(),
+            })),
+        })
+        .await
+        .unwrap();
+    let rx = client
+        .event_stream(tokio_stream::wrappers::ReceiverStream::new(rx_stream))
+        .await
+        .unwrap()
+        .into_inner();
+    (tx_client, rx)
+}
+
+/// `delivered` without the envelope metadata `EventBus::publish` added to `published`,
+/// for comparing the two
+pub fn as_published(mut delivered: Event, published: &Event) -> Event {
+    for (_, key, _) in loom_proto::conventions::keys::ALL {
+        if !published.metadata.contains_key(*key) {
+            delivered.metadata.remove(*key);
+        }
+    }
+    delivered
+}
+
+/// Serve a scripted OpenAI Responses endpoint on an ephemeral port and return its base URL.
+/// `reply` maps the request's `input` text to the assistant's `output_text`.
+pub async fn start_mock_llm<F>(reply: F) -> String
+where
+    F: Fn(&str) -> String + Clone + Send + Sync + 'static,
+{
+    use axum::{routing::post, Json, Router};
+
+    let app = Router::new().route(
+        "/responses",
+        post(move |Json(body): Json<serde_json::Value>| {
+            let reply = reply.clone();
+            async move {
+                let input = body.get("input").and_then(|v| v.as_str()).unwrap_or("");
+                Json(serde_json::json!({
+                    "model": "mock",
+                    "output_text": reply(input),
+                }))
+            }
+        }),
+    );
+    let listener = TcpListener::bind((std::net::Ipv4Addr::LOCALHOST, 0))
+        .await
+        .expect("bind mock llm listener");
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.expect("mock llm exited");
+    });
+    format!("http://{}", addr)
+}
+
 mod e2e_basic;
+mod e2e_batch;
+mod e2e_blobs;
+mod e2e_discovery;
+mod e2e_fanout;
+mod e2e_format;
 mod e2e_forward_action;
+mod e2e_guaranteed;
+mod e2e_liveness;
+mod e2e_loadgen;
+mod e2e_payload;
+mod e2e_peer;
+mod e2e_rate_limit;
+mod e2e_remote_tool_loop;
+mod e2e_replay;
 mod e2e_server_push;
+mod e2e_tenancy;
+mod e2e_topic_acl;
@@ -0,0 +1,207 @@
+use loom_bridge::liveness::AGENT_ID_HEADER;
+use loom_bridge::{BridgeService, BridgeState, LivenessChange, LivenessConfig, LivenessTracker};
+use loom_core::dashboard::{DashboardEventType, EventBroadcaster};
+use loom_core::{AgentDirectory, AgentStatus, EventBus, ToolRegistry};
+use loom_proto::{bridge_server::Bridge, AgentRegisterRequest, HeartbeatRequest};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::time::Instant;
+use tonic::Request;
+
+fn config(interval_ms: u64, degraded_after: u32, disconnect_after: u32) -> LivenessConfig {
+    LivenessConfig {
+        heartbeat_interval: Duration::from_millis(interval_ms),
+        degraded_after,
+        disconnect_after,
+        unregister: false,
+    }
+}
+
+async fn service(config: LivenessConfig) -> (BridgeService, BridgeState, EventBroadcaster) {
+    let event_bus = Arc::new(EventBus::new().await.unwrap());
+    let mut state = BridgeState::new(
+        event_bus,
+        Arc::new(ToolRegistry::new()),
+        Arc::new(AgentDirectory::new()),
+    );
+    let broadcaster = EventBroadcaster::new(16);
+    state.set_dashboard_broadcaster(broadcaster.clone());
+    state.set_liveness_config(config);
+    (BridgeService::new(state.clone()), state, broadcaster)
+}
+
+async fn register(svc: &BridgeService, agent_id: &str) {
+    let resp = svc
+        .register_agent(Request::new(AgentRegisterRequest {
+            agent_id: agent_id.into(),
+            subscribed_topics: vec!["topic.live".into()],
+            tools: vec![],
+            metadata: Default::default(),
+        }))
+        .await
+        .unwrap()
+        .into_inner();
+    assert!(resp.success);
+}
+
+fn heartbeat_from(agent_id: &str) -> Request<HeartbeatRequest> {
+    let mut request = Request::new(HeartbeatRequest { timestamp_ms: 7 });
+    request
+        .metadata_mut()
+        .insert(AGENT_ID_HEADER, agent_id.parse().unwrap());
+    request
+}
+
+#[test]
+fn test_tracker_degrades_then_declares_dead() {
+    let interval = Duration::from_secs(60);
+    let tracker = LivenessTracker::new(config(60_000, 2, 4));
+    tracker.register("b");
+    tracker.register("a");
+    let start = Instant::now();
+
+    assert!(tracker.check(start + interval).is_empty());
+    assert_eq!(
+        tracker.check(start + interval * 2 + Duration::from_secs(1)),
+        vec![
+            LivenessChange::Degraded {
+                agent_id: "a".into(),
+                missed: 2
+            },
+            LivenessChange::Degraded {
+                agent_id: "b".into(),
+                missed: 2
+            },
+        ]
+    );
+    // Each change is reported once
+    assert!(tracker.check(start + interval * 3).is_empty());
+
+    assert_eq!(tracker.touch("b"), Some(true));
+    assert_eq!(tracker.touch("b"), Some(false));
+    assert_eq!(tracker.touch("unknown"), None);
+    assert!(tracker.is_degraded("a"));
+    assert!(!tracker.is_degraded("b"));
+
+    let changes = tracker.check(start + interval * 5);
+    assert_eq!(changes.len(), 2);
+    assert_eq!(
+        changes[0],
+        LivenessChange::Dead {
+            agent_id: "a".into(),
+            missed: 5
+        }
+    );
+    // Dead agents are no longer tracked
+    assert!(!tracker.is_tracked("a"));
+    assert_eq!(tracker.touch("a"), None);
+    assert!(tracker.check(start + interval * 10).is_empty());
+
+    let stats = tracker.stats();
+    assert_eq!(stats.tracked_agents, 0);
+    assert_eq!(stats.degraded, 2);
+    assert_eq!(stats.recovered, 1);
+    assert_eq!(stats.dead, 2);
+}
+
+#[test]
+fn test_tracker_stream_replaced_by_newer_one() {
+    let tracker = LivenessTracker::new(config(60_000, 2, 4));
+    let (old_id, old_cancel) = tracker.watch_stream("agent");
+    let (new_id, new_cancel) = tracker.watch_stream("agent");
+
+    // The old stream ending does not stop tracking the agent's new stream
+    tracker.stream_closed("agent", old_id);
+    assert!(tracker.is_tracked("agent"));
+    assert!(tracker.close_stream("agent"));
+    assert!(new_cancel.is_cancelled());
+    assert!(!old_cancel.is_cancelled());
+
+    tracker.stream_closed("agent", new_id);
+    assert!(!tracker.is_tracked("agent"));
+    assert!(!tracker.close_stream("agent"));
+}
+
+#[test]
+fn test_disabled_config_reports_nothing() {
+    let tracker = LivenessTracker::new(config(0, 2, 4));
+    assert!(!tracker.config().enabled());
+    tracker.register("agent");
+    assert!(tracker
+        .check(Instant::now() + Duration::from_secs(3600))
+        .is_empty());
+}
+
+#[tokio::test]
+async fn test_heartbeat_with_agent_id_recovers_degraded_agent() {
+    let (svc, state, broadcaster) = service(config(40, 2, 1000)).await;
+    let mut events = broadcaster.subscribe();
+    register(&svc, "slow").await;
+    let _ = events.try_recv(); // AgentRegistered
+
+    tokio::time::sleep(Duration::from_millis(120)).await;
+    let changes = state.check_liveness();
+    assert_eq!(changes.len(), 1);
+    assert!(matches!(changes[0], LivenessChange::Degraded { .. }));
+    assert_eq!(changes[0].agent_id(), "slow");
+    assert_eq!(
+        state.agent_directory.get("slow").unwrap().status,
+        AgentStatus::Degraded
+    );
+    let event = events.try_recv().unwrap();
+    assert!(matches!(
+        event.event_type,
+        DashboardEventType::AgentDegraded
+    ));
+    assert_eq!(event.sender.as_deref(), Some("slow"));
+
+    let resp = svc
+        .heartbeat(heartbeat_from("slow"))
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(resp.timestamp_ms, 7);
+    assert_eq!(
+        state.agent_directory.get("slow").unwrap().status,
+        AgentStatus::Active
+    );
+    let event = events.try_recv().unwrap();
+    assert!(matches!(
+        event.event_type,
+        DashboardEventType::AgentRecovered
+    ));
+    assert_eq!(state.liveness.stats().recovered, 1);
+}
+
+#[tokio::test]
+async fn test_dead_agent_without_stream_is_unregistered() {
+    let mut dead_config = config(20, 1, 2);
+    dead_config.unregister = true;
+    let (svc, state, broadcaster) = service(dead_config).await;
+    let mut events = broadcaster.subscribe();
+    register(&svc, "ghost").await;
+    let _ = events.try_recv(); // AgentRegistered
+
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    let changes = state.check_liveness();
+    assert_eq!(changes.len(), 1);
+    assert!(matches!(changes[0], LivenessChange::Dead { .. }));
+    assert_eq!(changes[0].agent_id(), "ghost");
+    assert!(state.agent_directory.get("ghost").is_none());
+    assert!(!state.subscriptions.contains_key("ghost"));
+    let event = events.try_recv().unwrap();
+    assert!(matches!(
+        event.event_type,
+        DashboardEventType::AgentUnregistered
+    ));
+    assert!(event.payload_preview.contains("missed"));
+
+    // A heartbeat for the dead agent tells it to register again
+    let status = svc.heartbeat(heartbeat_from("ghost")).await.unwrap_err();
+    assert_eq!(status.code(), tonic::Code::NotFound);
+    // Anonymous heartbeats still just echo
+    assert!(svc
+        .heartbeat(Request::new(HeartbeatRequest { timestamp_ms: 1 }))
+        .await
+        .is_ok());
+}
@@ -0,0 +1,43 @@
+use loom_bridge::BridgeState;
+use loom_core::{
+    AgentDirectory, EventBus, MemoryComponent, MemoryGovernor, MemoryGovernorConfig, PressureLevel,
+    ToolRegistry,
+};
+use loom_proto::{ToolResult, ToolStatus};
+use std::sync::Arc;
+
+fn tool_result(id: &str) -> ToolResult {
+    ToolResult {
+        id: id.to_string(),
+        status: ToolStatus::ToolOk as i32,
+        output: "x".repeat(200),
+        error: None,
+    }
+}
+
+#[tokio::test]
+async fn test_unclaimed_tool_results_are_shed_under_pressure() {
+    let event_bus = Arc::new(EventBus::new().await.unwrap());
+    let agent_directory = Arc::new(AgentDirectory::new());
+    let tool_registry = Arc::new(ToolRegistry::new());
+    let state = BridgeState::new(event_bus, tool_registry, agent_directory);
+
+    for i in 0..10 {
+        let id = format!("call-{i}");
+        state.tool_results.insert(id.clone(), tool_result(&id));
+    }
+    let usage = state.memory_usage().await;
+    assert!(usage > 10 * 200);
+
+    // Cap the component at roughly half its usage
+    let governor = MemoryGovernor::new(
+        MemoryGovernorConfig::default().with_component_cap("bridge.tool_results", usage / 2),
+    );
+    governor.register(Arc::new(state.clone()));
+    let report = governor.check().await;
+
+    assert_eq!(report.level, PressureLevel::Normal);
+    assert!(state.memory_usage().await <= usage / 2);
+    assert!(state.tool_results.len() < 10);
+    assert!(!state.tool_results.is_empty());
+}
@@ -2,11 +2,13 @@
 ///
 /// Tests the full stack: Bridge MemoryHandler → Core InMemoryMemory
 use loom_bridge::memory_handler::MemoryHandler;
-use loom_bridge::trading_memory::InMemoryMemory;
+use loom_bridge::trading_memory::{InMemoryMemory, RecordFilter, RocksDbMemory, TradingMemory};
+use loom_core::context::{MemoryReader, MemoryWriter};
 use loom_core::proto::{
     memory_service_client::MemoryServiceClient, memory_service_server::MemoryServiceServer,
-    CheckDuplicateRequest, CheckExecutedRequest, ExecutionRecord, GetExecutionStatsRequest,
-    GetRecentPlansRequest, MarkExecutedRequest, PlanRecord, SavePlanRequest,
+    AggregateExecutionsRequest, CheckDuplicateRequest, CheckExecutedRequest, Event,
+    ExecutionRecord, GetExecutionStatsRequest, GetRecentPlansRequest, MarkExecutedRequest,
+    PlanRecord, SavePlanRequest, TradingQuery,
 };
 use std::sync::Arc;
 use tokio::time::{sleep, Duration};
@@ -19,7 +21,8 @@ async fn start_test_service() -> (
     String,
 ) {
     let memory_store = InMemoryMemory::new();
-    let memory_handler = MemoryHandler::new(Arc::clone(&memory_store));
+    let handler_store: Arc<dyn TradingMemory> = memory_store.clone();
+    let memory_handler = MemoryHandler::new(handler_store);
 
     // Use a unique port per test based on PID and current time
     let port = 50052
@@ -196,6 +199,8 @@ async fn test_memory_service_execution_tracking() {
         order_id: "order-123".to_string(),
         order_size_usdt: 100.0,
         error_message: String::new(),
+        pnl_usdt: 0.0,
+        strategy: String::new(),
     };
 
     let mark_req = Request::new(MarkExecutedRequest {
@@ -253,6 +258,8 @@ async fn test_memory_service_execution_stats() {
             order_id: format!("order-{}", i),
             order_size_usdt: 100.0,
             error_message: String::new(),
+            pnl_usdt: 0.0,
+            strategy: String::new(),
         };
 
         client
@@ -371,3 +378,241 @@ async fn test_memory_service_session_isolation() {
 
     server_handle.abort();
 }
+
+fn execution(
+    timestamp_ms: i64,
+    symbol: &str,
+    strategy: &str,
+    status: &str,
+    pnl_usdt: f64,
+) -> ExecutionRecord {
+    ExecutionRecord {
+        timestamp_ms,
+        plan_hash: format!("{}-{}", symbol, timestamp_ms),
+        symbol: symbol.to_string(),
+        action: "BUY".to_string(),
+        confidence: 0.8,
+        status: status.to_string(),
+        executed: status == "success",
+        order_id: String::new(),
+        order_size_usdt: 100.0,
+        error_message: String::new(),
+        pnl_usdt,
+        strategy: strategy.to_string(),
+    }
+}
+
+#[tokio::test]
+async fn test_memory_service_queries_and_aggregates() {
+    let (memory_store, server_handle, endpoint) = start_test_service().await;
+
+    let channel = tonic::transport::Channel::from_shared(endpoint)
+        .expect("Invalid endpoint")
+        .connect()
+        .await
+        .expect("Failed to connect");
+
+    let mut client = MemoryServiceClient::new(channel);
+
+    // A plan whose strategy executions without one inherit
+    let mut metadata = std::collections::HashMap::new();
+    metadata.insert("strategy".to_string(), "breakout".to_string());
+    memory_store
+        .save_plan(SavePlanRequest {
+            session_id: "query-test".to_string(),
+            plan: Some(PlanRecord {
+                timestamp_ms: 500,
+                symbol: "SOL".to_string(),
+                action: "BUY".to_string(),
+                confidence: 0.9,
+                reasoning: String::new(),
+                plan_hash: "SOL-5000".to_string(),
+                method: "llm".to_string(),
+                metadata,
+            }),
+        })
+        .unwrap();
+
+    for record in [
+        execution(1000, "BTC", "momentum", "success", 12.5),
+        execution(2000, "ETH", "momentum", "error", -4.0),
+        execution(3000, "BTC", "mean_reversion", "success", 3.0),
+        execution(4000, "BTC", "momentum", "success", 1.5),
+        execution(5000, "SOL", "", "success", 7.0),
+    ] {
+        client
+            .mark_executed(Request::new(MarkExecutedRequest {
+                session_id: "query-test".to_string(),
+                plan_hash: record.plan_hash.clone(),
+                execution: Some(record),
+            }))
+            .await
+            .expect("MarkExecuted failed");
+    }
+
+    // Structured fields and the query string combine
+    let resp = client
+        .query_executions(Request::new(TradingQuery {
+            session_id: "query-test".to_string(),
+            symbols: vec!["btc".to_string()],
+            query: "from:1500 to:4000".to_string(),
+            ..Default::default()
+        }))
+        .await
+        .expect("QueryExecutions failed")
+        .into_inner();
+    assert_eq!(resp.executions.len(), 1);
+    assert_eq!(resp.executions[0].strategy, "mean_reversion");
+
+    // Newest first unless asked otherwise
+    let resp = client
+        .query_executions(Request::new(TradingQuery {
+            query: "session:query-test strategy:momentum limit:2".to_string(),
+            ..Default::default()
+        }))
+        .await
+        .expect("QueryExecutions failed")
+        .into_inner();
+    let times: Vec<i64> = resp.executions.iter().map(|e| e.timestamp_ms).collect();
+    assert_eq!(times, vec![4000, 2000]);
+
+    let resp = client
+        .aggregate_executions(Request::new(AggregateExecutionsRequest {
+            query: Some(TradingQuery {
+                session_id: "query-test".to_string(),
+                ..Default::default()
+            }),
+            group_by: "strategy".to_string(),
+        }))
+        .await
+        .expect("AggregateExecutions failed")
+        .into_inner();
+    let keys: Vec<&str> = resp.groups.iter().map(|g| g.key.as_str()).collect();
+    assert_eq!(keys, vec!["breakout", "mean_reversion", "momentum"]);
+    let momentum = &resp.groups[2];
+    assert_eq!(
+        (momentum.executions, momentum.successful, momentum.failed),
+        (3, 2, 1)
+    );
+    assert!((momentum.pnl_usdt - 10.0).abs() < 1e-9);
+    assert_eq!((momentum.first_ms, momentum.last_ms), (1000, 4000));
+    let total = resp.total.unwrap();
+    assert_eq!(total.executions, 5);
+    assert!((total.pnl_usdt - 20.0).abs() < 1e-9);
+
+    // Malformed queries are the caller's error
+    let err = client
+        .query_plans(Request::new(TradingQuery {
+            query: "symbol".to_string(),
+            ..Default::default()
+        }))
+        .await
+        .unwrap_err();
+    assert_eq!(err.code(), tonic::Code::InvalidArgument);
+    let err = client
+        .aggregate_executions(Request::new(AggregateExecutionsRequest {
+            query: None,
+            group_by: "weather".to_string(),
+        }))
+        .await
+        .unwrap_err();
+    assert_eq!(err.code(), tonic::Code::InvalidArgument);
+
+    server_handle.abort();
+}
+
+#[tokio::test]
+async fn test_rocksdb_memory_survives_reopen() {
+    let dir = tempfile::tempdir().unwrap();
+
+    {
+        let store = RocksDbMemory::open(dir.path()).unwrap();
+        store
+            .save_plan(SavePlanRequest {
+                session_id: "persist".to_string(),
+                plan: Some(PlanRecord {
+                    timestamp_ms: 1000,
+                    symbol: "BTC".to_string(),
+                    action: "BUY".to_string(),
+                    confidence: 0.8,
+                    reasoning: String::new(),
+                    plan_hash: "BTC-2000".to_string(),
+                    method: "rule".to_string(),
+                    metadata: Default::default(),
+                }),
+            })
+            .unwrap();
+        for record in [
+            execution(2000, "BTC", "", "success", 5.0),
+            execution(-3000, "ETH", "carry", "error", -1.0),
+        ] {
+            store
+                .mark_executed(MarkExecutedRequest {
+                    session_id: "persist".to_string(),
+                    plan_hash: record.plan_hash.clone(),
+                    execution: Some(record),
+                })
+                .unwrap();
+        }
+        store
+            .append_event(
+                "persist",
+                Event {
+                    id: "e1".to_string(),
+                    r#type: "market.tick".to_string(),
+                    timestamp_ms: 1500,
+                    source: "feed".to_string(),
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+    }
+
+    let store = RocksDbMemory::open(dir.path()).unwrap();
+    let plans = store
+        .get_recent_plans(GetRecentPlansRequest {
+            session_id: "persist".to_string(),
+            symbol: "BTC".to_string(),
+            limit: 10,
+        })
+        .unwrap();
+    assert_eq!(plans.plans.len(), 1);
+
+    let executed = store
+        .check_executed(CheckExecutedRequest {
+            session_id: "persist".to_string(),
+            plan_hash: "BTC-2000".to_string(),
+        })
+        .unwrap();
+    // The strategy came from the plan's method
+    assert_eq!(executed.execution.unwrap().strategy, "rule");
+
+    // Negative timestamps sort before positive ones
+    let all = store
+        .query_executions(&RecordFilter::session("persist").oldest_first())
+        .unwrap();
+    let times: Vec<i64> = all.iter().map(|e| e.timestamp_ms).collect();
+    assert_eq!(times, vec![-3000, 2000]);
+
+    // Records written after reopening do not overwrite earlier ones
+    store
+        .mark_executed(MarkExecutedRequest {
+            session_id: "persist".to_string(),
+            plan_hash: String::new(),
+            execution: Some(execution(2000, "BTC", "carry", "success", 1.0)),
+        })
+        .unwrap();
+    let stats = store
+        .get_execution_stats(GetExecutionStatsRequest {
+            session_id: "persist".to_string(),
+            symbol: "BTC".to_string(),
+        })
+        .unwrap();
+    assert_eq!(stats.total_executions, 2);
+
+    let summary = store.summarize_episode("persist").await.unwrap().unwrap();
+    assert!(summary.contains("market.tick from feed"));
+    assert_eq!(store.retrieve("TICK", 5, None).await.unwrap().len(), 1);
+    assert!(store.summarize_episode("other").await.unwrap().is_none());
+}
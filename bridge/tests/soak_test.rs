@@ -0,0 +1,62 @@
+//! Soak harness tests. Run with `cargo test -p loom-bridge --features soak --test soak_test`.
+#![cfg(feature = "soak")]
+
+use loom_bridge::soak::{run_soak, LeakDetector, ResourceSnapshot, SoakConfig};
+use std::time::Duration;
+
+fn snapshot(streams: usize, tasks: usize, rss_bytes: u64) -> ResourceSnapshot {
+    ResourceSnapshot {
+        bridge_streams: streams,
+        tasks,
+        rss_bytes: Some(rss_bytes),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_detector_uses_warmup_maxima_as_baseline() {
+    let mut detector = LeakDetector::new(2, 1_000);
+    assert!(detector.check(&snapshot(100, 100, 0)).is_empty());
+
+    detector.observe_warmup(&snapshot(3, 10, 5_000));
+    detector.observe_warmup(&snapshot(5, 8, 4_000));
+    let baseline = detector.baseline().unwrap();
+    assert_eq!(baseline.bridge_streams, 5);
+    assert_eq!(baseline.tasks, 10);
+    assert_eq!(baseline.rss_bytes, Some(5_000));
+
+    // Within slack
+    assert!(detector.check(&snapshot(7, 12, 6_000)).is_empty());
+
+    let violations = detector.check(&snapshot(8, 40, 6_001));
+    let gauges: Vec<&str> = violations.iter().map(|v| v.gauge).collect();
+    assert_eq!(gauges, vec!["bridge_streams", "tasks", "rss_bytes"]);
+    assert_eq!(violations[1].baseline, 10);
+    assert_eq!(violations[1].current, 40);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn test_short_soak_run_exercises_pipeline_without_leaks() {
+    let report = run_soak(SoakConfig {
+        duration: Duration::from_secs(6),
+        warmup: Duration::from_secs(3),
+        sample_interval: Duration::from_millis(500),
+        agents: 2,
+        clients: 3,
+        topics: 2,
+        publish_rate: 100,
+        count_slack: 32,
+        rss_growth_bytes: 256 * 1024 * 1024,
+        fail_fast: false,
+    })
+    .await
+    .unwrap();
+
+    assert!(report.samples >= 10, "samples: {}", report.samples);
+    assert!(report.events_published > 0);
+    assert!(report.events_handled > 0);
+    assert!(report.deliveries > 0);
+    assert!(report.client_sessions >= 3);
+    assert!(report.tool_calls_ok > 0);
+    assert!(report.passed(), "violations: {:?}", report.violations);
+}
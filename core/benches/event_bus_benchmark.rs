@@ -50,7 +50,7 @@ fn bench_single_publisher(c: &mut Criterion) {
                         // Consumer task
                         let consumer = tokio::spawn(async move {
                             let mut received = 0;
-                            while let Some(_) = rx.recv().await {
+                            while rx.recv().await.is_some() {
                                 received += 1;
                                 if received >= count {
                                     break;
@@ -102,7 +102,7 @@ fn bench_concurrent_publishers(c: &mut Criterion) {
                         let total = pubs * events_per_publisher;
                         let consumer = tokio::spawn(async move {
                             let mut received = 0;
-                            while let Some(_) = rx.recv().await {
+                            while rx.recv().await.is_some() {
                                 received += 1;
                                 if received >= total {
                                     break;
@@ -154,6 +154,8 @@ fn bench_qos_levels(c: &mut Criterion) {
             QoSLevel::QosRealtime => "realtime",
             QoSLevel::QosBatched => "batched",
             QoSLevel::QosBackground => "background",
+            // Need acks (subscribe_reliable / subscribe_guaranteed); not comparable here
+            QoSLevel::QosReliable | QoSLevel::QosGuaranteed => continue,
         };
 
         group.throughput(Throughput::Elements(event_count as u64));
@@ -175,7 +177,7 @@ fn bench_qos_levels(c: &mut Criterion) {
                         // Consumer
                         let consumer = tokio::spawn(async move {
                             let mut received = 0;
-                            while let Some(_) = rx.recv().await {
+                            while rx.recv().await.is_some() {
                                 received += 1;
                                 if received >= event_count {
                                     break;
@@ -253,7 +255,7 @@ fn bench_multiple_subscribers(c: &mut Criterion) {
 
                             consumers.push(tokio::spawn(async move {
                                 let mut received = 0;
-                                while let Some(_) = rx.recv().await {
+                                while rx.recv().await.is_some() {
                                     received += 1;
                                     if received >= event_count {
                                         break;
@@ -303,7 +305,7 @@ fn bench_event_filtering(c: &mut Criterion) {
 
                 let consumer = tokio::spawn(async move {
                     let mut received = 0;
-                    while let Some(_) = rx.recv().await {
+                    while rx.recv().await.is_some() {
                         received += 1;
                         if received >= event_count {
                             break;
@@ -340,7 +342,7 @@ fn bench_event_filtering(c: &mut Criterion) {
 
                 let consumer = tokio::spawn(async move {
                     let mut received = 0;
-                    while let Some(_) = rx.recv().await {
+                    while rx.recv().await.is_some() {
                         received += 1;
                         if received >= event_count / 2 {
                             break;
@@ -57,6 +57,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             env: None,
             cwd: None,
             protocol_version: None, // Use latest supported version
+            ..Default::default()
         },
         // Example 2: Brave Search (requires API key)
         // Uncomment and add your API key to use:
@@ -75,6 +76,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             }),
             cwd: None,
             protocol_version: None,
+            ..Default::default()
         },
         */
     ];
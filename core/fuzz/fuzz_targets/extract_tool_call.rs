@@ -0,0 +1,12 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use loom_core::cognitive::extract_tool_call;
+
+fuzz_target!(|text: &str| {
+    if let Some(call) = extract_tool_call(text) {
+        // A call is only produced from a JSON object somewhere in the text
+        assert!(text.contains('{') && text.contains('}'));
+        let _ = call.arguments.to_string();
+    }
+});
@@ -0,0 +1,13 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use loom_core::cognitive::llm::parse_tool_calls_from_responses;
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(v) = serde_json::from_slice::<serde_json::Value>(data) else {
+        return;
+    };
+    for call in parse_tool_calls_from_responses(&v) {
+        assert!(!call.name.is_empty());
+    }
+});
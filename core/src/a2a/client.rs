@@ -0,0 +1,307 @@
+//! A2A client and the `a2a:delegate` tool
+
+use super::{AgentCard, Message, Task, TaskIdParams, TaskSendParams, TaskState};
+use crate::tools::{Tool, ToolError, ToolResult};
+use crate::{EventBus, LoomError, Result};
+use async_trait::async_trait;
+use serde::de::DeserializeOwned;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{debug, warn};
+
+/// JSON-RPC client for the task endpoint of an A2A agent
+#[derive(Clone)]
+pub struct A2aClient {
+    http_client: reqwest::Client,
+    request_seq: Arc<AtomicU64>,
+}
+
+impl Default for A2aClient {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl A2aClient {
+    pub fn new() -> Self {
+        Self::with_timeout(Duration::from_secs(120))
+    }
+
+    /// Client whose HTTP requests time out after `timeout`; `tasks/send` waits for the
+    /// remote agent, so this bounds how long a synchronous task may run
+    pub fn with_timeout(timeout: Duration) -> Self {
+        let http_client = reqwest::Client::builder()
+            .timeout(timeout)
+            .user_agent("loom-agent/0.1")
+            .build()
+            .unwrap_or_else(|_| reqwest::Client::new());
+        Self {
+            http_client,
+            request_seq: Arc::new(AtomicU64::new(1)),
+        }
+    }
+
+    /// Fetch `<agent_url>/.well-known/agent.json`
+    pub async fn card(&self, agent_url: &str) -> Result<AgentCard> {
+        let url = format!("{}/.well-known/agent.json", agent_url.trim_end_matches('/'));
+        let resp = self
+            .http_client
+            .get(&url)
+            .send()
+            .await
+            .map_err(|e| LoomError::A2aError(format!("GET {url} failed: {e}")))?;
+        if !resp.status().is_success() {
+            return Err(LoomError::A2aError(format!(
+                "GET {url} returned {}",
+                resp.status()
+            )));
+        }
+        resp.json()
+            .await
+            .map_err(|e| LoomError::A2aError(format!("invalid agent card: {e}")))
+    }
+
+    pub async fn send_task(&self, agent_url: &str, params: &TaskSendParams) -> Result<Task> {
+        self.rpc(agent_url, "tasks/send", serde_json::to_value(params)?)
+            .await
+    }
+
+    pub async fn get_task(&self, agent_url: &str, task_id: &str) -> Result<Task> {
+        let params = TaskIdParams {
+            id: task_id.to_string(),
+        };
+        self.rpc(agent_url, "tasks/get", serde_json::to_value(params)?)
+            .await
+    }
+
+    pub async fn cancel_task(&self, agent_url: &str, task_id: &str) -> Result<Task> {
+        let params = TaskIdParams {
+            id: task_id.to_string(),
+        };
+        self.rpc(agent_url, "tasks/cancel", serde_json::to_value(params)?)
+            .await
+    }
+
+    async fn rpc<T: DeserializeOwned>(
+        &self,
+        agent_url: &str,
+        method: &str,
+        params: Value,
+    ) -> Result<T> {
+        let id = self.request_seq.fetch_add(1, Ordering::Relaxed);
+        let body = json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": params });
+        debug!(target: "a2a", url = %agent_url, method = %method, "A2A request");
+
+        let resp = self
+            .http_client
+            .post(agent_url)
+            .json(&body)
+            .send()
+            .await
+            .map_err(|e| LoomError::A2aError(format!("{method} to {agent_url} failed: {e}")))?;
+        if !resp.status().is_success() {
+            return Err(LoomError::A2aError(format!(
+                "{method} to {agent_url} returned {}",
+                resp.status()
+            )));
+        }
+        let mut reply: Value = resp
+            .json()
+            .await
+            .map_err(|e| LoomError::A2aError(format!("invalid {method} response: {e}")))?;
+
+        if let Some(error) = reply.get("error").filter(|e| !e.is_null()) {
+            return Err(LoomError::A2aError(format!(
+                "{method} failed ({}): {}",
+                error["code"],
+                error["message"].as_str().unwrap_or_default()
+            )));
+        }
+        serde_json::from_value(reply["result"].take())
+            .map_err(|e| LoomError::A2aError(format!("invalid {method} result: {e}")))
+    }
+}
+
+static TASK_SEQ: AtomicU64 = AtomicU64::new(0);
+
+fn next_task_id() -> String {
+    format!(
+        "a2a-{:x}{:04x}",
+        chrono::Utc::now().timestamp_millis(),
+        TASK_SEQ.fetch_add(1, Ordering::Relaxed) & 0xffff
+    )
+}
+
+/// Tool that delegates a task to an external A2A agent and waits for its result
+///
+/// The task id doubles as the Envelope thread id: pass the caller's `thread_id` to keep
+/// the delegated task on the same thread. With an event bus, every state change observed
+/// is published as an `a2a.task.<state>` event on `thread.<task id>.broadcast`.
+pub struct A2aDelegateTool {
+    client: A2aClient,
+    event_bus: Option<Arc<EventBus>>,
+    timeout: Duration,
+    poll_interval: Duration,
+}
+
+impl Default for A2aDelegateTool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl A2aDelegateTool {
+    pub fn new() -> Self {
+        Self {
+            client: A2aClient::new(),
+            event_bus: None,
+            timeout: Duration::from_secs(120),
+            poll_interval: Duration::from_millis(500),
+        }
+    }
+
+    pub fn with_client(mut self, client: A2aClient) -> Self {
+        self.client = client;
+        self
+    }
+
+    pub fn with_event_bus(mut self, event_bus: Arc<EventBus>) -> Self {
+        self.event_bus = Some(event_bus);
+        self
+    }
+
+    /// How long to poll a task that is still working after `tasks/send` returned
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    pub fn with_poll_interval(mut self, poll_interval: Duration) -> Self {
+        self.poll_interval = poll_interval;
+        self
+    }
+
+    async fn publish(&self, task: &Task) {
+        let Some(ref event_bus) = self.event_bus else {
+            return;
+        };
+        if let Err(e) = event_bus
+            .publish(&task.thread_topic(), task.lifecycle_event("a2a.delegate"))
+            .await
+        {
+            warn!(target: "a2a", task_id = %task.id, error = %e, "Failed to publish task event");
+        }
+    }
+}
+
+fn a2a_error(e: LoomError) -> ToolError {
+    ToolError::ExecutionFailed(e.to_string())
+}
+
+#[async_trait]
+impl Tool for A2aDelegateTool {
+    fn name(&self) -> String {
+        "a2a:delegate".to_string()
+    }
+
+    fn description(&self) -> String {
+        "Delegate a task to an external A2A-compliant agent and return its result".to_string()
+    }
+
+    fn parameters(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "agent_url": {
+                    "type": "string",
+                    "description": "A2A task endpoint of the remote agent (the `url` of its agent card)"
+                },
+                "message": {
+                    "type": "string",
+                    "description": "Task for the remote agent"
+                },
+                "thread_id": {
+                    "type": "string",
+                    "description": "Thread to map the task onto; used as the task id (default: new thread)"
+                },
+                "session_id": {
+                    "type": "string",
+                    "description": "A2A session grouping related tasks"
+                }
+            },
+            "required": ["agent_url", "message"]
+        })
+    }
+
+    fn output_schema(&self) -> Option<Value> {
+        Some(json!({
+            "type": "object",
+            "properties": {
+                "task_id": { "type": "string" },
+                "thread_id": { "type": "string" },
+                "state": { "type": "string" },
+                "text": { "type": "string" },
+                "task": { "type": "object" }
+            },
+            "required": ["task_id", "thread_id", "state", "text", "task"]
+        }))
+    }
+
+    async fn call(&self, arguments: Value) -> ToolResult<Value> {
+        let agent_url = arguments["agent_url"]
+            .as_str()
+            .ok_or_else(|| ToolError::InvalidArguments("Missing 'agent_url'".to_string()))?;
+        let message = arguments["message"]
+            .as_str()
+            .ok_or_else(|| ToolError::InvalidArguments("Missing 'message'".to_string()))?;
+        let task_id = arguments["thread_id"]
+            .as_str()
+            .map(str::to_string)
+            .unwrap_or_else(next_task_id);
+
+        let params = TaskSendParams {
+            id: task_id.clone(),
+            session_id: arguments["session_id"].as_str().map(str::to_string),
+            message: Message::user(message),
+            metadata: HashMap::new(),
+        };
+        let mut task = self
+            .client
+            .send_task(agent_url, &params)
+            .await
+            .map_err(a2a_error)?;
+        self.publish(&task).await;
+
+        let deadline = tokio::time::Instant::now() + self.timeout;
+        while !task.status.state.is_terminal() && task.status.state != TaskState::InputRequired {
+            if tokio::time::Instant::now() + self.poll_interval > deadline {
+                if let Err(e) = self.client.cancel_task(agent_url, &task_id).await {
+                    warn!(target: "a2a", task_id = %task_id, error = %e, "Failed to cancel timed out task");
+                }
+                return Err(ToolError::Timeout);
+            }
+            tokio::time::sleep(self.poll_interval).await;
+            let polled = self
+                .client
+                .get_task(agent_url, &task_id)
+                .await
+                .map_err(a2a_error)?;
+            let changed = polled.status.state != task.status.state;
+            task = polled;
+            if changed {
+                self.publish(&task).await;
+            }
+        }
+
+        Ok(json!({
+            "task_id": task.id,
+            "thread_id": task_id,
+            "state": task.status.state.as_str(),
+            "text": task.output_text(),
+            "task": task,
+        }))
+    }
+}
@@ -0,0 +1,273 @@
+//! A2A (agent-to-agent protocol) support
+//!
+//! `A2aServer` publishes the agents in the `AgentDirectory` as A2A agent cards and
+//! accepts JSON-RPC task requests for them (`tasks/send`, `tasks/get`, `tasks/cancel`).
+//! A task is delivered to the agent as a `user.query` event on its private topic with
+//! `expect_reply=true`, exactly like the OpenAI facade; the agent's `agent.response`
+//! completes the task with a text artifact.
+//!
+//! `A2aDelegateTool` (`a2a:delegate`) is the other direction: a Loom agent sends a task
+//! to an external A2A agent through `A2aClient` and polls it to a final state.
+//!
+//! Both sides map a task onto an Envelope thread whose thread id is the task id. Every
+//! state change is published as an `a2a.task.<state>` event (payload: the `Task` as
+//! JSON) on the thread's broadcast topic, `thread.<task id>.broadcast`.
+
+pub mod client;
+pub mod server;
+
+pub use client::{A2aClient, A2aDelegateTool};
+pub use server::{A2aConfig, A2aServer};
+
+use crate::{Envelope, Event, ThreadTopicKind};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// Prefix of the task lifecycle event types (`a2a.task.working`, ...)
+pub const TASK_EVENT_PREFIX: &str = "a2a.task.";
+
+/// Lifecycle state of a task
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum TaskState {
+    Submitted,
+    Working,
+    InputRequired,
+    Completed,
+    Canceled,
+    Failed,
+    #[serde(other)]
+    Unknown,
+}
+
+impl TaskState {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            TaskState::Submitted => "submitted",
+            TaskState::Working => "working",
+            TaskState::InputRequired => "input-required",
+            TaskState::Completed => "completed",
+            TaskState::Canceled => "canceled",
+            TaskState::Failed => "failed",
+            TaskState::Unknown => "unknown",
+        }
+    }
+
+    /// Completed, canceled and failed tasks never change state again
+    pub fn is_terminal(self) -> bool {
+        matches!(
+            self,
+            TaskState::Completed | TaskState::Canceled | TaskState::Failed
+        )
+    }
+}
+
+/// Content of a message or artifact; only text parts are produced by Loom
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum Part {
+    Text {
+        text: String,
+    },
+    Data {
+        data: Value,
+    },
+    /// File or other part types, kept as a placeholder
+    #[serde(other)]
+    Unsupported,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Message {
+    /// `user` or `agent`
+    pub role: String,
+    pub parts: Vec<Part>,
+}
+
+impl Message {
+    pub fn user(text: impl Into<String>) -> Self {
+        Self {
+            role: "user".to_string(),
+            parts: vec![Part::Text { text: text.into() }],
+        }
+    }
+
+    pub fn agent(text: impl Into<String>) -> Self {
+        Self {
+            role: "agent".to_string(),
+            parts: vec![Part::Text { text: text.into() }],
+        }
+    }
+
+    /// Text parts joined by newlines
+    pub fn text(&self) -> String {
+        parts_text(&self.parts)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TaskStatus {
+    pub state: TaskState,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message: Option<Message>,
+    /// RFC 3339
+    #[serde(default)]
+    pub timestamp: String,
+}
+
+impl TaskStatus {
+    pub fn new(state: TaskState, message: Option<Message>) -> Self {
+        Self {
+            state,
+            message,
+            timestamp: chrono::Utc::now().to_rfc3339(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Artifact {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    pub parts: Vec<Part>,
+    #[serde(default)]
+    pub index: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Task {
+    pub id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub session_id: Option<String>,
+    pub status: TaskStatus,
+    #[serde(default)]
+    pub artifacts: Vec<Artifact>,
+    #[serde(default)]
+    pub history: Vec<Message>,
+    #[serde(default)]
+    pub metadata: HashMap<String, Value>,
+}
+
+impl Task {
+    pub fn new(id: impl Into<String>, session_id: Option<String>) -> Self {
+        Self {
+            id: id.into(),
+            session_id,
+            status: TaskStatus::new(TaskState::Submitted, None),
+            artifacts: vec![],
+            history: vec![],
+            metadata: HashMap::new(),
+        }
+    }
+
+    /// Text of the artifacts, or of the status message if there are none
+    pub fn output_text(&self) -> String {
+        if self.artifacts.is_empty() {
+            return self
+                .status
+                .message
+                .as_ref()
+                .map(Message::text)
+                .unwrap_or_default();
+        }
+        self.artifacts
+            .iter()
+            .map(|a| parts_text(&a.parts))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// `a2a.task.<state>` event for this task on its Envelope thread
+    pub fn lifecycle_event(&self, sender: &str) -> Event {
+        let mut event = Event {
+            id: format!("evt_a2a_{}_{}", self.id, self.status.state.as_str()),
+            r#type: format!("{TASK_EVENT_PREFIX}{}", self.status.state.as_str()),
+            timestamp_ms: chrono::Utc::now().timestamp_millis(),
+            source: sender.to_string(),
+            metadata: HashMap::new(),
+            payload: serde_json::to_vec(self).unwrap_or_default(),
+            confidence: 1.0,
+            tags: vec!["a2a".to_string()],
+            priority: 50,
+        };
+        Envelope::new(self.id.clone(), sender).attach_to_event(&mut event);
+        event
+    }
+
+    /// Topic the lifecycle events are published on
+    pub fn thread_topic(&self) -> String {
+        ThreadTopicKind::Broadcast.topic(&self.id)
+    }
+}
+
+/// Parameters of `tasks/send`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TaskSendParams {
+    pub id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub session_id: Option<String>,
+    pub message: Message,
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub metadata: HashMap<String, Value>,
+}
+
+/// Parameters of `tasks/get` and `tasks/cancel`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TaskIdParams {
+    pub id: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AgentCapabilities {
+    #[serde(default)]
+    pub streaming: bool,
+    #[serde(default)]
+    pub push_notifications: bool,
+    #[serde(default)]
+    pub state_transition_history: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AgentSkill {
+    pub id: String,
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+/// Self-description an A2A agent serves at `.well-known/agent.json`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AgentCard {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// JSON-RPC endpoint for tasks
+    pub url: String,
+    pub version: String,
+    pub capabilities: AgentCapabilities,
+    #[serde(default)]
+    pub default_input_modes: Vec<String>,
+    #[serde(default)]
+    pub default_output_modes: Vec<String>,
+    #[serde(default)]
+    pub skills: Vec<AgentSkill>,
+}
+
+fn parts_text(parts: &[Part]) -> String {
+    parts
+        .iter()
+        .filter_map(|part| match part {
+            Part::Text { text } => Some(text.as_str()),
+            _ => None,
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
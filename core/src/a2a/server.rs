@@ -0,0 +1,468 @@
+//! A2A server: agent cards and JSON-RPC task endpoints for Loom agents
+//!
+//! Routes:
+//! - `GET /a2a/agents`: cards of every agent in the `AgentDirectory`
+//! - `GET /a2a/{agent_id}/.well-known/agent.json`: card of one agent
+//! - `POST /a2a/{agent_id}`: JSON-RPC 2.0 `tasks/send`, `tasks/get`, `tasks/cancel`
+//!
+//! `tasks/send` answers once the task reaches a final state: `completed` with the agent's
+//! response as a text artifact, or `failed` when no agent received the query or it did not
+//! answer within the timeout. Tasks are kept in memory, oldest evicted first.
+//!
+//! `loom-bridge-server` serves the endpoints when `LOOM_A2A=true`. Env overrides for
+//! `A2aConfig::from_env()`:
+//! - LOOM_A2A_HOST (default 127.0.0.1)
+//! - LOOM_A2A_PORT (default 8099)
+//! - LOOM_A2A_URL (base URL advertised in agent cards; default `http://<host>:<port>`)
+//! - LOOM_A2A_TIMEOUT_MS (default 60000)
+
+use super::{
+    AgentCapabilities, AgentCard, AgentSkill, Artifact, Message, Task, TaskIdParams,
+    TaskSendParams, TaskState, TaskStatus,
+};
+use crate::cognitive::{RESPONSE_EVENT, RESPONSE_FINAL_EVENT};
+use crate::messaging::collab::is_correlated;
+use crate::openai::QUERY_EVENT;
+use crate::{agent_reply_topic, AgentDirectory, AgentInfo, Envelope, Event, EventBus, QoSLevel};
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    routing::{get, post},
+    Json, Router,
+};
+use dashmap::DashMap;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tracing::{info, warn};
+
+/// JSON-RPC error codes
+pub const METHOD_NOT_FOUND: i64 = -32601;
+pub const INVALID_PARAMS: i64 = -32602;
+pub const TASK_NOT_FOUND: i64 = -32001;
+pub const TASK_NOT_CANCELABLE: i64 = -32002;
+
+/// Sender of the events the server publishes
+const SENDER: &str = "a2a";
+
+/// Server configuration
+#[derive(Debug, Clone)]
+pub struct A2aConfig {
+    pub host: String,
+    pub port: u16,
+    /// Base URL advertised in agent cards; `None` derives it from host and port
+    pub public_url: Option<String>,
+    /// How long `tasks/send` waits for the agent's response
+    pub timeout: Duration,
+    /// Tasks kept for `tasks/get`; the oldest are evicted first
+    pub max_tasks: usize,
+}
+
+impl Default for A2aConfig {
+    fn default() -> Self {
+        Self {
+            host: "127.0.0.1".to_string(),
+            port: 8099,
+            public_url: None,
+            timeout: Duration::from_secs(60),
+            max_tasks: 1_000,
+        }
+    }
+}
+
+impl A2aConfig {
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        Self {
+            host: std::env::var("LOOM_A2A_HOST").unwrap_or(defaults.host),
+            port: std::env::var("LOOM_A2A_PORT")
+                .ok()
+                .and_then(|p| p.parse().ok())
+                .unwrap_or(defaults.port),
+            public_url: std::env::var("LOOM_A2A_URL").ok().filter(|u| !u.is_empty()),
+            timeout: std::env::var("LOOM_A2A_TIMEOUT_MS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .map(Duration::from_millis)
+                .unwrap_or(defaults.timeout),
+            max_tasks: defaults.max_tasks,
+        }
+    }
+
+    /// Whether `LOOM_A2A` asks for the endpoints to be served
+    pub fn enabled() -> bool {
+        std::env::var("LOOM_A2A")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(false)
+    }
+
+    /// Base URL of the server, without a trailing slash
+    pub fn base_url(&self) -> String {
+        match self.public_url {
+            Some(ref url) => url.trim_end_matches('/').to_string(),
+            None => format!("http://{}:{}", self.host, self.port),
+        }
+    }
+}
+
+/// Tasks by id, bounded by `max_tasks`
+struct TaskStore {
+    tasks: DashMap<String, Task>,
+    order: Mutex<VecDeque<String>>,
+    max_tasks: usize,
+}
+
+impl TaskStore {
+    fn new(max_tasks: usize) -> Self {
+        Self {
+            tasks: DashMap::new(),
+            order: Mutex::new(VecDeque::new()),
+            max_tasks: max_tasks.max(1),
+        }
+    }
+
+    fn get(&self, id: &str) -> Option<Task> {
+        self.tasks.get(id).map(|t| t.clone())
+    }
+
+    fn put(&self, task: Task) {
+        let id = task.id.clone();
+        if self.tasks.insert(id.clone(), task).is_some() {
+            return;
+        }
+        let mut order = self.order.lock().unwrap();
+        order.push_back(id);
+        while order.len() > self.max_tasks {
+            if let Some(evicted) = order.pop_front() {
+                self.tasks.remove(&evicted);
+            }
+        }
+    }
+}
+
+#[derive(Clone)]
+struct A2aState {
+    config: Arc<A2aConfig>,
+    event_bus: Arc<EventBus>,
+    directory: Arc<AgentDirectory>,
+    tasks: Arc<TaskStore>,
+}
+
+/// A2A HTTP server for the agents in an `AgentDirectory`
+pub struct A2aServer {
+    config: A2aConfig,
+    event_bus: Arc<EventBus>,
+    directory: Arc<AgentDirectory>,
+}
+
+impl A2aServer {
+    pub fn new(
+        config: A2aConfig,
+        event_bus: Arc<EventBus>,
+        directory: Arc<AgentDirectory>,
+    ) -> Self {
+        Self {
+            config,
+            event_bus,
+            directory,
+        }
+    }
+
+    /// Routes of the server, for serving on an existing listener
+    pub fn router(self) -> Router {
+        let state = A2aState {
+            tasks: Arc::new(TaskStore::new(self.config.max_tasks)),
+            config: Arc::new(self.config),
+            event_bus: self.event_bus,
+            directory: self.directory,
+        };
+        Router::new()
+            .route("/a2a/agents", get(agents_handler))
+            .route("/a2a/:agent_id/.well-known/agent.json", get(card_handler))
+            .route("/a2a/:agent_id", post(rpc_handler))
+            .with_state(state)
+    }
+
+    /// Start the server on the configured address
+    pub async fn serve(self) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let addr = format!("{}:{}", self.config.host, self.config.port);
+        let base_url = self.config.base_url();
+        let listener = tokio::net::TcpListener::bind(&addr).await?;
+        info!(target: "a2a", url = %format!("{}/a2a", base_url), "A2A endpoints ready");
+        axum::serve(listener, self.router()).await?;
+        Ok(())
+    }
+}
+
+/// Card for a directory entry; `description` and `version` come from its metadata and
+/// each capability becomes a skill
+pub fn agent_card(info: &AgentInfo, base_url: &str) -> AgentCard {
+    AgentCard {
+        name: info.agent_id.clone(),
+        description: info.metadata.get("description").cloned(),
+        url: format!("{}/a2a/{}", base_url.trim_end_matches('/'), info.agent_id),
+        version: info
+            .metadata
+            .get("version")
+            .cloned()
+            .unwrap_or_else(|| env!("CARGO_PKG_VERSION").to_string()),
+        capabilities: AgentCapabilities {
+            streaming: false,
+            push_notifications: false,
+            state_transition_history: true,
+        },
+        default_input_modes: vec!["text".to_string()],
+        default_output_modes: vec!["text".to_string()],
+        skills: info
+            .capabilities
+            .iter()
+            .map(|capability| AgentSkill {
+                id: capability.clone(),
+                name: capability.clone(),
+                description: None,
+                tags: vec![],
+            })
+            .collect(),
+    }
+}
+
+async fn agents_handler(State(state): State<A2aState>) -> Json<Vec<AgentCard>> {
+    let base_url = state.config.base_url();
+    Json(
+        state
+            .directory
+            .all()
+            .iter()
+            .map(|info| agent_card(info, &base_url))
+            .collect(),
+    )
+}
+
+async fn card_handler(
+    State(state): State<A2aState>,
+    Path(agent_id): Path<String>,
+) -> Result<Json<AgentCard>, StatusCode> {
+    let info = state
+        .directory
+        .get(&agent_id)
+        .ok_or(StatusCode::NOT_FOUND)?;
+    Ok(Json(agent_card(&info, &state.config.base_url())))
+}
+
+#[derive(Debug, Deserialize)]
+struct RpcRequest {
+    #[serde(default)]
+    id: Value,
+    method: String,
+    #[serde(default)]
+    params: Value,
+}
+
+#[derive(Debug)]
+struct RpcError {
+    code: i64,
+    message: String,
+}
+
+impl RpcError {
+    fn new(code: i64, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+        }
+    }
+}
+
+fn params<T: serde::de::DeserializeOwned>(params: Value) -> Result<T, RpcError> {
+    serde_json::from_value(params).map_err(|e| RpcError::new(INVALID_PARAMS, e.to_string()))
+}
+
+async fn rpc_handler(
+    State(state): State<A2aState>,
+    Path(agent_id): Path<String>,
+    Json(request): Json<RpcRequest>,
+) -> Response {
+    let result = match request.method.as_str() {
+        "tasks/send" => match params(request.params) {
+            Ok(p) => state.send_task(&agent_id, p).await,
+            Err(e) => Err(e),
+        },
+        "tasks/get" => params(request.params).and_then(|p: TaskIdParams| state.get_task(&p.id)),
+        "tasks/cancel" => match params::<TaskIdParams>(request.params) {
+            Ok(p) => state.cancel_task(&p.id).await,
+            Err(e) => Err(e),
+        },
+        other => Err(RpcError::new(
+            METHOD_NOT_FOUND,
+            format!("Method not found: {other}"),
+        )),
+    };
+    let body = match result {
+        Ok(task) => json!({ "jsonrpc": "2.0", "id": request.id, "result": task }),
+        Err(e) => json!({
+            "jsonrpc": "2.0",
+            "id": request.id,
+            "error": { "code": e.code, "message": e.message },
+        }),
+    };
+    Json(body).into_response()
+}
+
+impl A2aState {
+    fn get_task(&self, id: &str) -> Result<Task, RpcError> {
+        self.tasks
+            .get(id)
+            .ok_or_else(|| RpcError::new(TASK_NOT_FOUND, format!("Task not found: {id}")))
+    }
+
+    /// Store `task` and publish its lifecycle event on the task's thread
+    async fn update(&self, task: Task) -> Task {
+        self.tasks.put(task.clone());
+        if let Err(e) = self
+            .event_bus
+            .publish(&task.thread_topic(), task.lifecycle_event(SENDER))
+            .await
+        {
+            warn!(target: "a2a", task_id = %task.id, error = %e, "Failed to publish task event");
+        }
+        task
+    }
+
+    async fn cancel_task(&self, id: &str) -> Result<Task, RpcError> {
+        let mut task = self.get_task(id)?;
+        if task.status.state.is_terminal() {
+            return Err(RpcError::new(
+                TASK_NOT_CANCELABLE,
+                format!("Task {id} is already {}", task.status.state.as_str()),
+            ));
+        }
+        task.status = TaskStatus::new(TaskState::Canceled, None);
+        Ok(self.update(task).await)
+    }
+
+    async fn send_task(&self, agent_id: &str, params: TaskSendParams) -> Result<Task, RpcError> {
+        let query = params.message.text();
+        if query.trim().is_empty() {
+            return Err(RpcError::new(
+                INVALID_PARAMS,
+                "message must have a text part",
+            ));
+        }
+
+        // A follow-up message continues the task's history
+        let mut task = match self.tasks.get(&params.id) {
+            Some(task) if task.status.state.is_terminal() => {
+                return Err(RpcError::new(
+                    INVALID_PARAMS,
+                    format!("Task {} is already {}", task.id, task.status.state.as_str()),
+                ))
+            }
+            Some(task) => task,
+            None => Task::new(params.id.clone(), params.session_id.clone()),
+        };
+        task.metadata.extend(params.metadata);
+        task.metadata
+            .insert("agent_id".to_string(), Value::String(agent_id.to_string()));
+        task.history.push(params.message);
+        task.status = TaskStatus::new(TaskState::Working, None);
+        let task = self.update(task).await;
+
+        let outcome = self.ask_agent(agent_id, &task, &query).await;
+
+        // The task may have been canceled while the agent was working
+        let mut task = self.tasks.get(&task.id).unwrap_or(task);
+        if task.status.state == TaskState::Canceled {
+            return Ok(task);
+        }
+        task.status = match outcome {
+            Ok(text) => {
+                task.artifacts = vec![Artifact {
+                    name: Some("response".to_string()),
+                    parts: Message::agent(text.clone()).parts,
+                    index: 0,
+                }];
+                task.history.push(Message::agent(text));
+                TaskStatus::new(TaskState::Completed, None)
+            }
+            Err(reason) => TaskStatus::new(TaskState::Failed, Some(Message::agent(reason))),
+        };
+        Ok(self.update(task).await)
+    }
+
+    /// Publish the task's query to the agent and wait for its response text
+    async fn ask_agent(&self, agent_id: &str, task: &Task, query: &str) -> Result<String, String> {
+        let envelope = Envelope::new(task.id.clone(), SENDER)
+            .with_reply_expected(true)
+            .with_timeout(self.config.timeout);
+
+        // Subscribe to the reply topic before publishing so a fast agent cannot be missed
+        let (subscription_id, mut rx) = self
+            .event_bus
+            .subscribe(
+                envelope.reply_to.clone(),
+                vec![RESPONSE_EVENT.to_string(), RESPONSE_FINAL_EVENT.to_string()],
+                QoSLevel::QosBatched,
+            )
+            .await
+            .map_err(|e| e.to_string())?;
+
+        let result = async {
+            let delivered = self
+                .event_bus
+                .publish(
+                    &agent_reply_topic(agent_id),
+                    query_event(&envelope, task, query),
+                )
+                .await
+                .map_err(|e| e.to_string())?;
+            if delivered == 0 {
+                warn!(target: "a2a", agent_id = %agent_id, "No agent received the task");
+                return Err(format!("Agent '{agent_id}' is not running"));
+            }
+
+            let deadline = tokio::time::Instant::now() + self.config.timeout;
+            loop {
+                match tokio::time::timeout_at(deadline, rx.recv()).await {
+                    Ok(Some(event)) if is_correlated(&event, &envelope.correlation_id) => {
+                        return Ok(String::from_utf8_lossy(&event.payload).into_owned())
+                    }
+                    Ok(Some(_)) => continue,
+                    Ok(None) => return Err("reply subscription closed".to_string()),
+                    Err(_) => return Err("Timed out waiting for the agent's response".to_string()),
+                }
+            }
+        }
+        .await;
+
+        let _ = self.event_bus.unsubscribe(&subscription_id).await;
+        result
+    }
+}
+
+/// `user.query` event for a task message, addressed back to `envelope.reply_to`
+fn query_event(envelope: &Envelope, task: &Task, query: &str) -> Event {
+    let mut metadata = HashMap::new();
+    metadata.insert("text".to_string(), query.to_string());
+    metadata.insert("a2a_task_id".to_string(), task.id.clone());
+    if let Some(ref session_id) = task.session_id {
+        metadata.insert("session_id".to_string(), session_id.clone());
+    }
+
+    let mut event = Event {
+        id: format!("evt_a2a_{}_query_{}", task.id, task.history.len()),
+        r#type: QUERY_EVENT.to_string(),
+        timestamp_ms: chrono::Utc::now().timestamp_millis(),
+        source: SENDER.to_string(),
+        metadata,
+        payload: query.as_bytes().to_vec(),
+        confidence: 1.0,
+        tags: vec!["a2a".to_string()],
+        priority: 50,
+    };
+    envelope.attach_to_event(&mut event);
+    event
+}
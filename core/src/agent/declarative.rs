@@ -0,0 +1,486 @@
+//! Declarative agent configuration (the `loom-config` loader).
+//!
+//! An agents file lists cognitive agents: id, topics, thinking strategy, system prompt
+//! and tool allowlist. TOML is the default; files ending in `.yaml`/`.yml` are YAML.
+//!
+//! ```toml
+//! [[agents]]
+//! id = "support"
+//! topics = ["support.requests"]
+//! strategy = "react"
+//! system_prompt = "You answer questions about our product."
+//! tools = ["kb:*", "math:eval"]
+//! denied_tools = ["kb:delete"]
+//! bootstrap = "seeds/support"
+//! ```
+//!
+//! `bootstrap` names a directory of prompt files and documents the agent is seeded from
+//! when it starts (see `cognitive::bootstrap`). `AgentsFile::parse` (and so `load` and
+//! `watch`) resolves relative paths against the agents file's directory.
+//!
+//! `AgentConfigLoader::apply` reconciles an `AgentRuntime` with a parsed file: agents
+//! new to the file are created, changed ones are recreated, and agents the loader created
+//! earlier but the file no longer lists are deleted. Agents created by other code are
+//! left alone. `AgentConfigLoader::watch` polls the file and applies every change; a
+//! file that fails to parse or validate, or disappears, is logged and leaves the running
+//! agents as they are.
+//!
+//! Parameter values may reference secrets, environment variables and `LoomConfig`
+//! settings (`${secrets.SLACK_TOKEN}`, see `agent::params`), so the file can be committed
+//! without them. References are resolved on every apply: an agent whose resolved
+//! parameters changed is recreated even if its entry did not, and
+//! `AgentConfigLoader::refresh` re-applies the current file to pick up rotated secrets.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use tokio::sync::Mutex;
+use tokio::task::JoinHandle;
+use tracing::{info, warn};
+
+use crate::cognitive::{
+    CognitiveAgent, CognitiveConfig, GuardrailConfig, SimpleCognitiveLoop, ThinkingStrategy,
+    BOOTSTRAP_DIR_PARAM,
+};
+use crate::proto::AgentConfig;
+use crate::{LlmClient, LoomError, Result, ToolRegistry};
+
+use super::params::ParameterResolver;
+use super::{AgentBehavior, AgentFactory, AgentRuntime};
+
+/// How often `watch` checks the file for changes by default
+const DEFAULT_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// One agent of an agents file
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AgentSpec {
+    pub id: String,
+    /// Topics subscribed to at creation (the private reply topic is always added)
+    #[serde(default)]
+    pub topics: Vec<String>,
+    #[serde(default)]
+    pub strategy: ThinkingStrategy,
+    #[serde(default)]
+    pub system_prompt: Option<String>,
+    /// Tools the agent may use: names or prefixes ending in `*`. Omitted exposes every
+    /// registered tool; an empty list none.
+    #[serde(default)]
+    pub tools: Option<Vec<String>>,
+    /// Tools the agent may never use, even when `tools` allows them: names or glob
+    /// patterns such as `shell:*`
+    #[serde(default)]
+    pub denied_tools: Vec<String>,
+    /// Overrides the strategy's default iteration budget
+    #[serde(default)]
+    pub max_iterations: Option<usize>,
+    #[serde(default)]
+    pub temperature: Option<f32>,
+    #[serde(default)]
+    pub guardrails: Option<GuardrailConfig>,
+    /// `false` keeps the agent's LLM calls out of the response cache
+    #[serde(default)]
+    pub llm_cache: Option<bool>,
+    /// Directory the agent's system prompt and memory are seeded from
+    #[serde(default)]
+    pub bootstrap: Option<PathBuf>,
+    /// Passed through as `AgentConfig.parameters` (schedules, `routing.policy`, ...);
+    /// values may hold `${...}` references (see `agent::params`)
+    #[serde(default)]
+    pub parameters: HashMap<String, String>,
+}
+
+impl AgentSpec {
+    pub fn new(id: impl Into<String>) -> Self {
+        Self {
+            id: id.into(),
+            topics: Vec::new(),
+            strategy: ThinkingStrategy::default(),
+            system_prompt: None,
+            tools: None,
+            denied_tools: Vec::new(),
+            max_iterations: None,
+            temperature: None,
+            guardrails: None,
+            llm_cache: None,
+            bootstrap: None,
+            parameters: HashMap::new(),
+        }
+    }
+
+    pub fn with_topic(mut self, topic: impl Into<String>) -> Self {
+        self.topics.push(topic.into());
+        self
+    }
+
+    pub fn with_strategy(mut self, strategy: ThinkingStrategy) -> Self {
+        self.strategy = strategy;
+        self
+    }
+
+    pub fn with_system_prompt(mut self, prompt: impl Into<String>) -> Self {
+        self.system_prompt = Some(prompt.into());
+        self
+    }
+
+    pub fn with_tools<I, S>(mut self, tools: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.tools = Some(tools.into_iter().map(Into::into).collect());
+        self
+    }
+
+    pub fn with_denied_tools<I, S>(mut self, tools: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.denied_tools.extend(tools.into_iter().map(Into::into));
+        self
+    }
+
+    pub fn with_bootstrap(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.bootstrap = Some(dir.into());
+        self
+    }
+
+    /// Cognitive loop config for this agent
+    pub fn cognitive_config(&self) -> CognitiveConfig {
+        let mut config = match self.strategy {
+            ThinkingStrategy::SingleShot => CognitiveConfig::single_shot(),
+            ThinkingStrategy::ReAct => CognitiveConfig::react(),
+            ThinkingStrategy::ChainOfThought => CognitiveConfig::chain_of_thought(),
+        };
+        config.system_prompt = self.system_prompt.clone();
+        config.temperature = self.temperature;
+        config.allowed_tools = self.tools.clone();
+        config.denied_tools = self.denied_tools.clone();
+        if let Some(max_iterations) = self.max_iterations {
+            config.max_iterations = max_iterations;
+        }
+        if let Some(ref guardrails) = self.guardrails {
+            config.guardrails = guardrails.clone();
+        }
+        if let Some(llm_cache) = self.llm_cache {
+            config.llm_cache = llm_cache;
+        }
+        config
+    }
+
+    /// Runtime config for this agent
+    pub fn agent_config(&self) -> AgentConfig {
+        let mut parameters = self.parameters.clone();
+        if let Some(ref dir) = self.bootstrap {
+            parameters.insert(
+                BOOTSTRAP_DIR_PARAM.to_string(),
+                dir.to_string_lossy().into_owned(),
+            );
+        }
+        AgentConfig {
+            agent_id: self.id.clone(),
+            agent_type: "cognitive".to_string(),
+            subscribed_topics: self.topics.clone(),
+            capabilities: self.tools.clone().unwrap_or_default(),
+            parameters,
+        }
+    }
+}
+
+/// Parsed agents file
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct AgentsFile {
+    #[serde(default)]
+    pub agents: Vec<AgentSpec>,
+}
+
+impl AgentsFile {
+    /// Parse `[[agents]]` tables, see the module docs
+    pub fn from_toml(source: &str) -> Result<Self> {
+        let file: Self = toml::from_str(source)
+            .map_err(|e| LoomError::AgentError(format!("Invalid agents file: {e}")))?;
+        file.validate()?;
+        Ok(file)
+    }
+
+    /// Parse an `agents:` list
+    pub fn from_yaml(source: &str) -> Result<Self> {
+        let file: Self = serde_yaml::from_str(source)
+            .map_err(|e| LoomError::AgentError(format!("Invalid agents file: {e}")))?;
+        file.validate()?;
+        Ok(file)
+    }
+
+    /// Parse `source` as YAML if `path` ends in `.yaml`/`.yml`, as TOML otherwise;
+    /// relative bootstrap directories are taken relative to `path`'s directory
+    pub fn parse(path: &Path, source: &str) -> Result<Self> {
+        let mut file = match path.extension().and_then(|ext| ext.to_str()) {
+            Some("yaml" | "yml") => Self::from_yaml(source)?,
+            _ => Self::from_toml(source)?,
+        };
+        if let Some(base) = path.parent() {
+            for spec in &mut file.agents {
+                if let Some(ref mut dir) = spec.bootstrap {
+                    if dir.is_relative() {
+                        *dir = base.join(&*dir);
+                    }
+                }
+            }
+        }
+        Ok(file)
+    }
+
+    /// Read and parse the file at `path`
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let source = std::fs::read_to_string(path)?;
+        Self::parse(path, &source)
+    }
+
+    /// Every agent needs a non-empty id, unique within the file
+    pub fn validate(&self) -> Result<()> {
+        let mut seen = std::collections::HashSet::new();
+        for spec in &self.agents {
+            if spec.id.trim().is_empty() {
+                return Err(LoomError::AgentError(
+                    "Invalid agents file: agent without an id".to_string(),
+                ));
+            }
+            if !seen.insert(spec.id.as_str()) {
+                return Err(LoomError::AgentError(format!(
+                    "Invalid agents file: agent {} is listed twice",
+                    spec.id
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Builds the behavior of a configured agent
+pub type BehaviorFactory = Arc<dyn Fn(&AgentSpec) -> Result<Box<dyn AgentBehavior>> + Send + Sync>;
+
+/// What one `apply` changed
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ReloadReport {
+    pub created: Vec<String>,
+    pub updated: Vec<String>,
+    pub removed: Vec<String>,
+    /// Agents that could not be created or updated, with the error; retried next apply
+    pub failed: Vec<(String, String)>,
+}
+
+impl ReloadReport {
+    /// Whether the runtime was left as it was
+    pub fn is_empty(&self) -> bool {
+        self.created.is_empty()
+            && self.updated.is_empty()
+            && self.removed.is_empty()
+            && self.failed.is_empty()
+    }
+}
+
+/// Creates, updates and removes `AgentRuntime` agents from an agents file
+pub struct AgentConfigLoader {
+    runtime: AgentRuntime,
+    factory: BehaviorFactory,
+    resolver: ParameterResolver,
+    // agent id -> its entry and the config it runs with (parameters resolved)
+    applied: Mutex<HashMap<String, (AgentSpec, AgentConfig)>>,
+    // The file last applied, for `refresh`
+    file: Mutex<Option<AgentsFile>>,
+    poll_interval: Duration,
+}
+
+impl AgentConfigLoader {
+    /// Loader building behaviors with `factory`
+    pub fn new(runtime: AgentRuntime, factory: BehaviorFactory) -> Self {
+        Self {
+            runtime,
+            factory,
+            resolver: ParameterResolver::default(),
+            applied: Mutex::new(HashMap::new()),
+            file: Mutex::new(None),
+            poll_interval: DEFAULT_POLL_INTERVAL,
+        }
+    }
+
+    /// Loader running each agent as a `CognitiveAgent` over `SimpleCognitiveLoop`,
+    /// with `tools` narrowed to the agent's allowlist
+    pub fn cognitive(runtime: AgentRuntime, llm: Arc<LlmClient>, tools: Arc<ToolRegistry>) -> Self {
+        let factory: BehaviorFactory = Arc::new(move |spec: &AgentSpec| {
+            let tools = match spec.tools {
+                Some(ref allowed) => Arc::new(tools.scoped(allowed.iter().cloned())),
+                None => Arc::clone(&tools),
+            };
+            let loop_impl =
+                SimpleCognitiveLoop::new(spec.cognitive_config(), Arc::clone(&llm), tools);
+            Ok(Box::new(CognitiveAgent::new(loop_impl)) as Box<dyn AgentBehavior>)
+        });
+        Self::new(runtime, factory)
+    }
+
+    /// How often `watch` checks the file
+    pub fn with_poll_interval(mut self, interval: Duration) -> Self {
+        self.poll_interval = interval;
+        self
+    }
+
+    /// Resolve parameter references with `resolver` (default: secrets from the
+    /// environment, settings from the environment only)
+    pub fn with_parameter_resolver(mut self, resolver: ParameterResolver) -> Self {
+        self.resolver = resolver;
+        self
+    }
+
+    /// Ids of the agents currently running from the file, sorted
+    pub async fn managed_agents(&self) -> Vec<String> {
+        let mut ids: Vec<String> = self.applied.lock().await.keys().cloned().collect();
+        ids.sort();
+        ids
+    }
+
+    /// Reconcile the runtime with `file`
+    pub async fn apply(&self, file: &AgentsFile) -> Result<ReloadReport> {
+        file.validate()?;
+        *self.file.lock().await = Some(file.clone());
+        let mut applied = self.applied.lock().await;
+        let mut report = ReloadReport::default();
+
+        let listed: std::collections::HashSet<&str> =
+            file.agents.iter().map(|spec| spec.id.as_str()).collect();
+        let mut stale: Vec<String> = applied
+            .keys()
+            .filter(|id| !listed.contains(id.as_str()))
+            .cloned()
+            .collect();
+        stale.sort();
+        for id in stale {
+            applied.remove(&id);
+            if let Err(e) = self.runtime.delete_agent(&id).await {
+                warn!(agent_id = %id, error = %e, "Failed to delete unconfigured agent");
+            }
+            report.removed.push(id);
+        }
+
+        for spec in &file.agents {
+            let mut config = spec.agent_config();
+            config.parameters = match self.resolver.resolve(&config.parameters) {
+                Ok(parameters) => parameters,
+                Err(e) => {
+                    report.failed.push((spec.id.clone(), e.to_string()));
+                    continue;
+                }
+            };
+            let previous = applied.get(&spec.id);
+            if previous.is_some_and(|(s, c)| s == spec && *c == config) {
+                continue;
+            }
+            let updating = previous.is_some();
+            let behavior = match (self.factory)(spec) {
+                Ok(behavior) => behavior,
+                Err(e) => {
+                    report.failed.push((spec.id.clone(), e.to_string()));
+                    continue;
+                }
+            };
+            if updating {
+                applied.remove(&spec.id);
+                if let Err(e) = self.runtime.delete_agent(&spec.id).await {
+                    warn!(agent_id = %spec.id, error = %e, "Failed to stop agent before update");
+                }
+            }
+            // File agents can hibernate; reactivation rebuilds them from their spec
+            let factory = Arc::clone(&self.factory);
+            let reactivated = spec.clone();
+            let rebuild: AgentFactory =
+                Arc::new(move |_config: &AgentConfig| factory(&reactivated));
+            match self
+                .runtime
+                .spawn_agent(config.clone(), behavior, Some(rebuild))
+                .await
+            {
+                Ok(_) => {
+                    applied.insert(spec.id.clone(), (spec.clone(), config));
+                    if updating {
+                        report.updated.push(spec.id.clone());
+                    } else {
+                        report.created.push(spec.id.clone());
+                    }
+                }
+                Err(e) => report.failed.push((spec.id.clone(), e.to_string())),
+            }
+        }
+
+        for (id, error) in &report.failed {
+            warn!(agent_id = %id, error = %error, "Failed to apply agent config");
+        }
+        Ok(report)
+    }
+
+    /// Read, parse and apply the file at `path`
+    pub async fn load(&self, path: impl AsRef<Path>) -> Result<ReloadReport> {
+        let path = path.as_ref();
+        let source = tokio::fs::read_to_string(path).await?;
+        self.apply(&AgentsFile::parse(path, &source)?).await
+    }
+
+    /// Apply the last applied file again, re-resolving parameter references; agents
+    /// whose resolved parameters are unchanged keep running
+    pub async fn refresh(&self) -> Result<ReloadReport> {
+        let file = self.file.lock().await.clone();
+        match file {
+            Some(file) => self.apply(&file).await,
+            None => Ok(ReloadReport::default()),
+        }
+    }
+
+    /// Apply the file at `path` now and whenever its contents change
+    pub fn watch(self: Arc<Self>, path: impl Into<PathBuf>) -> JoinHandle<()> {
+        let path = path.into();
+        tokio::spawn(async move {
+            let mut tick = tokio::time::interval(self.poll_interval);
+            tick.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            let mut last: Option<String> = None;
+            loop {
+                tick.tick().await;
+                let source = match tokio::fs::read_to_string(&path).await {
+                    Ok(source) => source,
+                    Err(e) => {
+                        if last.is_some() {
+                            warn!(path = %path.display(), error = %e, "Agents file unreadable; keeping current agents");
+                        }
+                        continue;
+                    }
+                };
+                if last.as_deref() == Some(source.as_str()) {
+                    continue;
+                }
+                match AgentsFile::parse(&path, &source) {
+                    Ok(file) => match self.apply(&file).await {
+                        Ok(report) if !report.is_empty() => info!(
+                            path = %path.display(),
+                            created = report.created.len(),
+                            updated = report.updated.len(),
+                            removed = report.removed.len(),
+                            failed = report.failed.len(),
+                            "Applied agents file"
+                        ),
+                        Ok(_) => {}
+                        Err(e) => {
+                            warn!(path = %path.display(), error = %e, "Failed to apply agents file")
+                        }
+                    },
+                    Err(e) => {
+                        warn!(path = %path.display(), error = %e, "Invalid agents file; keeping current agents")
+                    }
+                }
+                last = Some(source);
+            }
+        })
+    }
+}
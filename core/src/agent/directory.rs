@@ -1,16 +1,23 @@
 use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use std::sync::OnceLock;
 
 use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
 
+use super::directory_store::DirectoryStore;
 use crate::proto::{CapabilityDescriptor, ProviderKind};
 use crate::tools::ToolRegistry;
+use crate::Result;
 
 /// Information about a registered agent including subscriptions and capabilities.
 ///
 /// `AgentInfo` describes an agent's identity, the topics it subscribes to,
 /// the capabilities it provides, arbitrary metadata for filtering or routing,
 /// and health status information including last heartbeat timestamp.
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
 pub struct AgentInfo {
     /// Unique identifier for this agent
     pub agent_id: String,
@@ -27,7 +34,7 @@ pub struct AgentInfo {
 }
 
 /// Agent health status
-#[derive(Debug, Clone, PartialEq, Eq, Default)]
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
 pub enum AgentStatus {
     /// Agent is active and responsive
     #[default]
@@ -36,10 +43,31 @@ pub enum AgentStatus {
     Idle,
     /// Agent has not sent heartbeat for some time
     Inactive,
+    /// Agent's connection is open but it has missed heartbeats
+    Degraded,
+    /// Agent is paused by its runtime; events queue up in its mailbox
+    Paused,
+    /// Agent is handling its queued events before stopping
+    Draining,
     /// Agent has disconnected
     Disconnected,
 }
 
+/// One connection of an agent, from registration until disconnect.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConnectionRecord {
+    /// When the agent registered (milliseconds since Unix epoch)
+    pub connected_at_ms: i64,
+    /// When the agent disconnected; `None` while the connection is open
+    pub disconnected_at_ms: Option<i64>,
+}
+
+/// Connection records kept per agent; older records are dropped first.
+pub const MAX_CONNECTION_HISTORY: usize = 32;
+
+/// Disconnected agents retained by default before the oldest are evicted.
+pub const DEFAULT_MAX_DISCONNECTED: usize = 1000;
+
 /// Thread-safe, in-memory directory for agent discovery and indexing.
 ///
 /// `AgentDirectory` maintains indices of agents by topic and capability,
@@ -51,6 +79,21 @@ pub enum AgentStatus {
 /// All methods are safe to call concurrently from multiple threads. Internal
 /// indices are automatically updated on register/unregister operations.
 ///
+/// # Persistence
+///
+/// A directory created with [`AgentDirectory::with_persistence`] writes every
+/// registration, heartbeat and status change through to RocksDB, together with
+/// the agent's connection history. Stored agents are loaded lazily on the first
+/// access after startup with status `Disconnected` and their last-known
+/// metadata and heartbeat; a connection left open by the previous process is
+/// closed at the agent's last heartbeat. When the agent registers again it
+/// becomes `Active` and a new connection record is started.
+///
+/// Agents that disconnect via [`AgentDirectory::mark_disconnected`] stay in the
+/// directory (and its indices) so their last-known state remains visible; use
+/// [`AgentInfo::status`] to tell live agents apart. Only the most recently
+/// disconnected agents are retained, see [`AgentDirectory::with_max_disconnected`].
+///
 /// # Examples
 ///
 /// ```
@@ -78,6 +121,10 @@ pub struct AgentDirectory {
     agents: DashMap<String, AgentInfo>,
     topic_index: DashMap<String, HashSet<String>>, // topic -> agent_ids
     capability_index: DashMap<String, HashSet<String>>, // capability -> agent_ids
+    history: DashMap<String, Vec<ConnectionRecord>>, // agent_id -> connections, oldest first
+    store: Option<DirectoryStore>,
+    hydrated: OnceLock<()>,
+    max_disconnected: Option<usize>,
 }
 
 impl AgentDirectory {
@@ -86,12 +133,111 @@ impl AgentDirectory {
         Self::default()
     }
 
+    /// Creates an `AgentDirectory` backed by a RocksDB database at `path`.
+    ///
+    /// Agents stored by a previous process are rehydrated on first access.
+    ///
+    /// # Errors
+    ///
+    /// Returns `LoomError::StorageError` if the database cannot be opened.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use loom_core::{AgentDirectory, AgentInfo};
+    ///
+    /// let dir = tempfile::tempdir().unwrap();
+    ///
+    /// {
+    ///     let agents = AgentDirectory::with_persistence(dir.path()).unwrap();
+    ///     agents.register_agent(AgentInfo {
+    ///         agent_id: "agent-1".to_string(),
+    ///         capabilities: vec!["translate".to_string()],
+    ///         ..Default::default()
+    ///     });
+    /// }
+    ///
+    /// // After a restart the registration is still there
+    /// let agents = AgentDirectory::with_persistence(dir.path()).unwrap();
+    /// assert_eq!(agents.by_capability("translate"), vec!["agent-1".to_string()]);
+    /// ```
+    pub fn with_persistence<P: AsRef<Path>>(path: P) -> Result<Self> {
+        Ok(Self {
+            store: Some(DirectoryStore::open(path)?),
+            ..Self::default()
+        })
+    }
+
+    /// Sets how many disconnected agents are retained (default
+    /// [`DEFAULT_MAX_DISCONNECTED`]). Beyond the limit, the agents that
+    /// disconnected longest ago are unregistered.
+    pub fn with_max_disconnected(mut self, max: usize) -> Self {
+        self.max_disconnected = Some(max);
+        self
+    }
+
+    /// Returns `true` if this directory writes through to persistent storage.
+    pub fn is_persistent(&self) -> bool {
+        self.store.is_some()
+    }
+
+    /// Flushes persisted records to disk; a no-op for in-memory directories.
+    pub fn flush(&self) -> Result<()> {
+        match self.store {
+            Some(ref store) => store.flush(),
+            None => Ok(()),
+        }
+    }
+
+    /// Loads stored agents into memory once, before the first read or write.
+    fn hydrate(&self) {
+        let Some(ref store) = self.store else {
+            return;
+        };
+        self.hydrated.get_or_init(|| match store.load_all() {
+            Ok(agents) => {
+                let count = agents.len();
+                for (mut info, mut history) in agents {
+                    // Whatever was connected before the restart no longer is
+                    info.status = AgentStatus::Disconnected;
+                    if let Some(open) = history
+                        .last_mut()
+                        .filter(|r| r.disconnected_at_ms.is_none())
+                    {
+                        let closed_at = info.last_heartbeat.unwrap_or(open.connected_at_ms);
+                        open.disconnected_at_ms = Some(closed_at.max(open.connected_at_ms));
+                    }
+                    self.history.insert(info.agent_id.clone(), history);
+                    self.index_agent(info);
+                }
+                info!(agents = count, "Rehydrated AgentDirectory from storage");
+            }
+            Err(e) => warn!(error = %e, "Failed to rehydrate AgentDirectory"),
+        });
+    }
+
+    fn persist(&self, info: &AgentInfo) {
+        if let Some(ref store) = self.store {
+            let history = self
+                .history
+                .get(&info.agent_id)
+                .map(|h| h.clone())
+                .unwrap_or_default();
+            if let Err(e) = store.put(info, &history) {
+                warn!(agent_id = %info.agent_id, error = %e, "Failed to persist agent");
+            }
+        }
+    }
+
     /// Registers or updates an agent in the directory.
     ///
     /// If an agent with the same `agent_id` already exists, it is replaced
     /// and all indices are updated atomically. Old topic and capability
     /// subscriptions are removed before adding new ones.
     ///
+    /// Each registration starts a new [`ConnectionRecord`] in the agent's
+    /// history, closing the previous one if it was still open.
+    ///
     /// # Arguments
     ///
     /// * `info` - Agent information including ID, topics, capabilities, and metadata
@@ -118,6 +264,29 @@ impl AgentDirectory {
     /// dir.register_agent(info);
     /// ```
     pub fn register_agent(&self, info: AgentInfo) {
+        self.hydrate();
+        let now = chrono::Utc::now().timestamp_millis();
+        {
+            let mut history = self.history.entry(info.agent_id.clone()).or_default();
+            if let Some(open) = history
+                .last_mut()
+                .filter(|r| r.disconnected_at_ms.is_none())
+            {
+                open.disconnected_at_ms = Some(now);
+            }
+            history.push(ConnectionRecord {
+                connected_at_ms: now,
+                disconnected_at_ms: None,
+            });
+            let excess = history.len().saturating_sub(MAX_CONNECTION_HISTORY);
+            history.drain(..excess);
+        }
+        self.persist(&info);
+        self.index_agent(info);
+    }
+
+    /// Inserts or replaces an agent in memory and updates the indices.
+    fn index_agent(&self, info: AgentInfo) {
         let id = info.agent_id.clone();
         // Remove old indexes if exists
         if let Some(old) = self.agents.get(&id) {
@@ -180,6 +349,13 @@ impl AgentDirectory {
     /// assert_eq!(dir.by_topic("tasks").len(), 0);
     /// ```
     pub fn unregister_agent(&self, agent_id: &str) {
+        self.hydrate();
+        if let Some(ref store) = self.store {
+            if let Err(e) = store.delete(agent_id) {
+                warn!(agent_id = %agent_id, error = %e, "Failed to delete persisted agent");
+            }
+        }
+        self.history.remove(agent_id);
         if let Some((_, old)) = self.agents.remove(agent_id) {
             for t in old.subscribed_topics {
                 if let Some(mut set) = self.topic_index.get_mut(&t) {
@@ -194,6 +370,89 @@ impl AgentDirectory {
         }
     }
 
+    /// Marks an agent as disconnected while keeping its entry.
+    ///
+    /// Sets the status to `Disconnected` and closes the open connection record.
+    /// The agent stays in the directory and its indices until it registers
+    /// again or is evicted because more than the configured number of agents
+    /// are disconnected. If the agent doesn't exist, this is a no-op.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use loom_core::{AgentDirectory, AgentInfo, AgentStatus};
+    ///
+    /// let dir = AgentDirectory::new();
+    ///
+    /// dir.register_agent(AgentInfo {
+    ///     agent_id: "agent-1".to_string(),
+    ///     ..Default::default()
+    /// });
+    /// dir.mark_disconnected("agent-1");
+    ///
+    /// let agent = dir.get("agent-1").unwrap();
+    /// assert_eq!(agent.status, AgentStatus::Disconnected);
+    /// assert!(dir.history("agent-1")[0].disconnected_at_ms.is_some());
+    /// ```
+    pub fn mark_disconnected(&self, agent_id: &str) {
+        self.hydrate();
+        let updated = self.agents.get_mut(agent_id).map(|mut agent| {
+            agent.status = AgentStatus::Disconnected;
+            agent.clone()
+        });
+        let Some(info) = updated else {
+            return;
+        };
+        if let Some(mut history) = self.history.get_mut(agent_id) {
+            if let Some(open) = history
+                .last_mut()
+                .filter(|r| r.disconnected_at_ms.is_none())
+            {
+                open.disconnected_at_ms = Some(chrono::Utc::now().timestamp_millis());
+            }
+        }
+        self.persist(&info);
+        self.evict_disconnected();
+    }
+
+    /// Unregisters the longest-disconnected agents beyond the retention limit.
+    fn evict_disconnected(&self) {
+        let max = self.max_disconnected.unwrap_or(DEFAULT_MAX_DISCONNECTED);
+        let mut disconnected: Vec<(i64, String)> = self
+            .agents
+            .iter()
+            .filter(|a| a.status == AgentStatus::Disconnected)
+            .map(|a| {
+                let last_seen = self
+                    .history
+                    .get(a.key())
+                    .and_then(|h| h.last().and_then(|r| r.disconnected_at_ms))
+                    .unwrap_or_default();
+                (last_seen, a.key().clone())
+            })
+            .collect();
+        if disconnected.len() <= max {
+            return;
+        }
+        disconnected.sort();
+        let excess = disconnected.len() - max;
+        for (_, agent_id) in disconnected.into_iter().take(excess) {
+            self.unregister_agent(&agent_id);
+        }
+    }
+
+    /// Returns the connection history of an agent, oldest first.
+    ///
+    /// Histories survive restarts for persistent directories and hold at most
+    /// [`MAX_CONNECTION_HISTORY`] records. Empty for unknown agents.
+    pub fn history(&self, agent_id: &str) -> Vec<ConnectionRecord> {
+        self.hydrate();
+        self.history
+            .get(agent_id)
+            .map(|h| h.clone())
+            .unwrap_or_default()
+    }
+
     /// Retrieves information about a specific agent by ID.
     ///
     /// # Arguments
@@ -227,6 +486,7 @@ impl AgentDirectory {
     /// }
     /// ```
     pub fn get(&self, agent_id: &str) -> Option<AgentInfo> {
+        self.hydrate();
         self.agents.get(agent_id).map(|e| e.clone())
     }
 
@@ -265,6 +525,7 @@ impl AgentDirectory {
     /// assert_eq!(subscribers.len(), 3);
     /// ```
     pub fn by_topic(&self, topic: &str) -> Vec<String> {
+        self.hydrate();
         self.topic_index
             .get(topic)
             .map(|s| s.iter().cloned().collect())
@@ -304,6 +565,7 @@ impl AgentDirectory {
     /// assert_eq!(translators.len(), 1);
     /// ```
     pub fn by_capability(&self, capability: &str) -> Vec<String> {
+        self.hydrate();
         self.capability_index
             .get(capability)
             .map(|s| s.iter().cloned().collect())
@@ -347,6 +609,7 @@ impl AgentDirectory {
     /// assert_eq!(all_agents.len(), 2);
     /// ```
     pub fn all(&self) -> Vec<AgentInfo> {
+        self.hydrate();
         self.agents.iter().map(|e| e.clone()).collect()
     }
 
@@ -381,10 +644,15 @@ impl AgentDirectory {
     /// assert_eq!(agent.status, AgentStatus::Active);
     /// ```
     pub fn update_heartbeat(&self, agent_id: &str) {
-        if let Some(mut agent) = self.agents.get_mut(agent_id) {
+        self.hydrate();
+        let updated = self.agents.get_mut(agent_id).map(|mut agent| {
             let now = chrono::Utc::now().timestamp_millis();
             agent.last_heartbeat = Some(now);
             agent.status = AgentStatus::Active;
+            agent.clone()
+        });
+        if let Some(info) = updated {
+            self.persist(&info);
         }
     }
 
@@ -419,8 +687,13 @@ impl AgentDirectory {
     /// assert_eq!(agent.status, AgentStatus::Idle);
     /// ```
     pub fn update_status(&self, agent_id: &str, status: AgentStatus) {
-        if let Some(mut agent) = self.agents.get_mut(agent_id) {
+        self.hydrate();
+        let updated = self.agents.get_mut(agent_id).map(|mut agent| {
             agent.status = status;
+            agent.clone()
+        });
+        if let Some(info) = updated {
+            self.persist(&info);
         }
     }
 }
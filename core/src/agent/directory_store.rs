@@ -0,0 +1,127 @@
+//! RocksDB backing for `AgentDirectory`.
+//!
+//! Stores one JSON record per agent id in the `agents` column family, following the
+//! layout conventions of `RocksDbStore`. Indices are not persisted; they are rebuilt
+//! from the records on rehydration.
+//!
+//! Records are versioned:
+//! - v0: a bare `AgentInfo` (written before connection history existed)
+//! - v1: `{"version": 1, "info": AgentInfo, "history": [ConnectionRecord]}`
+//!
+//! Older records are upgraded on read; records from a newer release are skipped.
+
+use super::directory::{AgentInfo, ConnectionRecord};
+use crate::{LoomError, Result};
+use rocksdb::{ColumnFamilyDescriptor, IteratorMode, Options, DB};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::path::Path;
+use tracing::{info, warn};
+
+const CF_AGENTS: &str = "agents";
+
+/// Version written by this release
+pub(crate) const RECORD_VERSION: u64 = 1;
+
+#[derive(Deserialize)]
+struct AgentRecord {
+    info: AgentInfo,
+    #[serde(default)]
+    history: Vec<ConnectionRecord>,
+}
+
+/// A stored agent with its connection history
+pub(crate) type StoredAgent = (AgentInfo, Vec<ConnectionRecord>);
+
+fn decode(bytes: &[u8]) -> std::result::Result<StoredAgent, String> {
+    let value: Value = serde_json::from_slice(bytes).map_err(|e| e.to_string())?;
+    match value.get("version").and_then(Value::as_u64) {
+        None => serde_json::from_value::<AgentInfo>(value)
+            .map(|info| (info, Vec::new()))
+            .map_err(|e| e.to_string()),
+        Some(version) if version <= RECORD_VERSION => serde_json::from_value::<AgentRecord>(value)
+            .map(|r| (r.info, r.history))
+            .map_err(|e| e.to_string()),
+        Some(version) => Err(format!(
+            "record version {version} is newer than supported version {RECORD_VERSION}"
+        )),
+    }
+}
+
+pub(crate) struct DirectoryStore {
+    db: DB,
+}
+
+impl std::fmt::Debug for DirectoryStore {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DirectoryStore")
+            .field("path", &self.db.path())
+            .finish()
+    }
+}
+
+impl DirectoryStore {
+    pub(crate) fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let mut opts = Options::default();
+        opts.create_if_missing(true);
+        opts.create_missing_column_families(true);
+
+        let cf_descriptors = vec![ColumnFamilyDescriptor::new(CF_AGENTS, Options::default())];
+        let db = DB::open_cf_descriptors(&opts, path, cf_descriptors)
+            .map_err(|e| LoomError::StorageError(e.to_string()))?;
+
+        info!("AgentDirectory persistence initialized");
+        Ok(Self { db })
+    }
+
+    pub(crate) fn put(&self, info: &AgentInfo, history: &[ConnectionRecord]) -> Result<()> {
+        let cf = self.cf()?;
+        let serialized = serde_json::to_vec(&json!({
+            "version": RECORD_VERSION,
+            "info": info,
+            "history": history,
+        }))?;
+        self.db
+            .put_cf(cf, &info.agent_id, serialized)
+            .map_err(|e| LoomError::StorageError(e.to_string()))
+    }
+
+    pub(crate) fn delete(&self, agent_id: &str) -> Result<()> {
+        let cf = self.cf()?;
+        self.db
+            .delete_cf(cf, agent_id)
+            .map_err(|e| LoomError::StorageError(e.to_string()))
+    }
+
+    /// Load all stored agents; undecodable records are skipped
+    pub(crate) fn load_all(&self) -> Result<Vec<StoredAgent>> {
+        let cf = self.cf()?;
+        let mut agents = Vec::new();
+        for entry in self.db.iterator_cf(cf, IteratorMode::Start) {
+            let (key, value) = entry.map_err(|e| LoomError::StorageError(e.to_string()))?;
+            match decode(&value) {
+                Ok(agent) => agents.push(agent),
+                Err(e) => warn!(
+                    agent_id = %String::from_utf8_lossy(&key),
+                    error = %e,
+                    "Skipping undecodable agent record"
+                ),
+            }
+        }
+        Ok(agents)
+    }
+
+    /// Flush memtables to disk
+    pub(crate) fn flush(&self) -> Result<()> {
+        let cf = self.cf()?;
+        self.db
+            .flush_cf(cf)
+            .map_err(|e| LoomError::StorageError(e.to_string()))
+    }
+
+    fn cf(&self) -> Result<&rocksdb::ColumnFamily> {
+        self.db
+            .cf_handle(CF_AGENTS)
+            .ok_or_else(|| LoomError::StorageError(format!("Missing CF: {}", CF_AGENTS)))
+    }
+}
@@ -2,27 +2,43 @@ use opentelemetry::metrics::{Counter, Histogram};
 use opentelemetry::KeyValue;
 use std::sync::Arc;
 use std::time::Instant;
-use tokio::sync::RwLock;
-use tracing::{debug, info, warn};
+use tokio::sync::{mpsc, watch, Notify, RwLock};
+use tracing::{debug, info, warn, Instrument};
 
+use crate::cognitive::llm::policy::{estimate_tokens, policy_from_name};
 use crate::cognitive::llm::router::{
-    AgentContext, ModelRouter, PrivacyLevel, Route, RoutingDecision, RoutingPolicy,
+    AgentContext, ModelRouter, PrivacyLevel, Route, RoutingConstraints, RoutingDecision,
 };
+use crate::cognitive::llm::tiers::LatencyClass;
+use crate::cognitive::{
+    result_keys, result_topic, REPLY_ACTION, REPLY_EVENT_TYPE_PARAM, RESPONSE_EVENT, RESULT_ACTION,
+    RESULT_EVENT,
+};
+use crate::messaging::envelope::keys;
+use crate::messaging::receipts::RECEIPT_SUBSCRIBER_KEY;
+use crate::namespace::Namespace;
 use crate::proto::{Action, AgentConfig, AgentState};
-use crate::tools::ToolRegistry;
+use crate::tools::{with_caller, ToolRegistry};
 use crate::{Envelope, Event, EventBus, Result};
 
 use super::behavior::AgentBehavior;
+use super::runtime::agent_namespace;
 
 /// Agent instance
 pub struct Agent {
     pub(crate) config: AgentConfig,
     pub(crate) state: Arc<RwLock<AgentState>>,
     pub(crate) behavior: Box<dyn AgentBehavior>,
-    pub(crate) event_rx: tokio::sync::mpsc::Receiver<Event>,
+    pub(crate) event_rx: mpsc::Receiver<Event>,
+    // Event handled before the mailbox, set when a hibernated agent is woken by it
+    pub(crate) pending: Option<Event>,
+    // Set by the runtime; while true the mailbox is left untouched
+    pub(crate) paused: Option<watch::Receiver<bool>>,
     pub(crate) tool_registry: Arc<ToolRegistry>,
     pub(crate) event_bus: Arc<EventBus>,
     pub(crate) model_router: ModelRouter,
+    // Namespace the agent publishes in (`AgentConfig.parameters["namespace"]`)
+    namespace: Option<Namespace>,
     // OpenTelemetry metrics
     events_processed_counter: Counter<u64>,
     actions_executed_counter: Counter<u64>,
@@ -34,7 +50,7 @@ impl Agent {
     pub fn new(
         config: AgentConfig,
         behavior: Box<dyn AgentBehavior>,
-        event_rx: tokio::sync::mpsc::Receiver<Event>,
+        event_rx: mpsc::Receiver<Event>,
         tool_registry: Arc<ToolRegistry>,
         event_bus: Arc<EventBus>,
         model_router: ModelRouter,
@@ -70,14 +86,22 @@ impl Agent {
             .with_description("Number of routing decisions made")
             .init();
 
+        let namespace = agent_namespace(&config).unwrap_or_else(|e| {
+            warn!(error = %e, "Ignoring invalid namespace");
+            None
+        });
+
         Self {
             config,
             state: Arc::new(RwLock::new(state)),
             behavior,
             event_rx,
+            pending: None,
+            paused: None,
             tool_registry,
             event_bus,
             model_router,
+            namespace,
             events_processed_counter,
             actions_executed_counter,
             event_latency_histogram,
@@ -86,72 +110,172 @@ impl Agent {
     }
 
     /// Start agent event loop
-    #[tracing::instrument(skip(self), fields(agent_id = %self.config.agent_id))]
-    pub async fn run(mut self) -> Result<()> {
+    pub async fn run(self) -> Result<()> {
+        self.run_until(None).await.map(|_| ())
+    }
+
+    /// Run the event loop until the mailbox closes or, with an empty mailbox,
+    /// `hibernate` is notified. Hibernating shuts the behavior down and hands back the
+    /// still-open mailbox.
+    #[tracing::instrument(skip(self, hibernate), fields(agent_id = %self.config.agent_id))]
+    pub(crate) async fn run_until(
+        mut self,
+        hibernate: Option<Arc<Notify>>,
+    ) -> Result<Option<mpsc::Receiver<Event>>> {
         info!("Agent {} starting", self.config.agent_id);
 
         // Initialize
         self.behavior.on_init(&self.config).await?;
 
         // Event loop
-        while let Some(mut event) = self.event_rx.recv().await {
-            let event_start = Instant::now();
-            debug!("Agent {} received event {}", self.config.agent_id, event.id);
-
-            // Ensure envelope metadata present; attach defaults if missing
-            let mut env = Envelope::from_event(&event);
-            if env.sender.is_empty() {
-                env.sender = format!("agent.{}", self.config.agent_id);
+        let parked = loop {
+            // A paused agent leaves events queued in its mailbox until it is resumed; a
+            // dropped sender means the runtime let go of the agent
+            if let Some(paused) = &mut self.paused {
+                if *paused.borrow_and_update() {
+                    debug!("Agent {} paused", self.config.agent_id);
+                    if paused.wait_for(|paused| !*paused).await.is_err() {
+                        // The last value stays `true`; stop watching it
+                        self.paused = None;
+                    }
+                }
             }
-            // Increment hop & ttl; drop if expired
-            if !env.next_hop() {
-                debug!("Dropping event {} due to TTL exhaustion", event.id);
-                continue;
+            let event = match self.pending.take() {
+                Some(event) => event,
+                None => {
+                    let paused = &mut self.paused;
+                    let next = tokio::select! {
+                        biased;
+                        _ = async {
+                            if let Some(paused) = paused {
+                                if paused.wait_for(|paused| *paused).await.is_ok() {
+                                    return;
+                                }
+                            }
+                            std::future::pending::<()>().await
+                        } => continue,
+                        event = self.event_rx.recv() => event,
+                        _ = async {
+                            match &hibernate {
+                                Some(notify) => notify.notified().await,
+                                None => std::future::pending().await,
+                            }
+                        } => break true,
+                    };
+                    match next {
+                        Some(event) => event,
+                        None => break false,
+                    }
+                }
+            };
+            // One span per event, parented to the publisher's span when the envelope
+            // carries trace context (mirrors the Bridge's forwarding spans)
+            let span = tracing::info_span!(
+                parent: None,
+                "agent.on_event",
+                agent_id = %self.config.agent_id,
+                event_id = %event.id,
+                event_type = %event.r#type,
+                trace_id = tracing::field::Empty,
+                span_id = tracing::field::Empty
+            );
+            let env = Envelope::from_event(&event);
+            if env.set_span_parent(&span) {
+                span.record("trace_id", tracing::field::display(&env.trace_id));
+                span.record("span_id", tracing::field::display(&env.span_id));
             }
-            env.attach_to_event(&mut event);
+            // Tools called while handling the event see this agent as their caller
+            let agent_id = self.config.agent_id.clone();
+            with_caller(agent_id, self.process_event(event, env).instrument(span)).await?;
+        };
 
-            // Snapshot state (read) for routing context
-            let state_snapshot = {
-                let state_read = self.state.read().await;
-                state_read.clone()
-            };
+        // Cleanup
+        self.behavior.on_shutdown().await?;
+        info!("Agent {} stopped", self.config.agent_id);
 
-            // Route the event first
-            let decision = self.route_event(&event, &state_snapshot, &env).await;
+        Ok(parked.then_some(self.event_rx))
+    }
 
-            match self.handle_with_route(event, decision).await {
-                Ok(actions) => {
-                    // Execute actions
-                    for action in actions {
-                        self.execute_action(action).await?;
-                    }
+    /// Route one event, run the behavior and execute the resulting actions
+    async fn process_event(&mut self, mut event: Event, mut env: Envelope) -> Result<()> {
+        let event_start = Instant::now();
+        debug!("Agent {} received event {}", self.config.agent_id, event.id);
+
+        // Deliveries on critical topics are acked once handled, or on an intentional drop
+        let receipt = event
+            .metadata
+            .get(RECEIPT_SUBSCRIBER_KEY)
+            .map(|subscriber| (subscriber.clone(), event.id.clone()));
+
+        // Ensure envelope metadata present; attach defaults if missing
+        if env.sender.is_empty() {
+            env.sender = format!("agent.{}", self.config.agent_id);
+        }
+        // Increment hop & ttl; drop if expired
+        if !env.next_hop() {
+            debug!("Dropping event {} due to TTL exhaustion", event.id);
+            if let Some((subscriber, event_id)) = receipt {
+                self.event_bus.ack(&subscriber, &event_id);
+            }
+            return Ok(());
+        }
+        // Nobody waits for an answer past the deadline
+        if env.is_expired() {
+            debug!("Dropping event {} past its deadline", event.id);
+            if let Some((subscriber, event_id)) = receipt {
+                self.event_bus.ack(&subscriber, &event_id);
+            }
+            return Ok(());
+        }
+        env.attach_to_event(&mut event);
+
+        // Snapshot state (read) for routing context
+        let state_snapshot = {
+            let state_read = self.state.read().await;
+            state_read.clone()
+        };
+
+        // Route the event first
+        let decision = self.route_event(&event, &state_snapshot, &env).await;
+
+        // Feed observed latency back to the router (model latency and class SLO)
+        let handle_start = Instant::now();
+        let result = self.handle_with_route(event, decision.clone()).await;
+        self.model_router
+            .record_outcome(&decision, handle_start.elapsed());
+
+        match result {
+            Ok(actions) => {
+                // Execute actions
+                for action in actions {
+                    self.execute_action(action).await?;
                 }
-                Err(e) => {
-                    warn!("Agent {} error handling event: {}", self.config.agent_id, e);
+                if let Some((subscriber, event_id)) = receipt {
+                    self.event_bus.ack(&subscriber, &event_id);
                 }
             }
-
-            // Update timestamp
-            {
-                let mut state = self.state.write().await;
-                state.last_update_ms = chrono::Utc::now().timestamp_millis();
+            Err(e) => {
+                // Left unacked: a critical event is redelivered after its ack timeout
+                warn!("Agent {} error handling event: {}", self.config.agent_id, e);
             }
+        }
 
-            // Record metrics
-            let elapsed = event_start.elapsed().as_secs_f64();
-            self.events_processed_counter.add(
-                1,
-                &[KeyValue::new("agent_id", self.config.agent_id.clone())],
-            );
-            self.event_latency_histogram.record(
-                elapsed,
-                &[KeyValue::new("agent_id", self.config.agent_id.clone())],
-            );
+        // Update timestamp
+        {
+            let mut state = self.state.write().await;
+            state.last_update_ms = chrono::Utc::now().timestamp_millis();
         }
 
-        // Cleanup
-        self.behavior.on_shutdown().await?;
-        info!("Agent {} stopped", self.config.agent_id);
+        // Record metrics
+        let elapsed = event_start.elapsed().as_secs_f64();
+        self.events_processed_counter.add(
+            1,
+            &[KeyValue::new("agent_id", self.config.agent_id.clone())],
+        );
+        self.event_latency_histogram.record(
+            elapsed,
+            &[KeyValue::new("agent_id", self.config.agent_id.clone())],
+        );
 
         Ok(())
     }
@@ -175,9 +299,25 @@ impl Agent {
                 .unwrap_or(1.0),
         };
 
-        // Effective policy: base router policy with optional overrides from config
-        let effective_policy = self.effective_policy();
-        let router = self.model_router.with_policy(effective_policy.clone());
+        // Effective constraints: router defaults with optional overrides from config
+        let effective_policy = self.effective_constraints();
+        let mut router = self.model_router.with_constraints(effective_policy.clone());
+        if let Some(policy) = self
+            .config
+            .parameters
+            .get("routing.policy")
+            .and_then(|name| policy_from_name(name))
+        {
+            router = router.with_routing_policy(policy);
+        }
+        if let Some(class) = self
+            .config
+            .parameters
+            .get("routing.latency_class")
+            .and_then(|name| LatencyClass::parse(name))
+        {
+            router = router.with_latency_class(class);
+        }
 
         let decision = match router.route(event, Some(&ctx)).await {
             Ok(d) => d,
@@ -192,6 +332,8 @@ impl Agent {
                     reason: "Router error fallback to local".to_string(),
                     estimated_latency_ms: 0,
                     estimated_cost: 0.0,
+                    model: None,
+                    latency_class: None,
                 }
             }
         };
@@ -219,6 +361,9 @@ impl Agent {
             decision.estimated_latency_ms.to_string(),
         );
         md.insert("est_cost".into(), format!("{:.4}", decision.estimated_cost));
+        if let Some(ref model) = decision.model {
+            md.insert("model".into(), model.clone());
+        }
 
         let mut obs_evt = Event {
             id: format!("evt_route_{}", chrono::Utc::now().timestamp_millis()),
@@ -232,10 +377,8 @@ impl Agent {
             priority: 50,
         };
         env.attach_to_event(&mut obs_evt);
-        let _ = self
-            .event_bus
-            .publish(&format!("agent.{}", self.config.agent_id), obs_evt)
-            .await;
+        let topic = self.scope(&format!("agent.{}", self.config.agent_id), &mut obs_evt);
+        let _ = self.event_bus.publish(&topic, obs_evt).await;
 
         // Record routing decision metric
         self.routing_decisions_counter.add(
@@ -262,6 +405,14 @@ impl Agent {
         event
             .metadata
             .insert("routing_reason".into(), decision.reason.clone());
+        // Size context assembly to the chosen model rather than a static budget
+        let (_, max_output_tokens) = estimate_tokens(&event);
+        if let Some(budget) = self
+            .model_router
+            .context_budget(&decision, max_output_tokens as usize)
+        {
+            budget.to_metadata(&mut event.metadata);
+        }
 
         match decision.route {
             Route::Local | Route::LocalFallback => {
@@ -315,9 +466,9 @@ impl Agent {
         }
     }
 
-    /// Compute effective routing policy from agent config parameters (fallback to router defaults)
-    fn effective_policy(&self) -> RoutingPolicy {
-        let base = self.model_router.policy();
+    /// Compute effective routing constraints from agent config parameters (fallback to router defaults)
+    fn effective_constraints(&self) -> RoutingConstraints {
+        let base = self.model_router.constraints();
         let p = &self.config.parameters;
 
         let privacy = p
@@ -346,7 +497,7 @@ impl Agent {
             .and_then(|s| s.parse::<f32>().ok())
             .unwrap_or(base.quality_threshold);
 
-        RoutingPolicy {
+        RoutingConstraints {
             privacy_level: privacy,
             latency_budget_ms,
             cost_cap_per_event,
@@ -358,6 +509,13 @@ impl Agent {
     async fn execute_action(&self, action: Action) -> Result<()> {
         debug!("Executing action: {}", action.action_type);
 
+        if action.action_type == REPLY_ACTION {
+            return self.publish_reply(action).await;
+        }
+        if action.action_type == RESULT_ACTION {
+            return self.publish_result(action).await;
+        }
+
         // Parse payload as JSON
         let args: serde_json::Value = if action.payload.is_empty() {
             serde_json::Value::Null
@@ -369,7 +527,7 @@ impl Agent {
         let res = self.tool_registry.call(&action.action_type, args).await;
 
         // Optionally publish result event for observability
-        let evt = Event {
+        let mut evt = Event {
             id: format!(
                 "evt_action_result_{}",
                 chrono::Utc::now().timestamp_millis()
@@ -399,10 +557,8 @@ impl Agent {
         };
 
         // Best-effort publish; ignore delivery count
-        let _ = self
-            .event_bus
-            .publish(&format!("agent.{}", self.config.agent_id), evt)
-            .await;
+        let topic = self.scope(&format!("agent.{}", self.config.agent_id), &mut evt);
+        let _ = self.event_bus.publish(&topic, evt).await;
 
         // Record action execution metric
         self.actions_executed_counter.add(
@@ -422,4 +578,86 @@ impl Agent {
 
         Ok(())
     }
+
+    /// Publish a `REPLY_ACTION`'s payload as a response event to its `reply_to` topic
+    async fn publish_reply(&self, action: Action) -> Result<()> {
+        let Some(reply_to) = action.parameters.get("reply_to") else {
+            warn!("Reply action without reply_to; dropping");
+            return Ok(());
+        };
+        let mut metadata = std::collections::HashMap::new();
+        if let Some(correlation_id) = action.parameters.get("correlation_id") {
+            metadata.insert(keys::CORRELATION_ID.to_string(), correlation_id.clone());
+        }
+        if let Some(caused_by) = action.parameters.get("caused_by") {
+            metadata.insert(keys::CAUSED_BY.to_string(), caused_by.clone());
+        }
+        let mut evt = Event {
+            id: format!("evt_response_{}", chrono::Utc::now().timestamp_millis()),
+            r#type: action
+                .parameters
+                .get(REPLY_EVENT_TYPE_PARAM)
+                .cloned()
+                .unwrap_or_else(|| RESPONSE_EVENT.to_string()),
+            timestamp_ms: chrono::Utc::now().timestamp_millis(),
+            source: format!("agent.{}", self.config.agent_id),
+            metadata,
+            payload: action.payload,
+            confidence: 1.0,
+            tags: vec![],
+            priority: action.priority,
+        };
+        let reply_to = &self.scope(reply_to, &mut evt);
+        if let Err(e) = self.event_bus.publish(reply_to, evt).await {
+            warn!(reply_to = %reply_to, error = %e, "Failed to publish reply");
+        }
+        Ok(())
+    }
+
+    /// Publish a `RESULT_ACTION`'s payload as a structured result to `result.<agent_id>`
+    async fn publish_result(&self, action: Action) -> Result<()> {
+        let topic = result_topic(&self.config.agent_id);
+        let mut metadata = std::collections::HashMap::new();
+        for key in [
+            result_keys::KIND,
+            result_keys::SCHEMA,
+            result_keys::CONTENT_TYPE,
+            keys::CORRELATION_ID,
+            keys::CAUSED_BY,
+        ] {
+            if let Some(value) = action.parameters.get(key) {
+                metadata.insert(key.to_string(), value.clone());
+            }
+        }
+        let mut evt = Event {
+            id: format!("evt_result_{}", chrono::Utc::now().timestamp_millis()),
+            r#type: RESULT_EVENT.to_string(),
+            timestamp_ms: chrono::Utc::now().timestamp_millis(),
+            source: format!("agent.{}", self.config.agent_id),
+            metadata,
+            payload: action.payload,
+            confidence: 1.0,
+            tags: vec!["result".into()],
+            priority: action.priority,
+        };
+        let topic = self.scope(&topic, &mut evt);
+        if let Err(e) = self.event_bus.publish(&topic, evt).await {
+            warn!(topic = %topic, error = %e, "Failed to publish structured result");
+        }
+        Ok(())
+    }
+
+    /// Bus topic of `topic` for this agent; in a namespace, `event` is stamped with it
+    /// so the bus keeps it there
+    fn scope(&self, topic: &str, event: &mut Event) -> String {
+        match &self.namespace {
+            Some(ns) => {
+                event
+                    .metadata
+                    .insert(crate::tenancy::TENANT_KEY.to_string(), ns.to_string());
+                ns.scope(topic)
+            }
+            None => topic.to_string(),
+        }
+    }
 }
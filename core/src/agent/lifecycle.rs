@@ -0,0 +1,255 @@
+//! Hibernation of idle agents.
+//!
+//! Deployments that create an agent per user or session accumulate agents that sit idle
+//! most of the time. Agents created with `AgentRuntime::create_agent_with_factory` can be
+//! hibernated once a `HibernationPolicy` is set:
+//!
+//! - An agent that has not handled an event for `idle_after` and whose mailbox is empty
+//!   runs `on_shutdown` and drops its behavior. Its `AgentState` is checkpointed as
+//!   encoded bytes and cleared.
+//! - Its EventBus subscriptions stay in place, so events addressed to it (its topics,
+//!   private reply topic and schedules) keep arriving in its mailbox.
+//! - The first such event rebuilds the behavior with the agent's factory, restores the
+//!   checkpointed state and runs `on_init` again; the event is then handled as usual.
+//!
+//! Operators can also drive this by hand: `hibernate_agent`, `wake_agent` (reactivate
+//! without an event) and `restart_agent` (a fresh behavior on the same state).
+//!
+//! ```rust,ignore
+//! runtime.set_hibernation_policy(HibernationPolicy::new(Duration::from_secs(600)));
+//! let factory: AgentFactory = Arc::new(|_config| Ok(Box::new(SessionAgent::default()) as _));
+//! runtime.create_agent_with_factory(config, factory).await?;
+//! ```
+//!
+//! Any agent can also be paused and drained:
+//!
+//! - `pause_agent` stops it from taking events off its mailbox; subscriptions and
+//!   schedules stay in place, so events queue up until `resume_agent`.
+//! - `drain_agent` unsubscribes it, lets it handle what is already queued, runs
+//!   `on_shutdown` and removes it.
+//!
+//! Each transition is published on `LIFECYCLE_TOPIC` (an `agent.lifecycle` event with
+//! `agent_id` and `state` metadata) and mirrored in the runtime's `AgentDirectory`.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use prost::Message;
+use tokio::sync::{Notify, RwLock};
+use tracing::{info, warn};
+
+use crate::proto::{AgentConfig, AgentState};
+use crate::Result;
+
+use super::behavior::AgentBehavior;
+use super::instance::Agent;
+
+/// Topic pause, resume and drain transitions are published on
+pub const LIFECYCLE_TOPIC: &str = crate::proto::conventions::topics::AGENT_LIFECYCLE;
+
+/// Builds a fresh behavior for an agent; called at creation and on every reactivation
+pub type AgentFactory = Arc<dyn Fn(&AgentConfig) -> Result<Box<dyn AgentBehavior>> + Send + Sync>;
+
+/// Shortest and longest interval between idle checks chosen by `HibernationPolicy::new`
+const MIN_SWEEP_INTERVAL: Duration = Duration::from_millis(50);
+const MAX_SWEEP_INTERVAL: Duration = Duration::from_secs(30);
+
+/// When idle agents are hibernated
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HibernationPolicy {
+    /// How long an agent's mailbox must stay empty before it is hibernated
+    pub idle_after: Duration,
+    /// How often agents are checked
+    pub sweep_interval: Duration,
+}
+
+impl HibernationPolicy {
+    /// Hibernate agents idle for `idle_after`, checking four times per period
+    pub fn new(idle_after: Duration) -> Self {
+        Self {
+            idle_after,
+            sweep_interval: (idle_after / 4).clamp(MIN_SWEEP_INTERVAL, MAX_SWEEP_INTERVAL),
+        }
+    }
+
+    pub fn with_sweep_interval(mut self, interval: Duration) -> Self {
+        self.sweep_interval = interval;
+        self
+    }
+
+    /// Policy from `LOOM_AGENT_IDLE_MS`; None when unset or zero
+    pub fn from_env() -> Option<Self> {
+        std::env::var("LOOM_AGENT_IDLE_MS")
+            .ok()
+            .and_then(|v| v.trim().parse::<u64>().ok())
+            .filter(|ms| *ms > 0)
+            .map(|ms| Self::new(Duration::from_millis(ms)))
+    }
+}
+
+/// Hibernation state of an agent created with a factory
+pub(crate) struct Lifecycle {
+    /// State shared by the agent's successive activations
+    state: Arc<RwLock<AgentState>>,
+    /// Asks the running agent to hibernate; replaced on every activation so requests
+    /// racing with the previous hibernation are dropped
+    hibernate: Mutex<Arc<Notify>>,
+    /// Wakes the hibernated agent without an event; replaced likewise
+    wake: Mutex<Arc<Notify>>,
+    /// Reactivate right after the requested hibernation
+    restart: AtomicBool,
+    hibernated: AtomicBool,
+    /// Encoded `AgentState` while hibernated
+    checkpoint: Mutex<Option<Vec<u8>>>,
+}
+
+impl Lifecycle {
+    /// Lifecycle of the agent owning `state`
+    pub(crate) fn new(state: Arc<RwLock<AgentState>>) -> Self {
+        Self {
+            state,
+            hibernate: Mutex::new(Arc::new(Notify::new())),
+            wake: Mutex::new(Arc::new(Notify::new())),
+            restart: AtomicBool::new(false),
+            hibernated: AtomicBool::new(false),
+            checkpoint: Mutex::new(None),
+        }
+    }
+
+    pub(crate) fn is_hibernated(&self) -> bool {
+        self.hibernated.load(Ordering::SeqCst)
+    }
+
+    /// Ask the running agent to hibernate once its mailbox is empty
+    pub(crate) fn request_hibernation(&self) {
+        if !self.is_hibernated() {
+            self.hibernate.lock().unwrap().notify_one();
+        }
+    }
+
+    /// Reactivate the hibernated agent without waiting for an event; false if it is
+    /// running
+    pub(crate) fn request_wake(&self) -> bool {
+        if !self.is_hibernated() {
+            return false;
+        }
+        self.wake.lock().unwrap().notify_one();
+        true
+    }
+
+    /// Shut the behavior down once the mailbox is empty and start a fresh one on the
+    /// same state
+    pub(crate) fn request_restart(&self) {
+        if !self.request_wake() {
+            self.restart.store(true, Ordering::SeqCst);
+            self.request_hibernation();
+        }
+    }
+
+    /// Milliseconds since the agent last handled an event, at `now_ms`; None while it is
+    /// handling one
+    pub(crate) fn idle_ms(&self, now_ms: i64) -> Option<i64> {
+        let state = self.state.try_read().ok()?;
+        Some(now_ms - state.last_update_ms)
+    }
+
+    /// Encode the state and free everything but the agent id
+    async fn checkpoint(&self) {
+        let mut state = self.state.write().await;
+        let encoded = state.encode_to_vec();
+        *state = AgentState {
+            agent_id: state.agent_id.clone(),
+            last_update_ms: state.last_update_ms,
+            ..Default::default()
+        };
+        *self.checkpoint.lock().unwrap() = Some(encoded);
+        self.hibernated.store(true, Ordering::SeqCst);
+    }
+
+    /// Decode the checkpointed state, marking the agent active now
+    async fn restore(&self) {
+        let encoded = self.checkpoint.lock().unwrap().take();
+        let mut state = self.state.write().await;
+        if let Some(encoded) = encoded {
+            match AgentState::decode(encoded.as_slice()) {
+                Ok(restored) => *state = restored,
+                Err(e) => warn!(
+                    agent_id = %state.agent_id,
+                    error = %e,
+                    "Discarding unreadable agent checkpoint"
+                ),
+            }
+        }
+        state.last_update_ms = chrono::Utc::now().timestamp_millis();
+        *self.hibernate.lock().unwrap() = Arc::new(Notify::new());
+        *self.wake.lock().unwrap() = Arc::new(Notify::new());
+        self.hibernated.store(false, Ordering::SeqCst);
+    }
+}
+
+/// Run `agent`, hibernating and reactivating it as `lifecycle` asks, until its mailbox
+/// closes
+pub(crate) async fn supervise(mut agent: Agent, factory: AgentFactory, lifecycle: Arc<Lifecycle>) {
+    loop {
+        let config = agent.config.clone();
+        let tool_registry = Arc::clone(&agent.tool_registry);
+        let event_bus = Arc::clone(&agent.event_bus);
+        let model_router = agent.model_router.clone();
+        let paused = agent.paused.clone();
+
+        let hibernate = Arc::clone(&lifecycle.hibernate.lock().unwrap());
+        let mut mailbox = match agent.run_until(Some(hibernate)).await {
+            Ok(Some(mailbox)) => mailbox,
+            Ok(None) => return,
+            Err(e) => {
+                warn!("Agent error: {}", e);
+                return;
+            }
+        };
+        lifecycle.checkpoint().await;
+
+        let event = if lifecycle.restart.swap(false, Ordering::SeqCst) {
+            info!("Agent {} restarting", config.agent_id);
+            None
+        } else {
+            info!("Agent {} hibernated", config.agent_id);
+            let wake = Arc::clone(&lifecycle.wake.lock().unwrap());
+            tokio::select! {
+                biased;
+                event = mailbox.recv() => match event {
+                    Some(event) => Some(event),
+                    // Deleting the agent closes the mailbox
+                    None => return,
+                },
+                _ = wake.notified() => None,
+            }
+        };
+        lifecycle.restore().await;
+        let behavior = match factory(&config) {
+            Ok(behavior) => behavior,
+            Err(e) => {
+                warn!("Agent {} could not be reactivated: {}", config.agent_id, e);
+                return;
+            }
+        };
+        match &event {
+            Some(event) => info!(
+                "Agent {} reactivated by event {}",
+                config.agent_id, event.id
+            ),
+            None => info!("Agent {} reactivated", config.agent_id),
+        }
+        agent = Agent::new(
+            config,
+            behavior,
+            mailbox,
+            tool_registry,
+            event_bus,
+            model_router,
+        );
+        agent.state = Arc::clone(&lifecycle.state);
+        agent.pending = event;
+        agent.paused = paused;
+    }
+}
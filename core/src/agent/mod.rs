@@ -4,7 +4,14 @@
 //! - `AgentBehavior`: Trait for implementing agent logic
 //! - `Agent`: Running agent instance with event loop
 //! - `AgentRuntime`: Manager for agent lifecycle
+//! - `declarative`: Agents spawned and hot-reloaded from a TOML/YAML agents file
 //! - `directory`: Agent and capability discovery
+//! - `lifecycle`: Hibernation of idle agents and their reactivation on new events, plus
+//!   the `agent.lifecycle` events of paused and drained agents
+//! - `params`: `${secrets...}`/`${env...}`/`${config...}` references in agent parameters
+//! - `reminders`: One-off reminders agents schedule for themselves
+//! - `schedule`: Recurring self-triggers declared in `AgentConfig.parameters`
+//! - `sentinel`: Built-in anomaly detection publishing `anomaly.detected` events
 //!
 //! # Basic Agent
 //!
@@ -41,11 +48,19 @@
 pub use crate::proto::{Action, AgentConfig, AgentState};
 
 mod behavior;
+pub mod declarative;
 pub mod directory;
+mod directory_store;
 mod instance;
+mod lifecycle;
+pub mod params;
+pub mod reminders;
 mod runtime;
+pub mod schedule;
+pub mod sentinel;
 
 // Public re-exports so external code keeps using crate::agent::{Agent, AgentRuntime}
 pub use behavior::AgentBehavior;
 pub use instance::Agent;
+pub use lifecycle::{AgentFactory, HibernationPolicy, LIFECYCLE_TOPIC};
 pub use runtime::AgentRuntime;
@@ -0,0 +1,161 @@
+//! Templated agent parameters.
+//!
+//! `AgentConfig.parameters` values may reference settings that are looked up when the
+//! agent is created instead of being written into the agents file:
+//!
+//! - `${secrets.NAME}`: the secret `NAME` from a `SecretProvider` (the process
+//!   environment by default)
+//! - `${env.NAME}`: the environment variable `NAME`
+//! - `${config.section.key}`: a `LoomConfig` setting, e.g. `${config.llm.model}`; its
+//!   variable wins over the manifest, as at startup
+//!
+//! ```toml
+//! [[agents]]
+//! id = "notifier"
+//! parameters = { "slack.token" = "${secrets.SLACK_TOKEN}", "slack.channel" = "#ops-${env.DEPLOY_ENV}" }
+//! ```
+//!
+//! References may be embedded in longer values; `$${` writes a literal `${`. A reference
+//! that cannot be resolved fails the whole agent, naming the parameter but never a
+//! resolved value. `AgentConfigLoader` resolves parameters on every apply, so a rotated
+//! secret reaches the agent on the next reload.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::config::LoomConfig;
+use crate::{LoomError, Result};
+
+/// Source of the values `${secrets.NAME}` refers to
+pub trait SecretProvider: Send + Sync {
+    /// The secret `name`; `None` if it is not set
+    fn secret(&self, name: &str) -> Option<String>;
+}
+
+/// Secrets read from the process environment
+#[derive(Debug, Clone, Copy, Default)]
+pub struct EnvSecrets;
+
+impl SecretProvider for EnvSecrets {
+    fn secret(&self, name: &str) -> Option<String> {
+        std::env::var(name).ok()
+    }
+}
+
+impl SecretProvider for HashMap<String, String> {
+    fn secret(&self, name: &str) -> Option<String> {
+        self.get(name).cloned()
+    }
+}
+
+/// Resolves `${...}` references in agent parameters, see the module docs
+#[derive(Clone)]
+pub struct ParameterResolver {
+    secrets: Arc<dyn SecretProvider>,
+    config: LoomConfig,
+}
+
+impl Default for ParameterResolver {
+    fn default() -> Self {
+        Self::new(Arc::new(EnvSecrets))
+    }
+}
+
+impl ParameterResolver {
+    /// Resolver reading secrets from `secrets` and settings from the environment
+    pub fn new(secrets: Arc<dyn SecretProvider>) -> Self {
+        Self {
+            secrets,
+            config: LoomConfig::default(),
+        }
+    }
+
+    /// Read `${config...}` settings the environment does not set from `config`
+    pub fn with_config(mut self, config: LoomConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// `parameters` with every reference replaced
+    pub fn resolve(&self, parameters: &HashMap<String, String>) -> Result<HashMap<String, String>> {
+        parameters
+            .iter()
+            .map(|(name, value)| {
+                self.resolve_value(value)
+                    .map(|value| (name.clone(), value))
+                    .map_err(|e| LoomError::ConfigError(format!("Parameter {name}: {e}")))
+            })
+            .collect()
+    }
+
+    /// `value` with every reference replaced
+    pub fn resolve_value(&self, value: &str) -> std::result::Result<String, String> {
+        let mut resolved = String::with_capacity(value.len());
+        let mut rest = value;
+        while let Some(start) = rest.find("${") {
+            if rest[..start].ends_with('$') {
+                resolved.push_str(&rest[..start - 1]);
+                resolved.push_str("${");
+                rest = &rest[start + 2..];
+                continue;
+            }
+            let Some(len) = rest[start..].find('}') else {
+                return Err(format!("unterminated reference in '{value}'"));
+            };
+            resolved.push_str(&rest[..start]);
+            resolved.push_str(&self.lookup(&rest[start + 2..start + len])?);
+            rest = &rest[start + len + 1..];
+        }
+        resolved.push_str(rest);
+        Ok(resolved)
+    }
+
+    fn lookup(&self, reference: &str) -> std::result::Result<String, String> {
+        let (source, name) = reference.split_once('.').unwrap_or((reference, ""));
+        if name.is_empty() {
+            return Err(format!("${{{reference}}} names no value"));
+        }
+        match source {
+            "secrets" => self
+                .secrets
+                .secret(name)
+                .ok_or_else(|| format!("secret {name} is not set")),
+            "env" => std::env::var(name).map_err(|_| format!("variable {name} is not set")),
+            "config" => {
+                let (section, key) = name
+                    .split_once('.')
+                    .ok_or_else(|| format!("${{{reference}}}: expected config.<section>.<key>"))?;
+                self.config
+                    .value(section, key)
+                    .ok_or_else(|| format!("setting {section}.{key} is not set"))
+            }
+            _ => Err(format!(
+                "${{{reference}}}: must start with secrets, env or config"
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resolves_embedded_references_and_escapes() {
+        let secrets = HashMap::from([("TOKEN".to_string(), "s3cret".to_string())]);
+        let resolver = ParameterResolver::new(Arc::new(secrets));
+        assert_eq!(
+            resolver.resolve_value("Bearer ${secrets.TOKEN}").unwrap(),
+            "Bearer s3cret"
+        );
+        assert_eq!(
+            resolver
+                .resolve_value("$${secrets.TOKEN} costs $5")
+                .unwrap(),
+            "${secrets.TOKEN} costs $5"
+        );
+        assert!(resolver.resolve_value("${secrets.MISSING}").is_err());
+        assert!(resolver.resolve_value("${vault.TOKEN}").is_err());
+        assert!(resolver.resolve_value("${secrets.TOKEN").is_err());
+    }
+}
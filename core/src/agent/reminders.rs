@@ -0,0 +1,372 @@
+//! One-off reminders agents schedule for themselves.
+//!
+//! A reminder delivers an event (`schedule.reminder` unless it names another type) to
+//! its agent's private reply topic, `agent.<id>.replies`, at a given time. Delivery goes
+//! through the EventBus, so a hibernated agent is reactivated by its reminder. Agents
+//! manage their reminders with the `schedule:remind`, `schedule:list` and
+//! `schedule:cancel` tools (`tools::native::schedule`); host code uses
+//! `ReminderScheduler` directly.
+//!
+//! `ReminderScheduler::open` stores reminders in RocksDB, so they survive restarts.
+//! Reloaded reminders are suspended until their agent exists again: an `AgentRuntime`
+//! with `with_reminders` resumes an agent's reminders in `create_agent` and suspends
+//! them when the agent is deleted or drained. A reminder that came due while suspended
+//! fires as soon as it is resumed.
+
+use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Weak};
+
+use chrono::{DateTime, Utc};
+use dashmap::DashMap;
+use opentelemetry::metrics::Counter;
+use rocksdb::{ColumnFamilyDescriptor, IteratorMode, Options, DB};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use tokio::task::JoinHandle;
+use tracing::{debug, info, warn};
+
+use super::schedule::SCHEDULE_EVENT_SOURCE;
+use crate::messaging::agent_reply_topic;
+use crate::proto::Event;
+use crate::{EventBus, LoomError, Result};
+
+/// Event type used when a reminder sets no `event_type`
+pub const DEFAULT_REMINDER_EVENT_TYPE: &str = "schedule.reminder";
+
+/// Pending reminders one agent may have unless `set_max_per_agent` changes it
+pub const DEFAULT_MAX_REMINDERS_PER_AGENT: usize = 100;
+
+const CF_REMINDERS: &str = "reminders";
+
+/// Disambiguates reminders created in the same nanosecond
+static NEXT_ID: AtomicUsize = AtomicUsize::new(0);
+
+fn next_reminder_id() -> String {
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos();
+    format!(
+        "rem_{:x}_{}",
+        nanos,
+        NEXT_ID.fetch_add(1, Ordering::Relaxed)
+    )
+}
+
+/// A future event an agent scheduled for itself
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Reminder {
+    pub id: String,
+    pub agent_id: String,
+    /// Milliseconds since Unix epoch
+    pub fire_at_ms: i64,
+    pub message: String,
+    /// Extra JSON handed back with the reminder
+    #[serde(default, skip_serializing_if = "Value::is_null")]
+    pub data: Value,
+    pub event_type: String,
+    pub created_at_ms: i64,
+}
+
+impl Reminder {
+    /// Reminder for `agent_id` at `fire_at`, with a fresh id
+    pub fn new(
+        agent_id: impl Into<String>,
+        fire_at: DateTime<Utc>,
+        message: impl Into<String>,
+    ) -> Self {
+        Self {
+            id: next_reminder_id(),
+            agent_id: agent_id.into(),
+            fire_at_ms: fire_at.timestamp_millis(),
+            message: message.into(),
+            data: Value::Null,
+            event_type: DEFAULT_REMINDER_EVENT_TYPE.to_string(),
+            created_at_ms: Utc::now().timestamp_millis(),
+        }
+    }
+
+    pub fn with_data(mut self, data: Value) -> Self {
+        self.data = data;
+        self
+    }
+
+    pub fn with_event_type(mut self, event_type: impl Into<String>) -> Self {
+        self.event_type = event_type.into();
+        self
+    }
+
+    /// Build the event delivered when the reminder fires
+    ///
+    /// The payload is JSON: `{"reminder_id", "message", "data"}`.
+    pub fn event(&self) -> Event {
+        let mut metadata = std::collections::HashMap::new();
+        metadata.insert("reminder".to_string(), self.id.clone());
+        metadata.insert("scheduled_at_ms".to_string(), self.fire_at_ms.to_string());
+        metadata.insert("agent_id".to_string(), self.agent_id.clone());
+        let payload = json!({
+            "reminder_id": self.id,
+            "message": self.message,
+            "data": self.data,
+        });
+
+        Event {
+            id: format!("evt_{}", self.id),
+            r#type: self.event_type.clone(),
+            timestamp_ms: Utc::now().timestamp_millis(),
+            source: SCHEDULE_EVENT_SOURCE.to_string(),
+            metadata,
+            payload: payload.to_string().into_bytes(),
+            confidence: 1.0,
+            tags: vec!["scheduled".to_string(), "reminder".to_string()],
+            priority: 50,
+        }
+    }
+}
+
+/// RocksDB backing: one JSON record per reminder id
+struct ReminderStore {
+    db: DB,
+}
+
+impl ReminderStore {
+    fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let mut opts = Options::default();
+        opts.create_if_missing(true);
+        opts.create_missing_column_families(true);
+
+        let cf_descriptors = vec![ColumnFamilyDescriptor::new(
+            CF_REMINDERS,
+            Options::default(),
+        )];
+        let db = DB::open_cf_descriptors(&opts, path, cf_descriptors)
+            .map_err(|e| LoomError::StorageError(e.to_string()))?;
+        Ok(Self { db })
+    }
+
+    fn put(&self, reminder: &Reminder) -> Result<()> {
+        self.db
+            .put_cf(self.cf()?, &reminder.id, serde_json::to_vec(reminder)?)
+            .map_err(|e| LoomError::StorageError(e.to_string()))
+    }
+
+    fn delete(&self, reminder_id: &str) -> Result<()> {
+        self.db
+            .delete_cf(self.cf()?, reminder_id)
+            .map_err(|e| LoomError::StorageError(e.to_string()))
+    }
+
+    /// All stored reminders; undecodable records are skipped
+    fn load_all(&self) -> Result<Vec<Reminder>> {
+        let mut reminders = Vec::new();
+        for entry in self.db.iterator_cf(self.cf()?, IteratorMode::Start) {
+            let (key, value) = entry.map_err(|e| LoomError::StorageError(e.to_string()))?;
+            match serde_json::from_slice(&value) {
+                Ok(reminder) => reminders.push(reminder),
+                Err(e) => warn!(
+                    target: "reminders",
+                    reminder_id = %String::from_utf8_lossy(&key),
+                    error = %e,
+                    "Skipping undecodable reminder record"
+                ),
+            }
+        }
+        Ok(reminders)
+    }
+
+    fn cf(&self) -> Result<&rocksdb::ColumnFamily> {
+        self.db
+            .cf_handle(CF_REMINDERS)
+            .ok_or_else(|| LoomError::StorageError(format!("Missing CF: {}", CF_REMINDERS)))
+    }
+}
+
+/// Pending reminders of all agents and the timers delivering them
+pub struct ReminderScheduler {
+    event_bus: Arc<EventBus>,
+    reminders: DashMap<String, Reminder>,
+    /// Armed reminders: reminder id -> timer task
+    timers: DashMap<String, JoinHandle<()>>,
+    store: Option<ReminderStore>,
+    max_per_agent: AtomicUsize,
+    /// Timers hold a weak handle, so dropping the scheduler stops them
+    this: Weak<ReminderScheduler>,
+    fired_counter: Counter<u64>,
+}
+
+impl ReminderScheduler {
+    /// In-memory scheduler; pending reminders are lost when the process exits
+    pub fn new(event_bus: Arc<EventBus>) -> Arc<Self> {
+        Self::build(event_bus, None, Vec::new())
+    }
+
+    /// Scheduler stored in a RocksDB database at `path`, reloading the reminders already
+    /// there suspended (see `resume`)
+    pub fn open<P: AsRef<Path>>(event_bus: Arc<EventBus>, path: P) -> Result<Arc<Self>> {
+        let store = ReminderStore::open(path)?;
+        let stored = store.load_all()?;
+        info!(target: "reminders", reminders = stored.len(), "Reminder persistence initialized");
+        Ok(Self::build(event_bus, Some(store), stored))
+    }
+
+    fn build(
+        event_bus: Arc<EventBus>,
+        store: Option<ReminderStore>,
+        stored: Vec<Reminder>,
+    ) -> Arc<Self> {
+        let fired_counter = opentelemetry::global::meter("loom.agent_runtime")
+            .u64_counter("agent_runtime.reminders.fired")
+            .with_description("Reminders delivered to agents")
+            .init();
+        Arc::new_cyclic(|this| Self {
+            event_bus,
+            reminders: stored.into_iter().map(|r| (r.id.clone(), r)).collect(),
+            timers: DashMap::new(),
+            store,
+            max_per_agent: AtomicUsize::new(DEFAULT_MAX_REMINDERS_PER_AGENT),
+            this: this.clone(),
+            fired_counter,
+        })
+    }
+
+    pub fn is_persistent(&self) -> bool {
+        self.store.is_some()
+    }
+
+    /// Limit the pending reminders of each agent
+    pub fn set_max_per_agent(&self, max: usize) {
+        self.max_per_agent.store(max, Ordering::Relaxed);
+    }
+
+    /// Store `reminder` and start its timer.
+    ///
+    /// Fails with `LoomError::QuotaExceeded` if its agent already has the maximum number
+    /// of pending reminders. A reminder due in the past fires at once.
+    pub fn schedule(&self, reminder: Reminder) -> Result<Reminder> {
+        let max = self.max_per_agent.load(Ordering::Relaxed);
+        if self.pending(&reminder.agent_id).len() >= max {
+            return Err(LoomError::QuotaExceeded(format!(
+                "agent '{}' is limited to {} pending reminders",
+                reminder.agent_id, max
+            )));
+        }
+        if let Some(ref store) = self.store {
+            store.put(&reminder)?;
+        }
+        self.reminders.insert(reminder.id.clone(), reminder.clone());
+        self.arm(&reminder);
+        debug!(target: "reminders", agent_id = %reminder.agent_id, reminder_id = %reminder.id, fire_at_ms = reminder.fire_at_ms, "Reminder scheduled");
+        Ok(reminder)
+    }
+
+    /// Remove a pending reminder of `agent_id`; returns whether there was one
+    pub fn cancel(&self, agent_id: &str, reminder_id: &str) -> Result<bool> {
+        let removed = self
+            .reminders
+            .remove_if(reminder_id, |_, reminder| reminder.agent_id == agent_id);
+        if removed.is_none() {
+            return Ok(false);
+        }
+        if let Some((_, timer)) = self.timers.remove(reminder_id) {
+            timer.abort();
+        }
+        if let Some(ref store) = self.store {
+            store.delete(reminder_id)?;
+        }
+        debug!(target: "reminders", agent_id = %agent_id, reminder_id = %reminder_id, "Reminder cancelled");
+        Ok(true)
+    }
+
+    /// Pending reminders of `agent_id`, soonest first
+    pub fn pending(&self, agent_id: &str) -> Vec<Reminder> {
+        let mut pending: Vec<Reminder> = self
+            .reminders
+            .iter()
+            .filter(|entry| entry.agent_id == agent_id)
+            .map(|entry| entry.value().clone())
+            .collect();
+        pending.sort_by(|a, b| a.fire_at_ms.cmp(&b.fire_at_ms).then(a.id.cmp(&b.id)));
+        pending
+    }
+
+    /// Start the timers of `agent_id`'s suspended reminders; returns how many
+    pub fn resume(&self, agent_id: &str) -> usize {
+        let suspended: Vec<Reminder> = self
+            .pending(agent_id)
+            .into_iter()
+            .filter(|reminder| !self.timers.contains_key(&reminder.id))
+            .collect();
+        for reminder in &suspended {
+            self.arm(reminder);
+        }
+        suspended.len()
+    }
+
+    /// Stop the timers of `agent_id`'s reminders, keeping them pending; returns how many
+    pub fn suspend(&self, agent_id: &str) -> usize {
+        let mut suspended = 0;
+        for reminder in self.pending(agent_id) {
+            if let Some((_, timer)) = self.timers.remove(&reminder.id) {
+                timer.abort();
+                suspended += 1;
+            }
+        }
+        suspended
+    }
+
+    fn arm(&self, reminder: &Reminder) {
+        let this = self.this.clone();
+        let id = reminder.id.clone();
+        let fire_at_ms = reminder.fire_at_ms;
+        let timer = tokio::spawn(async move {
+            let wait = (fire_at_ms - Utc::now().timestamp_millis()).max(0) as u64;
+            tokio::time::sleep(std::time::Duration::from_millis(wait)).await;
+            if let Some(scheduler) = this.upgrade() {
+                scheduler.fire(&id).await;
+            }
+        });
+        if let Some(previous) = self.timers.insert(reminder.id.clone(), timer) {
+            previous.abort();
+        }
+        // A reminder already due may have fired before its timer was recorded
+        if !self.reminders.contains_key(&reminder.id) {
+            self.timers.remove(&reminder.id);
+        }
+    }
+
+    async fn fire(&self, reminder_id: &str) {
+        self.timers.remove(reminder_id);
+        let Some((_, reminder)) = self.reminders.remove(reminder_id) else {
+            return;
+        };
+        if let Some(ref store) = self.store {
+            if let Err(e) = store.delete(reminder_id) {
+                warn!(target: "reminders", reminder_id = %reminder_id, error = %e, "Failed to remove fired reminder");
+            }
+        }
+
+        let topic = agent_reply_topic(&reminder.agent_id);
+        match self.event_bus.publish(&topic, reminder.event()).await {
+            Ok(_) => {
+                self.fired_counter.add(1, &[]);
+                debug!(target: "reminders", agent_id = %reminder.agent_id, reminder_id = %reminder_id, "Reminder delivered");
+            }
+            Err(e) => warn!(
+                target: "reminders",
+                agent_id = %reminder.agent_id,
+                reminder_id = %reminder_id,
+                error = %e,
+                "Failed to deliver reminder"
+            ),
+        }
+    }
+}
+
+impl Drop for ReminderScheduler {
+    fn drop(&mut self) {
+        for timer in self.timers.iter() {
+            timer.abort();
+        }
+    }
+}
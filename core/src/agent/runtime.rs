@@ -1,17 +1,31 @@
-use std::sync::Arc;
+use std::sync::{Arc, Mutex, Weak};
+use std::time::Duration;
 
+use async_trait::async_trait;
 use dashmap::DashMap;
 use opentelemetry::metrics::{Counter, UpDownCounter};
-use tokio::sync::mpsc;
+use tokio::sync::{mpsc, watch};
+use tokio::task::JoinHandle;
+use tokio::time::Instant;
 use tracing::{info, warn};
 
+use crate::cognitive::llm::policy::policy_from_name;
 use crate::cognitive::llm::router::ModelRouter;
+use crate::cognitive::llm::tiers::LatencyClass;
+use crate::cognitive::BOOTSTRAP_DIR_PARAM;
+use crate::messaging::backpressure::{BackpressurePolicy, BACKPRESSURE_PARAM};
+use crate::namespace::{Namespace, NAMESPACE_KEY};
 use crate::proto::AgentConfig;
+use crate::shutdown::ShutdownHook;
 use crate::tools::ToolRegistry;
 use crate::{proto, Event, EventBus, LoomError, Result};
 
 use super::behavior::AgentBehavior;
+use super::directory::{AgentDirectory, AgentStatus};
 use super::instance::Agent;
+use super::lifecycle::{supervise, AgentFactory, HibernationPolicy, Lifecycle, LIFECYCLE_TOPIC};
+use super::reminders::ReminderScheduler;
+use super::schedule::{parse_schedules, AgentScheduler};
 
 /// Subscription handle for an agent
 #[derive(Debug)]
@@ -33,14 +47,37 @@ struct AgentMetadata {
     event_tx: mpsc::Sender<Event>,
     /// Active subscriptions
     subscriptions: Arc<DashMap<String, AgentSubscription>>,
+    /// Backpressure policy of the agent's topic subscriptions
+    backpressure: BackpressurePolicy,
+    /// Namespace the agent's topics are scoped to
+    namespace: Option<Namespace>,
+    /// Hibernation state, for agents created with a factory
+    lifecycle: Option<Arc<Lifecycle>>,
+    /// Whether the agent is paused; dropping it releases a paused agent
+    pause: watch::Sender<bool>,
 }
 
+/// Active hibernation policy and the task sweeping for idle agents
+type HibernationSlot = Option<(HibernationPolicy, JoinHandle<()>)>;
+
+/// How long `AgentRuntime::shutdown` waits for agents to drain their mailboxes
+const DEFAULT_DRAIN_TIMEOUT: Duration = Duration::from_secs(5);
+
 /// Agent runtime manager
+///
+/// Clones share the same set of agents.
+#[derive(Clone)]
 pub struct AgentRuntime {
     agents: Arc<DashMap<String, AgentMetadata>>,
     event_bus: Arc<EventBus>,
     tool_registry: Arc<ToolRegistry>,
     model_router: ModelRouter,
+    scheduler: Arc<AgentScheduler>,
+    hibernation: Arc<Mutex<HibernationSlot>>,
+    /// Directory mirroring pause and drain transitions
+    directory: Option<Arc<AgentDirectory>>,
+    /// Reminders resumed and suspended with their agents
+    reminders: Option<Arc<ReminderScheduler>>,
     // OpenTelemetry metrics
     agents_active_gauge: UpDownCounter<i64>,
     agents_created_counter: Counter<u64>,
@@ -88,6 +125,10 @@ impl AgentRuntime {
             event_bus,
             tool_registry,
             model_router,
+            scheduler: Arc::new(AgentScheduler::new()),
+            hibernation: Arc::new(Mutex::new(None)),
+            directory: None,
+            reminders: None,
             agents_active_gauge,
             agents_created_counter,
             agents_deleted_counter,
@@ -96,6 +137,23 @@ impl AgentRuntime {
         })
     }
 
+    /// Mirror pause and drain transitions into `directory`
+    ///
+    /// Only agents registered there are updated: paused and draining agents get the
+    /// matching `AgentStatus`, resumed ones `Active`, and drained ones are marked
+    /// disconnected.
+    pub fn with_directory(mut self, directory: Arc<AgentDirectory>) -> Self {
+        self.directory = Some(directory);
+        self
+    }
+
+    /// Resume an agent's reminders in `reminders` when it is created, and suspend them
+    /// when it is deleted or drained, so reloaded reminders wait for their agent
+    pub fn with_reminders(mut self, reminders: Arc<ReminderScheduler>) -> Self {
+        self.reminders = Some(reminders);
+        self
+    }
+
     pub async fn start(&mut self) -> Result<()> {
         info!("Agent Runtime started");
         Ok(())
@@ -103,19 +161,68 @@ impl AgentRuntime {
 
     pub async fn shutdown(&mut self) -> Result<()> {
         info!("Agent Runtime shutting down");
+        self.drain(Instant::now() + DEFAULT_DRAIN_TIMEOUT).await;
+        Ok(())
+    }
 
-        // Stop all agents
-        for entry in self.agents.iter() {
-            // Abort forwarder tasks
-            for sub in entry.value().subscriptions.iter() {
-                sub.value().forwarder_handle.abort();
+    /// Stop all agents gracefully.
+    ///
+    /// Unsubscribes every agent from the EventBus so no new events arrive, lets each
+    /// agent process what is already in its mailbox and run `on_shutdown`, and aborts
+    /// agents still running at `deadline`. Returns the number of aborted agents.
+    pub async fn drain(&self, deadline: Instant) -> usize {
+        let ids: Vec<String> = self.agents.iter().map(|e| e.key().clone()).collect();
+        let mut stopping: Vec<(String, JoinHandle<()>, Vec<JoinHandle<()>>)> = Vec::new();
+        for id in ids {
+            if let Some((task_handle, forwarders)) = self.detach_agent(&id).await {
+                stopping.push((id, task_handle, forwarders));
             }
-            // Abort agent task
-            entry.value().task_handle.abort();
         }
-        self.agents.clear();
 
-        Ok(())
+        let mut aborted = 0;
+        for (id, task_handle, forwarders) in stopping {
+            if !wait_stopped(&id, task_handle, forwarders, deadline).await {
+                aborted += 1;
+            }
+        }
+        aborted
+    }
+
+    /// Remove an agent and cut it off from new events, leaving it running until its
+    /// mailbox is empty. Returns its task and subscription forwarders.
+    async fn detach_agent(&self, id: &str) -> Option<(JoinHandle<()>, Vec<JoinHandle<()>>)> {
+        let (_, metadata) = self.agents.remove(id)?;
+        // Dropping the pause sender releases a paused agent
+        let AgentMetadata {
+            task_handle,
+            event_tx,
+            subscriptions,
+            ..
+        } = metadata;
+
+        // Schedule timers hold mailbox senders; stop them first
+        self.scheduler.unregister(id);
+        if let Some(reminders) = &self.reminders {
+            reminders.suspend(id);
+        }
+
+        // Unsubscribing drops the bus side of each forwarder's queue; forwarders exit
+        // once drained, and the mailbox closes when the last sender is gone.
+        let topics: Vec<String> = subscriptions.iter().map(|e| e.key().clone()).collect();
+        let mut forwarders = Vec::with_capacity(topics.len());
+        for topic in topics {
+            if let Some((_, sub)) = subscriptions.remove(&topic) {
+                let _ = self.event_bus.unsubscribe(&sub.subscription_id).await;
+                forwarders.push(sub.forwarder_handle);
+            }
+        }
+        drop(event_tx);
+
+        self.agents_active_gauge.add(-1, &[]);
+        self.agents_deleted_counter.add(1, &[]);
+        self.unsubscriptions_counter
+            .add(forwarders.len() as u64, &[]);
+        Some((task_handle, forwarders))
     }
 
     /// Create and start an Agent
@@ -124,9 +231,73 @@ impl AgentRuntime {
         &self,
         config: AgentConfig,
         behavior: Box<dyn AgentBehavior>,
+    ) -> Result<String> {
+        self.spawn_agent(config, behavior, None).await
+    }
+
+    /// Create and start an Agent that can be hibernated
+    ///
+    /// `factory` builds the agent's behavior now and again each time the agent is
+    /// reactivated after hibernating (see `set_hibernation_policy`).
+    #[tracing::instrument(skip(self, factory), fields(agent_id = %config.agent_id, topic_count = config.subscribed_topics.len()))]
+    pub async fn create_agent_with_factory(
+        &self,
+        config: AgentConfig,
+        factory: AgentFactory,
+    ) -> Result<String> {
+        let behavior = factory(&config)?;
+        self.spawn_agent(config, behavior, Some(factory)).await
+    }
+
+    /// Start an agent running `behavior`; with a `factory` it can be hibernated
+    pub(crate) async fn spawn_agent(
+        &self,
+        config: AgentConfig,
+        behavior: Box<dyn AgentBehavior>,
+        factory: Option<AgentFactory>,
     ) -> Result<String> {
         let agent_id = config.agent_id.clone();
 
+        // Validate recurring self-triggers before touching the EventBus
+        let schedules = parse_schedules(&config.parameters).map_err(|e| {
+            LoomError::AgentError(format!("Agent {} has an invalid schedule: {}", agent_id, e))
+        })?;
+        if let Some(name) = config.parameters.get("routing.policy") {
+            if policy_from_name(name).is_none() {
+                return Err(LoomError::AgentError(format!(
+                    "Agent {} has an unknown routing.policy: {}",
+                    agent_id, name
+                )));
+            }
+        }
+        if let Some(name) = config.parameters.get("routing.latency_class") {
+            if LatencyClass::parse(name).is_none() {
+                return Err(LoomError::AgentError(format!(
+                    "Agent {} has an unknown routing.latency_class: {}",
+                    agent_id, name
+                )));
+            }
+        }
+        if let Some(dir) = config.parameters.get(BOOTSTRAP_DIR_PARAM) {
+            if !std::path::Path::new(dir).is_dir() {
+                return Err(LoomError::AgentError(format!(
+                    "Agent {} has a {} that is not a directory: {}",
+                    agent_id, BOOTSTRAP_DIR_PARAM, dir
+                )));
+            }
+        }
+
+        let backpressure = match config.parameters.get(BACKPRESSURE_PARAM) {
+            Some(name) => BackpressurePolicy::parse(name).ok_or_else(|| {
+                LoomError::AgentError(format!(
+                    "Agent {} has an unknown {}: {}",
+                    agent_id, BACKPRESSURE_PARAM, name
+                ))
+            })?,
+            None => BackpressurePolicy::default_for(proto::QoSLevel::QosBatched),
+        };
+        let namespace = agent_namespace(&config)?;
+
         // Create event receiving channel for agent
         let (event_tx, event_rx) = tokio::sync::mpsc::channel(1000);
 
@@ -163,11 +334,17 @@ impl AgentRuntime {
             );
         }
 
-        // Subscribe to initial topics from config
+        // Subscribe to initial topics from config, inside the agent's namespace
         for topic in &config.subscribed_topics {
+            let topic = &scope_topic(namespace.as_ref(), topic);
             let (sub_id, mut rx) = self
                 .event_bus
-                .subscribe(topic.clone(), vec![], proto::QoSLevel::QosBatched)
+                .subscribe_with_policy(
+                    topic.clone(),
+                    vec![],
+                    proto::QoSLevel::QosBatched,
+                    backpressure,
+                )
                 .await?;
 
             // Forward events to agent
@@ -188,20 +365,44 @@ impl AgentRuntime {
             );
         }
 
-        // Create and start agent
-        let agent = Agent::new(
+        // Create and start agent; a namespaced agent sees its namespace's tools
+        let tool_registry = match &namespace {
+            Some(ns) => Arc::new(self.tool_registry.for_namespace(ns)),
+            None => Arc::clone(&self.tool_registry),
+        };
+        let mut agent = Agent::new(
             config,
             behavior,
             event_rx,
-            Arc::clone(&self.tool_registry),
+            tool_registry,
             Arc::clone(&self.event_bus),
             self.model_router.clone(),
         );
-        let task_handle = tokio::spawn(async move {
-            if let Err(e) = agent.run().await {
-                warn!("Agent error: {}", e);
+        let (pause, paused) = watch::channel(false);
+        agent.paused = Some(paused);
+        let (task_handle, lifecycle) = match factory {
+            Some(factory) => {
+                let lifecycle = Arc::new(Lifecycle::new(Arc::clone(&agent.state)));
+                let task_handle = tokio::spawn(supervise(agent, factory, Arc::clone(&lifecycle)));
+                (task_handle, Some(lifecycle))
             }
-        });
+            None => {
+                let task_handle = tokio::spawn(async move {
+                    if let Err(e) = agent.run().await {
+                        warn!("Agent error: {}", e);
+                    }
+                });
+                (task_handle, None)
+            }
+        };
+
+        // Register recurring self-triggers
+        let schedule_count = schedules.len();
+        self.scheduler
+            .register(&agent_id, schedules, event_tx.clone());
+        if let Some(reminders) = &self.reminders {
+            reminders.resume(&agent_id);
+        }
 
         // Capture subscription count before moving subscriptions
         let sub_count = subscriptions.len();
@@ -210,6 +411,10 @@ impl AgentRuntime {
             task_handle,
             event_tx,
             subscriptions,
+            backpressure,
+            namespace,
+            lifecycle,
+            pause,
         };
 
         self.agents.insert(agent_id.clone(), metadata);
@@ -220,9 +425,10 @@ impl AgentRuntime {
         self.subscriptions_counter.add(sub_count as u64, &[]);
 
         info!(
-            "Created agent {} with private reply topic {}",
+            "Created agent {} with private reply topic {} and {} schedule(s)",
             agent_id,
-            crate::messaging::agent_reply_topic(&agent_id)
+            crate::messaging::agent_reply_topic(&agent_id),
+            schedule_count
         );
 
         Ok(agent_id)
@@ -234,6 +440,12 @@ impl AgentRuntime {
         if let Some((_, metadata)) = self.agents.remove(agent_id) {
             let sub_count = metadata.subscriptions.len();
 
+            // Stop recurring self-triggers
+            self.scheduler.unregister(agent_id);
+            if let Some(reminders) = &self.reminders {
+                reminders.suspend(agent_id);
+            }
+
             // Unsubscribe from all topics and abort forwarder tasks
             for entry in metadata.subscriptions.iter() {
                 let sub = entry.value();
@@ -267,7 +479,7 @@ impl AgentRuntime {
     /// # Arguments
     ///
     /// * `agent_id` - The unique identifier of the agent
-    /// * `topic` - The topic to subscribe to
+    /// * `topic` - The topic to subscribe to, relative to the agent's namespace if it has one
     ///
     /// # Errors
     ///
@@ -295,6 +507,7 @@ impl AgentRuntime {
             .agents
             .get(agent_id)
             .ok_or_else(|| LoomError::AgentError(format!("Agent {} not found", agent_id)))?;
+        let topic = scope_topic(metadata.namespace.as_ref(), &topic);
 
         // Check if already subscribed
         if metadata.subscriptions.contains_key(&topic) {
@@ -307,15 +520,26 @@ impl AgentRuntime {
         // Subscribe to event bus
         let (sub_id, mut rx) = self
             .event_bus
-            .subscribe(topic.clone(), vec![], proto::QoSLevel::QosBatched)
+            .subscribe_with_policy(
+                topic.clone(),
+                vec![],
+                proto::QoSLevel::QosBatched,
+                metadata.backpressure,
+            )
             .await?;
 
-        // Forward events to agent mailbox
+        // Forward events to agent mailbox; a subscription the bus drops (its thread
+        // was closed) is forgotten
         let tx = metadata.event_tx.clone();
+        let subscriptions = Arc::clone(&metadata.subscriptions);
+        let (forwarded_topic, forwarded_id) = (topic.clone(), sub_id.clone());
         let forwarder_handle = tokio::spawn(async move {
             while let Some(event) = rx.recv().await {
                 let _ = tx.send(event).await;
             }
+            subscriptions.remove_if(&forwarded_topic, |_, sub| {
+                sub.subscription_id == forwarded_id
+            });
         });
 
         // Track subscription
@@ -343,7 +567,7 @@ impl AgentRuntime {
     /// # Arguments
     ///
     /// * `agent_id` - The unique identifier of the agent
-    /// * `topic` - The topic to unsubscribe from
+    /// * `topic` - The topic to unsubscribe from, relative to the agent's namespace if it has one
     ///
     /// # Errors
     ///
@@ -371,6 +595,7 @@ impl AgentRuntime {
             .agents
             .get(agent_id)
             .ok_or_else(|| LoomError::AgentError(format!("Agent {} not found", agent_id)))?;
+        let topic = &scope_topic(metadata.namespace.as_ref(), topic);
 
         // Remove subscription
         let sub = metadata
@@ -397,6 +622,18 @@ impl AgentRuntime {
         Ok(())
     }
 
+    /// Number of agents currently running
+    pub fn agent_count(&self) -> usize {
+        self.agents.len()
+    }
+
+    /// Ids of all agents, sorted
+    pub fn agent_ids(&self) -> Vec<String> {
+        let mut ids: Vec<String> = self.agents.iter().map(|e| e.key().clone()).collect();
+        ids.sort();
+        ids
+    }
+
     /// Get list of topics an agent is currently subscribed to
     ///
     /// Returns all active subscriptions for diagnostic and coordination purposes.
@@ -422,4 +659,373 @@ impl AgentRuntime {
 
         Ok(topics)
     }
+
+    /// Events waiting for an agent: in its mailbox and in its subscriptions' queues
+    ///
+    /// # Errors
+    ///
+    /// Returns error if agent doesn't exist
+    pub fn agent_queue_depth(&self, agent_id: &str) -> Result<usize> {
+        let metadata = self
+            .agents
+            .get(agent_id)
+            .ok_or_else(|| LoomError::AgentError(format!("Agent {} not found", agent_id)))?;
+        let mailbox = metadata.event_tx.max_capacity() - metadata.event_tx.capacity();
+        let queued: usize = metadata
+            .subscriptions
+            .iter()
+            .filter_map(|sub| self.event_bus.queued_events(&sub.subscription_id))
+            .sum();
+        Ok(mailbox + queued)
+    }
+
+    /// Namespace an agent was created in (`AgentConfig.parameters["namespace"]`)
+    ///
+    /// # Errors
+    ///
+    /// Returns error if agent doesn't exist
+    pub fn get_agent_namespace(&self, agent_id: &str) -> Result<Option<Namespace>> {
+        self.agents
+            .get(agent_id)
+            .map(|metadata| metadata.namespace.clone())
+            .ok_or_else(|| LoomError::AgentError(format!("Agent {} not found", agent_id)))
+    }
+
+    /// Get the names of an agent's active recurring schedules
+    ///
+    /// Schedules are declared with `schedule.<name>.*` parameters in `AgentConfig`.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if agent doesn't exist
+    pub fn get_agent_schedules(&self, agent_id: &str) -> Result<Vec<String>> {
+        if !self.agents.contains_key(agent_id) {
+            return Err(LoomError::AgentError(format!(
+                "Agent {} not found",
+                agent_id
+            )));
+        }
+        Ok(self.scheduler.schedules(agent_id))
+    }
+
+    /// Hibernate idle agents created with `create_agent_with_factory` according to
+    /// `policy`; None stops hibernating agents (hibernated ones still wake on events)
+    ///
+    /// A hibernated agent has run `on_shutdown`, dropped its behavior and checkpointed
+    /// its state, but keeps its EventBus subscriptions: the next event reaching its
+    /// mailbox rebuilds the behavior, restores the state and is handled as usual.
+    pub fn set_hibernation_policy(&self, policy: Option<HibernationPolicy>) {
+        let mut hibernation = self.hibernation.lock().unwrap();
+        if let Some((_, sweeper)) = hibernation.take() {
+            sweeper.abort();
+        }
+        if let Some(policy) = policy {
+            let sweeper = tokio::spawn(sweep_idle_agents(Arc::downgrade(&self.agents), policy));
+            *hibernation = Some((policy, sweeper));
+            info!("Hibernating agents idle for {:?}", policy.idle_after);
+        }
+    }
+
+    /// The active hibernation policy, if any
+    pub fn hibernation_policy(&self) -> Option<HibernationPolicy> {
+        self.hibernation
+            .lock()
+            .unwrap()
+            .as_ref()
+            .map(|(policy, _)| *policy)
+    }
+
+    /// Hibernate an agent once its mailbox is empty, regardless of the policy
+    ///
+    /// # Errors
+    ///
+    /// Returns error if agent doesn't exist or was not created with a factory
+    pub fn hibernate_agent(&self, agent_id: &str) -> Result<()> {
+        self.agent_lifecycle(agent_id)?.request_hibernation();
+        Ok(())
+    }
+
+    /// Reactivate a hibernated agent without waiting for an event addressed to it
+    ///
+    /// Returns false if the agent was not hibernated.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if agent doesn't exist or was not created with a factory
+    pub fn wake_agent(&self, agent_id: &str) -> Result<bool> {
+        let woken = self.agent_lifecycle(agent_id)?.request_wake();
+        if woken {
+            info!("Waking agent {}", agent_id);
+        }
+        Ok(woken)
+    }
+
+    /// Replace an agent's behavior with a fresh one from its factory, keeping its state
+    /// and subscriptions
+    ///
+    /// A running agent finishes the events already in its mailbox and runs `on_shutdown`
+    /// first; a hibernated one is woken.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if agent doesn't exist or was not created with a factory
+    pub fn restart_agent(&self, agent_id: &str) -> Result<()> {
+        self.agent_lifecycle(agent_id)?.request_restart();
+        info!("Restarting agent {}", agent_id);
+        Ok(())
+    }
+
+    /// Whether an agent was created with a factory, so it can hibernate and restart
+    ///
+    /// # Errors
+    ///
+    /// Returns error if agent doesn't exist
+    pub fn is_agent_hibernatable(&self, agent_id: &str) -> Result<bool> {
+        self.agents
+            .get(agent_id)
+            .map(|metadata| metadata.lifecycle.is_some())
+            .ok_or_else(|| LoomError::AgentError(format!("Agent {} not found", agent_id)))
+    }
+
+    /// Whether an agent is hibernated; always false for agents created without a factory
+    ///
+    /// # Errors
+    ///
+    /// Returns error if agent doesn't exist
+    pub fn is_agent_hibernated(&self, agent_id: &str) -> Result<bool> {
+        let metadata = self
+            .agents
+            .get(agent_id)
+            .ok_or_else(|| LoomError::AgentError(format!("Agent {} not found", agent_id)))?;
+        Ok(metadata
+            .lifecycle
+            .as_ref()
+            .is_some_and(|lifecycle| lifecycle.is_hibernated()))
+    }
+
+    /// Stop an agent from handling events while keeping its subscriptions
+    ///
+    /// Events addressed to the agent keep queuing in its mailbox (up to its backpressure
+    /// policy) until `resume_agent`; an event already being handled is finished. Returns
+    /// false if the agent was already paused.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if agent doesn't exist
+    pub async fn pause_agent(&self, agent_id: &str) -> Result<bool> {
+        let paused = self.set_paused(agent_id, true)?;
+        if paused {
+            info!("Pausing agent {}", agent_id);
+            self.publish_transition(agent_id, "paused").await;
+        }
+        Ok(paused)
+    }
+
+    /// Let a paused agent handle the events queued while it was paused and new ones
+    ///
+    /// Returns false if the agent was not paused.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if agent doesn't exist
+    pub async fn resume_agent(&self, agent_id: &str) -> Result<bool> {
+        let resumed = self.set_paused(agent_id, false)?;
+        if resumed {
+            info!("Resuming agent {}", agent_id);
+            self.publish_transition(agent_id, "running").await;
+        }
+        Ok(resumed)
+    }
+
+    /// Whether an agent is paused
+    ///
+    /// # Errors
+    ///
+    /// Returns error if agent doesn't exist
+    pub fn is_agent_paused(&self, agent_id: &str) -> Result<bool> {
+        self.agents
+            .get(agent_id)
+            .map(|metadata| *metadata.pause.borrow())
+            .ok_or_else(|| LoomError::AgentError(format!("Agent {} not found", agent_id)))
+    }
+
+    /// Stop an agent after it has handled the events already queued for it
+    ///
+    /// Unsubscribes the agent and stops its schedules so no new events arrive, resumes
+    /// it if paused, and waits for it to empty its mailbox and run `on_shutdown`; the
+    /// agent is removed from the runtime. Returns false if it was still running after
+    /// the drain timeout and had to be aborted.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if agent doesn't exist
+    #[tracing::instrument(skip(self), fields(agent_id = %agent_id))]
+    pub async fn drain_agent(&self, agent_id: &str) -> Result<bool> {
+        let (task_handle, forwarders) = self
+            .detach_agent(agent_id)
+            .await
+            .ok_or_else(|| LoomError::AgentError(format!("Agent {} not found", agent_id)))?;
+        info!("Draining agent {}", agent_id);
+        self.publish_transition(agent_id, "draining").await;
+
+        let deadline = Instant::now() + DEFAULT_DRAIN_TIMEOUT;
+        let stopped = wait_stopped(agent_id, task_handle, forwarders, deadline).await;
+        self.publish_transition(agent_id, "drained").await;
+        Ok(stopped)
+    }
+
+    /// Set an agent's pause flag; true if it changed
+    fn set_paused(&self, agent_id: &str, paused: bool) -> Result<bool> {
+        let metadata = self
+            .agents
+            .get(agent_id)
+            .ok_or_else(|| LoomError::AgentError(format!("Agent {} not found", agent_id)))?;
+        Ok(metadata
+            .pause
+            .send_if_modified(|current| std::mem::replace(current, paused) != paused))
+    }
+
+    /// Publish an `agent.lifecycle` event for `agent_id` entering `state` and mirror
+    /// it in the directory
+    async fn publish_transition(&self, agent_id: &str, state: &str) {
+        if let Some(directory) = &self.directory {
+            match state {
+                "paused" => directory.update_status(agent_id, AgentStatus::Paused),
+                "draining" => directory.update_status(agent_id, AgentStatus::Draining),
+                "drained" => directory.mark_disconnected(agent_id),
+                _ => directory.update_status(agent_id, AgentStatus::Active),
+            }
+        }
+
+        let timestamp_ms = chrono::Utc::now().timestamp_millis();
+        let mut metadata = std::collections::HashMap::new();
+        metadata.insert("agent_id".to_string(), agent_id.to_string());
+        metadata.insert("state".to_string(), state.to_string());
+        let event = Event {
+            id: format!("evt_lifecycle_{}_{}_{}", agent_id, state, timestamp_ms),
+            r#type: LIFECYCLE_TOPIC.to_string(),
+            timestamp_ms,
+            source: "agent_runtime".to_string(),
+            metadata,
+            payload: vec![],
+            confidence: 1.0,
+            tags: vec!["system".into()],
+            priority: 80,
+        };
+        if let Err(e) = self.event_bus.publish(LIFECYCLE_TOPIC, event).await {
+            warn!(agent_id = %agent_id, error = %e, "Failed to publish lifecycle event");
+        }
+    }
+
+    fn agent_lifecycle(&self, agent_id: &str) -> Result<Arc<Lifecycle>> {
+        let metadata = self
+            .agents
+            .get(agent_id)
+            .ok_or_else(|| LoomError::AgentError(format!("Agent {} not found", agent_id)))?;
+        metadata.lifecycle.clone().ok_or_else(|| {
+            LoomError::AgentError(format!(
+                "Agent {} was not created with a factory and cannot hibernate",
+                agent_id
+            ))
+        })
+    }
+}
+
+/// Ask agents idle for `policy.idle_after` with empty mailboxes to hibernate, until the
+/// runtime is dropped
+async fn sweep_idle_agents(
+    agents: Weak<DashMap<String, AgentMetadata>>,
+    policy: HibernationPolicy,
+) {
+    let idle_after_ms = policy.idle_after.as_millis() as i64;
+    let mut ticker = tokio::time::interval(policy.sweep_interval);
+    ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+    loop {
+        ticker.tick().await;
+        let Some(agents) = agents.upgrade() else {
+            return;
+        };
+        let now_ms = chrono::Utc::now().timestamp_millis();
+        for entry in agents.iter() {
+            let metadata = entry.value();
+            let Some(lifecycle) = &metadata.lifecycle else {
+                continue;
+            };
+            let mailbox_empty = metadata.event_tx.capacity() == metadata.event_tx.max_capacity();
+            if lifecycle.is_hibernated() || *metadata.pause.borrow() || !mailbox_empty {
+                continue;
+            }
+            if lifecycle
+                .idle_ms(now_ms)
+                .is_some_and(|idle_ms| idle_ms >= idle_after_ms)
+            {
+                lifecycle.request_hibernation();
+            }
+        }
+    }
+}
+
+/// Wait until `deadline` for a detached agent's forwarders and task to finish, aborting
+/// whatever is still running; false if the agent was aborted
+async fn wait_stopped(
+    id: &str,
+    mut task_handle: JoinHandle<()>,
+    forwarders: Vec<JoinHandle<()>>,
+    deadline: Instant,
+) -> bool {
+    for mut forwarder in forwarders {
+        if tokio::time::timeout_at(deadline, &mut forwarder)
+            .await
+            .is_err()
+        {
+            forwarder.abort();
+        }
+    }
+    if tokio::time::timeout_at(deadline, &mut task_handle)
+        .await
+        .is_err()
+    {
+        warn!("Agent {} did not stop before the deadline; aborting", id);
+        task_handle.abort();
+        return false;
+    }
+    true
+}
+
+/// The namespace `config` places its agent in, if any
+pub(crate) fn agent_namespace(config: &AgentConfig) -> Result<Option<Namespace>> {
+    config
+        .parameters
+        .get(NAMESPACE_KEY)
+        .map(|id| {
+            Namespace::new(id.as_str()).map_err(|e| {
+                LoomError::AgentError(format!(
+                    "Agent {} has an invalid namespace: {}",
+                    config.agent_id, e
+                ))
+            })
+        })
+        .transpose()
+}
+
+/// Bus topic of `topic` for an agent in `namespace`
+fn scope_topic(namespace: Option<&Namespace>, topic: &str) -> String {
+    match namespace {
+        Some(ns) => ns.scope(topic),
+        None => topic.to_string(),
+    }
+}
+
+#[async_trait]
+impl ShutdownHook for AgentRuntime {
+    async fn stop(&self, deadline: Instant) -> Result<()> {
+        let aborted = self.drain(deadline).await;
+        if aborted > 0 {
+            return Err(LoomError::AgentError(format!(
+                "{} agent(s) aborted at shutdown deadline",
+                aborted
+            )));
+        }
+        Ok(())
+    }
 }
@@ -0,0 +1,502 @@
+//! Recurring self-triggers declared in `AgentConfig.parameters`.
+//!
+//! Each schedule is a group of `schedule.<name>.*` parameters:
+//!
+//! ```text
+//! schedule.review.cron        = "0 */15 * * * *"    # required: cron expression or @macro
+//! schedule.review.event_type  = "memory.review"     # default: schedule.tick
+//! schedule.review.payload     = "{\"window\":\"1h\"}"
+//! schedule.review.timezone    = "Europe/Berlin"     # default: LOOM_TIMEZONE or UTC
+//! schedule.review.metadata.k  = "v"                 # copied into event metadata
+//! ```
+//!
+//! Cron expressions have five fields (`min hour dom month dow`) or six with a leading
+//! seconds field. Fields accept `*`, lists, ranges, steps and month/weekday names.
+//! `@yearly`, `@monthly`, `@weekly`, `@daily`, `@hourly` and `@every <duration>`
+//! (e.g. `@every 90s`) are also accepted.
+//!
+//! The runtime registers schedules with [`AgentScheduler`] on `create_agent` and removes
+//! them on `delete_agent`. Each tick delivers a synthetic event straight to the agent's
+//! mailbox; ticks that find the mailbox full are skipped, and missed ticks are not
+//! replayed.
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use chrono::{DateTime, Datelike, Duration as ChronoDuration, NaiveDateTime, TimeZone, Timelike};
+use chrono::{LocalResult, Utc};
+use chrono_tz::Tz;
+use dashmap::DashMap;
+use opentelemetry::metrics::Counter;
+use opentelemetry::KeyValue;
+use tokio::sync::mpsc;
+use tokio::sync::mpsc::error::TrySendError;
+use tokio::task::JoinHandle;
+use tracing::{debug, warn};
+
+use crate::proto::Event;
+use crate::tools::native::time::{parse_timezone, timezone_from_env};
+
+/// Parameter prefix for schedule declarations
+pub const SCHEDULE_PARAM_PREFIX: &str = "schedule.";
+
+/// Event type used when a schedule sets no `event_type`
+pub const DEFAULT_SCHEDULE_EVENT_TYPE: &str = "schedule.tick";
+
+/// `source` of scheduled events
+pub const SCHEDULE_EVENT_SOURCE: &str = "scheduler";
+
+/// How far ahead `next_after` searches before declaring a schedule dead
+const MAX_LOOKAHEAD_DAYS: i64 = 366 * 5;
+
+/// A parsed cron expression; each field is a bitmask of allowed values
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CronSchedule {
+    seconds: u64,
+    minutes: u64,
+    hours: u64,
+    days_of_month: u64,
+    months: u64,
+    days_of_week: u64,
+    /// Day-of-month field was `*`
+    any_day_of_month: bool,
+    /// Day-of-week field was `*`
+    any_day_of_week: bool,
+}
+
+const MONTH_NAMES: &[&str] = &[
+    "jan", "feb", "mar", "apr", "may", "jun", "jul", "aug", "sep", "oct", "nov", "dec",
+];
+const WEEKDAY_NAMES: &[&str] = &["sun", "mon", "tue", "wed", "thu", "fri", "sat"];
+
+impl CronSchedule {
+    /// Parse a 5- or 6-field cron expression or one of the `@` macros
+    pub fn parse(expression: &str) -> Result<Self, String> {
+        let expression = expression.trim();
+        let expanded = match expression.to_ascii_lowercase().as_str() {
+            "@yearly" | "@annually" => "0 0 1 1 *".to_string(),
+            "@monthly" => "0 0 1 * *".to_string(),
+            "@weekly" => "0 0 * * 0".to_string(),
+            "@daily" | "@midnight" => "0 0 * * *".to_string(),
+            "@hourly" => "0 * * * *".to_string(),
+            other if other.starts_with('@') => {
+                return Err(format!("unknown cron macro '{}'", expression))
+            }
+            _ => expression.to_string(),
+        };
+
+        let fields: Vec<&str> = expanded.split_whitespace().collect();
+        let (seconds, rest) = match fields.len() {
+            5 => ("0", &fields[..]),
+            6 => (fields[0], &fields[1..]),
+            n => {
+                return Err(format!(
+                    "expected 5 or 6 cron fields, found {} in '{}'",
+                    n, expression
+                ))
+            }
+        };
+
+        let days_of_week = parse_field(rest[4], 0, 7, WEEKDAY_NAMES, 0)?;
+        Ok(Self {
+            seconds: parse_field(seconds, 0, 59, &[], 0)?,
+            minutes: parse_field(rest[0], 0, 59, &[], 0)?,
+            hours: parse_field(rest[1], 0, 23, &[], 0)?,
+            days_of_month: parse_field(rest[2], 1, 31, &[], 0)?,
+            months: parse_field(rest[3], 1, 12, MONTH_NAMES, 1)?,
+            // 7 is an alias for Sunday
+            days_of_week: (days_of_week | (days_of_week >> 7)) & 0x7f,
+            any_day_of_month: is_wildcard(rest[2]),
+            any_day_of_week: is_wildcard(rest[4]),
+        })
+    }
+
+    fn day_matches(&self, date: &NaiveDateTime) -> bool {
+        let dom = bit(self.days_of_month, date.day());
+        let dow = bit(self.days_of_week, date.weekday().num_days_from_sunday());
+        // Classic cron: when both day fields are restricted, either may match
+        match (self.any_day_of_month, self.any_day_of_week) {
+            (false, false) => dom || dow,
+            _ => dom && dow,
+        }
+    }
+
+    /// First fire time strictly after `after`, in `after`'s timezone
+    ///
+    /// Local times skipped by a DST change never fire; repeated local times fire once.
+    pub fn next_after(&self, after: &DateTime<Tz>) -> Option<DateTime<Tz>> {
+        let tz = after.timezone();
+        let mut t = after.naive_local().with_nanosecond(0)? + ChronoDuration::seconds(1);
+        let limit = t + ChronoDuration::days(MAX_LOOKAHEAD_DAYS);
+
+        while t < limit {
+            if !bit(self.months, t.month()) {
+                let (year, month) = if t.month() == 12 {
+                    (t.year() + 1, 1)
+                } else {
+                    (t.year(), t.month() + 1)
+                };
+                t = chrono::NaiveDate::from_ymd_opt(year, month, 1)?.and_hms_opt(0, 0, 0)?;
+                continue;
+            }
+            if !self.day_matches(&t) {
+                t = t.date().succ_opt()?.and_hms_opt(0, 0, 0)?;
+                continue;
+            }
+            if !bit(self.hours, t.hour()) {
+                t = t.date().and_hms_opt(t.hour(), 0, 0)? + ChronoDuration::hours(1);
+                continue;
+            }
+            if !bit(self.minutes, t.minute()) {
+                t = t.date().and_hms_opt(t.hour(), t.minute(), 0)? + ChronoDuration::minutes(1);
+                continue;
+            }
+            if !bit(self.seconds, t.second()) {
+                t += ChronoDuration::seconds(1);
+                continue;
+            }
+
+            match tz.from_local_datetime(&t) {
+                LocalResult::Single(dt) if dt > *after => return Some(dt),
+                LocalResult::Ambiguous(earliest, _) if earliest > *after => return Some(earliest),
+                _ => {}
+            }
+            t += ChronoDuration::seconds(1);
+        }
+        None
+    }
+}
+
+fn bit(mask: u64, value: u32) -> bool {
+    mask & (1 << value) != 0
+}
+
+fn is_wildcard(field: &str) -> bool {
+    field == "*" || field == "?"
+}
+
+/// Parse one cron field into a bitmask over `min..=max`
+///
+/// `names[i]` is an alias for `i + name_base`.
+fn parse_field(
+    field: &str,
+    min: u32,
+    max: u32,
+    names: &[&str],
+    name_base: u32,
+) -> Result<u64, String> {
+    let value = |s: &str| -> Result<u32, String> {
+        let lower = s.to_ascii_lowercase();
+        let parsed = match names.iter().position(|name| *name == lower) {
+            Some(index) => index as u32 + name_base,
+            None => s
+                .parse::<u32>()
+                .map_err(|_| format!("invalid cron value '{}'", s))?,
+        };
+        if parsed < min || parsed > max {
+            return Err(format!(
+                "cron value {} out of range {}-{}",
+                parsed, min, max
+            ));
+        }
+        Ok(parsed)
+    };
+
+    let mut mask = 0u64;
+    for item in field.split(',') {
+        let (range, step) = match item.split_once('/') {
+            Some((range, step)) => {
+                let step: u32 = step
+                    .parse()
+                    .ok()
+                    .filter(|s| *s > 0)
+                    .ok_or_else(|| format!("invalid cron step in '{}'", item))?;
+                (range, step)
+            }
+            None => (item, 1),
+        };
+
+        let (start, end) = if is_wildcard(range) {
+            (min, max)
+        } else if let Some((a, b)) = range.split_once('-') {
+            (value(a)?, value(b)?)
+        } else {
+            let start = value(range)?;
+            // `a/n` runs from a to the end of the range
+            (start, if item.contains('/') { max } else { start })
+        };
+        if start > end {
+            return Err(format!("invalid cron range '{}'", range));
+        }
+
+        let mut v = start;
+        while v <= end {
+            mask |= 1 << v;
+            v += step;
+        }
+    }
+    Ok(mask)
+}
+
+/// Parse a duration such as `500ms`, `30s`, `5m`, `2h` or `1d`
+fn parse_every(text: &str) -> Result<Duration, String> {
+    let text = text.trim();
+    let split = text
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(text.len());
+    let (number, unit) = text.split_at(split);
+    let n: u64 = number
+        .parse()
+        .map_err(|_| format!("invalid @every duration '{}'", text))?;
+    let duration = match unit.trim() {
+        "ms" => Duration::from_millis(n),
+        "s" | "" => Duration::from_secs(n),
+        "m" => Duration::from_secs(n * 60),
+        "h" => Duration::from_secs(n * 3600),
+        "d" => Duration::from_secs(n * 86_400),
+        other => return Err(format!("unknown @every unit '{}'", other)),
+    };
+    if duration.is_zero() {
+        return Err("@every duration must be positive".to_string());
+    }
+    Ok(duration)
+}
+
+/// When a schedule fires
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Cadence {
+    Cron(CronSchedule),
+    Every(Duration),
+}
+
+/// A recurring self-trigger for one agent
+#[derive(Debug, Clone)]
+pub struct Schedule {
+    pub name: String,
+    pub cadence: Cadence,
+    pub timezone: Tz,
+    pub event_type: String,
+    pub payload: Vec<u8>,
+    pub metadata: HashMap<String, String>,
+}
+
+impl Schedule {
+    /// Next fire time strictly after `after`
+    pub fn next_after(&self, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        match &self.cadence {
+            Cadence::Every(every) => Some(after + ChronoDuration::from_std(*every).ok()?),
+            Cadence::Cron(cron) => cron
+                .next_after(&after.with_timezone(&self.timezone))
+                .map(|dt| dt.with_timezone(&Utc)),
+        }
+    }
+
+    /// Build the synthetic event delivered at `fire_at`
+    pub fn event(&self, agent_id: &str, fire_at: DateTime<Utc>) -> Event {
+        let fire_ms = fire_at.timestamp_millis();
+        let mut metadata = self.metadata.clone();
+        metadata.insert("schedule".to_string(), self.name.clone());
+        metadata.insert("scheduled_at_ms".to_string(), fire_ms.to_string());
+        metadata.insert("agent_id".to_string(), agent_id.to_string());
+
+        Event {
+            id: format!("evt_schedule_{}_{}_{}", agent_id, self.name, fire_ms),
+            r#type: self.event_type.clone(),
+            timestamp_ms: Utc::now().timestamp_millis(),
+            source: SCHEDULE_EVENT_SOURCE.to_string(),
+            metadata,
+            payload: self.payload.clone(),
+            confidence: 1.0,
+            tags: vec!["scheduled".to_string()],
+            priority: 50,
+        }
+    }
+}
+
+/// Parse every `schedule.<name>.*` group from agent parameters, sorted by name
+pub fn parse_schedules(parameters: &HashMap<String, String>) -> Result<Vec<Schedule>, String> {
+    let mut groups: HashMap<&str, HashMap<&str, &str>> = HashMap::new();
+    for (key, value) in parameters {
+        let Some(rest) = key.strip_prefix(SCHEDULE_PARAM_PREFIX) else {
+            continue;
+        };
+        let Some((name, field)) = rest.split_once('.') else {
+            return Err(format!("'{}' must be schedule.<name>.<field>", key));
+        };
+        if name.is_empty() {
+            return Err(format!("'{}' has an empty schedule name", key));
+        }
+        groups
+            .entry(name)
+            .or_default()
+            .insert(field, value.as_str());
+    }
+
+    let mut schedules = Vec::with_capacity(groups.len());
+    for (name, fields) in groups {
+        let cron = fields
+            .get("cron")
+            .ok_or_else(|| format!("schedule '{}' has no cron expression", name))?;
+        let cadence = match cron.trim().strip_prefix("@every") {
+            Some(every) => Cadence::Every(parse_every(every)?),
+            None => Cadence::Cron(
+                CronSchedule::parse(cron).map_err(|e| format!("schedule '{}': {}", name, e))?,
+            ),
+        };
+        let timezone = match fields.get("timezone") {
+            Some(tz) => parse_timezone(tz).map_err(|e| format!("schedule '{}': {}", name, e))?,
+            None => timezone_from_env(),
+        };
+
+        let mut metadata = HashMap::new();
+        for (field, value) in &fields {
+            match field.strip_prefix("metadata.") {
+                Some(key) => {
+                    metadata.insert(key.to_string(), value.to_string());
+                }
+                None if matches!(*field, "cron" | "event_type" | "payload" | "timezone") => {}
+                None => return Err(format!("schedule '{}' has unknown field '{}'", name, field)),
+            }
+        }
+
+        schedules.push(Schedule {
+            name: name.to_string(),
+            cadence,
+            timezone,
+            event_type: fields
+                .get("event_type")
+                .map(|s| s.to_string())
+                .unwrap_or_else(|| DEFAULT_SCHEDULE_EVENT_TYPE.to_string()),
+            payload: fields
+                .get("payload")
+                .map(|s| s.as_bytes().to_vec())
+                .unwrap_or_default(),
+            metadata,
+        });
+    }
+    schedules.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(schedules)
+}
+
+/// Timer tasks delivering scheduled events to agent mailboxes
+pub struct AgentScheduler {
+    /// agent_id -> (schedule name, timer task)
+    jobs: DashMap<String, Vec<(String, JoinHandle<()>)>>,
+    fired_counter: Counter<u64>,
+    skipped_counter: Counter<u64>,
+}
+
+impl Default for AgentScheduler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AgentScheduler {
+    pub fn new() -> Self {
+        let meter = opentelemetry::global::meter("loom.agent_runtime");
+
+        let fired_counter = meter
+            .u64_counter("agent_runtime.schedules.fired")
+            .with_description("Scheduled events delivered to agent mailboxes")
+            .init();
+
+        let skipped_counter = meter
+            .u64_counter("agent_runtime.schedules.skipped")
+            .with_description("Scheduled events skipped because the mailbox was full")
+            .init();
+
+        Self {
+            jobs: DashMap::new(),
+            fired_counter,
+            skipped_counter,
+        }
+    }
+
+    /// Start timers for an agent's schedules, replacing any it already had
+    pub fn register(&self, agent_id: &str, schedules: Vec<Schedule>, mailbox: mpsc::Sender<Event>) {
+        self.unregister(agent_id);
+        if schedules.is_empty() {
+            return;
+        }
+
+        let jobs = schedules
+            .into_iter()
+            .map(|schedule| {
+                let name = schedule.name.clone();
+                let handle = tokio::spawn(run_schedule(
+                    agent_id.to_string(),
+                    schedule,
+                    mailbox.clone(),
+                    self.fired_counter.clone(),
+                    self.skipped_counter.clone(),
+                ));
+                (name, handle)
+            })
+            .collect();
+        self.jobs.insert(agent_id.to_string(), jobs);
+    }
+
+    /// Stop an agent's timers; returns how many were running
+    pub fn unregister(&self, agent_id: &str) -> usize {
+        match self.jobs.remove(agent_id) {
+            Some((_, jobs)) => {
+                for (_, handle) in &jobs {
+                    handle.abort();
+                }
+                jobs.len()
+            }
+            None => 0,
+        }
+    }
+
+    /// Names of an agent's active schedules
+    pub fn schedules(&self, agent_id: &str) -> Vec<String> {
+        self.jobs
+            .get(agent_id)
+            .map(|jobs| jobs.iter().map(|(name, _)| name.clone()).collect())
+            .unwrap_or_default()
+    }
+}
+
+async fn run_schedule(
+    agent_id: String,
+    schedule: Schedule,
+    mailbox: mpsc::Sender<Event>,
+    fired: Counter<u64>,
+    skipped: Counter<u64>,
+) {
+    let attrs = [KeyValue::new("schedule", schedule.name.clone())];
+    let mut last = Utc::now();
+
+    loop {
+        // Never schedule in the past: after a slow tick, resume from now
+        let Some(fire_at) = schedule.next_after(last.max(Utc::now())) else {
+            warn!(
+                agent_id = %agent_id,
+                schedule = %schedule.name,
+                "Schedule has no future fire time; stopping"
+            );
+            return;
+        };
+        let wait = (fire_at - Utc::now()).to_std().unwrap_or_default();
+        tokio::time::sleep(wait).await;
+        last = fire_at;
+
+        match mailbox.try_send(schedule.event(&agent_id, fire_at)) {
+            Ok(()) => {
+                fired.add(1, &attrs);
+                debug!(agent_id = %agent_id, schedule = %schedule.name, "Scheduled event delivered");
+            }
+            Err(TrySendError::Full(_)) => {
+                skipped.add(1, &attrs);
+                warn!(
+                    agent_id = %agent_id,
+                    schedule = %schedule.name,
+                    "Agent mailbox full; skipping scheduled event"
+                );
+            }
+            Err(TrySendError::Closed(_)) => return,
+        }
+    }
+}
@@ -0,0 +1,560 @@
+//! Sentinel: built-in anomaly detection over runtime metrics
+//!
+//! `SentinelAgent` samples runtime signals on an interval and publishes an
+//! `anomaly.detected` event on the bus when one of them misbehaves:
+//!
+//! | Signal                   | Subject | Source                          | Detector |
+//! |--------------------------|---------|---------------------------------|----------|
+//! | `event_bus.publish_rate` | topic   | `EventBus::all_stats` (per sec) | z-score  |
+//! | `event_bus.backlog`      | topic   | `EventBus::all_stats`           | z-score  |
+//! | `event_bus.drop_rate`    | topic   | `EventBus::all_stats` (per sec) | EWMA     |
+//! | `tool.error_rate`        | tool    | `ToolRegistry::stats`           | EWMA     |
+//! | `llm.latency_p95_ms`     | model   | `ModelRouter` latency tracker   | z-score  |
+//!
+//! The z-score detector flags samples more than `zscore_threshold` standard deviations
+//! from the mean of a rolling window; the EWMA detector flags when the smoothed value
+//! crosses a fixed ceiling and re-arms once it falls back below. A signal that fired
+//! stays quiet for `cooldown`. The payload is an `Anomaly` (JSON) including recent
+//! samples and the source stats.
+//!
+//! Env overrides for `SentinelConfig::default()`:
+//! - LOOM_SENTINEL_ENABLED (default true)
+//! - LOOM_SENTINEL_INTERVAL_MS (default 10000)
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use opentelemetry::metrics::Counter;
+use opentelemetry::{global, KeyValue};
+use serde::{Deserialize, Serialize};
+use tokio::task::JoinHandle;
+use tokio::time::Instant;
+use tracing::{info, warn};
+
+use crate::cognitive::llm::router::ModelRouter;
+use crate::messaging::event_bus::EventBusStats;
+use crate::proto::Event;
+use crate::tools::ToolRegistry;
+use crate::EventBus;
+
+/// Topic (and event type) of anomaly events
+pub const ANOMALY_TOPIC: &str = crate::proto::conventions::topics::ANOMALY;
+
+/// Samples kept per signal for anomaly context
+const RECENT_SAMPLES: usize = 10;
+
+// ============================================================================
+// Detectors
+// ============================================================================
+
+/// Why a detector flagged a sample
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Detection {
+    pub detector: String,
+    /// Z-score, or the smoothed value for EWMA
+    pub score: f64,
+    pub threshold: f64,
+    /// Expected value before this sample (window mean or previous EWMA)
+    pub baseline: f64,
+}
+
+/// Streaming anomaly detector over one signal
+pub trait Detector: Send {
+    /// Feed one sample; `Some` if it is anomalous
+    fn observe(&mut self, value: f64) -> Option<Detection>;
+}
+
+/// Flags samples far from the mean of a rolling window
+#[derive(Debug, Clone)]
+pub struct ZScoreDetector {
+    window: VecDeque<f64>,
+    capacity: usize,
+    threshold: f64,
+    min_samples: usize,
+}
+
+impl ZScoreDetector {
+    pub fn new(capacity: usize, threshold: f64, min_samples: usize) -> Self {
+        Self {
+            window: VecDeque::with_capacity(capacity),
+            capacity: capacity.max(2),
+            threshold,
+            min_samples: min_samples.max(2),
+        }
+    }
+}
+
+impl Detector for ZScoreDetector {
+    fn observe(&mut self, value: f64) -> Option<Detection> {
+        let detection = if self.window.len() >= self.min_samples {
+            let n = self.window.len() as f64;
+            let mean = self.window.iter().sum::<f64>() / n;
+            let variance = self.window.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / n;
+            // A perfectly flat baseline would make any change infinitely anomalous
+            let std_dev = variance.sqrt().max(mean.abs() * 0.01).max(1e-9);
+            let z = (value - mean) / std_dev;
+            (z.abs() >= self.threshold).then(|| Detection {
+                detector: "zscore".to_string(),
+                score: z,
+                threshold: self.threshold,
+                baseline: mean,
+            })
+        } else {
+            None
+        };
+
+        if self.window.len() == self.capacity {
+            self.window.pop_front();
+        }
+        self.window.push_back(value);
+        detection
+    }
+}
+
+/// Flags when an exponentially weighted moving average crosses a ceiling
+#[derive(Debug, Clone)]
+pub struct EwmaDetector {
+    alpha: f64,
+    ceiling: f64,
+    ewma: Option<f64>,
+    armed: bool,
+}
+
+impl EwmaDetector {
+    /// `alpha` is the weight of each new sample (0-1]
+    pub fn new(alpha: f64, ceiling: f64) -> Self {
+        Self {
+            alpha: alpha.clamp(f64::EPSILON, 1.0),
+            ceiling,
+            ewma: None,
+            armed: true,
+        }
+    }
+}
+
+impl Detector for EwmaDetector {
+    fn observe(&mut self, value: f64) -> Option<Detection> {
+        let previous = self.ewma;
+        let ewma = match previous {
+            Some(prev) => self.alpha * value + (1.0 - self.alpha) * prev,
+            None => value,
+        };
+        self.ewma = Some(ewma);
+
+        if ewma <= self.ceiling {
+            self.armed = true;
+            return None;
+        }
+        if !self.armed {
+            return None;
+        }
+        self.armed = false;
+        Some(Detection {
+            detector: "ewma".to_string(),
+            score: ewma,
+            threshold: self.ceiling,
+            baseline: previous.unwrap_or(ewma),
+        })
+    }
+}
+
+// ============================================================================
+// Sentinel
+// ============================================================================
+
+/// Sentinel thresholds and sampling interval
+#[derive(Debug, Clone)]
+pub struct SentinelConfig {
+    pub enabled: bool,
+    pub check_interval: Duration,
+    /// Samples in the z-score window
+    pub zscore_window: usize,
+    pub zscore_threshold: f64,
+    /// Samples needed before the z-score detector can fire
+    pub min_samples: usize,
+    pub ewma_alpha: f64,
+    /// Smoothed failed/total tool calls that counts as anomalous
+    pub max_tool_error_rate: f64,
+    /// Smoothed dropped events per second (per topic) that counts as anomalous
+    pub max_drop_rate: f64,
+    /// Quiet period per signal after an anomaly
+    pub cooldown: Duration,
+}
+
+impl Default for SentinelConfig {
+    fn default() -> Self {
+        let enabled = std::env::var("LOOM_SENTINEL_ENABLED")
+            .map(|v| !matches!(v.to_ascii_lowercase().as_str(), "0" | "false" | "off"))
+            .unwrap_or(true);
+        let interval_ms = std::env::var("LOOM_SENTINEL_INTERVAL_MS")
+            .ok()
+            .and_then(|v| v.parse::<u64>().ok())
+            .unwrap_or(10_000);
+        Self {
+            enabled,
+            check_interval: Duration::from_millis(interval_ms.max(1)),
+            zscore_window: 60,
+            zscore_threshold: 3.0,
+            min_samples: 10,
+            ewma_alpha: 0.3,
+            max_tool_error_rate: 0.5,
+            max_drop_rate: 1.0,
+            cooldown: Duration::from_secs(60),
+        }
+    }
+}
+
+impl SentinelConfig {
+    pub fn with_check_interval(mut self, interval: Duration) -> Self {
+        self.check_interval = interval;
+        self
+    }
+
+    pub fn with_zscore(mut self, window: usize, threshold: f64, min_samples: usize) -> Self {
+        self.zscore_window = window;
+        self.zscore_threshold = threshold;
+        self.min_samples = min_samples;
+        self
+    }
+
+    pub fn with_cooldown(mut self, cooldown: Duration) -> Self {
+        self.cooldown = cooldown;
+        self
+    }
+}
+
+/// Payload of an `anomaly.detected` event
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Anomaly {
+    /// Signal name, e.g. `tool.error_rate`
+    pub signal: String,
+    /// Topic, tool or model the signal belongs to
+    pub subject: String,
+    pub value: f64,
+    pub detection: Detection,
+    /// `critical` at twice the threshold, otherwise `warning`
+    pub severity: String,
+    /// Samples before this one, oldest first
+    pub recent: Vec<f64>,
+    /// Source stats at detection time
+    pub context: serde_json::Value,
+    pub timestamp_ms: i64,
+}
+
+struct SignalState {
+    detector: Box<dyn Detector>,
+    recent: VecDeque<f64>,
+    last_fired: Option<Instant>,
+}
+
+#[derive(Default)]
+struct Samples {
+    signals: HashMap<(String, String), SignalState>,
+    last_check: Option<Instant>,
+    topics: HashMap<String, EventBusStats>,
+    // tool -> (calls, failed or rejected calls)
+    tools: HashMap<String, (u64, u64)>,
+}
+
+/// Watches EventBus, tool and LLM metrics and publishes `anomaly.detected` events
+pub struct SentinelAgent {
+    config: SentinelConfig,
+    event_bus: Option<Arc<EventBus>>,
+    tool_registry: Option<Arc<ToolRegistry>>,
+    model_router: Option<ModelRouter>,
+    samples: Mutex<Samples>,
+    task: Mutex<Option<JoinHandle<()>>>,
+    anomalies_counter: Counter<u64>,
+}
+
+impl SentinelAgent {
+    pub fn new(config: SentinelConfig) -> Self {
+        let anomalies_counter = global::meter("loom.sentinel")
+            .u64_counter("loom.sentinel.anomalies_total")
+            .with_description("Anomalies detected by the sentinel")
+            .init();
+        Self {
+            config,
+            event_bus: None,
+            tool_registry: None,
+            model_router: None,
+            samples: Mutex::new(Samples::default()),
+            task: Mutex::new(None),
+            anomalies_counter,
+        }
+    }
+
+    /// Watch this bus and publish anomalies on it
+    pub fn with_event_bus(mut self, event_bus: Arc<EventBus>) -> Self {
+        self.event_bus = Some(event_bus);
+        self
+    }
+
+    /// Watch tool error rates
+    pub fn with_tool_registry(mut self, tool_registry: Arc<ToolRegistry>) -> Self {
+        self.tool_registry = Some(tool_registry);
+        self
+    }
+
+    /// Watch LLM latencies recorded by the router
+    pub fn with_model_router(mut self, model_router: ModelRouter) -> Self {
+        self.model_router = Some(model_router);
+        self
+    }
+
+    pub fn config(&self) -> &SentinelConfig {
+        &self.config
+    }
+
+    /// Sample every signal once and publish what is anomalous
+    pub async fn check(&self) -> Vec<Anomaly> {
+        let anomalies = self.sample();
+        for anomaly in &anomalies {
+            self.emit(anomaly).await;
+        }
+        anomalies
+    }
+
+    fn sample(&self) -> Vec<Anomaly> {
+        let mut samples = self.samples.lock().unwrap();
+        let now = Instant::now();
+        let elapsed = samples
+            .last_check
+            .replace(now)
+            .map(|last| now.duration_since(last).as_secs_f64())
+            .filter(|secs| *secs > 0.0);
+        let mut anomalies = Vec::new();
+
+        if let Some(ref event_bus) = self.event_bus {
+            for (topic, stats) in event_bus.all_stats() {
+                // Our own output is not a signal
+                if topic == ANOMALY_TOPIC {
+                    continue;
+                }
+                let context = serde_json::to_value(&stats).unwrap_or_default();
+                let previous = samples.topics.insert(topic.clone(), stats.clone());
+                self.observe(
+                    &mut samples,
+                    "event_bus.backlog",
+                    &topic,
+                    stats.backlog_size as f64,
+                    &context,
+                    &mut anomalies,
+                );
+                let (Some(previous), Some(secs)) = (previous, elapsed) else {
+                    continue;
+                };
+                let published = stats
+                    .total_published
+                    .saturating_sub(previous.total_published);
+                let dropped = stats.dropped_events.saturating_sub(previous.dropped_events);
+                self.observe(
+                    &mut samples,
+                    "event_bus.publish_rate",
+                    &topic,
+                    published as f64 / secs,
+                    &context,
+                    &mut anomalies,
+                );
+                self.observe(
+                    &mut samples,
+                    "event_bus.drop_rate",
+                    &topic,
+                    dropped as f64 / secs,
+                    &context,
+                    &mut anomalies,
+                );
+            }
+        }
+
+        if let Some(ref tool_registry) = self.tool_registry {
+            for stats in tool_registry.stats() {
+                // Calls rejected by an open circuit fail from the caller's point of view
+                let total_failures = stats.failures + stats.rejected;
+                let previous = samples
+                    .tools
+                    .insert(stats.name.clone(), (stats.calls, total_failures));
+                let (prev_calls, prev_failures) = previous.unwrap_or((0, 0));
+                let calls = stats.calls.saturating_sub(prev_calls);
+                // Idle tools have no error rate
+                if calls == 0 {
+                    continue;
+                }
+                let failures = total_failures.saturating_sub(prev_failures);
+                let context = serde_json::to_value(&stats).unwrap_or_default();
+                self.observe(
+                    &mut samples,
+                    "tool.error_rate",
+                    &stats.name,
+                    (failures as f64 / calls as f64).min(1.0),
+                    &context,
+                    &mut anomalies,
+                );
+            }
+        }
+
+        if let Some(ref model_router) = self.model_router {
+            let tracker = model_router.latency_tracker();
+            for model in tracker.models() {
+                let Some(p95) = tracker.p95(&model) else {
+                    continue;
+                };
+                let context = serde_json::json!({
+                    "p50_ms": tracker.p50(&model),
+                    "samples": tracker.sample_count(&model),
+                });
+                self.observe(
+                    &mut samples,
+                    "llm.latency_p95_ms",
+                    &model,
+                    p95 as f64,
+                    &context,
+                    &mut anomalies,
+                );
+            }
+        }
+
+        anomalies
+    }
+
+    fn observe(
+        &self,
+        samples: &mut Samples,
+        signal: &str,
+        subject: &str,
+        value: f64,
+        context: &serde_json::Value,
+        anomalies: &mut Vec<Anomaly>,
+    ) {
+        let config = &self.config;
+        let state = samples
+            .signals
+            .entry((signal.to_string(), subject.to_string()))
+            .or_insert_with(|| SignalState {
+                detector: match signal {
+                    "tool.error_rate" => Box::new(EwmaDetector::new(
+                        config.ewma_alpha,
+                        config.max_tool_error_rate,
+                    )),
+                    "event_bus.drop_rate" => {
+                        Box::new(EwmaDetector::new(config.ewma_alpha, config.max_drop_rate))
+                    }
+                    _ => Box::new(ZScoreDetector::new(
+                        config.zscore_window,
+                        config.zscore_threshold,
+                        config.min_samples,
+                    )),
+                },
+                recent: VecDeque::with_capacity(RECENT_SAMPLES),
+                last_fired: None,
+            });
+
+        let detection = state.detector.observe(value);
+        let recent: Vec<f64> = state.recent.iter().copied().collect();
+        if state.recent.len() == RECENT_SAMPLES {
+            state.recent.pop_front();
+        }
+        state.recent.push_back(value);
+
+        let Some(detection) = detection else {
+            return;
+        };
+        let now = Instant::now();
+        if state
+            .last_fired
+            .is_some_and(|at| now.duration_since(at) < config.cooldown)
+        {
+            return;
+        }
+        state.last_fired = Some(now);
+
+        let severity = if detection.score.abs() >= 2.0 * detection.threshold.abs() {
+            "critical"
+        } else {
+            "warning"
+        };
+        anomalies.push(Anomaly {
+            signal: signal.to_string(),
+            subject: subject.to_string(),
+            value,
+            detection,
+            severity: severity.to_string(),
+            recent,
+            context: context.clone(),
+            timestamp_ms: chrono::Utc::now().timestamp_millis(),
+        });
+    }
+
+    async fn emit(&self, anomaly: &Anomaly) {
+        self.anomalies_counter.add(
+            1,
+            &[
+                KeyValue::new("signal", anomaly.signal.clone()),
+                KeyValue::new("severity", anomaly.severity.clone()),
+            ],
+        );
+        info!(
+            target: "sentinel",
+            signal = %anomaly.signal,
+            subject = %anomaly.subject,
+            value = anomaly.value,
+            score = anomaly.detection.score,
+            severity = %anomaly.severity,
+            "Anomaly detected"
+        );
+
+        let Some(ref event_bus) = self.event_bus else {
+            return;
+        };
+        let mut metadata = HashMap::new();
+        metadata.insert("signal".to_string(), anomaly.signal.clone());
+        metadata.insert("subject".to_string(), anomaly.subject.clone());
+        metadata.insert("severity".to_string(), anomaly.severity.clone());
+        metadata.insert("detector".to_string(), anomaly.detection.detector.clone());
+        let event = Event {
+            id: format!(
+                "evt_anomaly_{}_{}_{}",
+                anomaly.signal, anomaly.subject, anomaly.timestamp_ms
+            ),
+            r#type: ANOMALY_TOPIC.to_string(),
+            timestamp_ms: anomaly.timestamp_ms,
+            source: "sentinel".to_string(),
+            metadata,
+            payload: serde_json::to_vec(anomaly).unwrap_or_default(),
+            confidence: 1.0,
+            tags: vec!["system".into(), "anomaly".into()],
+            priority: if anomaly.severity == "critical" {
+                90
+            } else {
+                70
+            },
+        };
+        if let Err(e) = event_bus.publish(ANOMALY_TOPIC, event).await {
+            warn!(target: "sentinel", error = %e, "Failed to publish anomaly event");
+        }
+    }
+
+    /// Start the periodic check; restarting replaces the previous task
+    pub fn start(self: &Arc<Self>) {
+        let sentinel = Arc::clone(self);
+        let handle = tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(sentinel.config.check_interval);
+            ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            loop {
+                ticker.tick().await;
+                sentinel.check().await;
+            }
+        });
+        if let Some(old) = self.task.lock().unwrap().replace(handle) {
+            old.abort();
+        }
+    }
+
+    /// Stop the periodic check
+    pub fn stop(&self) {
+        if let Some(handle) = self.task.lock().unwrap().take() {
+            handle.abort();
+        }
+    }
+}
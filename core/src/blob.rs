@@ -0,0 +1,548 @@
+//! Blob store for large payloads passed by reference.
+//!
+//! Images, audio and documents should not be copied through every subscriber queue. A
+//! producer stashes the content in a `BlobStore` and publishes an event whose payload is
+//! empty and whose metadata carries a `BlobRef` (the `blob::*` keys: id, size, SHA-256
+//! and content type). Consumers resolve the reference when they need the content;
+//! resolving checks the blob's `BlobAccess` and the hash.
+//!
+//! Backends: `DiskBlobBackend` (one directory) and `S3BlobBackend` (any S3-compatible
+//! object store, through `crate::s3`).
+//! `BlobStore::from_env` picks one from `LOOM_BLOB_DIR` or `LOOM_BLOB_S3_*`.
+//!
+//! Access: the owner may always read its blobs. A blob put with a namespace can only be
+//! read from that namespace. A blob that lists readers can only be read by those agent
+//! ids (`*` for any agent); without readers, any agent may read it. Readers with no agent
+//! id (host code, Bridge tool calls) are allowed only on blobs open to `*` or to anyone.
+//! Host code can bypass the checks with `BlobStore::load`.
+//!
+//! Blobs stay until deleted; the store does not expire them.
+
+use crate::proto::Event;
+use crate::s3::{check_status, object_path, S3Client};
+use crate::{LoomError, Result};
+use async_trait::async_trait;
+use ring::{digest, rand::SecureRandom};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+/// Metadata keys of a blob reference, shared with the SDKs through
+/// `loom_proto::conventions`
+pub use crate::proto::conventions::blob as keys;
+
+/// Largest blob accepted unless `LOOM_BLOB_MAX_BYTES` says otherwise
+pub const DEFAULT_MAX_BYTES: u64 = 256 * 1024 * 1024;
+
+/// Payloads larger than this are moved into the store by `stash`, unless
+/// `LOOM_BLOB_INLINE_MAX_BYTES` says otherwise
+pub const DEFAULT_INLINE_MAX_BYTES: usize = 1024 * 1024;
+
+/// Reference to a stored blob, as carried in event metadata
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BlobRef {
+    pub id: String,
+    pub size: u64,
+    /// Hex SHA-256 of the content
+    pub sha256: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub content_type: String,
+}
+
+impl BlobRef {
+    /// Reference carried by `event`, if any
+    pub fn from_event(event: &Event) -> Option<Self> {
+        Self::from_metadata(&event.metadata)
+    }
+
+    pub fn from_metadata(meta: &HashMap<String, String>) -> Option<Self> {
+        Some(Self {
+            id: meta.get(keys::REF)?.clone(),
+            size: meta.get(keys::SIZE)?.parse().ok()?,
+            sha256: meta.get(keys::SHA256)?.clone(),
+            content_type: meta.get(keys::CONTENT_TYPE).cloned().unwrap_or_default(),
+        })
+    }
+
+    pub fn apply_to_metadata(&self, meta: &mut HashMap<String, String>) {
+        meta.insert(keys::REF.into(), self.id.clone());
+        meta.insert(keys::SIZE.into(), self.size.to_string());
+        meta.insert(keys::SHA256.into(), self.sha256.clone());
+        if !self.content_type.is_empty() {
+            meta.insert(keys::CONTENT_TYPE.into(), self.content_type.clone());
+        }
+    }
+
+    /// Put `content` back into `event` as its payload and drop the reference
+    pub fn inline(event: &mut Event, content: Vec<u8>) {
+        for key in [keys::REF, keys::SIZE, keys::SHA256, keys::CONTENT_TYPE] {
+            event.metadata.remove(key);
+        }
+        event.payload = content;
+    }
+}
+
+/// Who may read a blob
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BlobAccess {
+    /// Agent that stored the blob; empty for host code
+    #[serde(default)]
+    pub owner: String,
+    /// Namespace the blob is confined to
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub namespace: Option<String>,
+    /// Agent ids allowed to read (`*` for any); empty allows every agent
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub readers: Vec<String>,
+}
+
+impl BlobAccess {
+    pub fn owned_by(owner: impl Into<String>) -> Self {
+        Self {
+            owner: owner.into(),
+            ..Default::default()
+        }
+    }
+
+    pub fn with_namespace(mut self, namespace: impl Into<String>) -> Self {
+        self.namespace = Some(namespace.into());
+        self
+    }
+
+    pub fn with_readers<I, S>(mut self, readers: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.readers = readers.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Readers listed in the `blob.readers` metadata of `event`
+    pub fn with_readers_of(self, event: &Event) -> Self {
+        match event.metadata.get(keys::READERS) {
+            Some(list) => self.with_readers(
+                list.split(',')
+                    .map(str::trim)
+                    .filter(|r| !r.is_empty())
+                    .map(str::to_string),
+            ),
+            None => self,
+        }
+    }
+
+    /// Whether `agent_id` (`None` outside an agent) in `namespace` may read the blob
+    pub fn allows(&self, agent_id: Option<&str>, namespace: Option<&str>) -> bool {
+        if agent_id.is_some_and(|a| !self.owner.is_empty() && a == self.owner) {
+            return true;
+        }
+        if self.namespace.is_some() && self.namespace.as_deref() != namespace {
+            return false;
+        }
+        if self.readers.is_empty() {
+            return true;
+        }
+        self.readers
+            .iter()
+            .any(|r| r == "*" || agent_id.is_some_and(|a| a == r))
+    }
+}
+
+/// What is stored next to each blob
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct BlobRecord {
+    #[serde(flatten)]
+    blob: BlobRef,
+    access: BlobAccess,
+    created_at_ms: i64,
+}
+
+/// Storage of blob content and records by key
+#[async_trait]
+pub trait BlobBackend: Send + Sync {
+    /// Short name for logs (`disk`, `s3`)
+    fn name(&self) -> &'static str;
+
+    async fn put(&self, key: &str, bytes: Vec<u8>) -> Result<()>;
+
+    /// `None` if nothing is stored under `key`
+    async fn get(&self, key: &str) -> Result<Option<Vec<u8>>>;
+
+    /// Deleting a missing key succeeds
+    async fn delete(&self, key: &str) -> Result<()>;
+}
+
+/// Blobs as files in one directory
+pub struct DiskBlobBackend {
+    dir: PathBuf,
+}
+
+impl DiskBlobBackend {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+}
+
+#[async_trait]
+impl BlobBackend for DiskBlobBackend {
+    fn name(&self) -> &'static str {
+        "disk"
+    }
+
+    async fn put(&self, key: &str, bytes: Vec<u8>) -> Result<()> {
+        tokio::fs::create_dir_all(&self.dir).await?;
+        // Written aside and renamed, so readers never see a partial file
+        let path = self.dir.join(key);
+        let partial = self.dir.join(format!("{key}.partial"));
+        tokio::fs::write(&partial, bytes).await?;
+        tokio::fs::rename(&partial, &path).await?;
+        Ok(())
+    }
+
+    async fn get(&self, key: &str) -> Result<Option<Vec<u8>>> {
+        match tokio::fs::read(self.dir.join(key)).await {
+            Ok(bytes) => Ok(Some(bytes)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    async fn delete(&self, key: &str) -> Result<()> {
+        match tokio::fs::remove_file(self.dir.join(key)).await {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Location and credentials of an S3-compatible bucket
+#[derive(Debug, Clone)]
+pub struct S3Config {
+    /// Base URL of the service, e.g. `https://s3.eu-west-1.amazonaws.com` or
+    /// `http://localhost:9000`
+    pub endpoint: String,
+    pub bucket: String,
+    pub region: String,
+    pub access_key: String,
+    pub secret_key: String,
+    /// Prepended to every object key
+    pub prefix: String,
+}
+
+/// Blobs as objects in an S3-compatible bucket
+pub struct S3BlobBackend {
+    client: S3Client,
+    bucket: String,
+    prefix: String,
+}
+
+impl S3BlobBackend {
+    pub fn new(config: S3Config) -> Result<Self> {
+        Ok(Self {
+            client: S3Client::new(
+                config.endpoint,
+                config.region,
+                config.access_key,
+                config.secret_key,
+            )?,
+            bucket: config.bucket,
+            prefix: config.prefix,
+        })
+    }
+
+    async fn send(
+        &self,
+        method: reqwest::Method,
+        key: &str,
+        body: Vec<u8>,
+    ) -> Result<reqwest::Response> {
+        let path = object_path(&self.bucket, &format!("{}{}", self.prefix, key));
+        self.client.send(method, &path, &[], body, None).await
+    }
+}
+
+#[async_trait]
+impl BlobBackend for S3BlobBackend {
+    fn name(&self) -> &'static str {
+        "s3"
+    }
+
+    async fn put(&self, key: &str, bytes: Vec<u8>) -> Result<()> {
+        let resp = self.send(reqwest::Method::PUT, key, bytes).await?;
+        check_status(resp, "PUT", key).await.map(drop)
+    }
+
+    async fn get(&self, key: &str) -> Result<Option<Vec<u8>>> {
+        let resp = self.send(reqwest::Method::GET, key, Vec::new()).await?;
+        if resp.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        let resp = check_status(resp, "GET", key).await?;
+        let bytes = resp
+            .bytes()
+            .await
+            .map_err(|e| LoomError::StorageError(format!("S3 GET {key} failed: {e}")))?;
+        Ok(Some(bytes.to_vec()))
+    }
+
+    async fn delete(&self, key: &str) -> Result<()> {
+        let resp = self.send(reqwest::Method::DELETE, key, Vec::new()).await?;
+        if resp.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(());
+        }
+        check_status(resp, "DELETE", key).await.map(drop)
+    }
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+/// Content-addressed checks and access control over a `BlobBackend`
+pub struct BlobStore {
+    backend: Arc<dyn BlobBackend>,
+    max_bytes: u64,
+    inline_max_bytes: usize,
+}
+
+impl std::fmt::Debug for BlobStore {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("BlobStore")
+            .field("backend", &self.backend.name())
+            .field("max_bytes", &self.max_bytes)
+            .field("inline_max_bytes", &self.inline_max_bytes)
+            .finish()
+    }
+}
+
+impl BlobStore {
+    pub fn new(backend: Arc<dyn BlobBackend>) -> Self {
+        Self {
+            backend,
+            max_bytes: DEFAULT_MAX_BYTES,
+            inline_max_bytes: DEFAULT_INLINE_MAX_BYTES,
+        }
+    }
+
+    pub fn with_max_bytes(mut self, max_bytes: u64) -> Self {
+        self.max_bytes = max_bytes;
+        self
+    }
+
+    /// Payloads above `bytes` are moved into the store by `stash`; 0 never stashes
+    pub fn with_inline_max_bytes(mut self, bytes: usize) -> Self {
+        self.inline_max_bytes = bytes;
+        self
+    }
+
+    pub fn inline_max_bytes(&self) -> usize {
+        self.inline_max_bytes
+    }
+
+    /// Store from `LOOM_BLOB_DIR`, or from `LOOM_BLOB_S3_ENDPOINT`, `LOOM_BLOB_S3_BUCKET`,
+    /// `LOOM_BLOB_S3_REGION` (default `us-east-1`), `LOOM_BLOB_S3_ACCESS_KEY`,
+    /// `LOOM_BLOB_S3_SECRET_KEY` and `LOOM_BLOB_S3_PREFIX`; limits from
+    /// `LOOM_BLOB_MAX_BYTES` and `LOOM_BLOB_INLINE_MAX_BYTES`. `Ok(None)` unless a
+    /// directory or endpoint is set.
+    pub fn from_env() -> Result<Option<Self>> {
+        let backend: Arc<dyn BlobBackend> = if let Ok(dir) = std::env::var("LOOM_BLOB_DIR") {
+            Arc::new(DiskBlobBackend::new(dir))
+        } else if let Ok(endpoint) = std::env::var("LOOM_BLOB_S3_ENDPOINT") {
+            let var = |name: &str| {
+                std::env::var(name).map_err(|_| {
+                    LoomError::ConfigError(format!("{name} is required with LOOM_BLOB_S3_ENDPOINT"))
+                })
+            };
+            Arc::new(S3BlobBackend::new(S3Config {
+                endpoint,
+                bucket: var("LOOM_BLOB_S3_BUCKET")?,
+                region: std::env::var("LOOM_BLOB_S3_REGION")
+                    .unwrap_or_else(|_| "us-east-1".to_string()),
+                access_key: var("LOOM_BLOB_S3_ACCESS_KEY")?,
+                secret_key: var("LOOM_BLOB_S3_SECRET_KEY")?,
+                prefix: std::env::var("LOOM_BLOB_S3_PREFIX").unwrap_or_default(),
+            })?)
+        } else {
+            return Ok(None);
+        };
+        let mut store = Self::new(backend);
+        if let Some(max) = env_number("LOOM_BLOB_MAX_BYTES")? {
+            store.max_bytes = max;
+        }
+        if let Some(inline) = env_number("LOOM_BLOB_INLINE_MAX_BYTES")? {
+            store.inline_max_bytes = inline as usize;
+        }
+        Ok(Some(store))
+    }
+
+    /// Store `content` readable per `access`
+    pub async fn put(
+        &self,
+        content: Vec<u8>,
+        content_type: &str,
+        access: BlobAccess,
+    ) -> Result<BlobRef> {
+        if content.len() as u64 > self.max_bytes {
+            return Err(LoomError::StorageError(format!(
+                "Blob of {} bytes exceeds the limit of {} bytes",
+                content.len(),
+                self.max_bytes
+            )));
+        }
+        let blob = BlobRef {
+            id: new_blob_id()?,
+            size: content.len() as u64,
+            sha256: hex(digest::digest(&digest::SHA256, &content).as_ref()),
+            content_type: content_type.to_string(),
+        };
+        let record = BlobRecord {
+            blob: blob.clone(),
+            access,
+            created_at_ms: chrono::Utc::now().timestamp_millis(),
+        };
+        // Content first: a record always points at stored content
+        self.backend.put(&blob.id, content).await?;
+        self.backend
+            .put(&record_key(&blob.id), serde_json::to_vec(&record)?)
+            .await?;
+        Ok(blob)
+    }
+
+    /// Who may read blob `id`; `None` if it does not exist
+    pub async fn access_of(&self, id: &str) -> Result<Option<BlobAccess>> {
+        Ok(self.record(id).await?.map(|r| r.access))
+    }
+
+    /// Reference to blob `id`; `None` if it does not exist
+    pub async fn blob_ref(&self, id: &str) -> Result<Option<BlobRef>> {
+        Ok(self.record(id).await?.map(|r| r.blob))
+    }
+
+    /// Content of `blob` and who may read it, without checking access. Fails if the blob
+    /// is missing or its content does not match the reference.
+    pub async fn load(&self, blob: &BlobRef) -> Result<(Vec<u8>, BlobAccess)> {
+        let record = self
+            .record(&blob.id)
+            .await?
+            .ok_or_else(|| LoomError::StorageError(format!("Blob {} not found", blob.id)))?;
+        let content = self
+            .backend
+            .get(&blob.id)
+            .await?
+            .ok_or_else(|| LoomError::StorageError(format!("Blob {} not found", blob.id)))?;
+        let sha256 = hex(digest::digest(&digest::SHA256, &content).as_ref());
+        if content.len() as u64 != blob.size || sha256 != blob.sha256 {
+            return Err(LoomError::StorageError(format!(
+                "Blob {} does not match its reference",
+                blob.id
+            )));
+        }
+        Ok((content, record.access))
+    }
+
+    /// Content of `blob` if `agent_id` in `namespace` may read it
+    pub async fn get(
+        &self,
+        blob: &BlobRef,
+        agent_id: Option<&str>,
+        namespace: Option<&str>,
+    ) -> Result<Vec<u8>> {
+        let (content, access) = self.load(blob).await?;
+        if !access.allows(agent_id, namespace) {
+            return Err(LoomError::StorageError(format!(
+                "{} may not read blob {}",
+                agent_id.unwrap_or("anonymous reader"),
+                blob.id
+            )));
+        }
+        Ok(content)
+    }
+
+    /// Delete blob `id`; returns whether it existed
+    pub async fn delete(&self, id: &str) -> Result<bool> {
+        if self.record(id).await?.is_none() {
+            return Ok(false);
+        }
+        // Record first: a half-deleted blob is unreachable rather than unverifiable
+        self.backend.delete(&record_key(id)).await?;
+        self.backend.delete(id).await?;
+        Ok(true)
+    }
+
+    /// Move the payload of `event` into the store if it is larger than
+    /// `inline_max_bytes`, leaving a reference. Returns the reference if it stashed. On
+    /// error the event has lost its payload and should not be published.
+    pub async fn stash(&self, event: &mut Event, access: BlobAccess) -> Result<Option<BlobRef>> {
+        if self.inline_max_bytes == 0
+            || event.payload.len() <= self.inline_max_bytes
+            || event.metadata.contains_key(keys::REF)
+        {
+            return Ok(None);
+        }
+        let content_type = event
+            .metadata
+            .get(keys::CONTENT_TYPE)
+            .cloned()
+            .unwrap_or_default();
+        let content = std::mem::take(&mut event.payload);
+        let blob = self.put(content, &content_type, access).await?;
+        blob.apply_to_metadata(&mut event.metadata);
+        Ok(Some(blob))
+    }
+
+    /// Replace the reference `event` carries by the blob's content, if `agent_id` in
+    /// `namespace` may read it. Returns whether the event carried a reference.
+    pub async fn resolve(
+        &self,
+        event: &mut Event,
+        agent_id: Option<&str>,
+        namespace: Option<&str>,
+    ) -> Result<bool> {
+        let Some(blob) = BlobRef::from_event(event) else {
+            return Ok(false);
+        };
+        let content = self.get(&blob, agent_id, namespace).await?;
+        BlobRef::inline(event, content);
+        Ok(true)
+    }
+
+    async fn record(&self, id: &str) -> Result<Option<BlobRecord>> {
+        if !is_blob_id(id) {
+            return Err(LoomError::StorageError(format!("Invalid blob id {id:?}")));
+        }
+        match self.backend.get(&record_key(id)).await? {
+            Some(bytes) => Ok(Some(serde_json::from_slice(&bytes)?)),
+            None => Ok(None),
+        }
+    }
+}
+
+fn record_key(id: &str) -> String {
+    format!("{id}.json")
+}
+
+/// 128 random bits, hex
+fn new_blob_id() -> Result<String> {
+    let mut bytes = [0u8; 16];
+    ring::rand::SystemRandom::new()
+        .fill(&mut bytes)
+        .map_err(|_| LoomError::StorageError("No randomness for a blob id".to_string()))?;
+    Ok(hex(&bytes))
+}
+
+/// Ids are generated hex, which also keeps them safe as file names and object keys
+fn is_blob_id(id: &str) -> bool {
+    id.len() == 32 && id.bytes().all(|b| b.is_ascii_hexdigit())
+}
+
+fn env_number(name: &str) -> Result<Option<u64>> {
+    match std::env::var(name) {
+        Ok(value) => {
+            value.trim().parse().map(Some).map_err(|_| {
+                LoomError::ConfigError(format!("{name} must be a number, got {value:?}"))
+            })
+        }
+        Err(_) => Ok(None),
+    }
+}
@@ -1,12 +1,38 @@
 //! Adapter that wraps a CognitiveLoop as an AgentBehavior.
 
+use std::sync::Arc;
+
 use async_trait::async_trait;
 
 use crate::agent::AgentBehavior;
+use crate::context::MessageRole;
+use crate::messaging::envelope::keys;
+use crate::namespace::{Namespace, NAMESPACE_KEY};
+use crate::openai::QUERY_EVENT;
 use crate::proto::{Action, AgentConfig, AgentState, Event};
-use crate::Result;
+use crate::{Envelope, EventBus, Result};
+
+use super::bootstrap::{BootstrapSeed, BOOTSTRAP_DIR_PARAM};
+use super::loop_trait::{CognitiveLoop, Perception};
+use super::session::{Session, SessionManager};
+use super::streaming::{forward_partials, PartialTarget, ResponseSink, RESPONSE_FINAL_EVENT};
+use super::structured::RESULT_ACTION;
+
+/// Event metadata key asking the agent to publish its response (value `"true"`); the
+/// envelope's `reply_expected` field
+pub const EXPECT_REPLY_KEY: &str = crate::messaging::envelope::keys::REPLY_EXPECTED;
+
+/// Action carrying a response to the requester; the agent publishes its payload as an
+/// [`RESPONSE_EVENT`] to the `reply_to` parameter's topic instead of calling a tool
+pub const REPLY_ACTION: &str = "agent.reply";
 
-use super::loop_trait::CognitiveLoop;
+/// Event type of a published response (payload: UTF-8 text)
+pub const RESPONSE_EVENT: &str = "agent.response";
+
+/// `REPLY_ACTION` parameter overriding the published event type (default [`RESPONSE_EVENT`])
+pub const REPLY_EVENT_TYPE_PARAM: &str = "event_type";
+
+// Structured results: `RESULT_ACTION`s published on `result.<agent_id>` (see `structured`)
 
 /// Adapter that lets a [`CognitiveLoop`] be used as an [`AgentBehavior`].
 ///
@@ -34,6 +60,12 @@ pub struct CognitiveAgent<L: CognitiveLoop> {
 
     /// Whether initialization has completed
     initialized: bool,
+
+    /// Session that each cycle's request and response are recorded to
+    session: Option<(Arc<SessionManager>, String)>,
+
+    /// Bus partial responses are published on; `None` disables streaming
+    streaming: Option<Arc<EventBus>>,
 }
 
 impl<L: CognitiveLoop> CognitiveAgent<L> {
@@ -42,9 +74,55 @@ impl<L: CognitiveLoop> CognitiveAgent<L> {
         Self {
             loop_impl,
             initialized: false,
+            session: None,
+            streaming: None,
         }
     }
 
+    /// Stream responses to `user.query` requests that expect a reply: partial output is
+    /// published to the reply topic as `response.partial` events while the loop generates
+    /// it, and the reply is published as `response.final` (see `streaming`)
+    pub fn with_response_streaming(mut self, event_bus: Arc<EventBus>) -> Self {
+        self.streaming = Some(event_bus);
+        self
+    }
+
+    /// Record every cycle's request and response to `session_id`
+    pub fn with_session(
+        mut self,
+        manager: Arc<SessionManager>,
+        session_id: impl Into<String>,
+    ) -> Self {
+        self.session = Some((manager, session_id.into()));
+        self
+    }
+
+    /// Reload a session's transcript into the memory buffer (replacing its contents)
+    /// and record subsequent cycles to it
+    pub async fn resume_session(
+        &mut self,
+        manager: Arc<SessionManager>,
+        session_id: &str,
+    ) -> Result<Session> {
+        let (session, turns) = manager.resume_session(session_id).await?;
+        let memory = self.loop_impl.memory_buffer_mut();
+        memory.clear();
+        for turn in turns.iter() {
+            match turn.role {
+                MessageRole::User => memory.add_user_message(&turn.content),
+                MessageRole::Assistant => memory.add_agent_response(&turn.content),
+                MessageRole::System => {}
+            }
+        }
+        self.session = Some((manager, session_id.to_string()));
+        Ok(session)
+    }
+
+    /// Id of the session this agent records to, if any
+    pub fn session_id(&self) -> Option<&str> {
+        self.session.as_ref().map(|(_, id)| id.as_str())
+    }
+
     /// Get a reference to the underlying cognitive loop
     pub fn inner(&self) -> &L {
         &self.loop_impl
@@ -79,8 +157,79 @@ impl<L: CognitiveLoop + 'static> AgentBehavior for CognitiveAgent<L> {
             "Starting cognitive cycle"
         );
 
+        let request = self
+            .session
+            .as_ref()
+            .and_then(|_| Perception::from_event(event.clone()).goal)
+            .map(|goal| (event.source.clone(), goal));
+        let envelope = Envelope::from_event(&event);
+        let correlation_id = envelope.correlation_id.clone();
+        let reply_to = Some(envelope)
+            .filter(|env| env.reply_expected)
+            .map(|env| (env, event.id.clone()));
+        let priority = event.priority;
+        let event_id = event.id.clone();
+
+        // Stream partial output to the requester while the cycle runs
+        let forwarder = match (&self.streaming, &reply_to) {
+            (Some(event_bus), Some((env, _))) if event.r#type == QUERY_EVENT => {
+                let (sink, rx) = ResponseSink::channel();
+                self.loop_impl.set_response_sink(Some(sink));
+                let target = PartialTarget {
+                    topic: env.reply_to.clone(),
+                    correlation_id: env.correlation_id.clone(),
+                    caused_by: event_id.clone(),
+                    source: format!("agent.{}", state.agent_id),
+                    priority,
+                    namespace: state
+                        .metadata
+                        .get(NAMESPACE_KEY)
+                        .and_then(|id| Namespace::new(id.as_str()).ok()),
+                };
+                Some(tokio::spawn(forward_partials(
+                    Arc::clone(event_bus),
+                    target,
+                    rx,
+                )))
+            }
+            _ => None,
+        };
+
         // Run the complete cognitive cycle
-        let result = self.loop_impl.run_cycle(event, state).await?;
+        let result = self.loop_impl.run_cycle(event, state).await;
+        let streamed = match forwarder {
+            Some(forwarder) => {
+                // Dropping the sink ends the forwarder once every partial is published
+                self.loop_impl.set_response_sink(None);
+                Some(forwarder.await.unwrap_or_default())
+            }
+            None => None,
+        };
+        let result = result?;
+
+        if let Some((ref manager, ref session_id)) = self.session {
+            if let Some((source, goal)) = request {
+                if let Err(e) = manager
+                    .record_turn(session_id, &source, MessageRole::User, goal)
+                    .await
+                {
+                    tracing::warn!(target = "cognitive", session_id = %session_id, error = %e, "Failed to record session turn");
+                }
+            }
+            if let Some(ref response) = result.response {
+                if let Err(e) = manager
+                    .record_turn(
+                        session_id,
+                        &state.agent_id,
+                        MessageRole::Assistant,
+                        response.clone(),
+                    )
+                    .await
+                {
+                    tracing::warn!(target = "cognitive", session_id = %session_id, error = %e, "Failed to record session turn");
+                }
+            }
+        }
 
         tracing::debug!(
             target = "cognitive",
@@ -89,16 +238,80 @@ impl<L: CognitiveLoop + 'static> AgentBehavior for CognitiveAgent<L> {
             "Cognitive cycle complete"
         );
 
-        Ok(result.into_actions())
+        // Structured results go to the agent's result topic, correlated with the request
+        let results: Vec<Action> = result
+            .structured
+            .iter()
+            .map(|output| {
+                let mut parameters = output.metadata();
+                parameters.insert(keys::CORRELATION_ID.to_string(), correlation_id.clone());
+                parameters.insert(keys::CAUSED_BY.to_string(), event_id.clone());
+                Action {
+                    action_type: RESULT_ACTION.to_string(),
+                    parameters,
+                    payload: serde_json::to_vec(&output.payload).unwrap_or_default(),
+                    priority,
+                }
+            })
+            .collect();
+
+        // A stream is always closed by a final response, empty if the cycle produced none
+        let response = match streamed {
+            Some(partials) => {
+                tracing::debug!(target = "cognitive", partials, "Response streamed");
+                Some(result.response.clone().unwrap_or_default())
+            }
+            None => result.response.clone(),
+        };
+        let reply = reply_to.zip(response).map(|((env, event_id), response)| {
+            let mut parameters: std::collections::HashMap<String, String> = [
+                ("reply_to".to_string(), env.reply_to),
+                ("correlation_id".to_string(), env.correlation_id),
+                ("caused_by".to_string(), event_id),
+            ]
+            .into();
+            if streamed.is_some() {
+                parameters.insert(
+                    REPLY_EVENT_TYPE_PARAM.to_string(),
+                    RESPONSE_FINAL_EVENT.to_string(),
+                );
+            }
+            Action {
+                action_type: REPLY_ACTION.to_string(),
+                parameters,
+                payload: response.into_bytes(),
+                priority,
+            }
+        });
+        let mut actions = result.into_actions();
+        actions.extend(reply);
+        actions.extend(results);
+        Ok(actions)
     }
 
-    async fn on_init(&mut self, _config: &AgentConfig) -> Result<()> {
+    async fn on_init(&mut self, config: &AgentConfig) -> Result<()> {
         tracing::info!(
             target = "cognitive",
-            agent_id = %_config.agent_id,
+            agent_id = %config.agent_id,
             "Initializing cognitive agent"
         );
 
+        self.loop_impl.restrict_tools(&config.parameters)?;
+
+        if let Some(dir) = config.parameters.get(BOOTSTRAP_DIR_PARAM) {
+            let seed = BootstrapSeed::load(dir)?;
+            let written = self.loop_impl.bootstrap(&config.agent_id, &seed).await?;
+            tracing::info!(
+                target = "cognitive",
+                agent_id = %config.agent_id,
+                dir = %dir,
+                prompts = seed.prompts.len(),
+                documents = seed.documents.len(),
+                written,
+                "Seeded agent from bootstrap directory"
+            );
+        }
+
         // Note: Session tracking is now handled by AgentContext
         // Memory buffer is a simple in-process cache
 
@@ -0,0 +1,276 @@
+//! Cold-start bootstrap: seed an agent's memory and system prompt from a directory.
+//!
+//! A [`CognitiveAgent`](super::CognitiveAgent) whose `AgentConfig.parameters` set
+//! [`BOOTSTRAP_DIR_PARAM`] loads that directory when it starts, so shipping a
+//! preconfigured assistant is a matter of pointing it at its documents:
+//!
+//! ```text
+//! seeds/support/
+//! ├── persona.prompt.md      appended to the system prompt
+//! ├── prompts/policies.md    appended to the system prompt
+//! ├── faq.md                 stored in the agent's MemoryStore
+//! └── docs/returns.txt       stored in the agent's MemoryStore
+//! ```
+//!
+//! - Prompt files (`*.prompt.md`, `*.prompt.txt`, or any text file under `prompts/`) are
+//!   appended to the configured system prompt, in path order. They are always in the prompt.
+//! - Other `.md`/`.markdown`/`.txt` files are split into paragraph-aligned chunks and stored
+//!   as `Observation` items of the [`SEED_SESSION_ID`] session, tagged with their path
+//!   ([`SEED_PATH_TAG`]). Each cycle adds the chunks that share the most words with the
+//!   request to the prompt's context.
+//!
+//! Hidden files and other extensions are skipped. Chunk ids are derived from the agent,
+//! path and chunk position, so restarting an agent on a persistent store rewrites its
+//! seed chunks instead of duplicating them.
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+use crate::context::{
+    ContextContent, ContextItem, ContextItemType, ContextMetadata, MemoryQuery, MemoryStore,
+};
+use crate::{LoomError, Result};
+
+/// `AgentConfig` parameter naming the directory an agent is seeded from
+pub const BOOTSTRAP_DIR_PARAM: &str = "bootstrap.dir";
+
+/// Session id of seeded memory items
+pub const SEED_SESSION_ID: &str = "bootstrap";
+
+/// Tag holding a seeded item's path, relative to the bootstrap directory
+pub const SEED_PATH_TAG: &str = "seed.path";
+
+/// `Observation` source of seeded memory items
+const SEED_SOURCE: &str = "bootstrap";
+
+/// Longest chunk a seed document is split into, in characters (longer paragraphs are
+/// kept whole)
+const MAX_CHUNK_CHARS: usize = 1500;
+
+/// Most seed chunks considered per request
+const MAX_SEED_ITEMS: usize = 10_000;
+
+/// Extensions of the files a bootstrap directory is read from
+const TEXT_EXTENSIONS: [&str; 3] = ["md", "markdown", "txt"];
+
+/// A document stored in the agent's memory
+#[derive(Debug, Clone, PartialEq)]
+pub struct SeedDocument {
+    /// Path relative to the bootstrap directory, `/`-separated
+    pub path: String,
+    pub text: String,
+}
+
+/// Contents of a bootstrap directory
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BootstrapSeed {
+    /// Prompt files' contents, in path order
+    pub prompts: Vec<String>,
+    /// Documents to store, in path order
+    pub documents: Vec<SeedDocument>,
+}
+
+impl BootstrapSeed {
+    /// Read the directory at `dir` (see the module docs)
+    pub fn load(dir: impl AsRef<Path>) -> Result<Self> {
+        let dir = dir.as_ref();
+        if !dir.is_dir() {
+            return Err(LoomError::AgentError(format!(
+                "Bootstrap directory {} does not exist",
+                dir.display()
+            )));
+        }
+        let mut files = Vec::new();
+        collect_files(dir, &mut files)?;
+        files.sort();
+
+        let mut seed = Self::default();
+        for file in files {
+            let relative = file
+                .strip_prefix(dir)
+                .unwrap_or(&file)
+                .components()
+                .map(|c| c.as_os_str().to_string_lossy())
+                .collect::<Vec<_>>()
+                .join("/");
+            let text = std::fs::read_to_string(&file)?;
+            let text = text.trim();
+            if text.is_empty() {
+                continue;
+            }
+            if is_prompt_file(&relative) {
+                seed.prompts.push(text.to_string());
+            } else {
+                seed.documents.push(SeedDocument {
+                    path: relative,
+                    text: text.to_string(),
+                });
+            }
+        }
+        Ok(seed)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.prompts.is_empty() && self.documents.is_empty()
+    }
+
+    /// `base` followed by the prompt files, separated by blank lines
+    pub fn system_prompt(&self, base: Option<&str>) -> Option<String> {
+        let parts: Vec<&str> = base
+            .into_iter()
+            .chain(self.prompts.iter().map(String::as_str))
+            .collect();
+        if parts.is_empty() {
+            None
+        } else {
+            Some(parts.join("\n\n"))
+        }
+    }
+
+    /// The documents' chunks as memory items of `agent_id`
+    pub fn context_items(&self, agent_id: &str) -> Vec<ContextItem> {
+        let mut items = Vec::new();
+        for document in &self.documents {
+            for (index, chunk) in chunk_text(&document.text, MAX_CHUNK_CHARS)
+                .into_iter()
+                .enumerate()
+            {
+                items.push(ContextItem {
+                    id: format!("seed_{}_{}#{}", agent_id, document.path, index),
+                    item_type: ContextItemType::Observation {
+                        source: SEED_SOURCE.to_string(),
+                    },
+                    content: ContextContent::from_string(chunk),
+                    metadata: ContextMetadata::new(
+                        SEED_SESSION_ID.to_string(),
+                        agent_id.to_string(),
+                    )
+                    .with_importance(1.0)
+                    .with_tag(SEED_PATH_TAG.to_string(), document.path.clone()),
+                });
+            }
+        }
+        items
+    }
+
+    /// Store the documents' chunks in `store`; returns the number written. Chunks the
+    /// store already holds with the same text are left alone.
+    pub async fn seed(&self, store: &dyn MemoryStore, agent_id: &str) -> Result<usize> {
+        let mut written = Vec::new();
+        for item in self.context_items(agent_id) {
+            match store.get(&item.id).await? {
+                Some(existing) if existing.content.text == item.content.text => {}
+                _ => written.push(item),
+            }
+        }
+        let count = written.len();
+        if count > 0 {
+            store.store_batch(written).await?;
+        }
+        Ok(count)
+    }
+}
+
+/// Seed chunks of `agent_id` most relevant to `goal`: those sharing the most words
+/// with it, at most `limit`, formatted as `[path] text`
+pub async fn relevant_seed_chunks(
+    store: &dyn MemoryStore,
+    agent_id: &str,
+    goal: &str,
+    limit: usize,
+) -> Result<Vec<String>> {
+    let goal_words = words(goal);
+    if goal_words.is_empty() || limit == 0 {
+        return Ok(vec![]);
+    }
+    let mut query = MemoryQuery::new()
+        .for_session(SEED_SESSION_ID.to_string())
+        .for_agent(agent_id.to_string());
+    query.limit = MAX_SEED_ITEMS;
+    let items = store.query(&query).await?;
+
+    // A rewritten chunk may be listed twice
+    let mut seen = HashSet::new();
+    let mut scored: Vec<(usize, &ContextItem)> = items
+        .iter()
+        .filter(|item| seen.insert(item.id.as_str()))
+        .filter_map(|item| {
+            let score = words(&item.content.text).intersection(&goal_words).count();
+            (score > 0).then_some((score, item))
+        })
+        .collect();
+    // Best match first; ties in id (path, then chunk) order
+    scored.sort_by(|a, b| b.0.cmp(&a.0).then_with(|| a.1.id.cmp(&b.1.id)));
+    Ok(scored
+        .into_iter()
+        .take(limit)
+        .map(|(_, item)| {
+            let path = item
+                .metadata
+                .tags
+                .get(SEED_PATH_TAG)
+                .map(String::as_str)
+                .unwrap_or_default();
+            format!("[{}] {}", path, item.content.text)
+        })
+        .collect())
+}
+
+/// Text files under `dir`, skipping hidden entries
+fn collect_files(dir: &Path, files: &mut Vec<PathBuf>) -> Result<()> {
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        let hidden = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .is_some_and(|name| name.starts_with('.'));
+        if hidden {
+            continue;
+        }
+        if path.is_dir() {
+            collect_files(&path, files)?;
+        } else if path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .is_some_and(|ext| TEXT_EXTENSIONS.contains(&ext.to_ascii_lowercase().as_str()))
+        {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+/// Whether the file at `relative` is appended to the system prompt
+fn is_prompt_file(relative: &str) -> bool {
+    let name = relative.rsplit('/').next().unwrap_or(relative);
+    let stem = name.rsplit_once('.').map_or(name, |(stem, _)| stem);
+    relative.starts_with("prompts/") || stem.ends_with(".prompt")
+}
+
+/// Split `text` at blank lines into chunks of at most `max_chars` characters
+fn chunk_text(text: &str, max_chars: usize) -> Vec<String> {
+    let mut chunks = Vec::new();
+    let mut current = String::new();
+    for paragraph in text.split("\n\n").map(str::trim).filter(|p| !p.is_empty()) {
+        let joined = current.chars().count() + 2 + paragraph.chars().count();
+        if !current.is_empty() && joined > max_chars {
+            chunks.push(std::mem::take(&mut current));
+        }
+        if !current.is_empty() {
+            current.push_str("\n\n");
+        }
+        current.push_str(paragraph);
+    }
+    if !current.is_empty() {
+        chunks.push(current);
+    }
+    chunks
+}
+
+/// Lowercased words of three or more characters
+fn words(text: &str) -> HashSet<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| word.chars().count() >= 3)
+        .map(str::to_lowercase)
+        .collect()
+}
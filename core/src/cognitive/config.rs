@@ -1,16 +1,31 @@
 //! Configuration for cognitive agents.
 
+use std::collections::HashMap;
+
 use serde::{Deserialize, Serialize};
 
-/// Strategy for the thinking phase
+use super::guardrails::GuardrailConfig;
+
+/// Agent parameter (`AgentConfig.parameters`) with comma-separated glob patterns of the
+/// tools the agent may use; narrows `CognitiveConfig::allowed_tools`
+pub const ALLOWED_TOOLS_PARAM: &str = "tools.allowed";
+
+/// Agent parameter with comma-separated glob patterns of tools the agent may not use;
+/// added to `CognitiveConfig::denied_tools`
+pub const DENIED_TOOLS_PARAM: &str = "tools.denied";
+
+/// Strategy for the thinking phase; config files may also use the snake_case names
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
 pub enum ThinkingStrategy {
     /// Simple single-shot LLM call
     #[default]
+    #[serde(alias = "single_shot")]
     SingleShot,
     /// ReAct pattern: Reason → Act → Observe → Repeat
+    #[serde(alias = "react")]
     ReAct,
     /// Chain of Thought with explicit reasoning steps
+    #[serde(alias = "chain_of_thought")]
     ChainOfThought,
 }
 
@@ -32,17 +47,47 @@ pub struct CognitiveConfig {
     /// Timeout for each tool invocation in milliseconds
     pub tool_timeout_ms: u64,
 
+    /// Maximum number of pending tool calls executed concurrently in the act phase
+    /// (1 runs them one after another)
+    #[serde(default = "default_max_parallel_tools")]
+    pub max_parallel_tools: usize,
+
     /// Whether to refine the answer after tool use
     pub refine_after_tools: bool,
 
     /// Maximum number of tools to expose to the LLM
     pub max_tools_exposed: usize,
 
+    /// Glob patterns (`*`, `?`) of the tools the agent may see and call; `None` allows
+    /// every tool, an empty list none
+    #[serde(default)]
+    pub allowed_tools: Option<Vec<String>>,
+
+    /// Glob patterns of tools the agent may never see or call, even when allowed
+    #[serde(default)]
+    pub denied_tools: Vec<String>,
+
     /// System prompt template
     pub system_prompt: Option<String>,
 
     /// Temperature for LLM calls
     pub temperature: Option<f32>,
+
+    /// Content filters for inbound text, answers and tool arguments
+    #[serde(default)]
+    pub guardrails: GuardrailConfig,
+
+    /// Whether LLM calls may be served from (and stored in) the client's response cache
+    #[serde(default = "default_llm_cache")]
+    pub llm_cache: bool,
+}
+
+fn default_max_parallel_tools() -> usize {
+    4
+}
+
+fn default_llm_cache() -> bool {
+    true
 }
 
 impl Default for CognitiveConfig {
@@ -53,10 +98,15 @@ impl Default for CognitiveConfig {
             memory_window_size: 20,
             thinking_strategy: ThinkingStrategy::default(),
             tool_timeout_ms: 30_000,
+            max_parallel_tools: default_max_parallel_tools(),
             refine_after_tools: true,
             max_tools_exposed: 32,
+            allowed_tools: None,
+            denied_tools: Vec::new(),
             system_prompt: None,
             temperature: None,
+            guardrails: GuardrailConfig::default(),
+            llm_cache: default_llm_cache(),
         }
     }
 }
@@ -112,4 +162,119 @@ impl CognitiveConfig {
         self.memory_window_size = size;
         self
     }
+
+    /// Set how many tool calls may run at once (1 for sequential execution)
+    pub fn with_max_parallel_tools(mut self, max: usize) -> Self {
+        self.max_parallel_tools = max.max(1);
+        self
+    }
+
+    /// Set the guardrails
+    pub fn with_guardrails(mut self, guardrails: GuardrailConfig) -> Self {
+        self.guardrails = guardrails;
+        self
+    }
+
+    /// Keep this agent's LLM calls out of the response cache
+    pub fn without_llm_cache(mut self) -> Self {
+        self.llm_cache = false;
+        self
+    }
+
+    /// Only let the agent use tools matching one of `patterns`
+    pub fn with_allowed_tools<I, S>(mut self, patterns: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.allowed_tools = Some(patterns.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Never let the agent use tools matching one of `patterns`
+    pub fn with_denied_tools<I, S>(mut self, patterns: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.denied_tools
+            .extend(patterns.into_iter().map(Into::into));
+        self
+    }
+
+    /// Whether the agent may see and call tool `name`
+    pub fn allows_tool(&self, name: &str) -> bool {
+        let allowed = self
+            .allowed_tools
+            .as_ref()
+            .is_none_or(|allowed| allowed.iter().any(|p| glob_matches(p, name)));
+        allowed && !self.denied_tools.iter().any(|p| glob_matches(p, name))
+    }
+
+    /// Apply the `tools.allowed` and `tools.denied` agent parameters. Parameters can only
+    /// take tools away: an allowlist is narrowed to the tools both lists allow.
+    pub fn apply_tool_parameters(&mut self, parameters: &HashMap<String, String>) {
+        if let Some(allowed) = parameters.get(ALLOWED_TOOLS_PARAM) {
+            let allowed = split_patterns(allowed);
+            self.allowed_tools = Some(match self.allowed_tools.take() {
+                Some(configured) => narrow_patterns(&configured, &allowed),
+                None => allowed,
+            });
+        }
+        if let Some(denied) = parameters.get(DENIED_TOOLS_PARAM) {
+            self.denied_tools.extend(split_patterns(denied));
+        }
+    }
+}
+
+fn split_patterns(list: &str) -> Vec<String> {
+    list.split(',')
+        .map(str::trim)
+        .filter(|p| !p.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Patterns of either list whose tools the other list also allows
+fn narrow_patterns(a: &[String], b: &[String]) -> Vec<String> {
+    let within =
+        |pattern: &String, outer: &[String]| outer.iter().any(|o| glob_matches(o, pattern));
+    let mut narrowed: Vec<String> = a.iter().filter(|p| within(p, b)).cloned().collect();
+    for pattern in b.iter().filter(|p| within(p, a)) {
+        if !narrowed.contains(pattern) {
+            narrowed.push(pattern.clone());
+        }
+    }
+    narrowed
+}
+
+/// Whether `name` matches glob `pattern`: `*` matches any run of characters, `?` one
+fn glob_matches(pattern: &str, name: &str) -> bool {
+    let (pattern, name): (Vec<char>, Vec<char>) =
+        (pattern.chars().collect(), name.chars().collect());
+    let (mut p, mut n) = (0, 0);
+    // Position after the last `*` seen, and the name position it was tried at
+    let mut backtrack: Option<(usize, usize)> = None;
+    while n < name.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                p += 1;
+                backtrack = Some((p, n));
+            }
+            Some(&c) if c == '?' || c == name[n] => {
+                p += 1;
+                n += 1;
+            }
+            _ => match backtrack {
+                // Let the last `*` swallow one more character
+                Some((star_p, star_n)) => {
+                    p = star_p;
+                    n = star_n + 1;
+                    backtrack = Some((star_p, star_n + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
 }
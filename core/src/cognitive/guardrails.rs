@@ -0,0 +1,645 @@
+//! Guardrails: content filters applied by `SimpleCognitiveLoop`.
+//!
+//! A `Guardrail` inspects text at one of three stages: inbound event text (perceive),
+//! LLM output and tool arguments (act). For each it allows the text, rewrites it, or
+//! blocks it. Guardrails run in order and each sees the previous one's rewrite.
+//!
+//! Builtins cover keyword and regex filters, length limits and PII redaction. Agents
+//! configure them through `CognitiveConfig::guardrails` and can add their own with
+//! `SimpleCognitiveLoop::with_guardrail`.
+
+use std::fmt;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use opentelemetry::{global, metrics::Counter, KeyValue};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tracing::warn;
+
+use crate::{LoomError, Result};
+
+/// Where in the cognitive cycle a guardrail runs
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GuardStage {
+    /// Inbound event text, before it reaches the LLM (perceive)
+    Input,
+    /// The agent's answer (act)
+    Output,
+    /// String values in tool call arguments, before the tool runs (act)
+    ToolArguments,
+}
+
+impl GuardStage {
+    pub const ALL: [GuardStage; 3] = [
+        GuardStage::Input,
+        GuardStage::Output,
+        GuardStage::ToolArguments,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            GuardStage::Input => "input",
+            GuardStage::Output => "output",
+            GuardStage::ToolArguments => "tool_arguments",
+        }
+    }
+}
+
+/// Outcome of inspecting one piece of text
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Verdict {
+    Allow,
+    /// Continue with this text instead
+    Modify(String),
+    /// Stop: the text must not be used (reason)
+    Block(String),
+}
+
+/// A content filter for one or more stages of the cognitive cycle
+#[async_trait]
+pub trait Guardrail: Send + Sync {
+    fn name(&self) -> &str;
+
+    async fn inspect(&self, stage: GuardStage, text: &str) -> Verdict;
+}
+
+/// A guardrail blocked some text
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GuardrailViolation {
+    pub guardrail: String,
+    pub stage: GuardStage,
+    pub reason: String,
+}
+
+impl fmt::Display for GuardrailViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "blocked by guardrail {} ({}): {}",
+            self.guardrail,
+            self.stage.as_str(),
+            self.reason
+        )
+    }
+}
+
+/// What a keyword or regex filter does with a match
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FilterAction {
+    #[default]
+    Block,
+    /// Replace the match and continue
+    Mask,
+}
+
+/// Case-insensitive whole-word keyword filter
+pub struct KeywordFilter {
+    name: String,
+    words: Vec<String>,
+    action: FilterAction,
+}
+
+impl KeywordFilter {
+    pub fn new(words: impl IntoIterator<Item = impl Into<String>>, action: FilterAction) -> Self {
+        Self {
+            name: "keywords".to_string(),
+            words: words
+                .into_iter()
+                .map(|w| w.into().to_ascii_lowercase())
+                .filter(|w| !w.is_empty())
+                .collect(),
+            action,
+        }
+    }
+
+    pub fn with_name(mut self, name: impl Into<String>) -> Self {
+        self.name = name.into();
+        self
+    }
+
+    /// Byte ranges of whole-word matches. ASCII-only case folding keeps offsets
+    /// aligned with `text`.
+    fn matches(&self, text: &str) -> Vec<(usize, usize, &str)> {
+        let folded = text.to_ascii_lowercase();
+        let is_word = |c: Option<char>| c.is_some_and(|c| c.is_alphanumeric() || c == '_');
+        let mut found = Vec::new();
+        for word in &self.words {
+            let mut from = 0;
+            while let Some(pos) = folded[from..].find(word.as_str()) {
+                let start = from + pos;
+                let end = start + word.len();
+                if !is_word(text[..start].chars().next_back())
+                    && !is_word(text[end..].chars().next())
+                {
+                    found.push((start, end, word.as_str()));
+                }
+                from = end;
+            }
+        }
+        found.sort_unstable();
+        found
+    }
+}
+
+#[async_trait]
+impl Guardrail for KeywordFilter {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    async fn inspect(&self, _stage: GuardStage, text: &str) -> Verdict {
+        let found = self.matches(text);
+        let Some(&(_, _, word)) = found.first() else {
+            return Verdict::Allow;
+        };
+        match self.action {
+            FilterAction::Block => Verdict::Block(format!("contains blocked keyword \"{}\"", word)),
+            FilterAction::Mask => {
+                let mut masked = String::with_capacity(text.len());
+                let mut last = 0;
+                for (start, end, _) in found {
+                    // Overlapping keywords: the earlier match already covers this one
+                    if start < last {
+                        continue;
+                    }
+                    masked.push_str(&text[last..start]);
+                    masked.push_str(&"*".repeat(text[start..end].chars().count()));
+                    last = end;
+                }
+                masked.push_str(&text[last..]);
+                Verdict::Modify(masked)
+            }
+        }
+    }
+}
+
+/// Regex filter: blocks on a match, or replaces matches with `replacement`
+pub struct RegexFilter {
+    name: String,
+    regex: Regex,
+    action: FilterAction,
+    replacement: String,
+}
+
+impl RegexFilter {
+    pub fn new(pattern: &str, action: FilterAction) -> Result<Self> {
+        let regex = Regex::new(pattern)
+            .map_err(|e| LoomError::AgentError(format!("guardrail regex {:?}: {}", pattern, e)))?;
+        Ok(Self {
+            name: "regex".to_string(),
+            regex,
+            action,
+            replacement: "[REDACTED]".to_string(),
+        })
+    }
+
+    pub fn with_name(mut self, name: impl Into<String>) -> Self {
+        self.name = name.into();
+        self
+    }
+
+    /// Text that replaces each match when masking (`$1`-style group references work)
+    pub fn with_replacement(mut self, replacement: impl Into<String>) -> Self {
+        self.replacement = replacement.into();
+        self
+    }
+}
+
+#[async_trait]
+impl Guardrail for RegexFilter {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    async fn inspect(&self, _stage: GuardStage, text: &str) -> Verdict {
+        if !self.regex.is_match(text) {
+            return Verdict::Allow;
+        }
+        match self.action {
+            FilterAction::Block => {
+                Verdict::Block(format!("matches pattern {}", self.regex.as_str()))
+            }
+            FilterAction::Mask => Verdict::Modify(
+                self.regex
+                    .replace_all(text, self.replacement.as_str())
+                    .into_owned(),
+            ),
+        }
+    }
+}
+
+/// Length limit in characters; blocks longer text unless truncating
+pub struct MaxLength {
+    max_chars: usize,
+    truncate: bool,
+}
+
+impl MaxLength {
+    pub fn new(max_chars: usize, truncate: bool) -> Self {
+        Self {
+            max_chars,
+            truncate,
+        }
+    }
+}
+
+#[async_trait]
+impl Guardrail for MaxLength {
+    fn name(&self) -> &str {
+        "max_length"
+    }
+
+    async fn inspect(&self, _stage: GuardStage, text: &str) -> Verdict {
+        let chars = text.chars().count();
+        if chars <= self.max_chars {
+            Verdict::Allow
+        } else if self.truncate {
+            Verdict::Modify(text.chars().take(self.max_chars).collect())
+        } else {
+            Verdict::Block(format!(
+                "{} characters exceeds the limit of {}",
+                chars, self.max_chars
+            ))
+        }
+    }
+}
+
+/// Kinds of personal data `PiiRedactor` recognizes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PiiKind {
+    Email,
+    /// Card numbers of 13-19 digits that pass the Luhn check
+    CreditCard,
+    /// US social security numbers (`123-45-6789`)
+    Ssn,
+    Phone,
+    IpAddress,
+}
+
+impl PiiKind {
+    pub const ALL: [PiiKind; 5] = [
+        PiiKind::Email,
+        PiiKind::CreditCard,
+        PiiKind::Ssn,
+        PiiKind::Phone,
+        PiiKind::IpAddress,
+    ];
+
+    fn placeholder(&self) -> &'static str {
+        match self {
+            PiiKind::Email => "[EMAIL]",
+            PiiKind::CreditCard => "[CREDIT_CARD]",
+            PiiKind::Ssn => "[SSN]",
+            PiiKind::Phone => "[PHONE]",
+            PiiKind::IpAddress => "[IP_ADDRESS]",
+        }
+    }
+
+    fn pattern(&self) -> &'static str {
+        match self {
+            PiiKind::Email => r"\b[A-Za-z0-9._%+-]+@[A-Za-z0-9.-]+\.[A-Za-z]{2,}\b",
+            PiiKind::CreditCard => r"\b\d(?:[ -]?\d){12,18}\b",
+            PiiKind::Ssn => r"\b\d{3}-\d{2}-\d{4}\b",
+            PiiKind::Phone => r"(?:\+\d{1,3}[ .-]?)?(?:\(\d{3}\)|\b\d{3})[ .-]?\d{3}[ .-]?\d{4}\b",
+            PiiKind::IpAddress => {
+                r"\b(?:(?:25[0-5]|2[0-4]\d|1?\d?\d)\.){3}(?:25[0-5]|2[0-4]\d|1?\d?\d)\b"
+            }
+        }
+    }
+}
+
+fn luhn_valid(candidate: &str) -> bool {
+    let digits: Vec<u32> = candidate.chars().filter_map(|c| c.to_digit(10)).collect();
+    let sum: u32 = digits
+        .iter()
+        .rev()
+        .enumerate()
+        .map(|(i, &d)| match (i % 2 == 1, d * 2) {
+            (true, doubled) if doubled > 9 => doubled - 9,
+            (true, doubled) => doubled,
+            (false, _) => d,
+        })
+        .sum();
+    sum % 10 == 0
+}
+
+/// Replaces personal data with placeholders such as `[EMAIL]`
+pub struct PiiRedactor {
+    patterns: Vec<(PiiKind, Regex)>,
+}
+
+impl PiiRedactor {
+    /// Redact the given kinds; card numbers go before phone numbers so that a card is
+    /// never half-redacted as a phone
+    pub fn new(kinds: &[PiiKind]) -> Self {
+        let patterns = PiiKind::ALL
+            .iter()
+            .filter(|kind| kinds.contains(kind))
+            .map(|kind| {
+                (
+                    *kind,
+                    Regex::new(kind.pattern()).expect("builtin PII pattern"),
+                )
+            })
+            .collect();
+        Self { patterns }
+    }
+
+    pub fn all() -> Self {
+        Self::new(&PiiKind::ALL)
+    }
+
+    /// `text` with every recognized item replaced
+    pub fn redact(&self, text: &str) -> String {
+        let mut redacted = text.to_string();
+        for (kind, regex) in &self.patterns {
+            redacted = regex
+                .replace_all(&redacted, |caps: &regex::Captures| {
+                    let found = &caps[0];
+                    if *kind == PiiKind::CreditCard && !luhn_valid(found) {
+                        found.to_string()
+                    } else {
+                        kind.placeholder().to_string()
+                    }
+                })
+                .into_owned();
+        }
+        redacted
+    }
+}
+
+#[async_trait]
+impl Guardrail for PiiRedactor {
+    fn name(&self) -> &str {
+        "pii"
+    }
+
+    async fn inspect(&self, _stage: GuardStage, text: &str) -> Verdict {
+        let redacted = self.redact(text);
+        if redacted == text {
+            Verdict::Allow
+        } else {
+            Verdict::Modify(redacted)
+        }
+    }
+}
+
+/// Blocks everything; stands in for a guardrail configuration that failed to build
+struct DenyAll {
+    reason: String,
+}
+
+#[async_trait]
+impl Guardrail for DenyAll {
+    fn name(&self) -> &str {
+        "invalid_config"
+    }
+
+    async fn inspect(&self, _stage: GuardStage, _text: &str) -> Verdict {
+        Verdict::Block(self.reason.clone())
+    }
+}
+
+/// A builtin guardrail as configured in `CognitiveConfig`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum GuardrailKind {
+    Keywords {
+        words: Vec<String>,
+        #[serde(default)]
+        action: FilterAction,
+    },
+    Regex {
+        pattern: String,
+        #[serde(default)]
+        action: FilterAction,
+        #[serde(default)]
+        replacement: Option<String>,
+    },
+    MaxLength {
+        max_chars: usize,
+        #[serde(default)]
+        truncate: bool,
+    },
+    Pii {
+        #[serde(default = "all_pii_kinds")]
+        kinds: Vec<PiiKind>,
+    },
+}
+
+fn all_pii_kinds() -> Vec<PiiKind> {
+    PiiKind::ALL.to_vec()
+}
+
+impl GuardrailKind {
+    pub fn build(&self) -> Result<Arc<dyn Guardrail>> {
+        Ok(match self {
+            GuardrailKind::Keywords { words, action } => {
+                Arc::new(KeywordFilter::new(words.iter().cloned(), *action))
+            }
+            GuardrailKind::Regex {
+                pattern,
+                action,
+                replacement,
+            } => {
+                let mut filter = RegexFilter::new(pattern, *action)?;
+                if let Some(replacement) = replacement {
+                    filter = filter.with_replacement(replacement.clone());
+                }
+                Arc::new(filter)
+            }
+            GuardrailKind::MaxLength {
+                max_chars,
+                truncate,
+            } => Arc::new(MaxLength::new(*max_chars, *truncate)),
+            GuardrailKind::Pii { kinds } => Arc::new(PiiRedactor::new(kinds)),
+        })
+    }
+}
+
+/// A builtin guardrail and the stages it runs at
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GuardrailRule {
+    #[serde(flatten)]
+    pub kind: GuardrailKind,
+    /// Empty runs the rule at every stage
+    #[serde(default)]
+    pub stages: Vec<GuardStage>,
+}
+
+/// Guardrails of a cognitive agent
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GuardrailConfig {
+    /// Applied in order
+    #[serde(default)]
+    pub rules: Vec<GuardrailRule>,
+    /// Answer given when a request or response is blocked
+    #[serde(default = "default_blocked_response")]
+    pub blocked_response: String,
+}
+
+fn default_blocked_response() -> String {
+    "Sorry, I can't help with that.".to_string()
+}
+
+impl Default for GuardrailConfig {
+    fn default() -> Self {
+        Self {
+            rules: Vec::new(),
+            blocked_response: default_blocked_response(),
+        }
+    }
+}
+
+impl GuardrailConfig {
+    /// Add a rule for `stages` (empty for all)
+    pub fn with_rule(mut self, kind: GuardrailKind, stages: &[GuardStage]) -> Self {
+        self.rules.push(GuardrailRule {
+            kind,
+            stages: stages.to_vec(),
+        });
+        self
+    }
+
+    pub fn with_blocked_response(mut self, response: impl Into<String>) -> Self {
+        self.blocked_response = response.into();
+        self
+    }
+}
+
+/// Ordered guardrails, each bound to the stages it runs at
+pub struct Guardrails {
+    rules: Vec<(Vec<GuardStage>, Arc<dyn Guardrail>)>,
+    triggered_counter: Counter<u64>,
+}
+
+impl Default for Guardrails {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Guardrails {
+    pub fn new() -> Self {
+        let meter = global::meter("loom.cognitive");
+        Self {
+            rules: Vec::new(),
+            triggered_counter: meter
+                .u64_counter("loom.guardrails.triggered_total")
+                .with_description("Guardrail verdicts that modified or blocked text")
+                .init(),
+        }
+    }
+
+    /// Build the configured builtins; fails on an invalid rule (e.g. a bad regex)
+    pub fn from_config(config: &GuardrailConfig) -> Result<Self> {
+        let mut guardrails = Self::new();
+        for rule in &config.rules {
+            guardrails.add(&rule.stages, rule.kind.build()?);
+        }
+        Ok(guardrails)
+    }
+
+    /// Guardrails that block every stage with `reason`, for failing closed
+    pub fn deny_all(reason: impl Into<String>) -> Self {
+        let mut guardrails = Self::new();
+        guardrails.add(
+            &[],
+            Arc::new(DenyAll {
+                reason: reason.into(),
+            }),
+        );
+        guardrails
+    }
+
+    /// Append `guardrail` for `stages` (empty for all)
+    pub fn add(&mut self, stages: &[GuardStage], guardrail: Arc<dyn Guardrail>) {
+        let stages = if stages.is_empty() {
+            GuardStage::ALL.to_vec()
+        } else {
+            stages.to_vec()
+        };
+        self.rules.push((stages, guardrail));
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// Whether any guardrail runs at `stage`
+    pub fn covers(&self, stage: GuardStage) -> bool {
+        self.rules.iter().any(|(stages, _)| stages.contains(&stage))
+    }
+
+    /// Run the guardrails for `stage` over `text`, returning the (possibly rewritten)
+    /// text or the first block
+    pub async fn check(
+        &self,
+        stage: GuardStage,
+        text: &str,
+    ) -> std::result::Result<String, GuardrailViolation> {
+        let mut current = text.to_string();
+        for (stages, guardrail) in &self.rules {
+            if !stages.contains(&stage) {
+                continue;
+            }
+            match guardrail.inspect(stage, &current).await {
+                Verdict::Allow => {}
+                Verdict::Modify(text) => {
+                    self.record(guardrail.name(), stage, "modify");
+                    current = text;
+                }
+                Verdict::Block(reason) => {
+                    self.record(guardrail.name(), stage, "block");
+                    let violation = GuardrailViolation {
+                        guardrail: guardrail.name().to_string(),
+                        stage,
+                        reason,
+                    };
+                    warn!(target = "cognitive.guardrails", %violation, "Guardrail blocked text");
+                    return Err(violation);
+                }
+            }
+        }
+        Ok(current)
+    }
+
+    /// Run the `ToolArguments` guardrails over every string value in `arguments`
+    pub async fn check_arguments(
+        &self,
+        arguments: &Value,
+    ) -> std::result::Result<Value, GuardrailViolation> {
+        if !self.covers(GuardStage::ToolArguments) {
+            return Ok(arguments.clone());
+        }
+        let mut checked = arguments.clone();
+        let mut stack = vec![&mut checked];
+        while let Some(value) = stack.pop() {
+            match value {
+                Value::String(text) => {
+                    *text = self.check(GuardStage::ToolArguments, text).await?;
+                }
+                Value::Array(items) => stack.extend(items.iter_mut()),
+                Value::Object(map) => stack.extend(map.values_mut()),
+                _ => {}
+            }
+        }
+        Ok(checked)
+    }
+
+    fn record(&self, guardrail: &str, stage: GuardStage, verdict: &'static str) {
+        self.triggered_counter.add(
+            1,
+            &[
+                KeyValue::new("guardrail", guardrail.to_string()),
+                KeyValue::new("stage", stage.as_str()),
+                KeyValue::new("verdict", verdict),
+            ],
+        );
+    }
+}
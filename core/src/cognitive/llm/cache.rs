@@ -0,0 +1,415 @@
+//! Response cache for repeated LLM prompts.
+//!
+//! Where coalescing shares one backend call between identical *concurrent* requests,
+//! the cache serves identical requests made *later* (reflection passes, wake-word
+//! confirmations, templated prompts) without another backend call. Entries are keyed
+//! by `prompt_hash`, a SHA-256 over model, temperature, prompt bundle and budget, so
+//! distinct prompts do not collide and persisted keys stay valid across restarts.
+//!
+//! The in-memory LRU is authoritative. With a persistence path, RocksDB mirrors it so a
+//! restarted process starts warm: entries are written through on insert, removed on
+//! eviction or expiry, and reloaded (unexpired ones only) on open.
+
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use opentelemetry::{global, metrics::Counter};
+use rocksdb::{ColumnFamilyDescriptor, IteratorMode, Options, DB};
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+
+use super::client::LlmResponse;
+use crate::{LoomError, Result};
+
+const CF_RESPONSES: &str = "llm_cache";
+
+/// Configuration for `LlmCache`
+#[derive(Debug, Clone)]
+pub struct LlmCacheConfig {
+    /// Maximum number of cached responses; least recently used ones are evicted first
+    pub capacity: usize,
+    /// How long a response may be served after it was generated
+    pub ttl: Duration,
+    /// RocksDB directory to persist entries in; in-memory only when `None`
+    pub path: Option<PathBuf>,
+}
+
+impl Default for LlmCacheConfig {
+    fn default() -> Self {
+        Self {
+            capacity: 1024,
+            ttl: Duration::from_secs(3600),
+            path: None,
+        }
+    }
+}
+
+impl LlmCacheConfig {
+    pub fn with_capacity(mut self, capacity: usize) -> Self {
+        self.capacity = capacity;
+        self
+    }
+
+    pub fn with_ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = ttl;
+        self
+    }
+
+    pub fn with_path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.path = Some(path.into());
+        self
+    }
+
+    /// Read the cache configuration; `None` unless `LOOM_LLM_CACHE` is enabled
+    ///
+    /// - `LOOM_LLM_CACHE`: `1`/`true` turns the cache on
+    /// - `LOOM_LLM_CACHE_CAPACITY`: maximum entries (default 1024)
+    /// - `LOOM_LLM_CACHE_TTL_SECS`: entry lifetime (default 3600)
+    /// - `LOOM_LLM_CACHE_PATH`: RocksDB directory for persistence
+    pub fn from_env() -> Result<Option<Self>> {
+        let enabled = std::env::var("LOOM_LLM_CACHE")
+            .map(|v| matches!(v.trim(), "1" | "true"))
+            .unwrap_or(false);
+        if !enabled {
+            return Ok(None);
+        }
+        let mut config = Self::default();
+        if let Ok(v) = std::env::var("LOOM_LLM_CACHE_CAPACITY") {
+            config.capacity = v.trim().parse().map_err(|_| {
+                LoomError::AgentError(format!("Invalid LOOM_LLM_CACHE_CAPACITY: {v}"))
+            })?;
+        }
+        if let Ok(v) = std::env::var("LOOM_LLM_CACHE_TTL_SECS") {
+            let secs: u64 = v.trim().parse().map_err(|_| {
+                LoomError::AgentError(format!("Invalid LOOM_LLM_CACHE_TTL_SECS: {v}"))
+            })?;
+            config.ttl = Duration::from_secs(secs);
+        }
+        if let Ok(path) = std::env::var("LOOM_LLM_CACHE_PATH") {
+            if !path.trim().is_empty() {
+                config.path = Some(PathBuf::from(path.trim()));
+            }
+        }
+        Ok(Some(config))
+    }
+
+    fn validate(&self) -> Result<()> {
+        if self.capacity == 0 {
+            return Err(LoomError::AgentError(
+                "LLM cache capacity must be at least 1".into(),
+            ));
+        }
+        if self.ttl.is_zero() {
+            return Err(LoomError::AgentError(
+                "LLM cache TTL must be non-zero".into(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Counters for cache effectiveness
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LlmCacheStats {
+    pub hits: u64,
+    pub misses: u64,
+    pub evictions: u64,
+    pub expirations: u64,
+}
+
+/// Persisted form of an entry
+#[derive(Serialize, Deserialize)]
+struct StoredEntry {
+    expires_at_ms: i64,
+    response: LlmResponse,
+}
+
+struct Slot {
+    response: LlmResponse,
+    expires_at_ms: i64,
+    tick: u64,
+}
+
+/// LRU bookkeeping: entries plus their recency order (oldest tick first)
+#[derive(Default)]
+struct Lru {
+    entries: HashMap<String, Slot>,
+    order: BTreeMap<u64, String>,
+    tick: u64,
+}
+
+impl Lru {
+    fn next_tick(&mut self) -> u64 {
+        self.tick += 1;
+        self.tick
+    }
+}
+
+/// LRU cache of LLM responses with TTL and optional RocksDB persistence
+///
+/// Shared through `LlmClient::with_cache`; clones of a client share their cache.
+pub struct LlmCache {
+    config: LlmCacheConfig,
+    lru: Mutex<Lru>,
+    db: Option<DB>,
+    hits: AtomicU64,
+    misses: AtomicU64,
+    evictions: AtomicU64,
+    expirations: AtomicU64,
+    hits_counter: Counter<u64>,
+    misses_counter: Counter<u64>,
+}
+
+impl std::fmt::Debug for LlmCache {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LlmCache")
+            .field("config", &self.config)
+            .field("len", &self.len())
+            .finish()
+    }
+}
+
+impl LlmCache {
+    pub fn new(config: LlmCacheConfig) -> Result<Self> {
+        config.validate()?;
+        let db = match &config.path {
+            Some(path) => Some(open_db(path)?),
+            None => None,
+        };
+        let meter = global::meter("loom.llm");
+        let cache = Self {
+            hits_counter: meter
+                .u64_counter("loom.llm.cache_hits_total")
+                .with_description("Total number of LLM calls served from the response cache")
+                .init(),
+            misses_counter: meter
+                .u64_counter("loom.llm.cache_misses_total")
+                .with_description("Total number of cacheable LLM calls sent to the backend")
+                .init(),
+            config,
+            lru: Mutex::new(Lru::default()),
+            db,
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+            evictions: AtomicU64::new(0),
+            expirations: AtomicU64::new(0),
+        };
+        cache.rehydrate()?;
+        Ok(cache)
+    }
+
+    /// Cached response for `key`, if present and unexpired
+    pub fn get(&self, key: &str) -> Option<LlmResponse> {
+        let now = now_ms();
+        let mut lru = self.lru.lock().unwrap();
+        let Some(expired) = lru.entries.get(key).map(|slot| slot.expires_at_ms <= now) else {
+            drop(lru);
+            self.record_miss();
+            return None;
+        };
+        if expired {
+            if let Some(slot) = lru.entries.remove(key) {
+                lru.order.remove(&slot.tick);
+            }
+            drop(lru);
+            self.expirations.fetch_add(1, Ordering::Relaxed);
+            self.delete_persisted(key);
+            self.record_miss();
+            return None;
+        }
+
+        let tick = lru.next_tick();
+        let slot = lru.entries.get_mut(key).expect("checked above");
+        let old_tick = std::mem::replace(&mut slot.tick, tick);
+        let response = slot.response.clone();
+        lru.order.remove(&old_tick);
+        lru.order.insert(tick, key.to_string());
+        drop(lru);
+
+        self.hits.fetch_add(1, Ordering::Relaxed);
+        self.hits_counter.add(1, &[]);
+        Some(response)
+    }
+
+    /// Store `response` under `key` for the configured TTL
+    pub fn put(&self, key: &str, response: &LlmResponse) {
+        let expires_at_ms = now_ms() + self.config.ttl.as_millis() as i64;
+        if let Some(db) = &self.db {
+            let stored = StoredEntry {
+                expires_at_ms,
+                response: response.clone(),
+            };
+            let written = serde_json::to_vec(&stored)
+                .map_err(|e| e.to_string())
+                .and_then(|bytes| {
+                    let cf = db.cf_handle(CF_RESPONSES).ok_or("missing column family")?;
+                    db.put_cf(cf, key, bytes).map_err(|e| e.to_string())
+                });
+            if let Err(e) = written {
+                warn!(target = "llm_cache", key = %key, error = %e, "Failed to persist cached response");
+            }
+        }
+        self.insert(key.to_string(), response.clone(), expires_at_ms);
+    }
+
+    /// Drop the entry for `key`
+    pub fn invalidate(&self, key: &str) {
+        let mut lru = self.lru.lock().unwrap();
+        if let Some(slot) = lru.entries.remove(key) {
+            lru.order.remove(&slot.tick);
+        }
+        drop(lru);
+        self.delete_persisted(key);
+    }
+
+    /// Drop every entry
+    pub fn clear(&self) {
+        let keys: Vec<String> = {
+            let mut lru = self.lru.lock().unwrap();
+            lru.order.clear();
+            lru.entries.drain().map(|(key, _)| key).collect()
+        };
+        for key in keys {
+            self.delete_persisted(&key);
+        }
+    }
+
+    /// Number of cached responses (expired ones included until they are next looked up)
+    pub fn len(&self) -> usize {
+        self.lru.lock().unwrap().entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn stats(&self) -> LlmCacheStats {
+        LlmCacheStats {
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+            evictions: self.evictions.load(Ordering::Relaxed),
+            expirations: self.expirations.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Flush persisted entries to disk
+    pub fn flush(&self) -> Result<()> {
+        if let Some(db) = &self.db {
+            let cf = cf(db)?;
+            db.flush_cf(cf)
+                .map_err(|e| LoomError::StorageError(e.to_string()))?;
+        }
+        Ok(())
+    }
+
+    fn insert(&self, key: String, response: LlmResponse, expires_at_ms: i64) {
+        let mut evicted = Vec::new();
+        {
+            let mut lru = self.lru.lock().unwrap();
+            let tick = lru.next_tick();
+            if let Some(old) = lru.entries.insert(
+                key.clone(),
+                Slot {
+                    response,
+                    expires_at_ms,
+                    tick,
+                },
+            ) {
+                lru.order.remove(&old.tick);
+            }
+            lru.order.insert(tick, key);
+            while lru.entries.len() > self.config.capacity {
+                let Some((_, oldest)) = lru.order.pop_first() else {
+                    break;
+                };
+                lru.entries.remove(&oldest);
+                evicted.push(oldest);
+            }
+        }
+        self.evictions
+            .fetch_add(evicted.len() as u64, Ordering::Relaxed);
+        for key in evicted {
+            self.delete_persisted(&key);
+        }
+    }
+
+    fn record_miss(&self) {
+        self.misses.fetch_add(1, Ordering::Relaxed);
+        self.misses_counter.add(1, &[]);
+    }
+
+    fn delete_persisted(&self, key: &str) {
+        let Some(db) = &self.db else {
+            return;
+        };
+        let deleted = cf(db).and_then(|cf| {
+            db.delete_cf(cf, key)
+                .map_err(|e| LoomError::StorageError(e.to_string()))
+        });
+        if let Err(e) = deleted {
+            warn!(target = "llm_cache", key = %key, error = %e, "Failed to delete cached response");
+        }
+    }
+
+    /// Load unexpired persisted entries, oldest expiry first so the freshest survive eviction
+    fn rehydrate(&self) -> Result<()> {
+        let Some(db) = &self.db else {
+            return Ok(());
+        };
+        let now = now_ms();
+        let mut live = Vec::new();
+        let mut stale = Vec::new();
+        for entry in db.iterator_cf(cf(db)?, IteratorMode::Start) {
+            let (key, value) = entry.map_err(|e| LoomError::StorageError(e.to_string()))?;
+            let key = String::from_utf8_lossy(&key).into_owned();
+            match serde_json::from_slice::<StoredEntry>(&value) {
+                Ok(stored) if stored.expires_at_ms > now => live.push((key, stored)),
+                Ok(_) => stale.push(key),
+                Err(e) => {
+                    warn!(target = "llm_cache", key = %key, error = %e, "Dropping undecodable cached response");
+                    stale.push(key);
+                }
+            }
+        }
+        for key in stale {
+            self.delete_persisted(&key);
+        }
+        live.sort_by_key(|(_, stored)| stored.expires_at_ms);
+        let loaded = live.len();
+        for (key, stored) in live {
+            self.insert(key, stored.response, stored.expires_at_ms);
+        }
+        // Rehydration is not eviction pressure
+        self.evictions.store(0, Ordering::Relaxed);
+        info!(
+            target = "llm_cache",
+            entries = loaded.min(self.config.capacity),
+            "LLM cache rehydrated"
+        );
+        Ok(())
+    }
+}
+
+fn open_db(path: &Path) -> Result<DB> {
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+    opts.create_missing_column_families(true);
+    let cf_descriptors = vec![ColumnFamilyDescriptor::new(
+        CF_RESPONSES,
+        Options::default(),
+    )];
+    DB::open_cf_descriptors(&opts, path, cf_descriptors)
+        .map_err(|e| LoomError::StorageError(e.to_string()))
+}
+
+fn cf(db: &DB) -> Result<&rocksdb::ColumnFamily> {
+    db.cf_handle(CF_RESPONSES)
+        .ok_or_else(|| LoomError::StorageError(format!("Missing CF: {}", CF_RESPONSES)))
+}
+
+fn now_ms() -> i64 {
+    chrono::Utc::now().timestamp_millis()
+}
@@ -1,12 +1,19 @@
+use crate::cognitive::streaming::ResponseSink;
 use crate::context::{PromptBundle, TokenBudget};
+use crate::pools::DedicatedPool;
+use crate::tenancy::TenantRegistry;
 use crate::{LoomError, Result};
 use reqwest::{Client, StatusCode};
 use serde::{Deserialize, Serialize};
 use serde_json::json;
+use std::sync::Arc;
 use std::time::Duration;
 use tracing::{debug, error, warn};
 
 use super::adapter::promptbundle_to_messages_and_text;
+use super::cache::LlmCache;
+use super::coalesce::{prompt_hash, wait_for, RequestCoalescer, Slot};
+use super::usage::LlmUsage;
 
 /// Configuration for LlmClient loaded from environment variables
 #[derive(Debug, Clone)]
@@ -52,11 +59,57 @@ pub struct LlmResponse {
     pub raw: Option<serde_json::Value>,
 }
 
+/// Per-call options for `LlmClient::generate_with_options`
+#[derive(Debug, Clone, Copy)]
+pub struct GenerateOptions {
+    /// Share the backend response with identical in-flight calls (default: true).
+    /// Disable when each caller needs an independent sample.
+    pub coalesce: bool,
+    /// Serve and store the response in the client's `LlmCache`, if it has one
+    /// (default: true). Disable for prompts whose answer must be fresh.
+    pub cache: bool,
+}
+
+impl Default for GenerateOptions {
+    fn default() -> Self {
+        Self {
+            coalesce: true,
+            cache: true,
+        }
+    }
+}
+
+impl GenerateOptions {
+    /// Options that always perform a dedicated backend call
+    pub fn no_coalesce() -> Self {
+        Self {
+            coalesce: false,
+            cache: false,
+        }
+    }
+
+    /// Options that bypass the response cache but still coalesce
+    pub fn no_cache() -> Self {
+        Self {
+            cache: false,
+            ..Self::default()
+        }
+    }
+}
+
 /// HTTP client that prefers the OpenAI Responses API and falls back to Chat Completions
+///
+/// Clones share the same in-flight table, so identical concurrent calls made through
+/// any clone are coalesced into a single backend request.
 #[derive(Clone)]
 pub struct LlmClient {
     pub(crate) http: Client,
     pub(crate) cfg: LlmClientConfig,
+    coalescer: RequestCoalescer,
+    io_pool: Option<Arc<DedicatedPool>>,
+    tenant: Option<(Arc<TenantRegistry>, String)>,
+    cache: Option<Arc<LlmCache>>,
+    usage: Option<Arc<LlmUsage>>,
 }
 
 impl LlmClient {
@@ -65,25 +118,311 @@ impl LlmClient {
             .timeout(Duration::from_millis(cfg.request_timeout_ms))
             .build()
             .map_err(|e| LoomError::AgentError(format!("Failed to build HTTP client: {e}")))?;
-        Ok(Self { http, cfg })
+        Ok(Self {
+            http,
+            cfg,
+            coalescer: RequestCoalescer::new(),
+            io_pool: None,
+            tenant: None,
+            cache: None,
+            usage: None,
+        })
+    }
+
+    /// Run backend requests on a dedicated IO pool instead of the caller's runtime
+    pub fn with_io_pool(mut self, pool: Arc<DedicatedPool>) -> Self {
+        self.io_pool = Some(pool);
+        self
+    }
+
+    /// Charge backend token usage to `tenant`; calls fail with `QuotaExceeded` once its
+    /// per-minute LLM token quota is used up
+    pub fn with_tenant(mut self, tenants: Arc<TenantRegistry>, tenant: impl Into<String>) -> Self {
+        self.tenant = Some((tenants, tenant.into()));
+        self
+    }
+
+    /// Serve repeated prompts from `cache` instead of the backend
+    pub fn with_cache(mut self, cache: Arc<LlmCache>) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    /// Count backend requests and reported tokens in `usage`
+    pub fn with_usage(mut self, usage: Arc<LlmUsage>) -> Self {
+        self.usage = Some(usage);
+        self
     }
 
     pub fn from_env() -> Result<Self> {
         Self::new(LlmClientConfig::default())
     }
 
+    /// The response cache, if one is attached
+    pub fn cache(&self) -> Option<&Arc<LlmCache>> {
+        self.cache.as_ref()
+    }
+
+    /// The usage counters, if attached
+    pub fn usage(&self) -> Option<&Arc<LlmUsage>> {
+        self.usage.as_ref()
+    }
+
+    /// Cache to consult for a call made with `opts`
+    pub(crate) fn cache_for(&self, opts: GenerateOptions) -> Option<&Arc<LlmCache>> {
+        self.cache.as_ref().filter(|_| opts.cache)
+    }
+
+    /// Number of distinct prompts currently awaiting a backend response
+    pub fn inflight_requests(&self) -> usize {
+        self.coalescer.inflight_len()
+    }
+
     /// Generate a completion for the given prompt bundle
     /// Contract:
     /// - Input: PromptBundle + optional budget
     /// - Output: LlmResponse with assistant text
     /// - Error: network/parse; safe fallbacks are attempted before erroring
+    ///
+    /// Identical concurrent calls are coalesced and repeated ones served from the cache;
+    /// see `generate_with_options` to opt out.
     pub async fn generate(
         &self,
         bundle: &PromptBundle,
         budget: Option<TokenBudget>,
     ) -> Result<LlmResponse> {
-        // Prepare payloads
+        self.generate_with_options(bundle, budget, GenerateOptions::default())
+            .await
+    }
+
+    /// Generate a completion with per-call options
+    ///
+    /// With `coalesce` enabled, calls whose `prompt_hash` matches a request already in
+    /// flight wait for that request and receive a clone of its response (or error).
+    /// With `cache` enabled and a cache attached, a stored response for the same
+    /// `prompt_hash` is returned without a backend call (or quota check), and successful
+    /// responses are stored.
+    pub async fn generate_with_options(
+        &self,
+        bundle: &PromptBundle,
+        budget: Option<TokenBudget>,
+        opts: GenerateOptions,
+    ) -> Result<LlmResponse> {
         let budget = budget.unwrap_or_default();
+        let key = prompt_hash(&self.cfg, bundle, &budget);
+        let cache = self.cache_for(opts);
+        if let Some(resp) = cache.and_then(|c| c.get(&key)) {
+            debug!(target = "llm_client", key = %key, "Serving response from cache");
+            return Ok(resp);
+        }
+        if let Some((ref tenants, ref tenant)) = self.tenant {
+            tenants.check_llm_budget(tenant)?;
+        }
+        if !opts.coalesce {
+            let result = self.dispatch(bundle, budget).await;
+            if let (Some(cache), Ok(resp)) = (cache, &result) {
+                cache.put(&key, resp);
+            }
+            return result;
+        }
+
+        match self.coalescer.join(&key) {
+            Slot::Leader(guard) => {
+                let result = self.dispatch(bundle, budget).await;
+                if let (Some(cache), Ok(resp)) = (cache, &result) {
+                    cache.put(&key, resp);
+                }
+                let shared = match &result {
+                    Ok(resp) => Ok(resp.clone()),
+                    Err(e) => Err(e.to_string()),
+                };
+                guard.complete(shared);
+                result
+            }
+            Slot::Follower(rx) => {
+                debug!(target = "llm_client", key = %key, "Coalescing with in-flight request");
+                match wait_for(rx).await {
+                    Some(Ok(resp)) => Ok(resp),
+                    Some(Err(e)) => Err(LoomError::AgentError(e)),
+                    // Leader was cancelled before completing; issue our own request
+                    None => self.dispatch(bundle, budget).await,
+                }
+            }
+        }
+    }
+
+    /// Generate a completion, sending its text into `sink` as the backend produces it
+    ///
+    /// Streams from Chat Completions (`stream: true`). If the streaming request cannot be
+    /// made, falls back to an uncoalesced `generate` and sends the whole text as one chunk.
+    /// Streaming requests run inline, never on the IO pool.
+    pub async fn generate_stream(
+        &self,
+        bundle: &PromptBundle,
+        budget: Option<TokenBudget>,
+        sink: &ResponseSink,
+    ) -> Result<LlmResponse> {
+        self.generate_stream_with_options(bundle, budget, sink, GenerateOptions::default())
+            .await
+    }
+
+    /// `generate_stream` with per-call options
+    ///
+    /// Streams are never coalesced. With `cache` enabled, a cached response is sent into
+    /// `sink` as one chunk, and a completed stream is stored.
+    pub async fn generate_stream_with_options(
+        &self,
+        bundle: &PromptBundle,
+        budget: Option<TokenBudget>,
+        sink: &ResponseSink,
+        opts: GenerateOptions,
+    ) -> Result<LlmResponse> {
+        let budget = budget.unwrap_or_default();
+        let cache = self.cache_for(opts);
+        let key = prompt_hash(&self.cfg, bundle, &budget);
+        if let Some(resp) = cache.and_then(|c| c.get(&key)) {
+            debug!(target = "llm_client", key = %key, "Serving streamed response from cache");
+            sink.send(&resp.text);
+            return Ok(resp);
+        }
+        let result = self.stream_uncached(bundle, budget, sink).await;
+        if let (Some(cache), Ok(resp)) = (cache, &result) {
+            cache.put(&key, resp);
+        }
+        result
+    }
+
+    async fn stream_uncached(
+        &self,
+        bundle: &PromptBundle,
+        budget: TokenBudget,
+        sink: &ResponseSink,
+    ) -> Result<LlmResponse> {
+        if let Some((ref tenants, ref tenant)) = self.tenant {
+            tenants.check_llm_budget(tenant)?;
+        }
+        let resp = match self.send_stream_request(bundle, budget).await {
+            Ok(resp) => resp,
+            Err(e) => {
+                warn!(target = "llm_client", error = %e, "Streaming request failed; generating without streaming");
+                let resp = self
+                    .generate_with_options(bundle, Some(budget), GenerateOptions::no_coalesce())
+                    .await?;
+                sink.send(&resp.text);
+                return Ok(resp);
+            }
+        };
+
+        // Backends that ignore `stream` answer with a plain completion
+        let result = if is_event_stream(&resp) {
+            read_chat_stream(resp, sink).await
+        } else {
+            let val: serde_json::Value = resp.json().await.map_err(|e| {
+                LoomError::AgentError(format!("Failed to parse Chat Completions JSON: {e}"))
+            })?;
+            let text = extract_text_from_chat_completions(&val).ok_or_else(|| {
+                LoomError::AgentError(
+                    "Missing choices[0].message.content in chat completions".into(),
+                )
+            })?;
+            sink.send(&text);
+            Ok(LlmResponse {
+                text,
+                model: val
+                    .get("model")
+                    .and_then(|v| v.as_str())
+                    .map(|s| s.to_string()),
+                provider: Some("chat.completions".to_string()),
+                usage: val.get("usage").cloned(),
+                raw: Some(val),
+            })
+        };
+        if let (Some((tenants, tenant)), Ok(resp)) = (&self.tenant, &result) {
+            let tokens = resp.usage.as_ref().map(usage_tokens).unwrap_or_default();
+            tenants.record_llm_tokens(tenant, tokens);
+        }
+        if let Some(ref usage) = self.usage {
+            usage.record(&result);
+        }
+        result
+    }
+
+    /// Start a streaming Chat Completions request
+    async fn send_stream_request(
+        &self,
+        bundle: &PromptBundle,
+        budget: TokenBudget,
+    ) -> Result<reqwest::Response> {
+        let (messages, _) = promptbundle_to_messages_and_text(bundle, budget);
+        let chat_url = format!(
+            "{}/chat/completions",
+            self.cfg.base_url.trim_end_matches('/')
+        );
+        debug!(
+            target = "llm_client",
+            "POST {} via Chat Completions (stream)", chat_url
+        );
+
+        let mut req = self
+            .http
+            .post(&chat_url)
+            .header("content-type", "application/json")
+            .header("accept", "text/event-stream");
+        if let Some(key) = &self.cfg.api_key {
+            req = req.bearer_auth(key);
+        }
+        let body = json!({
+            "model": self.cfg.model,
+            "messages": messages,
+            "max_tokens": budget.max_output_tokens as u32,
+            "temperature": self.cfg.temperature,
+            "stream": true,
+            "stream_options": { "include_usage": true },
+        });
+
+        let resp = req
+            .json(&body)
+            .send()
+            .await
+            .map_err(|e| LoomError::AgentError(format!("Chat Completions HTTP error: {e}")))?;
+        if !resp.status().is_success() {
+            let status = resp.status();
+            let text = resp.text().await.unwrap_or_default();
+            return Err(LoomError::AgentError(format!(
+                "Chat Completions error: status={} body={}",
+                status, text
+            )));
+        }
+        Ok(resp)
+    }
+
+    /// Send the request on the IO pool if configured, otherwise inline
+    async fn dispatch(&self, bundle: &PromptBundle, budget: TokenBudget) -> Result<LlmResponse> {
+        let result = match &self.io_pool {
+            None => self.send_generate(bundle, budget).await,
+            Some(pool) => {
+                let client = self.clone();
+                let bundle = bundle.clone();
+                pool.run(async move { client.send_generate(&bundle, budget).await })
+                    .await?
+            }
+        };
+        if let (Some((tenants, tenant)), Ok(resp)) = (&self.tenant, &result) {
+            let tokens = resp.usage.as_ref().map(usage_tokens).unwrap_or_default();
+            tenants.record_llm_tokens(tenant, tokens);
+        }
+        if let Some(ref usage) = self.usage {
+            usage.record(&result);
+        }
+        result
+    }
+
+    async fn send_generate(
+        &self,
+        bundle: &PromptBundle,
+        budget: TokenBudget,
+    ) -> Result<LlmResponse> {
+        // Prepare payloads
         let (messages, input_text) = promptbundle_to_messages_and_text(bundle, budget);
 
         // Try Responses API first
@@ -202,6 +541,79 @@ impl LlmClient {
     }
 }
 
+fn is_event_stream(resp: &reqwest::Response) -> bool {
+    resp.headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.starts_with("text/event-stream"))
+}
+
+/// Read a streaming Chat Completions response, sending each content delta into `sink`
+async fn read_chat_stream(mut resp: reqwest::Response, sink: &ResponseSink) -> Result<LlmResponse> {
+    let mut buffer = String::new();
+    let mut text = String::new();
+    let mut model = None;
+    let mut usage = None;
+    loop {
+        let chunk = resp
+            .chunk()
+            .await
+            .map_err(|e| LoomError::AgentError(format!("Chat Completions stream error: {e}")))?;
+        let Some(chunk) = chunk else {
+            break;
+        };
+        buffer.push_str(&String::from_utf8_lossy(&chunk));
+
+        while let Some(end) = buffer.find('\n') {
+            let line: String = buffer.drain(..end + 1).collect();
+            let Some(data) = line.trim_end().strip_prefix("data:") else {
+                continue;
+            };
+            let data = data.trim_start();
+            if data == "[DONE]" {
+                return Ok(stream_response(text, model, usage));
+            }
+            let Ok(val) = serde_json::from_str::<serde_json::Value>(data) else {
+                warn!(target = "llm_client", data = %data, "Skipping malformed stream chunk");
+                continue;
+            };
+            if let Some(delta) = extract_delta_from_chat_chunk(&val) {
+                text.push_str(delta);
+                sink.send(delta);
+            }
+            if let Some(m) = val.get("model").and_then(|v| v.as_str()) {
+                model = Some(m.to_string());
+            }
+            if let Some(u) = val.get("usage").filter(|u| !u.is_null()) {
+                usage = Some(u.clone());
+            }
+        }
+    }
+    Ok(stream_response(text, model, usage))
+}
+
+fn stream_response(
+    text: String,
+    model: Option<String>,
+    usage: Option<serde_json::Value>,
+) -> LlmResponse {
+    LlmResponse {
+        text,
+        model,
+        provider: Some("chat.completions".to_string()),
+        usage,
+        raw: None,
+    }
+}
+
+fn extract_delta_from_chat_chunk(v: &serde_json::Value) -> Option<&str> {
+    v.get("choices")?
+        .get(0)?
+        .get("delta")?
+        .get("content")?
+        .as_str()
+}
+
 fn extract_text_from_chat_completions(v: &serde_json::Value) -> Option<String> {
     v.get("choices")?
         .get(0)?
@@ -249,3 +661,16 @@ fn extract_text_from_responses(v: &serde_json::Value) -> Option<String> {
     }
     None
 }
+
+/// Total tokens reported by either API's `usage` object
+fn usage_tokens(usage: &serde_json::Value) -> u64 {
+    let field = |name: &str| usage.get(name).and_then(|v| v.as_u64());
+    field("total_tokens").unwrap_or_else(|| {
+        field("input_tokens")
+            .or(field("prompt_tokens"))
+            .unwrap_or(0)
+            + field("output_tokens")
+                .or(field("completion_tokens"))
+                .unwrap_or(0)
+    })
+}
@@ -0,0 +1,175 @@
+//! Request coalescing for identical concurrent LLM calls.
+//!
+//! When several agents send the exact same prompt at the same time (e.g. fanout
+//! patterns), only the first caller (the "leader") hits the backend. Later callers
+//! with the same prompt hash wait for the leader's response and share it.
+
+use std::sync::Arc;
+
+use dashmap::mapref::entry::Entry;
+use dashmap::DashMap;
+use opentelemetry::{global, metrics::Counter};
+use ring::digest;
+use tokio::sync::watch;
+
+use crate::context::{PromptBundle, TokenBudget};
+
+use super::client::{LlmClientConfig, LlmResponse};
+
+/// Shared outcome of a coalesced call. Errors are carried as strings because
+/// `LoomError` is not `Clone`.
+pub(crate) type SharedOutcome = std::result::Result<LlmResponse, String>;
+
+/// Compute the stable hash identifying a prompt for a given backend configuration.
+///
+/// The key covers everything that influences the completion: endpoint, model,
+/// temperature, the full prompt bundle and the token budget. Identical keys are
+/// safe to serve from a single backend response. It is the hex SHA-256 of those
+/// fields (each length-prefixed), so it is collision-resistant and stays the same
+/// across processes and toolchains, as the persisted response cache requires.
+pub fn prompt_hash(cfg: &LlmClientConfig, bundle: &PromptBundle, budget: &TokenBudget) -> String {
+    let mut context = digest::Context::new(&digest::SHA256);
+    let bundle = serde_json::to_string(bundle).unwrap_or_default();
+    for field in [
+        cfg.base_url.trim_end_matches('/').as_bytes(),
+        cfg.model.as_bytes(),
+        bundle.as_bytes(),
+    ] {
+        context.update(&(field.len() as u64).to_le_bytes());
+        context.update(field);
+    }
+    context.update(&cfg.temperature.to_bits().to_le_bytes());
+    context.update(&(budget.max_input_tokens as u64).to_le_bytes());
+    context.update(&(budget.max_output_tokens as u64).to_le_bytes());
+    context
+        .finish()
+        .as_ref()
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect()
+}
+
+/// Role taken by a caller when joining the in-flight table.
+pub(crate) enum Slot {
+    /// First caller for this key; must perform the backend call and publish it.
+    Leader(LeaderGuard),
+    /// Another caller is already in flight; wait on its outcome.
+    Follower(watch::Receiver<Option<SharedOutcome>>),
+}
+
+/// Tracks in-flight requests keyed by prompt hash.
+#[derive(Clone)]
+pub(crate) struct RequestCoalescer {
+    inflight: Arc<DashMap<String, watch::Receiver<Option<SharedOutcome>>>>,
+    coalesced_counter: Counter<u64>,
+}
+
+impl RequestCoalescer {
+    pub(crate) fn new() -> Self {
+        let meter = global::meter("loom.llm");
+        let coalesced_counter = meter
+            .u64_counter("loom.llm.coalesced_requests_total")
+            .with_description("Total number of LLM calls served by an identical in-flight call")
+            .init();
+        Self {
+            inflight: Arc::new(DashMap::new()),
+            coalesced_counter,
+        }
+    }
+
+    /// Join the in-flight table for `key`, becoming either leader or follower.
+    pub(crate) fn join(&self, key: &str) -> Slot {
+        match self.inflight.entry(key.to_string()) {
+            Entry::Occupied(e) => {
+                self.coalesced_counter.add(1, &[]);
+                Slot::Follower(e.get().clone())
+            }
+            Entry::Vacant(e) => {
+                let (tx, rx) = watch::channel(None);
+                e.insert(rx.clone());
+                Slot::Leader(LeaderGuard {
+                    key: key.to_string(),
+                    inflight: Arc::clone(&self.inflight),
+                    tx,
+                    rx,
+                })
+            }
+        }
+    }
+
+    /// Number of distinct prompts currently in flight
+    pub(crate) fn inflight_len(&self) -> usize {
+        self.inflight.len()
+    }
+}
+
+/// Held by the leader for the duration of the backend call.
+///
+/// Dropping the guard (on completion or cancellation) removes the in-flight entry,
+/// so a cancelled leader never leaves followers waiting forever. Only the leader's own
+/// entry is removed: once it completed, a newer leader may hold the same key.
+pub(crate) struct LeaderGuard {
+    key: String,
+    inflight: Arc<DashMap<String, watch::Receiver<Option<SharedOutcome>>>>,
+    tx: watch::Sender<Option<SharedOutcome>>,
+    /// Identifies this leader's entry in `inflight`
+    rx: watch::Receiver<Option<SharedOutcome>>,
+}
+
+impl LeaderGuard {
+    /// Publish the outcome to all followers
+    pub(crate) fn complete(self, outcome: SharedOutcome) {
+        // Remove first so callers arriving after completion start a fresh request
+        self.release();
+        let _ = self.tx.send(Some(outcome));
+    }
+
+    fn release(&self) {
+        self.inflight
+            .remove_if(&self.key, |_, rx| rx.same_channel(&self.rx));
+    }
+}
+
+impl Drop for LeaderGuard {
+    fn drop(&mut self) {
+        self.release();
+    }
+}
+
+/// Wait for the leader's outcome. Returns `None` if the leader was dropped
+/// before publishing (e.g. its task was cancelled).
+pub(crate) async fn wait_for(
+    mut rx: watch::Receiver<Option<SharedOutcome>>,
+) -> Option<SharedOutcome> {
+    loop {
+        if let Some(outcome) = rx.borrow().clone() {
+            return Some(outcome);
+        }
+        if rx.changed().await.is_err() {
+            return rx.borrow().clone();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finished_leader_keeps_its_successors_entry() {
+        let coalescer = RequestCoalescer::new();
+        let Slot::Leader(first) = coalescer.join("k") else {
+            panic!("first caller should lead");
+        };
+        // As in `complete`: the entry is released before the guard is dropped
+        first.release();
+        let Slot::Leader(second) = coalescer.join("k") else {
+            panic!("caller after completion should lead");
+        };
+        drop(first);
+        assert_eq!(coalescer.inflight_len(), 1);
+        assert!(matches!(coalescer.join("k"), Slot::Follower(_)));
+        drop(second);
+        assert_eq!(coalescer.inflight_len(), 0);
+    }
+}
@@ -2,22 +2,35 @@
 //!
 //! This module provides:
 //! - `LlmClientConfig`, `LlmClient`, `LlmResponse` for talking to OpenAI-compatible backends
+//! - `prompt_hash` keying used to coalesce identical in-flight requests
+//! - `LlmUsage` counting backend requests and tokens
+//! - `LlmCache` serving repeated prompts from an LRU with TTL and optional persistence
 //! - `ModelRouter` for intelligent model selection and routing
+//! - `RoutingPolicy` and builtin cost-, latency- and quality-first policies
+//! - `LatencyClass` hints mapped to model tiers with per-class SLO tracking
 //! - `promptbundle_to_messages_and_text` adapter for turning `PromptBundle` into payloads
 //! - `LlmGenerateProvider` capability provider registered as `llm.generate`
 //! - `ToolOrchestrator` for multi-step tool execution
 
 mod adapter;
+mod cache;
 mod client;
+mod coalesce;
+pub mod policy;
 mod provider;
 pub mod router;
+pub mod tiers;
 mod tool_orchestrator;
+mod usage;
 
 pub use adapter::promptbundle_to_messages_and_text;
-pub use client::{LlmClient, LlmClientConfig, LlmResponse};
+pub use cache::{LlmCache, LlmCacheConfig, LlmCacheStats};
+pub use client::{GenerateOptions, LlmClient, LlmClientConfig, LlmResponse};
+pub use coalesce::prompt_hash;
 pub use provider::LlmGenerateProvider;
 pub use tool_orchestrator::{
-    build_action_call, make_refine_bundle, parse_tool_calls_from_chat,
+    build_action_call, describe_tool, make_refine_bundle, parse_tool_calls_from_chat,
     parse_tool_calls_from_responses, FinalAnswer, NormalizedToolCall, OrchestratorOptions,
     ToolChoice, ToolOrchestrator, ToolOrchestratorStats,
 };
+pub use usage::{LlmUsage, LlmUsageStats};
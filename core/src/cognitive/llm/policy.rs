@@ -0,0 +1,378 @@
+// Routing policies for cost- and latency-aware model selection
+//
+// `ModelRouter` builds one `Candidate` per usable model (per-model cost table,
+// rolling latency percentiles and a token estimate for the event) and asks a
+// `RoutingPolicy` to pick one. Builtin policies: `CheapestFirst`, `FastestFirst`
+// and `QualityFirst`, selectable per agent with `routing.policy`.
+
+use std::cmp::Ordering;
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use super::router::RoutingConstraints;
+use crate::proto::Event;
+use crate::{LoomError, Result};
+
+/// Output tokens assumed when the event does not set `max_tokens`
+pub const DEFAULT_OUTPUT_TOKENS: u32 = 256;
+
+/// Latency samples kept per model
+pub const DEFAULT_LATENCY_WINDOW: usize = 256;
+
+/// Samples needed before observed percentiles replace a model's typical latency
+pub const MIN_LATENCY_SAMPLES: usize = 5;
+
+// ============================================================================
+// Cost table
+// ============================================================================
+
+/// Pricing, quality and latency prior for one model
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ModelProfile {
+    /// USD per 1K input tokens
+    #[serde(default)]
+    pub input_cost_per_1k: f32,
+    /// USD per 1K output tokens
+    #[serde(default)]
+    pub output_cost_per_1k: f32,
+    /// Expected answer quality, 0.0-1.0
+    #[serde(default = "default_quality")]
+    pub quality: f32,
+    /// Latency assumed until enough samples are observed
+    #[serde(default = "default_typical_latency_ms")]
+    pub typical_latency_ms: u64,
+    /// Input plus output tokens the model accepts; unset: the `TokenizerRegistry` entry
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub context_window: Option<usize>,
+}
+
+fn default_quality() -> f32 {
+    0.5
+}
+
+fn default_typical_latency_ms() -> u64 {
+    500
+}
+
+impl ModelProfile {
+    pub fn new(input_cost_per_1k: f32, output_cost_per_1k: f32, quality: f32) -> Self {
+        Self {
+            input_cost_per_1k,
+            output_cost_per_1k,
+            quality,
+            typical_latency_ms: default_typical_latency_ms(),
+            context_window: None,
+        }
+    }
+
+    pub fn with_typical_latency_ms(mut self, ms: u64) -> Self {
+        self.typical_latency_ms = ms;
+        self
+    }
+
+    pub fn with_context_window(mut self, tokens: usize) -> Self {
+        self.context_window = Some(tokens);
+        self
+    }
+
+    /// Cost of one call with the given token counts
+    pub fn cost(&self, input_tokens: u32, output_tokens: u32) -> f32 {
+        input_tokens as f32 / 1000.0 * self.input_cost_per_1k
+            + output_tokens as f32 / 1000.0 * self.output_cost_per_1k
+    }
+}
+
+/// Per-model cost table
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ModelCostTable {
+    models: HashMap<String, ModelProfile>,
+}
+
+impl ModelCostTable {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Profiles for the router's default local models and cloud endpoints
+    pub fn builtin() -> Self {
+        let mut table = Self::new();
+        for local in ["face_detector", "emotion_classifier"] {
+            table.insert(
+                local,
+                ModelProfile::new(0.0, 0.0, 0.7).with_typical_latency_ms(50),
+            );
+        }
+        table.insert(
+            "lightweight_llm",
+            ModelProfile::new(0.0, 0.0, 0.7)
+                .with_typical_latency_ms(50)
+                .with_context_window(4_096),
+        );
+        table.insert(
+            "gpt-4",
+            ModelProfile::new(0.03, 0.06, 0.95).with_typical_latency_ms(800),
+        );
+        table.insert(
+            "claude-3",
+            ModelProfile::new(0.015, 0.075, 0.93)
+                .with_typical_latency_ms(700)
+                .with_context_window(200_000),
+        );
+        table
+    }
+
+    /// Parse a JSON object of `{"model": {"input_cost_per_1k": ..., ...}}`
+    pub fn from_json(json: &str) -> Result<Self> {
+        let models: HashMap<String, ModelProfile> = serde_json::from_str(json)?;
+        for (name, profile) in &models {
+            if !(0.0..=1.0).contains(&profile.quality) {
+                return Err(LoomError::RouterError(format!(
+                    "Model {} has quality {} outside 0.0-1.0",
+                    name, profile.quality
+                )));
+            }
+        }
+        Ok(Self { models })
+    }
+
+    /// Builtin profiles overlaid with `LOOM_MODEL_COSTS` (JSON) when set
+    pub fn from_env() -> Result<Self> {
+        let mut table = Self::builtin();
+        if let Ok(json) = std::env::var("LOOM_MODEL_COSTS") {
+            if !json.trim().is_empty() {
+                table.merge(Self::from_json(&json)?);
+            }
+        }
+        Ok(table)
+    }
+
+    pub fn insert(&mut self, model: impl Into<String>, profile: ModelProfile) {
+        self.models.insert(model.into(), profile);
+    }
+
+    pub fn get(&self, model: &str) -> Option<&ModelProfile> {
+        self.models.get(model)
+    }
+
+    /// Add or replace entries from `other`
+    pub fn merge(&mut self, other: ModelCostTable) {
+        self.models.extend(other.models);
+    }
+}
+
+// ============================================================================
+// Latency tracking
+// ============================================================================
+
+/// Rolling window of observed latencies per model
+#[derive(Debug)]
+pub struct LatencyTracker {
+    window: usize,
+    samples: Mutex<HashMap<String, VecDeque<u64>>>,
+}
+
+impl Default for LatencyTracker {
+    fn default() -> Self {
+        Self::new(DEFAULT_LATENCY_WINDOW)
+    }
+}
+
+impl LatencyTracker {
+    pub fn new(window: usize) -> Self {
+        Self {
+            window: window.max(1),
+            samples: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Record one observed call latency
+    pub fn record(&self, model: &str, latency: Duration) {
+        let mut samples = self.samples.lock().unwrap();
+        let window = samples.entry(model.to_string()).or_default();
+        if window.len() == self.window {
+            window.pop_front();
+        }
+        window.push_back(latency.as_millis() as u64);
+    }
+
+    /// Number of samples currently held for `model`
+    pub fn sample_count(&self, model: &str) -> usize {
+        self.samples
+            .lock()
+            .unwrap()
+            .get(model)
+            .map_or(0, VecDeque::len)
+    }
+
+    /// Models with at least one recorded sample
+    pub fn models(&self) -> Vec<String> {
+        let mut models: Vec<String> = self.samples.lock().unwrap().keys().cloned().collect();
+        models.sort();
+        models
+    }
+
+    /// Nearest-rank percentile (`p` in 0-100) of the recorded latencies, in ms
+    pub fn percentile(&self, model: &str, p: f64) -> Option<u64> {
+        let samples = self.samples.lock().unwrap();
+        let window = samples.get(model).filter(|w| !w.is_empty())?;
+        let mut sorted: Vec<u64> = window.iter().copied().collect();
+        sorted.sort_unstable();
+        let rank = ((p.clamp(0.0, 100.0) / 100.0) * sorted.len() as f64).ceil() as usize;
+        Some(sorted[rank.saturating_sub(1).min(sorted.len() - 1)])
+    }
+
+    pub fn p50(&self, model: &str) -> Option<u64> {
+        self.percentile(model, 50.0)
+    }
+
+    pub fn p95(&self, model: &str) -> Option<u64> {
+        self.percentile(model, 95.0)
+    }
+}
+
+// ============================================================================
+// Token estimates
+// ============================================================================
+
+/// Estimate (input, output) tokens for routing an event
+///
+/// Input is roughly four bytes per token over payload and metadata values; set
+/// `estimated_input_tokens` in metadata to override it. Output comes from
+/// `max_tokens` when present.
+pub fn estimate_tokens(event: &Event) -> (u32, u32) {
+    let parse = |key: &str| event.metadata.get(key).and_then(|v| v.parse::<u32>().ok());
+
+    let input = parse("estimated_input_tokens").unwrap_or_else(|| {
+        let bytes = event.payload.len() + event.metadata.values().map(String::len).sum::<usize>();
+        (bytes as u32).div_ceil(4).max(1)
+    });
+    let output = parse("max_tokens").unwrap_or(DEFAULT_OUTPUT_TOKENS);
+    (input, output)
+}
+
+// ============================================================================
+// Policies
+// ============================================================================
+
+/// A model the router could send an event to
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Candidate {
+    pub model: String,
+    /// Runs on a local model (otherwise a cloud endpoint)
+    pub local: bool,
+    pub estimated_cost: f32,
+    /// Observed p95 latency, or the model's typical latency before enough samples
+    pub latency_ms: u64,
+    /// Local: estimator confidence; cloud: quality from the cost table
+    pub quality: f32,
+}
+
+impl Candidate {
+    /// Whether the candidate satisfies every limit in `constraints`
+    pub fn within(&self, constraints: &RoutingConstraints) -> bool {
+        self.estimated_cost <= constraints.cost_cap_per_event
+            && self.latency_ms <= constraints.latency_budget_ms
+            && self.quality >= constraints.quality_threshold
+    }
+}
+
+/// Chooses among routing candidates
+///
+/// The router passes only candidates within the agent's constraints when any are,
+/// and all candidates otherwise.
+pub trait RoutingPolicy: Send + Sync {
+    /// Identifier used in `routing.policy` and decision reasons
+    fn name(&self) -> &'static str;
+
+    /// Index of the chosen candidate, or `None` to defer
+    fn select(&self, candidates: &[Candidate], constraints: &RoutingConstraints) -> Option<usize>;
+}
+
+fn pick_min(
+    candidates: &[Candidate],
+    cmp: impl Fn(&Candidate, &Candidate) -> Ordering,
+) -> Option<usize> {
+    candidates
+        .iter()
+        .enumerate()
+        .min_by(|(_, a), (_, b)| cmp(a, b))
+        .map(|(index, _)| index)
+}
+
+fn by_cost(a: &Candidate, b: &Candidate) -> Ordering {
+    a.estimated_cost.total_cmp(&b.estimated_cost)
+}
+
+fn by_latency(a: &Candidate, b: &Candidate) -> Ordering {
+    a.latency_ms.cmp(&b.latency_ms)
+}
+
+fn by_quality_desc(a: &Candidate, b: &Candidate) -> Ordering {
+    b.quality.total_cmp(&a.quality)
+}
+
+/// Lowest estimated cost; ties go to the faster model
+#[derive(Debug, Default, Clone, Copy)]
+pub struct CheapestFirst;
+
+impl RoutingPolicy for CheapestFirst {
+    fn name(&self) -> &'static str {
+        "cheapest"
+    }
+
+    fn select(&self, candidates: &[Candidate], _constraints: &RoutingConstraints) -> Option<usize> {
+        pick_min(candidates, |a, b| {
+            by_cost(a, b).then_with(|| by_latency(a, b))
+        })
+    }
+}
+
+/// Lowest p95 latency; ties go to the cheaper model
+#[derive(Debug, Default, Clone, Copy)]
+pub struct FastestFirst;
+
+impl RoutingPolicy for FastestFirst {
+    fn name(&self) -> &'static str {
+        "fastest"
+    }
+
+    fn select(&self, candidates: &[Candidate], _constraints: &RoutingConstraints) -> Option<usize> {
+        pick_min(candidates, |a, b| {
+            by_latency(a, b).then_with(|| by_cost(a, b))
+        })
+    }
+}
+
+/// Highest quality; ties go to the cheaper model
+#[derive(Debug, Default, Clone, Copy)]
+pub struct QualityFirst;
+
+impl RoutingPolicy for QualityFirst {
+    fn name(&self) -> &'static str {
+        "quality"
+    }
+
+    fn select(&self, candidates: &[Candidate], _constraints: &RoutingConstraints) -> Option<usize> {
+        pick_min(candidates, |a, b| {
+            by_quality_desc(a, b).then_with(|| by_cost(a, b))
+        })
+    }
+}
+
+/// Builtin policy for a `routing.policy` value (`cheapest`, `fastest`, `quality`)
+pub fn policy_from_name(name: &str) -> Option<Arc<dyn RoutingPolicy>> {
+    match name
+        .trim()
+        .to_ascii_lowercase()
+        .replace(['-', '_'], "")
+        .as_str()
+    {
+        "cheapest" | "cheapestfirst" => Some(Arc::new(CheapestFirst)),
+        "fastest" | "fastestfirst" => Some(Arc::new(FastestFirst)),
+        "quality" | "qualityfirst" => Some(Arc::new(QualityFirst)),
+        _ => None,
+    }
+}
@@ -6,7 +6,7 @@ use serde::Deserialize;
 use serde_json::{json, Value};
 use std::sync::Arc;
 
-use super::client::LlmClient;
+use super::client::{GenerateOptions, LlmClient};
 
 /// Native Action provider that wraps LlmClient
 pub struct LlmGenerateProvider {
@@ -30,6 +30,14 @@ struct GeneratePayload {
     input: String,
     bundle: Option<PromptBundle>,
     budget: Option<TokenBudget>,
+    #[serde(default = "default_true")]
+    coalesce: bool,
+    #[serde(default = "default_true")]
+    cache: bool,
+}
+
+fn default_true() -> bool {
+    true
 }
 
 #[async_trait]
@@ -57,6 +65,14 @@ impl Tool for LlmGenerateProvider {
                 "budget": {
                     "type": "object",
                     "description": "Token budget (optional)"
+                },
+                "coalesce": {
+                    "type": "boolean",
+                    "description": "Share the response with identical in-flight calls (default: true)"
+                },
+                "cache": {
+                    "type": "boolean",
+                    "description": "Serve repeated prompts from the response cache (default: true)"
                 }
             },
             "required": ["input"]
@@ -82,7 +98,14 @@ impl Tool for LlmGenerateProvider {
 
         let res = self
             .client
-            .generate(&bundle, payload.budget)
+            .generate_with_options(
+                &bundle,
+                payload.budget,
+                GenerateOptions {
+                    coalesce: payload.coalesce,
+                    cache: payload.cache,
+                },
+            )
             .await
             .map_err(|e| ToolError::ExecutionFailed(format!("LLM generation failed: {}", e)))?;
 
@@ -1,14 +1,24 @@
 // Model Router implementation
 //
-// The Router makes Local/Cloud/Hybrid routing decisions based on policy
-// (privacy, latency, cost, quality) and confidence estimates.
+// The Router makes Local/Cloud/Hybrid routing decisions based on constraints
+// (privacy, latency, cost, quality) and confidence estimates. With a
+// `RoutingPolicy` set, it instead ranks candidate models by cost table, observed
+// latency and token estimates (see `policy.rs`). Events with a latency class are
+// served from that class's model tier (see `tiers.rs`). `context_budget` turns a
+// decision into the chosen model's `ModelBudget` for context assembly.
 
 use std::sync::Arc;
+use std::time::Duration;
 
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 use tracing::{debug, info, Span};
 
+use super::policy::{
+    estimate_tokens, Candidate, LatencyTracker, ModelCostTable, RoutingPolicy, MIN_LATENCY_SAMPLES,
+};
+use super::tiers::{LatencyClass, ModelTier, SloStats, SloTracker, TierTable};
+use crate::context::{ModelBudget, TokenBudget, TokenizerRegistry};
 use crate::{proto::Event, Result};
 
 // OpenTelemetry imports
@@ -100,11 +110,18 @@ pub struct RoutingDecision {
     pub reason: String,
     pub estimated_latency_ms: u64,
     pub estimated_cost: f32,
+    /// Model serving the route; look up its tokenizer and context window in a
+    /// `TokenizerRegistry` to budget the prompt
+    #[serde(default)]
+    pub model: Option<String>,
+    /// Latency class the decision was made for, if the event had one
+    #[serde(default)]
+    pub latency_class: Option<LatencyClass>,
 }
 
-/// Routing policy
+/// Routing constraints
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct RoutingPolicy {
+pub struct RoutingConstraints {
     pub privacy_level: PrivacyLevel,
     pub latency_budget_ms: u64,
     pub cost_cap_per_event: f32,
@@ -122,10 +139,18 @@ pub enum PrivacyLevel {
 /// Model Router core
 #[derive(Clone)]
 pub struct ModelRouter {
-    policy: RoutingPolicy,
+    constraints: RoutingConstraints,
     local_models: Vec<String>,
     cloud_endpoints: Vec<String>,
     confidence_estimator: Arc<dyn ConfidenceEstimator>,
+    routing_policy: Option<Arc<dyn RoutingPolicy>>,
+    cost_table: Arc<ModelCostTable>,
+    // Shared by clones so per-agent routers see the same observations
+    latency: Arc<LatencyTracker>,
+    tiers: Arc<TierTable>,
+    default_latency_class: Option<LatencyClass>,
+    slo: Arc<SloTracker>,
+    tokenizers: Arc<TokenizerRegistry>,
 
     // OpenTelemetry metrics
     decisions_counter: Counter<u64>,
@@ -166,7 +191,7 @@ impl ModelRouter {
             .init();
 
         Ok(Self {
-            policy: RoutingPolicy {
+            constraints: RoutingConstraints {
                 privacy_level: PrivacyLevel::Sensitive,
                 latency_budget_ms: 200,
                 cost_cap_per_event: 0.01,
@@ -179,6 +204,13 @@ impl ModelRouter {
             ],
             cloud_endpoints: vec!["gpt-4".to_string(), "claude-3".to_string()],
             confidence_estimator: Arc::new(DummyConfidenceEstimator),
+            routing_policy: None,
+            cost_table: Arc::new(ModelCostTable::from_env()?),
+            latency: Arc::new(LatencyTracker::default()),
+            tiers: Arc::new(TierTable::from_env()?),
+            default_latency_class: None,
+            slo: Arc::new(SloTracker::new()),
+            tokenizers: Arc::new(TokenizerRegistry::with_defaults()),
             decisions_counter,
             confidence_histogram,
             estimated_latency_histogram,
@@ -193,6 +225,37 @@ impl ModelRouter {
         self
     }
 
+    /// Select among candidate models with `policy` instead of the rule-based flow
+    pub fn with_routing_policy(mut self, policy: Arc<dyn RoutingPolicy>) -> Self {
+        self.routing_policy = Some(policy);
+        self
+    }
+
+    /// Replace the per-model cost table (defaults to `ModelCostTable::from_env`)
+    pub fn with_cost_table(mut self, table: ModelCostTable) -> Self {
+        self.cost_table = Arc::new(table);
+        self
+    }
+
+    /// Replace the model tiers (defaults to `TierTable::from_env`)
+    pub fn with_tiers(mut self, tiers: TierTable) -> Self {
+        self.tiers = Arc::new(tiers);
+        self
+    }
+
+    /// Replace the tokenizer registry consulted for context windows the cost table
+    /// does not set (defaults to `TokenizerRegistry::with_defaults`)
+    pub fn with_tokenizers(mut self, tokenizers: Arc<TokenizerRegistry>) -> Self {
+        self.tokenizers = tokenizers;
+        self
+    }
+
+    /// Latency class for events without a `latency_class` hint
+    pub fn with_latency_class(mut self, class: LatencyClass) -> Self {
+        self.default_latency_class = Some(class);
+        self
+    }
+
     pub async fn start(&mut self) -> Result<()> {
         info!("Model Router started");
         Ok(())
@@ -203,7 +266,7 @@ impl ModelRouter {
         Ok(())
     }
 
-    /// Route event based on policy
+    /// Route event based on constraints and the routing policy, if any
     #[tracing::instrument(skip(self, event, _context), fields(event_id = %event.id, event_type = %event.r#type, route, confidence, reason))]
     pub async fn route(
         &self,
@@ -232,38 +295,60 @@ impl ModelRouter {
                 reason: "Privacy policy requires local-only processing".to_string(),
                 estimated_latency_ms: 50,
                 estimated_cost: 0.0,
+                model: self.model_for(&Route::Local, &event.r#type),
+                latency_class: None,
             };
             self.record_decision(&decision, &event.r#type);
             return Ok(decision);
         }
 
-        // 2. Check if local model supports event type
+        // 2. Serve hinted latency classes from their model tier
+        let latency_class = LatencyClass::from_event(event).or(self.default_latency_class);
+        if let Some((class, tier)) =
+            latency_class.and_then(|class| self.tiers.get(class).map(|tier| (class, tier)))
+        {
+            let decision = self.route_with_tier(class, tier, event);
+            self.record_decision(&decision, &event.r#type);
+            return Ok(decision);
+        }
+
+        // 3. Check if local model supports event type
         let local_model_available = self.has_local_model_for(&event.r#type);
         let estimator_supports = self.confidence_estimator.supports_event_type(&event.r#type);
         let local_supported = local_model_available && estimator_supports;
 
-        // 3. Estimate local confidence
+        // 4. Estimate local confidence
         let local_confidence = if local_supported {
             self.confidence_estimator.estimate_confidence(event).await?
         } else {
             0.0
         };
 
-        // 4. Make routing decision based on rules
-        if local_supported && local_confidence >= self.policy.quality_threshold {
+        // 5. Let the routing policy rank candidates when one is set
+        if let Some(policy) = &self.routing_policy {
+            let local_confidence = local_supported.then_some(local_confidence);
+            let decision = self.route_with_policy(policy.as_ref(), event, local_confidence);
+            self.record_decision(&decision, &event.r#type);
+            return Ok(decision);
+        }
+
+        // 6. Otherwise make routing decision based on rules
+        if local_supported && local_confidence >= self.constraints.quality_threshold {
             let decision = RoutingDecision {
                 route: Route::Local,
                 confidence: local_confidence,
                 reason: "Local model confidence exceeds threshold".to_string(),
                 estimated_latency_ms: 50,
                 estimated_cost: 0.0,
+                model: self.model_for(&Route::Local, &event.r#type),
+                latency_class: None,
             };
             self.record_decision(&decision, &event.r#type);
             return Ok(decision);
         }
 
-        // 5. Check latency budget
-        if self.policy.latency_budget_ms < 100 {
+        // 7. Check latency budget
+        if self.constraints.latency_budget_ms < 100 {
             self.policy_violations_counter
                 .add(1, &[KeyValue::new("violation_type", "latency_budget")]);
             let decision = RoutingDecision {
@@ -272,14 +357,16 @@ impl ModelRouter {
                 reason: "Latency budget too tight for cloud".to_string(),
                 estimated_latency_ms: 50,
                 estimated_cost: 0.0,
+                model: self.model_for(&Route::Local, &event.r#type),
+                latency_class: None,
             };
             self.record_decision(&decision, &event.r#type);
             return Ok(decision);
         }
 
-        // 6. Check cost limit
+        // 8. Check cost limit
         let cloud_cost = self.estimate_cloud_cost(event);
-        if cloud_cost > self.policy.cost_cap_per_event {
+        if cloud_cost > self.constraints.cost_cap_per_event {
             self.policy_violations_counter
                 .add(1, &[KeyValue::new("violation_type", "cost_cap")]);
             let decision = RoutingDecision {
@@ -288,12 +375,14 @@ impl ModelRouter {
                 reason: "Cloud cost exceeds budget".to_string(),
                 estimated_latency_ms: 50,
                 estimated_cost: 0.0,
+                model: self.model_for(&Route::LocalFallback, &event.r#type),
+                latency_class: None,
             };
             self.record_decision(&decision, &event.r#type);
             return Ok(decision);
         }
 
-        // 7. Hybrid strategy: local quick + cloud refine
+        // 9. Hybrid strategy: local quick + cloud refine
         if local_supported && local_confidence > 0.5 {
             let decision = RoutingDecision {
                 route: Route::Hybrid,
@@ -301,12 +390,14 @@ impl ModelRouter {
                 reason: "Hybrid: local quick + cloud refine".to_string(),
                 estimated_latency_ms: 300,
                 estimated_cost: cloud_cost,
+                model: self.model_for(&Route::Hybrid, &event.r#type),
+                latency_class: None,
             };
             self.record_decision(&decision, &event.r#type);
             return Ok(decision);
         }
 
-        // 8. Default to cloud if available, otherwise defer
+        // 10. Default to cloud if available, otherwise defer
         if self.has_cloud_endpoint() {
             let decision = RoutingDecision {
                 route: Route::Cloud,
@@ -314,6 +405,8 @@ impl ModelRouter {
                 reason: "Default to cloud for quality".to_string(),
                 estimated_latency_ms: 500,
                 estimated_cost: cloud_cost,
+                model: self.model_for(&Route::Cloud, &event.r#type),
+                latency_class: None,
             };
             self.record_decision(&decision, &event.r#type);
             Ok(decision)
@@ -325,12 +418,205 @@ impl ModelRouter {
                 reason: "No cloud endpoints available".to_string(),
                 estimated_latency_ms: 0,
                 estimated_cost: 0.0,
+                model: self.model_for(&Route::Defer, &event.r#type),
+                latency_class: None,
             };
             self.record_decision(&decision, &event.r#type);
             Ok(decision)
         }
     }
 
+    /// Pick a model with `policy`, preferring candidates within the constraints
+    fn route_with_policy(
+        &self,
+        policy: &dyn RoutingPolicy,
+        event: &Event,
+        local_confidence: Option<f32>,
+    ) -> RoutingDecision {
+        let candidates = self.candidates(event, local_confidence);
+        let eligible: Vec<Candidate> = candidates
+            .iter()
+            .filter(|c| c.within(&self.constraints))
+            .cloned()
+            .collect();
+
+        let relaxed = eligible.is_empty() && !candidates.is_empty();
+        let pool = if relaxed { &candidates } else { &eligible };
+        if relaxed {
+            self.policy_violations_counter.add(
+                1,
+                &[
+                    KeyValue::new("violation_type", "constraints"),
+                    KeyValue::new("policy", policy.name()),
+                ],
+            );
+        }
+
+        match policy
+            .select(pool, &self.constraints)
+            .and_then(|index| pool.get(index))
+        {
+            Some(chosen) => RoutingDecision {
+                route: if chosen.local {
+                    Route::Local
+                } else {
+                    Route::Cloud
+                },
+                confidence: chosen.quality,
+                reason: if relaxed {
+                    format!(
+                        "Policy {} chose {}; no model within constraints",
+                        policy.name(),
+                        chosen.model
+                    )
+                } else {
+                    format!("Policy {} chose {}", policy.name(), chosen.model)
+                },
+                estimated_latency_ms: chosen.latency_ms,
+                estimated_cost: chosen.estimated_cost,
+                model: Some(chosen.model.clone()),
+                latency_class: None,
+            },
+            None => RoutingDecision {
+                route: Route::Defer,
+                confidence: 0.0,
+                reason: format!("Policy {} found no candidate model", policy.name()),
+                estimated_latency_ms: 0,
+                estimated_cost: 0.0,
+                model: None,
+                latency_class: None,
+            },
+        }
+    }
+
+    /// Pick a model from `tier`: with a routing policy, its choice among the tier's
+    /// models; otherwise the first model expected to meet the SLO, else the fastest
+    fn route_with_tier(
+        &self,
+        class: LatencyClass,
+        tier: &ModelTier,
+        event: &Event,
+    ) -> RoutingDecision {
+        let (input_tokens, output_tokens) = estimate_tokens(event);
+        let candidates: Vec<Candidate> = tier
+            .models
+            .iter()
+            .map(|model| {
+                let local = self.local_models.contains(model);
+                let profile = self.cost_table.get(model);
+                Candidate {
+                    model: model.clone(),
+                    local,
+                    estimated_cost: profile.map_or(0.0, |p| p.cost(input_tokens, output_tokens)),
+                    latency_ms: self.expected_latency_ms(
+                        model,
+                        profile.map_or(if local { 50 } else { 500 }, |p| p.typical_latency_ms),
+                    ),
+                    quality: profile.map_or(0.5, |p| p.quality),
+                }
+            })
+            .collect();
+
+        let within_slo: Vec<usize> = (0..candidates.len())
+            .filter(|&i| candidates[i].latency_ms <= tier.slo_ms)
+            .collect();
+        let chosen = match &self.routing_policy {
+            Some(policy) => {
+                let pool: Vec<Candidate> = if within_slo.is_empty() {
+                    candidates.clone()
+                } else {
+                    within_slo.iter().map(|&i| candidates[i].clone()).collect()
+                };
+                policy
+                    .select(&pool, &self.constraints)
+                    .and_then(|index| pool.get(index))
+                    .and_then(|c| candidates.iter().position(|o| o.model == c.model))
+            }
+            None => within_slo
+                .first()
+                .copied()
+                .or_else(|| (0..candidates.len()).min_by_key(|&i| candidates[i].latency_ms)),
+        };
+
+        match chosen.map(|i| &candidates[i]) {
+            Some(chosen) => RoutingDecision {
+                route: if chosen.local {
+                    Route::Local
+                } else {
+                    Route::Cloud
+                },
+                confidence: chosen.quality,
+                reason: if chosen.latency_ms <= tier.slo_ms {
+                    format!("Latency class {} served by {}", class, chosen.model)
+                } else {
+                    format!(
+                        "Latency class {} served by {}; no model expected within {}ms",
+                        class, chosen.model, tier.slo_ms
+                    )
+                },
+                estimated_latency_ms: chosen.latency_ms,
+                estimated_cost: chosen.estimated_cost,
+                model: Some(chosen.model.clone()),
+                latency_class: Some(class),
+            },
+            None => RoutingDecision {
+                route: Route::Defer,
+                confidence: 0.0,
+                reason: format!("Latency class {} has no model", class),
+                estimated_latency_ms: 0,
+                estimated_cost: 0.0,
+                model: None,
+                latency_class: Some(class),
+            },
+        }
+    }
+
+    /// Models that could serve `event`: the local model when the estimator
+    /// supports it, then every cloud endpoint
+    fn candidates(&self, event: &Event, local_confidence: Option<f32>) -> Vec<Candidate> {
+        let (input_tokens, output_tokens) = estimate_tokens(event);
+        let mut candidates = Vec::new();
+
+        if let (Some(confidence), Some(model)) =
+            (local_confidence, self.local_model_for(&event.r#type))
+        {
+            let profile = self.cost_table.get(model);
+            candidates.push(Candidate {
+                model: model.to_string(),
+                local: true,
+                estimated_cost: profile.map_or(0.0, |p| p.cost(input_tokens, output_tokens)),
+                latency_ms: self
+                    .expected_latency_ms(model, profile.map_or(50, |p| p.typical_latency_ms)),
+                quality: confidence,
+            });
+        }
+
+        for endpoint in &self.cloud_endpoints {
+            let profile = self.cost_table.get(endpoint);
+            candidates.push(Candidate {
+                model: endpoint.clone(),
+                local: false,
+                estimated_cost: profile.map_or_else(
+                    || self.estimate_cloud_cost(event),
+                    |p| p.cost(input_tokens, output_tokens),
+                ),
+                latency_ms: self
+                    .expected_latency_ms(endpoint, profile.map_or(500, |p| p.typical_latency_ms)),
+                quality: profile.map_or(0.5, |p| p.quality),
+            });
+        }
+        candidates
+    }
+
+    /// Observed p95 latency once enough samples exist, otherwise `typical_ms`
+    fn expected_latency_ms(&self, model: &str, typical_ms: u64) -> u64 {
+        if self.latency.sample_count(model) >= MIN_LATENCY_SAMPLES {
+            self.latency.p95(model).unwrap_or(typical_ms)
+        } else {
+            typical_ms
+        }
+    }
+
     // Helper method to record routing decision metrics and span attributes
     fn record_decision(&self, decision: &RoutingDecision, event_type: &str) {
         let route_str = format!("{:?}", decision.route);
@@ -389,16 +675,93 @@ pub struct AgentContext {
 }
 
 impl ModelRouter {
-    /// Replace the current policy with a new one and return a new router instance
-    pub fn with_policy(&self, policy: RoutingPolicy) -> Self {
+    /// Replace the current constraints with new ones and return a new router instance
+    pub fn with_constraints(&self, constraints: RoutingConstraints) -> Self {
         let mut cloned = self.clone();
-        cloned.policy = policy;
+        cloned.constraints = constraints;
         cloned
     }
 
-    /// Get a copy of the active routing policy
-    pub fn policy(&self) -> RoutingPolicy {
-        self.policy.clone()
+    /// Get a copy of the active routing constraints
+    pub fn constraints(&self) -> RoutingConstraints {
+        self.constraints.clone()
+    }
+
+    /// Active routing policy, if one is set
+    pub fn routing_policy(&self) -> Option<&Arc<dyn RoutingPolicy>> {
+        self.routing_policy.as_ref()
+    }
+
+    /// Record an observed call latency for `model`
+    pub fn record_latency(&self, model: &str, latency: Duration) {
+        self.latency.record(model, latency);
+    }
+
+    /// Rolling latency observations shared by this router and its clones
+    pub fn latency_tracker(&self) -> &LatencyTracker {
+        &self.latency
+    }
+
+    /// Record how long the event behind `decision` took: the model's latency for
+    /// single-model routes and, with a latency class, the class's SLO.
+    /// Returns true when the class's SLO was missed.
+    pub fn record_outcome(&self, decision: &RoutingDecision, latency: Duration) -> bool {
+        if let (Route::Local | Route::Cloud, Some(model)) = (&decision.route, &decision.model) {
+            self.latency.record(model, latency);
+        }
+        match decision
+            .latency_class
+            .and_then(|class| self.tiers.get(class).map(|tier| (class, tier)))
+        {
+            Some((class, tier)) => self.slo.record(class, tier.slo_ms, latency),
+            None => false,
+        }
+    }
+
+    /// Budget for a prompt to `decision`'s model, leaving `max_output_tokens` for the
+    /// reply. The context window comes from the model's cost table entry, else the
+    /// tokenizer registry; `None` when the decision names no model.
+    pub fn context_budget(
+        &self,
+        decision: &RoutingDecision,
+        max_output_tokens: usize,
+    ) -> Option<ModelBudget> {
+        let model = decision.model.as_deref()?;
+        let profile = self.cost_table.get(model);
+        let window = profile
+            .and_then(|p| p.context_window)
+            .unwrap_or_else(|| self.tokenizers.context_window(model));
+        let max_output_tokens = max_output_tokens.min(window);
+        Some(ModelBudget {
+            model: model.to_string(),
+            budget: TokenBudget {
+                max_input_tokens: window - max_output_tokens,
+                max_output_tokens,
+            },
+            input_cost_per_1k: profile.map_or(0.0, |p| p.input_cost_per_1k),
+        })
+    }
+
+    /// Tokenizers and context windows of routable models
+    pub fn tokenizers(&self) -> &Arc<TokenizerRegistry> {
+        &self.tokenizers
+    }
+
+    /// Model tiers per latency class
+    pub fn tiers(&self) -> &TierTable {
+        &self.tiers
+    }
+
+    /// SLO attainment of every latency class with a tier, shared by clones
+    pub fn slo_stats(&self) -> Vec<SloStats> {
+        LatencyClass::ALL
+            .iter()
+            .filter_map(|&class| {
+                self.tiers
+                    .get(class)
+                    .map(|tier| self.slo.stats(class, tier.slo_ms))
+            })
+            .collect()
     }
 
     /// Check if a local model is available for the given event type
@@ -410,6 +773,12 @@ impl ModelRouter {
     /// 1. A specific model mapping exists for the event type and is registered, OR
     /// 2. At least one local model is available (for unknown/generic event types)
     fn has_local_model_for(&self, event_type: &str) -> bool {
+        self.local_model_for(event_type).is_some()
+    }
+
+    /// Local model handling `event_type`: its mapped model if registered, otherwise
+    /// the first local model
+    fn local_model_for(&self, event_type: &str) -> Option<&str> {
         // First check for explicit model mappings
         let model_name = match event_type {
             "video_frame" | "face_event" => Some("face_detector"),
@@ -420,11 +789,25 @@ impl ModelRouter {
 
         if let Some(name) = model_name {
             // For known event types, check if the specific model is available
-            self.local_models.iter().any(|m| m == name)
+            self.local_models
+                .iter()
+                .find(|m| m.as_str() == name)
+                .map(String::as_str)
         } else {
             // For unknown event types, assume local models can handle if any are available
             // This allows the confidence estimator to make the final determination
-            !self.local_models.is_empty()
+            self.local_models.first().map(String::as_str)
+        }
+    }
+
+    /// Model that serves `route` for `event_type`; Hybrid is budgeted for its cloud pass
+    fn model_for(&self, route: &Route, event_type: &str) -> Option<String> {
+        match route {
+            Route::Local | Route::LocalFallback => {
+                self.local_model_for(event_type).map(str::to_string)
+            }
+            Route::Cloud | Route::Hybrid => self.cloud_endpoints.first().cloned(),
+            Route::Defer | Route::Drop => None,
         }
     }
 
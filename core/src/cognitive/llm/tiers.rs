@@ -0,0 +1,265 @@
+// Latency classes and model tiers
+//
+// Events carry an optional `latency_class` hint (`realtime` for voice, `interactive`,
+// `background` for slow analysis). `ModelRouter` maps each class to a `ModelTier`, an
+// ordered list of models with a latency SLO, and records every handled event against
+// its class's SLO in a `SloTracker`.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+use opentelemetry::{
+    global,
+    metrics::{Counter, Histogram},
+    KeyValue,
+};
+use serde::{Deserialize, Serialize};
+
+use super::policy::LatencyTracker;
+use crate::proto::Event;
+use crate::{LoomError, Result};
+
+/// Event metadata key (and `AgentConfig.parameters` key `routing.latency_class`) naming
+/// the latency class
+pub const LATENCY_CLASS_KEY: &str = "latency_class";
+
+/// How quickly an answer is needed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LatencyClass {
+    /// A person is waiting on audio (voice turns)
+    Realtime,
+    /// A person is waiting on text (chat, API calls)
+    Interactive,
+    /// Nobody is waiting (summaries, analysis, batch jobs)
+    Background,
+}
+
+impl LatencyClass {
+    pub const ALL: [LatencyClass; 3] = [
+        LatencyClass::Realtime,
+        LatencyClass::Interactive,
+        LatencyClass::Background,
+    ];
+
+    /// Parse a class name; `voice` means `realtime`, `batch` means `background`
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "realtime" | "real_time" | "voice" => Some(Self::Realtime),
+            "interactive" | "chat" => Some(Self::Interactive),
+            "background" | "batch" => Some(Self::Background),
+            _ => None,
+        }
+    }
+
+    /// Class hinted by the event's `latency_class` metadata
+    pub fn from_event(event: &Event) -> Option<Self> {
+        event
+            .metadata
+            .get(LATENCY_CLASS_KEY)
+            .and_then(|name| Self::parse(name))
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Realtime => "realtime",
+            Self::Interactive => "interactive",
+            Self::Background => "background",
+        }
+    }
+
+    fn index(self) -> usize {
+        match self {
+            Self::Realtime => 0,
+            Self::Interactive => 1,
+            Self::Background => 2,
+        }
+    }
+}
+
+impl fmt::Display for LatencyClass {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Models serving one latency class, in order of preference
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ModelTier {
+    pub models: Vec<String>,
+    /// Target end-to-end latency for events of the class
+    pub slo_ms: u64,
+}
+
+impl ModelTier {
+    pub fn new<I, S>(models: I, slo_ms: u64) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self {
+            models: models.into_iter().map(Into::into).collect(),
+            slo_ms,
+        }
+    }
+}
+
+/// Model tier per latency class
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TierTable {
+    tiers: HashMap<LatencyClass, ModelTier>,
+}
+
+impl TierTable {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Tiers over the router's default models: the small local model for realtime,
+    /// cloud models for interactive and background (largest first for background)
+    pub fn builtin() -> Self {
+        let mut table = Self::new();
+        table.insert(
+            LatencyClass::Realtime,
+            ModelTier::new(["lightweight_llm"], 300),
+        );
+        table.insert(
+            LatencyClass::Interactive,
+            ModelTier::new(["claude-3", "gpt-4"], 2_000),
+        );
+        table.insert(
+            LatencyClass::Background,
+            ModelTier::new(["gpt-4", "claude-3"], 30_000),
+        );
+        table
+    }
+
+    /// Parse a JSON object of `{"realtime": {"models": [...], "slo_ms": 300}, ...}`
+    pub fn from_json(json: &str) -> Result<Self> {
+        let raw: HashMap<String, ModelTier> = serde_json::from_str(json)?;
+        let mut table = Self::new();
+        for (name, tier) in raw {
+            let class = LatencyClass::parse(&name)
+                .ok_or_else(|| LoomError::RouterError(format!("Unknown latency class {}", name)))?;
+            if tier.models.is_empty() {
+                return Err(LoomError::RouterError(format!(
+                    "Latency class {} has no models",
+                    class
+                )));
+            }
+            table.insert(class, tier);
+        }
+        Ok(table)
+    }
+
+    /// Builtin tiers overlaid with `LOOM_LATENCY_TIERS` (JSON) when set
+    pub fn from_env() -> Result<Self> {
+        let mut table = Self::builtin();
+        if let Ok(json) = std::env::var("LOOM_LATENCY_TIERS") {
+            if !json.trim().is_empty() {
+                table.merge(Self::from_json(&json)?);
+            }
+        }
+        Ok(table)
+    }
+
+    pub fn insert(&mut self, class: LatencyClass, tier: ModelTier) {
+        self.tiers.insert(class, tier);
+    }
+
+    pub fn get(&self, class: LatencyClass) -> Option<&ModelTier> {
+        self.tiers.get(&class)
+    }
+
+    /// Add or replace tiers from `other`
+    pub fn merge(&mut self, other: TierTable) {
+        self.tiers.extend(other.tiers);
+    }
+}
+
+/// SLO attainment of one latency class
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SloStats {
+    pub class: LatencyClass,
+    pub slo_ms: u64,
+    pub requests: u64,
+    /// Requests that took longer than `slo_ms`
+    pub violations: u64,
+    /// Over the tracker's rolling window
+    pub p50_ms: Option<u64>,
+    pub p95_ms: Option<u64>,
+}
+
+impl SloStats {
+    /// Fraction of requests within the SLO (1.0 before any request)
+    pub fn attainment(&self) -> f64 {
+        if self.requests == 0 {
+            1.0
+        } else {
+            1.0 - self.violations as f64 / self.requests as f64
+        }
+    }
+}
+
+/// Observed latency per latency class against its SLO
+pub struct SloTracker {
+    latency: LatencyTracker,
+    requests: [AtomicU64; 3],
+    violations: [AtomicU64; 3],
+    latency_histogram: Histogram<f64>,
+    violations_counter: Counter<u64>,
+}
+
+impl Default for SloTracker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SloTracker {
+    pub fn new() -> Self {
+        let meter = global::meter("loom.router");
+        Self {
+            latency: LatencyTracker::default(),
+            requests: Default::default(),
+            violations: Default::default(),
+            latency_histogram: meter
+                .f64_histogram("loom.router.latency_class_ms")
+                .with_description("Observed latency per latency class")
+                .init(),
+            violations_counter: meter
+                .u64_counter("loom.router.slo_violations_total")
+                .with_description("Requests slower than their latency class SLO")
+                .init(),
+        }
+    }
+
+    /// Record one request of `class`; returns true when it missed `slo_ms`
+    pub fn record(&self, class: LatencyClass, slo_ms: u64, latency: Duration) -> bool {
+        let ms = latency.as_millis() as u64;
+        self.latency.record(class.as_str(), latency);
+        self.requests[class.index()].fetch_add(1, Ordering::Relaxed);
+        let labels = [KeyValue::new("latency_class", class.as_str())];
+        self.latency_histogram.record(ms as f64, &labels);
+        let violated = ms > slo_ms;
+        if violated {
+            self.violations[class.index()].fetch_add(1, Ordering::Relaxed);
+            self.violations_counter.add(1, &labels);
+        }
+        violated
+    }
+
+    /// Stats for `class` measured against `slo_ms`
+    pub fn stats(&self, class: LatencyClass, slo_ms: u64) -> SloStats {
+        SloStats {
+            class,
+            slo_ms,
+            requests: self.requests[class.index()].load(Ordering::Relaxed),
+            violations: self.violations[class.index()].load(Ordering::Relaxed),
+            p50_ms: self.latency.p50(class.as_str()),
+            p95_ms: self.latency.p95(class.as_str()),
+        }
+    }
+}
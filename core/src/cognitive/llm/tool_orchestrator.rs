@@ -1,17 +1,19 @@
 use std::sync::Arc;
 use std::time::Instant;
 
+use ring::digest;
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
 use tracing::{debug, info, warn, Span};
 
 use crate::context::{PromptBundle, TokenBudget};
 use crate::proto::{ActionCall, ActionResult, ActionStatus, QoSLevel};
-use crate::tools::{Tool, ToolRegistry};
+use crate::tools::{CancellationToken, Tool, ToolError, ToolRegistry};
 use crate::{LoomError, Result};
 
 use super::adapter::promptbundle_to_messages_and_text;
-use super::client::LlmClient;
+use super::client::{GenerateOptions, LlmClient, LlmResponse};
+use super::coalesce::prompt_hash;
 
 // OpenTelemetry imports
 use opentelemetry::{
@@ -39,6 +41,14 @@ pub struct OrchestratorOptions {
     pub per_tool_timeout_ms: u64,
     pub refine_on_tool_result: bool,
     pub max_tools_exposed: usize,
+    /// Reuse the model's cached tool-call decision and refine answer for a repeated
+    /// prompt (tools still run every time). Only applies when the client has a cache.
+    #[serde(default = "default_cache")]
+    pub cache: bool,
+}
+
+fn default_cache() -> bool {
+    true
 }
 
 impl Default for OrchestratorOptions {
@@ -48,6 +58,7 @@ impl Default for OrchestratorOptions {
             per_tool_timeout_ms: 30_000,
             refine_on_tool_result: true,
             max_tools_exposed: 64,
+            cache: default_cache(),
         }
     }
 }
@@ -156,13 +167,36 @@ impl ToolOrchestrator {
     /// Contract:
     /// - Input: PromptBundle + budget + options
     /// - Output: FinalAnswer (text, tool calls, results)
-    #[tracing::instrument(name = "tool_orchestrator.run", skip(self, bundle), fields(tool_choice = ?options.tool_choice, tool_count, refine_enabled = options.refine_on_tool_result))]
     pub async fn run(
         &mut self,
         bundle: &PromptBundle,
         budget: Option<TokenBudget>,
         options: OrchestratorOptions,
         correlation_id: Option<String>,
+    ) -> Result<FinalAnswer> {
+        self.run_with_cancel(
+            bundle,
+            budget,
+            options,
+            correlation_id,
+            CancellationToken::new(),
+        )
+        .await
+    }
+
+    /// `run`, stopping tool execution once `cancel` fires.
+    ///
+    /// The in-flight tool call is cancelled through `ToolRegistry::call_with_cancel`;
+    /// it and every call not yet started get a `CANCELLED` result, and the refine turn
+    /// is skipped.
+    #[tracing::instrument(name = "tool_orchestrator.run", skip(self, bundle, cancel), fields(tool_choice = ?options.tool_choice, tool_count, refine_enabled = options.refine_on_tool_result))]
+    pub async fn run_with_cancel(
+        &mut self,
+        bundle: &PromptBundle,
+        budget: Option<TokenBudget>,
+        options: OrchestratorOptions,
+        correlation_id: Option<String>,
+        cancel: CancellationToken,
     ) -> Result<FinalAnswer> {
         let budget = budget.unwrap_or_default();
         // Record invocation metric
@@ -190,9 +224,23 @@ impl ToolOrchestrator {
         debug!(target="tool_orch", count=%tools.len(), latency_ms=%discovery_elapsed_ms, "Tool discovery complete");
         let (messages, input_text) = promptbundle_to_messages_and_text(bundle, budget);
 
+        // A cached decision replays the model output; the tools below still run
+        let cache = self.llm.cache_for(GenerateOptions {
+            cache: options.cache,
+            ..GenerateOptions::default()
+        });
+        let cache_key = cache.map(|_| tool_call_key(&self.llm, bundle, &budget, &tools, &options));
+        let cached = cache
+            .zip(cache_key.as_deref())
+            .and_then(|(cache, key)| cache.get(key))
+            .and_then(|resp| Some((resp.raw?, resp.provider?)));
+
         // Prefer Responses API; fallback to Chat Completions
         let use_tools = !tools.is_empty() && options.tool_choice != ToolChoice::None;
-        let resp_val = if use_tools {
+        let replayed = cached.is_some();
+        let resp_val = if replayed || !use_tools {
+            None
+        } else {
             match self
                 .post_responses_with_tools(&input_text, &tools, &options, budget)
                 .await
@@ -203,11 +251,18 @@ impl ToolOrchestrator {
                     None
                 }
             }
-        } else {
-            None
         };
 
-        let (raw, parsed_calls, provider_tag) = if let Some(v) = resp_val {
+        let (raw, parsed_calls, provider_tag) = if let Some((v, provider)) = cached {
+            debug!(target="tool_orch", provider=%provider, "Replaying cached model output");
+            if provider == "responses" {
+                let calls = parse_tool_calls_from_responses(&v);
+                (v, calls, "responses")
+            } else {
+                let calls = parse_tool_calls_from_chat(&v);
+                (v, calls, "chat.completions")
+            }
+        } else if let Some(v) = resp_val {
             let calls = parse_tool_calls_from_responses(&v);
             (v, calls, "responses")
         } else {
@@ -220,6 +275,19 @@ impl ToolOrchestrator {
 
         debug!(target="tool_orch", provider=%provider_tag, calls=%parsed_calls.len(), "Parsed tool calls");
 
+        if let (Some(cache), Some(key), false) = (cache, cache_key.as_deref(), replayed) {
+            cache.put(
+                key,
+                &LlmResponse {
+                    text: extract_text_fallback(&raw).unwrap_or_default(),
+                    model: raw.get("model").and_then(Value::as_str).map(str::to_string),
+                    provider: Some(provider_tag.to_string()),
+                    usage: raw.get("usage").cloned(),
+                    raw: Some(raw.clone()),
+                },
+            );
+        }
+
         if parsed_calls.is_empty() {
             let text = extract_text_fallback(&raw).ok_or_else(|| {
                 LoomError::AgentError("No tool calls and no assistant text in model output".into())
@@ -237,7 +305,10 @@ impl ToolOrchestrator {
         for call in &parsed_calls {
             let started = Instant::now();
 
-            let result = self.tools.call(&call.name, call.arguments.clone()).await;
+            let result = self
+                .tools
+                .call_with_cancel(&call.name, call.arguments.clone(), cancel.clone())
+                .await;
             let elapsed = started.elapsed().as_secs_f64() * 1000.0;
 
             let res = match result {
@@ -252,7 +323,10 @@ impl ToolOrchestrator {
                     status: ActionStatus::ActionError as i32,
                     output: Vec::new(),
                     error: Some(crate::proto::ActionError {
-                        code: "500".to_string(),
+                        code: match e {
+                            ToolError::Cancelled => "CANCELLED".to_string(),
+                            _ => "500".to_string(),
+                        },
                         message: e.to_string(),
                         details: std::collections::HashMap::new(),
                     }),
@@ -297,10 +371,20 @@ impl ToolOrchestrator {
         }
 
         // Optional refine with tool results
-        if options.refine_on_tool_result {
+        if options.refine_on_tool_result && !cancel.is_cancelled() {
             let refine_bundle = make_refine_bundle(bundle, &parsed_calls, &results);
             let refine_started = Instant::now();
-            let final_resp = self.llm.generate(&refine_bundle, Some(budget)).await?;
+            let final_resp = self
+                .llm
+                .generate_with_options(
+                    &refine_bundle,
+                    Some(budget),
+                    GenerateOptions {
+                        cache: options.cache,
+                        ..GenerateOptions::default()
+                    },
+                )
+                .await?;
             let refine_elapsed_ms = refine_started.elapsed().as_secs_f64() * 1000.0;
 
             // Record refine cycle metric
@@ -333,7 +417,7 @@ impl ToolOrchestrator {
             tools.push(json!({
                 "type": "function",
                 "name": tool.name(),
-                "description": tool.description(),
+                "description": describe_tool(tool.as_ref()),
                 "parameters": tool.parameters(),
             }));
         }
@@ -439,6 +523,42 @@ impl ToolOrchestrator {
     }
 }
 
+/// Cache key of the model's tool-call decision: the prompt plus the exposed tools and
+/// tool choice, kept apart from plain `generate` entries for the same prompt. Like
+/// `prompt_hash`, the tools part is the hex SHA-256 of its length-prefixed fields.
+fn tool_call_key(
+    llm: &LlmClient,
+    bundle: &PromptBundle,
+    budget: &TokenBudget,
+    tools: &[Value],
+    options: &OrchestratorOptions,
+) -> String {
+    let mut context = digest::Context::new(&digest::SHA256);
+    let tools = serde_json::to_string(tools).unwrap_or_default();
+    let tool_choice = format!("{:?}", options.tool_choice);
+    for field in [tools.as_bytes(), tool_choice.as_bytes()] {
+        context.update(&(field.len() as u64).to_le_bytes());
+        context.update(field);
+    }
+    let digest: String = context
+        .finish()
+        .as_ref()
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect();
+    format!("{}:tools:{digest}", prompt_hash(&llm.cfg, bundle, budget))
+}
+
+/// Tool description for the LLM, with the result contract appended when the tool
+/// declares an output schema (function-calling APIs have no field for it).
+pub fn describe_tool(tool: &dyn Tool) -> String {
+    let description = tool.description();
+    match tool.output_schema() {
+        Some(schema) => format!("{description}\n\nReturns JSON matching this schema: {schema}"),
+        None => description,
+    }
+}
+
 pub fn build_action_call(
     call: &NormalizedToolCall,
     timeout_ms: u64,
@@ -0,0 +1,77 @@
+//! Backend usage counters shared by `LlmClient`s.
+//!
+//! Counts requests that reached the backend (cache hits and coalesced followers are not
+//! counted) and the tokens reported in their `usage` objects, for `Loom::stats()`.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use serde::{Deserialize, Serialize};
+
+use super::client::LlmResponse;
+use crate::Result;
+
+/// Snapshot of `LlmUsage`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LlmUsageStats {
+    /// Backend requests made, including failed ones
+    pub requests: u64,
+    pub failures: u64,
+    /// Input tokens reported by the backend
+    pub prompt_tokens: u64,
+    /// Output tokens reported by the backend
+    pub completion_tokens: u64,
+    pub total_tokens: u64,
+}
+
+/// Usage counters; attach with `LlmClient::with_usage`
+#[derive(Debug, Default)]
+pub struct LlmUsage {
+    requests: AtomicU64,
+    failures: AtomicU64,
+    prompt_tokens: AtomicU64,
+    completion_tokens: AtomicU64,
+    total_tokens: AtomicU64,
+}
+
+impl LlmUsage {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Count a backend request and the tokens of its response
+    pub fn record(&self, result: &Result<LlmResponse>) {
+        self.requests.fetch_add(1, Ordering::Relaxed);
+        let usage = match result {
+            Ok(resp) => resp.usage.as_ref(),
+            Err(_) => {
+                self.failures.fetch_add(1, Ordering::Relaxed);
+                None
+            }
+        };
+        let Some(usage) = usage else {
+            return;
+        };
+        let field = |name: &str| usage.get(name).and_then(|v| v.as_u64());
+        let prompt = field("input_tokens")
+            .or(field("prompt_tokens"))
+            .unwrap_or(0);
+        let completion = field("output_tokens")
+            .or(field("completion_tokens"))
+            .unwrap_or(0);
+        let total = field("total_tokens").unwrap_or(prompt + completion);
+        self.prompt_tokens.fetch_add(prompt, Ordering::Relaxed);
+        self.completion_tokens
+            .fetch_add(completion, Ordering::Relaxed);
+        self.total_tokens.fetch_add(total, Ordering::Relaxed);
+    }
+
+    pub fn stats(&self) -> LlmUsageStats {
+        LlmUsageStats {
+            requests: self.requests.load(Ordering::Relaxed),
+            failures: self.failures.load(Ordering::Relaxed),
+            prompt_tokens: self.prompt_tokens.load(Ordering::Relaxed),
+            completion_tokens: self.completion_tokens.load(Ordering::Relaxed),
+            total_tokens: self.total_tokens.load(Ordering::Relaxed),
+        }
+    }
+}
@@ -1,11 +1,17 @@
 //! Core cognitive loop trait definition.
 
+use std::collections::HashMap;
+
 use async_trait::async_trait;
 
 use crate::proto::{Action, AgentState, Event};
-use crate::Result;
+use crate::{LoomError, Result};
 
+use super::bootstrap::BootstrapSeed;
+use super::config::{ALLOWED_TOOLS_PARAM, DENIED_TOOLS_PARAM};
 use super::memory_buffer::MemoryBuffer;
+use super::streaming::ResponseSink;
+use super::structured::StructuredOutput;
 use super::thought::Plan;
 
 /// Perception result from the perceive phase
@@ -87,6 +93,9 @@ pub struct ExecutionResult {
     /// Text response (if any)
     pub response: Option<String>,
 
+    /// Machine-consumable results, published on the agent's `result.<agent_id>` topic
+    pub structured: Vec<StructuredOutput>,
+
     /// Whether the goal was achieved
     pub goal_achieved: bool,
 
@@ -127,6 +136,12 @@ impl ExecutionResult {
         }
     }
 
+    /// Add a structured result
+    pub fn with_structured(mut self, output: StructuredOutput) -> Self {
+        self.structured.push(output);
+        self
+    }
+
     /// Convert to actions list for AgentBehavior
     pub fn into_actions(self) -> Vec<Action> {
         self.actions
@@ -213,10 +228,38 @@ pub trait CognitiveLoop: Send + Sync {
     /// Mutable access to memory buffer
     fn memory_buffer_mut(&mut self) -> &mut MemoryBuffer;
 
+    /// Send the response of the following cycles into `sink` as it is generated (`None`
+    /// to stop). Default: ignored; only the complete response is returned.
+    fn set_response_sink(&mut self, _sink: Option<ResponseSink>) {}
+
+    /// Seed the system prompt and memory of agent `agent_id` (see `bootstrap`); returns
+    /// the number of memory items written. Default: unsupported.
+    async fn bootstrap(&mut self, agent_id: &str, _seed: &BootstrapSeed) -> Result<usize> {
+        Err(LoomError::AgentError(format!(
+            "Agent {} has a bootstrap directory but its cognitive loop cannot be seeded",
+            agent_id
+        )))
+    }
+
+    /// Apply the `tools.allowed`/`tools.denied` parameters of the agent's config (see
+    /// `CognitiveConfig::apply_tool_parameters`). Default: an error if either is set,
+    /// since the loop cannot enforce them.
+    fn restrict_tools(&mut self, parameters: &HashMap<String, String>) -> Result<()> {
+        match [ALLOWED_TOOLS_PARAM, DENIED_TOOLS_PARAM]
+            .into_iter()
+            .find(|key| parameters.contains_key(*key))
+        {
+            Some(key) => Err(LoomError::AgentError(format!(
+                "Agent has a {key} parameter but its cognitive loop cannot restrict tools"
+            ))),
+            None => Ok(()),
+        }
+    }
+
     /// Run the complete cognitive cycle
     async fn run_cycle(&mut self, event: Event, state: &mut AgentState) -> Result<ExecutionResult> {
         // 1. Perceive
-        let perception = self.perceive(event.clone(), state).await?;
+        let perception = self.perceive(event, state).await?;
 
         // 2. Think
         let plan = self.think(&perception).await?;
@@ -233,8 +276,9 @@ pub trait CognitiveLoop: Send + Sync {
             );
         }
 
-        // 5. Update memory buffer
-        self.memory_buffer_mut().add_event_summary(&event);
+        // 5. Update memory buffer (with the event as perceived, e.g. after redaction)
+        self.memory_buffer_mut()
+            .add_event_summary(&perception.event);
 
         Ok(result)
     }
@@ -5,6 +5,13 @@
 //! - **LLM**: HTTP client, model routing, and provider abstraction
 //! - **Loop**: Perceive-Think-Act cognitive loop pattern
 //! - **Orchestrator**: Tool execution and multi-step reasoning
+//! - **Guardrails**: Content filters for inbound text, answers and tool arguments
+//! - **Sessions**: Conversation transcripts persisted as context items, resumable across restarts
+//! - **Replay**: Sessions forked at a past point and replayed against another config or model
+//! - **Structured results**: Typed JSON outputs published on `result.<agent_id>`, apart from text
+//! - **Response streaming**: Partial LLM output published to the requester as it is generated
+//! - **Bootstrap**: System prompt and memory seeded from a directory of documents at startup
+//! - **Trace log**: Plans, steps and tool observations persisted as `ToolTrace` context items
 //!
 //! # Architecture
 //!
@@ -47,20 +54,49 @@ pub mod llm;
 
 // Cognitive loop components
 mod agent_adapter;
+mod bootstrap;
 mod config;
+mod guardrails;
 mod loop_trait;
 mod memory_buffer;
+mod replay;
+mod session;
 mod simple_loop;
+mod streaming;
+mod structured;
 mod thought;
+pub mod trace_log;
 
 // Core cognitive types
-pub use agent_adapter::CognitiveAgent;
-pub use config::{CognitiveConfig, ThinkingStrategy};
+pub use agent_adapter::{
+    CognitiveAgent, EXPECT_REPLY_KEY, REPLY_ACTION, REPLY_EVENT_TYPE_PARAM, RESPONSE_EVENT,
+};
+pub use bootstrap::{
+    relevant_seed_chunks, BootstrapSeed, SeedDocument, BOOTSTRAP_DIR_PARAM, SEED_PATH_TAG,
+    SEED_SESSION_ID,
+};
+pub use config::{CognitiveConfig, ThinkingStrategy, ALLOWED_TOOLS_PARAM, DENIED_TOOLS_PARAM};
+pub use guardrails::{
+    FilterAction, GuardStage, Guardrail, GuardrailConfig, GuardrailKind, GuardrailRule,
+    GuardrailViolation, Guardrails, KeywordFilter, MaxLength, PiiKind, PiiRedactor, RegexFilter,
+    Verdict,
+};
 pub use loop_trait::{CognitiveLoop, ExecutionResult, Perception};
 pub use memory_buffer::{MemoryBuffer, MemoryItem, MemoryItemType};
-pub use simple_loop::SimpleCognitiveLoop;
+pub use replay::{ReplayRun, ReplayStep, SessionReplay, REPLAY_EVENT, REPLAY_SOURCE_KEY};
+pub use session::{
+    ForkOrigin, ForkPoint, Session, SessionFork, SessionManager, SessionTurn, FORKED_FROM_TAG,
+    SESSION_MARKER_SOURCE,
+};
+pub use simple_loop::{extract_tool_call, SimpleCognitiveLoop};
+pub use streaming::{ResponseSink, RESPONSE_FINAL_EVENT, RESPONSE_PARTIAL_EVENT, STREAM_SEQ_KEY};
+pub use structured::{
+    keys as result_keys, result_topic, StructuredOutput, RESULT_ACTION, RESULT_EVENT,
+    RESULT_TOPIC_PREFIX,
+};
 pub use thought::{Observation, Plan, Thought, ThoughtStep, ToolCall};
+pub use trace_log::{past_tool_failures, trace_items, ToolAttempt};
 
 // Re-export key LLM types for convenience
 pub use llm::router::{ModelRouter, Route, RoutingDecision};
-pub use llm::{LlmClient, LlmClientConfig, LlmResponse};
+pub use llm::{LlmCache, LlmCacheConfig, LlmClient, LlmClientConfig, LlmResponse};
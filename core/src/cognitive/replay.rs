@@ -0,0 +1,278 @@
+//! What-if replays of recorded sessions.
+//!
+//! `SessionReplay` forks a session at a past point and feeds the user turns that
+//! followed it, one cycle each, to a `CognitiveAgent` built with a different
+//! configuration, prompt or model. Every answer is recorded to the fork and compared with
+//! what the agent originally said, so "why did the agent answer that" can be narrowed
+//! down by changing one thing at a time. Each `run` makes its own fork, so several
+//! variants can be replayed from the same source and compared.
+//!
+//! ```rust,ignore
+//! let replay = SessionReplay::new(Arc::clone(&sessions), &session_id, ForkPoint::AfterTurns(4));
+//! let mut agent = CognitiveAgent::new(SimpleCognitiveLoop::new(new_config, llm, broker));
+//! let run = replay.run("gpt-4o-mini", &mut agent).await?;
+//! if let Some(step) = run.first_divergence() {
+//!     println!("{:?} -> {:?}", step.original, step.replayed);
+//! }
+//! ```
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use serde::Serialize;
+use tracing::{info, warn};
+
+use crate::context::{AgentContext, MessageRole};
+use crate::proto::{AgentState, Event};
+use crate::Result;
+
+use super::agent_adapter::CognitiveAgent;
+use super::loop_trait::CognitiveLoop;
+use super::session::{ForkPoint, SessionManager, SessionTurn};
+
+/// Event type of replayed user turns
+pub const REPLAY_EVENT: &str = "user.message";
+
+/// Event metadata key naming the session a replayed event comes from
+pub const REPLAY_SOURCE_KEY: &str = "replay.source_session";
+
+/// One replayed request and both answers to it
+#[derive(Debug, Clone, Serialize)]
+pub struct ReplayStep {
+    /// Participant that made the request
+    pub participant: String,
+    pub request: String,
+    /// What the agent answered in the source session
+    pub original: Option<String>,
+    /// What the replayed agent answered
+    pub replayed: Option<String>,
+    /// Error of the replayed cycle, if it failed
+    pub error: Option<String>,
+    /// Word overlap of the two answers, 0.0 to 1.0 (1.0 when both are empty)
+    pub similarity: f64,
+}
+
+impl ReplayStep {
+    /// Whether the answers differ beyond case and whitespace
+    pub fn changed(&self) -> bool {
+        self.error.is_some()
+            || normalize(self.original.as_deref().unwrap_or_default())
+                != normalize(self.replayed.as_deref().unwrap_or_default())
+    }
+}
+
+/// Outcome of replaying a session with one agent variant
+#[derive(Debug, Clone, Serialize)]
+pub struct ReplayRun {
+    /// Caller's name for the variant (e.g. the model or config tried)
+    pub label: String,
+    pub source_session_id: String,
+    /// Session the replay was recorded to
+    pub fork_session_id: String,
+    /// Source turns the fork started with
+    pub forked_after_turns: usize,
+    pub steps: Vec<ReplayStep>,
+}
+
+impl ReplayRun {
+    /// Steps whose answer changed
+    pub fn changed_steps(&self) -> Vec<&ReplayStep> {
+        self.steps.iter().filter(|s| s.changed()).collect()
+    }
+
+    /// First step whose answer changed
+    pub fn first_divergence(&self) -> Option<&ReplayStep> {
+        self.steps.iter().find(|s| s.changed())
+    }
+
+    /// Mean answer similarity over all steps (1.0 for an empty replay)
+    pub fn mean_similarity(&self) -> f64 {
+        if self.steps.is_empty() {
+            return 1.0;
+        }
+        self.steps.iter().map(|s| s.similarity).sum::<f64>() / self.steps.len() as f64
+    }
+}
+
+/// Replays the turns after a fork point of a recorded session
+pub struct SessionReplay {
+    manager: Arc<SessionManager>,
+    source_session_id: String,
+    fork_point: ForkPoint,
+}
+
+impl SessionReplay {
+    pub fn new(
+        manager: Arc<SessionManager>,
+        source_session_id: impl Into<String>,
+        fork_point: ForkPoint,
+    ) -> Self {
+        Self {
+            manager,
+            source_session_id: source_session_id.into(),
+            fork_point,
+        }
+    }
+
+    /// Fork the source session and replay its later user turns through `agent`.
+    ///
+    /// The agent's memory is replaced with the fork's transcript first, and it keeps
+    /// recording to the fork afterwards. A failed cycle is kept as a step with `error`
+    /// set; the replay continues with the next turn. Answers the source gave before the
+    /// first replayed request (a fork point between a request and its answer) are
+    /// skipped.
+    pub async fn run<L: CognitiveLoop>(
+        &self,
+        label: impl Into<String>,
+        agent: &mut CognitiveAgent<L>,
+    ) -> Result<ReplayRun> {
+        let label = label.into();
+        let fork = self
+            .manager
+            .fork_session(&self.source_session_id, self.fork_point.clone())
+            .await?;
+        let fork_id = fork.session.session_id.clone();
+        let agent_id = fork.session.agent_id.clone();
+        agent
+            .resume_session(Arc::clone(&self.manager), &fork_id)
+            .await?;
+
+        let mut state = AgentState {
+            agent_id: agent_id.clone(),
+            persistent_state: vec![],
+            ephemeral_context: vec![],
+            last_update_ms: chrono::Utc::now().timestamp_millis(),
+            metadata: HashMap::new(),
+        };
+        let mut steps = Vec::new();
+        for (request, original) in exchanges(&fork.remaining) {
+            let event = self.replay_event(request);
+            let (replayed, error) = match agent.inner_mut().run_cycle(event, &mut state).await {
+                Ok(result) => (result.response, result.error),
+                Err(e) => (None, Some(e.to_string())),
+            };
+            if let Err(e) = self
+                .manager
+                .record_turn(
+                    &fork_id,
+                    &request.participant,
+                    MessageRole::User,
+                    request.content.clone(),
+                )
+                .await
+            {
+                warn!(target = "cognitive", session_id = %fork_id, error = %e, "Failed to record replayed turn");
+            }
+            if let Some(answer) = &replayed {
+                if let Err(e) = self
+                    .manager
+                    .record_turn(&fork_id, &agent_id, MessageRole::Assistant, answer.clone())
+                    .await
+                {
+                    warn!(target = "cognitive", session_id = %fork_id, error = %e, "Failed to record replayed turn");
+                }
+            }
+            steps.push(ReplayStep {
+                participant: request.participant.clone(),
+                request: request.content.clone(),
+                similarity: similarity(
+                    original.as_deref().unwrap_or_default(),
+                    replayed.as_deref().unwrap_or_default(),
+                ),
+                original,
+                replayed,
+                error,
+            });
+        }
+
+        let run = ReplayRun {
+            label,
+            source_session_id: self.source_session_id.clone(),
+            fork_session_id: fork_id,
+            forked_after_turns: fork
+                .session
+                .forked_from
+                .as_ref()
+                .map_or(0, |origin| origin.turns),
+            steps,
+        };
+        info!(
+            target = "cognitive",
+            label = %run.label,
+            source = %run.source_session_id,
+            fork = %run.fork_session_id,
+            steps = run.steps.len(),
+            changed = run.changed_steps().len(),
+            "Session replay complete"
+        );
+        Ok(run)
+    }
+
+    fn replay_event(&self, turn: &SessionTurn) -> Event {
+        Event {
+            id: AgentContext::generate_id(),
+            r#type: REPLAY_EVENT.to_string(),
+            timestamp_ms: chrono::Utc::now().timestamp_millis(),
+            source: turn.participant.clone(),
+            metadata: HashMap::from([(
+                REPLAY_SOURCE_KEY.to_string(),
+                self.source_session_id.clone(),
+            )]),
+            payload: turn.content.as_bytes().to_vec(),
+            confidence: 1.0,
+            tags: vec![],
+            priority: 50,
+        }
+    }
+}
+
+/// Pair each user turn with the assistant answer(s) that followed it
+fn exchanges(turns: &[SessionTurn]) -> Vec<(&SessionTurn, Option<String>)> {
+    let mut exchanges: Vec<(&SessionTurn, Option<String>)> = Vec::new();
+    for turn in turns {
+        match turn.role {
+            MessageRole::User => exchanges.push((turn, None)),
+            MessageRole::Assistant => {
+                if let Some((_, answer)) = exchanges.last_mut() {
+                    match answer {
+                        Some(text) => {
+                            text.push('\n');
+                            text.push_str(&turn.content);
+                        }
+                        None => *answer = Some(turn.content.clone()),
+                    }
+                }
+            }
+            MessageRole::System => {}
+        }
+    }
+    exchanges
+}
+
+fn normalize(text: &str) -> String {
+    text.split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+}
+
+/// Dice coefficient over lowercased words (with multiplicity)
+fn similarity(a: &str, b: &str) -> f64 {
+    let words = |text: &str| -> HashMap<String, usize> {
+        let mut counts = HashMap::new();
+        for word in text.split_whitespace() {
+            *counts.entry(word.to_lowercase()).or_insert(0) += 1;
+        }
+        counts
+    };
+    let (a, b) = (words(a), words(b));
+    let total: usize = a.values().sum::<usize>() + b.values().sum::<usize>();
+    if total == 0 {
+        return 1.0;
+    }
+    let common: usize = a
+        .iter()
+        .map(|(word, n)| (*n).min(b.get(word).copied().unwrap_or(0)))
+        .sum();
+    2.0 * common as f64 / total as f64
+}
@@ -0,0 +1,459 @@
+//! Conversation sessions for cognitive agents.
+//!
+//! A session groups the interactions of one agent with its participants. The
+//! `SessionManager` writes everything it tracks to a `MemoryStore` as `ContextItem`s
+//! under the session id:
+//! - Session start/end markers as `Observation { source: "session" }` items
+//! - Each turn as a `Message` item, tagged with the participant that produced it
+//!
+//! Because the store is the source of truth, a session can be resumed after a restart
+//! with `resume_session(id)` when the store is persistent (e.g. `RocksDbStore`), and
+//! `CognitiveAgent::resume_session` reloads the transcript into the agent's memory.
+//!
+//! `fork_session(id, at)` branches a session at a past point: every item stored up to
+//! the `ForkPoint` (turns, tool calls, observations) is copied to a new session, whose
+//! start marker records where it came from. The turns after the fork point are returned
+//! so they can be replayed against a different configuration (see `SessionReplay`).
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tracing::{debug, info};
+
+use crate::context::{
+    AgentContext, ContextContent, ContextItem, ContextItemType, ContextMetadata, MemoryQuery,
+    MemoryStore, MessageRole,
+};
+use crate::{LoomError, Result};
+
+/// Source of session marker items
+pub const SESSION_MARKER_SOURCE: &str = "session";
+
+/// Tag key carrying the marker kind (`start` / `end`)
+const MARKER_TAG: &str = "session.marker";
+
+/// Tag key carrying the participant that produced a turn
+const PARTICIPANT_TAG: &str = "session.participant";
+
+/// Tag key on copied items naming the session they were forked from
+pub const FORKED_FROM_TAG: &str = "session.forked_from";
+
+/// Upper bound on items loaded when resuming a session
+const MAX_RESUME_ITEMS: usize = 10_000;
+
+/// Session metadata
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Session {
+    pub session_id: String,
+    /// Agent that owns the session
+    pub agent_id: String,
+    /// Everyone who has taken a turn (or was named at start), in order of appearance
+    pub participants: Vec<String>,
+    pub started_at_ms: i64,
+    /// Set once the session is ended
+    pub ended_at_ms: Option<i64>,
+    /// Set when the session was forked from another one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub forked_from: Option<ForkOrigin>,
+}
+
+/// Where a forked session branched off
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ForkOrigin {
+    pub session_id: String,
+    /// Turns of the source session the fork starts with
+    pub turns: usize,
+    /// Last item copied from the source, if any
+    pub item_id: Option<String>,
+}
+
+/// Point in a session's history to fork at; everything up to and including it is copied
+#[derive(Debug, Clone, PartialEq)]
+pub enum ForkPoint {
+    /// After the first `n` turns (0 forks before the first turn)
+    AfterTurns(usize),
+    /// After a specific stored item, e.g. the id returned by `record_turn`
+    AfterItem(String),
+    /// After the last item: a full copy
+    End,
+}
+
+/// A newly forked session
+#[derive(Debug, Clone)]
+pub struct SessionFork {
+    pub session: Session,
+    /// Items copied from the source session (markers excluded)
+    pub copied_items: usize,
+    /// Source turns after the fork point, oldest first
+    pub remaining: Vec<SessionTurn>,
+}
+
+impl Session {
+    pub fn is_active(&self) -> bool {
+        self.ended_at_ms.is_none()
+    }
+
+    fn add_participant(&mut self, participant: &str) {
+        if !self.participants.iter().any(|p| p == participant) {
+            self.participants.push(participant.to_string());
+        }
+    }
+}
+
+/// A single transcript entry of a session
+#[derive(Debug, Clone, PartialEq)]
+pub struct SessionTurn {
+    pub participant: String,
+    pub role: MessageRole,
+    pub content: String,
+    pub timestamp_ms: i64,
+}
+
+/// Tracks sessions and persists their transcripts to a `MemoryStore`
+pub struct SessionManager {
+    store: Arc<dyn MemoryStore>,
+    sessions: DashMap<String, Session>,
+}
+
+impl SessionManager {
+    pub fn new(store: Arc<dyn MemoryStore>) -> Self {
+        Self {
+            store,
+            sessions: DashMap::new(),
+        }
+    }
+
+    pub fn store(&self) -> &Arc<dyn MemoryStore> {
+        &self.store
+    }
+
+    /// Start a new session owned by `agent_id`
+    pub async fn start_session(
+        &self,
+        agent_id: &str,
+        participants: Vec<String>,
+    ) -> Result<Session> {
+        let session_id = format!("session_{}", AgentContext::generate_id());
+        self.start_session_with_id(session_id, agent_id, participants)
+            .await
+    }
+
+    /// Start a session with a caller-chosen id (e.g. a thread id)
+    pub async fn start_session_with_id(
+        &self,
+        session_id: impl Into<String>,
+        agent_id: &str,
+        participants: Vec<String>,
+    ) -> Result<Session> {
+        let session_id = session_id.into();
+        if self.sessions.contains_key(&session_id) {
+            return Err(LoomError::AgentError(format!(
+                "session already exists: {session_id}"
+            )));
+        }
+
+        let mut session = Session {
+            session_id: session_id.clone(),
+            agent_id: agent_id.to_string(),
+            participants: Vec::new(),
+            started_at_ms: chrono::Utc::now().timestamp_millis(),
+            ended_at_ms: None,
+            forked_from: None,
+        };
+        for participant in participants.iter() {
+            session.add_participant(participant);
+        }
+
+        self.store_marker(&session, "start").await?;
+        info!(session_id = %session_id, agent_id = %agent_id, "Session started");
+        self.sessions.insert(session_id, session.clone());
+        Ok(session)
+    }
+
+    /// Append a turn to an active session's transcript; returns the stored item id
+    pub async fn record_turn(
+        &self,
+        session_id: &str,
+        participant: &str,
+        role: MessageRole,
+        content: impl Into<String>,
+    ) -> Result<String> {
+        let agent_id = {
+            let mut session = self
+                .sessions
+                .get_mut(session_id)
+                .ok_or_else(|| unknown_session(session_id))?;
+            if !session.is_active() {
+                return Err(LoomError::AgentError(format!(
+                    "session already ended: {session_id}"
+                )));
+            }
+            session.add_participant(participant);
+            session.agent_id.clone()
+        };
+
+        let item = ContextItem {
+            id: AgentContext::generate_id(),
+            item_type: ContextItemType::Message { role },
+            content: ContextContent::from_string(content.into()),
+            metadata: ContextMetadata::new(session_id.to_string(), agent_id)
+                .with_tag(PARTICIPANT_TAG.to_string(), participant.to_string())
+                .with_current_trace(),
+        };
+        let id = item.id.clone();
+        self.store.store(item).await?;
+        debug!(session_id = %session_id, participant = %participant, role = ?role, "Recorded session turn");
+        Ok(id)
+    }
+
+    /// End a session; further turns are rejected
+    pub async fn end_session(&self, session_id: &str) -> Result<Session> {
+        let session = {
+            let mut session = self
+                .sessions
+                .get_mut(session_id)
+                .ok_or_else(|| unknown_session(session_id))?;
+            if session.ended_at_ms.is_none() {
+                session.ended_at_ms = Some(chrono::Utc::now().timestamp_millis());
+            }
+            session.clone()
+        };
+        self.store_marker(&session, "end").await?;
+        info!(session_id = %session_id, "Session ended");
+        Ok(session)
+    }
+
+    /// Session metadata if the session is known to this manager
+    pub fn get(&self, session_id: &str) -> Option<Session> {
+        self.sessions.get(session_id).map(|s| s.clone())
+    }
+
+    /// Sessions known to this manager that have not ended
+    pub fn active_sessions(&self) -> Vec<Session> {
+        self.sessions
+            .iter()
+            .filter(|s| s.is_active())
+            .map(|s| s.clone())
+            .collect()
+    }
+
+    /// Reload a session and its transcript (oldest turn first) from the store.
+    ///
+    /// An ended session is resumed as ended; its transcript stays readable but it no
+    /// longer accepts turns.
+    pub async fn resume_session(&self, session_id: &str) -> Result<(Session, Vec<SessionTurn>)> {
+        let items = self.load_items(session_id).await?;
+
+        let mut session: Option<Session> = None;
+        let mut turns = Vec::new();
+        for item in items {
+            match &item.item_type {
+                ContextItemType::Observation { source } if source == SESSION_MARKER_SOURCE => {
+                    let Ok(marker) = serde_json::from_value::<Session>(item.content.raw.clone())
+                    else {
+                        continue;
+                    };
+                    match session.as_mut() {
+                        // Keep the original start; later markers carry the end time
+                        Some(s) => {
+                            s.ended_at_ms = marker.ended_at_ms.or(s.ended_at_ms);
+                            for p in marker.participants.iter() {
+                                s.add_participant(p);
+                            }
+                        }
+                        None => session = Some(marker),
+                    }
+                }
+                ContextItemType::Message { .. } => turns.extend(to_turn(&item)),
+                _ => {}
+            }
+        }
+
+        let mut session = session.ok_or_else(|| unknown_session(session_id))?;
+        for turn in turns.iter() {
+            session.add_participant(&turn.participant);
+        }
+        info!(session_id = %session_id, turns = turns.len(), "Session resumed");
+        self.sessions
+            .insert(session_id.to_string(), session.clone());
+        Ok((session, turns))
+    }
+
+    /// Fork `session_id` at `at` into a new session owned by the same agent
+    pub async fn fork_session(&self, session_id: &str, at: ForkPoint) -> Result<SessionFork> {
+        let fork_id = format!("session_{}", AgentContext::generate_id());
+        self.fork_session_with_id(session_id, at, fork_id).await
+    }
+
+    /// Fork `session_id` at `at` into a session with a caller-chosen id.
+    ///
+    /// Works on any session in the store, active or ended. Copied items keep their
+    /// timestamps, content and tags, get new ids (with `related_items` remapped) and are
+    /// tagged with [`FORKED_FROM_TAG`]. The fork is active and owned by the source's agent.
+    pub async fn fork_session_with_id(
+        &self,
+        session_id: &str,
+        at: ForkPoint,
+        fork_id: impl Into<String>,
+    ) -> Result<SessionFork> {
+        let fork_id = fork_id.into();
+        if self.sessions.contains_key(&fork_id) {
+            return Err(LoomError::AgentError(format!(
+                "session already exists: {fork_id}"
+            )));
+        }
+        let source = match self.get(session_id) {
+            Some(session) => session,
+            None => self.resume_session(session_id).await?.0,
+        };
+
+        // Markers are the source's own; the fork writes a fresh start marker
+        let items: Vec<ContextItem> = self
+            .load_items(session_id)
+            .await?
+            .into_iter()
+            .filter(|item| !is_marker(item))
+            .collect();
+        let cut = match &at {
+            ForkPoint::End => items.len(),
+            ForkPoint::AfterTurns(0) => items
+                .iter()
+                .position(|item| matches!(item.item_type, ContextItemType::Message { .. }))
+                .unwrap_or(items.len()),
+            ForkPoint::AfterTurns(n) => items
+                .iter()
+                .enumerate()
+                .filter(|(_, item)| matches!(item.item_type, ContextItemType::Message { .. }))
+                .nth(n - 1)
+                .map(|(i, _)| i + 1)
+                .ok_or_else(|| {
+                    LoomError::AgentError(format!("session {session_id} has fewer than {n} turns"))
+                })?,
+            ForkPoint::AfterItem(id) => {
+                items
+                    .iter()
+                    .position(|item| &item.id == id)
+                    .ok_or_else(|| {
+                        LoomError::AgentError(format!("item {id} is not in session {session_id}"))
+                    })?
+                    + 1
+            }
+        };
+        let (copied, rest) = items.split_at(cut);
+
+        let ids: HashMap<&str, String> = copied
+            .iter()
+            .map(|item| (item.id.as_str(), AgentContext::generate_id()))
+            .collect();
+        let mut turns = 0;
+        let mut copies = Vec::with_capacity(copied.len());
+        for item in copied {
+            if matches!(item.item_type, ContextItemType::Message { .. }) {
+                turns += 1;
+            }
+            let mut copy = item.clone();
+            copy.id = ids[item.id.as_str()].clone();
+            copy.metadata.session_id = fork_id.clone();
+            copy.metadata.related_items = item
+                .metadata
+                .related_items
+                .iter()
+                .map(|id| ids.get(id.as_str()).cloned().unwrap_or_else(|| id.clone()))
+                .collect();
+            copy.metadata
+                .tags
+                .insert(FORKED_FROM_TAG.to_string(), session_id.to_string());
+            copies.push(copy);
+        }
+        let copied_turns: Vec<SessionTurn> = copied.iter().filter_map(to_turn).collect();
+        let remaining: Vec<SessionTurn> = rest.iter().filter_map(to_turn).collect();
+
+        let mut session = Session {
+            session_id: fork_id.clone(),
+            agent_id: source.agent_id.clone(),
+            participants: Vec::new(),
+            started_at_ms: chrono::Utc::now().timestamp_millis(),
+            ended_at_ms: None,
+            forked_from: Some(ForkOrigin {
+                session_id: session_id.to_string(),
+                turns,
+                item_id: copied.last().map(|item| item.id.clone()),
+            }),
+        };
+        for turn in copied_turns.iter() {
+            session.add_participant(&turn.participant);
+        }
+
+        let copied_items = copies.len();
+        self.store.store_batch(copies).await?;
+        self.store_marker(&session, "start").await?;
+        info!(
+            session_id = %fork_id,
+            forked_from = %session_id,
+            turns,
+            remaining = remaining.len(),
+            "Session forked"
+        );
+        self.sessions.insert(fork_id, session.clone());
+        Ok(SessionFork {
+            session,
+            copied_items,
+            remaining,
+        })
+    }
+
+    /// Every item stored under `session_id`, oldest first
+    async fn load_items(&self, session_id: &str) -> Result<Vec<ContextItem>> {
+        let query = MemoryQuery::new()
+            .for_session(session_id.to_string())
+            .limit(MAX_RESUME_ITEMS);
+        let mut items = self.store.query(&query).await?;
+        // Ids embed a nanosecond timestamp, ordering items written within the same ms
+        items.sort_by(|a, b| {
+            (a.metadata.timestamp_ms, &a.id).cmp(&(b.metadata.timestamp_ms, &b.id))
+        });
+        Ok(items)
+    }
+
+    async fn store_marker(&self, session: &Session, kind: &str) -> Result<()> {
+        let item = ContextItem {
+            id: AgentContext::generate_id(),
+            item_type: ContextItemType::Observation {
+                source: SESSION_MARKER_SOURCE.to_string(),
+            },
+            content: ContextContent::from_value(json!(session)),
+            metadata: ContextMetadata::new(session.session_id.clone(), session.agent_id.clone())
+                .with_tag(MARKER_TAG.to_string(), kind.to_string()),
+        };
+        self.store.store(item).await
+    }
+}
+
+fn is_marker(item: &ContextItem) -> bool {
+    matches!(&item.item_type, ContextItemType::Observation { source } if source == SESSION_MARKER_SOURCE)
+}
+
+fn to_turn(item: &ContextItem) -> Option<SessionTurn> {
+    let ContextItemType::Message { role } = &item.item_type else {
+        return None;
+    };
+    let participant = item
+        .metadata
+        .tags
+        .get(PARTICIPANT_TAG)
+        .cloned()
+        .unwrap_or_else(|| item.metadata.agent_id.clone());
+    Some(SessionTurn {
+        participant,
+        role: *role,
+        content: item.content.text.clone(),
+        timestamp_ms: item.metadata.timestamp_ms,
+    })
+}
+
+fn unknown_session(session_id: &str) -> LoomError {
+    LoomError::StorageError(format!("unknown session: {session_id}"))
+}
@@ -9,24 +9,64 @@
 //! - Automatically records user messages, LLM responses, and tool calls
 //! - Uses `AgentContext` for unified context retrieval
 //! - Supports both new `AgentContext` and simple `MemoryBuffer`
+//!
+//! # Guardrails
+//!
+//! Configured guardrails (`CognitiveConfig::guardrails`) run over inbound event text in
+//! perceive, and over tool arguments and the final answer in act. Blocked input is
+//! answered with the configured `blocked_response` without calling the LLM.
+//!
+//! # Tool access
+//!
+//! Tools outside `CognitiveConfig::allowed_tools`, or matching `denied_tools`, are left
+//! out of the perception's tool list and refused in act with an error observation. The
+//! agent's `tools.allowed`/`tools.denied` parameters can take further tools away on init.
+//!
+//! # Streaming
+//!
+//! With a response sink set, SingleShot answers and post-tool refinements are streamed
+//! from the LLM; ReAct steps are not, since they must be parsed before anything is said.
+//!
+//! # Bootstrap
+//!
+//! A seeded loop (`CognitiveLoop::bootstrap`) has its seed prompts appended to the system
+//! prompt and its seed documents written to the `AgentContext`'s store (an in-memory one
+//! without a context). Perceive adds the seed chunks most relevant to the goal to the
+//! perception's context.
+//!
+//! # Trace log
+//!
+//! With an `AgentContext` set, each cycle's steps, tool observations and final plan are
+//! recorded as `ToolTrace` items sharing a trace id (see `trace_log`), and perceive adds
+//! the agent's most recent failed tool calls to the perception's context.
 
+use std::collections::HashMap;
 use std::sync::Arc;
 use std::time::Instant;
 
 use async_trait::async_trait;
 use serde_json::{json, Value};
-use tracing::{debug, info, warn};
-
-use super::llm::LlmClient;
-use crate::context::{AgentContext, PromptBundle};
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
+use tracing::{debug, error, info, warn, Instrument};
+
+use super::llm::{GenerateOptions, LlmClient, LlmResponse};
+use crate::context::{
+    AgentContext, InMemoryStore, MemoryStore, ModelBudget, PromptBundle, TokenBudget,
+};
 use crate::proto::{AgentState, Event};
-use crate::tools::ToolRegistry;
+use crate::tools::caller::inherit_caller;
+use crate::tools::{caller_agent_id, ToolRegistry};
 use crate::Result;
 
+use super::bootstrap::{relevant_seed_chunks, BootstrapSeed};
 use super::config::{CognitiveConfig, ThinkingStrategy};
+use super::guardrails::{GuardStage, Guardrail, GuardrailViolation, Guardrails};
 use super::loop_trait::{CognitiveLoop, ExecutionResult, Perception};
 use super::memory_buffer::MemoryBuffer;
+use super::streaming::ResponseSink;
 use super::thought::{Observation, Plan, ThoughtStep, ToolCall};
+use super::trace_log::{self, ToolAttempt};
 
 /// A simple implementation of the CognitiveLoop trait.
 ///
@@ -71,12 +111,71 @@ pub struct SimpleCognitiveLoop {
 
     /// Correlation ID for tracing
     correlation_id: Option<String>,
+
+    /// Content filters for input, output and tool arguments
+    guardrails: Guardrails,
+
+    /// Input blocked in the current cycle's perceive phase
+    blocked: Option<GuardrailViolation>,
+
+    /// Receives answer text as it is generated (see `CognitiveLoop::set_response_sink`)
+    response_sink: Option<ResponseSink>,
+
+    /// Store and agent id of the seed documents (see `CognitiveLoop::bootstrap`)
+    seeded: Option<(Arc<dyn MemoryStore>, String)>,
+
+    /// Trace log of the current cycle, while a context is set
+    trace: Option<CycleTrace>,
+}
+
+/// Trace items recorded for the current cycle
+struct CycleTrace {
+    trace_id: String,
+    /// Item of each recorded step, by step number
+    steps: Vec<(usize, String)>,
+    /// Every item recorded so far, for the plan item to relate to
+    items: Vec<String>,
 }
 
+impl CycleTrace {
+    fn new(trace_id: String) -> Self {
+        Self {
+            trace_id,
+            steps: Vec::new(),
+            items: Vec::new(),
+        }
+    }
+
+    fn step_item(&self, step: usize) -> Option<String> {
+        self.steps
+            .iter()
+            .find(|(n, _)| *n == step)
+            .map(|(_, id)| id.clone())
+    }
+}
+
+/// Event metadata keys that carry the request text (see `Perception::from_event`)
+const INPUT_METADATA_KEYS: [&str; 2] = ["goal", "instruction"];
+
+/// Seed chunks added to each perception's context
+const SEED_CHUNKS_PER_CYCLE: usize = 3;
+
+/// Past tool failures added to each perception's context
+const TOOL_FAILURES_PER_CYCLE: usize = 3;
+
 impl SimpleCognitiveLoop {
     /// Create a new SimpleCognitiveLoop
     pub fn new(config: CognitiveConfig, llm: Arc<LlmClient>, tools: Arc<ToolRegistry>) -> Self {
         let memory = MemoryBuffer::new(config.memory_window_size);
+        // Fail closed: a guardrail that cannot be built must not silently let text through
+        let guardrails = Guardrails::from_config(&config.guardrails).unwrap_or_else(|e| {
+            error!(
+                target = "cognitive.guardrails",
+                error = %e,
+                "Invalid guardrail config; blocking all input"
+            );
+            Guardrails::deny_all(format!("invalid guardrail config: {}", e))
+        });
         Self {
             config,
             llm,
@@ -84,7 +183,38 @@ impl SimpleCognitiveLoop {
             memory,
             context: None,
             correlation_id: None,
+            guardrails,
+            blocked: None,
+            response_sink: None,
+            seeded: None,
+            trace: None,
+        }
+    }
+
+    /// Add a guardrail after the configured ones, for `stages` (empty for all)
+    pub fn with_guardrail(mut self, stages: &[GuardStage], guardrail: Arc<dyn Guardrail>) -> Self {
+        self.guardrails.add(stages, guardrail);
+        self
+    }
+
+    /// Run the input guardrails over the event's request text, rewriting it in place
+    async fn guard_input(&self, event: &mut Event) -> std::result::Result<(), GuardrailViolation> {
+        if !self.guardrails.covers(GuardStage::Input) {
+            return Ok(());
+        }
+        for key in INPUT_METADATA_KEYS {
+            if let Some(text) = event.metadata.get(key).cloned() {
+                let checked = self.guardrails.check(GuardStage::Input, &text).await?;
+                event.metadata.insert(key.to_string(), checked);
+            }
+        }
+        if let Ok(text) = std::str::from_utf8(&event.payload) {
+            if !text.trim().is_empty() {
+                let checked = self.guardrails.check(GuardStage::Input, text).await?;
+                event.payload = checked.into_bytes();
+            }
         }
+        Ok(())
     }
 
     /// Set the AgentContext for context recording
@@ -104,6 +234,36 @@ impl SimpleCognitiveLoop {
         self
     }
 
+    /// Generate an answer, streaming it into the response sink when one is set.
+    /// Answers are not streamed while output guardrails are configured, since they may
+    /// rewrite or block the answer once it is complete.
+    async fn generate_answer(
+        &self,
+        bundle: &PromptBundle,
+        budget: Option<TokenBudget>,
+    ) -> Result<LlmResponse> {
+        match self.response_sink {
+            Some(ref sink) if !self.guardrails.covers(GuardStage::Output) => {
+                self.llm
+                    .generate_stream_with_options(bundle, budget, sink, self.generate_options())
+                    .await
+            }
+            _ => {
+                self.llm
+                    .generate_with_options(bundle, budget, self.generate_options())
+                    .await
+            }
+        }
+    }
+
+    /// Per-call LLM options from the agent's config
+    fn generate_options(&self) -> GenerateOptions {
+        GenerateOptions {
+            cache: self.config.llm_cache,
+            ..GenerateOptions::default()
+        }
+    }
+
     /// Build a PromptBundle from the current context
     fn build_prompt(&self, perception: &Perception, plan: &Plan) -> PromptBundle {
         let system = self.config.system_prompt.clone().unwrap_or_else(|| {
@@ -224,8 +384,9 @@ impl SimpleCognitiveLoop {
     pub(crate) fn parse_llm_response(&self, text: &str) -> ParsedResponse {
         let text = text.trim();
 
-        // Check for final answer
-        if let Some(idx) = text.to_uppercase().find("FINAL ANSWER:") {
+        // Check for final answer. ASCII-only case folding keeps byte offsets aligned
+        // with `text` (full Unicode uppercasing can change lengths, e.g. "ﬀ" -> "FF").
+        if let Some(idx) = text.to_ascii_uppercase().find("FINAL ANSWER:") {
             let answer = text[idx + 13..].trim().to_string();
             return ParsedResponse::FinalAnswer(answer);
         }
@@ -250,82 +411,141 @@ impl SimpleCognitiveLoop {
 
     /// Extract a tool call from text
     pub(crate) fn extract_tool_call(&self, text: &str) -> Option<ToolCall> {
-        // Look for JSON-like tool call patterns
-        // Pattern 1: {"tool": "name", "args": {...}}
-        // Pattern 2: {"action": "name", "arguments": {...}}
-
-        // Find JSON object boundaries
-        let start = text.find('{')?;
-        let end = text.rfind('}')?;
-        if end <= start {
-            return None;
-        }
-
-        let json_str = &text[start..=end];
-        let parsed: Value = serde_json::from_str(json_str).ok()?;
-
-        // Try different patterns
-        let (name, args) = if let Some(tool) = parsed.get("tool") {
-            let name = tool.as_str()?.to_string();
-            let args = parsed.get("args").cloned().unwrap_or(json!({}));
-            (name, args)
-        } else if let Some(action) = parsed.get("action") {
-            let name = action.as_str()?.to_string();
-            let args = parsed
-                .get("arguments")
-                .or_else(|| parsed.get("input"))
-                .cloned()
-                .unwrap_or(json!({}));
-            (name, args)
-        } else if let Some(name) = parsed.get("name") {
-            let name = name.as_str()?.to_string();
-            let args = parsed
-                .get("arguments")
-                .or_else(|| parsed.get("input"))
-                .or_else(|| parsed.get("parameters"))
-                .cloned()
-                .unwrap_or(json!({}));
-            (name, args)
-        } else {
-            return None;
-        };
+        extract_tool_call(text)
+    }
+
+    /// Execute tool calls via ToolRegistry, up to `max_parallel_tools` at a time.
+    ///
+    /// Observations are returned in the order of `tool_calls`, whatever order the calls
+    /// finish in.
+    async fn execute_tools(&self, tool_calls: Vec<ToolCall>) -> Vec<Observation> {
+        let limit = self.config.max_parallel_tools.max(1);
+        if limit == 1 || tool_calls.len() < 2 {
+            let mut observations = Vec::with_capacity(tool_calls.len());
+            for tool_call in &tool_calls {
+                observations.push(execute_tool(&self.tools, tool_call).await);
+            }
+            return observations;
+        }
+
+        let semaphore = Arc::new(Semaphore::new(limit));
+        let caller = caller_agent_id();
+        let mut running = JoinSet::new();
+        for (index, tool_call) in tool_calls.iter().cloned().enumerate() {
+            let tools = Arc::clone(&self.tools);
+            let semaphore = Arc::clone(&semaphore);
+            let caller = caller.clone();
+            running.spawn(
+                async move {
+                    let _permit = semaphore.acquire_owned().await;
+                    let observation =
+                        inherit_caller(caller, execute_tool(&tools, &tool_call)).await;
+                    (index, observation)
+                }
+                .in_current_span(),
+            );
+        }
 
-        Some(ToolCall::new(name, args))
+        let mut observations: Vec<Option<Observation>> = tool_calls.iter().map(|_| None).collect();
+        while let Some(joined) = running.join_next().await {
+            match joined {
+                Ok((index, observation)) => observations[index] = Some(observation),
+                Err(e) => warn!(target = "cognitive.act", error = %e, "Tool task failed"),
+            }
+        }
+        observations
+            .into_iter()
+            .zip(&tool_calls)
+            .map(|(observation, tool_call)| {
+                observation
+                    .unwrap_or_else(|| Observation::error(&tool_call.name, "tool task panicked", 0))
+            })
+            .collect()
     }
 
-    /// Execute a single tool call via ToolRegistry
-    async fn execute_tool(&self, tool_call: &ToolCall) -> Observation {
-        let started = Instant::now();
+    /// Record the plan's steps that are not in the cycle's trace log yet
+    async fn trace_steps(&mut self, plan: &Plan) {
+        let Some(ref context) = self.context else {
+            return;
+        };
+        let trace = self
+            .trace
+            .get_or_insert_with(|| CycleTrace::new(trace_log::new_trace_id()));
+        for step in &plan.steps {
+            if trace.step_item(step.step).is_some() {
+                continue;
+            }
+            match trace_log::record_step(context, &trace.trace_id, step).await {
+                Ok(id) => {
+                    trace.steps.push((step.step, id.clone()));
+                    trace.items.push(id);
+                }
+                Err(e) => warn!(target = "cognitive.trace", error = %e, "Failed to record step"),
+            }
+        }
+    }
 
-        match self
-            .tools
-            .call(&tool_call.name, tool_call.arguments.clone())
+    /// Record the observations of the steps at `indices` in the cycle's trace log
+    async fn trace_observations(&mut self, plan: &Plan, indices: &[usize]) {
+        let (Some(context), Some(trace)) = (&self.context, &mut self.trace) else {
+            return;
+        };
+        for &index in indices {
+            let step = &plan.steps[index];
+            let Some(ref observation) = step.observation else {
+                continue;
+            };
+            let step_item = trace.step_item(step.step);
+            match trace_log::record_observation(
+                context,
+                &trace.trace_id,
+                step,
+                observation,
+                step_item,
+            )
             .await
-        {
-            Ok(result) => {
-                let latency_ms = started.elapsed().as_millis() as u64;
-                let output =
-                    serde_json::to_string_pretty(&result).unwrap_or_else(|_| result.to_string());
-                Observation::success(&tool_call.name, output, latency_ms)
-            }
-            Err(e) => {
-                let latency_ms = started.elapsed().as_millis() as u64;
-                Observation::error(&tool_call.name, e.to_string(), latency_ms)
+            {
+                Ok(id) => trace.items.push(id),
+                Err(e) => {
+                    warn!(target = "cognitive.trace", error = %e, "Failed to record observation")
+                }
             }
         }
     }
 
-    /// Get available tools from ActionBroker
+    /// Get available tools from ActionBroker, minus those the config does not allow
     fn get_available_tools(&self) -> Vec<String> {
         self.tools
             .list_tools()
             .into_iter()
-            .take(self.config.max_tools_exposed)
             .map(|tool| tool.name())
+            .filter(|name| self.config.allows_tool(name))
+            .take(self.config.max_tools_exposed)
             .collect()
     }
 }
 
+/// Execute a single tool call via ToolRegistry
+async fn execute_tool(tools: &ToolRegistry, tool_call: &ToolCall) -> Observation {
+    let started = Instant::now();
+
+    match tools
+        .call(&tool_call.name, tool_call.arguments.clone())
+        .await
+    {
+        Ok(result) => {
+            let latency_ms = started.elapsed().as_millis() as u64;
+            let output =
+                serde_json::to_string_pretty(&result).unwrap_or_else(|_| result.to_string());
+            Observation::success(&tool_call.name, output, latency_ms)
+        }
+        Err(e) => {
+            let latency_ms = started.elapsed().as_millis() as u64;
+            Observation::error(&tool_call.name, e.to_string(), latency_ms)
+        }
+    }
+}
+
 /// Parsed response from LLM
 #[derive(Debug)]
 pub(crate) enum ParsedResponse {
@@ -339,7 +559,7 @@ pub(crate) enum ParsedResponse {
 
 #[async_trait]
 impl CognitiveLoop for SimpleCognitiveLoop {
-    async fn perceive(&mut self, event: Event, _state: &AgentState) -> Result<Perception> {
+    async fn perceive(&mut self, mut event: Event, _state: &AgentState) -> Result<Perception> {
         debug!(
             target = "cognitive.perceive",
             event_id = %event.id,
@@ -347,9 +567,19 @@ impl CognitiveLoop for SimpleCognitiveLoop {
             "Perceiving event"
         );
 
-        // Record incoming event in context if available
+        // Guard the request text; blocked text never reaches context, memory or the LLM
+        self.blocked = self.guard_input(&mut event).await.err();
+        if self.blocked.is_some() {
+            for key in INPUT_METADATA_KEYS {
+                event.metadata.remove(key);
+            }
+            event.payload.clear();
+        }
+
+        // Record incoming event in context if available, and start the cycle's trace
         if let Some(ref context) = self.context {
             context.record_event(&event).await.ok();
+            self.trace = Some(CycleTrace::new(trace_log::trace_id_for(&event)));
         }
 
         // Build base perception from event
@@ -365,6 +595,39 @@ impl CognitiveLoop for SimpleCognitiveLoop {
             .map(|item| item.content.clone())
             .collect();
 
+        // Add the seed documents relevant to the request
+        if let (Some((store, agent_id)), Some(goal)) = (&self.seeded, &perception.goal) {
+            match relevant_seed_chunks(store.as_ref(), agent_id, goal, SEED_CHUNKS_PER_CYCLE).await
+            {
+                Ok(chunks) => perception.context.extend(chunks),
+                Err(e) => warn!(
+                    target = "cognitive.perceive",
+                    error = %e,
+                    "Failed to retrieve seed documents"
+                ),
+            }
+        }
+
+        // Remind the LLM of tool calls that failed before
+        if let Some(ref context) = self.context {
+            match trace_log::past_tool_failures(
+                context.store().as_ref(),
+                context.agent_id(),
+                TOOL_FAILURES_PER_CYCLE,
+            )
+            .await
+            {
+                Ok(failures) => perception
+                    .context
+                    .extend(failures.iter().map(ToolAttempt::describe)),
+                Err(e) => warn!(
+                    target = "cognitive.perceive",
+                    error = %e,
+                    "Failed to retrieve past tool failures"
+                ),
+            }
+        }
+
         debug!(
             target = "cognitive.perceive",
             goal = ?perception.goal,
@@ -385,12 +648,19 @@ impl CognitiveLoop for SimpleCognitiveLoop {
         );
 
         let mut plan = Plan::with_goal(perception.goal.clone().unwrap_or_default());
+        // Fit prompts to the model the event was routed to, when it was routed
+        let budget = ModelBudget::from_metadata(&perception.event.metadata).map(|b| b.budget);
+
+        if self.blocked.is_some() {
+            plan.complete_with_answer(&self.config.guardrails.blocked_response);
+            return Ok(plan);
+        }
 
         match self.config.thinking_strategy {
             ThinkingStrategy::SingleShot => {
                 // Single LLM call, no tool use
                 let bundle = self.build_prompt(perception, &plan);
-                let response = self.llm.generate(&bundle, None).await?;
+                let response = self.generate_answer(&bundle, budget).await?;
 
                 plan.complete_with_answer(&response.text);
             }
@@ -405,7 +675,10 @@ impl CognitiveLoop for SimpleCognitiveLoop {
                     );
 
                     let bundle = self.build_prompt(perception, &plan);
-                    let response = self.llm.generate(&bundle, None).await?;
+                    let response = self
+                        .llm
+                        .generate_with_options(&bundle, budget, self.generate_options())
+                        .await?;
 
                     match self.parse_llm_response(&response.text) {
                         ParsedResponse::FinalAnswer(answer) => {
@@ -443,6 +716,8 @@ impl CognitiveLoop for SimpleCognitiveLoop {
             }
         }
 
+        self.trace_steps(&plan).await;
+
         info!(
             target = "cognitive.think",
             steps = plan.steps.len(),
@@ -463,52 +738,99 @@ impl CognitiveLoop for SimpleCognitiveLoop {
 
         // Clone plan to allow modifications
         let mut plan = plan.clone();
+        self.trace_steps(&plan).await;
+
+        // Pending tool calls come from one think phase and do not depend on each other
+        let pending: Vec<usize> = plan
+            .steps
+            .iter()
+            .enumerate()
+            .filter(|(_, step)| step.tool_call.is_some() && step.observation.is_none())
+            .map(|(index, _)| index)
+            .collect();
 
-        // Execute pending tool calls
-        for step in &mut plan.steps {
-            if let Some(ref tool_call) = step.tool_call {
-                if step.observation.is_none() {
-                    debug!(
+        // Refuse tools the agent may not use and guard the arguments of the others; a
+        // refused call is answered with an error instead of running
+        let mut refused = Vec::new();
+        for &index in &pending {
+            if let Some(tool_call) = plan.steps[index].tool_call.as_mut() {
+                if !self.config.allows_tool(&tool_call.name) {
+                    warn!(
                         target = "cognitive.act",
                         tool = %tool_call.name,
-                        "Executing tool"
+                        "Refusing tool the agent may not use"
                     );
+                    let message =
+                        format!("tool '{}' is not allowed for this agent", tool_call.name);
+                    refused.push((index, Observation::error(&tool_call.name, message, 0)));
+                    continue;
+                }
+                match self.guardrails.check_arguments(&tool_call.arguments).await {
+                    Ok(arguments) => tool_call.arguments = arguments,
+                    Err(violation) => refused.push((
+                        index,
+                        Observation::error(&tool_call.name, violation.to_string(), 0),
+                    )),
+                }
+            }
+        }
+        for (index, observation) in refused {
+            plan.steps[index].observation = Some(observation);
+        }
+        let traced = pending.clone();
+        let pending: Vec<usize> = pending
+            .into_iter()
+            .filter(|&index| plan.steps[index].observation.is_none())
+            .collect();
+        let tool_calls: Vec<ToolCall> = pending
+            .iter()
+            .filter_map(|&index| plan.steps[index].tool_call.clone())
+            .collect();
 
-                    // Record tool call in context if available
-                    let call_id = if let Some(ref context) = self.context {
-                        context
-                            .record_tool_call(&tool_call.name, tool_call.arguments.clone())
-                            .await
-                            .ok()
-                    } else {
-                        None
-                    };
-
-                    let observation = self.execute_tool(tool_call).await;
-
-                    // Record tool result in context if available
-                    if let Some(ref context) = self.context {
-                        context
-                            .record_tool_result(
-                                &tool_call.name,
-                                observation.success,
-                                serde_json::json!({ "output": &observation.output }),
-                                call_id,
-                            )
-                            .await
-                            .ok();
-                    }
+        // Record tool calls in context if available, in step order
+        let mut call_ids = Vec::with_capacity(tool_calls.len());
+        for tool_call in &tool_calls {
+            debug!(
+                target = "cognitive.act",
+                tool = %tool_call.name,
+                "Executing tool"
+            );
+            call_ids.push(if let Some(ref context) = self.context {
+                context
+                    .record_tool_call(&tool_call.name, tool_call.arguments.clone())
+                    .await
+                    .ok()
+            } else {
+                None
+            });
+        }
 
-                    // Add to working memory
-                    if observation.success {
-                        self.memory
-                            .add_observation(&tool_call.name, &observation.output);
-                    }
+        let observations = self.execute_tools(tool_calls).await;
+
+        for ((index, call_id), observation) in pending.into_iter().zip(call_ids).zip(observations) {
+            let tool_name = observation.tool_name.clone();
+
+            // Record tool result in context if available
+            if let Some(ref context) = self.context {
+                context
+                    .record_tool_result(
+                        &tool_name,
+                        observation.success,
+                        serde_json::json!({ "output": &observation.output }),
+                        call_id,
+                    )
+                    .await
+                    .ok();
+            }
 
-                    step.observation = Some(observation);
-                }
+            // Add to working memory
+            if observation.success {
+                self.memory.add_observation(&tool_name, &observation.output);
             }
+
+            plan.steps[index].observation = Some(observation);
         }
+        self.trace_observations(&plan, &traced).await;
 
         // If we executed tools and refinement is enabled, do another think cycle
         if self.config.refine_after_tools && !plan.observations().is_empty() && !plan.complete {
@@ -518,7 +840,7 @@ impl CognitiveLoop for SimpleCognitiveLoop {
                 &plan,
             );
 
-            if let Ok(response) = self.llm.generate(&bundle, None).await {
+            if let Ok(response) = self.generate_answer(&bundle, None).await {
                 plan.complete_with_answer(&response.text);
             }
         }
@@ -529,10 +851,25 @@ impl CognitiveLoop for SimpleCognitiveLoop {
             .metadata
             .insert("last_goal".to_string(), plan.goal.clone());
 
+        // Guard the answer; a blocked answer is replaced with the blocked response
+        let mut error = self.blocked.take().map(|violation| violation.to_string());
+        if let Some(answer) = plan.final_answer.take() {
+            plan.final_answer = Some(
+                match self.guardrails.check(GuardStage::Output, &answer).await {
+                    Ok(answer) => answer,
+                    Err(violation) => {
+                        error = Some(violation.to_string());
+                        self.config.guardrails.blocked_response.clone()
+                    }
+                },
+            );
+        }
+
         // Build result
         let result = ExecutionResult {
-            goal_achieved: plan.complete,
+            goal_achieved: plan.complete && error.is_none(),
             response: plan.final_answer.clone(),
+            error,
             ..Default::default()
         };
 
@@ -541,6 +878,15 @@ impl CognitiveLoop for SimpleCognitiveLoop {
             self.memory.add_agent_response(response);
         }
 
+        // Close the cycle's trace with the plan as acted on
+        if let (Some(context), Some(trace)) = (&self.context, self.trace.take()) {
+            if let Err(e) =
+                trace_log::record_plan(context, &trace.trace_id, &plan, trace.items).await
+            {
+                warn!(target = "cognitive.trace", error = %e, "Failed to record plan");
+            }
+        }
+
         info!(
             target = "cognitive.act",
             goal_achieved = result.goal_achieved,
@@ -590,7 +936,11 @@ impl CognitiveLoop for SimpleCognitiveLoop {
             history: vec![],
         };
 
-        match self.llm.generate(&bundle, None).await {
+        match self
+            .llm
+            .generate_with_options(&bundle, None, self.generate_options())
+            .await
+        {
             Ok(response) => {
                 debug!(
                     target = "cognitive.reflect",
@@ -617,6 +967,84 @@ impl CognitiveLoop for SimpleCognitiveLoop {
     fn memory_buffer_mut(&mut self) -> &mut MemoryBuffer {
         &mut self.memory
     }
+
+    fn restrict_tools(&mut self, parameters: &HashMap<String, String>) -> Result<()> {
+        self.config.apply_tool_parameters(parameters);
+        Ok(())
+    }
+
+    fn set_response_sink(&mut self, sink: Option<ResponseSink>) {
+        self.response_sink = sink;
+    }
+
+    async fn bootstrap(&mut self, agent_id: &str, seed: &BootstrapSeed) -> Result<usize> {
+        self.config.system_prompt = seed.system_prompt(self.config.system_prompt.as_deref());
+        if seed.documents.is_empty() {
+            return Ok(0);
+        }
+        let store: Arc<dyn MemoryStore> = match self.context {
+            Some(ref context) => Arc::clone(context.store()),
+            None => InMemoryStore::new(),
+        };
+        let written = seed.seed(store.as_ref(), agent_id).await?;
+        self.seeded = Some((store, agent_id.to_string()));
+        Ok(written)
+    }
+}
+
+/// Extract a tool call from free-form LLM output.
+///
+/// Accepts the first JSON object in `text` using any of the key conventions models
+/// tend to produce:
+/// - `{"tool": "name", "args": {...}}`
+/// - `{"action": "name", "arguments" | "input": {...}}`
+/// - `{"name": "name", "arguments" | "input" | "parameters": {...}}`
+///
+/// The input is untrusted model output: anything that is not one of these shapes
+/// yields `None`.
+pub fn extract_tool_call(text: &str) -> Option<ToolCall> {
+    let start = text.find('{')?;
+    let end = text.rfind('}')?;
+    if end <= start {
+        return None;
+    }
+
+    // Prefer the widest `{...}` span; fall back to the first complete object so
+    // trailing prose containing `}` does not hide the call.
+    let parsed: Value = serde_json::from_str(&text[start..=end]).ok().or_else(|| {
+        serde_json::Deserializer::from_str(&text[start..])
+            .into_iter::<Value>()
+            .next()?
+            .ok()
+    })?;
+
+    // Try different patterns
+    let (name, args) = if let Some(tool) = parsed.get("tool") {
+        let name = tool.as_str()?.to_string();
+        let args = parsed.get("args").cloned().unwrap_or(json!({}));
+        (name, args)
+    } else if let Some(action) = parsed.get("action") {
+        let name = action.as_str()?.to_string();
+        let args = parsed
+            .get("arguments")
+            .or_else(|| parsed.get("input"))
+            .cloned()
+            .unwrap_or(json!({}));
+        (name, args)
+    } else if let Some(name) = parsed.get("name") {
+        let name = name.as_str()?.to_string();
+        let args = parsed
+            .get("arguments")
+            .or_else(|| parsed.get("input"))
+            .or_else(|| parsed.get("parameters"))
+            .cloned()
+            .unwrap_or(json!({}));
+        (name, args)
+    } else {
+        return None;
+    };
+
+    Some(ToolCall::new(name, args))
 }
 
 #[cfg(test)]
@@ -706,6 +1134,31 @@ mod tests {
         assert_eq!(tc.name, "translate");
     }
 
+    #[test]
+    fn test_parse_final_answer_after_non_ascii_text() {
+        let config = CognitiveConfig::default();
+        let llm = Arc::new(LlmClient::from_env().unwrap());
+        let tools = Arc::new(ToolRegistry::new());
+        let loop_impl = SimpleCognitiveLoop::new(config, llm, tools);
+
+        // "ﬀ" uppercases to "FF" (3 bytes -> 2), which used to shift the answer offset
+        match loop_impl.parse_llm_response("ﬀ Final Answer: 42") {
+            ParsedResponse::FinalAnswer(answer) => assert_eq!(answer, "42"),
+            _ => panic!("Expected FinalAnswer"),
+        }
+    }
+
+    #[test]
+    fn test_extract_tool_call_ignores_trailing_braces() {
+        let tc =
+            extract_tool_call(r#"Call {"tool": "search", "args": {"q": "x"}} and done }"#).unwrap();
+        assert_eq!(tc.name, "search");
+        assert_eq!(tc.arguments, json!({"q": "x"}));
+
+        assert!(extract_tool_call("no json } here {").is_none());
+        assert!(extract_tool_call(r#"{"tool": 7}"#).is_none());
+    }
+
     #[test]
     fn test_with_context_builder() {
         let config = CognitiveConfig::default();
@@ -0,0 +1,106 @@
+//! Response streaming to the originating requester.
+//!
+//! A [`CognitiveAgent`](super::CognitiveAgent) built with `with_response_streaming` hands
+//! its loop a [`ResponseSink`] when a `user.query` arrives with a reply topic. The loop
+//! sends LLM output into the sink as it is generated; the agent publishes each chunk to
+//! the reply topic as a [`RESPONSE_PARTIAL_EVENT`], then the complete answer as a
+//! [`RESPONSE_FINAL_EVENT`]. Partials carry the request's correlation id and a
+//! [`STREAM_SEQ_KEY`] sequence number starting at 0. A namespaced agent's partials stay
+//! inside its namespace, like its other events.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use tokio::sync::mpsc;
+use tracing::warn;
+
+use crate::messaging::envelope::keys;
+use crate::namespace::Namespace;
+use crate::{Event, EventBus};
+
+/// Event type of a streamed response chunk (payload: UTF-8 text)
+pub const RESPONSE_PARTIAL_EVENT: &str = "response.partial";
+
+/// Event type of the complete response closing a stream (payload: UTF-8 text)
+pub const RESPONSE_FINAL_EVENT: &str = "response.final";
+
+/// Metadata key of a partial's position in its stream
+pub const STREAM_SEQ_KEY: &str = "stream.seq";
+
+/// Receives response text as it is generated
+#[derive(Debug, Clone)]
+pub struct ResponseSink {
+    tx: mpsc::UnboundedSender<String>,
+}
+
+impl ResponseSink {
+    /// Sink and the receiver of the chunks sent into it
+    pub fn channel() -> (Self, mpsc::UnboundedReceiver<String>) {
+        let (tx, rx) = mpsc::unbounded_channel();
+        (Self { tx }, rx)
+    }
+
+    /// Send a chunk (empty chunks are skipped); false once the receiver is gone
+    pub fn send(&self, delta: &str) -> bool {
+        delta.is_empty() || self.tx.send(delta.to_string()).is_ok()
+    }
+
+    /// Whether the receiver is gone
+    pub fn is_closed(&self) -> bool {
+        self.tx.is_closed()
+    }
+}
+
+/// Where the partials of one request go
+#[derive(Debug, Clone)]
+pub(crate) struct PartialTarget {
+    pub(crate) topic: String,
+    pub(crate) correlation_id: String,
+    pub(crate) caused_by: String,
+    pub(crate) source: String,
+    pub(crate) priority: i32,
+    pub(crate) namespace: Option<Namespace>,
+}
+
+/// Publish every chunk received on `rx` as a [`RESPONSE_PARTIAL_EVENT`] until all senders
+/// are dropped; returns the number published
+pub(crate) async fn forward_partials(
+    event_bus: Arc<EventBus>,
+    target: PartialTarget,
+    mut rx: mpsc::UnboundedReceiver<String>,
+) -> u64 {
+    let topic = match &target.namespace {
+        Some(ns) => ns.scope(&target.topic),
+        None => target.topic.clone(),
+    };
+    let mut seq = 0u64;
+    while let Some(delta) = rx.recv().await {
+        let mut metadata = HashMap::from([
+            (
+                keys::CORRELATION_ID.to_string(),
+                target.correlation_id.clone(),
+            ),
+            (keys::CAUSED_BY.to_string(), target.caused_by.clone()),
+            (STREAM_SEQ_KEY.to_string(), seq.to_string()),
+        ]);
+        if let Some(ns) = &target.namespace {
+            metadata.insert(crate::tenancy::TENANT_KEY.to_string(), ns.to_string());
+        }
+        let evt = Event {
+            id: format!("evt_partial_{}_{}", target.caused_by, seq),
+            r#type: RESPONSE_PARTIAL_EVENT.to_string(),
+            timestamp_ms: chrono::Utc::now().timestamp_millis(),
+            source: target.source.clone(),
+            metadata,
+            payload: delta.into_bytes(),
+            confidence: 1.0,
+            tags: vec![],
+            priority: target.priority,
+        };
+        if let Err(e) = event_bus.publish(&topic, evt).await {
+            warn!(reply_to = %topic, error = %e, "Failed to publish response partial");
+        }
+        seq += 1;
+    }
+    seq
+}
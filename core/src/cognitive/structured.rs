@@ -0,0 +1,102 @@
+//! Structured agent results, published apart from conversational text.
+//!
+//! A cognitive loop attaches [`StructuredOutput`]s to its `ExecutionResult`; the agent
+//! publishes each one as a [`RESULT_EVENT`] on `result.<agent_id>` (see [`result_topic`]).
+//! The payload is JSON, and the metadata names its kind and, optionally, the schema it
+//! follows, so downstream agents can subscribe to typed results without parsing replies.
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::proto::Event;
+
+/// Prefix of the topics agents publish structured results on
+pub const RESULT_TOPIC_PREFIX: &str = "result.";
+
+/// Action carrying a structured result; the agent publishes its payload as a
+/// [`RESULT_EVENT`] to its result topic instead of calling a tool
+pub const RESULT_ACTION: &str = "agent.result";
+
+/// Event type of a published structured result (payload: JSON)
+pub const RESULT_EVENT: &str = "agent.result";
+
+/// Metadata keys of a result event
+pub mod keys {
+    /// Payload type name, e.g. `"trade.plan"`
+    pub const KIND: &str = "result.kind";
+    /// Schema reference (URI or registered name) the payload follows
+    pub const SCHEMA: &str = "result.schema";
+    /// Always `"application/json"`
+    pub const CONTENT_TYPE: &str = "content_type";
+}
+
+/// Topic `agent_id` publishes structured results on: `result.<agent_id>`
+pub fn result_topic(agent_id: &str) -> String {
+    format!("{}{}", RESULT_TOPIC_PREFIX, agent_id)
+}
+
+/// Machine-consumable result of a cognitive cycle
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StructuredOutput {
+    /// Payload type name
+    pub kind: String,
+    /// Schema reference the payload follows, if any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub schema: Option<String>,
+    pub payload: serde_json::Value,
+}
+
+impl StructuredOutput {
+    pub fn new(kind: impl Into<String>, payload: serde_json::Value) -> Self {
+        Self {
+            kind: kind.into(),
+            schema: None,
+            payload,
+        }
+    }
+
+    /// Output of a serializable value
+    pub fn from_value<T: Serialize>(kind: impl Into<String>, value: &T) -> crate::Result<Self> {
+        Ok(Self::new(kind, serde_json::to_value(value)?))
+    }
+
+    pub fn with_schema(mut self, schema: impl Into<String>) -> Self {
+        self.schema = Some(schema.into());
+        self
+    }
+
+    /// Parse the payload into `T`
+    pub fn parse<T: serde::de::DeserializeOwned>(&self) -> crate::Result<T> {
+        Ok(serde_json::from_value(self.payload.clone())?)
+    }
+
+    /// Metadata describing the payload (kind, schema, content type)
+    pub fn metadata(&self) -> HashMap<String, String> {
+        let mut metadata = HashMap::from([
+            (keys::KIND.to_string(), self.kind.clone()),
+            (
+                keys::CONTENT_TYPE.to_string(),
+                "application/json".to_string(),
+            ),
+        ]);
+        if let Some(ref schema) = self.schema {
+            metadata.insert(keys::SCHEMA.to_string(), schema.clone());
+        }
+        metadata
+    }
+
+    /// Read the output back from a [`RESULT_EVENT`]; `None` for other events or a
+    /// payload that is not JSON
+    pub fn from_event(event: &Event) -> Option<Self> {
+        if event.r#type != RESULT_EVENT {
+            return None;
+        }
+        let payload = serde_json::from_slice(&event.payload).ok()?;
+        Some(Self {
+            kind: event.metadata.get(keys::KIND).cloned().unwrap_or_default(),
+            schema: event.metadata.get(keys::SCHEMA).cloned(),
+            payload,
+        })
+    }
+}
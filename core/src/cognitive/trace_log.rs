@@ -0,0 +1,275 @@
+//! Cognitive traces persisted to the context store.
+//!
+//! With an `AgentContext` set, `SimpleCognitiveLoop` records every cycle as
+//! `ContextItemType::ToolTrace` items sharing the cycle's trace id:
+//!
+//! - one `step` item per `ThoughtStep` produced by think,
+//! - one `observation` item per tool observation in act (related to its step item),
+//! - one `plan` item once act is done (related to all of the above).
+//!
+//! Items are tagged with [`trace_tags`], so failed attempts can be queried back: the
+//! loop adds the agent's most recent failures to each perception's context ("you tried
+//! this tool with these args and it failed"), which keeps it from repeating them.
+//!
+//! The trace id is the event's envelope `trace_id` when it has one, so cycles can be
+//! joined with the distributed trace of the request; otherwise one is generated.
+
+use serde_json::{json, Value};
+
+use super::thought::{Observation, Plan, ThoughtStep};
+use crate::context::{
+    AgentContext, ContextContent, ContextItem, ContextItemType, ContextMetadata, MemoryQuery,
+    MemoryStore,
+};
+use crate::messaging::envelope::keys;
+use crate::proto::Event;
+use crate::Result;
+
+/// Tags set on trace items
+pub mod trace_tags {
+    /// What the item records (see [`super::trace_kinds`])
+    pub const KIND: &str = "trace.kind";
+    /// Tool called by the step or observation
+    pub const TOOL: &str = "trace.tool";
+    /// `"true"` or `"false"`, on observations
+    pub const SUCCESS: &str = "trace.success";
+    /// Step number within the plan
+    pub const STEP: &str = "trace.step";
+}
+
+/// Values of the [`trace_tags::KIND`] tag
+pub mod trace_kinds {
+    pub const PLAN: &str = "plan";
+    pub const STEP: &str = "step";
+    pub const OBSERVATION: &str = "observation";
+}
+
+/// Failures scanned for the most recent ones (items are listed newest first)
+const MAX_FAILURES_SCANNED: usize = 100;
+
+/// Trace id of the cycle handling `event`
+pub fn trace_id_for(event: &Event) -> String {
+    event
+        .metadata
+        .get(keys::TRACE_ID)
+        .filter(|id| !id.is_empty())
+        .cloned()
+        .unwrap_or_else(new_trace_id)
+}
+
+/// A fresh trace id, for cycles not started from an event
+pub fn new_trace_id() -> String {
+    format!("trace_{}", AgentContext::generate_id())
+}
+
+/// A failed tool call read back from the trace log
+#[derive(Debug, Clone)]
+pub struct ToolAttempt {
+    pub trace_id: String,
+    pub tool: String,
+    pub arguments: Value,
+    pub error: String,
+    pub timestamp_ms: i64,
+}
+
+impl ToolAttempt {
+    /// One-line note for a prompt's context
+    pub fn describe(&self) -> String {
+        format!(
+            "You tried tool `{}` with {} and it failed: {}",
+            self.tool, self.arguments, self.error
+        )
+    }
+
+    fn from_item(item: &ContextItem) -> Option<Self> {
+        let ContextItemType::ToolTrace { ref trace_id } = item.item_type else {
+            return None;
+        };
+        let raw = &item.content.raw;
+        Some(Self {
+            trace_id: trace_id.clone(),
+            tool: raw.get("tool")?.as_str()?.to_string(),
+            arguments: raw.get("arguments").cloned().unwrap_or(Value::Null),
+            error: raw
+                .get("error")
+                .and_then(Value::as_str)
+                .unwrap_or("unknown")
+                .to_string(),
+            timestamp_ms: item.timestamp(),
+        })
+    }
+}
+
+fn trace_item(
+    context: &AgentContext,
+    trace_id: &str,
+    kind: &str,
+    text: String,
+    raw: Value,
+    related: Vec<String>,
+) -> ContextItem {
+    let mut metadata = ContextMetadata::new(context.session_id().into(), context.agent_id().into())
+        .with_current_trace()
+        .with_tag(trace_tags::KIND.into(), kind.into());
+    metadata.related_items = related;
+    ContextItem {
+        id: AgentContext::generate_id(),
+        item_type: ContextItemType::ToolTrace {
+            trace_id: trace_id.to_string(),
+        },
+        content: ContextContent {
+            raw,
+            text,
+            token_count: None,
+            embedding: None,
+        },
+        metadata,
+    }
+}
+
+async fn store(context: &AgentContext, item: ContextItem) -> Result<String> {
+    let id = item.id.clone();
+    context.store().store(item).await?;
+    Ok(id)
+}
+
+/// Record a reasoning step (and the tool call it plans, if any)
+pub async fn record_step(
+    context: &AgentContext,
+    trace_id: &str,
+    step: &ThoughtStep,
+) -> Result<String> {
+    let mut text = format!("Thought {}: {}", step.step, step.reasoning);
+    if let Some(ref tool_call) = step.tool_call {
+        text.push_str(&format!(
+            "\nAction: {} with {}",
+            tool_call.name, tool_call.arguments
+        ));
+    }
+    let raw = json!({
+        "step": step.step,
+        "reasoning": &step.reasoning,
+        "tool": step.tool_call.as_ref().map(|c| &c.name),
+        "arguments": step.tool_call.as_ref().map(|c| &c.arguments),
+    });
+    let mut item = trace_item(context, trace_id, trace_kinds::STEP, text, raw, vec![]);
+    item.metadata = item
+        .metadata
+        .with_tag(trace_tags::STEP.into(), step.step.to_string());
+    if let Some(ref tool_call) = step.tool_call {
+        item.metadata = item
+            .metadata
+            .with_tag(trace_tags::TOOL.into(), tool_call.name.clone());
+    }
+    store(context, item).await
+}
+
+/// Record the observation of `step`'s tool call, related to the step's item
+pub async fn record_observation(
+    context: &AgentContext,
+    trace_id: &str,
+    step: &ThoughtStep,
+    observation: &Observation,
+    step_item: Option<String>,
+) -> Result<String> {
+    let arguments = step
+        .tool_call
+        .as_ref()
+        .map(|c| c.arguments.clone())
+        .unwrap_or(Value::Null);
+    let text = if observation.success {
+        format!(
+            "Tool `{}` with {} returned: {}",
+            observation.tool_name, arguments, observation.output
+        )
+    } else {
+        format!(
+            "Tool `{}` with {} failed: {}",
+            observation.tool_name,
+            arguments,
+            observation.error.as_deref().unwrap_or("unknown")
+        )
+    };
+    let raw = json!({
+        "step": step.step,
+        "tool": &observation.tool_name,
+        "arguments": arguments,
+        "success": observation.success,
+        "output": &observation.output,
+        "error": &observation.error,
+        "latency_ms": observation.latency_ms,
+    });
+    let mut item = trace_item(
+        context,
+        trace_id,
+        trace_kinds::OBSERVATION,
+        text,
+        raw,
+        step_item.into_iter().collect(),
+    );
+    // Failures are what later cycles need to remember
+    item.metadata = item
+        .metadata
+        .with_importance(if observation.success { 0.5 } else { 0.8 })
+        .with_tag(trace_tags::STEP.into(), step.step.to_string())
+        .with_tag(trace_tags::TOOL.into(), observation.tool_name.clone())
+        .with_tag(trace_tags::SUCCESS.into(), observation.success.to_string());
+    store(context, item).await
+}
+
+/// Record the plan as it stands after act, related to the cycle's other trace items
+pub async fn record_plan(
+    context: &AgentContext,
+    trace_id: &str,
+    plan: &Plan,
+    related: Vec<String>,
+) -> Result<String> {
+    let raw = serde_json::to_value(plan).unwrap_or(Value::Null);
+    let item = trace_item(
+        context,
+        trace_id,
+        trace_kinds::PLAN,
+        plan.to_summary(),
+        raw,
+        related,
+    );
+    store(context, item).await
+}
+
+/// All items of one trace, oldest first
+pub async fn trace_items(store: &dyn MemoryStore, trace_id: &str) -> Result<Vec<ContextItem>> {
+    let mut items = store
+        .query(
+            &MemoryQuery::new().with_types(vec![ContextItemType::ToolTrace {
+                trace_id: trace_id.to_string(),
+            }]),
+        )
+        .await?;
+    items.sort_by_key(|item| item.timestamp());
+    Ok(items)
+}
+
+/// `agent_id`'s most recent failed tool calls, newest first
+pub async fn past_tool_failures(
+    store: &dyn MemoryStore,
+    agent_id: &str,
+    limit: usize,
+) -> Result<Vec<ToolAttempt>> {
+    if limit == 0 {
+        return Ok(vec![]);
+    }
+    let query = MemoryQuery::new()
+        .for_agent(agent_id.to_string())
+        .with_tag(trace_tags::KIND, trace_kinds::OBSERVATION)
+        .with_tag(trace_tags::SUCCESS, "false")
+        .limit(MAX_FAILURES_SCANNED);
+    let mut attempts: Vec<ToolAttempt> = store
+        .query(&query)
+        .await?
+        .iter()
+        .filter_map(ToolAttempt::from_item)
+        .collect();
+    attempts.sort_by(|a, b| b.timestamp_ms.cmp(&a.timestamp_ms));
+    attempts.truncate(limit);
+    Ok(attempts)
+}
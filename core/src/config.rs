@@ -0,0 +1,330 @@
+//! `LoomConfig`: a TOML manifest for the settings Loom reads from environment variables
+//!
+//! Each variable maps to a key of a manifest section by its prefix: `LOOM_DASHBOARD_PORT`
+//! becomes `port` in `[dashboard]`, `VLLM_MODEL` becomes `model` in `[llm]` and a bare
+//! prefix such as `LOOM_DASHBOARD` becomes `enabled`. `LOOM_MCP_SERVERS` becomes
+//! `[[mcp_servers]]` tables. Secrets (`*_KEY`, `*_TOKEN`, `*_SECRET`, ...) are not
+//! written by default; they are listed in `env` and keep coming from the environment.
+//!
+//! ```toml
+//! env = ["VLLM_API_KEY"]
+//!
+//! [[mcp_servers]]
+//! name = "filesystem"
+//! command = "npx"
+//! args = ["-y", "@modelcontextprotocol/server-filesystem", "/data"]
+//!
+//! [dashboard]
+//! enabled = true
+//! port = 3030
+//!
+//! [llm]
+//! base_url = "http://localhost:8000/v1"
+//! model = "qwen2.5-7b"
+//! ```
+//!
+//! `loom-bridge-server config export [--include-secrets] [PATH]` writes the manifest
+//! equivalent to the current environment. At startup the manifest named by
+//! `LOOM_CONFIG` is applied to the environment; variables already set win. With
+//! `LOOM_CONFIG_STRICT=true`, every recognized variable set in the environment but not
+//! declared in the manifest is logged as a warning.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+use crate::tools::mcp::types::McpServerConfig;
+use crate::{LoomError, Result};
+
+/// Manifest path applied by `load_startup`
+pub const CONFIG_PATH_ENV: &str = "LOOM_CONFIG";
+
+/// Set to `true` to warn about undeclared variables
+pub const CONFIG_STRICT_ENV: &str = "LOOM_CONFIG_STRICT";
+
+const MCP_SERVERS_ENV: &str = "LOOM_MCP_SERVERS";
+
+/// Variable prefix → manifest section. More specific prefixes come first; a prefix
+/// without a trailing `_` is also a variable of its own (its `enabled` key).
+const SECTIONS: &[(&str, &str)] = &[
+    ("LOOM_DASHBOARD", "dashboard"),
+    ("LOOM_LLM_CACHE", "llm_cache"),
+    ("LOOM_BRIDGE_", "bridge"),
+    ("LOOM_OPENAI", "openai"),
+    ("LOOM_A2A", "a2a"),
+    ("LOOM_PEER_", "peer"),
+    ("LOOM_MEMORY_", "memory"),
+    ("LOOM_SENTINEL_", "sentinel"),
+    ("LOOM_USAGE_EXPORT_", "usage_export"),
+    ("LOOM_CRITICAL_", "critical"),
+    ("LOOM_", "loom"),
+    ("VLLM_", "llm"),
+    ("OTEL_", "telemetry"),
+    ("TTS_", "tts"),
+    ("MIC_", "mic"),
+    ("STT_", "stt"),
+    ("VAD_", "vad"),
+    ("WAKE_", "wake"),
+];
+
+/// Variables outside the prefixes above: (variable, section, key)
+const EXTRA_VARS: &[(&str, &str, &str)] = &[
+    ("REQUEST_TIMEOUT_MS", "llm", "request_timeout_ms"),
+    ("BRAVE_API_KEY", "web_search", "brave_api_key"),
+];
+
+/// Variables that configure the manifest itself
+const MANIFEST_VARS: &[&str] = &[CONFIG_PATH_ENV, CONFIG_STRICT_ENV];
+
+const SECRET_SUFFIXES: &[&str] = &["_KEY", "_TOKEN", "_SECRET", "_SALT", "_PASSWORD"];
+
+/// Whether `var` holds a credential that `export` leaves in the environment
+pub fn is_secret(var: &str) -> bool {
+    SECRET_SUFFIXES.iter().any(|suffix| var.ends_with(suffix))
+}
+
+/// Section and key of a recognized variable; `None` for variables Loom does not read
+pub fn manifest_key(var: &str) -> Option<(String, String)> {
+    if var == MCP_SERVERS_ENV || MANIFEST_VARS.contains(&var) {
+        return None;
+    }
+    if let Some((_, section, key)) = EXTRA_VARS.iter().find(|(v, _, _)| *v == var) {
+        return Some((section.to_string(), key.to_string()));
+    }
+    SECTIONS.iter().find_map(|(prefix, section)| {
+        let rest = var.strip_prefix(prefix)?;
+        let key = match rest {
+            "" if !prefix.ends_with('_') => "enabled".to_string(),
+            "" => return None,
+            _ if prefix.ends_with('_') => rest.to_ascii_lowercase(),
+            _ => rest.strip_prefix('_')?.to_ascii_lowercase(),
+        };
+        Some((section.to_string(), key))
+    })
+}
+
+/// Variable for a section key; the inverse of `manifest_key`
+pub fn env_var(section: &str, key: &str) -> Option<String> {
+    if let Some((var, _, _)) = EXTRA_VARS
+        .iter()
+        .find(|(_, s, k)| *s == section && *k == key)
+    {
+        return Some(var.to_string());
+    }
+    let (prefix, _) = SECTIONS.iter().find(|(_, s)| *s == section)?;
+    let var = match (prefix.ends_with('_'), key) {
+        (false, "enabled") => prefix.to_string(),
+        (false, _) => format!("{}_{}", prefix, key.to_ascii_uppercase()),
+        (true, _) => format!("{}{}", prefix, key.to_ascii_uppercase()),
+    };
+    // Keys that another section owns (e.g. `loom.dashboard_port`) do not round-trip
+    (manifest_key(&var)? == (section.to_string(), key.to_string())).then_some(var)
+}
+
+/// Settings manifest, see the module docs
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LoomConfig {
+    /// Variables still read from the environment (secrets); declared for strict mode
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub env: Vec<String>,
+    /// `LOOM_MCP_SERVERS`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub mcp_servers: Vec<McpServerConfig>,
+    /// Section → key → value
+    #[serde(flatten)]
+    pub sections: BTreeMap<String, BTreeMap<String, toml::Value>>,
+}
+
+impl LoomConfig {
+    /// Manifest equivalent to the current environment
+    pub fn export_env(include_secrets: bool) -> Result<Self> {
+        Self::from_vars(std::env::vars(), include_secrets)
+    }
+
+    /// Manifest for `vars`; unrecognized variables are skipped
+    pub fn from_vars(
+        vars: impl IntoIterator<Item = (String, String)>,
+        include_secrets: bool,
+    ) -> Result<Self> {
+        let mut config = Self::default();
+        for (var, value) in vars {
+            if var == MCP_SERVERS_ENV {
+                config.mcp_servers = crate::tools::mcp::McpManager::parse_servers(&value)
+                    .map_err(|e| LoomError::ConfigError(e.to_string()))?;
+                config.mcp_servers.sort_by(|a, b| a.name.cmp(&b.name));
+                continue;
+            }
+            let Some((section, key)) = manifest_key(&var) else {
+                continue;
+            };
+            if is_secret(&var) && !include_secrets {
+                config.env.push(var);
+                continue;
+            }
+            config
+                .sections
+                .entry(section)
+                .or_default()
+                .insert(key, parse_value(&value));
+        }
+        config.env.sort();
+        Ok(config)
+    }
+
+    pub fn from_toml(source: &str) -> Result<Self> {
+        let config: Self = toml::from_str(source)
+            .map_err(|e| LoomError::ConfigError(format!("Invalid config manifest: {e}")))?;
+        // Reject keys that would not reach any variable
+        for (section, keys) in &config.sections {
+            for key in keys.keys() {
+                if env_var(section, key).is_none() {
+                    return Err(LoomError::ConfigError(format!(
+                        "Unknown setting {}.{}",
+                        section, key
+                    )));
+                }
+            }
+        }
+        Ok(config)
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        Self::from_toml(&std::fs::read_to_string(path)?)
+    }
+
+    pub fn to_toml(&self) -> Result<String> {
+        toml::to_string_pretty(self)
+            .map_err(|e| LoomError::ConfigError(format!("Cannot write config manifest: {e}")))
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+        std::fs::write(path, self.to_toml()?)?;
+        Ok(())
+    }
+
+    /// Variable assignments the manifest stands for
+    pub fn env_vars(&self) -> Result<BTreeMap<String, String>> {
+        let mut vars = BTreeMap::new();
+        for (section, keys) in &self.sections {
+            for (key, value) in keys {
+                let var = env_var(section, key).ok_or_else(|| {
+                    LoomError::ConfigError(format!("Unknown setting {}.{}", section, key))
+                })?;
+                vars.insert(var, format_value(value)?);
+            }
+        }
+        if !self.mcp_servers.is_empty() {
+            vars.insert(
+                MCP_SERVERS_ENV.to_string(),
+                serde_json::to_string(&self.mcp_servers)?,
+            );
+        }
+        Ok(vars)
+    }
+
+    /// Variables the manifest sets or declares as coming from the environment
+    pub fn declared(&self) -> Result<BTreeSet<String>> {
+        let mut declared: BTreeSet<String> = self.env_vars()?.into_keys().collect();
+        declared.extend(self.env.iter().cloned());
+        Ok(declared)
+    }
+
+    /// Set the manifest's variables that are not already set; returns their names
+    pub fn apply(&self) -> Result<Vec<String>> {
+        let mut applied = Vec::new();
+        for (var, value) in self.env_vars()? {
+            if std::env::var_os(&var).is_none() {
+                std::env::set_var(&var, value);
+                applied.push(var);
+            }
+        }
+        Ok(applied)
+    }
+
+    /// Current value of `section.key`: its variable if set (the environment wins, as in
+    /// `apply`), else the manifest's value
+    pub fn value(&self, section: &str, key: &str) -> Option<String> {
+        if let Some(value) = env_var(section, key).and_then(|var| std::env::var(var).ok()) {
+            return Some(value);
+        }
+        self.sections
+            .get(section)
+            .and_then(|keys| keys.get(key))
+            .and_then(|value| format_value(value).ok())
+    }
+
+    /// Recognized variables set in the environment but not declared by the manifest
+    pub fn undeclared_env(&self) -> Result<Vec<String>> {
+        let declared = self.declared()?;
+        let mut undeclared: Vec<String> = std::env::vars()
+            .map(|(var, _)| var)
+            .filter(|var| var == MCP_SERVERS_ENV || manifest_key(var).is_some())
+            .filter(|var| !declared.contains(var))
+            .collect();
+        undeclared.sort();
+        Ok(undeclared)
+    }
+
+    /// Whether `LOOM_CONFIG_STRICT` is enabled
+    pub fn strict() -> bool {
+        std::env::var(CONFIG_STRICT_ENV)
+            .map(|v| v == "1" || v.eq_ignore_ascii_case("true"))
+            .unwrap_or(false)
+    }
+
+    /// Load and apply the manifest named by `LOOM_CONFIG`; empty without one.
+    /// Call before anything reads the environment.
+    pub fn load_startup() -> Result<Self> {
+        match std::env::var(CONFIG_PATH_ENV) {
+            Ok(path) => {
+                let config = Self::load(&path)?;
+                config.apply()?;
+                Ok(config)
+            }
+            Err(_) => Ok(Self::default()),
+        }
+    }
+
+    /// In strict mode, log a warning for each undeclared variable; returns them
+    pub fn warn_undeclared(&self) -> Result<Vec<String>> {
+        if !Self::strict() {
+            return Ok(vec![]);
+        }
+        let undeclared = self.undeclared_env()?;
+        for var in &undeclared {
+            warn!(
+                target: "config",
+                var = %var,
+                "Environment variable used without being declared in the config manifest"
+            );
+        }
+        Ok(undeclared)
+    }
+}
+
+/// Booleans and numbers become typed TOML values, anything else a string
+fn parse_value(value: &str) -> toml::Value {
+    if let Ok(b) = value.parse::<bool>() {
+        toml::Value::Boolean(b)
+    } else if let Ok(i) = value.parse::<i64>() {
+        toml::Value::Integer(i)
+    } else if let Some(f) = value.parse::<f64>().ok().filter(|f| f.is_finite()) {
+        toml::Value::Float(f)
+    } else {
+        toml::Value::String(value.to_string())
+    }
+}
+
+fn format_value(value: &toml::Value) -> Result<String> {
+    Ok(match value {
+        toml::Value::String(s) => s.clone(),
+        toml::Value::Boolean(b) => b.to_string(),
+        toml::Value::Integer(i) => i.to_string(),
+        toml::Value::Float(f) => f.to_string(),
+        toml::Value::Datetime(d) => d.to_string(),
+        // Lists and tables are passed as JSON, like `LOOM_MCP_SERVERS`
+        other => serde_json::to_string(other)?,
+    })
+}
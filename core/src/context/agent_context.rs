@@ -6,7 +6,8 @@
 use crate::context::retrieval::RetrievalTrigger;
 use crate::context::{
     ContextContent, ContextItem, ContextItemType, ContextMetadata, ContextPipeline, InMemoryStore,
-    MemoryStore, MessageRole, RecencyRetrieval, TemporalRanker, TiktokenCounter, WindowConfig,
+    MemoryStore, MessageRole, ModelBudget, RecencyRetrieval, TemporalRanker, TiktokenCounter,
+    WindowConfig,
 };
 use crate::proto::ActionResult;
 use crate::Result;
@@ -68,6 +69,21 @@ impl AgentContext {
         Self::new(session_id, agent_id, store, pipeline)
     }
 
+    /// The store items are recorded to
+    pub fn store(&self) -> &Arc<dyn MemoryStore> {
+        &self.store
+    }
+
+    /// Session items are recorded under
+    pub fn session_id(&self) -> &str {
+        &self.session_id
+    }
+
+    /// Agent items are recorded under
+    pub fn agent_id(&self) -> &str {
+        &self.agent_id
+    }
+
     /// Record a message in the context
     #[instrument(skip(self, content), fields(session = %self.session_id, role = ?role))]
     pub async fn record_message(
@@ -270,6 +286,24 @@ impl AgentContext {
         Ok(result.items)
     }
 
+    /// Retrieve relevant context sized for the model the request was routed to
+    #[instrument(skip(self, budget), fields(session = %self.session_id, model = %budget.model))]
+    pub async fn get_context_for_model(
+        &self,
+        goal: Option<&str>,
+        budget: &ModelBudget,
+    ) -> Result<Vec<ContextItem>> {
+        let mut trigger = RetrievalTrigger::new(self.session_id.clone(), self.agent_id.clone())
+            .with_max_items(100);
+
+        if let Some(g) = goal {
+            trigger = trigger.with_goal(g.to_string());
+        }
+
+        let result = self.pipeline.execute_with_budget(trigger, budget).await?;
+        Ok(result.items)
+    }
+
     /// Generate unique ID for context items
     pub fn generate_id() -> String {
         let counter = ID_COUNTER.fetch_add(1, Ordering::SeqCst);
@@ -345,7 +379,7 @@ mod tests {
         let event = crate::proto::Event {
             id: "evt_123".to_string(),
             r#type: "test.event".to_string(),
-            timestamp_ms: chrono::Utc::now().timestamp_millis() as i64,
+            timestamp_ms: chrono::Utc::now().timestamp_millis(),
             source: "test".to_string(),
             metadata: std::collections::HashMap::new(),
             payload: vec![],
@@ -1,4 +1,6 @@
-use super::{MemoryReader, MemoryWriter, PromptBundle, TokenBudget};
+use super::{
+    MemoryReader, MemoryWriter, ModelBudget, PromptBundle, TokenBudget, TokenizerRegistry,
+};
 use crate::Result;
 use std::sync::Arc;
 use tracing::debug;
@@ -10,17 +12,40 @@ pub struct TriggerInput {
     pub goal: Option<String>,
     pub tool_hints: Vec<String>,
     pub budget: TokenBudget,
+    /// Model the prompt is for, e.g. from `RoutingDecision::model`
+    pub model: Option<String>,
+}
+
+impl TriggerInput {
+    /// Build for the model a request was routed to: its budget replaces `budget`
+    pub fn with_model_budget(mut self, budget: &ModelBudget) -> Self {
+        self.budget = budget.budget;
+        self.model = Some(budget.model.clone());
+        self
+    }
 }
 
 /// ContextBuilder assembles a PromptBundle from memory and recent events
 pub struct ContextBuilder<R: MemoryReader, W: MemoryWriter> {
     reader: Arc<R>,
     writer: Arc<W>,
+    tokenizers: Option<Arc<TokenizerRegistry>>,
 }
 
 impl<R: MemoryReader, W: MemoryWriter> ContextBuilder<R, W> {
     pub fn new(reader: Arc<R>, writer: Arc<W>) -> Self {
-        Self { reader, writer }
+        Self {
+            reader,
+            writer,
+            tokenizers: None,
+        }
+    }
+
+    /// Fit context docs into the input budget, counted with the trigger model's
+    /// tokenizer and capped by its context window
+    pub fn with_tokenizers(mut self, tokenizers: Arc<TokenizerRegistry>) -> Self {
+        self.tokenizers = Some(tokenizers);
+        self
     }
 
     /// Build a minimal prompt bundle; this is a skeleton to be expanded
@@ -48,10 +73,32 @@ impl<R: MemoryReader, W: MemoryWriter> ContextBuilder<R, W> {
             }
         }
 
+        let system = "You are Loom Agent. Be concise and precise.".to_string();
+        let instructions = trigger.goal.unwrap_or_default();
+        if let Some(ref tokenizers) = self.tokenizers {
+            let model = trigger.model.as_deref().unwrap_or_default();
+            let window = tokenizers.budget(model, trigger.budget.max_output_tokens);
+            let max_input = trigger.budget.max_input_tokens.min(window.max_input_tokens);
+            let counter = tokenizers.counter(model);
+            let mut used = counter.count_text(&system) + counter.count_text(&instructions);
+            // Docs are in priority order: keep the longest prefix that fits
+            let fitting = context_docs
+                .iter()
+                .take_while(|doc| {
+                    used += counter.count_text(doc);
+                    used <= max_input
+                })
+                .count();
+            if fitting < context_docs.len() {
+                debug!(target: "context_builder", model = %model, max_input, dropped = context_docs.len() - fitting, "Context docs exceed input budget");
+                context_docs.truncate(fitting);
+            }
+        }
+
         // Assemble prompt bundle; history is left empty at P0 (no dialog turns tracked yet)
         Ok(PromptBundle {
-            system: "You are Loom Agent. Be concise and precise.".to_string(),
-            instructions: trigger.goal.unwrap_or_default(),
+            system,
+            instructions,
             tools_json_schema: None,
             context_docs,
             history: vec![],
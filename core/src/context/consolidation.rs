@@ -0,0 +1,352 @@
+//! Memory consolidation: a periodic job turning old episodic items into summaries.
+//!
+//! Each run of `MemoryConsolidator`:
+//! 1. **Boosts** items retrieved at least `boost_after` times since the last run (as
+//!    reported by `record_retrieval`, which `ContextPipeline::with_consolidator` calls
+//!    for every selected item), and records when they were last retrieved.
+//! 2. **Clusters** each session's items older than `min_age` that no summary covers yet:
+//!    oldest first, in chunks of `cluster_size`. Each full chunk becomes a level-1
+//!    summary (the same kind of item the pipeline's summarization stage builds, reused
+//!    when it already exists) linked to the items it covers through `related_items`;
+//!    the originals link back to it and are tagged `consolidation.summary`.
+//! 3. **Demotes** items neither retrieved nor demoted for `stale_after`, lowering their
+//!    importance by `demotion` down to `importance_floor`, so retrieval filtering on
+//!    importance favours the summaries and recently useful items.
+//!
+//! Only metadata is rewritten; item content is never changed and nothing is deleted, so
+//! every original stays retrievable through its summary.
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+use std::time::Duration;
+
+use dashmap::DashMap;
+use tracing::{debug, info, warn};
+
+use crate::context::memory::MemoryStore;
+use crate::context::pipeline::summarize::{
+    chunk_key, is_summary, Summarizer, SUMMARY_COVERS_TAG, SUMMARY_KEY_TAG, SUMMARY_LEVEL_TAG,
+    SUMMARY_SOURCE,
+};
+use crate::context::types::{
+    ContextContent, ContextItem, ContextItemType, ContextMetadata, MemoryQuery,
+};
+use crate::Result;
+
+/// Tag holding the id of the consolidation summary covering an item
+pub const CONSOLIDATED_INTO_TAG: &str = "consolidation.summary";
+
+/// Tag holding when a consolidation summary was built (ms since epoch)
+pub const CONSOLIDATED_AT_TAG: &str = "consolidation.at_ms";
+
+/// Tag holding how many times an item was retrieved, as of the last run
+pub const RETRIEVALS_TAG: &str = "memory.retrievals";
+
+/// Tag holding when an item was last retrieved (ms since epoch)
+pub const LAST_RETRIEVED_TAG: &str = "memory.last_retrieved_ms";
+
+/// Tag holding when an item was last demoted (ms since epoch)
+pub const DEMOTED_AT_TAG: &str = "memory.demoted_ms";
+
+/// Configuration of memory consolidation
+#[derive(Debug, Clone)]
+pub struct ConsolidationConfig {
+    /// Items younger than this are not clustered
+    pub min_age: Duration,
+    /// Items per consolidation summary
+    pub cluster_size: usize,
+    /// Output tokens requested per summary
+    pub summary_tokens: usize,
+    /// Lowest importance of a summary (it gets at least the highest of the items it covers)
+    pub summary_importance: f32,
+    /// Retrievals between runs that earn an item a boost
+    pub boost_after: u32,
+    /// Importance added to a boosted item
+    pub boost: f32,
+    /// Time without retrieval (or demotion) after which an item is demoted
+    pub stale_after: Duration,
+    /// Importance removed from a stale item
+    pub demotion: f32,
+    /// Importance below which stale items are not demoted
+    pub importance_floor: f32,
+    /// Most items examined per run
+    pub scan_limit: usize,
+}
+
+impl Default for ConsolidationConfig {
+    fn default() -> Self {
+        Self {
+            min_age: Duration::from_secs(3600),
+            cluster_size: 16,
+            summary_tokens: 256,
+            summary_importance: 0.6,
+            boost_after: 3,
+            boost: 0.1,
+            stale_after: Duration::from_secs(24 * 3600),
+            demotion: 0.1,
+            importance_floor: 0.05,
+            scan_limit: 10_000,
+        }
+    }
+}
+
+/// What one consolidation run did
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ConsolidationReport {
+    /// Sessions with items old enough to consolidate
+    pub sessions: usize,
+    /// Summaries built (or reused) for new clusters
+    pub summaries: usize,
+    /// Items newly covered by a summary
+    pub consolidated: usize,
+    pub boosted: usize,
+    pub demoted: usize,
+}
+
+/// Periodic consolidation of a `MemoryStore`, as described in the module docs
+pub struct MemoryConsolidator {
+    store: Arc<dyn MemoryStore>,
+    summarizer: Arc<dyn Summarizer>,
+    config: ConsolidationConfig,
+    /// item id -> (retrievals since the last run, last retrieval ms)
+    retrievals: DashMap<String, (u32, i64)>,
+}
+
+impl MemoryConsolidator {
+    pub fn new(
+        store: Arc<dyn MemoryStore>,
+        summarizer: Arc<dyn Summarizer>,
+        config: ConsolidationConfig,
+    ) -> Self {
+        Self {
+            store,
+            summarizer,
+            config,
+            retrievals: DashMap::new(),
+        }
+    }
+
+    pub fn config(&self) -> &ConsolidationConfig {
+        &self.config
+    }
+
+    /// Count a retrieval of each of `items`
+    pub fn record_retrieval(&self, items: &[ContextItem]) {
+        let now = now_ms();
+        for item in items {
+            let mut entry = self.retrievals.entry(item.id.clone()).or_insert((0, now));
+            entry.0 += 1;
+            entry.1 = now;
+        }
+    }
+
+    /// Items with retrievals not yet applied by a run
+    pub fn pending_retrievals(&self) -> usize {
+        self.retrievals.len()
+    }
+
+    /// Run consolidation every `interval` until the consolidator is dropped
+    pub fn spawn(self: &Arc<Self>, interval: Duration) -> tokio::task::JoinHandle<()> {
+        let consolidator = Arc::downgrade(self);
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            ticker.tick().await;
+            loop {
+                ticker.tick().await;
+                let Some(consolidator) = consolidator.upgrade() else {
+                    break;
+                };
+                if let Err(e) = consolidator.run_once().await {
+                    warn!(error = %e, "Memory consolidation failed");
+                }
+            }
+        })
+    }
+
+    /// Boost, cluster and demote once
+    pub async fn run_once(&self) -> Result<ConsolidationReport> {
+        let now = now_ms();
+        let mut report = ConsolidationReport::default();
+        // Rewrites, by item id
+        let mut dirty: HashMap<String, ContextItem> = HashMap::new();
+
+        // Boost repeatedly retrieved items
+        let ids: Vec<String> = self.retrievals.iter().map(|e| e.key().clone()).collect();
+        for id in ids {
+            let Some((_, (count, at))) = self.retrievals.remove(&id) else {
+                continue;
+            };
+            let Some(mut item) = self.store.get(&id).await? else {
+                continue;
+            };
+            let total = tag_number(&item, RETRIEVALS_TAG).unwrap_or(0) + i64::from(count);
+            let tags = &mut item.metadata.tags;
+            tags.insert(RETRIEVALS_TAG.to_string(), total.to_string());
+            tags.insert(LAST_RETRIEVED_TAG.to_string(), at.to_string());
+            if count >= self.config.boost_after {
+                item.metadata.importance =
+                    (item.metadata.importance + self.config.boost).clamp(0.0, 1.0);
+                report.boosted += 1;
+            }
+            dirty.insert(id, item);
+        }
+        if !dirty.is_empty() {
+            self.store
+                .store_batch(dirty.drain().map(|(_, i)| i).collect())
+                .await?;
+        }
+
+        // Old items, by session
+        let cutoff = self.config.min_age.min(self.config.stale_after);
+        let query = MemoryQuery::new()
+            .in_time_range(i64::MIN, now.saturating_sub(duration_ms(cutoff)))
+            .limit(self.config.scan_limit);
+        let mut sessions: BTreeMap<String, Vec<ContextItem>> = BTreeMap::new();
+        for item in self.store.query(&query).await? {
+            sessions
+                .entry(item.metadata.session_id.clone())
+                .or_default()
+                .push(item);
+        }
+        report.sessions = sessions.len();
+
+        let cluster_before = now.saturating_sub(duration_ms(self.config.min_age));
+        let cluster_size = self.config.cluster_size.max(2);
+        for (session_id, mut items) in sessions {
+            items.sort_by(|a, b| {
+                (a.metadata.timestamp_ms, &a.id).cmp(&(b.metadata.timestamp_ms, &b.id))
+            });
+
+            let pending: Vec<ContextItem> = items
+                .iter()
+                .filter(|item| item.metadata.timestamp_ms <= cluster_before)
+                .filter(|item| !is_summary(item))
+                .filter(|item| !item.metadata.tags.contains_key(CONSOLIDATED_INTO_TAG))
+                .cloned()
+                .collect();
+            // A partial cluster waits for more items
+            for cluster in pending.chunks_exact(cluster_size) {
+                let summary = self.summary_for(cluster).await?;
+                for item in cluster {
+                    let item = dirty.entry(item.id.clone()).or_insert_with(|| item.clone());
+                    item.metadata
+                        .tags
+                        .insert(CONSOLIDATED_INTO_TAG.to_string(), summary.id.clone());
+                    if !item.metadata.related_items.contains(&summary.id) {
+                        item.metadata.related_items.push(summary.id.clone());
+                    }
+                }
+                report.summaries += 1;
+                report.consolidated += cluster.len();
+                debug!(session = %session_id, summary = %summary.id, items = cluster.len(), "Consolidated context items");
+            }
+
+            // Demote stale items
+            let stale_before = now.saturating_sub(duration_ms(self.config.stale_after));
+            for item in &items {
+                let last_active = [
+                    Some(item.metadata.timestamp_ms),
+                    tag_number(item, LAST_RETRIEVED_TAG),
+                    tag_number(item, DEMOTED_AT_TAG),
+                    tag_number(item, CONSOLIDATED_AT_TAG),
+                ]
+                .into_iter()
+                .flatten()
+                .max()
+                .unwrap_or(item.metadata.timestamp_ms);
+                if last_active > stale_before
+                    || item.metadata.importance <= self.config.importance_floor
+                {
+                    continue;
+                }
+                let item = dirty.entry(item.id.clone()).or_insert_with(|| item.clone());
+                item.metadata.importance = (item.metadata.importance - self.config.demotion)
+                    .max(self.config.importance_floor);
+                item.metadata
+                    .tags
+                    .insert(DEMOTED_AT_TAG.to_string(), now.to_string());
+                report.demoted += 1;
+            }
+        }
+        self.store
+            .store_batch(dirty.into_values().collect())
+            .await?;
+
+        info!(
+            sessions = report.sessions,
+            summaries = report.summaries,
+            consolidated = report.consolidated,
+            boosted = report.boosted,
+            demoted = report.demoted,
+            "Memory consolidation run complete"
+        );
+        Ok(report)
+    }
+
+    /// Level-1 summary of `cluster` linked to the items it covers, reusing a stored one
+    async fn summary_for(&self, cluster: &[ContextItem]) -> Result<ContextItem> {
+        let key = chunk_key(cluster, 1);
+        let covers: Vec<String> = cluster.iter().map(|item| item.id.clone()).collect();
+        let session_id = cluster[0].metadata.session_id.clone();
+
+        let query = MemoryQuery::new()
+            .for_session(session_id.clone())
+            .with_tag(SUMMARY_KEY_TAG, key.clone())
+            .limit(1);
+        if let Some(mut summary) = self.store.query(&query).await?.into_iter().next() {
+            if summary.metadata.related_items != covers {
+                summary.metadata.related_items = covers;
+                self.store.store(summary.clone()).await?;
+            }
+            return Ok(summary);
+        }
+
+        let texts: Vec<String> = cluster
+            .iter()
+            .map(|item| item.content.text.clone())
+            .collect();
+        let text = self
+            .summarizer
+            .summarize(&texts, self.config.summary_tokens)
+            .await?;
+        let importance = cluster
+            .iter()
+            .map(|item| item.metadata.importance)
+            .fold(self.config.summary_importance, f32::max);
+        let mut metadata = ContextMetadata::new(session_id, cluster[0].metadata.agent_id.clone())
+            .with_importance(importance)
+            .with_tag(SUMMARY_KEY_TAG.to_string(), key)
+            .with_tag(SUMMARY_LEVEL_TAG.to_string(), "1".to_string())
+            .with_tag(SUMMARY_COVERS_TAG.to_string(), covers.join(","))
+            .with_tag(CONSOLIDATED_AT_TAG.to_string(), now_ms().to_string());
+        metadata.related_items = covers;
+        // Sorts with the newest item it covers
+        metadata.timestamp_ms = cluster
+            .iter()
+            .map(|item| item.metadata.timestamp_ms)
+            .max()
+            .unwrap_or(metadata.timestamp_ms);
+        let summary = ContextItem::new(
+            ContextItemType::Observation {
+                source: SUMMARY_SOURCE.to_string(),
+            },
+            ContextContent::from_string(text),
+            metadata,
+        );
+        self.store.store(summary.clone()).await?;
+        Ok(summary)
+    }
+}
+
+fn tag_number(item: &ContextItem, tag: &str) -> Option<i64> {
+    item.metadata.tags.get(tag).and_then(|v| v.parse().ok())
+}
+
+fn duration_ms(duration: Duration) -> i64 {
+    i64::try_from(duration.as_millis()).unwrap_or(i64::MAX)
+}
+
+fn now_ms() -> i64 {
+    chrono::Utc::now().timestamp_millis()
+}
@@ -3,5 +3,5 @@
 pub mod persistent;
 pub mod store;
 
-pub use persistent::RocksDbStore;
+pub use persistent::{Partitioning, RocksDbStore, RocksDbStoreConfig};
 pub use store::{InMemoryStore, MemoryStore};
@@ -2,54 +2,318 @@
 //!
 //! This provides a production-ready implementation of `MemoryStore` using RocksDB.
 //! It maintains the same indexing patterns as `InMemoryStore` but persists data to disk.
+//!
+//! Items may expire (`ContextMetadata::expires_at_ms`, or the store's default TTL).
+//! Expired items are hidden from reads immediately and deleted by `compact_expired`,
+//! which `spawn_compaction` runs in the background. With `Partitioning::ByAgent` or
+//! `BySession`, items live in one column family per agent or session, so scans for
+//! one partition never touch another's data.
 
 use crate::context::types::{ContextItem, ContextItemType, MemoryQuery};
 use crate::{LoomError, Result};
 use async_trait::async_trait;
-use rocksdb::{ColumnFamilyDescriptor, Options, DB};
+use rocksdb::{
+    BoundColumnFamily, ColumnFamilyDescriptor, DBWithThreadMode, IteratorMode, MultiThreaded,
+    Options,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
 use std::path::Path;
-use std::sync::Arc;
-use tracing::{debug, info};
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+use tracing::{debug, info, warn};
 
 use super::MemoryStore;
 
+type DB = DBWithThreadMode<MultiThreaded>;
+
 /// Column family names for indexing
 const CF_ITEMS: &str = "items";
 const CF_SESSION_INDEX: &str = "session_index";
 const CF_TYPE_INDEX: &str = "type_index";
 const CF_TIME_INDEX: &str = "time_index";
+/// Item ID -> partition column family, for items outside `items`
+const CF_ITEM_PARTITION: &str = "item_partition";
+/// `{expires_at_ms:020}:{item_id}` keys, ordered by expiry
+const CF_EXPIRY_INDEX: &str = "expiry_index";
+
+/// Prefix of per-partition item column families (`items.<agent or session>`)
+const PARTITION_PREFIX: &str = "items.";
+
+/// How items are spread over column families
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Partitioning {
+    /// All items in the shared `items` column family
+    #[default]
+    Shared,
+    /// One column family per `agent_id`
+    ByAgent,
+    /// One column family per `session_id`
+    BySession,
+}
+
+/// RocksDbStore options
+#[derive(Debug, Clone, Default)]
+pub struct RocksDbStoreConfig {
+    pub partitioning: Partitioning,
+    /// TTL applied to items stored without `expires_at_ms`
+    pub default_ttl: Option<Duration>,
+}
+
+impl RocksDbStoreConfig {
+    pub fn with_partitioning(mut self, partitioning: Partitioning) -> Self {
+        self.partitioning = partitioning;
+        self
+    }
+
+    pub fn with_default_ttl(mut self, ttl: Duration) -> Self {
+        self.default_ttl = Some(ttl);
+        self
+    }
+}
 
 /// Persistent RocksDB-based implementation of MemoryStore.
 ///
 /// Uses column families for efficient indexing:
 /// - `items`: Main storage (item_id -> ContextItem)
+/// - `items.<partition>`: Main storage for one agent or session when partitioned
+/// - `item_partition`: Item ID to partition column family mapping
 /// - `session_index`: Session to item IDs mapping
 /// - `type_index`: Item type to item IDs mapping
 /// - `time_index`: Timestamp bucket to item IDs mapping
+/// - `expiry_index`: Expiry time to item ID, scanned by compaction
 pub struct RocksDbStore {
     db: DB,
+    config: RocksDbStoreConfig,
+    /// Partition column families that exist in the database
+    partitions: RwLock<BTreeSet<String>>,
+}
+
+fn now_ms() -> i64 {
+    chrono::Utc::now().timestamp_millis()
+}
+
+fn expiry_key(expires_at_ms: i64, item_id: &str) -> String {
+    format!("{:020}:{}", expires_at_ms.max(0), item_id)
 }
 
 impl RocksDbStore {
     /// Create a new RocksDB store at the given path
     pub fn new<P: AsRef<Path>>(path: P) -> Result<Arc<Self>> {
+        Self::open(path, RocksDbStoreConfig::default())
+    }
+
+    /// Open a store with TTL and partitioning options
+    pub fn open<P: AsRef<Path>>(path: P, config: RocksDbStoreConfig) -> Result<Arc<Self>> {
         let mut opts = Options::default();
         opts.create_if_missing(true);
         opts.create_missing_column_families(true);
 
+        // Partitions created by earlier runs must be opened too
+        let partitions: BTreeSet<String> = DB::list_cf(&opts, path.as_ref())
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|name| name.starts_with(PARTITION_PREFIX))
+            .collect();
+
         // Define column families
-        let cf_descriptors = vec![
-            ColumnFamilyDescriptor::new(CF_ITEMS, Options::default()),
-            ColumnFamilyDescriptor::new(CF_SESSION_INDEX, Options::default()),
-            ColumnFamilyDescriptor::new(CF_TYPE_INDEX, Options::default()),
-            ColumnFamilyDescriptor::new(CF_TIME_INDEX, Options::default()),
-        ];
+        let cf_descriptors = [
+            CF_ITEMS,
+            CF_SESSION_INDEX,
+            CF_TYPE_INDEX,
+            CF_TIME_INDEX,
+            CF_ITEM_PARTITION,
+            CF_EXPIRY_INDEX,
+        ]
+        .into_iter()
+        .map(str::to_string)
+        .chain(partitions.iter().cloned())
+        .map(|name| ColumnFamilyDescriptor::new(name, Options::default()))
+        .collect::<Vec<_>>();
 
         let db = DB::open_cf_descriptors(&opts, path, cf_descriptors)
             .map_err(|e| LoomError::StorageError(e.to_string()))?;
 
-        info!("RocksDbStore initialized");
-        Ok(Arc::new(Self { db }))
+        info!(
+            partitioning = ?config.partitioning,
+            partitions = partitions.len(),
+            "RocksDbStore initialized"
+        );
+        Ok(Arc::new(Self {
+            db,
+            config,
+            partitions: RwLock::new(partitions),
+        }))
+    }
+
+    /// Delete expired items every `interval` until the store is dropped
+    pub fn spawn_compaction(self: &Arc<Self>, interval: Duration) -> tokio::task::JoinHandle<()> {
+        let store = Arc::downgrade(self);
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            ticker.tick().await;
+            loop {
+                ticker.tick().await;
+                let Some(store) = store.upgrade() else {
+                    break;
+                };
+                if let Err(e) = store.compact_expired() {
+                    warn!(error = %e, "RocksDbStore compaction failed");
+                }
+            }
+        })
+    }
+
+    /// Delete every expired item and its index entries; returns how many were removed
+    pub fn compact_expired(&self) -> Result<usize> {
+        let now = now_ms();
+        let expiry = self.cf(CF_EXPIRY_INDEX)?;
+
+        // Keys sort by expiry time, so stop at the first one still in the future
+        let mut due = Vec::new();
+        for entry in self.db.iterator_cf(&expiry, IteratorMode::Start) {
+            let (key, _) = entry.map_err(|e| LoomError::StorageError(e.to_string()))?;
+            let key = String::from_utf8_lossy(&key).to_string();
+            let Some((at, id)) = key.split_once(':') else {
+                continue;
+            };
+            match at.parse::<i64>() {
+                Ok(at) if at <= now => {}
+                _ => break,
+            }
+            due.push((key.clone(), id.to_string()));
+        }
+
+        let mut removed = 0;
+        let mut touched = BTreeSet::new();
+        for (key, id) in due {
+            // The item may have been rewritten with a later expiry since
+            if let Some((cf_name, item)) = self.get_with_cf(&id)? {
+                if item.metadata.is_expired(now) {
+                    self.remove_item(&cf_name, &item)?;
+                    touched.insert(cf_name);
+                    removed += 1;
+                }
+            }
+            self.db
+                .delete_cf(&expiry, key)
+                .map_err(|e| LoomError::StorageError(e.to_string()))?;
+        }
+
+        // Reclaim the space held by the tombstones
+        for cf_name in touched {
+            let cf = self.cf(&cf_name)?;
+            self.db.compact_range_cf(&cf, None::<&[u8]>, None::<&[u8]>);
+        }
+
+        if removed > 0 {
+            info!(removed, "Compacted expired context items");
+        }
+        Ok(removed)
+    }
+
+    /// Partition column families currently in the database
+    pub fn partitions(&self) -> Vec<String> {
+        self.partitions
+            .read()
+            .unwrap()
+            .iter()
+            .map(|name| name[PARTITION_PREFIX.len()..].to_string())
+            .collect()
+    }
+
+    fn cf(&self, name: &str) -> Result<Arc<BoundColumnFamily<'_>>> {
+        self.db
+            .cf_handle(name)
+            .ok_or_else(|| LoomError::StorageError(format!("Missing CF: {}", name)))
+    }
+
+    /// Column family an item belongs in under the configured partitioning
+    fn partition_for(&self, item: &ContextItem) -> String {
+        let key = match self.config.partitioning {
+            Partitioning::Shared => return CF_ITEMS.to_string(),
+            Partitioning::ByAgent => &item.metadata.agent_id,
+            Partitioning::BySession => &item.metadata.session_id,
+        };
+        if key.is_empty() {
+            CF_ITEMS.to_string()
+        } else {
+            format!("{}{}", PARTITION_PREFIX, key)
+        }
+    }
+
+    /// Create a partition column family on first use
+    fn ensure_partition(&self, cf_name: &str) -> Result<()> {
+        if cf_name == CF_ITEMS || self.partitions.read().unwrap().contains(cf_name) {
+            return Ok(());
+        }
+        let mut partitions = self.partitions.write().unwrap();
+        if !partitions.contains(cf_name) {
+            self.db
+                .create_cf(cf_name, &Options::default())
+                .map_err(|e| LoomError::StorageError(e.to_string()))?;
+            debug!(cf = %cf_name, "Created context partition");
+            partitions.insert(cf_name.to_string());
+        }
+        Ok(())
+    }
+
+    /// Item column family holding `id`
+    fn cf_name_of(&self, id: &str) -> Result<String> {
+        let cf = self.cf(CF_ITEM_PARTITION)?;
+        match self.db.get_cf(&cf, id) {
+            Ok(Some(name)) => Ok(String::from_utf8_lossy(&name).to_string()),
+            Ok(None) => Ok(CF_ITEMS.to_string()),
+            Err(e) => Err(LoomError::StorageError(e.to_string())),
+        }
+    }
+
+    /// Stored item (expired or not) with the column family it lives in
+    fn get_with_cf(&self, id: &str) -> Result<Option<(String, ContextItem)>> {
+        let cf_name = self.cf_name_of(id)?;
+        let cf = self.cf(&cf_name)?;
+        match self.db.get_cf(&cf, id) {
+            Ok(Some(data)) => Ok(Some((cf_name, serde_json::from_slice(&data)?))),
+            Ok(None) => Ok(None),
+            Err(e) => Err(LoomError::StorageError(e.to_string())),
+        }
+    }
+
+    /// IDs stored in one item column family
+    fn scan_ids(&self, cf_name: &str) -> Result<Vec<String>> {
+        let cf = self.cf(cf_name)?;
+        let iter = self.db.iterator_cf(&cf, IteratorMode::Start);
+        Ok(iter
+            .filter_map(|r| r.ok())
+            .map(|(k, _)| String::from_utf8_lossy(&k).to_string())
+            .collect())
+    }
+
+    /// All item column families: `items` plus every partition
+    fn item_cfs(&self) -> Vec<String> {
+        std::iter::once(CF_ITEMS.to_string())
+            .chain(self.partitions.read().unwrap().iter().cloned())
+            .collect()
+    }
+
+    /// Delete an item and every index entry pointing at it
+    fn remove_item(&self, cf_name: &str, item: &ContextItem) -> Result<()> {
+        let cf = self.cf(cf_name)?;
+        self.db
+            .delete_cf(&cf, &item.id)
+            .map_err(|e| LoomError::StorageError(e.to_string()))?;
+
+        self.remove_from_index(CF_SESSION_INDEX, &item.metadata.session_id, &item.id)?;
+        self.remove_from_index(CF_TYPE_INDEX, &Self::type_key(&item.item_type), &item.id)?;
+        let time_bucket = (item.metadata.timestamp_ms / 1000).to_string();
+        self.remove_from_index(CF_TIME_INDEX, &time_bucket, &item.id)?;
+
+        let partition = self.cf(CF_ITEM_PARTITION)?;
+        self.db
+            .delete_cf(&partition, &item.id)
+            .map_err(|e| LoomError::StorageError(e.to_string()))
     }
 
     /// Get the type key for indexing
@@ -62,15 +326,13 @@ impl RocksDbStore {
             }
             ContextItemType::Event { event_type } => format!("event:{}", event_type),
             ContextItemType::Observation { source } => format!("observation:{}", source),
+            ContextItemType::ToolTrace { trace_id } => format!("tool_trace:{}", trace_id),
         }
     }
 
     /// Append an ID to an index (stored as JSON array)
     fn append_to_index(&self, cf_name: &str, key: &str, item_id: &str) -> Result<()> {
-        let cf = self
-            .db
-            .cf_handle(cf_name)
-            .ok_or_else(|| LoomError::StorageError(format!("Missing CF: {}", cf_name)))?;
+        let cf = self.cf(cf_name)?;
 
         // Get existing IDs or create empty vec
         let mut ids: Vec<String> = match self.db.get_cf(&cf, key) {
@@ -79,6 +341,10 @@ impl RocksDbStore {
             Err(e) => return Err(LoomError::StorageError(e.to_string())),
         };
 
+        // A rewritten item is already indexed
+        if ids.iter().any(|id| id == item_id) {
+            return Ok(());
+        }
         ids.push(item_id.to_string());
 
         let serialized = serde_json::to_vec(&ids)?;
@@ -87,12 +353,23 @@ impl RocksDbStore {
             .map_err(|e| LoomError::StorageError(e.to_string()))
     }
 
+    /// Remove an ID from an index, dropping the key once it is empty
+    fn remove_from_index(&self, cf_name: &str, key: &str, item_id: &str) -> Result<()> {
+        let cf = self.cf(cf_name)?;
+        let mut ids = self.get_index_ids(cf_name, key)?;
+        ids.retain(|id| id != item_id);
+
+        let result = if ids.is_empty() {
+            self.db.delete_cf(&cf, key)
+        } else {
+            self.db.put_cf(&cf, key, serde_json::to_vec(&ids)?)
+        };
+        result.map_err(|e| LoomError::StorageError(e.to_string()))
+    }
+
     /// Get IDs from an index
     fn get_index_ids(&self, cf_name: &str, key: &str) -> Result<Vec<String>> {
-        let cf = self
-            .db
-            .cf_handle(cf_name)
-            .ok_or_else(|| LoomError::StorageError(format!("Missing CF: {}", cf_name)))?;
+        let cf = self.cf(cf_name)?;
 
         match self.db.get_cf(&cf, key) {
             Ok(Some(data)) => Ok(serde_json::from_slice(&data)?),
@@ -103,6 +380,11 @@ impl RocksDbStore {
 
     /// Match an item against query filters
     fn matches_query(&self, item: &ContextItem, query: &MemoryQuery) -> bool {
+        // Expired items stay hidden until compaction deletes them
+        if item.metadata.is_expired(now_ms()) {
+            return false;
+        }
+
         // Session filter
         if let Some(ref session_id) = query.session_id {
             if &item.metadata.session_id != session_id {
@@ -154,18 +436,56 @@ impl RocksDbStore {
 
 #[async_trait]
 impl MemoryStore for RocksDbStore {
-    async fn store(&self, item: ContextItem) -> Result<()> {
-        let cf = self
-            .db
-            .cf_handle(CF_ITEMS)
-            .ok_or_else(|| LoomError::StorageError("Missing items CF".to_string()))?;
+    async fn store(&self, mut item: ContextItem) -> Result<()> {
+        if item.metadata.expires_at_ms.is_none() {
+            if let Some(ttl) = self.config.default_ttl {
+                item.metadata.expires_at_ms = Some(now_ms().saturating_add(ttl.as_millis() as i64));
+            }
+        }
+
+        let cf_name = self.partition_for(&item);
+        self.ensure_partition(&cf_name)?;
+
+        // A rewrite may move the item or change its expiry
+        if let Some((previous_cf, previous)) = self.get_with_cf(&item.id)? {
+            if previous_cf != cf_name {
+                let cf = self.cf(&previous_cf)?;
+                self.db
+                    .delete_cf(&cf, &item.id)
+                    .map_err(|e| LoomError::StorageError(e.to_string()))?;
+            }
+            if let Some(at) = previous.metadata.expires_at_ms {
+                if Some(at) != item.metadata.expires_at_ms {
+                    let expiry = self.cf(CF_EXPIRY_INDEX)?;
+                    self.db
+                        .delete_cf(&expiry, expiry_key(at, &item.id))
+                        .map_err(|e| LoomError::StorageError(e.to_string()))?;
+                }
+            }
+        }
 
         // Store the item
+        let cf = self.cf(&cf_name)?;
         let serialized = serde_json::to_vec(&item)?;
         self.db
             .put_cf(&cf, &item.id, serialized)
             .map_err(|e| LoomError::StorageError(e.to_string()))?;
 
+        let partition = self.cf(CF_ITEM_PARTITION)?;
+        let located = if cf_name == CF_ITEMS {
+            self.db.delete_cf(&partition, &item.id)
+        } else {
+            self.db.put_cf(&partition, &item.id, &cf_name)
+        };
+        located.map_err(|e| LoomError::StorageError(e.to_string()))?;
+
+        if let Some(at) = item.metadata.expires_at_ms {
+            let expiry = self.cf(CF_EXPIRY_INDEX)?;
+            self.db
+                .put_cf(&expiry, expiry_key(at, &item.id), b"")
+                .map_err(|e| LoomError::StorageError(e.to_string()))?;
+        }
+
         // Update indices
         self.append_to_index(CF_SESSION_INDEX, &item.metadata.session_id, &item.id)?;
 
@@ -175,7 +495,7 @@ impl MemoryStore for RocksDbStore {
         let time_bucket = (item.metadata.timestamp_ms / 1000).to_string();
         self.append_to_index(CF_TIME_INDEX, &time_bucket, &item.id)?;
 
-        debug!(item_id = %item.id, "Stored context item");
+        debug!(item_id = %item.id, cf = %cf_name, "Stored context item");
         Ok(())
     }
 
@@ -187,33 +507,34 @@ impl MemoryStore for RocksDbStore {
     }
 
     async fn get(&self, id: &str) -> Result<Option<ContextItem>> {
-        let cf = self
-            .db
-            .cf_handle(CF_ITEMS)
-            .ok_or_else(|| LoomError::StorageError("Missing items CF".to_string()))?;
-
-        match self.db.get_cf(&cf, id) {
-            Ok(Some(data)) => Ok(Some(serde_json::from_slice(&data)?)),
-            Ok(None) => Ok(None),
-            Err(e) => Err(LoomError::StorageError(e.to_string())),
-        }
+        let now = now_ms();
+        Ok(self
+            .get_with_cf(id)?
+            .map(|(_, item)| item)
+            .filter(|item| !item.metadata.is_expired(now)))
     }
 
     async fn query(&self, query: &MemoryQuery) -> Result<Vec<ContextItem>> {
         // Use session index if session filter is provided
         let candidate_ids: Vec<String> = if let Some(ref session_id) = query.session_id {
             self.get_index_ids(CF_SESSION_INDEX, session_id)?
+        } else if let (Partitioning::ByAgent, Some(agent_id)) =
+            (self.config.partitioning, &query.agent_id)
+        {
+            // Only the agent's own partition
+            let cf_name = format!("{}{}", PARTITION_PREFIX, agent_id);
+            if self.partitions.read().unwrap().contains(&cf_name) {
+                self.scan_ids(&cf_name)?
+            } else {
+                vec![]
+            }
         } else {
             // Scan all items (expensive for large datasets)
-            let cf = self
-                .db
-                .cf_handle(CF_ITEMS)
-                .ok_or_else(|| LoomError::StorageError("Missing items CF".to_string()))?;
-
-            let iter = self.db.iterator_cf(&cf, rocksdb::IteratorMode::Start);
-            iter.filter_map(|r| r.ok())
-                .map(|(k, _)| String::from_utf8_lossy(&k).to_string())
-                .collect()
+            let mut ids = Vec::new();
+            for cf_name in self.item_cfs() {
+                ids.extend(self.scan_ids(&cf_name)?);
+            }
+            ids
         };
 
         // Filter and collect matching items
@@ -232,7 +553,7 @@ impl MemoryStore for RocksDbStore {
         }
 
         // Sort by timestamp (most recent first)
-        results.sort_by(|a, b| b.metadata.timestamp_ms.cmp(&a.metadata.timestamp_ms));
+        results.sort_by_key(|r| std::cmp::Reverse(r.metadata.timestamp_ms));
 
         Ok(results)
     }
@@ -255,17 +576,27 @@ impl MemoryStore for RocksDbStore {
     }
 
     async fn count(&self) -> Result<usize> {
-        let cf = self
-            .db
-            .cf_handle(CF_ITEMS)
-            .ok_or_else(|| LoomError::StorageError("Missing items CF".to_string()))?;
+        let mut count = 0;
+        for cf_name in self.item_cfs() {
+            let cf = self.cf(&cf_name)?;
+            count += self.db.iterator_cf(&cf, IteratorMode::Start).count();
+        }
 
-        let count = self
+        // Expired items awaiting compaction are not counted
+        let now = now_ms();
+        let expiry = self.cf(CF_EXPIRY_INDEX)?;
+        let expired = self
             .db
-            .iterator_cf(&cf, rocksdb::IteratorMode::Start)
+            .iterator_cf(&expiry, IteratorMode::Start)
+            .filter_map(|r| r.ok())
+            .map_while(|(k, _)| {
+                let key = String::from_utf8_lossy(&k).to_string();
+                let at = key.split_once(':')?.0.parse::<i64>().ok()?;
+                (at <= now).then_some(())
+            })
             .count();
 
-        Ok(count)
+        Ok(count.saturating_sub(expired))
     }
 }
 
@@ -377,4 +708,119 @@ mod tests {
         let results = store.query(&query).await.unwrap();
         assert_eq!(results.len(), 3);
     }
+
+    fn agent_item(id: &str, agent: &str) -> ContextItem {
+        let mut item = make_test_item(id, &format!("{}-session", agent), "Content");
+        item.metadata.agent_id = agent.to_string();
+        item
+    }
+
+    #[tokio::test]
+    async fn test_expired_items_are_hidden_and_compacted() {
+        let dir = tempdir().unwrap();
+        let store = RocksDbStore::new(dir.path()).unwrap();
+
+        let mut expired = make_test_item("old", "session", "Stale");
+        expired.metadata = expired.metadata.with_ttl(Duration::ZERO);
+        let mut fresh = make_test_item("new", "session", "Fresh");
+        fresh.metadata = fresh.metadata.with_ttl(Duration::from_secs(3600));
+        store.store(expired).await.unwrap();
+        store.store(fresh).await.unwrap();
+        store
+            .store(make_test_item("forever", "session", "Kept"))
+            .await
+            .unwrap();
+
+        assert!(store.get("old").await.unwrap().is_none());
+        let query = MemoryQuery::new().for_session("session".to_string());
+        assert_eq!(store.query(&query).await.unwrap().len(), 2);
+        assert_eq!(store.count().await.unwrap(), 2);
+
+        assert_eq!(store.compact_expired().unwrap(), 1);
+        assert_eq!(store.compact_expired().unwrap(), 0);
+        assert_eq!(store.count().await.unwrap(), 2);
+        assert_eq!(
+            store.get_index_ids(CF_SESSION_INDEX, "session").unwrap(),
+            vec!["new", "forever"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_rewrite_extends_expiry() {
+        let dir = tempdir().unwrap();
+        let store = RocksDbStore::open(
+            dir.path(),
+            RocksDbStoreConfig::default().with_default_ttl(Duration::from_millis(1)),
+        )
+        .unwrap();
+
+        store
+            .store(make_test_item("item-1", "s1", "Hello"))
+            .await
+            .unwrap();
+        tokio::time::sleep(Duration::from_millis(5)).await;
+        assert!(store.get("item-1").await.unwrap().is_none());
+
+        // Rewriting with an explicit expiry replaces the default TTL entry
+        let mut item = make_test_item("item-1", "s1", "Hello again");
+        item.metadata = item.metadata.with_ttl(Duration::from_secs(3600));
+        store.store(item).await.unwrap();
+        assert_eq!(store.compact_expired().unwrap(), 0);
+        assert!(store.get("item-1").await.unwrap().is_some());
+    }
+
+    #[tokio::test]
+    async fn test_partitioning_by_agent() {
+        let dir = tempdir().unwrap();
+        let config = RocksDbStoreConfig::default().with_partitioning(Partitioning::ByAgent);
+        {
+            let store = RocksDbStore::open(dir.path(), config.clone()).unwrap();
+            for i in 0..5 {
+                store
+                    .store(agent_item(&format!("chatty-{}", i), "chatty"))
+                    .await
+                    .unwrap();
+            }
+            store.store(agent_item("quiet-0", "quiet")).await.unwrap();
+
+            assert_eq!(store.partitions(), vec!["chatty", "quiet"]);
+            assert_eq!(store.scan_ids("items.quiet").unwrap(), vec!["quiet-0"]);
+            assert!(store.scan_ids(CF_ITEMS).unwrap().is_empty());
+
+            let query = MemoryQuery {
+                agent_id: Some("quiet".to_string()),
+                ..MemoryQuery::new()
+            };
+            let results = store.query(&query).await.unwrap();
+            assert_eq!(results.len(), 1);
+            assert_eq!(results[0].id, "quiet-0");
+            assert_eq!(store.count().await.unwrap(), 6);
+        }
+
+        // Partitions are reopened with the database
+        let store = RocksDbStore::open(dir.path(), config).unwrap();
+        assert_eq!(store.partitions().len(), 2);
+        assert!(store.get("chatty-3").await.unwrap().is_some());
+        assert_eq!(store.query(&MemoryQuery::new()).await.unwrap().len(), 6);
+    }
+
+    #[tokio::test]
+    async fn test_background_compaction() {
+        let dir = tempdir().unwrap();
+        let store = RocksDbStore::new(dir.path()).unwrap();
+        let mut item = make_test_item("short", "s1", "Brief");
+        item.metadata = item.metadata.with_ttl(Duration::ZERO);
+        store.store(item).await.unwrap();
+
+        let handle = store.spawn_compaction(Duration::from_millis(10));
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert!(store.scan_ids(CF_ITEMS).unwrap().is_empty());
+
+        // The task stops once the store is dropped
+        drop(store);
+        tokio::time::timeout(Duration::from_secs(1), handle)
+            .await
+            .unwrap()
+            .unwrap();
+    }
 }
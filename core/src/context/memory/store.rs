@@ -12,8 +12,10 @@ use tracing::{debug, trace};
 
 /// Unified storage interface for context items.
 ///
-/// Items are stored immutably - they are never modified or deleted.
-/// This ensures full traceability and allows for temporal queries.
+/// Items are stored immutably - their content is never modified or deleted.
+/// This ensures full traceability and allows for temporal queries. Storing an item
+/// under an existing id replaces it; memory consolidation uses this to update
+/// importance, tags and links.
 #[async_trait]
 pub trait MemoryStore: Send + Sync {
     /// Store a single context item
@@ -74,6 +76,7 @@ impl InMemoryStore {
             }
             ContextItemType::Event { event_type } => format!("event:{}", event_type),
             ContextItemType::Observation { source } => format!("observation:{}", source),
+            ContextItemType::ToolTrace { trace_id } => format!("tool_trace:{}", trace_id),
         }
     }
 
@@ -100,8 +103,32 @@ impl InMemoryStore {
             .push(item.id.clone());
     }
 
+    /// Remove a replaced item from the indices
+    fn remove_from_indices(&self, item: &ContextItem) {
+        if let Some(mut ids) = self.session_index.get_mut(&item.metadata.session_id) {
+            ids.retain(|id| id != &item.id);
+        }
+        if let Some(mut ids) = self.type_index.get_mut(&Self::type_key(&item.item_type)) {
+            ids.retain(|id| id != &item.id);
+        }
+        if let Some(mut ids) = self
+            .time_index
+            .get_mut(&(item.metadata.timestamp_ms / 1000))
+        {
+            ids.retain(|id| id != &item.id);
+        }
+    }
+
     /// Match an item against query filters
     fn matches_query(&self, item: &ContextItem, query: &MemoryQuery) -> bool {
+        // Expired items are never returned
+        if item
+            .metadata
+            .is_expired(chrono::Utc::now().timestamp_millis())
+        {
+            return false;
+        }
+
         // Session filter
         if let Some(ref session_id) = query.session_id {
             if &item.metadata.session_id != session_id {
@@ -172,6 +199,9 @@ impl MemoryStore for InMemoryStore {
             "Storing context item"
         );
 
+        if let Some(previous) = self.items.get(&item.id).map(|e| e.value().clone()) {
+            self.remove_from_indices(&previous);
+        }
         self.update_indices(&item);
         self.items.insert(item.id.clone(), item);
 
@@ -189,7 +219,12 @@ impl MemoryStore for InMemoryStore {
     }
 
     async fn get(&self, id: &str) -> Result<Option<ContextItem>> {
-        Ok(self.items.get(id).map(|entry| entry.value().clone()))
+        let now_ms = chrono::Utc::now().timestamp_millis();
+        Ok(self
+            .items
+            .get(id)
+            .map(|entry| entry.value().clone())
+            .filter(|item| !item.metadata.is_expired(now_ms)))
     }
 
     async fn query(&self, query: &MemoryQuery) -> Result<Vec<ContextItem>> {
@@ -218,7 +253,7 @@ impl MemoryStore for InMemoryStore {
             .collect();
 
         // Sort by timestamp (newest first by default)
-        results.sort_by(|a, b| b.metadata.timestamp_ms.cmp(&a.metadata.timestamp_ms));
+        results.sort_by_key(|r| std::cmp::Reverse(r.metadata.timestamp_ms));
 
         // Apply offset and limit
         let start = query.offset.min(results.len());
@@ -255,6 +290,44 @@ impl MemoryStore for InMemoryStore {
     }
 }
 
+/// Context items are immutable by design, so the store reports its size (counting
+/// towards the global cap) but never sheds.
+#[async_trait]
+impl crate::governor::MemoryComponent for InMemoryStore {
+    fn name(&self) -> String {
+        "context_store".to_string()
+    }
+
+    fn shed_priority(&self) -> u8 {
+        u8::MAX
+    }
+
+    async fn memory_usage(&self) -> usize {
+        self.items
+            .iter()
+            .map(|e| {
+                let item = e.value();
+                let content = serde_json::to_string(&item.content)
+                    .map(|c| c.len())
+                    .unwrap_or(0);
+                let tags: usize = item
+                    .metadata
+                    .tags
+                    .iter()
+                    .map(|(k, v)| k.len() + v.len())
+                    .sum();
+                // id is also held by the three indices
+                4 * item.id.len()
+                    + content
+                    + tags
+                    + item.metadata.session_id.len()
+                    + item.metadata.agent_id.len()
+                    + 128
+            })
+            .sum()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
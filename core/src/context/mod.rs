@@ -10,19 +10,23 @@
 //! - **Retrieval**: Strategies for finding relevant context
 //! - **Ranking**: Strategies for ordering context by relevance
 //! - **Window**: Token budget management
-//! - **Pipeline**: Orchestration of full retrieval→ranking→windowing flow
+//! - **Pipeline**: Orchestration of full retrieval→ranking→windowing flow, with optional
+//!   dedup and hierarchical summarization of overflow
+//! - **Consolidation**: Periodic background job summarizing old items into linked summaries,
+//!   boosting repeatedly retrieved items and demoting stale ones
 //! - **AgentContext**: High-level API for agents
 //! - **Builder**: Legacy prompt bundle builder (will be replaced by pipeline)
 //!
 //! # Design Principles
 //!
-//! 1. **Everything is Retrievable**: No irreversible summarization
+//! 1. **Everything is Retrievable**: No irreversible summarization (summaries are new items)
 //! 2. **Full Traceability**: All items linked via OpenTelemetry traces
 //! 3. **Tool-First**: Tool calls and results are first-class citizens
 //! 4. **Intelligent Selection**: Dynamic context windowing based on relevance
 
 pub mod agent_context;
 pub mod builder;
+pub mod consolidation;
 pub mod memory;
 pub mod pipeline;
 pub mod ranking;
@@ -34,7 +38,7 @@ pub use types::{
     ContextContent, ContextItem, ContextItemType, ContextMetadata, MemoryQuery, MessageRole,
 };
 
-pub use memory::{InMemoryStore, MemoryStore, RocksDbStore};
+pub use memory::{InMemoryStore, MemoryStore, Partitioning, RocksDbStore, RocksDbStoreConfig};
 
 pub use retrieval::{
     CompositeRetrieval, ImportanceRetrieval, RecencyRetrieval, RetrievalStrategy, RetrievalTrigger,
@@ -43,16 +47,21 @@ pub use retrieval::{
 
 pub use ranking::{CompositeRanker, ContextRanker, ImportanceRanker, TemporalRanker};
 
-pub use window::{TiktokenCounter, TokenCounter, WindowConfig, WindowManager};
+pub use window::{TiktokenCounter, TokenCounter, TokenizerRegistry, WindowConfig, WindowManager};
 
-pub use pipeline::{ContextPipeline, PipelineConfig, PipelineResult};
+pub use pipeline::{
+    ContextPipeline, LlmSummarizer, PipelineConfig, PipelineResult, SummarizationConfig,
+    SummarizationStage, Summarizer,
+};
+
+pub use consolidation::{ConsolidationConfig, ConsolidationReport, MemoryConsolidator};
 
 pub use agent_context::AgentContext;
 
 use serde::{Deserialize, Serialize};
 
 /// Token budget to control prompt assembly size
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub struct TokenBudget {
     pub max_input_tokens: usize,
     pub max_output_tokens: usize,
@@ -67,6 +76,64 @@ impl Default for TokenBudget {
     }
 }
 
+/// Event metadata keys carrying a `ModelBudget` from routing to the behavior
+pub const ROUTING_MAX_INPUT_TOKENS_KEY: &str = "routing_max_input_tokens";
+pub const ROUTING_MAX_OUTPUT_TOKENS_KEY: &str = "routing_max_output_tokens";
+pub const ROUTING_INPUT_COST_KEY: &str = "routing_input_cost_per_1k";
+
+/// Budget of the model a request was routed to
+///
+/// Produced by `ModelRouter::context_budget` once a model is chosen, so context
+/// assembly (`ContextPipeline::execute_with_budget`, `TriggerInput::with_model_budget`)
+/// fits the prompt to that model instead of a static `TokenBudget`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ModelBudget {
+    pub model: String,
+    pub budget: TokenBudget,
+    /// USD per 1K input tokens (0.0 for local models)
+    pub input_cost_per_1k: f32,
+}
+
+impl ModelBudget {
+    /// Cost of sending `input_tokens` to the model
+    pub fn input_cost(&self, input_tokens: usize) -> f32 {
+        input_tokens as f32 / 1000.0 * self.input_cost_per_1k
+    }
+
+    /// Write the budget into event metadata (the model goes under `routing_model`)
+    pub fn to_metadata(&self, metadata: &mut std::collections::HashMap<String, String>) {
+        metadata.insert("routing_model".into(), self.model.clone());
+        metadata.insert(
+            ROUTING_MAX_INPUT_TOKENS_KEY.into(),
+            self.budget.max_input_tokens.to_string(),
+        );
+        metadata.insert(
+            ROUTING_MAX_OUTPUT_TOKENS_KEY.into(),
+            self.budget.max_output_tokens.to_string(),
+        );
+        metadata.insert(
+            ROUTING_INPUT_COST_KEY.into(),
+            self.input_cost_per_1k.to_string(),
+        );
+    }
+
+    /// Budget written by `to_metadata`, if the event was routed to a model
+    pub fn from_metadata(metadata: &std::collections::HashMap<String, String>) -> Option<Self> {
+        let parse = |key: &str| metadata.get(key).and_then(|v| v.parse::<usize>().ok());
+        Some(Self {
+            model: metadata.get("routing_model")?.clone(),
+            budget: TokenBudget {
+                max_input_tokens: parse(ROUTING_MAX_INPUT_TOKENS_KEY)?,
+                max_output_tokens: parse(ROUTING_MAX_OUTPUT_TOKENS_KEY)?,
+            },
+            input_cost_per_1k: metadata
+                .get(ROUTING_INPUT_COST_KEY)
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(0.0),
+        })
+    }
+}
+
 /// A bundle of prompt components for an LLM call
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct PromptBundle {
@@ -1,3 +1,5 @@
 pub mod orchestrator;
+pub mod summarize;
 
 pub use orchestrator::{ContextPipeline, PipelineConfig, PipelineResult};
+pub use summarize::{LlmSummarizer, SummarizationConfig, SummarizationStage, Summarizer};
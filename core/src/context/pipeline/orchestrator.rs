@@ -1,12 +1,15 @@
 //! Context Pipeline Orchestrator
 //!
-//! Coordinates retrieval → ranking → windowing → assembly flow
+//! Coordinates retrieval → ranking → (dedup) → windowing → (summarization) → assembly flow
 
+use crate::context::consolidation::MemoryConsolidator;
 use crate::context::memory::MemoryStore;
+use crate::context::pipeline::summarize::SummarizationStage;
 use crate::context::ranking::ContextRanker;
 use crate::context::retrieval::{RetrievalStrategy, RetrievalTrigger};
 use crate::context::types::ContextItem;
-use crate::context::window::WindowManager;
+use crate::context::window::{TokenizerRegistry, WindowManager};
+use crate::context::ModelBudget;
 use crate::Result;
 use std::sync::Arc;
 use tracing::{debug, info, instrument};
@@ -58,6 +61,15 @@ pub struct PipelineResult {
 
     /// Number of items after ranking
     pub ranked_count: usize,
+
+    /// Cost of sending the selected items to the budgeted model (0.0 without a budget)
+    pub estimated_input_cost: f32,
+
+    /// Ranked items removed as duplicates by the summarization stage
+    pub deduplicated_count: usize,
+
+    /// Summaries included in `items` (they cover `overflow_items`)
+    pub summary_count: usize,
 }
 
 /// Main context pipeline orchestrator
@@ -66,13 +78,17 @@ pub struct PipelineResult {
 /// 1. **Retrieve** relevant items from memory (configurable strategy)
 /// 2. **Rank** items by relevance/importance
 /// 3. **Window** selection based on token budget
-/// 4. **Assemble** final context for LLM
+/// 4. **Summarize** overflow, with `with_summarization`
+/// 5. **Assemble** final context for LLM
 pub struct ContextPipeline {
     store: Arc<dyn MemoryStore>,
     retrieval: Arc<dyn RetrievalStrategy>,
     ranker: Arc<dyn ContextRanker>,
     window: WindowManager,
     config: PipelineConfig,
+    tokenizers: Option<Arc<TokenizerRegistry>>,
+    summarization: Option<Arc<SummarizationStage>>,
+    consolidator: Option<Arc<MemoryConsolidator>>,
 }
 
 impl ContextPipeline {
@@ -89,12 +105,57 @@ impl ContextPipeline {
             ranker,
             window,
             config,
+            tokenizers: None,
+            summarization: None,
+            consolidator: None,
         }
     }
 
+    /// Count items with the budgeted model's tokenizer in `execute_with_budget`
+    pub fn with_tokenizers(mut self, tokenizers: Arc<TokenizerRegistry>) -> Self {
+        self.tokenizers = Some(tokenizers);
+        self
+    }
+
+    /// Dedup ranked items and summarize what overflows the window instead of dropping it
+    pub fn with_summarization(mut self, stage: Arc<SummarizationStage>) -> Self {
+        self.summarization = Some(stage);
+        self
+    }
+
+    /// Report the selected items of every run to `consolidator`, which boosts items
+    /// that are retrieved repeatedly
+    pub fn with_consolidator(mut self, consolidator: Arc<MemoryConsolidator>) -> Self {
+        self.consolidator = Some(consolidator);
+        self
+    }
+
     /// Execute the full pipeline for a given query
     #[instrument(skip(self, trigger), fields(session = %trigger.session_id))]
     pub async fn execute(&self, trigger: RetrievalTrigger) -> Result<PipelineResult> {
+        self.run(trigger, &self.window, None).await
+    }
+
+    /// Execute the pipeline for the model a request was routed to: the window is
+    /// resized to `budget` (and its tokenizer used, with `with_tokenizers`) for this
+    /// run only
+    #[instrument(skip(self, trigger, budget), fields(session = %trigger.session_id, model = %budget.model))]
+    pub async fn execute_with_budget(
+        &self,
+        trigger: RetrievalTrigger,
+        budget: &ModelBudget,
+    ) -> Result<PipelineResult> {
+        let mut window = self.window.clone();
+        window.set_budget(budget, self.tokenizers.as_deref());
+        self.run(trigger, &window, Some(budget)).await
+    }
+
+    async fn run(
+        &self,
+        trigger: RetrievalTrigger,
+        window: &WindowManager,
+        budget: Option<&ModelBudget>,
+    ) -> Result<PipelineResult> {
         info!("Pipeline execution started: session={}", trigger.session_id);
 
         // Phase 1: Retrieval
@@ -119,17 +180,68 @@ impl ContextPipeline {
         let ranked_count = ranked_items.len();
         debug!("Ranked {} items", ranked_count);
 
+        // Phase 2.5: Dedup (optional)
+        let (ranked_items, deduplicated_count) = match self.summarization {
+            Some(ref stage) => stage.dedup(ranked_items),
+            None => (ranked_items, 0),
+        };
+        if deduplicated_count > 0 {
+            debug!("Removed {} duplicate items", deduplicated_count);
+        }
+
         // Phase 3: Window selection
         debug!(
             "Phase 3: Window selection (budget={})",
-            self.window.config().available_tokens()
+            window.config().available_tokens()
         );
-        let selection = self.window.select_items(ranked_items);
+        let mut selection = window.select_items(ranked_items);
+
+        // Phase 4: Summarization of the overflow (optional)
+        let mut summary_count = 0;
+        if let Some(ref stage) = self.summarization {
+            if !selection.overflow.is_empty() {
+                // Free the summary share of the window, least relevant items first
+                let reserve = (selection.budget as f32
+                    * stage.config().summary_budget.clamp(0.0, 1.0))
+                    as usize;
+                while selection.tokens_used + reserve > selection.budget {
+                    let Some(item) = selection.selected.pop() else {
+                        break;
+                    };
+                    selection.tokens_used -= window.count_item(&item);
+                    selection.overflow.push(item);
+                }
+                let remaining = selection.budget - selection.tokens_used;
+                debug!(
+                    "Phase 4: Summarizing {} overflow items (budget={})",
+                    selection.overflow.len(),
+                    remaining
+                );
+                let selected_ids: std::collections::HashSet<String> =
+                    selection.selected.iter().map(|i| i.id.clone()).collect();
+                let summaries = stage
+                    .summarize(&*self.store, window, selection.overflow.clone(), remaining)
+                    .await?;
+                for summary in summaries {
+                    if selected_ids.contains(&summary.id) {
+                        continue;
+                    }
+                    selection.tokens_used += window.count_item(&summary);
+                    selection.selected.push(summary);
+                    summary_count += 1;
+                }
+            }
+        }
+
+        if let Some(ref consolidator) = self.consolidator {
+            consolidator.record_retrieval(&selection.selected);
+        }
 
         info!(
-            "Pipeline complete: {}/{} items selected, {}/{} tokens used",
+            "Pipeline complete: {}/{} items selected ({} summaries), {}/{} tokens used",
             selection.selected.len(),
             retrieved_count,
+            summary_count,
             selection.tokens_used,
             selection.budget
         );
@@ -141,6 +253,9 @@ impl ContextPipeline {
             budget: selection.budget,
             retrieved_count,
             ranked_count,
+            estimated_input_cost: budget.map_or(0.0, |b| b.input_cost(selection.tokens_used)),
+            deduplicated_count,
+            summary_count,
         })
     }
 
@@ -272,8 +387,10 @@ mod tests {
         let mut pipeline = create_test_pipeline().await;
 
         // Set very small window
-        let mut small_config = WindowConfig::default();
-        small_config.max_tokens = 500; // Very small
+        let small_config = WindowConfig {
+            max_tokens: 500, // Very small
+            ..Default::default()
+        };
         pipeline.window_mut().set_config(small_config);
 
         // Add many large items
@@ -343,12 +460,56 @@ mod tests {
         let mut pipeline = create_test_pipeline().await;
 
         // Update config
-        let mut new_config = PipelineConfig::default();
-        new_config.max_retrieval_items = 50;
-        new_config.include_related = false;
+        let new_config = PipelineConfig {
+            max_retrieval_items: 50,
+            include_related: false,
+            ..Default::default()
+        };
         pipeline.set_config(new_config);
 
         assert_eq!(pipeline.config().max_retrieval_items, 50);
         assert!(!pipeline.config().include_related);
     }
+
+    #[tokio::test]
+    async fn test_pipeline_fits_routed_model_budget() {
+        use crate::context::TokenBudget;
+
+        let pipeline = create_test_pipeline().await;
+        for i in 0..10 {
+            let item = create_test_item(
+                "session1",
+                &format!("Message {} - {}", i, "x".repeat(200)),
+                0.5,
+            );
+            pipeline.store.store(item).await.unwrap();
+        }
+        let trigger = || RetrievalTrigger::new("session1".to_string(), "test".to_string());
+
+        // The default window fits everything
+        let result = pipeline.execute(trigger()).await.unwrap();
+        assert!(result.overflow_items.is_empty());
+        assert_eq!(result.estimated_input_cost, 0.0);
+
+        // A small local model does not
+        let budget = ModelBudget {
+            model: "lightweight_llm".to_string(),
+            budget: TokenBudget {
+                max_input_tokens: 1_200,
+                max_output_tokens: 256,
+            },
+            input_cost_per_1k: 0.5,
+        };
+        let result = pipeline
+            .execute_with_budget(trigger(), &budget)
+            .await
+            .unwrap();
+        assert_eq!(result.budget, 200);
+        assert!(!result.overflow_items.is_empty());
+        assert!(result.tokens_used <= 200);
+        assert!((result.estimated_input_cost - budget.input_cost(result.tokens_used)).abs() < 1e-6);
+
+        // The pipeline's own window is unchanged
+        assert_eq!(pipeline.window.config().max_tokens, 8000);
+    }
 }
@@ -0,0 +1,333 @@
+//! Dedup and hierarchical summarization stage.
+//!
+//! When ranked items overflow the window, the stage summarizes the overflow instead of
+//! dropping it: chronological chunks of `chunk_size` items become level-1 summaries,
+//! and while those still do not fit, groups of `fanout` summaries are summarized again,
+//! up to `max_levels`. Summaries are stored as new `ContextItem`s (tagged with their
+//! level and the ids they cover), so they are retrievable later; originals are never
+//! modified or removed.
+//!
+//! Summaries are cached under a SHA-256 of the items they cover: first in memory, then in
+//! the `MemoryStore` (by tag), so a chunk is summarized by the LLM once.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use dashmap::DashMap;
+use ring::digest;
+use tracing::debug;
+
+use crate::cognitive::llm::LlmClient;
+use crate::context::memory::MemoryStore;
+use crate::context::types::{
+    ContextContent, ContextItem, ContextItemType, ContextMetadata, MemoryQuery,
+};
+use crate::context::window::WindowManager;
+use crate::context::{PromptBundle, TokenBudget};
+use crate::Result;
+
+/// `ContextItemType::Observation` source of summary items
+pub const SUMMARY_SOURCE: &str = "context.summary";
+
+/// Tag holding the hash of the items a summary covers (its cache key)
+pub const SUMMARY_KEY_TAG: &str = "summary.key";
+
+/// Tag holding a summary's level (1 summarizes originals)
+pub const SUMMARY_LEVEL_TAG: &str = "summary.level";
+
+/// Tag holding the comma-separated ids of the items a summary covers
+pub const SUMMARY_COVERS_TAG: &str = "summary.covers";
+
+/// Produces the text of a summary
+#[async_trait]
+pub trait Summarizer: Send + Sync {
+    /// Summarize `texts` (oldest first) in at most `max_tokens` tokens
+    async fn summarize(&self, texts: &[String], max_tokens: usize) -> Result<String>;
+}
+
+/// Summarizer backed by `LlmClient::generate`
+pub struct LlmSummarizer {
+    llm: Arc<LlmClient>,
+    system_prompt: String,
+}
+
+impl LlmSummarizer {
+    pub fn new(llm: Arc<LlmClient>) -> Self {
+        Self {
+            llm,
+            system_prompt: "You condense conversation history for an agent's context window. \
+                Keep facts, decisions, open questions, names and numbers; drop pleasantries."
+                .to_string(),
+        }
+    }
+
+    pub fn with_system_prompt(mut self, prompt: impl Into<String>) -> Self {
+        self.system_prompt = prompt.into();
+        self
+    }
+}
+
+#[async_trait]
+impl Summarizer for LlmSummarizer {
+    async fn summarize(&self, texts: &[String], max_tokens: usize) -> Result<String> {
+        let bundle = PromptBundle {
+            system: self.system_prompt.clone(),
+            instructions: "Summarize the context above, oldest to newest, in a few sentences."
+                .to_string(),
+            tools_json_schema: None,
+            context_docs: texts.to_vec(),
+            history: vec![],
+        };
+        let budget = TokenBudget {
+            max_output_tokens: max_tokens,
+            ..TokenBudget::default()
+        };
+        let response = self.llm.generate(&bundle, Some(budget)).await?;
+        Ok(response.text.trim().to_string())
+    }
+}
+
+/// Configuration of the summarization stage
+#[derive(Debug, Clone)]
+pub struct SummarizationConfig {
+    /// Drop items repeating an earlier item's type and text, and stored summaries whose
+    /// covered items were all retrieved
+    pub dedup: bool,
+    /// Original items per level-1 summary
+    pub chunk_size: usize,
+    /// Summaries per higher-level summary
+    pub fanout: usize,
+    /// Highest summary level built
+    pub max_levels: usize,
+    /// Output tokens requested per summary
+    pub summary_tokens: usize,
+    /// Fraction of the window kept free for summaries once items overflow (0.0-1.0)
+    pub summary_budget: f32,
+    /// Importance of stored summaries
+    pub importance: f32,
+}
+
+impl Default for SummarizationConfig {
+    fn default() -> Self {
+        Self {
+            dedup: true,
+            chunk_size: 8,
+            fanout: 4,
+            max_levels: 3,
+            summary_tokens: 256,
+            summary_budget: 0.2,
+            importance: 0.6,
+        }
+    }
+}
+
+/// Dedup and summarization stage run by `ContextPipeline` between ranking and assembly
+pub struct SummarizationStage {
+    summarizer: Arc<dyn Summarizer>,
+    config: SummarizationConfig,
+    cache: DashMap<String, ContextItem>,
+}
+
+impl SummarizationStage {
+    pub fn new(summarizer: Arc<dyn Summarizer>, config: SummarizationConfig) -> Self {
+        Self {
+            summarizer,
+            config,
+            cache: DashMap::new(),
+        }
+    }
+
+    /// Stage summarizing with `llm` and the default configuration
+    pub fn with_llm(llm: Arc<LlmClient>) -> Self {
+        Self::new(
+            Arc::new(LlmSummarizer::new(llm)),
+            SummarizationConfig::default(),
+        )
+    }
+
+    pub fn config(&self) -> &SummarizationConfig {
+        &self.config
+    }
+
+    /// Summaries held in the in-memory cache
+    pub fn cached_summaries(&self) -> usize {
+        self.cache.len()
+    }
+
+    /// Remove repeated items from ranked `items`, keeping the first (most relevant)
+    /// occurrence; returns the kept items and how many were removed
+    pub fn dedup(&self, items: Vec<ContextItem>) -> (Vec<ContextItem>, usize) {
+        if !self.config.dedup {
+            return (items, 0);
+        }
+        let ids: HashSet<&str> = items.iter().map(|i| i.id.as_str()).collect();
+        let redundant: HashSet<String> = items
+            .iter()
+            .filter(|item| match covered_ids(item) {
+                Some(covered) => covered.iter().all(|id| ids.contains(id.as_str())),
+                None => false,
+            })
+            .map(|item| item.id.clone())
+            .collect();
+
+        let before = items.len();
+        let mut seen = HashSet::new();
+        let kept: Vec<ContextItem> = items
+            .into_iter()
+            .filter(|item| !redundant.contains(&item.id))
+            .filter(|item| seen.insert(content_key(item)))
+            .collect();
+        let removed = before - kept.len();
+        (kept, removed)
+    }
+
+    /// Summaries of `overflow` fitting in `budget` tokens, built (or reused) as
+    /// described in the module docs; stored summaries go to `store`
+    pub async fn summarize(
+        &self,
+        store: &dyn MemoryStore,
+        window: &WindowManager,
+        mut overflow: Vec<ContextItem>,
+        budget: usize,
+    ) -> Result<Vec<ContextItem>> {
+        if overflow.is_empty() || budget == 0 {
+            return Ok(vec![]);
+        }
+        overflow.sort_by_key(|item| item.metadata.timestamp_ms);
+
+        let mut level = 1;
+        let mut current = overflow;
+        let summaries = loop {
+            let group = if level == 1 {
+                self.config.chunk_size
+            } else {
+                self.config.fanout
+            };
+            let mut summaries = Vec::new();
+            for chunk in current.chunks(group.max(2)) {
+                summaries.push(self.summary_for(store, chunk, level).await?);
+            }
+            let tokens: usize = summaries.iter().map(|s| window.count_item(s)).sum();
+            debug!(
+                level,
+                summaries = summaries.len(),
+                tokens,
+                budget,
+                "Built context summaries"
+            );
+            if tokens <= budget || summaries.len() == 1 || level >= self.config.max_levels {
+                break summaries;
+            }
+            current = summaries;
+            level += 1;
+        };
+
+        // Newest summaries first, as far as they fit
+        let mut summaries = summaries;
+        summaries.reverse();
+        Ok(window.select_with_budget(summaries, budget).selected)
+    }
+
+    async fn summary_for(
+        &self,
+        store: &dyn MemoryStore,
+        chunk: &[ContextItem],
+        level: usize,
+    ) -> Result<ContextItem> {
+        let key = chunk_key(chunk, level);
+        if let Some(summary) = self.cache.get(&key) {
+            return Ok(summary.clone());
+        }
+
+        let session_id = chunk[0].metadata.session_id.clone();
+        let query = MemoryQuery {
+            tags: Some(HashMap::from([(SUMMARY_KEY_TAG.to_string(), key.clone())])),
+            ..MemoryQuery::new().for_session(session_id.clone()).limit(1)
+        };
+        if let Some(summary) = store.query(&query).await?.into_iter().next() {
+            self.cache.insert(key, summary.clone());
+            return Ok(summary);
+        }
+
+        let texts: Vec<String> = chunk.iter().map(|item| item.content.text.clone()).collect();
+        let text = self
+            .summarizer
+            .summarize(&texts, self.config.summary_tokens)
+            .await?;
+        let covers: Vec<&str> = chunk.iter().map(|item| item.id.as_str()).collect();
+        let mut metadata = ContextMetadata::new(session_id, chunk[0].metadata.agent_id.clone())
+            .with_importance(self.config.importance)
+            .with_tag(SUMMARY_KEY_TAG.to_string(), key.clone())
+            .with_tag(SUMMARY_LEVEL_TAG.to_string(), level.to_string())
+            .with_tag(SUMMARY_COVERS_TAG.to_string(), covers.join(","));
+        // Sorts with the newest item it covers
+        metadata.timestamp_ms = chunk
+            .iter()
+            .map(|item| item.metadata.timestamp_ms)
+            .max()
+            .unwrap_or(metadata.timestamp_ms);
+        let summary = ContextItem::new(
+            ContextItemType::Observation {
+                source: SUMMARY_SOURCE.to_string(),
+            },
+            ContextContent::from_string(text),
+            metadata,
+        );
+        store.store(summary.clone()).await?;
+        self.cache.insert(key, summary.clone());
+        Ok(summary)
+    }
+}
+
+/// Whether `item` is a summary built by the stage
+pub fn is_summary(item: &ContextItem) -> bool {
+    matches!(item.item_type, ContextItemType::Observation { ref source } if source == SUMMARY_SOURCE)
+}
+
+/// Ids a summary covers; `None` for other items
+fn covered_ids(item: &ContextItem) -> Option<Vec<String>> {
+    if !is_summary(item) {
+        return None;
+    }
+    let covers = item.metadata.tags.get(SUMMARY_COVERS_TAG)?;
+    Some(covers.split(',').map(str::to_string).collect())
+}
+
+/// Type and whitespace/case-normalized text of an item
+fn content_key(item: &ContextItem) -> String {
+    let item_type = serde_json::to_string(&item.item_type).unwrap_or_default();
+    let words: Vec<String> = item
+        .content
+        .text
+        .split_whitespace()
+        .map(str::to_lowercase)
+        .collect();
+    let text = words.join(" ");
+    digest_hex([item_type.as_bytes(), text.as_bytes()])
+}
+
+/// Cache key of a summary: its level and the ids and text of the items it covers.
+/// Persisted as `SUMMARY_KEY_TAG`, so it must not change across processes.
+pub(crate) fn chunk_key(chunk: &[ContextItem], level: usize) -> String {
+    let level = (level as u64).to_le_bytes();
+    let fields = chunk
+        .iter()
+        .flat_map(|item| [item.id.as_bytes(), item.content.text.as_bytes()]);
+    digest_hex(std::iter::once(&level[..]).chain(fields))
+}
+
+/// Hex SHA-256 of `fields`, each prefixed with its length
+fn digest_hex<'a>(fields: impl IntoIterator<Item = &'a [u8]>) -> String {
+    let mut context = digest::Context::new(&digest::SHA256);
+    for field in fields {
+        context.update(&(field.len() as u64).to_le_bytes());
+        context.update(field);
+    }
+    context
+        .finish()
+        .as_ref()
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect()
+}
@@ -375,7 +375,7 @@ mod tests {
         let items = strategy.retrieve(&*store, &trigger).await.unwrap();
 
         // Should get items from both strategies, deduplicated
-        assert!(items.len() > 0);
+        assert!(!items.is_empty());
         assert!(items.len() <= 8); // 3 + 5, but with potential overlap
     }
 }
@@ -89,6 +89,10 @@ pub enum ContextItemType {
 
     /// An observation or perception from the environment
     Observation { source: String },
+
+    /// A plan, reasoning step or tool observation of one cognitive cycle; items of the
+    /// same cycle share `trace_id` (see `cognitive::trace_log`)
+    ToolTrace { trace_id: String },
 }
 
 /// Roles for messages in conversation
@@ -233,6 +237,10 @@ pub struct ContextMetadata {
     /// OpenTelemetry span ID for distributed tracing
     #[serde(skip_serializing_if = "Option::is_none")]
     pub span_id: Option<String>,
+
+    /// When this item expires (Unix timestamp in milliseconds); never if unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires_at_ms: Option<i64>,
 }
 
 impl ContextMetadata {
@@ -247,6 +255,7 @@ impl ContextMetadata {
             tags: HashMap::new(),
             trace_id: None,
             span_id: None,
+            expires_at_ms: None,
         }
     }
 
@@ -262,6 +271,17 @@ impl ContextMetadata {
         self
     }
 
+    /// Expire the item `ttl` after its timestamp
+    pub fn with_ttl(mut self, ttl: std::time::Duration) -> Self {
+        self.expires_at_ms = Some(self.timestamp_ms.saturating_add(ttl.as_millis() as i64));
+        self
+    }
+
+    /// Whether the item has expired at `now_ms`
+    pub fn is_expired(&self, now_ms: i64) -> bool {
+        self.expires_at_ms.is_some_and(|at| at <= now_ms)
+    }
+
     /// Add a tag
     pub fn with_tag(mut self, key: String, value: String) -> Self {
         self.tags.insert(key, value);
@@ -347,6 +367,14 @@ impl MemoryQuery {
     }
 
     /// Set result limit
+    /// Filter by a tag value (all tags added must match)
+    pub fn with_tag(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.tags
+            .get_or_insert_with(HashMap::new)
+            .insert(key.into(), value.into());
+        self
+    }
+
     pub fn limit(mut self, limit: usize) -> Self {
         self.limit = limit;
         self
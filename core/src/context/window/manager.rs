@@ -4,6 +4,8 @@
 
 use crate::context::types::{ContextItem, ContextItemType, MessageRole};
 use crate::context::window::token_counter::TokenCounter;
+use crate::context::window::tokenizer::TokenizerRegistry;
+use crate::context::{ModelBudget, TokenBudget};
 use std::sync::Arc;
 
 /// Configuration for context window management
@@ -54,14 +56,24 @@ impl WindowConfig {
             .saturating_sub(self.query_reserve)
     }
 
+    /// `self` resized to `budget`: the window holds its input and output tokens and the
+    /// response reserve is its output. The other reserves and fractions are kept.
+    pub fn fit_to(&self, budget: &TokenBudget) -> WindowConfig {
+        WindowConfig {
+            max_tokens: budget.max_input_tokens + budget.max_output_tokens,
+            response_reserve: budget.max_output_tokens,
+            ..self.clone()
+        }
+    }
+
     /// Get token budget for a specific item type
     pub fn budget_for_type(&self, item_type: &ContextItemType) -> usize {
         let available = self.available_tokens() as f32;
 
         match item_type {
-            ContextItemType::ToolCall { .. } | ContextItemType::ToolResult { .. } => {
-                (available * self.tool_results_budget) as usize
-            }
+            ContextItemType::ToolCall { .. }
+            | ContextItemType::ToolResult { .. }
+            | ContextItemType::ToolTrace { .. } => (available * self.tool_results_budget) as usize,
             ContextItemType::Message { .. } => (available * self.messages_budget) as usize,
             ContextItemType::Event { .. } | ContextItemType::Observation { .. } => {
                 (available * self.observations_budget) as usize
@@ -87,6 +99,7 @@ pub struct WindowSelection {
 }
 
 /// Manages context window selection based on token budgets
+#[derive(Clone)]
 pub struct WindowManager {
     counter: Arc<dyn TokenCounter>,
     config: WindowConfig,
@@ -102,6 +115,31 @@ impl WindowManager {
         Self::new(counter, WindowConfig::default())
     }
 
+    /// Create for `model`: its tokenizer counts items and its context window becomes
+    /// `max_tokens`; the reserves and per-type budgets come from `config`
+    pub fn for_model(tokenizers: &TokenizerRegistry, model: &str, config: WindowConfig) -> Self {
+        Self::new(
+            tokenizers.counter(model),
+            tokenizers.window_config(model, config),
+        )
+    }
+
+    /// Switch to `model`'s tokenizer and context window, e.g. after the router picked
+    /// a different model
+    pub fn set_model(&mut self, tokenizers: &TokenizerRegistry, model: &str) {
+        self.counter = tokenizers.counter(model);
+        self.config.max_tokens = tokenizers.context_window(model);
+    }
+
+    /// Fit the window to the model a request was routed to; with `tokenizers`, items
+    /// are also counted with that model's tokenizer
+    pub fn set_budget(&mut self, budget: &ModelBudget, tokenizers: Option<&TokenizerRegistry>) {
+        if let Some(tokenizers) = tokenizers {
+            self.counter = tokenizers.counter(&budget.model);
+        }
+        self.config = self.config.fit_to(&budget.budget);
+    }
+
     /// Count tokens for a single item
     pub fn count_item(&self, item: &ContextItem) -> usize {
         // Count main content
@@ -132,7 +170,9 @@ impl WindowManager {
         // Helper to get budget for item type
         let get_budget = |item_type: &ContextItemType| -> usize {
             match item_type {
-                ContextItemType::ToolCall { .. } | ContextItemType::ToolResult { .. } => {
+                ContextItemType::ToolCall { .. }
+                | ContextItemType::ToolResult { .. }
+                | ContextItemType::ToolTrace { .. } => {
                     (self.config.available_tokens() as f32 * self.config.tool_results_budget)
                         as usize
                 }
@@ -169,9 +209,9 @@ impl WindowManager {
 
             // Check per-type budget
             let type_budget = match &item.item_type {
-                ContextItemType::ToolCall { .. } | ContextItemType::ToolResult { .. } => {
-                    &mut tool_budget
-                }
+                ContextItemType::ToolCall { .. }
+                | ContextItemType::ToolResult { .. }
+                | ContextItemType::ToolTrace { .. } => &mut tool_budget,
                 ContextItemType::Message { .. } => &mut message_budget,
                 ContextItemType::Event { .. } | ContextItemType::Observation { .. } => {
                     &mut observation_budget
@@ -362,7 +402,7 @@ mod tests {
         let selection = manager.select_items(items);
 
         // Should have some overflow
-        assert!(selection.overflow.len() > 0);
+        assert!(!selection.overflow.is_empty());
         assert!(selection.tokens_used <= selection.budget);
     }
 
@@ -389,18 +429,19 @@ mod tests {
         let selection = manager.select_with_budget(items, 50); // Very small budget
 
         // Should select at least one, might overflow second
-        assert!(selection.selected.len() > 0);
+        assert!(!selection.selected.is_empty());
         assert!(selection.tokens_used <= 50);
     }
 
     #[test]
     fn test_per_type_budget_enforcement() {
         let counter = Arc::new(TiktokenCounter::gpt4());
-        let mut config = WindowConfig::default();
-
         // Give messages tiny budget
-        config.messages_budget = 0.1; // Only 10% of available
-        config.tool_results_budget = 0.5;
+        let config = WindowConfig {
+            messages_budget: 0.1, // Only 10% of available
+            tool_results_budget: 0.5,
+            ..Default::default()
+        };
 
         let manager = WindowManager::new(counter, config);
 
@@ -453,4 +494,37 @@ mod tests {
         // Tool result should fit in its larger budget
         assert_eq!(tool_count, 1);
     }
+
+    #[test]
+    fn test_budget_shrinks_window() {
+        let counter = Arc::new(TiktokenCounter::gpt4());
+        let mut manager = WindowManager::with_counter(counter);
+        manager.set_budget(
+            &ModelBudget {
+                model: "lightweight_llm".to_string(),
+                budget: TokenBudget {
+                    max_input_tokens: 1_500,
+                    max_output_tokens: 256,
+                },
+                input_cost_per_1k: 0.0,
+            },
+            None,
+        );
+
+        assert_eq!(manager.config().max_tokens, 1_756);
+        assert_eq!(manager.config().response_reserve, 256);
+        // Input budget less the system and query reserves
+        assert_eq!(manager.config().available_tokens(), 500);
+
+        let content = "x".repeat(4_000);
+        let items = vec![create_test_item(
+            ContextItemType::Message {
+                role: MessageRole::User,
+            },
+            &content,
+        )];
+        let selection = manager.select_items(items);
+        assert!(selection.selected.is_empty());
+        assert_eq!(selection.budget, 500);
+    }
 }
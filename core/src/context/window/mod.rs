@@ -1,5 +1,7 @@
 pub mod manager;
 pub mod token_counter;
+pub mod tokenizer;
 
 pub use manager::{WindowConfig, WindowManager};
 pub use token_counter::{create_counter, TiktokenCounter, TokenCounter};
+pub use tokenizer::{BpeCounter, ModelTokenizer, TokenizerKind, TokenizerRegistry};
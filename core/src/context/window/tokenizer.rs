@@ -0,0 +1,304 @@
+//! Per-model Tokenizers
+//!
+//! `TokenizerRegistry` maps model names to the tokenizer and context window of that
+//! model, so token budgets follow whichever model a request is routed to. OpenAI
+//! models use the exact cl100k / o200k BPE encodings; Llama 3 models use the BPE
+//! ranks file shipped with the model once one is registered. Models without a known
+//! tokenizer fall back to the `TiktokenCounter` estimate.
+
+use crate::context::window::token_counter::{TiktokenCounter, TokenCounter};
+use crate::context::window::WindowConfig;
+use crate::context::TokenBudget;
+use crate::{LoomError, Result};
+use base64::Engine;
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::{Arc, OnceLock};
+use tiktoken_rs::CoreBPE;
+use tracing::{info, warn};
+
+/// Pre-tokenization pattern of the Llama 3 tokenizer
+pub const LLAMA3_PATTERN: &str = r"(?i:'s|'t|'re|'ve|'m|'ll|'d)|[^\r\n\p{L}\p{N}]?\p{L}+|\p{N}{1,3}| ?[^\s\p{L}\p{N}]+[\r\n]*|\s*[\r\n]+|\s+(?!\S)|\s+";
+
+/// Context window assumed for models the registry does not know
+pub const DEFAULT_CONTEXT_WINDOW: usize = 8_000;
+
+/// Tokenizer family of a model
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TokenizerKind {
+    /// GPT-4, GPT-3.5
+    Cl100k,
+    /// GPT-4o, GPT-4.1, o-series
+    O200k,
+    /// Llama 3 family, loaded from the model's `tokenizer.model`
+    LlamaBpe,
+    /// Character-based estimate
+    Estimate,
+}
+
+/// Exact token counts from a BPE encoding
+pub struct BpeCounter {
+    bpe: Arc<CoreBPE>,
+}
+
+impl BpeCounter {
+    /// OpenAI `cl100k_base`, loaded once per process
+    pub fn cl100k() -> Option<Self> {
+        static BPE: OnceLock<Option<Arc<CoreBPE>>> = OnceLock::new();
+        Self::shared(&BPE, "cl100k_base", tiktoken_rs::cl100k_base)
+    }
+
+    /// OpenAI `o200k_base`, loaded once per process
+    pub fn o200k() -> Option<Self> {
+        static BPE: OnceLock<Option<Arc<CoreBPE>>> = OnceLock::new();
+        Self::shared(&BPE, "o200k_base", tiktoken_rs::o200k_base)
+    }
+
+    /// Load a tiktoken-format ranks file (`<base64 token> <rank>` per line), such as
+    /// Llama 3's `tokenizer.model`
+    pub fn from_tiktoken_file(path: impl AsRef<Path>, pattern: &str) -> Result<Self> {
+        let path = path.as_ref();
+        let source = std::fs::read_to_string(path)?;
+        let mut encoder = HashMap::new();
+        for (line_no, line) in source.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+            let parsed = line.split_once(' ').and_then(|(token, rank)| {
+                let token = base64::engine::general_purpose::STANDARD
+                    .decode(token)
+                    .ok()?;
+                Some((token, rank.trim().parse::<usize>().ok()?))
+            });
+            let Some((token, rank)) = parsed else {
+                return Err(LoomError::TokenizerError(format!(
+                    "{}:{}: expected '<base64 token> <rank>'",
+                    path.display(),
+                    line_no + 1
+                )));
+            };
+            encoder.insert(token, rank);
+        }
+        // `CoreBPE` takes `FxHashMap`s; collecting lets the signature pick the hasher
+        let bpe = CoreBPE::new(encoder.into_iter().collect(), Default::default(), pattern)
+            .map_err(|e| {
+                LoomError::TokenizerError(format!("Invalid tokenizer {}: {e}", path.display()))
+            })?;
+        Ok(Self { bpe: Arc::new(bpe) })
+    }
+
+    fn shared<E: std::fmt::Display>(
+        cell: &OnceLock<Option<Arc<CoreBPE>>>,
+        name: &str,
+        load: fn() -> std::result::Result<CoreBPE, E>,
+    ) -> Option<Self> {
+        cell.get_or_init(|| match load() {
+            Ok(bpe) => Some(Arc::new(bpe)),
+            Err(e) => {
+                warn!(target: "tokenizer", encoding = name, error = %e, "Failed to load BPE encoding");
+                None
+            }
+        })
+        .clone()
+        .map(|bpe| Self { bpe })
+    }
+}
+
+impl TokenCounter for BpeCounter {
+    fn count_text(&self, text: &str) -> usize {
+        self.bpe.encode_ordinary(text).len()
+    }
+}
+
+/// Tokenizer and context window of a model
+#[derive(Clone)]
+pub struct ModelTokenizer {
+    pub kind: TokenizerKind,
+    pub counter: Arc<dyn TokenCounter>,
+    /// Input plus output tokens the model accepts
+    pub context_window: usize,
+}
+
+impl ModelTokenizer {
+    pub fn new(kind: TokenizerKind, counter: Arc<dyn TokenCounter>, context_window: usize) -> Self {
+        Self {
+            kind,
+            counter,
+            context_window,
+        }
+    }
+
+    /// `kind`'s built-in counter; BPE encodings that fail to load degrade to the estimate
+    pub fn builtin(kind: TokenizerKind, context_window: usize) -> Self {
+        let bpe = match kind {
+            TokenizerKind::Cl100k => BpeCounter::cl100k(),
+            TokenizerKind::O200k => BpeCounter::o200k(),
+            TokenizerKind::LlamaBpe | TokenizerKind::Estimate => None,
+        };
+        match bpe {
+            Some(counter) => Self::new(kind, Arc::new(counter), context_window),
+            None => Self::estimate(context_window),
+        }
+    }
+
+    pub fn estimate(context_window: usize) -> Self {
+        Self::new(
+            TokenizerKind::Estimate,
+            Arc::new(TiktokenCounter::gpt4()),
+            context_window,
+        )
+    }
+}
+
+/// Tokenizers and context windows keyed by model name
+///
+/// A model resolves to the entry with the longest matching prefix, compared
+/// case-insensitively after dropping any `org/` path (`meta-llama/Llama-3.1-8B`
+/// matches `llama-3.1`).
+///
+/// ```
+/// use loom_core::context::window::{TokenizerKind, TokenizerRegistry};
+///
+/// let tokenizers = TokenizerRegistry::with_defaults();
+/// assert_eq!(tokenizers.get("gpt-4o-mini").kind, TokenizerKind::O200k);
+/// assert_eq!(tokenizers.context_window("gpt-4-0613"), 8_192);
+/// ```
+pub struct TokenizerRegistry {
+    models: HashMap<String, ModelTokenizer>,
+    fallback: ModelTokenizer,
+}
+
+impl Default for TokenizerRegistry {
+    fn default() -> Self {
+        Self::with_defaults()
+    }
+}
+
+impl TokenizerRegistry {
+    /// Registry without models: every lookup returns the fallback estimate
+    pub fn new() -> Self {
+        Self {
+            models: HashMap::new(),
+            fallback: ModelTokenizer::estimate(DEFAULT_CONTEXT_WINDOW),
+        }
+    }
+
+    /// Registry with the OpenAI and Llama 3 model families. Llama 3 uses the ranks file
+    /// named by `LOOM_LLAMA_TOKENIZER` and the estimate otherwise.
+    pub fn with_defaults() -> Self {
+        let mut registry = Self::new();
+        for (prefix, kind, window) in [
+            ("gpt-3.5-turbo", TokenizerKind::Cl100k, 16_385),
+            ("gpt-4", TokenizerKind::Cl100k, 8_192),
+            ("gpt-4-32k", TokenizerKind::Cl100k, 32_768),
+            ("gpt-4-turbo", TokenizerKind::Cl100k, 128_000),
+            ("gpt-4o", TokenizerKind::O200k, 128_000),
+            ("gpt-4.1", TokenizerKind::O200k, 1_047_576),
+            ("o1", TokenizerKind::O200k, 200_000),
+            ("o3", TokenizerKind::O200k, 200_000),
+            ("o4-mini", TokenizerKind::O200k, 200_000),
+        ] {
+            registry.register(prefix, ModelTokenizer::builtin(kind, window));
+        }
+
+        let llama = std::env::var("LOOM_LLAMA_TOKENIZER")
+            .ok()
+            .and_then(|path| match BpeCounter::from_tiktoken_file(&path, LLAMA3_PATTERN) {
+                Ok(counter) => {
+                    info!(target: "tokenizer", path = %path, "Loaded Llama 3 tokenizer");
+                    Some(Arc::new(counter) as Arc<dyn TokenCounter>)
+                }
+                Err(e) => {
+                    warn!(target: "tokenizer", path = %path, error = %e, "Failed to load Llama 3 tokenizer; estimating");
+                    None
+                }
+            });
+        for (prefix, window) in [
+            ("llama-3", 8_192),
+            ("llama3", 8_192),
+            ("llama-3.1", 131_072),
+            ("llama3.1", 131_072),
+            ("llama-3.2", 131_072),
+            ("llama3.2", 131_072),
+            ("llama-3.3", 131_072),
+            ("llama3.3", 131_072),
+        ] {
+            let tokenizer = match llama {
+                Some(ref counter) => {
+                    ModelTokenizer::new(TokenizerKind::LlamaBpe, Arc::clone(counter), window)
+                }
+                None => ModelTokenizer::estimate(window),
+            };
+            registry.register(prefix, tokenizer);
+        }
+        registry
+    }
+
+    /// Use `tokenizer` for models whose name starts with `prefix`
+    pub fn register(&mut self, prefix: impl Into<String>, tokenizer: ModelTokenizer) {
+        self.models
+            .insert(prefix.into().to_ascii_lowercase(), tokenizer);
+    }
+
+    /// Use a Llama 3 ranks file for models whose name starts with `prefix`
+    pub fn register_llama_bpe(
+        &mut self,
+        prefix: impl Into<String>,
+        path: impl AsRef<Path>,
+        context_window: usize,
+    ) -> Result<()> {
+        let counter = BpeCounter::from_tiktoken_file(path, LLAMA3_PATTERN)?;
+        self.register(
+            prefix,
+            ModelTokenizer::new(TokenizerKind::LlamaBpe, Arc::new(counter), context_window),
+        );
+        Ok(())
+    }
+
+    /// Tokenizer for models no prefix matches
+    pub fn with_fallback(mut self, tokenizer: ModelTokenizer) -> Self {
+        self.fallback = tokenizer;
+        self
+    }
+
+    pub fn get(&self, model: &str) -> &ModelTokenizer {
+        let name = model
+            .rsplit('/')
+            .next()
+            .unwrap_or(model)
+            .to_ascii_lowercase();
+        self.models
+            .iter()
+            .filter(|(prefix, _)| name.starts_with(prefix.as_str()))
+            .max_by_key(|(prefix, _)| prefix.len())
+            .map(|(_, tokenizer)| tokenizer)
+            .unwrap_or(&self.fallback)
+    }
+
+    pub fn counter(&self, model: &str) -> Arc<dyn TokenCounter> {
+        Arc::clone(&self.get(model).counter)
+    }
+
+    pub fn context_window(&self, model: &str) -> usize {
+        self.get(model).context_window
+    }
+
+    /// Budget filling `model`'s context window, leaving `max_output_tokens` for the reply
+    pub fn budget(&self, model: &str, max_output_tokens: usize) -> TokenBudget {
+        let window = self.context_window(model);
+        let max_output_tokens = max_output_tokens.min(window);
+        TokenBudget {
+            max_input_tokens: window - max_output_tokens,
+            max_output_tokens,
+        }
+    }
+
+    /// `config` with `max_tokens` set to `model`'s context window
+    pub fn window_config(&self, model: &str, config: WindowConfig) -> WindowConfig {
+        WindowConfig {
+            max_tokens: self.context_window(model),
+            ..config
+        }
+    }
+}
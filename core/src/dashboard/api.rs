@@ -3,17 +3,23 @@
 // Provides REST endpoints and SSE streaming for the Dashboard UI
 
 use crate::agent::directory::AgentDirectory;
+use crate::agent::AgentRuntime;
 use crate::dashboard::event_stream::EventBroadcaster;
 use crate::dashboard::flow_tracker::FlowTracker;
+use crate::dashboard::preview::PreviewPolicy;
 use crate::dashboard::topology::TopologyBuilder;
 use crate::dashboard::DashboardConfig;
+use crate::messaging::{DeadLetter, DeadLetterFilter, EventBus};
+use crate::stats::StatsCollector;
 use crate::telemetry::SpanCollector;
+use crate::tools::{ToolError, ToolRegistry};
 use axum::{
-    extract::{Path, Query, State},
-    http::{header, HeaderMap, StatusCode},
+    extract::{Path, Query, Request, State},
+    http::{header, HeaderMap, HeaderValue, Method, StatusCode},
+    middleware::{self, Next},
     response::{
         sse::{Event, KeepAlive},
-        Html, IntoResponse, Sse,
+        Html, IntoResponse, Response, Sse,
     },
     routing::{get, post},
     Router,
@@ -22,7 +28,7 @@ use serde::{Deserialize, Serialize};
 use std::convert::Infallible;
 use std::sync::Arc;
 use tokio_stream::{wrappers::BroadcastStream, StreamExt};
-use tower_http::cors::{Any, CorsLayer};
+use tower_http::cors::{AllowOrigin, Any, CorsLayer};
 use tracing::{info, warn};
 
 /// Dashboard server state
@@ -32,6 +38,10 @@ struct DashboardState {
     topology_builder: Arc<TopologyBuilder>,
     flow_tracker: Arc<FlowTracker>,
     span_collector: SpanCollector,
+    tool_registry: Option<Arc<ToolRegistry>>,
+    agent_runtime: Option<AgentRuntime>,
+    stats: Option<Arc<StatsCollector>>,
+    event_bus: Option<Arc<EventBus>>,
 }
 
 /// Dashboard HTTP server
@@ -41,6 +51,10 @@ pub struct DashboardServer {
     agent_directory: Arc<AgentDirectory>,
     flow_tracker: Arc<FlowTracker>,
     span_collector: SpanCollector,
+    tool_registry: Option<Arc<ToolRegistry>>,
+    agent_runtime: Option<AgentRuntime>,
+    stats: Option<Arc<StatsCollector>>,
+    event_bus: Option<Arc<EventBus>>,
 }
 
 impl DashboardServer {
@@ -57,6 +71,10 @@ impl DashboardServer {
             agent_directory,
             flow_tracker,
             span_collector,
+            tool_registry: None,
+            agent_runtime: None,
+            stats: None,
+            event_bus: None,
         }
     }
 
@@ -65,6 +83,32 @@ impl DashboardServer {
         self
     }
 
+    /// Expose tools under `/api/tools`; manual calls also need `LOOM_DASHBOARD_TOOL_CALLS=true`
+    pub fn with_tool_registry(mut self, tool_registry: Arc<ToolRegistry>) -> Self {
+        self.tool_registry = Some(tool_registry);
+        self
+    }
+
+    /// Expose agents under `/api/agents`; hibernate/wake/restart also need
+    /// `LOOM_DASHBOARD_AGENT_CONTROL=true`
+    pub fn with_agent_runtime(mut self, agent_runtime: AgentRuntime) -> Self {
+        self.agent_runtime = Some(agent_runtime);
+        self
+    }
+
+    /// Serve `Loom::stats()` snapshots under `/api/stats`
+    pub fn with_stats(mut self, stats: Arc<StatsCollector>) -> Self {
+        self.stats = Some(stats);
+        self
+    }
+
+    /// Expose the bus's dead letters under `/api/dead_letters`; re-publishing also needs
+    /// `LOOM_DASHBOARD_DEAD_LETTER_REPLAY=true`
+    pub fn with_event_bus(mut self, event_bus: Arc<EventBus>) -> Self {
+        self.event_bus = Some(event_bus);
+        self
+    }
+
     /// Start the Dashboard server
     pub async fn serve(self) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
         let addr = format!("{}:{}", self.config.host, self.config.port);
@@ -74,15 +118,8 @@ impl DashboardServer {
             "Starting Dashboard server"
         );
 
-        let state = DashboardState {
-            broadcaster: self.broadcaster,
-            topology_builder: Arc::new(TopologyBuilder::new(self.agent_directory)),
-            flow_tracker: self.flow_tracker.clone(),
-            span_collector: self.span_collector.clone(),
-        };
-
         // Start cleanup task for flow tracker
-        let flow_tracker_clone = state.flow_tracker.clone();
+        let flow_tracker_clone = self.flow_tracker.clone();
         tokio::spawn(async move {
             let mut interval = tokio::time::interval(std::time::Duration::from_secs(30));
             loop {
@@ -97,7 +134,7 @@ impl DashboardServer {
             .map(|v| v == "1" || v.eq_ignore_ascii_case("true"))
             .unwrap_or(false)
         {
-            let broadcaster = state.broadcaster.clone();
+            let broadcaster = self.broadcaster.clone();
             tokio::spawn(async move {
                 let mut i = 0u64;
                 let mut interval = tokio::time::interval(std::time::Duration::from_secs(2));
@@ -121,8 +158,36 @@ impl DashboardServer {
             info!(target: "dashboard", "Started FAKE_FEED for SSE debug");
         }
 
-        // Build router
-        let app = Router::new()
+        let app = self.router();
+
+        // Start server
+        let listener = tokio::net::TcpListener::bind(&addr).await?;
+        info!(
+            target: "dashboard",
+            url = %format!("http://{}", addr),
+            "Dashboard server ready"
+        );
+
+        axum::serve(listener, app).await?;
+
+        Ok(())
+    }
+
+    /// Routes of the dashboard, for serving on an existing listener
+    pub fn router(self) -> Router {
+        let control = control_routes(&self.config);
+        let state = DashboardState {
+            broadcaster: self.broadcaster,
+            topology_builder: Arc::new(TopologyBuilder::new(self.agent_directory)),
+            flow_tracker: self.flow_tracker,
+            span_collector: self.span_collector,
+            tool_registry: self.tool_registry,
+            agent_runtime: self.agent_runtime,
+            stats: self.stats,
+            event_bus: self.event_bus,
+        };
+
+        Router::new()
             .route("/", get(index_handler))
             .route("/static/*asset", get(static_asset_handler))
             .route("/api/events/stream", get(event_stream_handler))
@@ -130,32 +195,105 @@ impl DashboardServer {
             .route("/api/topology", get(topology_handler))
             .route("/api/flow", get(flow_handler))
             .route("/api/metrics", get(metrics_handler))
+            .route("/api/stats", get(stats_handler))
             .route("/api/spans/recent", get(spans_recent_handler))
             .route("/api/traces/:trace_id", get(trace_handler))
             .route("/api/spans/stream", get(spans_stream_handler))
             .route("/api/debug/emit", post(debug_emit_handler))
+            .route("/api/tools", get(tools_handler))
+            .route("/api/agents", get(agents_handler))
+            .route("/api/dead_letters", get(dead_letters_handler))
             .layer(
                 CorsLayer::new()
                     .allow_origin(Any)
                     .allow_methods(Any)
                     .allow_headers(Any),
             )
-            .with_state(state);
+            .merge(control)
+            .with_state(state)
+    }
+}
 
-        // Start server
-        let listener = tokio::net::TcpListener::bind(&addr).await?;
-        info!(
-            target: "dashboard",
-            url = %format!("http://{}", addr),
-            "Dashboard server ready"
-        );
+/// Routes that change state. Each needs `Authorization: Bearer <control_token>`, and
+/// browsers may only call them from `allowed_origins`.
+fn control_routes(config: &DashboardConfig) -> Router<DashboardState> {
+    let token: Option<Arc<str>> = config.control_token.as_deref().map(Arc::from);
+    if token.is_none() {
+        info!(target: "dashboard", "LOOM_DASHBOARD_TOKEN unset; tool calls, agent control and dead-letter replay are refused");
+    }
+    let origins: Vec<HeaderValue> = config
+        .allowed_origins
+        .iter()
+        .filter_map(|origin| match HeaderValue::from_str(origin) {
+            Ok(value) => Some(value),
+            Err(_) => {
+                warn!(target: "dashboard", origin = %origin, "Ignoring invalid allowed origin");
+                None
+            }
+        })
+        .collect();
 
-        axum::serve(listener, app).await?;
+    Router::new()
+        .route("/api/tools/:name/call", post(tool_call_handler))
+        .route(
+            "/api/agents/:agent_id/:operation",
+            post(agent_control_handler),
+        )
+        .route(
+            "/api/dead_letters/:id/republish",
+            post(dead_letter_republish_handler),
+        )
+        .route_layer(middleware::from_fn_with_state(token, require_control_token))
+        .layer(
+            CorsLayer::new()
+                .allow_origin(AllowOrigin::list(origins))
+                .allow_methods([Method::POST])
+                .allow_headers([header::AUTHORIZATION, header::CONTENT_TYPE]),
+        )
+}
 
-        Ok(())
+/// Reject requests without the configured bearer token, and every request while no
+/// token is configured
+async fn require_control_token(
+    State(token): State<Option<Arc<str>>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let refuse = |status: StatusCode, error: &str| {
+        (
+            status,
+            axum::Json(serde_json::json!({ "ok": false, "error": error })),
+        )
+            .into_response()
+    };
+    let Some(token) = token else {
+        return refuse(
+            StatusCode::FORBIDDEN,
+            "control routes disabled: LOOM_DASHBOARD_TOKEN is not set",
+        );
+    };
+    let presented = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .map(str::trim);
+    match presented {
+        Some(presented) if tokens_match(presented, &token) => next.run(request).await,
+        _ => {
+            warn!(target: "dashboard", path = %request.uri().path(), "Rejected control request without a valid token");
+            refuse(StatusCode::UNAUTHORIZED, "missing or invalid bearer token")
+        }
     }
 }
 
+/// Compare digests rather than the tokens, so the time taken does not depend on how
+/// much of the token a caller guessed right
+fn tokens_match(presented: &str, expected: &str) -> bool {
+    let digest = |token: &str| ring::digest::digest(&ring::digest::SHA256, token.as_bytes());
+    digest(presented).as_ref() == digest(expected).as_ref()
+}
+
 /// Serve the main HTML page
 const FALLBACK_INDEX: &str = r#"<!DOCTYPE html><html><head><meta charset="utf-8"><title>Loom Dashboard</title></head><body><h1>Loom Dashboard assets not found</h1><p>Please run <code>npm run build</code> inside <code>core/src/dashboard/frontend</code> to generate the static assets.</p></body></html>"#;
 
@@ -295,6 +433,14 @@ async fn metrics_handler(
     Ok((StatusCode::OK, metrics.to_string()))
 }
 
+/// Snapshot of every subsystem (`Loom::stats()`); 404 without `with_stats`
+async fn stats_handler(State(state): State<DashboardState>) -> impl IntoResponse {
+    match state.stats {
+        Some(stats) => axum::Json(stats.snapshot().await).into_response(),
+        None => StatusCode::NOT_FOUND.into_response(),
+    }
+}
+
 /// Query parameters for spans/recent endpoint
 #[derive(Deserialize)]
 struct SpansRecentQuery {
@@ -436,3 +582,448 @@ async fn debug_emit_handler(
 
     (StatusCode::OK, "ok")
 }
+
+fn tool_calls_enabled() -> bool {
+    std::env::var("LOOM_DASHBOARD_TOOL_CALLS")
+        .ok()
+        .map(|v| v == "1" || v.eq_ignore_ascii_case("true"))
+        .unwrap_or(false)
+}
+
+#[derive(Serialize)]
+struct ToolInfo {
+    name: String,
+    description: String,
+    parameters: serde_json::Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    output_schema: Option<serde_json::Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    stats: Option<crate::tools::ToolStats>,
+}
+
+#[derive(Serialize)]
+struct ToolsResponse {
+    /// Whether `POST /api/tools/:name/call` is allowed
+    calls_enabled: bool,
+    tools: Vec<ToolInfo>,
+}
+
+/// List registered tools with their JSON schemas and call stats
+async fn tools_handler(State(state): State<DashboardState>) -> impl IntoResponse {
+    let mut tools: Vec<ToolInfo> = match state.tool_registry {
+        Some(ref registry) => registry
+            .list_tools()
+            .into_iter()
+            .map(|tool| {
+                let name = tool.name();
+                ToolInfo {
+                    stats: registry.tool_stats(&name),
+                    description: tool.description(),
+                    parameters: tool.parameters(),
+                    output_schema: tool.output_schema(),
+                    name,
+                }
+            })
+            .collect(),
+        None => Vec::new(),
+    };
+    tools.sort_by(|a, b| a.name.cmp(&b.name));
+
+    axum::Json(ToolsResponse {
+        calls_enabled: state.tool_registry.is_some() && tool_calls_enabled(),
+        tools,
+    })
+}
+
+#[derive(Deserialize)]
+struct ToolCallRequest {
+    #[serde(default = "empty_arguments")]
+    arguments: serde_json::Value,
+}
+
+fn empty_arguments() -> serde_json::Value {
+    serde_json::json!({})
+}
+
+#[derive(Serialize)]
+struct ToolCallResponse {
+    tool: String,
+    ok: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    result: Option<serde_json::Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+    duration_ms: u64,
+}
+
+/// Invoke a tool manually through the registry (policies and output checks apply)
+/// Enabled only when LOOM_DASHBOARD_TOOL_CALLS=true
+async fn tool_call_handler(
+    State(state): State<DashboardState>,
+    Path(name): Path<String>,
+    axum::extract::Json(req): axum::extract::Json<ToolCallRequest>,
+) -> impl IntoResponse {
+    let failure = |status: StatusCode, error: String| {
+        (
+            status,
+            axum::Json(ToolCallResponse {
+                tool: name.clone(),
+                ok: false,
+                result: None,
+                error: Some(error),
+                duration_ms: 0,
+            }),
+        )
+    };
+    if !tool_calls_enabled() {
+        return failure(StatusCode::FORBIDDEN, "tool calls disabled".to_string());
+    }
+    let Some(registry) = state.tool_registry else {
+        return failure(StatusCode::NOT_FOUND, "no tool registry".to_string());
+    };
+
+    info!(target: "dashboard", tool = %name, "Manual tool call");
+    let started = std::time::Instant::now();
+    let result = registry.call(&name, req.arguments).await;
+    let duration_ms = started.elapsed().as_millis() as u64;
+
+    let (status, response) = match result {
+        Ok(value) => (
+            StatusCode::OK,
+            ToolCallResponse {
+                tool: name.clone(),
+                ok: true,
+                result: Some(value),
+                error: None,
+                duration_ms,
+            },
+        ),
+        Err(e) => {
+            let status = match e {
+                ToolError::NotFound(_) => StatusCode::NOT_FOUND,
+                ToolError::InvalidArguments(_) => StatusCode::BAD_REQUEST,
+                ToolError::PermissionDenied(_) => StatusCode::FORBIDDEN,
+                ToolError::Timeout => StatusCode::GATEWAY_TIMEOUT,
+                _ => StatusCode::BAD_GATEWAY,
+            };
+            (
+                status,
+                ToolCallResponse {
+                    tool: name.clone(),
+                    ok: false,
+                    result: None,
+                    error: Some(e.to_string()),
+                    duration_ms,
+                },
+            )
+        }
+    };
+
+    let preview = match (&response.result, &response.error) {
+        (Some(value), _) => value.to_string(),
+        (None, Some(error)) => format!("error: {}", error),
+        (None, None) => String::new(),
+    };
+    state
+        .broadcaster
+        .broadcast(crate::dashboard::DashboardEvent {
+            timestamp: chrono::Utc::now().to_rfc3339(),
+            event_type: crate::dashboard::DashboardEventType::ToolInvoked,
+            event_id: format!("tool-call-{}", chrono::Utc::now().timestamp_millis()),
+            topic: format!("tool.{}", name),
+            sender: Some("dashboard".to_string()),
+            thread_id: None,
+            correlation_id: None,
+            payload_preview: crate::dashboard::preview_text(preview.as_bytes()),
+            trace_id: String::new(),
+        });
+
+    (status, axum::Json(response))
+}
+
+fn agent_control_enabled() -> bool {
+    std::env::var("LOOM_DASHBOARD_AGENT_CONTROL")
+        .ok()
+        .map(|v| v == "1" || v.eq_ignore_ascii_case("true"))
+        .unwrap_or(false)
+}
+
+#[derive(Serialize)]
+struct AgentInfo {
+    agent_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    namespace: Option<String>,
+    subscriptions: Vec<String>,
+    /// Created with a factory, so it can hibernate and restart
+    hibernatable: bool,
+    hibernated: bool,
+}
+
+#[derive(Serialize)]
+struct AgentsResponse {
+    /// Whether `POST /api/agents/:agent_id/:operation` is allowed
+    control_enabled: bool,
+    /// Idle time after which agents hibernate, if a policy is set
+    #[serde(skip_serializing_if = "Option::is_none")]
+    hibernate_after_ms: Option<u64>,
+    agents: Vec<AgentInfo>,
+}
+
+/// List the runtime's agents with their hibernation status
+async fn agents_handler(State(state): State<DashboardState>) -> impl IntoResponse {
+    let Some(runtime) = state.agent_runtime else {
+        return axum::Json(AgentsResponse {
+            control_enabled: false,
+            hibernate_after_ms: None,
+            agents: Vec::new(),
+        });
+    };
+    // Agents deleted while listing are skipped
+    let agents = runtime
+        .agent_ids()
+        .into_iter()
+        .filter_map(|agent_id| {
+            Some(AgentInfo {
+                namespace: runtime
+                    .get_agent_namespace(&agent_id)
+                    .ok()?
+                    .map(String::from),
+                subscriptions: {
+                    let mut topics = runtime.get_agent_subscriptions(&agent_id).ok()?;
+                    topics.sort();
+                    topics
+                },
+                hibernatable: runtime.is_agent_hibernatable(&agent_id).ok()?,
+                hibernated: runtime.is_agent_hibernated(&agent_id).ok()?,
+                agent_id,
+            })
+        })
+        .collect();
+
+    axum::Json(AgentsResponse {
+        control_enabled: agent_control_enabled(),
+        hibernate_after_ms: runtime
+            .hibernation_policy()
+            .map(|policy| policy.idle_after.as_millis() as u64),
+        agents,
+    })
+}
+
+#[derive(Serialize)]
+struct AgentControlResponse {
+    agent_id: String,
+    operation: String,
+    ok: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+/// Hibernate, wake or restart an agent; its state and subscriptions are kept
+/// Enabled only when LOOM_DASHBOARD_AGENT_CONTROL=true
+async fn agent_control_handler(
+    State(state): State<DashboardState>,
+    Path((agent_id, operation)): Path<(String, String)>,
+) -> impl IntoResponse {
+    let respond = |status: StatusCode, error: Option<String>| {
+        (
+            status,
+            axum::Json(AgentControlResponse {
+                agent_id: agent_id.clone(),
+                operation: operation.clone(),
+                ok: error.is_none(),
+                error,
+            }),
+        )
+    };
+    if !agent_control_enabled() {
+        return respond(
+            StatusCode::FORBIDDEN,
+            Some("agent control disabled".to_string()),
+        );
+    }
+    let Some(runtime) = state.agent_runtime else {
+        return respond(StatusCode::NOT_FOUND, Some("no agent runtime".to_string()));
+    };
+    match runtime.is_agent_hibernatable(&agent_id) {
+        Ok(true) => {}
+        Ok(false) => {
+            return respond(
+                StatusCode::CONFLICT,
+                Some("agent was not created with a factory".to_string()),
+            )
+        }
+        Err(e) => return respond(StatusCode::NOT_FOUND, Some(e.to_string())),
+    }
+
+    let result = match operation.as_str() {
+        "hibernate" => runtime.hibernate_agent(&agent_id),
+        "wake" => runtime.wake_agent(&agent_id).map(|_| ()),
+        "restart" => runtime.restart_agent(&agent_id),
+        _ => {
+            return respond(
+                StatusCode::BAD_REQUEST,
+                Some(format!("unknown operation '{}'", operation)),
+            )
+        }
+    };
+    info!(target: "dashboard", agent_id = %agent_id, operation = %operation, "Agent control");
+    match result {
+        Ok(()) => respond(StatusCode::ACCEPTED, None),
+        // Deleted since the check above
+        Err(e) => respond(StatusCode::NOT_FOUND, Some(e.to_string())),
+    }
+}
+
+fn dead_letter_replay_enabled() -> bool {
+    std::env::var("LOOM_DASHBOARD_DEAD_LETTER_REPLAY")
+        .ok()
+        .map(|v| v == "1" || v.eq_ignore_ascii_case("true"))
+        .unwrap_or(false)
+}
+
+/// Query parameters of `/api/dead_letters`
+#[derive(Deserialize)]
+struct DeadLettersQuery {
+    topic: Option<String>,
+    reason: Option<String>,
+    subscription: Option<String>,
+    since_ms: Option<i64>,
+    #[serde(default = "default_limit")]
+    limit: usize,
+}
+
+#[derive(Serialize)]
+struct DeadLetterInfo {
+    id: u64,
+    topic: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    subscription: Option<String>,
+    reason: String,
+    attempts: u32,
+    dead_lettered_at_ms: i64,
+    event_id: String,
+    event_type: String,
+    source: String,
+    metadata: std::collections::HashMap<String, String>,
+    payload_preview: String,
+}
+
+impl DeadLetterInfo {
+    /// Listing entry for `letter`, its preview shaped like those of the event stream
+    fn new(letter: DeadLetter, previews: &PreviewPolicy) -> Self {
+        let payload_preview = previews.apply(
+            &letter.topic,
+            &letter.event.id,
+            &crate::dashboard::preview_text(&letter.event.payload),
+        );
+        Self {
+            id: letter.id,
+            topic: letter.topic,
+            subscription: letter.subscription,
+            reason: letter.reason,
+            attempts: letter.attempts,
+            dead_lettered_at_ms: letter.dead_lettered_at_ms,
+            event_id: letter.event.id,
+            event_type: letter.event.r#type,
+            source: letter.event.source,
+            metadata: letter.event.metadata,
+            payload_preview,
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct DeadLettersResponse {
+    /// Whether `POST /api/dead_letters/:id/republish` is allowed
+    replay_enabled: bool,
+    /// Dead letters stored, before filtering
+    total: usize,
+    dead_letters: Vec<DeadLetterInfo>,
+}
+
+/// List dead letters, newest first
+/// Query params: ?topic=orders.*&reason=ack_timeout&subscription=..&since_ms=..&limit=100
+/// (default: 100, max: 1000)
+async fn dead_letters_handler(
+    State(state): State<DashboardState>,
+    Query(query): Query<DeadLettersQuery>,
+) -> impl IntoResponse {
+    let Some(bus) = state.event_bus else {
+        return StatusCode::NOT_FOUND.into_response();
+    };
+    let filter = DeadLetterFilter {
+        topic: query.topic,
+        reason: query.reason,
+        subscription: query.subscription,
+        since_ms: query.since_ms,
+        limit: Some(query.limit.min(1000)),
+    };
+    axum::Json(DeadLettersResponse {
+        replay_enabled: dead_letter_replay_enabled(),
+        total: bus.dead_letter_store().len(),
+        dead_letters: bus
+            .dead_letters(&filter)
+            .into_iter()
+            .map(|letter| DeadLetterInfo::new(letter, state.broadcaster.preview_policy()))
+            .collect(),
+    })
+    .into_response()
+}
+
+#[derive(Deserialize)]
+struct RepublishRequest {
+    /// Topic to publish to instead of the one the dead letter was recorded on
+    topic: Option<String>,
+}
+
+#[derive(Serialize)]
+struct RepublishResponse {
+    id: u64,
+    ok: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    delivered: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+/// Publish a dead letter again and remove it from the store
+/// Enabled only when LOOM_DASHBOARD_DEAD_LETTER_REPLAY=true
+async fn dead_letter_republish_handler(
+    State(state): State<DashboardState>,
+    Path(id): Path<u64>,
+    req: Option<axum::extract::Json<RepublishRequest>>,
+) -> impl IntoResponse {
+    let respond = |status: StatusCode, result: Result<u64, String>| {
+        let (delivered, error) = match result {
+            Ok(delivered) => (Some(delivered), None),
+            Err(error) => (None, Some(error)),
+        };
+        (
+            status,
+            axum::Json(RepublishResponse {
+                id,
+                ok: error.is_none(),
+                delivered,
+                error,
+            }),
+        )
+    };
+    if !dead_letter_replay_enabled() {
+        return respond(
+            StatusCode::FORBIDDEN,
+            Err("dead letter replay disabled".to_string()),
+        );
+    }
+    let Some(bus) = state.event_bus else {
+        return respond(StatusCode::NOT_FOUND, Err("no event bus".to_string()));
+    };
+    if bus.dead_letter_store().get(id).is_none() {
+        return respond(StatusCode::NOT_FOUND, Err(format!("no dead letter {}", id)));
+    }
+    let topic = req.and_then(|axum::extract::Json(req)| req.topic);
+    info!(target: "dashboard", dead_letter_id = id, topic = ?topic, "Re-publishing dead letter");
+    match bus.republish_dead_letter(id, topic.as_deref()).await {
+        Ok(delivered) => respond(StatusCode::OK, Ok(delivered)),
+        Err(e) => respond(StatusCode::CONFLICT, Err(e.to_string())),
+    }
+}
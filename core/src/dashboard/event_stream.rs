@@ -2,9 +2,13 @@
 //
 // Uses tokio broadcast channel to stream events to multiple SSE clients
 
+use std::sync::Arc;
+
 use serde::{Deserialize, Serialize};
 use tokio::sync::broadcast;
 
+use super::preview::PreviewPolicy;
+
 /// Helper function for serde skip_serializing_if
 #[allow(dead_code)]
 fn is_empty_string(s: &str) -> bool {
@@ -28,7 +32,7 @@ pub struct DashboardEvent {
     pub thread_id: Option<String>,
     /// Correlation ID
     pub correlation_id: Option<String>,
-    /// Payload preview (first 100 chars)
+    /// Payload preview, shaped by the broadcaster's `PreviewPolicy`
     pub payload_preview: String,
     /// OpenTelemetry trace ID (if available)
     #[serde(default, skip_serializing_if = "is_empty_string")]
@@ -46,6 +50,10 @@ pub enum DashboardEventType {
     AgentRegistered,
     /// Agent unregistered
     AgentUnregistered,
+    /// Agent missed heartbeats
+    AgentDegraded,
+    /// Degraded agent is heard from again
+    AgentRecovered,
     /// Tool invoked
     ToolInvoked,
     /// Routing decision
@@ -56,17 +64,34 @@ pub enum DashboardEventType {
 #[derive(Clone)]
 pub struct EventBroadcaster {
     sender: broadcast::Sender<DashboardEvent>,
+    preview_policy: Arc<PreviewPolicy>,
 }
 
 impl EventBroadcaster {
-    /// Create a new broadcaster with buffer size
+    /// Create a new broadcaster with buffer size; previews follow `PreviewPolicy::from_env`
     pub fn new(capacity: usize) -> Self {
         let (sender, _) = broadcast::channel(capacity);
-        Self { sender }
+        Self {
+            sender,
+            preview_policy: Arc::new(PreviewPolicy::from_env()),
+        }
+    }
+
+    /// Replace the payload preview policy
+    pub fn with_preview_policy(mut self, policy: PreviewPolicy) -> Self {
+        self.preview_policy = Arc::new(policy);
+        self
+    }
+
+    pub fn preview_policy(&self) -> &PreviewPolicy {
+        &self.preview_policy
     }
 
-    /// Broadcast an event to all subscribers
-    pub fn broadcast(&self, event: DashboardEvent) {
+    /// Broadcast an event to all subscribers, with its preview capped and redacted
+    pub fn broadcast(&self, mut event: DashboardEvent) {
+        event.payload_preview =
+            self.preview_policy
+                .apply(&event.topic, &event.event_id, &event.payload_preview);
         // Ignore error if no subscribers
         let _ = self.sender.send(event);
     }
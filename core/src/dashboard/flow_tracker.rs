@@ -2,8 +2,10 @@
 //
 // Tracks event flow between agents and components for visualization
 
+use crate::governor::{MemoryComponent, PressureLevel};
+use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
-use std::collections::{HashMap, VecDeque};
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::sync::Arc;
 use tokio::sync::RwLock;
 
@@ -205,6 +207,15 @@ impl FlowTracker {
             });
     }
 
+    fn flow_bytes(flow: &EventFlow) -> usize {
+        // Key and value both hold the three strings
+        2 * (flow.source.len() + flow.target.len() + flow.topic.len()) + 48
+    }
+
+    fn node_bytes(node: &FlowNode) -> usize {
+        2 * node.id.len() + node.topics.iter().map(|t| t.len() + 24).sum::<usize>() + 64
+    }
+
     fn now_ms() -> u64 {
         std::time::SystemTime::now()
             .duration_since(std::time::UNIX_EPOCH)
@@ -218,3 +229,64 @@ impl Default for FlowTracker {
         Self::new()
     }
 }
+
+/// Flow history is dashboard-only and cheap to rebuild, so it is shed first:
+/// oldest flows go first; under critical pressure node topic history is cut to the
+/// latest topic and nodes without remaining flows are dropped.
+#[async_trait]
+impl MemoryComponent for FlowTracker {
+    fn name(&self) -> String {
+        "flow_tracker".to_string()
+    }
+
+    fn shed_priority(&self) -> u8 {
+        10
+    }
+
+    async fn memory_usage(&self) -> usize {
+        let flows: usize = self.flows.read().await.values().map(Self::flow_bytes).sum();
+        let nodes: usize = self.nodes.read().await.values().map(Self::node_bytes).sum();
+        flows + nodes
+    }
+
+    async fn shed(&self, bytes: usize, level: PressureLevel) -> usize {
+        let mut freed = 0;
+        let remaining: HashSet<String> = {
+            let mut flows = self.flows.write().await;
+            let mut by_age: Vec<(u64, FlowKey)> = flows
+                .iter()
+                .map(|(k, f)| (f.last_event_ms, k.clone()))
+                .collect();
+            by_age.sort_unstable_by_key(|(ts, _)| *ts);
+            for (_, key) in by_age {
+                if freed >= bytes {
+                    break;
+                }
+                if let Some(flow) = flows.remove(&key) {
+                    freed += Self::flow_bytes(&flow);
+                }
+            }
+            flows
+                .keys()
+                .flat_map(|(source, target, _)| [source.clone(), target.clone()])
+                .collect()
+        };
+
+        if level == PressureLevel::Critical {
+            let mut nodes = self.nodes.write().await;
+            nodes.retain(|id, node| {
+                if id != "EventBus" && !remaining.contains(id) {
+                    freed += Self::node_bytes(node);
+                    return false;
+                }
+                let before = Self::node_bytes(node);
+                while node.topics.len() > 1 {
+                    node.topics.pop_front();
+                }
+                freed += before - Self::node_bytes(node);
+                true
+            });
+        }
+        freed
+    }
+}
@@ -25,6 +25,42 @@ function buildUrl(path: string): string {
   }
 }
 
+const CONTROL_TOKEN_KEY = "loom.dashboard.controlToken";
+
+// Control routes (tool calls, agent control) need the server's LOOM_DASHBOARD_TOKEN;
+// it is asked for once per browser session
+function controlToken(forcePrompt = false): string | null {
+  if (typeof window === "undefined") {
+    return null;
+  }
+  let token = window.sessionStorage.getItem(CONTROL_TOKEN_KEY);
+  if (!token || forcePrompt) {
+    token = window.prompt("Dashboard control token (LOOM_DASHBOARD_TOKEN)");
+    if (token) {
+      window.sessionStorage.setItem(CONTROL_TOKEN_KEY, token);
+    }
+  }
+  return token;
+}
+
+async function controlFetch(path: string, init: RequestInit): Promise<Response> {
+  const send = (token: string | null) =>
+    fetch(buildUrl(path), {
+      ...init,
+      headers: {
+        ...(init.headers as Record<string, string>),
+        ...(token ? { Authorization: `Bearer ${token}` } : {}),
+      },
+      credentials: "omit",
+    });
+  const response = await send(controlToken());
+  if (response.status !== 401) {
+    return response;
+  }
+  window.sessionStorage.removeItem(CONTROL_TOKEN_KEY);
+  return send(controlToken(true));
+}
+
 async function fetchJson<T>(path: string): Promise<T> {
   const response = await fetch(buildUrl(path), {
     headers: {
@@ -122,6 +158,8 @@ export interface DashboardEventPayload {
     | "event_delivered"
     | "agent_registered"
     | "agent_unregistered"
+    | "agent_degraded"
+    | "agent_recovered"
     | "tool_invoked"
     | "routing_decision";
   event_id: string;
@@ -147,6 +185,62 @@ function normalizeBreakdown(
   return { realtime: 60, batched: 30, background: 10 };
 }
 
+export interface ToolStats {
+  circuit_state: string;
+  calls: number;
+  successes: number;
+  failures: number;
+  retries: number;
+  timeouts: number;
+  rejected: number;
+  consecutive_failures: number;
+  last_error?: string | null;
+}
+
+export interface ToolInfo {
+  name: string;
+  description: string;
+  parameters: Record<string, unknown>;
+  output_schema?: Record<string, unknown>;
+  stats?: ToolStats;
+}
+
+export interface ToolList {
+  calls_enabled: boolean;
+  tools: ToolInfo[];
+}
+
+export interface ToolCallResult {
+  tool: string;
+  ok: boolean;
+  result?: unknown;
+  error?: string;
+  duration_ms: number;
+}
+
+export interface AgentInfo {
+  agent_id: string;
+  namespace?: string;
+  subscriptions: string[];
+  hibernatable: boolean;
+  hibernated: boolean;
+}
+
+export interface AgentList {
+  control_enabled: boolean;
+  hibernate_after_ms?: number;
+  agents: AgentInfo[];
+}
+
+export type AgentOperation = "hibernate" | "wake" | "restart";
+
+export interface AgentControlResult {
+  agent_id: string;
+  operation: string;
+  ok: boolean;
+  error?: string;
+}
+
 export function normalizeMetrics(
   raw: DashboardMetricsResponse,
 ): DashboardMetrics {
@@ -174,6 +268,53 @@ export async function fetchFlow(): Promise<FlowGraph> {
   return fetchJson<FlowGraph>("/api/flow");
 }
 
+export async function fetchTools(): Promise<ToolList> {
+  return fetchJson<ToolList>("/api/tools");
+}
+
+export async function callTool(
+  name: string,
+  args: Record<string, unknown>,
+): Promise<ToolCallResult> {
+  const path = `/api/tools/${encodeURIComponent(name)}/call`;
+  const response = await controlFetch(path, {
+    method: "POST",
+    headers: {
+      Accept: "application/json",
+      "Content-Type": "application/json",
+    },
+    body: JSON.stringify({ arguments: args }),
+  });
+
+  // Failed calls still carry a structured body worth rendering
+  try {
+    return (await response.json()) as ToolCallResult;
+  } catch (_) {
+    throw new Error(`Failed to call ${name}: ${response.status}`);
+  }
+}
+
+export async function fetchAgents(): Promise<AgentList> {
+  return fetchJson<AgentList>("/api/agents");
+}
+
+export async function controlAgent(
+  agentId: string,
+  operation: AgentOperation,
+): Promise<AgentControlResult> {
+  const path = `/api/agents/${encodeURIComponent(agentId)}/${operation}`;
+  const response = await controlFetch(path, {
+    method: "POST",
+    headers: { Accept: "application/json" },
+  });
+
+  try {
+    return (await response.json()) as AgentControlResult;
+  } catch (_) {
+    throw new Error(`Failed to ${operation} ${agentId}: ${response.status}`);
+  }
+}
+
 export function createEventStream(): EventSource {
   return new EventSource(buildUrl("/api/events/stream"));
 }
@@ -0,0 +1,231 @@
+import { useMemo, useState } from "react";
+import { useQuery } from "@tanstack/react-query";
+import { Link } from "react-router-dom";
+import { Card } from "@/components/ui/card";
+import { Badge } from "@/components/ui/badge";
+import { Button } from "@/components/ui/button";
+import { Input } from "@/components/ui/input";
+import {
+  Table,
+  TableBody,
+  TableCell,
+  TableHead,
+  TableHeader,
+  TableRow,
+} from "@/components/ui/table";
+import { Home, Moon, RefreshCw, RotateCcw, Sun } from "lucide-react";
+import {
+  controlAgent,
+  fetchAgents,
+  type AgentControlResult,
+  type AgentOperation,
+} from "@/lib/dashboardApi";
+
+const Agents = () => {
+  const [filter, setFilter] = useState("");
+  const [pending, setPending] = useState<string | null>(null);
+  const [lastResult, setLastResult] = useState<AgentControlResult | null>(
+    null,
+  );
+
+  const { data, refetch, isFetching } = useQuery({
+    queryKey: ["agents"],
+    queryFn: fetchAgents,
+    refetchInterval: 5000,
+  });
+
+  const agents = useMemo(() => {
+    const all = data?.agents ?? [];
+    const needle = filter.trim().toLowerCase();
+    if (!needle) return all;
+    return all.filter(
+      (agent) =>
+        agent.agent_id.toLowerCase().includes(needle) ||
+        (agent.namespace ?? "").toLowerCase().includes(needle),
+    );
+  }, [data, filter]);
+
+  const run = async (agentId: string, operation: AgentOperation) => {
+    setPending(`${agentId}:${operation}`);
+    try {
+      setLastResult(await controlAgent(agentId, operation));
+    } catch (error) {
+      setLastResult({
+        agent_id: agentId,
+        operation,
+        ok: false,
+        error: (error as Error).message,
+      });
+    } finally {
+      setPending(null);
+      refetch();
+    }
+  };
+
+  const controlEnabled = data?.control_enabled ?? false;
+  const hibernatedCount = (data?.agents ?? []).filter((a) => a.hibernated)
+    .length;
+
+  return (
+    <div className="min-h-screen bg-background">
+      <div className="container mx-auto px-4 py-8">
+        {/* Header */}
+        <div className="mb-8">
+          <div className="flex items-center justify-between mb-4">
+            <div>
+              <h1 className="text-4xl font-bold bg-gradient-to-r from-primary via-secondary to-accent bg-clip-text text-transparent mb-2">
+                Agents
+              </h1>
+              <p className="text-muted-foreground">
+                Hibernate, wake or restart agents without losing their state
+              </p>
+            </div>
+            <Link to="/">
+              <Button variant="outline" size="sm" className="gap-2">
+                <Home className="h-4 w-4" />
+                Back to Dashboard
+              </Button>
+            </Link>
+          </div>
+          {!controlEnabled && (
+            <Card className="p-3 bg-muted/30 border-border/50 text-sm text-muted-foreground">
+              Agent control is disabled. Start the server with{" "}
+              <code>LOOM_DASHBOARD_AGENT_CONTROL=true</code> to enable it.
+            </Card>
+          )}
+        </div>
+
+        <Card className="p-4 bg-card/50 backdrop-blur border-border/50">
+          <div className="flex items-center gap-2 mb-4">
+            <Input
+              placeholder="Filter agents"
+              value={filter}
+              onChange={(e) => setFilter(e.target.value)}
+            />
+            <Button
+              variant="outline"
+              size="icon"
+              onClick={() => refetch()}
+              disabled={isFetching}
+            >
+              <RefreshCw className="h-4 w-4" />
+            </Button>
+          </div>
+          <div className="flex gap-4 mb-4 text-xs text-muted-foreground">
+            <span>{data?.agents.length ?? 0} agents</span>
+            <span>{hibernatedCount} hibernated</span>
+            <span>
+              {data?.hibernate_after_ms
+                ? `idle agents hibernate after ${data.hibernate_after_ms} ms`
+                : "no hibernation policy"}
+            </span>
+          </div>
+
+          <Table>
+            <TableHeader>
+              <TableRow>
+                <TableHead>Agent</TableHead>
+                <TableHead>Namespace</TableHead>
+                <TableHead>Subscriptions</TableHead>
+                <TableHead>Status</TableHead>
+                <TableHead className="text-right">Actions</TableHead>
+              </TableRow>
+            </TableHeader>
+            <TableBody>
+              {agents.map((agent) => {
+                const busy = pending?.startsWith(`${agent.agent_id}:`) ?? false;
+                const disabled =
+                  !controlEnabled || !agent.hibernatable || busy;
+                return (
+                  <TableRow key={agent.agent_id}>
+                    <TableCell className="font-mono text-sm">
+                      {agent.agent_id}
+                    </TableCell>
+                    <TableCell className="text-sm">
+                      {agent.namespace ?? "—"}
+                    </TableCell>
+                    <TableCell className="text-xs text-muted-foreground">
+                      {agent.subscriptions.join(", ")}
+                    </TableCell>
+                    <TableCell>
+                      {agent.hibernatable ? (
+                        <Badge
+                          variant={agent.hibernated ? "secondary" : "default"}
+                        >
+                          {agent.hibernated ? "hibernated" : "running"}
+                        </Badge>
+                      ) : (
+                        <Badge variant="outline">running (pinned)</Badge>
+                      )}
+                    </TableCell>
+                    <TableCell>
+                      <div className="flex justify-end gap-2">
+                        {agent.hibernated ? (
+                          <Button
+                            variant="outline"
+                            size="sm"
+                            className="gap-1"
+                            disabled={disabled}
+                            onClick={() => run(agent.agent_id, "wake")}
+                          >
+                            <Sun className="h-4 w-4" />
+                            Wake
+                          </Button>
+                        ) : (
+                          <Button
+                            variant="outline"
+                            size="sm"
+                            className="gap-1"
+                            disabled={disabled}
+                            onClick={() => run(agent.agent_id, "hibernate")}
+                          >
+                            <Moon className="h-4 w-4" />
+                            Hibernate
+                          </Button>
+                        )}
+                        <Button
+                          variant="outline"
+                          size="sm"
+                          className="gap-1"
+                          disabled={disabled}
+                          onClick={() => run(agent.agent_id, "restart")}
+                        >
+                          <RotateCcw className="h-4 w-4" />
+                          Restart
+                        </Button>
+                      </div>
+                    </TableCell>
+                  </TableRow>
+                );
+              })}
+              {agents.length === 0 && (
+                <TableRow>
+                  <TableCell
+                    colSpan={5}
+                    className="text-sm text-muted-foreground"
+                  >
+                    No agents running
+                  </TableCell>
+                </TableRow>
+              )}
+            </TableBody>
+          </Table>
+
+          {lastResult && (
+            <p
+              className={`mt-4 text-sm ${
+                lastResult.ok ? "text-muted-foreground" : "text-destructive"
+              }`}
+            >
+              {lastResult.ok
+                ? `${lastResult.operation} requested for ${lastResult.agent_id}`
+                : `${lastResult.operation} ${lastResult.agent_id} failed: ${lastResult.error}`}
+            </p>
+          )}
+        </Card>
+      </div>
+    </div>
+  );
+};
+
+export default Agents;
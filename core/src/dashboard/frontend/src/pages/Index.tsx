@@ -10,7 +10,7 @@ import {
   type Communication,
 } from "@/components/AgentCommunication";
 import { Button } from "@/components/ui/button";
-import { Activity } from "lucide-react";
+import { Activity, Bot, Wrench } from "lucide-react";
 import {
   createEventStream,
   createSpansStream,
@@ -58,6 +58,8 @@ const qosByEventType: Record<DashboardEventPayload["event_type"], QoSLabel> = {
   event_delivered: "Realtime",
   agent_registered: "Batched",
   agent_unregistered: "Background",
+  agent_degraded: "Background",
+  agent_recovered: "Background",
   tool_invoked: "Realtime",
   routing_decision: "Batched",
 };
@@ -70,6 +72,8 @@ const communicationType: Record<
   event_delivered: "message",
   agent_registered: "message",
   agent_unregistered: "message",
+  agent_degraded: "message",
+  agent_recovered: "message",
   tool_invoked: "tool_call",
   routing_decision: "output",
 };
@@ -426,8 +430,20 @@ const Index = () => {
       <div className="container mx-auto px-4 py-8">
         <HeroSection />
 
-        {/* Timeline Link */}
-        <div className="mb-6 flex justify-end">
+        {/* Page Links */}
+        <div className="mb-6 flex justify-end gap-2">
+          <Link to="/agents">
+            <Button variant="outline" className="gap-2">
+              <Bot className="h-4 w-4" />
+              Agents
+            </Button>
+          </Link>
+          <Link to="/tools">
+            <Button variant="outline" className="gap-2">
+              <Wrench className="h-4 w-4" />
+              Tools
+            </Button>
+          </Link>
           <Link to="/timeline">
             <Button variant="outline" className="gap-2">
               <Activity className="h-4 w-4" />
@@ -0,0 +1,289 @@
+import { useEffect, useMemo, useState } from "react";
+import { useQuery } from "@tanstack/react-query";
+import { Link } from "react-router-dom";
+import { Card } from "@/components/ui/card";
+import { Badge } from "@/components/ui/badge";
+import { Button } from "@/components/ui/button";
+import { Input } from "@/components/ui/input";
+import { Textarea } from "@/components/ui/textarea";
+import { ScrollArea } from "@/components/ui/scroll-area";
+import { Home, Play, RefreshCw, Wrench } from "lucide-react";
+import {
+  callTool,
+  fetchTools,
+  type ToolCallResult,
+  type ToolInfo,
+} from "@/lib/dashboardApi";
+
+/** Build a starting argument object from a tool's JSON schema */
+function exampleArguments(schema: Record<string, unknown>): string {
+  const properties = (schema.properties ?? {}) as Record<
+    string,
+    { type?: string; default?: unknown }
+  >;
+  const example: Record<string, unknown> = {};
+  for (const [key, prop] of Object.entries(properties)) {
+    if (prop.default !== undefined) {
+      example[key] = prop.default;
+      continue;
+    }
+    switch (prop.type) {
+      case "number":
+      case "integer":
+        example[key] = 0;
+        break;
+      case "boolean":
+        example[key] = false;
+        break;
+      case "array":
+        example[key] = [];
+        break;
+      case "object":
+        example[key] = {};
+        break;
+      default:
+        example[key] = "";
+    }
+  }
+  return JSON.stringify(example, null, 2);
+}
+
+const Tools = () => {
+  const [filter, setFilter] = useState("");
+  const [selected, setSelected] = useState<string | null>(null);
+  const [args, setArgs] = useState("{}");
+  const [argsError, setArgsError] = useState<string | null>(null);
+  const [running, setRunning] = useState(false);
+  const [result, setResult] = useState<ToolCallResult | null>(null);
+
+  const { data, refetch, isFetching } = useQuery({
+    queryKey: ["tools"],
+    queryFn: fetchTools,
+    refetchInterval: 10000,
+  });
+
+  const tools = useMemo(() => {
+    const all = data?.tools ?? [];
+    const needle = filter.trim().toLowerCase();
+    if (!needle) return all;
+    return all.filter(
+      (tool) =>
+        tool.name.toLowerCase().includes(needle) ||
+        tool.description.toLowerCase().includes(needle),
+    );
+  }, [data, filter]);
+
+  const current: ToolInfo | undefined = useMemo(
+    () => data?.tools.find((tool) => tool.name === selected),
+    [data, selected],
+  );
+
+  useEffect(() => {
+    if (!current) return;
+    setArgs(exampleArguments(current.parameters));
+    setArgsError(null);
+    setResult(null);
+    // Only reset the form when switching tools, not on every refetch
+    // eslint-disable-next-line react-hooks/exhaustive-deps
+  }, [current?.name]);
+
+  const invoke = async () => {
+    if (!current) return;
+    let parsed: Record<string, unknown>;
+    try {
+      parsed = JSON.parse(args || "{}");
+    } catch (error) {
+      setArgsError((error as Error).message);
+      return;
+    }
+    setArgsError(null);
+    setRunning(true);
+    try {
+      setResult(await callTool(current.name, parsed));
+    } catch (error) {
+      setResult({
+        tool: current.name,
+        ok: false,
+        error: (error as Error).message,
+        duration_ms: 0,
+      });
+    } finally {
+      setRunning(false);
+      refetch();
+    }
+  };
+
+  const callsEnabled = data?.calls_enabled ?? false;
+
+  return (
+    <div className="min-h-screen bg-background">
+      <div className="container mx-auto px-4 py-8">
+        {/* Header */}
+        <div className="mb-8">
+          <div className="flex items-center justify-between mb-4">
+            <div>
+              <h1 className="text-4xl font-bold bg-gradient-to-r from-primary via-secondary to-accent bg-clip-text text-transparent mb-2">
+                Tools
+              </h1>
+              <p className="text-muted-foreground">
+                Inspect registered tools and invoke them manually
+              </p>
+            </div>
+            <Link to="/">
+              <Button variant="outline" size="sm" className="gap-2">
+                <Home className="h-4 w-4" />
+                Back to Dashboard
+              </Button>
+            </Link>
+          </div>
+          {!callsEnabled && (
+            <Card className="p-3 bg-muted/30 border-border/50 text-sm text-muted-foreground">
+              Manual calls are disabled. Start the server with{" "}
+              <code>LOOM_DASHBOARD_TOOL_CALLS=true</code> to enable them.
+            </Card>
+          )}
+        </div>
+
+        <div className="grid gap-6 lg:grid-cols-[320px_1fr]">
+          {/* Tool list */}
+          <Card className="p-4 bg-card/50 backdrop-blur border-border/50">
+            <div className="flex items-center gap-2 mb-4">
+              <Input
+                placeholder="Filter tools"
+                value={filter}
+                onChange={(e) => setFilter(e.target.value)}
+              />
+              <Button
+                variant="outline"
+                size="icon"
+                onClick={() => refetch()}
+                disabled={isFetching}
+              >
+                <RefreshCw className="h-4 w-4" />
+              </Button>
+            </div>
+            <ScrollArea className="h-[560px] pr-2">
+              <div className="space-y-2">
+                {tools.map((tool) => (
+                  <button
+                    key={tool.name}
+                    onClick={() => setSelected(tool.name)}
+                    className={`w-full text-left rounded-md border p-3 transition-colors ${
+                      tool.name === selected
+                        ? "border-primary bg-primary/10"
+                        : "border-border/50 hover:bg-muted/40"
+                    }`}
+                  >
+                    <div className="flex items-center justify-between gap-2">
+                      <span className="font-mono text-sm">{tool.name}</span>
+                      {tool.stats && tool.stats.calls > 0 && (
+                        <Badge variant="outline">{tool.stats.calls}</Badge>
+                      )}
+                    </div>
+                    <p className="text-xs text-muted-foreground mt-1 line-clamp-2">
+                      {tool.description}
+                    </p>
+                  </button>
+                ))}
+                {tools.length === 0 && (
+                  <p className="text-sm text-muted-foreground">
+                    No tools registered
+                  </p>
+                )}
+              </div>
+            </ScrollArea>
+          </Card>
+
+          {/* Invocation panel */}
+          <Card className="p-6 bg-card/50 backdrop-blur border-border/50">
+            {current ? (
+              <div className="space-y-6">
+                <div>
+                  <div className="flex items-center gap-3 mb-1">
+                    <Wrench className="h-5 w-5 text-primary" />
+                    <h2 className="text-2xl font-semibold font-mono">
+                      {current.name}
+                    </h2>
+                    {current.stats && (
+                      <Badge variant="secondary">
+                        {current.stats.circuit_state}
+                      </Badge>
+                    )}
+                  </div>
+                  <p className="text-muted-foreground">{current.description}</p>
+                  {current.stats && (
+                    <div className="flex gap-4 mt-2 text-xs text-muted-foreground">
+                      <span>calls {current.stats.calls}</span>
+                      <span>ok {current.stats.successes}</span>
+                      <span>failed {current.stats.failures}</span>
+                      <span>rejected {current.stats.rejected}</span>
+                    </div>
+                  )}
+                </div>
+
+                <div className="grid gap-4 xl:grid-cols-2">
+                  <div>
+                    <h3 className="text-sm font-medium mb-2">Parameters</h3>
+                    <pre className="text-xs bg-muted/30 rounded-md p-3 overflow-auto max-h-72">
+                      {JSON.stringify(current.parameters, null, 2)}
+                    </pre>
+                  </div>
+                  <div>
+                    <h3 className="text-sm font-medium mb-2">Arguments</h3>
+                    <Textarea
+                      className="font-mono text-xs min-h-[180px]"
+                      value={args}
+                      onChange={(e) => setArgs(e.target.value)}
+                    />
+                    {argsError && (
+                      <p className="text-xs text-destructive mt-1">
+                        Invalid JSON: {argsError}
+                      </p>
+                    )}
+                    <Button
+                      className="mt-3 gap-2"
+                      onClick={invoke}
+                      disabled={!callsEnabled || running}
+                    >
+                      <Play className="h-4 w-4" />
+                      {running ? "Running..." : "Invoke"}
+                    </Button>
+                  </div>
+                </div>
+
+                {result && (
+                  <div>
+                    <div className="flex items-center gap-3 mb-2">
+                      <h3 className="text-sm font-medium">Result</h3>
+                      <Badge variant={result.ok ? "default" : "destructive"}>
+                        {result.ok ? "ok" : "error"}
+                      </Badge>
+                      <span className="text-xs text-muted-foreground">
+                        {result.duration_ms} ms
+                      </span>
+                    </div>
+                    <pre
+                      className={`text-xs rounded-md p-3 overflow-auto max-h-96 ${
+                        result.ok ? "bg-muted/30" : "bg-destructive/10"
+                      }`}
+                    >
+                      {result.ok
+                        ? JSON.stringify(result.result, null, 2)
+                        : result.error}
+                    </pre>
+                  </div>
+                )}
+              </div>
+            ) : (
+              <p className="text-muted-foreground">
+                Select a tool to inspect its schema and invoke it
+              </p>
+            )}
+          </Card>
+        </div>
+      </div>
+    </div>
+  );
+};
+
+export default Tools;
@@ -5,12 +5,14 @@
 mod api;
 mod event_stream;
 mod flow_tracker;
+mod preview;
 mod static_assets;
 mod topology;
 
 pub use api::DashboardServer;
 pub use event_stream::{DashboardEvent, DashboardEventType, EventBroadcaster};
 pub use flow_tracker::{EventFlow, FlowGraph, FlowNode, FlowTracker, NodeType};
+pub use preview::{preview_text, PreviewPolicy, PREVIEW_SCAN_BYTES};
 pub use topology::TopologyBuilder;
 
 /// Dashboard configuration
@@ -18,6 +20,12 @@ pub use topology::TopologyBuilder;
 pub struct DashboardConfig {
     pub port: u16,
     pub host: String,
+    /// Bearer token required on the routes that change state (tool calls, agent
+    /// control, dead-letter replay); those routes are refused while it is unset
+    pub control_token: Option<String>,
+    /// Origins allowed to call the state-changing routes from a browser; the
+    /// read-only routes accept any origin
+    pub allowed_origins: Vec<String>,
 }
 
 impl Default for DashboardConfig {
@@ -25,6 +33,8 @@ impl Default for DashboardConfig {
         Self {
             port: 3030,
             host: "127.0.0.1".to_string(),
+            control_token: None,
+            allowed_origins: Vec::new(),
         }
     }
 }
@@ -37,6 +47,17 @@ impl DashboardConfig {
                 .and_then(|p| p.parse().ok())
                 .unwrap_or(3030),
             host: std::env::var("LOOM_DASHBOARD_HOST").unwrap_or_else(|_| "127.0.0.1".to_string()),
+            control_token: std::env::var("LOOM_DASHBOARD_TOKEN")
+                .ok()
+                .filter(|t| !t.trim().is_empty()),
+            allowed_origins: std::env::var("LOOM_DASHBOARD_ALLOWED_ORIGINS")
+                .map(|v| {
+                    v.split(',')
+                        .map(|o| o.trim().trim_end_matches('/').to_string())
+                        .filter(|o| !o.is_empty())
+                        .collect()
+                })
+                .unwrap_or_default(),
         }
     }
 
@@ -0,0 +1,180 @@
+//! Payload preview policy for Dashboard events
+//!
+//! `payload_preview` is the only place the monitoring plane sees event content. Every
+//! `DashboardEvent` passes through `EventBroadcaster::broadcast`, which applies this
+//! policy, so EventBus, Bridge and API previews are capped and redacted in one place.
+
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+use regex::Regex;
+use tracing::warn;
+
+use crate::cognitive::PiiRedactor;
+use crate::messaging::topic_matches;
+
+/// Bytes of a payload decoded for a preview; redaction sees more than the final cap so a
+/// pattern straddling the cut is still caught
+pub const PREVIEW_SCAN_BYTES: usize = 4096;
+
+/// Replacement of custom redaction matches
+const REDACTED: &str = "[REDACTED]";
+
+/// Decode the start of a payload as preview text
+pub fn preview_text(payload: &[u8]) -> String {
+    String::from_utf8_lossy(&payload[..payload.len().min(PREVIEW_SCAN_BYTES)]).into_owned()
+}
+
+/// How payload previews are shaped before they reach Dashboard clients
+///
+/// Environment (read by `from_env`):
+/// - `LOOM_DASHBOARD_PREVIEW_MAX_CHARS`: length cap, `0` disables previews (default 100)
+/// - `LOOM_DASHBOARD_PREVIEW_REDACT_PII`: replace emails, cards, phones... (default true)
+/// - `LOOM_DASHBOARD_PREVIEW_REDACT`: extra regex whose matches become `[REDACTED]`
+/// - `LOOM_DASHBOARD_PREVIEW_DISABLED_TOPICS`: comma-separated topic patterns without previews
+/// - `LOOM_DASHBOARD_PREVIEW_SAMPLE`: keep previews for one event in N (default 1)
+pub struct PreviewPolicy {
+    max_chars: usize,
+    pii: Option<PiiRedactor>,
+    patterns: Vec<Regex>,
+    disabled_topics: Vec<String>,
+    sample_every: u32,
+}
+
+impl std::fmt::Debug for PreviewPolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PreviewPolicy")
+            .field("max_chars", &self.max_chars)
+            .field("redact_pii", &self.pii.is_some())
+            .field("patterns", &self.patterns.len())
+            .field("disabled_topics", &self.disabled_topics)
+            .field("sample_every", &self.sample_every)
+            .finish()
+    }
+}
+
+impl Default for PreviewPolicy {
+    fn default() -> Self {
+        Self {
+            max_chars: 100,
+            pii: Some(PiiRedactor::all()),
+            patterns: Vec::new(),
+            disabled_topics: Vec::new(),
+            sample_every: 1,
+        }
+    }
+}
+
+impl PreviewPolicy {
+    /// Previews copied verbatim up to `max_chars`, without redaction
+    pub fn raw(max_chars: usize) -> Self {
+        Self {
+            max_chars,
+            pii: None,
+            ..Default::default()
+        }
+    }
+
+    pub fn from_env() -> Self {
+        let mut policy = Self::default();
+        if let Some(max_chars) = std::env::var("LOOM_DASHBOARD_PREVIEW_MAX_CHARS")
+            .ok()
+            .and_then(|v| v.parse().ok())
+        {
+            policy.max_chars = max_chars;
+        }
+        if let Ok(v) = std::env::var("LOOM_DASHBOARD_PREVIEW_REDACT_PII") {
+            policy = policy.with_pii_redaction(v.parse().unwrap_or(true));
+        }
+        if let Ok(pattern) = std::env::var("LOOM_DASHBOARD_PREVIEW_REDACT") {
+            match Regex::new(&pattern) {
+                Ok(regex) => policy.patterns.push(regex),
+                Err(e) => {
+                    // Fail closed: a redaction the operator asked for cannot be skipped
+                    warn!(target: "dashboard", error = %e, "Invalid LOOM_DASHBOARD_PREVIEW_REDACT; previews disabled");
+                    policy.max_chars = 0;
+                }
+            }
+        }
+        if let Ok(topics) = std::env::var("LOOM_DASHBOARD_PREVIEW_DISABLED_TOPICS") {
+            policy.disabled_topics = topics
+                .split(',')
+                .map(str::trim)
+                .filter(|t| !t.is_empty())
+                .map(str::to_string)
+                .collect();
+        }
+        if let Some(n) = std::env::var("LOOM_DASHBOARD_PREVIEW_SAMPLE")
+            .ok()
+            .and_then(|v| v.parse().ok())
+        {
+            policy.sample_every = n;
+        }
+        policy
+    }
+
+    pub fn with_max_chars(mut self, max_chars: usize) -> Self {
+        self.max_chars = max_chars;
+        self
+    }
+
+    pub fn with_pii_redaction(mut self, enabled: bool) -> Self {
+        self.pii = enabled.then(PiiRedactor::all);
+        self
+    }
+
+    /// Replace matches of `pattern` with `[REDACTED]`
+    pub fn with_redaction(mut self, pattern: &str) -> crate::Result<Self> {
+        let regex = Regex::new(pattern).map_err(|e| {
+            crate::LoomError::MetricsError(format!("preview redaction {:?}: {}", pattern, e))
+        })?;
+        self.patterns.push(regex);
+        Ok(self)
+    }
+
+    /// Never preview payloads on topics matching `pattern` (exact or `prefix.*`)
+    pub fn with_disabled_topic(mut self, pattern: impl Into<String>) -> Self {
+        self.disabled_topics.push(pattern.into());
+        self
+    }
+
+    /// Keep previews for one event in `n`, chosen by event id
+    pub fn with_sample_every(mut self, n: u32) -> Self {
+        self.sample_every = n;
+        self
+    }
+
+    /// Whether events of `event_id` on `topic` get a preview at all
+    pub fn allows(&self, topic: &str, event_id: &str) -> bool {
+        if self.max_chars == 0
+            || self
+                .disabled_topics
+                .iter()
+                .any(|pattern| topic_matches(pattern, topic))
+        {
+            return false;
+        }
+        if self.sample_every > 1 {
+            // Keyed by id so an event's publish and deliveries agree
+            let mut hasher = DefaultHasher::new();
+            event_id.hash(&mut hasher);
+            return hasher.finish() % self.sample_every as u64 == 0;
+        }
+        true
+    }
+
+    /// The preview Dashboard clients see for `text`; empty when not allowed
+    pub fn apply(&self, topic: &str, event_id: &str, text: &str) -> String {
+        if text.is_empty() || !self.allows(topic, event_id) {
+            return String::new();
+        }
+        let mut preview = text.to_string();
+        if let Some(pii) = &self.pii {
+            preview = pii.redact(&preview);
+        }
+        for regex in &self.patterns {
+            preview = regex.replace_all(&preview, REDACTED).into_owned();
+        }
+        preview.chars().take(self.max_chars).collect()
+    }
+}
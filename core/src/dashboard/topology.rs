@@ -2,7 +2,7 @@
 //
 // Builds agent topology graph from AgentDirectory and EventBus metrics
 
-use crate::agent::directory::AgentDirectory;
+use crate::agent::directory::{AgentDirectory, AgentStatus};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::sync::Arc;
@@ -19,6 +19,11 @@ pub struct AgentNode {
     pub id: String,
     pub topics: Vec<String>,
     pub capabilities: Vec<String>,
+    /// Disconnected agents stay listed with their last-known state
+    #[serde(default)]
+    pub status: AgentStatus,
+    #[serde(default)]
+    pub last_heartbeat: Option<i64>,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -51,6 +56,8 @@ impl TopologyBuilder {
                 id: info.agent_id.clone(),
                 topics: info.subscribed_topics.clone(),
                 capabilities: info.capabilities.clone(),
+                status: info.status.clone(),
+                last_heartbeat: info.last_heartbeat,
             });
 
             // Build topic -> agent mapping
@@ -0,0 +1,413 @@
+//! Event-to-metric extraction rules
+//!
+//! Rules derive OpenTelemetry metrics from published events, so dashboards can chart
+//! domain events (TTS errors, synthesis latency, ...) without code changes. The
+//! EventBus evaluates every rule against every published event; a matching event
+//! increments a counter or records a histogram value under the rule's name.
+//!
+//! Rules are loaded from the TOML file named by `LOOM_EVENT_METRICS_FILE`:
+//!
+//! ```toml
+//! [[rules]]
+//! name = "tts.errors"                # counter: +1 per matching event
+//! kind = "counter"
+//! topic = "tts.*"                    # optional subscription-style topic pattern
+//! event_type = "tts.error"           # optional
+//! labels = ["voice"]                 # metadata keys (or topic/event_type/source)
+//!
+//! [[rules]]
+//! name = "tts.synthesis_ms"
+//! kind = "histogram"
+//! event_type = "tts.done"
+//! value = "metadata.synthesis_ms"    # or payload.<json.path>, confidence, priority, payload_bytes
+//! unit = "ms"
+//! where = { status = "ok" }          # optional metadata equality filter
+//! ```
+//!
+//! Counters count events unless `value` is set, in which case they sum it. Per-minute
+//! rates are a query over the counter (`rate(...[1m])`). Events whose value is missing
+//! or not numeric are skipped and counted in `loom.event_metrics.skipped_total`.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use opentelemetry::metrics::{Counter, Histogram, Meter, Unit};
+use opentelemetry::{global, KeyValue};
+use serde::{Deserialize, Serialize};
+use tracing::info;
+
+use crate::messaging::event_bus::topic_matches;
+use crate::proto::Event;
+use crate::{LoomError, Result};
+
+/// Instrument a rule records into
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MetricKind {
+    #[default]
+    Counter,
+    Histogram,
+}
+
+/// One event-to-metric rule, see the module docs
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MetricRule {
+    /// Metric name registered with the meter
+    pub name: String,
+    #[serde(default)]
+    pub kind: MetricKind,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub unit: Option<String>,
+    /// Topic pattern (`a.b` or `a.*`); all topics when unset
+    #[serde(default)]
+    pub topic: Option<String>,
+    #[serde(default)]
+    pub event_type: Option<String>,
+    /// Metadata values an event must carry to match
+    #[serde(default, rename = "where")]
+    pub where_metadata: HashMap<String, String>,
+    /// Numeric source; required for histograms
+    #[serde(default)]
+    pub value: Option<String>,
+    /// Attributes taken from the event
+    #[serde(default)]
+    pub labels: Vec<String>,
+}
+
+impl MetricRule {
+    /// Counter of matching events
+    pub fn counter(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            kind: MetricKind::Counter,
+            description: None,
+            unit: None,
+            topic: None,
+            event_type: None,
+            where_metadata: HashMap::new(),
+            value: None,
+            labels: Vec::new(),
+        }
+    }
+
+    /// Histogram of `value` over matching events
+    pub fn histogram(name: impl Into<String>, value: impl Into<String>) -> Self {
+        Self {
+            kind: MetricKind::Histogram,
+            value: Some(value.into()),
+            ..Self::counter(name)
+        }
+    }
+
+    pub fn with_topic(mut self, pattern: impl Into<String>) -> Self {
+        self.topic = Some(pattern.into());
+        self
+    }
+
+    pub fn with_event_type(mut self, event_type: impl Into<String>) -> Self {
+        self.event_type = Some(event_type.into());
+        self
+    }
+
+    pub fn with_where(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.where_metadata.insert(key.into(), value.into());
+        self
+    }
+
+    pub fn with_value(mut self, source: impl Into<String>) -> Self {
+        self.value = Some(source.into());
+        self
+    }
+
+    pub fn with_labels(mut self, labels: &[&str]) -> Self {
+        self.labels = labels.iter().map(|l| l.to_string()).collect();
+        self
+    }
+
+    fn validate(&self) -> Result<()> {
+        if self.name.trim().is_empty() {
+            return Err(LoomError::MetricsError(
+                "Metric rule name must not be empty".to_string(),
+            ));
+        }
+        if self.kind == MetricKind::Histogram && self.value.is_none() {
+            return Err(LoomError::MetricsError(format!(
+                "Histogram rule {} needs a value",
+                self.name
+            )));
+        }
+        if let Some(source) = &self.value {
+            ValueSource::parse(source)
+                .map_err(|e| LoomError::MetricsError(format!("Rule {}: {}", self.name, e)))?;
+        }
+        Ok(())
+    }
+
+    /// Whether `event` published to `topic` matches the rule's filters
+    pub fn matches(&self, topic: &str, event: &Event) -> bool {
+        let topic_ok = match self.topic.as_deref() {
+            Some(pattern) => topic_matches(pattern, topic),
+            None => true,
+        };
+        let type_ok = match self.event_type.as_deref() {
+            Some(event_type) => event_type == event.r#type,
+            None => true,
+        };
+        topic_ok
+            && type_ok
+            && self
+                .where_metadata
+                .iter()
+                .all(|(k, v)| event.metadata.get(k) == Some(v))
+    }
+}
+
+/// Where a rule reads its number from
+#[derive(Debug, Clone, PartialEq)]
+enum ValueSource {
+    Metadata(String),
+    Payload(Vec<String>),
+    Confidence,
+    Priority,
+    PayloadBytes,
+}
+
+impl ValueSource {
+    fn parse(source: &str) -> std::result::Result<Self, String> {
+        match source {
+            "confidence" => return Ok(Self::Confidence),
+            "priority" => return Ok(Self::Priority),
+            "payload_bytes" => return Ok(Self::PayloadBytes),
+            _ => {}
+        }
+        if let Some(key) = source.strip_prefix("metadata.").filter(|k| !k.is_empty()) {
+            return Ok(Self::Metadata(key.to_string()));
+        }
+        if let Some(path) = source.strip_prefix("payload.").filter(|p| !p.is_empty()) {
+            return Ok(Self::Payload(path.split('.').map(str::to_string).collect()));
+        }
+        Err(format!(
+            "unknown value source '{}' (expected metadata.<key>, payload.<path>, confidence, priority or payload_bytes)",
+            source
+        ))
+    }
+
+    fn extract(&self, event: &Event) -> Option<f64> {
+        let value = match self {
+            Self::Metadata(key) => event.metadata.get(key)?.trim().parse().ok(),
+            Self::Payload(path) => {
+                let root: serde_json::Value = serde_json::from_slice(&event.payload).ok()?;
+                let value = path.iter().try_fold(&root, |node, segment| match node {
+                    serde_json::Value::Array(items) => items.get(segment.parse::<usize>().ok()?),
+                    _ => node.get(segment),
+                })?;
+                match value {
+                    serde_json::Value::Number(n) => n.as_f64(),
+                    serde_json::Value::String(s) => s.trim().parse().ok(),
+                    _ => None,
+                }
+            }
+            Self::Confidence => Some(event.confidence as f64),
+            Self::Priority => Some(event.priority as f64),
+            Self::PayloadBytes => Some(event.payload.len() as f64),
+        };
+        value.filter(|v| v.is_finite())
+    }
+}
+
+enum Instrument {
+    Counter(Counter<f64>),
+    Histogram(Histogram<f64>),
+}
+
+struct CompiledRule {
+    rule: MetricRule,
+    value: Option<ValueSource>,
+    instrument: Instrument,
+    matched: AtomicU64,
+    skipped: AtomicU64,
+    total: std::sync::Mutex<f64>,
+}
+
+/// Per-rule totals since startup
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RuleStats {
+    pub name: String,
+    /// Events that matched the rule's filters
+    pub matched: u64,
+    /// Matching events without a usable value (or a negative counter value)
+    pub skipped: u64,
+    /// Sum of recorded values (event count for plain counters)
+    pub total: f64,
+}
+
+/// Compiled rule set evaluated by the EventBus on every publish
+pub struct EventMetrics {
+    rules: Vec<CompiledRule>,
+    skipped_counter: Counter<u64>,
+}
+
+impl std::fmt::Debug for EventMetrics {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("EventMetrics")
+            .field(
+                "rules",
+                &self.rules.iter().map(|r| &r.rule.name).collect::<Vec<_>>(),
+            )
+            .finish()
+    }
+}
+
+impl EventMetrics {
+    /// Register `rules` with the `loom.event_metrics` meter
+    pub fn new(rules: Vec<MetricRule>) -> Result<Self> {
+        Self::with_meter(rules, global::meter("loom.event_metrics"))
+    }
+
+    /// Register `rules` with a specific meter
+    pub fn with_meter(rules: Vec<MetricRule>, meter: Meter) -> Result<Self> {
+        let mut names = std::collections::HashSet::new();
+        let mut compiled = Vec::with_capacity(rules.len());
+        for rule in rules {
+            rule.validate()?;
+            if !names.insert(rule.name.clone()) {
+                return Err(LoomError::MetricsError(format!(
+                    "Duplicate metric rule: {}",
+                    rule.name
+                )));
+            }
+            let description = rule
+                .description
+                .clone()
+                .unwrap_or_else(|| format!("Derived from events by rule {}", rule.name));
+            let unit = rule.unit.clone().unwrap_or_default();
+            let instrument = match rule.kind {
+                MetricKind::Counter => Instrument::Counter(
+                    meter
+                        .f64_counter(rule.name.clone())
+                        .with_description(description)
+                        .with_unit(Unit::new(unit.clone()))
+                        .init(),
+                ),
+                MetricKind::Histogram => Instrument::Histogram(
+                    meter
+                        .f64_histogram(rule.name.clone())
+                        .with_description(description)
+                        .with_unit(Unit::new(unit.clone()))
+                        .init(),
+                ),
+            };
+            compiled.push(CompiledRule {
+                value: rule
+                    .value
+                    .as_deref()
+                    .map(ValueSource::parse)
+                    .transpose()
+                    .map_err(LoomError::MetricsError)?,
+                rule,
+                instrument,
+                matched: AtomicU64::new(0),
+                skipped: AtomicU64::new(0),
+                total: std::sync::Mutex::new(0.0),
+            });
+        }
+
+        let skipped_counter = meter
+            .u64_counter("loom.event_metrics.skipped_total")
+            .with_description("Matching events without a usable metric value")
+            .init();
+        Ok(Self {
+            rules: compiled,
+            skipped_counter,
+        })
+    }
+
+    /// Parse `[[rules]]` tables, see the module docs
+    pub fn from_toml(source: &str) -> Result<Self> {
+        #[derive(Deserialize)]
+        struct File {
+            #[serde(default)]
+            rules: Vec<MetricRule>,
+        }
+        let file: File = toml::from_str(source)
+            .map_err(|e| LoomError::MetricsError(format!("Invalid metric rules file: {e}")))?;
+        Self::new(file.rules)
+    }
+
+    /// Load the file named by `LOOM_EVENT_METRICS_FILE`; `Ok(None)` if it is unset
+    pub fn from_env() -> Result<Option<Self>> {
+        let Ok(path) = std::env::var("LOOM_EVENT_METRICS_FILE") else {
+            return Ok(None);
+        };
+        let source = std::fs::read_to_string(&path)?;
+        let metrics = Self::from_toml(&source)?;
+        info!(target: "event_metrics", path = %path, rules = metrics.rules.len(), "Loaded metric rules");
+        Ok(Some(metrics))
+    }
+
+    /// Evaluate every rule against an event published to `topic`
+    pub fn observe(&self, topic: &str, event: &Event) {
+        for compiled in &self.rules {
+            let rule = &compiled.rule;
+            if !rule.matches(topic, event) {
+                continue;
+            }
+            compiled.matched.fetch_add(1, Ordering::Relaxed);
+
+            let value = match &compiled.value {
+                None => Some(1.0),
+                Some(source) => source.extract(event),
+            };
+            // Counters are monotonic: negative values are skipped too
+            let value = value.filter(|v| rule.kind == MetricKind::Histogram || *v >= 0.0);
+            let Some(value) = value else {
+                compiled.skipped.fetch_add(1, Ordering::Relaxed);
+                self.skipped_counter
+                    .add(1, &[KeyValue::new("rule", rule.name.clone())]);
+                continue;
+            };
+
+            let attributes = labels(rule, topic, event);
+            match &compiled.instrument {
+                Instrument::Counter(counter) => counter.add(value, &attributes),
+                Instrument::Histogram(histogram) => histogram.record(value, &attributes),
+            }
+            *compiled.total.lock().unwrap() += value;
+        }
+    }
+
+    /// Totals per rule, in rule order
+    pub fn stats(&self) -> Vec<RuleStats> {
+        self.rules
+            .iter()
+            .map(|compiled| RuleStats {
+                name: compiled.rule.name.clone(),
+                matched: compiled.matched.load(Ordering::Relaxed),
+                skipped: compiled.skipped.load(Ordering::Relaxed),
+                total: *compiled.total.lock().unwrap(),
+            })
+            .collect()
+    }
+
+    pub fn rules(&self) -> impl Iterator<Item = &MetricRule> {
+        self.rules.iter().map(|compiled| &compiled.rule)
+    }
+}
+
+/// Attributes for `rule`; labels missing from the event are left out
+fn labels(rule: &MetricRule, topic: &str, event: &Event) -> Vec<KeyValue> {
+    rule.labels
+        .iter()
+        .filter_map(|label| {
+            let value = match label.as_str() {
+                "topic" => topic.to_string(),
+                "event_type" => event.r#type.clone(),
+                "source" => event.source.clone(),
+                key => event.metadata.get(key)?.clone(),
+            };
+            Some(KeyValue::new(label.clone(), value))
+        })
+        .collect()
+}
@@ -0,0 +1,431 @@
+//! Memory governor: caps for large in-memory structures and pressure-based shedding
+//!
+//! Components holding unbounded or large in-memory state (EventBus subscriber queues,
+//! the dashboard flow graph, Bridge tool results, context stores) implement
+//! `MemoryComponent` and register with a `MemoryGovernor`. On every check the governor
+//! sums their reported usage against a global cap and optional per-component caps:
+//!
+//! - A component over its own cap is asked to shed down to that cap
+//! - Above `elevated_ratio` of the global cap the level becomes `Elevated`, above the cap
+//!   `Critical`; components are asked to shed in `shed_priority` order (lowest first)
+//!   until usage is back under the elevated threshold
+//! - Level changes are pushed to every component via `on_pressure` (the EventBus stops
+//!   enqueueing to background, then batched subscribers)
+//! - While elevated, and once on recovery, a `system.pressure` event is published
+//!
+//! Sizes are estimates; they are meant for relative pressure, not exact accounting.
+//!
+//! Env overrides for `MemoryGovernorConfig::default()`:
+//! - LOOM_MEMORY_CAP_MB (default 1024)
+//! - LOOM_MEMORY_CHECK_INTERVAL_MS (default 5000)
+
+use crate::proto::Event;
+use crate::EventBus;
+use async_trait::async_trait;
+use dashmap::DashMap;
+use opentelemetry::{
+    global,
+    metrics::{Counter, Histogram},
+    KeyValue,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::task::JoinHandle;
+use tracing::{debug, info, warn};
+
+/// Topic (and event type) of pressure events
+pub const PRESSURE_TOPIC: &str = crate::proto::conventions::topics::SYSTEM_PRESSURE;
+
+/// Memory pressure level
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PressureLevel {
+    Normal,
+    /// Usage above `elevated_ratio` of the global cap
+    Elevated,
+    /// Usage at or above the global cap
+    Critical,
+}
+
+impl PressureLevel {
+    pub fn as_u8(self) -> u8 {
+        match self {
+            Self::Normal => 0,
+            Self::Elevated => 1,
+            Self::Critical => 2,
+        }
+    }
+
+    pub fn from_u8(v: u8) -> Self {
+        match v {
+            0 => Self::Normal,
+            1 => Self::Elevated,
+            _ => Self::Critical,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Normal => "normal",
+            Self::Elevated => "elevated",
+            Self::Critical => "critical",
+        }
+    }
+}
+
+/// A component whose memory the governor tracks and can shed
+#[async_trait]
+pub trait MemoryComponent: Send + Sync {
+    /// Stable name, used for per-component caps and reporting
+    fn name(&self) -> String;
+
+    /// Order in which components are asked to shed (lower sheds first)
+    fn shed_priority(&self) -> u8 {
+        100
+    }
+
+    /// Approximate bytes currently held
+    async fn memory_usage(&self) -> usize;
+
+    /// Free roughly `bytes`; returns the estimated bytes actually freed.
+    /// Report-only components keep the default.
+    async fn shed(&self, _bytes: usize, _level: PressureLevel) -> usize {
+        0
+    }
+
+    /// Called when the global pressure level changes
+    async fn on_pressure(&self, _level: PressureLevel) {}
+}
+
+/// Governor configuration
+#[derive(Debug, Clone)]
+pub struct MemoryGovernorConfig {
+    /// Cap for the sum of all components
+    pub global_cap_bytes: usize,
+    /// Caps for individual components, by component name
+    pub component_caps: HashMap<String, usize>,
+    /// Fraction of the global cap at which pressure becomes `Elevated`
+    pub elevated_ratio: f64,
+    /// Interval of the background check started by `start`
+    pub check_interval: Duration,
+}
+
+impl Default for MemoryGovernorConfig {
+    fn default() -> Self {
+        let cap_mb = std::env::var("LOOM_MEMORY_CAP_MB")
+            .ok()
+            .and_then(|v| v.parse::<usize>().ok())
+            .unwrap_or(1024);
+        let interval_ms = std::env::var("LOOM_MEMORY_CHECK_INTERVAL_MS")
+            .ok()
+            .and_then(|v| v.parse::<u64>().ok())
+            .unwrap_or(5_000);
+        Self {
+            global_cap_bytes: cap_mb * 1024 * 1024,
+            component_caps: HashMap::new(),
+            elevated_ratio: 0.8,
+            check_interval: Duration::from_millis(interval_ms.max(1)),
+        }
+    }
+}
+
+impl MemoryGovernorConfig {
+    pub fn with_global_cap(mut self, bytes: usize) -> Self {
+        self.global_cap_bytes = bytes;
+        self
+    }
+
+    pub fn with_component_cap(mut self, name: impl Into<String>, bytes: usize) -> Self {
+        self.component_caps.insert(name.into(), bytes);
+        self
+    }
+
+    pub fn with_elevated_ratio(mut self, ratio: f64) -> Self {
+        self.elevated_ratio = ratio.clamp(0.0, 1.0);
+        self
+    }
+
+    pub fn with_check_interval(mut self, interval: Duration) -> Self {
+        self.check_interval = interval;
+        self
+    }
+
+    fn elevated_threshold(&self) -> usize {
+        (self.global_cap_bytes as f64 * self.elevated_ratio) as usize
+    }
+
+    fn level_for(&self, total: usize) -> PressureLevel {
+        if total >= self.global_cap_bytes {
+            PressureLevel::Critical
+        } else if total > self.elevated_threshold() {
+            PressureLevel::Elevated
+        } else {
+            PressureLevel::Normal
+        }
+    }
+}
+
+/// Usage of one component during a check
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ComponentUsage {
+    pub name: String,
+    /// Bytes after shedding
+    pub bytes: usize,
+    pub cap_bytes: Option<usize>,
+    /// Bytes freed by this check
+    pub shed_bytes: usize,
+}
+
+/// Result of a governor check; also the payload of `system.pressure` events
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PressureReport {
+    /// Level observed before shedding
+    pub level: PressureLevel,
+    pub previous_level: PressureLevel,
+    /// Sum of component usage after shedding
+    pub total_bytes: usize,
+    pub global_cap_bytes: usize,
+    pub shed_bytes: usize,
+    pub components: Vec<ComponentUsage>,
+    pub timestamp_ms: i64,
+}
+
+/// Periodically checks registered components against their caps
+pub struct MemoryGovernor {
+    config: MemoryGovernorConfig,
+    components: DashMap<String, Arc<dyn MemoryComponent>>,
+    event_bus: Option<Arc<EventBus>>,
+    level: Mutex<PressureLevel>,
+    task: Mutex<Option<JoinHandle<()>>>,
+
+    // OpenTelemetry metrics
+    usage_histogram: Histogram<u64>,
+    shed_counter: Counter<u64>,
+    pressure_events_counter: Counter<u64>,
+}
+
+impl MemoryGovernor {
+    pub fn new(config: MemoryGovernorConfig) -> Self {
+        let meter = global::meter("loom.memory");
+        let usage_histogram = meter
+            .u64_histogram("loom.memory.usage_bytes")
+            .with_description("Estimated bytes held per component at each governor check")
+            .init();
+        let shed_counter = meter
+            .u64_counter("loom.memory.shed_bytes_total")
+            .with_description("Estimated bytes freed by pressure-based shedding")
+            .init();
+        let pressure_events_counter = meter
+            .u64_counter("loom.memory.pressure_events_total")
+            .with_description("system.pressure events emitted")
+            .init();
+
+        Self {
+            config,
+            components: DashMap::new(),
+            event_bus: None,
+            level: Mutex::new(PressureLevel::Normal),
+            task: Mutex::new(None),
+            usage_histogram,
+            shed_counter,
+            pressure_events_counter,
+        }
+    }
+
+    /// Publish `system.pressure` events on this bus
+    pub fn with_event_bus(mut self, event_bus: Arc<EventBus>) -> Self {
+        self.event_bus = Some(event_bus);
+        self
+    }
+
+    pub fn config(&self) -> &MemoryGovernorConfig {
+        &self.config
+    }
+
+    /// Register a component; replaces any component with the same name
+    pub fn register(&self, component: Arc<dyn MemoryComponent>) {
+        let name = component.name();
+        debug!(target: "memory_governor", component = %name, "Registered memory component");
+        self.components.insert(name, component);
+    }
+
+    pub fn unregister(&self, name: &str) {
+        self.components.remove(name);
+    }
+
+    /// Names of registered components
+    pub fn components(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.components.iter().map(|e| e.key().clone()).collect();
+        names.sort();
+        names
+    }
+
+    /// Level observed by the last check
+    pub fn level(&self) -> PressureLevel {
+        *self.level.lock().unwrap()
+    }
+
+    /// Current usage of every component, measured without shedding or changing the level
+    pub async fn usage(&self) -> Vec<ComponentUsage> {
+        let components: Vec<Arc<dyn MemoryComponent>> = self
+            .components
+            .iter()
+            .map(|e| Arc::clone(e.value()))
+            .collect();
+        let mut usages = Vec::with_capacity(components.len());
+        for component in components {
+            let name = component.name();
+            usages.push(ComponentUsage {
+                cap_bytes: self.config.component_caps.get(&name).copied(),
+                bytes: component.memory_usage().await,
+                shed_bytes: 0,
+                name,
+            });
+        }
+        usages.sort_by(|a, b| a.name.cmp(&b.name));
+        usages
+    }
+
+    /// Measure all components, shed as needed and emit a pressure event if warranted
+    pub async fn check(&self) -> PressureReport {
+        // Snapshot so no DashMap guard is held across awaits
+        let mut components: Vec<Arc<dyn MemoryComponent>> = self
+            .components
+            .iter()
+            .map(|e| Arc::clone(e.value()))
+            .collect();
+        components.sort_by_key(|c| (c.shed_priority(), c.name()));
+
+        let mut usages = Vec::with_capacity(components.len());
+        for component in &components {
+            let name = component.name();
+            let cap = self.config.component_caps.get(&name).copied();
+            let mut bytes = component.memory_usage().await;
+            let mut shed_bytes = 0;
+            if let Some(cap) = cap.filter(|cap| bytes > *cap) {
+                let freed = component.shed(bytes - cap, PressureLevel::Elevated).await;
+                shed_bytes += freed;
+                bytes = bytes.saturating_sub(freed);
+            }
+            usages.push(ComponentUsage {
+                name,
+                bytes,
+                cap_bytes: cap,
+                shed_bytes,
+            });
+        }
+
+        let mut total: usize = usages.iter().map(|u| u.bytes).sum();
+        let level = self.config.level_for(total);
+        if level != PressureLevel::Normal {
+            let threshold = self.config.elevated_threshold();
+            for (component, usage) in components.iter().zip(usages.iter_mut()) {
+                if total <= threshold {
+                    break;
+                }
+                let freed = component.shed(total - threshold, level).await;
+                let freed = freed.min(usage.bytes);
+                usage.bytes -= freed;
+                usage.shed_bytes += freed;
+                total -= freed;
+            }
+        }
+
+        for usage in &usages {
+            let attrs = [KeyValue::new("component", usage.name.clone())];
+            self.usage_histogram.record(usage.bytes as u64, &attrs);
+            if usage.shed_bytes > 0 {
+                self.shed_counter.add(usage.shed_bytes as u64, &attrs);
+            }
+        }
+
+        let previous_level = std::mem::replace(&mut *self.level.lock().unwrap(), level);
+        if previous_level != level {
+            if level > previous_level {
+                warn!(target: "memory_governor", level = level.as_str(), total_bytes = total, cap = self.config.global_cap_bytes, "Memory pressure rising");
+            } else {
+                info!(target: "memory_governor", level = level.as_str(), total_bytes = total, "Memory pressure easing");
+            }
+            for component in &components {
+                component.on_pressure(level).await;
+            }
+        }
+
+        let report = PressureReport {
+            level,
+            previous_level,
+            total_bytes: total,
+            global_cap_bytes: self.config.global_cap_bytes,
+            shed_bytes: usages.iter().map(|u| u.shed_bytes).sum(),
+            components: usages,
+            timestamp_ms: chrono::Utc::now().timestamp_millis(),
+        };
+
+        if level != PressureLevel::Normal || previous_level != level {
+            self.emit(&report).await;
+        }
+        report
+    }
+
+    async fn emit(&self, report: &PressureReport) {
+        let Some(ref event_bus) = self.event_bus else {
+            return;
+        };
+        let mut metadata = HashMap::new();
+        metadata.insert("level".to_string(), report.level.as_str().to_string());
+        metadata.insert("total_bytes".to_string(), report.total_bytes.to_string());
+        let event = Event {
+            id: format!("evt_pressure_{}", report.timestamp_ms),
+            r#type: PRESSURE_TOPIC.to_string(),
+            timestamp_ms: report.timestamp_ms,
+            source: "memory_governor".to_string(),
+            metadata,
+            payload: serde_json::to_vec(report).unwrap_or_default(),
+            confidence: 1.0,
+            tags: vec!["system".into()],
+            priority: 90,
+        };
+        self.pressure_events_counter
+            .add(1, &[KeyValue::new("level", report.level.as_str())]);
+        if let Err(e) = event_bus.publish(PRESSURE_TOPIC, event).await {
+            warn!(target: "memory_governor", error = %e, "Failed to publish pressure event");
+        }
+    }
+
+    /// Start the periodic check; restarting replaces the previous task
+    pub fn start(self: &Arc<Self>) {
+        let governor = Arc::clone(self);
+        let handle = tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(governor.config.check_interval);
+            ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            loop {
+                ticker.tick().await;
+                governor.check().await;
+            }
+        });
+        if let Some(old) = self.task.lock().unwrap().replace(handle) {
+            old.abort();
+        }
+    }
+
+    /// Stop the periodic check
+    pub fn stop(&self) {
+        if let Some(handle) = self.task.lock().unwrap().take() {
+            handle.abort();
+        }
+    }
+}
+
+/// Rough in-memory size of an event (strings, payload and a fixed overhead)
+pub fn approx_event_bytes(event: &Event) -> usize {
+    let metadata: usize = event.metadata.iter().map(|(k, v)| k.len() + v.len()).sum();
+    let tags: usize = event.tags.iter().map(|t| t.len()).sum();
+    64 + event.id.len()
+        + event.r#type.len()
+        + event.source.len()
+        + event.payload.len()
+        + metadata
+        + tags
+}
@@ -1,50 +1,135 @@
 // Loom Core Library
 // Event-driven AI operating system runtime
 
+pub mod a2a; // Agent-to-agent protocol server and client
 pub mod agent;
+pub mod blob; // Blob store for large payloads passed by reference
 pub mod cognitive; // LLM + Cognitive Loop (perceive-think-act)
+pub mod config; // TOML manifest for env-var settings, export and strict mode
 pub mod context; // Context Engineering system
 pub mod dashboard; // Real-time event flow visualization
+pub mod event_metrics; // Metrics derived from events by configurable rules
+pub mod governor; // Memory caps and pressure-based shedding
 pub mod messaging; // Event Bus, Envelope, Collab
+pub mod namespace; // Namespaces scoping topics, agents and tools
+pub mod openai; // OpenAI-compatible chat completions facade
+pub mod pools; // Dedicated runtimes for blocking / IO-heavy work
+pub mod replay; // Trace recording and deterministic replay of agent runs
+pub mod s3; // S3-compatible object storage client
+pub mod shutdown; // Ordered, graceful component shutdown
+pub mod simulation; // Scripted persona agents for load and behavior tests
+pub mod stats; // One snapshot of every subsystem's counters
+pub mod task_queue; // Durable priority task queue with leases and worker pools
 pub mod telemetry;
+pub mod tenancy; // Tenant namespaces, credentials and quotas
 pub mod tools; // Unified tool system (Native + MCP)
+pub mod usage_export; // Differentially private usage counts for upstream analytics
+pub mod workflow; // DAG orchestration of tool calls and agent requests
 
 // Export agent types
-pub use agent::directory::{AgentDirectory, AgentInfo, AgentStatus, CapabilityDirectory};
-pub use agent::{Agent, AgentBehavior, AgentRuntime};
+pub use agent::declarative::{AgentConfigLoader, AgentSpec, AgentsFile};
+pub use agent::directory::{
+    AgentDirectory, AgentInfo, AgentStatus, CapabilityDirectory, ConnectionRecord,
+};
+pub use agent::params::{EnvSecrets, ParameterResolver, SecretProvider};
+pub use agent::reminders::{Reminder, ReminderScheduler};
+pub use agent::{Agent, AgentBehavior, AgentFactory, AgentRuntime, HibernationPolicy};
+
+// Export blob store types
+pub use blob::{BlobAccess, BlobRef, BlobStore};
 
 // Export agent state from proto
 pub use proto::{AgentConfig, AgentState};
 
 // Export cognitive types
+pub use cognitive::llm::policy::{
+    CheapestFirst, FastestFirst, ModelCostTable, ModelProfile, QualityFirst, RoutingPolicy,
+};
 pub use cognitive::llm::router::{
     ConfidenceEstimator, DummyConfidenceEstimator, ModelRouter, Route, RoutingDecision,
 };
-pub use cognitive::llm::{LlmClient, LlmClientConfig, LlmResponse};
+pub use cognitive::llm::tiers::{LatencyClass, ModelTier, SloStats, TierTable};
+pub use cognitive::llm::{
+    LlmCache, LlmCacheConfig, LlmClient, LlmClientConfig, LlmResponse, LlmUsage, LlmUsageStats,
+};
 pub use cognitive::{
-    CognitiveAgent, CognitiveConfig, CognitiveLoop, MemoryBuffer, SimpleCognitiveLoop,
-    ThinkingStrategy,
+    CognitiveAgent, CognitiveConfig, CognitiveLoop, MemoryBuffer, Session, SessionManager,
+    SimpleCognitiveLoop, StructuredOutput, ThinkingStrategy,
 };
 
 // Export context types
-pub use context::{builder::ContextBuilder, PromptBundle, TokenBudget};
+pub use context::{builder::ContextBuilder, ModelBudget, PromptBundle, TokenBudget};
 pub use context::{AgentContext, ContextPipeline, InMemoryStore, MemoryStore, RocksDbStore};
 
 // Export messaging types
-pub use messaging::collab::{types as collab_types, Collaborator};
+pub use messaging::collab::{
+    types as collab_types, BidConstraints, BidScorer, BidTerms, CollabRetryPolicy, Collaborator,
+    ContractNetConfig, IdempotencyCache, ProposalValidator,
+};
 pub use messaging::{
-    agent_reply_topic, Envelope, EventBus, EventBusStats, EventExt, EventHandler, ThreadTopicKind,
+    agent_reply_topic, topic_matches, BackpressurePolicy, DeadLetter, DeadLetterFilter,
+    DeadLetterStore, DeliveredEvent, Envelope, EnvelopeSigner, EventBus, EventBusStats, EventExt,
+    EventHandler, OpenThread, OutstandingAcks, RateLimit, RateLimited, ReliableConfig,
+    ThreadTopicKind, ThreadTracker, THREAD_CLOSED,
 };
 
 // Export tool types
 pub use tools::mcp::{McpClient, McpManager, McpToolAdapter};
 pub use tools::native::{
-    DeleteFileTool, ListDirTool, ReadFileTool, ShellTool, WeatherTool, WebSearchTool, WriteFileTool,
+    DeleteFileTool, KvPolicy, KvQuotas, KvStore, ListDirTool, MathEvalTool, ReadFileTool,
+    SearchProviderKind, ShellSandbox, ShellTool, WeatherTool, WebSearchConfig, WebSearchTool,
+    WriteFileTool,
 };
-pub use tools::{Tool, ToolError, ToolRegistry};
+pub use tools::{CancellationToken, Tool, ToolError, ToolPolicy, ToolRegistry, ToolStats};
+
+// Export A2A types
+pub use a2a::{A2aClient, A2aConfig, A2aDelegateTool, A2aServer, AgentCard};
+
+// Export OpenAI facade
+pub use openai::{OpenAiConfig, OpenAiServer};
+
+// Export config manifest
+pub use config::LoomConfig;
+
+// Export tenancy and namespace types
+pub use namespace::{Namespace, NamespaceBridge};
+pub use tenancy::{TenantConfig, TenantQuotas, TenantRegistry, TenantUsage};
+
+// Export workflow types
+pub use event_metrics::{EventMetrics, MetricKind, MetricRule, RuleStats};
+pub use workflow::{Step, Workflow, WorkflowEngine, WorkflowReport};
+
+// Export sentinel
+pub use agent::sentinel::{Anomaly, SentinelAgent, SentinelConfig};
+
+// Export memory governor
+pub use governor::{
+    MemoryComponent, MemoryGovernor, MemoryGovernorConfig, PressureLevel, PressureReport,
+};
+
+// Export dedicated pools
+pub use pools::{DedicatedPool, OverloadPolicy, PoolConfig, RuntimePools, RuntimePoolsConfig};
+
+// Export trace recording and replay
+pub use replay::{RecordingBehavior, ReplayReport, ReplayRuntime, Trace, TraceRecorder};
+
+// Shutdown
+pub use shutdown::{ShutdownHook, ShutdownRegistry, ShutdownReport, StopOutcome};
+
+// Export persona simulation
+pub use simulation::{Simulation, SimulationConfig, SimulationStats};
+
+// Export unified stats
+pub use stats::{LoomStats, StatsCollector, StatsSource};
+
+// Export task queue
+pub use task_queue::{TaskLease, TaskQueue, TaskQueueConfig, TaskQueueStats, TaskWorker};
 
 // Export telemetry
 pub use telemetry::{init_telemetry, shutdown_telemetry, SpanCollector, SpanData};
+pub use usage_export::{
+    UsageExportConfig, UsageExporter, UsageMetric, UsageReport, UsageRow, USAGE_SCHEMA,
+};
 
 // Re-export proto types (Event, QoSLevel, etc.)
 pub use proto::{Event, QoSLevel};
@@ -74,6 +159,42 @@ pub enum LoomError {
 
     #[error("Serialization error: {0}")]
     SerializationError(#[from] serde_json::Error),
+
+    #[error("Overloaded: {0}")]
+    Overloaded(String),
+
+    #[error("Workflow error: {0}")]
+    WorkflowError(String),
+
+    #[error("Tenant error: {0}")]
+    TenantError(String),
+
+    #[error("Namespace error: {0}")]
+    NamespaceError(String),
+
+    #[error("Quota exceeded: {0}")]
+    QuotaExceeded(String),
+
+    #[error("Rate limited: {0}")]
+    RateLimited(messaging::rate_limit::RateLimited),
+
+    #[error("Cancelled: {0}")]
+    Cancelled(String),
+
+    #[error("Tokenizer error: {0}")]
+    TokenizerError(String),
+
+    #[error("A2A error: {0}")]
+    A2aError(String),
+
+    #[error("Metrics error: {0}")]
+    MetricsError(String),
+
+    #[error("Config error: {0}")]
+    ConfigError(String),
+
+    #[error("Signature error: {0}")]
+    SignatureError(String),
 }
 pub type Result<T> = std::result::Result<T, LoomError>;
 
@@ -85,25 +206,102 @@ pub struct Loom {
     pub tool_registry: std::sync::Arc<ToolRegistry>,
     pub mcp_manager: std::sync::Arc<tools::mcp::McpManager>,
     pub agent_directory: std::sync::Arc<AgentDirectory>,
+    pub pools: RuntimePools,
+    pub memory_governor: std::sync::Arc<MemoryGovernor>,
+    pub sentinel: std::sync::Arc<SentinelAgent>,
+    pub shutdown_registry: std::sync::Arc<ShutdownRegistry>,
+    /// Set when `LOOM_USAGE_EXPORT_ENDPOINT` opts into usage export
+    pub usage_exporter: Option<std::sync::Arc<UsageExporter>>,
+    /// Set when `LOOM_LLM_CACHE` enables the LLM response cache; attached to the built-in
+    /// `llm:generate` client, and shareable with application clients via `with_cache`
+    pub llm_cache: Option<std::sync::Arc<LlmCache>>,
+    /// Reminders of the `schedule:*` tools, persistent when `LOOM_REMINDERS_PATH` is set
+    pub reminders: std::sync::Arc<ReminderScheduler>,
+    /// Backend requests and tokens of the built-in `llm:generate` client; shareable with
+    /// application clients via `with_usage`
+    pub llm_usage: std::sync::Arc<LlmUsage>,
+    /// Builds `stats()`; register extra sections (the Bridge) with `register`
+    pub stats_collector: std::sync::Arc<StatsCollector>,
+    /// Set when `LOOM_BLOB_DIR` or `LOOM_BLOB_S3_ENDPOINT` configures the blob store;
+    /// backs the `blob:*` tools
+    pub blob_store: Option<std::sync::Arc<BlobStore>>,
 }
 
 impl Loom {
     pub async fn new() -> Result<Self> {
-        let event_bus = std::sync::Arc::new(EventBus::new().await?);
+        let mut event_bus = EventBus::new().await?;
+        if let Some(metrics) = EventMetrics::from_env()? {
+            event_bus.set_event_metrics(std::sync::Arc::new(metrics));
+        }
+        let usage_exporter = match UsageExportConfig::from_env()? {
+            Some(config) => Some(std::sync::Arc::new(UsageExporter::new(config)?)),
+            None => None,
+        };
+        if let Some(exporter) = &usage_exporter {
+            event_bus.set_usage_exporter(std::sync::Arc::clone(exporter));
+        }
+        if let Some(signer) = EnvelopeSigner::from_env()? {
+            event_bus.set_signer(std::sync::Arc::new(signer));
+        }
+        event_bus.set_dead_letter_store(std::sync::Arc::new(DeadLetterStore::from_env()?));
+        let event_bus = std::sync::Arc::new(event_bus);
+        if let Some((patterns, config)) = crate::messaging::receipts::critical_topics_from_env() {
+            for pattern in patterns {
+                event_bus.mark_critical(pattern, config.clone());
+            }
+        }
+        if let Some(ttl) = crate::messaging::threads::thread_ttl_from_env() {
+            event_bus.set_thread_ttl(ttl);
+        }
         let tool_registry = std::sync::Arc::new(ToolRegistry::new());
-        let agent_directory = std::sync::Arc::new(AgentDirectory::new());
+        let agent_directory =
+            std::sync::Arc::new(match std::env::var("LOOM_AGENT_DIRECTORY_PATH") {
+                Ok(path) => AgentDirectory::with_persistence(path)?,
+                Err(_) => AgentDirectory::new(),
+            });
         let model_router = ModelRouter::new().await?;
+        let pools = RuntimePools::new(RuntimePoolsConfig::default())?;
+        let memory_governor = std::sync::Arc::new(
+            MemoryGovernor::new(MemoryGovernorConfig::default())
+                .with_event_bus(std::sync::Arc::clone(&event_bus)),
+        );
+        memory_governor.register(std::sync::Arc::clone(&event_bus) as _);
+        let llm_cache = match LlmCacheConfig::from_env()? {
+            Some(config) => Some(std::sync::Arc::new(LlmCache::new(config)?)),
+            None => None,
+        };
+
+        let llm_usage = std::sync::Arc::new(LlmUsage::new());
+
+        let blob_store = BlobStore::from_env()?.map(std::sync::Arc::new);
+
+        let reminders = match std::env::var("LOOM_REMINDERS_PATH") {
+            Ok(path) => ReminderScheduler::open(std::sync::Arc::clone(&event_bus), path)?,
+            Err(_) => ReminderScheduler::new(std::sync::Arc::clone(&event_bus)),
+        };
 
         // Register built-in tools
         {
-            use crate::cognitive::llm::LlmGenerateProvider;
+            use crate::cognitive::llm::{LlmClient, LlmGenerateProvider};
+            use crate::pools::PooledTool;
             use crate::tools::native::{
-                DeleteFileTool, ListDirTool, ReadFileTool, ShellTool, WeatherTool, WebSearchTool,
-                WriteFileTool,
+                blob_tools, kv_tools, schedule_tools, storage_tools, time, time_tools,
+                DeleteFileTool, KvPolicy, KvStore, ListDirTool, MathConfig, MathEvalTool,
+                ObjectStorage, ReadFileTool, ReportRenderTool, ShellSandbox, ShellTool,
+                StorageConfig, TableQueryTool, WeatherTool, WebSearchTool, WriteFileTool,
             };
             use std::sync::Arc as SyncArc;
 
-            if let Ok(provider) = LlmGenerateProvider::new(None) {
+            let llm_client = LlmClient::from_env().map(|c| {
+                let c = c
+                    .with_io_pool(std::sync::Arc::clone(&pools.llm_io))
+                    .with_usage(std::sync::Arc::clone(&llm_usage));
+                match &llm_cache {
+                    Some(cache) => c.with_cache(std::sync::Arc::clone(cache)),
+                    None => c,
+                }
+            });
+            if let Ok(provider) = llm_client.and_then(|c| LlmGenerateProvider::new(Some(c))) {
                 tool_registry.register(SyncArc::new(provider)).await;
             } else {
                 tracing::warn!(
@@ -114,94 +312,147 @@ impl Loom {
 
             let workspace_root = std::env::current_dir().unwrap_or_default();
 
-            // File system tools (workspace-scoped)
-            tool_registry
-                .register(SyncArc::new(ReadFileTool::new(workspace_root.clone())))
-                .await;
-            tool_registry
-                .register(SyncArc::new(WriteFileTool::new(workspace_root.clone())))
-                .await;
+            // File system tools (workspace-scoped), run on the blocking tools pool
+            let fs_tools: Vec<SyncArc<dyn Tool>> = vec![
+                SyncArc::new(ReadFileTool::new(workspace_root.clone())),
+                SyncArc::new(WriteFileTool::new(workspace_root.clone())),
+                SyncArc::new(ListDirTool::new(workspace_root.clone())),
+                SyncArc::new(DeleteFileTool::new(workspace_root.clone())),
+                // CSV/TSV analysis over workspace files and blobs
+                SyncArc::new(TableQueryTool::new(workspace_root, blob_store.clone())),
+            ];
+            for tool in fs_tools {
+                tool_registry
+                    .register(SyncArc::new(PooledTool::new(
+                        tool,
+                        SyncArc::clone(&pools.tools_blocking),
+                    )))
+                    .await;
+            }
+
+            // Sandbox (working dir, environment, limits) from LOOM_SHELL_* variables
+            let shell = ShellTool::new(vec![
+                // File listing & navigation
+                "ls".to_string(),
+                "pwd".to_string(),
+                "find".to_string(),
+                "which".to_string(),
+                "whereis".to_string(),
+                "file".to_string(),
+                "stat".to_string(),
+                "realpath".to_string(),
+                "readlink".to_string(),
+                "basename".to_string(),
+                "dirname".to_string(),
+                // File content reading
+                "cat".to_string(),
+                "head".to_string(),
+                "tail".to_string(),
+                "less".to_string(),
+                "more".to_string(),
+                "wc".to_string(),
+                // Text search & processing
+                "grep".to_string(),
+                "awk".to_string(),
+                "sed".to_string(),
+                "sort".to_string(),
+                "uniq".to_string(),
+                "cut".to_string(),
+                "tr".to_string(),
+                "diff".to_string(),
+                // System info (read-only)
+                "echo".to_string(),
+                "date".to_string(),
+                "whoami".to_string(),
+                "hostname".to_string(),
+                "uname".to_string(),
+                "env".to_string(),
+                "printenv".to_string(),
+                "df".to_string(),
+                "du".to_string(),
+                "free".to_string(),
+                "uptime".to_string(),
+                "ps".to_string(),
+                "top".to_string(),
+                "htop".to_string(),
+                // Network info (read-only)
+                "ping".to_string(),
+                "curl".to_string(),
+                "wget".to_string(),
+                "nslookup".to_string(),
+                "dig".to_string(),
+                "host".to_string(),
+                "ifconfig".to_string(),
+                "ip".to_string(),
+                "netstat".to_string(),
+                "ss".to_string(),
+                // Development tools
+                "git".to_string(),
+                "python".to_string(),
+                "python3".to_string(),
+                "node".to_string(),
+                "npm".to_string(),
+                "cargo".to_string(),
+                "rustc".to_string(),
+                "make".to_string(),
+                "cmake".to_string(),
+            ])
+            .with_sandbox(ShellSandbox::from_env());
+            tool_registry.register(SyncArc::new(shell)).await;
+
             tool_registry
-                .register(SyncArc::new(ListDirTool::new(workspace_root.clone())))
+                .register(SyncArc::new(WeatherTool::new()))
                 .await;
             tool_registry
-                .register(SyncArc::new(DeleteFileTool::new(workspace_root)))
+                .register(SyncArc::new(WebSearchTool::new()))
                 .await;
 
             tool_registry
-                .register(SyncArc::new(ShellTool::new(vec![
-                    // File listing & navigation
-                    "ls".to_string(),
-                    "pwd".to_string(),
-                    "find".to_string(),
-                    "which".to_string(),
-                    "whereis".to_string(),
-                    "file".to_string(),
-                    "stat".to_string(),
-                    "realpath".to_string(),
-                    "readlink".to_string(),
-                    "basename".to_string(),
-                    "dirname".to_string(),
-                    // File content reading
-                    "cat".to_string(),
-                    "head".to_string(),
-                    "tail".to_string(),
-                    "less".to_string(),
-                    "more".to_string(),
-                    "wc".to_string(),
-                    // Text search & processing
-                    "grep".to_string(),
-                    "awk".to_string(),
-                    "sed".to_string(),
-                    "sort".to_string(),
-                    "uniq".to_string(),
-                    "cut".to_string(),
-                    "tr".to_string(),
-                    "diff".to_string(),
-                    // System info (read-only)
-                    "echo".to_string(),
-                    "date".to_string(),
-                    "whoami".to_string(),
-                    "hostname".to_string(),
-                    "uname".to_string(),
-                    "env".to_string(),
-                    "printenv".to_string(),
-                    "df".to_string(),
-                    "du".to_string(),
-                    "free".to_string(),
-                    "uptime".to_string(),
-                    "ps".to_string(),
-                    "top".to_string(),
-                    "htop".to_string(),
-                    // Network info (read-only)
-                    "ping".to_string(),
-                    "curl".to_string(),
-                    "wget".to_string(),
-                    "nslookup".to_string(),
-                    "dig".to_string(),
-                    "host".to_string(),
-                    "ifconfig".to_string(),
-                    "ip".to_string(),
-                    "netstat".to_string(),
-                    "ss".to_string(),
-                    // Development tools
-                    "git".to_string(),
-                    "python".to_string(),
-                    "python3".to_string(),
-                    "node".to_string(),
-                    "npm".to_string(),
-                    "cargo".to_string(),
-                    "rustc".to_string(),
-                    "make".to_string(),
-                    "cmake".to_string(),
-                ])))
+                .register(SyncArc::new(MathEvalTool::with_config(
+                    MathConfig::from_env(),
+                )))
                 .await;
+            for tool in time_tools(time::timezone_from_env()) {
+                tool_registry.register(tool).await;
+            }
 
+            // Agent scratch space, persistent when LOOM_KV_PATH is set
+            let kv_store = SyncArc::new(match std::env::var("LOOM_KV_PATH") {
+                Ok(path) => KvStore::open(path)?,
+                Err(_) => KvStore::in_memory(),
+            });
+            for tool in kv_tools(kv_store, SyncArc::new(KvPolicy::from_env())) {
+                tool_registry.register(tool).await;
+            }
+
+            // Large content passed between agents by reference
+            if let Some(store) = &blob_store {
+                for tool in blob_tools(SyncArc::clone(store)) {
+                    tool_registry.register(tool).await;
+                }
+                // Reports rendered to HTML/PDF artifacts in the same store
+                tool_registry
+                    .register(SyncArc::new(ReportRenderTool::new(SyncArc::clone(store))))
+                    .await;
+            }
+
+            // Artifacts agents persist in object storage and hand out links to
+            if let Some(config) = StorageConfig::from_env()? {
+                let storage = SyncArc::new(ObjectStorage::new(config)?);
+                for tool in storage_tools(storage, blob_store.clone()) {
+                    tool_registry.register(tool).await;
+                }
+            }
+
+            // Reminders agents schedule for themselves
+            for tool in schedule_tools(SyncArc::clone(&reminders), time::timezone_from_env()) {
+                tool_registry.register(tool).await;
+            }
             tool_registry
-                .register(SyncArc::new(WeatherTool::new()))
-                .await;
-            tool_registry
-                .register(SyncArc::new(WebSearchTool::new()))
+                .register(SyncArc::new(
+                    crate::a2a::A2aDelegateTool::new()
+                        .with_event_bus(std::sync::Arc::clone(&event_bus)),
+                ))
                 .await;
         }
 
@@ -218,38 +469,221 @@ impl Loom {
             );
         }
 
+        let agent_runtime = AgentRuntime::new(
+            std::sync::Arc::clone(&event_bus),
+            std::sync::Arc::clone(&tool_registry),
+            model_router.clone(),
+        )
+        .await?
+        .with_directory(std::sync::Arc::clone(&agent_directory))
+        .with_reminders(std::sync::Arc::clone(&reminders));
+        agent_runtime.set_hibernation_policy(HibernationPolicy::from_env());
+
+        let sentinel = std::sync::Arc::new(
+            SentinelAgent::new(SentinelConfig::default())
+                .with_event_bus(std::sync::Arc::clone(&event_bus))
+                .with_tool_registry(std::sync::Arc::clone(&tool_registry))
+                .with_model_router(model_router.clone()),
+        );
+
+        let mut stats_collector = StatsCollector::new(
+            std::sync::Arc::clone(&event_bus),
+            agent_runtime.clone(),
+            std::sync::Arc::clone(&tool_registry),
+            std::sync::Arc::clone(&mcp_manager),
+            std::sync::Arc::clone(&memory_governor),
+            std::sync::Arc::clone(&llm_usage),
+        );
+        if let Some(cache) = &llm_cache {
+            stats_collector = stats_collector.with_llm_cache(std::sync::Arc::clone(cache));
+        }
+        let stats_collector = std::sync::Arc::new(stats_collector);
+
+        let shutdown_registry = std::sync::Arc::new(ShutdownRegistry::new());
+        Self::register_shutdown_hooks(
+            &shutdown_registry,
+            &agent_runtime,
+            &model_router,
+            &event_bus,
+            &tool_registry,
+            &mcp_manager,
+            &agent_directory,
+            &pools,
+            &memory_governor,
+            &sentinel,
+        )?;
+        if let Some(exporter) = &usage_exporter {
+            let exporter = std::sync::Arc::clone(exporter);
+            shutdown_registry.register_fn("usage_export", &["event_bus"], move |_| {
+                exporter.stop();
+                async { Ok(()) }
+            })?;
+        }
+        if let Some(cache) = &llm_cache {
+            let cache = std::sync::Arc::clone(cache);
+            shutdown_registry.register_fn("llm_cache", &[], move |_| {
+                let result = cache.flush();
+                async { result }
+            })?;
+        }
+
         Ok(Self {
-            agent_runtime: AgentRuntime::new(
-                std::sync::Arc::clone(&event_bus),
-                std::sync::Arc::clone(&tool_registry),
-                model_router.clone(),
-            )
-            .await?,
+            agent_runtime,
             model_router,
             event_bus,
             tool_registry,
             mcp_manager,
             agent_directory,
+            pools,
+            memory_governor,
+            sentinel,
+            shutdown_registry,
+            usage_exporter,
+            llm_cache,
+            reminders,
+            llm_usage,
+            stats_collector,
+            blob_store,
         })
     }
 
+    /// Built-in components, stopped dependents-first: agents, then the tools they call,
+    /// then MCP servers and pools, with the EventBus and directory last.
+    #[allow(clippy::too_many_arguments)]
+    fn register_shutdown_hooks(
+        registry: &ShutdownRegistry,
+        agent_runtime: &AgentRuntime,
+        model_router: &ModelRouter,
+        event_bus: &std::sync::Arc<EventBus>,
+        tool_registry: &std::sync::Arc<ToolRegistry>,
+        mcp_manager: &std::sync::Arc<tools::mcp::McpManager>,
+        agent_directory: &std::sync::Arc<AgentDirectory>,
+        pools: &RuntimePools,
+        memory_governor: &std::sync::Arc<MemoryGovernor>,
+        sentinel: &std::sync::Arc<SentinelAgent>,
+    ) -> Result<()> {
+        use std::sync::Arc as SyncArc;
+
+        registry.register(
+            "agents",
+            &[
+                "tool_registry",
+                "event_bus",
+                "model_router",
+                "agent_directory",
+            ],
+            SyncArc::new(agent_runtime.clone()),
+        )?;
+
+        let tools = SyncArc::clone(tool_registry);
+        registry.register_fn("tool_registry", &["mcp", "pools"], move |deadline| {
+            let tools = SyncArc::clone(&tools);
+            async move {
+                if tools.drain(deadline).await {
+                    Ok(())
+                } else {
+                    Err(LoomError::AgentError(format!(
+                        "{} tool call(s) still in flight at shutdown deadline",
+                        tools.inflight_calls()
+                    )))
+                }
+            }
+        })?;
+
+        let governor = SyncArc::clone(memory_governor);
+        registry.register_fn("memory_governor", &["event_bus"], move |_| {
+            governor.stop();
+            async { Ok(()) }
+        })?;
+
+        let sentinel = SyncArc::clone(sentinel);
+        registry.register_fn(
+            "sentinel",
+            &["event_bus", "tool_registry", "model_router"],
+            move |_| {
+                sentinel.stop();
+                async { Ok(()) }
+            },
+        )?;
+
+        let mcp = SyncArc::clone(mcp_manager);
+        registry.register_fn("mcp", &[], move |_| {
+            let mcp = SyncArc::clone(&mcp);
+            async move {
+                mcp.shutdown().await;
+                Ok(())
+            }
+        })?;
+
+        let router = model_router.clone();
+        registry.register_fn("model_router", &[], move |_| {
+            let mut router = router.clone();
+            async move { router.shutdown().await }
+        })?;
+
+        let bus = SyncArc::clone(event_bus);
+        registry.register_fn("event_bus", &[], move |_| {
+            let bus = SyncArc::clone(&bus);
+            async move { bus.shutdown().await }
+        })?;
+
+        let directory = SyncArc::clone(agent_directory);
+        registry.register_fn("agent_directory", &[], move |_| {
+            let result = directory.flush();
+            async { result }
+        })?;
+
+        let pools = pools.clone();
+        registry.register_fn("pools", &[], move |_| {
+            pools.shutdown();
+            async { Ok(()) }
+        })?;
+
+        Ok(())
+    }
+
     pub async fn start(&mut self) -> Result<()> {
         tracing::info!("Starting Loom...");
         self.event_bus.start().await?;
         self.agent_runtime.start().await?;
         self.model_router.start().await?;
+        self.memory_governor.start();
+        if self.sentinel.config().enabled {
+            self.sentinel.start();
+        }
+        if let Some(exporter) = &self.usage_exporter {
+            exporter.start();
+        }
         tracing::info!("Loom started successfully");
         Ok(())
     }
 
-    pub async fn shutdown(&mut self) -> Result<()> {
+    /// Snapshot of every subsystem: EventBus counters, agent queue depths, tool stats,
+    /// LLM usage, MCP server states, memory usage and registered sources (the Bridge)
+    pub async fn stats(&self) -> LoomStats {
+        self.stats_collector.snapshot().await
+    }
+
+    /// Stop all registered components in dependency order.
+    ///
+    /// The overall deadline comes from `LOOM_SHUTDOWN_TIMEOUT_MS` (default 10000).
+    /// Components that fail or miss the deadline are reported, not returned as errors.
+    pub async fn shutdown(&mut self) -> Result<ShutdownReport> {
         tracing::info!("Shutting down Loom...");
-        self.mcp_manager.shutdown().await;
-        self.model_router.shutdown().await?;
-        self.agent_runtime.shutdown().await?;
-        self.event_bus.shutdown().await?;
+        let timeout_ms = std::env::var("LOOM_SHUTDOWN_TIMEOUT_MS")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(10_000);
+        let report = self
+            .shutdown_registry
+            .shutdown(std::time::Duration::from_millis(timeout_ms))
+            .await;
         telemetry::shutdown_telemetry();
-        tracing::info!("Loom shut down successfully");
-        Ok(())
+        if report.is_clean() {
+            tracing::info!("Loom shut down successfully");
+        } else {
+            tracing::warn!(report = ?report, "Loom shut down with errors");
+        }
+        Ok(report)
     }
 }
@@ -0,0 +1,419 @@
+//! Result aggregation on top of `Collaborator::fanout_fanin`.
+//!
+//! Three common ways of turning several replies into one answer:
+//!
+//! - **Majority vote** (`majority_vote` / `Collaborator::fanout_vote`): replies are
+//!   reduced to a vote (normalized payload text by default) and the most common vote
+//!   wins if it has the quorum.
+//! - **Best-of** (`best_of` / `Collaborator::fanout_best_of`): a `ReplyJudge` (e.g.
+//!   `LlmJudge`) picks the best reply.
+//! - **Merge-and-summarize** (`merge_and_summarize` / `Collaborator::fanout_summarize`):
+//!   all replies are condensed into one text by a `Summarizer`.
+//!
+//! Every combinator returns an `Aggregate`: the combined result together with the
+//! individual replies and where each came from.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use super::collab::{bidder_of, Collaborator};
+use crate::cognitive::llm::LlmClient;
+use crate::context::pipeline::Summarizer;
+use crate::context::{PromptBundle, TokenBudget};
+use crate::{Event, LoomError, Result};
+
+/// A fanout reply and where it came from
+#[derive(Debug, Clone)]
+pub struct AttributedReply {
+    /// Replying agent: the event source, else its envelope sender
+    pub source: String,
+    /// Reply id
+    pub event_id: String,
+    /// Time from the start of the fanout to the reply's timestamp
+    pub latency_ms: u64,
+    pub event: Event,
+}
+
+impl AttributedReply {
+    fn new(event: Event, started_ms: i64) -> Self {
+        Self {
+            source: bidder_of(&event).to_string(),
+            event_id: event.id.clone(),
+            latency_ms: event.timestamp_ms.saturating_sub(started_ms).max(0) as u64,
+            event,
+        }
+    }
+
+    /// Reply payload as (lossy) UTF-8
+    pub fn text(&self) -> String {
+        String::from_utf8_lossy(&self.event.payload).into_owned()
+    }
+}
+
+/// Attribute replies collected by a fanout started at `started_ms`
+pub fn attribute(replies: Vec<Event>, started_ms: i64) -> Vec<AttributedReply> {
+    replies
+        .into_iter()
+        .map(|event| AttributedReply::new(event, started_ms))
+        .collect()
+}
+
+/// A combined result together with the replies it was computed from, in arrival order
+#[derive(Debug, Clone)]
+pub struct Aggregate<T> {
+    pub result: T,
+    pub replies: Vec<AttributedReply>,
+}
+
+/// Turns a reply into a vote; `None` abstains
+pub type VoteKey = Arc<dyn Fn(&Event) -> Option<String> + Send + Sync>;
+
+/// Settings of `majority_vote`
+#[derive(Clone)]
+pub struct VoteConfig {
+    /// Default: the payload text, trimmed and lowercased; empty payloads abstain
+    pub key: VoteKey,
+    /// Share of the votes cast the winner needs (0.0-1.0, default 0.5). The winner must
+    /// also have strictly more votes than any other value.
+    pub quorum: f64,
+}
+
+impl Default for VoteConfig {
+    fn default() -> Self {
+        Self {
+            key: Arc::new(|ev: &Event| {
+                let text = String::from_utf8_lossy(&ev.payload).trim().to_lowercase();
+                (!text.is_empty()).then_some(text)
+            }),
+            quorum: 0.5,
+        }
+    }
+}
+
+impl VoteConfig {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_key(
+        mut self,
+        key: impl Fn(&Event) -> Option<String> + Send + Sync + 'static,
+    ) -> Self {
+        self.key = Arc::new(key);
+        self
+    }
+
+    /// Vote with the value of a reply metadata field
+    pub fn with_metadata_key(self, field: impl Into<String>) -> Self {
+        let field = field.into();
+        self.with_key(move |ev| ev.metadata.get(&field).map(|v| v.trim().to_string()))
+    }
+
+    pub fn with_quorum(mut self, quorum: f64) -> Self {
+        self.quorum = quorum.clamp(0.0, 1.0);
+        self
+    }
+}
+
+/// Votes for one value
+#[derive(Debug, Clone, PartialEq)]
+pub struct VoteCount {
+    pub value: String,
+    /// Sources that voted for the value, in arrival order
+    pub voters: Vec<String>,
+}
+
+/// Result of `majority_vote`
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct VoteOutcome {
+    /// Value with the quorum, if any
+    pub winner: Option<String>,
+    /// Replies that cast a vote
+    pub votes_cast: usize,
+    /// Replies whose key was `None`
+    pub abstained: usize,
+    /// All values, most votes first; ties keep the order of each value's first vote
+    pub tally: Vec<VoteCount>,
+}
+
+impl VoteOutcome {
+    /// Votes for the winner (0 without one)
+    pub fn winner_votes(&self) -> usize {
+        match self.winner {
+            Some(_) => self.tally[0].voters.len(),
+            None => 0,
+        }
+    }
+}
+
+/// Tally `replies` under `config`
+pub fn majority_vote(replies: &[AttributedReply], config: &VoteConfig) -> VoteOutcome {
+    let mut tally: Vec<VoteCount> = Vec::new();
+    let mut index: HashMap<String, usize> = HashMap::new();
+    let mut abstained = 0;
+    for reply in replies {
+        let Some(value) = (config.key)(&reply.event) else {
+            abstained += 1;
+            continue;
+        };
+        let slot = *index.entry(value.clone()).or_insert_with(|| {
+            tally.push(VoteCount {
+                value,
+                voters: Vec::new(),
+            });
+            tally.len() - 1
+        });
+        tally[slot].voters.push(reply.source.clone());
+    }
+    // Stable: equal counts keep first-vote order
+    tally.sort_by(|a, b| b.voters.len().cmp(&a.voters.len()));
+
+    let votes_cast = replies.len() - abstained;
+    let winner = match tally.as_slice() {
+        [first, rest @ ..] => {
+            let unique = match rest.first() {
+                Some(second) => first.voters.len() > second.voters.len(),
+                None => true,
+            };
+            let has_quorum = first.voters.len() as f64 >= config.quorum * votes_cast as f64;
+            (unique && has_quorum).then(|| first.value.clone())
+        }
+        [] => None,
+    };
+    VoteOutcome {
+        winner,
+        votes_cast,
+        abstained,
+        tally,
+    }
+}
+
+/// A judge's pick among candidate replies
+#[derive(Debug, Clone, PartialEq)]
+pub struct Judgement {
+    /// Index of the best reply in the candidates (and in `Aggregate::replies`)
+    pub index: usize,
+    /// Source of the best reply
+    pub source: String,
+    pub reason: String,
+}
+
+/// Picks the best of several replies to a request
+#[async_trait]
+pub trait ReplyJudge: Send + Sync {
+    /// Index into `candidates` (never empty) of the best reply to `request`, and why
+    async fn judge(
+        &self,
+        request: &[u8],
+        candidates: &[AttributedReply],
+    ) -> Result<(usize, String)>;
+}
+
+/// Judge backed by `LlmClient::generate`: the model sees the request and the numbered
+/// candidates and answers with the number of the best one
+pub struct LlmJudge {
+    llm: Arc<LlmClient>,
+    criteria: String,
+}
+
+impl LlmJudge {
+    pub fn new(llm: Arc<LlmClient>) -> Self {
+        Self {
+            llm,
+            criteria: "correctness, completeness and clarity".to_string(),
+        }
+    }
+
+    /// What makes a reply better, e.g. "factual accuracy"
+    pub fn with_criteria(mut self, criteria: impl Into<String>) -> Self {
+        self.criteria = criteria.into();
+        self
+    }
+}
+
+#[async_trait]
+impl ReplyJudge for LlmJudge {
+    async fn judge(
+        &self,
+        request: &[u8],
+        candidates: &[AttributedReply],
+    ) -> Result<(usize, String)> {
+        let bundle = PromptBundle {
+            system: format!(
+                "You judge candidate answers to a request written by different agents. \
+                 Prefer {}.",
+                self.criteria
+            ),
+            instructions: format!(
+                "Request:\n{}\n\nAnswer with the number of the best candidate on the first \
+                 line, then one sentence explaining the choice.",
+                String::from_utf8_lossy(request)
+            ),
+            tools_json_schema: None,
+            context_docs: candidates
+                .iter()
+                .enumerate()
+                .map(|(i, c)| format!("Candidate {} (from {}):\n{}", i + 1, c.source, c.text()))
+                .collect(),
+            history: vec![],
+        };
+        let budget = TokenBudget {
+            max_output_tokens: 128,
+            ..TokenBudget::default()
+        };
+        let response = self.llm.generate(&bundle, Some(budget)).await?;
+        parse_verdict(&response.text, candidates.len())
+    }
+}
+
+/// 0-based index and reason from a verdict whose first number is a 1-based candidate
+fn parse_verdict(text: &str, candidates: usize) -> Result<(usize, String)> {
+    let digits: String = text
+        .chars()
+        .skip_while(|c| !c.is_ascii_digit())
+        .take_while(|c| c.is_ascii_digit())
+        .collect();
+    let number: usize = digits
+        .parse()
+        .map_err(|_| LoomError::AgentError(format!("judge verdict names no candidate: {text}")))?;
+    if number == 0 || number > candidates {
+        return Err(LoomError::AgentError(format!(
+            "judge picked candidate {number} of {candidates}"
+        )));
+    }
+    let reason = text
+        .lines()
+        .skip(1)
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ");
+    Ok((number - 1, reason))
+}
+
+/// The judge's pick among `replies`; `None` without replies
+pub async fn best_of(
+    request: &[u8],
+    replies: &[AttributedReply],
+    judge: &dyn ReplyJudge,
+) -> Result<Option<Judgement>> {
+    if replies.is_empty() {
+        return Ok(None);
+    }
+    let (index, reason) = judge.judge(request, replies).await?;
+    let reply = replies.get(index).ok_or_else(|| {
+        LoomError::AgentError(format!("judge picked reply {index} of {}", replies.len()))
+    })?;
+    Ok(Some(Judgement {
+        index,
+        source: reply.source.clone(),
+        reason,
+    }))
+}
+
+/// One text condensing all `replies`, each introduced by its source; `None` without
+/// replies
+pub async fn merge_and_summarize(
+    replies: &[AttributedReply],
+    summarizer: &dyn Summarizer,
+    max_tokens: usize,
+) -> Result<Option<String>> {
+    if replies.is_empty() {
+        return Ok(None);
+    }
+    let texts: Vec<String> = replies
+        .iter()
+        .map(|r| format!("[{}] {}", r.source, r.text()))
+        .collect();
+    summarizer.summarize(&texts, max_tokens).await.map(Some)
+}
+
+impl Collaborator {
+    /// `fanout_fanin` with attributed replies
+    pub async fn fanout_attributed(
+        &self,
+        topics: &[String],
+        payload: Vec<u8>,
+        first_k: usize,
+        timeout_ms: u64,
+    ) -> Result<Vec<AttributedReply>> {
+        let started_ms = chrono::Utc::now().timestamp_millis();
+        let replies = self
+            .fanout_fanin(topics, payload, first_k, timeout_ms)
+            .await?;
+        Ok(attribute(replies, started_ms))
+    }
+
+    /// Fan out and take a `majority_vote` over the replies
+    pub async fn fanout_vote(
+        &self,
+        topics: &[String],
+        payload: Vec<u8>,
+        first_k: usize,
+        timeout_ms: u64,
+        config: &VoteConfig,
+    ) -> Result<Aggregate<VoteOutcome>> {
+        let replies = self
+            .fanout_attributed(topics, payload, first_k, timeout_ms)
+            .await?;
+        Ok(Aggregate {
+            result: majority_vote(&replies, config),
+            replies,
+        })
+    }
+
+    /// Fan out and let `judge` pick the best reply (`best_of`)
+    pub async fn fanout_best_of(
+        &self,
+        topics: &[String],
+        payload: Vec<u8>,
+        first_k: usize,
+        timeout_ms: u64,
+        judge: &dyn ReplyJudge,
+    ) -> Result<Aggregate<Option<Judgement>>> {
+        let replies = self
+            .fanout_attributed(topics, payload.clone(), first_k, timeout_ms)
+            .await?;
+        Ok(Aggregate {
+            result: best_of(&payload, &replies, judge).await?,
+            replies,
+        })
+    }
+
+    /// Fan out and condense the replies into one text (`merge_and_summarize`)
+    pub async fn fanout_summarize(
+        &self,
+        topics: &[String],
+        payload: Vec<u8>,
+        first_k: usize,
+        timeout_ms: u64,
+        summarizer: &dyn Summarizer,
+        max_tokens: usize,
+    ) -> Result<Aggregate<Option<String>>> {
+        let replies = self
+            .fanout_attributed(topics, payload, first_k, timeout_ms)
+            .await?;
+        Ok(Aggregate {
+            result: merge_and_summarize(&replies, summarizer, max_tokens).await?,
+            replies,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn verdict_parsing() {
+        assert_eq!(
+            parse_verdict("2\nIt cites sources.", 3).unwrap(),
+            (1, "It cites sources.".to_string())
+        );
+        assert_eq!(parse_verdict("Candidate 1 is best", 2).unwrap().0, 0);
+        assert!(parse_verdict("none of them", 2).is_err());
+        assert!(parse_verdict("4", 3).is_err());
+    }
+}
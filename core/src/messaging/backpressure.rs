@@ -0,0 +1,305 @@
+//! Per-subscription backpressure policies.
+//!
+//! A policy decides what happens when a subscriber's queue is full:
+//! - `DropNewest`: the incoming event is dropped (the publisher never waits)
+//! - `DropOldest`: the oldest queued event is dropped to make room
+//! - `Block`: the publisher waits for queue capacity
+//! - `SpillToDisk`: overflow is appended to a file and read back, in order, once the
+//!   subscriber catches up
+//!
+//! `DropNewest` and `Block` act on the subscription's channel directly. `DropOldest` and
+//! `SpillToDisk` put a `PolicyQueue` in front of it, drained into the channel by a
+//! forwarding task.
+
+use std::collections::VecDeque;
+use std::fmt;
+use std::fs::{self, File, OpenOptions};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use prost::Message;
+use serde::{Deserialize, Serialize};
+use tokio::sync::{mpsc, Notify};
+use tracing::warn;
+
+use crate::proto::{Event, QoSLevel};
+
+/// `AgentConfig.parameters` key naming the policy of an agent's subscriptions
+pub const BACKPRESSURE_PARAM: &str = "event_bus.backpressure";
+
+/// What a full subscription queue does with new events
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BackpressurePolicy {
+    /// Keep the newest events; the oldest queued event is dropped
+    DropOldest,
+    /// Keep the queued events; the incoming event is dropped
+    DropNewest,
+    /// The publisher waits for capacity
+    Block,
+    /// Overflow goes to disk and is delivered once the queue drains
+    SpillToDisk,
+}
+
+impl BackpressurePolicy {
+    /// Policy a subscription of `qos` gets unless it asks for another: realtime drops
+    /// new events, every other level blocks
+    pub fn default_for(qos: QoSLevel) -> Self {
+        match qos {
+            QoSLevel::QosRealtime => Self::DropNewest,
+            QoSLevel::QosBatched
+            | QoSLevel::QosBackground
+            | QoSLevel::QosReliable
+            | QoSLevel::QosGuaranteed => Self::Block,
+        }
+    }
+
+    /// Parse a policy name (`drop_oldest`, `drop_newest`, `block`, `spill_to_disk`;
+    /// dashes and `spill` are accepted too)
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.trim().to_ascii_lowercase().replace('-', "_").as_str() {
+            "drop_oldest" => Some(Self::DropOldest),
+            "drop_newest" | "drop" => Some(Self::DropNewest),
+            "block" => Some(Self::Block),
+            "spill_to_disk" | "spill" => Some(Self::SpillToDisk),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::DropOldest => "drop_oldest",
+            Self::DropNewest => "drop_newest",
+            Self::Block => "block",
+            Self::SpillToDisk => "spill_to_disk",
+        }
+    }
+
+    /// Whether the policy needs a `PolicyQueue` in front of the subscription channel
+    pub(crate) fn is_queued(&self) -> bool {
+        matches!(self, Self::DropOldest | Self::SpillToDisk)
+    }
+}
+
+impl fmt::Display for BackpressurePolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Directory spill files go to: `LOOM_EVENT_SPILL_DIR`, else `loom-event-spill` under the
+/// system temp directory
+pub fn spill_dir_from_env() -> PathBuf {
+    match std::env::var("LOOM_EVENT_SPILL_DIR") {
+        Ok(dir) if !dir.trim().is_empty() => PathBuf::from(dir),
+        _ => std::env::temp_dir().join("loom-event-spill"),
+    }
+}
+
+/// What `PolicyQueue::push` did with an event
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum PushOutcome {
+    Queued,
+    /// Queued after dropping the oldest event
+    DroppedOldest,
+    /// Written to the spill file
+    Spilled,
+    /// Not queued: the queue is closed, or spilling failed
+    Rejected,
+}
+
+/// Bounded queue in front of a `DropOldest` or `SpillToDisk` subscription
+#[derive(Debug)]
+pub(crate) struct PolicyQueue {
+    policy: BackpressurePolicy,
+    capacity: usize,
+    spill_path: PathBuf,
+    state: Mutex<QueueState>,
+    notify: Notify,
+}
+
+#[derive(Debug, Default)]
+struct QueueState {
+    events: VecDeque<Event>,
+    spill: Option<SpillFile>,
+    closed: bool,
+}
+
+impl PolicyQueue {
+    /// Queue of `capacity` events; `SpillToDisk` overflow goes to `spill_path`
+    pub(crate) fn new(policy: BackpressurePolicy, capacity: usize, spill_path: PathBuf) -> Self {
+        Self {
+            policy,
+            capacity: capacity.max(1),
+            spill_path,
+            state: Mutex::new(QueueState::default()),
+            notify: Notify::new(),
+        }
+    }
+
+    pub(crate) fn push(&self, event: Event) -> PushOutcome {
+        let outcome = {
+            let mut state = self.state.lock().unwrap();
+            if state.closed {
+                return PushOutcome::Rejected;
+            }
+            let spilling = matches!(state.spill, Some(ref spill) if spill.pending > 0);
+            if !spilling && state.events.len() < self.capacity {
+                state.events.push_back(event);
+                PushOutcome::Queued
+            } else if self.policy == BackpressurePolicy::SpillToDisk {
+                // Once spilling, new events follow the spilled ones so order is kept
+                match self.spill(&mut state, &event) {
+                    Ok(()) => PushOutcome::Spilled,
+                    Err(e) => {
+                        warn!(path = %self.spill_path.display(), error = %e, "Failed to spill event");
+                        return PushOutcome::Rejected;
+                    }
+                }
+            } else {
+                state.events.pop_front();
+                state.events.push_back(event);
+                PushOutcome::DroppedOldest
+            }
+        };
+        self.notify.notify_one();
+        outcome
+    }
+
+    fn spill(&self, state: &mut QueueState, event: &Event) -> std::io::Result<()> {
+        if state.spill.is_none() {
+            state.spill = Some(SpillFile::create(&self.spill_path)?);
+        }
+        if let Some(ref mut spill) = state.spill {
+            spill.append(event)?;
+        }
+        Ok(())
+    }
+
+    /// Next event, oldest first; `None` once the queue is closed and drained
+    pub(crate) async fn next(&self) -> Option<Event> {
+        loop {
+            let notified = self.notify.notified();
+            {
+                let mut state = self.state.lock().unwrap();
+                if let Some(event) = state.events.pop_front() {
+                    return Some(event);
+                }
+                if let Some(ref mut spill) = state.spill {
+                    match spill.read_next() {
+                        Ok(Some(event)) => return Some(event),
+                        Ok(None) => {}
+                        Err(e) => {
+                            warn!(path = %self.spill_path.display(), error = %e, "Failed to read spilled events; discarding them");
+                            spill.reset();
+                        }
+                    }
+                }
+                if state.closed {
+                    return None;
+                }
+            }
+            notified.await;
+        }
+    }
+
+    /// Events queued in memory and on disk
+    pub(crate) fn len(&self) -> usize {
+        let state = self.state.lock().unwrap();
+        let spilled = match state.spill {
+            Some(ref spill) => spill.pending,
+            None => 0,
+        };
+        state.events.len() + spilled as usize
+    }
+
+    /// Events queued in memory
+    pub(crate) fn memory_len(&self) -> usize {
+        self.state.lock().unwrap().events.len()
+    }
+
+    /// Stop accepting events; the forwarder delivers what is queued, then ends
+    pub(crate) fn close(&self) {
+        self.state.lock().unwrap().closed = true;
+        self.notify.notify_one();
+    }
+
+    /// Forward queued events into `sender` until the queue closes or the receiver is
+    /// dropped, then remove the spill file
+    pub(crate) async fn forward(self: Arc<Self>, sender: mpsc::Sender<Event>) {
+        while let Some(event) = self.next().await {
+            if sender.send(event).await.is_err() {
+                break;
+            }
+        }
+        let mut state = self.state.lock().unwrap();
+        state.closed = true;
+        state.events.clear();
+        if state.spill.take().is_some() {
+            let _ = fs::remove_file(&self.spill_path);
+        }
+    }
+}
+
+/// Length-prefixed, prost-encoded events appended to one file and read back in order.
+/// The file is truncated whenever every spilled event has been read.
+#[derive(Debug)]
+struct SpillFile {
+    file: File,
+    read_pos: u64,
+    pending: u64,
+}
+
+impl SpillFile {
+    fn create(path: &Path) -> std::io::Result<Self> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(path)?;
+        Ok(Self {
+            file,
+            read_pos: 0,
+            pending: 0,
+        })
+    }
+
+    fn append(&mut self, event: &Event) -> std::io::Result<()> {
+        let bytes = event.encode_to_vec();
+        self.file.seek(SeekFrom::End(0))?;
+        self.file.write_all(&(bytes.len() as u32).to_be_bytes())?;
+        self.file.write_all(&bytes)?;
+        self.pending += 1;
+        Ok(())
+    }
+
+    fn read_next(&mut self) -> std::io::Result<Option<Event>> {
+        if self.pending == 0 {
+            return Ok(None);
+        }
+        self.file.seek(SeekFrom::Start(self.read_pos))?;
+        let mut len = [0u8; 4];
+        self.file.read_exact(&mut len)?;
+        let mut bytes = vec![0u8; u32::from_be_bytes(len) as usize];
+        self.file.read_exact(&mut bytes)?;
+        let event = Event::decode(bytes.as_slice())
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+        self.read_pos += 4 + bytes.len() as u64;
+        self.pending -= 1;
+        if self.pending == 0 {
+            self.reset();
+        }
+        Ok(Some(event))
+    }
+
+    fn reset(&mut self) {
+        let _ = self.file.set_len(0);
+        self.read_pos = 0;
+        self.pending = 0;
+    }
+}
@@ -1,29 +1,452 @@
-use std::{collections::HashMap, sync::Arc};
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::Arc,
+};
 
+use dashmap::DashMap;
+use tokio::sync::mpsc;
 use tokio::time::{timeout, Duration, Instant};
+use tracing::debug;
 
 use crate::{
-    messaging::envelope::keys, messaging::ThreadTopicKind, Envelope, Event, EventBus, Result,
+    agent::directory::{AgentDirectory, AgentStatus},
+    messaging::envelope::keys,
+    messaging::ThreadTopicKind,
+    Envelope, Event, EventBus, Result,
 };
 
-/// Control event type names used on Event.r#type for collaboration protocols
-pub mod types {
-    /// Request event in request-reply or fanout-fanin patterns
-    pub const REQ: &str = "collab.request";
-    /// Reply event in request-reply or fanout-fanin patterns
-    pub const REPLY: &str = "collab.reply";
-    /// Call for proposals in contract-net protocol
-    pub const CFP: &str = "collab.cfp";
-    /// Proposal response in contract-net protocol
-    pub const PROPOSAL: &str = "collab.proposal";
-    /// Award announcement in contract-net protocol
-    pub const AWARD: &str = "collab.award";
-    /// Optional heartbeat for barrier synchronization
-    pub const BARRIER_TICK: &str = "collab.barrier";
-    /// Timeout notification when collaboration fails to complete
-    pub const TIMEOUT: &str = "collab.timeout";
-    /// Summary event with collaboration results and statistics
-    pub const SUMMARY: &str = "collab.summary";
+/// Event types, request metadata and bid terms of the collaboration protocols, shared
+/// with the SDKs through `loom_proto::conventions`.
+pub use crate::proto::conventions::collab::{meta, proposal, types};
+
+/// Retry behaviour of `request_reply` and `fanout_fanin`.
+///
+/// A retry republishes the request with the same correlation and idempotency key, so a
+/// late reply to an earlier attempt still completes the collaboration. Each retry is a
+/// forwarding hop: it consumes the envelope's TTL, and retrying stops once the TTL is
+/// spent even if `max_retries` allows more.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CollabRetryPolicy {
+    /// Retries after the first attempt (0 = fail after one timeout)
+    pub max_retries: u32,
+    /// Delay before the first retry
+    pub initial_backoff: Duration,
+    /// Upper bound for the retry delay
+    pub max_backoff: Duration,
+    /// Factor applied to the delay after each retry
+    pub backoff_multiplier: f64,
+    /// Capability whose providers (in the collaborator's `AgentDirectory`) are tried as
+    /// alternate targets on retries; `None` retries the original targets only
+    pub alternate_capability: Option<String>,
+}
+
+impl Default for CollabRetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 0,
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_secs(5),
+            backoff_multiplier: 2.0,
+            alternate_capability: None,
+        }
+    }
+}
+
+impl CollabRetryPolicy {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_retries(mut self, max_retries: u32) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+
+    pub fn with_backoff(mut self, initial: Duration, max: Duration, multiplier: f64) -> Self {
+        self.initial_backoff = initial;
+        self.max_backoff = max;
+        self.backoff_multiplier = multiplier;
+        self
+    }
+
+    /// Retry with other agents that provide `capability`
+    pub fn with_alternates(mut self, capability: impl Into<String>) -> Self {
+        self.alternate_capability = Some(capability.into());
+        self
+    }
+
+    /// Delay before retry number `retry` (0-based), capped at `max_backoff`
+    pub fn backoff_for(&self, retry: u32) -> Duration {
+        let factor = self.backoff_multiplier.max(1.0).powi(retry as i32);
+        let delay = self.initial_backoff.as_secs_f64() * factor;
+        Duration::from_secs_f64(delay.min(self.max_backoff.as_secs_f64()))
+    }
+}
+
+/// Replies remembered by idempotency key, for responders that may see a request again.
+///
+/// ```no_run
+/// # use loom_core::messaging::collab::IdempotencyCache;
+/// # fn handle(req: &loom_core::Event, cache: &IdempotencyCache) -> Vec<u8> {
+/// if let Some(reply) = cache.get(req) {
+///     return reply; // a retry of a request we already answered
+/// }
+/// let reply = b"result".to_vec();
+/// cache.insert(req, reply.clone());
+/// reply
+/// # }
+/// ```
+pub struct IdempotencyCache {
+    replies: DashMap<String, (Instant, Vec<u8>)>,
+    ttl: Duration,
+}
+
+impl IdempotencyCache {
+    /// Remember replies for `ttl`
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            replies: DashMap::new(),
+            ttl,
+        }
+    }
+
+    /// Reply recorded for `request`'s idempotency key, if still fresh
+    pub fn get(&self, request: &Event) -> Option<Vec<u8>> {
+        let key = request.metadata.get(meta::IDEMPOTENCY_KEY)?;
+        let entry = self.replies.get(key)?;
+        (entry.0.elapsed() < self.ttl).then(|| entry.1.clone())
+    }
+
+    /// Record the reply to `request`; requests without an idempotency key are ignored
+    pub fn insert(&self, request: &Event, reply: Vec<u8>) {
+        let Some(key) = request.metadata.get(meta::IDEMPOTENCY_KEY) else {
+            return;
+        };
+        let ttl = self.ttl;
+        self.replies
+            .retain(|_, (recorded, _)| recorded.elapsed() < ttl);
+        self.replies.insert(key.clone(), (Instant::now(), reply));
+    }
+
+    pub fn len(&self) -> usize {
+        self.replies.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.replies.is_empty()
+    }
+}
+
+/// Terms of a contract-net bid, read from and written to proposal metadata.
+///
+/// Values that are missing or do not parse are `None`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BidTerms {
+    pub score: Option<f64>,
+    pub cost: Option<f64>,
+    pub capacity: Option<u32>,
+    pub eta_ms: Option<u64>,
+}
+
+impl BidTerms {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_score(mut self, score: f64) -> Self {
+        self.score = Some(score);
+        self
+    }
+
+    pub fn with_cost(mut self, cost: f64) -> Self {
+        self.cost = Some(cost);
+        self
+    }
+
+    pub fn with_capacity(mut self, capacity: u32) -> Self {
+        self.capacity = Some(capacity);
+        self
+    }
+
+    pub fn with_eta_ms(mut self, eta_ms: u64) -> Self {
+        self.eta_ms = Some(eta_ms);
+        self
+    }
+
+    /// Terms of the proposal `ev`
+    pub fn from_event(ev: &Event) -> Self {
+        let float = |key: &str| {
+            ev.metadata
+                .get(key)
+                .and_then(|v| v.trim().parse::<f64>().ok())
+                .filter(|v| v.is_finite())
+        };
+        Self {
+            score: float(proposal::SCORE),
+            cost: float(proposal::COST),
+            capacity: ev
+                .metadata
+                .get(proposal::CAPACITY)
+                .and_then(|v| v.trim().parse().ok()),
+            eta_ms: ev
+                .metadata
+                .get(proposal::ETA_MS)
+                .and_then(|v| v.trim().parse().ok()),
+        }
+    }
+
+    /// Write the terms that are set into proposal metadata
+    pub fn apply_to_metadata(&self, md: &mut HashMap<String, String>) {
+        if let Some(score) = self.score {
+            md.insert(proposal::SCORE.into(), score.to_string());
+        }
+        if let Some(cost) = self.cost {
+            md.insert(proposal::COST.into(), cost.to_string());
+        }
+        if let Some(capacity) = self.capacity {
+            md.insert(proposal::CAPACITY.into(), capacity.to_string());
+        }
+        if let Some(eta_ms) = self.eta_ms {
+            md.insert(proposal::ETA_MS.into(), eta_ms.to_string());
+        }
+    }
+}
+
+/// Limits a contract-net coordinator puts on bids.
+///
+/// The limits are announced in the CFP (`cfp.*` metadata) so bidders can sit out a task
+/// they cannot take; proposals that break them, or leave a constrained term out, are
+/// not awarded.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BidConstraints {
+    pub max_cost: Option<f64>,
+    pub min_capacity: Option<u32>,
+    pub max_eta_ms: Option<u64>,
+}
+
+impl BidConstraints {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_max_cost(mut self, max_cost: f64) -> Self {
+        self.max_cost = Some(max_cost);
+        self
+    }
+
+    pub fn with_min_capacity(mut self, min_capacity: u32) -> Self {
+        self.min_capacity = Some(min_capacity);
+        self
+    }
+
+    pub fn with_max_eta_ms(mut self, max_eta_ms: u64) -> Self {
+        self.max_eta_ms = Some(max_eta_ms);
+        self
+    }
+
+    /// Constraints announced in the CFP `ev`
+    pub fn from_event(ev: &Event) -> Self {
+        let get = |key: &str| ev.metadata.get(key).map(|v| v.trim());
+        Self {
+            max_cost: get(meta::CFP_MAX_COST).and_then(|v| v.parse().ok()),
+            min_capacity: get(meta::CFP_MIN_CAPACITY).and_then(|v| v.parse().ok()),
+            max_eta_ms: get(meta::CFP_MAX_ETA_MS).and_then(|v| v.parse().ok()),
+        }
+    }
+
+    /// Write the constraints that are set into CFP metadata
+    pub fn apply_to_metadata(&self, md: &mut HashMap<String, String>) {
+        if let Some(max_cost) = self.max_cost {
+            md.insert(meta::CFP_MAX_COST.into(), max_cost.to_string());
+        }
+        if let Some(min_capacity) = self.min_capacity {
+            md.insert(meta::CFP_MIN_CAPACITY.into(), min_capacity.to_string());
+        }
+        if let Some(max_eta_ms) = self.max_eta_ms {
+            md.insert(meta::CFP_MAX_ETA_MS.into(), max_eta_ms.to_string());
+        }
+    }
+
+    /// Whether a bid with `terms` meets every constraint
+    pub fn admits(&self, terms: &BidTerms) -> bool {
+        let cost_ok = match (self.max_cost, terms.cost) {
+            (Some(max), Some(cost)) => cost <= max,
+            (Some(_), None) => false,
+            (None, _) => true,
+        };
+        let capacity_ok = match (self.min_capacity, terms.capacity) {
+            (Some(min), Some(capacity)) => capacity >= min,
+            (Some(_), None) => false,
+            (None, _) => true,
+        };
+        let eta_ok = match (self.max_eta_ms, terms.eta_ms) {
+            (Some(max), Some(eta)) => eta <= max,
+            (Some(_), None) => false,
+            (None, _) => true,
+        };
+        cost_ok && capacity_ok && eta_ok
+    }
+}
+
+/// Ranks contract-net proposals: bids with a higher value are awarded first.
+///
+/// Closures `Fn(&BidTerms, &Event) -> f64` implement this trait. A NaN value ranks last.
+pub trait BidScorer: Send + Sync {
+    fn score(&self, terms: &BidTerms, proposal: &Event) -> f64;
+}
+
+impl<F> BidScorer for F
+where
+    F: Fn(&BidTerms, &Event) -> f64 + Send + Sync,
+{
+    fn score(&self, terms: &BidTerms, proposal: &Event) -> f64 {
+        self(terms, proposal)
+    }
+}
+
+/// The bidder's own `score` (missing scores count as 0.0); the default scorer
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ByScore;
+
+impl BidScorer for ByScore {
+    fn score(&self, terms: &BidTerms, _proposal: &Event) -> f64 {
+        terms.score.unwrap_or(0.0)
+    }
+}
+
+/// Cheapest bid first; bids without a `cost` rank last
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LowestCost;
+
+impl BidScorer for LowestCost {
+    fn score(&self, terms: &BidTerms, _proposal: &Event) -> f64 {
+        terms.cost.map(|cost| -cost).unwrap_or(f64::NEG_INFINITY)
+    }
+}
+
+/// Weighted sum `score * score_weight - cost * cost_weight - eta_ms * eta_weight`;
+/// missing terms count as 0.0
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WeightedScore {
+    pub score_weight: f64,
+    pub cost_weight: f64,
+    pub eta_weight: f64,
+}
+
+impl BidScorer for WeightedScore {
+    fn score(&self, terms: &BidTerms, _proposal: &Event) -> f64 {
+        terms.score.unwrap_or(0.0) * self.score_weight
+            - terms.cost.unwrap_or(0.0) * self.cost_weight
+            - terms.eta_ms.unwrap_or(0) as f64 * self.eta_weight
+    }
+}
+
+/// Checks contract-net proposals before they are ranked; an `Err` carries the reason
+/// sent back to the bidder in its `collab.reject`.
+///
+/// Closures `Fn(&BidTerms, &Event) -> Result<(), String>` implement this trait.
+pub trait ProposalValidator: Send + Sync {
+    fn validate(&self, terms: &BidTerms, proposal: &Event) -> std::result::Result<(), String>;
+}
+
+impl<F> ProposalValidator for F
+where
+    F: Fn(&BidTerms, &Event) -> std::result::Result<(), String> + Send + Sync,
+{
+    fn validate(&self, terms: &BidTerms, proposal: &Event) -> std::result::Result<(), String> {
+        self(terms, proposal)
+    }
+}
+
+/// Requires the proposal payload to be JSON matching a schema (the subset understood by
+/// `tools::schema::validate`)
+#[derive(Debug, Clone, PartialEq)]
+pub struct PayloadSchema(pub serde_json::Value);
+
+impl ProposalValidator for PayloadSchema {
+    fn validate(&self, _terms: &BidTerms, proposal: &Event) -> std::result::Result<(), String> {
+        let payload: serde_json::Value = serde_json::from_slice(&proposal.payload)
+            .map_err(|e| format!("payload is not JSON: {e}"))?;
+        crate::tools::schema::validate(&self.0, &payload)
+    }
+}
+
+/// Settings of `Collaborator::contract_net_with`
+#[derive(Clone)]
+pub struct ContractNetConfig {
+    /// How long proposals are collected after the CFP
+    pub window: Duration,
+    /// Most bidders awarded
+    pub max_awards: usize,
+    /// With `Some`, awards are two-phase: a winner has this long to answer with
+    /// `collab.confirm`, otherwise (or on `collab.decline`) the award goes to the next
+    /// bidder in rank order. `None` awards once, without waiting.
+    pub confirm_window: Option<Duration>,
+    pub constraints: BidConstraints,
+    pub scorer: Arc<dyn BidScorer>,
+    /// Proposals it refuses are rejected before ranking; `None` accepts every proposal
+    pub validator: Option<Arc<dyn ProposalValidator>>,
+}
+
+impl ContractNetConfig {
+    pub fn new(window: Duration, max_awards: usize) -> Self {
+        Self {
+            window,
+            max_awards,
+            confirm_window: None,
+            constraints: BidConstraints::default(),
+            scorer: Arc::new(ByScore),
+            validator: None,
+        }
+    }
+
+    /// Make winners confirm their award within `window`
+    pub fn with_confirmation(mut self, window: Duration) -> Self {
+        self.confirm_window = Some(window);
+        self
+    }
+
+    pub fn with_constraints(mut self, constraints: BidConstraints) -> Self {
+        self.constraints = constraints;
+        self
+    }
+
+    pub fn with_scorer(mut self, scorer: impl BidScorer + 'static) -> Self {
+        self.scorer = Arc::new(scorer);
+        self
+    }
+
+    pub fn with_validator(mut self, validator: impl ProposalValidator + 'static) -> Self {
+        self.validator = Some(Arc::new(validator));
+        self
+    }
+
+    /// Validate proposal payloads against a JSON schema (see `PayloadSchema`)
+    pub fn with_payload_schema(self, schema: serde_json::Value) -> Self {
+        self.with_validator(PayloadSchema(schema))
+    }
+}
+
+/// Agent that sent a proposal or award answer: the event source, else its envelope sender
+pub(crate) fn bidder_of(ev: &Event) -> &str {
+    if ev.source.is_empty() {
+        ev.metadata
+            .get(keys::SENDER)
+            .map(String::as_str)
+            .unwrap_or_default()
+    } else {
+        &ev.source
+    }
+}
+
+/// Returns whether `ev` answers the collaboration identified by `correlation_id`.
+///
+/// Replies, proposals and fan-in responses are matched on the envelope's
+/// `correlation_id` metadata; events without one never match.
+pub fn is_correlated(ev: &Event, correlation_id: &str) -> bool {
+    ev.metadata
+        .get(keys::CORRELATION_ID)
+        .is_some_and(|id| id == correlation_id)
 }
 
 /// Lightweight collaboration coordinator for multi-agent interactions.
@@ -67,6 +490,38 @@ pub mod types {
 pub struct Collaborator {
     event_bus: Arc<EventBus>,
     sender_id: String,
+    retry: CollabRetryPolicy,
+    directory: Option<Arc<AgentDirectory>>,
+    /// Collaborations waiting in this collaborator: thread id -> request targets
+    active: DashMap<String, Vec<String>>,
+}
+
+/// Registers a collaboration as active until dropped
+struct ActiveThread<'a> {
+    active: &'a DashMap<String, Vec<String>>,
+    thread_id: String,
+}
+
+impl<'a> ActiveThread<'a> {
+    fn new(active: &'a DashMap<String, Vec<String>>, thread_id: &str, targets: &[String]) -> Self {
+        active.insert(thread_id.to_string(), targets.to_vec());
+        Self {
+            active,
+            thread_id: thread_id.to_string(),
+        }
+    }
+
+    fn add_target(&self, target: &str) {
+        if let Some(mut targets) = self.active.get_mut(&self.thread_id) {
+            targets.push(target.to_string());
+        }
+    }
+}
+
+impl Drop for ActiveThread<'_> {
+    fn drop(&mut self) {
+        self.active.remove(&self.thread_id);
+    }
 }
 
 impl Collaborator {
@@ -93,9 +548,126 @@ impl Collaborator {
         Self {
             event_bus,
             sender_id: sender_id.into(),
+            retry: CollabRetryPolicy::default(),
+            directory: None,
+            active: DashMap::new(),
         }
     }
 
+    /// Retry `request_reply` and `fanout_fanin` on timeout (default: no retries)
+    pub fn with_retry_policy(mut self, policy: CollabRetryPolicy) -> Self {
+        self.retry = policy;
+        self
+    }
+
+    /// Directory used to find alternate targets (`CollabRetryPolicy::with_alternates`)
+    pub fn with_directory(mut self, directory: Arc<AgentDirectory>) -> Self {
+        self.directory = Some(directory);
+        self
+    }
+
+    /// Calls off the collaboration on `thread_id`.
+    ///
+    /// Publishes `collab.cancel` to the thread's reply topic, where a pending
+    /// `request_reply`, `fanout_fanin` or `contract_net` on the thread (of this or any
+    /// other collaborator) resolves with `LoomError::Cancelled`, then to the thread's
+    /// broadcast topic and to the topics this collaborator sent the thread's requests
+    /// to, so participating agents can abandon their work.
+    ///
+    /// Cancellation and replies race on the reply topic: whatever reaches it first
+    /// decides the outcome, so a reply published before the cancellation is still
+    /// returned and one published after it is ignored.
+    ///
+    /// Returns whether a collaboration of this collaborator was waiting on the thread.
+    pub async fn cancel(&self, thread_id: &str) -> Result<bool> {
+        let targets = self.active.get(thread_id).map(|targets| targets.clone());
+        let env = Envelope::new(thread_id, self.sender_id.clone());
+        let mut topics = vec![env.reply_topic(), env.broadcast_topic()];
+        for target in targets.iter().flatten() {
+            if !topics.contains(target) {
+                topics.push(target.clone());
+            }
+        }
+        for topic in &topics {
+            let mut md = HashMap::new();
+            env.apply_to_metadata(&mut md);
+            let mut evt = Event {
+                id: format!("evt_{}", chrono::Utc::now().timestamp_millis()),
+                r#type: types::CANCEL.into(),
+                timestamp_ms: chrono::Utc::now().timestamp_millis(),
+                source: self.sender_id.clone(),
+                metadata: md,
+                payload: Vec::new(),
+                confidence: 1.0,
+                tags: vec!["collab".into()],
+                priority: 70,
+            };
+            env.attach_to_event(&mut evt);
+            let _ = self.event_bus.publish(topic, evt).await?;
+        }
+        debug!(target: "collab", thread_id = %thread_id, waiting = targets.is_some(), topics = topics.len(), "Collaboration cancelled");
+        Ok(targets.is_some())
+    }
+
+    /// Request topic of an agent providing the retry policy's alternate capability
+    /// that has not been tried yet.
+    ///
+    /// An agent is reached on its first subscribed topic; disconnected, inactive, degraded,
+    /// paused and draining agents are skipped. Candidates are taken in agent id order.
+    fn alternate_target(&self, tried: &[String]) -> Option<String> {
+        let capability = self.retry.alternate_capability.as_deref()?;
+        let directory = self.directory.as_ref()?;
+        let mut candidates: Vec<_> = directory
+            .by_capability(capability)
+            .into_iter()
+            .filter_map(|agent_id| directory.get(&agent_id))
+            .filter(|info| {
+                !matches!(
+                    info.status,
+                    AgentStatus::Disconnected
+                        | AgentStatus::Inactive
+                        | AgentStatus::Degraded
+                        | AgentStatus::Paused
+                        | AgentStatus::Draining
+                )
+            })
+            .collect();
+        candidates.sort_by(|a, b| a.agent_id.cmp(&b.agent_id));
+        candidates
+            .into_iter()
+            .filter_map(|info| info.subscribed_topics.into_iter().next())
+            .find(|topic| !tried.contains(topic))
+    }
+
+    /// Build a `collab.request` for `env`
+    fn request_event(
+        &self,
+        env: &Envelope,
+        payload: Vec<u8>,
+        idempotency_key: &str,
+        attempt: u32,
+    ) -> Event {
+        let mut md = HashMap::new();
+        env.apply_to_metadata(&mut md);
+        md.insert(meta::IDEMPOTENCY_KEY.into(), idempotency_key.to_string());
+        if attempt > 1 {
+            md.insert(meta::ATTEMPT.into(), attempt.to_string());
+        }
+        let mut evt = Event {
+            id: format!("evt_{}", chrono::Utc::now().timestamp_millis()),
+            r#type: types::REQ.into(),
+            timestamp_ms: chrono::Utc::now().timestamp_millis(),
+            source: self.sender_id.clone(),
+            metadata: md,
+            payload,
+            confidence: 1.0,
+            tags: vec!["collab".into()],
+            priority: 50,
+        };
+        env.attach_to_event(&mut evt);
+        evt
+    }
+
     /// Performs a request-reply interaction with timeout.
     ///
     /// Publishes a request event to the specified topic, then waits for the first
@@ -116,8 +688,19 @@ impl Collaborator {
     ///
     /// # Timeout Behavior
     ///
-    /// On timeout, publishes a `collab.timeout` event to the reply topic for
-    /// observability before returning `Ok(None)`.
+    /// Each attempt waits `timeout_ms`. When the last allowed attempt times out (see
+    /// `with_retry_policy`), publishes a `collab.timeout` event with `reason` and
+    /// `attempts` metadata to the reply topic for observability before returning
+    /// `Ok(None)`.
+    ///
+    /// Every request carries an idempotency key (`meta::IDEMPOTENCY_KEY`) that stays
+    /// the same across retries.
+    ///
+    /// # Cancellation
+    ///
+    /// Fails with `LoomError::Cancelled` once the thread is cancelled (see `cancel`); use
+    /// `request_reply_in` to choose the thread id. A reply that reaches the reply topic
+    /// before the cancellation is still returned.
     ///
     /// # Examples
     ///
@@ -149,68 +732,122 @@ impl Collaborator {
         payload: Vec<u8>,
         timeout_ms: u64,
     ) -> Result<Option<Event>> {
-        if timeout_ms == 0 {
-            return Err(crate::LoomError::EventBusError(
-                "timeout_ms must be greater than 0".into(),
-            ));
-        }
+        let idempotency_key = format!(
+            "idem_{}",
+            chrono::Utc::now()
+                .timestamp_nanos_opt()
+                .unwrap_or_else(|| chrono::Utc::now().timestamp_millis() * 1_000_000)
+        );
+        self.request_reply_with_key(topic, payload, timeout_ms, &idempotency_key)
+            .await
+    }
 
-        // Prepare envelope and subscribe to reply topic first to avoid races
+    /// Like `request_reply`, with a caller-chosen idempotency key.
+    ///
+    /// Use a key derived from the logical operation (e.g. a workflow run and step) when
+    /// the caller itself may repeat the request, so responders can recognise repeats
+    /// across calls as well as across this call's retries.
+    ///
+    /// `timeout_ms` bounds each attempt; with retries the total wait also includes the
+    /// backoff delays. A reply to any earlier attempt is accepted.
+    pub async fn request_reply_with_key(
+        &self,
+        topic: &str,
+        payload: Vec<u8>,
+        timeout_ms: u64,
+        idempotency_key: &str,
+    ) -> Result<Option<Event>> {
         let thread_id = format!(
             "req_{}",
             chrono::Utc::now()
                 .timestamp_nanos_opt()
                 .unwrap_or_else(|| chrono::Utc::now().timestamp_millis() * 1_000_000)
         );
-        let env = Envelope::new(thread_id, self.sender_id.clone());
+        self.request_reply_on_thread(&thread_id, topic, payload, timeout_ms, idempotency_key)
+            .await
+    }
+
+    /// Like `request_reply`, on the caller-chosen thread `thread_id`, so it can be
+    /// cancelled with `cancel(thread_id)` while it waits.
+    pub async fn request_reply_in(
+        &self,
+        thread_id: &str,
+        topic: &str,
+        payload: Vec<u8>,
+        timeout_ms: u64,
+    ) -> Result<Option<Event>> {
+        let idempotency_key = format!("idem_{}", thread_id);
+        self.request_reply_on_thread(thread_id, topic, payload, timeout_ms, &idempotency_key)
+            .await
+    }
+
+    async fn request_reply_on_thread(
+        &self,
+        thread_id: &str,
+        topic: &str,
+        payload: Vec<u8>,
+        timeout_ms: u64,
+        idempotency_key: &str,
+    ) -> Result<Option<Event>> {
+        if timeout_ms == 0 {
+            return Err(crate::LoomError::EventBusError(
+                "timeout_ms must be greater than 0".into(),
+            ));
+        }
+
+        // Prepare envelope and subscribe to reply topic first to avoid races
+        let mut env = Envelope::new(thread_id, self.sender_id.clone());
         let reply_topic = env.reply_topic();
 
         let (_sub_id, mut rx) = self
             .event_bus
             .subscribe(
                 reply_topic.clone(),
-                vec![types::REPLY.into()],
+                vec![types::REPLY.into(), types::CANCEL.into()],
                 crate::proto::QoSLevel::QosBatched,
             )
             .await?;
+        let active = ActiveThread::new(&self.active, thread_id, &[topic.to_string()]);
 
-        let mut md = HashMap::new();
-        env.apply_to_metadata(&mut md);
-        let mut evt = Event {
-            id: format!("evt_{}", chrono::Utc::now().timestamp_millis()),
-            r#type: types::REQ.into(),
-            timestamp_ms: chrono::Utc::now().timestamp_millis(),
-            source: self.sender_id.clone(),
-            metadata: md,
-            payload,
-            confidence: 1.0,
-            tags: vec!["collab".into()],
-            priority: 50,
-        };
-        env.attach_to_event(&mut evt);
-        let _ = self.event_bus.publish(topic, evt).await?;
-
-        // Await first reply that matches correlation
-        let deadline = Duration::from_millis(timeout_ms);
         let corr_id = env.correlation_id.clone();
-        let res = timeout(deadline, async move {
-            while let Some(ev) = rx.recv().await {
-                // basic correlation match
-                if ev.metadata.get(keys::CORRELATION_ID) == Some(&corr_id) {
-                    return Some(ev);
-                }
+        let mut tried = vec![topic.to_string()];
+        let mut target = topic.to_string();
+        let mut attempt = 1;
+        let wait = Duration::from_millis(timeout_ms);
+        let res = loop {
+            // Responders can skip an attempt nobody waits for any more
+            env = env.with_timeout(wait);
+            let evt = self.request_event(&env, payload.clone(), idempotency_key, attempt);
+            let _ = self.event_bus.publish(&target, evt).await?;
+
+            // Await first reply that matches correlation
+            if let Some(ev) = recv_correlated(&mut rx, &corr_id, wait).await {
+                break Some(not_cancelled(ev)?);
+            }
+            if attempt > self.retry.max_retries || !env.next_hop() {
+                break None;
             }
-            None
-        })
-        .await
-        .ok()
-        .flatten();
+
+            // Back off, still accepting a late reply to an earlier attempt
+            let delay = self.retry.backoff_for(attempt - 1);
+            if let Some(ev) = recv_correlated(&mut rx, &corr_id, delay).await {
+                break Some(not_cancelled(ev)?);
+            }
+            if let Some(alternate) = self.alternate_target(&tried) {
+                tried.push(alternate.clone());
+                active.add_target(&alternate);
+                target = alternate;
+            }
+            attempt += 1;
+            debug!(target: "collab", thread_id = %env.thread_id, attempt, topic = %target, "Retrying request");
+        };
 
         if res.is_none() {
             // emit timeout summary (best-effort) on reply topic
             let mut md = HashMap::new();
             env.apply_to_metadata(&mut md);
             md.insert("reason".into(), "request_reply_timeout".into());
+            md.insert("attempts".into(), attempt.to_string());
             let mut evt = Event {
                 id: format!("evt_{}", chrono::Utc::now().timestamp_millis()),
                 r#type: types::TIMEOUT.into(),
@@ -249,8 +886,21 @@ impl Collaborator {
     ///
     /// # Completion Behavior
     ///
-    /// Always publishes a `collab.summary` event with `received` and `target_first_k`
-    /// metadata for observability, regardless of whether `first_k` was reached.
+    /// Always publishes a `collab.summary` event with `received`, `target_first_k` and
+    /// `attempts` metadata for observability, regardless of whether `first_k` was reached.
+    ///
+    /// # Retries
+    ///
+    /// With a retry policy, a round that ends with fewer than `first_k` replies is
+    /// rebroadcast to all targets after the backoff, plus one alternate target per
+    /// missing reply. Replies from a source that already answered are not counted again.
+    /// `timeout_ms` bounds each round.
+    ///
+    /// # Cancellation
+    ///
+    /// Fails with `LoomError::Cancelled` once the thread is cancelled (see `cancel`),
+    /// dropping the replies collected so far; use `fanout_fanin_in` to choose the
+    /// thread id.
     ///
     /// # Examples
     ///
@@ -289,6 +939,26 @@ impl Collaborator {
         payload: Vec<u8>,
         first_k: usize,
         timeout_ms: u64,
+    ) -> Result<Vec<Event>> {
+        let thread_id = format!(
+            "fanout_{}",
+            chrono::Utc::now()
+                .timestamp_nanos_opt()
+                .unwrap_or_else(|| chrono::Utc::now().timestamp_millis() * 1_000_000)
+        );
+        self.fanout_fanin_in(&thread_id, topics, payload, first_k, timeout_ms)
+            .await
+    }
+
+    /// Like `fanout_fanin`, on the caller-chosen thread `thread_id`, so it can be
+    /// cancelled with `cancel(thread_id)` while it waits.
+    pub async fn fanout_fanin_in(
+        &self,
+        thread_id: &str,
+        topics: &[String],
+        payload: Vec<u8>,
+        first_k: usize,
+        timeout_ms: u64,
     ) -> Result<Vec<Event>> {
         if topics.is_empty() {
             return Ok(Vec::new());
@@ -304,61 +974,68 @@ impl Collaborator {
             ));
         }
 
-        let thread_id = format!(
-            "fanout_{}",
-            chrono::Utc::now()
-                .timestamp_nanos_opt()
-                .unwrap_or_else(|| chrono::Utc::now().timestamp_millis() * 1_000_000)
-        );
-        let env = Envelope::new(thread_id, self.sender_id.clone());
+        let mut env = Envelope::new(thread_id, self.sender_id.clone());
         let reply_topic = env.reply_topic();
         let (_sub_id, mut rx) = self
             .event_bus
             .subscribe(
                 reply_topic.clone(),
-                vec![types::REPLY.into(), types::PROPOSAL.into()],
+                vec![
+                    types::REPLY.into(),
+                    types::PROPOSAL.into(),
+                    types::CANCEL.into(),
+                ],
                 crate::proto::QoSLevel::QosBatched,
             )
             .await?;
+        let active = ActiveThread::new(&self.active, thread_id, topics);
 
-        // Broadcast a request to each topic
-        for t in topics {
-            let mut md = HashMap::new();
-            env.apply_to_metadata(&mut md);
-            let mut evt = Event {
-                id: format!("evt_{}", chrono::Utc::now().timestamp_millis()),
-                r#type: types::REQ.into(),
-                timestamp_ms: chrono::Utc::now().timestamp_millis(),
-                source: self.sender_id.clone(),
-                metadata: md.clone(),
-                payload: payload.clone(),
-                confidence: 1.0,
-                tags: vec!["collab".into()],
-                priority: 50,
-            };
-            env.attach_to_event(&mut evt);
-            let _ = self.event_bus.publish(t, evt).await?;
-        }
-
-        // Gather first_k
+        let idempotency_key = format!("idem_{}", env.thread_id);
+        let mut targets = topics.to_vec();
         let mut out = Vec::with_capacity(first_k);
-        let deadline = Instant::now() + Duration::from_millis(timeout_ms);
         let corr_id = env.correlation_id.clone();
-        while out.len() < first_k && Instant::now() < deadline {
-            let remaining = deadline.saturating_duration_since(Instant::now());
-            if let Ok(Some(ev)) = timeout(remaining, rx.recv()).await {
-                if ev.metadata.get(keys::CORRELATION_ID) == Some(&corr_id) {
-                    out.push(ev);
-                }
-            } else {
+        let mut attempt = 1;
+        loop {
+            // Broadcast a request to each topic
+            env = env.with_timeout(Duration::from_millis(timeout_ms));
+            for t in &targets {
+                let evt = self.request_event(&env, payload.clone(), &idempotency_key, attempt);
+                let _ = self.event_bus.publish(t, evt).await?;
+            }
+
+            // Gather first_k
+            let deadline = Instant::now() + Duration::from_millis(timeout_ms);
+            gather_replies(&mut rx, &corr_id, &mut out, first_k, attempt, deadline).await?;
+            if out.len() >= first_k || attempt > self.retry.max_retries || !env.next_hop() {
                 break;
             }
+
+            // Back off, still accepting late replies (and a cancellation)
+            let backoff = Instant::now() + self.retry.backoff_for(attempt - 1);
+            gather_replies(&mut rx, &corr_id, &mut out, first_k, attempt, backoff).await?;
+            if out.len() >= first_k {
+                break;
+            }
+            tokio::time::sleep_until(backoff).await;
+            // Widen the fanout by one alternate per missing reply
+            for _ in out.len()..first_k {
+                match self.alternate_target(&targets) {
+                    Some(alternate) => {
+                        active.add_target(&alternate);
+                        targets.push(alternate);
+                    }
+                    None => break,
+                }
+            }
+            attempt += 1;
+            debug!(target: "collab", thread_id = %env.thread_id, attempt, received = out.len(), targets = targets.len(), "Retrying fanout");
         }
         // Emit barrier summary
         let mut md = HashMap::new();
         env.apply_to_metadata(&mut md);
         md.insert("received".into(), out.len().to_string());
         md.insert("target_first_k".into(), first_k.to_string());
+        md.insert("attempts".into(), attempt.to_string());
         let mut evt = Event {
             id: format!("evt_{}", chrono::Utc::now().timestamp_millis()),
             r#type: types::SUMMARY.into(),
@@ -385,6 +1062,9 @@ impl Collaborator {
     /// 4. Coordinator publishes awards to broadcast topic for top `max_awards`
     /// 5. Coordinator returns winning proposals for task assignment
     ///
+    /// Shorthand for `contract_net_with` and `ContractNetConfig::new`; use that for
+    /// two-phase awards, bid constraints and other scoring functions.
+    ///
     /// # Arguments
     ///
     /// * `broadcast_thread_id` - Thread ID for the collaboration session
@@ -406,6 +1086,7 @@ impl Collaborator {
     ///
     /// Always publishes:
     /// - `collab.award` events to broadcast topic (one per winner)
+    /// - `collab.reject` events to broadcast topic (one per other bidder)
     /// - `collab.summary` event with `winners` and `max_awards` metadata
     ///
     /// # Examples
@@ -443,16 +1124,82 @@ impl Collaborator {
         window_ms: u64,
         max_awards: usize,
     ) -> Result<Vec<Event>> {
-        if window_ms == 0 {
+        let config = ContractNetConfig::new(Duration::from_millis(window_ms), max_awards);
+        self.contract_net_with(broadcast_thread_id, cfp_payload, config)
+            .await
+    }
+
+    /// Contract Net Protocol with bid constraints, a scoring function and optional
+    /// two-phase awards.
+    ///
+    /// Proposals are read as `BidTerms`. Those refused by `config.validator` or that
+    /// break `config.constraints` are rejected; the rest are ranked by `config.scorer`
+    /// (ties keep arrival order).
+    ///
+    /// With `config.confirm_window`, each award waits for the winner to publish
+    /// `collab.confirm` (or `collab.decline`) on the thread reply topic, correlated with
+    /// the CFP and sent by the awarded bidder. An award that is declined or not confirmed
+    /// in time is withdrawn with a `collab.revoke` to the broadcast topic, and the next
+    /// ranked bidder is awarded, until `max_awards` bidders confirmed or the bids run out.
+    ///
+    /// Every bidder that is neither awarded, revoked nor declined gets a `collab.reject`
+    /// on the broadcast topic, with `reject_to` naming it and `reason` set to
+    /// `invalid: <why>`, `constraints` or `outbid`.
+    ///
+    /// # Returns
+    ///
+    /// Winning proposals in rank order: the confirmed ones with a confirm window,
+    /// otherwise the awarded ones.
+    ///
+    /// # Completion Behavior
+    ///
+    /// Publishes a `collab.summary` to the broadcast topic with `winners`, `max_awards`,
+    /// `proposals`, `rejected` (constraint violations), `invalid` (refused by the
+    /// validator) and `revoked` metadata.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use loom_core::messaging::collab::{BidConstraints, ContractNetConfig, LowestCost};
+    /// use loom_core::{Collaborator, EventBus};
+    /// use std::sync::Arc;
+    /// use std::time::Duration;
+    ///
+    /// # async fn example() -> loom_core::Result<()> {
+    /// let bus = Arc::new(EventBus::new().await?);
+    /// let collab = Collaborator::new(bus, "task-coordinator");
+    ///
+    /// let config = ContractNetConfig::new(Duration::from_secs(2), 1)
+    ///     .with_constraints(BidConstraints::new().with_max_cost(10.0).with_min_capacity(2))
+    ///     .with_scorer(LowestCost)
+    ///     .with_confirmation(Duration::from_millis(500));
+    /// let winners = collab
+    ///     .contract_net_with("task-123", b"Transcode video".to_vec(), config)
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn contract_net_with(
+        &self,
+        broadcast_thread_id: &str,
+        cfp_payload: Vec<u8>,
+        config: ContractNetConfig,
+    ) -> Result<Vec<Event>> {
+        if config.window.is_zero() {
             return Err(crate::LoomError::EventBusError(
                 "window_ms must be greater than 0".into(),
             ));
         }
-        if max_awards == 0 {
+        if config.max_awards == 0 {
             return Err(crate::LoomError::EventBusError(
                 "max_awards must be greater than 0".into(),
             ));
         }
+        if config.confirm_window.is_some_and(|w| w.is_zero()) {
+            return Err(crate::LoomError::EventBusError(
+                "confirm window must be greater than 0".into(),
+            ));
+        }
 
         let thread_id = broadcast_thread_id.to_string();
         let env = Envelope::new(thread_id.clone(), self.sender_id.clone());
@@ -462,15 +1209,28 @@ impl Collaborator {
             .event_bus
             .subscribe(
                 reply_topic.clone(),
-                vec![types::PROPOSAL.into()],
+                vec![
+                    types::PROPOSAL.into(),
+                    types::CONFIRM.into(),
+                    types::DECLINE.into(),
+                    types::CANCEL.into(),
+                ],
                 crate::proto::QoSLevel::QosBatched,
             )
             .await?;
+        let _active = ActiveThread::new(&self.active, &thread_id, &[]);
 
         // Publish CFP to broadcast topic
         let broadcast_topic = ThreadTopicKind::Broadcast.topic(&thread_id);
         let mut md = HashMap::new();
         env.apply_to_metadata(&mut md);
+        config.constraints.apply_to_metadata(&mut md);
+        if let Some(window) = config.confirm_window {
+            md.insert(
+                meta::CONFIRM_WITHIN_MS.into(),
+                window.as_millis().to_string(),
+            );
+        }
         let mut cfp_evt = Event {
             id: format!("evt_{}", chrono::Utc::now().timestamp_millis()),
             r#type: types::CFP.into(),
@@ -486,63 +1246,147 @@ impl Collaborator {
         let _ = self.event_bus.publish(&broadcast_topic, cfp_evt).await?;
 
         // Collect proposals during window
-        let end = Instant::now() + Duration::from_millis(window_ms);
+        let end = Instant::now() + config.window;
         let mut proposals: Vec<Event> = Vec::new();
         while Instant::now() < end {
             let remaining = end.saturating_duration_since(Instant::now());
             match timeout(remaining, rx.recv()).await {
-                Ok(Some(ev)) => {
-                    if ev.metadata.get(keys::CORRELATION_ID) == Some(&env.correlation_id) {
+                Ok(Some(ev)) if is_correlated(&ev, &env.correlation_id) => {
+                    let ev = not_cancelled(ev)?;
+                    if ev.r#type == types::PROPOSAL {
                         proposals.push(ev);
                     }
                 }
+                Ok(Some(_)) => {}
                 _ => break,
             }
         }
+        let received = proposals.len();
 
-        // Rank by metadata.score (descending)
-        proposals.sort_by(|a, b| {
-            let sa = a
-                .metadata
-                .get("score")
-                .and_then(|s| s.parse::<f64>().ok())
-                .unwrap_or(0.0);
-            let sb = b
-                .metadata
-                .get("score")
-                .and_then(|s| s.parse::<f64>().ok())
-                .unwrap_or(0.0);
-            sb.partial_cmp(&sa).unwrap_or(std::cmp::Ordering::Equal)
-        });
-        let winners = proposals.into_iter().take(max_awards).collect::<Vec<_>>();
-
-        // Publish awards to broadcast topic
-        for w in &winners {
-            let mut award_meta = w.metadata.clone();
-            award_meta.insert(
-                "award_to".into(),
-                w.metadata.get(keys::SENDER).cloned().unwrap_or_default(),
-            );
-            let mut award_evt = Event {
-                id: format!("evt_{}", chrono::Utc::now().timestamp_millis()),
-                r#type: types::AWARD.into(),
-                timestamp_ms: chrono::Utc::now().timestamp_millis(),
-                source: self.sender_id.clone(),
-                metadata: award_meta,
-                payload: Vec::new(),
-                confidence: 1.0,
-                tags: vec!["collab".into()],
-                priority: 60,
+        // Reject invalid bids and those that break the constraints, rank the rest
+        // (descending, stable)
+        let mut invalid = 0;
+        let mut rejected = 0;
+        let mut ranked: Vec<(f64, Event)> = Vec::with_capacity(received);
+        for ev in proposals {
+            let terms = BidTerms::from_event(&ev);
+            let refusal = match config.validator.as_ref().map(|v| v.validate(&terms, &ev)) {
+                Some(Err(reason)) => {
+                    invalid += 1;
+                    Some(format!("invalid: {reason}"))
+                }
+                _ if !config.constraints.admits(&terms) => {
+                    rejected += 1;
+                    Some("constraints".to_string())
+                }
+                _ => None,
+            };
+            if let Some(reason) = refusal {
+                debug!(target: "collab", thread_id = %thread_id, bidder = %bidder_of(&ev), %reason, "Proposal rejected");
+                self.publish_reject(&env, &broadcast_topic, &ev, &reason)
+                    .await?;
+                continue;
+            }
+            let score = config.scorer.score(&terms, &ev);
+            let score = if score.is_nan() {
+                f64::NEG_INFINITY
+            } else {
+                score
             };
-            env.attach_to_event(&mut award_evt);
-            let _ = self.event_bus.publish(&broadcast_topic, award_evt).await?;
+            ranked.push((score, ev));
+        }
+        ranked.sort_by(|a, b| b.0.total_cmp(&a.0));
+        let mut queue: VecDeque<(usize, Event)> =
+            ranked.into_iter().map(|(_, ev)| ev).enumerate().collect();
+
+        let mut revoked = 0;
+        let winners = match config.confirm_window {
+            None => {
+                let winners: Vec<Event> = queue
+                    .drain(..config.max_awards.min(queue.len()))
+                    .map(|(_, ev)| ev)
+                    .collect();
+                for w in &winners {
+                    self.publish_award(&env, &broadcast_topic, w, None).await?;
+                }
+                winners
+            }
+            Some(window) => {
+                // Awarded bidders waiting to confirm: (rank, proposal, deadline)
+                let mut pending: Vec<(usize, Event, Instant)> = Vec::new();
+                let mut confirmed: Vec<(usize, Event)> = Vec::new();
+                loop {
+                    // Fill open award slots from the next ranked bidders
+                    while confirmed.len() + pending.len() < config.max_awards {
+                        let Some((rank, ev)) = queue.pop_front() else {
+                            break;
+                        };
+                        self.publish_award(&env, &broadcast_topic, &ev, Some(window))
+                            .await?;
+                        pending.push((rank, ev, Instant::now() + window));
+                    }
+                    let Some(deadline) = pending.iter().map(|(_, _, d)| *d).min() else {
+                        break;
+                    };
+
+                    let wait = deadline.saturating_duration_since(Instant::now());
+                    match timeout(wait, rx.recv()).await {
+                        Ok(Some(ev)) => {
+                            if ev.r#type == types::CANCEL && is_correlated(&ev, &env.correlation_id)
+                            {
+                                return Err(crate::LoomError::Cancelled(thread_id));
+                            }
+                            let answer = (ev.r#type == types::CONFIRM
+                                || ev.r#type == types::DECLINE)
+                                && is_correlated(&ev, &env.correlation_id);
+                            let pos = pending
+                                .iter()
+                                .position(|(_, p, _)| bidder_of(p) == bidder_of(&ev))
+                                .filter(|_| answer);
+                            if let Some(pos) = pos {
+                                let (rank, proposal, _) = pending.remove(pos);
+                                if ev.r#type == types::CONFIRM {
+                                    confirmed.push((rank, proposal));
+                                } else {
+                                    debug!(target: "collab", thread_id = %thread_id, bidder = %bidder_of(&proposal), "Award declined");
+                                }
+                            }
+                        }
+                        Ok(None) => break,
+                        Err(_) => {}
+                    }
+
+                    // Withdraw every award whose window has lapsed
+                    let now = Instant::now();
+                    let (lapsed, waiting): (Vec<_>, Vec<_>) =
+                        pending.into_iter().partition(|(_, _, d)| *d <= now);
+                    pending = waiting;
+                    for (_, proposal, _) in lapsed {
+                        self.publish_revoke(&env, &broadcast_topic, &proposal)
+                            .await?;
+                        revoked += 1;
+                    }
+                }
+                confirmed.sort_by_key(|(rank, _)| *rank);
+                confirmed.into_iter().map(|(_, ev)| ev).collect()
+            }
+        };
+
+        // Bidders that were never awarded lost to better bids
+        for (_, proposal) in queue {
+            self.publish_reject(&env, &broadcast_topic, &proposal, "outbid")
+                .await?;
         }
 
         // Publish summary with total winners
         let mut md = HashMap::new();
         env.apply_to_metadata(&mut md);
         md.insert("winners".into(), winners.len().to_string());
-        md.insert("max_awards".into(), max_awards.to_string());
+        md.insert("max_awards".into(), config.max_awards.to_string());
+        md.insert("proposals".into(), received.to_string());
+        md.insert("rejected".into(), rejected.to_string());
+        md.insert("invalid".into(), invalid.to_string());
+        md.insert("revoked".into(), revoked.to_string());
         let mut summary_evt = Event {
             id: format!("evt_{}", chrono::Utc::now().timestamp_millis()),
             r#type: types::SUMMARY.into(),
@@ -562,4 +1406,149 @@ impl Collaborator {
 
         Ok(winners)
     }
+
+    /// Announce the award of `proposal` on `broadcast_topic`
+    async fn publish_award(
+        &self,
+        env: &Envelope,
+        broadcast_topic: &str,
+        proposal: &Event,
+        confirm_window: Option<Duration>,
+    ) -> Result<()> {
+        let mut award_meta = proposal.metadata.clone();
+        award_meta.insert(meta::AWARD_TO.into(), bidder_of(proposal).to_string());
+        if let Some(window) = confirm_window {
+            award_meta.insert(
+                meta::CONFIRM_WITHIN_MS.into(),
+                window.as_millis().to_string(),
+            );
+        }
+        let mut award_evt = Event {
+            id: format!("evt_{}", chrono::Utc::now().timestamp_millis()),
+            r#type: types::AWARD.into(),
+            timestamp_ms: chrono::Utc::now().timestamp_millis(),
+            source: self.sender_id.clone(),
+            metadata: award_meta,
+            payload: Vec::new(),
+            confidence: 1.0,
+            tags: vec!["collab".into()],
+            priority: 60,
+        };
+        env.attach_to_event(&mut award_evt);
+        let _ = self.event_bus.publish(broadcast_topic, award_evt).await?;
+        Ok(())
+    }
+
+    /// Withdraw the unconfirmed award of `proposal`
+    async fn publish_revoke(
+        &self,
+        env: &Envelope,
+        broadcast_topic: &str,
+        proposal: &Event,
+    ) -> Result<()> {
+        let mut md = HashMap::new();
+        env.apply_to_metadata(&mut md);
+        md.insert(meta::AWARD_TO.into(), bidder_of(proposal).to_string());
+        md.insert(meta::REASON.into(), "confirm_timeout".into());
+        let mut evt = Event {
+            id: format!("evt_{}", chrono::Utc::now().timestamp_millis()),
+            r#type: types::REVOKE.into(),
+            timestamp_ms: chrono::Utc::now().timestamp_millis(),
+            source: self.sender_id.clone(),
+            metadata: md,
+            payload: Vec::new(),
+            confidence: 1.0,
+            tags: vec!["collab".into()],
+            priority: 60,
+        };
+        env.attach_to_event(&mut evt);
+        let _ = self.event_bus.publish(broadcast_topic, evt).await?;
+        debug!(target: "collab", thread_id = %env.thread_id, bidder = %bidder_of(proposal), "Award revoked");
+        Ok(())
+    }
+
+    /// Tell the bidder of `proposal` that it was not awarded, and why
+    async fn publish_reject(
+        &self,
+        env: &Envelope,
+        broadcast_topic: &str,
+        proposal: &Event,
+        reason: &str,
+    ) -> Result<()> {
+        let mut md = HashMap::new();
+        env.apply_to_metadata(&mut md);
+        md.insert(meta::REJECT_TO.into(), bidder_of(proposal).to_string());
+        md.insert(meta::REASON.into(), reason.to_string());
+        let mut evt = Event {
+            id: format!("evt_{}", chrono::Utc::now().timestamp_millis()),
+            r#type: types::REJECT.into(),
+            timestamp_ms: chrono::Utc::now().timestamp_millis(),
+            source: self.sender_id.clone(),
+            metadata: md,
+            payload: Vec::new(),
+            confidence: 1.0,
+            tags: vec!["collab".into()],
+            priority: 40,
+        };
+        env.attach_to_event(&mut evt);
+        let _ = self.event_bus.publish(broadcast_topic, evt).await?;
+        Ok(())
+    }
+}
+
+/// `ev`, unless it is the cancellation of its collaboration
+fn not_cancelled(ev: Event) -> Result<Event> {
+    if ev.r#type == types::CANCEL {
+        return Err(crate::LoomError::Cancelled(
+            Envelope::from_event(&ev).thread_id,
+        ));
+    }
+    Ok(ev)
+}
+
+/// Collect fanout replies correlated with `correlation_id` into `out` until `deadline`
+/// or `first_k` of them
+async fn gather_replies(
+    rx: &mut mpsc::Receiver<Event>,
+    correlation_id: &str,
+    out: &mut Vec<Event>,
+    first_k: usize,
+    attempt: u32,
+    deadline: Instant,
+) -> Result<()> {
+    while out.len() < first_k && Instant::now() < deadline {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        let Ok(Some(ev)) = timeout(remaining, rx.recv()).await else {
+            break;
+        };
+        if !is_correlated(&ev, correlation_id) {
+            continue;
+        }
+        let ev = not_cancelled(ev)?;
+        // A retry reaches responders that already replied; count each once
+        let repeated = attempt > 1 && out.iter().any(|seen| seen.source == ev.source);
+        if !repeated {
+            out.push(ev);
+        }
+    }
+    Ok(())
+}
+
+/// Wait up to `wait` for an event on `rx` correlated with `correlation_id`
+async fn recv_correlated(
+    rx: &mut mpsc::Receiver<Event>,
+    correlation_id: &str,
+    wait: Duration,
+) -> Option<Event> {
+    timeout(wait, async {
+        while let Some(ev) = rx.recv().await {
+            if is_correlated(&ev, correlation_id) {
+                return Some(ev);
+            }
+        }
+        None
+    })
+    .await
+    .ok()
+    .flatten()
 }
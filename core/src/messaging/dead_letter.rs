@@ -0,0 +1,372 @@
+//! Dead-letter queue: events the bus gave up on, kept for inspection and re-publishing.
+//!
+//! The bus records an event here when
+//! - a `QosReliable` subscription, a critical topic or a `QosGuaranteed` subscription
+//!   used up its `max_deliveries` attempts (`nacked`, `ack_timeout`), or its subscriber
+//!   went away with the delivery unacked (`subscriber_gone`);
+//! - a delivery failed because the subscriber dropped its receiver (`subscriber_gone`);
+//! - a publish was rejected by the topic's payload schema (`schema_invalid`, see
+//!   `EventBus::set_topic_schema`).
+//!
+//! Each dead letter is the event with `dead_letter_keys` metadata describing why it was
+//! given up on. The store is capped: once full, the oldest dead letter makes room for
+//! the next. `DeadLetterStore::open` keeps dead letters in RocksDB across restarts;
+//! `EventBus::dead_letters` queries them and `EventBus::republish_dead_letter` publishes
+//! one to its original topic again.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::sync::Mutex;
+
+use prost::Message;
+use rocksdb::{ColumnFamilyDescriptor, IteratorMode, Options, DB};
+use tracing::{info, warn};
+
+use crate::messaging::event_bus::topic_matches;
+use crate::messaging::receipts::{RECEIPT_ATTEMPT_KEY, RECEIPT_SUBSCRIBER_KEY};
+use crate::messaging::reliable::dead_letter_keys;
+use crate::proto::Event;
+use crate::{LoomError, Result};
+
+/// Dead letters kept unless `LOOM_DEAD_LETTER_CAPACITY` or `with_capacity` says otherwise
+pub const DEFAULT_DEAD_LETTER_CAPACITY: usize = 10_000;
+
+const CF_DEAD_LETTERS: &str = "dead_letters";
+
+/// Values of `dead_letter_keys::REASON`
+pub mod dead_letter_reasons {
+    /// Nacked on its last attempt
+    pub const NACKED: &str = "nacked";
+    /// Unacked on its last attempt
+    pub const ACK_TIMEOUT: &str = "ack_timeout";
+    /// The subscriber's receiver was dropped before the event reached it or was acked
+    pub const SUBSCRIBER_GONE: &str = "subscriber_gone";
+    /// The payload did not match the topic's schema; the event was never delivered
+    pub const SCHEMA_INVALID: &str = "schema_invalid";
+}
+
+/// An event the bus gave up on
+#[derive(Debug, Clone, PartialEq)]
+pub struct DeadLetter {
+    /// Increasing with every dead letter recorded by the store
+    pub id: u64,
+    /// Topic the event was published on (for subscriptions, the subscribed topic)
+    pub topic: String,
+    pub subscription: Option<String>,
+    /// One of `dead_letter_reasons`
+    pub reason: String,
+    /// Deliveries made before giving up
+    pub attempts: u32,
+    /// Milliseconds since Unix epoch
+    pub dead_lettered_at_ms: i64,
+    /// The event as published, without dead-letter or receipt metadata
+    pub event: Event,
+}
+
+impl DeadLetter {
+    /// Read a dead letter back from an event marked by `mark_dead`
+    fn from_marked(id: u64, mut event: Event) -> Self {
+        let mut take = |key: &str| event.metadata.remove(key);
+        let topic = take(dead_letter_keys::TOPIC).unwrap_or_default();
+        let subscription = take(dead_letter_keys::SUBSCRIPTION);
+        let reason = take(dead_letter_keys::REASON).unwrap_or_default();
+        let attempts = take(dead_letter_keys::ATTEMPTS)
+            .and_then(|a| a.parse().ok())
+            .unwrap_or(0);
+        let dead_lettered_at_ms = take(dead_letter_keys::AT_MS)
+            .and_then(|t| t.parse().ok())
+            .unwrap_or(0);
+        take(RECEIPT_SUBSCRIBER_KEY);
+        take(RECEIPT_ATTEMPT_KEY);
+        Self {
+            id,
+            topic,
+            subscription,
+            reason,
+            attempts,
+            dead_lettered_at_ms,
+            event,
+        }
+    }
+}
+
+/// Stamp `event` with the `dead_letter_keys` metadata describing why it is given up on
+pub(crate) fn mark_dead(
+    event: &mut Event,
+    topic: &str,
+    subscription: Option<&str>,
+    reason: &str,
+    attempts: u32,
+) {
+    let metadata = &mut event.metadata;
+    metadata.insert(dead_letter_keys::REASON.into(), reason.to_string());
+    metadata.insert(dead_letter_keys::ATTEMPTS.into(), attempts.to_string());
+    metadata.insert(dead_letter_keys::TOPIC.into(), topic.to_string());
+    if let Some(subscription) = subscription {
+        metadata.insert(
+            dead_letter_keys::SUBSCRIPTION.into(),
+            subscription.to_string(),
+        );
+    }
+    metadata.insert(
+        dead_letter_keys::AT_MS.into(),
+        chrono::Utc::now().timestamp_millis().to_string(),
+    );
+}
+
+/// Which dead letters `EventBus::dead_letters` returns; the default matches all of them
+#[derive(Debug, Clone, Default)]
+pub struct DeadLetterFilter {
+    /// Topic or pattern (`orders.*`, see `topic_matches`) the event was published on
+    pub topic: Option<String>,
+    pub reason: Option<String>,
+    pub subscription: Option<String>,
+    /// Only dead letters recorded at or after this time (ms since Unix epoch)
+    pub since_ms: Option<i64>,
+    /// Most dead letters returned
+    pub limit: Option<usize>,
+}
+
+impl DeadLetterFilter {
+    pub fn with_topic(mut self, topic: impl Into<String>) -> Self {
+        self.topic = Some(topic.into());
+        self
+    }
+
+    pub fn with_reason(mut self, reason: impl Into<String>) -> Self {
+        self.reason = Some(reason.into());
+        self
+    }
+
+    pub fn with_subscription(mut self, subscription: impl Into<String>) -> Self {
+        self.subscription = Some(subscription.into());
+        self
+    }
+
+    pub fn with_since_ms(mut self, since_ms: i64) -> Self {
+        self.since_ms = Some(since_ms);
+        self
+    }
+
+    pub fn with_limit(mut self, limit: usize) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    pub fn matches(&self, letter: &DeadLetter) -> bool {
+        self.topic
+            .as_deref()
+            .is_none_or(|pattern| topic_matches(pattern, &letter.topic))
+            && self.reason.as_deref().is_none_or(|r| r == letter.reason)
+            && self
+                .subscription
+                .as_deref()
+                .is_none_or(|s| letter.subscription.as_deref() == Some(s))
+            && self
+                .since_ms
+                .is_none_or(|since| letter.dead_lettered_at_ms >= since)
+    }
+}
+
+/// RocksDB backing: the marked, prost-encoded event under its big-endian id
+struct DeadLetterDb {
+    db: DB,
+}
+
+impl DeadLetterDb {
+    fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let mut opts = Options::default();
+        opts.create_if_missing(true);
+        opts.create_missing_column_families(true);
+
+        let cf_descriptors = vec![ColumnFamilyDescriptor::new(
+            CF_DEAD_LETTERS,
+            Options::default(),
+        )];
+        let db = DB::open_cf_descriptors(&opts, path, cf_descriptors)
+            .map_err(|e| LoomError::StorageError(e.to_string()))?;
+        Ok(Self { db })
+    }
+
+    fn put(&self, id: u64, marked: &Event) -> Result<()> {
+        self.db
+            .put_cf(self.cf()?, id.to_be_bytes(), marked.encode_to_vec())
+            .map_err(|e| LoomError::StorageError(e.to_string()))
+    }
+
+    fn delete(&self, id: u64) -> Result<()> {
+        self.db
+            .delete_cf(self.cf()?, id.to_be_bytes())
+            .map_err(|e| LoomError::StorageError(e.to_string()))
+    }
+
+    /// All stored dead letters, oldest first; undecodable records are skipped
+    fn load_all(&self) -> Result<Vec<DeadLetter>> {
+        let mut letters = Vec::new();
+        for entry in self.db.iterator_cf(self.cf()?, IteratorMode::Start) {
+            let (key, value) = entry.map_err(|e| LoomError::StorageError(e.to_string()))?;
+            let id = <[u8; 8]>::try_from(key.as_ref()).map(u64::from_be_bytes);
+            match (id, Event::decode(value.as_ref())) {
+                (Ok(id), Ok(event)) => letters.push(DeadLetter::from_marked(id, event)),
+                _ => warn!(
+                    target: "event_bus",
+                    key = ?key,
+                    "Skipping undecodable dead letter record"
+                ),
+            }
+        }
+        Ok(letters)
+    }
+
+    fn cf(&self) -> Result<&rocksdb::ColumnFamily> {
+        self.db
+            .cf_handle(CF_DEAD_LETTERS)
+            .ok_or_else(|| LoomError::StorageError(format!("Missing CF: {}", CF_DEAD_LETTERS)))
+    }
+}
+
+struct Letters {
+    by_id: BTreeMap<u64, DeadLetter>,
+    next_id: u64,
+}
+
+/// Capped store of dead letters, in memory or in RocksDB
+pub struct DeadLetterStore {
+    letters: Mutex<Letters>,
+    capacity: usize,
+    db: Option<DeadLetterDb>,
+}
+
+impl Default for DeadLetterStore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DeadLetterStore {
+    /// In-memory store of `DEFAULT_DEAD_LETTER_CAPACITY` dead letters, lost when the
+    /// process exits
+    pub fn new() -> Self {
+        Self::build(None, Vec::new())
+    }
+
+    /// Store in a RocksDB database at `path`, reloading the dead letters already there
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let db = DeadLetterDb::open(path)?;
+        let stored = db.load_all()?;
+        info!(target: "event_bus", dead_letters = stored.len(), "Dead-letter persistence initialized");
+        Ok(Self::build(Some(db), stored))
+    }
+
+    /// Store configured by the environment: RocksDB at `LOOM_DEAD_LETTER_PATH` if set,
+    /// else in memory, holding `LOOM_DEAD_LETTER_CAPACITY` dead letters
+    pub fn from_env() -> Result<Self> {
+        let store = match std::env::var("LOOM_DEAD_LETTER_PATH") {
+            Ok(path) => Self::open(path)?,
+            Err(_) => Self::new(),
+        };
+        match std::env::var("LOOM_DEAD_LETTER_CAPACITY") {
+            Ok(capacity) => {
+                let capacity = capacity.parse().map_err(|_| {
+                    LoomError::ConfigError(format!(
+                        "LOOM_DEAD_LETTER_CAPACITY must be a number, got '{capacity}'"
+                    ))
+                })?;
+                store.with_capacity(capacity)
+            }
+            Err(_) => Ok(store),
+        }
+    }
+
+    fn build(db: Option<DeadLetterDb>, stored: Vec<DeadLetter>) -> Self {
+        let next_id = stored.last().map_or(1, |letter| letter.id + 1);
+        Self {
+            letters: Mutex::new(Letters {
+                by_id: stored.into_iter().map(|l| (l.id, l)).collect(),
+                next_id,
+            }),
+            capacity: DEFAULT_DEAD_LETTER_CAPACITY,
+            db,
+        }
+    }
+
+    /// Keep at most `capacity` dead letters (at least 1), dropping the oldest beyond it
+    pub fn with_capacity(mut self, capacity: usize) -> Result<Self> {
+        self.capacity = capacity.max(1);
+        let mut letters = self.letters.lock().unwrap();
+        self.evict(&mut letters)?;
+        drop(letters);
+        Ok(self)
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn is_persistent(&self) -> bool {
+        self.db.is_some()
+    }
+
+    /// Keep an event marked by `mark_dead`
+    pub(crate) fn record(&self, marked: &Event) -> Result<DeadLetter> {
+        let mut letters = self.letters.lock().unwrap();
+        let id = letters.next_id;
+        letters.next_id += 1;
+        if let Some(ref db) = self.db {
+            db.put(id, marked)?;
+        }
+        let letter = DeadLetter::from_marked(id, marked.clone());
+        letters.by_id.insert(id, letter.clone());
+        self.evict(&mut letters)?;
+        Ok(letter)
+    }
+
+    fn evict(&self, letters: &mut Letters) -> Result<()> {
+        while letters.by_id.len() > self.capacity {
+            let Some((id, _)) = letters.by_id.pop_first() else {
+                break;
+            };
+            if let Some(ref db) = self.db {
+                db.delete(id)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Dead letters matching `filter`, newest first
+    pub fn list(&self, filter: &DeadLetterFilter) -> Vec<DeadLetter> {
+        let letters = self.letters.lock().unwrap();
+        letters
+            .by_id
+            .values()
+            .rev()
+            .filter(|letter| filter.matches(letter))
+            .take(filter.limit.unwrap_or(usize::MAX))
+            .cloned()
+            .collect()
+    }
+
+    pub fn get(&self, id: u64) -> Option<DeadLetter> {
+        self.letters.lock().unwrap().by_id.get(&id).cloned()
+    }
+
+    /// Forget a dead letter; returns it if it was stored
+    pub fn remove(&self, id: u64) -> Result<Option<DeadLetter>> {
+        let mut letters = self.letters.lock().unwrap();
+        let Some(letter) = letters.by_id.remove(&id) else {
+            return Ok(None);
+        };
+        if let Some(ref db) = self.db {
+            db.delete(id)?;
+        }
+        Ok(Some(letter))
+    }
+
+    pub fn len(&self) -> usize {
+        self.letters.lock().unwrap().by_id.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
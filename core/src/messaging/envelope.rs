@@ -4,32 +4,9 @@ use serde::{Deserialize, Serialize};
 
 use crate::proto::{ActionCall, Event};
 
-/// Reserved metadata/header keys for thread & correlation semantics.
-///
-/// These keys are used in `Event.metadata` and `ActionCall.headers` to carry
-/// envelope information for multi-agent coordination.
-pub mod keys {
-    /// Thread identifier for grouping related messages
-    pub const THREAD_ID: &str = "thread_id";
-    /// Correlation identifier linking replies to requests
-    pub const CORRELATION_ID: &str = "correlation_id";
-    /// Logical identity of the message sender (e.g., "agent.foo")
-    pub const SENDER: &str = "sender";
-    /// Canonical reply topic for responses
-    pub const REPLY_TO: &str = "reply_to";
-    /// Remaining time-to-live (hops budget)
-    pub const TTL: &str = "ttl";
-    /// Current hop count (incremented each forwarding)
-    pub const HOP_COUNT: &str = "hop";
-    /// Timestamp in milliseconds since epoch
-    pub const TIMESTAMP_MS: &str = "ts";
-    /// OpenTelemetry trace identifier (128-bit hex string)
-    pub const TRACE_ID: &str = "trace_id";
-    /// OpenTelemetry span identifier (64-bit hex string)
-    pub const SPAN_ID: &str = "span_id";
-    /// OpenTelemetry trace flags (8-bit hex string, typically "01" for sampled)
-    pub const TRACE_FLAGS: &str = "trace_flags";
-}
+/// Reserved metadata/header keys for thread & correlation semantics, shared with the
+/// SDKs through `loom_proto::conventions`.
+pub use crate::proto::conventions::keys;
 
 /// Topic conventions for thread-scoped communication.
 ///
@@ -113,13 +90,22 @@ impl ThreadTopicKind {
 /// * `trace_id` - OpenTelemetry trace ID (128-bit hex string) for distributed tracing
 /// * `span_id` - OpenTelemetry span ID (64-bit hex string) for distributed tracing
 /// * `trace_flags` - OpenTelemetry trace flags (8-bit hex string, typically "01" for sampled)
+/// * `caused_by` - Id of the event that caused this message (empty for root messages)
+/// * `deadline_ms` - Absolute deadline (ms since epoch) after which the message is stale
+/// * `reply_expected` - Whether the sender waits for a reply on `reply_to`
+///
+/// The last three fields were added in version 2 of the envelope. They are optional on
+/// the wire: only set fields are written to metadata or serialized, and envelopes
+/// without them read back as unset, so v1 and v2 peers interoperate.
 ///
 /// # Thread Lifecycle
 ///
 /// 1. **Creation**: Initiator creates envelope with `new(thread_id, sender)`
 /// 2. **Propagation**: Agent loop calls `next_hop()` to increment hop and decrement TTL
-/// 3. **Expiration**: When `next_hop()` returns false (ttl ≤ 0), message is dropped
-/// 4. **Reply**: Responders use `reply_topic()` and preserve correlation_id
+/// 3. **Expiration**: When `next_hop()` returns false (ttl ≤ 0) or `is_expired()` reports
+///    a passed deadline, message is dropped
+/// 4. **Reply**: Responders use `reply_topic()` and preserve correlation_id; `caused_by()`
+///    derives the envelope of a message sent in response to an event
 ///
 /// # Integration Points
 ///
@@ -186,6 +172,15 @@ pub struct Envelope {
     /// OpenTelemetry trace flags (8-bit hex string, typically "01" for sampled)
     #[serde(default, skip_serializing_if = "String::is_empty")]
     pub trace_flags: String,
+    /// Id of the event that caused this message; empty for root messages
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub caused_by: String,
+    /// Absolute deadline in milliseconds since epoch
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deadline_ms: Option<i64>,
+    /// Whether the sender waits for a reply on `reply_to`
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub reply_expected: bool,
 }
 
 impl Envelope {
@@ -226,6 +221,9 @@ impl Envelope {
             trace_id: String::new(),
             span_id: String::new(),
             trace_flags: String::new(),
+            caused_by: String::new(),
+            deadline_ms: None,
+            reply_expected: false,
         }
     }
 
@@ -282,6 +280,11 @@ impl Envelope {
         let trace_id = meta.get(keys::TRACE_ID).cloned().unwrap_or_default();
         let span_id = meta.get(keys::SPAN_ID).cloned().unwrap_or_default();
         let trace_flags = meta.get(keys::TRACE_FLAGS).cloned().unwrap_or_default();
+        let caused_by = meta.get(keys::CAUSED_BY).cloned().unwrap_or_default();
+        let deadline_ms = meta
+            .get(keys::DEADLINE_MS)
+            .and_then(|s| s.parse::<i64>().ok());
+        let reply_expected = meta.get(keys::REPLY_EXPECTED).is_some_and(|v| v == "true");
         Self {
             thread_id,
             correlation_id,
@@ -293,6 +296,9 @@ impl Envelope {
             trace_id,
             span_id,
             trace_flags,
+            caused_by,
+            deadline_ms,
+            reply_expected,
         }
     }
 
@@ -336,6 +342,16 @@ impl Envelope {
         if !self.trace_flags.is_empty() {
             meta.insert(keys::TRACE_FLAGS.into(), self.trace_flags.clone());
         }
+        // Version 2 fields, likewise only when set
+        if !self.caused_by.is_empty() {
+            meta.insert(keys::CAUSED_BY.into(), self.caused_by.clone());
+        }
+        if let Some(deadline_ms) = self.deadline_ms {
+            meta.insert(keys::DEADLINE_MS.into(), deadline_ms.to_string());
+        }
+        if self.reply_expected {
+            meta.insert(keys::REPLY_EXPECTED.into(), "true".into());
+        }
     }
 
     /// Extracts envelope from an Event with fallback to event ID.
@@ -489,6 +505,95 @@ impl Envelope {
         self.ttl > 0
     }
 
+    /// Sets the id of the event this message was caused by.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use loom_core::Envelope;
+    ///
+    /// let env = Envelope::new("task-1", "agent.planner").with_caused_by("evt-7");
+    /// assert_eq!(env.caused_by, "evt-7");
+    /// ```
+    pub fn with_caused_by(mut self, event_id: impl Into<String>) -> Self {
+        self.caused_by = event_id.into();
+        self
+    }
+
+    /// Sets an absolute deadline in milliseconds since epoch.
+    pub fn with_deadline_ms(mut self, deadline_ms: i64) -> Self {
+        self.deadline_ms = Some(deadline_ms);
+        self
+    }
+
+    /// Sets the deadline to `timeout` from now.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use loom_core::Envelope;
+    /// use std::time::Duration;
+    ///
+    /// let env = Envelope::new("task-1", "agent.planner").with_timeout(Duration::from_secs(5));
+    /// assert!(env.remaining().unwrap() > Duration::from_secs(4));
+    /// assert!(!env.is_expired());
+    /// ```
+    pub fn with_timeout(self, timeout: std::time::Duration) -> Self {
+        let deadline_ms = chrono::Utc::now().timestamp_millis() + timeout.as_millis() as i64;
+        self.with_deadline_ms(deadline_ms)
+    }
+
+    /// Marks the message as awaiting a reply on `reply_to`.
+    pub fn with_reply_expected(mut self, reply_expected: bool) -> Self {
+        self.reply_expected = reply_expected;
+        self
+    }
+
+    /// Time left until the deadline: `None` without a deadline, zero once it has passed.
+    pub fn remaining(&self) -> Option<std::time::Duration> {
+        self.deadline_ms.map(|deadline_ms| {
+            let left = deadline_ms - chrono::Utc::now().timestamp_millis();
+            std::time::Duration::from_millis(left.max(0) as u64)
+        })
+    }
+
+    /// Returns true once the deadline has passed. Messages without a deadline never expire.
+    pub fn is_expired(&self) -> bool {
+        self.deadline_ms
+            .is_some_and(|deadline_ms| chrono::Utc::now().timestamp_millis() >= deadline_ms)
+    }
+
+    /// Envelope for a message sent in response to `event_id`.
+    ///
+    /// Keeps the thread, correlation, reply topic, hop budget, deadline and trace
+    /// context, records `event_id` as the cause, and does not expect a reply.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use loom_core::Envelope;
+    /// use std::time::Duration;
+    ///
+    /// let request = Envelope::new("task-1", "agent.planner")
+    ///     .with_timeout(Duration::from_secs(5))
+    ///     .with_reply_expected(true);
+    /// let reply = request.caused_by("evt-1", "agent.worker");
+    /// assert_eq!(reply.correlation_id, "task-1");
+    /// assert_eq!(reply.caused_by, "evt-1");
+    /// assert_eq!(reply.sender, "agent.worker");
+    /// assert_eq!(reply.deadline_ms, request.deadline_ms);
+    /// assert!(!reply.reply_expected);
+    /// ```
+    pub fn caused_by(&self, event_id: impl Into<String>, sender: impl Into<String>) -> Self {
+        Self {
+            sender: sender.into(),
+            timestamp_ms: chrono::Utc::now().timestamp_millis(),
+            caused_by: event_id.into(),
+            reply_expected: false,
+            ..self.clone()
+        }
+    }
+
     /// Returns the broadcast topic for this thread.
     ///
     /// Convenience method wrapping `ThreadTopicKind::Broadcast.topic(&self.thread_id)`.
@@ -587,6 +692,9 @@ impl Envelope {
             trace_id: String::new(),
             span_id: String::new(),
             trace_flags: String::new(),
+            caused_by: String::new(),
+            deadline_ms: None,
+            reply_expected: false,
         }
     }
 
@@ -608,9 +716,10 @@ impl Envelope {
     /// ```
     pub fn inject_trace_context(&mut self) {
         use opentelemetry::trace::TraceContextExt;
+        use tracing_opentelemetry::OpenTelemetrySpanExt;
 
         // Get the OpenTelemetry context from current tracing span
-        let otel_context = opentelemetry::Context::current();
+        let otel_context = tracing::Span::current().context();
         let span_ref = otel_context.span();
         let span_context = span_ref.span_context();
 
@@ -642,6 +751,16 @@ impl Envelope {
     /// env.extract_trace_context(); // Sets current span's parent
     /// ```
     pub fn extract_trace_context(&self) -> bool {
+        self.set_span_parent(&tracing::Span::current())
+    }
+
+    /// Set `span`'s parent from this envelope's trace context.
+    ///
+    /// Like `extract_trace_context`, but for a span that has not been entered yet, e.g.
+    /// one created to wrap a handler future with `Instrument::instrument`.
+    ///
+    /// Returns true if the envelope carried a valid trace context.
+    pub fn set_span_parent(&self, span: &tracing::Span) -> bool {
         use opentelemetry::trace::{
             SpanContext, SpanId, TraceContextExt, TraceFlags, TraceId, TraceState,
         };
@@ -685,8 +804,6 @@ impl Envelope {
         // Create context with remote parent
         let context = opentelemetry::Context::current().with_remote_span_context(span_context);
 
-        // Set as parent of current span
-        let span = tracing::Span::current();
         span.set_parent(context);
 
         true
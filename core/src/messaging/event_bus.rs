@@ -1,20 +1,72 @@
 //! Event Bus implementation with QoS-aware backpressure and topic routing.
 
+use crate::governor::{approx_event_bytes, MemoryComponent, PressureLevel, PRESSURE_TOPIC};
+use crate::messaging::backpressure::{
+    spill_dir_from_env, BackpressurePolicy, PolicyQueue, PushOutcome,
+};
+use crate::messaging::dead_letter::{
+    dead_letter_reasons, mark_dead, DeadLetter, DeadLetterFilter, DeadLetterStore,
+};
+use crate::messaging::envelope::keys;
+use crate::messaging::envelope::Envelope;
 use crate::messaging::event_ext::EventExt;
+use crate::messaging::rate_limit::{RateLimiter, PUBLISHER_KEY};
+use crate::messaging::receipts::{OutstandingAcks, ReceiptTracker, Target, RECEIPT_SUBSCRIBER_KEY};
+use crate::messaging::reliable::{dead_letter_keys, DeliveredEvent, Dispatcher, ReliableConfig};
+use crate::messaging::signing::{EnvelopeSigner, INGRESS_KEY};
+use crate::messaging::threads::{close_reasons, closed_event, thread_topics, ThreadTracker};
+use crate::namespace::NamespaceBridge;
 use crate::proto::{Event, QoSLevel};
-use crate::Result;
+use crate::{LoomError, Result};
 use async_trait::async_trait;
 use dashmap::DashMap;
 use opentelemetry::{
     global,
     metrics::{Counter, Histogram, UpDownCounter},
+    trace::TraceContextExt,
     KeyValue,
 };
 use serde::{Deserialize, Serialize};
-use std::sync::Arc;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, AtomicU8, Ordering};
+use std::sync::{Arc, RwLock};
 use std::time::Instant;
 use tokio::sync::{broadcast, mpsc};
-use tracing::{debug, info, warn, Span};
+use tokio::task::JoinHandle;
+use tracing::{debug, info, warn, Instrument, Span};
+use tracing_opentelemetry::OpenTelemetrySpanExt;
+
+/// Queue capacity for reliable subscriptions (bus -> dispatcher and dispatcher -> subscriber)
+/// and guaranteed ones
+const RELIABLE_QUEUE_CAP: usize = 2048;
+
+/// Channel capacity between a `DropOldest`/`SpillToDisk` queue and its subscriber; the
+/// queue itself holds the QoS capacity
+const POLICY_HANDOFF_CAP: usize = 1;
+
+/// How often unacked deliveries on critical topics and guaranteed subscriptions are checked
+/// against their deadline
+const RECEIPT_SWEEP_INTERVAL: std::time::Duration = std::time::Duration::from_millis(50);
+
+/// How often tracked threads are checked for idle expiry
+const THREAD_SWEEP_INTERVAL: std::time::Duration = std::time::Duration::from_millis(50);
+
+/// Returns whether a subscription `pattern` matches a published `topic`.
+///
+/// A pattern matches its exact topic. A pattern ending in `.*` also matches any topic
+/// that starts with the prefix followed by a dot (`market.price.*` matches
+/// `market.price.BTC`, but not `market.price` or `market.prices`).
+pub fn topic_matches(pattern: &str, topic: &str) -> bool {
+    if pattern == topic {
+        return true;
+    }
+    match pattern.strip_suffix(".*") {
+        Some(prefix) => topic
+            .strip_prefix(prefix)
+            .is_some_and(|rest| rest.starts_with('.')),
+        None => false,
+    }
+}
 
 /// Event handler trait
 #[async_trait]
@@ -30,7 +82,21 @@ struct Subscription {
     topic: String,
     event_types: Vec<String>,
     qos: QoSLevel,
+    policy: BackpressurePolicy,
     sender: mpsc::Sender<Event>,
+    /// `DropOldest` and `SpillToDisk` only: queue drained into `sender`
+    queue: Option<Arc<PolicyQueue>>,
+    /// `QosGuaranteed` only: receipt settings for every delivery
+    guaranteed: Option<ReliableConfig>,
+}
+
+impl Subscription {
+    /// Stop the forwarder of a queued subscription once it has delivered what is queued
+    fn close(&self) {
+        if let Some(ref queue) = self.queue {
+            queue.close();
+        }
+    }
 }
 
 /// Event bus statistics
@@ -41,6 +107,29 @@ pub struct EventBusStats {
     pub active_subscriptions: usize,
     pub backlog_size: usize,
     pub dropped_events: u64,
+    /// `QosReliable` redeliveries after ack timeout or nack
+    pub redelivered: u64,
+    /// Events given up on and kept in the dead-letter store (see `messaging::dead_letter`)
+    pub dead_lettered: u64,
+    /// Queued events dropped to make room for newer ones (`DropOldest`)
+    #[serde(default)]
+    pub dropped_oldest: u64,
+    /// Incoming events dropped on a full queue or over the backpressure threshold
+    /// (`DropNewest`)
+    #[serde(default)]
+    pub dropped_newest: u64,
+    /// Deliveries that waited for queue capacity (`Block`)
+    #[serde(default)]
+    pub blocked: u64,
+    /// Events written to disk by full queues (`SpillToDisk`)
+    #[serde(default)]
+    pub spilled: u64,
+    /// Publishes rejected by a publisher or topic rate limit
+    #[serde(default)]
+    pub rate_limited: u64,
+    /// Bridge publishes rejected for a missing or invalid signature
+    #[serde(default)]
+    pub signature_rejected: u64,
 }
 
 /// Event bus core implementation
@@ -64,6 +153,43 @@ pub struct EventBus {
     // Flow tracker for event flow visualization (optional)
     flow_tracker: Option<Arc<crate::dashboard::FlowTracker>>,
 
+    // Event-to-metric rules evaluated on publish (optional)
+    event_metrics: Option<Arc<crate::event_metrics::EventMetrics>>,
+
+    // Differentially private usage counts exported upstream (optional)
+    usage_exporter: Option<Arc<crate::usage_export::UsageExporter>>,
+
+    // Verifies signatures of events published through the Bridge (optional)
+    signer: Option<Arc<EnvelopeSigner>>,
+
+    // Memory pressure level pushed by the MemoryGovernor (PressureLevel::as_u8)
+    memory_pressure: AtomicU8,
+
+    // Critical topics and their outstanding delivery receipts
+    receipts: Arc<ReceiptTracker>,
+
+    // Open threads, closed when idle past their TTL
+    threads: Arc<ThreadTracker>,
+
+    // Directory for `SpillToDisk` overflow files
+    spill_dir: PathBuf,
+
+    // Cross-namespace publishes explicitly allowed
+    namespace_bridges: RwLock<Vec<NamespaceBridge>>,
+
+    // Per-publisher and per-topic token buckets
+    rate_limits: Arc<RateLimiter>,
+
+    // Events given up on
+    dead_letters: Arc<DeadLetterStore>,
+
+    // Topic patterns and the JSON schema their payloads must match
+    topic_schemas: RwLock<Vec<(String, serde_json::Value)>>,
+
+    // Running totals for the average published event size
+    published_bytes: AtomicU64,
+    published_events: AtomicU64,
+
     // OpenTelemetry metrics
     published_counter: Counter<u64>,
     delivered_counter: Counter<u64>,
@@ -71,6 +197,10 @@ pub struct EventBus {
     backlog_gauge: UpDownCounter<i64>,
     active_subscriptions_gauge: UpDownCounter<i64>,
     publish_latency: Histogram<f64>,
+    redelivered_counter: Counter<u64>,
+    dead_lettered_counter: Counter<u64>,
+    blocked_counter: Counter<u64>,
+    spilled_counter: Counter<u64>,
 }
 
 impl EventBus {
@@ -110,6 +240,26 @@ impl EventBus {
             .with_description("Event publish latency in milliseconds")
             .init();
 
+        let redelivered_counter = meter
+            .u64_counter("loom.event_bus.redelivered_total")
+            .with_description("Total number of reliable events redelivered")
+            .init();
+
+        let dead_lettered_counter = meter
+            .u64_counter("loom.event_bus.dead_lettered_total")
+            .with_description("Total number of events given up on and dead-lettered")
+            .init();
+
+        let blocked_counter = meter
+            .u64_counter("loom.event_bus.blocked_total")
+            .with_description("Total number of deliveries that waited for queue capacity")
+            .init();
+
+        let spilled_counter = meter
+            .u64_counter("loom.event_bus.spilled_total")
+            .with_description("Total number of events spilled to disk by full queues")
+            .init();
+
         Ok(Self {
             subscriptions: Arc::new(DashMap::new()),
             broadcast_tx,
@@ -117,12 +267,29 @@ impl EventBus {
             backpressure_threshold: 10_000,
             dashboard_broadcaster: None,
             flow_tracker: None,
+            event_metrics: None,
+            usage_exporter: None,
+            signer: None,
+            memory_pressure: AtomicU8::new(PressureLevel::Normal.as_u8()),
+            receipts: Arc::new(ReceiptTracker::new()),
+            threads: Arc::new(ThreadTracker::new()),
+            spill_dir: spill_dir_from_env(),
+            namespace_bridges: RwLock::new(Vec::new()),
+            rate_limits: Arc::new(RateLimiter::new()),
+            dead_letters: Arc::new(DeadLetterStore::new()),
+            topic_schemas: RwLock::new(Vec::new()),
+            published_bytes: AtomicU64::new(0),
+            published_events: AtomicU64::new(0),
             published_counter,
             delivered_counter,
             dropped_counter,
             backlog_gauge,
             active_subscriptions_gauge,
             publish_latency,
+            redelivered_counter,
+            dead_lettered_counter,
+            blocked_counter,
+            spilled_counter,
         })
     }
 
@@ -133,6 +300,9 @@ impl EventBus {
 
     pub async fn shutdown(&self) -> Result<()> {
         info!("Event Bus shutting down");
+        for entry in self.subscriptions.iter() {
+            entry.value().iter().for_each(Subscription::close);
+        }
         self.subscriptions.clear();
         Ok(())
     }
@@ -147,13 +317,246 @@ impl EventBus {
         self.flow_tracker = Some(flow_tracker);
     }
 
+    /// Set the directory `SpillToDisk` subscriptions write their overflow to
+    pub fn set_spill_dir(&mut self, dir: impl Into<PathBuf>) {
+        self.spill_dir = dir.into();
+    }
+
+    /// Set event-to-metric rules evaluated for every published event
+    pub fn set_event_metrics(&mut self, metrics: Arc<crate::event_metrics::EventMetrics>) {
+        self.event_metrics = Some(metrics);
+    }
+
+    /// Set the usage exporter counting every published event
+    pub fn set_usage_exporter(&mut self, exporter: Arc<crate::usage_export::UsageExporter>) {
+        self.usage_exporter = Some(exporter);
+    }
+
+    /// Set the signer verifying events published through the Bridge (see
+    /// `messaging::signing`)
+    pub fn set_signer(&mut self, signer: Arc<EnvelopeSigner>) {
+        self.signer = Some(signer);
+    }
+
+    /// Set the store keeping events given up on (in memory by default, see
+    /// `DeadLetterStore::from_env`)
+    pub fn set_dead_letter_store(&mut self, store: Arc<DeadLetterStore>) {
+        self.dead_letters = store;
+    }
+
+    /// Signer verifying events published through the Bridge, if any
+    pub fn signer(&self) -> Option<&Arc<EnvelopeSigner>> {
+        self.signer.as_ref()
+    }
+
+    /// Memory pressure level last pushed by the governor
+    pub fn memory_pressure(&self) -> PressureLevel {
+        PressureLevel::from_u8(self.memory_pressure.load(Ordering::Relaxed))
+    }
+
+    /// Whether deliveries to subscribers of `qos` are shed at the current pressure level.
+    /// Lowest QoS goes first; realtime queues are small and reliable ones promise
+    /// delivery, so neither is shed.
+    fn sheds_qos(&self, qos: QoSLevel) -> bool {
+        match self.memory_pressure() {
+            PressureLevel::Normal => false,
+            PressureLevel::Elevated => qos == QoSLevel::QosBackground,
+            PressureLevel::Critical => {
+                matches!(qos, QoSLevel::QosBatched | QoSLevel::QosBackground)
+            }
+        }
+    }
+
+    /// Allow events stamped with `bridge.from` to be published to `bridge.to`'s topics
+    /// matching `bridge.topics`; adding an identical bridge again is a no-op
+    pub fn bridge_namespaces(&self, bridge: NamespaceBridge) {
+        let mut bridges = self.namespace_bridges.write().unwrap();
+        if !bridges.contains(&bridge) {
+            info!(from = %bridge.from, to = %bridge.to, topics = %bridge.topics, "Namespaces bridged");
+            bridges.push(bridge);
+        }
+    }
+
+    /// Remove a bridge added by `bridge_namespaces`; returns whether it existed
+    pub fn unbridge_namespaces(&self, bridge: &NamespaceBridge) -> bool {
+        let mut bridges = self.namespace_bridges.write().unwrap();
+        let before = bridges.len();
+        bridges.retain(|b| b != bridge);
+        bridges.len() != before
+    }
+
+    /// Bridges currently allowing cross-namespace publishes
+    pub fn namespace_bridges(&self) -> Vec<NamespaceBridge> {
+        self.namespace_bridges.read().unwrap().clone()
+    }
+
+    /// Publisher and topic rate limits (see `messaging::rate_limit`)
+    pub fn rate_limits(&self) -> &Arc<RateLimiter> {
+        &self.rate_limits
+    }
+
+    /// Take a token for the event's publisher (the ingress publisher, else envelope
+    /// sender, else source) and topic
+    fn check_rate_limits(&self, topic: &str, event: &Event, ingress: Option<&str>) -> Result<()> {
+        if !self.rate_limits.is_enabled() {
+            return Ok(());
+        }
+        let publisher = ingress
+            .or_else(|| event.sender())
+            .or_else(|| (!event.source.is_empty()).then_some(event.source.as_str()));
+        self.rate_limits.check(publisher, topic).map_err(|limited| {
+            self.update_stats_and_get(topic, |stats| {
+                stats.rate_limited += 1;
+                0
+            });
+            debug!(target: "event_bus", topic = %topic, event_id = %event.id, reason = %limited, "Publish rate limited");
+            LoomError::RateLimited(limited)
+        })
+    }
+
+    /// Verify an event published through the Bridge: a signature it carries must match
+    /// `topic` and be fresh, and topics requiring one reject it unsigned. Only verified events keep
+    /// `SIGNATURE_VERIFIED`; the ingress marker is removed either way.
+    ///
+    /// Agents in a namespace sign, and are required to sign, the namespace-relative
+    /// topics they publish to.
+    fn check_signature(&self, topic: &str, event: &mut Event) -> Result<()> {
+        if event.metadata.remove(INGRESS_KEY).is_none() {
+            return Ok(());
+        }
+        event.metadata.remove(keys::SIGNATURE_VERIFIED);
+        let Some(ref signer) = self.signer else {
+            return Ok(());
+        };
+        let signed_topic = crate::tenancy::relative_topic(topic);
+        let signed = event.metadata.contains_key(keys::SIGNATURE);
+        if !signed && !signer.requires_signature(signed_topic) {
+            return Ok(());
+        }
+        signer.verify(signed_topic, event).map_err(|e| {
+            self.update_stats_and_get(topic, |stats| {
+                stats.signature_rejected += 1;
+                0
+            });
+            warn!(target: "event_bus", topic = %topic, event_id = %event.id, sender = ?event.sender(), reason = %e, "Rejecting publish with bad signature");
+            e
+        })?;
+        event
+            .metadata
+            .insert(keys::SIGNATURE_VERIFIED.to_string(), "true".to_string());
+        Ok(())
+    }
+
+    /// Tenant isolation (`tenancy::check_isolation`), relaxed by the namespace bridges
+    fn check_isolation(&self, topic: &str, event: &Event) -> Result<()> {
+        let Err(e) = crate::tenancy::check_isolation(topic, event) else {
+            return Ok(());
+        };
+        let from = event
+            .metadata
+            .get(crate::tenancy::TENANT_KEY)
+            .map(String::as_str)
+            .unwrap_or_default();
+        let bridged = self
+            .namespace_bridges
+            .read()
+            .unwrap()
+            .iter()
+            .any(|bridge| bridge.allows(from, topic));
+        if bridged {
+            debug!(namespace = %from, topic = %topic, "Cross-namespace publish bridged");
+            Ok(())
+        } else {
+            Err(e)
+        }
+    }
+
+    /// Reject publishes to topics matching `pattern` whose payload is not JSON matching
+    /// `schema` (the subset understood by `tools::schema::validate`). Rejected events are
+    /// dead-lettered with reason `schema_invalid`. Setting a pattern again replaces its
+    /// schema.
+    pub fn set_topic_schema(&self, pattern: impl Into<String>, schema: serde_json::Value) {
+        let pattern = pattern.into();
+        let mut schemas = self.topic_schemas.write().unwrap();
+        schemas.retain(|(p, _)| *p != pattern);
+        info!(pattern = %pattern, "Topic schema set");
+        schemas.push((pattern, schema));
+    }
+
+    /// Stop validating payloads on `pattern`; returns whether it had a schema
+    pub fn remove_topic_schema(&self, pattern: &str) -> bool {
+        let mut schemas = self.topic_schemas.write().unwrap();
+        let before = schemas.len();
+        schemas.retain(|(p, _)| p != pattern);
+        schemas.len() != before
+    }
+
+    /// Validate the payload against the schemas of every pattern matching `topic`
+    fn check_schema(&self, topic: &str, event: &Event) -> Result<()> {
+        let failure = {
+            let schemas = self.topic_schemas.read().unwrap();
+            let mut matching = schemas
+                .iter()
+                .filter(|(pattern, _)| topic_matches(pattern, topic))
+                .peekable();
+            if matching.peek().is_none() {
+                return Ok(());
+            }
+            match serde_json::from_slice::<serde_json::Value>(&event.payload) {
+                Err(e) => Some(format!("payload is not JSON: {e}")),
+                Ok(payload) => matching
+                    .find_map(|(_, schema)| crate::tools::schema::validate(schema, &payload).err()),
+            }
+        };
+        let Some(reason) = failure else {
+            return Ok(());
+        };
+        warn!(target: "event_bus", topic = %topic, event_id = %event.id, %reason, "Dead-lettering publish that does not match the topic schema");
+        let mut marked = event.clone();
+        mark_dead(
+            &mut marked,
+            topic,
+            None,
+            dead_letter_reasons::SCHEMA_INVALID,
+            0,
+        );
+        self.keep_dead_letter(&marked);
+        Err(LoomError::EventBusError(format!(
+            "Event {} does not match the schema of {topic}: {reason}",
+            event.id
+        )))
+    }
+
     /// Publish event to topic
-    #[tracing::instrument(skip(self, event), fields(topic = %topic, event_id = %event.id, event_type = %event.r#type, qos_level = "unknown"))]
     pub async fn publish(&self, topic: &str, mut event: Event) -> Result<u64> {
+        let publisher = event.metadata.remove(PUBLISHER_KEY);
+        self.check_signature(topic, &mut event)?;
+        // Events stamped with a tenant stay inside that tenant's topic namespace
+        self.check_isolation(topic, &event)?;
+        self.check_rate_limits(topic, &event, publisher.as_deref())?;
+        self.check_schema(topic, &event)?;
+        self.deliver(topic, event).await
+    }
+
+    /// Deliver an event that passed the publish checks to the topic's subscribers
+    #[tracing::instrument(name = "publish", skip(self, event), fields(topic = %topic, event_id = %event.id, event_type = %event.r#type, qos_level = "unknown"))]
+    async fn deliver(&self, topic: &str, mut event: Event) -> Result<u64> {
         let start_time = Instant::now();
+        self.threads.touch(topic);
 
-        // Inject trace context into event metadata for distributed tracing
+        // Continue the trace the event already carries (unless the caller's span is
+        // already part of it), then inject this span's context so subscribers become
+        // children of the publish
         let mut envelope = crate::messaging::Envelope::from_event(&event);
+        let current_trace = Span::current()
+            .context()
+            .span()
+            .span_context()
+            .trace_id()
+            .to_string();
+        if envelope.trace_id != current_trace {
+            envelope.extract_trace_context();
+        }
         envelope.inject_trace_context();
         envelope.attach_to_event(&mut event);
 
@@ -161,11 +564,6 @@ impl EventBus {
 
         // Broadcast to Dashboard (if enabled)
         if let Some(ref broadcaster) = self.dashboard_broadcaster {
-            let payload_preview = String::from_utf8_lossy(&event.payload)
-                .chars()
-                .take(100)
-                .collect::<String>();
-
             broadcaster.broadcast(crate::dashboard::DashboardEvent {
                 timestamp: chrono::Utc::now().to_rfc3339(),
                 event_type: crate::dashboard::DashboardEventType::EventPublished,
@@ -174,7 +572,7 @@ impl EventBus {
                 sender: event.sender().map(|s| s.to_string()),
                 thread_id: event.thread_id().map(|s| s.to_string()),
                 correlation_id: event.correlation_id().map(|s| s.to_string()),
-                payload_preview,
+                payload_preview: crate::dashboard::preview_text(&event.payload),
                 trace_id: envelope.trace_id.clone(),
             });
         }
@@ -186,6 +584,17 @@ impl EventBus {
             }
         }
 
+        if let Some(ref metrics) = self.event_metrics {
+            metrics.observe(topic, &event);
+        }
+        if let Some(ref exporter) = self.usage_exporter {
+            exporter.observe(&event);
+        }
+
+        self.published_bytes
+            .fetch_add(approx_event_bytes(&event) as u64, Ordering::Relaxed);
+        self.published_events.fetch_add(1, Ordering::Relaxed);
+
         // Record published metric
         self.published_counter.add(
             1,
@@ -233,21 +642,22 @@ impl EventBus {
         // Then, check for wildcard patterns (e.g., "market.price.*" matches "market.price.BTC")
         for entry in self.subscriptions.iter() {
             let pattern = entry.key().as_str();
-            if let Some(prefix) = pattern.strip_suffix(".*") {
-                // Check if topic starts with this prefix followed by a dot
-                if topic.len() > prefix.len()
-                    && topic.starts_with(prefix)
-                    && topic.as_bytes().get(prefix.len()) == Some(&b'.')
-                {
-                    // This pattern matches the topic
-                    all_matching_subs.extend(entry.value().iter().cloned());
-                }
+            if pattern.ends_with(".*") && topic_matches(pattern, topic) {
+                all_matching_subs.extend(entry.value().iter().cloned());
             }
         }
 
+        // Deliveries on critical topics are tracked until each subscriber acks them
+        let critical = self.receipts.config_for(topic);
+
         if !all_matching_subs.is_empty() {
             let mut delivered = 0;
             let mut dropped = 0;
+            let mut shed = 0;
+            let mut dropped_newest = 0;
+            let mut dropped_oldest = 0;
+            let mut blocked = 0;
+            let mut spilled = 0;
 
             for sub in &all_matching_subs {
                 // Check event type filtering
@@ -255,6 +665,13 @@ impl EventBus {
                     continue;
                 }
 
+                // Under memory pressure, stop growing low-QoS queues (pressure and critical
+                // events still flow)
+                if topic != PRESSURE_TOPIC && critical.is_none() && self.sheds_qos(sub.qos) {
+                    shed += 1;
+                    continue;
+                }
+
                 // Create a span for delivery to this subscriber
                 let delivery_span = tracing::debug_span!(
                     "event_bus.deliver",
@@ -264,111 +681,91 @@ impl EventBus {
                 );
                 let _guard = delivery_span.enter();
 
-                // Handle based on QoS level
-                match sub.qos {
-                    QoSLevel::QosRealtime => {
-                        // Realtime mode: drop aggressively when backpressured, and drop on full queue
+                // Tracked before sending so an ack can never arrive ahead of its receipt;
+                // a critical delivery dropped below is retried once its ack times out
+                let receipt = critical.as_ref().or(sub.guaranteed.as_ref());
+                let delivery = self.prepare_delivery(sub, topic, &event, receipt);
+
+                // Handle based on the subscription's backpressure policy
+                let accepted = match sub.policy {
+                    BackpressurePolicy::DropNewest => {
+                        // Never wait: drop when the topic is backpressured or the queue is full
                         if over_threshold {
                             dropped += 1;
-                            tracing::Span::current().record("delivered", false);
-                            continue;
-                        }
-                        if sub.sender.try_send(event.clone()).is_ok() {
-                            delivered += 1;
-                            tracing::Span::current().record("delivered", true);
-
-                            // Record flow in FlowTracker (EventBus -> subscriber)
-                            if let Some(ref flow_tracker) = self.flow_tracker {
-                                let flow_tracker_clone = Arc::clone(flow_tracker);
-                                let sub_id = sub.id.clone();
-                                let topic_clone = topic.to_string();
-                                tokio::spawn(async move {
-                                    flow_tracker_clone
-                                        .record_flow("EventBus", &sub_id, &topic_clone)
-                                        .await;
-                                });
-                            }
-
-                            // Broadcast EventDelivered to Dashboard
-                            if let Some(ref broadcaster) = self.dashboard_broadcaster {
-                                broadcaster.broadcast(crate::dashboard::DashboardEvent {
-                                    timestamp: chrono::Utc::now().to_rfc3339(),
-                                    event_type:
-                                        crate::dashboard::DashboardEventType::EventDelivered,
-                                    event_id: event.id.clone(),
-                                    topic: topic.to_string(),
-                                    sender: event.sender().map(|s| s.to_string()),
-                                    thread_id: event.thread_id().map(|s| s.to_string()),
-                                    correlation_id: event.correlation_id().map(|s| s.to_string()),
-                                    payload_preview: String::from_utf8_lossy(&event.payload)
-                                        .chars()
-                                        .take(100)
-                                        .collect::<String>(),
-                                    trace_id: envelope.trace_id.clone(),
-                                });
-                            }
+                            dropped_newest += 1;
+                            false
                         } else {
-                            dropped += 1;
-                            warn!("Dropped realtime event for subscription {}", sub.id);
-                        }
-                    }
-                    QoSLevel::QosBatched | QoSLevel::QosBackground => {
-                        // Batch/background mode: queue (bounded mpsc); await if necessary
-                        match sub.sender.send(event.clone()).await {
-                            Ok(_) => {
-                                delivered += 1;
-
-                                // Record flow in FlowTracker (EventBus -> subscriber)
-                                if let Some(ref flow_tracker) = self.flow_tracker {
-                                    let flow_tracker_clone = Arc::clone(flow_tracker);
-                                    let sub_id = sub.id.clone();
-                                    let topic_clone = topic.to_string();
-                                    tokio::spawn(async move {
-                                        flow_tracker_clone
-                                            .record_flow("EventBus", &sub_id, &topic_clone)
-                                            .await;
-                                    });
-                                }
-
-                                // Broadcast EventDelivered to Dashboard
-                                if let Some(ref broadcaster) = self.dashboard_broadcaster {
-                                    broadcaster.broadcast(crate::dashboard::DashboardEvent {
-                                        timestamp: chrono::Utc::now().to_rfc3339(),
-                                        event_type:
-                                            crate::dashboard::DashboardEventType::EventDelivered,
-                                        event_id: event.id.clone(),
-                                        topic: topic.to_string(),
-                                        sender: event.sender().map(|s| s.to_string()),
-                                        thread_id: event.thread_id().map(|s| s.to_string()),
-                                        correlation_id: event
-                                            .correlation_id()
-                                            .map(|s| s.to_string()),
-                                        payload_preview: String::from_utf8_lossy(&event.payload)
-                                            .chars()
-                                            .take(100)
-                                            .collect::<String>(),
-                                        trace_id: envelope.trace_id.clone(),
-                                    });
+                            match sub.sender.try_send(delivery) {
+                                Ok(()) => true,
+                                Err(e) => {
+                                    dropped += 1;
+                                    if sub.sender.is_closed() {
+                                        self.receipts.untrack(&sub.id, &event.id);
+                                        self.dead_letter_undelivered(topic, &sub.id, &event);
+                                    } else if matches!(e, mpsc::error::TrySendError::Full(_)) {
+                                        dropped_newest += 1;
+                                    }
+                                    warn!("Dropped event for subscription {}", sub.id);
+                                    false
                                 }
                             }
+                        }
+                    }
+                    BackpressurePolicy::Block => {
+                        // Queue (bounded mpsc); await capacity if necessary
+                        if sub.sender.capacity() == 0 && !sub.sender.is_closed() {
+                            blocked += 1;
+                        }
+                        match sub.sender.send(delivery).await {
+                            Ok(()) => true,
                             Err(_) => {
                                 dropped += 1;
-                                // 🔧 More detailed logging for channel full errors
+                                self.receipts.untrack(&sub.id, &event.id);
+                                self.dead_letter_undelivered(topic, &sub.id, &event);
                                 warn!(
                                     subscription_id = %sub.id,
                                     topic = %topic,
                                     qos = ?sub.qos,
-                                    "Failed to send event to subscription - channel may be full"
+                                    "Failed to send event to subscription - channel closed"
                                 );
+                                false
+                            }
+                        }
+                    }
+                    BackpressurePolicy::DropOldest | BackpressurePolicy::SpillToDisk => {
+                        match sub.queue.as_ref().map(|queue| queue.push(delivery)) {
+                            Some(PushOutcome::Queued) => true,
+                            Some(PushOutcome::DroppedOldest) => {
+                                dropped_oldest += 1;
+                                true
+                            }
+                            Some(PushOutcome::Spilled) => {
+                                spilled += 1;
+                                true
+                            }
+                            Some(PushOutcome::Rejected) | None => {
+                                dropped += 1;
+                                self.receipts.untrack(&sub.id, &event.id);
+                                self.dead_letter_undelivered(topic, &sub.id, &event);
+                                false
                             }
                         }
                     }
+                };
+                tracing::Span::current().record("delivered", accepted);
+                if accepted {
+                    delivered += 1;
+                    self.record_delivery(&sub.id, topic, &event, &envelope.trace_id);
                 }
             }
 
             self.update_stats(topic, |stats| {
                 stats.total_delivered += delivered;
-                stats.dropped_events += dropped;
+                stats.dropped_events += dropped + dropped_oldest + shed;
+                stats.dropped_newest += dropped_newest;
+                stats.dropped_oldest += dropped_oldest;
+                stats.blocked += blocked;
+                stats.spilled += spilled;
                 stats.backlog_size = stats.backlog_size.saturating_sub(1);
             });
 
@@ -391,6 +788,32 @@ impl EventBus {
                     ],
                 );
             }
+            if dropped_oldest > 0 {
+                self.dropped_counter.add(
+                    dropped_oldest,
+                    &[
+                        KeyValue::new("topic", topic.to_string()),
+                        KeyValue::new("reason", "drop_oldest"),
+                    ],
+                );
+            }
+            if blocked > 0 {
+                self.blocked_counter
+                    .add(blocked, &[KeyValue::new("topic", topic.to_string())]);
+            }
+            if spilled > 0 {
+                self.spilled_counter
+                    .add(spilled, &[KeyValue::new("topic", topic.to_string())]);
+            }
+            if shed > 0 {
+                self.dropped_counter.add(
+                    shed,
+                    &[
+                        KeyValue::new("topic", topic.to_string()),
+                        KeyValue::new("reason", "memory_pressure"),
+                    ],
+                );
+            }
 
             // Update backlog gauge (decrement)
             self.backlog_gauge
@@ -402,7 +825,7 @@ impl EventBus {
                 .record(elapsed_ms, &[KeyValue::new("topic", topic.to_string())]);
 
             Span::current().record("delivered_count", delivered);
-            Span::current().record("dropped_count", dropped);
+            Span::current().record("dropped_count", dropped + dropped_oldest + shed);
             Span::current().record("latency_ms", elapsed_ms);
 
             Ok(delivered)
@@ -426,36 +849,435 @@ impl EventBus {
         }
     }
 
+    /// Record an accepted delivery with the FlowTracker and the Dashboard
+    fn record_delivery(&self, sub_id: &str, topic: &str, event: &Event, trace_id: &str) {
+        // Record flow in FlowTracker (EventBus -> subscriber)
+        if let Some(ref flow_tracker) = self.flow_tracker {
+            let flow_tracker_clone = Arc::clone(flow_tracker);
+            let sub_id = sub_id.to_string();
+            let topic_clone = topic.to_string();
+            tokio::spawn(async move {
+                flow_tracker_clone
+                    .record_flow("EventBus", &sub_id, &topic_clone)
+                    .await;
+            });
+        }
+
+        // Broadcast EventDelivered to Dashboard
+        if let Some(ref broadcaster) = self.dashboard_broadcaster {
+            broadcaster.broadcast(crate::dashboard::DashboardEvent {
+                timestamp: chrono::Utc::now().to_rfc3339(),
+                event_type: crate::dashboard::DashboardEventType::EventDelivered,
+                event_id: event.id.clone(),
+                topic: topic.to_string(),
+                sender: event.sender().map(|s| s.to_string()),
+                thread_id: event.thread_id().map(|s| s.to_string()),
+                correlation_id: event.correlation_id().map(|s| s.to_string()),
+                payload_preview: crate::dashboard::preview_text(&event.payload),
+                trace_id: trace_id.to_string(),
+            });
+        }
+    }
+
+    /// Publish several events to one topic, in order; returns the total deliveries.
+    ///
+    /// Signatures, tenant isolation and schemas are checked for the whole batch up front,
+    /// so a batch failing any of them is rejected as a whole. Rate limits apply per
+    /// event: a batch stops at its first rate-limited event, with the events before it
+    /// published. Signed events left unpublished may be sent again.
+    #[tracing::instrument(skip(self, events), fields(topic = %topic, batch_size = events.len()))]
+    pub async fn publish_batch(&self, topic: &str, events: Vec<Event>) -> Result<u64> {
+        let mut admitted = Vec::with_capacity(events.len());
+        for mut event in events {
+            let publisher = event.metadata.remove(PUBLISHER_KEY);
+            let checked = self
+                .check_signature(topic, &mut event)
+                .and_then(|()| self.check_isolation(topic, &event))
+                .and_then(|()| self.check_schema(topic, &event));
+            if let Err(e) = checked {
+                self.forget_verified(admitted.iter().map(|(event, _)| event));
+                return Err(e);
+            }
+            admitted.push((event, publisher));
+        }
+        let mut delivered = 0;
+        let mut admitted = admitted.into_iter();
+        while let Some((event, publisher)) = admitted.next() {
+            if let Err(e) = self.check_rate_limits(topic, &event, publisher.as_deref()) {
+                self.forget_verified(
+                    std::iter::once(&event)
+                        .chain(admitted.as_slice().iter().map(|(event, _)| event)),
+                );
+                return Err(e);
+            }
+            delivered += self.deliver(topic, event).await?;
+        }
+        Ok(delivered)
+    }
+
+    /// Let signed events of a batch that were verified but not published be sent again
+    fn forget_verified<'a>(&self, events: impl Iterator<Item = &'a Event>) {
+        let Some(ref signer) = self.signer else {
+            return;
+        };
+        for event in events {
+            if event.metadata.contains_key(keys::SIGNATURE_VERIFIED) {
+                signer.forget(&event.id);
+            }
+        }
+    }
+
+    /// The event as delivered to `sub`: on critical topics and to `QosGuaranteed`
+    /// subscriptions, stamped with the subscriber and tracked until acked (`QosReliable`
+    /// subscriptions settle through their own acks)
+    fn prepare_delivery(
+        &self,
+        sub: &Subscription,
+        topic: &str,
+        event: &Event,
+        receipt: Option<&ReliableConfig>,
+    ) -> Event {
+        let mut delivery = event.clone();
+        if let Some(config) = receipt {
+            if sub.qos != QoSLevel::QosReliable {
+                delivery
+                    .metadata
+                    .insert(RECEIPT_SUBSCRIBER_KEY.to_string(), sub.id.clone());
+                self.receipts.track(
+                    &sub.id,
+                    topic,
+                    &delivery,
+                    config.clone(),
+                    Target::Subscription(sub.sender.clone()),
+                );
+            }
+        }
+        delivery
+    }
+
+    /// Require every subscriber of topics matching `pattern` to ack each delivery.
+    ///
+    /// Unacked deliveries are redelivered after `config.ack_timeout` and dead-lettered
+    /// after `config.max_deliveries` attempts. Marking a pattern again replaces its config.
+    pub fn mark_critical(self: &Arc<Self>, pattern: impl Into<String>, config: ReliableConfig) {
+        let pattern = pattern.into();
+        info!(pattern = %pattern, ack_timeout_ms = config.ack_timeout.as_millis() as u64, "Marked topic critical");
+        self.receipts.mark(pattern, config);
+        self.start_receipt_sweeper();
+    }
+
+    /// Start the task redelivering unacked receipts, unless it already runs
+    fn start_receipt_sweeper(self: &Arc<Self>) {
+        if !self.receipts.claim_sweeper() {
+            return;
+        }
+        let bus = Arc::downgrade(self);
+        tokio::spawn(async move {
+            let mut tick = tokio::time::interval(RECEIPT_SWEEP_INTERVAL);
+            tick.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            loop {
+                tick.tick().await;
+                let Some(bus) = bus.upgrade() else {
+                    break;
+                };
+                bus.sweep_receipts().await;
+            }
+        });
+    }
+
+    /// Stop requiring acks on `pattern`; outstanding receipts still settle
+    pub fn unmark_critical(&self, pattern: &str) -> bool {
+        self.receipts.unmark(pattern)
+    }
+
+    /// Config of the critical pattern covering `topic`, if any
+    pub fn critical_config(&self, topic: &str) -> Option<ReliableConfig> {
+        self.receipts.config_for(topic)
+    }
+
+    /// Delivery receipts on critical topics
+    pub fn receipts(&self) -> &Arc<ReceiptTracker> {
+        &self.receipts
+    }
+
+    /// Ack the delivery of `event_id` to `subscriber` (a subscription id, or the
+    /// downstream id given to `ReceiptTracker::hand_off`)
+    pub fn ack(&self, subscriber: &str, event_id: &str) -> bool {
+        self.receipts.ack(subscriber, event_id)
+    }
+
+    /// Ack a delivery received from this bus; false if it needed no ack
+    pub fn ack_event(&self, event: &Event) -> bool {
+        self.receipts.ack_event(event)
+    }
+
+    /// Unacked deliveries on critical topics, per subscriber and topic
+    pub fn outstanding_acks(&self) -> Vec<OutstandingAcks> {
+        self.receipts.outstanding()
+    }
+
+    /// Redeliver or dead-letter receipts whose ack timed out
+    async fn sweep_receipts(&self) {
+        let outcome = self.receipts.sweep(tokio::time::Instant::now());
+        for topic in outcome.redelivered {
+            self.record_redelivery(&topic, "ack_timeout");
+        }
+        for dead in outcome.dead {
+            warn!(
+                target: "event_bus",
+                subscriber = %dead.subscriber,
+                event_id = %dead.event.id,
+                attempts = dead.attempts,
+                reason = dead.reason,
+                dead_letter_topic = %dead.dead_letter_topic,
+                "Dead-lettering unacked critical event"
+            );
+            let mut event = dead.event;
+            mark_dead(
+                &mut event,
+                &dead.topic,
+                Some(&dead.subscriber),
+                dead.reason,
+                dead.attempts,
+            );
+            self.dead_letter(event, Some(&dead.dead_letter_topic)).await;
+        }
+    }
+
+    /// Close threads idle for longer than `ttl` (see `close_thread`). Threads opened
+    /// with their own TTL keep it; setting the TTL again replaces the default.
+    pub fn set_thread_ttl(self: &Arc<Self>, ttl: std::time::Duration) {
+        info!(ttl_ms = ttl.as_millis() as u64, "Thread idle TTL set");
+        if self.threads.set_default_ttl(ttl) {
+            self.start_thread_sweeper();
+        }
+    }
+
+    /// Track `thread_id`, closing it once idle for `ttl`; `None` keeps it open until
+    /// `close_thread` regardless of the default TTL
+    pub fn open_thread(self: &Arc<Self>, thread_id: &str, ttl: Option<std::time::Duration>) {
+        if self.threads.open(thread_id, ttl) {
+            self.start_thread_sweeper();
+        }
+    }
+
+    /// Close a thread: publish `thread.closed` on its broadcast topic, then drop every
+    /// subscription on its broadcast and reply topics and remove their stats. Subscribers
+    /// receive what was queued before the close, then their stream ends. Returns the
+    /// number of subscriptions dropped.
+    pub async fn close_thread(&self, thread_id: &str) -> Result<usize> {
+        self.close_thread_for(thread_id, close_reasons::CLOSED)
+            .await
+    }
+
+    /// Open threads tracked for idle expiry
+    pub fn threads(&self) -> &Arc<ThreadTracker> {
+        &self.threads
+    }
+
+    #[tracing::instrument(skip(self), fields(thread_id = %thread_id, reason = %reason))]
+    async fn close_thread_for(&self, thread_id: &str, reason: &str) -> Result<usize> {
+        let topics = thread_topics(thread_id);
+        let result = self
+            .publish(&topics[0], closed_event(thread_id, reason))
+            .await;
+        self.threads.forget(thread_id, reason);
+
+        let mut dropped = 0;
+        for topic in &topics {
+            if let Some((_, subs)) = self.subscriptions.remove(topic) {
+                subs.iter().for_each(Subscription::close);
+                dropped += subs.len();
+                self.active_subscriptions_gauge.add(
+                    -(subs.len() as i64),
+                    &[KeyValue::new("topic", topic.clone())],
+                );
+            }
+            self.stats.remove(topic);
+        }
+        info!(dropped, "Closed thread");
+        result.map(|_| dropped)
+    }
+
+    fn start_thread_sweeper(self: &Arc<Self>) {
+        let bus = Arc::downgrade(self);
+        tokio::spawn(async move {
+            let mut tick = tokio::time::interval(THREAD_SWEEP_INTERVAL);
+            tick.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            loop {
+                tick.tick().await;
+                let Some(bus) = bus.upgrade() else {
+                    break;
+                };
+                for thread_id in bus.threads.expired(tokio::time::Instant::now()) {
+                    if let Err(e) = bus
+                        .close_thread_for(&thread_id, close_reasons::EXPIRED)
+                        .await
+                    {
+                        warn!(target: "event_bus", thread_id = %thread_id, error = %e, "Failed to announce thread expiry");
+                    }
+                }
+            }
+        });
+    }
+
     /// Subscribe to topic
-    #[tracing::instrument(skip(self, event_types), fields(topic = %topic, subscription_id, qos = ?qos))]
+    ///
+    /// Events received on critical topics (see `mark_critical`) must be acked with
+    /// `ack_event` once processed, or they are redelivered. The subscription gets the
+    /// default backpressure policy of `qos` (see `BackpressurePolicy::default_for`).
     pub async fn subscribe(
         &self,
         topic: String,
         event_types: Vec<String>,
         qos: QoSLevel,
     ) -> Result<(String, mpsc::Receiver<Event>)> {
-        let subscription_id = format!("sub_{}_{}", topic, uuid::Uuid::new_v4());
+        self.subscribe_with_policy(
+            topic,
+            event_types,
+            qos,
+            BackpressurePolicy::default_for(qos),
+        )
+        .await
+    }
+
+    /// Subscribe to topic, choosing what happens when the subscription's queue is full.
+    ///
+    /// The queue holds as many events as the QoS level allows (512 realtime, 2048
+    /// batched, 4096 background); `SpillToDisk` overflow goes to `spill_dir`.
+    #[tracing::instrument(skip(self, event_types), fields(topic = %topic, subscription_id, qos = ?qos, policy = %policy))]
+    pub async fn subscribe_with_policy(
+        &self,
+        topic: String,
+        event_types: Vec<String>,
+        qos: QoSLevel,
+        policy: BackpressurePolicy,
+    ) -> Result<(String, mpsc::Receiver<Event>)> {
+        if qos == QoSLevel::QosReliable {
+            return Err(LoomError::EventBusError(
+                "QosReliable subscriptions need acks; use subscribe_reliable".to_string(),
+            ));
+        }
+        if qos == QoSLevel::QosGuaranteed {
+            return Err(LoomError::EventBusError(
+                "QosGuaranteed subscriptions need a receipt config; use subscribe_guaranteed"
+                    .to_string(),
+            ));
+        }
+        let (subscription_id, rx) = self.add_subscription(topic, event_types, qos, policy, None);
+        Span::current().record("subscription_id", &subscription_id);
+        Ok((subscription_id, rx))
+    }
+
+    /// Subscribe to topic with at-least-once delivery (`QosReliable`).
+    ///
+    /// Every `DeliveredEvent` must be acked; unacked or nacked events are redelivered
+    /// and, after `max_deliveries` attempts, published to the dead-letter topic. Unlike
+    /// other QoS levels, reliable deliveries are never dropped under backpressure or
+    /// memory pressure; publishers wait for queue capacity instead.
+    #[tracing::instrument(skip(self, event_types, config), fields(topic = %topic, subscription_id))]
+    pub async fn subscribe_reliable(
+        self: &Arc<Self>,
+        topic: String,
+        event_types: Vec<String>,
+        config: ReliableConfig,
+    ) -> Result<(String, mpsc::Receiver<DeliveredEvent>)> {
+        let (subscription_id, input) = self.add_subscription(
+            topic.clone(),
+            event_types,
+            QoSLevel::QosReliable,
+            BackpressurePolicy::Block,
+            None,
+        );
         Span::current().record("subscription_id", &subscription_id);
 
+        let (output, rx) = mpsc::channel(RELIABLE_QUEUE_CAP);
+        let dispatcher = Dispatcher {
+            topic,
+            subscription_id: subscription_id.clone(),
+            config,
+            bus: Arc::downgrade(self),
+            input,
+            output,
+        };
+        tokio::spawn(dispatcher.run());
+        Ok((subscription_id, rx))
+    }
+
+    /// Subscribe to topic with per-delivery receipts (`QosGuaranteed`).
+    ///
+    /// Every event is delivered stamped with `RECEIPT_SUBSCRIBER_KEY` and tracked like a
+    /// delivery on a critical topic, whether or not the topic is critical: it must be
+    /// acked (`ack` / `ack_event`) or it is redelivered after `config.ack_timeout`, with
+    /// `RECEIPT_ATTEMPT_KEY` counting attempts, and dead-lettered after
+    /// `config.max_deliveries`. Other subscribers of the topic are unaffected. The
+    /// Bridge uses these subscriptions for its guaranteed topics and hands each receipt
+    /// off to the agents it forwards the event to.
+    #[tracing::instrument(skip(self, event_types, config), fields(topic = %topic, subscription_id))]
+    pub async fn subscribe_guaranteed(
+        self: &Arc<Self>,
+        topic: String,
+        event_types: Vec<String>,
+        config: ReliableConfig,
+    ) -> Result<(String, mpsc::Receiver<Event>)> {
+        let (subscription_id, rx) = self.add_subscription(
+            topic,
+            event_types,
+            QoSLevel::QosGuaranteed,
+            BackpressurePolicy::Block,
+            Some(config),
+        );
+        Span::current().record("subscription_id", &subscription_id);
+        self.start_receipt_sweeper();
+        Ok((subscription_id, rx))
+    }
+
+    fn add_subscription(
+        &self,
+        topic: String,
+        event_types: Vec<String>,
+        qos: QoSLevel,
+        policy: BackpressurePolicy,
+        guaranteed: Option<ReliableConfig>,
+    ) -> (String, mpsc::Receiver<Event>) {
+        let subscription_id = format!("sub_{}_{}", topic, uuid::Uuid::new_v4());
+
         let cap = match qos {
             QoSLevel::QosRealtime => 512,    // Raise from 64 to 512
             QoSLevel::QosBatched => 2048,    // Raise from 1024 to 2048
             QoSLevel::QosBackground => 4096, // Keep unchanged
+            QoSLevel::QosReliable | QoSLevel::QosGuaranteed => RELIABLE_QUEUE_CAP,
+        };
+        let (tx, rx, queue) = if policy.is_queued() {
+            let (tx, rx) = mpsc::channel(POLICY_HANDOFF_CAP);
+            let spill_path = self.spill_dir.join(format!(
+                "{}.spill",
+                subscription_id.replace(|c: char| !c.is_ascii_alphanumeric(), "_")
+            ));
+            let queue = Arc::new(PolicyQueue::new(policy, cap, spill_path));
+            tokio::spawn(Arc::clone(&queue).forward(tx.clone()));
+            (tx, rx, Some(queue))
+        } else {
+            let (tx, rx) = mpsc::channel(cap);
+            (tx, rx, None)
         };
-        let (tx, rx) = mpsc::channel(cap);
 
         let subscription = Subscription {
             id: subscription_id.clone(),
             topic: topic.clone(),
             event_types,
             qos,
+            policy,
             sender: tx,
+            queue,
+            guaranteed,
         };
 
         self.subscriptions
             .entry(topic.clone())
             .or_default()
             .push(subscription);
+        self.threads.touch(&topic);
 
         self.update_stats(&topic, |stats| {
             stats.active_subscriptions += 1;
@@ -466,10 +1288,146 @@ impl EventBus {
             .add(1, &[KeyValue::new("topic", topic.clone())]);
 
         info!(
-            "Created subscription {} for topic {}",
-            subscription_id, topic
+            "Created subscription {} for topic {} ({})",
+            subscription_id, topic, policy
         );
-        Ok((subscription_id, rx))
+        (subscription_id, rx)
+    }
+
+    pub(crate) fn record_redelivery(&self, topic: &str, reason: &'static str) {
+        self.update_stats(topic, |stats| stats.redelivered += 1);
+        self.redelivered_counter.add(
+            1,
+            &[
+                KeyValue::new("topic", topic.to_string()),
+                KeyValue::new("reason", reason),
+            ],
+        );
+    }
+
+    /// Give up on an event marked by `mark_dead`: keep it in the dead-letter store and
+    /// publish it to `dead_letter_topic`, if any
+    pub(crate) async fn dead_letter(&self, marked: Event, dead_letter_topic: Option<&str>) {
+        self.keep_dead_letter(&marked);
+        if let Some(dead_letter_topic) = dead_letter_topic {
+            if let Err(e) = self.publish(dead_letter_topic, marked).await {
+                warn!(target: "event_bus", error = %e, "Failed to publish dead letter");
+            }
+        }
+    }
+
+    fn keep_dead_letter(&self, marked: &Event) {
+        let topic = marked
+            .metadata
+            .get(dead_letter_keys::TOPIC)
+            .map(String::as_str)
+            .unwrap_or_default();
+        self.update_stats(topic, |stats| stats.dead_lettered += 1);
+        self.dead_lettered_counter
+            .add(1, &[KeyValue::new("topic", topic.to_string())]);
+        if let Err(e) = self.dead_letters.record(marked) {
+            warn!(target: "event_bus", event_id = %marked.id, error = %e, "Failed to store dead letter");
+        }
+    }
+
+    /// Dead-letter `event` for a subscriber whose receiver was dropped
+    fn dead_letter_undelivered(&self, topic: &str, subscription_id: &str, event: &Event) {
+        // A dead letter on its way to a dead-letter topic is already stored
+        if event.metadata.contains_key(dead_letter_keys::REASON) {
+            return;
+        }
+        let mut marked = event.clone();
+        mark_dead(
+            &mut marked,
+            topic,
+            Some(subscription_id),
+            dead_letter_reasons::SUBSCRIBER_GONE,
+            1,
+        );
+        self.keep_dead_letter(&marked);
+    }
+
+    /// Store of the events given up on
+    pub fn dead_letter_store(&self) -> &Arc<DeadLetterStore> {
+        &self.dead_letters
+    }
+
+    /// Dead letters matching `filter`, newest first
+    pub fn dead_letters(&self, filter: &DeadLetterFilter) -> Vec<DeadLetter> {
+        self.dead_letters.list(filter)
+    }
+
+    /// Publish dead letter `id` again, to `topic` or else the topic it was published on,
+    /// and remove it from the store; returns the deliveries made.
+    ///
+    /// Dead letters of wildcard subscriptions only know the subscribed pattern, so they
+    /// need a `topic`. If the publish fails, the dead letter is kept (a publish rejected
+    /// by the topic schema is dead-lettered again, as a new dead letter).
+    pub async fn republish_dead_letter(&self, id: u64, topic: Option<&str>) -> Result<u64> {
+        let letter = self
+            .dead_letters
+            .get(id)
+            .ok_or_else(|| LoomError::EventBusError(format!("No dead letter {id}")))?;
+        let topic = topic.unwrap_or(letter.topic.as_str()).to_string();
+        if topic.is_empty() || topic.ends_with(".*") {
+            return Err(LoomError::EventBusError(format!(
+                "Dead letter {id} was recorded on '{topic}'; give a topic to publish it to"
+            )));
+        }
+        let delivered = self.publish(&topic, letter.event).await?;
+        self.dead_letters.remove(id)?;
+        info!(target: "event_bus", dead_letter_id = id, topic = %topic, delivered, "Re-published dead letter");
+        Ok(delivered)
+    }
+
+    /// Subscribe `handler` to topic.
+    ///
+    /// Each invocation runs in an `event.handle` span whose parent is taken from the
+    /// event's envelope, so in-process handlers join the publisher's trace. The task
+    /// ends after `unsubscribe`; handler errors are logged and do not stop it.
+    /// Deliveries on critical topics are acked when the handler succeeds.
+    pub async fn subscribe_handler(
+        &self,
+        topic: String,
+        event_types: Vec<String>,
+        qos: QoSLevel,
+        handler: Arc<dyn EventHandler>,
+    ) -> Result<(String, JoinHandle<()>)> {
+        let (subscription_id, mut rx) = self.subscribe(topic.clone(), event_types, qos).await?;
+        let sub_id = subscription_id.clone();
+        let receipts = Arc::clone(&self.receipts);
+        let handle = tokio::spawn(async move {
+            while let Some(event) = rx.recv().await {
+                let span = tracing::info_span!(
+                    parent: None,
+                    "event.handle",
+                    topic = %topic,
+                    subscription_id = %sub_id,
+                    event_id = %event.id,
+                    event_type = %event.r#type,
+                    trace_id = tracing::field::Empty,
+                    span_id = tracing::field::Empty
+                );
+                let env = Envelope::from_event(&event);
+                if env.set_span_parent(&span) {
+                    span.record("trace_id", tracing::field::display(&env.trace_id));
+                    span.record("span_id", tracing::field::display(&env.span_id));
+                }
+                let event_id = event.id.clone();
+                let needs_ack = event.metadata.contains_key(RECEIPT_SUBSCRIBER_KEY);
+                match handler.handle(event).instrument(span).await {
+                    // Critical deliveries are acked once handled; failures are redelivered
+                    Ok(()) if needs_ack => {
+                        receipts.ack(&sub_id, &event_id);
+                    }
+                    Ok(()) => {}
+                    Err(e) => {
+                        warn!(topic = %topic, event_id = %event_id, error = %e, "Event handler failed");
+                    }
+                }
+            }
+        });
+        Ok((subscription_id, handle))
     }
 
     /// Unsubscribe from topic
@@ -478,7 +1436,14 @@ impl EventBus {
         for mut entry in self.subscriptions.iter_mut() {
             let topic = entry.key().clone();
             let before_count = entry.value().len();
-            entry.value_mut().retain(|sub| sub.id != subscription_id);
+            entry.value_mut().retain(|sub| {
+                if sub.id == subscription_id {
+                    sub.close();
+                    false
+                } else {
+                    true
+                }
+            });
             let after_count = entry.value().len();
 
             if before_count != after_count {
@@ -501,6 +1466,42 @@ impl EventBus {
         self.stats.get(topic).map(|s| s.clone())
     }
 
+    /// Stats for every topic that has seen traffic or subscriptions
+    pub fn all_stats(&self) -> Vec<(String, EventBusStats)> {
+        self.stats
+            .iter()
+            .map(|e| (e.key().clone(), e.value().clone()))
+            .collect()
+    }
+
+    /// Number of topics (including wildcard patterns) with a subscription entry
+    pub fn topic_count(&self) -> usize {
+        self.subscriptions.len()
+    }
+
+    /// Number of live subscriptions across all topics
+    pub fn subscription_count(&self) -> usize {
+        self.subscriptions.iter().map(|e| e.value().len()).sum()
+    }
+
+    /// Events waiting for `subscription_id`: in its channel, its policy queue and its
+    /// spill file
+    pub fn queued_events(&self, subscription_id: &str) -> Option<usize> {
+        self.subscriptions.iter().find_map(|entry| {
+            entry
+                .value()
+                .iter()
+                .find(|s| s.id == subscription_id)
+                .map(|s| {
+                    let queued = match s.queue {
+                        Some(ref queue) => queue.len(),
+                        None => 0,
+                    };
+                    s.sender.max_capacity() - s.sender.capacity() + queued
+                })
+        })
+    }
+
     // Update stats helper function
     fn update_stats<F>(&self, topic: &str, f: F)
     where
@@ -524,6 +1525,50 @@ impl EventBus {
     }
 }
 
+/// Subscriber queues are the bus's memory. Queued events cannot be reclaimed from the
+/// publishing side, so the bus sheds by no longer enqueueing to low-QoS subscribers
+/// while pressure lasts (see `sheds_qos`), letting their queues drain.
+#[async_trait]
+impl MemoryComponent for EventBus {
+    fn name(&self) -> String {
+        "event_bus".to_string()
+    }
+
+    fn shed_priority(&self) -> u8 {
+        80
+    }
+
+    async fn memory_usage(&self) -> usize {
+        let queued: usize = self
+            .subscriptions
+            .iter()
+            .map(|e| {
+                e.value()
+                    .iter()
+                    .map(|s| {
+                        let queued = match s.queue {
+                            Some(ref queue) => queue.memory_len(),
+                            None => 0,
+                        };
+                        s.sender.max_capacity() - s.sender.capacity() + queued
+                    })
+                    .sum::<usize>()
+            })
+            .sum();
+        let events = self.published_events.load(Ordering::Relaxed);
+        let avg_bytes = self
+            .published_bytes
+            .load(Ordering::Relaxed)
+            .checked_div(events)
+            .unwrap_or(0) as usize;
+        queued * avg_bytes
+    }
+
+    async fn on_pressure(&self, level: PressureLevel) {
+        self.memory_pressure.store(level.as_u8(), Ordering::Relaxed);
+    }
+}
+
 // Helper trait for chaining calls
 trait Apply {
     fn apply<F>(&mut self, f: F)
@@ -2,17 +2,51 @@
 //!
 //! This module provides the core messaging infrastructure for Loom:
 //! - `EventBus`: Topic-based pub/sub with QoS and backpressure
+//! - `BackpressurePolicy`: What a full subscription queue does with new events
 //! - `Envelope`: Coordination metadata for thread/correlation/routing/tracing
 //! - `EventExt`: Fluent helpers for reading/writing envelope fields on Events
+//! - `DeliveredEvent`: Ack/nack handle for at-least-once (`QosReliable`) subscriptions
+//! - `ReceiptTracker`: Per-subscriber delivery receipts on critical topics
+//! - `DeadLetterStore`: Capped store of events given up on, queried with `EventBus::dead_letters`
+//! - `ThreadTracker`: Idle expiry and cleanup of thread-scoped topics
+//! - `RateLimiter`: Token-bucket publish limits per publisher and per topic
+//! - `EnvelopeSigner`: HMAC signatures verified on events published through the Bridge
 //! - `Collaborator`: Multi-agent collaboration patterns (request/reply, fanout, contract-net)
+//! - `aggregate`: Majority vote, best-of and summarize combinators over fanout replies
 
+pub mod aggregate;
+pub mod backpressure;
 pub mod collab;
+pub mod dead_letter;
 pub mod envelope;
 pub mod event_bus;
 pub mod event_ext;
+pub mod rate_limit;
+pub mod receipts;
+pub mod reliable;
+pub mod signing;
+pub mod threads;
 
 // Re-export key types for ergonomic access
-pub use collab::Collaborator;
+pub use aggregate::{
+    Aggregate, AttributedReply, Judgement, LlmJudge, ReplyJudge, VoteConfig, VoteOutcome,
+};
+pub use backpressure::{BackpressurePolicy, BACKPRESSURE_PARAM};
+pub use collab::{
+    BidConstraints, BidScorer, BidTerms, CollabRetryPolicy, Collaborator, ContractNetConfig,
+    IdempotencyCache,
+};
+pub use dead_letter::{
+    dead_letter_reasons, DeadLetter, DeadLetterFilter, DeadLetterStore,
+    DEFAULT_DEAD_LETTER_CAPACITY,
+};
 pub use envelope::{agent_reply_topic, Envelope, ThreadTopicKind};
-pub use event_bus::{EventBus, EventBusStats, EventHandler};
+pub use event_bus::{topic_matches, EventBus, EventBusStats, EventHandler};
 pub use event_ext::EventExt;
+pub use rate_limit::{
+    RateLimit, RateLimitScope, RateLimitStats, RateLimited, RateLimiter, PUBLISHER_KEY,
+};
+pub use receipts::{OutstandingAcks, ReceiptTracker, RECEIPT_SUBSCRIBER_KEY};
+pub use reliable::{DeliveredEvent, ReliableConfig};
+pub use signing::{EnvelopeSigner, BRIDGE_INGRESS, INGRESS_KEY};
+pub use threads::{OpenThread, ThreadTracker, THREAD_CLOSED};
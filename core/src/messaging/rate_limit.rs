@@ -0,0 +1,408 @@
+//! Token-bucket rate limits on publishing.
+//!
+//! `EventBus::rate_limits` holds limits per publisher and per topic pattern. The
+//! publisher of an event is the connection it came in on (`PUBLISHER_KEY`, stamped by
+//! the Bridge with the agent id), else its envelope sender, else its `source`. Each publisher and each concrete topic
+//! gets its own bucket of `burst` tokens, refilled at `per_sec`; a publish takes one
+//! token from both. An event that finds either bucket empty is rejected with
+//! `LoomError::RateLimited` before it reaches any subscriber, and consumes nothing, so
+//! a runaway agent or a chatty source only ever throttles itself.
+//!
+//! Publishers without a limit of their own get the default publisher limit, if one is
+//! set. Topics take the limit of the most specific matching pattern (see
+//! `topic_matches`).
+//!
+//! Rejections are counted in `loom.event_bus.rate_limited_total` (by scope and the
+//! publisher or topic pattern whose limit applied) and in the topic's
+//! `EventBusStats::rate_limited`.
+
+use crate::messaging::event_bus::topic_matches;
+use dashmap::DashMap;
+use opentelemetry::{global, metrics::Counter, KeyValue};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::sync::RwLock;
+use std::time::{Duration, Instant};
+
+/// Buckets kept per scope before full (idle) ones are dropped
+const MAX_BUCKETS: usize = 10_000;
+
+/// Publishers and topics whose rejections are counted one by one in `stats`; later
+/// ones are counted together under `OTHERS`
+const MAX_STATS_KEYS: usize = 1_000;
+
+/// Key of the rejections of publishers and topics past `MAX_STATS_KEYS`
+pub const OTHERS: &str = "(others)";
+
+/// Metrics key of the rejections of publishers limited by the default publisher limit
+const DEFAULT_PUBLISHER: &str = "(default)";
+
+/// Metadata key of the publisher an event is charged to, set by the ingress it came in
+/// on (the Bridge stamps the agent id, replacing any value the client sent). Removed by
+/// the EventBus before delivery.
+pub const PUBLISHER_KEY: &str = "ingress_publisher";
+
+/// Sustained rate and burst of one bucket
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct RateLimit {
+    /// Tokens added per second; 0 rejects everything, burst included
+    pub per_sec: f64,
+    /// Bucket capacity: events that may be published at once after a quiet period
+    pub burst: u32,
+}
+
+impl RateLimit {
+    /// `per_sec` events per second, with a burst of one second's worth
+    pub fn per_second(per_sec: f64) -> Self {
+        let per_sec = per_sec.max(0.0);
+        Self {
+            per_sec,
+            burst: (per_sec.ceil() as u32).max(1),
+        }
+    }
+
+    pub fn with_burst(mut self, burst: u32) -> Self {
+        self.burst = burst.max(1);
+        self
+    }
+}
+
+/// What a limit applies to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RateLimitScope {
+    Publisher,
+    Topic,
+}
+
+impl RateLimitScope {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RateLimitScope::Publisher => "publisher",
+            RateLimitScope::Topic => "topic",
+        }
+    }
+}
+
+impl fmt::Display for RateLimitScope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Why a publish was rejected (`LoomError::RateLimited`)
+#[derive(Debug, Clone, PartialEq)]
+pub struct RateLimited {
+    pub scope: RateLimitScope,
+    /// Publisher id or topic
+    pub key: String,
+    pub limit: RateLimit,
+    /// Time until the bucket has a token again
+    pub retry_after: Duration,
+}
+
+impl fmt::Display for RateLimited {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} '{}' is over {}/s (burst {}), retry in {}ms",
+            self.scope,
+            self.key,
+            self.limit.per_sec,
+            self.limit.burst,
+            self.retry_after.as_millis()
+        )
+    }
+}
+
+/// Rejections of one publisher or topic
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RateLimitStats {
+    pub scope: RateLimitScope,
+    pub key: String,
+    pub rejected: u64,
+}
+
+struct Bucket {
+    limit: RateLimit,
+    tokens: f64,
+    updated: Instant,
+}
+
+impl Bucket {
+    /// A bucket that has been idle; one that never refills starts (and stays) empty
+    fn full(limit: RateLimit, now: Instant) -> Self {
+        Self {
+            limit,
+            tokens: if limit.per_sec > 0.0 {
+                f64::from(limit.burst)
+            } else {
+                0.0
+            },
+            updated: now,
+        }
+    }
+
+    fn refill(&mut self, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.updated).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.limit.per_sec).min(f64::from(self.limit.burst));
+        self.updated = now;
+    }
+
+    /// Time until a token is available, if none is now
+    fn wait(&self) -> Option<Duration> {
+        if self.tokens >= 1.0 {
+            return None;
+        }
+        let secs = (1.0 - self.tokens) / self.limit.per_sec;
+        Some(Duration::try_from_secs_f64(secs).unwrap_or(Duration::MAX))
+    }
+}
+
+/// Publisher and topic limits of an EventBus, and their buckets
+pub struct RateLimiter {
+    default_publisher: RwLock<Option<RateLimit>>,
+    publishers: DashMap<String, RateLimit>,
+    topics: RwLock<Vec<(String, RateLimit)>>,
+    // Separate maps, so a publisher and a topic bucket can be held at once
+    publisher_buckets: DashMap<String, Bucket>,
+    topic_buckets: DashMap<String, Bucket>,
+    rejected: DashMap<(RateLimitScope, String), u64>,
+    rejected_counter: Counter<u64>,
+}
+
+impl Default for RateLimiter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RateLimiter {
+    pub fn new() -> Self {
+        Self {
+            default_publisher: RwLock::new(None),
+            publishers: DashMap::new(),
+            topics: RwLock::new(Vec::new()),
+            publisher_buckets: DashMap::new(),
+            topic_buckets: DashMap::new(),
+            rejected: DashMap::new(),
+            rejected_counter: global::meter("loom.event_bus")
+                .u64_counter("loom.event_bus.rate_limited_total")
+                .with_description("Total number of publishes rejected by rate limits")
+                .init(),
+        }
+    }
+
+    /// Limit every publisher without a limit of its own; `None` removes the default
+    pub fn set_default_publisher_limit(&self, limit: Option<RateLimit>) {
+        *self.default_publisher.write().unwrap() = limit;
+        self.publisher_buckets.clear();
+    }
+
+    /// Limit `publisher` (an agent id); replaces its previous limit
+    pub fn set_publisher_limit(&self, publisher: impl Into<String>, limit: RateLimit) {
+        let publisher = publisher.into();
+        self.publisher_buckets.remove(&publisher);
+        self.publishers.insert(publisher, limit);
+    }
+
+    /// Remove `publisher`'s own limit; returns whether it had one
+    pub fn remove_publisher_limit(&self, publisher: &str) -> bool {
+        self.publisher_buckets.remove(publisher);
+        self.publishers.remove(publisher).is_some()
+    }
+
+    /// Limit each topic matching `pattern`; replaces the pattern's previous limit
+    pub fn set_topic_limit(&self, pattern: impl Into<String>, limit: RateLimit) {
+        let pattern = pattern.into();
+        {
+            let mut topics = self.topics.write().unwrap();
+            match topics.iter_mut().find(|(p, _)| *p == pattern) {
+                Some(entry) => entry.1 = limit,
+                None => topics.push((pattern, limit)),
+            }
+        }
+        self.topic_buckets.clear();
+    }
+
+    /// Remove the limit of `pattern`; returns whether it existed
+    pub fn remove_topic_limit(&self, pattern: &str) -> bool {
+        let removed = {
+            let mut topics = self.topics.write().unwrap();
+            let before = topics.len();
+            topics.retain(|(p, _)| p != pattern);
+            topics.len() != before
+        };
+        if removed {
+            self.topic_buckets.clear();
+        }
+        removed
+    }
+
+    /// Limit applying to `publisher`
+    pub fn publisher_limit(&self, publisher: &str) -> Option<RateLimit> {
+        self.publishers
+            .get(publisher)
+            .map(|limit| *limit)
+            .or(*self.default_publisher.read().unwrap())
+    }
+
+    /// Limit of the most specific pattern matching `topic`
+    pub fn topic_limit(&self, topic: &str) -> Option<RateLimit> {
+        self.topic_rule(topic).map(|(_, limit)| limit)
+    }
+
+    /// Most specific pattern matching `topic`, with its limit
+    fn topic_rule(&self, topic: &str) -> Option<(String, RateLimit)> {
+        self.topics
+            .read()
+            .unwrap()
+            .iter()
+            .filter(|(pattern, _)| topic_matches(pattern, topic))
+            .max_by_key(|(pattern, _)| pattern.len())
+            .cloned()
+    }
+
+    /// Whether any limit is configured
+    pub fn is_enabled(&self) -> bool {
+        !self.publishers.is_empty()
+            || self.default_publisher.read().unwrap().is_some()
+            || !self.topics.read().unwrap().is_empty()
+    }
+
+    /// Take a token for one publish by `publisher` to `topic`, or report the first
+    /// exhausted limit. Nothing is taken from either bucket on rejection.
+    pub fn check(&self, publisher: Option<&str>, topic: &str) -> Result<(), RateLimited> {
+        let now = Instant::now();
+        let publisher_limit = publisher.and_then(|p| self.publisher_limit(p).map(|l| (p, l)));
+        let topic_limit = self.topic_limit(topic);
+
+        let mut publisher_bucket = match publisher_limit {
+            Some((publisher, limit)) => {
+                prune(&self.publisher_buckets, now);
+                let mut bucket = self
+                    .publisher_buckets
+                    .entry(publisher.to_string())
+                    .or_insert_with(|| Bucket::full(limit, now));
+                bucket.refill(now);
+                if let Some(wait) = bucket.wait() {
+                    drop(bucket);
+                    return Err(self.reject(RateLimitScope::Publisher, publisher, limit, wait));
+                }
+                Some(bucket)
+            }
+            None => None,
+        };
+        if let Some(limit) = topic_limit {
+            prune(&self.topic_buckets, now);
+            let mut bucket = self
+                .topic_buckets
+                .entry(topic.to_string())
+                .or_insert_with(|| Bucket::full(limit, now));
+            bucket.refill(now);
+            if let Some(wait) = bucket.wait() {
+                drop(bucket);
+                drop(publisher_bucket);
+                return Err(self.reject(RateLimitScope::Topic, topic, limit, wait));
+            }
+            bucket.tokens -= 1.0;
+        }
+        if let Some(ref mut bucket) = publisher_bucket {
+            bucket.tokens -= 1.0;
+        }
+        Ok(())
+    }
+
+    /// Rejections so far, most rejected first
+    pub fn stats(&self) -> Vec<RateLimitStats> {
+        let mut stats: Vec<RateLimitStats> = self
+            .rejected
+            .iter()
+            .map(|entry| RateLimitStats {
+                scope: entry.key().0,
+                key: entry.key().1.clone(),
+                rejected: *entry.value(),
+            })
+            .collect();
+        stats.sort_by(|a, b| b.rejected.cmp(&a.rejected).then(a.key.cmp(&b.key)));
+        stats
+    }
+
+    fn reject(
+        &self,
+        scope: RateLimitScope,
+        key: &str,
+        limit: RateLimit,
+        retry_after: Duration,
+    ) -> RateLimited {
+        let entry = (scope, key.to_string());
+        if self.rejected.contains_key(&entry) || self.rejected.len() < MAX_STATS_KEYS {
+            *self.rejected.entry(entry).or_default() += 1;
+        } else {
+            *self
+                .rejected
+                .entry((scope, OTHERS.to_string()))
+                .or_default() += 1;
+        }
+        // Labelled by the configured limit rather than the key, which any agent id or
+        // topic name can be
+        let label = match scope {
+            RateLimitScope::Publisher if self.publishers.contains_key(key) => key.to_string(),
+            RateLimitScope::Publisher => DEFAULT_PUBLISHER.to_string(),
+            RateLimitScope::Topic => self
+                .topic_rule(key)
+                .map(|(pattern, _)| pattern)
+                .unwrap_or_else(|| OTHERS.to_string()),
+        };
+        self.rejected_counter.add(
+            1,
+            &[
+                KeyValue::new("scope", scope.as_str()),
+                KeyValue::new("key", label),
+            ],
+        );
+        RateLimited {
+            scope,
+            key: key.to_string(),
+            limit,
+            retry_after,
+        }
+    }
+}
+
+/// Drop buckets that have refilled completely, and those that never refill, once there
+/// are too many; a new bucket starts in the same state, so this forgets nothing
+fn prune(buckets: &DashMap<String, Bucket>, now: Instant) {
+    if buckets.len() < MAX_BUCKETS {
+        return;
+    }
+    buckets.retain(|_, bucket| {
+        bucket.refill(now);
+        bucket.limit.per_sec > 0.0 && bucket.tokens < f64::from(bucket.limit.burst)
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bucket_refills_at_the_configured_rate() {
+        let start = Instant::now();
+        let mut bucket = Bucket::full(RateLimit::per_second(4.0).with_burst(2), start);
+        bucket.tokens -= 2.0;
+        assert_eq!(bucket.wait(), Some(Duration::from_millis(250)));
+
+        bucket.refill(start + Duration::from_millis(300));
+        assert!(bucket.wait().is_none());
+        bucket.refill(start + Duration::from_secs(10));
+        assert_eq!(bucket.tokens, 2.0);
+    }
+
+    #[test]
+    fn zero_rate_never_refills() {
+        let bucket = Bucket::full(RateLimit::per_second(0.0).with_burst(5), Instant::now());
+        assert_eq!(bucket.tokens, 0.0);
+        assert_eq!(bucket.wait(), Some(Duration::MAX));
+    }
+}
@@ -0,0 +1,390 @@
+//! Delivery receipts for critical topics.
+//!
+//! Topics marked with `EventBus::mark_critical` promise that every subscriber processes
+//! each event. Deliveries to plain (non-`QosReliable`) subscriptions on those topics are
+//! stamped with `RECEIPT_SUBSCRIBER_KEY` and tracked per subscriber until acked with
+//! `EventBus::ack` / `ack_event`. The bus sweeps unacked receipts: after `ack_timeout`
+//! the event is redelivered, and after `max_deliveries` attempts it is published to the
+//! dead-letter topic with `dead_letter_keys` metadata, like `QosReliable` subscriptions.
+//!
+//! `QosGuaranteed` subscriptions (`EventBus::subscribe_guaranteed`) get the same receipts
+//! for every delivery, on any topic.
+//!
+//! Subscribers that fan events out further (the Bridge's shared topic subscriptions)
+//! pass responsibility on with `hand_off`, which replaces their receipt with one per
+//! downstream subscriber and a redelivery function of their own.
+
+use crate::messaging::event_bus::topic_matches;
+use crate::messaging::reliable::ReliableConfig;
+use crate::proto::Event;
+use dashmap::DashMap;
+use opentelemetry::{
+    global,
+    metrics::{Counter, UpDownCounter},
+    KeyValue,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio::time::Instant;
+
+/// Metadata key stamped on deliveries that must be acked; the value names the subscriber
+/// the bus tracks the receipt under
+pub const RECEIPT_SUBSCRIBER_KEY: &str = "receipt.subscriber";
+
+/// Metadata key set on redeliveries: the delivery attempt, starting at 2
+pub const RECEIPT_ATTEMPT_KEY: &str = "receipt.attempt";
+
+/// Redelivers an event to a downstream subscriber. Returns false once the subscriber is
+/// gone for good, which dead-letters the event right away.
+pub type RedeliverFn = Arc<dyn Fn(Event) -> bool + Send + Sync>;
+
+pub(crate) enum Target {
+    /// Queue of an EventBus subscription
+    Subscription(mpsc::Sender<Event>),
+    /// Subscriber behind a `hand_off`
+    External(RedeliverFn),
+}
+
+impl Target {
+    fn redeliver(&self, event: Event) -> bool {
+        match self {
+            // A full queue keeps the receipt; the next timeout retries
+            Target::Subscription(tx) => match tx.try_send(event) {
+                Ok(()) | Err(mpsc::error::TrySendError::Full(_)) => true,
+                Err(mpsc::error::TrySendError::Closed(_)) => false,
+            },
+            Target::External(redeliver) => redeliver(event),
+        }
+    }
+}
+
+struct PendingReceipt {
+    topic: String,
+    event: Event,
+    config: ReliableConfig,
+    attempt: u32,
+    first_delivered: Instant,
+    deadline: Instant,
+    target: Target,
+}
+
+/// Unacked deliveries of one subscriber on one topic
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OutstandingAcks {
+    pub subscriber: String,
+    pub topic: String,
+    pub count: usize,
+    /// Age of the oldest unacked delivery
+    pub oldest_ms: u64,
+}
+
+/// A receipt given up on during a sweep
+pub(crate) struct DeadReceipt {
+    pub subscriber: String,
+    pub topic: String,
+    pub event: Event,
+    pub attempts: u32,
+    pub dead_letter_topic: String,
+    /// `ack_timeout` or `subscriber_gone`
+    pub reason: &'static str,
+}
+
+#[derive(Default)]
+pub(crate) struct SweepOutcome {
+    /// Topic of each redelivered event
+    pub redelivered: Vec<String>,
+    pub dead: Vec<DeadReceipt>,
+}
+
+/// Critical topics configured through the environment:
+/// `LOOM_CRITICAL_TOPICS` (comma-separated patterns), `LOOM_CRITICAL_ACK_TIMEOUT_MS`
+/// and `LOOM_CRITICAL_MAX_DELIVERIES`
+pub fn critical_topics_from_env() -> Option<(Vec<String>, ReliableConfig)> {
+    let patterns: Vec<String> = std::env::var("LOOM_CRITICAL_TOPICS")
+        .ok()?
+        .split(',')
+        .map(str::trim)
+        .filter(|p| !p.is_empty())
+        .map(str::to_string)
+        .collect();
+    if patterns.is_empty() {
+        return None;
+    }
+    let mut config = ReliableConfig::default();
+    if let Some(ms) = std::env::var("LOOM_CRITICAL_ACK_TIMEOUT_MS")
+        .ok()
+        .and_then(|v| v.parse().ok())
+    {
+        config = config.with_ack_timeout(Duration::from_millis(ms));
+    }
+    if let Some(max) = std::env::var("LOOM_CRITICAL_MAX_DELIVERIES")
+        .ok()
+        .and_then(|v| v.parse().ok())
+    {
+        config = config.with_max_deliveries(max);
+    }
+    Some((patterns, config))
+}
+
+/// Critical topic patterns and the receipts outstanding on them
+pub struct ReceiptTracker {
+    critical: RwLock<Vec<(String, ReliableConfig)>>,
+    pending: DashMap<(String, String), PendingReceipt>,
+    sweeper_started: AtomicBool,
+    outstanding_gauge: UpDownCounter<i64>,
+    acked_counter: Counter<u64>,
+}
+
+impl ReceiptTracker {
+    pub(crate) fn new() -> Self {
+        let meter = global::meter("loom.event_bus");
+        Self {
+            critical: RwLock::new(Vec::new()),
+            pending: DashMap::new(),
+            sweeper_started: AtomicBool::new(false),
+            outstanding_gauge: meter
+                .i64_up_down_counter("loom.event_bus.outstanding_acks")
+                .with_description("Deliveries on critical topics awaiting an ack")
+                .init(),
+            acked_counter: meter
+                .u64_counter("loom.event_bus.acked_total")
+                .with_description("Total number of critical deliveries acked")
+                .init(),
+        }
+    }
+
+    /// Register `pattern` as critical; replaces the config of an existing pattern
+    pub(crate) fn mark(&self, pattern: String, config: ReliableConfig) {
+        let mut critical = self.critical.write().unwrap();
+        match critical.iter_mut().find(|(p, _)| *p == pattern) {
+            Some(entry) => entry.1 = config,
+            None => critical.push((pattern, config)),
+        }
+    }
+
+    /// True on the first call, when the sweeper needs starting
+    pub(crate) fn claim_sweeper(&self) -> bool {
+        !self.sweeper_started.swap(true, Ordering::SeqCst)
+    }
+
+    /// Stop requiring acks on `pattern`; receipts already outstanding still settle
+    pub(crate) fn unmark(&self, pattern: &str) -> bool {
+        let mut critical = self.critical.write().unwrap();
+        let before = critical.len();
+        critical.retain(|(p, _)| p != pattern);
+        critical.len() != before
+    }
+
+    /// Config of the most specific critical pattern matching `topic`
+    pub fn config_for(&self, topic: &str) -> Option<ReliableConfig> {
+        self.critical
+            .read()
+            .unwrap()
+            .iter()
+            .filter(|(pattern, _)| topic_matches(pattern, topic))
+            .max_by_key(|(pattern, _)| pattern.len())
+            .map(|(_, config)| config.clone())
+    }
+
+    /// Critical topic patterns
+    pub fn critical_patterns(&self) -> Vec<String> {
+        self.critical
+            .read()
+            .unwrap()
+            .iter()
+            .map(|(pattern, _)| pattern.clone())
+            .collect()
+    }
+
+    pub(crate) fn track(
+        &self,
+        subscriber: &str,
+        topic: &str,
+        event: &Event,
+        config: ReliableConfig,
+        target: Target,
+    ) {
+        let now = Instant::now();
+        let receipt = PendingReceipt {
+            topic: topic.to_string(),
+            event: event.clone(),
+            attempt: 1,
+            first_delivered: now,
+            deadline: now + config.ack_timeout,
+            config,
+            target,
+        };
+        let key = (subscriber.to_string(), event.id.clone());
+        if self.pending.insert(key, receipt).is_none() {
+            self.outstanding_gauge
+                .add(1, &[KeyValue::new("topic", topic.to_string())]);
+        }
+    }
+
+    /// Forget a receipt without counting an ack (the delivery never happened)
+    pub(crate) fn untrack(&self, subscriber: &str, event_id: &str) {
+        if let Some((_, receipt)) = self
+            .pending
+            .remove(&(subscriber.to_string(), event_id.to_string()))
+        {
+            self.outstanding_gauge
+                .add(-1, &[KeyValue::new("topic", receipt.topic)]);
+        }
+    }
+
+    /// Settle the receipt of `subscriber` for `event_id`; false if none was outstanding
+    pub fn ack(&self, subscriber: &str, event_id: &str) -> bool {
+        let Some((_, receipt)) = self
+            .pending
+            .remove(&(subscriber.to_string(), event_id.to_string()))
+        else {
+            return false;
+        };
+        let topic = KeyValue::new("topic", receipt.topic);
+        self.outstanding_gauge.add(-1, &[topic.clone()]);
+        self.acked_counter.add(1, &[topic]);
+        true
+    }
+
+    /// Ack a delivery by the subscriber stamped on it; false for unstamped events
+    pub fn ack_event(&self, event: &Event) -> bool {
+        match event.metadata.get(RECEIPT_SUBSCRIBER_KEY) {
+            Some(subscriber) => self.ack(subscriber, &event.id),
+            None => false,
+        }
+    }
+
+    /// Whether `subscriber` still owes an ack for `event_id`
+    pub fn is_pending(&self, subscriber: &str, event_id: &str) -> bool {
+        self.pending
+            .contains_key(&(subscriber.to_string(), event_id.to_string()))
+    }
+
+    /// Replace the receipt of `subscriber` for `event_id` with one per downstream
+    /// subscriber in `targets`, keeping topic, config and deadline. Call before
+    /// delivering downstream so an early ack finds its receipt. Returns false (and
+    /// tracks nothing) if `subscriber` owed no ack for the event.
+    pub fn hand_off(
+        &self,
+        subscriber: &str,
+        event_id: &str,
+        targets: Vec<(String, RedeliverFn)>,
+    ) -> bool {
+        let Some((_, receipt)) = self
+            .pending
+            .remove(&(subscriber.to_string(), event_id.to_string()))
+        else {
+            return false;
+        };
+        let topic = KeyValue::new("topic", receipt.topic.clone());
+        self.outstanding_gauge.add(-1, &[topic.clone()]);
+        for (downstream, redeliver) in targets {
+            let handed = PendingReceipt {
+                topic: receipt.topic.clone(),
+                event: receipt.event.clone(),
+                config: receipt.config.clone(),
+                attempt: receipt.attempt,
+                first_delivered: receipt.first_delivered,
+                deadline: receipt.deadline,
+                target: Target::External(redeliver),
+            };
+            let key = (downstream, event_id.to_string());
+            if self.pending.insert(key, handed).is_none() {
+                self.outstanding_gauge.add(1, &[topic.clone()]);
+            }
+        }
+        true
+    }
+
+    /// Number of unacked deliveries
+    pub fn outstanding_count(&self) -> usize {
+        self.pending.len()
+    }
+
+    /// Unacked deliveries grouped by subscriber and topic
+    pub fn outstanding(&self) -> Vec<OutstandingAcks> {
+        let now = Instant::now();
+        let mut groups: BTreeMap<(String, String), OutstandingAcks> = BTreeMap::new();
+        for entry in self.pending.iter() {
+            let (subscriber, _) = entry.key();
+            let receipt = entry.value();
+            let age_ms = now.duration_since(receipt.first_delivered).as_millis() as u64;
+            let group = groups
+                .entry((subscriber.clone(), receipt.topic.clone()))
+                .or_insert_with(|| OutstandingAcks {
+                    subscriber: subscriber.clone(),
+                    topic: receipt.topic.clone(),
+                    count: 0,
+                    oldest_ms: 0,
+                });
+            group.count += 1;
+            group.oldest_ms = group.oldest_ms.max(age_ms);
+        }
+        groups.into_values().collect()
+    }
+
+    /// Redeliver receipts past their deadline, giving up on those out of attempts or
+    /// whose subscriber is gone
+    pub(crate) fn sweep(&self, now: Instant) -> SweepOutcome {
+        let mut expired: Vec<(String, String)> = self
+            .pending
+            .iter()
+            .filter(|e| e.value().deadline <= now)
+            .map(|e| e.key().clone())
+            .collect();
+        expired.sort_unstable();
+
+        let mut outcome = SweepOutcome::default();
+        for key in expired {
+            // Acked since the scan
+            let Some((key, mut receipt)) = self.pending.remove(&key) else {
+                continue;
+            };
+            let (subscriber, _) = key.clone();
+
+            let reason = if receipt.attempt >= receipt.config.max_deliveries {
+                Some("ack_timeout")
+            } else {
+                receipt.attempt += 1;
+                receipt.deadline = now + receipt.config.ack_timeout;
+                let mut event = receipt.event.clone();
+                event
+                    .metadata
+                    .insert(RECEIPT_ATTEMPT_KEY.into(), receipt.attempt.to_string());
+                if receipt.target.redeliver(event) {
+                    None
+                } else {
+                    receipt.attempt -= 1;
+                    Some("subscriber_gone")
+                }
+            };
+
+            match reason {
+                None => {
+                    outcome.redelivered.push(receipt.topic.clone());
+                    self.pending.insert(key, receipt);
+                }
+                Some(reason) => {
+                    self.outstanding_gauge
+                        .add(-1, &[KeyValue::new("topic", receipt.topic.clone())]);
+                    let mut event = receipt.event;
+                    event.metadata.remove(RECEIPT_SUBSCRIBER_KEY);
+                    event.metadata.remove(RECEIPT_ATTEMPT_KEY);
+                    outcome.dead.push(DeadReceipt {
+                        subscriber,
+                        dead_letter_topic: receipt.config.dead_letter_topic_for(&receipt.topic),
+                        topic: receipt.topic,
+                        event,
+                        attempts: receipt.attempt,
+                        reason,
+                    });
+                }
+            }
+        }
+        outcome
+    }
+}
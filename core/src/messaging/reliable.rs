@@ -0,0 +1,320 @@
+//! At-least-once delivery for `QosReliable` subscriptions.
+//!
+//! `EventBus::subscribe_reliable` puts a dispatcher task between the bus and the
+//! subscriber. The dispatcher hands out `DeliveredEvent`s, keeps every delivery pending
+//! until it is acked, and redelivers it after `ack_timeout` or on `nack`. An event that
+//! used up `max_deliveries` attempts is published to the subscription's dead-letter
+//! topic with `dead_letter_keys` metadata describing why, and kept in the bus's
+//! dead-letter store (`messaging::dead_letter`).
+
+use crate::messaging::dead_letter::mark_dead;
+use crate::messaging::event_bus::EventBus;
+use crate::proto::Event;
+use std::collections::HashMap;
+use std::ops::Deref;
+use std::sync::Weak;
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio::time::Instant;
+use tracing::{debug, warn};
+
+/// Dead-letter topics default to this prefix followed by the subscribed topic.
+///
+/// A prefix (rather than a suffix) keeps dead letters of `a.*` out of `a.*` itself.
+pub const DEAD_LETTER_TOPIC_PREFIX: &str = crate::proto::conventions::topics::DEAD_LETTER_PREFIX;
+
+/// Metadata keys set on dead-lettered events
+pub mod dead_letter_keys {
+    /// One of `messaging::dead_letter::dead_letter_reasons`
+    pub const REASON: &str = "dead_letter.reason";
+    /// Number of deliveries made before giving up
+    pub const ATTEMPTS: &str = "dead_letter.attempts";
+    /// Topic the subscription was registered on
+    pub const TOPIC: &str = "dead_letter.topic";
+    pub const SUBSCRIPTION: &str = "dead_letter.subscription";
+    /// When the event was given up on, in milliseconds since Unix epoch
+    pub const AT_MS: &str = "dead_letter.at_ms";
+}
+
+/// Settings for a reliable subscription
+#[derive(Debug, Clone)]
+pub struct ReliableConfig {
+    /// How long a delivery may stay unacked before it is redelivered
+    pub ack_timeout: Duration,
+    /// Total deliveries (first one included) before an event is dead-lettered
+    pub max_deliveries: u32,
+    /// Defaults to `DEAD_LETTER_TOPIC_PREFIX` + subscribed topic
+    pub dead_letter_topic: Option<String>,
+}
+
+impl Default for ReliableConfig {
+    fn default() -> Self {
+        Self {
+            ack_timeout: Duration::from_secs(30),
+            max_deliveries: 5,
+            dead_letter_topic: None,
+        }
+    }
+}
+
+impl ReliableConfig {
+    pub fn with_ack_timeout(mut self, timeout: Duration) -> Self {
+        self.ack_timeout = timeout;
+        self
+    }
+
+    pub fn with_max_deliveries(mut self, max: u32) -> Self {
+        self.max_deliveries = max.max(1);
+        self
+    }
+
+    pub fn with_dead_letter_topic(mut self, topic: impl Into<String>) -> Self {
+        self.dead_letter_topic = Some(topic.into());
+        self
+    }
+
+    /// Dead-letter topic used for a subscription on `topic`
+    pub fn dead_letter_topic_for(&self, topic: &str) -> String {
+        self.dead_letter_topic
+            .clone()
+            .unwrap_or_else(|| format!("{DEAD_LETTER_TOPIC_PREFIX}{topic}"))
+    }
+}
+
+#[derive(Debug)]
+enum Settle {
+    Ack(u64),
+    Nack { seq: u64, attempt: u32 },
+}
+
+/// An event received on a reliable subscription.
+///
+/// Call `ack` once the event is processed, or `nack` to have it redelivered. Dropping
+/// it without either leaves the delivery pending until the ack timeout expires.
+#[derive(Debug)]
+pub struct DeliveredEvent {
+    pub event: Event,
+    /// 1 for the first delivery, incremented on every redelivery
+    pub attempt: u32,
+    seq: u64,
+    settle_tx: mpsc::UnboundedSender<Settle>,
+}
+
+impl DeliveredEvent {
+    /// Confirm processing; the event will not be delivered again.
+    ///
+    /// An ack for an earlier attempt still settles the event.
+    pub fn ack(self) {
+        let _ = self.settle_tx.send(Settle::Ack(self.seq));
+    }
+
+    /// Reject the event; it is redelivered, or dead-lettered once out of attempts.
+    ///
+    /// A nack for an attempt that was already redelivered is ignored.
+    pub fn nack(self) {
+        let _ = self.settle_tx.send(Settle::Nack {
+            seq: self.seq,
+            attempt: self.attempt,
+        });
+    }
+}
+
+impl Deref for DeliveredEvent {
+    type Target = Event;
+
+    fn deref(&self) -> &Event {
+        &self.event
+    }
+}
+
+struct Pending {
+    event: Event,
+    attempt: u32,
+    deadline: Instant,
+}
+
+/// Per-subscription task tracking unacked deliveries
+pub(crate) struct Dispatcher {
+    pub(crate) topic: String,
+    pub(crate) subscription_id: String,
+    pub(crate) config: ReliableConfig,
+    pub(crate) bus: Weak<EventBus>,
+    pub(crate) input: mpsc::Receiver<Event>,
+    pub(crate) output: mpsc::Sender<DeliveredEvent>,
+}
+
+impl Dispatcher {
+    /// Runs until the subscriber is dropped, or the subscription is gone and every
+    /// pending delivery has been settled.
+    pub(crate) async fn run(self) {
+        let Dispatcher {
+            topic,
+            subscription_id,
+            config,
+            bus,
+            mut input,
+            output,
+        } = self;
+        let (settle_tx, mut settle_rx) = mpsc::unbounded_channel();
+        let mut state = DispatchState {
+            dead_letter_topic: config.dead_letter_topic_for(&topic),
+            topic,
+            subscription_id,
+            config,
+            bus,
+            output,
+            settle_tx,
+            pending: HashMap::new(),
+        };
+
+        let mut next_seq = 0u64;
+        let mut input_open = true;
+        loop {
+            if !input_open && state.pending.is_empty() {
+                break;
+            }
+            let next_deadline = state.pending.values().map(|p| p.deadline).min();
+            let alive = tokio::select! {
+                event = input.recv(), if input_open => match event {
+                    Some(event) => {
+                        next_seq += 1;
+                        state.deliver(next_seq, event, 1).await
+                    }
+                    None => {
+                        input_open = false;
+                        true
+                    }
+                },
+                Some(settle) = settle_rx.recv() => state.settle(settle).await,
+                _ = sleep_until(next_deadline), if next_deadline.is_some() => state.expire().await,
+            };
+            if !alive {
+                break;
+            }
+        }
+        debug!(
+            target: "event_bus",
+            subscription_id = %state.subscription_id,
+            unsettled = state.pending.len(),
+            "Reliable dispatcher stopped"
+        );
+    }
+}
+
+async fn sleep_until(deadline: Option<Instant>) {
+    match deadline {
+        Some(deadline) => tokio::time::sleep_until(deadline).await,
+        None => std::future::pending().await,
+    }
+}
+
+struct DispatchState {
+    topic: String,
+    subscription_id: String,
+    dead_letter_topic: String,
+    config: ReliableConfig,
+    bus: Weak<EventBus>,
+    output: mpsc::Sender<DeliveredEvent>,
+    settle_tx: mpsc::UnboundedSender<Settle>,
+    pending: HashMap<u64, Pending>,
+}
+
+impl DispatchState {
+    /// Hand the event to the subscriber; false once the subscriber is gone
+    async fn deliver(&mut self, seq: u64, event: Event, attempt: u32) -> bool {
+        let delivered = DeliveredEvent {
+            event: event.clone(),
+            attempt,
+            seq,
+            settle_tx: self.settle_tx.clone(),
+        };
+        if self.output.send(delivered).await.is_err() {
+            return false;
+        }
+        self.pending.insert(
+            seq,
+            Pending {
+                event,
+                attempt,
+                deadline: Instant::now() + self.config.ack_timeout,
+            },
+        );
+        true
+    }
+
+    async fn settle(&mut self, settle: Settle) -> bool {
+        match settle {
+            Settle::Ack(seq) => {
+                self.pending.remove(&seq);
+                true
+            }
+            Settle::Nack { seq, attempt } => match self.pending.remove(&seq) {
+                Some(pending) if pending.attempt == attempt => {
+                    self.retry(seq, pending, "nacked").await
+                }
+                Some(stale) => {
+                    self.pending.insert(seq, stale);
+                    true
+                }
+                None => true,
+            },
+        }
+    }
+
+    async fn expire(&mut self) -> bool {
+        let now = Instant::now();
+        let mut expired: Vec<u64> = self
+            .pending
+            .iter()
+            .filter(|(_, p)| p.deadline <= now)
+            .map(|(seq, _)| *seq)
+            .collect();
+        expired.sort_unstable();
+        for seq in expired {
+            let pending = self.pending.remove(&seq).expect("collected above");
+            if !self.retry(seq, pending, "ack_timeout").await {
+                return false;
+            }
+        }
+        true
+    }
+
+    /// Redeliver, or dead-letter once `max_deliveries` is reached
+    async fn retry(&mut self, seq: u64, pending: Pending, reason: &'static str) -> bool {
+        let Some(bus) = self.bus.upgrade() else {
+            return false;
+        };
+        if pending.attempt >= self.config.max_deliveries {
+            warn!(
+                target: "event_bus",
+                subscription_id = %self.subscription_id,
+                event_id = %pending.event.id,
+                attempts = pending.attempt,
+                reason,
+                dead_letter_topic = %self.dead_letter_topic,
+                "Dead-lettering event"
+            );
+            let mut event = pending.event;
+            mark_dead(
+                &mut event,
+                &self.topic,
+                Some(&self.subscription_id),
+                reason,
+                pending.attempt,
+            );
+            bus.dead_letter(event, Some(&self.dead_letter_topic)).await;
+            return true;
+        }
+
+        debug!(
+            target: "event_bus",
+            subscription_id = %self.subscription_id,
+            event_id = %pending.event.id,
+            attempt = pending.attempt + 1,
+            reason,
+            "Redelivering event"
+        );
+        bus.record_redelivery(&self.topic, reason);
+        self.deliver(seq, pending.event, pending.attempt + 1).await
+    }
+}
@@ -0,0 +1,254 @@
+//! HMAC signing of envelopes for events arriving through the Bridge.
+//!
+//! In-process agents publish straight onto the `EventBus` and are trusted. External
+//! agents publish through the Bridge, which stamps each event with `INGRESS_KEY`; any
+//! such event can claim any `sender`. With an `EnvelopeSigner` set
+//! (`EventBus::set_signer`, or `LOOM_SIGNING_KEY` for `Loom::new`), `EventBus::publish`
+//! checks ingress events against the deployment's key:
+//!
+//! - an event carrying `keys::SIGNATURE` must verify, or the publish fails with
+//!   `LoomError::SignatureError`;
+//! - an unsigned event is rejected on topics that require signatures (`system.*` by
+//!   default, `LOOM_SIGNING_REQUIRED_TOPICS` for a comma-separated list of patterns);
+//! - a verified event is delivered with `keys::SIGNATURE_VERIFIED` set to `"true"`, which
+//!   the bus strips from every other ingress event.
+//!
+//! The signature is the hex HMAC-SHA256 of the canonical form of the event: the topic
+//! it is published to, its id, type and source, the values of the envelope keys in
+//! `SIGNED_KEYS` (empty when absent), then the payload. Each part is prefixed with its
+//! length as a big-endian `u32`. The trace context is left out, as it is re-injected at
+//! every hop. The payload is signed as the EventBus carries it, so agents that sign
+//! should publish in the `raw` payload format.
+//!
+//! Events published to a namespace (`tenant.<ns>.<topic>`) are signed, and required to
+//! be signed, for the namespace-relative `<topic>` their agent publishes to.
+//!
+//! Signing the topic keeps a signed event from being replayed onto another topic. To
+//! stop it being replayed onto the same one, `verify` also rejects events whose signed
+//! `keys::TIMESTAMP_MS` is further than the allowed clock skew from now
+//! (`LOOM_SIGNING_MAX_SKEW_SECS`, 5 minutes by default), and ids it already accepted
+//! within that window.
+
+use crate::messaging::envelope::keys;
+use crate::messaging::event_bus::topic_matches;
+use crate::proto::Event;
+use crate::{LoomError, Result};
+use ring::hmac;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// Metadata key the Bridge sets on every event it publishes for an agent; removed by
+/// `EventBus::publish`
+pub const INGRESS_KEY: &str = "ingress";
+
+/// Value of `INGRESS_KEY` on events published through the Bridge
+pub const BRIDGE_INGRESS: &str = "bridge";
+
+/// Topics that require a signature when none are configured
+pub const DEFAULT_REQUIRED_TOPICS: &[&str] = &["system.*"];
+
+/// How far a signed event's timestamp may be from the verifier's clock
+pub const DEFAULT_MAX_CLOCK_SKEW: Duration = Duration::from_secs(300);
+
+/// Envelope keys covered by the signature, in signing order; fixed so that new keys do
+/// not invalidate existing signers
+pub const SIGNED_KEYS: &[&str] = &[
+    keys::THREAD_ID,
+    keys::CORRELATION_ID,
+    keys::SENDER,
+    keys::REPLY_TO,
+    keys::TTL,
+    keys::HOP_COUNT,
+    keys::TIMESTAMP_MS,
+    keys::CAUSED_BY,
+    keys::DEADLINE_MS,
+    keys::REPLY_EXPECTED,
+];
+
+/// Signs and verifies events with the deployment's HMAC key
+pub struct EnvelopeSigner {
+    key: hmac::Key,
+    required: Vec<String>,
+    max_skew: Duration,
+    /// Ids of events verified within the skew window, with their timestamps
+    seen: Mutex<HashMap<String, i64>>,
+}
+
+impl std::fmt::Debug for EnvelopeSigner {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("EnvelopeSigner")
+            .field("required", &self.required)
+            .field("max_skew", &self.max_skew)
+            .finish_non_exhaustive()
+    }
+}
+
+impl EnvelopeSigner {
+    /// Signer keyed with `key`, requiring signatures on `DEFAULT_REQUIRED_TOPICS`
+    pub fn new(key: impl AsRef<[u8]>) -> Self {
+        Self {
+            key: hmac::Key::new(hmac::HMAC_SHA256, key.as_ref()),
+            required: DEFAULT_REQUIRED_TOPICS
+                .iter()
+                .map(|p| p.to_string())
+                .collect(),
+            max_skew: DEFAULT_MAX_CLOCK_SKEW,
+            seen: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Replace the allowed distance between a signed timestamp and the local clock
+    pub fn with_max_clock_skew(mut self, max_skew: Duration) -> Self {
+        self.max_skew = max_skew;
+        self
+    }
+
+    /// Replace the topic patterns on which ingress events must be signed
+    pub fn with_required_topics<I, S>(mut self, patterns: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.required = patterns.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Settings from `LOOM_SIGNING_KEY`, `LOOM_SIGNING_REQUIRED_TOPICS` and
+    /// `LOOM_SIGNING_MAX_SKEW_SECS`; `Ok(None)` unless a key is set
+    pub fn from_env() -> Result<Option<Self>> {
+        let Ok(key) = std::env::var("LOOM_SIGNING_KEY") else {
+            return Ok(None);
+        };
+        if key.is_empty() {
+            return Err(LoomError::ConfigError(
+                "LOOM_SIGNING_KEY must not be empty".to_string(),
+            ));
+        }
+        let mut signer = Self::new(key);
+        if let Ok(topics) = std::env::var("LOOM_SIGNING_REQUIRED_TOPICS") {
+            signer = signer.with_required_topics(
+                topics
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|p| !p.is_empty())
+                    .map(str::to_string),
+            );
+        }
+        if let Ok(secs) = std::env::var("LOOM_SIGNING_MAX_SKEW_SECS") {
+            let secs = secs.trim().parse().map_err(|_| {
+                LoomError::ConfigError(format!("Invalid LOOM_SIGNING_MAX_SKEW_SECS: {secs}"))
+            })?;
+            signer = signer.with_max_clock_skew(Duration::from_secs(secs));
+        }
+        Ok(Some(signer))
+    }
+
+    /// Whether ingress events on `topic` must carry a valid signature
+    pub fn requires_signature(&self, topic: &str) -> bool {
+        self.required
+            .iter()
+            .any(|pattern| topic_matches(pattern, topic))
+    }
+
+    /// Hex signature of `event` published to `topic`
+    pub fn signature(&self, topic: &str, event: &Event) -> String {
+        hmac::sign(&self.key, &canonical_form(topic, event))
+            .as_ref()
+            .iter()
+            .map(|b| format!("{b:02x}"))
+            .collect()
+    }
+
+    /// Set the event's `keys::SIGNATURE` for publishing to `topic`, stamping
+    /// `keys::TIMESTAMP_MS` with the current time if it has none; sign after every other
+    /// envelope field is set
+    pub fn sign(&self, topic: &str, event: &mut Event) {
+        event
+            .metadata
+            .entry(keys::TIMESTAMP_MS.to_string())
+            .or_insert_with(|| now_ms().to_string());
+        let signature = self.signature(topic, event);
+        event
+            .metadata
+            .insert(keys::SIGNATURE.to_string(), signature);
+    }
+
+    /// Check the event's `keys::SIGNATURE` for `topic` in constant time, then that it is
+    /// fresh and not a replay. Accepting an event records its id.
+    pub fn verify(&self, topic: &str, event: &Event) -> Result<()> {
+        let signature = event
+            .metadata
+            .get(keys::SIGNATURE)
+            .ok_or_else(|| LoomError::SignatureError("event is not signed".to_string()))?;
+        let tag = decode_hex(signature)
+            .ok_or_else(|| LoomError::SignatureError("signature is not hex".to_string()))?;
+        hmac::verify(&self.key, &canonical_form(topic, event), &tag)
+            .map_err(|_| LoomError::SignatureError("signature does not match".to_string()))?;
+
+        let signed_at = event
+            .metadata
+            .get(keys::TIMESTAMP_MS)
+            .and_then(|ts| ts.parse::<i64>().ok())
+            .ok_or_else(|| {
+                LoomError::SignatureError("signed event has no timestamp".to_string())
+            })?;
+        let now = now_ms();
+        let window = self.max_skew.as_millis() as i64;
+        if (now - signed_at).abs() > window {
+            return Err(LoomError::SignatureError(
+                "timestamp is outside the allowed clock skew".to_string(),
+            ));
+        }
+        let mut seen = self.seen.lock().unwrap();
+        // Older ids cannot come back: their timestamps now fail the check above
+        seen.retain(|_, at| now - *at <= window);
+        if seen.contains_key(&event.id) {
+            return Err(LoomError::SignatureError(
+                "event id was already seen".to_string(),
+            ));
+        }
+        seen.insert(event.id.clone(), signed_at);
+        Ok(())
+    }
+
+    /// Drop `event_id` from the ids already accepted, for an event verified but then
+    /// not published
+    pub(crate) fn forget(&self, event_id: &str) {
+        self.seen.lock().unwrap().remove(event_id);
+    }
+}
+
+/// Bytes covered by the signature (see the module docs)
+fn canonical_form(topic: &str, event: &Event) -> Vec<u8> {
+    fn part(out: &mut Vec<u8>, bytes: &[u8]) {
+        out.extend((bytes.len() as u32).to_be_bytes());
+        out.extend(bytes);
+    }
+
+    let mut out = Vec::with_capacity(event.payload.len() + 256);
+    part(&mut out, topic.as_bytes());
+    part(&mut out, event.id.as_bytes());
+    part(&mut out, event.r#type.as_bytes());
+    part(&mut out, event.source.as_bytes());
+    for key in SIGNED_KEYS {
+        let value = event.metadata.get(*key).map(String::as_str).unwrap_or("");
+        part(&mut out, value.as_bytes());
+    }
+    part(&mut out, &event.payload);
+    out
+}
+
+fn decode_hex(hex: &str) -> Option<Vec<u8>> {
+    if !hex.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+fn now_ms() -> i64 {
+    chrono::Utc::now().timestamp_millis()
+}
@@ -0,0 +1,210 @@
+//! Lifecycle of thread-scoped topics.
+//!
+//! Collaboration threads talk on `thread.<id>.broadcast` and `thread.<id>.reply` (see
+//! `ThreadTopicKind`). Nothing ends a conversation on the bus, so without cleanup the
+//! subscriptions and stats of every thread ever started stay around. The
+//! `ThreadTracker` records when each thread was last active. A thread that stays idle
+//! past its TTL, or is closed with `EventBus::close_thread`, gets a `THREAD_CLOSED` event
+//! on its broadcast topic; then every subscription on its topics is dropped (ending the
+//! subscribers' streams) and its topic stats are removed.
+//!
+//! Threads are tracked once `EventBus::set_thread_ttl` sets a default idle TTL (every
+//! thread topic published or subscribed to is then tracked; `Loom::new` reads it from
+//! `LOOM_THREAD_TTL_MS`) or when opened explicitly with `EventBus::open_thread`.
+
+use crate::messaging::envelope::{keys, ThreadTopicKind};
+use crate::proto::Event;
+use dashmap::DashMap;
+use opentelemetry::{global, metrics::Counter, KeyValue};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::RwLock;
+use std::time::Duration;
+use tokio::time::Instant;
+
+/// Event type published on a thread's broadcast topic when the thread is closed
+pub const THREAD_CLOSED: &str = "thread.closed";
+
+/// Metadata key on `THREAD_CLOSED` events: why the thread was closed
+pub const CLOSE_REASON_KEY: &str = "thread.close_reason";
+
+/// Values of `CLOSE_REASON_KEY`
+pub mod close_reasons {
+    /// Closed with `EventBus::close_thread`
+    pub const CLOSED: &str = "closed";
+    /// Idle for longer than its TTL
+    pub const EXPIRED: &str = "expired";
+}
+
+/// Thread id of a thread topic (`thread.<id>.broadcast` or `thread.<id>.reply`)
+pub fn thread_id_of(topic: &str) -> Option<&str> {
+    let rest = topic.strip_prefix("thread.")?;
+    let id = rest
+        .strip_suffix(".broadcast")
+        .or_else(|| rest.strip_suffix(".reply"))?;
+    (!id.is_empty()).then_some(id)
+}
+
+/// Topics owned by thread `thread_id`
+pub fn thread_topics(thread_id: &str) -> [String; 2] {
+    [
+        ThreadTopicKind::Broadcast.topic(thread_id),
+        ThreadTopicKind::Reply.topic(thread_id),
+    ]
+}
+
+/// Default thread idle TTL configured through `LOOM_THREAD_TTL_MS`
+pub fn thread_ttl_from_env() -> Option<Duration> {
+    std::env::var("LOOM_THREAD_TTL_MS")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .filter(|ms| *ms > 0)
+        .map(Duration::from_millis)
+}
+
+/// A tracked thread, as reported by `ThreadTracker::open_threads`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OpenThread {
+    pub thread_id: String,
+    /// Time since the last publish or subscribe on the thread's topics
+    pub idle: Duration,
+    /// Idle time after which the thread is closed; `None` keeps it until closed explicitly
+    pub ttl: Option<Duration>,
+}
+
+struct ThreadState {
+    last_active: Instant,
+    ttl: Option<Duration>,
+}
+
+/// Open threads and their idle deadlines
+pub struct ThreadTracker {
+    default_ttl: RwLock<Option<Duration>>,
+    threads: DashMap<String, ThreadState>,
+    sweeper_started: AtomicBool,
+    closed_counter: Counter<u64>,
+}
+
+impl ThreadTracker {
+    pub(crate) fn new() -> Self {
+        Self {
+            default_ttl: RwLock::new(None),
+            threads: DashMap::new(),
+            sweeper_started: AtomicBool::new(false),
+            closed_counter: global::meter("loom.event_bus")
+                .u64_counter("loom.event_bus.threads_closed_total")
+                .with_description("Total number of threads closed and cleaned up")
+                .init(),
+        }
+    }
+
+    /// Set the idle TTL of threads without one of their own.
+    /// Returns true on the first call that needs the sweeper running.
+    pub(crate) fn set_default_ttl(&self, ttl: Duration) -> bool {
+        *self.default_ttl.write().unwrap() = Some(ttl);
+        !self.sweeper_started.swap(true, Ordering::SeqCst)
+    }
+
+    /// Idle TTL applied to threads seen on the bus, if any
+    pub fn default_ttl(&self) -> Option<Duration> {
+        *self.default_ttl.read().unwrap()
+    }
+
+    /// Track `thread_id` with its own TTL (`None`: until closed).
+    /// Returns true when the sweeper needs starting.
+    pub(crate) fn open(&self, thread_id: &str, ttl: Option<Duration>) -> bool {
+        self.threads.insert(
+            thread_id.to_string(),
+            ThreadState {
+                last_active: Instant::now(),
+                ttl,
+            },
+        );
+        ttl.is_some() && !self.sweeper_started.swap(true, Ordering::SeqCst)
+    }
+
+    /// Record activity on `topic`. Untracked threads start being tracked only when a
+    /// default TTL is set.
+    pub(crate) fn touch(&self, topic: &str) {
+        let Some(thread_id) = thread_id_of(topic) else {
+            return;
+        };
+        if let Some(mut state) = self.threads.get_mut(thread_id) {
+            state.last_active = Instant::now();
+            return;
+        }
+        if let Some(ttl) = self.default_ttl() {
+            self.threads
+                .entry(thread_id.to_string())
+                .or_insert_with(|| ThreadState {
+                    last_active: Instant::now(),
+                    ttl: Some(ttl),
+                })
+                .last_active = Instant::now();
+        }
+    }
+
+    /// Stop tracking `thread_id`, counting it as closed for `reason`
+    pub(crate) fn forget(&self, thread_id: &str, reason: &str) {
+        self.threads.remove(thread_id);
+        self.closed_counter
+            .add(1, &[KeyValue::new("reason", reason.to_string())]);
+    }
+
+    /// Threads idle past their TTL at `now`
+    pub(crate) fn expired(&self, now: Instant) -> Vec<String> {
+        self.threads
+            .iter()
+            .filter(|entry| {
+                entry
+                    .ttl
+                    .is_some_and(|ttl| now.duration_since(entry.last_active) >= ttl)
+            })
+            .map(|entry| entry.key().clone())
+            .collect()
+    }
+
+    /// Whether `thread_id` is tracked
+    pub fn is_open(&self, thread_id: &str) -> bool {
+        self.threads.contains_key(thread_id)
+    }
+
+    /// Tracked threads, sorted by id
+    pub fn open_threads(&self) -> Vec<OpenThread> {
+        let now = Instant::now();
+        let mut threads: Vec<OpenThread> = self
+            .threads
+            .iter()
+            .map(|entry| OpenThread {
+                thread_id: entry.key().clone(),
+                idle: now.duration_since(entry.last_active),
+                ttl: entry.ttl,
+            })
+            .collect();
+        threads.sort_by(|a, b| a.thread_id.cmp(&b.thread_id));
+        threads
+    }
+}
+
+/// The `THREAD_CLOSED` announcement for `thread_id`
+pub(crate) fn closed_event(thread_id: &str, reason: &str) -> Event {
+    let timestamp_ms = chrono::Utc::now().timestamp_millis();
+    let mut metadata = HashMap::new();
+    metadata.insert(keys::THREAD_ID.to_string(), thread_id.to_string());
+    metadata.insert(CLOSE_REASON_KEY.to_string(), reason.to_string());
+    Event {
+        id: format!("evt_thread_closed_{thread_id}_{timestamp_ms}"),
+        r#type: THREAD_CLOSED.to_string(),
+        timestamp_ms,
+        source: "event_bus".to_string(),
+        metadata,
+        payload: serde_json::to_vec(&serde_json::json!({
+            "thread_id": thread_id,
+            "reason": reason,
+        }))
+        .unwrap_or_default(),
+        confidence: 1.0,
+        tags: vec!["system".into()],
+        priority: 80,
+    }
+}
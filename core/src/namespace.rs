@@ -0,0 +1,142 @@
+//! Namespaces: independent applications sharing one Loom process
+//!
+//! A namespace scopes topics, agents and tools:
+//!
+//! - **Topics**: topic `t` of namespace `ns` is the bus topic `tenant.<ns>.t`. The root is
+//!   shared with tenancy; a tenant's namespace is its id.
+//! - **Agents**: an `AgentRuntime` agent whose `AgentConfig.parameters` set
+//!   [`NAMESPACE_KEY`] subscribes and publishes inside its namespace. Its events are
+//!   stamped with the namespace (`tenancy::TENANT_KEY`) and its tools come from
+//!   `ToolRegistry::for_namespace`.
+//! - **Tools**: `ToolRegistry::register_in` adds a tool to one namespace. The namespace's
+//!   view holds the global tools plus its own, by their plain names; its own shadow
+//!   global ones.
+//!
+//! The Bridge assigns each external agent a namespace at registration: its tenant when
+//! tenancy is on, otherwise the one requested in the registration metadata
+//! ([`NAMESPACE_KEY`]).
+//!
+//! `EventBus::publish` rejects a stamped event whose topic lies outside its namespace,
+//! unless a [`NamespaceBridge`] explicitly allows it:
+//!
+//! ```rust,ignore
+//! let ops = Namespace::new("ops")?;
+//! let shop = Namespace::new("shop")?;
+//! // shop's agents may publish to ops' `alerts.*` topics
+//! event_bus.bridge_namespaces(NamespaceBridge::new(shop, ops, "alerts.*"));
+//! ```
+//!
+//! Unstamped (in-process, un-namespaced) publishers are trusted and unrestricted.
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::messaging::topic_matches;
+use crate::tenancy::{namespace_topic, strip_namespace};
+use crate::{LoomError, Result};
+
+/// `AgentConfig` parameter and Bridge registration metadata key naming an agent's
+/// namespace
+pub const NAMESPACE_KEY: &str = "namespace";
+
+/// Separates a namespace from a tool name in `ToolRegistry` keys (`shop/kb:search`)
+pub const TOOL_NAMESPACE_SEPARATOR: char = '/';
+
+/// A validated namespace id: non-empty, without `.` or `/`
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct Namespace(String);
+
+impl Namespace {
+    pub fn new(id: impl Into<String>) -> Result<Self> {
+        let id = id.into();
+        if id.is_empty() || id.contains(['.', TOOL_NAMESPACE_SEPARATOR]) {
+            return Err(LoomError::NamespaceError(format!(
+                "Invalid namespace '{id}': ids must be non-empty and contain no '.' or '/'"
+            )));
+        }
+        Ok(Self(id))
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// The bus topic of `topic` in this namespace
+    pub fn topic(&self, topic: &str) -> String {
+        namespace_topic(&self.0, topic)
+    }
+
+    /// `topic` if it lies in this namespace already, otherwise its bus topic here
+    pub fn scope(&self, topic: &str) -> String {
+        if self.owns(topic) {
+            topic.to_string()
+        } else {
+            self.topic(topic)
+        }
+    }
+
+    /// The namespace-relative part of a bus topic in this namespace
+    pub fn strip<'a>(&self, topic: &'a str) -> Option<&'a str> {
+        strip_namespace(&self.0, topic)
+    }
+
+    /// Whether bus topic `topic` lies in this namespace
+    pub fn owns(&self, topic: &str) -> bool {
+        self.strip(topic).is_some()
+    }
+
+    /// `ToolRegistry` key of tool `name` registered in this namespace
+    pub fn tool_key(&self, name: &str) -> String {
+        format!("{}{}{}", self.0, TOOL_NAMESPACE_SEPARATOR, name)
+    }
+}
+
+impl fmt::Display for Namespace {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl TryFrom<String> for Namespace {
+    type Error = LoomError;
+
+    fn try_from(id: String) -> Result<Self> {
+        Self::new(id)
+    }
+}
+
+impl From<Namespace> for String {
+    fn from(namespace: Namespace) -> Self {
+        namespace.0
+    }
+}
+
+/// Lets events stamped with namespace `from` be published to `to`'s topics matching
+/// `topics` (a namespace-relative pattern, see `topic_matches`)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NamespaceBridge {
+    pub from: Namespace,
+    pub to: Namespace,
+    pub topics: String,
+}
+
+impl NamespaceBridge {
+    pub fn new(from: Namespace, to: Namespace, topics: impl Into<String>) -> Self {
+        Self {
+            from,
+            to,
+            topics: topics.into(),
+        }
+    }
+
+    /// Whether an event stamped with namespace `from` may be published to bus topic `topic`
+    pub fn allows(&self, from: &str, topic: &str) -> bool {
+        from == self.from.as_str()
+            && self
+                .to
+                .strip(topic)
+                .is_some_and(|relative| topic_matches(&self.topics, relative))
+    }
+}
@@ -0,0 +1,546 @@
+//! OpenAI-compatible Chat Completions Facade
+//!
+//! `OpenAiServer` exposes one agent as `POST /v1/chat/completions` (plus `GET /v1/models`)
+//! so existing chat UIs and OpenAI SDKs can talk to a Loom agent without changes.
+//!
+//! Each request becomes a `user.query` event on the agent's private topic
+//! (`agent.<id>.replies`, which every runtime agent subscribes to). The event carries the
+//! last user message as its payload, `expect_reply=true` and a fresh thread envelope. The
+//! agent answers with an `agent.response` event on the envelope's `reply_to` topic, which
+//! `CognitiveAgent` does automatically; the response text becomes the completion.
+//!
+//! With `"stream": true` the completion is sent as server-sent `chat.completion.chunk`
+//! events ending in `data: [DONE]`. Agents that publish `agent.response.delta` (or
+//! `response.partial`, see `CognitiveAgent::with_response_streaming`) events before their
+//! final response have each delta forwarded as a chunk; otherwise the whole response
+//! arrives as one chunk. `response.final` is accepted as the final response.
+//!
+//! `loom-bridge-server` serves the facade when `LOOM_OPENAI=true`. Env overrides for
+//! `OpenAiConfig::from_env()`:
+//! - LOOM_OPENAI_HOST (default 127.0.0.1)
+//! - LOOM_OPENAI_PORT (default 8088)
+//! - LOOM_OPENAI_AGENT (default `assistant`)
+//! - LOOM_OPENAI_API_KEY (unset: no authentication)
+//! - LOOM_OPENAI_TIMEOUT_MS (default 60000)
+
+use crate::cognitive::{RESPONSE_EVENT, RESPONSE_FINAL_EVENT, RESPONSE_PARTIAL_EVENT};
+use crate::context::{TiktokenCounter, TokenCounter};
+use crate::messaging::collab::is_correlated;
+use crate::{agent_reply_topic, Envelope, Event, EventBus, QoSLevel};
+use axum::{
+    extract::State,
+    http::{header, HeaderMap, StatusCode},
+    response::{
+        sse::{Event as SseEvent, KeepAlive},
+        IntoResponse, Response, Sse,
+    },
+    routing::{get, post},
+    Json, Router,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::convert::Infallible;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::ReceiverStream;
+use tracing::{info, warn};
+
+/// Event type of the queries the facade publishes
+pub const QUERY_EVENT: &str = "user.query";
+
+/// Event type of an incremental response chunk (payload: UTF-8 text)
+pub const RESPONSE_DELTA_EVENT: &str = "agent.response.delta";
+
+/// Facade configuration
+#[derive(Debug, Clone)]
+pub struct OpenAiConfig {
+    pub host: String,
+    pub port: u16,
+    /// Agent that answers every request, whatever `model` the client asks for
+    pub agent_id: String,
+    /// Bearer token clients must send; `None` accepts any client
+    pub api_key: Option<String>,
+    /// How long to wait for the agent's response
+    pub timeout: Duration,
+}
+
+impl Default for OpenAiConfig {
+    fn default() -> Self {
+        Self {
+            host: "127.0.0.1".to_string(),
+            port: 8088,
+            agent_id: "assistant".to_string(),
+            api_key: None,
+            timeout: Duration::from_secs(60),
+        }
+    }
+}
+
+impl OpenAiConfig {
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        Self {
+            host: std::env::var("LOOM_OPENAI_HOST").unwrap_or(defaults.host),
+            port: std::env::var("LOOM_OPENAI_PORT")
+                .ok()
+                .and_then(|p| p.parse().ok())
+                .unwrap_or(defaults.port),
+            agent_id: std::env::var("LOOM_OPENAI_AGENT").unwrap_or(defaults.agent_id),
+            api_key: std::env::var("LOOM_OPENAI_API_KEY")
+                .ok()
+                .filter(|k| !k.is_empty()),
+            timeout: std::env::var("LOOM_OPENAI_TIMEOUT_MS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .map(Duration::from_millis)
+                .unwrap_or(defaults.timeout),
+        }
+    }
+
+    /// Whether `LOOM_OPENAI` asks for the facade to be served
+    pub fn enabled() -> bool {
+        std::env::var("LOOM_OPENAI")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(false)
+    }
+}
+
+/// Body of `POST /v1/chat/completions`; fields the facade does not use are ignored
+#[derive(Debug, Clone, Deserialize)]
+pub struct ChatCompletionRequest {
+    #[serde(default)]
+    pub model: String,
+    pub messages: Vec<ChatMessage>,
+    #[serde(default)]
+    pub stream: bool,
+    #[serde(default)]
+    pub user: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChatMessage {
+    pub role: String,
+    /// A string, or an array of content parts
+    #[serde(default)]
+    pub content: Value,
+}
+
+impl ChatMessage {
+    /// Text of the message; text parts of a multi-part message are joined by newlines
+    pub fn text(&self) -> String {
+        match &self.content {
+            Value::String(text) => text.clone(),
+            Value::Array(parts) => parts
+                .iter()
+                .filter_map(|part| part.get("text").and_then(Value::as_str))
+                .collect::<Vec<_>>()
+                .join("\n"),
+            _ => String::new(),
+        }
+    }
+}
+
+#[derive(Clone)]
+struct ApiState {
+    config: Arc<OpenAiConfig>,
+    event_bus: Arc<EventBus>,
+    counter: Arc<TiktokenCounter>,
+}
+
+/// OpenAI-compatible HTTP server for one agent
+pub struct OpenAiServer {
+    config: OpenAiConfig,
+    event_bus: Arc<EventBus>,
+}
+
+impl OpenAiServer {
+    pub fn new(config: OpenAiConfig, event_bus: Arc<EventBus>) -> Self {
+        Self { config, event_bus }
+    }
+
+    /// Routes of the facade, for serving on an existing listener
+    pub fn router(self) -> Router {
+        let state = ApiState {
+            config: Arc::new(self.config),
+            event_bus: self.event_bus,
+            counter: Arc::new(TiktokenCounter::gpt4()),
+        };
+        Router::new()
+            .route("/v1/chat/completions", post(chat_completions_handler))
+            .route("/v1/models", get(models_handler))
+            .with_state(state)
+    }
+
+    /// Start the server on the configured address
+    pub async fn serve(self) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let addr = format!("{}:{}", self.config.host, self.config.port);
+        let agent_id = self.config.agent_id.clone();
+        let listener = tokio::net::TcpListener::bind(&addr).await?;
+        info!(
+            target: "openai",
+            url = %format!("http://{}/v1", addr),
+            agent_id = %agent_id,
+            "OpenAI-compatible API ready"
+        );
+        axum::serve(listener, self.router()).await?;
+        Ok(())
+    }
+}
+
+/// OpenAI-style error body: `{"error": {"message", "type", "code"}}`
+#[derive(Debug)]
+struct ApiError {
+    status: StatusCode,
+    kind: &'static str,
+    message: String,
+}
+
+impl ApiError {
+    fn new(status: StatusCode, kind: &'static str, message: impl Into<String>) -> Self {
+        Self {
+            status,
+            kind,
+            message: message.into(),
+        }
+    }
+
+    fn invalid_request(message: impl Into<String>) -> Self {
+        Self::new(StatusCode::BAD_REQUEST, "invalid_request_error", message)
+    }
+
+    fn internal(error: impl std::fmt::Display) -> Self {
+        Self::new(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "server_error",
+            error.to_string(),
+        )
+    }
+
+    fn body(&self) -> Value {
+        json!({
+            "error": {
+                "message": self.message,
+                "type": self.kind,
+                "code": Value::Null,
+            }
+        })
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        (self.status, Json(self.body())).into_response()
+    }
+}
+
+impl ApiState {
+    fn authorize(&self, headers: &HeaderMap) -> Result<(), ApiError> {
+        let Some(ref key) = self.config.api_key else {
+            return Ok(());
+        };
+        let presented = headers
+            .get(header::AUTHORIZATION)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.strip_prefix("Bearer "))
+            .map(str::trim);
+        if presented == Some(key.as_str()) {
+            Ok(())
+        } else {
+            Err(ApiError::new(
+                StatusCode::UNAUTHORIZED,
+                "invalid_request_error",
+                "Invalid API key",
+            ))
+        }
+    }
+}
+
+static COMPLETION_SEQ: AtomicU64 = AtomicU64::new(0);
+
+fn next_completion_id() -> String {
+    format!(
+        "chatcmpl-{:x}{:04x}",
+        chrono::Utc::now().timestamp_millis(),
+        COMPLETION_SEQ.fetch_add(1, Ordering::Relaxed) & 0xffff
+    )
+}
+
+/// `user.query` event for the last user message, addressed back to `envelope.reply_to`
+fn query_event(
+    id: &str,
+    envelope: &Envelope,
+    request: &ChatCompletionRequest,
+    query: &str,
+) -> Event {
+    let mut metadata = HashMap::new();
+    metadata.insert("text".to_string(), query.to_string());
+    metadata.insert("model".to_string(), request.model.clone());
+    let system = request
+        .messages
+        .iter()
+        .filter(|m| m.role == "system")
+        .map(ChatMessage::text)
+        .collect::<Vec<_>>()
+        .join("\n");
+    if !system.is_empty() {
+        metadata.insert("system".to_string(), system);
+    }
+    if let Some(ref user) = request.user {
+        metadata.insert("user".to_string(), user.clone());
+    }
+    if let Ok(messages) = serde_json::to_string(&request.messages) {
+        metadata.insert("messages".to_string(), messages);
+    }
+
+    let mut event = Event {
+        id: id.to_string(),
+        r#type: QUERY_EVENT.to_string(),
+        timestamp_ms: chrono::Utc::now().timestamp_millis(),
+        source: "openai".to_string(),
+        metadata,
+        payload: query.as_bytes().to_vec(),
+        confidence: 1.0,
+        tags: vec!["openai".to_string()],
+        priority: 50,
+    };
+    envelope.attach_to_event(&mut event);
+    event
+}
+
+enum Reply {
+    Delta(String),
+    Done(String),
+}
+
+/// Responses to one request; unsubscribes from the reply topic when dropped
+struct Replies {
+    event_bus: Arc<EventBus>,
+    subscription_id: String,
+    rx: mpsc::Receiver<Event>,
+    correlation_id: String,
+    deadline: tokio::time::Instant,
+}
+
+impl Replies {
+    async fn next(&mut self) -> Result<Reply, ApiError> {
+        loop {
+            let event = match tokio::time::timeout_at(self.deadline, self.rx.recv()).await {
+                Ok(Some(event)) => event,
+                Ok(None) => return Err(ApiError::internal("reply subscription closed")),
+                Err(_) => {
+                    return Err(ApiError::new(
+                        StatusCode::GATEWAY_TIMEOUT,
+                        "timeout",
+                        "Timed out waiting for the agent's response",
+                    ))
+                }
+            };
+            if !is_correlated(&event, &self.correlation_id) {
+                continue;
+            }
+            let text = String::from_utf8_lossy(&event.payload).into_owned();
+            return Ok(
+                if event.r#type == RESPONSE_DELTA_EVENT || event.r#type == RESPONSE_PARTIAL_EVENT {
+                    Reply::Delta(text)
+                } else {
+                    Reply::Done(text)
+                },
+            );
+        }
+    }
+
+    /// The final response, or the concatenated deltas if the final response is empty
+    async fn collect(&mut self) -> Result<String, ApiError> {
+        let mut streamed = String::new();
+        loop {
+            match self.next().await? {
+                Reply::Delta(text) => streamed.push_str(&text),
+                Reply::Done(text) if text.is_empty() => return Ok(streamed),
+                Reply::Done(text) => return Ok(text),
+            }
+        }
+    }
+}
+
+impl Drop for Replies {
+    fn drop(&mut self) {
+        let event_bus = Arc::clone(&self.event_bus);
+        let subscription_id = std::mem::take(&mut self.subscription_id);
+        tokio::spawn(async move {
+            let _ = event_bus.unsubscribe(&subscription_id).await;
+        });
+    }
+}
+
+async fn chat_completions_handler(
+    State(state): State<ApiState>,
+    headers: HeaderMap,
+    Json(request): Json<ChatCompletionRequest>,
+) -> Result<Response, ApiError> {
+    state.authorize(&headers)?;
+    let query = request
+        .messages
+        .iter()
+        .rev()
+        .find(|m| m.role == "user")
+        .map(ChatMessage::text)
+        .filter(|text| !text.trim().is_empty())
+        .ok_or_else(|| ApiError::invalid_request("messages must include a user message"))?;
+    let model = if request.model.is_empty() {
+        state.config.agent_id.clone()
+    } else {
+        request.model.clone()
+    };
+
+    // Subscribe to the reply topic before publishing so a fast agent cannot be missed
+    let id = next_completion_id();
+    let envelope = Envelope::new(id.clone(), "openai")
+        .with_reply_expected(true)
+        .with_timeout(state.config.timeout);
+    let (subscription_id, rx) = state
+        .event_bus
+        .subscribe(
+            envelope.reply_to.clone(),
+            vec![
+                RESPONSE_EVENT.to_string(),
+                RESPONSE_DELTA_EVENT.to_string(),
+                RESPONSE_PARTIAL_EVENT.to_string(),
+                RESPONSE_FINAL_EVENT.to_string(),
+            ],
+            QoSLevel::QosBatched,
+        )
+        .await
+        .map_err(ApiError::internal)?;
+    let mut replies = Replies {
+        event_bus: Arc::clone(&state.event_bus),
+        subscription_id,
+        rx,
+        correlation_id: envelope.correlation_id.clone(),
+        deadline: tokio::time::Instant::now() + state.config.timeout,
+    };
+
+    let topic = agent_reply_topic(&state.config.agent_id);
+    let delivered = state
+        .event_bus
+        .publish(&topic, query_event(&id, &envelope, &request, &query))
+        .await
+        .map_err(ApiError::internal)?;
+    if delivered == 0 {
+        warn!(target: "openai", agent_id = %state.config.agent_id, "No agent received the query");
+        return Err(ApiError::new(
+            StatusCode::SERVICE_UNAVAILABLE,
+            "server_error",
+            format!("Agent '{}' is not running", state.config.agent_id),
+        ));
+    }
+
+    if request.stream {
+        return Ok(stream_completion(replies, id, model).into_response());
+    }
+
+    let text = replies.collect().await?;
+    let prompt_tokens: usize = request
+        .messages
+        .iter()
+        .map(|m| state.counter.count_text(&m.text()))
+        .sum();
+    let completion_tokens = state.counter.count_text(&text);
+    Ok(Json(json!({
+        "id": id,
+        "object": "chat.completion",
+        "created": chrono::Utc::now().timestamp(),
+        "model": model,
+        "choices": [{
+            "index": 0,
+            "message": { "role": "assistant", "content": text },
+            "finish_reason": "stop",
+        }],
+        "usage": {
+            "prompt_tokens": prompt_tokens,
+            "completion_tokens": completion_tokens,
+            "total_tokens": prompt_tokens + completion_tokens,
+        },
+    }))
+    .into_response())
+}
+
+/// Forward the agent's response as `chat.completion.chunk` events
+fn stream_completion(
+    mut replies: Replies,
+    id: String,
+    model: String,
+) -> Sse<ReceiverStream<Result<SseEvent, Infallible>>> {
+    let (tx, rx) = mpsc::channel(32);
+    let created = chrono::Utc::now().timestamp();
+    tokio::spawn(async move {
+        let chunk = |delta: Value, finish_reason: Option<&str>| -> Result<SseEvent, Infallible> {
+            let body = json!({
+                "id": id,
+                "object": "chat.completion.chunk",
+                "created": created,
+                "model": model,
+                "choices": [{ "index": 0, "delta": delta, "finish_reason": finish_reason }],
+            });
+            Ok(SseEvent::default().data(body.to_string()))
+        };
+
+        if tx
+            .send(chunk(json!({ "role": "assistant" }), None))
+            .await
+            .is_err()
+        {
+            return;
+        }
+        let mut streamed = false;
+        loop {
+            let content = match replies.next().await {
+                Ok(Reply::Delta(text)) => {
+                    streamed = true;
+                    text
+                }
+                Ok(Reply::Done(text)) if streamed || text.is_empty() => break,
+                Ok(Reply::Done(text)) => {
+                    let _ = tx.send(chunk(json!({ "content": text }), None)).await;
+                    break;
+                }
+                Err(e) => {
+                    let _ = tx
+                        .send(Ok(SseEvent::default().data(e.body().to_string())))
+                        .await;
+                    return;
+                }
+            };
+            if tx
+                .send(chunk(json!({ "content": content }), None))
+                .await
+                .is_err()
+            {
+                // Client went away
+                return;
+            }
+        }
+        let _ = tx.send(chunk(json!({}), Some("stop"))).await;
+        let _ = tx.send(Ok(SseEvent::default().data("[DONE]"))).await;
+    });
+    Sse::new(ReceiverStream::new(rx)).keep_alive(KeepAlive::default())
+}
+
+async fn models_handler(
+    State(state): State<ApiState>,
+    headers: HeaderMap,
+) -> Result<Json<Value>, ApiError> {
+    state.authorize(&headers)?;
+    Ok(Json(json!({
+        "object": "list",
+        "data": [{
+            "id": state.config.agent_id,
+            "object": "model",
+            "created": 0,
+            "owned_by": "loom",
+        }],
+    })))
+}
@@ -0,0 +1,369 @@
+//! Dedicated runtimes for blocking and IO-heavy work
+//!
+//! Audio engines (TTS/STT), blocking tool work (file IO, subprocesses) and LLM HTTP
+//! calls run on their own Tokio runtimes instead of the global one, so a burst of slow
+//! work in one area cannot starve the event bus and agent loops.
+//!
+//! Each `DedicatedPool` bounds its in-flight work (`workers + max_queue`). When full,
+//! the `OverloadPolicy` either rejects with `LoomError::Overloaded` or runs the work on
+//! the caller's runtime.
+//!
+//! Env overrides for `RuntimePoolsConfig::default()`:
+//! - LOOM_POOL_AUDIO_WORKERS, LOOM_POOL_AUDIO_QUEUE
+//! - LOOM_POOL_TOOLS_WORKERS, LOOM_POOL_TOOLS_QUEUE
+//! - LOOM_POOL_LLM_WORKERS, LOOM_POOL_LLM_QUEUE
+//! - LOOM_POOL_OVERLOAD (`reject` | `caller_runs`)
+
+use crate::tools::{Tool, ToolError, ToolResult};
+use crate::{LoomError, Result};
+use async_trait::async_trait;
+use opentelemetry::{
+    global,
+    metrics::{Counter, Histogram, UpDownCounter},
+    KeyValue,
+};
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use tokio::runtime::{Builder, Handle, Runtime};
+use tracing::{info, warn};
+
+/// What to do when a pool is at capacity
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OverloadPolicy {
+    /// Fail fast with `LoomError::Overloaded`
+    Reject,
+    /// Run the work on the caller's runtime instead
+    CallerRuns,
+}
+
+impl std::str::FromStr for OverloadPolicy {
+    type Err = LoomError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "reject" => Ok(Self::Reject),
+            "caller_runs" | "caller-runs" => Ok(Self::CallerRuns),
+            other => Err(LoomError::AgentError(format!(
+                "Unknown overload policy: {other}"
+            ))),
+        }
+    }
+}
+
+/// Sizing and overload behavior of a single pool
+#[derive(Debug, Clone)]
+pub struct PoolConfig {
+    /// Worker threads (and blocking threads) of the dedicated runtime
+    pub workers: usize,
+    /// Work items allowed to wait beyond `workers` before overload kicks in
+    pub max_queue: usize,
+    pub overload: OverloadPolicy,
+}
+
+impl PoolConfig {
+    pub fn new(workers: usize, max_queue: usize) -> Self {
+        Self {
+            workers: workers.max(1),
+            max_queue,
+            overload: OverloadPolicy::Reject,
+        }
+    }
+
+    pub fn with_overload(mut self, overload: OverloadPolicy) -> Self {
+        self.overload = overload;
+        self
+    }
+
+    fn from_env(prefix: &str, workers: usize, max_queue: usize) -> Self {
+        let read = |key: &str, default: usize| {
+            std::env::var(format!("LOOM_POOL_{prefix}_{key}"))
+                .ok()
+                .and_then(|v| v.parse::<usize>().ok())
+                .unwrap_or(default)
+        };
+        let overload = std::env::var("LOOM_POOL_OVERLOAD")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(OverloadPolicy::Reject);
+        Self::new(read("WORKERS", workers), read("QUEUE", max_queue)).with_overload(overload)
+    }
+}
+
+/// Configuration for all dedicated pools
+#[derive(Debug, Clone)]
+pub struct RuntimePoolsConfig {
+    pub audio: PoolConfig,
+    pub tools_blocking: PoolConfig,
+    pub llm_io: PoolConfig,
+}
+
+impl Default for RuntimePoolsConfig {
+    fn default() -> Self {
+        Self {
+            audio: PoolConfig::from_env("AUDIO", 2, 16),
+            tools_blocking: PoolConfig::from_env("TOOLS", 4, 64),
+            llm_io: PoolConfig::from_env("LLM", 2, 256),
+        }
+    }
+}
+
+/// A dedicated Tokio runtime with bounded in-flight work
+pub struct DedicatedPool {
+    name: &'static str,
+    config: PoolConfig,
+    runtime: Mutex<Option<Runtime>>,
+    handle: Handle,
+    inflight: Arc<AtomicUsize>,
+    closed: AtomicBool,
+
+    // OpenTelemetry metrics
+    inflight_gauge: UpDownCounter<i64>,
+    queue_depth: Histogram<u64>,
+    rejected_counter: Counter<u64>,
+}
+
+impl std::fmt::Debug for DedicatedPool {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DedicatedPool")
+            .field("name", &self.name)
+            .field("config", &self.config)
+            .field("inflight", &self.inflight())
+            .finish()
+    }
+}
+
+/// Decrements the in-flight count when the work item finishes (or is dropped)
+struct InflightGuard {
+    inflight: Arc<AtomicUsize>,
+    gauge: UpDownCounter<i64>,
+    attrs: [KeyValue; 1],
+}
+
+impl Drop for InflightGuard {
+    fn drop(&mut self) {
+        self.inflight.fetch_sub(1, Ordering::SeqCst);
+        self.gauge.add(-1, &self.attrs);
+    }
+}
+
+enum Admission {
+    Pooled(InflightGuard),
+    CallerRuns,
+}
+
+impl DedicatedPool {
+    pub fn new(name: &'static str, config: PoolConfig) -> Result<Self> {
+        let runtime = Builder::new_multi_thread()
+            .worker_threads(config.workers)
+            .max_blocking_threads(config.workers)
+            .thread_name(format!("loom-{name}"))
+            .enable_all()
+            .build()?;
+        let handle = runtime.handle().clone();
+
+        let meter = global::meter("loom.pools");
+        let inflight_gauge = meter
+            .i64_up_down_counter("loom.pools.inflight")
+            .with_description("Work items submitted to a dedicated pool and not yet finished")
+            .init();
+        let queue_depth = meter
+            .u64_histogram("loom.pools.queue_depth")
+            .with_description("Items waiting beyond the pool's workers at submit time")
+            .init();
+        let rejected_counter = meter
+            .u64_counter("loom.pools.rejected_total")
+            .with_description("Work items rejected because the pool was full")
+            .init();
+
+        info!(target: "pools", pool = name, workers = config.workers, max_queue = config.max_queue, "Dedicated pool started");
+        Ok(Self {
+            name,
+            config,
+            runtime: Mutex::new(Some(runtime)),
+            handle,
+            inflight: Arc::new(AtomicUsize::new(0)),
+            closed: AtomicBool::new(false),
+            inflight_gauge,
+            queue_depth,
+            rejected_counter,
+        })
+    }
+
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    pub fn config(&self) -> &PoolConfig {
+        &self.config
+    }
+
+    /// Work items submitted and not yet finished
+    pub fn inflight(&self) -> usize {
+        self.inflight.load(Ordering::SeqCst)
+    }
+
+    /// Work items waiting for a free worker
+    pub fn queue_depth(&self) -> usize {
+        self.inflight().saturating_sub(self.config.workers)
+    }
+
+    fn admit(&self) -> Result<Admission> {
+        if self.closed.load(Ordering::SeqCst) {
+            return Err(LoomError::Overloaded(format!(
+                "{} pool is shut down",
+                self.name
+            )));
+        }
+        let attrs = [KeyValue::new("pool", self.name)];
+        let capacity = self.config.workers + self.config.max_queue;
+        let prev = self.inflight.fetch_add(1, Ordering::SeqCst);
+        if prev >= capacity {
+            self.inflight.fetch_sub(1, Ordering::SeqCst);
+            return match self.config.overload {
+                OverloadPolicy::Reject => {
+                    self.rejected_counter.add(1, &attrs);
+                    warn!(target: "pools", pool = self.name, inflight = prev, "Pool overloaded; rejecting work");
+                    Err(LoomError::Overloaded(format!(
+                        "{} pool full ({} in flight)",
+                        self.name, prev
+                    )))
+                }
+                OverloadPolicy::CallerRuns => Ok(Admission::CallerRuns),
+            };
+        }
+        self.inflight_gauge.add(1, &attrs);
+        self.queue_depth
+            .record(prev.saturating_sub(self.config.workers) as u64, &attrs);
+        Ok(Admission::Pooled(InflightGuard {
+            inflight: Arc::clone(&self.inflight),
+            gauge: self.inflight_gauge.clone(),
+            attrs,
+        }))
+    }
+
+    /// Run a future on this pool's runtime
+    pub async fn run<F>(&self, fut: F) -> Result<F::Output>
+    where
+        F: Future + Send + 'static,
+        F::Output: Send + 'static,
+    {
+        match self.admit()? {
+            Admission::Pooled(guard) => self
+                .handle
+                .spawn(async move {
+                    let _guard = guard;
+                    fut.await
+                })
+                .await
+                .map_err(|e| LoomError::AgentError(format!("{} pool task failed: {e}", self.name))),
+            Admission::CallerRuns => Ok(fut.await),
+        }
+    }
+
+    /// Run a blocking closure on this pool's blocking threads
+    pub async fn run_blocking<F, R>(&self, f: F) -> Result<R>
+    where
+        F: FnOnce() -> R + Send + 'static,
+        R: Send + 'static,
+    {
+        let join = match self.admit()? {
+            Admission::Pooled(guard) => self.handle.spawn_blocking(move || {
+                let _guard = guard;
+                f()
+            }),
+            Admission::CallerRuns => tokio::task::spawn_blocking(f),
+        };
+        join.await
+            .map_err(|e| LoomError::AgentError(format!("{} pool task failed: {e}", self.name)))
+    }
+
+    /// Stop accepting work and shut the runtime down without blocking the caller
+    pub fn shutdown(&self) {
+        self.closed.store(true, Ordering::SeqCst);
+        if let Some(rt) = self.runtime.lock().unwrap().take() {
+            rt.shutdown_background();
+            info!(target: "pools", pool = self.name, "Dedicated pool shut down");
+        }
+    }
+}
+
+impl Drop for DedicatedPool {
+    fn drop(&mut self) {
+        // Dropping a Runtime from async context panics; always detach instead
+        if let Some(rt) = self.runtime.get_mut().unwrap().take() {
+            rt.shutdown_background();
+        }
+    }
+}
+
+/// The set of dedicated pools owned by `Loom`
+#[derive(Debug, Clone)]
+pub struct RuntimePools {
+    pub audio: Arc<DedicatedPool>,
+    pub tools_blocking: Arc<DedicatedPool>,
+    pub llm_io: Arc<DedicatedPool>,
+}
+
+impl RuntimePools {
+    pub fn new(config: RuntimePoolsConfig) -> Result<Self> {
+        Ok(Self {
+            audio: Arc::new(DedicatedPool::new("audio", config.audio)?),
+            tools_blocking: Arc::new(DedicatedPool::new("tools", config.tools_blocking)?),
+            llm_io: Arc::new(DedicatedPool::new("llm", config.llm_io)?),
+        })
+    }
+
+    pub fn shutdown(&self) {
+        self.audio.shutdown();
+        self.tools_blocking.shutdown();
+        self.llm_io.shutdown();
+    }
+}
+
+/// Tool wrapper that executes the inner tool on a dedicated pool
+///
+/// Tools built on `tokio::fs` or `spawn_blocking` then use the pool's blocking threads
+/// instead of the global ones.
+pub struct PooledTool {
+    inner: Arc<dyn Tool>,
+    pool: Arc<DedicatedPool>,
+}
+
+impl PooledTool {
+    pub fn new(inner: Arc<dyn Tool>, pool: Arc<DedicatedPool>) -> Self {
+        Self { inner, pool }
+    }
+}
+
+#[async_trait]
+impl Tool for PooledTool {
+    fn name(&self) -> String {
+        self.inner.name()
+    }
+
+    fn description(&self) -> String {
+        self.inner.description()
+    }
+
+    fn parameters(&self) -> serde_json::Value {
+        self.inner.parameters()
+    }
+
+    fn output_schema(&self) -> Option<serde_json::Value> {
+        self.inner.output_schema()
+    }
+
+    async fn call(&self, arguments: serde_json::Value) -> ToolResult<serde_json::Value> {
+        let inner = Arc::clone(&self.inner);
+        match self
+            .pool
+            .run(async move { inner.call(arguments).await })
+            .await
+        {
+            Ok(result) => result,
+            Err(LoomError::Overloaded(msg)) => Err(ToolError::ExecutionFailed(msg)),
+            Err(e) => Err(ToolError::Internal(e.to_string())),
+        }
+    }
+}
@@ -0,0 +1,930 @@
+//! Trace recording and deterministic replay of agent runs
+//!
+//! A `TraceRecorder` writes what an agent consumes to a JSON Lines trace file: every
+//! event handed to `AgentBehavior::on_event` (as the behavior saw it, routing annotations
+//! included), the actions it returned, and every tool result it got back, tagged with
+//! the correlation id of the event being handled (the event id if it has none). Wrap the
+//! behavior in a `RecordingBehavior` and build it with the registry returned by
+//! `TraceRecorder::record_tools`:
+//!
+//! ```rust,ignore
+//! let recorder = TraceRecorder::create("traces/planner.jsonl")?;
+//! let tools = Arc::new(recorder.record_tools(&loom.tool_registry).await);
+//! let behavior = CognitiveAgent::new(SimpleCognitiveLoop::new(config, llm, tools));
+//! let behavior = RecordingBehavior::new(behavior, Arc::clone(&recorder));
+//! runtime.create_agent(agent_config, Box::new(behavior)).await?;
+//! ```
+//!
+//! `ReplayRuntime` loads a trace and feeds its events, in order, to a fresh behavior.
+//! Tools in `ReplayRuntime::tools` answer from the trace instead of running, so the
+//! behavior gets the results of the recorded run without the bus, the network or any
+//! side effects, and its decisions can be stepped through offline. The report lists the
+//! events whose actions differ from the recorded ones. Anything the trace does not cover
+//! (LLM calls, clocks, randomness) has to be stubbed by the caller.
+
+use crate::agent::AgentBehavior;
+use crate::messaging::EventExt;
+use crate::proto::{Action, AgentConfig, AgentState, Event};
+use crate::tools::{caller_agent_id, with_caller, Tool, ToolError, ToolRegistry, ToolResult};
+use crate::tools::{CancellationToken, ToolPolicy};
+use crate::{LoomError, Result};
+use async_trait::async_trait;
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{HashMap, VecDeque};
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use tracing::{debug, info, warn};
+
+/// An `Event` as stored in a trace. UTF-8 payloads are kept readable, others are base64.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TracedEvent {
+    pub id: String,
+    #[serde(rename = "type")]
+    pub event_type: String,
+    pub timestamp_ms: i64,
+    pub source: String,
+    #[serde(default)]
+    pub metadata: HashMap<String, String>,
+    #[serde(default)]
+    pub payload: String,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub payload_base64: bool,
+    pub confidence: f32,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    pub priority: i32,
+}
+
+impl TracedEvent {
+    pub fn from_event(event: &Event) -> Self {
+        let (payload, payload_base64) = encode_payload(&event.payload);
+        Self {
+            id: event.id.clone(),
+            event_type: event.r#type.clone(),
+            timestamp_ms: event.timestamp_ms,
+            source: event.source.clone(),
+            metadata: event.metadata.clone(),
+            payload,
+            payload_base64,
+            confidence: event.confidence,
+            tags: event.tags.clone(),
+            priority: event.priority,
+        }
+    }
+
+    pub fn to_event(&self) -> Event {
+        Event {
+            id: self.id.clone(),
+            r#type: self.event_type.clone(),
+            timestamp_ms: self.timestamp_ms,
+            source: self.source.clone(),
+            metadata: self.metadata.clone(),
+            payload: decode_payload(&self.payload, self.payload_base64),
+            confidence: self.confidence,
+            tags: self.tags.clone(),
+            priority: self.priority,
+        }
+    }
+}
+
+/// An `Action` as stored in a trace, payload encoded like `TracedEvent`'s
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TracedAction {
+    pub action_type: String,
+    #[serde(default)]
+    pub parameters: HashMap<String, String>,
+    #[serde(default)]
+    pub payload: String,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub payload_base64: bool,
+    pub priority: i32,
+}
+
+impl TracedAction {
+    pub fn from_action(action: &Action) -> Self {
+        let (payload, payload_base64) = encode_payload(&action.payload);
+        Self {
+            action_type: action.action_type.clone(),
+            parameters: action.parameters.clone(),
+            payload,
+            payload_base64,
+            priority: action.priority,
+        }
+    }
+
+    pub fn to_action(&self) -> Action {
+        Action {
+            action_type: self.action_type.clone(),
+            parameters: self.parameters.clone(),
+            payload: decode_payload(&self.payload, self.payload_base64),
+            priority: self.priority,
+        }
+    }
+}
+
+/// Name, description and schemas of a recorded tool
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TracedToolSpec {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub parameters: Value,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output_schema: Option<Value>,
+}
+
+impl TracedToolSpec {
+    fn of(tool: &dyn Tool) -> Self {
+        Self {
+            name: tool.name(),
+            description: tool.description(),
+            parameters: tool.parameters(),
+            output_schema: tool.output_schema(),
+        }
+    }
+}
+
+/// A `ToolError` as stored in a trace: its variant and message
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TracedToolError {
+    pub kind: String,
+    #[serde(default)]
+    pub message: String,
+}
+
+impl TracedToolError {
+    pub fn from_error(error: &ToolError) -> Self {
+        let (kind, message) = match error {
+            ToolError::NotFound(m) => ("not_found", m.clone()),
+            ToolError::InvalidArguments(m) => ("invalid_arguments", m.clone()),
+            ToolError::ExecutionFailed(m) => ("execution_failed", m.clone()),
+            ToolError::InvalidOutput(m) => ("invalid_output", m.clone()),
+            ToolError::PermissionDenied(m) => ("permission_denied", m.clone()),
+            ToolError::Timeout => ("timeout", String::new()),
+            ToolError::Cancelled => ("cancelled", String::new()),
+            ToolError::CircuitOpen(m) => ("circuit_open", m.clone()),
+            ToolError::Internal(m) => ("internal", m.clone()),
+        };
+        Self {
+            kind: kind.to_string(),
+            message,
+        }
+    }
+
+    /// The recorded error; unknown kinds come back as `ToolError::Internal`
+    pub fn to_error(&self) -> ToolError {
+        let message = self.message.clone();
+        match self.kind.as_str() {
+            "not_found" => ToolError::NotFound(message),
+            "invalid_arguments" => ToolError::InvalidArguments(message),
+            "execution_failed" => ToolError::ExecutionFailed(message),
+            "invalid_output" => ToolError::InvalidOutput(message),
+            "permission_denied" => ToolError::PermissionDenied(message),
+            "timeout" => ToolError::Timeout,
+            "cancelled" => ToolError::Cancelled,
+            "circuit_open" => ToolError::CircuitOpen(message),
+            _ => ToolError::Internal(message),
+        }
+    }
+}
+
+/// One line of a trace file
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum TraceEntry {
+    /// The recorded agent, written by `on_init`
+    Init {
+        agent_id: String,
+        #[serde(default)]
+        agent_type: String,
+        #[serde(default)]
+        parameters: HashMap<String, String>,
+    },
+    /// Tools the behavior was given, written by `TraceRecorder::record_tools`
+    Tools { tools: Vec<TracedToolSpec> },
+    /// An event handed to `on_event`; `seq` counts from 1 over the whole trace
+    Event {
+        seq: u64,
+        correlation_id: String,
+        event: TracedEvent,
+    },
+    /// Result of one tool call; `correlation_id` is that of the event being handled
+    ToolResult {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        correlation_id: Option<String>,
+        tool: String,
+        #[serde(default)]
+        arguments: Value,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        output: Option<Value>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        error: Option<TracedToolError>,
+    },
+    /// What `on_event` returned for event `seq`
+    Actions {
+        seq: u64,
+        #[serde(default)]
+        actions: Vec<TracedAction>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        error: Option<String>,
+    },
+}
+
+/// A recorded run, in recording order
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Trace {
+    pub entries: Vec<TraceEntry>,
+}
+
+impl Trace {
+    /// Read a trace file; blank lines are skipped
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let reader = BufReader::new(File::open(path)?);
+        let mut entries = Vec::new();
+        for (index, line) in reader.lines().enumerate() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let entry = serde_json::from_str(&line).map_err(|e| {
+                LoomError::StorageError(format!(
+                    "{}:{}: invalid trace entry: {}",
+                    path.display(),
+                    index + 1,
+                    e
+                ))
+            })?;
+            entries.push(entry);
+        }
+        Ok(Self { entries })
+    }
+
+    /// Write the trace as JSON Lines
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        for entry in &self.entries {
+            serde_json::to_writer(&mut writer, entry)?;
+            writer.write_all(b"\n")?;
+        }
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// Id of the recorded agent, if the trace saw its `on_init`
+    pub fn agent_id(&self) -> Option<&str> {
+        self.entries.iter().find_map(|entry| match entry {
+            TraceEntry::Init { agent_id, .. } => Some(agent_id.as_str()),
+            _ => None,
+        })
+    }
+
+    /// Number of recorded events
+    pub fn event_count(&self) -> usize {
+        self.entries
+            .iter()
+            .filter(|entry| matches!(entry, TraceEntry::Event { .. }))
+            .count()
+    }
+
+    /// Tool results recorded while handling the event with `correlation_id`
+    pub fn tool_results(&self, correlation_id: &str) -> Vec<&TraceEntry> {
+        self.entries
+            .iter()
+            .filter(|entry| {
+                matches!(entry, TraceEntry::ToolResult { correlation_id: Some(id), .. } if id == correlation_id)
+            })
+            .collect()
+    }
+}
+
+enum TraceSink {
+    File(BufWriter<File>),
+    Memory(Vec<TraceEntry>),
+}
+
+/// Writes a trace of one agent's run
+///
+/// Entries are flushed as they are written, so the trace of a crashed agent is usable.
+/// Write failures are logged and never fail the agent.
+pub struct TraceRecorder {
+    sink: Mutex<TraceSink>,
+    path: Option<PathBuf>,
+    agent_id: Mutex<Option<String>>,
+    // Correlation id of the event being handled
+    current: Mutex<Option<String>>,
+    seq: AtomicU64,
+}
+
+impl TraceRecorder {
+    /// Record to `path`, replacing an existing file
+    pub fn create(path: impl AsRef<Path>) -> Result<Arc<Self>> {
+        let path = path.as_ref();
+        if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            std::fs::create_dir_all(dir)?;
+        }
+        let file = File::create(path)?;
+        Ok(Arc::new(Self::with_sink(
+            TraceSink::File(BufWriter::new(file)),
+            Some(path.to_path_buf()),
+        )))
+    }
+
+    /// Record in memory; read the entries back with `trace`
+    pub fn in_memory() -> Arc<Self> {
+        Arc::new(Self::with_sink(TraceSink::Memory(Vec::new()), None))
+    }
+
+    fn with_sink(sink: TraceSink, path: Option<PathBuf>) -> Self {
+        Self {
+            sink: Mutex::new(sink),
+            path,
+            agent_id: Mutex::new(None),
+            current: Mutex::new(None),
+            seq: AtomicU64::new(0),
+        }
+    }
+
+    /// File the trace is written to, if any
+    pub fn path(&self) -> Option<&Path> {
+        self.path.as_deref()
+    }
+
+    /// Everything recorded so far
+    pub fn trace(&self) -> Result<Trace> {
+        match &mut *self.sink.lock().unwrap() {
+            TraceSink::Memory(entries) => Ok(Trace {
+                entries: entries.clone(),
+            }),
+            TraceSink::File(writer) => {
+                writer.flush()?;
+                match &self.path {
+                    Some(path) => Trace::load(path),
+                    None => Ok(Trace::default()),
+                }
+            }
+        }
+    }
+
+    /// A registry with every tool `source` lists, wrapped to record its results.
+    ///
+    /// Per-tool policies and strict output checking are copied. Results are recorded per
+    /// attempt, so retries show up as several results; a call cut off by its timeout or
+    /// cancellation leaves none.
+    pub async fn record_tools(self: &Arc<Self>, source: &ToolRegistry) -> ToolRegistry {
+        let recorded = ToolRegistry::new();
+        recorded.set_strict_outputs(source.strict_outputs());
+        let mut specs = Vec::new();
+        for tool in source.list_tools() {
+            let name = tool.name();
+            specs.push(TracedToolSpec::of(tool.as_ref()));
+            recorded.set_policy(&name, source.policy_for(&name));
+            recorded
+                .register(Arc::new(RecordingTool {
+                    inner: tool,
+                    recorder: Arc::clone(self),
+                }))
+                .await;
+        }
+        specs.sort_by(|a, b| a.name.cmp(&b.name));
+        self.write(TraceEntry::Tools { tools: specs });
+        recorded
+    }
+
+    fn write(&self, entry: TraceEntry) {
+        match &mut *self.sink.lock().unwrap() {
+            TraceSink::Memory(entries) => entries.push(entry),
+            TraceSink::File(writer) => {
+                let written = serde_json::to_writer(&mut *writer, &entry)
+                    .map_err(std::io::Error::from)
+                    .and_then(|_| writer.write_all(b"\n"))
+                    .and_then(|_| writer.flush());
+                if let Err(e) = written {
+                    warn!(target: "replay", path = ?self.path, error = %e, "Failed to write trace entry");
+                }
+            }
+        }
+    }
+
+    fn record_init(&self, config: &AgentConfig) {
+        *self.agent_id.lock().unwrap() = Some(config.agent_id.clone());
+        self.write(TraceEntry::Init {
+            agent_id: config.agent_id.clone(),
+            agent_type: config.agent_type.clone(),
+            parameters: config.parameters.clone(),
+        });
+    }
+
+    fn begin_event(&self, event: &Event) -> u64 {
+        let seq = self.seq.fetch_add(1, Ordering::SeqCst) + 1;
+        let correlation_id = event
+            .correlation_id()
+            .map(str::to_string)
+            .unwrap_or_else(|| event.id.clone());
+        *self.current.lock().unwrap() = Some(correlation_id.clone());
+        self.write(TraceEntry::Event {
+            seq,
+            correlation_id,
+            event: TracedEvent::from_event(event),
+        });
+        seq
+    }
+
+    fn record_tool_result(&self, tool: String, arguments: Value, result: &ToolResult<Value>) {
+        // A registry shared between agents also serves calls made for other agents
+        let caller = caller_agent_id();
+        let recorded_agent = self.agent_id.lock().unwrap().clone();
+        if let (Some(caller), Some(agent_id)) = (&caller, &recorded_agent) {
+            if caller != agent_id {
+                return;
+            }
+        }
+        let correlation_id = self.current.lock().unwrap().clone();
+        let (output, error) = match result {
+            Ok(output) => (Some(output.clone()), None),
+            Err(e) => (None, Some(TracedToolError::from_error(e))),
+        };
+        self.write(TraceEntry::ToolResult {
+            correlation_id,
+            tool,
+            arguments,
+            output,
+            error,
+        });
+    }
+}
+
+/// Tool wrapper recording each result to a `TraceRecorder`
+struct RecordingTool {
+    inner: Arc<dyn Tool>,
+    recorder: Arc<TraceRecorder>,
+}
+
+#[async_trait]
+impl Tool for RecordingTool {
+    fn name(&self) -> String {
+        self.inner.name()
+    }
+
+    fn description(&self) -> String {
+        self.inner.description()
+    }
+
+    fn parameters(&self) -> Value {
+        self.inner.parameters()
+    }
+
+    fn output_schema(&self) -> Option<Value> {
+        self.inner.output_schema()
+    }
+
+    async fn call(&self, arguments: Value) -> ToolResult<Value> {
+        self.call_with_cancel(arguments, CancellationToken::new())
+            .await
+    }
+
+    async fn call_with_cancel(
+        &self,
+        arguments: Value,
+        cancel: CancellationToken,
+    ) -> ToolResult<Value> {
+        let result = self.inner.call_with_cancel(arguments.clone(), cancel).await;
+        self.recorder
+            .record_tool_result(self.inner.name(), arguments, &result);
+        result
+    }
+}
+
+/// `AgentBehavior` wrapper recording the events it handles and the actions it returns
+pub struct RecordingBehavior<B> {
+    inner: B,
+    recorder: Arc<TraceRecorder>,
+}
+
+impl<B: AgentBehavior> RecordingBehavior<B> {
+    pub fn new(inner: B, recorder: Arc<TraceRecorder>) -> Self {
+        Self { inner, recorder }
+    }
+
+    pub fn inner(&self) -> &B {
+        &self.inner
+    }
+
+    pub fn recorder(&self) -> &Arc<TraceRecorder> {
+        &self.recorder
+    }
+}
+
+#[async_trait]
+impl<B: AgentBehavior> AgentBehavior for RecordingBehavior<B> {
+    async fn on_event(&mut self, event: Event, state: &mut AgentState) -> Result<Vec<Action>> {
+        let seq = self.recorder.begin_event(&event);
+        let result = self.inner.on_event(event, state).await;
+        let (actions, error) = match &result {
+            Ok(actions) => (
+                actions.iter().map(TracedAction::from_action).collect(),
+                None,
+            ),
+            Err(e) => (vec![], Some(e.to_string())),
+        };
+        self.recorder.write(TraceEntry::Actions {
+            seq,
+            actions,
+            error,
+        });
+        result
+    }
+
+    async fn on_init(&mut self, config: &AgentConfig) -> Result<()> {
+        self.recorder.record_init(config);
+        self.inner.on_init(config).await
+    }
+
+    async fn on_shutdown(&mut self) -> Result<()> {
+        self.inner.on_shutdown().await
+    }
+}
+
+/// A tool call the trace had no result for
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct MissingToolCall {
+    pub correlation_id: Option<String>,
+    pub tool: String,
+    pub arguments: Value,
+}
+
+/// One replayed event with the recorded and the replayed outcome
+#[derive(Debug, Clone, Serialize)]
+pub struct ReplayedEvent {
+    pub seq: u64,
+    pub correlation_id: String,
+    pub event_type: String,
+    pub recorded_actions: Vec<TracedAction>,
+    pub recorded_error: Option<String>,
+    pub actions: Vec<TracedAction>,
+    pub error: Option<String>,
+}
+
+impl ReplayedEvent {
+    /// Whether the behavior returned something other than in the recorded run
+    pub fn diverged(&self) -> bool {
+        self.actions != self.recorded_actions || self.error != self.recorded_error
+    }
+}
+
+/// Outcome of `ReplayRuntime::run`
+#[derive(Debug, Clone, Serialize)]
+pub struct ReplayReport {
+    pub agent_id: String,
+    pub events: Vec<ReplayedEvent>,
+    /// Tool calls answered from the trace
+    pub tool_calls_served: usize,
+    /// Tool calls with no recorded result; they failed with `ToolError::NotFound`
+    pub missing_tool_calls: Vec<MissingToolCall>,
+    /// Tool calls answered with a recorded result for different arguments
+    pub mismatched_arguments: usize,
+    /// Recorded tool results no call asked for
+    pub unused_tool_results: usize,
+}
+
+impl ReplayReport {
+    /// Events whose outcome differs from the recorded one
+    pub fn divergences(&self) -> Vec<&ReplayedEvent> {
+        self.events.iter().filter(|e| e.diverged()).collect()
+    }
+
+    /// True if every event and tool call went as recorded
+    pub fn is_faithful(&self) -> bool {
+        self.divergences().is_empty()
+            && self.missing_tool_calls.is_empty()
+            && self.mismatched_arguments == 0
+            && self.unused_tool_results == 0
+    }
+}
+
+struct RecordedCall {
+    arguments: Value,
+    result: ToolResult<Value>,
+}
+
+/// Recorded tool results not served yet, per (correlation id, tool)
+#[derive(Default)]
+struct ReplayState {
+    current: Option<String>,
+    results: HashMap<(Option<String>, String), VecDeque<RecordedCall>>,
+    served: usize,
+    missing: Vec<MissingToolCall>,
+    mismatched: usize,
+}
+
+impl ReplayState {
+    fn reset(&mut self, trace: &Trace) {
+        *self = Self::default();
+        for entry in &trace.entries {
+            if let TraceEntry::ToolResult {
+                correlation_id,
+                tool,
+                arguments,
+                output,
+                error,
+            } = entry
+            {
+                let result = match (output, error) {
+                    (_, Some(error)) => Err(error.to_error()),
+                    (Some(output), None) => Ok(output.clone()),
+                    (None, None) => Ok(Value::Null),
+                };
+                self.results
+                    .entry((correlation_id.clone(), tool.clone()))
+                    .or_default()
+                    .push_back(RecordedCall {
+                        arguments: arguments.clone(),
+                        result,
+                    });
+            }
+        }
+    }
+
+    /// Next recorded result of `tool` for the current event, preferring one recorded
+    /// with the same arguments
+    fn serve(&mut self, tool: &str, arguments: &Value) -> ToolResult<Value> {
+        let key = (self.current.clone(), tool.to_string());
+        let queue = self.results.get_mut(&key);
+        let call = queue.and_then(|queue| {
+            match queue.iter().position(|call| &call.arguments == arguments) {
+                Some(index) => queue.remove(index),
+                None => {
+                    let call = queue.pop_front();
+                    if call.is_some() {
+                        self.mismatched += 1;
+                    }
+                    call
+                }
+            }
+        });
+        match call {
+            Some(call) => {
+                self.served += 1;
+                call.result
+            }
+            None => {
+                debug!(target: "replay", tool = %tool, correlation_id = ?key.0, "No recorded result");
+                self.missing.push(MissingToolCall {
+                    correlation_id: key.0,
+                    tool: tool.to_string(),
+                    arguments: arguments.clone(),
+                });
+                Err(ToolError::NotFound(format!(
+                    "{} (no recorded result in trace)",
+                    tool
+                )))
+            }
+        }
+    }
+
+    fn unused(&self) -> usize {
+        self.results.values().map(VecDeque::len).sum()
+    }
+}
+
+/// Tool answering from a trace
+struct ReplayTool {
+    spec: TracedToolSpec,
+    state: Arc<Mutex<ReplayState>>,
+}
+
+#[async_trait]
+impl Tool for ReplayTool {
+    fn name(&self) -> String {
+        self.spec.name.clone()
+    }
+
+    fn description(&self) -> String {
+        self.spec.description.clone()
+    }
+
+    fn parameters(&self) -> Value {
+        self.spec.parameters.clone()
+    }
+
+    fn output_schema(&self) -> Option<Value> {
+        self.spec.output_schema.clone()
+    }
+
+    async fn call(&self, arguments: Value) -> ToolResult<Value> {
+        self.state
+            .lock()
+            .unwrap()
+            .serve(&self.spec.name, &arguments)
+    }
+}
+
+/// Feeds a recorded trace to an `AgentBehavior`, serving tool calls from the trace
+pub struct ReplayRuntime {
+    trace: Trace,
+    tools: Arc<ToolRegistry>,
+    state: Arc<Mutex<ReplayState>>,
+}
+
+impl ReplayRuntime {
+    /// Replay `trace`. Its tools are registered in `tools()` with a policy without
+    /// retries or circuit breaker, so each call takes exactly one recorded result; set
+    /// the recorded run's policies on the registry to replay its retries.
+    pub async fn new(trace: Trace) -> Self {
+        let state = Arc::new(Mutex::new(ReplayState::default()));
+        let tools = ToolRegistry::new();
+        tools.set_default_policy(ToolPolicy {
+            max_retries: 0,
+            circuit_breaker: None,
+            ..ToolPolicy::default()
+        });
+
+        let mut specs: HashMap<String, TracedToolSpec> = HashMap::new();
+        for entry in &trace.entries {
+            match entry {
+                TraceEntry::Tools { tools } => {
+                    for spec in tools {
+                        specs.insert(spec.name.clone(), spec.clone());
+                    }
+                }
+                TraceEntry::ToolResult { tool, .. } if !specs.contains_key(tool) => {
+                    specs.insert(
+                        tool.clone(),
+                        TracedToolSpec {
+                            name: tool.clone(),
+                            description: String::new(),
+                            parameters: serde_json::json!({ "type": "object" }),
+                            output_schema: None,
+                        },
+                    );
+                }
+                _ => {}
+            }
+        }
+        for spec in specs.into_values() {
+            tools
+                .register(Arc::new(ReplayTool {
+                    spec,
+                    state: Arc::clone(&state),
+                }))
+                .await;
+        }
+
+        Self {
+            trace,
+            tools: Arc::new(tools),
+            state,
+        }
+    }
+
+    /// Replay the trace file at `path`
+    pub async fn load(path: impl AsRef<Path>) -> Result<Self> {
+        Ok(Self::new(Trace::load(path)?).await)
+    }
+
+    pub fn trace(&self) -> &Trace {
+        &self.trace
+    }
+
+    /// Registry answering tool calls from the trace; build the replayed behavior with it
+    pub fn tools(&self) -> Arc<ToolRegistry> {
+        Arc::clone(&self.tools)
+    }
+
+    /// Run `behavior` through the trace: `on_init` with the recorded config, `on_event`
+    /// for each recorded event in order, then `on_shutdown`.
+    ///
+    /// The behavior runs as the recorded agent (see `caller_agent_id`) with a fresh
+    /// `AgentState`. A failing `on_event` is reported and the replay continues, as in a
+    /// live agent. Each run starts from the full trace, so one runtime can replay
+    /// several behaviors.
+    pub async fn run<B: AgentBehavior + ?Sized>(&self, behavior: &mut B) -> Result<ReplayReport> {
+        self.state.lock().unwrap().reset(&self.trace);
+        let config = self.config();
+        let agent_id = config.agent_id.clone();
+
+        let mut recorded: HashMap<u64, (Vec<TracedAction>, Option<String>)> = HashMap::new();
+        for entry in &self.trace.entries {
+            if let TraceEntry::Actions {
+                seq,
+                actions,
+                error,
+            } = entry
+            {
+                recorded.insert(*seq, (actions.clone(), error.clone()));
+            }
+        }
+
+        let events = with_caller(agent_id.clone(), async {
+            behavior.on_init(&config).await?;
+            let mut state = AgentState {
+                agent_id: config.agent_id.clone(),
+                persistent_state: vec![],
+                ephemeral_context: vec![],
+                last_update_ms: chrono::Utc::now().timestamp_millis(),
+                metadata: config.parameters.clone(),
+            };
+            let mut events = Vec::new();
+            for entry in &self.trace.entries {
+                let TraceEntry::Event {
+                    seq,
+                    correlation_id,
+                    event,
+                } = entry
+                else {
+                    continue;
+                };
+                self.state.lock().unwrap().current = Some(correlation_id.clone());
+                let (actions, error) = match behavior.on_event(event.to_event(), &mut state).await {
+                    Ok(actions) => (
+                        actions.iter().map(TracedAction::from_action).collect(),
+                        None,
+                    ),
+                    Err(e) => (vec![], Some(e.to_string())),
+                };
+                let (recorded_actions, recorded_error) = recorded.remove(seq).unwrap_or_default();
+                events.push(ReplayedEvent {
+                    seq: *seq,
+                    correlation_id: correlation_id.clone(),
+                    event_type: event.event_type.clone(),
+                    recorded_actions,
+                    recorded_error,
+                    actions,
+                    error,
+                });
+            }
+            self.state.lock().unwrap().current = None;
+            behavior.on_shutdown().await?;
+            Ok::<_, LoomError>(events)
+        })
+        .await?;
+
+        let state = self.state.lock().unwrap();
+        let report = ReplayReport {
+            agent_id,
+            events,
+            tool_calls_served: state.served,
+            missing_tool_calls: state.missing.clone(),
+            mismatched_arguments: state.mismatched,
+            unused_tool_results: state.unused(),
+        };
+        info!(
+            target: "replay",
+            agent_id = %report.agent_id,
+            events = report.events.len(),
+            diverged = report.divergences().len(),
+            missing_tool_calls = report.missing_tool_calls.len(),
+            "Replay complete"
+        );
+        Ok(report)
+    }
+
+    /// Config of the recorded agent, as far as the trace has it
+    fn config(&self) -> AgentConfig {
+        let init = self.trace.entries.iter().find_map(|entry| match entry {
+            TraceEntry::Init {
+                agent_id,
+                agent_type,
+                parameters,
+            } => Some(AgentConfig {
+                agent_id: agent_id.clone(),
+                agent_type: agent_type.clone(),
+                parameters: parameters.clone(),
+                ..Default::default()
+            }),
+            _ => None,
+        });
+        init.unwrap_or_else(|| AgentConfig {
+            agent_id: "replay".to_string(),
+            ..Default::default()
+        })
+    }
+}
+
+fn encode_payload(payload: &[u8]) -> (String, bool) {
+    match std::str::from_utf8(payload) {
+        Ok(text) => (text.to_string(), false),
+        Err(_) => (
+            base64::engine::general_purpose::STANDARD.encode(payload),
+            true,
+        ),
+    }
+}
+
+fn decode_payload(payload: &str, base64: bool) -> Vec<u8> {
+    if !base64 {
+        return payload.as_bytes().to_vec();
+    }
+    base64::engine::general_purpose::STANDARD
+        .decode(payload)
+        .unwrap_or_else(|e| {
+            warn!(target: "replay", error = %e, "Invalid base64 payload in trace");
+            Vec::new()
+        })
+}
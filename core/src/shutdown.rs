@@ -0,0 +1,290 @@
+//! Ordered, graceful shutdown of runtime components
+//!
+//! Components register a `ShutdownHook` under a name together with the names of the
+//! components they depend on. `ShutdownRegistry::shutdown` stops dependents before
+//! their dependencies (agents before the tools they call, tools before the pools and
+//! MCP servers they run on, ...), giving each hook a deadline to finish gracefully:
+//! drain mailboxes, finish in-flight tool calls, flush storage.
+//!
+//! A hook that misses its deadline is reported as `TimedOut` and shutdown moves on;
+//! a failing hook does not prevent the remaining components from stopping.
+
+use crate::{LoomError, Result};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::time::Instant;
+use tracing::{debug, info, warn};
+
+/// Graceful stop logic for a component
+#[async_trait]
+pub trait ShutdownHook: Send + Sync {
+    /// Stop the component, finishing outstanding work before `deadline` where possible
+    async fn stop(&self, deadline: Instant) -> Result<()>;
+}
+
+type StopFn =
+    dyn Fn(Instant) -> Pin<Box<dyn Future<Output = Result<()>> + Send>> + Send + Sync + 'static;
+
+/// Hook built from a closure, see `ShutdownRegistry::register_fn`
+struct FnHook(Box<StopFn>);
+
+#[async_trait]
+impl ShutdownHook for FnHook {
+    async fn stop(&self, deadline: Instant) -> Result<()> {
+        (self.0)(deadline).await
+    }
+}
+
+struct Component {
+    name: String,
+    depends_on: Vec<String>,
+    timeout: Option<Duration>,
+    hook: Arc<dyn ShutdownHook>,
+}
+
+/// How a component's stop hook ended
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StopOutcome {
+    Stopped,
+    Failed(String),
+    TimedOut,
+}
+
+/// Result of stopping one component
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ComponentStop {
+    pub name: String,
+    pub outcome: StopOutcome,
+    pub elapsed_ms: u64,
+}
+
+/// Result of `ShutdownRegistry::shutdown`, in stop order
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ShutdownReport {
+    pub components: Vec<ComponentStop>,
+}
+
+impl ShutdownReport {
+    /// True if every component stopped within its deadline
+    pub fn is_clean(&self) -> bool {
+        self.components
+            .iter()
+            .all(|c| c.outcome == StopOutcome::Stopped)
+    }
+
+    pub fn outcome(&self, name: &str) -> Option<&StopOutcome> {
+        self.components
+            .iter()
+            .find(|c| c.name == name)
+            .map(|c| &c.outcome)
+    }
+}
+
+/// Registry of components with declared dependencies and stop hooks
+#[derive(Default)]
+pub struct ShutdownRegistry {
+    components: Mutex<Vec<Component>>,
+}
+
+impl ShutdownRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a component. `depends_on` names components that must still be running
+    /// while this one stops; they may be registered later.
+    ///
+    /// Fails on a duplicate name or if the dependency would create a cycle.
+    pub fn register(
+        &self,
+        name: impl Into<String>,
+        depends_on: &[&str],
+        hook: Arc<dyn ShutdownHook>,
+    ) -> Result<()> {
+        self.insert(Component {
+            name: name.into(),
+            depends_on: depends_on.iter().map(|d| d.to_string()).collect(),
+            timeout: None,
+            hook,
+        })
+    }
+
+    /// Like `register`, but the component gets at most `timeout` of the overall deadline
+    pub fn register_with_timeout(
+        &self,
+        name: impl Into<String>,
+        depends_on: &[&str],
+        timeout: Duration,
+        hook: Arc<dyn ShutdownHook>,
+    ) -> Result<()> {
+        self.insert(Component {
+            name: name.into(),
+            depends_on: depends_on.iter().map(|d| d.to_string()).collect(),
+            timeout: Some(timeout),
+            hook,
+        })
+    }
+
+    /// Register a closure as stop hook
+    pub fn register_fn<F, Fut>(
+        &self,
+        name: impl Into<String>,
+        depends_on: &[&str],
+        f: F,
+    ) -> Result<()>
+    where
+        F: Fn(Instant) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<()>> + Send + 'static,
+    {
+        let hook = FnHook(Box::new(move |deadline| Box::pin(f(deadline))));
+        self.register(name, depends_on, Arc::new(hook))
+    }
+
+    fn insert(&self, component: Component) -> Result<()> {
+        let mut components = self.components.lock().unwrap();
+        if components.iter().any(|c| c.name == component.name) {
+            return Err(LoomError::AgentError(format!(
+                "Shutdown component already registered: {}",
+                component.name
+            )));
+        }
+        debug!(target: "shutdown", component = %component.name, depends_on = ?component.depends_on, "Registered shutdown component");
+        components.push(component);
+        if let Err(e) = stop_order(&components) {
+            components.pop();
+            return Err(e);
+        }
+        Ok(())
+    }
+
+    /// Remove a component; returns false if it was not registered
+    pub fn unregister(&self, name: &str) -> bool {
+        let mut components = self.components.lock().unwrap();
+        let before = components.len();
+        components.retain(|c| c.name != name);
+        components.len() != before
+    }
+
+    /// Names in the order `shutdown` stops them
+    pub fn stop_order(&self) -> Vec<String> {
+        let components = self.components.lock().unwrap();
+        stop_order(&components)
+            .map(|order| {
+                order
+                    .into_iter()
+                    .map(|i| components[i].name.clone())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Stop all components in dependency order within `timeout`.
+    ///
+    /// Components are removed from the registry, so a second call is a no-op.
+    pub async fn shutdown(&self, timeout: Duration) -> ShutdownReport {
+        let deadline = Instant::now() + timeout;
+        let ordered: Vec<(String, Option<Duration>, Arc<dyn ShutdownHook>)> = {
+            let mut components = self.components.lock().unwrap();
+            // Cycles are rejected on insert, so the order always exists
+            let order = stop_order(&components).unwrap_or_default();
+            let ordered = order
+                .into_iter()
+                .map(|i| {
+                    let c = &components[i];
+                    (c.name.clone(), c.timeout, Arc::clone(&c.hook))
+                })
+                .collect();
+            components.clear();
+            ordered
+        };
+
+        let mut report = ShutdownReport::default();
+        for (name, component_timeout, hook) in ordered {
+            let started = Instant::now();
+            let component_deadline = component_timeout
+                .map(|t| deadline.min(started + t))
+                .unwrap_or(deadline);
+            let outcome = match tokio::time::timeout_at(
+                component_deadline,
+                hook.stop(component_deadline),
+            )
+            .await
+            {
+                Ok(Ok(())) => StopOutcome::Stopped,
+                Ok(Err(e)) => {
+                    warn!(target: "shutdown", component = %name, error = %e, "Component failed to stop cleanly");
+                    StopOutcome::Failed(e.to_string())
+                }
+                Err(_) => {
+                    warn!(target: "shutdown", component = %name, "Component missed its shutdown deadline");
+                    StopOutcome::TimedOut
+                }
+            };
+            let elapsed_ms = started.elapsed().as_millis() as u64;
+            debug!(target: "shutdown", component = %name, ?outcome, elapsed_ms, "Component stopped");
+            report.components.push(ComponentStop {
+                name,
+                outcome,
+                elapsed_ms,
+            });
+        }
+        info!(target: "shutdown", components = report.components.len(), clean = report.is_clean(), "Shutdown complete");
+        report
+    }
+}
+
+/// Indices into `components` such that every component comes before its dependencies.
+/// Ties keep registration order; dependencies on unregistered names are ignored.
+fn stop_order(components: &[Component]) -> Result<Vec<usize>> {
+    let index: HashMap<&str, usize> = components
+        .iter()
+        .enumerate()
+        .map(|(i, c)| (c.name.as_str(), i))
+        .collect();
+
+    // pending_dependents[i] = components depending on i that have not stopped yet
+    let mut pending_dependents = vec![0usize; components.len()];
+    for c in components {
+        let deps: HashSet<&str> = c.depends_on.iter().map(String::as_str).collect();
+        for dep in deps {
+            if let Some(&d) = index.get(dep) {
+                pending_dependents[d] += 1;
+            }
+        }
+    }
+
+    let mut order = Vec::with_capacity(components.len());
+    let mut done = vec![false; components.len()];
+    while order.len() < components.len() {
+        let Some(next) = (0..components.len()).find(|&i| !done[i] && pending_dependents[i] == 0)
+        else {
+            let cycle: Vec<&str> = (0..components.len())
+                .filter(|&i| !done[i])
+                .map(|i| components[i].name.as_str())
+                .collect();
+            return Err(LoomError::AgentError(format!(
+                "Shutdown dependency cycle among: {}",
+                cycle.join(", ")
+            )));
+        };
+        done[next] = true;
+        order.push(next);
+        let deps: HashSet<&str> = components[next]
+            .depends_on
+            .iter()
+            .map(String::as_str)
+            .collect();
+        for dep in deps {
+            if let Some(&d) = index.get(dep) {
+                pending_dependents[d] -= 1;
+            }
+        }
+    }
+    Ok(order)
+}
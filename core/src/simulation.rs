@@ -0,0 +1,502 @@
+//! Scripted persona agents for load and behavior testing.
+//!
+//! `Simulation::spawn` starts N personas on an `EventBus` that answer requests and bid
+//! on contract nets like real agents, without any LLM calls, with latencies, scores and
+//! failures drawn from a seeded generator. See "Simulated personas" in
+//! `docs/core/collaboration.md`.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+use tracing::debug;
+
+use crate::agent::directory::{AgentDirectory, AgentInfo};
+use crate::messaging::collab::{meta, types, BidTerms};
+use crate::messaging::envelope::{Envelope, ThreadTopicKind};
+use crate::proto::{Event, QoSLevel};
+use crate::{EventBus, Result};
+
+/// Metadata set on personas registered in a directory
+pub const PERSONA_KEY: &str = "simulation.persona";
+
+/// How long cancelled threads are remembered once there are `MAX_CANCELLED` of them
+const CANCELLED_TTL: Duration = Duration::from_secs(60);
+const MAX_CANCELLED: usize = 1024;
+
+/// How N personas behave
+#[derive(Debug, Clone)]
+pub struct SimulationConfig {
+    pub personas: usize,
+    /// Personas are named `{prefix}-{i}` and listen on `{prefix}.{i}` (default `persona`)
+    pub prefix: String,
+    /// Capabilities listed for each persona in a directory
+    pub capabilities: Vec<String>,
+    /// Contract-net threads whose calls for proposals every persona bids on
+    pub threads: Vec<String>,
+    /// Delay before each reply, drawn uniformly from `min..=max` (default none)
+    pub reply_latency: (Duration, Duration),
+    /// Share of requests left unanswered and of awards declined (0.0-1.0)
+    pub error_rate: f64,
+    /// Range each persona's proposal score is drawn from (default 0-100)
+    pub scores: (f64, f64),
+    /// Range each persona's bid cost is drawn from; `None` bids without a cost
+    pub costs: Option<(f64, f64)>,
+    pub capacity: Option<u32>,
+    /// Reply payload; `None` echoes the request payload
+    pub answer: Option<String>,
+    /// Event type answered, and the type of the answer
+    pub request_type: String,
+    pub reply_type: String,
+    pub seed: u64,
+}
+
+impl Default for SimulationConfig {
+    fn default() -> Self {
+        Self::new(1)
+    }
+}
+
+impl SimulationConfig {
+    pub fn new(personas: usize) -> Self {
+        Self {
+            personas,
+            prefix: "persona".to_string(),
+            capabilities: Vec::new(),
+            threads: Vec::new(),
+            reply_latency: (Duration::ZERO, Duration::ZERO),
+            error_rate: 0.0,
+            scores: (0.0, 100.0),
+            costs: None,
+            capacity: None,
+            answer: None,
+            request_type: types::REQ.to_string(),
+            reply_type: types::REPLY.to_string(),
+            seed: 0x5eed,
+        }
+    }
+
+    pub fn with_prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.prefix = prefix.into();
+        self
+    }
+
+    pub fn with_capabilities(mut self, capabilities: Vec<String>) -> Self {
+        self.capabilities = capabilities;
+        self
+    }
+
+    pub fn with_threads(mut self, threads: Vec<String>) -> Self {
+        self.threads = threads;
+        self
+    }
+
+    pub fn with_reply_latency(mut self, min: Duration, max: Duration) -> Self {
+        self.reply_latency = (min, max.max(min));
+        self
+    }
+
+    pub fn with_error_rate(mut self, error_rate: f64) -> Self {
+        self.error_rate = error_rate.clamp(0.0, 1.0);
+        self
+    }
+
+    pub fn with_scores(mut self, min: f64, max: f64) -> Self {
+        self.scores = (min, max.max(min));
+        self
+    }
+
+    pub fn with_costs(mut self, min: f64, max: f64) -> Self {
+        self.costs = Some((min, max.max(min)));
+        self
+    }
+
+    pub fn with_capacity(mut self, capacity: u32) -> Self {
+        self.capacity = Some(capacity);
+        self
+    }
+
+    pub fn with_answer(mut self, answer: impl Into<String>) -> Self {
+        self.answer = Some(answer.into());
+        self
+    }
+
+    /// Answer `request_type` events with `reply_type` ones instead of collab requests
+    pub fn with_request_type(
+        mut self,
+        request_type: impl Into<String>,
+        reply_type: impl Into<String>,
+    ) -> Self {
+        self.request_type = request_type.into();
+        self.reply_type = reply_type.into();
+        self
+    }
+
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Request topics of the personas, in persona order
+    pub fn topics(&self) -> Vec<String> {
+        (0..self.personas)
+            .map(|i| format!("{}.{}", self.prefix, i))
+            .collect()
+    }
+}
+
+/// One simulated agent
+#[derive(Debug, Clone)]
+pub struct Persona {
+    pub id: String,
+    /// Topic its requests arrive on
+    pub topic: String,
+    /// What it bids on every call for proposals
+    pub terms: BidTerms,
+}
+
+/// What the personas of a simulation did
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SimulationStats {
+    pub requests: u64,
+    pub replies: u64,
+    /// Requests left unanswered and awards declined on purpose
+    pub errors: u64,
+    pub proposals: u64,
+    pub awards: u64,
+    pub confirms: u64,
+    /// Replies not sent because their thread was cancelled meanwhile
+    pub abandoned: u64,
+}
+
+#[derive(Default)]
+struct Counters {
+    requests: AtomicU64,
+    replies: AtomicU64,
+    errors: AtomicU64,
+    proposals: AtomicU64,
+    awards: AtomicU64,
+    confirms: AtomicU64,
+    abandoned: AtomicU64,
+}
+
+/// xorshift64*, seeded per persona
+struct Rng(u64);
+
+impl Rng {
+    fn new(seed: u64, persona: usize) -> Self {
+        // splitmix64 of the pair, so neighbouring personas diverge at once
+        let mut z = seed ^ (persona as u64 + 1).wrapping_mul(0x9e37_79b9_7f4a_7c15);
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        Self((z ^ (z >> 31)).max(1))
+    }
+
+    /// Uniform in `[0, 1)`
+    fn next_f64(&mut self) -> f64 {
+        self.0 ^= self.0 >> 12;
+        self.0 ^= self.0 << 25;
+        self.0 ^= self.0 >> 27;
+        (self.0.wrapping_mul(0x2545_f491_4f6c_dd1d) >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    fn range(&mut self, (min, max): (f64, f64)) -> f64 {
+        min + (max - min) * self.next_f64()
+    }
+
+    fn duration(&mut self, (min, max): (Duration, Duration)) -> Duration {
+        min + max.saturating_sub(min).mul_f64(self.next_f64())
+    }
+}
+
+struct PersonaTask {
+    persona: Persona,
+    config: Arc<SimulationConfig>,
+    bus: Arc<EventBus>,
+    rng: Mutex<Rng>,
+    counters: Arc<Counters>,
+    /// Threads cancelled, shared by all personas
+    cancelled: Arc<DashMap<String, Instant>>,
+}
+
+impl PersonaTask {
+    /// Reply latency and whether this event fails
+    fn roll(&self) -> (Duration, bool) {
+        let mut rng = self.rng.lock().unwrap();
+        let latency = rng.duration(self.config.reply_latency);
+        let fails = self.config.error_rate > 0.0 && rng.next_f64() < self.config.error_rate;
+        (latency, fails)
+    }
+
+    fn cancel(&self, event: &Event) {
+        if self.cancelled.len() >= MAX_CANCELLED {
+            self.cancelled.retain(|_, at| at.elapsed() < CANCELLED_TTL);
+        }
+        let thread_id = Envelope::from_event(event).thread_id;
+        self.cancelled.insert(thread_id, Instant::now());
+    }
+
+    async fn handle(&self, event: Event) {
+        if event.r#type == types::CANCEL {
+            self.cancel(&event);
+            return;
+        }
+        let (latency, fails) = self.roll();
+        let reply_type = if event.r#type == self.config.request_type {
+            self.counters.requests.fetch_add(1, Ordering::Relaxed);
+            if fails {
+                self.counters.errors.fetch_add(1, Ordering::Relaxed);
+                return;
+            }
+            self.config.reply_type.as_str()
+        } else if event.r#type == types::CFP {
+            types::PROPOSAL
+        } else if event.r#type == types::AWARD
+            && event.metadata.get(meta::AWARD_TO) == Some(&self.persona.id)
+        {
+            self.counters.awards.fetch_add(1, Ordering::Relaxed);
+            if !event.metadata.contains_key(meta::CONFIRM_WITHIN_MS) {
+                return;
+            }
+            if fails {
+                self.counters.errors.fetch_add(1, Ordering::Relaxed);
+                types::DECLINE
+            } else {
+                types::CONFIRM
+            }
+        } else {
+            return;
+        };
+
+        if !latency.is_zero() {
+            tokio::time::sleep(latency).await;
+        }
+        let request_env = Envelope::from_event(&event);
+        if self.cancelled.contains_key(&request_env.thread_id) {
+            self.counters.abandoned.fetch_add(1, Ordering::Relaxed);
+            return;
+        }
+        let env = request_env.caused_by(event.id.clone(), self.persona.id.clone());
+        let (topic, mut metadata, payload) = if reply_type == self.config.reply_type {
+            let payload = match self.config.answer {
+                Some(ref answer) => answer.clone().into_bytes(),
+                None => event.payload,
+            };
+            (request_env.reply_to, event.metadata, payload)
+        } else {
+            let mut md = std::collections::HashMap::new();
+            self.persona.terms.apply_to_metadata(&mut md);
+            (env.reply_topic(), md, Vec::new())
+        };
+        env.apply_to_metadata(&mut metadata);
+        let reply = Event {
+            id: format!("{}-{}", self.persona.id, event.id),
+            r#type: reply_type.to_string(),
+            timestamp_ms: chrono::Utc::now().timestamp_millis(),
+            source: self.persona.id.clone(),
+            metadata,
+            payload,
+            confidence: 1.0,
+            tags: vec!["simulation".into()],
+            priority: event.priority,
+        };
+        match self.bus.publish(&topic, reply).await {
+            Ok(_) => {
+                let counter = match reply_type {
+                    types::PROPOSAL => &self.counters.proposals,
+                    types::CONFIRM => &self.counters.confirms,
+                    types::DECLINE => return,
+                    _ => &self.counters.replies,
+                };
+                counter.fetch_add(1, Ordering::Relaxed);
+            }
+            Err(e) => {
+                debug!(target: "simulation", persona = %self.persona.id, error = %e, "Persona reply failed")
+            }
+        }
+    }
+
+    /// Handle events from `rx` until it closes; delayed replies run concurrently
+    async fn run(self: Arc<Self>, mut rx: mpsc::Receiver<Event>) {
+        let immediate = self.config.reply_latency.1.is_zero();
+        while let Some(event) = rx.recv().await {
+            if immediate {
+                self.handle(event).await;
+            } else {
+                let task = Arc::clone(&self);
+                tokio::spawn(async move { task.handle(event).await });
+            }
+        }
+    }
+}
+
+/// Running personas; stopped (and their subscriptions released) by `stop`
+pub struct Simulation {
+    bus: Arc<EventBus>,
+    config: Arc<SimulationConfig>,
+    personas: Vec<Persona>,
+    counters: Arc<Counters>,
+    subscriptions: Vec<String>,
+    tasks: Vec<JoinHandle<()>>,
+}
+
+impl Simulation {
+    /// Start `config.personas` personas on `bus`
+    pub async fn spawn(bus: Arc<EventBus>, config: SimulationConfig) -> Result<Self> {
+        let config = Arc::new(config);
+        let counters = Arc::new(Counters::default());
+        let cancelled = Arc::new(DashMap::new());
+        let mut sim = Self {
+            bus: Arc::clone(&bus),
+            config: Arc::clone(&config),
+            personas: Vec::with_capacity(config.personas),
+            counters: Arc::clone(&counters),
+            subscriptions: Vec::new(),
+            tasks: Vec::new(),
+        };
+        for (i, topic) in config.topics().into_iter().enumerate() {
+            let mut rng = Rng::new(config.seed, i);
+            let mut terms = BidTerms::new().with_score(rng.range(config.scores));
+            if let Some(costs) = config.costs {
+                terms = terms.with_cost(rng.range(costs));
+            }
+            if let Some(capacity) = config.capacity {
+                terms = terms.with_capacity(capacity);
+            }
+            let persona = Persona {
+                id: format!("{}-{}", config.prefix, i),
+                topic,
+                terms,
+            };
+            let task = Arc::new(PersonaTask {
+                persona: persona.clone(),
+                config: Arc::clone(&config),
+                bus: Arc::clone(&bus),
+                rng: Mutex::new(rng),
+                counters: Arc::clone(&counters),
+                cancelled: Arc::clone(&cancelled),
+            });
+
+            let mut subscriptions = vec![(
+                persona.topic.clone(),
+                vec![config.request_type.clone(), types::CANCEL.to_string()],
+            )];
+            for thread in &config.threads {
+                subscriptions.push((
+                    ThreadTopicKind::Broadcast.topic(thread),
+                    vec![
+                        types::CFP.to_string(),
+                        types::AWARD.to_string(),
+                        types::CANCEL.to_string(),
+                    ],
+                ));
+            }
+            for (topic, event_types) in subscriptions {
+                let (sub_id, rx) = bus
+                    .subscribe(topic, event_types, QoSLevel::QosBatched)
+                    .await?;
+                sim.subscriptions.push(sub_id);
+                sim.tasks.push(tokio::spawn(Arc::clone(&task).run(rx)));
+            }
+            sim.personas.push(persona);
+        }
+        debug!(target: "simulation", personas = sim.personas.len(), threads = config.threads.len(), "Simulation started");
+        Ok(sim)
+    }
+
+    pub fn personas(&self) -> &[Persona] {
+        &self.personas
+    }
+
+    /// Request topics of the personas, in persona order
+    pub fn topics(&self) -> Vec<String> {
+        self.personas.iter().map(|p| p.topic.clone()).collect()
+    }
+
+    /// The persona with the highest proposal score
+    pub fn best_bidder(&self) -> Option<&Persona> {
+        self.personas.iter().max_by(|a, b| {
+            let score = |p: &Persona| p.terms.score.unwrap_or_default();
+            score(a).total_cmp(&score(b))
+        })
+    }
+
+    /// List every persona in `directory`, with its topic and the configured capabilities
+    pub fn register(&self, directory: &AgentDirectory) {
+        for persona in &self.personas {
+            let mut metadata = std::collections::HashMap::new();
+            metadata.insert(PERSONA_KEY.to_string(), self.config.prefix.clone());
+            directory.register_agent(AgentInfo {
+                agent_id: persona.id.clone(),
+                subscribed_topics: vec![persona.topic.clone()],
+                capabilities: self.config.capabilities.clone(),
+                metadata,
+                last_heartbeat: Some(chrono::Utc::now().timestamp_millis()),
+                ..Default::default()
+            });
+        }
+    }
+
+    pub fn stats(&self) -> SimulationStats {
+        let c = &self.counters;
+        SimulationStats {
+            requests: c.requests.load(Ordering::Relaxed),
+            replies: c.replies.load(Ordering::Relaxed),
+            errors: c.errors.load(Ordering::Relaxed),
+            proposals: c.proposals.load(Ordering::Relaxed),
+            awards: c.awards.load(Ordering::Relaxed),
+            confirms: c.confirms.load(Ordering::Relaxed),
+            abandoned: c.abandoned.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Stop the personas and release their subscriptions
+    pub async fn stop(mut self) {
+        for task in self.tasks.drain(..) {
+            task.abort();
+        }
+        for sub_id in self.subscriptions.drain(..) {
+            let _ = self.bus.unsubscribe(&sub_id).await;
+        }
+    }
+}
+
+impl Drop for Simulation {
+    fn drop(&mut self) {
+        for task in &self.tasks {
+            task.abort();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rng_is_reproducible_and_in_range() {
+        let draw = |persona| {
+            let mut rng = Rng::new(7, persona);
+            (0..100)
+                .map(|_| rng.range((10.0, 20.0)))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(draw(0), draw(0));
+        assert_ne!(draw(0), draw(1));
+        assert!(draw(3).iter().all(|x| (10.0..20.0).contains(x)));
+    }
+
+    #[test]
+    fn latency_stays_within_bounds() {
+        let mut rng = Rng::new(1, 0);
+        let bounds = (Duration::from_millis(5), Duration::from_millis(10));
+        for _ in 0..100 {
+            let latency = rng.duration(bounds);
+            assert!(latency >= bounds.0 && latency <= bounds.1);
+        }
+    }
+}
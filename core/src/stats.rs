@@ -0,0 +1,212 @@
+//! Unified stats: one snapshot of every subsystem, for scraping or inspection.
+//!
+//! `StatsCollector` reads the EventBus, agent runtime, tool registry, LLM usage, MCP
+//! servers and memory governor on demand; nothing is sampled in the background.
+//! Components outside core (the Bridge) implement `StatsSource` and register with the
+//! collector; their sections appear under `sources`, keyed by name.
+//!
+//! `Loom::stats()` returns the snapshot, and the dashboard serves it as JSON at
+//! `/api/stats` (`DashboardServer::with_stats`).
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
+
+use crate::agent::AgentRuntime;
+use crate::cognitive::llm::{LlmCache, LlmCacheStats, LlmUsage, LlmUsageStats};
+use crate::governor::{ComponentUsage, MemoryGovernor, PressureLevel};
+use crate::messaging::event_bus::EventBusStats;
+use crate::tools::mcp::{McpManager, McpServerState};
+use crate::tools::{ToolRegistry, ToolStats};
+use crate::EventBus;
+
+/// A component contributing a section to the snapshot
+#[async_trait]
+pub trait StatsSource: Send + Sync {
+    /// Key of the section in `LoomStats::sources`
+    fn name(&self) -> String;
+
+    async fn stats(&self) -> serde_json::Value;
+}
+
+/// EventBus totals and per-topic counters
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct EventBusSnapshot {
+    /// Topics (including wildcard patterns) with subscriptions
+    pub topics: usize,
+    pub subscriptions: usize,
+    /// Sum of the per-topic counters
+    pub totals: EventBusStats,
+    pub per_topic: BTreeMap<String, EventBusStats>,
+}
+
+/// An agent and the events waiting for it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AgentQueueStats {
+    pub agent_id: String,
+    /// Events in the agent's mailbox and subscription queues
+    pub queue_depth: usize,
+    pub subscriptions: usize,
+    pub hibernated: bool,
+    pub paused: bool,
+}
+
+/// LLM backend usage of the built-in client, and its response cache
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LlmSnapshot {
+    pub usage: LlmUsageStats,
+    /// Present when the response cache is enabled
+    pub cache: Option<LlmCacheStats>,
+}
+
+/// Memory governor view: estimated bytes per component
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MemorySnapshot {
+    /// Level observed by the governor's last check
+    pub level: PressureLevel,
+    pub total_bytes: usize,
+    pub global_cap_bytes: usize,
+    pub components: Vec<ComponentUsage>,
+}
+
+/// Snapshot returned by `Loom::stats()`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LoomStats {
+    pub timestamp_ms: i64,
+    pub event_bus: EventBusSnapshot,
+    /// Sorted by agent id
+    pub agents: Vec<AgentQueueStats>,
+    /// Sorted by tool name
+    pub tools: Vec<ToolStats>,
+    pub llm: LlmSnapshot,
+    pub mcp_servers: Vec<McpServerState>,
+    pub memory: MemorySnapshot,
+    /// Sections of registered `StatsSource`s (e.g. `bridge`)
+    pub sources: BTreeMap<String, serde_json::Value>,
+}
+
+/// Builds `LoomStats` from the subsystems it was given
+pub struct StatsCollector {
+    event_bus: Arc<EventBus>,
+    agent_runtime: AgentRuntime,
+    tool_registry: Arc<ToolRegistry>,
+    mcp_manager: Arc<McpManager>,
+    memory_governor: Arc<MemoryGovernor>,
+    llm_usage: Arc<LlmUsage>,
+    llm_cache: Option<Arc<LlmCache>>,
+    sources: DashMap<String, Arc<dyn StatsSource>>,
+}
+
+impl StatsCollector {
+    pub fn new(
+        event_bus: Arc<EventBus>,
+        agent_runtime: AgentRuntime,
+        tool_registry: Arc<ToolRegistry>,
+        mcp_manager: Arc<McpManager>,
+        memory_governor: Arc<MemoryGovernor>,
+        llm_usage: Arc<LlmUsage>,
+    ) -> Self {
+        Self {
+            event_bus,
+            agent_runtime,
+            tool_registry,
+            mcp_manager,
+            memory_governor,
+            llm_usage,
+            llm_cache: None,
+            sources: DashMap::new(),
+        }
+    }
+
+    /// Report the hit/miss counters of `cache`
+    pub fn with_llm_cache(mut self, cache: Arc<LlmCache>) -> Self {
+        self.llm_cache = Some(cache);
+        self
+    }
+
+    /// Register a source; replaces any source with the same name
+    pub fn register(&self, source: Arc<dyn StatsSource>) {
+        self.sources.insert(source.name(), source);
+    }
+
+    pub fn unregister(&self, name: &str) {
+        self.sources.remove(name);
+    }
+
+    /// Snapshot of every subsystem
+    pub async fn snapshot(&self) -> LoomStats {
+        let mut event_bus = EventBusSnapshot {
+            topics: self.event_bus.topic_count(),
+            subscriptions: self.event_bus.subscription_count(),
+            ..Default::default()
+        };
+        for (topic, stats) in self.event_bus.all_stats() {
+            let totals = &mut event_bus.totals;
+            totals.total_published += stats.total_published;
+            totals.total_delivered += stats.total_delivered;
+            totals.active_subscriptions += stats.active_subscriptions;
+            totals.backlog_size += stats.backlog_size;
+            totals.dropped_events += stats.dropped_events;
+            totals.redelivered += stats.redelivered;
+            totals.dead_lettered += stats.dead_lettered;
+            totals.dropped_oldest += stats.dropped_oldest;
+            totals.dropped_newest += stats.dropped_newest;
+            totals.blocked += stats.blocked;
+            totals.spilled += stats.spilled;
+            totals.rate_limited += stats.rate_limited;
+            totals.signature_rejected += stats.signature_rejected;
+            event_bus.per_topic.insert(topic, stats);
+        }
+
+        // Agents deleted while listing are skipped
+        let runtime = &self.agent_runtime;
+        let agents = runtime
+            .agent_ids()
+            .into_iter()
+            .filter_map(|agent_id| {
+                Some(AgentQueueStats {
+                    queue_depth: runtime.agent_queue_depth(&agent_id).ok()?,
+                    subscriptions: runtime.get_agent_subscriptions(&agent_id).ok()?.len(),
+                    hibernated: runtime.is_agent_hibernated(&agent_id).ok()?,
+                    paused: runtime.is_agent_paused(&agent_id).ok()?,
+                    agent_id,
+                })
+            })
+            .collect();
+
+        let mut tools = self.tool_registry.stats();
+        tools.sort_by(|a, b| a.name.cmp(&b.name));
+
+        let components = self.memory_governor.usage().await;
+        let memory = MemorySnapshot {
+            level: self.memory_governor.level(),
+            total_bytes: components.iter().map(|c| c.bytes).sum(),
+            global_cap_bytes: self.memory_governor.config().global_cap_bytes,
+            components,
+        };
+
+        let sources: Vec<Arc<dyn StatsSource>> =
+            self.sources.iter().map(|e| Arc::clone(e.value())).collect();
+        let mut sections = BTreeMap::new();
+        for source in sources {
+            sections.insert(source.name(), source.stats().await);
+        }
+
+        LoomStats {
+            timestamp_ms: chrono::Utc::now().timestamp_millis(),
+            event_bus,
+            agents,
+            tools,
+            llm: LlmSnapshot {
+                usage: self.llm_usage.stats(),
+                cache: self.llm_cache.as_ref().map(|cache| cache.stats()),
+            },
+            mcp_servers: self.mcp_manager.server_states().await,
+            memory,
+            sources: sections,
+        }
+    }
+}
@@ -0,0 +1,48 @@
+//! Durable task queue with priorities and worker agents
+//!
+//! A `TaskQueue` holds task events for a pool of identical workers. Tasks are handed
+//! out highest priority first (the event's `priority`, then enqueue order) under a
+//! lease: a leased task is invisible to other workers until the lease is acked, failed
+//! or runs out (the visibility timeout). Failed and expired attempts are retried until
+//! `max_attempts`; a task out of attempts, or past its `deadline_ms` envelope field,
+//! is dead. Dead tasks stay in the queue for inspection and requeueing and, with an
+//! EventBus attached, are published to `dead_letter.task.<queue>` with the usual
+//! dead-letter metadata (see `messaging::reliable::dead_letter_keys`).
+//!
+//! With `TaskQueue::with_persistence` tasks survive restarts (RocksDB); leases do not,
+//! so tasks leased at shutdown are ready again afterwards.
+//!
+//! `TaskWorker` runs an `AgentBehavior` over leased tasks: `Ok` acks the task, `Err`
+//! fails it. `TaskWorker::spawn_pool` starts N workers from a factory.
+//!
+//! Metrics: `loom.task_queue.depth` (by queue and state) and
+//! `loom.task_queue.settled_total` (by queue and outcome).
+
+pub mod queue;
+mod store;
+pub mod worker;
+
+pub use queue::{DeadTask, Task, TaskLease, TaskQueue, TaskQueueConfig, TaskQueueStats};
+pub use worker::{TaskWorker, WorkerReport};
+
+/// Metadata set on the events of leased and dead-lettered tasks
+pub mod task_keys {
+    /// Queue name
+    pub const QUEUE: &str = "task.queue";
+    /// Task id
+    pub const ID: &str = "task.id";
+    /// Attempt number of the lease, starting at 1
+    pub const ATTEMPT: &str = "task.attempt";
+    /// Last reported error (dead letters only)
+    pub const ERROR: &str = "task.error";
+}
+
+/// Why a task was declared dead
+pub mod dead_reasons {
+    /// The last attempt was failed by its worker
+    pub const FAILED: &str = "failed";
+    /// The last attempt's lease ran out
+    pub const VISIBILITY_TIMEOUT: &str = "visibility_timeout";
+    /// The task's deadline passed before it was leased
+    pub const DEADLINE_EXPIRED: &str = "deadline_expired";
+}
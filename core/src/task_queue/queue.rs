@@ -0,0 +1,743 @@
+//! `TaskQueue`: priority queue of task events handed out under leases.
+
+use std::cmp::Reverse;
+use std::collections::{BTreeSet, HashMap};
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::Duration;
+
+use opentelemetry::{
+    global,
+    metrics::{Counter, UpDownCounter},
+    KeyValue,
+};
+use serde::Serialize;
+use tokio::sync::Notify;
+use tokio::task::JoinHandle;
+use tokio::time::Instant;
+use tracing::{debug, info, warn};
+
+use super::store::TaskStore;
+use super::{dead_reasons, task_keys};
+use crate::messaging::envelope::keys;
+use crate::messaging::reliable::{dead_letter_keys, DEAD_LETTER_TOPIC_PREFIX};
+use crate::proto::Event;
+use crate::{EventBus, Result};
+
+/// Task queue settings
+#[derive(Debug, Clone)]
+pub struct TaskQueueConfig {
+    /// How long a leased task stays invisible to other workers before it is handed out
+    /// again, unless the lease is extended
+    pub visibility_timeout: Duration,
+    /// Leases (first one included) before a task is declared dead
+    pub max_attempts: u32,
+    /// Defaults to `DEAD_LETTER_TOPIC_PREFIX` + `task.<queue name>`
+    pub dead_letter_topic: Option<String>,
+}
+
+impl Default for TaskQueueConfig {
+    fn default() -> Self {
+        Self {
+            visibility_timeout: Duration::from_secs(30),
+            max_attempts: 5,
+            dead_letter_topic: None,
+        }
+    }
+}
+
+impl TaskQueueConfig {
+    pub fn with_visibility_timeout(mut self, timeout: Duration) -> Self {
+        self.visibility_timeout = timeout;
+        self
+    }
+
+    pub fn with_max_attempts(mut self, max: u32) -> Self {
+        self.max_attempts = max.max(1);
+        self
+    }
+
+    pub fn with_dead_letter_topic(mut self, topic: impl Into<String>) -> Self {
+        self.dead_letter_topic = Some(topic.into());
+        self
+    }
+
+    /// Dead-letter topic of the queue named `queue`
+    pub fn dead_letter_topic_for(&self, queue: &str) -> String {
+        self.dead_letter_topic
+            .clone()
+            .unwrap_or_else(|| format!("{DEAD_LETTER_TOPIC_PREFIX}task.{queue}"))
+    }
+}
+
+/// A queued task
+#[derive(Debug, Clone)]
+pub struct Task {
+    pub id: String,
+    /// Enqueue order; breaks ties between tasks of equal priority
+    pub seq: u64,
+    pub event: Event,
+    /// Higher runs first (the event's priority)
+    pub priority: i32,
+    /// Absolute deadline (ms since epoch) from the event's `deadline_ms` envelope field;
+    /// the task is dead once it passes unprocessed
+    pub deadline_ms: Option<i64>,
+    pub enqueued_at_ms: i64,
+    /// Times the task has been leased
+    pub attempts: u32,
+    /// Error reported by the last failed attempt
+    pub last_error: Option<String>,
+}
+
+/// A task handed to a worker, invisible to other workers until `expires_at`.
+///
+/// The lease's event carries `task_keys` metadata. A lease is current until it is
+/// acked, failed or expires; settling a stale lease has no effect.
+#[derive(Debug, Clone)]
+pub struct TaskLease {
+    pub task: Task,
+    pub worker: String,
+    pub expires_at: Instant,
+}
+
+impl TaskLease {
+    /// 1 for the first lease of the task, incremented on every re-lease
+    pub fn attempt(&self) -> u32 {
+        self.task.attempts
+    }
+}
+
+/// A task given up on
+#[derive(Debug, Clone)]
+pub struct DeadTask {
+    pub task: Task,
+    /// One of `dead_reasons`
+    pub reason: String,
+}
+
+/// Queue depth and outcome counters
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct TaskQueueStats {
+    /// Tasks waiting for a worker
+    pub ready: usize,
+    /// Tasks currently leased
+    pub leased: usize,
+    /// Dead tasks kept for inspection or requeueing
+    pub dead: usize,
+    /// Age of the oldest ready task
+    pub oldest_ready_ms: Option<u64>,
+    pub enqueued: u64,
+    pub acked: u64,
+    /// Failed attempts (retried or not)
+    pub failed: u64,
+    /// Leases that ran out before the task was settled
+    pub lease_expired: u64,
+    /// Tasks declared dead
+    pub died: u64,
+}
+
+enum Slot {
+    Ready,
+    Leased { expires_at: Instant },
+    Dead { reason: String },
+}
+
+impl Slot {
+    fn label(&self) -> &'static str {
+        match self {
+            Slot::Ready => "ready",
+            Slot::Leased { .. } => "leased",
+            Slot::Dead { .. } => "dead",
+        }
+    }
+}
+
+struct Entry {
+    task: Task,
+    slot: Slot,
+}
+
+#[derive(Default)]
+struct State {
+    entries: HashMap<String, Entry>,
+    /// Ready tasks, highest priority then oldest first
+    ready: BTreeSet<(Reverse<i32>, u64, String)>,
+    /// Leased tasks by expiry
+    leased: BTreeSet<(Instant, String)>,
+    next_seq: u64,
+}
+
+#[derive(Default)]
+struct Counters {
+    enqueued: AtomicU64,
+    acked: AtomicU64,
+    failed: AtomicU64,
+    lease_expired: AtomicU64,
+    died: AtomicU64,
+}
+
+/// Durable priority queue of task events for pools of identical workers.
+///
+/// Workers `lease` the highest-priority ready task; it stays invisible to other workers
+/// for the visibility timeout (`extend` renews it). `ack` removes the task, `fail` puts
+/// it back, and a lease that runs out counts as a failed attempt. After `max_attempts`
+/// leases, or once its deadline passes, a task is dead: it is kept for `dead_tasks` /
+/// `requeue_dead` and, with an EventBus attached, published to the dead-letter topic.
+pub struct TaskQueue {
+    name: String,
+    config: TaskQueueConfig,
+    state: Mutex<State>,
+    available: Notify,
+    store: Option<TaskStore>,
+    event_bus: Option<Arc<EventBus>>,
+    counters: Counters,
+    depth_gauge: UpDownCounter<i64>,
+    settled_counter: Counter<u64>,
+}
+
+impl TaskQueue {
+    /// In-memory queue; tasks are lost when the process exits
+    pub fn new(name: impl Into<String>, config: TaskQueueConfig) -> Self {
+        let meter = global::meter("loom.task_queue");
+        Self {
+            name: name.into(),
+            config,
+            state: Mutex::new(State::default()),
+            available: Notify::new(),
+            store: None,
+            event_bus: None,
+            counters: Counters::default(),
+            depth_gauge: meter
+                .i64_up_down_counter("loom.task_queue.depth")
+                .with_description("Tasks in a queue by state (ready, leased, dead)")
+                .init(),
+            settled_counter: meter
+                .u64_counter("loom.task_queue.settled_total")
+                .with_description(
+                    "Task attempts settled, by outcome (acked, failed, expired, dead)",
+                )
+                .init(),
+        }
+    }
+
+    /// Queue stored in a RocksDB database at `path`, reloading the tasks already there.
+    ///
+    /// Tasks leased before a restart are ready again; their attempts still count.
+    pub fn with_persistence<P: AsRef<Path>>(
+        name: impl Into<String>,
+        config: TaskQueueConfig,
+        path: P,
+    ) -> Result<Self> {
+        let store = TaskStore::open(path)?;
+        let stored = store.load_all()?;
+        let mut queue = Self::new(name, config);
+        {
+            let mut state = queue.lock();
+            for (task, dead_reason) in stored {
+                state.next_seq = state.next_seq.max(task.seq + 1);
+                let slot = match dead_reason {
+                    Some(reason) => Slot::Dead { reason },
+                    None => {
+                        state
+                            .ready
+                            .insert((Reverse(task.priority), task.seq, task.id.clone()));
+                        Slot::Ready
+                    }
+                };
+                queue.gauge(None, Some(&slot));
+                state.entries.insert(task.id.clone(), Entry { task, slot });
+            }
+            info!(target: "task_queue", queue = %queue.name, tasks = state.entries.len(), "Reloaded task queue");
+        }
+        queue.store = Some(store);
+        Ok(queue)
+    }
+
+    /// Publish dead tasks to the dead-letter topic on `event_bus`
+    pub fn with_event_bus(mut self, event_bus: Arc<EventBus>) -> Self {
+        self.event_bus = Some(event_bus);
+        self
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn config(&self) -> &TaskQueueConfig {
+        &self.config
+    }
+
+    /// Queue `event` as a task and return its id.
+    ///
+    /// The task's priority is the event's; its deadline is the event's `deadline_ms`
+    /// envelope field, if any.
+    pub async fn enqueue(&self, event: Event) -> Result<String> {
+        let task = {
+            let mut state = self.lock();
+            let seq = state.next_seq;
+            let task = Task {
+                id: format!("{}_{}", self.name, seq),
+                seq,
+                priority: event.priority,
+                deadline_ms: event
+                    .metadata
+                    .get(keys::DEADLINE_MS)
+                    .and_then(|v| v.parse().ok()),
+                event,
+                enqueued_at_ms: chrono::Utc::now().timestamp_millis(),
+                attempts: 0,
+                last_error: None,
+            };
+            if let Some(ref store) = self.store {
+                store.put(&task, None)?;
+            }
+            state.next_seq += 1;
+            state
+                .ready
+                .insert((Reverse(task.priority), seq, task.id.clone()));
+            state.entries.insert(
+                task.id.clone(),
+                Entry {
+                    task: task.clone(),
+                    slot: Slot::Ready,
+                },
+            );
+            task
+        };
+        self.gauge(None, Some(&Slot::Ready));
+        self.counters.enqueued.fetch_add(1, Ordering::Relaxed);
+        self.available.notify_one();
+        debug!(target: "task_queue", queue = %self.name, task_id = %task.id, priority = task.priority, "Enqueued task");
+        Ok(task.id)
+    }
+
+    /// Lease the highest-priority ready task to `worker`, or `None` if no task is ready.
+    ///
+    /// Expired leases are released first; ready tasks past their deadline are declared
+    /// dead instead of being handed out.
+    pub async fn lease(&self, worker: &str) -> Result<Option<TaskLease>> {
+        let now = Instant::now();
+        let now_ms = chrono::Utc::now().timestamp_millis();
+        let (lease, dead) = {
+            let mut state = self.lock();
+            let mut dead = self.expire_leases(&mut state, now)?;
+            let mut lease = None;
+            while let Some((_, _, id)) = state.ready.pop_first() {
+                let Some(entry) = state.entries.get_mut(&id) else {
+                    continue;
+                };
+                if entry.task.deadline_ms.is_some_and(|d| d <= now_ms) {
+                    dead.push(self.bury(entry, dead_reasons::DEADLINE_EXPIRED)?);
+                    continue;
+                }
+                let mut task = entry.task.clone();
+                task.attempts += 1;
+                if let Some(ref store) = self.store {
+                    if let Err(e) = store.put(&task, None) {
+                        let key = (Reverse(task.priority), task.seq, task.id.clone());
+                        state.ready.insert(key);
+                        return Err(e);
+                    }
+                }
+                let expires_at = now + self.config.visibility_timeout;
+                entry.task.attempts = task.attempts;
+                entry.slot = Slot::Leased { expires_at };
+                state.leased.insert((expires_at, id));
+                self.gauge(Some(&Slot::Ready), Some(&Slot::Leased { expires_at }));
+                lease = Some(self.lease_of(task, worker, expires_at));
+                break;
+            }
+            (lease, dead)
+        };
+        self.dead_letter(dead).await;
+        if let Some(ref lease) = lease {
+            debug!(target: "task_queue", queue = %self.name, task_id = %lease.task.id, worker = %worker, attempt = lease.attempt(), "Leased task");
+        }
+        Ok(lease)
+    }
+
+    /// `lease`, waiting up to `timeout` for a task to become ready
+    pub async fn lease_wait(&self, worker: &str, timeout: Duration) -> Result<Option<TaskLease>> {
+        let deadline = Instant::now() + timeout;
+        loop {
+            if let Some(lease) = self.lease(worker).await? {
+                return Ok(Some(lease));
+            }
+            let now = Instant::now();
+            if now >= deadline {
+                return Ok(None);
+            }
+            // An expiring lease makes its task ready again without a notification
+            let wake = self
+                .lock()
+                .leased
+                .first()
+                .map_or(deadline, |(expires_at, _)| (*expires_at).min(deadline));
+            tokio::select! {
+                _ = self.available.notified() => {}
+                _ = tokio::time::sleep_until(wake.max(now)) => {}
+            }
+        }
+    }
+
+    /// Renew a current lease for another visibility timeout; false if it is stale
+    pub fn extend(&self, lease: &mut TaskLease) -> bool {
+        let mut state = self.lock();
+        let Some(old) = current_expiry(&state, lease) else {
+            return false;
+        };
+        let expires_at = Instant::now() + self.config.visibility_timeout;
+        state.leased.remove(&(old, lease.task.id.clone()));
+        state.leased.insert((expires_at, lease.task.id.clone()));
+        if let Some(entry) = state.entries.get_mut(&lease.task.id) {
+            entry.slot = Slot::Leased { expires_at };
+        }
+        lease.expires_at = expires_at;
+        true
+    }
+
+    /// Complete a leased task; false if the lease is stale (the task was re-leased,
+    /// settled or declared dead meanwhile)
+    pub async fn ack(&self, lease: &TaskLease) -> Result<bool> {
+        {
+            let mut state = self.lock();
+            let Some(expires_at) = current_expiry(&state, lease) else {
+                return Ok(false);
+            };
+            if let Some(ref store) = self.store {
+                store.delete(&lease.task.id)?;
+            }
+            state.leased.remove(&(expires_at, lease.task.id.clone()));
+            state.entries.remove(&lease.task.id);
+        }
+        self.gauge(
+            Some(&Slot::Leased {
+                expires_at: lease.expires_at,
+            }),
+            None,
+        );
+        self.counters.acked.fetch_add(1, Ordering::Relaxed);
+        self.settled("acked");
+        debug!(target: "task_queue", queue = %self.name, task_id = %lease.task.id, "Acked task");
+        Ok(true)
+    }
+
+    /// Report a failed attempt: the task is ready again, or dead once it has used up
+    /// `max_attempts`. False if the lease is stale.
+    pub async fn fail(&self, lease: &TaskLease, error: &str) -> Result<bool> {
+        let dead = {
+            let mut state = self.lock();
+            let Some(expires_at) = current_expiry(&state, lease) else {
+                return Ok(false);
+            };
+            state.leased.remove(&(expires_at, lease.task.id.clone()));
+            let Some(entry) = state.entries.get_mut(&lease.task.id) else {
+                return Ok(false);
+            };
+            entry.task.last_error = Some(error.to_string());
+            self.counters.failed.fetch_add(1, Ordering::Relaxed);
+            self.settled("failed");
+            if entry.task.attempts >= self.config.max_attempts {
+                Some(self.bury(entry, dead_reasons::FAILED)?)
+            } else {
+                self.requeue(&mut state, &lease.task.id)?;
+                None
+            }
+        };
+        warn!(target: "task_queue", queue = %self.name, task_id = %lease.task.id, attempt = lease.attempt(), error = %error, "Task attempt failed");
+        self.dead_letter(dead.into_iter().collect()).await;
+        Ok(true)
+    }
+
+    /// Release expired leases and declare ready tasks past their deadline dead; returns
+    /// the number of tasks affected. `lease` does the former on its own, so this is only
+    /// needed to keep stats and dead letters timely on an idle queue.
+    pub async fn sweep(&self) -> Result<usize> {
+        let now_ms = chrono::Utc::now().timestamp_millis();
+        let (affected, dead) = {
+            let mut state = self.lock();
+            let expired_before = self.counters.lease_expired.load(Ordering::Relaxed);
+            let mut dead = self.expire_leases(&mut state, Instant::now())?;
+            let released =
+                (self.counters.lease_expired.load(Ordering::Relaxed) - expired_before) as usize;
+            let overdue: Vec<(Reverse<i32>, u64, String)> = state
+                .ready
+                .iter()
+                .filter(|(_, _, id)| {
+                    state
+                        .entries
+                        .get(id)
+                        .and_then(|e| e.task.deadline_ms)
+                        .is_some_and(|d| d <= now_ms)
+                })
+                .cloned()
+                .collect();
+            for key in overdue {
+                state.ready.remove(&key);
+                if let Some(entry) = state.entries.get_mut(&key.2) {
+                    dead.push(self.bury(entry, dead_reasons::DEADLINE_EXPIRED)?);
+                }
+            }
+            // Buried leases are counted among the released ones already
+            let overdue_count = dead
+                .iter()
+                .filter(|d| d.reason == dead_reasons::DEADLINE_EXPIRED)
+                .count();
+            (released + overdue_count, dead)
+        };
+        self.dead_letter(dead).await;
+        Ok(affected)
+    }
+
+    /// Run `sweep` every `interval` until the task is aborted
+    pub fn spawn_sweeper(self: &Arc<Self>, interval: Duration) -> JoinHandle<()> {
+        let queue = Arc::clone(self);
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            loop {
+                ticker.tick().await;
+                if let Err(e) = queue.sweep().await {
+                    warn!(target: "task_queue", queue = %queue.name, error = %e, "Task queue sweep failed");
+                }
+            }
+        })
+    }
+
+    /// Dead tasks, oldest first
+    pub fn dead_tasks(&self) -> Vec<DeadTask> {
+        let state = self.lock();
+        let mut dead: Vec<DeadTask> = state
+            .entries
+            .values()
+            .filter_map(|entry| match &entry.slot {
+                Slot::Dead { reason } => Some(DeadTask {
+                    task: entry.task.clone(),
+                    reason: reason.clone(),
+                }),
+                _ => None,
+            })
+            .collect();
+        dead.sort_by_key(|d| d.task.seq);
+        dead
+    }
+
+    /// Make a dead task ready again with a fresh set of attempts; false if `task_id` is
+    /// not a dead task
+    pub async fn requeue_dead(&self, task_id: &str) -> Result<bool> {
+        {
+            let mut state = self.lock();
+            let Some(entry) = state.entries.get_mut(task_id) else {
+                return Ok(false);
+            };
+            if !matches!(entry.slot, Slot::Dead { .. }) {
+                return Ok(false);
+            }
+            entry.task.attempts = 0;
+            self.gauge(Some(&entry.slot), None);
+            self.requeue(&mut state, task_id)?;
+        }
+        info!(target: "task_queue", queue = %self.name, task_id = %task_id, "Requeued dead task");
+        Ok(true)
+    }
+
+    /// Drop all dead tasks; returns how many were dropped
+    pub fn purge_dead(&self) -> Result<usize> {
+        let mut state = self.lock();
+        let dead: Vec<String> = state
+            .entries
+            .iter()
+            .filter(|(_, entry)| matches!(entry.slot, Slot::Dead { .. }))
+            .map(|(id, _)| id.clone())
+            .collect();
+        for id in &dead {
+            if let Some(ref store) = self.store {
+                store.delete(id)?;
+            }
+            if let Some(entry) = state.entries.remove(id) {
+                self.gauge(Some(&entry.slot), None);
+            }
+        }
+        Ok(dead.len())
+    }
+
+    /// Tasks not yet settled (ready or leased)
+    pub fn depth(&self) -> usize {
+        let state = self.lock();
+        state.ready.len() + state.leased.len()
+    }
+
+    pub fn stats(&self) -> TaskQueueStats {
+        let state = self.lock();
+        let now_ms = chrono::Utc::now().timestamp_millis();
+        let oldest_ready_ms = state
+            .ready
+            .iter()
+            .filter_map(|(_, _, id)| state.entries.get(id))
+            .map(|entry| entry.task.enqueued_at_ms)
+            .min()
+            .map(|enqueued| now_ms.saturating_sub(enqueued).max(0) as u64);
+        TaskQueueStats {
+            ready: state.ready.len(),
+            leased: state.leased.len(),
+            dead: state.entries.len() - state.ready.len() - state.leased.len(),
+            oldest_ready_ms,
+            enqueued: self.counters.enqueued.load(Ordering::Relaxed),
+            acked: self.counters.acked.load(Ordering::Relaxed),
+            failed: self.counters.failed.load(Ordering::Relaxed),
+            lease_expired: self.counters.lease_expired.load(Ordering::Relaxed),
+            died: self.counters.died.load(Ordering::Relaxed),
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Release leases that ran out by `now`; tasks out of attempts are declared dead
+    fn expire_leases(&self, state: &mut State, now: Instant) -> Result<Vec<DeadTask>> {
+        let mut dead = Vec::new();
+        while let Some((expires_at, id)) = state.leased.first().cloned() {
+            if expires_at > now {
+                break;
+            }
+            state.leased.pop_first();
+            let Some(entry) = state.entries.get_mut(&id) else {
+                continue;
+            };
+            self.counters.lease_expired.fetch_add(1, Ordering::Relaxed);
+            self.settled("expired");
+            warn!(target: "task_queue", queue = %self.name, task_id = %id, attempt = entry.task.attempts, "Task lease expired");
+            if entry.task.attempts >= self.config.max_attempts {
+                dead.push(self.bury(entry, dead_reasons::VISIBILITY_TIMEOUT)?);
+            } else {
+                self.requeue(state, &id)?;
+            }
+        }
+        Ok(dead)
+    }
+
+    /// Make a (leased or dead) task ready again
+    fn requeue(&self, state: &mut State, task_id: &str) -> Result<()> {
+        let Some(entry) = state.entries.get_mut(task_id) else {
+            return Ok(());
+        };
+        if let Some(ref store) = self.store {
+            store.put(&entry.task, None)?;
+        }
+        if !matches!(entry.slot, Slot::Dead { .. }) {
+            self.gauge(Some(&entry.slot), None);
+        }
+        entry.slot = Slot::Ready;
+        self.gauge(None, Some(&Slot::Ready));
+        let key = (
+            Reverse(entry.task.priority),
+            entry.task.seq,
+            task_id.to_string(),
+        );
+        state.ready.insert(key);
+        self.available.notify_one();
+        Ok(())
+    }
+
+    /// Declare a task dead; the caller has taken it out of `ready` / `leased`
+    fn bury(&self, entry: &mut Entry, reason: &str) -> Result<DeadTask> {
+        if let Some(ref store) = self.store {
+            store.put(&entry.task, Some(reason))?;
+        }
+        let slot = Slot::Dead {
+            reason: reason.to_string(),
+        };
+        self.gauge(Some(&entry.slot), Some(&slot));
+        entry.slot = slot;
+        self.counters.died.fetch_add(1, Ordering::Relaxed);
+        self.settled("dead");
+        warn!(target: "task_queue", queue = %self.name, task_id = %entry.task.id, attempts = entry.task.attempts, reason = %reason, "Task is dead");
+        Ok(DeadTask {
+            task: entry.task.clone(),
+            reason: reason.to_string(),
+        })
+    }
+
+    fn lease_of(&self, mut task: Task, worker: &str, expires_at: Instant) -> TaskLease {
+        let metadata = &mut task.event.metadata;
+        metadata.insert(task_keys::QUEUE.into(), self.name.clone());
+        metadata.insert(task_keys::ID.into(), task.id.clone());
+        metadata.insert(task_keys::ATTEMPT.into(), task.attempts.to_string());
+        TaskLease {
+            task,
+            worker: worker.to_string(),
+            expires_at,
+        }
+    }
+
+    /// Publish dead tasks to the dead-letter topic (best-effort)
+    async fn dead_letter(&self, dead: Vec<DeadTask>) {
+        let Some(ref event_bus) = self.event_bus else {
+            return;
+        };
+        let topic = self.config.dead_letter_topic_for(&self.name);
+        for DeadTask { task, reason } in dead {
+            let mut event = task.event;
+            let metadata = &mut event.metadata;
+            metadata.insert(dead_letter_keys::REASON.into(), reason);
+            metadata.insert(dead_letter_keys::ATTEMPTS.into(), task.attempts.to_string());
+            metadata.insert(
+                dead_letter_keys::TOPIC.into(),
+                format!("task.{}", self.name),
+            );
+            metadata.insert(task_keys::QUEUE.into(), self.name.clone());
+            metadata.insert(task_keys::ID.into(), task.id.clone());
+            if let Some(error) = task.last_error {
+                metadata.insert(task_keys::ERROR.into(), error);
+            }
+            if let Err(e) = event_bus.publish(&topic, event).await {
+                warn!(target: "task_queue", queue = %self.name, task_id = %task.id, error = %e, "Failed to dead-letter task");
+            }
+        }
+    }
+
+    /// Move one task between depth gauge states (`None`: in or out of the queue)
+    fn gauge(&self, from: Option<&Slot>, to: Option<&Slot>) {
+        if let Some(from) = from {
+            self.depth_gauge.add(
+                -1,
+                &[
+                    KeyValue::new("queue", self.name.clone()),
+                    KeyValue::new("state", from.label()),
+                ],
+            );
+        }
+        if let Some(to) = to {
+            self.depth_gauge.add(
+                1,
+                &[
+                    KeyValue::new("queue", self.name.clone()),
+                    KeyValue::new("state", to.label()),
+                ],
+            );
+        }
+    }
+
+    fn settled(&self, outcome: &'static str) {
+        self.settled_counter.add(
+            1,
+            &[
+                KeyValue::new("queue", self.name.clone()),
+                KeyValue::new("outcome", outcome),
+            ],
+        );
+    }
+}
+
+/// Expiry of `lease` if it is still the task's current lease
+fn current_expiry(state: &State, lease: &TaskLease) -> Option<Instant> {
+    let entry = state.entries.get(&lease.task.id)?;
+    match entry.slot {
+        Slot::Leased { expires_at } if entry.task.attempts == lease.task.attempts => {
+            Some(expires_at)
+        }
+        _ => None,
+    }
+}
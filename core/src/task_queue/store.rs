@@ -0,0 +1,146 @@
+//! RocksDB backing for `TaskQueue`.
+//!
+//! Stores one JSON record per task id in the `tasks` column family. The event is kept
+//! as its protobuf encoding (base64), so payloads and metadata round-trip unchanged.
+//! Leases are not stored: after a restart every live task is ready again, with the
+//! attempts it had already been leased for.
+//!
+//! Records are versioned (`{"version": 1, ...}`); records from a newer release are
+//! skipped.
+
+use super::queue::Task;
+use crate::proto::Event;
+use crate::{LoomError, Result};
+use base64::Engine;
+use prost::Message;
+use rocksdb::{ColumnFamilyDescriptor, IteratorMode, Options, DB};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use tracing::{info, warn};
+
+const CF_TASKS: &str = "tasks";
+
+/// Version written by this release
+const RECORD_VERSION: u64 = 1;
+
+#[derive(Serialize, Deserialize)]
+struct TaskRecord {
+    version: u64,
+    id: String,
+    seq: u64,
+    priority: i32,
+    deadline_ms: Option<i64>,
+    enqueued_at_ms: i64,
+    attempts: u32,
+    last_error: Option<String>,
+    /// Why the task was given up on; `None` while it is live
+    dead_reason: Option<String>,
+    /// Protobuf encoding of the event, base64
+    event: String,
+}
+
+/// A stored task and, for a dead one, why it was given up on
+pub(crate) type StoredTask = (Task, Option<String>);
+
+pub(crate) struct TaskStore {
+    db: DB,
+}
+
+impl std::fmt::Debug for TaskStore {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TaskStore")
+            .field("path", &self.db.path())
+            .finish()
+    }
+}
+
+impl TaskStore {
+    pub(crate) fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let mut opts = Options::default();
+        opts.create_if_missing(true);
+        opts.create_missing_column_families(true);
+
+        let cf_descriptors = vec![ColumnFamilyDescriptor::new(CF_TASKS, Options::default())];
+        let db = DB::open_cf_descriptors(&opts, path, cf_descriptors)
+            .map_err(|e| LoomError::StorageError(e.to_string()))?;
+
+        info!(target: "task_queue", "TaskQueue persistence initialized");
+        Ok(Self { db })
+    }
+
+    pub(crate) fn put(&self, task: &Task, dead_reason: Option<&str>) -> Result<()> {
+        let cf = self.cf()?;
+        let record = TaskRecord {
+            version: RECORD_VERSION,
+            id: task.id.clone(),
+            seq: task.seq,
+            priority: task.priority,
+            deadline_ms: task.deadline_ms,
+            enqueued_at_ms: task.enqueued_at_ms,
+            attempts: task.attempts,
+            last_error: task.last_error.clone(),
+            dead_reason: dead_reason.map(str::to_string),
+            event: base64::engine::general_purpose::STANDARD.encode(task.event.encode_to_vec()),
+        };
+        self.db
+            .put_cf(cf, &task.id, serde_json::to_vec(&record)?)
+            .map_err(|e| LoomError::StorageError(e.to_string()))
+    }
+
+    pub(crate) fn delete(&self, task_id: &str) -> Result<()> {
+        let cf = self.cf()?;
+        self.db
+            .delete_cf(cf, task_id)
+            .map_err(|e| LoomError::StorageError(e.to_string()))
+    }
+
+    /// Load all stored tasks; undecodable records are skipped
+    pub(crate) fn load_all(&self) -> Result<Vec<StoredTask>> {
+        let cf = self.cf()?;
+        let mut tasks = Vec::new();
+        for entry in self.db.iterator_cf(cf, IteratorMode::Start) {
+            let (key, value) = entry.map_err(|e| LoomError::StorageError(e.to_string()))?;
+            match decode(&value) {
+                Ok(task) => tasks.push(task),
+                Err(e) => warn!(
+                    target: "task_queue",
+                    task_id = %String::from_utf8_lossy(&key),
+                    error = %e,
+                    "Skipping undecodable task record"
+                ),
+            }
+        }
+        Ok(tasks)
+    }
+
+    fn cf(&self) -> Result<&rocksdb::ColumnFamily> {
+        self.db
+            .cf_handle(CF_TASKS)
+            .ok_or_else(|| LoomError::StorageError(format!("Missing CF: {}", CF_TASKS)))
+    }
+}
+
+fn decode(bytes: &[u8]) -> std::result::Result<StoredTask, String> {
+    let record: TaskRecord = serde_json::from_slice(bytes).map_err(|e| e.to_string())?;
+    if record.version > RECORD_VERSION {
+        return Err(format!(
+            "record version {} is newer than supported version {RECORD_VERSION}",
+            record.version
+        ));
+    }
+    let encoded = base64::engine::general_purpose::STANDARD
+        .decode(&record.event)
+        .map_err(|e| e.to_string())?;
+    let event = Event::decode(encoded.as_slice()).map_err(|e| e.to_string())?;
+    let task = Task {
+        id: record.id,
+        seq: record.seq,
+        event,
+        priority: record.priority,
+        deadline_ms: record.deadline_ms,
+        enqueued_at_ms: record.enqueued_at_ms,
+        attempts: record.attempts,
+        last_error: record.last_error,
+    };
+    Ok((task, record.dead_reason))
+}
@@ -0,0 +1,213 @@
+//! `TaskWorker`: runs an `AgentBehavior` over tasks leased from a `TaskQueue`.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::task::JoinHandle;
+use tracing::{debug, info, warn};
+
+use super::queue::{TaskLease, TaskQueue};
+use super::task_keys;
+use crate::agent::AgentBehavior;
+use crate::proto::{Action, AgentConfig, AgentState};
+use crate::{CancellationToken, Event, EventBus, Result};
+
+/// Tasks settled by a worker over its run
+#[derive(Debug, Clone, Default)]
+pub struct WorkerReport {
+    pub worker_id: String,
+    pub acked: u64,
+    pub failed: u64,
+    /// Leases that went stale while the task was being handled (not settled)
+    pub lost: u64,
+}
+
+/// One worker of a queue's pool.
+///
+/// The behavior sees each leased task's event (with `task_keys` metadata); the lease is
+/// extended every half visibility timeout while it runs. Actions returned for a task
+/// are published as events to the results topic, if one is set, before the task is
+/// acked.
+pub struct TaskWorker<B: AgentBehavior> {
+    queue: Arc<TaskQueue>,
+    worker_id: String,
+    behavior: B,
+    results: Option<(Arc<EventBus>, String)>,
+    poll_interval: Duration,
+}
+
+impl<B: AgentBehavior + 'static> TaskWorker<B> {
+    pub fn new(queue: Arc<TaskQueue>, worker_id: impl Into<String>, behavior: B) -> Self {
+        Self {
+            queue,
+            worker_id: worker_id.into(),
+            behavior,
+            results: None,
+            poll_interval: Duration::from_secs(1),
+        }
+    }
+
+    /// Publish the actions returned for each task to `topic`
+    pub fn with_results(mut self, event_bus: Arc<EventBus>, topic: impl Into<String>) -> Self {
+        self.results = Some((event_bus, topic.into()));
+        self
+    }
+
+    /// Longest wait for a task before `cancel` is checked again
+    pub fn with_poll_interval(mut self, interval: Duration) -> Self {
+        self.poll_interval = interval;
+        self
+    }
+
+    pub fn worker_id(&self) -> &str {
+        &self.worker_id
+    }
+
+    /// Lease and handle tasks until `cancel` fires. The task in progress when it fires
+    /// is finished first.
+    pub async fn run(mut self, cancel: CancellationToken) -> Result<WorkerReport> {
+        let config = AgentConfig {
+            agent_id: self.worker_id.clone(),
+            agent_type: format!("task_worker.{}", self.queue.name()),
+            subscribed_topics: vec![],
+            capabilities: vec![],
+            parameters: HashMap::new(),
+        };
+        self.behavior.on_init(&config).await?;
+        let mut state = AgentState {
+            agent_id: self.worker_id.clone(),
+            persistent_state: vec![],
+            ephemeral_context: vec![],
+            last_update_ms: chrono::Utc::now().timestamp_millis(),
+            metadata: HashMap::new(),
+        };
+        let mut report = WorkerReport {
+            worker_id: self.worker_id.clone(),
+            ..Default::default()
+        };
+        info!(target: "task_queue", queue = %self.queue.name(), worker = %self.worker_id, "Task worker started");
+
+        loop {
+            let leased = tokio::select! {
+                _ = cancel.cancelled() => break,
+                leased = self.queue.lease_wait(&self.worker_id, self.poll_interval) => leased,
+            };
+            let lease = match leased {
+                Ok(Some(lease)) => lease,
+                Ok(None) => continue,
+                Err(e) => {
+                    warn!(target: "task_queue", worker = %self.worker_id, error = %e, "Failed to lease task");
+                    tokio::time::sleep(self.poll_interval).await;
+                    continue;
+                }
+            };
+            self.handle(lease, &mut state, &mut report).await;
+        }
+
+        if let Err(e) = self.behavior.on_shutdown().await {
+            warn!(target: "task_queue", worker = %self.worker_id, error = %e, "Task worker shutdown failed");
+        }
+        info!(target: "task_queue", queue = %self.queue.name(), worker = %self.worker_id, acked = report.acked, failed = report.failed, "Task worker stopped");
+        Ok(report)
+    }
+
+    /// Start `count` workers named `<prefix>-<n>`, each with a behavior from `factory`
+    pub fn spawn_pool<F>(
+        queue: Arc<TaskQueue>,
+        prefix: &str,
+        count: usize,
+        factory: F,
+        cancel: CancellationToken,
+    ) -> Vec<JoinHandle<Result<WorkerReport>>>
+    where
+        F: Fn(usize) -> B,
+    {
+        (0..count)
+            .map(|n| {
+                let worker =
+                    TaskWorker::new(Arc::clone(&queue), format!("{prefix}-{n}"), factory(n));
+                tokio::spawn(worker.run(cancel.clone()))
+            })
+            .collect()
+    }
+
+    async fn handle(
+        &mut self,
+        mut lease: TaskLease,
+        state: &mut AgentState,
+        report: &mut WorkerReport,
+    ) {
+        let queue = Arc::clone(&self.queue);
+        let mut renew = tokio::time::interval(queue.config().visibility_timeout / 2);
+        renew.tick().await;
+
+        // Scoped so the behavior's borrow ends before the task is settled
+        let outcome = {
+            let handling = self.behavior.on_event(lease.task.event.clone(), state);
+            tokio::pin!(handling);
+            loop {
+                tokio::select! {
+                    outcome = &mut handling => break outcome,
+                    _ = renew.tick() => {
+                        if !queue.extend(&mut lease) {
+                            warn!(target: "task_queue", worker = %self.worker_id, task_id = %lease.task.id, "Task lease lost while handling");
+                        }
+                    }
+                }
+            }
+        };
+
+        let settled = match outcome {
+            Ok(actions) => {
+                self.publish_results(&lease, actions).await;
+                let acked = queue.ack(&lease).await;
+                if matches!(acked, Ok(true)) {
+                    report.acked += 1;
+                }
+                acked
+            }
+            Err(e) => {
+                let failed = queue.fail(&lease, &e.to_string()).await;
+                if matches!(failed, Ok(true)) {
+                    report.failed += 1;
+                }
+                failed
+            }
+        };
+        match settled {
+            Ok(true) => {
+                debug!(target: "task_queue", worker = %self.worker_id, task_id = %lease.task.id, "Task settled")
+            }
+            Ok(false) => report.lost += 1,
+            Err(e) => {
+                warn!(target: "task_queue", worker = %self.worker_id, task_id = %lease.task.id, error = %e, "Failed to settle task")
+            }
+        }
+    }
+
+    async fn publish_results(&self, lease: &TaskLease, actions: Vec<Action>) {
+        let Some((ref event_bus, ref topic)) = self.results else {
+            return;
+        };
+        for (i, action) in actions.into_iter().enumerate() {
+            let mut metadata = action.parameters;
+            metadata.insert(task_keys::QUEUE.into(), self.queue.name().to_string());
+            metadata.insert(task_keys::ID.into(), lease.task.id.clone());
+            let event = Event {
+                id: format!("{}_result_{}", lease.task.id, i),
+                r#type: action.action_type,
+                timestamp_ms: chrono::Utc::now().timestamp_millis(),
+                source: format!("task_worker.{}", self.worker_id),
+                metadata,
+                payload: action.payload,
+                confidence: 1.0,
+                tags: vec![],
+                priority: action.priority,
+            };
+            if let Err(e) = event_bus.publish(topic, event).await {
+                warn!(target: "task_queue", worker = %self.worker_id, task_id = %lease.task.id, error = %e, "Failed to publish task result");
+            }
+        }
+    }
+}
@@ -0,0 +1,399 @@
+//! Multi-tenancy: tenant credentials, topic namespaces and quotas
+//!
+//! A tenant owns the topic namespace `tenant.<id>.`. The Bridge authenticates external
+//! agents with a bearer token, maps every topic they subscribe or publish to into
+//! their tenant's namespace (and back on delivery) and stamps their events with the
+//! `tenant` metadata key. `EventBus::publish` rejects a stamped event whose topic lies
+//! outside that tenant's namespace, so one tenant cannot reach another's topics even
+//! through a misbehaving gateway. Untenanted (in-process) publishers are unrestricted.
+//!
+//! Per-tenant quotas cap registered agents, published events per second and LLM
+//! tokens per minute; usage is tracked whether or not a quota is set.
+//!
+//! Tenants are loaded from the TOML file named by `LOOM_TENANTS_FILE`:
+//!
+//! ```toml
+//! [[tenants]]
+//! id = "acme"
+//! tokens = ["acme-secret"]
+//! max_agents = 10
+//! max_events_per_sec = 500
+//! max_llm_tokens_per_min = 100000
+//! ```
+
+use std::collections::{HashMap, HashSet};
+use std::time::Duration;
+
+use dashmap::DashMap;
+use opentelemetry::metrics::Counter;
+use opentelemetry::{global, KeyValue};
+use serde::{Deserialize, Serialize};
+use tokio::time::Instant;
+use tracing::{info, warn};
+
+use crate::proto::Event;
+use crate::{LoomError, Result};
+
+/// Event metadata key naming the tenant that published the event
+pub const TENANT_KEY: &str = "tenant";
+
+/// Root of all tenant topic namespaces
+pub const TENANT_TOPIC_PREFIX: &str = "tenant";
+
+/// `topic` inside `tenant`'s namespace
+pub fn namespace_topic(tenant: &str, topic: &str) -> String {
+    format!("{TENANT_TOPIC_PREFIX}.{tenant}.{topic}")
+}
+
+/// The tenant-relative part of a topic in `tenant`'s namespace
+pub fn strip_namespace<'a>(tenant: &str, topic: &'a str) -> Option<&'a str> {
+    topic
+        .strip_prefix(TENANT_TOPIC_PREFIX)
+        .and_then(|t| t.strip_prefix('.'))
+        .and_then(|t| t.strip_prefix(tenant))
+        .and_then(|t| t.strip_prefix('.'))
+}
+
+/// The tenant whose namespace `topic` lies in
+pub fn topic_tenant(topic: &str) -> Option<&str> {
+    let rest = topic.strip_prefix(TENANT_TOPIC_PREFIX)?.strip_prefix('.')?;
+    rest.split_once('.').map(|(tenant, _)| tenant)
+}
+
+/// `topic` relative to the namespace it lies in, or `topic` itself outside namespaces
+pub fn relative_topic(topic: &str) -> &str {
+    topic_tenant(topic)
+        .and_then(|tenant| strip_namespace(tenant, topic))
+        .unwrap_or(topic)
+}
+
+/// Reject events stamped with a tenant that are published outside its namespace
+pub fn check_isolation(topic: &str, event: &Event) -> Result<()> {
+    match event.metadata.get(TENANT_KEY) {
+        Some(tenant) if topic_tenant(topic) != Some(tenant.as_str()) => Err(
+            LoomError::TenantError(format!("tenant '{tenant}' may not publish to '{topic}'")),
+        ),
+        _ => Ok(()),
+    }
+}
+
+/// Limits applied to one tenant; `None` means unlimited
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TenantQuotas {
+    /// Agents registered at the same time
+    pub max_agents: Option<usize>,
+    /// Events published through the Bridge per second
+    pub max_events_per_sec: Option<u32>,
+    /// LLM tokens consumed per minute
+    pub max_llm_tokens_per_min: Option<u64>,
+}
+
+/// A tenant with the bearer tokens that authenticate its agents
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TenantConfig {
+    pub id: String,
+    #[serde(default)]
+    pub tokens: Vec<String>,
+    #[serde(flatten)]
+    pub quotas: TenantQuotas,
+}
+
+impl TenantConfig {
+    pub fn new(id: impl Into<String>) -> Self {
+        Self {
+            id: id.into(),
+            tokens: Vec::new(),
+            quotas: TenantQuotas::default(),
+        }
+    }
+
+    pub fn with_token(mut self, token: impl Into<String>) -> Self {
+        self.tokens.push(token.into());
+        self
+    }
+
+    pub fn with_quotas(mut self, quotas: TenantQuotas) -> Self {
+        self.quotas = quotas;
+        self
+    }
+}
+
+/// Current usage of a tenant
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TenantUsage {
+    pub tenant: String,
+    pub agents: usize,
+    pub events_total: u64,
+    pub events_rejected: u64,
+    /// Events admitted in the current one-second window
+    pub events_this_second: u32,
+    pub llm_tokens_total: u64,
+    /// LLM tokens recorded in the current one-minute window
+    pub llm_tokens_this_minute: u64,
+    pub llm_calls_rejected: u64,
+    pub agents_rejected: u64,
+}
+
+/// Fixed-window counter
+#[derive(Debug)]
+struct Window {
+    length: Duration,
+    started: Instant,
+    used: u64,
+}
+
+impl Window {
+    fn new(length: Duration) -> Self {
+        Self {
+            length,
+            started: Instant::now(),
+            used: 0,
+        }
+    }
+
+    /// Usage in the current window, starting a new one if it elapsed
+    fn current(&mut self) -> u64 {
+        if self.started.elapsed() >= self.length {
+            self.started = Instant::now();
+            self.used = 0;
+        }
+        self.used
+    }
+}
+
+#[derive(Debug)]
+struct TenantState {
+    quotas: TenantQuotas,
+    agents: HashSet<String>,
+    events: Window,
+    llm_tokens: Window,
+    usage: TenantUsage,
+}
+
+/// Registered tenants, their credentials and quota accounting
+pub struct TenantRegistry {
+    tenants: DashMap<String, TenantState>,
+    // bearer token -> tenant id
+    credentials: DashMap<String, String>,
+    rejected_counter: Counter<u64>,
+}
+
+impl Default for TenantRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TenantRegistry {
+    pub fn new() -> Self {
+        let rejected_counter = global::meter("loom.tenancy")
+            .u64_counter("loom.tenancy.rejected_total")
+            .with_description("Operations rejected by tenant quotas")
+            .init();
+        Self {
+            tenants: DashMap::new(),
+            credentials: DashMap::new(),
+            rejected_counter,
+        }
+    }
+
+    /// Parse `[[tenants]]` tables, see the module docs
+    pub fn from_toml(source: &str) -> Result<Self> {
+        #[derive(Deserialize)]
+        struct File {
+            #[serde(default)]
+            tenants: Vec<TenantConfig>,
+        }
+        let file: File = toml::from_str(source)
+            .map_err(|e| LoomError::TenantError(format!("Invalid tenants file: {e}")))?;
+        let registry = Self::new();
+        for tenant in file.tenants {
+            registry.register(tenant)?;
+        }
+        Ok(registry)
+    }
+
+    /// Load the file named by `LOOM_TENANTS_FILE`; `Ok(None)` if it is unset
+    pub fn from_env() -> Result<Option<Self>> {
+        let Ok(path) = std::env::var("LOOM_TENANTS_FILE") else {
+            return Ok(None);
+        };
+        let source = std::fs::read_to_string(&path)?;
+        let registry = Self::from_toml(&source)?;
+        info!(target: "tenancy", path = %path, tenants = registry.tenants.len(), "Loaded tenants");
+        Ok(Some(registry))
+    }
+
+    /// Add a tenant. Fails on an invalid or duplicate id, or a token already in use.
+    pub fn register(&self, config: TenantConfig) -> Result<()> {
+        // A tenant's id is its namespace (see `Namespace::new`)
+        if config.id.is_empty() || config.id.contains(['.', '/']) {
+            return Err(LoomError::TenantError(format!(
+                "Invalid tenant id '{}': ids must be non-empty and contain no '.' or '/'",
+                config.id
+            )));
+        }
+        if self.tenants.contains_key(&config.id) {
+            return Err(LoomError::TenantError(format!(
+                "Tenant already registered: {}",
+                config.id
+            )));
+        }
+        if let Some(token) = config
+            .tokens
+            .iter()
+            .find(|t| t.is_empty() || self.credentials.contains_key(*t))
+        {
+            let reason = if token.is_empty() {
+                "empty token"
+            } else {
+                "token already assigned to another tenant"
+            };
+            return Err(LoomError::TenantError(format!(
+                "Tenant {}: {reason}",
+                config.id
+            )));
+        }
+        for token in &config.tokens {
+            self.credentials.insert(token.clone(), config.id.clone());
+        }
+        self.tenants.insert(
+            config.id.clone(),
+            TenantState {
+                quotas: config.quotas,
+                agents: HashSet::new(),
+                events: Window::new(Duration::from_secs(1)),
+                llm_tokens: Window::new(Duration::from_secs(60)),
+                usage: TenantUsage {
+                    tenant: config.id,
+                    ..Default::default()
+                },
+            },
+        );
+        Ok(())
+    }
+
+    /// Tenant owning a bearer token
+    pub fn authenticate(&self, token: &str) -> Option<String> {
+        self.credentials.get(token).map(|t| t.clone())
+    }
+
+    pub fn contains(&self, tenant: &str) -> bool {
+        self.tenants.contains_key(tenant)
+    }
+
+    pub fn tenant_ids(&self) -> Vec<String> {
+        let mut ids: Vec<String> = self.tenants.iter().map(|t| t.key().clone()).collect();
+        ids.sort();
+        ids
+    }
+
+    pub fn quotas(&self, tenant: &str) -> Option<TenantQuotas> {
+        self.tenants.get(tenant).map(|t| t.quotas.clone())
+    }
+
+    /// Count `agent_id` against the tenant's agent quota; re-admitting a registered
+    /// agent is a no-op
+    pub fn admit_agent(&self, tenant: &str, agent_id: &str) -> Result<()> {
+        let mut state = self.state(tenant)?;
+        if state.agents.contains(agent_id) {
+            return Ok(());
+        }
+        if let Some(max) = state.quotas.max_agents {
+            if state.agents.len() >= max {
+                state.usage.agents_rejected += 1;
+                drop(state);
+                return Err(self.reject(tenant, "agents", format!("at most {max} agents")));
+            }
+        }
+        state.agents.insert(agent_id.to_string());
+        state.usage.agents = state.agents.len();
+        Ok(())
+    }
+
+    /// Stop counting `agent_id` against the tenant's agent quota
+    pub fn release_agent(&self, tenant: &str, agent_id: &str) {
+        if let Some(mut state) = self.tenants.get_mut(tenant) {
+            state.agents.remove(agent_id);
+            state.usage.agents = state.agents.len();
+        }
+    }
+
+    /// Count one published event against the tenant's rate
+    pub fn admit_event(&self, tenant: &str) -> Result<()> {
+        let mut state = self.state(tenant)?;
+        let used = state.events.current();
+        if let Some(max) = state.quotas.max_events_per_sec {
+            if used >= u64::from(max) {
+                state.usage.events_rejected += 1;
+                drop(state);
+                return Err(self.reject(tenant, "events", format!("at most {max} events/sec")));
+            }
+        }
+        state.events.used += 1;
+        state.usage.events_total += 1;
+        Ok(())
+    }
+
+    /// Check that the tenant has LLM tokens left in the current minute
+    pub fn check_llm_budget(&self, tenant: &str) -> Result<()> {
+        let mut state = self.state(tenant)?;
+        let used = state.llm_tokens.current();
+        if let Some(max) = state.quotas.max_llm_tokens_per_min {
+            if used >= max {
+                state.usage.llm_calls_rejected += 1;
+                drop(state);
+                return Err(self.reject(
+                    tenant,
+                    "llm_tokens",
+                    format!("at most {max} LLM tokens/min"),
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Record LLM tokens consumed by the tenant
+    pub fn record_llm_tokens(&self, tenant: &str, tokens: u64) {
+        if let Some(mut state) = self.tenants.get_mut(tenant) {
+            state.llm_tokens.current();
+            state.llm_tokens.used += tokens;
+            state.usage.llm_tokens_total += tokens;
+        }
+    }
+
+    pub fn usage(&self, tenant: &str) -> Option<TenantUsage> {
+        self.tenants.get_mut(tenant).map(|mut state| {
+            state.usage.events_this_second = state.events.current() as u32;
+            state.usage.llm_tokens_this_minute = state.llm_tokens.current();
+            state.usage.clone()
+        })
+    }
+
+    /// Usage of every tenant, keyed by tenant id
+    pub fn usage_all(&self) -> HashMap<String, TenantUsage> {
+        self.tenant_ids()
+            .into_iter()
+            .filter_map(|id| self.usage(&id).map(|u| (id, u)))
+            .collect()
+    }
+
+    fn state(&self, tenant: &str) -> Result<dashmap::mapref::one::RefMut<'_, String, TenantState>> {
+        self.tenants
+            .get_mut(tenant)
+            .ok_or_else(|| LoomError::TenantError(format!("Unknown tenant: {tenant}")))
+    }
+
+    fn reject(&self, tenant: &str, quota: &'static str, limit: String) -> LoomError {
+        warn!(target: "tenancy", tenant = %tenant, quota, "Tenant quota exceeded");
+        self.rejected_counter.add(
+            1,
+            &[
+                KeyValue::new("tenant", tenant.to_string()),
+                KeyValue::new("quota", quota),
+            ],
+        );
+        LoomError::QuotaExceeded(format!("tenant {tenant}: {limit}"))
+    }
+}
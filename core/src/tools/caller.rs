@@ -0,0 +1,30 @@
+//! Identity of the agent on whose behalf a tool runs.
+//!
+//! `Agent` runs each event, and the actions it produces, inside `with_caller`, so tools
+//! called from a behavior (directly or through `ToolRegistry`) can read
+//! `caller_agent_id()`. Code outside an agent, such as host code or Bridge tool calls,
+//! has no caller.
+
+use std::future::Future;
+
+tokio::task_local! {
+    static CALLER_AGENT_ID: String;
+}
+
+/// Run `fut` with `agent_id` as the calling agent
+pub async fn with_caller<F: Future>(agent_id: impl Into<String>, fut: F) -> F::Output {
+    CALLER_AGENT_ID.scope(agent_id.into(), fut).await
+}
+
+/// Run `fut` with the same caller as the current task; for work spawned on other tasks
+pub async fn inherit_caller<F: Future>(caller: Option<String>, fut: F) -> F::Output {
+    match caller {
+        Some(agent_id) => with_caller(agent_id, fut).await,
+        None => fut.await,
+    }
+}
+
+/// The agent the current task runs for, if any
+pub fn caller_agent_id() -> Option<String> {
+    CALLER_AGENT_ID.try_with(Clone::clone).ok()
+}
@@ -0,0 +1,310 @@
+//! Composite tools: one LLM-callable tool made of a chain of registry tools.
+//!
+//! Each step calls a registered tool with arguments rendered from a template, using the
+//! same `${...}` references as workflow steps: `${input.<path>}` reads the composite's
+//! arguments and `${steps.<id>.output.<path>}` the result of an earlier step. Paths are
+//! dot-separated object keys and array indices (`${steps.search.output.results.0.url}`).
+//! A string that is a single reference keeps the referenced value's JSON type; references
+//! embedded in text are rendered as text.
+//!
+//! The composite's parameter schema is synthesized from the steps: every referenced
+//! input becomes a required property, described by the schema of the step argument it
+//! is passed to as a whole (or by `with_input`). Its result is the last step's output
+//! unless `with_output` sets a template; when the result is a step's whole output the
+//! composite declares that tool's output schema.
+//!
+//! Steps run in order through the registry, so their policies (timeouts, retries,
+//! circuit breakers) apply, as does the composite's own policy around the whole chain.
+
+use super::error::{ToolError, ToolResult};
+use super::registry::ToolRegistry;
+use super::traits::Tool;
+use crate::workflow::template;
+use async_trait::async_trait;
+use serde_json::{json, Map, Value};
+use std::collections::HashMap;
+use tokio_util::sync::CancellationToken;
+use tracing::debug;
+
+/// One tool call of a composite
+#[derive(Debug, Clone, PartialEq)]
+pub struct CompositeStep {
+    pub id: String,
+    pub tool: String,
+    /// Arguments template
+    pub arguments: Value,
+}
+
+/// Builder of a `CompositeTool`
+///
+/// ```no_run
+/// use loom_core::tools::composite::CompositeTool;
+/// use loom_core::ToolRegistry;
+/// use serde_json::json;
+/// use std::sync::Arc;
+///
+/// # async fn example(registry: ToolRegistry) -> loom_core::tools::ToolResult<()> {
+/// let tool = CompositeTool::builder("research:brief", "Search the web and summarize the top results")
+///     .step("search", "web:search", json!({ "query": "${input.topic}" }))
+///     .step("summary", "llm:generate", json!({ "prompt": "Summarize: ${steps.search.output}" }))
+///     .build(&registry)?;
+/// registry.register(Arc::new(tool)).await;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct CompositeToolBuilder {
+    name: String,
+    description: String,
+    steps: Vec<CompositeStep>,
+    inputs: Vec<(String, Value)>,
+    output: Option<Value>,
+}
+
+impl CompositeToolBuilder {
+    /// Append a step calling `tool` with the `arguments` template
+    pub fn step(
+        mut self,
+        id: impl Into<String>,
+        tool: impl Into<String>,
+        arguments: Value,
+    ) -> Self {
+        self.steps.push(CompositeStep {
+            id: id.into(),
+            tool: tool.into(),
+            arguments,
+        });
+        self
+    }
+
+    /// Schema of the input `name`, replacing the synthesized one
+    pub fn with_input(mut self, name: impl Into<String>, schema: Value) -> Self {
+        self.inputs.push((name.into(), schema));
+        self
+    }
+
+    /// Template of the result (default: `${steps.<last>.output}`)
+    pub fn with_output(mut self, output: Value) -> Self {
+        self.output = Some(output);
+        self
+    }
+
+    /// Check the steps against `registry` and build the tool, which calls them through
+    /// `registry`.
+    ///
+    /// Fails with `NotFound` if a step's tool is not registered, and with
+    /// `InvalidArguments` if there are no steps, step ids repeat, a step calls the
+    /// composite itself, or a template references a step that does not run before it.
+    pub fn build(self, registry: &ToolRegistry) -> ToolResult<CompositeTool> {
+        let invalid = |reason: String| {
+            ToolError::InvalidArguments(format!("composite tool '{}': {}", self.name, reason))
+        };
+        if self.steps.is_empty() {
+            return Err(invalid("has no steps".to_string()));
+        }
+
+        let mut tools = Vec::with_capacity(self.steps.len());
+        for (index, step) in self.steps.iter().enumerate() {
+            if self.steps[..index].iter().any(|s| s.id == step.id) {
+                return Err(invalid(format!("step id '{}' is used twice", step.id)));
+            }
+            if step.tool == self.name {
+                return Err(invalid(format!(
+                    "step '{}' calls the composite itself",
+                    step.id
+                )));
+            }
+            let tool = registry
+                .get(&step.tool)
+                .ok_or_else(|| ToolError::NotFound(step.tool.clone()))?;
+            for referenced in template::step_references(&step.arguments) {
+                if !self.steps[..index].iter().any(|s| s.id == referenced) {
+                    return Err(invalid(format!(
+                        "step '{}' references step '{}', which does not run before it",
+                        step.id, referenced
+                    )));
+                }
+            }
+            tools.push(tool);
+        }
+
+        let last = &self.steps[self.steps.len() - 1];
+        let output = self
+            .output
+            .clone()
+            .unwrap_or_else(|| json!(format!("${{steps.{}.output}}", last.id)));
+        for referenced in template::step_references(&output) {
+            if !self.steps.iter().any(|s| s.id == referenced) {
+                return Err(invalid(format!(
+                    "output references unknown step '{}'",
+                    referenced
+                )));
+            }
+        }
+
+        // Parameters: each referenced input, described by the argument it fills
+        let mut properties = Map::new();
+        let mut required = Vec::new();
+        for (step, tool) in self.steps.iter().zip(&tools) {
+            let parameters = tool.parameters();
+            for name in template::input_references(&step.arguments) {
+                if !required.contains(&name) {
+                    required.push(name.clone());
+                }
+                let schema = argument_schema(&step.arguments, &parameters, &name);
+                let entry = properties.entry(name).or_insert(Value::Null);
+                if entry.is_null() || *entry == json!({}) {
+                    *entry = schema;
+                }
+            }
+        }
+        for name in template::input_references(&output) {
+            if !required.contains(&name) {
+                required.push(name.clone());
+            }
+            properties.entry(name).or_insert_with(|| json!({}));
+        }
+        for (name, schema) in &self.inputs {
+            properties.insert(name.clone(), schema.clone());
+        }
+        let parameters = json!({
+            "type": "object",
+            "properties": properties,
+            "required": required,
+        });
+
+        // Result schema: that of the step whose whole output is returned
+        let output_schema =
+            output
+                .as_str()
+                .and_then(template::lone_reference)
+                .and_then(|reference| {
+                    let id = reference.strip_prefix("steps.")?.strip_suffix(".output")?;
+                    let index = self.steps.iter().position(|s| s.id == id)?;
+                    tools[index].output_schema()
+                });
+
+        debug!(target: "tool_registry", tool = %self.name, steps = self.steps.len(), "Built composite tool");
+        Ok(CompositeTool {
+            name: self.name,
+            description: self.description,
+            steps: self.steps,
+            required,
+            output,
+            parameters,
+            output_schema,
+            registry: registry.clone(),
+        })
+    }
+}
+
+/// Schema for input `name` from the step argument that is exactly `${input.<name>}`
+fn argument_schema(arguments: &Value, parameters: &Value, name: &str) -> Value {
+    let whole = format!("input.{}", name);
+    arguments
+        .as_object()
+        .into_iter()
+        .flatten()
+        .find(|(_, value)| {
+            value.as_str().and_then(template::lone_reference) == Some(whole.as_str())
+        })
+        .and_then(|(argument, _)| parameters["properties"].get(argument))
+        .cloned()
+        .unwrap_or_else(|| json!({}))
+}
+
+/// A chain of registry tools exposed as one tool
+pub struct CompositeTool {
+    name: String,
+    description: String,
+    steps: Vec<CompositeStep>,
+    /// Inputs the templates reference
+    required: Vec<String>,
+    output: Value,
+    parameters: Value,
+    output_schema: Option<Value>,
+    registry: ToolRegistry,
+}
+
+impl CompositeTool {
+    pub fn builder(
+        name: impl Into<String>,
+        description: impl Into<String>,
+    ) -> CompositeToolBuilder {
+        CompositeToolBuilder {
+            name: name.into(),
+            description: description.into(),
+            steps: Vec::new(),
+            inputs: Vec::new(),
+            output: None,
+        }
+    }
+
+    pub fn steps(&self) -> &[CompositeStep] {
+        &self.steps
+    }
+}
+
+#[async_trait]
+impl Tool for CompositeTool {
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn description(&self) -> String {
+        self.description.clone()
+    }
+
+    fn parameters(&self) -> Value {
+        self.parameters.clone()
+    }
+
+    fn output_schema(&self) -> Option<Value> {
+        self.output_schema.clone()
+    }
+
+    async fn call(&self, arguments: Value) -> ToolResult<Value> {
+        self.call_with_cancel(arguments, CancellationToken::new())
+            .await
+    }
+
+    async fn call_with_cancel(
+        &self,
+        arguments: Value,
+        cancel: CancellationToken,
+    ) -> ToolResult<Value> {
+        if let Some(missing) = self.required.iter().find(|name| {
+            arguments
+                .get(name.as_str())
+                .filter(|v| !v.is_null())
+                .is_none()
+        }) {
+            return Err(ToolError::InvalidArguments(format!(
+                "Missing '{}'",
+                missing
+            )));
+        }
+
+        let mut outputs: HashMap<String, Value> = HashMap::new();
+        for step in &self.steps {
+            let rendered = template::render(&step.arguments, &arguments, &outputs)
+                .map_err(|e| ToolError::ExecutionFailed(format!("step '{}': {}", step.id, e)))?;
+            debug!(target: "tool_registry", tool = %self.name, step = %step.id, step_tool = %step.tool, "Running composite step");
+            let output = self
+                .registry
+                .call_with_cancel(&step.tool, rendered, cancel.child_token())
+                .await
+                .map_err(|e| match e {
+                    ToolError::Cancelled => ToolError::Cancelled,
+                    e => ToolError::ExecutionFailed(format!(
+                        "step '{}' ({}): {}",
+                        step.id, step.tool, e
+                    )),
+                })?;
+            outputs.insert(step.id.clone(), output);
+        }
+
+        template::render(&self.output, &arguments, &outputs)
+            .map_err(|e| ToolError::ExecutionFailed(format!("output: {}", e)))
+    }
+}
@@ -11,12 +11,21 @@ pub enum ToolError {
     #[error("Execution failed: {0}")]
     ExecutionFailed(String),
 
+    #[error("Invalid output: {0}")]
+    InvalidOutput(String),
+
     #[error("Permission denied: {0}")]
     PermissionDenied(String),
 
     #[error("Timeout")]
     Timeout,
 
+    #[error("Cancelled")]
+    Cancelled,
+
+    #[error("Circuit open: {0}")]
+    CircuitOpen(String),
+
     #[error("Internal error: {0}")]
     Internal(String),
 }
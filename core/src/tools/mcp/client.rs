@@ -1,8 +1,12 @@
 /// MCP Client implementation
 ///
-/// Provides low-level communication with MCP servers via stdio transport.
-/// Supports JSON-RPC 2.0 protocol with proper request/response correlation.
+/// Provides low-level communication with MCP servers via the stdio or streamable
+/// HTTP transport. Supports JSON-RPC 2.0 protocol with proper request/response
+/// correlation. Dropping an in-flight request (or timing out) notifies the server
+/// with `notifications/cancelled`.
+use super::http::HttpTransport;
 use super::types::*;
+use serde::Serialize;
 use serde_json::json;
 use std::collections::HashMap;
 use std::process::Stdio;
@@ -14,13 +18,98 @@ use tokio::sync::{oneshot, Mutex};
 use tokio::time::{timeout, Duration};
 use tracing::{debug, error, info, warn};
 
-/// MCP transport type
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub enum McpTransport {
-    /// Standard input/output (most common)
-    Stdio,
-    /// Server-Sent Events (HTTP-based)
-    Sse,
+/// Per-request timeout
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+type PendingMap = Arc<Mutex<HashMap<u64, oneshot::Sender<JsonRpcResponse>>>>;
+
+/// Outbound side of a live connection
+#[derive(Clone)]
+enum Channel {
+    Stdio(Arc<Mutex<Option<ChildStdin>>>),
+    Http(Arc<HttpTransport>),
+}
+
+impl Channel {
+    /// Send a JSON-RPC notification
+    async fn notify(
+        &self,
+        method: &str,
+        params: Option<serde_json::Value>,
+    ) -> Result<(), McpError> {
+        match self {
+            Channel::Stdio(stdin) => {
+                let mut message = json!({ "jsonrpc": "2.0", "method": method });
+                if let Some(params) = params {
+                    message["params"] = params;
+                }
+                write_message(stdin, &message).await
+            }
+            Channel::Http(http) => http.notify(method, params).await,
+        }
+    }
+}
+
+/// Write one newline-delimited JSON-RPC message to the server's stdin
+async fn write_message(
+    stdin: &Mutex<Option<ChildStdin>>,
+    message: &impl Serialize,
+) -> Result<(), McpError> {
+    let mut line = serde_json::to_string(message)?;
+    line.push('\n');
+
+    let mut stdin_guard = stdin.lock().await;
+    let stdin = stdin_guard
+        .as_mut()
+        .ok_or_else(|| McpError::Transport("stdin not available".to_string()))?;
+
+    stdin.write_all(line.as_bytes()).await.map_err(|e| {
+        error!(target: "mcp_client", error = %e, "Failed to write request");
+        McpError::Io(e)
+    })?;
+
+    stdin.flush().await.map_err(|e| {
+        error!(target: "mcp_client", error = %e, "Failed to flush stdin");
+        McpError::Io(e)
+    })
+}
+
+/// Sends `notifications/cancelled` for a request that is dropped before it completes
+struct CancelOnDrop {
+    id: u64,
+    channel: Option<Channel>,
+    pending: PendingMap,
+}
+
+impl CancelOnDrop {
+    /// The request completed; nothing to cancel
+    fn disarm(mut self) {
+        self.channel = None;
+    }
+}
+
+impl Drop for CancelOnDrop {
+    fn drop(&mut self) {
+        let Some(channel) = self.channel.take() else {
+            return;
+        };
+        let Ok(handle) = tokio::runtime::Handle::try_current() else {
+            return;
+        };
+
+        let id = self.id;
+        let pending = Arc::clone(&self.pending);
+        handle.spawn(async move {
+            pending.lock().await.remove(&id);
+            let params = json!({ "requestId": id, "reason": "Request cancelled by client" });
+            if let Err(e) = channel
+                .notify("notifications/cancelled", Some(params))
+                .await
+            {
+                debug!(target: "mcp_client", id = id, error = %e, "Failed to send cancellation");
+            }
+        });
+    }
 }
 
 /// MCP client for communicating with a single MCP server
@@ -31,10 +120,12 @@ pub struct McpClient {
     process: Arc<Mutex<Option<Child>>>,
     /// Stdin writer
     stdin: Arc<Mutex<Option<ChildStdin>>>,
+    /// Streamable HTTP connection
+    http: Arc<Mutex<Option<Arc<HttpTransport>>>>,
     /// Request ID counter
     request_id: Arc<AtomicU64>,
     /// Pending requests: request_id -> response channel
-    pending: Arc<Mutex<HashMap<u64, oneshot::Sender<JsonRpcResponse>>>>,
+    pending: PendingMap,
     /// Server info after initialization
     server_info: Arc<Mutex<Option<ServerInfo>>>,
     /// Server capabilities
@@ -48,6 +139,7 @@ impl McpClient {
             config,
             process: Arc::new(Mutex::new(None)),
             stdin: Arc::new(Mutex::new(None)),
+            http: Arc::new(Mutex::new(None)),
             request_id: Arc::new(AtomicU64::new(1)),
             pending: Arc::new(Mutex::new(HashMap::new())),
             server_info: Arc::new(Mutex::new(None)),
@@ -55,16 +147,55 @@ impl McpClient {
         }
     }
 
-    /// Start the MCP server process and initialize connection
+    /// Open the transport and initialize the connection
     pub async fn connect(&self) -> Result<(), McpError> {
         info!(
             target: "mcp_client",
             server = %self.config.name,
-            command = %self.config.command,
+            transport = ?self.config.transport,
             "Connecting to MCP server"
         );
 
-        // Spawn process
+        let channel = match self.config.transport {
+            McpTransport::Stdio => self.spawn_process().await?,
+            McpTransport::StreamableHttp => {
+                let http = Arc::new(HttpTransport::new(&self.config)?);
+                *self.http.lock().await = Some(Arc::clone(&http));
+                Channel::Http(http)
+            }
+            McpTransport::Sse => {
+                return Err(McpError::Transport(
+                    "The legacy SSE transport is not supported; use streamable_http".to_string(),
+                ))
+            }
+        };
+
+        let init_result = self.handshake(&channel).await?;
+
+        *self.server_info.lock().await = Some(init_result.server_info.clone());
+        *self.capabilities.lock().await = Some(init_result.capabilities.clone());
+
+        info!(
+            target: "mcp_client",
+            server = %self.config.name,
+            server_name = %init_result.server_info.name,
+            server_version = %init_result.server_info.version,
+            protocol_version = %init_result.protocol_version,
+            "MCP server connected and initialized"
+        );
+
+        Ok(())
+    }
+
+    /// Spawn the server process and start reading its stdout
+    async fn spawn_process(&self) -> Result<Channel, McpError> {
+        if self.config.command.is_empty() {
+            return Err(McpError::Transport(format!(
+                "Server '{}' uses stdio but has no command",
+                self.config.name
+            )));
+        }
+
         let mut cmd = Command::new(&self.config.command);
         cmd.args(&self.config.args)
             .stdin(Stdio::piped())
@@ -102,27 +233,18 @@ impl McpClient {
         // Start stdout reader task
         self.spawn_reader(stdout);
 
-        // Send initialize request
-        let init_result = self.initialize().await?;
-
-        *self.server_info.lock().await = Some(init_result.server_info.clone());
-        *self.capabilities.lock().await = Some(init_result.capabilities.clone());
-
-        info!(
-            target: "mcp_client",
-            server = %self.config.name,
-            server_name = %init_result.server_info.name,
-            server_version = %init_result.server_info.version,
-            "MCP server connected and initialized"
-        );
-
-        Ok(())
+        Ok(Channel::Stdio(Arc::clone(&self.stdin)))
     }
 
     /// Disconnect from the MCP server
     pub async fn disconnect(&self) -> Result<(), McpError> {
         info!(target: "mcp_client", server = %self.config.name, "Disconnecting from MCP server");
 
+        // Terminate the HTTP session
+        if let Some(http) = self.http.lock().await.take() {
+            http.close().await;
+        }
+
         // Close stdin to signal shutdown
         if let Some(mut stdin) = self.stdin.lock().await.take() {
             let _ = stdin.shutdown().await;
@@ -137,8 +259,22 @@ impl McpClient {
         Ok(())
     }
 
-    /// Send initialize request
-    async fn initialize(&self) -> Result<InitializeResult, McpError> {
+    /// Whether the transport is open (for stdio, while the server process runs)
+    pub async fn is_connected(&self) -> bool {
+        if self.http.lock().await.is_some() {
+            return true;
+        }
+        if self.stdin.lock().await.is_none() {
+            return false;
+        }
+        match self.process.lock().await.as_mut() {
+            Some(child) => matches!(child.try_wait(), Ok(None)),
+            None => false,
+        }
+    }
+
+    /// Send initialize and the initialized notification
+    async fn handshake(&self, channel: &Channel) -> Result<InitializeResult, McpError> {
         let params = InitializeParams {
             protocol_version: self.config.protocol_version().to_string(),
             capabilities: ClientCapabilities {
@@ -152,10 +288,25 @@ impl McpClient {
             },
         };
 
-        let result = self.send_request("initialize", Some(json!(params))).await?;
+        let request = self.build_request("initialize", Some(json!(params)));
+        let response = timeout(REQUEST_TIMEOUT, self.roundtrip(channel, &request))
+            .await
+            .map_err(|_| McpError::Timeout)??;
+        let result: InitializeResult = serde_json::from_value(into_result(response)?)
+            .map_err(|e| McpError::Protocol(format!("Invalid initialize result: {}", e)))?;
+
+        if let Channel::Http(http) = channel {
+            http.set_protocol_version(&result.protocol_version);
+        }
+        channel.notify("notifications/initialized", None).await?;
 
-        serde_json::from_value(result)
-            .map_err(|e| McpError::Protocol(format!("Invalid initialize result: {}", e)))
+        Ok(result)
+    }
+
+    /// Current streamable HTTP session id, if the server assigned one
+    pub async fn session_id(&self) -> Option<String> {
+        let http = self.http.lock().await.clone();
+        http.and_then(|http| http.session_id())
     }
 
     /// List available tools
@@ -242,66 +393,100 @@ impl McpClient {
     }
 
     /// Send a JSON-RPC request and wait for response
+    ///
+    /// If the future is dropped or times out, the server is sent
+    /// `notifications/cancelled` for the request.
     async fn send_request(
         &self,
         method: &str,
         params: Option<serde_json::Value>,
     ) -> Result<serde_json::Value, McpError> {
-        let id = self.request_id.fetch_add(1, Ordering::SeqCst);
-        let (tx, rx) = oneshot::channel();
+        let request = self.build_request(method, params);
+        let id = request.id.as_u64().unwrap_or_default();
+        let channel = self.channel().await;
+
+        let guard = CancelOnDrop {
+            id,
+            channel: Some(channel.clone()),
+            pending: Arc::clone(&self.pending),
+        };
 
-        // Register pending request
-        self.pending.lock().await.insert(id, tx);
+        let response = timeout(REQUEST_TIMEOUT, self.dispatch(&channel, &request))
+            .await
+            .map_err(|_| {
+                warn!(target: "mcp_client", method = %method, "Request timeout");
+                McpError::Timeout
+            })?;
+        guard.disarm();
+
+        into_result(response?)
+    }
 
-        // Build request
-        let request = JsonRpcRequest {
+    /// Build a request with a fresh id
+    fn build_request(&self, method: &str, params: Option<serde_json::Value>) -> JsonRpcRequest {
+        JsonRpcRequest {
             jsonrpc: "2.0".to_string(),
-            id: json!(id),
+            id: json!(self.request_id.fetch_add(1, Ordering::SeqCst)),
             method: method.to_string(),
             params,
-        };
-
-        // Serialize and send
-        let mut line = serde_json::to_string(&request)?;
-        line.push('\n');
-
-        let mut stdin_guard = self.stdin.lock().await;
-        let stdin = stdin_guard
-            .as_mut()
-            .ok_or_else(|| McpError::Transport("stdin not available".to_string()))?;
+        }
+    }
 
-        stdin.write_all(line.as_bytes()).await.map_err(|e| {
-            error!(target: "mcp_client", error = %e, "Failed to write request");
-            McpError::Io(e)
-        })?;
+    /// Outbound channel for the active transport
+    async fn channel(&self) -> Channel {
+        match self.http.lock().await.clone() {
+            Some(http) => Channel::Http(http),
+            None => Channel::Stdio(Arc::clone(&self.stdin)),
+        }
+    }
 
-        stdin.flush().await.map_err(|e| {
-            error!(target: "mcp_client", error = %e, "Failed to flush stdin");
-            McpError::Io(e)
-        })?;
+    /// Send a request, re-initializing once if the HTTP session expired
+    async fn dispatch(
+        &self,
+        channel: &Channel,
+        request: &JsonRpcRequest,
+    ) -> Result<JsonRpcResponse, McpError> {
+        match self.roundtrip(channel, request).await {
+            Err(McpError::SessionExpired) => {
+                warn!(
+                    target: "mcp_client",
+                    server = %self.config.name,
+                    "MCP session expired, re-initializing"
+                );
+                if let Channel::Http(http) = channel {
+                    http.reset_session();
+                }
+                self.handshake(channel).await?;
+                self.roundtrip(channel, request).await
+            }
+            other => other,
+        }
+    }
 
-        drop(stdin_guard);
+    /// Send a request over the channel and wait for its response
+    async fn roundtrip(
+        &self,
+        channel: &Channel,
+        request: &JsonRpcRequest,
+    ) -> Result<JsonRpcResponse, McpError> {
+        match channel {
+            Channel::Http(http) => http.request(request).await,
+            Channel::Stdio(stdin) => {
+                let id = request.id.as_u64().unwrap_or_default();
+                let (tx, rx) = oneshot::channel();
+
+                // Register pending request
+                self.pending.lock().await.insert(id, tx);
+
+                if let Err(e) = write_message(stdin, request).await {
+                    self.pending.lock().await.remove(&id);
+                    return Err(e);
+                }
 
-        // Wait for response with timeout
-        let response = timeout(Duration::from_secs(30), rx)
-            .await
-            .map_err(|_| {
-                warn!(target: "mcp_client", method = %method, "Request timeout");
-                McpError::Timeout
-            })?
-            .map_err(|_| McpError::Transport("Response channel closed".to_string()))?;
-
-        // Check for error
-        if let Some(error) = response.error {
-            return Err(McpError::ServerError(format!(
-                "{} (code: {})",
-                error.message, error.code
-            )));
+                rx.await
+                    .map_err(|_| McpError::Transport("Response channel closed".to_string()))
+            }
         }
-
-        response
-            .result
-            .ok_or_else(|| McpError::Protocol("Missing result in response".to_string()))
     }
 
     /// Spawn stdout reader task
@@ -360,6 +545,20 @@ impl McpClient {
     }
 }
 
+/// Extract the result from a response, surfacing JSON-RPC errors
+fn into_result(response: JsonRpcResponse) -> Result<serde_json::Value, McpError> {
+    if let Some(error) = response.error {
+        return Err(McpError::ServerError(format!(
+            "{} (code: {})",
+            error.message, error.code
+        )));
+    }
+
+    response
+        .result
+        .ok_or_else(|| McpError::Protocol("Missing result in response".to_string()))
+}
+
 impl Drop for McpClient {
     fn drop(&mut self) {
         // Best-effort cleanup
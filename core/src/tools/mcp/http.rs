@@ -0,0 +1,430 @@
+/// Streamable HTTP transport
+///
+/// Implements the MCP "Streamable HTTP" transport (protocol 2025-03-26): every
+/// JSON-RPC message is POSTed to a single endpoint, and the server answers either
+/// with a JSON body or with an SSE stream carrying the response. The server may
+/// assign an `Mcp-Session-Id` on initialize, which is echoed on every later request.
+/// Interrupted SSE streams are resumed with `Last-Event-ID`.
+use super::types::*;
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue, ACCEPT, AUTHORIZATION, CONTENT_TYPE};
+use reqwest::{Response, StatusCode};
+use serde_json::{json, Value};
+use std::sync::Mutex;
+use tokio::time::{sleep, Duration};
+use tracing::{debug, warn};
+
+/// Session header assigned by the server on initialize
+pub const SESSION_ID_HEADER: &str = "mcp-session-id";
+
+/// Negotiated protocol version header, sent after initialize
+pub const PROTOCOL_VERSION_HEADER: &str = "mcp-protocol-version";
+
+/// Resume header for interrupted SSE streams
+const LAST_EVENT_ID_HEADER: &str = "last-event-id";
+
+/// Times an interrupted SSE stream is resumed before giving up
+const MAX_RESUME_ATTEMPTS: u32 = 3;
+
+/// Delay before resuming an interrupted stream when the server sends no `retry`
+const DEFAULT_RESUME_DELAY: Duration = Duration::from_millis(250);
+
+/// Streamable HTTP connection to a single MCP server
+pub(crate) struct HttpTransport {
+    client: reqwest::Client,
+    url: String,
+    server_name: String,
+    /// Static headers (custom headers and Authorization)
+    headers: HeaderMap,
+    /// Session assigned by the server, if any
+    session_id: Mutex<Option<String>>,
+    /// Protocol version agreed during initialize
+    protocol_version: Mutex<Option<String>>,
+}
+
+/// One parsed SSE event
+#[derive(Debug, Default)]
+struct SseEvent {
+    id: Option<String>,
+    data: String,
+    retry: Option<u64>,
+}
+
+impl HttpTransport {
+    /// Build the transport from a server config (validates url, headers and token)
+    pub fn new(config: &McpServerConfig) -> Result<Self, McpError> {
+        let url = config
+            .url
+            .clone()
+            .filter(|url| !url.trim().is_empty())
+            .ok_or_else(|| {
+                McpError::Transport(format!(
+                    "Server '{}' uses streamable_http but has no url",
+                    config.name
+                ))
+            })?;
+
+        let mut headers = HeaderMap::new();
+        for (key, value) in config.headers.iter().flatten() {
+            let name = HeaderName::from_bytes(key.as_bytes()).map_err(|e| {
+                McpError::Transport(format!("Invalid header name '{}': {}", key, e))
+            })?;
+            let value = HeaderValue::from_str(value)
+                .map_err(|e| McpError::Transport(format!("Invalid value for '{}': {}", key, e)))?;
+            headers.insert(name, value);
+        }
+
+        if let Some(token) = config.bearer_token()? {
+            let mut value = HeaderValue::from_str(&format!("Bearer {}", token))
+                .map_err(|_| McpError::Transport("Invalid bearer token".to_string()))?;
+            value.set_sensitive(true);
+            headers.insert(AUTHORIZATION, value);
+        }
+
+        let client = reqwest::Client::builder()
+            .user_agent(concat!("loom/", env!("CARGO_PKG_VERSION")))
+            .build()
+            .unwrap_or_else(|_| reqwest::Client::new());
+
+        Ok(Self {
+            client,
+            url,
+            server_name: config.name.clone(),
+            headers,
+            session_id: Mutex::new(None),
+            protocol_version: Mutex::new(None),
+        })
+    }
+
+    /// Current session id, if the server assigned one
+    pub fn session_id(&self) -> Option<String> {
+        self.session_id.lock().unwrap().clone()
+    }
+
+    /// Forget the current session (e.g. after the server expired it)
+    pub fn reset_session(&self) {
+        *self.session_id.lock().unwrap() = None;
+        *self.protocol_version.lock().unwrap() = None;
+    }
+
+    /// Record the protocol version negotiated during initialize
+    pub fn set_protocol_version(&self, version: &str) {
+        *self.protocol_version.lock().unwrap() = Some(version.to_string());
+    }
+
+    /// Headers for the next request: static headers plus session state
+    fn request_headers(&self) -> HeaderMap {
+        let mut headers = self.headers.clone();
+        if let Some(session) = self.session_id() {
+            if let Ok(value) = HeaderValue::from_str(&session) {
+                headers.insert(SESSION_ID_HEADER, value);
+            }
+        }
+        if let Some(version) = self.protocol_version.lock().unwrap().clone() {
+            if let Ok(value) = HeaderValue::from_str(&version) {
+                headers.insert(PROTOCOL_VERSION_HEADER, value);
+            }
+        }
+        headers
+    }
+
+    /// POST a message and check the status
+    async fn post(&self, message: &Value) -> Result<Response, McpError> {
+        let response = self
+            .client
+            .post(&self.url)
+            .headers(self.request_headers())
+            .header(ACCEPT, "application/json, text/event-stream")
+            .json(message)
+            .send()
+            .await
+            .map_err(|e| McpError::Transport(format!("POST {} failed: {}", self.url, e)))?;
+
+        if let Some(session) = response
+            .headers()
+            .get(SESSION_ID_HEADER)
+            .and_then(|v| v.to_str().ok())
+        {
+            let mut current = self.session_id.lock().unwrap();
+            if current.as_deref() != Some(session) {
+                debug!(target: "mcp_client", server = %self.server_name, session = %session, "MCP session assigned");
+                *current = Some(session.to_string());
+            }
+        }
+
+        self.check_status(response).await
+    }
+
+    /// Map HTTP failures to MCP errors
+    async fn check_status(&self, response: Response) -> Result<Response, McpError> {
+        let status = response.status();
+        if status.is_success() {
+            return Ok(response);
+        }
+
+        let body = response.text().await.unwrap_or_default();
+        match status {
+            StatusCode::NOT_FOUND if self.session_id().is_some() => Err(McpError::SessionExpired),
+            StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => Err(McpError::Unauthorized(
+                format!("{} from {}", status, self.url),
+            )),
+            _ => Err(McpError::Transport(format!(
+                "HTTP {} from {}: {}",
+                status,
+                self.url,
+                body.chars().take(200).collect::<String>()
+            ))),
+        }
+    }
+
+    /// Send a request and wait for the matching response
+    pub async fn request(&self, request: &JsonRpcRequest) -> Result<JsonRpcResponse, McpError> {
+        let response = self.post(&serde_json::to_value(request)?).await?;
+
+        if is_event_stream(&response) {
+            return self.read_stream(response, &request.id).await;
+        }
+
+        let body: Value = response
+            .json()
+            .await
+            .map_err(|e| McpError::Protocol(format!("Invalid JSON response: {}", e)))?;
+
+        // A batch may carry more than our response
+        let messages = match body {
+            Value::Array(items) => items,
+            other => vec![other],
+        };
+        for message in messages {
+            if message.get("id") == Some(&request.id) && message.get("method").is_none() {
+                return Ok(serde_json::from_value(message)?);
+            }
+        }
+
+        Err(McpError::Protocol(format!(
+            "Response did not contain a reply to request {}",
+            request.id
+        )))
+    }
+
+    /// Send a notification (no response expected)
+    pub async fn notify(&self, method: &str, params: Option<Value>) -> Result<(), McpError> {
+        let mut message = json!({ "jsonrpc": "2.0", "method": method });
+        if let Some(params) = params {
+            message["params"] = params;
+        }
+        self.post(&message).await.map(|_| ())
+    }
+
+    /// Terminate the session on the server (best effort)
+    pub async fn close(&self) {
+        if self.session_id().is_none() {
+            return;
+        }
+
+        let result = self
+            .client
+            .delete(&self.url)
+            .headers(self.request_headers())
+            .send()
+            .await;
+        if let Err(e) = result {
+            debug!(target: "mcp_client", server = %self.server_name, error = %e, "Failed to terminate MCP session");
+        }
+        self.reset_session();
+    }
+
+    /// Read an SSE response until the reply to `id` arrives, resuming if the stream drops
+    async fn read_stream(
+        &self,
+        mut response: Response,
+        id: &Value,
+    ) -> Result<JsonRpcResponse, McpError> {
+        let mut last_event_id: Option<String> = None;
+        let mut retry_delay = DEFAULT_RESUME_DELAY;
+        let mut attempts = 0;
+
+        loop {
+            let mut buffer = String::new();
+            let mut ended_cleanly = true;
+
+            loop {
+                let chunk = match response.chunk().await {
+                    Ok(Some(chunk)) => chunk,
+                    Ok(None) => break,
+                    Err(e) => {
+                        debug!(target: "mcp_client", server = %self.server_name, error = %e, "SSE stream interrupted");
+                        ended_cleanly = false;
+                        break;
+                    }
+                };
+                buffer.push_str(&String::from_utf8_lossy(&chunk));
+
+                while let Some(event) = take_event(&mut buffer) {
+                    if event.id.is_some() {
+                        last_event_id = event.id.clone();
+                    }
+                    if let Some(retry) = event.retry {
+                        retry_delay = Duration::from_millis(retry);
+                    }
+                    if let Some(reply) = self.handle_message(&event.data, id).await? {
+                        return Ok(reply);
+                    }
+                }
+            }
+
+            // The stream ended without our reply: resume it if the server made it resumable
+            let Some(ref event_id) = last_event_id else {
+                return Err(McpError::Transport(if ended_cleanly {
+                    "SSE stream closed before the response arrived".to_string()
+                } else {
+                    "SSE stream interrupted and is not resumable".to_string()
+                }));
+            };
+
+            attempts += 1;
+            if attempts > MAX_RESUME_ATTEMPTS {
+                return Err(McpError::Transport(format!(
+                    "SSE stream could not be resumed after {} attempts",
+                    MAX_RESUME_ATTEMPTS
+                )));
+            }
+
+            debug!(
+                target: "mcp_client",
+                server = %self.server_name,
+                last_event_id = %event_id,
+                attempt = attempts,
+                "Resuming SSE stream"
+            );
+            sleep(retry_delay).await;
+
+            let resumed = self
+                .client
+                .get(&self.url)
+                .headers(self.request_headers())
+                .header(ACCEPT, "text/event-stream")
+                .header(LAST_EVENT_ID_HEADER, event_id.as_str())
+                .send()
+                .await
+                .map_err(|e| McpError::Transport(format!("GET {} failed: {}", self.url, e)))?;
+            response = self.check_status(resumed).await?;
+        }
+    }
+
+    /// Handle one SSE message; returns the reply if it answers `id`
+    async fn handle_message(
+        &self,
+        data: &str,
+        id: &Value,
+    ) -> Result<Option<JsonRpcResponse>, McpError> {
+        if data.trim().is_empty() {
+            return Ok(None);
+        }
+
+        let message: Value = match serde_json::from_str(data) {
+            Ok(message) => message,
+            Err(e) => {
+                warn!(target: "mcp_client", server = %self.server_name, error = %e, "Failed to parse SSE message");
+                return Ok(None);
+            }
+        };
+
+        // Our reply
+        if message.get("method").is_none() && message.get("id") == Some(id) {
+            return Ok(Some(serde_json::from_value(message)?));
+        }
+
+        match (message.get("method"), message.get("id")) {
+            // A server-to-client request: we expose no client capabilities, so decline it
+            (Some(method), Some(request_id)) => {
+                debug!(target: "mcp_client", server = %self.server_name, method = %method, "Declining server request");
+                let reply = json!({
+                    "jsonrpc": "2.0",
+                    "id": request_id,
+                    "error": { "code": -32601, "message": "Method not supported by client" }
+                });
+                if let Err(e) = self.post(&reply).await {
+                    debug!(target: "mcp_client", server = %self.server_name, error = %e, "Failed to decline server request");
+                }
+                Ok(None)
+            }
+            // Notifications (progress, logging) and unrelated replies
+            _ => {
+                debug!(target: "mcp_client", server = %self.server_name, "Ignoring SSE message");
+                Ok(None)
+            }
+        }
+    }
+}
+
+fn is_event_stream(response: &Response) -> bool {
+    response
+        .headers()
+        .get(CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.starts_with("text/event-stream"))
+}
+
+/// Remove the first complete event from `buffer`
+fn take_event(buffer: &mut String) -> Option<SseEvent> {
+    loop {
+        if buffer.contains("\r\n") {
+            *buffer = buffer.replace("\r\n", "\n");
+        }
+        let end = buffer.find("\n\n")?;
+        let raw: String = buffer.drain(..end + 2).collect();
+
+        let mut event = SseEvent::default();
+        let mut data_lines = Vec::new();
+        for line in raw.lines() {
+            if line.starts_with(':') {
+                continue;
+            }
+            let (field, value) = match line.split_once(':') {
+                Some((field, value)) => (field, value.strip_prefix(' ').unwrap_or(value)),
+                None => (line, ""),
+            };
+            match field {
+                "data" => data_lines.push(value),
+                "id" => event.id = Some(value.to_string()),
+                "retry" => event.retry = value.parse().ok(),
+                _ => {}
+            }
+        }
+
+        // Comment-only blocks (keep-alives) carry nothing
+        if data_lines.is_empty() && event.id.is_none() {
+            continue;
+        }
+        event.data = data_lines.join("\n");
+        return Some(event);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_take_event_parses_fields() {
+        let mut buffer =
+            ": keep-alive\n\nid: 7\nretry: 100\ndata: {\"a\":\ndata: 1}\n\ndata: partial"
+                .to_string();
+
+        let event = take_event(&mut buffer).unwrap();
+        assert_eq!(event.id.as_deref(), Some("7"));
+        assert_eq!(event.retry, Some(100));
+        assert_eq!(event.data, "{\"a\":\n1}");
+
+        assert!(take_event(&mut buffer).is_none());
+        assert_eq!(buffer, "data: partial");
+    }
+
+    #[test]
+    fn test_take_event_handles_crlf() {
+        let mut buffer = "id: 1\r\ndata: x\r\n\r\n".to_string();
+        let event = take_event(&mut buffer).unwrap();
+        assert_eq!(event.id.as_deref(), Some("1"));
+        assert_eq!(event.data, "x");
+    }
+}
@@ -4,15 +4,25 @@
 /// with the ToolRegistry. Handles lifecycle (connect/disconnect/reconnect).
 use super::adapter::McpToolAdapter;
 use super::client::McpClient;
-use super::types::{McpError, McpServerConfig};
+use super::types::{McpError, McpServerConfig, ServerInfo};
 use crate::tools::ToolRegistry;
 use opentelemetry::metrics::{Counter, UpDownCounter};
 use opentelemetry::KeyValue;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::sync::Arc;
 use tokio::sync::RwLock;
 use tracing::{debug, error, info, warn};
 
+/// Connection state of an MCP server, from `McpManager::server_states`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct McpServerState {
+    pub name: String,
+    pub connected: bool,
+    /// Name and version reported at initialization
+    pub server_info: Option<ServerInfo>,
+}
+
 /// MCP Manager
 ///
 /// Responsible for:
@@ -191,6 +201,27 @@ impl McpManager {
         self.clients.read().await.keys().cloned().collect()
     }
 
+    /// Connection state of every added server, sorted by name
+    pub async fn server_states(&self) -> Vec<McpServerState> {
+        let clients: Vec<(String, Arc<McpClient>)> = self
+            .clients
+            .read()
+            .await
+            .iter()
+            .map(|(name, client)| (name.clone(), Arc::clone(client)))
+            .collect();
+        let mut states = Vec::with_capacity(clients.len());
+        for (name, client) in clients {
+            states.push(McpServerState {
+                name,
+                connected: client.is_connected().await,
+                server_info: client.server_info().await,
+            });
+        }
+        states.sort_by(|a, b| a.name.cmp(&b.name));
+        states
+    }
+
     /// Register tools from a client with the ToolRegistry
     #[tracing::instrument(skip(self, client), fields(server = %server_name))]
     async fn register_tools(
@@ -323,6 +354,13 @@ impl McpManager {
     ///   "brave-search": {"command": "npx", "args": ["-y", "@anthropics/mcp-brave-search"], "env": {"BRAVE_API_KEY": "..."}}
     /// }
     /// ```
+    ///
+    /// Remote servers use the streamable HTTP transport:
+    /// ```json
+    /// {
+    ///   "remote": {"transport": "streamable_http", "url": "https://example.com/mcp", "bearer_token_env": "REMOTE_MCP_TOKEN"}
+    /// }
+    /// ```
     #[tracing::instrument(skip(self))]
     pub async fn load_from_env(&self) -> Result<usize, McpError> {
         let env_value = match std::env::var("LOOM_MCP_SERVERS") {
@@ -336,7 +374,7 @@ impl McpManager {
         info!(target: "mcp_manager", "Loading MCP servers from LOOM_MCP_SERVERS");
 
         // Try to parse as JSON
-        let configs = self.parse_mcp_config(&env_value)?;
+        let configs = Self::parse_servers(&env_value)?;
 
         let mut loaded = 0;
         for config in configs {
@@ -359,8 +397,8 @@ impl McpManager {
         Ok(loaded)
     }
 
-    /// Parse MCP configuration from JSON string.
-    fn parse_mcp_config(&self, json_str: &str) -> Result<Vec<McpServerConfig>, McpError> {
+    /// Parse MCP configuration from JSON string (the `LOOM_MCP_SERVERS` formats).
+    pub(crate) fn parse_servers(json_str: &str) -> Result<Vec<McpServerConfig>, McpError> {
         // Try parsing as array first
         if let Ok(configs) = serde_json::from_str::<Vec<McpServerConfig>>(json_str) {
             return Ok(configs);
@@ -1,12 +1,13 @@
 pub mod adapter;
 pub mod client;
+mod http;
 pub mod manager;
 pub mod types;
 
 pub use adapter::McpToolAdapter;
-pub use client::{McpClient, McpTransport};
-pub use manager::McpManager;
+pub use client::McpClient;
+pub use manager::{McpManager, McpServerState};
 pub use types::{
-    McpError, McpTool, McpToolCall, McpToolResult, DEFAULT_PROTOCOL_VERSION,
-    SUPPORTED_PROTOCOL_VERSIONS,
+    McpError, McpTool, McpToolCall, McpToolResult, McpTransport, DEFAULT_PROTOCOL_VERSION,
+    STREAMABLE_HTTP_PROTOCOL_VERSION, SUPPORTED_PROTOCOL_VERSIONS,
 };
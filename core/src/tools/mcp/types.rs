@@ -7,15 +7,47 @@ use std::collections::HashMap;
 /// Default MCP protocol version (as of November 2024)
 pub const DEFAULT_PROTOCOL_VERSION: &str = "2024-11-05";
 
+/// Default protocol version for the streamable HTTP transport (introduced in 2025-03-26)
+pub const STREAMABLE_HTTP_PROTOCOL_VERSION: &str = "2025-03-26";
+
 /// Supported MCP protocol versions
-pub const SUPPORTED_PROTOCOL_VERSIONS: &[&str] = &["2024-11-05"];
+pub const SUPPORTED_PROTOCOL_VERSIONS: &[&str] = &["2024-11-05", "2025-03-26"];
+
+/// MCP transport type
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum McpTransport {
+    /// Standard input/output (most common)
+    #[default]
+    Stdio,
+    /// Server-Sent Events (HTTP-based, 2024-11-05 spec; not yet supported)
+    Sse,
+    /// Streamable HTTP: JSON-RPC over POST with JSON or SSE responses
+    StreamableHttp,
+}
 
 impl McpServerConfig {
-    /// Get the protocol version to use (configured or default)
+    /// Get the protocol version to use (configured or default for the transport)
     pub fn protocol_version(&self) -> &str {
         self.protocol_version
             .as_deref()
-            .unwrap_or(DEFAULT_PROTOCOL_VERSION)
+            .unwrap_or(match self.transport {
+                McpTransport::StreamableHttp => STREAMABLE_HTTP_PROTOCOL_VERSION,
+                _ => DEFAULT_PROTOCOL_VERSION,
+            })
+    }
+
+    /// Resolve the bearer token: inline value first, then the named env var
+    pub fn bearer_token(&self) -> Result<Option<String>, McpError> {
+        if let Some(ref token) = self.bearer_token {
+            return Ok(Some(token.clone()));
+        }
+        match self.bearer_token_env {
+            Some(ref var) => std::env::var(var)
+                .map(Some)
+                .map_err(|_| McpError::Unauthorized(format!("{} is not set", var))),
+            None => Ok(None),
+        }
     }
 
     /// Validate the protocol version is supported
@@ -251,6 +283,12 @@ pub enum McpError {
     #[error("Server error: {0}")]
     ServerError(String),
 
+    #[error("Unauthorized: {0}")]
+    Unauthorized(String),
+
+    #[error("Session expired")]
+    SessionExpired,
+
     #[error("IO error: {0}")]
     Io(#[from] std::io::Error),
 
@@ -269,6 +307,8 @@ impl McpError {
             McpError::ToolError(_) => "TOOL_ERROR",
             McpError::Timeout => "TIMEOUT",
             McpError::ServerError(_) => "SERVER_ERROR",
+            McpError::Unauthorized(_) => "UNAUTHORIZED",
+            McpError::SessionExpired => "SESSION_EXPIRED",
             McpError::Io(_) => "IO_ERROR",
             McpError::Json(_) => "JSON_ERROR",
         }
@@ -276,12 +316,16 @@ impl McpError {
 }
 
 /// MCP server configuration
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct McpServerConfig {
     /// Server name/identifier (defaults to empty, will be set from key in object format)
     #[serde(default)]
     pub name: String,
-    /// Command to execute (e.g., "node", "python")
+    /// Transport used to reach the server (defaults to stdio)
+    #[serde(default)]
+    pub transport: McpTransport,
+    /// Command to execute (e.g., "node", "python"); stdio only
+    #[serde(default)]
     pub command: String,
     /// Arguments to pass to command
     #[serde(default)]
@@ -295,4 +339,16 @@ pub struct McpServerConfig {
     /// MCP protocol version to use (defaults to latest supported)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub protocol_version: Option<String>,
+    /// Endpoint URL; streamable_http only
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    /// Extra HTTP headers sent with every request; streamable_http only
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub headers: Option<HashMap<String, String>>,
+    /// OAuth bearer token sent as `Authorization: Bearer <token>`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bearer_token: Option<String>,
+    /// Environment variable holding the bearer token (keeps secrets out of config files)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bearer_token_env: Option<String>,
 }
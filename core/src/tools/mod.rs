@@ -1,10 +1,18 @@
+pub mod caller;
+pub mod composite;
 pub mod error;
 pub mod mcp;
 pub mod native;
+pub mod policy;
 pub mod registry;
+pub mod schema;
 pub mod traits;
 
 // Re-export common types
+pub use caller::{caller_agent_id, with_caller};
+pub use composite::{CompositeTool, CompositeToolBuilder};
 pub use error::{ToolError, ToolResult};
+pub use policy::{CircuitBreakerConfig, CircuitState, ToolPolicy, ToolStats};
 pub use registry::ToolRegistry;
+pub use tokio_util::sync::CancellationToken;
 pub use traits::Tool;
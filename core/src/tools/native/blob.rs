@@ -0,0 +1,324 @@
+//! Blob store access for agents: `blob:put`, `blob:get`, `blob:delete`.
+//!
+//! Lets tool-using agents hand large content to each other by reference (see
+//! `crate::blob`): `blob:put` stores content owned by the calling agent and returns its
+//! `blob_ref`, which can travel in events or tool arguments; `blob:get` resolves a
+//! `blob_ref` if the calling agent may read it. Content is text, or base64 for binary
+//! data. Tool calls carry no namespace, so namespaced blobs are readable here by their
+//! owner only.
+
+use crate::blob::{BlobAccess, BlobStore};
+use crate::tools::{caller_agent_id, Tool, ToolError, ToolResult};
+use crate::LoomError;
+use async_trait::async_trait;
+use base64::Engine;
+use serde_json::{json, Value};
+use std::sync::Arc;
+use tracing::debug;
+
+fn store_error(e: LoomError) -> ToolError {
+    ToolError::ExecutionFailed(e.to_string())
+}
+
+fn blob_ref_argument(arguments: &Value) -> ToolResult<&str> {
+    arguments["blob_ref"]
+        .as_str()
+        .filter(|id| !id.is_empty())
+        .ok_or_else(|| ToolError::InvalidArguments("Missing 'blob_ref' argument".to_string()))
+}
+
+/// Bytes of the `content` argument, decoded per `encoding` (`text` or `base64`)
+pub(crate) fn content_argument(arguments: &Value) -> ToolResult<Vec<u8>> {
+    let content = arguments["content"]
+        .as_str()
+        .ok_or_else(|| ToolError::InvalidArguments("Missing 'content' argument".to_string()))?;
+    match arguments["encoding"].as_str().unwrap_or("text") {
+        "text" => Ok(content.as_bytes().to_vec()),
+        "base64" => base64::engine::general_purpose::STANDARD
+            .decode(content)
+            .map_err(|e| ToolError::InvalidArguments(format!("Invalid base64: {e}"))),
+        other => Err(ToolError::InvalidArguments(format!(
+            "Unknown encoding '{other}'"
+        ))),
+    }
+}
+
+/// `content` as the `encoding` argument asks (default: text when it is UTF-8, else
+/// base64), with the encoding used; `what` names the content in errors
+pub(crate) fn encode_content(
+    arguments: &Value,
+    content: Vec<u8>,
+    what: &str,
+) -> ToolResult<(&'static str, String)> {
+    match (arguments["encoding"].as_str(), String::from_utf8(content)) {
+        (None | Some("text"), Ok(text)) => Ok(("text", text)),
+        (Some("text"), Err(_)) => Err(ToolError::ExecutionFailed(format!(
+            "{what} is not UTF-8 text; read it as base64"
+        ))),
+        (None | Some("base64"), Err(e)) => Ok((
+            "base64",
+            base64::engine::general_purpose::STANDARD.encode(e.into_bytes()),
+        )),
+        (Some("base64"), Ok(text)) => Ok((
+            "base64",
+            base64::engine::general_purpose::STANDARD.encode(text),
+        )),
+        (Some(other), _) => Err(ToolError::InvalidArguments(format!(
+            "Unknown encoding '{other}'"
+        ))),
+    }
+}
+
+/// Agent ids listed in the `readers` argument
+pub(crate) fn readers_argument(arguments: &Value) -> Vec<String> {
+    arguments["readers"]
+        .as_array()
+        .map(|readers| {
+            readers
+                .iter()
+                .filter_map(|r| r.as_str().map(str::to_string))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+pub(crate) fn encoding_property() -> Value {
+    json!({
+        "type": "string",
+        "enum": ["text", "base64"],
+        "description": "How `content` is encoded (default: text)"
+    })
+}
+
+/// `blob:put`, `blob:get` and `blob:delete` over one store
+pub fn blob_tools(store: Arc<BlobStore>) -> Vec<Arc<dyn Tool>> {
+    vec![
+        Arc::new(BlobPutTool::new(Arc::clone(&store))),
+        Arc::new(BlobGetTool::new(Arc::clone(&store))),
+        Arc::new(BlobDeleteTool::new(store)),
+    ]
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// blob:put
+// ─────────────────────────────────────────────────────────────────────────────
+
+pub struct BlobPutTool {
+    store: Arc<BlobStore>,
+}
+
+impl BlobPutTool {
+    pub fn new(store: Arc<BlobStore>) -> Self {
+        Self { store }
+    }
+}
+
+#[async_trait]
+impl Tool for BlobPutTool {
+    fn name(&self) -> String {
+        "blob:put".to_string()
+    }
+
+    fn description(&self) -> String {
+        "Store large content in the blob store and get a reference to pass around instead"
+            .to_string()
+    }
+
+    fn parameters(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "content": { "type": "string", "description": "Content to store" },
+                "encoding": encoding_property(),
+                "content_type": {
+                    "type": "string",
+                    "description": "Media type of the content, e.g. image/png"
+                },
+                "readers": {
+                    "type": "array",
+                    "items": { "type": "string" },
+                    "description": "Agent ids allowed to read the blob (default: any agent)"
+                }
+            },
+            "required": ["content"]
+        })
+    }
+
+    fn output_schema(&self) -> Option<Value> {
+        Some(json!({
+            "type": "object",
+            "properties": {
+                "blob_ref": { "type": "string" },
+                "size": { "type": "integer" },
+                "sha256": { "type": "string" },
+                "content_type": { "type": "string" }
+            },
+            "required": ["blob_ref", "size", "sha256"]
+        }))
+    }
+
+    async fn call(&self, arguments: Value) -> ToolResult<Value> {
+        let content = content_argument(&arguments)?;
+        let readers = readers_argument(&arguments);
+        let access =
+            BlobAccess::owned_by(caller_agent_id().unwrap_or_default()).with_readers(readers);
+        let content_type = arguments["content_type"].as_str().unwrap_or_default();
+        let blob = self
+            .store
+            .put(content, content_type, access)
+            .await
+            .map_err(store_error)?;
+        debug!(target: "blob", blob_ref = %blob.id, size = blob.size, "blob:put");
+
+        Ok(json!({
+            "blob_ref": blob.id,
+            "size": blob.size,
+            "sha256": blob.sha256,
+            "content_type": blob.content_type,
+        }))
+    }
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// blob:get
+// ─────────────────────────────────────────────────────────────────────────────
+
+pub struct BlobGetTool {
+    store: Arc<BlobStore>,
+}
+
+impl BlobGetTool {
+    pub fn new(store: Arc<BlobStore>) -> Self {
+        Self { store }
+    }
+}
+
+#[async_trait]
+impl Tool for BlobGetTool {
+    fn name(&self) -> String {
+        "blob:get".to_string()
+    }
+
+    fn description(&self) -> String {
+        "Read the content behind a blob reference".to_string()
+    }
+
+    fn parameters(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "blob_ref": { "type": "string", "description": "Reference returned by blob:put" },
+                "encoding": {
+                    "type": "string",
+                    "enum": ["text", "base64"],
+                    "description": "How to return `content` (default: text when it is UTF-8, else base64)"
+                }
+            },
+            "required": ["blob_ref"]
+        })
+    }
+
+    fn output_schema(&self) -> Option<Value> {
+        Some(json!({
+            "type": "object",
+            "properties": {
+                "blob_ref": { "type": "string" },
+                "size": { "type": "integer" },
+                "content_type": { "type": "string" },
+                "encoding": { "type": "string" },
+                "content": { "type": "string" }
+            },
+            "required": ["blob_ref", "size", "encoding", "content"]
+        }))
+    }
+
+    async fn call(&self, arguments: Value) -> ToolResult<Value> {
+        let id = blob_ref_argument(&arguments)?;
+        let blob = self
+            .store
+            .blob_ref(id)
+            .await
+            .map_err(store_error)?
+            .ok_or_else(|| ToolError::ExecutionFailed(format!("Blob {id} not found")))?;
+        let (content, access) = self.store.load(&blob).await.map_err(store_error)?;
+        let caller = caller_agent_id();
+        if !access.allows(caller.as_deref(), None) {
+            return Err(ToolError::PermissionDenied(format!(
+                "{} may not read blob {id}",
+                caller.as_deref().unwrap_or("a call outside an agent")
+            )));
+        }
+        debug!(target: "blob", blob_ref = %id, size = blob.size, "blob:get");
+
+        let (encoding, content) = encode_content(&arguments, content, &format!("Blob {id}"))?;
+        Ok(json!({
+            "blob_ref": blob.id,
+            "size": blob.size,
+            "content_type": blob.content_type,
+            "encoding": encoding,
+            "content": content,
+        }))
+    }
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// blob:delete
+// ─────────────────────────────────────────────────────────────────────────────
+
+pub struct BlobDeleteTool {
+    store: Arc<BlobStore>,
+}
+
+impl BlobDeleteTool {
+    pub fn new(store: Arc<BlobStore>) -> Self {
+        Self { store }
+    }
+}
+
+#[async_trait]
+impl Tool for BlobDeleteTool {
+    fn name(&self) -> String {
+        "blob:delete".to_string()
+    }
+
+    fn description(&self) -> String {
+        "Delete a blob the calling agent stored".to_string()
+    }
+
+    fn parameters(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "blob_ref": { "type": "string", "description": "Reference returned by blob:put" }
+            },
+            "required": ["blob_ref"]
+        })
+    }
+
+    fn output_schema(&self) -> Option<Value> {
+        Some(json!({
+            "type": "object",
+            "properties": {
+                "blob_ref": { "type": "string" },
+                "deleted": { "type": "boolean" }
+            },
+            "required": ["blob_ref", "deleted"]
+        }))
+    }
+
+    async fn call(&self, arguments: Value) -> ToolResult<Value> {
+        let id = blob_ref_argument(&arguments)?;
+        let Some(access) = self.store.access_of(id).await.map_err(store_error)? else {
+            return Ok(json!({ "blob_ref": id, "deleted": false }));
+        };
+        let caller = caller_agent_id().unwrap_or_default();
+        if caller != access.owner {
+            return Err(ToolError::PermissionDenied(format!(
+                "Blob {id} belongs to another agent"
+            )));
+        }
+        let deleted = self.store.delete(id).await.map_err(store_error)?;
+        debug!(target: "blob", blob_ref = %id, deleted, "blob:delete");
+        Ok(json!({ "blob_ref": id, "deleted": deleted }))
+    }
+}
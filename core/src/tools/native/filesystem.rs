@@ -1,9 +1,34 @@
 use crate::tools::{Tool, ToolError, ToolResult};
 use async_trait::async_trait;
 use serde_json::{json, Value};
-use std::path::PathBuf;
+use std::path::{Component, Path, PathBuf};
 use tokio::fs;
 
+/// Resolve `path_str` against the workspace root, rejecting absolute paths and
+/// any `..` that would climb above the root. Resolution is lexical (no symlinks).
+pub(crate) fn resolve_in_workspace(root: &Path, path_str: &str) -> Option<PathBuf> {
+    let mut resolved = root.to_path_buf();
+    let mut depth = 0usize;
+    for component in Path::new(path_str).components() {
+        match component {
+            Component::Normal(part) => {
+                resolved.push(part);
+                depth += 1;
+            }
+            Component::CurDir => {}
+            Component::ParentDir => {
+                if depth == 0 {
+                    return None;
+                }
+                resolved.pop();
+                depth -= 1;
+            }
+            Component::RootDir | Component::Prefix(_) => return None,
+        }
+    }
+    Some(resolved)
+}
+
 // ─────────────────────────────────────────────────────────────────────────────
 // fs:read_file
 // ─────────────────────────────────────────────────────────────────────────────
@@ -41,19 +66,22 @@ impl Tool for ReadFileTool {
         })
     }
 
+    fn output_schema(&self) -> Option<Value> {
+        Some(json!({
+            "type": "object",
+            "properties": { "content": { "type": "string" } },
+            "required": ["content"]
+        }))
+    }
+
     async fn call(&self, arguments: Value) -> ToolResult<Value> {
         let path_str = arguments["path"]
             .as_str()
             .ok_or_else(|| ToolError::InvalidArguments("Missing 'path' argument".to_string()))?;
 
-        let path = self.workspace_root.join(path_str);
-
         // Security check: ensure path is within workspace
-        if !path.starts_with(&self.workspace_root) {
-            return Err(ToolError::PermissionDenied(
-                "Path traversal detected".to_string(),
-            ));
-        }
+        let path = resolve_in_workspace(&self.workspace_root, path_str)
+            .ok_or_else(|| ToolError::PermissionDenied("Path traversal detected".to_string()))?;
 
         if !path.exists() {
             return Err(ToolError::NotFound(format!("File not found: {}", path_str)));
@@ -111,6 +139,18 @@ impl Tool for WriteFileTool {
         })
     }
 
+    fn output_schema(&self) -> Option<Value> {
+        Some(json!({
+            "type": "object",
+            "properties": {
+                "success": { "type": "boolean" },
+                "path": { "type": "string" },
+                "bytes_written": { "type": "integer" }
+            },
+            "required": ["success", "path", "bytes_written"]
+        }))
+    }
+
     async fn call(&self, arguments: Value) -> ToolResult<Value> {
         let path_str = arguments["path"]
             .as_str()
@@ -119,14 +159,9 @@ impl Tool for WriteFileTool {
             .as_str()
             .ok_or_else(|| ToolError::InvalidArguments("Missing 'content' argument".to_string()))?;
 
-        let path = self.workspace_root.join(path_str);
-
-        // Security check
-        if !path.starts_with(&self.workspace_root) {
-            return Err(ToolError::PermissionDenied(
-                "Path traversal detected".to_string(),
-            ));
-        }
+        // Security check: ensure path is within workspace
+        let path = resolve_in_workspace(&self.workspace_root, path_str)
+            .ok_or_else(|| ToolError::PermissionDenied("Path traversal detected".to_string()))?;
 
         // Create parent directories
         if let Some(parent) = path.parent() {
@@ -184,16 +219,33 @@ impl Tool for ListDirTool {
         })
     }
 
+    fn output_schema(&self) -> Option<Value> {
+        Some(json!({
+            "type": "object",
+            "properties": {
+                "path": { "type": "string" },
+                "entries": {
+                    "type": "array",
+                    "items": {
+                        "type": "object",
+                        "properties": {
+                            "name": { "type": "string" },
+                            "is_dir": { "type": "boolean" },
+                            "size": { "type": "integer" }
+                        },
+                        "required": ["name", "is_dir", "size"]
+                    }
+                }
+            },
+            "required": ["path", "entries"]
+        }))
+    }
+
     async fn call(&self, arguments: Value) -> ToolResult<Value> {
         let path_str = arguments["path"].as_str().unwrap_or(".");
-        let path = self.workspace_root.join(path_str);
-
-        // Security check
-        if !path.starts_with(&self.workspace_root) {
-            return Err(ToolError::PermissionDenied(
-                "Path traversal detected".to_string(),
-            ));
-        }
+        // Security check: ensure path is within workspace
+        let path = resolve_in_workspace(&self.workspace_root, path_str)
+            .ok_or_else(|| ToolError::PermissionDenied("Path traversal detected".to_string()))?;
 
         if !path.exists() {
             return Err(ToolError::NotFound(format!(
@@ -268,19 +320,26 @@ impl Tool for DeleteFileTool {
         })
     }
 
+    fn output_schema(&self) -> Option<Value> {
+        Some(json!({
+            "type": "object",
+            "properties": {
+                "success": { "type": "boolean" },
+                "path": { "type": "string" },
+                "was_directory": { "type": "boolean" }
+            },
+            "required": ["success", "path", "was_directory"]
+        }))
+    }
+
     async fn call(&self, arguments: Value) -> ToolResult<Value> {
         let path_str = arguments["path"]
             .as_str()
             .ok_or_else(|| ToolError::InvalidArguments("Missing 'path' argument".to_string()))?;
 
-        let path = self.workspace_root.join(path_str);
-
-        // Security check
-        if !path.starts_with(&self.workspace_root) {
-            return Err(ToolError::PermissionDenied(
-                "Path traversal detected".to_string(),
-            ));
-        }
+        // Security check: ensure path is within workspace
+        let path = resolve_in_workspace(&self.workspace_root, path_str)
+            .ok_or_else(|| ToolError::PermissionDenied("Path traversal detected".to_string()))?;
 
         if !path.exists() {
             return Err(ToolError::NotFound(format!("Path not found: {}", path_str)));
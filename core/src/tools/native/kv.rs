@@ -0,0 +1,705 @@
+//! Key-value scratch space for agents: `kv:get`, `kv:set`, `kv:delete`, `kv:list`.
+//!
+//! Values are JSON, stored per namespace in a `KvStore` (RocksDB, or in memory when no
+//! path is configured) with optional TTLs and per-namespace quotas. A tool call uses the
+//! calling agent's own namespace (its agent id) unless it names another one with
+//! `namespace`; access to other namespaces is granted by `KvPolicy`. Calls made outside
+//! an agent have no namespace of their own and may only use namespaces shared with `*`.
+
+use crate::tools::{caller_agent_id, Tool, ToolError, ToolResult};
+use crate::{LoomError, Result};
+use async_trait::async_trait;
+use rocksdb::{ColumnFamilyDescriptor, Direction, IteratorMode, Options, DB};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::Duration;
+use tracing::{debug, info};
+
+const CF_KV: &str = "kv";
+
+/// Separates namespace and key in stored keys
+const SEPARATOR: char = '\u{0}';
+
+/// Limits applied to each namespace
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KvQuotas {
+    /// Live keys per namespace
+    pub max_keys: usize,
+    /// Serialized size of one value
+    pub max_value_bytes: usize,
+    /// Keys plus serialized values per namespace
+    pub max_namespace_bytes: usize,
+}
+
+impl Default for KvQuotas {
+    fn default() -> Self {
+        Self {
+            max_keys: 1_000,
+            max_value_bytes: 64 * 1024,
+            max_namespace_bytes: 1024 * 1024,
+        }
+    }
+}
+
+/// A stored value
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct KvEntry {
+    pub value: Value,
+    /// Milliseconds since Unix epoch after which the entry is gone
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires_at_ms: Option<i64>,
+    pub updated_at_ms: i64,
+}
+
+impl KvEntry {
+    fn is_expired(&self, now_ms: i64) -> bool {
+        self.expires_at_ms.is_some_and(|at| at <= now_ms)
+    }
+}
+
+enum Backend {
+    Memory(RwLock<BTreeMap<String, Vec<u8>>>),
+    RocksDb(DB),
+}
+
+/// Namespaced JSON key-value store with TTLs and quotas
+///
+/// Expired entries are removed when they are next read or listed, or by `purge_expired`.
+pub struct KvStore {
+    backend: Backend,
+    quotas: KvQuotas,
+    /// Serializes writes so quota checks and the write they admit are atomic
+    write_lock: Mutex<()>,
+}
+
+impl std::fmt::Debug for KvStore {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let backend = match self.backend {
+            Backend::Memory(_) => "memory".to_string(),
+            Backend::RocksDb(ref db) => db.path().display().to_string(),
+        };
+        f.debug_struct("KvStore")
+            .field("backend", &backend)
+            .field("quotas", &self.quotas)
+            .finish()
+    }
+}
+
+impl KvStore {
+    /// Store that lives as long as the process
+    pub fn in_memory() -> Self {
+        Self {
+            backend: Backend::Memory(RwLock::new(BTreeMap::new())),
+            quotas: KvQuotas::default(),
+            write_lock: Mutex::new(()),
+        }
+    }
+
+    /// RocksDB store at `path`, created if missing
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let mut opts = Options::default();
+        opts.create_if_missing(true);
+        opts.create_missing_column_families(true);
+
+        let cf_descriptors = vec![ColumnFamilyDescriptor::new(CF_KV, Options::default())];
+        let db = DB::open_cf_descriptors(&opts, path, cf_descriptors)
+            .map_err(|e| LoomError::StorageError(e.to_string()))?;
+
+        info!("KV store persistence initialized");
+        Ok(Self {
+            backend: Backend::RocksDb(db),
+            quotas: KvQuotas::default(),
+            write_lock: Mutex::new(()),
+        })
+    }
+
+    pub fn with_quotas(mut self, quotas: KvQuotas) -> Self {
+        self.quotas = quotas;
+        self
+    }
+
+    pub fn quotas(&self) -> KvQuotas {
+        self.quotas
+    }
+
+    pub fn is_persistent(&self) -> bool {
+        matches!(self.backend, Backend::RocksDb(_))
+    }
+
+    /// Live entry for `key`, if any
+    pub fn get(&self, namespace: &str, key: &str) -> Result<Option<KvEntry>> {
+        let stored_key = stored_key(namespace, key)?;
+        let Some(bytes) = self.raw_get(&stored_key)? else {
+            return Ok(None);
+        };
+        let entry: KvEntry = serde_json::from_slice(&bytes)?;
+        if entry.is_expired(now_ms()) {
+            let _guard = self.write_lock.lock().unwrap();
+            self.raw_delete(&stored_key)?;
+            return Ok(None);
+        }
+        Ok(Some(entry))
+    }
+
+    /// Store `value` under `key`, replacing any previous value
+    ///
+    /// Fails with `LoomError::QuotaExceeded` if the value or the namespace would exceed
+    /// the quotas.
+    pub fn set(
+        &self,
+        namespace: &str,
+        key: &str,
+        value: Value,
+        ttl: Option<Duration>,
+    ) -> Result<KvEntry> {
+        let stored_key = stored_key(namespace, key)?;
+        let now = now_ms();
+        let entry = KvEntry {
+            value,
+            expires_at_ms: ttl.map(|ttl| now + ttl.as_millis() as i64),
+            updated_at_ms: now,
+        };
+        let bytes = serde_json::to_vec(&entry)?;
+        if bytes.len() > self.quotas.max_value_bytes {
+            return Err(LoomError::QuotaExceeded(format!(
+                "value for '{key}' is {} bytes, limit is {}",
+                bytes.len(),
+                self.quotas.max_value_bytes
+            )));
+        }
+
+        let _guard = self.write_lock.lock().unwrap();
+        let (mut keys, mut used) = (1, key.len() + bytes.len());
+        for (other, other_bytes) in self.live_entries(namespace, now)? {
+            if other != key {
+                keys += 1;
+                used += other.len() + other_bytes.len();
+            }
+        }
+        if keys > self.quotas.max_keys {
+            return Err(LoomError::QuotaExceeded(format!(
+                "namespace '{namespace}' is limited to {} keys",
+                self.quotas.max_keys
+            )));
+        }
+        if used > self.quotas.max_namespace_bytes {
+            return Err(LoomError::QuotaExceeded(format!(
+                "namespace '{namespace}' would use {used} bytes, limit is {}",
+                self.quotas.max_namespace_bytes
+            )));
+        }
+        self.raw_put(&stored_key, bytes)?;
+        Ok(entry)
+    }
+
+    /// Remove `key`; returns whether a live entry was removed
+    pub fn delete(&self, namespace: &str, key: &str) -> Result<bool> {
+        let existed = self.get(namespace, key)?.is_some();
+        let _guard = self.write_lock.lock().unwrap();
+        self.raw_delete(&stored_key(namespace, key)?)?;
+        Ok(existed)
+    }
+
+    /// Live keys of `namespace` starting with `prefix`, sorted
+    pub fn list(&self, namespace: &str, prefix: &str) -> Result<Vec<String>> {
+        stored_key(namespace, prefix)?;
+        let _guard = self.write_lock.lock().unwrap();
+        Ok(self
+            .live_entries(namespace, now_ms())?
+            .into_iter()
+            .map(|(key, _)| key)
+            .filter(|key| key.starts_with(prefix))
+            .collect())
+    }
+
+    /// Remove every expired entry; returns how many were removed
+    pub fn purge_expired(&self) -> Result<usize> {
+        let _guard = self.write_lock.lock().unwrap();
+        let now = now_ms();
+        let mut purged = 0;
+        for (stored_key, bytes) in self.raw_scan("")? {
+            let expired = serde_json::from_slice::<KvEntry>(&bytes)
+                .map(|entry| entry.is_expired(now))
+                .unwrap_or(false);
+            if expired {
+                self.raw_delete(&stored_key)?;
+                purged += 1;
+            }
+        }
+        Ok(purged)
+    }
+
+    /// Live `(key, serialized entry)` pairs of a namespace; deletes expired entries.
+    /// Callers hold `write_lock`.
+    fn live_entries(&self, namespace: &str, now: i64) -> Result<Vec<(String, Vec<u8>)>> {
+        let prefix = format!("{namespace}{SEPARATOR}");
+        let mut live = Vec::new();
+        for (stored_key, bytes) in self.raw_scan(&prefix)? {
+            let expired = serde_json::from_slice::<KvEntry>(&bytes)
+                .map(|entry| entry.is_expired(now))
+                .unwrap_or(false);
+            if expired {
+                self.raw_delete(&stored_key)?;
+            } else {
+                live.push((stored_key[prefix.len()..].to_string(), bytes));
+            }
+        }
+        Ok(live)
+    }
+
+    fn raw_get(&self, stored_key: &str) -> Result<Option<Vec<u8>>> {
+        match self.backend {
+            Backend::Memory(ref map) => Ok(map.read().unwrap().get(stored_key).cloned()),
+            Backend::RocksDb(ref db) => db
+                .get_cf(cf(db)?, stored_key)
+                .map_err(|e| LoomError::StorageError(e.to_string())),
+        }
+    }
+
+    fn raw_put(&self, stored_key: &str, bytes: Vec<u8>) -> Result<()> {
+        match self.backend {
+            Backend::Memory(ref map) => {
+                map.write().unwrap().insert(stored_key.to_string(), bytes);
+                Ok(())
+            }
+            Backend::RocksDb(ref db) => db
+                .put_cf(cf(db)?, stored_key, bytes)
+                .map_err(|e| LoomError::StorageError(e.to_string())),
+        }
+    }
+
+    fn raw_delete(&self, stored_key: &str) -> Result<()> {
+        match self.backend {
+            Backend::Memory(ref map) => {
+                map.write().unwrap().remove(stored_key);
+                Ok(())
+            }
+            Backend::RocksDb(ref db) => db
+                .delete_cf(cf(db)?, stored_key)
+                .map_err(|e| LoomError::StorageError(e.to_string())),
+        }
+    }
+
+    /// Stored pairs whose key starts with `prefix`, in key order
+    fn raw_scan(&self, prefix: &str) -> Result<Vec<(String, Vec<u8>)>> {
+        match self.backend {
+            Backend::Memory(ref map) => Ok(map
+                .read()
+                .unwrap()
+                .range(prefix.to_string()..)
+                .take_while(|(key, _)| key.starts_with(prefix))
+                .map(|(key, bytes)| (key.clone(), bytes.clone()))
+                .collect()),
+            Backend::RocksDb(ref db) => {
+                let mode = IteratorMode::From(prefix.as_bytes(), Direction::Forward);
+                let mut pairs = Vec::new();
+                for item in db.iterator_cf(cf(db)?, mode) {
+                    let (key, bytes) = item.map_err(|e| LoomError::StorageError(e.to_string()))?;
+                    if !key.starts_with(prefix.as_bytes()) {
+                        break;
+                    }
+                    pairs.push((String::from_utf8_lossy(&key).into_owned(), bytes.to_vec()));
+                }
+                Ok(pairs)
+            }
+        }
+    }
+}
+
+fn cf(db: &DB) -> Result<&rocksdb::ColumnFamily> {
+    db.cf_handle(CF_KV)
+        .ok_or_else(|| LoomError::StorageError(format!("Missing CF: {}", CF_KV)))
+}
+
+fn stored_key(namespace: &str, key: &str) -> Result<String> {
+    if namespace.is_empty() || namespace.contains(SEPARATOR) || key.contains(SEPARATOR) {
+        return Err(LoomError::StorageError(format!(
+            "invalid KV namespace '{namespace}' or key"
+        )));
+    }
+    Ok(format!("{namespace}{SEPARATOR}{key}"))
+}
+
+fn now_ms() -> i64 {
+    chrono::Utc::now().timestamp_millis()
+}
+
+/// Which agents may use a namespace other than their own
+#[derive(Debug, Clone, Default)]
+pub struct KvPolicy {
+    shared: HashMap<String, Vec<String>>,
+}
+
+impl KvPolicy {
+    /// Policy without shared namespaces: every agent is confined to its own
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Let `agents` use `namespace`; `*` admits every caller, including code outside an
+    /// agent
+    pub fn with_shared<I, S>(mut self, namespace: impl Into<String>, agents: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.shared
+            .entry(namespace.into())
+            .or_default()
+            .extend(agents.into_iter().map(Into::into));
+        self
+    }
+
+    /// Parse `LOOM_KV_SHARED`: `;`-separated `namespace=agent,agent` entries
+    pub fn from_env() -> Self {
+        let mut policy = Self::new();
+        let Ok(spec) = std::env::var("LOOM_KV_SHARED") else {
+            return policy;
+        };
+        for entry in spec.split(';').filter(|e| !e.trim().is_empty()) {
+            if let Some((namespace, agents)) = entry.split_once('=') {
+                policy = policy.with_shared(
+                    namespace.trim(),
+                    agents.split(',').map(str::trim).filter(|a| !a.is_empty()),
+                );
+            }
+        }
+        policy
+    }
+
+    /// Whether `caller` (`None` outside an agent) may use `namespace`
+    pub fn allows(&self, namespace: &str, caller: Option<&str>) -> bool {
+        if caller == Some(namespace) {
+            return true;
+        }
+        self.shared.get(namespace).is_some_and(|agents| {
+            agents
+                .iter()
+                .any(|agent| agent == "*" || Some(agent.as_str()) == caller)
+        })
+    }
+
+    /// Namespace a call uses: `requested`, or the calling agent's own
+    fn resolve(&self, requested: Option<&str>) -> ToolResult<String> {
+        let caller = caller_agent_id();
+        let namespace = match (requested, caller.as_deref()) {
+            (Some(namespace), _) => namespace,
+            (None, Some(agent_id)) => agent_id,
+            (None, None) => {
+                return Err(ToolError::InvalidArguments(
+                    "'namespace' is required outside an agent".to_string(),
+                ))
+            }
+        };
+        if self.allows(namespace, caller.as_deref()) {
+            Ok(namespace.to_string())
+        } else {
+            Err(ToolError::PermissionDenied(format!(
+                "namespace '{namespace}' is not shared with {}",
+                caller.as_deref().unwrap_or("callers outside an agent")
+            )))
+        }
+    }
+}
+
+fn store_error(e: LoomError) -> ToolError {
+    ToolError::ExecutionFailed(e.to_string())
+}
+
+fn key_argument(arguments: &Value) -> ToolResult<&str> {
+    arguments["key"]
+        .as_str()
+        .filter(|key| !key.is_empty())
+        .ok_or_else(|| ToolError::InvalidArguments("Missing 'key' argument".to_string()))
+}
+
+fn namespace_property() -> Value {
+    json!({
+        "type": "string",
+        "description": "Namespace to use (default: the calling agent's own)"
+    })
+}
+
+/// `kv:get`, `kv:set`, `kv:delete` and `kv:list` over one store and policy
+pub fn kv_tools(store: Arc<KvStore>, policy: Arc<KvPolicy>) -> Vec<Arc<dyn Tool>> {
+    vec![
+        Arc::new(KvGetTool::new(Arc::clone(&store), Arc::clone(&policy))),
+        Arc::new(KvSetTool::new(Arc::clone(&store), Arc::clone(&policy))),
+        Arc::new(KvDeleteTool::new(Arc::clone(&store), Arc::clone(&policy))),
+        Arc::new(KvListTool::new(store, policy)),
+    ]
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// kv:get
+// ─────────────────────────────────────────────────────────────────────────────
+
+pub struct KvGetTool {
+    store: Arc<KvStore>,
+    policy: Arc<KvPolicy>,
+}
+
+impl KvGetTool {
+    pub fn new(store: Arc<KvStore>, policy: Arc<KvPolicy>) -> Self {
+        Self { store, policy }
+    }
+}
+
+#[async_trait]
+impl Tool for KvGetTool {
+    fn name(&self) -> String {
+        "kv:get".to_string()
+    }
+
+    fn description(&self) -> String {
+        "Read a value from the agent's persistent key-value scratch space".to_string()
+    }
+
+    fn parameters(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "key": { "type": "string", "description": "Key to read" },
+                "namespace": namespace_property()
+            },
+            "required": ["key"]
+        })
+    }
+
+    fn output_schema(&self) -> Option<Value> {
+        Some(json!({
+            "type": "object",
+            "properties": {
+                "namespace": { "type": "string" },
+                "key": { "type": "string" },
+                "found": { "type": "boolean" },
+                "value": {},
+                "expires_at_ms": { "type": ["integer", "null"] }
+            },
+            "required": ["namespace", "key", "found", "value"]
+        }))
+    }
+
+    async fn call(&self, arguments: Value) -> ToolResult<Value> {
+        let key = key_argument(&arguments)?;
+        let namespace = self.policy.resolve(arguments["namespace"].as_str())?;
+        let entry = self.store.get(&namespace, key).map_err(store_error)?;
+        debug!(target: "kv", namespace = %namespace, key = %key, found = entry.is_some(), "kv:get");
+
+        Ok(json!({
+            "namespace": namespace,
+            "key": key,
+            "found": entry.is_some(),
+            "value": entry.as_ref().map(|e| e.value.clone()).unwrap_or(Value::Null),
+            "expires_at_ms": entry.and_then(|e| e.expires_at_ms),
+        }))
+    }
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// kv:set
+// ─────────────────────────────────────────────────────────────────────────────
+
+pub struct KvSetTool {
+    store: Arc<KvStore>,
+    policy: Arc<KvPolicy>,
+}
+
+impl KvSetTool {
+    pub fn new(store: Arc<KvStore>, policy: Arc<KvPolicy>) -> Self {
+        Self { store, policy }
+    }
+}
+
+#[async_trait]
+impl Tool for KvSetTool {
+    fn name(&self) -> String {
+        "kv:set".to_string()
+    }
+
+    fn description(&self) -> String {
+        "Store a JSON value in the agent's persistent key-value scratch space, optionally expiring"
+            .to_string()
+    }
+
+    fn parameters(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "key": { "type": "string", "description": "Key to write" },
+                "value": { "description": "Any JSON value" },
+                "ttl_secs": {
+                    "type": "integer",
+                    "description": "Seconds until the value expires (default: never)"
+                },
+                "namespace": namespace_property()
+            },
+            "required": ["key", "value"]
+        })
+    }
+
+    fn output_schema(&self) -> Option<Value> {
+        Some(json!({
+            "type": "object",
+            "properties": {
+                "namespace": { "type": "string" },
+                "key": { "type": "string" },
+                "expires_at_ms": { "type": ["integer", "null"] }
+            },
+            "required": ["namespace", "key"]
+        }))
+    }
+
+    async fn call(&self, arguments: Value) -> ToolResult<Value> {
+        let key = key_argument(&arguments)?;
+        let value = arguments
+            .get("value")
+            .cloned()
+            .ok_or_else(|| ToolError::InvalidArguments("Missing 'value' argument".to_string()))?;
+        let ttl = match arguments.get("ttl_secs") {
+            None | Some(Value::Null) => None,
+            Some(ttl) => Some(Duration::from_secs(ttl.as_u64().ok_or_else(|| {
+                ToolError::InvalidArguments("'ttl_secs' must be a non-negative integer".to_string())
+            })?)),
+        };
+        let namespace = self.policy.resolve(arguments["namespace"].as_str())?;
+        let entry = self
+            .store
+            .set(&namespace, key, value, ttl)
+            .map_err(store_error)?;
+        debug!(target: "kv", namespace = %namespace, key = %key, "kv:set");
+
+        Ok(json!({
+            "namespace": namespace,
+            "key": key,
+            "expires_at_ms": entry.expires_at_ms,
+        }))
+    }
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// kv:delete
+// ─────────────────────────────────────────────────────────────────────────────
+
+pub struct KvDeleteTool {
+    store: Arc<KvStore>,
+    policy: Arc<KvPolicy>,
+}
+
+impl KvDeleteTool {
+    pub fn new(store: Arc<KvStore>, policy: Arc<KvPolicy>) -> Self {
+        Self { store, policy }
+    }
+}
+
+#[async_trait]
+impl Tool for KvDeleteTool {
+    fn name(&self) -> String {
+        "kv:delete".to_string()
+    }
+
+    fn description(&self) -> String {
+        "Delete a key from the agent's persistent key-value scratch space".to_string()
+    }
+
+    fn parameters(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "key": { "type": "string", "description": "Key to delete" },
+                "namespace": namespace_property()
+            },
+            "required": ["key"]
+        })
+    }
+
+    fn output_schema(&self) -> Option<Value> {
+        Some(json!({
+            "type": "object",
+            "properties": {
+                "namespace": { "type": "string" },
+                "key": { "type": "string" },
+                "deleted": { "type": "boolean" }
+            },
+            "required": ["namespace", "key", "deleted"]
+        }))
+    }
+
+    async fn call(&self, arguments: Value) -> ToolResult<Value> {
+        let key = key_argument(&arguments)?;
+        let namespace = self.policy.resolve(arguments["namespace"].as_str())?;
+        let deleted = self.store.delete(&namespace, key).map_err(store_error)?;
+
+        Ok(json!({
+            "namespace": namespace,
+            "key": key,
+            "deleted": deleted,
+        }))
+    }
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// kv:list
+// ─────────────────────────────────────────────────────────────────────────────
+
+pub struct KvListTool {
+    store: Arc<KvStore>,
+    policy: Arc<KvPolicy>,
+}
+
+impl KvListTool {
+    pub fn new(store: Arc<KvStore>, policy: Arc<KvPolicy>) -> Self {
+        Self { store, policy }
+    }
+}
+
+#[async_trait]
+impl Tool for KvListTool {
+    fn name(&self) -> String {
+        "kv:list".to_string()
+    }
+
+    fn description(&self) -> String {
+        "List keys in the agent's persistent key-value scratch space".to_string()
+    }
+
+    fn parameters(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "prefix": {
+                    "type": "string",
+                    "description": "Only keys starting with this prefix (default: all)"
+                },
+                "namespace": namespace_property()
+            }
+        })
+    }
+
+    fn output_schema(&self) -> Option<Value> {
+        Some(json!({
+            "type": "object",
+            "properties": {
+                "namespace": { "type": "string" },
+                "keys": { "type": "array", "items": { "type": "string" } },
+                "count": { "type": "integer" }
+            },
+            "required": ["namespace", "keys", "count"]
+        }))
+    }
+
+    async fn call(&self, arguments: Value) -> ToolResult<Value> {
+        let prefix = arguments["prefix"].as_str().unwrap_or("");
+        let namespace = self.policy.resolve(arguments["namespace"].as_str())?;
+        let keys = self.store.list(&namespace, prefix).map_err(store_error)?;
+
+        Ok(json!({
+            "namespace": namespace,
+            "count": keys.len(),
+            "keys": keys,
+        }))
+    }
+}
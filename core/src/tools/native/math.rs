@@ -0,0 +1,830 @@
+//! `math:eval` — deterministic calculator with unit and currency conversion
+//!
+//! Expressions are parsed and evaluated in-process (no shell, no `eval`): numbers,
+//! `+ - * / % ^`, parentheses, factorial `!`, constants (`pi`, `e`, `tau`) and common
+//! functions. A trailing `to <unit>` (or `in`/`as`) converts between units of the same
+//! dimension, e.g. `5 km to mi` or `(20 + 5) C in F`. Currency codes convert with
+//! exchange rates fetched from `MathConfig::rates_endpoint` and cached for `rates_ttl_secs`.
+
+use crate::tools::{Tool, ToolError, ToolResult};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
+use tracing::debug;
+
+/// Longest expression accepted
+const MAX_EXPRESSION_LEN: usize = 1024;
+/// Deepest nesting of parentheses, unary operators and function calls
+const MAX_DEPTH: usize = 64;
+
+/// Configuration for the math tool
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MathConfig {
+    /// Exchange rate API returning `{"base"|"base_code": "USD", "rates": {"EUR": 0.92, ..}}`;
+    /// None disables currency conversion
+    pub rates_endpoint: Option<String>,
+    /// How long fetched rates are reused
+    pub rates_ttl_secs: u64,
+    /// Timeout for rate requests in milliseconds
+    pub timeout_ms: u64,
+    /// User agent string
+    pub user_agent: String,
+}
+
+impl Default for MathConfig {
+    fn default() -> Self {
+        Self {
+            rates_endpoint: None,
+            rates_ttl_secs: 3600,
+            timeout_ms: 10_000,
+            user_agent: "loom-agent/0.1".to_string(),
+        }
+    }
+}
+
+impl MathConfig {
+    /// Reads `LOOM_CURRENCY_RATES_URL` and `LOOM_CURRENCY_RATES_TTL_SECS`
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        Self {
+            rates_endpoint: std::env::var("LOOM_CURRENCY_RATES_URL")
+                .ok()
+                .filter(|url| !url.trim().is_empty()),
+            rates_ttl_secs: std::env::var("LOOM_CURRENCY_RATES_TTL_SECS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(defaults.rates_ttl_secs),
+            ..defaults
+        }
+    }
+}
+
+// ─── Expression evaluation ──────────────────────────────────────────────────
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Num(f64),
+    Ident(String),
+    Op(char),
+    LParen,
+    RParen,
+    Comma,
+}
+
+fn tokenize(input: &str) -> ToolResult<Vec<Token>> {
+    let chars: Vec<char> = input.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        if c.is_whitespace() {
+            i += 1;
+        } else if c.is_ascii_digit()
+            || (c == '.' && chars.get(i + 1).is_some_and(char::is_ascii_digit))
+        {
+            let start = i;
+            while i < chars.len()
+                && (chars[i].is_ascii_digit() || chars[i] == '.' || chars[i] == '_')
+            {
+                i += 1;
+            }
+            // An exponent needs digits; a bare `e` is left for the constant
+            if i < chars.len() && (chars[i] == 'e' || chars[i] == 'E') {
+                let mut j = i + 1;
+                if j < chars.len() && (chars[j] == '+' || chars[j] == '-') {
+                    j += 1;
+                }
+                if j < chars.len() && chars[j].is_ascii_digit() {
+                    i = j;
+                    while i < chars.len() && chars[i].is_ascii_digit() {
+                        i += 1;
+                    }
+                }
+            }
+            let text: String = chars[start..i].iter().filter(|c| **c != '_').collect();
+            let value = text
+                .parse()
+                .map_err(|_| invalid(format!("Invalid number '{}'", text)))?;
+            tokens.push(Token::Num(value));
+        } else if c.is_alphabetic() || c == '_' {
+            let start = i;
+            while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+                i += 1;
+            }
+            tokens.push(Token::Ident(chars[start..i].iter().collect()));
+        } else {
+            tokens.push(match c {
+                '(' => Token::LParen,
+                ')' => Token::RParen,
+                ',' => Token::Comma,
+                '*' if chars.get(i + 1) == Some(&'*') => {
+                    i += 1;
+                    Token::Op('^')
+                }
+                '+' | '-' | '*' | '/' | '%' | '^' | '!' => Token::Op(c),
+                '×' => Token::Op('*'),
+                '÷' => Token::Op('/'),
+                _ => return Err(invalid(format!("Unexpected character '{}'", c))),
+            });
+            i += 1;
+        }
+    }
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+    depth: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn advance(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn eat(&mut self, token: &Token) -> bool {
+        if self.peek() == Some(token) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn nested<T>(&mut self, f: impl FnOnce(&mut Self) -> ToolResult<T>) -> ToolResult<T> {
+        self.depth += 1;
+        if self.depth > MAX_DEPTH {
+            return Err(invalid("Expression is nested too deeply".to_string()));
+        }
+        let result = f(self);
+        self.depth -= 1;
+        result
+    }
+
+    // expr := term (('+' | '-') term)*
+    fn expr(&mut self) -> ToolResult<f64> {
+        let mut value = self.term()?;
+        loop {
+            if self.eat(&Token::Op('+')) {
+                value += self.term()?;
+            } else if self.eat(&Token::Op('-')) {
+                value -= self.term()?;
+            } else {
+                return Ok(value);
+            }
+        }
+    }
+
+    // term := unary (('*' | '/' | '%') unary)*
+    fn term(&mut self) -> ToolResult<f64> {
+        let mut value = self.unary()?;
+        loop {
+            if self.eat(&Token::Op('*')) {
+                value *= self.unary()?;
+            } else if self.eat(&Token::Op('/')) {
+                let divisor = self.unary()?;
+                if divisor == 0.0 {
+                    return Err(invalid("Division by zero".to_string()));
+                }
+                value /= divisor;
+            } else if self.eat(&Token::Op('%')) {
+                let divisor = self.unary()?;
+                if divisor == 0.0 {
+                    return Err(invalid("Modulo by zero".to_string()));
+                }
+                value %= divisor;
+            } else {
+                return Ok(value);
+            }
+        }
+    }
+
+    // unary := ('-' | '+') unary | power
+    fn unary(&mut self) -> ToolResult<f64> {
+        if self.eat(&Token::Op('-')) {
+            return self.nested(|p| p.unary()).map(|v| -v);
+        }
+        if self.eat(&Token::Op('+')) {
+            return self.nested(|p| p.unary());
+        }
+        self.power()
+    }
+
+    // power := postfix ('^' unary)?   (right-associative, binds tighter than unary minus)
+    fn power(&mut self) -> ToolResult<f64> {
+        let base = self.postfix()?;
+        if self.eat(&Token::Op('^')) {
+            let exponent = self.nested(|p| p.unary())?;
+            return Ok(base.powf(exponent));
+        }
+        Ok(base)
+    }
+
+    // postfix := primary '!'*
+    fn postfix(&mut self) -> ToolResult<f64> {
+        let mut value = self.primary()?;
+        while self.eat(&Token::Op('!')) {
+            value = factorial(value)?;
+        }
+        Ok(value)
+    }
+
+    // primary := number | constant | function '(' args ')' | '(' expr ')'
+    fn primary(&mut self) -> ToolResult<f64> {
+        match self.advance() {
+            Some(Token::Num(value)) => Ok(value),
+            Some(Token::LParen) => {
+                let value = self.nested(|p| p.expr())?;
+                if !self.eat(&Token::RParen) {
+                    return Err(invalid("Missing ')'".to_string()));
+                }
+                Ok(value)
+            }
+            Some(Token::Ident(name)) => {
+                let name = name.to_ascii_lowercase();
+                if !self.eat(&Token::LParen) {
+                    return constant(&name)
+                        .ok_or_else(|| invalid(format!("Unknown constant '{}'", name)));
+                }
+                let mut args = Vec::new();
+                if !self.eat(&Token::RParen) {
+                    loop {
+                        args.push(self.nested(|p| p.expr())?);
+                        if self.eat(&Token::RParen) {
+                            break;
+                        }
+                        if !self.eat(&Token::Comma) {
+                            return Err(invalid(format!("Expected ',' or ')' in {}()", name)));
+                        }
+                    }
+                }
+                call_function(&name, &args)
+            }
+            Some(token) => Err(invalid(format!("Unexpected {:?}", token))),
+            None => Err(invalid("Unexpected end of expression".to_string())),
+        }
+    }
+}
+
+fn invalid(message: String) -> ToolError {
+    ToolError::InvalidArguments(message)
+}
+
+fn constant(name: &str) -> Option<f64> {
+    match name {
+        "pi" | "π" => Some(std::f64::consts::PI),
+        "e" => Some(std::f64::consts::E),
+        "tau" => Some(std::f64::consts::TAU),
+        "phi" => Some(1.618_033_988_749_895),
+        _ => None,
+    }
+}
+
+fn factorial(n: f64) -> ToolResult<f64> {
+    if n < 0.0 || n.fract() != 0.0 || n > 170.0 {
+        return Err(invalid(format!(
+            "Factorial needs an integer from 0 to 170, got {}",
+            n
+        )));
+    }
+    Ok((1..=n as u64).fold(1.0, |acc, k| acc * k as f64))
+}
+
+fn call_function(name: &str, args: &[f64]) -> ToolResult<f64> {
+    let one = |f: fn(f64) -> f64| match args {
+        [x] => Ok(f(*x)),
+        _ => Err(invalid(format!("{}() takes 1 argument", name))),
+    };
+    let two = |f: fn(f64, f64) -> f64| match args {
+        [x, y] => Ok(f(*x, *y)),
+        _ => Err(invalid(format!("{}() takes 2 arguments", name))),
+    };
+    match name {
+        "sqrt" => one(f64::sqrt),
+        "cbrt" => one(f64::cbrt),
+        "abs" => one(f64::abs),
+        "exp" => one(f64::exp),
+        "ln" => one(f64::ln),
+        "log10" => one(f64::log10),
+        "log2" => one(f64::log2),
+        // log(x) is base 10; log(x, base) any base
+        "log" => match args {
+            [x] => Ok(x.log10()),
+            [x, base] => Ok(x.log(*base)),
+            _ => Err(invalid("log() takes 1 or 2 arguments".to_string())),
+        },
+        "sin" => one(f64::sin),
+        "cos" => one(f64::cos),
+        "tan" => one(f64::tan),
+        "asin" => one(f64::asin),
+        "acos" => one(f64::acos),
+        "atan" => one(f64::atan),
+        "sinh" => one(f64::sinh),
+        "cosh" => one(f64::cosh),
+        "tanh" => one(f64::tanh),
+        "floor" => one(f64::floor),
+        "ceil" => one(f64::ceil),
+        "round" => match args {
+            [x] => Ok(x.round()),
+            [x, digits] => {
+                let scale = 10f64.powi(*digits as i32);
+                Ok((x * scale).round() / scale)
+            }
+            _ => Err(invalid("round() takes 1 or 2 arguments".to_string())),
+        },
+        "trunc" => one(f64::trunc),
+        "sign" => one(f64::signum),
+        "pow" => two(f64::powf),
+        "atan2" => two(f64::atan2),
+        "hypot" => two(f64::hypot),
+        "min" | "max" if args.is_empty() => {
+            Err(invalid(format!("{}() needs at least 1 argument", name)))
+        }
+        "min" => Ok(args.iter().copied().fold(f64::INFINITY, f64::min)),
+        "max" => Ok(args.iter().copied().fold(f64::NEG_INFINITY, f64::max)),
+        "sum" => Ok(args.iter().sum()),
+        "avg" | "mean" if args.is_empty() => {
+            Err(invalid(format!("{}() needs at least 1 argument", name)))
+        }
+        "avg" | "mean" => Ok(args.iter().sum::<f64>() / args.len() as f64),
+        _ => Err(invalid(format!("Unknown function '{}'", name))),
+    }
+}
+
+/// Evaluate an arithmetic expression
+pub fn evaluate(expression: &str) -> ToolResult<f64> {
+    if expression.len() > MAX_EXPRESSION_LEN {
+        return Err(invalid(format!(
+            "Expression longer than {} characters",
+            MAX_EXPRESSION_LEN
+        )));
+    }
+    let mut parser = Parser {
+        tokens: tokenize(expression)?,
+        pos: 0,
+        depth: 0,
+    };
+    if parser.tokens.is_empty() {
+        return Err(invalid("Empty expression".to_string()));
+    }
+    let value = parser.expr()?;
+    if let Some(token) = parser.peek() {
+        return Err(invalid(format!("Unexpected {:?}", token)));
+    }
+    if !value.is_finite() {
+        return Err(invalid(format!(
+            "Result is not a finite number ({})",
+            value
+        )));
+    }
+    Ok(value)
+}
+
+// ─── Units ──────────────────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Dimension {
+    Length,
+    Mass,
+    Time,
+    Volume,
+    Area,
+    Speed,
+    Data,
+    Energy,
+    Pressure,
+    Temperature,
+    Angle,
+}
+
+impl Dimension {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Dimension::Length => "length",
+            Dimension::Mass => "mass",
+            Dimension::Time => "time",
+            Dimension::Volume => "volume",
+            Dimension::Area => "area",
+            Dimension::Speed => "speed",
+            Dimension::Data => "data",
+            Dimension::Energy => "energy",
+            Dimension::Pressure => "pressure",
+            Dimension::Temperature => "temperature",
+            Dimension::Angle => "angle",
+        }
+    }
+}
+
+/// A unit: `base = (value + offset) * factor` in the dimension's SI base unit
+struct Unit {
+    names: &'static [&'static str],
+    dimension: Dimension,
+    factor: f64,
+    offset: f64,
+}
+
+const fn unit(names: &'static [&'static str], dimension: Dimension, factor: f64) -> Unit {
+    Unit {
+        names,
+        dimension,
+        factor,
+        offset: 0.0,
+    }
+}
+
+#[rustfmt::skip]
+static UNITS: &[Unit] = &[
+    // Length (metre)
+    unit(&["m", "meter", "meters", "metre", "metres"], Dimension::Length, 1.0),
+    unit(&["km", "kilometer", "kilometers", "kilometre", "kilometres"], Dimension::Length, 1e3),
+    unit(&["cm", "centimeter", "centimeters"], Dimension::Length, 1e-2),
+    unit(&["mm", "millimeter", "millimeters"], Dimension::Length, 1e-3),
+    unit(&["um", "µm", "micrometer", "micrometers"], Dimension::Length, 1e-6),
+    unit(&["nm", "nanometer", "nanometers"], Dimension::Length, 1e-9),
+    unit(&["mi", "mile", "miles"], Dimension::Length, 1609.344),
+    unit(&["yd", "yard", "yards"], Dimension::Length, 0.9144),
+    unit(&["ft", "foot", "feet"], Dimension::Length, 0.3048),
+    unit(&["in", "inch", "inches"], Dimension::Length, 0.0254),
+    unit(&["nmi", "nautical_mile", "nautical_miles"], Dimension::Length, 1852.0),
+    // Mass (kilogram)
+    unit(&["kg", "kilogram", "kilograms"], Dimension::Mass, 1.0),
+    unit(&["g", "gram", "grams"], Dimension::Mass, 1e-3),
+    unit(&["mg", "milligram", "milligrams"], Dimension::Mass, 1e-6),
+    unit(&["t", "tonne", "tonnes"], Dimension::Mass, 1e3),
+    unit(&["lb", "lbs", "pound", "pounds"], Dimension::Mass, 0.453_592_37),
+    unit(&["oz", "ounce", "ounces"], Dimension::Mass, 0.028_349_523_125),
+    unit(&["st", "stone", "stones"], Dimension::Mass, 6.350_293_18),
+    // Time (second)
+    unit(&["s", "sec", "secs", "second", "seconds"], Dimension::Time, 1.0),
+    unit(&["ms", "millisecond", "milliseconds"], Dimension::Time, 1e-3),
+    unit(&["us", "µs", "microsecond", "microseconds"], Dimension::Time, 1e-6),
+    unit(&["min", "mins", "minute", "minutes"], Dimension::Time, 60.0),
+    unit(&["h", "hr", "hrs", "hour", "hours"], Dimension::Time, 3600.0),
+    unit(&["d", "day", "days"], Dimension::Time, 86_400.0),
+    unit(&["wk", "week", "weeks"], Dimension::Time, 604_800.0),
+    unit(&["yr", "year", "years"], Dimension::Time, 31_557_600.0),
+    // Volume (cubic metre)
+    unit(&["m3"], Dimension::Volume, 1.0),
+    unit(&["l", "L", "liter", "liters", "litre", "litres"], Dimension::Volume, 1e-3),
+    unit(&["ml", "mL", "milliliter", "milliliters", "millilitre", "millilitres"], Dimension::Volume, 1e-6),
+    unit(&["cm3", "cc"], Dimension::Volume, 1e-6),
+    unit(&["gal", "gallon", "gallons"], Dimension::Volume, 0.003_785_411_784),
+    unit(&["qt", "quart", "quarts"], Dimension::Volume, 0.000_946_352_946),
+    unit(&["pt", "pint", "pints"], Dimension::Volume, 0.000_473_176_473),
+    unit(&["cup", "cups"], Dimension::Volume, 0.000_236_588_236_5),
+    unit(&["floz", "fl_oz"], Dimension::Volume, 2.957_352_956_25e-5),
+    unit(&["tbsp", "tablespoon", "tablespoons"], Dimension::Volume, 1.478_676_478_125e-5),
+    unit(&["tsp", "teaspoon", "teaspoons"], Dimension::Volume, 4.928_921_593_75e-6),
+    // Area (square metre)
+    unit(&["m2", "sqm"], Dimension::Area, 1.0),
+    unit(&["km2"], Dimension::Area, 1e6),
+    unit(&["cm2"], Dimension::Area, 1e-4),
+    unit(&["ha", "hectare", "hectares"], Dimension::Area, 1e4),
+    unit(&["acre", "acres"], Dimension::Area, 4_046.856_422_4),
+    unit(&["ft2", "sqft"], Dimension::Area, 0.092_903_04),
+    unit(&["mi2", "sqmi"], Dimension::Area, 2_589_988.110_336),
+    // Speed (metre per second)
+    unit(&["m/s", "mps"], Dimension::Speed, 1.0),
+    unit(&["km/h", "kmh", "kph"], Dimension::Speed, 1.0 / 3.6),
+    unit(&["mph"], Dimension::Speed, 0.447_04),
+    unit(&["kn", "knot", "knots"], Dimension::Speed, 1852.0 / 3600.0),
+    unit(&["ft/s", "fps"], Dimension::Speed, 0.3048),
+    // Data (byte)
+    unit(&["B", "byte", "bytes"], Dimension::Data, 1.0),
+    unit(&["bit", "bits"], Dimension::Data, 0.125),
+    unit(&["KB", "kB"], Dimension::Data, 1e3),
+    unit(&["MB"], Dimension::Data, 1e6),
+    unit(&["GB"], Dimension::Data, 1e9),
+    unit(&["TB"], Dimension::Data, 1e12),
+    unit(&["KiB"], Dimension::Data, 1024.0),
+    unit(&["MiB"], Dimension::Data, 1_048_576.0),
+    unit(&["GiB"], Dimension::Data, 1_073_741_824.0),
+    unit(&["TiB"], Dimension::Data, 1_099_511_627_776.0),
+    // Energy (joule)
+    unit(&["J", "joule", "joules"], Dimension::Energy, 1.0),
+    unit(&["kJ"], Dimension::Energy, 1e3),
+    unit(&["cal", "calorie", "calories"], Dimension::Energy, 4.184),
+    unit(&["kcal"], Dimension::Energy, 4184.0),
+    unit(&["Wh"], Dimension::Energy, 3600.0),
+    unit(&["kWh"], Dimension::Energy, 3.6e6),
+    unit(&["eV"], Dimension::Energy, 1.602_176_634e-19),
+    unit(&["BTU", "btu"], Dimension::Energy, 1_055.055_852_62),
+    // Pressure (pascal)
+    unit(&["Pa", "pascal"], Dimension::Pressure, 1.0),
+    unit(&["kPa"], Dimension::Pressure, 1e3),
+    unit(&["bar"], Dimension::Pressure, 1e5),
+    unit(&["atm"], Dimension::Pressure, 101_325.0),
+    unit(&["psi"], Dimension::Pressure, 6_894.757_293_168),
+    unit(&["mmHg"], Dimension::Pressure, 133.322_387_415),
+    // Temperature (kelvin)
+    unit(&["K", "kelvin"], Dimension::Temperature, 1.0),
+    Unit { names: &["C", "°C", "celsius"], dimension: Dimension::Temperature, factor: 1.0, offset: 273.15 },
+    Unit { names: &["F", "°F", "fahrenheit"], dimension: Dimension::Temperature, factor: 5.0 / 9.0, offset: 459.67 },
+    // Angle (radian)
+    unit(&["rad", "radian", "radians"], Dimension::Angle, 1.0),
+    unit(&["deg", "degree", "degrees", "°"], Dimension::Angle, std::f64::consts::PI / 180.0),
+];
+
+/// Exact name first, then case-insensitive
+fn find_unit(name: &str) -> Option<&'static Unit> {
+    UNITS.iter().find(|u| u.names.contains(&name)).or_else(|| {
+        UNITS
+            .iter()
+            .find(|u| u.names.iter().any(|n| n.eq_ignore_ascii_case(name)))
+    })
+}
+
+/// Convert `value` between two units of the same dimension (e.g. `km` to `mi`)
+pub fn convert_units(value: f64, from: &str, to: &str) -> ToolResult<f64> {
+    let from_unit = find_unit(from).ok_or_else(|| invalid(format!("Unknown unit '{}'", from)))?;
+    let to_unit = find_unit(to).ok_or_else(|| invalid(format!("Unknown unit '{}'", to)))?;
+    if from_unit.dimension != to_unit.dimension {
+        return Err(invalid(format!(
+            "Cannot convert {} ({}) to {} ({})",
+            from,
+            from_unit.dimension.as_str(),
+            to,
+            to_unit.dimension.as_str()
+        )));
+    }
+    let base = (value + from_unit.offset) * from_unit.factor;
+    Ok(base / to_unit.factor - to_unit.offset)
+}
+
+/// Split `"<expr> <unit> to <unit>"` into its parts; None when there is no conversion
+fn split_conversion(input: &str) -> Option<(&str, &str, &str)> {
+    let (left, target) = [" to ", " in ", " as ", " TO ", " IN ", " AS "]
+        .iter()
+        .find_map(|sep| input.rsplit_once(sep))?;
+    let target = target.trim();
+    let left = left.trim_end();
+    // The source unit is the last word, or the letters glued to a trailing number (`5km`)
+    let word_start = left
+        .rfind(|c: char| c.is_whitespace() || c == ')')
+        .map(|i| i + left[i..].chars().next().map_or(1, char::len_utf8))
+        .unwrap_or(0);
+    let word = &left[word_start..];
+    let unit_start = word
+        .char_indices()
+        .find(|(_, c)| !(c.is_ascii_digit() || *c == '.' || *c == '_'))
+        .map(|(i, _)| word_start + i)?;
+    let (expression, unit) = left.split_at(unit_start);
+    if target.is_empty() || unit.is_empty() || expression.trim().is_empty() {
+        return None;
+    }
+    Some((expression.trim(), unit, target))
+}
+
+// ─── Currency ───────────────────────────────────────────────────────────────
+
+/// Exchange rates relative to `base` (`rates[code]` units of `code` per 1 `base`)
+#[derive(Debug, Clone)]
+struct RateTable {
+    base: String,
+    rates: HashMap<String, f64>,
+}
+
+impl RateTable {
+    fn rate(&self, code: &str) -> Option<f64> {
+        if code == self.base {
+            Some(1.0)
+        } else {
+            self.rates.get(code).copied().filter(|r| *r > 0.0)
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct RatesResponse {
+    #[serde(alias = "base_code")]
+    base: String,
+    rates: HashMap<String, f64>,
+}
+
+fn is_currency_code(code: &str) -> bool {
+    code.len() == 3 && code.chars().all(|c| c.is_ascii_alphabetic())
+}
+
+// ─── math:eval ──────────────────────────────────────────────────────────────
+
+/// Deterministic calculator tool
+pub struct MathEvalTool {
+    config: MathConfig,
+    http_client: reqwest::Client,
+    // Fetched (or fixed) exchange rates and when they were fetched; None = never expire
+    rates: Mutex<Option<(RateTable, Option<Instant>)>>,
+}
+
+impl Default for MathEvalTool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MathEvalTool {
+    /// Calculator and unit conversion, without currency rates
+    pub fn new() -> Self {
+        Self::with_config(MathConfig::default())
+    }
+
+    pub fn with_config(config: MathConfig) -> Self {
+        let http_client = reqwest::Client::builder()
+            .timeout(Duration::from_millis(config.timeout_ms))
+            .user_agent(&config.user_agent)
+            .build()
+            .unwrap_or_else(|_| reqwest::Client::new());
+        Self {
+            config,
+            http_client,
+            rates: Mutex::new(None),
+        }
+    }
+
+    /// Use fixed exchange rates (units of each code per 1 `base`) instead of fetching
+    pub fn with_rates(self, base: &str, rates: HashMap<String, f64>) -> Self {
+        let table = RateTable {
+            base: base.to_ascii_uppercase(),
+            rates: rates
+                .into_iter()
+                .map(|(code, rate)| (code.to_ascii_uppercase(), rate))
+                .collect(),
+        };
+        Self {
+            rates: Mutex::new(Some((table, None))),
+            ..self
+        }
+    }
+
+    async fn rate_table(&self) -> ToolResult<RateTable> {
+        let mut cached = self.rates.lock().await;
+        let ttl = Duration::from_secs(self.config.rates_ttl_secs);
+        if let Some((table, fetched)) = cached.as_ref() {
+            let fresh = match fetched {
+                Some(at) => at.elapsed() < ttl,
+                None => true,
+            };
+            if fresh {
+                return Ok(table.clone());
+            }
+        }
+        let Some(ref endpoint) = self.config.rates_endpoint else {
+            return Err(ToolError::ExecutionFailed(
+                "Currency conversion is not configured (set LOOM_CURRENCY_RATES_URL)".to_string(),
+            ));
+        };
+
+        debug!(target: "math_tool", endpoint = %endpoint, "Fetching exchange rates");
+        let resp = self.http_client.get(endpoint).send().await.map_err(|e| {
+            ToolError::ExecutionFailed(format!("Exchange rate request failed: {}", e))
+        })?;
+        if !resp.status().is_success() {
+            return Err(ToolError::ExecutionFailed(format!(
+                "Exchange rate API error: {}",
+                resp.status()
+            )));
+        }
+        let data: RatesResponse = resp.json().await.map_err(|e| {
+            ToolError::ExecutionFailed(format!("Failed to parse exchange rates: {}", e))
+        })?;
+        let table = RateTable {
+            base: data.base.to_ascii_uppercase(),
+            rates: data.rates,
+        };
+        *cached = Some((table.clone(), Some(Instant::now())));
+        Ok(table)
+    }
+
+    async fn convert_currency(&self, amount: f64, from: &str, to: &str) -> ToolResult<Value> {
+        let table = self.rate_table().await?;
+        let from_rate = table
+            .rate(from)
+            .ok_or_else(|| invalid(format!("Unknown currency '{}'", from)))?;
+        let to_rate = table
+            .rate(to)
+            .ok_or_else(|| invalid(format!("Unknown currency '{}'", to)))?;
+        let rate = to_rate / from_rate;
+        Ok(json!({
+            "value": amount * rate,
+            "unit": to,
+            "from_value": amount,
+            "from_unit": from,
+            "rate": rate,
+            "kind": "currency",
+        }))
+    }
+}
+
+/// Round to 12 significant digits, hiding binary floating-point noise (0.1 + 0.2 = 0.3)
+fn clean(value: f64) -> f64 {
+    format!("{:.11e}", value).parse().unwrap_or(value)
+}
+
+fn round_to(value: f64, precision: Option<u32>) -> f64 {
+    match precision {
+        Some(digits) => {
+            let scale = 10f64.powi(digits.min(15) as i32);
+            (value * scale).round() / scale
+        }
+        None => clean(value),
+    }
+}
+
+fn format_value(value: f64, unit: Option<&str>) -> String {
+    let number = if value.fract() == 0.0 && value.abs() < 1e15 {
+        format!("{}", value as i64)
+    } else {
+        format!("{}", value)
+    };
+    match unit {
+        Some(unit) => format!("{} {}", number, unit),
+        None => number,
+    }
+}
+
+#[async_trait]
+impl Tool for MathEvalTool {
+    fn name(&self) -> String {
+        "math:eval".to_string()
+    }
+
+    fn description(&self) -> String {
+        "Evaluate a math expression exactly, optionally converting units or currencies (e.g. '(3.5 * 12) / 7', 'sqrt(2) * 10', '5 km to mi', '72 F to C', '100 USD to EUR'). Use this instead of mental arithmetic.".to_string()
+    }
+
+    fn parameters(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "expression": {
+                    "type": "string",
+                    "description": "Expression with + - * / % ^ !, parentheses, functions (sqrt, ln, log, sin, round, min, max, ...) and constants (pi, e); append 'to <unit>' to convert"
+                },
+                "precision": {
+                    "type": "integer",
+                    "description": "Decimal places to round the result to (default: 12 significant digits)"
+                }
+            },
+            "required": ["expression"]
+        })
+    }
+
+    fn output_schema(&self) -> Option<Value> {
+        Some(json!({
+            "type": "object",
+            "properties": {
+                "expression": { "type": "string" },
+                "value": { "type": "number" },
+                "unit": { "type": ["string", "null"] },
+                "text": { "type": "string" }
+            },
+            "required": ["expression", "value", "unit", "text"]
+        }))
+    }
+
+    async fn call(&self, arguments: Value) -> ToolResult<Value> {
+        let expression = arguments["expression"]
+            .as_str()
+            .ok_or_else(|| ToolError::InvalidArguments("Missing 'expression'".to_string()))?;
+        let precision = arguments["precision"].as_u64().map(|p| p as u32);
+
+        let mut out = match split_conversion(expression) {
+            Some((expr, from, to)) => {
+                let amount = evaluate(expr)?;
+                if find_unit(from).is_some() || !is_currency_code(from) || !is_currency_code(to) {
+                    let value = convert_units(amount, from, to)?;
+                    json!({
+                        "value": value,
+                        "unit": to,
+                        "from_value": amount,
+                        "from_unit": from,
+                        "kind": find_unit(to).map(|u| u.dimension.as_str()),
+                    })
+                } else {
+                    self.convert_currency(
+                        amount,
+                        &from.to_ascii_uppercase(),
+                        &to.to_ascii_uppercase(),
+                    )
+                    .await?
+                }
+            }
+            None => json!({ "value": evaluate(expression)?, "unit": null }),
+        };
+
+        let value = round_to(out["value"].as_f64().unwrap_or_default(), precision);
+        out["value"] = json!(value);
+        out["text"] = json!(format_value(value, out["unit"].as_str()));
+        out["expression"] = json!(expression);
+        Ok(out)
+    }
+}
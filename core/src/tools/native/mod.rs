@@ -1,9 +1,32 @@
+pub mod blob;
 pub mod filesystem;
+pub mod kv;
+pub mod math;
+pub mod report;
+pub mod schedule;
 pub mod shell;
+pub mod storage;
+pub mod table;
+pub mod time;
 pub mod weather;
 pub mod web_search;
 
+pub use blob::{blob_tools, BlobDeleteTool, BlobGetTool, BlobPutTool};
 pub use filesystem::{DeleteFileTool, ListDirTool, ReadFileTool, WriteFileTool};
-pub use shell::ShellTool;
+pub use kv::{
+    kv_tools, KvDeleteTool, KvGetTool, KvListTool, KvPolicy, KvQuotas, KvSetTool, KvStore,
+};
+pub use math::{MathConfig, MathEvalTool};
+pub use report::ReportRenderTool;
+pub use schedule::{schedule_tools, ScheduleCancelTool, ScheduleListTool, ScheduleRemindTool};
+pub use shell::{ShellSandbox, ShellTool};
+pub use storage::{
+    storage_tools, ObjectStorage, StorageConfig, StorageGetTool, StorageListTool,
+    StoragePresignTool, StoragePutTool,
+};
+pub use table::{TableQueryTool, DEFAULT_MAX_TABLE_BYTES};
+pub use time::{time_tools, TimeConvertTool, TimeDiffTool, TimeNowTool, TimeParseTool};
 pub use weather::WeatherTool;
-pub use web_search::WebSearchTool;
+pub use web_search::{
+    SearchProvider, SearchProviderKind, SearchResult, WebSearchConfig, WebSearchTool,
+};
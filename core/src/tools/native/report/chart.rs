@@ -0,0 +1,328 @@
+//! Charts drawn from data series, laid out once as shapes that the HTML (SVG) and PDF
+//! renderers draw in their own syntax.
+
+use crate::tools::{ToolError, ToolResult};
+use serde_json::Value;
+
+/// Chart size in drawing units (SVG pixels; scaled to the page in PDF)
+pub(super) const WIDTH: f64 = 640.0;
+pub(super) const HEIGHT: f64 = 320.0;
+
+/// Most points (labels) a chart accepts
+const MAX_POINTS: usize = 500;
+
+/// Most series a chart accepts
+const MAX_SERIES: usize = 12;
+
+pub(super) type Color = (u8, u8, u8);
+
+const PALETTE: [Color; 6] = [
+    (37, 99, 235),
+    (234, 88, 12),
+    (22, 163, 74),
+    (147, 51, 234),
+    (219, 39, 119),
+    (13, 148, 136),
+];
+const TEXT: Color = (55, 65, 81);
+const AXIS: Color = (107, 114, 128);
+const GRID: Color = (229, 231, 235);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum ChartKind {
+    Bar,
+    Line,
+}
+
+#[derive(Debug, Clone)]
+pub(super) struct Series {
+    pub name: String,
+    pub values: Vec<f64>,
+}
+
+#[derive(Debug, Clone)]
+pub(super) struct Chart {
+    pub id: String,
+    pub kind: ChartKind,
+    pub title: Option<String>,
+    /// One label per point along the x axis
+    pub labels: Vec<String>,
+    pub series: Vec<Series>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum Anchor {
+    Start,
+    Middle,
+    End,
+}
+
+/// Something to draw, in drawing units with y growing downwards
+#[derive(Debug, Clone)]
+pub(super) enum Shape {
+    Rect {
+        x: f64,
+        y: f64,
+        w: f64,
+        h: f64,
+        color: Color,
+    },
+    Line {
+        points: Vec<(f64, f64)>,
+        color: Color,
+        width: f64,
+    },
+    Text {
+        x: f64,
+        y: f64,
+        text: String,
+        size: f64,
+        anchor: Anchor,
+        color: Color,
+    },
+}
+
+fn invalid(id: &str, message: impl std::fmt::Display) -> ToolError {
+    ToolError::InvalidArguments(format!("Chart '{id}': {message}"))
+}
+
+impl Chart {
+    /// Parse one entry of the `charts` argument
+    pub(super) fn from_value(value: &Value) -> ToolResult<Self> {
+        let id = value["id"]
+            .as_str()
+            .filter(|id| !id.trim().is_empty())
+            .ok_or_else(|| ToolError::InvalidArguments("Every chart needs an 'id'".to_string()))?
+            .trim()
+            .to_string();
+        let kind = match value["type"].as_str().unwrap_or("bar") {
+            "bar" => ChartKind::Bar,
+            "line" => ChartKind::Line,
+            other => return Err(invalid(&id, format_args!("unknown type '{other}'"))),
+        };
+        let labels: Vec<String> = value["labels"]
+            .as_array()
+            .ok_or_else(|| invalid(&id, "missing 'labels'"))?
+            .iter()
+            .map(|label| match label {
+                Value::String(s) => s.clone(),
+                other => other.to_string(),
+            })
+            .collect();
+        if labels.is_empty() || labels.len() > MAX_POINTS {
+            return Err(invalid(
+                &id,
+                format_args!("needs between 1 and {MAX_POINTS} labels"),
+            ));
+        }
+
+        let entries = value["series"]
+            .as_array()
+            .filter(|s| !s.is_empty() && s.len() <= MAX_SERIES)
+            .ok_or_else(|| invalid(&id, format_args!("needs 1 to {MAX_SERIES} series")))?;
+        let mut series = Vec::with_capacity(entries.len());
+        for (n, entry) in entries.iter().enumerate() {
+            let name = entry["name"]
+                .as_str()
+                .map(str::to_string)
+                .unwrap_or_else(|| format!("Series {}", n + 1));
+            let values = entry["values"]
+                .as_array()
+                .ok_or_else(|| invalid(&id, format_args!("series '{name}' has no 'values'")))?
+                .iter()
+                .map(|v| v.as_f64().filter(|v| v.is_finite()))
+                .collect::<Option<Vec<f64>>>()
+                .ok_or_else(|| {
+                    invalid(&id, format_args!("series '{name}' has non-numeric values"))
+                })?;
+            if values.len() != labels.len() {
+                return Err(invalid(
+                    &id,
+                    format_args!(
+                        "series '{name}' has {} values for {} labels",
+                        values.len(),
+                        labels.len()
+                    ),
+                ));
+            }
+            series.push(Series { name, values });
+        }
+
+        Ok(Self {
+            id,
+            kind,
+            title: value["title"].as_str().map(str::to_string),
+            labels,
+            series,
+        })
+    }
+
+    /// Lay the chart out in a `WIDTH` × `HEIGHT` box
+    pub(super) fn shapes(&self) -> Vec<Shape> {
+        let mut shapes = Vec::new();
+        let top = if let Some(title) = &self.title {
+            shapes.push(Shape::Text {
+                x: WIDTH / 2.0,
+                y: 20.0,
+                text: title.clone(),
+                size: 14.0,
+                anchor: Anchor::Middle,
+                color: TEXT,
+            });
+            36.0
+        } else {
+            16.0
+        };
+        let (left, right, bottom) = (56.0, WIDTH - 16.0, HEIGHT - 52.0);
+
+        let values = self.series.iter().flat_map(|s| s.values.iter().copied());
+        let (min, max) = values.fold((0.0_f64, 0.0_f64), |(lo, hi), v| (lo.min(v), hi.max(v)));
+        let step = nice_step((max - min).max(f64::EPSILON) / 4.0);
+        let (lo, hi) = ((min / step).floor() * step, (max / step).ceil() * step);
+        let hi = if hi > lo { hi } else { lo + step };
+        let y = |v: f64| bottom - (v - lo) / (hi - lo) * (bottom - top);
+
+        // Grid and y axis labels
+        let ticks = ((hi - lo) / step).round() as usize;
+        for n in 0..=ticks {
+            let value = lo + step * n as f64;
+            shapes.push(Shape::Line {
+                points: vec![(left, y(value)), (right, y(value))],
+                color: GRID,
+                width: 1.0,
+            });
+            shapes.push(Shape::Text {
+                x: left - 6.0,
+                y: y(value) + 4.0,
+                text: format_number(value, step),
+                size: 10.0,
+                anchor: Anchor::End,
+                color: TEXT,
+            });
+        }
+
+        // Data
+        let slot = (right - left) / self.labels.len() as f64;
+        let center = |i: usize| left + slot * (i as f64 + 0.5);
+        for (n, series) in self.series.iter().enumerate() {
+            let color = PALETTE[n % PALETTE.len()];
+            match self.kind {
+                ChartKind::Bar => {
+                    let width = slot * 0.8 / self.series.len() as f64;
+                    for (i, &v) in series.values.iter().enumerate() {
+                        let x = center(i) - slot * 0.4 + width * n as f64;
+                        let (y0, y1) = (y(0.0), y(v));
+                        shapes.push(Shape::Rect {
+                            x,
+                            y: y0.min(y1),
+                            w: width,
+                            h: (y0 - y1).abs(),
+                            color,
+                        });
+                    }
+                }
+                ChartKind::Line => {
+                    let points: Vec<_> = series
+                        .values
+                        .iter()
+                        .enumerate()
+                        .map(|(i, &v)| (center(i), y(v)))
+                        .collect();
+                    for &(x, y) in &points {
+                        shapes.push(Shape::Rect {
+                            x: x - 2.5,
+                            y: y - 2.5,
+                            w: 5.0,
+                            h: 5.0,
+                            color,
+                        });
+                    }
+                    shapes.push(Shape::Line {
+                        points,
+                        color,
+                        width: 2.0,
+                    });
+                }
+            }
+        }
+
+        // Axes
+        shapes.push(Shape::Line {
+            points: vec![(left, top), (left, bottom)],
+            color: AXIS,
+            width: 1.0,
+        });
+        shapes.push(Shape::Line {
+            points: vec![(left, y(0.0)), (right, y(0.0))],
+            color: AXIS,
+            width: 1.0,
+        });
+
+        // X axis labels, thinned out so they do not overlap
+        let every = (self.labels.len() as f64 * 48.0 / (right - left))
+            .ceil()
+            .max(1.0) as usize;
+        for (i, label) in self.labels.iter().enumerate().step_by(every) {
+            shapes.push(Shape::Text {
+                x: center(i),
+                y: bottom + 16.0,
+                text: label.clone(),
+                size: 10.0,
+                anchor: Anchor::Middle,
+                color: TEXT,
+            });
+        }
+
+        // Legend
+        let mut x = left;
+        for (n, series) in self.series.iter().enumerate() {
+            shapes.push(Shape::Rect {
+                x,
+                y: HEIGHT - 20.0,
+                w: 10.0,
+                h: 10.0,
+                color: PALETTE[n % PALETTE.len()],
+            });
+            shapes.push(Shape::Text {
+                x: x + 14.0,
+                y: HEIGHT - 11.0,
+                text: series.name.clone(),
+                size: 10.0,
+                anchor: Anchor::Start,
+                color: TEXT,
+            });
+            x += 30.0 + series.name.chars().count() as f64 * 6.0;
+        }
+        shapes
+    }
+}
+
+/// 1, 2 or 5 times a power of ten, at least `raw`
+fn nice_step(raw: f64) -> f64 {
+    let magnitude = 10f64.powf(raw.log10().floor());
+    let step = [1.0, 2.0, 5.0, 10.0]
+        .into_iter()
+        .find(|m| m * magnitude >= raw)
+        .unwrap_or(10.0);
+    step * magnitude
+}
+
+/// `value` with as many decimals as `step` needs
+fn format_number(value: f64, step: f64) -> String {
+    let decimals = if step >= 1.0 {
+        0
+    } else {
+        (-step.log10()).ceil() as usize
+    };
+    let text = format!("{value:.decimals$}");
+    // Avoid "-0"
+    if text
+        .trim_start_matches('-')
+        .chars()
+        .all(|c| c == '0' || c == '.')
+    {
+        text.trim_start_matches('-').to_string()
+    } else {
+        text
+    }
+}
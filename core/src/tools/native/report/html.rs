@@ -0,0 +1,203 @@
+//! Reports as standalone HTML: one file with embedded CSS and inline SVG charts.
+
+use super::chart::{Anchor, Chart, Color, Shape, HEIGHT, WIDTH};
+use super::markdown::{Block, Span};
+use super::Report;
+use std::fmt::Write;
+
+const STYLE: &str = "\
+body{margin:0;background:#f9fafb;color:#111827;font:16px/1.6 system-ui,-apple-system,'Segoe UI',Helvetica,Arial,sans-serif}\
+main{max-width:820px;margin:0 auto;padding:48px 32px;background:#fff}\
+header{border-bottom:2px solid #e5e7eb;margin-bottom:24px}\
+h1,h2,h3,h4,h5,h6{line-height:1.25;margin:1.4em 0 .5em}\
+header h1{margin-top:0}\
+dl.meta{display:grid;grid-template-columns:max-content 1fr;gap:2px 16px;color:#4b5563;font-size:14px}\
+dl.meta dt{font-weight:600}dl.meta dd{margin:0}\
+a{color:#2563eb}\
+code{font-family:ui-monospace,Menlo,Consolas,monospace;font-size:.9em;background:#f3f4f6;padding:1px 4px;border-radius:3px}\
+pre{background:#f3f4f6;padding:12px 16px;overflow-x:auto;border-radius:4px}pre code{padding:0;background:none}\
+blockquote{margin:1em 0;padding:.2em 1em;border-left:4px solid #d1d5db;color:#4b5563}\
+table{border-collapse:collapse;margin:1em 0;width:100%}\
+th,td{border:1px solid #d1d5db;padding:6px 10px;text-align:left}th{background:#f3f4f6}\
+hr{border:0;border-top:1px solid #e5e7eb;margin:2em 0}\
+figure.chart{margin:1.5em 0}figure.chart svg{max-width:100%;height:auto}\
+@media print{body{background:#fff}main{padding:0}}";
+
+/// Escape text for HTML content and attribute values
+fn escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+/// The report as an HTML document
+pub(super) fn document(report: &Report) -> String {
+    let title = report.title.as_deref().unwrap_or("Report");
+    let mut html = format!(
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n\
+         <meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n\
+         <title>{}</title>\n<style>{STYLE}</style>\n</head>\n<body>\n<main>\n",
+        escape(title)
+    );
+    if report.title.is_some() || !report.metadata.is_empty() {
+        html.push_str("<header>\n");
+        if let Some(title) = &report.title {
+            let _ = writeln!(html, "<h1>{}</h1>", escape(title));
+        }
+        if !report.metadata.is_empty() {
+            html.push_str("<dl class=\"meta\">");
+            for (key, value) in &report.metadata {
+                let _ = write!(html, "<dt>{}</dt><dd>{}</dd>", escape(key), escape(value));
+            }
+            html.push_str("</dl>\n");
+        }
+        html.push_str("</header>\n");
+    }
+    for block in &report.blocks {
+        block_html(&mut html, block, report);
+    }
+    html.push_str("</main>\n</body>\n</html>\n");
+    html
+}
+
+fn block_html(html: &mut String, block: &Block, report: &Report) {
+    let _ = match block {
+        Block::Heading(level, spans) => {
+            writeln!(html, "<h{level}>{}</h{level}>", spans_html(spans))
+        }
+        Block::Paragraph(spans) => writeln!(html, "<p>{}</p>", spans_html(spans)),
+        Block::List { start, items } => {
+            let open = match start {
+                None => "<ul>".to_string(),
+                Some(1) => "<ol>".to_string(),
+                Some(n) => format!("<ol start=\"{n}\">"),
+            };
+            html.push_str(&open);
+            for item in items {
+                let _ = write!(html, "<li>{}</li>", spans_html(item));
+            }
+            writeln!(html, "{}", if start.is_some() { "</ol>" } else { "</ul>" })
+        }
+        Block::Quote(spans) => {
+            writeln!(
+                html,
+                "<blockquote><p>{}</p></blockquote>",
+                spans_html(spans)
+            )
+        }
+        Block::Code(code) => writeln!(html, "<pre><code>{}</code></pre>", escape(code)),
+        Block::Rule => writeln!(html, "<hr>"),
+        Block::Table { header, rows } => {
+            html.push_str("<table>\n<thead><tr>");
+            for cell in header {
+                let _ = write!(html, "<th>{}</th>", spans_html(cell));
+            }
+            html.push_str("</tr></thead>\n<tbody>\n");
+            for row in rows {
+                html.push_str("<tr>");
+                for cell in row {
+                    let _ = write!(html, "<td>{}</td>", spans_html(cell));
+                }
+                html.push_str("</tr>\n");
+            }
+            writeln!(html, "</tbody>\n</table>")
+        }
+        Block::Chart(id) => match report.charts.get(id) {
+            Some(chart) => writeln!(html, "<figure class=\"chart\">{}</figure>", svg(chart)),
+            None => Ok(()),
+        },
+    };
+}
+
+fn spans_html(spans: &[Span]) -> String {
+    let mut html = String::new();
+    for span in spans {
+        let mut text = escape(&span.text);
+        if span.style.code {
+            text = format!("<code>{text}</code>");
+        }
+        if span.style.italic {
+            text = format!("<em>{text}</em>");
+        }
+        if span.style.bold {
+            text = format!("<strong>{text}</strong>");
+        }
+        match &span.link {
+            Some(url) => {
+                let _ = write!(html, "<a href=\"{}\">{text}</a>", escape(url));
+            }
+            None => html.push_str(&text),
+        }
+    }
+    html
+}
+
+fn rgb((r, g, b): Color) -> String {
+    format!("rgb({r},{g},{b})")
+}
+
+/// The chart as an inline SVG element
+fn svg(chart: &Chart) -> String {
+    let mut svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"0 0 {WIDTH} {HEIGHT}\" \
+         width=\"{WIDTH}\" height=\"{HEIGHT}\" role=\"img\" aria-label=\"{}\" \
+         font-family=\"Helvetica,Arial,sans-serif\">",
+        escape(chart.title.as_deref().unwrap_or(&chart.id))
+    );
+    for shape in chart.shapes() {
+        let _ = match shape {
+            Shape::Rect { x, y, w, h, color } => write!(
+                svg,
+                "<rect x=\"{x:.1}\" y=\"{y:.1}\" width=\"{w:.1}\" height=\"{h:.1}\" fill=\"{}\"/>",
+                rgb(color)
+            ),
+            Shape::Line {
+                points,
+                color,
+                width,
+            } => {
+                let points: Vec<String> = points
+                    .iter()
+                    .map(|(x, y)| format!("{x:.1},{y:.1}"))
+                    .collect();
+                write!(
+                    svg,
+                    "<polyline points=\"{}\" fill=\"none\" stroke=\"{}\" stroke-width=\"{width}\"/>",
+                    points.join(" "),
+                    rgb(color)
+                )
+            }
+            Shape::Text {
+                x,
+                y,
+                text,
+                size,
+                anchor,
+                color,
+            } => {
+                let anchor = match anchor {
+                    Anchor::Start => "start",
+                    Anchor::Middle => "middle",
+                    Anchor::End => "end",
+                };
+                write!(
+                    svg,
+                    "<text x=\"{x:.1}\" y=\"{y:.1}\" font-size=\"{size}\" text-anchor=\"{anchor}\" fill=\"{}\">{}</text>",
+                    rgb(color),
+                    escape(&text)
+                )
+            }
+        };
+    }
+    svg.push_str("</svg>");
+    svg
+}
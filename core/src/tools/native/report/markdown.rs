@@ -0,0 +1,423 @@
+//! The Markdown subset reports are written in, parsed into blocks both renderers share.
+//!
+//! Supported: ATX headings, paragraphs, `-`/`*`/`+` and numbered lists, `>` quotes,
+//! fenced code, rules, pipe tables, and inline `**bold**`, `*italic*`, `` `code` `` and
+//! `[links](url)`. A line holding only `{{chart:<id>}}` places a chart.
+
+/// Inline formatting of a run of text
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(super) struct Style {
+    pub bold: bool,
+    pub italic: bool,
+    pub code: bool,
+}
+
+/// A run of text with one style
+#[derive(Debug, Clone, PartialEq)]
+pub(super) struct Span {
+    pub text: String,
+    pub style: Style,
+    /// Link target, only for `http`, `https`, `mailto`, anchors and relative links
+    pub link: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(super) enum Block {
+    Heading(u8, Vec<Span>),
+    Paragraph(Vec<Span>),
+    List {
+        /// First number of a numbered list; `None` for bullets
+        start: Option<u64>,
+        items: Vec<Vec<Span>>,
+    },
+    Quote(Vec<Span>),
+    Code(String),
+    Rule,
+    Table {
+        header: Vec<Vec<Span>>,
+        rows: Vec<Vec<Vec<Span>>>,
+    },
+    Chart(String),
+}
+
+/// Parse `markdown` into blocks
+pub(super) fn parse(markdown: &str) -> Vec<Block> {
+    let lines: Vec<&str> = markdown.lines().collect();
+    let mut blocks = Vec::new();
+    let mut i = 0;
+    while i < lines.len() {
+        let line = lines[i].trim();
+        if line.is_empty() {
+            i += 1;
+        } else if let Some(fence) = fence(line) {
+            let mut code = Vec::new();
+            i += 1;
+            while i < lines.len() && !lines[i].trim_start().starts_with(fence) {
+                code.push(lines[i]);
+                i += 1;
+            }
+            i += 1;
+            blocks.push(Block::Code(code.join("\n")));
+        } else if let Some(id) = chart_placeholder(line) {
+            blocks.push(Block::Chart(id.to_string()));
+            i += 1;
+        } else if let Some((level, text)) = heading(line) {
+            blocks.push(Block::Heading(level, inline(text)));
+            i += 1;
+        } else if is_rule(line) {
+            blocks.push(Block::Rule);
+            i += 1;
+        } else if line.starts_with('>') {
+            let mut text = Vec::new();
+            while let Some(quoted) = lines.get(i).and_then(|l| l.trim().strip_prefix('>')) {
+                text.push(quoted.trim());
+                i += 1;
+            }
+            blocks.push(Block::Quote(inline(&text.join(" "))));
+        } else if is_table_start(&lines, i) {
+            let header = cells(line).iter().map(|c| inline(c)).collect();
+            let mut rows = Vec::new();
+            i += 2;
+            while let Some(row) = lines.get(i).map(|l| l.trim()) {
+                if row.is_empty() || !row.contains('|') {
+                    break;
+                }
+                rows.push(cells(row).iter().map(|c| inline(c)).collect());
+                i += 1;
+            }
+            blocks.push(Block::Table { header, rows });
+        } else if let Some((start, _)) = list_item(line) {
+            let ordered = start.is_some();
+            let mut items: Vec<String> = Vec::new();
+            while let Some(next) = lines.get(i).map(|l| l.trim()) {
+                if next.is_empty() {
+                    break;
+                }
+                match list_item(next) {
+                    Some((s, text)) if s.is_some() == ordered => items.push(text.to_string()),
+                    Some(_) => break,
+                    None if starts_block(&lines, i) => break,
+                    // A continuation line of the last item
+                    None => {
+                        let last = items.last_mut().expect("a list starts with an item");
+                        last.push(' ');
+                        last.push_str(next);
+                    }
+                }
+                i += 1;
+            }
+            blocks.push(Block::List {
+                start,
+                items: items.iter().map(|item| inline(item)).collect(),
+            });
+        } else {
+            let mut text = vec![line];
+            i += 1;
+            while let Some(next) = lines.get(i).map(|l| l.trim()) {
+                if next.is_empty() || starts_block(&lines, i) || list_item(next).is_some() {
+                    break;
+                }
+                text.push(next);
+                i += 1;
+            }
+            blocks.push(Block::Paragraph(inline(&text.join(" "))));
+        }
+    }
+    blocks
+}
+
+/// Whether line `i` opens a block that ends a paragraph
+fn starts_block(lines: &[&str], i: usize) -> bool {
+    let line = lines[i].trim();
+    fence(line).is_some()
+        || chart_placeholder(line).is_some()
+        || heading(line).is_some()
+        || is_rule(line)
+        || line.starts_with('>')
+        || is_table_start(lines, i)
+}
+
+fn fence(line: &str) -> Option<&'static str> {
+    ["```", "~~~"].into_iter().find(|f| line.starts_with(f))
+}
+
+/// Id of a `{{chart:<id>}}` line
+fn chart_placeholder(line: &str) -> Option<&str> {
+    line.strip_prefix("{{")?
+        .strip_suffix("}}")?
+        .trim()
+        .strip_prefix("chart:")
+        .map(str::trim)
+}
+
+fn heading(line: &str) -> Option<(u8, &str)> {
+    let level = line.chars().take_while(|&c| c == '#').count();
+    let rest = &line[level..];
+    if !(1..=6).contains(&level) || !(rest.is_empty() || rest.starts_with(' ')) {
+        return None;
+    }
+    Some((level as u8, rest.trim().trim_end_matches('#').trim_end()))
+}
+
+fn is_rule(line: &str) -> bool {
+    let marks: Vec<char> = line.chars().filter(|c| !c.is_whitespace()).collect();
+    marks.len() >= 3 && ['-', '*', '_'].iter().any(|m| marks.iter().all(|c| c == m))
+}
+
+/// Bullet (`None`) or numbered item, and its text
+fn list_item(line: &str) -> Option<(Option<u64>, &str)> {
+    for bullet in ["- ", "* ", "+ "] {
+        if let Some(text) = line.strip_prefix(bullet) {
+            return Some((None, text.trim()));
+        }
+    }
+    let digits = line.chars().take_while(char::is_ascii_digit).count();
+    if digits == 0 || digits > 9 {
+        return None;
+    }
+    let rest = &line[digits..];
+    let text = rest
+        .strip_prefix(". ")
+        .or_else(|| rest.strip_prefix(") "))?;
+    Some((Some(line[..digits].parse().ok()?), text.trim()))
+}
+
+/// A row with pipes followed by a `---|:---:` delimiter row
+fn is_table_start(lines: &[&str], i: usize) -> bool {
+    let Some(delimiter) = lines.get(i + 1) else {
+        return false;
+    };
+    lines[i].contains('|')
+        && delimiter.contains('-')
+        && cells(delimiter.trim()).iter().all(|cell| {
+            let dashes = cell.trim_start_matches(':').trim_end_matches(':');
+            !dashes.is_empty() && dashes.chars().all(|c| c == '-')
+        })
+}
+
+/// Cells of a table row; `\|` is a literal pipe
+fn cells(row: &str) -> Vec<String> {
+    let row = row.strip_prefix('|').unwrap_or(row);
+    let row = match row.strip_suffix('|') {
+        Some(inner) if !inner.ends_with('\\') => inner,
+        _ => row,
+    };
+    let mut cells = vec![String::new()];
+    let mut chars = row.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' if chars.peek() == Some(&'|') => {
+                cells.last_mut().unwrap().push('|');
+                chars.next();
+            }
+            '|' => cells.push(String::new()),
+            _ => cells.last_mut().unwrap().push(c),
+        }
+    }
+    cells.iter().map(|c| c.trim().to_string()).collect()
+}
+
+/// Parse inline formatting into spans
+pub(super) fn inline(text: &str) -> Vec<Span> {
+    let chars: Vec<char> = text.chars().collect();
+    let mut spans = Vec::new();
+    let mut style = Style::default();
+    let mut text = String::new();
+    let flush = |spans: &mut Vec<Span>, text: &mut String, style: Style| {
+        if !text.is_empty() {
+            spans.push(Span {
+                text: std::mem::take(text),
+                style,
+                link: None,
+            });
+        }
+    };
+
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        match c {
+            '\\' if chars.get(i + 1).is_some_and(char::is_ascii_punctuation) => {
+                text.push(chars[i + 1]);
+                i += 2;
+            }
+            '`' => match find(&chars, i + 1, &['`']) {
+                Some(end) => {
+                    flush(&mut spans, &mut text, style);
+                    spans.push(Span {
+                        text: chars[i + 1..end].iter().collect(),
+                        style: Style {
+                            code: true,
+                            ..style
+                        },
+                        link: None,
+                    });
+                    i = end + 1;
+                }
+                None => {
+                    text.push(c);
+                    i += 1;
+                }
+            },
+            '*' | '_' => {
+                let double = chars.get(i + 1) == Some(&c);
+                let marker: &[char] = if double { &[c, c] } else { &[c] };
+                let after = chars.get(i + marker.len());
+                let on = if double { style.bold } else { style.italic };
+                // `_` inside a word is literal, as in snake_case
+                let intraword = c == '_'
+                    && i > 0
+                    && chars[i - 1].is_alphanumeric()
+                    && after.is_some_and(|a| a.is_alphanumeric());
+                let toggles = if on {
+                    i > 0 && !chars[i - 1].is_whitespace()
+                } else {
+                    after.is_some_and(|a| !a.is_whitespace())
+                        && find(&chars, i + marker.len() + 1, marker).is_some()
+                };
+                if toggles && !intraword {
+                    flush(&mut spans, &mut text, style);
+                    if double {
+                        style.bold = !style.bold;
+                    } else {
+                        style.italic = !style.italic;
+                    }
+                    i += marker.len();
+                } else {
+                    text.push(c);
+                    i += 1;
+                }
+            }
+            '[' => match link(&chars, i) {
+                Some((label, url, end)) => {
+                    flush(&mut spans, &mut text, style);
+                    let link = safe_url(&url);
+                    spans.extend(inline(&label).into_iter().map(|span| Span {
+                        style: Style {
+                            bold: span.style.bold || style.bold,
+                            italic: span.style.italic || style.italic,
+                            code: span.style.code,
+                        },
+                        link: link.clone(),
+                        ..span
+                    }));
+                    i = end;
+                }
+                None => {
+                    text.push(c);
+                    i += 1;
+                }
+            },
+            _ => {
+                text.push(c);
+                i += 1;
+            }
+        }
+    }
+    flush(&mut spans, &mut text, style);
+    spans
+}
+
+/// Index of the next `pattern` at or after `from`
+fn find(chars: &[char], from: usize, pattern: &[char]) -> Option<usize> {
+    (from..chars.len().saturating_sub(pattern.len() - 1))
+        .find(|&i| chars[i..i + pattern.len()] == *pattern)
+}
+
+/// Label, URL and the index past a `[label](url)` starting at `start`
+fn link(chars: &[char], start: usize) -> Option<(String, String, usize)> {
+    let close = find(chars, start + 1, &[']', '('])?;
+    let end = find(chars, close + 2, &[')'])?;
+    Some((
+        chars[start + 1..close].iter().collect(),
+        chars[close + 2..end]
+            .iter()
+            .collect::<String>()
+            .trim()
+            .to_string(),
+        end + 1,
+    ))
+}
+
+/// `url` if it cannot run script when followed
+fn safe_url(url: &str) -> Option<String> {
+    let lower = url.to_ascii_lowercase();
+    let scheme_end = lower.find(':');
+    let relative = scheme_end.is_none_or(|colon| lower[..colon].contains(['/', '?', '#']));
+    let allowed = ["http:", "https:", "mailto:"]
+        .iter()
+        .any(|scheme| lower.starts_with(scheme));
+    (!url.is_empty() && (relative || allowed)).then(|| url.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn plain(spans: &[Span]) -> String {
+        spans.iter().map(|s| s.text.as_str()).collect()
+    }
+
+    fn styled(spans: &[Span]) -> Vec<(&str, bool, bool, bool)> {
+        spans
+            .iter()
+            .map(|s| (s.text.as_str(), s.style.bold, s.style.italic, s.style.code))
+            .collect()
+    }
+
+    #[test]
+    fn inline_formatting() {
+        let spans = inline("a **b** *c* `d*` snake_case_name 2 * 3 \\*e\\*");
+        assert_eq!(
+            styled(&spans),
+            [
+                ("a ", false, false, false),
+                ("b", true, false, false),
+                (" ", false, false, false),
+                ("c", false, true, false),
+                (" ", false, false, false),
+                ("d*", false, false, true),
+                (" snake_case_name 2 * 3 *e*", false, false, false),
+            ]
+        );
+
+        let spans = inline("see [the **docs**](https://x.io) or [this](javascript:alert(1))");
+        assert_eq!(spans[1].text, "the ");
+        assert_eq!(spans[2].text, "docs");
+        assert!(spans[2].style.bold);
+        assert_eq!(spans[2].link.as_deref(), Some("https://x.io"));
+        assert_eq!(plain(&spans), "see the docs or this)");
+        assert_eq!(spans[4].text, "this");
+        assert_eq!(spans[4].link, None);
+        assert_eq!(
+            safe_url("reports/q3.html#top").as_deref(),
+            Some("reports/q3.html#top")
+        );
+    }
+
+    #[test]
+    fn blocks() {
+        let blocks = parse(
+            "# Title #\nfirst line\nsecond line\n\n- one\n- two\n  continued\n\
+             3. three\n\n> quoted\n> more\n\n```\nlet x = 1;\n```\n---\n\
+             | A | B \\| C |\n|---|:-:|\n| 1 | 2 |\n{{ chart:sales }}",
+        );
+        assert_eq!(blocks.len(), 9);
+        assert!(matches!(&blocks[0], Block::Heading(1, s) if plain(s) == "Title"));
+        assert!(matches!(&blocks[1], Block::Paragraph(s) if plain(s) == "first line second line"));
+        let Block::List { start: None, items } = &blocks[2] else {
+            panic!("expected a bullet list: {:?}", blocks[2]);
+        };
+        assert_eq!(plain(&items[1]), "two continued");
+        assert!(matches!(&blocks[3], Block::List { start: Some(3), items } if items.len() == 1));
+        assert!(matches!(&blocks[4], Block::Quote(s) if plain(s) == "quoted more"));
+        assert_eq!(blocks[5], Block::Code("let x = 1;".to_string()));
+        assert_eq!(blocks[6], Block::Rule);
+        let Block::Table { header, rows } = &blocks[7] else {
+            panic!("expected a table: {:?}", blocks[7]);
+        };
+        assert_eq!(plain(&header[1]), "B | C");
+        assert_eq!(plain(&rows[0][1]), "2");
+        assert_eq!(blocks[8], Block::Chart("sales".to_string()));
+    }
+}
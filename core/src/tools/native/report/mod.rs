@@ -0,0 +1,350 @@
+//! Report generation for agents: `report:render`.
+//!
+//! Turns agent-written Markdown into a finished document instead of raw chat text.
+//! `{{name}}` placeholders are filled from `variables`, and a line holding only
+//! `{{chart:<id>}}` places a bar or line chart drawn from the data series in `charts`.
+//! The report is rendered as a standalone HTML page (inline SVG charts), a PDF, or both,
+//! and each artifact is stored in the blob store (`crate::blob`) owned by the calling
+//! agent, so it can be handed on by `blob_ref` (for example to `storage:put`).
+
+mod chart;
+mod html;
+mod markdown;
+mod pdf;
+
+use crate::blob::{BlobAccess, BlobStore};
+use crate::tools::native::blob::readers_argument;
+use crate::tools::{caller_agent_id, Tool, ToolError, ToolResult};
+use async_trait::async_trait;
+use chart::Chart;
+use markdown::Block;
+use serde_json::{json, Map, Value};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tracing::debug;
+
+/// Longest Markdown accepted, after templating, in bytes
+const MAX_MARKDOWN_BYTES: usize = 1024 * 1024;
+
+/// Most charts one report may carry
+const MAX_CHARTS: usize = 32;
+
+/// A report ready to render
+struct Report {
+    title: Option<String>,
+    /// Fields shown under the title, such as author or period
+    metadata: Vec<(String, String)>,
+    blocks: Vec<Block>,
+    charts: HashMap<String, Chart>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Format {
+    Html,
+    Pdf,
+}
+
+impl Format {
+    fn name(self) -> &'static str {
+        match self {
+            Format::Html => "html",
+            Format::Pdf => "pdf",
+        }
+    }
+
+    fn content_type(self) -> &'static str {
+        match self {
+            Format::Html => "text/html; charset=utf-8",
+            Format::Pdf => "application/pdf",
+        }
+    }
+}
+
+fn value_text(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        Value::Null => String::new(),
+        other => other.to_string(),
+    }
+}
+
+/// Fill `{{name}}` placeholders from `variables`, leaving `{{chart:<id>}}` in place
+fn fill_template(template: &str, variables: &Map<String, Value>) -> ToolResult<String> {
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(open) = rest.find("{{") {
+        let Some(close) = rest[open + 2..].find("}}").map(|c| open + 2 + c) else {
+            break;
+        };
+        out.push_str(&rest[..open]);
+        let name = rest[open + 2..close].trim();
+        if name.starts_with("chart:") {
+            out.push_str(&rest[open..close + 2]);
+        } else {
+            let value = variables.get(name).ok_or_else(|| {
+                ToolError::InvalidArguments(format!("Unknown template variable '{name}'"))
+            })?;
+            out.push_str(&value_text(value));
+        }
+        rest = &rest[close + 2..];
+    }
+    out.push_str(rest);
+    Ok(out)
+}
+
+/// Requested formats, without duplicates (default: HTML)
+fn formats_argument(arguments: &Value) -> ToolResult<Vec<Format>> {
+    let Some(names) = arguments["formats"].as_array() else {
+        return Ok(vec![Format::Html]);
+    };
+    let mut formats = Vec::new();
+    for name in names {
+        let format = match name.as_str() {
+            Some("html") => Format::Html,
+            Some("pdf") => Format::Pdf,
+            _ => {
+                return Err(ToolError::InvalidArguments(format!(
+                    "Unknown format {name}; use \"html\" or \"pdf\""
+                )))
+            }
+        };
+        if !formats.contains(&format) {
+            formats.push(format);
+        }
+    }
+    if formats.is_empty() {
+        return Err(ToolError::InvalidArguments(
+            "'formats' must name at least one format".to_string(),
+        ));
+    }
+    Ok(formats)
+}
+
+impl Report {
+    fn from_arguments(arguments: &Value) -> ToolResult<Self> {
+        let markdown = arguments["markdown"].as_str().ok_or_else(|| {
+            ToolError::InvalidArguments("Missing 'markdown' argument".to_string())
+        })?;
+        let no_variables = Map::new();
+        let variables = match &arguments["variables"] {
+            Value::Object(variables) => variables,
+            Value::Null => &no_variables,
+            _ => {
+                return Err(ToolError::InvalidArguments(
+                    "'variables' must be an object".to_string(),
+                ))
+            }
+        };
+        let markdown = fill_template(markdown, variables)?;
+        if markdown.len() > MAX_MARKDOWN_BYTES {
+            return Err(ToolError::InvalidArguments(format!(
+                "Markdown of {} bytes exceeds the limit of {MAX_MARKDOWN_BYTES} bytes",
+                markdown.len()
+            )));
+        }
+        let title = arguments["title"]
+            .as_str()
+            .map(|title| fill_template(title, variables))
+            .transpose()?;
+        let metadata = match &arguments["metadata"] {
+            Value::Object(fields) => fields
+                .iter()
+                .map(|(key, value)| {
+                    Ok((key.clone(), fill_template(&value_text(value), variables)?))
+                })
+                .collect::<ToolResult<Vec<_>>>()?,
+            Value::Null => Vec::new(),
+            _ => {
+                return Err(ToolError::InvalidArguments(
+                    "'metadata' must be an object".to_string(),
+                ))
+            }
+        };
+
+        let entries = match &arguments["charts"] {
+            Value::Array(entries) if entries.len() <= MAX_CHARTS => entries.as_slice(),
+            Value::Null => &[],
+            _ => {
+                return Err(ToolError::InvalidArguments(format!(
+                    "'charts' must be an array of at most {MAX_CHARTS} charts"
+                )))
+            }
+        };
+        let mut order = Vec::with_capacity(entries.len());
+        let mut charts = HashMap::with_capacity(entries.len());
+        for entry in entries {
+            let chart = Chart::from_value(entry)?;
+            if charts.contains_key(&chart.id) {
+                return Err(ToolError::InvalidArguments(format!(
+                    "Duplicate chart id '{}'",
+                    chart.id
+                )));
+            }
+            order.push(chart.id.clone());
+            charts.insert(chart.id.clone(), chart);
+        }
+
+        let mut blocks = markdown::parse(&markdown);
+        for block in &blocks {
+            if let Block::Chart(id) = block {
+                if !charts.contains_key(id) {
+                    return Err(ToolError::InvalidArguments(format!("Unknown chart '{id}'")));
+                }
+            }
+        }
+        // Charts the Markdown does not place go at the end, in the order given
+        let unplaced: Vec<Block> = order
+            .into_iter()
+            .filter(|id| !blocks.contains(&Block::Chart(id.clone())))
+            .map(Block::Chart)
+            .collect();
+        blocks.extend(unplaced);
+
+        Ok(Self {
+            title,
+            metadata,
+            blocks,
+            charts,
+        })
+    }
+
+    fn render(&self, format: Format) -> Vec<u8> {
+        match format {
+            Format::Html => html::document(self).into_bytes(),
+            Format::Pdf => pdf::document(self),
+        }
+    }
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// report:render
+// ─────────────────────────────────────────────────────────────────────────────
+
+pub struct ReportRenderTool {
+    store: Arc<BlobStore>,
+}
+
+impl ReportRenderTool {
+    pub fn new(store: Arc<BlobStore>) -> Self {
+        Self { store }
+    }
+}
+
+#[async_trait]
+impl Tool for ReportRenderTool {
+    fn name(&self) -> String {
+        "report:render".to_string()
+    }
+
+    fn description(&self) -> String {
+        "Render a Markdown report, with template variables and charts from data series, \
+         into HTML and/or PDF files stored as blobs"
+            .to_string()
+    }
+
+    fn parameters(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "markdown": {
+                    "type": "string",
+                    "description": "Report body in Markdown (headings, lists, tables, code, quotes, links). \
+                                    `{{name}}` inserts a variable; a line with only `{{chart:<id>}}` places a chart"
+                },
+                "title": { "type": "string", "description": "Document title shown above the body" },
+                "metadata": {
+                    "type": "object",
+                    "additionalProperties": { "type": "string" },
+                    "description": "Header fields shown under the title, e.g. {\"Author\": \"...\", \"Period\": \"Q3\"}"
+                },
+                "variables": {
+                    "type": "object",
+                    "description": "Values for `{{name}}` placeholders in markdown, title and metadata"
+                },
+                "charts": {
+                    "type": "array",
+                    "items": {
+                        "type": "object",
+                        "properties": {
+                            "id": { "type": "string" },
+                            "type": { "type": "string", "enum": ["bar", "line"] },
+                            "title": { "type": "string" },
+                            "labels": { "type": "array", "items": { "type": "string" } },
+                            "series": {
+                                "type": "array",
+                                "items": {
+                                    "type": "object",
+                                    "properties": {
+                                        "name": { "type": "string" },
+                                        "values": { "type": "array", "items": { "type": "number" } }
+                                    },
+                                    "required": ["values"]
+                                }
+                            }
+                        },
+                        "required": ["id", "labels", "series"]
+                    },
+                    "description": "Charts; one value per label in each series. Charts not placed in markdown are appended"
+                },
+                "formats": {
+                    "type": "array",
+                    "items": { "type": "string", "enum": ["html", "pdf"] },
+                    "description": "Files to produce (default: [\"html\"])"
+                },
+                "readers": {
+                    "type": "array",
+                    "items": { "type": "string" },
+                    "description": "Agent ids allowed to read the files (default: any agent)"
+                }
+            },
+            "required": ["markdown"]
+        })
+    }
+
+    fn output_schema(&self) -> Option<Value> {
+        Some(json!({
+            "type": "object",
+            "properties": {
+                "title": { "type": ["string", "null"] },
+                "artifacts": {
+                    "type": "array",
+                    "items": {
+                        "type": "object",
+                        "properties": {
+                            "format": { "type": "string" },
+                            "blob_ref": { "type": "string" },
+                            "size": { "type": "integer" },
+                            "content_type": { "type": "string" }
+                        },
+                        "required": ["format", "blob_ref", "size", "content_type"]
+                    }
+                }
+            },
+            "required": ["artifacts"]
+        }))
+    }
+
+    async fn call(&self, arguments: Value) -> ToolResult<Value> {
+        let report = Report::from_arguments(&arguments)?;
+        let formats = formats_argument(&arguments)?;
+        let access = BlobAccess::owned_by(caller_agent_id().unwrap_or_default())
+            .with_readers(readers_argument(&arguments));
+
+        let mut artifacts = Vec::with_capacity(formats.len());
+        for format in formats {
+            let blob = self
+                .store
+                .put(report.render(format), format.content_type(), access.clone())
+                .await
+                .map_err(|e| ToolError::ExecutionFailed(e.to_string()))?;
+            debug!(target: "report", blob_ref = %blob.id, format = format.name(), size = blob.size, "report:render");
+            artifacts.push(json!({
+                "format": format.name(),
+                "blob_ref": blob.id,
+                "size": blob.size,
+                "content_type": blob.content_type,
+            }));
+        }
+        Ok(json!({ "title": report.title, "artifacts": artifacts }))
+    }
+}
@@ -0,0 +1,743 @@
+//! Reports as PDF: A4 pages set in the standard Helvetica and Courier fonts, so no fonts
+//! are embedded. Text outside Windows-1252 prints as `?`.
+
+use super::chart::{Anchor, Color, Shape, HEIGHT, WIDTH};
+use super::markdown::{Block, Span, Style};
+use super::Report;
+use std::fmt::Write;
+
+const PAGE_WIDTH: f64 = 595.0;
+const PAGE_HEIGHT: f64 = 842.0;
+const MARGIN: f64 = 56.0;
+const CONTENT_WIDTH: f64 = PAGE_WIDTH - 2.0 * MARGIN;
+
+const BLACK: Color = (17, 24, 39);
+const GRAY: Color = (75, 85, 99);
+const LINK: Color = (37, 99, 235);
+const RULE: Color = (209, 213, 219);
+const SHADE: Color = (243, 244, 246);
+
+/// Helvetica advance widths for ' '..='~', in 1/1000 em
+const HELVETICA: [u16; 95] = [
+    278, 278, 355, 556, 556, 889, 667, 191, 333, 333, 389, 584, 278, 333, 278, 278, 556, 556, 556,
+    556, 556, 556, 556, 556, 556, 556, 278, 278, 584, 584, 584, 556, 1015, 667, 667, 722, 722, 667,
+    611, 778, 722, 278, 500, 667, 556, 833, 722, 778, 667, 778, 722, 667, 611, 722, 667, 944, 667,
+    667, 611, 278, 278, 278, 469, 556, 333, 556, 556, 500, 556, 556, 278, 556, 556, 222, 222, 500,
+    222, 833, 556, 556, 556, 556, 333, 500, 278, 556, 500, 722, 500, 500, 500, 334, 260, 334, 584,
+];
+
+/// Helvetica-Bold advance widths for ' '..='~', in 1/1000 em
+const HELVETICA_BOLD: [u16; 95] = [
+    278, 333, 474, 556, 556, 889, 722, 238, 333, 333, 389, 584, 278, 333, 278, 278, 556, 556, 556,
+    556, 556, 556, 556, 556, 556, 556, 333, 333, 584, 584, 584, 611, 975, 722, 722, 722, 722, 667,
+    611, 778, 722, 278, 556, 722, 611, 833, 722, 778, 667, 778, 722, 667, 611, 722, 667, 944, 667,
+    667, 611, 333, 278, 333, 584, 556, 333, 556, 611, 556, 611, 556, 333, 611, 611, 278, 278, 556,
+    278, 889, 611, 611, 611, 611, 389, 556, 333, 611, 556, 778, 556, 556, 500, 389, 280, 389, 584,
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Font {
+    Regular,
+    Bold,
+    Italic,
+    BoldItalic,
+    Mono,
+}
+
+impl Font {
+    const ALL: [Font; 5] = [
+        Font::Regular,
+        Font::Bold,
+        Font::Italic,
+        Font::BoldItalic,
+        Font::Mono,
+    ];
+
+    fn of(style: Style) -> Self {
+        match (style.code, style.bold, style.italic) {
+            (true, _, _) => Font::Mono,
+            (false, true, true) => Font::BoldItalic,
+            (false, true, false) => Font::Bold,
+            (false, false, true) => Font::Italic,
+            (false, false, false) => Font::Regular,
+        }
+    }
+
+    /// Resource name in page content
+    fn resource(self) -> &'static str {
+        match self {
+            Font::Regular => "F1",
+            Font::Bold => "F2",
+            Font::Italic => "F3",
+            Font::BoldItalic => "F4",
+            Font::Mono => "F5",
+        }
+    }
+
+    fn base_font(self) -> &'static str {
+        match self {
+            Font::Regular => "Helvetica",
+            Font::Bold => "Helvetica-Bold",
+            Font::Italic => "Helvetica-Oblique",
+            Font::BoldItalic => "Helvetica-BoldOblique",
+            Font::Mono => "Courier",
+        }
+    }
+
+    /// Width of `text` at `size` points
+    fn width(self, text: &str, size: f64) -> f64 {
+        let units: u32 = text
+            .chars()
+            .map(|c| {
+                let widths = match self {
+                    Font::Mono => return 600,
+                    Font::Regular | Font::Italic => &HELVETICA,
+                    Font::Bold | Font::BoldItalic => &HELVETICA_BOLD,
+                };
+                match c {
+                    ' '..='~' => u32::from(widths[c as usize - 32]),
+                    '•' => 350,
+                    '—' => 1000,
+                    _ => 556,
+                }
+            })
+            .sum();
+        f64::from(units) * size / 1000.0
+    }
+}
+
+/// Windows-1252 byte for `c`, or `?`
+fn win_ansi(c: char) -> u8 {
+    match c {
+        ' '..='~' | '\u{a0}'..='\u{ff}' => c as u8,
+        '€' => 0x80,
+        '‘' => 0x91,
+        '’' => 0x92,
+        '“' => 0x93,
+        '”' => 0x94,
+        '•' => 0x95,
+        '–' => 0x96,
+        '—' => 0x97,
+        '…' => 0x85,
+        _ => b'?',
+    }
+}
+
+/// `text` as a PDF literal string
+fn pdf_string(text: &str) -> String {
+    let mut out = String::with_capacity(text.len() + 2);
+    out.push('(');
+    for c in text.chars() {
+        match win_ansi(c) {
+            b @ (b'(' | b')' | b'\\') => {
+                out.push('\\');
+                out.push(b as char);
+            }
+            b @ 0x20..=0x7e => out.push(b as char),
+            b => {
+                let _ = write!(out, "\\{b:03o}");
+            }
+        }
+    }
+    out.push(')');
+    out
+}
+
+fn color_op((r, g, b): Color, op: &str) -> String {
+    format!(
+        "{:.3} {:.3} {:.3} {op}",
+        f64::from(r) / 255.0,
+        f64::from(g) / 255.0,
+        f64::from(b) / 255.0
+    )
+}
+
+/// Consecutive characters with one font
+struct Run {
+    text: String,
+    font: Font,
+    link: bool,
+}
+
+/// Runs that may not be broken across lines
+struct Word {
+    runs: Vec<Run>,
+    space_before: bool,
+}
+
+impl Word {
+    fn width(&self, size: f64) -> f64 {
+        self.runs.iter().map(|r| r.font.width(&r.text, size)).sum()
+    }
+
+    /// Split into pieces no wider than `max`, for words longer than a line
+    fn split(self, size: f64, max: f64) -> Vec<Word> {
+        let mut pieces = vec![Word {
+            runs: Vec::new(),
+            space_before: self.space_before,
+        }];
+        let mut width = 0.0;
+        for run in self.runs {
+            for c in run.text.chars() {
+                let w = run.font.width(c.encode_utf8(&mut [0; 4]), size);
+                if width + w > max && width > 0.0 {
+                    pieces.push(Word {
+                        runs: Vec::new(),
+                        space_before: false,
+                    });
+                    width = 0.0;
+                }
+                width += w;
+                push_char(&mut pieces.last_mut().unwrap().runs, c, run.font, run.link);
+            }
+        }
+        pieces
+    }
+}
+
+fn push_char(runs: &mut Vec<Run>, c: char, font: Font, link: bool) {
+    match runs.last_mut() {
+        Some(run) if run.font == font && run.link == link => run.text.push(c),
+        _ => runs.push(Run {
+            text: c.to_string(),
+            font,
+            link,
+        }),
+    }
+}
+
+/// Words of `spans`, with `base` added to every span's style
+fn words(spans: &[Span], base: Style) -> Vec<Word> {
+    let mut words: Vec<Word> = Vec::new();
+    let (mut in_word, mut space) = (false, false);
+    for span in spans {
+        let font = Font::of(Style {
+            bold: span.style.bold || base.bold,
+            italic: span.style.italic || base.italic,
+            code: span.style.code || base.code,
+        });
+        for c in span.text.chars() {
+            if c.is_whitespace() {
+                in_word = false;
+                space = true;
+                continue;
+            }
+            if !in_word {
+                words.push(Word {
+                    runs: Vec::new(),
+                    space_before: space && !words.is_empty(),
+                });
+                in_word = true;
+                space = false;
+            }
+            push_char(
+                &mut words.last_mut().unwrap().runs,
+                c,
+                font,
+                span.link.is_some(),
+            );
+        }
+    }
+    words
+}
+
+/// Break words into lines no wider than `width`
+fn lines(words: Vec<Word>, size: f64, width: f64) -> Vec<Vec<Word>> {
+    let space = Font::Regular.width(" ", size);
+    let mut lines: Vec<Vec<Word>> = vec![Vec::new()];
+    let mut line_width = 0.0;
+    for word in words {
+        let pieces = if word.width(size) > width {
+            word.split(size, width)
+        } else {
+            vec![word]
+        };
+        for word in pieces {
+            let w = word.width(size);
+            let line = lines.last_mut().unwrap();
+            let gap = if word.space_before && !line.is_empty() {
+                space
+            } else {
+                0.0
+            };
+            if !line.is_empty() && line_width + gap + w > width {
+                lines.push(vec![word]);
+                line_width = w;
+            } else {
+                line_width += gap + w;
+                line.push(word);
+            }
+        }
+    }
+    lines
+}
+
+/// How a paragraph of text is set
+#[derive(Clone, Copy)]
+struct TextStyle {
+    size: f64,
+    leading: f64,
+    base: Style,
+    color: Color,
+    /// Draw a quote bar left of each line
+    bar: bool,
+}
+
+impl TextStyle {
+    fn body() -> Self {
+        Self {
+            size: 11.0,
+            leading: 15.0,
+            base: Style::default(),
+            color: BLACK,
+            bar: false,
+        }
+    }
+}
+
+/// Pages being laid out, top to bottom
+struct Layout {
+    pages: Vec<String>,
+    ops: String,
+    /// Top of the free space on the current page
+    y: f64,
+}
+
+impl Layout {
+    fn new() -> Self {
+        Self {
+            pages: Vec::new(),
+            ops: String::new(),
+            y: PAGE_HEIGHT - MARGIN,
+        }
+    }
+
+    /// Start a new page unless `height` fits on this one
+    fn ensure(&mut self, height: f64) {
+        if self.y - height < MARGIN && self.y < PAGE_HEIGHT - MARGIN {
+            self.pages.push(std::mem::take(&mut self.ops));
+            self.y = PAGE_HEIGHT - MARGIN;
+        }
+    }
+
+    fn finish(mut self) -> Vec<String> {
+        self.pages.push(self.ops);
+        self.pages
+    }
+
+    fn text(&mut self, x: f64, y: f64, font: Font, size: f64, color: Color, text: &str) {
+        let _ = writeln!(
+            self.ops,
+            "BT /{} {size:.2} Tf {} {x:.2} {y:.2} Td {} Tj ET",
+            font.resource(),
+            color_op(color, "rg"),
+            pdf_string(text)
+        );
+    }
+
+    fn rect(&mut self, x: f64, y: f64, w: f64, h: f64, color: Color) {
+        let _ = writeln!(
+            self.ops,
+            "{} {x:.2} {y:.2} {w:.2} {h:.2} re f",
+            color_op(color, "rg")
+        );
+    }
+
+    fn stroke(&mut self, points: &[(f64, f64)], color: Color, width: f64) {
+        let Some(((x0, y0), rest)) = points.split_first() else {
+            return;
+        };
+        let _ = write!(
+            self.ops,
+            "{} {width:.2} w {x0:.2} {y0:.2} m",
+            color_op(color, "RG")
+        );
+        for (x, y) in rest {
+            let _ = write!(self.ops, " {x:.2} {y:.2} l");
+        }
+        self.ops.push_str(" S\n");
+    }
+
+    /// Set `spans` from `x` across `width`, breaking lines and pages
+    fn paragraph(&mut self, spans: &[Span], x: f64, width: f64, style: TextStyle) {
+        let space = Font::Regular.width(" ", style.size);
+        for line in lines(words(spans, style.base), style.size, width) {
+            self.ensure(style.leading);
+            let baseline = self.y - style.size;
+            if style.bar {
+                self.rect(x - 12.0, self.y - style.leading, 3.0, style.leading, RULE);
+            }
+            let mut cursor = x;
+            for (n, word) in line.iter().enumerate() {
+                if word.space_before && n > 0 {
+                    cursor += space;
+                }
+                for run in &word.runs {
+                    let w = run.font.width(&run.text, style.size);
+                    let color = if run.link { LINK } else { style.color };
+                    self.text(cursor, baseline, run.font, style.size, color, &run.text);
+                    if run.link {
+                        self.stroke(
+                            &[(cursor, baseline - 1.5), (cursor + w, baseline - 1.5)],
+                            LINK,
+                            0.5,
+                        );
+                    }
+                    cursor += w;
+                }
+            }
+            self.y -= style.leading;
+        }
+    }
+
+    fn header(&mut self, report: &Report) {
+        if let Some(title) = &report.title {
+            let title = [Span {
+                text: title.clone(),
+                style: Style::default(),
+                link: None,
+            }];
+            let style = TextStyle {
+                size: 24.0,
+                leading: 30.0,
+                base: Style {
+                    bold: true,
+                    ..Style::default()
+                },
+                ..TextStyle::body()
+            };
+            self.paragraph(&title, MARGIN, CONTENT_WIDTH, style);
+            self.y -= 6.0;
+        }
+        for (key, value) in &report.metadata {
+            let spans = [
+                Span {
+                    text: format!("{key}: "),
+                    style: Style {
+                        bold: true,
+                        ..Style::default()
+                    },
+                    link: None,
+                },
+                Span {
+                    text: value.clone(),
+                    style: Style::default(),
+                    link: None,
+                },
+            ];
+            let style = TextStyle {
+                size: 10.0,
+                leading: 14.0,
+                color: GRAY,
+                ..TextStyle::body()
+            };
+            self.paragraph(&spans, MARGIN, CONTENT_WIDTH, style);
+        }
+        if report.title.is_some() || !report.metadata.is_empty() {
+            self.y -= 8.0;
+            self.stroke(
+                &[(MARGIN, self.y), (MARGIN + CONTENT_WIDTH, self.y)],
+                RULE,
+                1.5,
+            );
+            self.y -= 18.0;
+        }
+    }
+
+    fn block(&mut self, block: &Block, report: &Report) {
+        match block {
+            Block::Heading(level, spans) => {
+                let size = [20.0, 16.0, 14.0, 12.0, 11.0, 11.0][usize::from(*level) - 1];
+                self.y -= size * 0.5;
+                // Keep the heading with the first lines after it
+                self.ensure(size * 1.3 + 30.0);
+                let style = TextStyle {
+                    size,
+                    leading: size * 1.3,
+                    base: Style {
+                        bold: true,
+                        ..Style::default()
+                    },
+                    ..TextStyle::body()
+                };
+                self.paragraph(spans, MARGIN, CONTENT_WIDTH, style);
+                self.y -= 4.0;
+            }
+            Block::Paragraph(spans) => {
+                self.paragraph(spans, MARGIN, CONTENT_WIDTH, TextStyle::body());
+                self.y -= 8.0;
+            }
+            Block::List { start, items } => {
+                let style = TextStyle::body();
+                for (n, item) in items.iter().enumerate() {
+                    let marker = match start {
+                        Some(first) => format!("{}.", first + n as u64),
+                        None => "•".to_string(),
+                    };
+                    self.ensure(style.leading);
+                    let baseline = self.y - style.size;
+                    self.text(
+                        MARGIN + 4.0,
+                        baseline,
+                        Font::Regular,
+                        style.size,
+                        BLACK,
+                        &marker,
+                    );
+                    self.paragraph(item, MARGIN + 22.0, CONTENT_WIDTH - 22.0, style);
+                    self.y -= 2.0;
+                }
+                self.y -= 6.0;
+            }
+            Block::Quote(spans) => {
+                let style = TextStyle {
+                    base: Style {
+                        italic: true,
+                        ..Style::default()
+                    },
+                    color: GRAY,
+                    bar: true,
+                    ..TextStyle::body()
+                };
+                self.paragraph(spans, MARGIN + 16.0, CONTENT_WIDTH - 16.0, style);
+                self.y -= 8.0;
+            }
+            Block::Code(code) => {
+                let (size, leading, pad) = (9.0, 12.0, 6.0);
+                let columns = ((CONTENT_WIDTH - 2.0 * pad) / (size * 0.6)).floor() as usize;
+                let mut first = true;
+                for line in code.replace('\t', "    ").lines() {
+                    let chars: Vec<char> = line.chars().collect();
+                    for chunk in chars.chunks(columns.max(1)).chain(
+                        // Keep empty lines
+                        chars.is_empty().then_some(&[][..]),
+                    ) {
+                        let top = if first { pad } else { 0.0 };
+                        self.ensure(leading + top);
+                        if first {
+                            self.rect(MARGIN, self.y - pad, CONTENT_WIDTH, pad, SHADE);
+                            self.y -= pad;
+                            first = false;
+                        }
+                        self.rect(MARGIN, self.y - leading, CONTENT_WIDTH, leading, SHADE);
+                        let text: String = chunk.iter().collect();
+                        self.text(MARGIN + pad, self.y - size, Font::Mono, size, BLACK, &text);
+                        self.y -= leading;
+                    }
+                }
+                if !first {
+                    self.rect(MARGIN, self.y - pad, CONTENT_WIDTH, pad, SHADE);
+                    self.y -= pad;
+                }
+                self.y -= 10.0;
+            }
+            Block::Rule => {
+                self.ensure(20.0);
+                self.y -= 10.0;
+                self.stroke(
+                    &[(MARGIN, self.y), (MARGIN + CONTENT_WIDTH, self.y)],
+                    RULE,
+                    1.0,
+                );
+                self.y -= 10.0;
+            }
+            Block::Table { header, rows } => {
+                let columns = rows
+                    .iter()
+                    .map(Vec::len)
+                    .chain([header.len()])
+                    .max()
+                    .unwrap_or(1);
+                self.table_row(header, columns, true);
+                for row in rows {
+                    self.table_row(row, columns, false);
+                }
+                self.y -= 10.0;
+            }
+            Block::Chart(id) => {
+                if let Some(chart) = report.charts.get(id) {
+                    self.chart(&chart.shapes());
+                }
+            }
+        }
+    }
+
+    fn table_row(&mut self, cells: &[Vec<Span>], columns: usize, header: bool) {
+        let (size, leading, pad) = (10.0, 13.0, 4.0);
+        let width = CONTENT_WIDTH / columns as f64;
+        let base = Style {
+            bold: header,
+            ..Style::default()
+        };
+        let cell_lines: Vec<_> = (0..columns)
+            .map(|n| {
+                let spans = cells.get(n).map(Vec::as_slice).unwrap_or_default();
+                lines(words(spans, base), size, width - 2.0 * pad)
+            })
+            .collect();
+        let height =
+            cell_lines.iter().map(Vec::len).max().unwrap_or(1) as f64 * leading + 2.0 * pad;
+        self.ensure(height);
+        let top = self.y;
+        let space = Font::Regular.width(" ", size);
+        for (n, lines) in cell_lines.iter().enumerate() {
+            let x = MARGIN + width * n as f64;
+            if header {
+                self.rect(x, top - height, width, height, SHADE);
+            }
+            let border = [
+                (x, top),
+                (x + width, top),
+                (x + width, top - height),
+                (x, top - height),
+                (x, top),
+            ];
+            self.stroke(&border, RULE, 0.75);
+            for (row, line) in lines.iter().enumerate() {
+                let baseline = top - pad - leading * row as f64 - size;
+                let mut cursor = x + pad;
+                for (i, word) in line.iter().enumerate() {
+                    if word.space_before && i > 0 {
+                        cursor += space;
+                    }
+                    for run in &word.runs {
+                        let color = if run.link { LINK } else { BLACK };
+                        self.text(cursor, baseline, run.font, size, color, &run.text);
+                        cursor += run.font.width(&run.text, size);
+                    }
+                }
+            }
+        }
+        self.y -= height;
+    }
+
+    fn chart(&mut self, shapes: &[Shape]) {
+        let scale = CONTENT_WIDTH / WIDTH;
+        let height = HEIGHT * scale;
+        self.ensure(height + 12.0);
+        let top = self.y;
+        let at = |x: f64, y: f64| (MARGIN + x * scale, top - y * scale);
+        for shape in shapes {
+            match shape {
+                Shape::Rect { x, y, w, h, color } => {
+                    let (px, py) = at(*x, y + h);
+                    self.rect(px, py, w * scale, h * scale, *color);
+                }
+                Shape::Line {
+                    points,
+                    color,
+                    width,
+                } => {
+                    let points: Vec<_> = points.iter().map(|&(x, y)| at(x, y)).collect();
+                    self.stroke(&points, *color, width * scale);
+                }
+                Shape::Text {
+                    x,
+                    y,
+                    text,
+                    size,
+                    anchor,
+                    color,
+                } => {
+                    let size = size * scale;
+                    let width = Font::Regular.width(text, size);
+                    let (px, py) = at(*x, *y);
+                    let px = match anchor {
+                        Anchor::Start => px,
+                        Anchor::Middle => px - width / 2.0,
+                        Anchor::End => px - width,
+                    };
+                    self.text(px, py, Font::Regular, size, *color, text);
+                }
+            }
+        }
+        self.y -= height + 12.0;
+    }
+}
+
+/// The report as a PDF file
+pub(super) fn document(report: &Report) -> Vec<u8> {
+    let mut layout = Layout::new();
+    layout.header(report);
+    for block in &report.blocks {
+        layout.block(block, report);
+    }
+    let mut pages = layout.finish();
+
+    let count = pages.len();
+    for (n, ops) in pages.iter_mut().enumerate() {
+        let footer = format!("{} / {count}", n + 1);
+        let width = Font::Regular.width(&footer, 9.0);
+        let _ = writeln!(
+            ops,
+            "BT /F1 9 Tf {} {:.2} {:.2} Td {} Tj ET",
+            color_op(GRAY, "rg"),
+            (PAGE_WIDTH - width) / 2.0,
+            MARGIN / 2.0,
+            pdf_string(&footer)
+        );
+    }
+    serialize(&pages, report.title.as_deref().unwrap_or("Report"))
+}
+
+/// Write the PDF objects, cross-reference table and trailer
+fn serialize(pages: &[String], title: &str) -> Vec<u8> {
+    // 1: catalog, 2: page tree, 3: info, then fonts, then a content stream and a page
+    // per page
+    let first_font = 4;
+    let first_page = first_font + Font::ALL.len();
+    let mut objects: Vec<String> = vec![
+        "<< /Type /Catalog /Pages 2 0 R >>".to_string(),
+        format!(
+            "<< /Type /Pages /Kids [{}] /Count {} >>",
+            (0..pages.len())
+                .map(|n| format!("{} 0 R", first_page + 2 * n + 1))
+                .collect::<Vec<_>>()
+                .join(" "),
+            pages.len()
+        ),
+        format!("<< /Title {} /Producer (Loom) >>", pdf_string(title)),
+    ];
+    objects.extend(Font::ALL.iter().map(|font| {
+        format!(
+            "<< /Type /Font /Subtype /Type1 /BaseFont /{} /Encoding /WinAnsiEncoding >>",
+            font.base_font()
+        )
+    }));
+    let fonts: String = Font::ALL
+        .iter()
+        .enumerate()
+        .map(|(n, font)| format!("/{} {} 0 R ", font.resource(), first_font + n))
+        .collect();
+    for (n, ops) in pages.iter().enumerate() {
+        objects.push(format!(
+            "<< /Length {} >>\nstream\n{ops}endstream",
+            ops.len()
+        ));
+        objects.push(format!(
+            "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {PAGE_WIDTH} {PAGE_HEIGHT}] \
+             /Resources << /Font << {fonts}>> >> /Contents {} 0 R >>",
+            first_page + 2 * n
+        ));
+    }
+
+    // Content is ASCII apart from the binary marker comment
+    let mut pdf = b"%PDF-1.4\n%\xe2\xe3\xcf\xd3\n".to_vec();
+    let mut offsets = Vec::with_capacity(objects.len());
+    for (n, object) in objects.iter().enumerate() {
+        offsets.push(pdf.len());
+        pdf.extend_from_slice(format!("{} 0 obj\n{object}\nendobj\n", n + 1).as_bytes());
+    }
+    let xref = pdf.len();
+    let mut trailer = format!("xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1);
+    for offset in offsets {
+        let _ = writeln!(trailer, "{offset:010} 00000 n ");
+    }
+    let _ = write!(
+        trailer,
+        "trailer\n<< /Size {} /Root 1 0 R /Info 3 0 R >>\nstartxref\n{xref}\n%%EOF\n",
+        objects.len() + 1
+    );
+    pdf.extend_from_slice(trailer.as_bytes());
+    pdf
+}
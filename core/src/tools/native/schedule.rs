@@ -0,0 +1,319 @@
+//! Reminders agents schedule for themselves: `schedule:remind`, `schedule:list`,
+//! `schedule:cancel`
+//!
+//! A reminder is delivered to the calling agent's private reply topic at the requested
+//! time (see `agent::reminders`). Times are given as for the `time:*` tools (ISO 8601,
+//! Unix timestamps or natural language such as `in 20 minutes`) or as `in_secs`. Every
+//! call works on the calling agent's own reminders; there are none outside an agent.
+
+use crate::agent::reminders::{Reminder, ReminderScheduler};
+use crate::tools::native::time::{describe_time, parse_time, parse_timezone};
+use crate::tools::{caller_agent_id, Tool, ToolError, ToolResult};
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use chrono_tz::Tz;
+use serde_json::{json, Value};
+use std::sync::Arc;
+use tracing::debug;
+
+/// All three schedule tools over `reminders`, with `default_tz` for local times
+pub fn schedule_tools(reminders: Arc<ReminderScheduler>, default_tz: Tz) -> Vec<Arc<dyn Tool>> {
+    vec![
+        Arc::new(ScheduleRemindTool::new(Arc::clone(&reminders), default_tz)),
+        Arc::new(ScheduleListTool::new(Arc::clone(&reminders), default_tz)),
+        Arc::new(ScheduleCancelTool::new(reminders)),
+    ]
+}
+
+/// The calling agent, which reminders belong to
+fn caller() -> ToolResult<String> {
+    caller_agent_id().ok_or_else(|| {
+        ToolError::InvalidArguments("reminders can only be managed by an agent".to_string())
+    })
+}
+
+fn timezone_arg(arguments: &Value, default: Tz) -> ToolResult<Tz> {
+    match arguments["timezone"].as_str() {
+        Some(name) => parse_timezone(name),
+        None => Ok(default),
+    }
+}
+
+/// Fire time from `when` or `in_secs`, which must lie in the future
+fn fire_at_arg(arguments: &Value, tz: Tz) -> ToolResult<DateTime<Tz>> {
+    let now = Utc::now().with_timezone(&tz);
+    let fire_at = match (arguments["when"].as_str(), arguments.get("in_secs")) {
+        (Some(text), None | Some(Value::Null)) => parse_time(text, &now).ok_or_else(|| {
+            ToolError::InvalidArguments(format!("Could not parse time '{}'", text))
+        })?,
+        (None, Some(secs)) if !secs.is_null() => {
+            let secs = secs.as_u64().filter(|s| *s > 0).ok_or_else(|| {
+                ToolError::InvalidArguments("'in_secs' must be a positive integer".to_string())
+            })?;
+            i64::try_from(secs)
+                .ok()
+                .and_then(Duration::try_seconds)
+                .and_then(|delay| now.checked_add_signed(delay))
+                .ok_or_else(|| ToolError::InvalidArguments("'in_secs' is too large".to_string()))?
+        }
+        _ => {
+            return Err(ToolError::InvalidArguments(
+                "Exactly one of 'when' and 'in_secs' is required".to_string(),
+            ))
+        }
+    };
+    if fire_at <= now {
+        return Err(ToolError::InvalidArguments(format!(
+            "{} is in the past",
+            fire_at.to_rfc3339()
+        )));
+    }
+    Ok(fire_at)
+}
+
+fn describe_reminder(reminder: &Reminder, tz: Tz, now_ms: i64) -> Value {
+    let fire_at = tz.timestamp_millis_opt(reminder.fire_at_ms).single();
+    json!({
+        "reminder_id": reminder.id,
+        "message": reminder.message,
+        "data": reminder.data,
+        "event_type": reminder.event_type,
+        "fire_at": fire_at.map(|t| t.to_rfc3339()),
+        "fire_at_ms": reminder.fire_at_ms,
+        "in_secs": (reminder.fire_at_ms - now_ms).max(0) / 1000,
+    })
+}
+
+// ─── schedule:remind ────────────────────────────────────────────────────────
+
+/// Schedule an event to the calling agent
+pub struct ScheduleRemindTool {
+    reminders: Arc<ReminderScheduler>,
+    default_tz: Tz,
+}
+
+impl ScheduleRemindTool {
+    pub fn new(reminders: Arc<ReminderScheduler>, default_tz: Tz) -> Self {
+        Self {
+            reminders,
+            default_tz,
+        }
+    }
+}
+
+#[async_trait]
+impl Tool for ScheduleRemindTool {
+    fn name(&self) -> String {
+        "schedule:remind".to_string()
+    }
+
+    fn description(&self) -> String {
+        "Schedule a reminder to yourself, delivered as an event at a future time ('remind me in 20 minutes')".to_string()
+    }
+
+    fn parameters(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "message": {
+                    "type": "string",
+                    "description": "What to be reminded of"
+                },
+                "when": {
+                    "type": "string",
+                    "description": "When: ISO 8601, Unix timestamp, or natural language like 'in 20 minutes' or 'tomorrow 9am'"
+                },
+                "in_secs": {
+                    "type": "integer",
+                    "description": "Seconds from now, instead of 'when'"
+                },
+                "timezone": {
+                    "type": "string",
+                    "description": format!("IANA timezone for local times (default: {})", self.default_tz.name())
+                },
+                "data": { "description": "Any JSON to hand back with the reminder" },
+                "event_type": {
+                    "type": "string",
+                    "description": "Type of the delivered event (default: schedule.reminder)"
+                }
+            },
+            "required": ["message"]
+        })
+    }
+
+    fn output_schema(&self) -> Option<Value> {
+        Some(json!({
+            "type": "object",
+            "properties": {
+                "reminder_id": { "type": "string" },
+                "fire_at": { "type": "object" },
+                "now": { "type": "object" },
+                "in_secs": { "type": "integer" }
+            },
+            "required": ["reminder_id", "fire_at", "now", "in_secs"]
+        }))
+    }
+
+    async fn call(&self, arguments: Value) -> ToolResult<Value> {
+        let agent_id = caller()?;
+        let message = arguments["message"]
+            .as_str()
+            .filter(|m| !m.is_empty())
+            .ok_or_else(|| ToolError::InvalidArguments("Missing 'message'".to_string()))?;
+        let tz = timezone_arg(&arguments, self.default_tz)?;
+        let fire_at = fire_at_arg(&arguments, tz)?;
+
+        let mut reminder = Reminder::new(&agent_id, fire_at.with_timezone(&Utc), message)
+            .with_data(arguments.get("data").cloned().unwrap_or(Value::Null));
+        if let Some(event_type) = arguments["event_type"].as_str() {
+            reminder = reminder.with_event_type(event_type);
+        }
+        let reminder = self
+            .reminders
+            .schedule(reminder)
+            .map_err(|e| ToolError::ExecutionFailed(e.to_string()))?;
+        debug!(target: "reminders", agent_id = %agent_id, reminder_id = %reminder.id, "schedule:remind");
+
+        let now = Utc::now().with_timezone(&tz);
+        Ok(json!({
+            "reminder_id": reminder.id,
+            "fire_at": describe_time(&fire_at),
+            "now": describe_time(&now),
+            "in_secs": fire_at.signed_duration_since(now).num_seconds().max(0),
+        }))
+    }
+}
+
+// ─── schedule:list ──────────────────────────────────────────────────────────
+
+/// Pending reminders of the calling agent
+pub struct ScheduleListTool {
+    reminders: Arc<ReminderScheduler>,
+    default_tz: Tz,
+}
+
+impl ScheduleListTool {
+    pub fn new(reminders: Arc<ReminderScheduler>, default_tz: Tz) -> Self {
+        Self {
+            reminders,
+            default_tz,
+        }
+    }
+}
+
+#[async_trait]
+impl Tool for ScheduleListTool {
+    fn name(&self) -> String {
+        "schedule:list".to_string()
+    }
+
+    fn description(&self) -> String {
+        "List your pending reminders, soonest first, with the current time".to_string()
+    }
+
+    fn parameters(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "timezone": {
+                    "type": "string",
+                    "description": format!("IANA timezone for the times shown (default: {})", self.default_tz.name())
+                }
+            }
+        })
+    }
+
+    fn output_schema(&self) -> Option<Value> {
+        Some(json!({
+            "type": "object",
+            "properties": {
+                "now": { "type": "object" },
+                "reminders": { "type": "array", "items": { "type": "object" } },
+                "count": { "type": "integer" }
+            },
+            "required": ["now", "reminders", "count"]
+        }))
+    }
+
+    async fn call(&self, arguments: Value) -> ToolResult<Value> {
+        let agent_id = caller()?;
+        let tz = timezone_arg(&arguments, self.default_tz)?;
+        let now = Utc::now().with_timezone(&tz);
+        let reminders: Vec<Value> = self
+            .reminders
+            .pending(&agent_id)
+            .iter()
+            .map(|reminder| describe_reminder(reminder, tz, now.timestamp_millis()))
+            .collect();
+
+        Ok(json!({
+            "now": describe_time(&now),
+            "count": reminders.len(),
+            "reminders": reminders,
+        }))
+    }
+}
+
+// ─── schedule:cancel ────────────────────────────────────────────────────────
+
+/// Cancel a pending reminder of the calling agent
+pub struct ScheduleCancelTool {
+    reminders: Arc<ReminderScheduler>,
+}
+
+impl ScheduleCancelTool {
+    pub fn new(reminders: Arc<ReminderScheduler>) -> Self {
+        Self { reminders }
+    }
+}
+
+#[async_trait]
+impl Tool for ScheduleCancelTool {
+    fn name(&self) -> String {
+        "schedule:cancel".to_string()
+    }
+
+    fn description(&self) -> String {
+        "Cancel one of your pending reminders".to_string()
+    }
+
+    fn parameters(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "reminder_id": {
+                    "type": "string",
+                    "description": "Id returned by schedule:remind"
+                }
+            },
+            "required": ["reminder_id"]
+        })
+    }
+
+    fn output_schema(&self) -> Option<Value> {
+        Some(json!({
+            "type": "object",
+            "properties": {
+                "reminder_id": { "type": "string" },
+                "cancelled": { "type": "boolean" }
+            },
+            "required": ["reminder_id", "cancelled"]
+        }))
+    }
+
+    async fn call(&self, arguments: Value) -> ToolResult<Value> {
+        let agent_id = caller()?;
+        let reminder_id = arguments["reminder_id"]
+            .as_str()
+            .ok_or_else(|| ToolError::InvalidArguments("Missing 'reminder_id'".to_string()))?;
+        let cancelled = self
+            .reminders
+            .cancel(&agent_id, reminder_id)
+            .map_err(|e| ToolError::ExecutionFailed(e.to_string()))?;
+
+        Ok(json!({
+            "reminder_id": reminder_id,
+            "cancelled": cancelled,
+        }))
+    }
+}
@@ -1,15 +1,201 @@
+//! `system:shell` — run allowlisted commands inside a configurable sandbox
+//!
+//! Commands and their arguments are passed to the OS as-is, without shell parsing.
+//! With CPU or memory limits on Unix, the command is started through
+//! `sh -c 'ulimit ... && exec "$0" "$@"'`: `sh` sets the limits and replaces itself
+//! with the command, which stays in its positional parameters. Otherwise it is
+//! executed directly. On top of the command allowlist, `ShellSandbox` can:
+//!
+//! - confine commands to a working directory: they run there (or in a `cwd` below it),
+//!   and arguments naming paths outside it are rejected, including `--flag=path` and
+//!   `-Xpath` option values. Symlinks are resolved for the parts of a path that exist,
+//!   so a link inside the directory pointing out of it is rejected too; links the
+//!   command itself creates while running are not seen.
+//! - scrub the environment down to a few passthrough variables.
+//! - limit wall-clock time, CPU seconds and address space. On timeout the command's
+//!   whole process group is killed (Unix) and `timed_out` set. CPU and memory limits
+//!   use `ulimit` on Unix and are ignored elsewhere; cgroups are not supported, so
+//!   there is no limit on the total of a command's children.
+//! - cap the captured stdout/stderr; the excess is read and discarded, and the stream's
+//!   `*_truncated` flag set.
+
 use crate::tools::{Tool, ToolError, ToolResult};
 use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
+use std::path::{Component, Path, PathBuf};
+use std::process::Stdio;
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncRead, AsyncReadExt};
 use tokio::process::Command;
+use tokio::task::JoinHandle;
+use tracing::{debug, warn};
+
+/// Variables kept by default when the environment is scrubbed
+const DEFAULT_ENV_PASSTHROUGH: [&str; 4] = ["PATH", "LANG", "LC_ALL", "TERM"];
+
+/// How long output is still read after the process exits (a leftover background
+/// process may hold its pipes open)
+const OUTPUT_DRAIN_GRACE: Duration = Duration::from_secs(1);
+
+/// Sandbox applied to every command `ShellTool` runs
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ShellSandbox {
+    /// Directory commands are confined to; None runs them in the process's working
+    /// directory without confinement
+    pub working_dir: Option<PathBuf>,
+    /// Start commands with an empty environment plus `env_passthrough`
+    pub scrub_env: bool,
+    /// Variables copied from this process when `scrub_env` is set
+    pub env_passthrough: Vec<String>,
+    /// Wall-clock limit in milliseconds
+    pub timeout_ms: u64,
+    /// CPU time limit in seconds (Unix only)
+    pub cpu_secs: Option<u64>,
+    /// Address space limit in bytes (Unix only)
+    pub memory_bytes: Option<u64>,
+    /// Bytes of stdout and of stderr kept in the result
+    pub max_output_bytes: usize,
+}
+
+impl Default for ShellSandbox {
+    fn default() -> Self {
+        Self {
+            working_dir: None,
+            scrub_env: false,
+            env_passthrough: DEFAULT_ENV_PASSTHROUGH
+                .iter()
+                .map(|name| name.to_string())
+                .collect(),
+            timeout_ms: 30_000,
+            cpu_secs: None,
+            memory_bytes: None,
+            max_output_bytes: 1024 * 1024,
+        }
+    }
+}
+
+impl ShellSandbox {
+    /// Reads `LOOM_SHELL_WORKDIR`, `LOOM_SHELL_SCRUB_ENV`, `LOOM_SHELL_TIMEOUT_MS`,
+    /// `LOOM_SHELL_CPU_SECS`, `LOOM_SHELL_MEMORY_MB` and `LOOM_SHELL_MAX_OUTPUT_BYTES`
+    pub fn from_env() -> Self {
+        fn parsed<T: std::str::FromStr>(name: &str) -> Option<T> {
+            std::env::var(name).ok().and_then(|v| v.trim().parse().ok())
+        }
+        let defaults = Self::default();
+        Self {
+            working_dir: std::env::var("LOOM_SHELL_WORKDIR")
+                .ok()
+                .filter(|dir| !dir.trim().is_empty())
+                .map(PathBuf::from),
+            scrub_env: parsed("LOOM_SHELL_SCRUB_ENV").unwrap_or(defaults.scrub_env),
+            timeout_ms: parsed("LOOM_SHELL_TIMEOUT_MS").unwrap_or(defaults.timeout_ms),
+            cpu_secs: parsed("LOOM_SHELL_CPU_SECS"),
+            memory_bytes: parsed::<u64>("LOOM_SHELL_MEMORY_MB").map(|mb| mb * 1024 * 1024),
+            max_output_bytes: parsed("LOOM_SHELL_MAX_OUTPUT_BYTES")
+                .unwrap_or(defaults.max_output_bytes),
+            ..defaults
+        }
+    }
+}
 
 pub struct ShellTool {
     allowed_commands: Vec<String>,
+    sandbox: ShellSandbox,
 }
 
 impl ShellTool {
     pub fn new(allowed_commands: Vec<String>) -> Self {
-        Self { allowed_commands }
+        Self {
+            allowed_commands,
+            sandbox: ShellSandbox::default(),
+        }
+    }
+
+    /// Run commands inside `sandbox`
+    pub fn with_sandbox(mut self, sandbox: ShellSandbox) -> Self {
+        self.sandbox = sandbox;
+        self
+    }
+
+    pub fn sandbox(&self) -> &ShellSandbox {
+        &self.sandbox
+    }
+
+    /// Working directory for a call's `cwd` argument, and the jail it must stay in
+    fn resolve_cwd(&self, cwd: Option<&str>) -> ToolResult<(PathBuf, Option<PathBuf>)> {
+        let base = std::env::current_dir().unwrap_or_default();
+        let Some(jail) = &self.sandbox.working_dir else {
+            let dir = cwd.map(|cwd| base.join(cwd)).unwrap_or(base);
+            return Ok((dir, None));
+        };
+        let jail = resolve(&base.join(jail));
+        let dir = match cwd {
+            Some(cwd) => resolve(&jail.join(cwd)),
+            None => jail.clone(),
+        };
+        if !dir.starts_with(&jail) {
+            return Err(ToolError::PermissionDenied(format!(
+                "Working directory '{}' is outside the sandbox",
+                cwd.unwrap_or_default()
+            )));
+        }
+        Ok((dir, Some(jail)))
+    }
+
+    fn command(&self, program: &str, args: &[String], dir: &Path) -> Command {
+        let mut command = match self.limits() {
+            Some(limits) if cfg!(unix) => {
+                // `sh` only sets the limits, then replaces itself with the command;
+                // the command and its arguments stay positional parameters
+                let mut command = Command::new("sh");
+                command
+                    .arg("-c")
+                    .arg(format!("{limits} && exec \"$0\" \"$@\""))
+                    .arg(program)
+                    .args(args);
+                command
+            }
+            limits => {
+                if limits.is_some() {
+                    warn!(target: "shell", "CPU and memory limits are only supported on Unix");
+                }
+                let mut command = Command::new(program);
+                command.args(args);
+                command
+            }
+        };
+        command
+            .current_dir(dir)
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true);
+        // Own process group, so a timeout also kills whatever the command started
+        #[cfg(unix)]
+        command.process_group(0);
+        if self.sandbox.scrub_env {
+            command.env_clear();
+            for name in &self.sandbox.env_passthrough {
+                if let Ok(value) = std::env::var(name) {
+                    command.env(name, value);
+                }
+            }
+        }
+        command
+    }
+
+    /// `ulimit` commands for the configured CPU and memory limits
+    fn limits(&self) -> Option<String> {
+        let mut limits = Vec::new();
+        if let Some(secs) = self.sandbox.cpu_secs {
+            limits.push(format!("ulimit -t {}", secs.max(1)));
+        }
+        if let Some(bytes) = self.sandbox.memory_bytes {
+            limits.push(format!("ulimit -v {}", (bytes / 1024).max(1)));
+        }
+        (!limits.is_empty()).then(|| limits.join(" && "))
     }
 }
 
@@ -35,12 +221,39 @@ impl Tool for ShellTool {
                     "type": "array",
                     "items": { "type": "string" },
                     "description": "Arguments for the command"
+                },
+                "cwd": {
+                    "type": "string",
+                    "description": "Working directory, relative to the sandbox directory"
                 }
             },
             "required": ["command"]
         })
     }
 
+    fn output_schema(&self) -> Option<Value> {
+        Some(json!({
+            "type": "object",
+            "properties": {
+                "stdout": { "type": "string" },
+                "stderr": { "type": "string" },
+                "exit_code": {
+                    "type": ["integer", "null"],
+                    "description": "Null if the process was killed by a signal"
+                },
+                "signal": {
+                    "type": ["integer", "null"],
+                    "description": "Signal that killed the process (Unix)"
+                },
+                "timed_out": { "type": "boolean" },
+                "stdout_truncated": { "type": "boolean" },
+                "stderr_truncated": { "type": "boolean" },
+                "duration_ms": { "type": "integer" }
+            },
+            "required": ["stdout", "stderr", "exit_code"]
+        }))
+    }
+
     async fn call(&self, arguments: Value) -> ToolResult<Value> {
         let command_name = arguments["command"]
             .as_str()
@@ -62,16 +275,167 @@ impl Tool for ShellTool {
             )));
         }
 
-        let output = Command::new(command_name)
-            .args(&args)
-            .output()
-            .await
+        let (dir, jail) = self.resolve_cwd(arguments["cwd"].as_str())?;
+        if let Some(jail) = &jail {
+            if let Some(arg) = args.iter().find(|arg| escapes(jail, &dir, arg)) {
+                return Err(ToolError::PermissionDenied(format!(
+                    "Argument '{}' refers to a path outside the sandbox",
+                    arg
+                )));
+            }
+        }
+
+        debug!(target: "shell", command = %command_name, dir = %dir.display(), "Running command");
+        let start = Instant::now();
+        let mut child = self
+            .command(command_name, &args, &dir)
+            .spawn()
             .map_err(|e| ToolError::ExecutionFailed(format!("Failed to execute command: {}", e)))?;
 
+        let cap = self.sandbox.max_output_bytes;
+        let stdout = tokio::spawn(read_capped(child.stdout.take(), cap));
+        let stderr = tokio::spawn(read_capped(child.stderr.take(), cap));
+
+        let timeout = Duration::from_millis(self.sandbox.timeout_ms);
+        let (status, timed_out) = match tokio::time::timeout(timeout, child.wait()).await {
+            Ok(status) => (status, false),
+            Err(_) => {
+                warn!(target: "shell", command = %command_name, timeout_ms = self.sandbox.timeout_ms, "Command timed out; killing it");
+                kill_group(&mut child);
+                (child.wait().await, true)
+            }
+        };
+        let status = status.map_err(|e| {
+            ToolError::ExecutionFailed(format!("Failed to wait for command: {}", e))
+        })?;
+        let (stdout, stdout_truncated) = collect(stdout).await;
+        let (stderr, stderr_truncated) = collect(stderr).await;
+
         Ok(json!({
-            "stdout": String::from_utf8_lossy(&output.stdout),
-            "stderr": String::from_utf8_lossy(&output.stderr),
-            "exit_code": output.status.code()
+            "stdout": String::from_utf8_lossy(&stdout),
+            "stderr": String::from_utf8_lossy(&stderr),
+            "exit_code": status.code(),
+            "signal": exit_signal(&status),
+            "timed_out": timed_out,
+            "stdout_truncated": stdout_truncated,
+            "stderr_truncated": stderr_truncated,
+            "duration_ms": start.elapsed().as_millis() as u64
         }))
     }
 }
+
+/// Read `stream` to its end, keeping the first `cap` bytes; returns them and whether
+/// anything was dropped
+async fn read_capped<R: AsyncRead + Unpin>(stream: Option<R>, cap: usize) -> (Vec<u8>, bool) {
+    let Some(mut stream) = stream else {
+        return (Vec::new(), false);
+    };
+    let mut kept = Vec::new();
+    let mut truncated = false;
+    let mut buf = [0u8; 8192];
+    loop {
+        match stream.read(&mut buf).await {
+            Ok(0) | Err(_) => break,
+            Ok(n) => {
+                let room = cap.saturating_sub(kept.len());
+                kept.extend_from_slice(&buf[..n.min(room)]);
+                truncated |= n > room;
+            }
+        }
+    }
+    (kept, truncated)
+}
+
+/// Output read by `reader`, waiting at most `OUTPUT_DRAIN_GRACE` for the pipe to close
+async fn collect(mut reader: JoinHandle<(Vec<u8>, bool)>) -> (Vec<u8>, bool) {
+    match tokio::time::timeout(OUTPUT_DRAIN_GRACE, &mut reader).await {
+        Ok(Ok(output)) => output,
+        Ok(Err(_)) => (Vec::new(), false),
+        Err(_) => {
+            reader.abort();
+            (Vec::new(), true)
+        }
+    }
+}
+
+#[cfg(unix)]
+fn exit_signal(status: &std::process::ExitStatus) -> Option<i32> {
+    use std::os::unix::process::ExitStatusExt;
+    status.signal()
+}
+
+#[cfg(not(unix))]
+fn exit_signal(_status: &std::process::ExitStatus) -> Option<i32> {
+    None
+}
+
+/// Kill `child` and the rest of its process group
+#[cfg(unix)]
+fn kill_group(child: &mut tokio::process::Child) {
+    if let Some(pid) = child.id() {
+        // SAFETY: plain syscall; `pid` leads the group created by `process_group(0)`
+        unsafe {
+            libc::killpg(pid as libc::pid_t, libc::SIGKILL);
+        }
+    }
+    let _ = child.start_kill();
+}
+
+#[cfg(not(unix))]
+fn kill_group(child: &mut tokio::process::Child) {
+    let _ = child.start_kill();
+}
+
+/// Symlinks followed while resolving one path before giving up (Linux's limit)
+const MAX_SYMLINKS: usize = 40;
+
+/// Resolve `.`, `..` and symlinks in `path` as far as it exists; the missing rest is
+/// resolved lexically. `..` at the root stays at the root.
+fn resolve(path: &Path) -> PathBuf {
+    resolve_with(path, &mut 0)
+}
+
+fn resolve_with(path: &Path, followed: &mut usize) -> PathBuf {
+    let mut resolved = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => continue,
+            Component::ParentDir => {
+                resolved.pop();
+                continue;
+            }
+            other => resolved.push(other),
+        }
+        if let Ok(real) = resolved.canonicalize() {
+            resolved = real;
+        } else if let Ok(target) = std::fs::read_link(&resolved) {
+            // A dangling link: where it would point once the command creates the target
+            if *followed < MAX_SYMLINKS {
+                *followed += 1;
+                resolved.pop();
+                let target = resolved.join(target);
+                resolved = resolve_with(&target, followed);
+            }
+        }
+    }
+    resolved
+}
+
+/// Whether `arg`, read as a path relative to `dir`, lies outside `jail`. Option values
+/// are checked as well: `--flag=path`, and `path` in a short option's `-Xpath`.
+fn escapes(jail: &Path, dir: &Path, arg: &str) -> bool {
+    let mut candidates = vec![arg];
+    if let Some(option) = arg.strip_prefix('-') {
+        if let Some((_, value)) = arg.split_once('=') {
+            candidates.push(value);
+        }
+        if !option.starts_with('-') {
+            if let Some((at, _)) = option.char_indices().nth(1) {
+                candidates.push(&option[at..]);
+            }
+        }
+    }
+    candidates
+        .into_iter()
+        .any(|value| !resolve(&dir.join(value)).starts_with(jail))
+}
@@ -0,0 +1,800 @@
+//! Object storage for agents: `storage:put`, `storage:get`, `storage:list`,
+//! `storage:presign`.
+//!
+//! Lets agents persist artifacts (reports, generated audio, exports) in an S3-compatible
+//! bucket and hand out links to them. Only buckets on the allowlist are reachable, and
+//! objects larger than `max_bytes` are neither written nor read.
+//!
+//! Content moves to and from the blob store (`crate::blob`) by reference:
+//! `storage:put` takes a `blob_ref` the caller may read instead of `content`, and
+//! `storage:get` with `as_blob` stores the object as a blob owned by the caller and
+//! returns its `blob_ref` instead of the content.
+
+use crate::blob::{BlobAccess, BlobStore};
+use crate::s3::{check_status, object_path, S3Client};
+use crate::tools::native::blob::{
+    content_argument, encode_content, encoding_property, readers_argument,
+};
+use crate::tools::{caller_agent_id, Tool, ToolError, ToolResult};
+use crate::{LoomError, Result};
+use async_trait::async_trait;
+use serde_json::{json, Value};
+use std::sync::Arc;
+use tracing::debug;
+
+/// Longest object key S3 accepts, in bytes
+const MAX_KEY_BYTES: usize = 1024;
+
+/// Most keys `storage:list` returns per call
+const MAX_LIST_KEYS: u64 = 1000;
+
+/// Object storage settings
+#[derive(Debug, Clone)]
+pub struct StorageConfig {
+    /// Base URL of the service, e.g. `https://s3.eu-west-1.amazonaws.com` or
+    /// `http://localhost:9000`
+    pub endpoint: String,
+    /// Base URL presigned links point at, when clients reach the service under another
+    /// address than Loom does; defaults to `endpoint`
+    pub public_endpoint: Option<String>,
+    pub region: String,
+    pub access_key: String,
+    pub secret_key: String,
+    /// Buckets the tools may use; no others are reachable
+    pub buckets: Vec<String>,
+    /// Largest object written or read
+    pub max_bytes: u64,
+    /// Longest lifetime of a presigned link
+    pub max_presign_secs: u64,
+}
+
+impl StorageConfig {
+    pub fn new(
+        endpoint: impl Into<String>,
+        access_key: impl Into<String>,
+        secret_key: impl Into<String>,
+    ) -> Self {
+        Self {
+            endpoint: endpoint.into(),
+            public_endpoint: None,
+            region: "us-east-1".to_string(),
+            access_key: access_key.into(),
+            secret_key: secret_key.into(),
+            buckets: Vec::new(),
+            max_bytes: 64 * 1024 * 1024,
+            // Longest a SigV4 presigned URL may live
+            max_presign_secs: 7 * 24 * 3600,
+        }
+    }
+
+    pub fn with_buckets<I, S>(mut self, buckets: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.buckets = buckets.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Settings from `LOOM_STORAGE_S3_ENDPOINT`, `LOOM_STORAGE_S3_PUBLIC_ENDPOINT`,
+    /// `LOOM_STORAGE_S3_REGION`, `LOOM_STORAGE_S3_ACCESS_KEY`,
+    /// `LOOM_STORAGE_S3_SECRET_KEY`, `LOOM_STORAGE_BUCKETS` (comma-separated),
+    /// `LOOM_STORAGE_MAX_BYTES` and `LOOM_STORAGE_PRESIGN_MAX_SECS`; `Ok(None)` unless
+    /// an endpoint is set
+    pub fn from_env() -> Result<Option<Self>> {
+        let Ok(endpoint) = std::env::var("LOOM_STORAGE_S3_ENDPOINT") else {
+            return Ok(None);
+        };
+        let var = |name: &str| {
+            std::env::var(name).map_err(|_| {
+                LoomError::ConfigError(format!("{name} is required with LOOM_STORAGE_S3_ENDPOINT"))
+            })
+        };
+        let number = |name: &str| match std::env::var(name) {
+            Ok(value) => value.trim().parse::<u64>().map(Some).map_err(|_| {
+                LoomError::ConfigError(format!("{name} must be a number, got {value:?}"))
+            }),
+            Err(_) => Ok(None),
+        };
+        let mut config = Self::new(
+            endpoint,
+            var("LOOM_STORAGE_S3_ACCESS_KEY")?,
+            var("LOOM_STORAGE_S3_SECRET_KEY")?,
+        )
+        .with_buckets(
+            var("LOOM_STORAGE_BUCKETS")?
+                .split(',')
+                .map(str::trim)
+                .filter(|b| !b.is_empty()),
+        );
+        if config.buckets.is_empty() {
+            return Err(LoomError::ConfigError(
+                "LOOM_STORAGE_BUCKETS must list at least one bucket".to_string(),
+            ));
+        }
+        config.public_endpoint = std::env::var("LOOM_STORAGE_S3_PUBLIC_ENDPOINT").ok();
+        if let Ok(region) = std::env::var("LOOM_STORAGE_S3_REGION") {
+            config.region = region;
+        }
+        if let Some(max) = number("LOOM_STORAGE_MAX_BYTES")? {
+            config.max_bytes = max;
+        }
+        if let Some(max) = number("LOOM_STORAGE_PRESIGN_MAX_SECS")? {
+            config.max_presign_secs = max;
+        }
+        Ok(Some(config))
+    }
+}
+
+/// One object of a `list` result
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ObjectInfo {
+    pub key: String,
+    pub size: u64,
+    pub last_modified: String,
+}
+
+/// A page of `list` results
+#[derive(Debug, Clone, Default)]
+pub struct ObjectListing {
+    pub objects: Vec<ObjectInfo>,
+    /// Pass to the next `list` call for the following page
+    pub next_token: Option<String>,
+}
+
+/// The allowlisted buckets of one S3-compatible service
+pub struct ObjectStorage {
+    config: StorageConfig,
+    client: S3Client,
+    presigner: S3Client,
+}
+
+impl ObjectStorage {
+    pub fn new(config: StorageConfig) -> Result<Self> {
+        let client = |endpoint: &str| {
+            S3Client::new(
+                endpoint,
+                config.region.clone(),
+                config.access_key.clone(),
+                config.secret_key.clone(),
+            )
+        };
+        let presigner = client(
+            config
+                .public_endpoint
+                .as_deref()
+                .unwrap_or(&config.endpoint),
+        )?;
+        Ok(Self {
+            client: client(&config.endpoint)?,
+            presigner,
+            config,
+        })
+    }
+
+    pub fn config(&self) -> &StorageConfig {
+        &self.config
+    }
+
+    /// Whether `bucket` is on the allowlist
+    pub fn allows_bucket(&self, bucket: &str) -> bool {
+        self.config.buckets.iter().any(|b| b == bucket)
+    }
+
+    pub async fn put(
+        &self,
+        bucket: &str,
+        key: &str,
+        content: Vec<u8>,
+        content_type: &str,
+    ) -> Result<()> {
+        if content.len() as u64 > self.config.max_bytes {
+            return Err(LoomError::StorageError(format!(
+                "Object of {} bytes exceeds the limit of {} bytes",
+                content.len(),
+                self.config.max_bytes
+            )));
+        }
+        let resp = self
+            .client
+            .send(
+                reqwest::Method::PUT,
+                &object_path(bucket, key),
+                &[],
+                content,
+                Some(content_type),
+            )
+            .await?;
+        check_status(resp, "PUT", key).await.map(drop)
+    }
+
+    /// Content and content type of an object; `None` if it does not exist
+    pub async fn get(&self, bucket: &str, key: &str) -> Result<Option<(Vec<u8>, String)>> {
+        let resp = self
+            .client
+            .send(
+                reqwest::Method::GET,
+                &object_path(bucket, key),
+                &[],
+                Vec::new(),
+                None,
+            )
+            .await?;
+        if resp.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        let resp = check_status(resp, "GET", key).await?;
+        let too_large = || {
+            LoomError::StorageError(format!(
+                "Object {key} exceeds the limit of {} bytes",
+                self.config.max_bytes
+            ))
+        };
+        if resp
+            .content_length()
+            .is_some_and(|len| len > self.config.max_bytes)
+        {
+            return Err(too_large());
+        }
+        let content_type = resp
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or_default()
+            .to_string();
+        let bytes = resp
+            .bytes()
+            .await
+            .map_err(|e| LoomError::StorageError(format!("S3 GET {key} failed: {e}")))?;
+        if bytes.len() as u64 > self.config.max_bytes {
+            return Err(too_large());
+        }
+        Ok(Some((bytes.to_vec(), content_type)))
+    }
+
+    /// Up to `max_keys` objects whose keys start with `prefix`, from where `token` left off
+    pub async fn list(
+        &self,
+        bucket: &str,
+        prefix: &str,
+        max_keys: u64,
+        token: Option<&str>,
+    ) -> Result<ObjectListing> {
+        let max_keys = max_keys.to_string();
+        let mut query = vec![
+            ("list-type", "2"),
+            ("max-keys", max_keys.as_str()),
+            ("prefix", prefix),
+        ];
+        if let Some(token) = token {
+            query.push(("continuation-token", token));
+        }
+        let resp = self
+            .client
+            .send(
+                reqwest::Method::GET,
+                &object_path(bucket, ""),
+                &query,
+                Vec::new(),
+                None,
+            )
+            .await?;
+        let resp = check_status(resp, "LIST", bucket).await?;
+        let body = resp
+            .text()
+            .await
+            .map_err(|e| LoomError::StorageError(format!("S3 LIST {bucket} failed: {e}")))?;
+        Ok(parse_listing(&body))
+    }
+
+    /// Link allowing a `method` (`GET` or `PUT`) request for the object for
+    /// `expires_secs`, capped at `max_presign_secs`; returns the link and its lifetime
+    pub fn presign(
+        &self,
+        method: &str,
+        bucket: &str,
+        key: &str,
+        expires_secs: u64,
+    ) -> (String, u64) {
+        let expires_secs = expires_secs.clamp(1, self.config.max_presign_secs.max(1));
+        let url = self.presigner.presign(
+            method,
+            &object_path(bucket, key),
+            expires_secs,
+            chrono::Utc::now(),
+        );
+        (url, expires_secs)
+    }
+}
+
+/// Objects and continuation token of a ListObjectsV2 response
+fn parse_listing(xml: &str) -> ObjectListing {
+    let objects = xml_elements(xml, "Contents")
+        .into_iter()
+        .filter_map(|contents| {
+            Some(ObjectInfo {
+                key: xml_value(contents, "Key")?,
+                size: xml_value(contents, "Size")?.parse().ok()?,
+                last_modified: xml_value(contents, "LastModified").unwrap_or_default(),
+            })
+        })
+        .collect();
+    let truncated = xml_value(xml, "IsTruncated").is_some_and(|t| t == "true");
+    ObjectListing {
+        objects,
+        next_token: xml_value(xml, "NextContinuationToken").filter(|_| truncated),
+    }
+}
+
+/// Inner text of each `<tag>` element of `xml`
+fn xml_elements<'a>(xml: &'a str, tag: &str) -> Vec<&'a str> {
+    let (open, close) = (format!("<{tag}>"), format!("</{tag}>"));
+    let mut elements = Vec::new();
+    let mut rest = xml;
+    while let Some(start) = rest.find(&open) {
+        rest = &rest[start + open.len()..];
+        let Some(end) = rest.find(&close) else {
+            break;
+        };
+        elements.push(&rest[..end]);
+        rest = &rest[end + close.len()..];
+    }
+    elements
+}
+
+/// Unescaped text of the first `<tag>` element of `xml`
+fn xml_value(xml: &str, tag: &str) -> Option<String> {
+    let text = xml_elements(xml, tag).into_iter().next()?;
+    Some(
+        text.replace("&lt;", "<")
+            .replace("&gt;", ">")
+            .replace("&quot;", "\"")
+            .replace("&apos;", "'")
+            .replace("&amp;", "&"),
+    )
+}
+
+fn storage_error(e: LoomError) -> ToolError {
+    ToolError::ExecutionFailed(e.to_string())
+}
+
+/// The `bucket` argument, if it is on the allowlist
+fn bucket_argument<'a>(storage: &ObjectStorage, arguments: &'a Value) -> ToolResult<&'a str> {
+    let bucket = arguments["bucket"]
+        .as_str()
+        .filter(|b| !b.is_empty())
+        .ok_or_else(|| ToolError::InvalidArguments("Missing 'bucket' argument".to_string()))?;
+    if !storage.allows_bucket(bucket) {
+        return Err(ToolError::PermissionDenied(format!(
+            "Bucket {bucket} is not on the storage allowlist"
+        )));
+    }
+    Ok(bucket)
+}
+
+fn key_argument(arguments: &Value) -> ToolResult<&str> {
+    let key = arguments["key"]
+        .as_str()
+        .filter(|k| !k.is_empty())
+        .ok_or_else(|| ToolError::InvalidArguments("Missing 'key' argument".to_string()))?;
+    if key.len() > MAX_KEY_BYTES || key.starts_with('/') {
+        return Err(ToolError::InvalidArguments(format!(
+            "Invalid object key '{key}'"
+        )));
+    }
+    Ok(key)
+}
+
+/// `storage:put`, `storage:get`, `storage:list` and `storage:presign` over `storage`;
+/// with `blobs`, content can also move by blob reference
+pub fn storage_tools(
+    storage: Arc<ObjectStorage>,
+    blobs: Option<Arc<BlobStore>>,
+) -> Vec<Arc<dyn Tool>> {
+    vec![
+        Arc::new(StoragePutTool::new(Arc::clone(&storage), blobs.clone())),
+        Arc::new(StorageGetTool::new(Arc::clone(&storage), blobs)),
+        Arc::new(StorageListTool::new(Arc::clone(&storage))),
+        Arc::new(StoragePresignTool::new(storage)),
+    ]
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// storage:put
+// ─────────────────────────────────────────────────────────────────────────────
+
+pub struct StoragePutTool {
+    storage: Arc<ObjectStorage>,
+    blobs: Option<Arc<BlobStore>>,
+}
+
+impl StoragePutTool {
+    pub fn new(storage: Arc<ObjectStorage>, blobs: Option<Arc<BlobStore>>) -> Self {
+        Self { storage, blobs }
+    }
+}
+
+#[async_trait]
+impl Tool for StoragePutTool {
+    fn name(&self) -> String {
+        "storage:put".to_string()
+    }
+
+    fn description(&self) -> String {
+        "Write an object to a storage bucket, from content or a blob reference".to_string()
+    }
+
+    fn parameters(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "bucket": { "type": "string", "enum": self.storage.config().buckets },
+                "key": { "type": "string", "description": "Object key, e.g. reports/2024-06.md" },
+                "content": { "type": "string", "description": "Content to write" },
+                "encoding": encoding_property(),
+                "blob_ref": {
+                    "type": "string",
+                    "description": "Write the content of this blob instead of `content`"
+                },
+                "content_type": {
+                    "type": "string",
+                    "description": "Media type of the content, e.g. text/markdown"
+                }
+            },
+            "required": ["bucket", "key"]
+        })
+    }
+
+    fn output_schema(&self) -> Option<Value> {
+        Some(json!({
+            "type": "object",
+            "properties": {
+                "bucket": { "type": "string" },
+                "key": { "type": "string" },
+                "size": { "type": "integer" },
+                "content_type": { "type": "string" }
+            },
+            "required": ["bucket", "key", "size"]
+        }))
+    }
+
+    async fn call(&self, arguments: Value) -> ToolResult<Value> {
+        let bucket = bucket_argument(&self.storage, &arguments)?;
+        let key = key_argument(&arguments)?;
+        let mut content_type = arguments["content_type"]
+            .as_str()
+            .unwrap_or_default()
+            .to_string();
+        let content = match arguments["blob_ref"].as_str() {
+            Some(id) => {
+                let store = self.blobs.as_ref().ok_or_else(|| {
+                    ToolError::InvalidArguments("No blob store is configured".to_string())
+                })?;
+                let blob = store
+                    .blob_ref(id)
+                    .await
+                    .map_err(storage_error)?
+                    .ok_or_else(|| ToolError::ExecutionFailed(format!("Blob {id} not found")))?;
+                let (content, access) = store.load(&blob).await.map_err(storage_error)?;
+                let caller = caller_agent_id();
+                if !access.allows(caller.as_deref(), None) {
+                    return Err(ToolError::PermissionDenied(format!(
+                        "{} may not read blob {id}",
+                        caller.as_deref().unwrap_or("a call outside an agent")
+                    )));
+                }
+                if content_type.is_empty() {
+                    content_type = blob.content_type;
+                }
+                content
+            }
+            None => content_argument(&arguments)?,
+        };
+        let size = content.len();
+        self.storage
+            .put(bucket, key, content, &content_type)
+            .await
+            .map_err(storage_error)?;
+        debug!(target: "storage", bucket = %bucket, key = %key, size, "storage:put");
+
+        Ok(json!({
+            "bucket": bucket,
+            "key": key,
+            "size": size,
+            "content_type": content_type,
+        }))
+    }
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// storage:get
+// ─────────────────────────────────────────────────────────────────────────────
+
+pub struct StorageGetTool {
+    storage: Arc<ObjectStorage>,
+    blobs: Option<Arc<BlobStore>>,
+}
+
+impl StorageGetTool {
+    pub fn new(storage: Arc<ObjectStorage>, blobs: Option<Arc<BlobStore>>) -> Self {
+        Self { storage, blobs }
+    }
+}
+
+#[async_trait]
+impl Tool for StorageGetTool {
+    fn name(&self) -> String {
+        "storage:get".to_string()
+    }
+
+    fn description(&self) -> String {
+        "Read an object from a storage bucket, as content or as a blob reference".to_string()
+    }
+
+    fn parameters(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "bucket": { "type": "string", "enum": self.storage.config().buckets },
+                "key": { "type": "string" },
+                "encoding": {
+                    "type": "string",
+                    "enum": ["text", "base64"],
+                    "description": "How to return `content` (default: text when it is UTF-8, else base64)"
+                },
+                "as_blob": {
+                    "type": "boolean",
+                    "description": "Store the object as a blob and return its `blob_ref` instead of the content"
+                },
+                "readers": {
+                    "type": "array",
+                    "items": { "type": "string" },
+                    "description": "With as_blob: agent ids allowed to read the blob (default: any agent)"
+                }
+            },
+            "required": ["bucket", "key"]
+        })
+    }
+
+    fn output_schema(&self) -> Option<Value> {
+        Some(json!({
+            "type": "object",
+            "properties": {
+                "bucket": { "type": "string" },
+                "key": { "type": "string" },
+                "size": { "type": "integer" },
+                "content_type": { "type": "string" },
+                "encoding": { "type": "string" },
+                "content": { "type": "string" },
+                "blob_ref": { "type": "string" }
+            },
+            "required": ["bucket", "key", "size"]
+        }))
+    }
+
+    async fn call(&self, arguments: Value) -> ToolResult<Value> {
+        let bucket = bucket_argument(&self.storage, &arguments)?;
+        let key = key_argument(&arguments)?;
+        let (content, content_type) = self
+            .storage
+            .get(bucket, key)
+            .await
+            .map_err(storage_error)?
+            .ok_or_else(|| {
+                ToolError::ExecutionFailed(format!("Object {bucket}/{key} not found"))
+            })?;
+        let size = content.len();
+        debug!(target: "storage", bucket = %bucket, key = %key, size, "storage:get");
+
+        if arguments["as_blob"].as_bool().unwrap_or(false) {
+            let store = self.blobs.as_ref().ok_or_else(|| {
+                ToolError::InvalidArguments("No blob store is configured".to_string())
+            })?;
+            let access = BlobAccess::owned_by(caller_agent_id().unwrap_or_default())
+                .with_readers(readers_argument(&arguments));
+            let blob = store
+                .put(content, &content_type, access)
+                .await
+                .map_err(storage_error)?;
+            return Ok(json!({
+                "bucket": bucket,
+                "key": key,
+                "size": size,
+                "content_type": content_type,
+                "blob_ref": blob.id,
+            }));
+        }
+
+        let (encoding, content) =
+            encode_content(&arguments, content, &format!("Object {bucket}/{key}"))?;
+        Ok(json!({
+            "bucket": bucket,
+            "key": key,
+            "size": size,
+            "content_type": content_type,
+            "encoding": encoding,
+            "content": content,
+        }))
+    }
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// storage:list
+// ─────────────────────────────────────────────────────────────────────────────
+
+pub struct StorageListTool {
+    storage: Arc<ObjectStorage>,
+}
+
+impl StorageListTool {
+    pub fn new(storage: Arc<ObjectStorage>) -> Self {
+        Self { storage }
+    }
+}
+
+#[async_trait]
+impl Tool for StorageListTool {
+    fn name(&self) -> String {
+        "storage:list".to_string()
+    }
+
+    fn description(&self) -> String {
+        "List the objects in a storage bucket".to_string()
+    }
+
+    fn parameters(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "bucket": { "type": "string", "enum": self.storage.config().buckets },
+                "prefix": { "type": "string", "description": "Only keys starting with this prefix" },
+                "max_keys": {
+                    "type": "integer",
+                    "minimum": 1,
+                    "maximum": MAX_LIST_KEYS,
+                    "description": "Most objects to return (default: 100)"
+                },
+                "next_token": {
+                    "type": "string",
+                    "description": "Token from a previous call, for the next page"
+                }
+            },
+            "required": ["bucket"]
+        })
+    }
+
+    fn output_schema(&self) -> Option<Value> {
+        Some(json!({
+            "type": "object",
+            "properties": {
+                "bucket": { "type": "string" },
+                "objects": {
+                    "type": "array",
+                    "items": {
+                        "type": "object",
+                        "properties": {
+                            "key": { "type": "string" },
+                            "size": { "type": "integer" },
+                            "last_modified": { "type": "string" }
+                        }
+                    }
+                },
+                "count": { "type": "integer" },
+                "next_token": { "type": ["string", "null"] }
+            },
+            "required": ["bucket", "objects", "count"]
+        }))
+    }
+
+    async fn call(&self, arguments: Value) -> ToolResult<Value> {
+        let bucket = bucket_argument(&self.storage, &arguments)?;
+        let prefix = arguments["prefix"].as_str().unwrap_or_default();
+        let max_keys = arguments["max_keys"]
+            .as_u64()
+            .unwrap_or(100)
+            .clamp(1, MAX_LIST_KEYS);
+        let listing = self
+            .storage
+            .list(bucket, prefix, max_keys, arguments["next_token"].as_str())
+            .await
+            .map_err(storage_error)?;
+        let objects: Vec<Value> = listing
+            .objects
+            .iter()
+            .map(|o| json!({ "key": o.key, "size": o.size, "last_modified": o.last_modified }))
+            .collect();
+
+        Ok(json!({
+            "bucket": bucket,
+            "count": objects.len(),
+            "objects": objects,
+            "next_token": listing.next_token,
+        }))
+    }
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// storage:presign
+// ─────────────────────────────────────────────────────────────────────────────
+
+pub struct StoragePresignTool {
+    storage: Arc<ObjectStorage>,
+}
+
+impl StoragePresignTool {
+    pub fn new(storage: Arc<ObjectStorage>) -> Self {
+        Self { storage }
+    }
+}
+
+#[async_trait]
+impl Tool for StoragePresignTool {
+    fn name(&self) -> String {
+        "storage:presign".to_string()
+    }
+
+    fn description(&self) -> String {
+        "Create a temporary link to download (or upload) an object without credentials".to_string()
+    }
+
+    fn parameters(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "bucket": { "type": "string", "enum": self.storage.config().buckets },
+                "key": { "type": "string" },
+                "method": {
+                    "type": "string",
+                    "enum": ["GET", "PUT"],
+                    "description": "GET to download (default), PUT to upload"
+                },
+                "expires_secs": {
+                    "type": "integer",
+                    "minimum": 1,
+                    "description": format!(
+                        "Lifetime of the link (default: 3600, at most {})",
+                        self.storage.config().max_presign_secs
+                    )
+                }
+            },
+            "required": ["bucket", "key"]
+        })
+    }
+
+    fn output_schema(&self) -> Option<Value> {
+        Some(json!({
+            "type": "object",
+            "properties": {
+                "url": { "type": "string" },
+                "method": { "type": "string" },
+                "expires_secs": { "type": "integer" },
+                "expires_at_ms": { "type": "integer" }
+            },
+            "required": ["url", "method", "expires_secs", "expires_at_ms"]
+        }))
+    }
+
+    async fn call(&self, arguments: Value) -> ToolResult<Value> {
+        let bucket = bucket_argument(&self.storage, &arguments)?;
+        let key = key_argument(&arguments)?;
+        let method = match arguments["method"].as_str().unwrap_or("GET") {
+            m if m.eq_ignore_ascii_case("get") => "GET",
+            m if m.eq_ignore_ascii_case("put") => "PUT",
+            other => {
+                return Err(ToolError::InvalidArguments(format!(
+                    "Unsupported method '{other}'"
+                )))
+            }
+        };
+        let requested = arguments["expires_secs"].as_u64().unwrap_or(3600);
+        let (url, expires_secs) = self.storage.presign(method, bucket, key, requested);
+        debug!(target: "storage", bucket = %bucket, key = %key, method, expires_secs, "storage:presign");
+
+        Ok(json!({
+            "url": url,
+            "method": method,
+            "expires_secs": expires_secs,
+            "expires_at_ms": chrono::Utc::now().timestamp_millis() + expires_secs as i64 * 1000,
+        }))
+    }
+}
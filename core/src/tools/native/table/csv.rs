@@ -0,0 +1,350 @@
+//! Delimited text (CSV, TSV) parsed into typed columns.
+//!
+//! Records follow RFC 4180: fields may be quoted, a doubled quote inside quotes is a
+//! literal quote, and quoted fields may span lines. Each column gets the narrowest type
+//! all of its non-empty values fit: integer, float, boolean, else string. Empty fields
+//! are null.
+
+use crate::tools::{ToolError, ToolResult};
+use serde_json::{json, Value};
+use std::cmp::Ordering;
+use std::fmt;
+
+/// Most columns a table may have
+pub(super) const MAX_COLUMNS: usize = 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum ColumnType {
+    Integer,
+    Float,
+    Boolean,
+    String,
+}
+
+impl ColumnType {
+    pub(super) fn name(self) -> &'static str {
+        match self {
+            ColumnType::Integer => "integer",
+            ColumnType::Float => "float",
+            ColumnType::Boolean => "boolean",
+            ColumnType::String => "string",
+        }
+    }
+
+    pub(super) fn is_numeric(self) -> bool {
+        matches!(self, ColumnType::Integer | ColumnType::Float)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(super) struct Column {
+    pub name: String,
+    pub kind: ColumnType,
+}
+
+impl Column {
+    pub(super) fn to_json(&self) -> Value {
+        json!({ "name": self.name, "type": self.kind.name() })
+    }
+}
+
+/// One value of a table
+#[derive(Debug, Clone, PartialEq)]
+pub(super) enum Cell {
+    Null,
+    Int(i64),
+    Float(f64),
+    Bool(bool),
+    Text(String),
+}
+
+impl Cell {
+    pub(super) fn is_null(&self) -> bool {
+        matches!(self, Cell::Null)
+    }
+
+    pub(super) fn as_f64(&self) -> Option<f64> {
+        match self {
+            Cell::Int(n) => Some(*n as f64),
+            Cell::Float(x) => Some(*x),
+            _ => None,
+        }
+    }
+
+    pub(super) fn to_json(&self) -> Value {
+        match self {
+            Cell::Null => Value::Null,
+            Cell::Int(n) => json!(n),
+            Cell::Float(x) => json!(x),
+            Cell::Bool(b) => json!(b),
+            Cell::Text(s) => json!(s),
+        }
+    }
+
+    /// Order of two non-null values: numbers numerically, booleans false first, anything
+    /// else by its text
+    pub(super) fn compare(&self, other: &Cell) -> Ordering {
+        match (self, other) {
+            (Cell::Int(a), Cell::Int(b)) => a.cmp(b),
+            (Cell::Bool(a), Cell::Bool(b)) => a.cmp(b),
+            (Cell::Text(a), Cell::Text(b)) => a.cmp(b),
+            _ => match (self.as_f64(), other.as_f64()) {
+                (Some(a), Some(b)) => a.total_cmp(&b),
+                _ => self.to_string().cmp(&other.to_string()),
+            },
+        }
+    }
+}
+
+impl fmt::Display for Cell {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Cell::Null => Ok(()),
+            Cell::Int(n) => write!(f, "{n}"),
+            Cell::Float(x) => write!(f, "{x}"),
+            Cell::Bool(b) => write!(f, "{b}"),
+            Cell::Text(s) => f.write_str(s),
+        }
+    }
+}
+
+/// A parsed table: typed columns and one cell per column in every row
+#[derive(Debug, Clone)]
+pub(super) struct Table {
+    pub columns: Vec<Column>,
+    pub rows: Vec<Vec<Cell>>,
+}
+
+impl Table {
+    /// Parse `text`; with `header` the first record names the columns, otherwise they
+    /// are `column_1`, `column_2`, ...
+    pub(super) fn parse(text: &str, delimiter: char, header: bool) -> ToolResult<Self> {
+        let mut records = records(text.strip_prefix('\u{feff}').unwrap_or(text), delimiter)?;
+        let names = if header && !records.is_empty() {
+            records.remove(0)
+        } else {
+            Vec::new()
+        };
+        let width = records
+            .iter()
+            .map(Vec::len)
+            .max()
+            .unwrap_or(0)
+            .max(names.len());
+        if width > MAX_COLUMNS {
+            return Err(ToolError::InvalidArguments(format!(
+                "Table has {width} columns; the limit is {MAX_COLUMNS}"
+            )));
+        }
+        if header {
+            if let Some((n, record)) = records
+                .iter()
+                .enumerate()
+                .find(|(_, r)| r.len() > names.len())
+            {
+                return Err(ToolError::InvalidArguments(format!(
+                    "Row {} has {} fields but the header names {}",
+                    n + 1,
+                    record.len(),
+                    names.len()
+                )));
+            }
+        }
+
+        let kinds: Vec<ColumnType> = (0..width)
+            .map(|i| infer_type(records.iter().filter_map(|r| r.get(i))))
+            .collect();
+        let columns = column_names(&names, width)
+            .into_iter()
+            .zip(&kinds)
+            .map(|(name, &kind)| Column { name, kind })
+            .collect();
+        let rows = records
+            .into_iter()
+            .map(|record| {
+                kinds
+                    .iter()
+                    .enumerate()
+                    .map(|(i, &kind)| record.get(i).map_or(Cell::Null, |field| cell(field, kind)))
+                    .collect()
+            })
+            .collect();
+        Ok(Self { columns, rows })
+    }
+
+    /// Index of column `name`
+    pub(super) fn column(&self, name: &str) -> ToolResult<usize> {
+        self.columns
+            .iter()
+            .position(|c| c.name == name)
+            .ok_or_else(|| {
+                let names: Vec<&str> = self.columns.iter().map(|c| c.name.as_str()).collect();
+                ToolError::InvalidArguments(format!(
+                    "Unknown column '{name}'; the table has {}",
+                    names.join(", ")
+                ))
+            })
+    }
+}
+
+/// Split `text` into records of raw fields, skipping blank lines
+fn records(text: &str, delimiter: char) -> ToolResult<Vec<Vec<String>>> {
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    // Whether the current field was quoted, so `""` is an empty field rather than nothing
+    let mut was_quoted = false;
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        if quoted {
+            match c {
+                '"' if chars.peek() == Some(&'"') => {
+                    field.push('"');
+                    chars.next();
+                }
+                '"' => quoted = false,
+                _ => field.push(c),
+            }
+            continue;
+        }
+        match c {
+            '"' if field.is_empty() && !was_quoted => {
+                quoted = true;
+                was_quoted = true;
+            }
+            '\r' if chars.peek() == Some(&'\n') => {}
+            '\n' => {
+                if !record.is_empty() || !field.is_empty() || was_quoted {
+                    record.push(std::mem::take(&mut field));
+                    records.push(std::mem::take(&mut record));
+                }
+                was_quoted = false;
+            }
+            c if c == delimiter => {
+                record.push(std::mem::take(&mut field));
+                was_quoted = false;
+            }
+            _ => field.push(c),
+        }
+    }
+    if quoted {
+        return Err(ToolError::InvalidArguments(
+            "Unterminated quoted field".to_string(),
+        ));
+    }
+    if !record.is_empty() || !field.is_empty() || was_quoted {
+        record.push(field);
+        records.push(record);
+    }
+    Ok(records)
+}
+
+/// Header names made unique and non-empty, padded to `width`
+fn column_names(header: &[String], width: usize) -> Vec<String> {
+    let mut names: Vec<String> = Vec::with_capacity(width);
+    for i in 0..width {
+        let base = header
+            .get(i)
+            .map(|name| name.trim())
+            .filter(|name| !name.is_empty())
+            .map(str::to_string)
+            .unwrap_or_else(|| format!("column_{}", i + 1));
+        let mut name = base.clone();
+        let mut n = 2;
+        while names.contains(&name) {
+            name = format!("{base}_{n}");
+            n += 1;
+        }
+        names.push(name);
+    }
+    names
+}
+
+fn parse_bool(field: &str) -> Option<bool> {
+    if field.eq_ignore_ascii_case("true") {
+        Some(true)
+    } else if field.eq_ignore_ascii_case("false") {
+        Some(false)
+    } else {
+        None
+    }
+}
+
+fn parse_float(field: &str) -> Option<f64> {
+    field.parse::<f64>().ok().filter(|x| x.is_finite())
+}
+
+/// Narrowest type every non-empty field fits
+fn infer_type<'a>(fields: impl Iterator<Item = &'a String>) -> ColumnType {
+    let mut kind: Option<ColumnType> = None;
+    for field in fields.map(|f| f.trim()).filter(|f| !f.is_empty()) {
+        let fits = |kind: ColumnType| match kind {
+            ColumnType::Integer => field.parse::<i64>().is_ok(),
+            ColumnType::Float => parse_float(field).is_some(),
+            ColumnType::Boolean => parse_bool(field).is_some(),
+            ColumnType::String => true,
+        };
+        kind = Some(match kind {
+            Some(kind) if fits(kind) => kind,
+            Some(ColumnType::Integer) if fits(ColumnType::Float) => ColumnType::Float,
+            Some(_) => return ColumnType::String,
+            None => [ColumnType::Integer, ColumnType::Float, ColumnType::Boolean]
+                .into_iter()
+                .find(|&kind| fits(kind))
+                .unwrap_or(ColumnType::String),
+        });
+        if kind == Some(ColumnType::String) {
+            break;
+        }
+    }
+    kind.unwrap_or(ColumnType::String)
+}
+
+/// `field` as a cell of a column of type `kind`
+fn cell(field: &str, kind: ColumnType) -> Cell {
+    let trimmed = field.trim();
+    if trimmed.is_empty() {
+        return Cell::Null;
+    }
+    match kind {
+        ColumnType::Integer => trimmed.parse().map_or(Cell::Null, Cell::Int),
+        ColumnType::Float => parse_float(trimmed).map_or(Cell::Null, Cell::Float),
+        ColumnType::Boolean => parse_bool(trimmed).map_or(Cell::Null, Cell::Bool),
+        ColumnType::String => Cell::Text(field.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn quoted_fields_and_line_endings() {
+        let text = "name,notes\r\n\"Smith, J\",\"said \"\"hi\"\"\nand left\"\r\n\r\nDoe,\"\"\n";
+        let table = Table::parse(text, ',', true).unwrap();
+        assert_eq!(table.rows.len(), 2);
+        assert_eq!(table.rows[0][0], Cell::Text("Smith, J".to_string()));
+        assert_eq!(
+            table.rows[0][1],
+            Cell::Text("said \"hi\"\nand left".to_string())
+        );
+        assert_eq!(table.rows[1][1], Cell::Null);
+        assert!(Table::parse("a\n\"open", ',', true).is_err());
+    }
+
+    #[test]
+    fn infers_the_narrowest_column_type() {
+        let text = "id,price,ok,label,,id\n1,2,true,x,,\n2,2.5,FALSE,3,,\n,1e3,,,,\n";
+        let table = Table::parse(text, ',', true).unwrap();
+        let kinds: Vec<&str> = table.columns.iter().map(|c| c.kind.name()).collect();
+        assert_eq!(
+            kinds,
+            ["integer", "float", "boolean", "string", "string", "string"]
+        );
+        let names: Vec<&str> = table.columns.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, ["id", "price", "ok", "label", "column_5", "id_2"]);
+        assert_eq!(table.rows[1][1], Cell::Float(2.5));
+        assert_eq!(table.rows[2][0], Cell::Null);
+    }
+}
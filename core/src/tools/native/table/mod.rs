@@ -0,0 +1,347 @@
+//! Tabular data analysis for agents: `table:query`.
+//!
+//! Loads a CSV, TSV or Parquet file from the workspace or the blob store
+//! (`crate::blob`), infers column types, and runs a query written as JSON: filters,
+//! grouping with aggregates, column selection, sorting and paging. The result comes back
+//! with the file's schema, so an agent can answer "analyze this file" without reaching
+//! for the shell.
+//!
+//! Parquet files need the `parquet` cargo feature; without it they are refused. SQL is
+//! not supported. Files are read whole and capped at `TableQueryTool::with_max_bytes`.
+
+mod csv;
+#[cfg(feature = "parquet")]
+mod parquet;
+mod query;
+
+use crate::blob::BlobStore;
+use crate::tools::native::filesystem::resolve_in_workspace;
+use crate::tools::{caller_agent_id, Tool, ToolError, ToolResult};
+use async_trait::async_trait;
+use csv::{Column, Table};
+use query::{Query, MAX_LIMIT};
+use serde_json::{json, Value};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tracing::debug;
+
+/// Largest file loaded by default, in bytes
+pub const DEFAULT_MAX_TABLE_BYTES: u64 = 16 * 1024 * 1024;
+
+/// Layout of a table file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Format {
+    Csv,
+    Tsv,
+    Parquet,
+}
+
+impl Format {
+    /// Field separator of a delimited format
+    fn delimiter(self) -> Option<char> {
+        match self {
+            Format::Csv => Some(','),
+            Format::Tsv => Some('\t'),
+            Format::Parquet => None,
+        }
+    }
+
+    /// The format named by `format`, else guessed from the file name or content type
+    fn resolve(format: Option<&str>, name: &str) -> ToolResult<Self> {
+        let name = name.to_ascii_lowercase();
+        let guessed = if name.ends_with(".tsv") || name.ends_with(".tab") {
+            "tsv"
+        } else if name.ends_with(".parquet") || name.ends_with("/vnd.apache.parquet") {
+            "parquet"
+        } else if name.contains("tab-separated-values") {
+            "tsv"
+        } else {
+            "csv"
+        };
+        match format.unwrap_or(guessed) {
+            "csv" => Ok(Format::Csv),
+            "tsv" => Ok(Format::Tsv),
+            "parquet" if cfg!(feature = "parquet") => Ok(Format::Parquet),
+            "parquet" => Err(ToolError::InvalidArguments(
+                "Parquet support is not built in (loom-core's `parquet` feature); export the \
+                 data as CSV"
+                    .to_string(),
+            )),
+            other => Err(ToolError::InvalidArguments(format!(
+                "Unknown format '{other}'; use \"csv\", \"tsv\" or \"parquet\""
+            ))),
+        }
+    }
+}
+
+fn delimiter_argument(arguments: &Value, default: char) -> ToolResult<char> {
+    match arguments["delimiter"].as_str() {
+        None => Ok(default),
+        Some(delimiter) => {
+            let mut chars = delimiter.chars();
+            match (chars.next(), chars.next()) {
+                (Some(c), None) if c != '"' && c != '\n' && c != '\r' => Ok(c),
+                _ => Err(ToolError::InvalidArguments(
+                    "'delimiter' must be a single character other than a quote or newline"
+                        .to_string(),
+                )),
+            }
+        }
+    }
+}
+
+#[cfg(feature = "parquet")]
+fn read_parquet(content: Vec<u8>) -> ToolResult<Table> {
+    parquet::read(content)
+}
+
+/// Unreachable: `Format::resolve` refuses Parquet without the feature
+#[cfg(not(feature = "parquet"))]
+fn read_parquet(_content: Vec<u8>) -> ToolResult<Table> {
+    Err(ToolError::InvalidArguments(
+        "Parquet support is not built in".to_string(),
+    ))
+}
+
+fn columns_json(columns: &[Column]) -> Vec<Value> {
+    columns.iter().map(Column::to_json).collect()
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// table:query
+// ─────────────────────────────────────────────────────────────────────────────
+
+pub struct TableQueryTool {
+    workspace_root: PathBuf,
+    blobs: Option<Arc<BlobStore>>,
+    max_bytes: u64,
+}
+
+impl TableQueryTool {
+    /// Reads `path` arguments relative to `workspace_root`, and `blob_ref` arguments from
+    /// `blobs` when given
+    pub fn new(workspace_root: PathBuf, blobs: Option<Arc<BlobStore>>) -> Self {
+        Self {
+            workspace_root,
+            blobs,
+            max_bytes: DEFAULT_MAX_TABLE_BYTES,
+        }
+    }
+
+    /// Refuse files larger than `max_bytes`
+    pub fn with_max_bytes(mut self, max_bytes: u64) -> Self {
+        self.max_bytes = max_bytes;
+        self
+    }
+
+    fn too_large(&self, size: u64) -> ToolError {
+        ToolError::InvalidArguments(format!(
+            "File of {size} bytes exceeds the limit of {} bytes",
+            self.max_bytes
+        ))
+    }
+
+    /// The file's content and a name to guess its format from
+    async fn load(&self, arguments: &Value) -> ToolResult<(Vec<u8>, String)> {
+        if let Some(id) = arguments["blob_ref"].as_str() {
+            let store = self.blobs.as_ref().ok_or_else(|| {
+                ToolError::InvalidArguments("No blob store is configured".to_string())
+            })?;
+            let storage_error = |e: crate::LoomError| ToolError::ExecutionFailed(e.to_string());
+            let blob = store
+                .blob_ref(id)
+                .await
+                .map_err(storage_error)?
+                .ok_or_else(|| ToolError::NotFound(format!("Blob {id} not found")))?;
+            if blob.size > self.max_bytes {
+                return Err(self.too_large(blob.size));
+            }
+            let (content, access) = store.load(&blob).await.map_err(storage_error)?;
+            let caller = caller_agent_id();
+            if !access.allows(caller.as_deref(), None) {
+                return Err(ToolError::PermissionDenied(format!(
+                    "{} may not read blob {id}",
+                    caller.as_deref().unwrap_or("a call outside an agent")
+                )));
+            }
+            return Ok((content, blob.content_type));
+        }
+
+        let path_str = arguments["path"].as_str().ok_or_else(|| {
+            ToolError::InvalidArguments("Missing 'path' or 'blob_ref' argument".to_string())
+        })?;
+        let path = resolve_in_workspace(&self.workspace_root, path_str)
+            .ok_or_else(|| ToolError::PermissionDenied("Path traversal detected".to_string()))?;
+        let metadata = tokio::fs::metadata(&path)
+            .await
+            .map_err(|_| ToolError::NotFound(format!("File not found: {path_str}")))?;
+        if metadata.len() > self.max_bytes {
+            return Err(self.too_large(metadata.len()));
+        }
+        let content = tokio::fs::read(&path)
+            .await
+            .map_err(|e| ToolError::ExecutionFailed(format!("Failed to read file: {e}")))?;
+        Ok((content, file_name(&path)))
+    }
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
+#[async_trait]
+impl Tool for TableQueryTool {
+    fn name(&self) -> String {
+        "table:query".to_string()
+    }
+
+    fn description(&self) -> String {
+        "Analyze a CSV, TSV or Parquet file: filter, group and aggregate, select and sort rows. \
+         Returns the result rows and the file's column schema"
+            .to_string()
+    }
+
+    fn parameters(&self) -> Value {
+        let columns = json!({
+            "oneOf": [
+                { "type": "string" },
+                { "type": "array", "items": { "type": "string" } }
+            ]
+        });
+        json!({
+            "type": "object",
+            "properties": {
+                "path": { "type": "string", "description": "Workspace-relative path of the file" },
+                "blob_ref": { "type": "string", "description": "Blob holding the file, instead of 'path'" },
+                "format": {
+                    "type": "string",
+                    "enum": ["csv", "tsv", "parquet"],
+                    "description": "Default: from the file extension or content type, else csv"
+                },
+                "delimiter": { "type": "string", "description": "Field separator, overriding the format's (CSV/TSV)" },
+                "header": { "type": "boolean", "description": "Whether the first row names the columns (CSV/TSV, default: true)" },
+                "filter": {
+                    "type": "array",
+                    "items": {
+                        "type": "object",
+                        "properties": {
+                            "column": { "type": "string" },
+                            "op": {
+                                "type": "string",
+                                "enum": ["eq", "ne", "gt", "gte", "lt", "lte", "contains", "in", "is_null", "not_null"]
+                            },
+                            "value": {}
+                        },
+                        "required": ["column"]
+                    },
+                    "description": "Conditions every row must meet (default op: eq; 'in' takes an array)"
+                },
+                "group_by": columns.clone(),
+                "aggregates": {
+                    "type": "array",
+                    "items": {
+                        "type": "object",
+                        "properties": {
+                            "fn": { "type": "string", "enum": ["count", "count_distinct", "sum", "avg", "min", "max"] },
+                            "column": { "type": "string" },
+                            "as": { "type": "string" }
+                        },
+                        "required": ["fn"]
+                    },
+                    "description": "Computed per group (or over all rows without group_by); named '<fn>_<column>' unless 'as' is given"
+                },
+                "select": columns,
+                "sort": {
+                    "type": "array",
+                    "items": {
+                        "type": "object",
+                        "properties": {
+                            "column": { "type": "string" },
+                            "desc": { "type": "boolean" }
+                        },
+                        "required": ["column"]
+                    },
+                    "description": "Result columns to order by; nulls last"
+                },
+                "limit": { "type": "integer", "description": format!("Rows to return (default 100, at most {MAX_LIMIT})") },
+                "offset": { "type": "integer", "description": "Result rows to skip" }
+            }
+        })
+    }
+
+    fn output_schema(&self) -> Option<Value> {
+        let columns = json!({
+            "type": "array",
+            "items": {
+                "type": "object",
+                "properties": {
+                    "name": { "type": "string" },
+                    "type": { "type": "string", "enum": ["integer", "float", "boolean", "string"] }
+                },
+                "required": ["name", "type"]
+            }
+        });
+        Some(json!({
+            "type": "object",
+            "properties": {
+                "schema": columns.clone(),
+                "total_rows": { "type": "integer" },
+                "columns": columns,
+                "rows": { "type": "array", "items": { "type": "array" } },
+                "matched_rows": { "type": "integer" },
+                "truncated": { "type": "boolean" }
+            },
+            "required": ["schema", "total_rows", "columns", "rows", "matched_rows", "truncated"]
+        }))
+    }
+
+    async fn call(&self, arguments: Value) -> ToolResult<Value> {
+        let (content, name) = self.load(&arguments).await?;
+        let format = Format::resolve(arguments["format"].as_str(), &name)?;
+        let delimiter = format
+            .delimiter()
+            .map(|default| delimiter_argument(&arguments, default))
+            .transpose()?;
+        let header = arguments["header"].as_bool().unwrap_or(true);
+
+        // Parsing and querying are CPU-bound; keep them off the async workers
+        tokio::task::spawn_blocking(move || {
+            let table = match delimiter {
+                Some(delimiter) => {
+                    let text = String::from_utf8(content).map_err(|_| {
+                        ToolError::InvalidArguments("The file is not UTF-8 text".to_string())
+                    })?;
+                    Table::parse(&text, delimiter, header)?
+                }
+                None => read_parquet(content)?,
+            };
+            let query = Query::from_arguments(&table, &arguments)?;
+            let result = query.run(&table)?;
+            debug!(
+                target: "table",
+                rows = table.rows.len(),
+                matched = result.matched,
+                returned = result.rows.len(),
+                "table:query"
+            );
+            let offset = arguments["offset"].as_u64().unwrap_or(0) as usize;
+            let rows: Vec<Value> = result
+                .rows
+                .iter()
+                .map(|row| Value::Array(row.iter().map(|cell| cell.to_json()).collect()))
+                .collect();
+            Ok(json!({
+                "schema": columns_json(&table.columns),
+                "total_rows": table.rows.len(),
+                "columns": columns_json(&result.columns),
+                "truncated": offset + rows.len() < result.matched,
+                "rows": rows,
+                "matched_rows": result.matched,
+            }))
+        })
+        .await
+        .map_err(|e| ToolError::ExecutionFailed(e.to_string()))?
+    }
+}
@@ -0,0 +1,120 @@
+//! Parquet files read into the same typed columns as delimited text.
+//!
+//! Each top-level field becomes a column. Integer, floating point and boolean values
+//! keep their type; strings, dates, timestamps, decimals, binary and nested values are
+//! read as text. A column gets the narrowest type all its non-null values fit, as in
+//! `csv`, so integers mixed with floats make a float column.
+
+use super::csv::{Cell, Column, ColumnType, Table, MAX_COLUMNS};
+use crate::tools::{ToolError, ToolResult};
+use ::parquet::file::reader::{FileReader, SerializedFileReader};
+use ::parquet::record::Field;
+
+/// Most rows read from one file; Parquet compresses well, so the byte cap alone does
+/// not bound the decoded table
+pub(super) const MAX_ROWS: usize = 1_000_000;
+
+/// Decode a whole Parquet file
+pub(super) fn read(content: Vec<u8>) -> ToolResult<Table> {
+    let invalid = |e: ::parquet::errors::ParquetError| {
+        ToolError::InvalidArguments(format!("Invalid Parquet file: {e}"))
+    };
+    let reader = SerializedFileReader::new(bytes::Bytes::from(content)).map_err(invalid)?;
+    let metadata = reader.metadata().file_metadata();
+    let names: Vec<String> = metadata
+        .schema_descr()
+        .root_schema()
+        .get_fields()
+        .iter()
+        .map(|field| field.name().to_string())
+        .collect();
+    if names.len() > MAX_COLUMNS {
+        return Err(ToolError::InvalidArguments(format!(
+            "Table has {} columns; the limit is {MAX_COLUMNS}",
+            names.len()
+        )));
+    }
+    let total = metadata.num_rows().max(0) as usize;
+    if total > MAX_ROWS {
+        return Err(ToolError::InvalidArguments(format!(
+            "Table has {total} rows; the limit is {MAX_ROWS}"
+        )));
+    }
+
+    let mut rows = Vec::with_capacity(total);
+    for row in reader.get_row_iter(None).map_err(invalid)? {
+        let row = row.map_err(invalid)?;
+        rows.push(
+            row.get_column_iter()
+                .map(|(_, field)| cell(field))
+                .collect::<Vec<_>>(),
+        );
+    }
+
+    let kinds: Vec<ColumnType> = (0..names.len())
+        .map(|i| column_type(rows.iter().filter_map(|row| row.get(i))))
+        .collect();
+    for row in &mut rows {
+        for (cell, &kind) in row.iter_mut().zip(&kinds) {
+            coerce(cell, kind);
+        }
+    }
+    let columns = names
+        .into_iter()
+        .zip(kinds)
+        .map(|(name, kind)| Column { name, kind })
+        .collect();
+    Ok(Table { columns, rows })
+}
+
+fn cell(field: &Field) -> Cell {
+    match field {
+        Field::Null => Cell::Null,
+        Field::Bool(b) => Cell::Bool(*b),
+        Field::Byte(n) => Cell::Int(i64::from(*n)),
+        Field::Short(n) => Cell::Int(i64::from(*n)),
+        Field::Int(n) => Cell::Int(i64::from(*n)),
+        Field::Long(n) => Cell::Int(*n),
+        Field::UByte(n) => Cell::Int(i64::from(*n)),
+        Field::UShort(n) => Cell::Int(i64::from(*n)),
+        Field::UInt(n) => Cell::Int(i64::from(*n)),
+        Field::ULong(n) => i64::try_from(*n).map_or(Cell::Float(*n as f64), Cell::Int),
+        Field::Float16(x) => Cell::Float(x.to_f64()),
+        Field::Float(x) => Cell::Float(f64::from(*x)),
+        Field::Double(x) => Cell::Float(*x),
+        Field::Str(s) => Cell::Text(s.clone()),
+        other => Cell::Text(other.to_string()),
+    }
+}
+
+/// Narrowest type every non-null cell fits
+fn column_type<'a>(cells: impl Iterator<Item = &'a Cell>) -> ColumnType {
+    let mut kind: Option<ColumnType> = None;
+    for cell in cells {
+        let own = match cell {
+            Cell::Null => continue,
+            Cell::Int(_) => ColumnType::Integer,
+            Cell::Float(_) => ColumnType::Float,
+            Cell::Bool(_) => ColumnType::Boolean,
+            Cell::Text(_) => return ColumnType::String,
+        };
+        kind = Some(match kind {
+            None => own,
+            Some(kind) if kind == own => kind,
+            Some(kind) if kind.is_numeric() && own.is_numeric() => ColumnType::Float,
+            Some(_) => return ColumnType::String,
+        });
+    }
+    kind.unwrap_or(ColumnType::String)
+}
+
+/// Convert `cell` to a value of a column of type `kind`
+fn coerce(cell: &mut Cell, kind: ColumnType) {
+    match (kind, &*cell) {
+        (_, Cell::Null) => {}
+        (ColumnType::Float, Cell::Int(n)) => *cell = Cell::Float(*n as f64),
+        (ColumnType::String, Cell::Text(_)) => {}
+        (ColumnType::String, other) => *cell = Cell::Text(other.to_string()),
+        _ => {}
+    }
+}
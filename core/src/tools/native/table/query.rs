@@ -0,0 +1,500 @@
+//! Queries over a parsed table, expressed as JSON: filter, group and aggregate, select,
+//! sort, then page with offset and limit.
+
+use super::csv::{Cell, Column, ColumnType, Table};
+use crate::tools::{ToolError, ToolResult};
+use serde_json::Value;
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
+
+/// Rows returned when the query does not set `limit`
+const DEFAULT_LIMIT: usize = 100;
+
+/// Most rows one query may return
+pub(super) const MAX_LIMIT: usize = 1000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Op {
+    Eq,
+    Ne,
+    Gt,
+    Gte,
+    Lt,
+    Lte,
+    Contains,
+    In,
+    IsNull,
+    NotNull,
+}
+
+struct Filter {
+    column: usize,
+    op: Op,
+    /// Values to compare against; one for every op but `in`, none for the null checks
+    values: Vec<Cell>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Function {
+    Count,
+    CountDistinct,
+    Sum,
+    Avg,
+    Min,
+    Max,
+}
+
+impl Function {
+    fn name(self) -> &'static str {
+        match self {
+            Function::Count => "count",
+            Function::CountDistinct => "count_distinct",
+            Function::Sum => "sum",
+            Function::Avg => "avg",
+            Function::Min => "min",
+            Function::Max => "max",
+        }
+    }
+}
+
+struct Aggregate {
+    function: Function,
+    /// None only for `count`, which then counts rows
+    column: Option<usize>,
+    name: String,
+}
+
+struct SortKey {
+    /// Name of a result column, or of any table column when nothing is aggregated
+    column: String,
+    descending: bool,
+}
+
+/// A validated query against one table
+pub(super) struct Query {
+    filters: Vec<Filter>,
+    group_by: Vec<usize>,
+    aggregates: Vec<Aggregate>,
+    select: Option<Vec<usize>>,
+    sort: Vec<SortKey>,
+    offset: usize,
+    limit: usize,
+}
+
+/// What a query returns
+pub(super) struct QueryResult {
+    pub columns: Vec<Column>,
+    pub rows: Vec<Vec<Cell>>,
+    /// Result rows before `offset` and `limit`
+    pub matched: usize,
+}
+
+fn invalid(message: impl Into<String>) -> ToolError {
+    ToolError::InvalidArguments(message.into())
+}
+
+/// `arguments[key]` as a list of column names (a single string is a list of one)
+fn column_list(table: &Table, arguments: &Value, key: &str) -> ToolResult<Option<Vec<usize>>> {
+    match &arguments[key] {
+        Value::Null => Ok(None),
+        Value::String(name) => Ok(Some(vec![table.column(name)?])),
+        Value::Array(names) => names
+            .iter()
+            .map(|name| {
+                name.as_str()
+                    .ok_or_else(|| invalid(format!("'{key}' must list column names")))
+                    .and_then(|name| table.column(name))
+            })
+            .collect::<ToolResult<Vec<_>>>()
+            .map(Some),
+        _ => Err(invalid(format!("'{key}' must list column names"))),
+    }
+}
+
+fn objects<'a>(arguments: &'a Value, key: &str) -> ToolResult<&'a [Value]> {
+    match &arguments[key] {
+        Value::Null => Ok(&[]),
+        Value::Array(entries) if entries.iter().all(Value::is_object) => Ok(entries),
+        Value::Object(_) => Ok(std::slice::from_ref(&arguments[key])),
+        _ => Err(invalid(format!("'{key}' must be an array of objects"))),
+    }
+}
+
+fn count_argument(arguments: &Value, key: &str, default: usize) -> ToolResult<usize> {
+    match &arguments[key] {
+        Value::Null => Ok(default),
+        value => value
+            .as_u64()
+            .map(|n| n as usize)
+            .ok_or_else(|| invalid(format!("'{key}' must be a non-negative integer"))),
+    }
+}
+
+/// A JSON filter value as a cell comparable with the column's values
+fn filter_value(value: &Value, kind: ColumnType) -> ToolResult<Cell> {
+    Ok(match value {
+        Value::Null => Cell::Null,
+        Value::Bool(b) => Cell::Bool(*b),
+        Value::Number(n) => match n.as_i64() {
+            Some(n) => Cell::Int(n),
+            None => Cell::Float(n.as_f64().unwrap_or_default()),
+        },
+        Value::String(s) if kind.is_numeric() => match (s.trim().parse(), s.trim().parse()) {
+            (Ok(n), _) => Cell::Int(n),
+            (_, Ok(x)) => Cell::Float(x),
+            _ => Cell::Text(s.clone()),
+        },
+        Value::String(s) if kind == ColumnType::Boolean => match s.to_ascii_lowercase().as_str() {
+            "true" => Cell::Bool(true),
+            "false" => Cell::Bool(false),
+            _ => Cell::Text(s.clone()),
+        },
+        Value::String(s) => Cell::Text(s.clone()),
+        _ => {
+            return Err(invalid(
+                "Filter values must be strings, numbers or booleans",
+            ))
+        }
+    })
+}
+
+impl Filter {
+    fn from_value(table: &Table, entry: &Value) -> ToolResult<Self> {
+        let column = table.column(
+            entry["column"]
+                .as_str()
+                .ok_or_else(|| invalid("Every filter needs a 'column'"))?,
+        )?;
+        let op = match entry["op"].as_str().unwrap_or("eq") {
+            "eq" | "=" | "==" => Op::Eq,
+            "ne" | "!=" => Op::Ne,
+            "gt" | ">" => Op::Gt,
+            "gte" | ">=" => Op::Gte,
+            "lt" | "<" => Op::Lt,
+            "lte" | "<=" => Op::Lte,
+            "contains" => Op::Contains,
+            "in" => Op::In,
+            "is_null" => Op::IsNull,
+            "not_null" => Op::NotNull,
+            other => return Err(invalid(format!("Unknown filter op '{other}'"))),
+        };
+        let kind = table.columns[column].kind;
+        let values = match op {
+            Op::IsNull | Op::NotNull => Vec::new(),
+            Op::In => entry["value"]
+                .as_array()
+                .ok_or_else(|| invalid("An 'in' filter needs an array 'value'"))?
+                .iter()
+                .map(|value| filter_value(value, kind))
+                .collect::<ToolResult<_>>()?,
+            _ => match &entry["value"] {
+                Value::Null => {
+                    return Err(invalid("Filter needs a 'value'; use is_null for nulls"))
+                }
+                value => vec![filter_value(value, kind)?],
+            },
+        };
+        Ok(Self { column, op, values })
+    }
+
+    fn matches(&self, row: &[Cell]) -> bool {
+        let cell = &row[self.column];
+        match self.op {
+            Op::IsNull => return cell.is_null(),
+            Op::NotNull => return !cell.is_null(),
+            _ if cell.is_null() => return false,
+            _ => {}
+        }
+        match self.op {
+            Op::In => self
+                .values
+                .iter()
+                .any(|value| cell.compare(value) == Ordering::Equal),
+            Op::Contains => {
+                let needle = self.values[0].to_string().to_lowercase();
+                cell.to_string().to_lowercase().contains(&needle)
+            }
+            op => {
+                let order = cell.compare(&self.values[0]);
+                match op {
+                    Op::Eq => order == Ordering::Equal,
+                    Op::Ne => order != Ordering::Equal,
+                    Op::Gt => order == Ordering::Greater,
+                    Op::Gte => order != Ordering::Less,
+                    Op::Lt => order == Ordering::Less,
+                    _ => order != Ordering::Greater,
+                }
+            }
+        }
+    }
+}
+
+impl Aggregate {
+    fn from_value(table: &Table, entry: &Value) -> ToolResult<Self> {
+        let function = match entry["fn"].as_str().or(entry["function"].as_str()) {
+            Some("count") => Function::Count,
+            Some("count_distinct") => Function::CountDistinct,
+            Some("sum") => Function::Sum,
+            Some("avg") | Some("mean") => Function::Avg,
+            Some("min") => Function::Min,
+            Some("max") => Function::Max,
+            Some(other) => return Err(invalid(format!("Unknown aggregate '{other}'"))),
+            None => return Err(invalid("Every aggregate needs an 'fn'")),
+        };
+        let column = entry["column"]
+            .as_str()
+            .map(|name| table.column(name))
+            .transpose()?;
+        match column {
+            None if function != Function::Count => {
+                return Err(invalid(format!("'{}' needs a 'column'", function.name())))
+            }
+            Some(index)
+                if matches!(function, Function::Sum | Function::Avg)
+                    && !table.columns[index].kind.is_numeric() =>
+            {
+                return Err(invalid(format!(
+                    "'{}' needs a numeric column; '{}' is {}",
+                    function.name(),
+                    table.columns[index].name,
+                    table.columns[index].kind.name()
+                )))
+            }
+            _ => {}
+        }
+        let name = match (entry["as"].as_str(), column) {
+            (Some(name), _) => name.to_string(),
+            (None, Some(index)) => format!("{}_{}", function.name(), table.columns[index].name),
+            (None, None) => function.name().to_string(),
+        };
+        Ok(Self {
+            function,
+            column,
+            name,
+        })
+    }
+
+    fn kind(&self, table: &Table) -> ColumnType {
+        match (self.function, self.column) {
+            (Function::Count | Function::CountDistinct, _) => ColumnType::Integer,
+            (Function::Avg, _) => ColumnType::Float,
+            (_, Some(index)) => table.columns[index].kind,
+            (_, None) => ColumnType::Integer,
+        }
+    }
+
+    fn compute(&self, rows: &[&Vec<Cell>]) -> Cell {
+        let Some(column) = self.column else {
+            return Cell::Int(rows.len() as i64);
+        };
+        let values = rows.iter().map(|row| &row[column]).filter(|v| !v.is_null());
+        match self.function {
+            Function::Count => Cell::Int(values.count() as i64),
+            Function::CountDistinct => {
+                let distinct: HashSet<String> = values.map(Cell::to_string).collect();
+                Cell::Int(distinct.len() as i64)
+            }
+            Function::Sum => sum(values),
+            Function::Avg => {
+                let (total, n) = values
+                    .filter_map(Cell::as_f64)
+                    .fold((0.0, 0usize), |(total, n), x| (total + x, n + 1));
+                if n == 0 {
+                    Cell::Null
+                } else {
+                    Cell::Float(total / n as f64)
+                }
+            }
+            Function::Min => values
+                .min_by(|a, b| a.compare(b))
+                .cloned()
+                .unwrap_or(Cell::Null),
+            Function::Max => values
+                .max_by(|a, b| a.compare(b))
+                .cloned()
+                .unwrap_or(Cell::Null),
+        }
+    }
+}
+
+/// Sum of numeric cells: an integer unless a value is a float or the sum overflows
+fn sum<'a>(values: impl Iterator<Item = &'a Cell>) -> Cell {
+    let mut total = None;
+    for value in values {
+        total = Some(match (total, value) {
+            (None, value) => value.clone(),
+            (Some(Cell::Int(a)), Cell::Int(b)) => match a.checked_add(*b) {
+                Some(n) => Cell::Int(n),
+                None => Cell::Float(a as f64 + *b as f64),
+            },
+            (Some(acc), value) => {
+                Cell::Float(acc.as_f64().unwrap_or_default() + value.as_f64().unwrap_or_default())
+            }
+        });
+    }
+    total.unwrap_or(Cell::Null)
+}
+
+impl Query {
+    pub(super) fn from_arguments(table: &Table, arguments: &Value) -> ToolResult<Self> {
+        let filters = objects(arguments, "filter")?
+            .iter()
+            .map(|entry| Filter::from_value(table, entry))
+            .collect::<ToolResult<_>>()?;
+        let group_by = column_list(table, arguments, "group_by")?.unwrap_or_default();
+        let aggregates: Vec<Aggregate> = objects(arguments, "aggregates")?
+            .iter()
+            .map(|entry| Aggregate::from_value(table, entry))
+            .collect::<ToolResult<_>>()?;
+        let select = column_list(table, arguments, "select")?;
+        if select.is_some() && !(group_by.is_empty() && aggregates.is_empty()) {
+            return Err(invalid(
+                "'select' cannot be combined with 'group_by' or 'aggregates'",
+            ));
+        }
+        let sort = objects(arguments, "sort")?
+            .iter()
+            .map(|entry| {
+                Ok(SortKey {
+                    column: entry["column"]
+                        .as_str()
+                        .ok_or_else(|| invalid("Every sort key needs a 'column'"))?
+                        .to_string(),
+                    descending: entry["desc"].as_bool().unwrap_or(false)
+                        || entry["order"].as_str() == Some("desc"),
+                })
+            })
+            .collect::<ToolResult<_>>()?;
+        let limit = count_argument(arguments, "limit", DEFAULT_LIMIT)?;
+        if limit > MAX_LIMIT {
+            return Err(invalid(format!("'limit' may be at most {MAX_LIMIT}")));
+        }
+        Ok(Self {
+            filters,
+            group_by,
+            aggregates,
+            select,
+            sort,
+            offset: count_argument(arguments, "offset", 0)?,
+            limit,
+        })
+    }
+
+    pub(super) fn run(&self, table: &Table) -> ToolResult<QueryResult> {
+        let rows: Vec<&Vec<Cell>> = table
+            .rows
+            .iter()
+            .filter(|row| self.filters.iter().all(|filter| filter.matches(row)))
+            .collect();
+
+        // Without aggregation, sort keys may name any column of the table, so select last
+        let (mut columns, mut rows) = if self.group_by.is_empty() && self.aggregates.is_empty() {
+            (table.columns.clone(), rows.into_iter().cloned().collect())
+        } else {
+            self.aggregate(table, rows)
+        };
+
+        let keys = self
+            .sort
+            .iter()
+            .map(|key| {
+                columns
+                    .iter()
+                    .position(|c| c.name == key.column)
+                    .map(|index| (index, key.descending))
+                    .ok_or_else(|| {
+                        invalid(format!(
+                            "Cannot sort by '{}'; it is not a result column",
+                            key.column
+                        ))
+                    })
+            })
+            .collect::<ToolResult<Vec<_>>>()?;
+        if !keys.is_empty() {
+            rows.sort_by(|a: &Vec<Cell>, b: &Vec<Cell>| {
+                keys.iter()
+                    .map(|&(index, descending)| match (&a[index], &b[index]) {
+                        // Nulls last in either direction
+                        (Cell::Null, Cell::Null) => Ordering::Equal,
+                        (Cell::Null, _) => Ordering::Greater,
+                        (_, Cell::Null) => Ordering::Less,
+                        (x, y) if descending => y.compare(x),
+                        (x, y) => x.compare(y),
+                    })
+                    .find(|order| order.is_ne())
+                    .unwrap_or(Ordering::Equal)
+            });
+        }
+
+        if let Some(selected) = &self.select {
+            columns = selected.iter().map(|&i| columns[i].clone()).collect();
+            rows = rows
+                .into_iter()
+                .map(|row| selected.iter().map(|&i| row[i].clone()).collect())
+                .collect();
+        }
+
+        let matched = rows.len();
+        let rows = rows
+            .into_iter()
+            .skip(self.offset)
+            .take(self.limit)
+            .collect();
+        Ok(QueryResult {
+            columns,
+            rows,
+            matched,
+        })
+    }
+
+    /// One row per distinct `group_by` key, in order of first appearance, with the
+    /// aggregates computed over the group's rows
+    fn aggregate(&self, table: &Table, rows: Vec<&Vec<Cell>>) -> (Vec<Column>, Vec<Vec<Cell>>) {
+        let mut index: HashMap<Vec<String>, usize> = HashMap::new();
+        let mut groups: Vec<Vec<&Vec<Cell>>> = Vec::new();
+        for row in rows {
+            let key: Vec<String> = self
+                .group_by
+                .iter()
+                .map(|&i| match &row[i] {
+                    Cell::Null => "\0null".to_string(),
+                    cell => cell.to_string(),
+                })
+                .collect();
+            let n = *index.entry(key).or_insert_with(|| {
+                groups.push(Vec::new());
+                groups.len() - 1
+            });
+            groups[n].push(row);
+        }
+        // Aggregates over no rows at all still give one row, as in SQL
+        if groups.is_empty() && self.group_by.is_empty() {
+            groups.push(Vec::new());
+        }
+
+        let columns = self
+            .group_by
+            .iter()
+            .map(|&i| table.columns[i].clone())
+            .chain(self.aggregates.iter().map(|aggregate| Column {
+                name: aggregate.name.clone(),
+                kind: aggregate.kind(table),
+            }))
+            .collect();
+        let rows = groups
+            .iter()
+            .map(|group| {
+                self.group_by
+                    .iter()
+                    .map(|&i| group[0][i].clone())
+                    .chain(
+                        self.aggregates
+                            .iter()
+                            .map(|aggregate| aggregate.compute(group)),
+                    )
+                    .collect()
+            })
+            .collect();
+        (columns, rows)
+    }
+}
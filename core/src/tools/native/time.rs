@@ -0,0 +1,548 @@
+//! Date, time and timezone tools: `time:now`, `time:convert`, `time:diff`, `time:parse`
+//!
+//! Times are accepted as RFC 3339 / ISO 8601 strings, Unix timestamps (seconds or
+//! milliseconds), or natural language relative to now: `tomorrow at 5pm`, `in 3 hours`,
+//! `2 days ago`, `next friday`. Timezones are IANA names such as `Europe/Berlin`; the
+//! default is `LOOM_TIMEZONE`, or UTC.
+
+use crate::tools::{Tool, ToolError, ToolResult};
+use async_trait::async_trait;
+use chrono::{
+    DateTime, Datelike, Duration, LocalResult, Months, NaiveDate, NaiveDateTime, NaiveTime,
+    TimeZone, Utc, Weekday,
+};
+use chrono_tz::{OffsetComponents, Tz};
+use serde_json::{json, Value};
+use std::sync::Arc;
+
+/// Default timezone from `LOOM_TIMEZONE`, falling back to UTC
+pub fn timezone_from_env() -> Tz {
+    std::env::var("LOOM_TIMEZONE")
+        .ok()
+        .and_then(|name| parse_timezone(&name).ok())
+        .unwrap_or(Tz::UTC)
+}
+
+/// Parse an IANA timezone name; `UTC`, `GMT` and `Z` are accepted in any case
+pub fn parse_timezone(name: &str) -> ToolResult<Tz> {
+    let name = name.trim();
+    if ["utc", "gmt", "z"]
+        .iter()
+        .any(|alias| name.eq_ignore_ascii_case(alias))
+    {
+        return Ok(Tz::UTC);
+    }
+    name.parse::<Tz>().map_err(|_| {
+        ToolError::InvalidArguments(format!(
+            "Unknown timezone '{}' (expected an IANA name such as Europe/Berlin)",
+            name
+        ))
+    })
+}
+
+/// Parse an absolute or natural-language time, resolving relative expressions against
+/// `now` and local times in `now`'s timezone. Day expressions without a time of day
+/// (`tomorrow`, `next monday`) resolve to the start of that day.
+pub fn parse_time(input: &str, now: &DateTime<Tz>) -> Option<DateTime<Tz>> {
+    let input = input.trim();
+    if input.is_empty() {
+        return None;
+    }
+    parse_absolute(input, &now.timezone()).or_else(|| parse_natural(input, now))
+}
+
+fn parse_absolute(input: &str, tz: &Tz) -> Option<DateTime<Tz>> {
+    if let Ok(dt) = DateTime::parse_from_rfc3339(input) {
+        return Some(dt.with_timezone(tz));
+    }
+    if let Ok(dt) = DateTime::parse_from_rfc2822(input) {
+        return Some(dt.with_timezone(tz));
+    }
+    if input.bytes().all(|b| b.is_ascii_digit()) {
+        let n: i64 = input.parse().ok()?;
+        let utc = match input.len() {
+            10 => Utc.timestamp_opt(n, 0).single()?,
+            13 => Utc.timestamp_millis_opt(n).single()?,
+            _ => return None,
+        };
+        return Some(utc.with_timezone(tz));
+    }
+    for fmt in [
+        "%Y-%m-%dT%H:%M:%S%.f",
+        "%Y-%m-%d %H:%M:%S%.f",
+        "%Y-%m-%dT%H:%M",
+        "%Y-%m-%d %H:%M",
+    ] {
+        if let Ok(naive) = NaiveDateTime::parse_from_str(input, fmt) {
+            return resolve_local(tz, naive);
+        }
+    }
+    for fmt in ["%Y-%m-%d", "%Y/%m/%d", "%d %B %Y", "%B %d %Y", "%B %d, %Y"] {
+        if let Ok(date) = NaiveDate::parse_from_str(input, fmt) {
+            return resolve_local(tz, date.and_time(NaiveTime::MIN));
+        }
+    }
+    None
+}
+
+/// Local wall-clock time in `tz`. Ambiguous times (DST fall-back) take the earlier
+/// instant; times skipped by DST spring-forward move past the gap.
+fn resolve_local(tz: &Tz, naive: NaiveDateTime) -> Option<DateTime<Tz>> {
+    match tz.from_local_datetime(&naive) {
+        LocalResult::Single(dt) => Some(dt),
+        LocalResult::Ambiguous(earliest, _) => Some(earliest),
+        LocalResult::None => tz
+            .from_local_datetime(&(naive + Duration::hours(1)))
+            .earliest(),
+    }
+}
+
+enum WeekdayRef {
+    /// Today or the following six days
+    This,
+    /// Strictly after today
+    Next,
+    /// Strictly before today
+    Last,
+}
+
+fn parse_natural(input: &str, now: &DateTime<Tz>) -> Option<DateTime<Tz>> {
+    let lower = input.to_ascii_lowercase();
+    let mut words: Vec<&str> = lower
+        .split(|c: char| c.is_whitespace() || c == ',')
+        .filter(|w| !w.is_empty() && *w != "at" && *w != "on")
+        .collect();
+    let time_of_day = take_time_of_day(&mut words);
+
+    // (base instant, whether it names a whole day)
+    let (base, whole_day) = match words.as_slice() {
+        [] => (*now, time_of_day.is_some()),
+        ["now"] | ["right", "now"] if time_of_day.is_none() => (*now, false),
+        ["today"] | ["tonight"] => (*now, true),
+        ["tomorrow"] => (shift(now, 1, "day")?, true),
+        ["yesterday"] => (shift(now, -1, "day")?, true),
+        ["in", n, unit] | ["in", n, unit, "from", "now"] => (shift(now, amount(n)?, unit)?, false),
+        [n, unit, "ago"] => (shift(now, -amount(n)?, unit)?, false),
+        [n, unit, "from", "now"] => (shift(now, amount(n)?, unit)?, false),
+        ["next", word] | ["last", word] => {
+            let forward = words[0] == "next";
+            match word.parse::<Weekday>() {
+                Ok(day) => {
+                    let which = if forward {
+                        WeekdayRef::Next
+                    } else {
+                        WeekdayRef::Last
+                    };
+                    (weekday(now, day, which)?, true)
+                }
+                Err(_) => (shift(now, if forward { 1 } else { -1 }, word)?, false),
+            }
+        }
+        ["this", day] | [day] => (weekday(now, day.parse().ok()?, WeekdayRef::This)?, true),
+        _ => return None,
+    };
+
+    match (time_of_day, whole_day) {
+        (Some(time), _) => resolve_local(&now.timezone(), base.date_naive().and_time(time)),
+        (None, true) => resolve_local(&now.timezone(), base.date_naive().and_time(NaiveTime::MIN)),
+        (None, false) => Some(base),
+    }
+}
+
+/// Remove a trailing time of day (`17:30`, `5pm`, `5:30 pm`, `noon`, `midnight`)
+fn take_time_of_day(words: &mut Vec<&str>) -> Option<NaiveTime> {
+    let last = *words.last()?;
+    let (text, meridiem, consumed) = match last {
+        "noon" | "midday" => {
+            words.pop();
+            return NaiveTime::from_hms_opt(12, 0, 0);
+        }
+        "midnight" => {
+            words.pop();
+            return Some(NaiveTime::MIN);
+        }
+        "am" | "pm" if words.len() >= 2 => (words[words.len() - 2], Some(last), 2),
+        _ => match last
+            .strip_suffix("am")
+            .map(|t| (t, "am"))
+            .or_else(|| last.strip_suffix("pm").map(|t| (t, "pm")))
+        {
+            Some((text, meridiem)) => (text, Some(meridiem), 1),
+            None => (last, None, 1),
+        },
+    };
+
+    let mut parts = text.split(':');
+    let hour: u32 = parts.next()?.parse().ok()?;
+    let minute: u32 = match parts.next() {
+        Some(m) => m.parse().ok()?,
+        // A bare number is only a time with am/pm
+        None if meridiem.is_none() => return None,
+        None => 0,
+    };
+    let second: u32 = match parts.next() {
+        Some(s) => s.parse().ok()?,
+        None => 0,
+    };
+    if parts.next().is_some() {
+        return None;
+    }
+    let hour = match meridiem {
+        Some(_) if !(1..=12).contains(&hour) => return None,
+        Some("am") => hour % 12,
+        Some(_) => hour % 12 + 12,
+        None => hour,
+    };
+    let time = NaiveTime::from_hms_opt(hour, minute, second)?;
+    words.truncate(words.len() - consumed);
+    Some(time)
+}
+
+fn amount(word: &str) -> Option<i64> {
+    let n = match word {
+        "a" | "an" | "one" => 1,
+        "two" => 2,
+        "three" => 3,
+        "four" => 4,
+        "five" => 5,
+        "six" => 6,
+        "seven" => 7,
+        "eight" => 8,
+        "nine" => 9,
+        "ten" => 10,
+        "twelve" => 12,
+        _ => word.parse().ok()?,
+    };
+    Some(n)
+}
+
+/// `now` moved by `n` units. Day and larger units keep the local time of day across
+/// DST changes; smaller units are exact durations.
+fn shift(now: &DateTime<Tz>, n: i64, unit: &str) -> Option<DateTime<Tz>> {
+    let tz = now.timezone();
+    let local = now.naive_local();
+    let months = |n: i64| {
+        let months = Months::new(u32::try_from(n.unsigned_abs()).ok()?);
+        if n >= 0 {
+            local.checked_add_months(months)
+        } else {
+            local.checked_sub_months(months)
+        }
+    };
+    match unit.strip_suffix('s').unwrap_or(unit) {
+        "second" | "sec" => now.checked_add_signed(Duration::try_seconds(n)?),
+        "minute" | "min" => now.checked_add_signed(Duration::try_minutes(n)?),
+        "hour" | "hr" => now.checked_add_signed(Duration::try_hours(n)?),
+        "day" => resolve_local(&tz, local.checked_add_signed(Duration::try_days(n)?)?),
+        "week" => resolve_local(&tz, local.checked_add_signed(Duration::try_weeks(n)?)?),
+        "month" => resolve_local(&tz, months(n)?),
+        "year" => resolve_local(&tz, months(n.checked_mul(12)?)?),
+        _ => None,
+    }
+}
+
+fn weekday(now: &DateTime<Tz>, day: Weekday, which: WeekdayRef) -> Option<DateTime<Tz>> {
+    let today = now.weekday().num_days_from_monday() as i64;
+    let target = day.num_days_from_monday() as i64;
+    let ahead = (target - today).rem_euclid(7);
+    let days = match which {
+        WeekdayRef::This => ahead,
+        WeekdayRef::Next if ahead == 0 => 7,
+        WeekdayRef::Next => ahead,
+        WeekdayRef::Last => match (today - target).rem_euclid(7) {
+            0 => -7,
+            back => -back,
+        },
+    };
+    shift(now, days, "day")
+}
+
+/// JSON description of an instant in its timezone
+pub fn describe_time(dt: &DateTime<Tz>) -> Value {
+    json!({
+        "iso": dt.to_rfc3339(),
+        "timezone": dt.timezone().name(),
+        "utc_offset": dt.format("%:z").to_string(),
+        "abbreviation": dt.format("%Z").to_string(),
+        "dst": dt.offset().dst_offset() != Duration::zero(),
+        "unix": dt.timestamp(),
+        "unix_ms": dt.timestamp_millis(),
+        "date": dt.format("%Y-%m-%d").to_string(),
+        "time": dt.format("%H:%M:%S").to_string(),
+        "weekday": dt.format("%A").to_string(),
+    })
+}
+
+fn human_duration(seconds: i64) -> String {
+    let mut rest = seconds.unsigned_abs();
+    let mut parts = Vec::new();
+    for (unit, size) in [
+        ("day", 86_400),
+        ("hour", 3_600),
+        ("minute", 60),
+        ("second", 1),
+    ] {
+        let n = rest / size;
+        rest %= size;
+        if n > 0 {
+            parts.push(format!("{} {}{}", n, unit, if n == 1 { "" } else { "s" }));
+        }
+    }
+    if parts.is_empty() {
+        "0 seconds".to_string()
+    } else {
+        parts.join(", ")
+    }
+}
+
+fn timezone_arg(arguments: &Value, key: &str, default: Tz) -> ToolResult<Tz> {
+    match arguments[key].as_str() {
+        Some(name) => parse_timezone(name),
+        None => Ok(default),
+    }
+}
+
+/// Time argument `key` in `tz`, defaulting to now
+fn time_arg(arguments: &Value, key: &str, tz: Tz) -> ToolResult<DateTime<Tz>> {
+    let now = Utc::now().with_timezone(&tz);
+    match arguments[key].as_str() {
+        Some(text) => parse_time(text, &now)
+            .ok_or_else(|| ToolError::InvalidArguments(format!("Could not parse time '{}'", text))),
+        None => Ok(now),
+    }
+}
+
+/// All four time tools with `default_tz` for calls that name no timezone
+pub fn time_tools(default_tz: Tz) -> Vec<Arc<dyn Tool>> {
+    vec![
+        Arc::new(TimeNowTool::new(default_tz)),
+        Arc::new(TimeConvertTool::new(default_tz)),
+        Arc::new(TimeDiffTool::new(default_tz)),
+        Arc::new(TimeParseTool::new(default_tz)),
+    ]
+}
+
+// ─── time:now ───────────────────────────────────────────────────────────────
+
+/// Current date and time in a timezone
+pub struct TimeNowTool {
+    default_tz: Tz,
+}
+
+impl TimeNowTool {
+    pub fn new(default_tz: Tz) -> Self {
+        Self { default_tz }
+    }
+}
+
+#[async_trait]
+impl Tool for TimeNowTool {
+    fn name(&self) -> String {
+        "time:now".to_string()
+    }
+
+    fn description(&self) -> String {
+        "Get the current date, time and weekday in a timezone. Use this instead of guessing today's date.".to_string()
+    }
+
+    fn parameters(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "timezone": {
+                    "type": "string",
+                    "description": format!("IANA timezone, e.g. America/New_York (default: {})", self.default_tz.name())
+                }
+            }
+        })
+    }
+
+    async fn call(&self, arguments: Value) -> ToolResult<Value> {
+        let tz = timezone_arg(&arguments, "timezone", self.default_tz)?;
+        Ok(describe_time(&Utc::now().with_timezone(&tz)))
+    }
+}
+
+// ─── time:convert ───────────────────────────────────────────────────────────
+
+/// Express a time in another timezone
+pub struct TimeConvertTool {
+    default_tz: Tz,
+}
+
+impl TimeConvertTool {
+    pub fn new(default_tz: Tz) -> Self {
+        Self { default_tz }
+    }
+}
+
+#[async_trait]
+impl Tool for TimeConvertTool {
+    fn name(&self) -> String {
+        "time:convert".to_string()
+    }
+
+    fn description(&self) -> String {
+        "Convert a date/time from one timezone to another, accounting for daylight saving time"
+            .to_string()
+    }
+
+    fn parameters(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "time": {
+                    "type": "string",
+                    "description": "Time to convert: ISO 8601, Unix timestamp or natural language like 'tomorrow 9am' (default: now)"
+                },
+                "from_timezone": {
+                    "type": "string",
+                    "description": format!("IANA timezone the time is given in (default: {})", self.default_tz.name())
+                },
+                "to_timezone": {
+                    "type": "string",
+                    "description": "IANA timezone to convert to"
+                }
+            },
+            "required": ["to_timezone"]
+        })
+    }
+
+    async fn call(&self, arguments: Value) -> ToolResult<Value> {
+        let to = arguments["to_timezone"]
+            .as_str()
+            .ok_or_else(|| ToolError::InvalidArguments("Missing 'to_timezone'".to_string()))
+            .and_then(parse_timezone)?;
+        let from = timezone_arg(&arguments, "from_timezone", self.default_tz)?;
+        let time = time_arg(&arguments, "time", from)?;
+        Ok(json!({
+            "from": describe_time(&time),
+            "to": describe_time(&time.with_timezone(&to)),
+        }))
+    }
+}
+
+// ─── time:diff ──────────────────────────────────────────────────────────────
+
+/// Duration between two times
+pub struct TimeDiffTool {
+    default_tz: Tz,
+}
+
+impl TimeDiffTool {
+    pub fn new(default_tz: Tz) -> Self {
+        Self { default_tz }
+    }
+}
+
+#[async_trait]
+impl Tool for TimeDiffTool {
+    fn name(&self) -> String {
+        "time:diff".to_string()
+    }
+
+    fn description(&self) -> String {
+        "Compute the duration between two dates/times (end minus start)".to_string()
+    }
+
+    fn parameters(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "start": {
+                    "type": "string",
+                    "description": "Start time: ISO 8601, Unix timestamp or natural language"
+                },
+                "end": {
+                    "type": "string",
+                    "description": "End time (default: now)"
+                },
+                "timezone": {
+                    "type": "string",
+                    "description": format!("IANA timezone for times without an offset and for calendar days (default: {})", self.default_tz.name())
+                }
+            },
+            "required": ["start"]
+        })
+    }
+
+    async fn call(&self, arguments: Value) -> ToolResult<Value> {
+        if arguments["start"].as_str().is_none() {
+            return Err(ToolError::InvalidArguments("Missing 'start'".to_string()));
+        }
+        let tz = timezone_arg(&arguments, "timezone", self.default_tz)?;
+        let start = time_arg(&arguments, "start", tz)?;
+        let end = time_arg(&arguments, "end", tz)?;
+        let seconds = end.signed_duration_since(start).num_seconds();
+        Ok(json!({
+            "start": describe_time(&start),
+            "end": describe_time(&end),
+            "seconds": seconds,
+            "minutes": seconds as f64 / 60.0,
+            "hours": seconds as f64 / 3_600.0,
+            "days": seconds as f64 / 86_400.0,
+            "calendar_days": end.date_naive().signed_duration_since(start.date_naive()).num_days(),
+            "human": human_duration(seconds),
+            "end_is_after_start": seconds >= 0,
+        }))
+    }
+}
+
+// ─── time:parse ─────────────────────────────────────────────────────────────
+
+/// Resolve a date/time expression to an exact instant
+pub struct TimeParseTool {
+    default_tz: Tz,
+}
+
+impl TimeParseTool {
+    pub fn new(default_tz: Tz) -> Self {
+        Self { default_tz }
+    }
+}
+
+#[async_trait]
+impl Tool for TimeParseTool {
+    fn name(&self) -> String {
+        "time:parse".to_string()
+    }
+
+    fn description(&self) -> String {
+        "Resolve a date/time expression such as 'next friday 3pm', 'in 2 hours' or '2024-05-01' to an exact timestamp".to_string()
+    }
+
+    fn parameters(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "text": {
+                    "type": "string",
+                    "description": "Expression to parse: ISO 8601, RFC 2822, Unix timestamp, or natural language (today, tomorrow 9am, in 3 days, 2 hours ago, next monday, last month)"
+                },
+                "timezone": {
+                    "type": "string",
+                    "description": format!("IANA timezone for local times (default: {})", self.default_tz.name())
+                },
+                "reference": {
+                    "type": "string",
+                    "description": "Time that relative expressions count from (default: now)"
+                }
+            },
+            "required": ["text"]
+        })
+    }
+
+    async fn call(&self, arguments: Value) -> ToolResult<Value> {
+        let text = arguments["text"]
+            .as_str()
+            .ok_or_else(|| ToolError::InvalidArguments("Missing 'text'".to_string()))?;
+        let tz = timezone_arg(&arguments, "timezone", self.default_tz)?;
+        let reference = time_arg(&arguments, "reference", tz)?;
+        let parsed = parse_time(text, &reference).ok_or_else(|| {
+            ToolError::InvalidArguments(format!("Could not parse time '{}'", text))
+        })?;
+        let mut out = describe_time(&parsed);
+        out["input"] = json!(text);
+        Ok(out)
+    }
+}
@@ -191,6 +191,21 @@ impl Tool for WeatherTool {
         })
     }
 
+    fn output_schema(&self) -> Option<Value> {
+        Some(json!({
+            "type": "object",
+            "properties": {
+                "location": { "type": "string", "description": "Resolved location name" },
+                "temperature": { "type": "number", "description": "Temperature in °C" },
+                "conditions": { "type": "string" },
+                "humidity": { "type": "number", "description": "Relative humidity in %" },
+                "wind_speed": { "type": "number", "description": "Wind speed in km/h" },
+                "units": { "type": "string", "enum": ["metric"] }
+            },
+            "required": ["location", "temperature", "conditions", "humidity", "wind_speed", "units"]
+        }))
+    }
+
     async fn call(&self, arguments: Value) -> ToolResult<Value> {
         let location = arguments["location"]
             .as_str()
@@ -1,19 +1,267 @@
+//! `web:search` — web search over pluggable providers
+//!
+//! The backend is chosen by `WebSearchConfig::provider`: Brave Search (default), Bing Web
+//! Search, a SearxNG instance or DuckDuckGo's HTML endpoint. Each provider reads its own
+//! API key, and all of them return the same normalized `SearchResult` (title, url,
+//! snippet, published_at) with markup stripped, dates in RFC 3339 and duplicate URLs
+//! removed. Requests are spaced at least `min_interval_ms` apart, so bursts of tool
+//! calls queue instead of tripping the provider's rate limit.
+//!
+//! Other backends plug in through `SearchProvider` and `WebSearchTool::with_provider`.
+
 use crate::tools::{Tool, ToolError, ToolResult};
 use async_trait::async_trait;
+use regex::Regex;
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
+use std::collections::HashSet;
+use std::sync::{Arc, OnceLock};
 use std::time::Duration;
+use tokio::sync::Mutex;
+use tokio::time::Instant;
 use tracing::{debug, warn};
 
-/// Search result item
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Normalized search result, whichever provider produced it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct SearchResult {
     pub title: String,
     pub url: String,
     pub snippet: Option<String>,
+    /// Publication (or last crawl) time in RFC 3339, when the provider reports one
+    pub published_at: Option<String>,
+}
+
+/// Built-in search backends
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SearchProviderKind {
+    #[default]
+    Brave,
+    Bing,
+    Searxng,
+    DuckDuckGo,
+}
+
+impl SearchProviderKind {
+    /// Accepts `brave`, `bing`, `searxng` (or `searx`) and `duckduckgo` (or `ddg`)
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "brave" => Some(Self::Brave),
+            "bing" => Some(Self::Bing),
+            "searxng" | "searx" => Some(Self::Searxng),
+            "duckduckgo" | "ddg" => Some(Self::DuckDuckGo),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Brave => "brave",
+            Self::Bing => "bing",
+            Self::Searxng => "searxng",
+            Self::DuckDuckGo => "duckduckgo",
+        }
+    }
+
+    /// Environment variable holding this provider's API key
+    pub fn api_key_env(&self) -> Option<&'static str> {
+        match self {
+            Self::Brave => Some("BRAVE_API_KEY"),
+            Self::Bing => Some("BING_API_KEY"),
+            Self::Searxng => Some("SEARXNG_API_KEY"),
+            Self::DuckDuckGo => None,
+        }
+    }
+
+    /// Whether searches fail without an API key
+    pub fn requires_api_key(&self) -> bool {
+        matches!(self, Self::Brave | Self::Bing)
+    }
+
+    pub fn default_endpoint(&self) -> Option<&'static str> {
+        match self {
+            Self::Brave => Some("https://api.search.brave.com/res/v1/web/search"),
+            Self::Bing => Some("https://api.bing.microsoft.com/v7.0/search"),
+            // Self-hosted; there is no public default
+            Self::Searxng => None,
+            Self::DuckDuckGo => Some("https://html.duckduckgo.com/html/"),
+        }
+    }
+
+    /// Spacing between requests that stays inside the provider's free tier or etiquette
+    pub fn default_min_interval_ms(&self) -> u64 {
+        match self {
+            Self::Brave => 1_000,
+            Self::Bing => 334,
+            Self::Searxng => 0,
+            Self::DuckDuckGo => 2_000,
+        }
+    }
+}
+
+/// Configuration for the web search tool
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WebSearchConfig {
+    pub provider: SearchProviderKind,
+    /// Overrides the provider's endpoint; required for SearxNG (e.g. `http://localhost:8888`)
+    pub endpoint: Option<String>,
+    /// Key for the selected provider
+    #[serde(skip_serializing)]
+    pub api_key: Option<String>,
+    /// Minimum spacing between requests in milliseconds (0 disables rate limiting)
+    pub min_interval_ms: u64,
+    /// Timeout for search requests in milliseconds
+    pub timeout_ms: u64,
+    /// User agent string
+    pub user_agent: String,
+}
+
+impl std::fmt::Debug for WebSearchConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WebSearchConfig")
+            .field("provider", &self.provider)
+            .field("endpoint", &self.endpoint)
+            .field("api_key", &self.api_key.as_ref().map(|_| "***"))
+            .field("min_interval_ms", &self.min_interval_ms)
+            .field("timeout_ms", &self.timeout_ms)
+            .field("user_agent", &self.user_agent)
+            .finish()
+    }
+}
+
+impl Default for WebSearchConfig {
+    fn default() -> Self {
+        Self::for_provider(SearchProviderKind::default())
+    }
+}
+
+impl WebSearchConfig {
+    /// Defaults for `provider`, without an API key
+    pub fn for_provider(provider: SearchProviderKind) -> Self {
+        Self {
+            provider,
+            endpoint: None,
+            api_key: None,
+            min_interval_ms: provider.default_min_interval_ms(),
+            timeout_ms: 30_000,
+            user_agent: "Mozilla/5.0 (X11; Linux x86_64) AppleWebKit/537.36".to_string(),
+        }
+    }
+
+    /// Reads `LOOM_SEARCH_PROVIDER`, `LOOM_SEARCH_ENDPOINT` (or `SEARXNG_URL`),
+    /// `LOOM_SEARCH_MIN_INTERVAL_MS` and the selected provider's key variable
+    /// (`BRAVE_API_KEY`, `BING_API_KEY` or `SEARXNG_API_KEY`)
+    pub fn from_env() -> Self {
+        let provider = match std::env::var("LOOM_SEARCH_PROVIDER") {
+            Ok(value) => SearchProviderKind::parse(&value).unwrap_or_else(|| {
+                warn!(target: "web_search", provider = %value, "Unknown LOOM_SEARCH_PROVIDER, using brave");
+                SearchProviderKind::default()
+            }),
+            Err(_) => SearchProviderKind::default(),
+        };
+        let defaults = Self::for_provider(provider);
+        let non_empty = |name: &str| std::env::var(name).ok().filter(|v| !v.trim().is_empty());
+        Self {
+            endpoint: non_empty("LOOM_SEARCH_ENDPOINT").or_else(|| {
+                (provider == SearchProviderKind::Searxng)
+                    .then(|| non_empty("SEARXNG_URL"))
+                    .flatten()
+            }),
+            api_key: provider.api_key_env().and_then(non_empty),
+            min_interval_ms: std::env::var("LOOM_SEARCH_MIN_INTERVAL_MS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(defaults.min_interval_ms),
+            ..defaults
+        }
+    }
+
+    pub fn with_endpoint(mut self, endpoint: impl Into<String>) -> Self {
+        self.endpoint = Some(endpoint.into());
+        self
+    }
+
+    pub fn with_api_key(mut self, api_key: impl Into<String>) -> Self {
+        self.api_key = Some(api_key.into());
+        self
+    }
+
+    pub fn with_min_interval_ms(mut self, min_interval_ms: u64) -> Self {
+        self.min_interval_ms = min_interval_ms;
+        self
+    }
+}
+
+/// A search backend. Implementations return results as the provider reports them;
+/// `WebSearchTool` normalizes, deduplicates and truncates them.
+#[async_trait]
+pub trait SearchProvider: Send + Sync {
+    /// Short name reported in tool output (e.g. "brave")
+    fn name(&self) -> &str;
+
+    async fn search(
+        &self,
+        http: &reqwest::Client,
+        query: &str,
+        count: usize,
+    ) -> ToolResult<Vec<SearchResult>>;
+}
+
+/// Sanitize a proxy URL for safe logging (redact credentials)
+fn sanitize_proxy_url(proxy_url: &str) -> String {
+    // Try to parse and redact credentials
+    if let Ok(mut parsed) = url::Url::parse(proxy_url) {
+        if !parsed.username().is_empty() || parsed.password().is_some() {
+            let _ = parsed.set_username("***");
+            let _ = parsed.set_password(Some("***"));
+        }
+        parsed.to_string()
+    } else {
+        // If parsing fails, just show host:port pattern or redact entirely
+        "[proxy configured]".to_string()
+    }
+}
+
+fn request_error(e: reqwest::Error) -> ToolError {
+    tracing::error!(target: "web_search", error = %e, "Request failed");
+    if e.is_timeout() {
+        ToolError::ExecutionFailed(format!("Search request timed out: {}", e))
+    } else if e.is_connect() {
+        ToolError::ExecutionFailed(format!("Connection failed: {}", e))
+    } else {
+        ToolError::ExecutionFailed(format!(
+            "Search request failed: {} (is_request={}, is_body={}, is_decode={})",
+            e,
+            e.is_request(),
+            e.is_body(),
+            e.is_decode()
+        ))
+    }
 }
 
-/// Brave Search API response structures
+/// Send `request`, failing on non-2xx with the provider's name and response body
+async fn send(provider: &str, request: reqwest::RequestBuilder) -> ToolResult<reqwest::Response> {
+    let resp = request.send().await.map_err(request_error)?;
+    if !resp.status().is_success() {
+        let status = resp.status();
+        let body = resp.text().await.unwrap_or_default();
+        return Err(ToolError::ExecutionFailed(format!(
+            "{} API error: {} - {}",
+            provider, status, body
+        )));
+    }
+    Ok(resp)
+}
+
+async fn parse_json<T: serde::de::DeserializeOwned>(resp: reqwest::Response) -> ToolResult<T> {
+    resp.json()
+        .await
+        .map_err(|e| ToolError::ExecutionFailed(format!("Failed to parse search response: {}", e)))
+}
+
+// ─── Brave ──────────────────────────────────────────────────────────────────
+
 #[derive(Debug, Deserialize)]
 struct BraveSearchResponse {
     web: Option<BraveWebResults>,
@@ -29,27 +277,452 @@ struct BraveWebResult {
     title: String,
     url: String,
     description: Option<String>,
+    page_age: Option<String>,
 }
 
-/// Sanitize a proxy URL for safe logging (redact credentials)
-fn sanitize_proxy_url(proxy_url: &str) -> String {
-    // Try to parse and redact credentials
-    if let Ok(mut parsed) = url::Url::parse(proxy_url) {
-        if !parsed.username().is_empty() || parsed.password().is_some() {
-            let _ = parsed.set_username("***");
-            let _ = parsed.set_password(Some("***"));
+/// Brave Search API
+pub struct BraveProvider {
+    endpoint: String,
+    api_key: Option<String>,
+}
+
+#[async_trait]
+impl SearchProvider for BraveProvider {
+    fn name(&self) -> &str {
+        "brave"
+    }
+
+    async fn search(
+        &self,
+        http: &reqwest::Client,
+        query: &str,
+        count: usize,
+    ) -> ToolResult<Vec<SearchResult>> {
+        let api_key = self.api_key.as_ref().ok_or_else(|| {
+            ToolError::ExecutionFailed(
+                "BRAVE_API_KEY not configured. Set it in environment or loom.toml".to_string(),
+            )
+        })?;
+        let request = http
+            .get(&self.endpoint)
+            .query(&[("q", query), ("count", &count.to_string())])
+            .header("Accept", "application/json")
+            .header("Accept-Encoding", "gzip")
+            .header("X-Subscription-Token", api_key);
+        let data: BraveSearchResponse = parse_json(send("Brave Search", request).await?).await?;
+        Ok(data
+            .web
+            .map(|web| {
+                web.results
+                    .into_iter()
+                    .map(|r| SearchResult {
+                        title: r.title,
+                        url: r.url,
+                        snippet: r.description,
+                        published_at: r.page_age,
+                    })
+                    .collect()
+            })
+            .unwrap_or_default())
+    }
+}
+
+// ─── Bing ───────────────────────────────────────────────────────────────────
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BingSearchResponse {
+    web_pages: Option<BingWebPages>,
+}
+
+#[derive(Debug, Deserialize)]
+struct BingWebPages {
+    value: Vec<BingWebPage>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BingWebPage {
+    name: String,
+    url: String,
+    snippet: Option<String>,
+    date_published: Option<String>,
+    date_last_crawled: Option<String>,
+}
+
+/// Bing Web Search API (v7)
+pub struct BingProvider {
+    endpoint: String,
+    api_key: Option<String>,
+}
+
+#[async_trait]
+impl SearchProvider for BingProvider {
+    fn name(&self) -> &str {
+        "bing"
+    }
+
+    async fn search(
+        &self,
+        http: &reqwest::Client,
+        query: &str,
+        count: usize,
+    ) -> ToolResult<Vec<SearchResult>> {
+        let api_key = self.api_key.as_ref().ok_or_else(|| {
+            ToolError::ExecutionFailed(
+                "BING_API_KEY not configured. Set it in environment or loom.toml".to_string(),
+            )
+        })?;
+        let request = http
+            .get(&self.endpoint)
+            .query(&[
+                ("q", query),
+                ("count", &count.to_string()),
+                ("textFormat", "Raw"),
+            ])
+            .header("Ocp-Apim-Subscription-Key", api_key);
+        let data: BingSearchResponse = parse_json(send("Bing Search", request).await?).await?;
+        Ok(data
+            .web_pages
+            .map(|pages| {
+                pages
+                    .value
+                    .into_iter()
+                    .map(|p| SearchResult {
+                        title: p.name,
+                        url: p.url,
+                        snippet: p.snippet,
+                        published_at: p.date_published.or(p.date_last_crawled),
+                    })
+                    .collect()
+            })
+            .unwrap_or_default())
+    }
+}
+
+// ─── SearxNG ────────────────────────────────────────────────────────────────
+
+#[derive(Debug, Deserialize)]
+struct SearxngResponse {
+    #[serde(default)]
+    results: Vec<SearxngResult>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SearxngResult {
+    title: String,
+    url: String,
+    content: Option<String>,
+    published_date: Option<String>,
+}
+
+/// A SearxNG instance's JSON API (`format=json` must be enabled in its settings)
+pub struct SearxngProvider {
+    endpoint: String,
+    api_key: Option<String>,
+}
+
+#[async_trait]
+impl SearchProvider for SearxngProvider {
+    fn name(&self) -> &str {
+        "searxng"
+    }
+
+    async fn search(
+        &self,
+        http: &reqwest::Client,
+        query: &str,
+        _count: usize,
+    ) -> ToolResult<Vec<SearchResult>> {
+        if self.endpoint.is_empty() {
+            return Err(ToolError::ExecutionFailed(
+                "SearxNG search needs an endpoint. Set LOOM_SEARCH_ENDPOINT or SEARXNG_URL"
+                    .to_string(),
+            ));
         }
-        parsed.to_string()
+        // SearxNG pages by page number only; the tool truncates to `count`
+        let url = format!("{}/search", self.endpoint.trim_end_matches('/'));
+        let mut request = http
+            .get(&url)
+            .query(&[("q", query), ("format", "json")])
+            .header("Accept", "application/json");
+        // Instances behind an authenticating proxy
+        if let Some(api_key) = &self.api_key {
+            request = request.bearer_auth(api_key);
+        }
+        let data: SearxngResponse = parse_json(send("SearxNG", request).await?).await?;
+        Ok(data
+            .results
+            .into_iter()
+            .map(|r| SearchResult {
+                title: r.title,
+                url: r.url,
+                snippet: r.content,
+                published_at: r.published_date,
+            })
+            .collect())
+    }
+}
+
+// ─── DuckDuckGo ─────────────────────────────────────────────────────────────
+
+/// DuckDuckGo's HTML results page; needs no key, but the markup is unofficial and may
+/// change
+pub struct DuckDuckGoProvider {
+    endpoint: String,
+}
+
+#[async_trait]
+impl SearchProvider for DuckDuckGoProvider {
+    fn name(&self) -> &str {
+        "duckduckgo"
+    }
+
+    async fn search(
+        &self,
+        http: &reqwest::Client,
+        query: &str,
+        _count: usize,
+    ) -> ToolResult<Vec<SearchResult>> {
+        let request = http
+            .get(&self.endpoint)
+            .query(&[("q", query)])
+            .header("Accept", "text/html");
+        let html = send("DuckDuckGo", request)
+            .await?
+            .text()
+            .await
+            .map_err(request_error)?;
+        Ok(parse_duckduckgo_html(&html))
+    }
+}
+
+/// Results from DuckDuckGo's HTML page: `result__a` links followed by `result__snippet`
+pub fn parse_duckduckgo_html(html: &str) -> Vec<SearchResult> {
+    static PATTERNS: OnceLock<(Regex, Regex, Regex)> = OnceLock::new();
+    let (link, href, snippet) = PATTERNS.get_or_init(|| {
+        (
+            Regex::new(r#"(?s)<a\s([^>]*class="[^"]*\bresult__a\b[^"]*"[^>]*)>(.*?)</a>"#)
+                .expect("valid regex"),
+            Regex::new(r#"\bhref="([^"]+)""#).expect("valid regex"),
+            // Snippets contain <b> highlights, so match up to the closing container tag
+            Regex::new(
+                r#"(?s)class="[^"]*\bresult__snippet\b[^"]*"[^>]*>(.*?)</(?:a|div|td|span)>"#,
+            )
+            .expect("valid regex"),
+        )
+    });
+
+    let links: Vec<_> = link.captures_iter(html).collect();
+    let mut results = Vec::with_capacity(links.len());
+    for (i, caps) in links.iter().enumerate() {
+        let whole = caps.get(0).expect("match");
+        // A snippet belongs to the link before it
+        let end = links
+            .get(i + 1)
+            .and_then(|next| next.get(0))
+            .map(|m| m.start())
+            .unwrap_or(html.len());
+        let Some(target) = href.captures(&caps[1]) else {
+            continue;
+        };
+        let snippet = snippet
+            .captures(&html[whole.end()..end])
+            .map(|s| s[1].to_string());
+        results.push(SearchResult {
+            title: caps[2].to_string(),
+            url: duckduckgo_target(&decode_entities(&target[1])),
+            snippet,
+            published_at: None,
+        });
+    }
+    results
+}
+
+/// Unwrap DuckDuckGo's `/l/?uddg=<target>` redirect links
+fn duckduckgo_target(href: &str) -> String {
+    let absolute = if href.starts_with("//") {
+        format!("https:{}", href)
     } else {
-        // If parsing fails, just show host:port pattern or redact entirely
-        "[proxy configured]".to_string()
+        href.to_string()
+    };
+    url::Url::parse(&absolute)
+        .ok()
+        .filter(|u| u.path().starts_with("/l/"))
+        .and_then(|u| {
+            u.query_pairs()
+                .find(|(k, _)| k == "uddg")
+                .map(|(_, v)| v.into_owned())
+        })
+        .unwrap_or(absolute)
+}
+
+// ─── Normalization ──────────────────────────────────────────────────────────
+
+/// Trim and strip markup from titles and snippets, convert dates to RFC 3339, drop
+/// results without an http(s) URL or title, remove duplicate URLs and keep at most `limit`
+pub fn normalize_results(results: Vec<SearchResult>, limit: usize) -> Vec<SearchResult> {
+    let mut seen = HashSet::new();
+    results
+        .into_iter()
+        .filter_map(|r| {
+            let url = r.url.trim().to_string();
+            let scheme_ok = url::Url::parse(&url)
+                .map(|u| matches!(u.scheme(), "http" | "https"))
+                .unwrap_or(false);
+            let title = clean_text(&r.title);
+            if !scheme_ok || title.is_empty() {
+                return None;
+            }
+            // Trailing slashes and fragments point at the same page
+            let key = url
+                .split('#')
+                .next()
+                .unwrap_or(&url)
+                .trim_end_matches('/')
+                .to_ascii_lowercase();
+            if !seen.insert(key) {
+                return None;
+            }
+            Some(SearchResult {
+                title,
+                url,
+                snippet: r.snippet.map(|s| clean_text(&s)).filter(|s| !s.is_empty()),
+                published_at: r.published_at.as_deref().and_then(normalize_date),
+            })
+        })
+        .take(limit)
+        .collect()
+}
+
+/// Remove tags, decode entities and collapse whitespace
+fn clean_text(text: &str) -> String {
+    let mut stripped = String::with_capacity(text.len());
+    let mut in_tag = false;
+    for c in text.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' if in_tag => in_tag = false,
+            _ if !in_tag => stripped.push(c),
+            _ => {}
+        }
+    }
+    decode_entities(&stripped)
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn decode_entities(text: &str) -> String {
+    if !text.contains('&') {
+        return text.to_string();
+    }
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('&') {
+        out.push_str(&rest[..start]);
+        let tail = &rest[start..];
+        let decoded = tail.find(';').filter(|end| *end <= 10).and_then(|end| {
+            let entity = &tail[1..end];
+            let c = match entity {
+                "amp" => Some('&'),
+                "lt" => Some('<'),
+                "gt" => Some('>'),
+                "quot" => Some('"'),
+                "apos" => Some('\''),
+                "nbsp" => Some(' '),
+                _ => entity
+                    .strip_prefix("#x")
+                    .or_else(|| entity.strip_prefix("#X"))
+                    .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+                    .or_else(|| entity.strip_prefix('#').and_then(|d| d.parse().ok()))
+                    .and_then(char::from_u32),
+            };
+            c.map(|c| (c, end))
+        });
+        match decoded {
+            Some((c, end)) => {
+                out.push(c);
+                rest = &tail[end + 1..];
+            }
+            None => {
+                out.push('&');
+                rest = &tail[1..];
+            }
+        }
     }
+    out.push_str(rest);
+    out
 }
 
-/// Web search tool using Brave Search API
+/// RFC 3339 (UTC) from the date formats providers use; None when unparseable
+fn normalize_date(value: &str) -> Option<String> {
+    use chrono::{DateTime, NaiveDate, NaiveDateTime, SecondsFormat, Utc};
+    let value = value.trim();
+    if let Ok(dt) = DateTime::parse_from_rfc3339(value) {
+        return Some(
+            dt.with_timezone(&Utc)
+                .to_rfc3339_opts(SecondsFormat::Secs, true),
+        );
+    }
+    if let Ok(dt) = DateTime::parse_from_rfc2822(value) {
+        return Some(
+            dt.with_timezone(&Utc)
+                .to_rfc3339_opts(SecondsFormat::Secs, true),
+        );
+    }
+    // Timestamps without an offset are taken as UTC
+    if let Ok(dt) = NaiveDateTime::parse_from_str(value, "%Y-%m-%dT%H:%M:%S%.f") {
+        return Some(dt.and_utc().to_rfc3339_opts(SecondsFormat::Secs, true));
+    }
+    NaiveDate::parse_from_str(value, "%Y-%m-%d").ok().map(|d| {
+        d.and_hms_opt(0, 0, 0)
+            .expect("midnight")
+            .and_utc()
+            .to_rfc3339_opts(SecondsFormat::Secs, true)
+    })
+}
+
+// ─── Tool ───────────────────────────────────────────────────────────────────
+
+/// Spaces requests `interval` apart; callers wait for their slot in arrival order
+struct RateLimiter {
+    interval: Duration,
+    next: Mutex<Option<Instant>>,
+}
+
+impl RateLimiter {
+    fn new(interval: Duration) -> Self {
+        Self {
+            interval,
+            next: Mutex::new(None),
+        }
+    }
+
+    async fn acquire(&self) {
+        if self.interval.is_zero() {
+            return;
+        }
+        let slot = {
+            let mut next = self.next.lock().await;
+            let now = Instant::now();
+            let slot = next.map_or(now, |n| n.max(now));
+            *next = Some(slot + self.interval);
+            slot
+        };
+        if slot > Instant::now() {
+            debug!(target: "web_search", wait_ms = %(slot - Instant::now()).as_millis(), "Rate limited");
+            tokio::time::sleep_until(slot).await;
+        }
+    }
+}
+
+/// Web search tool backed by a configurable `SearchProvider`
 pub struct WebSearchTool {
-    api_key: Option<String>,
+    provider: Arc<dyn SearchProvider>,
     http_client: reqwest::Client,
+    rate_limiter: RateLimiter,
 }
 
 impl Default for WebSearchTool {
@@ -59,20 +732,32 @@ impl Default for WebSearchTool {
 }
 
 impl WebSearchTool {
-    /// Create a new web search tool, reading API key from environment
+    /// Create a new web search tool configured from the environment
     pub fn new() -> Self {
-        let api_key = std::env::var("BRAVE_API_KEY").ok();
+        Self::with_config(WebSearchConfig::from_env())
+    }
 
-        if api_key.is_some() {
-            tracing::info!(target: "web_search", "Brave Search API key configured");
-        } else {
-            warn!(target: "web_search", "BRAVE_API_KEY not set, web search will not work");
+    /// Create a Brave search tool with an explicit API key
+    pub fn with_api_key(api_key: String) -> Self {
+        Self::with_config(WebSearchConfig::default().with_api_key(api_key))
+    }
+
+    /// Create a search tool for the configured provider
+    pub fn with_config(config: WebSearchConfig) -> Self {
+        if config.api_key.is_some() {
+            tracing::info!(target: "web_search", provider = config.provider.as_str(), "Search API key configured");
+        } else if let Some(var) = config
+            .provider
+            .api_key_env()
+            .filter(|_| config.provider.requires_api_key())
+        {
+            warn!(target: "web_search", "{} not set, web search will not work", var);
         }
 
         // Build HTTP client with optional proxy support
         let mut client_builder = reqwest::Client::builder()
-            .timeout(Duration::from_secs(30))
-            .user_agent("Mozilla/5.0 (X11; Linux x86_64) AppleWebKit/537.36");
+            .timeout(Duration::from_millis(config.timeout_ms))
+            .user_agent(config.user_agent.clone());
 
         // Check for proxy settings (HTTP_PROXY, HTTPS_PROXY, or ALL_PROXY)
         if let Ok(proxy_url) = std::env::var("HTTPS_PROXY")
@@ -95,99 +780,53 @@ impl WebSearchTool {
             .unwrap_or_else(|_| reqwest::Client::new());
 
         Self {
-            api_key,
+            provider: builtin_provider(&config),
             http_client,
+            rate_limiter: RateLimiter::new(Duration::from_millis(config.min_interval_ms)),
         }
     }
 
-    /// Create with explicit API key
-    pub fn with_api_key(api_key: String) -> Self {
-        let http_client = reqwest::Client::builder()
-            .timeout(Duration::from_millis(15_000))
-            .user_agent("loom-agent/0.1")
-            .build()
-            .unwrap_or_else(|_| reqwest::Client::new());
-
-        Self {
-            api_key: Some(api_key),
-            http_client,
-        }
+    /// Replace the backend, e.g. with a custom `SearchProvider`
+    pub fn with_provider(mut self, provider: Arc<dyn SearchProvider>) -> Self {
+        self.provider = provider;
+        self
     }
 
-    /// Perform search using Brave Search API
-    async fn search_brave(&self, query: &str, count: usize) -> ToolResult<Vec<SearchResult>> {
-        let api_key = self.api_key.as_ref().ok_or_else(|| {
-            ToolError::ExecutionFailed(
-                "BRAVE_API_KEY not configured. Set it in environment or loom.toml".to_string(),
-            )
-        })?;
-
-        debug!(target: "web_search", query=%query, count=%count, "Performing Brave search");
-
-        let url = format!(
-            "https://api.search.brave.com/res/v1/web/search?q={}&count={}",
-            urlencoding::encode(query),
-            count
-        );
-
-        let resp = self
-            .http_client
-            .get(&url)
-            .header("Accept", "application/json")
-            .header("Accept-Encoding", "gzip")
-            .header("X-Subscription-Token", api_key)
-            .send()
-            .await
-            .map_err(|e| {
-                tracing::error!(target: "web_search", error = %e, "Request failed");
-                if e.is_timeout() {
-                    ToolError::ExecutionFailed(format!("Search request timed out: {}", e))
-                } else if e.is_connect() {
-                    ToolError::ExecutionFailed(format!("Connection failed: {}", e))
-                } else {
-                    ToolError::ExecutionFailed(format!(
-                        "Search request failed: {} (is_request={}, is_body={}, is_decode={})",
-                        e,
-                        e.is_request(),
-                        e.is_body(),
-                        e.is_decode()
-                    ))
-                }
-            })?;
-
-        if !resp.status().is_success() {
-            let status = resp.status();
-            let body = resp.text().await.unwrap_or_default();
-            return Err(ToolError::ExecutionFailed(format!(
-                "Brave Search API error: {} - {}",
-                status, body
-            )));
-        }
-
-        let data: BraveSearchResponse = resp.json().await.map_err(|e| {
-            ToolError::ExecutionFailed(format!("Failed to parse search response: {}", e))
-        })?;
-
-        let results: Vec<SearchResult> = data
-            .web
-            .map(|web| {
-                web.results
-                    .into_iter()
-                    .map(|r| SearchResult {
-                        title: r.title,
-                        url: r.url,
-                        snippet: r.description,
-                    })
-                    .collect()
-            })
-            .unwrap_or_default();
+    /// Name of the backend in use
+    pub fn provider_name(&self) -> &str {
+        self.provider.name()
+    }
 
+    /// Search and return normalized results
+    pub async fn search(&self, query: &str, count: usize) -> ToolResult<Vec<SearchResult>> {
+        self.rate_limiter.acquire().await;
+        debug!(target: "web_search", provider = %self.provider.name(), query=%query, count=%count, "Performing search");
+        let results = self
+            .provider
+            .search(&self.http_client, query, count)
+            .await?;
+        let results = normalize_results(results, count);
         debug!(target: "web_search", result_count=%results.len(), "Search completed");
-
         Ok(results)
     }
 }
 
+fn builtin_provider(config: &WebSearchConfig) -> Arc<dyn SearchProvider> {
+    // Empty for SearxNG without an endpoint; reported on first search
+    let endpoint = config
+        .endpoint
+        .clone()
+        .or_else(|| config.provider.default_endpoint().map(str::to_string))
+        .unwrap_or_default();
+    let api_key = config.api_key.clone();
+    match config.provider {
+        SearchProviderKind::Brave => Arc::new(BraveProvider { endpoint, api_key }),
+        SearchProviderKind::Bing => Arc::new(BingProvider { endpoint, api_key }),
+        SearchProviderKind::Searxng => Arc::new(SearxngProvider { endpoint, api_key }),
+        SearchProviderKind::DuckDuckGo => Arc::new(DuckDuckGoProvider { endpoint }),
+    }
+}
+
 #[async_trait]
 impl Tool for WebSearchTool {
     fn name(&self) -> String {
@@ -195,7 +834,10 @@ impl Tool for WebSearchTool {
     }
 
     fn description(&self) -> String {
-        "Search the web for information using Brave Search".to_string()
+        format!(
+            "Search the web for information (via {})",
+            self.provider.name()
+        )
     }
 
     fn parameters(&self) -> Value {
@@ -215,6 +857,31 @@ impl Tool for WebSearchTool {
         })
     }
 
+    fn output_schema(&self) -> Option<Value> {
+        Some(json!({
+            "type": "object",
+            "properties": {
+                "query": { "type": "string" },
+                "provider": { "type": "string" },
+                "results": {
+                    "type": "array",
+                    "items": {
+                        "type": "object",
+                        "properties": {
+                            "title": { "type": "string" },
+                            "url": { "type": "string" },
+                            "snippet": { "type": ["string", "null"] },
+                            "published_at": { "type": ["string", "null"] }
+                        },
+                        "required": ["title", "url"]
+                    }
+                },
+                "count": { "type": "integer" }
+            },
+            "required": ["query", "results", "count"]
+        }))
+    }
+
     async fn call(&self, arguments: Value) -> ToolResult<Value> {
         let query = arguments["query"]
             .as_str()
@@ -222,10 +889,11 @@ impl Tool for WebSearchTool {
 
         let limit = arguments["limit"].as_u64().unwrap_or(5).min(20) as usize;
 
-        let results = self.search_brave(query, limit).await?;
+        let results = self.search(query, limit).await?;
 
         Ok(json!({
             "query": query,
+            "provider": self.provider.name(),
             "results": results,
             "count": results.len()
         }))
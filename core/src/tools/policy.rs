@@ -0,0 +1,291 @@
+//! Invocation policy for tools: timeout, retries with exponential backoff and a
+//! circuit breaker with half-open probing.
+//!
+//! Policies are attached to a `ToolRegistry` either as the registry-wide default or
+//! per tool name. Only transient failures (timeouts, execution/internal errors) are
+//! retried and counted by the circuit breaker; caller errors such as invalid
+//! arguments never trip it.
+
+use super::error::ToolError;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use tokio::time::Instant;
+
+/// Circuit breaker settings
+#[derive(Debug, Clone, PartialEq)]
+pub struct CircuitBreakerConfig {
+    /// Consecutive failed calls before the circuit opens
+    pub failure_threshold: u32,
+    /// How long the circuit stays open before allowing a half-open probe
+    pub open_duration: Duration,
+    /// Concurrent probe calls allowed while half-open
+    pub half_open_max_calls: u32,
+}
+
+impl Default for CircuitBreakerConfig {
+    fn default() -> Self {
+        Self {
+            failure_threshold: 5,
+            open_duration: Duration::from_secs(30),
+            half_open_max_calls: 1,
+        }
+    }
+}
+
+/// Policy applied by `ToolRegistry::call`
+#[derive(Debug, Clone, PartialEq)]
+pub struct ToolPolicy {
+    /// Timeout for a single attempt
+    pub timeout: Duration,
+    /// Retries after the first attempt (0 = no retries)
+    pub max_retries: u32,
+    /// Delay before the first retry
+    pub initial_backoff: Duration,
+    /// Upper bound for the retry delay
+    pub max_backoff: Duration,
+    /// Factor applied to the delay after each retry
+    pub backoff_multiplier: f64,
+    /// Circuit breaker; `None` disables it
+    pub circuit_breaker: Option<CircuitBreakerConfig>,
+}
+
+impl Default for ToolPolicy {
+    fn default() -> Self {
+        Self {
+            timeout: Duration::from_secs(30),
+            max_retries: 0,
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_secs(5),
+            backoff_multiplier: 2.0,
+            circuit_breaker: Some(CircuitBreakerConfig::default()),
+        }
+    }
+}
+
+impl ToolPolicy {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    pub fn with_retries(mut self, max_retries: u32) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+
+    pub fn with_backoff(mut self, initial: Duration, max: Duration, multiplier: f64) -> Self {
+        self.initial_backoff = initial;
+        self.max_backoff = max;
+        self.backoff_multiplier = multiplier;
+        self
+    }
+
+    pub fn with_circuit_breaker(mut self, config: CircuitBreakerConfig) -> Self {
+        self.circuit_breaker = Some(config);
+        self
+    }
+
+    pub fn without_circuit_breaker(mut self) -> Self {
+        self.circuit_breaker = None;
+        self
+    }
+
+    /// Delay before retry number `retry` (0-based), capped at `max_backoff`
+    pub fn backoff_for(&self, retry: u32) -> Duration {
+        let factor = self.backoff_multiplier.max(1.0).powi(retry as i32);
+        let delay = self.initial_backoff.as_secs_f64() * factor;
+        Duration::from_secs_f64(delay.min(self.max_backoff.as_secs_f64()))
+    }
+}
+
+/// Whether an error is transient and worth retrying / counting against the breaker
+pub fn is_transient(err: &ToolError) -> bool {
+    matches!(
+        err,
+        ToolError::Timeout | ToolError::ExecutionFailed(_) | ToolError::Internal(_)
+    )
+}
+
+/// Circuit breaker state
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CircuitState {
+    /// Calls flow normally
+    Closed,
+    /// Calls are rejected until `open_duration` elapses
+    Open,
+    /// A limited number of probe calls decide whether to close or reopen
+    HalfOpen,
+}
+
+/// Snapshot of a tool's policy state and counters
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ToolStats {
+    pub name: String,
+    pub circuit_state: CircuitState,
+    /// Calls received (including rejected ones)
+    pub calls: u64,
+    pub successes: u64,
+    pub failures: u64,
+    /// Retry attempts performed
+    pub retries: u64,
+    /// Attempts that hit the policy timeout
+    pub timeouts: u64,
+    /// Calls rejected by an open circuit
+    pub rejected: u64,
+    /// Calls stopped by their cancellation token
+    #[serde(default)]
+    pub cancelled: u64,
+    pub consecutive_failures: u32,
+    pub last_error: Option<String>,
+}
+
+/// Mutable per-tool state tracked by the registry
+#[derive(Debug)]
+pub(crate) struct ToolState {
+    pub(crate) circuit: CircuitState,
+    opened_at: Option<Instant>,
+    half_open_inflight: u32,
+    pub(crate) calls: u64,
+    pub(crate) successes: u64,
+    pub(crate) failures: u64,
+    pub(crate) retries: u64,
+    pub(crate) timeouts: u64,
+    pub(crate) rejected: u64,
+    pub(crate) cancelled: u64,
+    pub(crate) consecutive_failures: u32,
+    pub(crate) last_error: Option<String>,
+}
+
+/// Outcome of asking the breaker for permission to call
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Admission {
+    /// Normal call, retries allowed
+    Allowed,
+    /// Half-open probe: single attempt, result decides the circuit
+    Probe,
+    /// Circuit open: reject without calling
+    Rejected,
+}
+
+impl Default for ToolState {
+    fn default() -> Self {
+        Self {
+            circuit: CircuitState::Closed,
+            opened_at: None,
+            half_open_inflight: 0,
+            calls: 0,
+            successes: 0,
+            failures: 0,
+            retries: 0,
+            timeouts: 0,
+            rejected: 0,
+            cancelled: 0,
+            consecutive_failures: 0,
+            last_error: None,
+        }
+    }
+}
+
+impl ToolState {
+    pub(crate) fn admit(&mut self, breaker: Option<&CircuitBreakerConfig>) -> Admission {
+        self.calls += 1;
+        let Some(cfg) = breaker else {
+            return Admission::Allowed;
+        };
+
+        if self.circuit == CircuitState::Open {
+            let elapsed = self.opened_at.map(|t| t.elapsed()).unwrap_or_default();
+            if elapsed >= cfg.open_duration {
+                self.circuit = CircuitState::HalfOpen;
+                self.half_open_inflight = 0;
+            }
+        }
+
+        match self.circuit {
+            CircuitState::Closed => Admission::Allowed,
+            CircuitState::HalfOpen if self.half_open_inflight < cfg.half_open_max_calls.max(1) => {
+                self.half_open_inflight += 1;
+                Admission::Probe
+            }
+            _ => {
+                self.rejected += 1;
+                Admission::Rejected
+            }
+        }
+    }
+
+    pub(crate) fn record_success(&mut self, admission: Admission) {
+        self.successes += 1;
+        self.consecutive_failures = 0;
+        if admission == Admission::Probe {
+            self.half_open_inflight = self.half_open_inflight.saturating_sub(1);
+            self.close();
+        }
+    }
+
+    /// A cancelled call is neither a success nor a failure; a probe releases its slot
+    pub(crate) fn record_cancel(&mut self, admission: Admission) {
+        self.cancelled += 1;
+        if admission == Admission::Probe {
+            self.half_open_inflight = self.half_open_inflight.saturating_sub(1);
+        }
+    }
+
+    pub(crate) fn record_failure(
+        &mut self,
+        admission: Admission,
+        err: &ToolError,
+        breaker: Option<&CircuitBreakerConfig>,
+    ) {
+        self.failures += 1;
+        self.last_error = Some(err.to_string());
+        if admission == Admission::Probe {
+            self.half_open_inflight = self.half_open_inflight.saturating_sub(1);
+        }
+        if !is_transient(err) {
+            // Caller errors say nothing about tool health; a probe that hit one
+            // releases its slot and leaves the circuit half-open.
+            return;
+        }
+        self.consecutive_failures += 1;
+        let Some(cfg) = breaker else {
+            return;
+        };
+        if admission == Admission::Probe || self.consecutive_failures >= cfg.failure_threshold {
+            self.open();
+        }
+    }
+
+    fn open(&mut self) {
+        self.circuit = CircuitState::Open;
+        self.opened_at = Some(Instant::now());
+        self.half_open_inflight = 0;
+    }
+
+    fn close(&mut self) {
+        self.circuit = CircuitState::Closed;
+        self.opened_at = None;
+        self.half_open_inflight = 0;
+        self.consecutive_failures = 0;
+    }
+
+    pub(crate) fn snapshot(&self, name: &str) -> ToolStats {
+        ToolStats {
+            name: name.to_string(),
+            circuit_state: self.circuit,
+            calls: self.calls,
+            successes: self.successes,
+            failures: self.failures,
+            retries: self.retries,
+            timeouts: self.timeouts,
+            rejected: self.rejected,
+            cancelled: self.cancelled,
+            consecutive_failures: self.consecutive_failures,
+            last_error: self.last_error.clone(),
+        }
+    }
+}
@@ -1,25 +1,54 @@
 use super::error::{ToolError, ToolResult};
+use super::policy::{is_transient, Admission, ToolPolicy, ToolState, ToolStats};
+use super::schema;
 use super::traits::Tool;
+use crate::namespace::{Namespace, TOOL_NAMESPACE_SEPARATOR};
 use dashmap::DashMap;
 use opentelemetry::{
     global,
     metrics::{Counter, Histogram, UpDownCounter},
     KeyValue,
 };
-use std::sync::Arc;
-use std::time::Duration;
-use tokio::time::timeout;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, RwLock};
+use tokio::sync::Notify;
+use tokio::time::{timeout, Instant};
+use tokio_util::sync::CancellationToken;
 use tracing::{debug, info, warn};
 
 /// A registry for managing available tools
+///
+/// Every `call` goes through a `ToolPolicy` (timeout, retries, circuit breaker):
+/// the per-tool policy if one was set, otherwise the registry default.
+///
+/// Results are checked against the tool's `output_schema`. Mismatches are logged;
+/// with `set_strict_outputs(true)` they fail the call with `ToolError::InvalidOutput`.
+///
+/// Tools registered with `register_in` belong to one namespace and are only visible,
+/// by their plain names, through that namespace's `for_namespace` handle.
 #[derive(Clone)]
 pub struct ToolRegistry {
     tools: Arc<DashMap<String, Arc<dyn Tool>>>,
+    default_policy: Arc<RwLock<ToolPolicy>>,
+    policies: Arc<DashMap<String, ToolPolicy>>,
+    states: Arc<DashMap<String, ToolState>>,
+    inflight: Arc<AtomicUsize>,
+    idle: Arc<Notify>,
+    closed: Arc<AtomicBool>,
+    strict_outputs: Arc<AtomicBool>,
+    // Names visible through this handle (see `scoped`); `None` shows every tool
+    allowed: Option<Arc<Vec<String>>>,
+    // Namespace whose tools this handle sees (see `for_namespace`)
+    namespace: Option<Namespace>,
 
     // OpenTelemetry metrics
     invocations_counter: Counter<u64>,
     errors_counter: Counter<u64>,
     timeouts_counter: Counter<u64>,
+    retries_counter: Counter<u64>,
+    rejected_counter: Counter<u64>,
+    cancelled_counter: Counter<u64>,
+    invalid_outputs_counter: Counter<u64>,
     invoke_latency: Histogram<f64>,
     registered_tools_gauge: UpDownCounter<i64>,
 }
@@ -49,6 +78,26 @@ impl ToolRegistry {
             .with_description("Total number of tool timeouts")
             .init();
 
+        let retries_counter = meter
+            .u64_counter("loom.tool_registry.retries_total")
+            .with_description("Total number of tool retry attempts")
+            .init();
+
+        let rejected_counter = meter
+            .u64_counter("loom.tool_registry.circuit_rejections_total")
+            .with_description("Total number of calls rejected by an open circuit")
+            .init();
+
+        let cancelled_counter = meter
+            .u64_counter("loom.tool_registry.cancelled_total")
+            .with_description("Total number of tool calls stopped by cancellation")
+            .init();
+
+        let invalid_outputs_counter = meter
+            .u64_counter("loom.tool_registry.invalid_outputs_total")
+            .with_description("Total number of tool results that did not match the output schema")
+            .init();
+
         let invoke_latency = meter
             .f64_histogram("loom.tool_registry.invoke_latency_ms")
             .with_description("Tool invocation latency in milliseconds")
@@ -61,9 +110,22 @@ impl ToolRegistry {
 
         Self {
             tools: Arc::new(DashMap::new()),
+            default_policy: Arc::new(RwLock::new(ToolPolicy::default())),
+            policies: Arc::new(DashMap::new()),
+            states: Arc::new(DashMap::new()),
+            inflight: Arc::new(AtomicUsize::new(0)),
+            idle: Arc::new(Notify::new()),
+            closed: Arc::new(AtomicBool::new(false)),
+            strict_outputs: Arc::new(AtomicBool::new(false)),
+            allowed: None,
+            namespace: None,
             invocations_counter,
             errors_counter,
             timeouts_counter,
+            retries_counter,
+            rejected_counter,
+            cancelled_counter,
+            invalid_outputs_counter,
             invoke_latency,
             registered_tools_gauge,
         }
@@ -79,45 +141,327 @@ impl ToolRegistry {
         }
     }
 
+    /// Register a tool visible only in `namespace`, where it shadows a global tool of
+    /// the same name
+    pub async fn register_in(&self, namespace: &Namespace, tool: Arc<dyn Tool>) {
+        let key = namespace.tool_key(&tool.name());
+        info!(target: "tool_registry", tool = %key, "Registering namespaced tool");
+
+        if self.tools.insert(key, tool).is_none() {
+            self.registered_tools_gauge.add(1, &[]);
+        }
+    }
+
+    /// Remove a tool registered with `register`; returns false if there was none
+    pub fn unregister(&self, name: &str) -> bool {
+        self.remove_key(name)
+    }
+
+    /// Remove a tool registered with `register_in`; returns false if there was none
+    pub fn unregister_in(&self, namespace: &Namespace, name: &str) -> bool {
+        self.remove_key(&namespace.tool_key(name))
+    }
+
+    fn remove_key(&self, key: &str) -> bool {
+        if self.tools.remove(key).is_none() {
+            return false;
+        }
+        info!(target: "tool_registry", tool = %key, "Unregistered tool");
+        self.registered_tools_gauge.add(-1, &[]);
+        true
+    }
+
     /// Get a tool by name
     pub fn get(&self, name: &str) -> Option<Arc<dyn Tool>> {
-        self.tools.get(name).map(|t| t.clone())
+        let key = self.resolve(name)?;
+        self.tools.get(&key).map(|t| t.clone())
     }
 
     /// List all registered tools
+    ///
+    /// Namespaced tools are only listed through their namespace's handle.
     pub fn list_tools(&self) -> Vec<Arc<dyn Tool>> {
-        self.tools.iter().map(|t| t.clone()).collect()
+        self.tools
+            .iter()
+            .filter(|t| {
+                let key = t.key();
+                match key.split_once(TOOL_NAMESPACE_SEPARATOR) {
+                    Some((namespace, name)) => {
+                        self.namespace
+                            .as_ref()
+                            .is_some_and(|ns| ns.as_str() == namespace)
+                            && self.allows(name)
+                    }
+                    None => self.resolve(key).as_deref() == Some(key),
+                }
+            })
+            .map(|t| t.clone())
+            .collect()
+    }
+
+    /// A handle to this registry that sees the global tools plus those registered in
+    /// `namespace`. It shares tools, policies and circuit state with this registry and
+    /// keeps its `scoped` restrictions.
+    pub fn for_namespace(&self, namespace: &Namespace) -> Self {
+        Self {
+            namespace: Some(namespace.clone()),
+            ..self.clone()
+        }
+    }
+
+    /// Namespace whose tools this handle sees
+    pub fn namespace(&self) -> Option<&Namespace> {
+        self.namespace.as_ref()
+    }
+
+    /// Registry key `name` refers to through this handle: the namespace's own tool if
+    /// there is one, otherwise the global tool
+    fn resolve(&self, name: &str) -> Option<String> {
+        if !self.allows(name) {
+            return None;
+        }
+        if let Some(namespace) = &self.namespace {
+            let key = namespace.tool_key(name);
+            if self.tools.contains_key(&key) {
+                return Some(key);
+            }
+            if name.contains(TOOL_NAMESPACE_SEPARATOR) {
+                return None;
+            }
+        }
+        self.tools.contains_key(name).then(|| name.to_string())
+    }
+
+    /// A handle to this registry that only sees the tools named in `allowed`.
+    ///
+    /// Entries are tool names, or prefixes ending in `*` (`"web:*"`). The handle shares
+    /// tools, policies and circuit state with this registry, so tools registered later
+    /// show up when allowed. Scoping a scoped handle narrows it further.
+    pub fn scoped<I, S>(&self, allowed: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let allowed: Vec<String> = allowed.into_iter().map(Into::into).collect();
+        let allowed = match &self.allowed {
+            Some(outer) => allowed
+                .into_iter()
+                .filter(|entry| entry_within(entry, outer))
+                .collect(),
+            None => allowed,
+        };
+        Self {
+            allowed: Some(Arc::new(allowed)),
+            ..self.clone()
+        }
+    }
+
+    /// Whether `name` is visible through this handle
+    pub fn allows(&self, name: &str) -> bool {
+        match &self.allowed {
+            Some(allowed) => allowed.iter().any(|entry| allow_entry_matches(entry, name)),
+            None => true,
+        }
+    }
+
+    /// Replace the policy used for tools without a per-tool override
+    pub fn set_default_policy(&self, policy: ToolPolicy) {
+        *self.default_policy.write().unwrap() = policy;
+    }
+
+    /// Set the policy for a single tool
+    pub fn set_policy(&self, name: &str, policy: ToolPolicy) {
+        self.policies.insert(name.to_string(), policy);
+    }
+
+    /// Remove a per-tool policy, falling back to the default
+    pub fn clear_policy(&self, name: &str) {
+        self.policies.remove(name);
+    }
+
+    /// Effective policy for a tool
+    pub fn policy_for(&self, name: &str) -> ToolPolicy {
+        self.policies
+            .get(name)
+            .map(|p| p.clone())
+            .unwrap_or_else(|| self.default_policy.read().unwrap().clone())
+    }
+
+    /// Reject results that do not match the tool's `output_schema` (off by default)
+    pub fn set_strict_outputs(&self, strict: bool) {
+        self.strict_outputs.store(strict, Ordering::SeqCst);
+    }
+
+    /// Whether schema mismatches fail the call
+    pub fn strict_outputs(&self) -> bool {
+        self.strict_outputs.load(Ordering::SeqCst)
+    }
+
+    /// Policy state and counters for every tool that has been called, sorted by name
+    pub fn stats(&self) -> Vec<ToolStats> {
+        let mut stats: Vec<ToolStats> = self
+            .states
+            .iter()
+            .map(|e| e.value().snapshot(e.key()))
+            .collect();
+        stats.sort_by(|a, b| a.name.cmp(&b.name));
+        stats
     }
 
-    /// Call a tool by name with timeout
-    #[tracing::instrument(skip(self, arguments), fields(tool.name = %name))]
+    /// Policy state and counters for a single tool
+    pub fn tool_stats(&self, name: &str) -> Option<ToolStats> {
+        self.states.get(name).map(|s| s.snapshot(name))
+    }
+
+    /// Calls currently executing
+    pub fn inflight_calls(&self) -> usize {
+        self.inflight.load(Ordering::SeqCst)
+    }
+
+    /// Stop accepting calls and wait for in-flight ones to finish.
+    ///
+    /// Returns `true` if all calls finished before `deadline`. New calls fail with
+    /// `ToolError::Internal` once draining has started.
+    pub async fn drain(&self, deadline: Instant) -> bool {
+        self.closed.store(true, Ordering::SeqCst);
+        loop {
+            let notified = self.idle.notified();
+            tokio::pin!(notified);
+            notified.as_mut().enable();
+            let remaining = self.inflight_calls();
+            if remaining == 0 {
+                return true;
+            }
+            debug!(target: "tool_registry", inflight = remaining, "Waiting for in-flight tool calls");
+            if tokio::time::timeout_at(deadline, notified).await.is_err() {
+                warn!(target: "tool_registry", inflight = self.inflight_calls(), "Tool calls still running at drain deadline");
+                return false;
+            }
+        }
+    }
+
+    /// Call a tool by name, applying its `ToolPolicy`
     pub async fn call(
         &self,
         name: &str,
         arguments: serde_json::Value,
+    ) -> ToolResult<serde_json::Value> {
+        self.call_with_cancel(name, arguments, CancellationToken::new())
+            .await
+    }
+
+    /// Call a tool by name, applying its `ToolPolicy`, until `cancel` fires.
+    ///
+    /// A cancelled call fails with `ToolError::Cancelled` at once: the tool's future is
+    /// polled once more, so `Tool::call_with_cancel` can see the token fire, then dropped,
+    /// and pending retries are skipped. Cancellations do not count against the circuit
+    /// breaker.
+    #[tracing::instrument(skip(self, arguments, cancel), fields(tool.name = %name))]
+    pub async fn call_with_cancel(
+        &self,
+        name: &str,
+        arguments: serde_json::Value,
+        cancel: CancellationToken,
     ) -> ToolResult<serde_json::Value> {
         let start_time = std::time::Instant::now();
 
+        if cancel.is_cancelled() {
+            return Err(ToolError::Cancelled);
+        }
+
+        if self.closed.load(Ordering::SeqCst) {
+            return Err(ToolError::Internal(
+                "tool registry is shutting down".to_string(),
+            ));
+        }
+        self.inflight.fetch_add(1, Ordering::SeqCst);
+        let _inflight = InflightCall {
+            inflight: Arc::clone(&self.inflight),
+            idle: Arc::clone(&self.idle),
+        };
+
+        let key = self
+            .resolve(name)
+            .ok_or_else(|| ToolError::NotFound(name.to_string()))?;
         let tool = self
-            .get(name)
+            .tools
+            .get(&key)
+            .map(|t| t.clone())
             .ok_or_else(|| ToolError::NotFound(name.to_string()))?;
+        // Policies, circuit state and metrics are kept per registered tool
+        let name = key.as_str();
+
+        let policy = self.policy_for(name);
+        let breaker = policy.circuit_breaker.as_ref();
+        let admission = self
+            .states
+            .entry(name.to_string())
+            .or_default()
+            .admit(breaker);
 
-        debug!(target: "tool_registry", tool = %name, "Invoking tool");
+        if admission == Admission::Rejected {
+            warn!(target: "tool_registry", tool = %name, "Circuit open; rejecting call");
+            self.rejected_counter
+                .add(1, &[KeyValue::new("tool", name.to_string())]);
+            return Err(ToolError::CircuitOpen(name.to_string()));
+        }
 
-        // Default timeout 30s, TODO: make configurable
-        let timeout_duration = Duration::from_secs(30);
+        debug!(target: "tool_registry", tool = %name, ?admission, "Invoking tool");
 
-        // Execute tool with timeout
-        let fut = tool.call(arguments);
-        let result = match timeout(timeout_duration, fut).await {
-            Ok(res) => res,
-            Err(_) => {
-                warn!(target: "tool_registry", tool = %name, "Tool execution timed out");
-                self.timeouts_counter
-                    .add(1, &[KeyValue::new("tool", name.to_string())]);
-                Err(ToolError::Timeout)
+        // Half-open probes get a single attempt so a sick tool is not hammered
+        let max_retries = match admission {
+            Admission::Probe => 0,
+            _ => policy.max_retries,
+        };
+
+        let mut attempt = 0;
+        let result = loop {
+            let fut = tool.call_with_cancel(arguments.clone(), cancel.child_token());
+            let result = match timeout(policy.timeout, with_cancel(&cancel, fut)).await {
+                Ok(res) => res,
+                Err(_) => {
+                    warn!(target: "tool_registry", tool = %name, attempt, "Tool execution timed out");
+                    self.timeouts_counter
+                        .add(1, &[KeyValue::new("tool", name.to_string())]);
+                    if let Some(mut state) = self.states.get_mut(name) {
+                        state.timeouts += 1;
+                    }
+                    Err(ToolError::Timeout)
+                }
+            };
+
+            match &result {
+                Err(e) if is_transient(e) && attempt < max_retries => {
+                    let delay = policy.backoff_for(attempt);
+                    debug!(target: "tool_registry", tool = %name, attempt, delay_ms = delay.as_millis() as u64, error = %e, "Retrying tool");
+                    self.retries_counter
+                        .add(1, &[KeyValue::new("tool", name.to_string())]);
+                    if let Some(mut state) = self.states.get_mut(name) {
+                        state.retries += 1;
+                    }
+                    if with_cancel(&cancel, async {
+                        tokio::time::sleep(delay).await;
+                        Ok(serde_json::Value::Null)
+                    })
+                    .await
+                    .is_err()
+                    {
+                        break Err(ToolError::Cancelled);
+                    }
+                    attempt += 1;
+                }
+                _ => break result,
             }
         };
+        let result = result.and_then(|output| self.check_output(name, tool.as_ref(), output));
+
+        if let Some(mut state) = self.states.get_mut(name) {
+            match &result {
+                Ok(_) => state.record_success(admission),
+                Err(ToolError::Cancelled) => state.record_cancel(admission),
+                Err(e) => state.record_failure(admission, e, breaker),
+            }
+        }
 
         let elapsed_ms = start_time.elapsed().as_secs_f64() * 1000.0;
         self.invoke_latency
@@ -133,6 +477,11 @@ impl ToolRegistry {
                     ],
                 );
             }
+            Err(ToolError::Cancelled) => {
+                info!(target: "tool_registry", tool = %name, "Tool call cancelled");
+                self.cancelled_counter
+                    .add(1, &[KeyValue::new("tool", name.to_string())]);
+            }
             Err(e) => {
                 warn!(target: "tool_registry", tool = %name, error = %e, "Tool execution failed");
                 self.errors_counter.add(
@@ -147,4 +496,69 @@ impl ToolRegistry {
 
         result
     }
+
+    /// Validate a result against the tool's declared output schema
+    fn check_output(
+        &self,
+        name: &str,
+        tool: &dyn Tool,
+        output: serde_json::Value,
+    ) -> ToolResult<serde_json::Value> {
+        let Some(output_schema) = tool.output_schema() else {
+            return Ok(output);
+        };
+        let Err(reason) = schema::validate(&output_schema, &output) else {
+            return Ok(output);
+        };
+        self.invalid_outputs_counter
+            .add(1, &[KeyValue::new("tool", name.to_string())]);
+        if self.strict_outputs() {
+            return Err(ToolError::InvalidOutput(reason));
+        }
+        warn!(target: "tool_registry", tool = %name, %reason, "Tool output does not match its schema");
+        Ok(output)
+    }
+}
+
+/// `fut`, or `ToolError::Cancelled` once `cancel` fires. `fut` is polled first, so a
+/// tool watching its token gets one poll to react before it is dropped.
+async fn with_cancel<F>(cancel: &CancellationToken, fut: F) -> ToolResult<serde_json::Value>
+where
+    F: std::future::Future<Output = ToolResult<serde_json::Value>>,
+{
+    tokio::select! {
+        biased;
+        result = fut => result,
+        _ = cancel.cancelled() => Err(ToolError::Cancelled),
+    }
+}
+
+/// Whether allowlist `entry` (a name, or a prefix ending in `*`) covers tool `name`
+fn allow_entry_matches(entry: &str, name: &str) -> bool {
+    match entry.strip_suffix('*') {
+        Some(prefix) => name.starts_with(prefix),
+        None => entry == name,
+    }
+}
+
+/// Whether every tool `entry` covers is also covered by `outer`
+fn entry_within(entry: &str, outer: &[String]) -> bool {
+    outer.iter().any(|o| match o.strip_suffix('*') {
+        Some(prefix) => entry.starts_with(prefix),
+        None => o == entry,
+    })
+}
+
+/// Decrements the in-flight count when a call ends and wakes `drain`
+struct InflightCall {
+    inflight: Arc<AtomicUsize>,
+    idle: Arc<Notify>,
+}
+
+impl Drop for InflightCall {
+    fn drop(&mut self) {
+        if self.inflight.fetch_sub(1, Ordering::SeqCst) == 1 {
+            self.idle.notify_waiters();
+        }
+    }
 }
@@ -0,0 +1,166 @@
+//! Minimal JSON Schema validation for tool results.
+//!
+//! Covers the subset tools use to describe their outputs: `type` (single or list),
+//! `enum`, `properties`, `required`, `additionalProperties` and `items`. Unknown
+//! keywords are ignored, so richer schemas (e.g. from MCP servers) still validate
+//! on the parts we understand.
+
+use serde_json::Value;
+
+/// Validate `value` against `schema`.
+///
+/// Returns the first mismatch as `"<path>: <reason>"`, with the path written as
+/// `$.field[0].nested`.
+pub fn validate(schema: &Value, value: &Value) -> Result<(), String> {
+    validate_at(schema, value, "$")
+}
+
+fn validate_at(schema: &Value, value: &Value, path: &str) -> Result<(), String> {
+    let Some(schema) = schema.as_object() else {
+        // `true`/`{}` accept anything; `false` accepts nothing
+        return match schema {
+            Value::Bool(false) => Err(format!("{path}: no value allowed")),
+            _ => Ok(()),
+        };
+    };
+
+    if let Some(expected) = schema.get("type") {
+        let allowed: Vec<&str> = match expected {
+            Value::String(t) => vec![t.as_str()],
+            Value::Array(ts) => ts.iter().filter_map(Value::as_str).collect(),
+            _ => vec![],
+        };
+        if !allowed.is_empty() && !allowed.iter().any(|t| type_matches(t, value)) {
+            return Err(format!(
+                "{path}: expected {}, got {}",
+                allowed.join(" or "),
+                type_name(value)
+            ));
+        }
+    }
+
+    if let Some(Value::Array(options)) = schema.get("enum") {
+        if !options.contains(value) {
+            return Err(format!(
+                "{path}: {value} is not one of {}",
+                Value::Array(options.clone())
+            ));
+        }
+    }
+
+    match value {
+        Value::Object(map) => {
+            if let Some(Value::Array(required)) = schema.get("required") {
+                for key in required.iter().filter_map(Value::as_str) {
+                    if !map.contains_key(key) {
+                        return Err(format!("{path}: missing required field '{key}'"));
+                    }
+                }
+            }
+            let properties = schema.get("properties").and_then(Value::as_object);
+            for (key, field) in map {
+                let field_path = format!("{path}.{key}");
+                match properties.and_then(|p| p.get(key)) {
+                    Some(field_schema) => validate_at(field_schema, field, &field_path)?,
+                    None => match schema.get("additionalProperties") {
+                        Some(Value::Bool(false)) => {
+                            return Err(format!("{path}: unexpected field '{key}'"));
+                        }
+                        Some(extra) => validate_at(extra, field, &field_path)?,
+                        None => {}
+                    },
+                }
+            }
+        }
+        Value::Array(items) => {
+            if let Some(item_schema) = schema.get("items") {
+                for (i, item) in items.iter().enumerate() {
+                    validate_at(item_schema, item, &format!("{path}[{i}]"))?;
+                }
+            }
+        }
+        _ => {}
+    }
+
+    Ok(())
+}
+
+fn type_matches(expected: &str, value: &Value) -> bool {
+    match expected {
+        "object" => value.is_object(),
+        "array" => value.is_array(),
+        "string" => value.is_string(),
+        "boolean" => value.is_boolean(),
+        "null" => value.is_null(),
+        "number" => value.is_number(),
+        "integer" => {
+            value.is_i64() || value.is_u64() || value.as_f64().is_some_and(|f| f.fract() == 0.0)
+        }
+        // Unknown type names are not ours to reject
+        _ => true,
+    }
+}
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_reports_path_of_first_mismatch() {
+        let schema = json!({
+            "type": "object",
+            "properties": {
+                "results": {
+                    "type": "array",
+                    "items": {
+                        "type": "object",
+                        "properties": { "url": { "type": "string" } },
+                        "required": ["url"]
+                    }
+                }
+            },
+            "required": ["results"]
+        });
+        assert!(validate(&schema, &json!({"results": [{"url": "a"}]})).is_ok());
+        assert_eq!(
+            validate(&schema, &json!({"results": [{"url": "a"}, {"url": 1}]})).unwrap_err(),
+            "$.results[1].url: expected string, got number"
+        );
+        assert_eq!(
+            validate(&schema, &json!({})).unwrap_err(),
+            "$: missing required field 'results'"
+        );
+    }
+
+    #[test]
+    fn test_type_lists_enum_and_additional_properties() {
+        let schema = json!({
+            "type": "object",
+            "properties": {
+                "code": { "type": ["integer", "null"] },
+                "units": { "enum": ["metric", "imperial"] }
+            },
+            "additionalProperties": false
+        });
+        assert!(validate(&schema, &json!({"code": null, "units": "metric"})).is_ok());
+        assert!(validate(&schema, &json!({"code": 2.0})).is_ok());
+        assert!(validate(&schema, &json!({"code": 2.5})).is_err());
+        assert!(validate(&schema, &json!({"units": "kelvin"})).is_err());
+        assert_eq!(
+            validate(&schema, &json!({"extra": 1})).unwrap_err(),
+            "$: unexpected field 'extra'"
+        );
+    }
+}
@@ -1,6 +1,7 @@
 use super::error::ToolResult;
 use async_trait::async_trait;
 use serde_json::Value;
+use tokio_util::sync::CancellationToken;
 
 /// The core trait for all tools (Native & MCP)
 #[async_trait]
@@ -14,6 +15,29 @@ pub trait Tool: Send + Sync {
     /// The JSON Schema for the tool's arguments
     fn parameters(&self) -> Value;
 
+    /// The JSON Schema for the tool's result, if it declares one.
+    ///
+    /// Exposed to the LLM alongside `parameters` and, in strict mode, enforced by
+    /// `ToolRegistry::call`.
+    fn output_schema(&self) -> Option<Value> {
+        None
+    }
+
     /// Execute the tool with the given arguments
     async fn call(&self, arguments: Value) -> ToolResult<Value>;
+
+    /// Execute the tool, stopping early once `cancel` fires.
+    ///
+    /// `ToolRegistry::call_with_cancel` stops awaiting the call when the token fires,
+    /// whatever the tool does. Tools with work outside the calling task (remote calls,
+    /// spawned processes) override this to abort that work too. The future is polled
+    /// once after the token fires and then dropped, so that clean-up must not wait on
+    /// anything. The default ignores the token.
+    async fn call_with_cancel(
+        &self,
+        arguments: Value,
+        _cancel: CancellationToken,
+    ) -> ToolResult<Value> {
+        self.call(arguments).await
+    }
 }
@@ -0,0 +1,426 @@
+//! Differentially private usage export
+//!
+//! Deployments that report usage upstream can opt into an aggregate-only export instead
+//! of shipping traces or dashboard events, which carry payload previews and agent ids.
+//! The EventBus feeds every published event to a `UsageExporter`, which keeps nothing but
+//! counts per reporting window and releases them under a fixed schema (`loom.usage.v1`):
+//!
+//! - `events_published`: events per type. Types outside `event_types` are counted as
+//!   `other`, so custom type names never leave the process.
+//! - `agent_events`: events per sending agent, keyed by the HMAC-SHA256 of the agent id
+//!   under the salt.
+//! - `active_agents`: distinct sending agents, under the key `all`.
+//!
+//! Every released count gets Laplace noise drawn from the system's secure random source. A single event changes at most one count per
+//! metric by one, so each of the three metrics spends a third of `epsilon`. Noisy counts
+//! are rounded to multiples of `bucket`, and counts below `min_count` after noise are
+//! dropped, which keeps a rare agent or event type from showing up at all.
+//!
+//! Enabled by `LOOM_USAGE_EXPORT_ENDPOINT`, either an HTTP URL the report is POSTed to
+//! as JSON or `log` to only log it:
+//!
+//! | Variable | Default |
+//! |----------|---------|
+//! | `LOOM_USAGE_EXPORT_INTERVAL_SECS` | 3600 |
+//! | `LOOM_USAGE_EXPORT_EPSILON` | 1.0 |
+//! | `LOOM_USAGE_EXPORT_BUCKET` | 10 |
+//! | `LOOM_USAGE_EXPORT_MIN_COUNT` | 20 |
+//! | `LOOM_USAGE_EXPORT_SALT` | random per process (hashes unlinkable across restarts) |
+//! | `LOOM_USAGE_EXPORT_EVENT_TYPES` | none (comma-separated allowlist) |
+
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use ring::hmac;
+use ring::rand::{SecureRandom, SystemRandom};
+use serde::{Deserialize, Serialize};
+use tokio::task::JoinHandle;
+use tracing::{info, warn};
+
+use crate::messaging::EventExt;
+use crate::proto::Event;
+use crate::{LoomError, Result};
+
+/// Schema identifier carried by every report
+pub const USAGE_SCHEMA: &str = "loom.usage.v1";
+
+/// Key of event types outside the allowlist
+const OTHER_KEY: &str = "other";
+/// Key of whole-deployment totals
+const ALL_KEY: &str = "all";
+/// Metrics a single event can move, sharing the privacy budget
+const METRIC_COUNT: f64 = 3.0;
+
+/// Settings of the usage export
+#[derive(Debug, Clone, PartialEq)]
+pub struct UsageExportConfig {
+    /// HTTP endpoint reports are POSTed to; `log` only logs them
+    pub endpoint: String,
+    /// Length of a reporting window
+    pub interval: Duration,
+    /// Privacy budget spent per report
+    pub epsilon: f64,
+    /// Released counts are multiples of this
+    pub bucket: u64,
+    /// Noisy counts below this are not released
+    pub min_count: u64,
+    /// Key of the agent id HMACs
+    pub salt: String,
+    /// Event types reported by name
+    pub event_types: HashSet<String>,
+}
+
+impl UsageExportConfig {
+    /// Export to `endpoint` with the defaults from the module docs
+    pub fn new(endpoint: impl Into<String>) -> Self {
+        Self {
+            endpoint: endpoint.into(),
+            interval: Duration::from_secs(3600),
+            epsilon: 1.0,
+            bucket: 10,
+            min_count: 20,
+            salt: hex(&NoiseRng::new().bytes::<16>()),
+            event_types: HashSet::new(),
+        }
+    }
+
+    pub fn with_interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    pub fn with_epsilon(mut self, epsilon: f64) -> Self {
+        self.epsilon = epsilon;
+        self
+    }
+
+    pub fn with_bucket(mut self, bucket: u64) -> Self {
+        self.bucket = bucket;
+        self
+    }
+
+    pub fn with_min_count(mut self, min_count: u64) -> Self {
+        self.min_count = min_count;
+        self
+    }
+
+    pub fn with_salt(mut self, salt: impl Into<String>) -> Self {
+        self.salt = salt.into();
+        self
+    }
+
+    pub fn with_event_types(mut self, event_types: &[&str]) -> Self {
+        self.event_types = event_types.iter().map(|t| t.to_string()).collect();
+        self
+    }
+
+    /// Settings from `LOOM_USAGE_EXPORT_*`; `Ok(None)` unless an endpoint is set
+    pub fn from_env() -> Result<Option<Self>> {
+        let Ok(endpoint) = std::env::var("LOOM_USAGE_EXPORT_ENDPOINT") else {
+            return Ok(None);
+        };
+        let mut config = Self::new(endpoint.trim());
+        if let Some(secs) = env_parse::<u64>("LOOM_USAGE_EXPORT_INTERVAL_SECS")? {
+            config.interval = Duration::from_secs(secs);
+        }
+        if let Some(epsilon) = env_parse("LOOM_USAGE_EXPORT_EPSILON")? {
+            config.epsilon = epsilon;
+        }
+        if let Some(bucket) = env_parse("LOOM_USAGE_EXPORT_BUCKET")? {
+            config.bucket = bucket;
+        }
+        if let Some(min_count) = env_parse("LOOM_USAGE_EXPORT_MIN_COUNT")? {
+            config.min_count = min_count;
+        }
+        if let Ok(salt) = std::env::var("LOOM_USAGE_EXPORT_SALT") {
+            config.salt = salt;
+        }
+        if let Ok(types) = std::env::var("LOOM_USAGE_EXPORT_EVENT_TYPES") {
+            config.event_types = types
+                .split(',')
+                .map(str::trim)
+                .filter(|t| !t.is_empty())
+                .map(str::to_string)
+                .collect();
+        }
+        config.validate()?;
+        Ok(Some(config))
+    }
+
+    fn validate(&self) -> Result<()> {
+        if self.endpoint.is_empty() {
+            return Err(LoomError::MetricsError(
+                "Usage export endpoint must not be empty".to_string(),
+            ));
+        }
+        if !(self.epsilon.is_finite() && self.epsilon > 0.0) {
+            return Err(LoomError::MetricsError(format!(
+                "Usage export epsilon must be positive, got {}",
+                self.epsilon
+            )));
+        }
+        if self.bucket == 0 || self.interval.is_zero() {
+            return Err(LoomError::MetricsError(
+                "Usage export bucket and interval must be positive".to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+fn env_parse<T: std::str::FromStr>(key: &str) -> Result<Option<T>> {
+    match std::env::var(key) {
+        Ok(value) => value
+            .trim()
+            .parse()
+            .map(Some)
+            .map_err(|_| LoomError::MetricsError(format!("Invalid {}: {}", key, value))),
+        Err(_) => Ok(None),
+    }
+}
+
+/// A released count
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UsageMetric {
+    EventsPublished,
+    AgentEvents,
+    ActiveAgents,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct UsageRow {
+    pub metric: UsageMetric,
+    /// Event type, `other`, hashed agent id or `all`
+    pub key: String,
+    /// Noisy, rounded count
+    pub count: u64,
+}
+
+/// One reporting window, the only thing the export sends
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct UsageReport {
+    /// Always `USAGE_SCHEMA`
+    pub schema: String,
+    pub window_start_ms: i64,
+    pub window_end_ms: i64,
+    /// Privacy budget spent on this report
+    pub epsilon: f64,
+    /// Sorted by metric, then key
+    pub rows: Vec<UsageRow>,
+}
+
+/// Exact counts of the current window; never leave the process
+#[derive(Debug, Default)]
+struct Window {
+    started_ms: i64,
+    events: HashMap<String, u64>,
+    agents: HashMap<String, u64>,
+}
+
+/// Aggregates published events and periodically exports private reports
+pub struct UsageExporter {
+    config: UsageExportConfig,
+    agent_key: hmac::Key,
+    window: Mutex<Window>,
+    rng: NoiseRng,
+    task: Mutex<Option<JoinHandle<()>>>,
+}
+
+impl std::fmt::Debug for UsageExporter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("UsageExporter")
+            .field("endpoint", &self.config.endpoint)
+            .field("epsilon", &self.config.epsilon)
+            .finish()
+    }
+}
+
+impl UsageExporter {
+    pub fn new(config: UsageExportConfig) -> Result<Self> {
+        config.validate()?;
+        Ok(Self {
+            agent_key: hmac::Key::new(hmac::HMAC_SHA256, config.salt.as_bytes()),
+            config,
+            window: Mutex::new(Window {
+                started_ms: now_ms(),
+                ..Default::default()
+            }),
+            rng: NoiseRng::new(),
+            task: Mutex::new(None),
+        })
+    }
+
+    pub fn config(&self) -> &UsageExportConfig {
+        &self.config
+    }
+
+    /// Count an event published to the bus
+    pub fn observe(&self, event: &Event) {
+        let event_type = if self.config.event_types.contains(&event.r#type) {
+            event.r#type.as_str()
+        } else {
+            OTHER_KEY
+        };
+        let agent = event.sender().map(|id| self.hash_agent_id(id));
+        let mut window = self.window.lock().unwrap();
+        *window.events.entry(event_type.to_string()).or_default() += 1;
+        if let Some(agent) = agent {
+            *window.agents.entry(agent).or_default() += 1;
+        }
+    }
+
+    /// Key an agent id is reported under: its HMAC-SHA256 keyed by the salt, truncated
+    /// to 128 bits
+    pub fn hash_agent_id(&self, agent_id: &str) -> String {
+        let tag = hmac::sign(&self.agent_key, agent_id.as_bytes());
+        format!("agent-{}", hex(&tag.as_ref()[..16]))
+    }
+
+    /// Close the current window and release its report
+    pub fn take_report(&self) -> UsageReport {
+        let now = now_ms();
+        let window = std::mem::replace(
+            &mut *self.window.lock().unwrap(),
+            Window {
+                started_ms: now,
+                ..Default::default()
+            },
+        );
+
+        let mut rows = Vec::new();
+        let mut release = |metric, key: &str, count: u64| {
+            if let Some(count) = self.privatize(count) {
+                rows.push(UsageRow {
+                    metric,
+                    key: key.to_string(),
+                    count,
+                });
+            }
+        };
+        for (event_type, count) in &window.events {
+            release(UsageMetric::EventsPublished, event_type, *count);
+        }
+        for (agent, count) in &window.agents {
+            release(UsageMetric::AgentEvents, agent, *count);
+        }
+        release(
+            UsageMetric::ActiveAgents,
+            ALL_KEY,
+            window.agents.len() as u64,
+        );
+        rows.sort_by(|a, b| (a.metric, &a.key).cmp(&(b.metric, &b.key)));
+
+        UsageReport {
+            schema: USAGE_SCHEMA.to_string(),
+            window_start_ms: window.started_ms,
+            window_end_ms: now,
+            epsilon: self.config.epsilon,
+            rows,
+        }
+    }
+
+    /// Noisy count rounded to the bucket; None below `min_count`
+    fn privatize(&self, count: u64) -> Option<u64> {
+        let scale = METRIC_COUNT / self.config.epsilon;
+        let noisy = count as f64 + self.rng.laplace(scale);
+        let bucket = self.config.bucket as f64;
+        let rounded = ((noisy / bucket).round() * bucket).max(0.0);
+        (rounded >= self.config.min_count as f64 && rounded > 0.0).then_some(rounded as u64)
+    }
+
+    /// Export a report every `interval` until stopped
+    pub fn start(self: &Arc<Self>) {
+        let exporter = Arc::clone(self);
+        let handle = tokio::spawn(async move {
+            let client = reqwest::Client::new();
+            let mut ticker = tokio::time::interval(exporter.config.interval);
+            ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            // The first tick completes immediately
+            ticker.tick().await;
+            loop {
+                ticker.tick().await;
+                let report = exporter.take_report();
+                if let Err(e) = exporter.send(&client, &report).await {
+                    warn!(target: "usage_export", error = %e, "Failed to export usage report");
+                }
+            }
+        });
+        if let Some(old) = self.task.lock().unwrap().replace(handle) {
+            old.abort();
+        }
+    }
+
+    /// Stop exporting; the open window is discarded
+    pub fn stop(&self) {
+        if let Some(handle) = self.task.lock().unwrap().take() {
+            handle.abort();
+        }
+    }
+
+    async fn send(&self, client: &reqwest::Client, report: &UsageReport) -> Result<()> {
+        if self.config.endpoint == "log" {
+            info!(
+                target: "usage_export",
+                report = %serde_json::to_string(report)?,
+                "Usage report"
+            );
+            return Ok(());
+        }
+        client
+            .post(&self.config.endpoint)
+            .timeout(Duration::from_secs(10))
+            .json(report)
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(|e| LoomError::MetricsError(format!("Usage export failed: {e}")))?;
+        info!(
+            target: "usage_export",
+            rows = report.rows.len(),
+            "Exported usage report"
+        );
+        Ok(())
+    }
+}
+
+fn now_ms() -> i64 {
+    chrono::Utc::now().timestamp_millis()
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+/// The operating system's secure random source; predictable noise could be subtracted
+/// from a report, undoing its privacy
+struct NoiseRng(SystemRandom);
+
+impl NoiseRng {
+    fn new() -> Self {
+        Self(SystemRandom::new())
+    }
+
+    fn bytes<const N: usize>(&self) -> [u8; N] {
+        let mut bytes = [0u8; N];
+        self.0
+            .fill(&mut bytes)
+            .expect("system random source unavailable");
+        bytes
+    }
+
+    /// Uniform in [0, 1)
+    fn next_f64(&self) -> f64 {
+        (u64::from_le_bytes(self.bytes()) >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /// Laplace(0, scale) by inverse transform
+    fn laplace(&self, scale: f64) -> f64 {
+        let u = self.next_f64() - 0.5;
+        let tail = (1.0 - 2.0 * u.abs()).max(f64::MIN_POSITIVE);
+        -scale * u.signum() * tail.ln()
+    }
+}
@@ -0,0 +1,329 @@
+//! Workflow definitions: steps, their dependencies and TOML loading
+
+use std::collections::{HashMap, HashSet};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::template;
+use crate::{LoomError, Result};
+
+/// Per-attempt timeout of a step unless configured
+pub const DEFAULT_STEP_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Delay before the first retry of a failed step; doubles on every further retry
+pub const DEFAULT_RETRY_BACKOFF: Duration = Duration::from_millis(200);
+
+/// What a step does
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StepAction {
+    /// Call a tool in the `ToolRegistry`
+    Tool { name: String, arguments: Value },
+    /// Send a `collab.request` with a JSON payload to an agent topic and wait for the reply
+    Agent { topic: String, payload: Value },
+}
+
+/// One node of a workflow DAG.
+///
+/// Tool arguments and agent payloads may reference the workflow input and the output
+/// of earlier steps with `${input.path}` and `${steps.<id>.output.path}`. A string that
+/// consists of a single reference is replaced by the referenced JSON value; references
+/// embedded in longer strings are interpolated as text. Referenced steps are implicit
+/// dependencies.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Step {
+    pub id: String,
+    pub action: StepAction,
+    /// Steps that must succeed before this one starts, in addition to referenced ones
+    pub depends_on: Vec<String>,
+    /// Additional attempts after a failed or timed out attempt
+    pub retries: u32,
+    pub retry_backoff: Duration,
+    /// Timeout of a single attempt
+    pub timeout: Duration,
+}
+
+impl Step {
+    fn new(id: impl Into<String>, action: StepAction) -> Self {
+        Self {
+            id: id.into(),
+            action,
+            depends_on: Vec::new(),
+            retries: 0,
+            retry_backoff: DEFAULT_RETRY_BACKOFF,
+            timeout: DEFAULT_STEP_TIMEOUT,
+        }
+    }
+
+    /// Step calling the tool `name` with `arguments`
+    pub fn tool(id: impl Into<String>, name: impl Into<String>, arguments: Value) -> Self {
+        Self::new(
+            id,
+            StepAction::Tool {
+                name: name.into(),
+                arguments,
+            },
+        )
+    }
+
+    /// Step sending `payload` to the agent listening on `topic`
+    pub fn agent(id: impl Into<String>, topic: impl Into<String>, payload: Value) -> Self {
+        Self::new(
+            id,
+            StepAction::Agent {
+                topic: topic.into(),
+                payload,
+            },
+        )
+    }
+
+    pub fn with_depends_on<I, S>(mut self, steps: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.depends_on.extend(steps.into_iter().map(Into::into));
+        self
+    }
+
+    pub fn with_retries(mut self, retries: u32) -> Self {
+        self.retries = retries;
+        self
+    }
+
+    pub fn with_retry_backoff(mut self, backoff: Duration) -> Self {
+        self.retry_backoff = backoff;
+        self
+    }
+
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Explicit and referenced dependencies, without duplicates
+    pub fn dependencies(&self) -> Vec<String> {
+        let template = match self.action {
+            StepAction::Tool { ref arguments, .. } => arguments,
+            StepAction::Agent { ref payload, .. } => payload,
+        };
+        let mut deps = Vec::new();
+        for dep in self
+            .depends_on
+            .iter()
+            .cloned()
+            .chain(template::step_references(template))
+        {
+            if !deps.contains(&dep) {
+                deps.push(dep);
+            }
+        }
+        deps
+    }
+}
+
+/// A named DAG of steps
+///
+/// ```
+/// use loom_core::workflow::{Step, Workflow};
+/// use serde_json::json;
+///
+/// let workflow = Workflow::new("research")
+///     .with_step(Step::tool("search", "web:search", json!({"query": "${input.topic}"})).with_retries(2))
+///     .with_step(Step::agent("summarize", "agent.summarizer", json!({"results": "${steps.search.output}"})));
+///
+/// assert_eq!(workflow.execution_order().unwrap(), vec!["search", "summarize"]);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Workflow {
+    pub name: String,
+    pub steps: Vec<Step>,
+    topic: Option<String>,
+}
+
+impl Workflow {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            steps: Vec::new(),
+            topic: None,
+        }
+    }
+
+    pub fn with_step(mut self, step: Step) -> Self {
+        self.steps.push(step);
+        self
+    }
+
+    /// Publish progress events on `topic` instead of `workflow.<name>`
+    pub fn with_topic(mut self, topic: impl Into<String>) -> Self {
+        self.topic = Some(topic.into());
+        self
+    }
+
+    /// Topic the engine publishes step events and the `workflow.completed` summary on
+    pub fn topic(&self) -> String {
+        self.topic
+            .clone()
+            .unwrap_or_else(|| format!("workflow.{}", self.name))
+    }
+
+    pub fn step(&self, id: &str) -> Option<&Step> {
+        self.steps.iter().find(|s| s.id == id)
+    }
+
+    /// Parse and validate a workflow definition
+    ///
+    /// ```toml
+    /// name = "research"
+    ///
+    /// [[steps]]
+    /// id = "search"
+    /// tool = "web:search"
+    /// arguments = { query = "${input.topic}" }
+    /// retries = 2
+    /// timeout_ms = 5000
+    ///
+    /// [[steps]]
+    /// id = "summarize"
+    /// agent = "agent.summarizer"
+    /// payload = { results = "${steps.search.output}" }
+    /// ```
+    pub fn from_toml(source: &str) -> Result<Self> {
+        let spec: WorkflowSpec = toml::from_str(source)
+            .map_err(|e| LoomError::WorkflowError(format!("Invalid workflow TOML: {e}")))?;
+        let mut workflow = Workflow::new(spec.name);
+        workflow.topic = spec.topic;
+        for step in spec.steps {
+            workflow.steps.push(step.into_step()?);
+        }
+        workflow.validate()?;
+        Ok(workflow)
+    }
+
+    /// Check ids, dependencies and acyclicity
+    pub fn validate(&self) -> Result<()> {
+        self.execution_order().map(|_| ())
+    }
+
+    /// Step ids such that every step comes after its dependencies. Ties keep
+    /// definition order.
+    pub fn execution_order(&self) -> Result<Vec<String>> {
+        if self.steps.is_empty() {
+            return Err(LoomError::WorkflowError(format!(
+                "Workflow '{}' has no steps",
+                self.name
+            )));
+        }
+        let mut index = HashMap::new();
+        for (i, step) in self.steps.iter().enumerate() {
+            if step.id.is_empty() || step.id.contains('.') {
+                return Err(LoomError::WorkflowError(format!(
+                    "Invalid step id '{}': ids must be non-empty and contain no '.'",
+                    step.id
+                )));
+            }
+            if index.insert(step.id.as_str(), i).is_some() {
+                return Err(LoomError::WorkflowError(format!(
+                    "Duplicate step id '{}'",
+                    step.id
+                )));
+            }
+        }
+
+        // remaining[i] = dependencies of step i that have not been ordered yet
+        let mut remaining = Vec::with_capacity(self.steps.len());
+        let mut dependents: Vec<Vec<usize>> = vec![Vec::new(); self.steps.len()];
+        for (i, step) in self.steps.iter().enumerate() {
+            let deps: HashSet<String> = step.dependencies().into_iter().collect();
+            for dep in &deps {
+                let Some(&d) = index.get(dep.as_str()) else {
+                    return Err(LoomError::WorkflowError(format!(
+                        "Step '{}' depends on unknown step '{}'",
+                        step.id, dep
+                    )));
+                };
+                dependents[d].push(i);
+            }
+            remaining.push(deps.len());
+        }
+
+        let mut order = Vec::with_capacity(self.steps.len());
+        let mut done = vec![false; self.steps.len()];
+        while order.len() < self.steps.len() {
+            let Some(next) = (0..self.steps.len()).find(|&i| !done[i] && remaining[i] == 0) else {
+                let cycle: Vec<&str> = (0..self.steps.len())
+                    .filter(|&i| !done[i])
+                    .map(|i| self.steps[i].id.as_str())
+                    .collect();
+                return Err(LoomError::WorkflowError(format!(
+                    "Dependency cycle between steps: {}",
+                    cycle.join(", ")
+                )));
+            };
+            done[next] = true;
+            for &dependent in &dependents[next] {
+                remaining[dependent] -= 1;
+            }
+            order.push(self.steps[next].id.clone());
+        }
+        Ok(order)
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct WorkflowSpec {
+    name: String,
+    topic: Option<String>,
+    #[serde(default)]
+    steps: Vec<StepSpec>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct StepSpec {
+    id: String,
+    tool: Option<String>,
+    agent: Option<String>,
+    arguments: Option<Value>,
+    payload: Option<Value>,
+    #[serde(default)]
+    depends_on: Vec<String>,
+    retries: Option<u32>,
+    retry_backoff_ms: Option<u64>,
+    timeout_ms: Option<u64>,
+}
+
+impl StepSpec {
+    fn into_step(self) -> Result<Step> {
+        let empty = || Value::Object(Default::default());
+        let mut step = match (self.tool, self.agent) {
+            (Some(name), None) if self.payload.is_none() => {
+                Step::tool(self.id, name, self.arguments.unwrap_or_else(empty))
+            }
+            (None, Some(topic)) if self.arguments.is_none() => {
+                Step::agent(self.id, topic, self.payload.unwrap_or_else(empty))
+            }
+            _ => {
+                return Err(LoomError::WorkflowError(format!(
+                    "Step '{}' needs either `tool` (with `arguments`) or `agent` (with `payload`)",
+                    self.id
+                )))
+            }
+        };
+        step.depends_on = self.depends_on;
+        if let Some(retries) = self.retries {
+            step.retries = retries;
+        }
+        if let Some(ms) = self.retry_backoff_ms {
+            step.retry_backoff = Duration::from_millis(ms);
+        }
+        if let Some(ms) = self.timeout_ms {
+            step.timeout = Duration::from_millis(ms);
+        }
+        Ok(step)
+    }
+}
@@ -0,0 +1,390 @@
+//! Executes workflows: schedules ready steps concurrently, retries failures and
+//! reports progress on the EventBus
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use tokio::task::JoinSet;
+use tokio::time::{timeout, Instant};
+use tracing::{debug, info, warn};
+
+use super::definition::{Step, StepAction, Workflow};
+use super::{template, types};
+use crate::proto::Event;
+use crate::{Collaborator, Envelope, EventBus, Result, ToolRegistry};
+
+/// How a step ended
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StepStatus {
+    Succeeded,
+    Failed,
+    /// Not run because a dependency did not succeed
+    Skipped,
+}
+
+/// Result of one step
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StepReport {
+    pub id: String,
+    pub status: StepStatus,
+    pub attempts: u32,
+    pub output: Option<Value>,
+    pub error: Option<String>,
+    pub elapsed_ms: u64,
+}
+
+/// Result of `WorkflowEngine::run`; also the payload of the `workflow.completed` event
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkflowReport {
+    pub workflow: String,
+    pub run_id: String,
+    /// Steps in completion order
+    pub steps: Vec<StepReport>,
+    pub elapsed_ms: u64,
+}
+
+impl WorkflowReport {
+    /// True if every step succeeded
+    pub fn succeeded(&self) -> bool {
+        self.steps.iter().all(|s| s.status == StepStatus::Succeeded)
+    }
+
+    pub fn step(&self, id: &str) -> Option<&StepReport> {
+        self.steps.iter().find(|s| s.id == id)
+    }
+
+    /// Output of a succeeded step
+    pub fn output(&self, id: &str) -> Option<&Value> {
+        self.step(id).and_then(|s| s.output.as_ref())
+    }
+}
+
+/// Runs workflows against a `ToolRegistry` and agents reachable over the `EventBus`
+///
+/// A step starts as soon as all of its dependencies succeeded; independent branches
+/// run concurrently. A step that still fails after its retries fails the workflow and
+/// every step depending on it is skipped, while unrelated branches run to completion.
+pub struct WorkflowEngine {
+    event_bus: Arc<EventBus>,
+    tool_registry: Arc<ToolRegistry>,
+    sender_id: String,
+}
+
+impl WorkflowEngine {
+    pub fn new(event_bus: Arc<EventBus>, tool_registry: Arc<ToolRegistry>) -> Self {
+        Self {
+            event_bus,
+            tool_registry,
+            sender_id: "workflow_engine".to_string(),
+        }
+    }
+
+    /// Source of published events and sender of agent requests
+    pub fn with_sender_id(mut self, sender_id: impl Into<String>) -> Self {
+        self.sender_id = sender_id.into();
+        self
+    }
+
+    /// Run `workflow` with `input` available to steps as `${input...}`.
+    ///
+    /// Step failures are reported in the returned `WorkflowReport`; only an invalid
+    /// definition is an error.
+    #[tracing::instrument(skip(self, workflow, input), fields(workflow = %workflow.name))]
+    pub async fn run(&self, workflow: &Workflow, input: Value) -> Result<WorkflowReport> {
+        let order = workflow.execution_order()?;
+        let started = Instant::now();
+        let run_id = format!(
+            "wf_{}",
+            chrono::Utc::now()
+                .timestamp_nanos_opt()
+                .unwrap_or_else(|| chrono::Utc::now().timestamp_millis() * 1_000_000)
+        );
+        let topic = workflow.topic();
+        let env = Envelope::new(run_id.clone(), self.sender_id.clone());
+        let collaborator = Arc::new(Collaborator::new(
+            Arc::clone(&self.event_bus),
+            self.sender_id.clone(),
+        ));
+        info!(target: "workflow", workflow = %workflow.name, run_id = %run_id, steps = order.len(), "Workflow started");
+        self.emit(
+            &topic,
+            &env,
+            &workflow.name,
+            types::STARTED,
+            None,
+            json!({ "input": input, "steps": order }),
+        )
+        .await;
+
+        let mut pending: Vec<&Step> = order.iter().filter_map(|id| workflow.step(id)).collect();
+        let mut run = RunState::default();
+        let mut running = JoinSet::new();
+
+        loop {
+            // Start or skip every step whose dependencies have all finished
+            let mut i = 0;
+            while i < pending.len() {
+                let step = pending[i];
+                let deps = step.dependencies();
+                if !deps.iter().all(|d| run.finished.contains_key(d)) {
+                    i += 1;
+                    continue;
+                }
+                pending.remove(i);
+                // Restart the scan: a skip can make later steps ready
+                i = 0;
+
+                let report = if let Some(dep) = deps
+                    .iter()
+                    .find(|d| run.finished.get(*d) != Some(&StepStatus::Succeeded))
+                {
+                    Some(StepReport {
+                        id: step.id.clone(),
+                        status: StepStatus::Skipped,
+                        attempts: 0,
+                        output: None,
+                        error: Some(format!("dependency '{dep}' did not succeed")),
+                        elapsed_ms: 0,
+                    })
+                } else {
+                    let template = match step.action {
+                        StepAction::Tool { ref arguments, .. } => arguments,
+                        StepAction::Agent { ref payload, .. } => payload,
+                    };
+                    match template::render(template, &input, &run.outputs) {
+                        Ok(rendered) => {
+                            self.emit(
+                                &topic,
+                                &env,
+                                &workflow.name,
+                                types::STEP_STARTED,
+                                Some(step.id.as_str()),
+                                json!({ "input": rendered }),
+                            )
+                            .await;
+                            let step = step.clone();
+                            let tool_registry = Arc::clone(&self.tool_registry);
+                            let collaborator = Arc::clone(&collaborator);
+                            running.spawn(async move {
+                                let id = step.id.clone();
+                                // Run on its own task so a panicking tool fails only its step
+                                let task = tokio::spawn(execute(
+                                    step,
+                                    rendered,
+                                    tool_registry,
+                                    collaborator,
+                                ));
+                                task.await.unwrap_or_else(|e| StepReport {
+                                    id,
+                                    status: StepStatus::Failed,
+                                    attempts: 1,
+                                    output: None,
+                                    error: Some(format!("step task failed: {e}")),
+                                    elapsed_ms: 0,
+                                })
+                            });
+                            None
+                        }
+                        Err(e) => Some(StepReport {
+                            id: step.id.clone(),
+                            status: StepStatus::Failed,
+                            attempts: 0,
+                            output: None,
+                            error: Some(e),
+                            elapsed_ms: 0,
+                        }),
+                    }
+                };
+                if let Some(report) = report {
+                    self.record(&topic, &env, &workflow.name, report, &mut run)
+                        .await;
+                }
+            }
+
+            let Some(joined) = running.join_next().await else {
+                break;
+            };
+            match joined {
+                Ok(report) => {
+                    self.record(&topic, &env, &workflow.name, report, &mut run)
+                        .await
+                }
+                Err(e) => warn!(target: "workflow", error = %e, "Workflow step task aborted"),
+            }
+        }
+
+        let report = WorkflowReport {
+            workflow: workflow.name.clone(),
+            run_id,
+            steps: run.reports,
+            elapsed_ms: started.elapsed().as_millis() as u64,
+        };
+        info!(target: "workflow", workflow = %workflow.name, run_id = %report.run_id, succeeded = report.succeeded(), elapsed_ms = report.elapsed_ms, "Workflow completed");
+        self.emit(
+            &topic,
+            &env,
+            &workflow.name,
+            types::COMPLETED,
+            None,
+            serde_json::to_value(&report).unwrap_or_default(),
+        )
+        .await;
+        Ok(report)
+    }
+
+    /// Store a finished step and publish its outcome
+    async fn record(
+        &self,
+        topic: &str,
+        env: &Envelope,
+        workflow: &str,
+        report: StepReport,
+        run: &mut RunState,
+    ) {
+        let event_type = match report.status {
+            StepStatus::Succeeded => types::STEP_COMPLETED,
+            StepStatus::Failed => types::STEP_FAILED,
+            StepStatus::Skipped => types::STEP_SKIPPED,
+        };
+        debug!(target: "workflow", workflow = %workflow, step = %report.id, status = ?report.status, attempts = report.attempts, "Step finished");
+        self.emit(
+            topic,
+            env,
+            workflow,
+            event_type,
+            Some(report.id.as_str()),
+            serde_json::to_value(&report).unwrap_or_default(),
+        )
+        .await;
+        run.finished
+            .insert(report.id.clone(), report.status.clone());
+        if let Some(ref output) = report.output {
+            run.outputs.insert(report.id.clone(), output.clone());
+        }
+        run.reports.push(report);
+    }
+
+    /// Publish a progress event; failures are logged, never fail the workflow
+    async fn emit(
+        &self,
+        topic: &str,
+        env: &Envelope,
+        workflow: &str,
+        event_type: &str,
+        step: Option<&str>,
+        payload: Value,
+    ) {
+        let mut metadata = HashMap::new();
+        env.apply_to_metadata(&mut metadata);
+        metadata.insert("workflow".to_string(), workflow.to_string());
+        if let Some(step) = step {
+            metadata.insert("step".to_string(), step.to_string());
+        }
+        let now = chrono::Utc::now();
+        let mut event = Event {
+            id: format!(
+                "evt_wf_{}",
+                now.timestamp_nanos_opt()
+                    .unwrap_or_else(|| now.timestamp_millis() * 1_000_000)
+            ),
+            r#type: event_type.to_string(),
+            timestamp_ms: now.timestamp_millis(),
+            source: self.sender_id.clone(),
+            metadata,
+            payload: serde_json::to_vec(&payload).unwrap_or_default(),
+            confidence: 1.0,
+            tags: vec!["workflow".into()],
+            priority: 50,
+        };
+        env.attach_to_event(&mut event);
+        if let Err(e) = self.event_bus.publish(topic, event).await {
+            warn!(target: "workflow", workflow = %workflow, event_type, error = %e, "Failed to publish workflow event");
+        }
+    }
+}
+
+/// Bookkeeping of one `run`
+#[derive(Default)]
+struct RunState {
+    finished: HashMap<String, StepStatus>,
+    outputs: HashMap<String, Value>,
+    reports: Vec<StepReport>,
+}
+
+/// Run a step with its retries and per-attempt timeout
+async fn execute(
+    step: Step,
+    input: Value,
+    tool_registry: Arc<ToolRegistry>,
+    collaborator: Arc<Collaborator>,
+) -> StepReport {
+    let started = Instant::now();
+    let mut attempts = 0;
+    let result = loop {
+        attempts += 1;
+        let attempt = run_action(&step, &input, &tool_registry, &collaborator);
+        let result = match timeout(step.timeout, attempt).await {
+            Ok(result) => result,
+            Err(_) => Err(format!("timed out after {}ms", step.timeout.as_millis())),
+        };
+        match result {
+            Err(e) if attempts <= step.retries => {
+                let delay = step.retry_backoff * 2u32.saturating_pow(attempts - 1);
+                debug!(target: "workflow", step = %step.id, attempts, delay_ms = delay.as_millis() as u64, error = %e, "Retrying step");
+                tokio::time::sleep(delay).await;
+            }
+            result => break result,
+        }
+    };
+    let elapsed_ms = started.elapsed().as_millis() as u64;
+    match result {
+        Ok(output) => StepReport {
+            id: step.id,
+            status: StepStatus::Succeeded,
+            attempts,
+            output: Some(output),
+            error: None,
+            elapsed_ms,
+        },
+        Err(e) => StepReport {
+            id: step.id,
+            status: StepStatus::Failed,
+            attempts,
+            output: None,
+            error: Some(e),
+            elapsed_ms,
+        },
+    }
+}
+
+async fn run_action(
+    step: &Step,
+    input: &Value,
+    tool_registry: &ToolRegistry,
+    collaborator: &Collaborator,
+) -> std::result::Result<Value, String> {
+    match step.action {
+        StepAction::Tool { ref name, .. } => tool_registry
+            .call(name, input.clone())
+            .await
+            .map_err(|e| e.to_string()),
+        StepAction::Agent { ref topic, .. } => {
+            let payload = serde_json::to_vec(input).map_err(|e| e.to_string())?;
+            // The step timeout bounds the attempt; the request itself waits as long
+            let timeout_ms = (step.timeout.as_millis() as u64).max(1);
+            match collaborator
+                .request_reply(topic, payload, timeout_ms)
+                .await
+                .map_err(|e| e.to_string())?
+            {
+                Some(reply) => Ok(serde_json::from_slice(&reply.payload).unwrap_or_else(|_| {
+                    Value::String(String::from_utf8_lossy(&reply.payload).into_owned())
+                })),
+                None => Err(format!("no reply from '{topic}'")),
+            }
+        }
+    }
+}
@@ -0,0 +1,36 @@
+//! Workflow/DAG orchestration on top of the EventBus
+//!
+//! A `Workflow` is a named DAG of steps. Each step either calls a tool in the
+//! `ToolRegistry` or sends a request to an agent topic (the `Collaborator`
+//! request/reply pattern); edges are data dependencies, declared with `depends_on`
+//! or implied by `${steps.<id>.output}` references in a step's arguments.
+//! Workflows are built in Rust or loaded from TOML with `Workflow::from_toml`.
+//!
+//! `WorkflowEngine::run` executes steps as soon as their dependencies succeed, with
+//! per-step retries and timeouts, and publishes progress on the workflow topic
+//! (`workflow.<name>` by default). Every event carries an envelope whose thread id is
+//! the run id, plus `workflow` and `step` metadata. The run ends with a
+//! `workflow.completed` event whose payload is the `WorkflowReport`.
+
+pub mod definition;
+pub mod engine;
+pub(crate) mod template;
+
+pub use definition::{Step, StepAction, Workflow};
+pub use engine::{StepReport, StepStatus, WorkflowEngine, WorkflowReport};
+
+/// Event types published by `WorkflowEngine`
+pub mod types {
+    /// Run started; payload holds the input and the execution order
+    pub const STARTED: &str = "workflow.started";
+    /// A step's first attempt is starting; payload holds the rendered input
+    pub const STEP_STARTED: &str = "workflow.step.started";
+    /// Payload is the `StepReport`
+    pub const STEP_COMPLETED: &str = "workflow.step.completed";
+    /// Step failed after all retries; payload is the `StepReport`
+    pub const STEP_FAILED: &str = "workflow.step.failed";
+    /// Step not run because a dependency failed; payload is the `StepReport`
+    pub const STEP_SKIPPED: &str = "workflow.step.skipped";
+    /// Summary of the run; payload is the `WorkflowReport`
+    pub const COMPLETED: &str = "workflow.completed";
+}
@@ -0,0 +1,175 @@
+//! `${...}` references in step arguments and payloads
+
+// Also used by `tools::composite` for composite tool steps
+
+use std::collections::HashMap;
+
+use serde_json::Value;
+
+/// Ids of the steps referenced anywhere in `template`
+pub(crate) fn step_references(template: &Value) -> Vec<String> {
+    let mut ids = Vec::new();
+    visit_strings(template, &mut |s| {
+        for reference in references(s) {
+            let mut parts = reference.split('.');
+            if parts.next() == Some("steps") {
+                if let Some(id) = parts.next() {
+                    if !ids.iter().any(|i| i == id) {
+                        ids.push(id.to_string());
+                    }
+                }
+            }
+        }
+    });
+    ids
+}
+
+/// Names of the input fields referenced anywhere in `template` (`${input.<name>...}`)
+pub(crate) fn input_references(template: &Value) -> Vec<String> {
+    let mut names = Vec::new();
+    visit_strings(template, &mut |s| {
+        for reference in references(s) {
+            let mut parts = reference.split('.');
+            if parts.next() == Some("input") {
+                if let Some(name) = parts.next() {
+                    if !names.iter().any(|n| n == name) {
+                        names.push(name.to_string());
+                    }
+                }
+            }
+        }
+    });
+    names
+}
+
+/// The reference `s` consists of, if it is nothing but one reference
+pub(crate) fn lone_reference(s: &str) -> Option<&str> {
+    s.strip_prefix("${")
+        .and_then(|r| r.strip_suffix('}'))
+        .filter(|r| !r.contains("${"))
+}
+
+/// Replace every reference in `template` with the workflow input or a step output
+pub(crate) fn render(
+    template: &Value,
+    input: &Value,
+    outputs: &HashMap<String, Value>,
+) -> Result<Value, String> {
+    match template {
+        Value::String(s) => render_string(s, input, outputs),
+        Value::Array(items) => items
+            .iter()
+            .map(|v| render(v, input, outputs))
+            .collect::<Result<Vec<_>, _>>()
+            .map(Value::Array),
+        Value::Object(map) => map
+            .iter()
+            .map(|(k, v)| render(v, input, outputs).map(|v| (k.clone(), v)))
+            .collect::<Result<serde_json::Map<_, _>, _>>()
+            .map(Value::Object),
+        other => Ok(other.clone()),
+    }
+}
+
+fn render_string(
+    s: &str,
+    input: &Value,
+    outputs: &HashMap<String, Value>,
+) -> Result<Value, String> {
+    // A lone reference keeps the type of the referenced value
+    if let Some(reference) = lone_reference(s) {
+        return resolve(reference, input, outputs).cloned();
+    }
+
+    let mut rendered = String::with_capacity(s.len());
+    let mut rest = s;
+    while let Some(start) = rest.find("${") {
+        let Some(len) = rest[start..].find('}') else {
+            break;
+        };
+        rendered.push_str(&rest[..start]);
+        match resolve(&rest[start + 2..start + len], input, outputs)? {
+            Value::String(text) => rendered.push_str(text),
+            value => rendered.push_str(&value.to_string()),
+        }
+        rest = &rest[start + len + 1..];
+    }
+    rendered.push_str(rest);
+    Ok(Value::String(rendered))
+}
+
+fn resolve<'a>(
+    reference: &str,
+    input: &'a Value,
+    outputs: &'a HashMap<String, Value>,
+) -> Result<&'a Value, String> {
+    let mut parts = reference.split('.');
+    let (root, mut path) = match parts.next() {
+        Some("input") => (input, parts),
+        Some("steps") => {
+            let id = parts.next().unwrap_or_default();
+            if parts.next() != Some("output") {
+                return Err(format!("${{{reference}}}: expected steps.<id>.output"));
+            }
+            let output = outputs
+                .get(id)
+                .ok_or_else(|| format!("${{{reference}}}: step '{id}' has no output"))?;
+            (output, parts)
+        }
+        _ => return Err(format!("${{{reference}}}: must start with input or steps")),
+    };
+    path.try_fold(root, |value, segment| match value {
+        Value::Array(items) => segment.parse::<usize>().ok().and_then(|i| items.get(i)),
+        Value::Object(map) => map.get(segment),
+        _ => None,
+    })
+    .ok_or_else(|| format!("${{{reference}}}: path not found"))
+}
+
+fn references(s: &str) -> Vec<&str> {
+    let mut found = Vec::new();
+    let mut rest = s;
+    while let Some(start) = rest.find("${") {
+        let Some(len) = rest[start..].find('}') else {
+            break;
+        };
+        found.push(&rest[start + 2..start + len]);
+        rest = &rest[start + len + 1..];
+    }
+    found
+}
+
+fn visit_strings(value: &Value, f: &mut impl FnMut(&str)) {
+    match value {
+        Value::String(s) => f(s),
+        Value::Array(items) => items.iter().for_each(|v| visit_strings(v, f)),
+        Value::Object(map) => map.values().for_each(|v| visit_strings(v, f)),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn renders_whole_and_embedded_references() {
+        let outputs = HashMap::from([("fetch".to_string(), json!({"items": [1, 2], "n": 2}))]);
+        let input = json!({"city": "Berlin"});
+        let template = json!({
+            "items": "${steps.fetch.output.items}",
+            "first": "${steps.fetch.output.items.0}",
+            "label": "${input.city}: ${steps.fetch.output.n} items",
+            "fixed": 3,
+        });
+        assert_eq!(
+            render(&template, &input, &outputs).unwrap(),
+            json!({"items": [1, 2], "first": 1, "label": "Berlin: 2 items", "fixed": 3})
+        );
+        assert_eq!(step_references(&template), vec!["fetch".to_string()]);
+        assert_eq!(input_references(&template), vec!["city".to_string()]);
+        assert!(render(&json!("${steps.fetch.output.missing}"), &input, &outputs).is_err());
+        assert!(render(&json!("${env.HOME}"), &input, &outputs).is_err());
+    }
+}
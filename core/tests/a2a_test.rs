@@ -0,0 +1,291 @@
+//! Tests for the A2A server endpoints and the `a2a:delegate` tool
+
+use async_trait::async_trait;
+use loom_core::a2a::{Message, Task, TaskSendParams, TaskState};
+use loom_core::cognitive::{
+    CognitiveAgent, CognitiveLoop, ExecutionResult, MemoryBuffer, Perception, Plan,
+};
+use loom_core::proto::{AgentConfig, AgentState, Event};
+use loom_core::{
+    A2aClient, A2aConfig, A2aDelegateTool, A2aServer, AgentCard, AgentDirectory, AgentInfo,
+    AgentRuntime, EventBus, LoomError, ModelRouter, QoSLevel, Result, Tool, ToolRegistry,
+};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Answers every goal with "echo: <goal>"
+struct EchoLoop {
+    memory: MemoryBuffer,
+}
+
+#[async_trait]
+impl CognitiveLoop for EchoLoop {
+    async fn perceive(&mut self, event: Event, _state: &AgentState) -> Result<Perception> {
+        Ok(Perception::from_event(event))
+    }
+
+    async fn think(&mut self, perception: &Perception) -> Result<Plan> {
+        let goal = perception.goal.clone().unwrap_or_default();
+        Ok(Plan::final_answer(goal.clone(), format!("echo: {goal}")))
+    }
+
+    async fn act(&mut self, plan: &Plan, _state: &mut AgentState) -> Result<ExecutionResult> {
+        Ok(ExecutionResult::with_response(
+            plan.final_answer.clone().unwrap_or_default(),
+        ))
+    }
+
+    fn memory_buffer(&self) -> &MemoryBuffer {
+        &self.memory
+    }
+
+    fn memory_buffer_mut(&mut self) -> &mut MemoryBuffer {
+        &mut self.memory
+    }
+}
+
+struct Node {
+    base_url: String,
+    bus: Arc<EventBus>,
+    _runtime: AgentRuntime,
+}
+
+/// Event bus with an echo agent `echo` (listed in the directory) and the A2A server on an
+/// ephemeral port
+async fn start_node(timeout: Duration) -> Node {
+    let bus = Arc::new(EventBus::new().await.unwrap());
+    bus.start().await.unwrap();
+    let runtime = AgentRuntime::new(
+        Arc::clone(&bus),
+        Arc::new(ToolRegistry::new()),
+        ModelRouter::new().await.unwrap(),
+    )
+    .await
+    .unwrap();
+    let agent_config = AgentConfig {
+        agent_id: "echo".to_string(),
+        agent_type: "cognitive".to_string(),
+        subscribed_topics: vec![],
+        capabilities: vec![],
+        parameters: Default::default(),
+    };
+    runtime
+        .create_agent(
+            agent_config,
+            Box::new(CognitiveAgent::new(EchoLoop {
+                memory: MemoryBuffer::new(10),
+            })),
+        )
+        .await
+        .unwrap();
+
+    let directory = Arc::new(AgentDirectory::new());
+    for agent_id in ["echo", "offline"] {
+        directory.register_agent(AgentInfo {
+            agent_id: agent_id.to_string(),
+            capabilities: vec!["echo.text".to_string()],
+            metadata: HashMap::from([("description".to_string(), "Echoes".to_string())]),
+            ..Default::default()
+        });
+    }
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let base_url = format!("http://{}", listener.local_addr().unwrap());
+    let config = A2aConfig {
+        public_url: Some(base_url.clone()),
+        timeout,
+        ..Default::default()
+    };
+    let app = A2aServer::new(config, Arc::clone(&bus), directory).router();
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+    Node {
+        base_url,
+        bus,
+        _runtime: runtime,
+    }
+}
+
+fn send_params(id: &str, text: &str) -> TaskSendParams {
+    TaskSendParams {
+        id: id.to_string(),
+        session_id: Some("s1".to_string()),
+        message: Message::user(text),
+        metadata: HashMap::new(),
+    }
+}
+
+#[test]
+fn task_wire_format_follows_the_protocol() {
+    let task: Task = serde_json::from_value(json!({
+        "id": "t1",
+        "sessionId": "s1",
+        "status": { "state": "input-required", "message": { "role": "agent", "parts": [{ "type": "text", "text": "which city?" }] } },
+        "artifacts": [{ "parts": [{ "type": "file", "file": {} }, { "type": "text", "text": "partial" }] }],
+    }))
+    .unwrap();
+    assert_eq!(task.status.state, TaskState::InputRequired);
+    assert!(!task.status.state.is_terminal());
+    assert_eq!(task.output_text(), "partial");
+
+    let event = task.lifecycle_event("test");
+    assert_eq!(event.r#type, "a2a.task.input-required");
+    assert_eq!(
+        event.metadata.get("thread_id").map(String::as_str),
+        Some("t1")
+    );
+    assert_eq!(task.thread_topic(), "thread.t1.broadcast");
+
+    let value = serde_json::to_value(&task).unwrap();
+    assert_eq!(value["sessionId"], "s1");
+    assert_eq!(value["status"]["state"], "input-required");
+}
+
+#[tokio::test]
+async fn serves_agent_cards_and_completes_tasks() {
+    let node = start_node(Duration::from_secs(5)).await;
+    let client = A2aClient::new();
+
+    let cards: Vec<AgentCard> = reqwest::get(format!("{}/a2a/agents", node.base_url))
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(cards.len(), 2);
+
+    let agent_url = format!("{}/a2a/echo", node.base_url);
+    let card = client.card(&agent_url).await.unwrap();
+    assert_eq!(card.name, "echo");
+    assert_eq!(card.url, agent_url);
+    assert_eq!(card.description.as_deref(), Some("Echoes"));
+    assert_eq!(card.skills[0].id, "echo.text");
+
+    // Lifecycle events land on the task's thread
+    let (_sub, mut lifecycle) = node
+        .bus
+        .subscribe(
+            "thread.task-1.broadcast".to_string(),
+            vec![],
+            QoSLevel::QosBatched,
+        )
+        .await
+        .unwrap();
+
+    let task = client
+        .send_task(&agent_url, &send_params("task-1", "hello"))
+        .await
+        .unwrap();
+    assert_eq!(task.status.state, TaskState::Completed);
+    assert_eq!(task.output_text(), "echo: hello");
+    assert_eq!(task.history.len(), 2);
+
+    let fetched = client.get_task(&agent_url, "task-1").await.unwrap();
+    assert_eq!(fetched, task);
+
+    let mut states = vec![];
+    while states.len() < 2 {
+        let event = tokio::time::timeout(Duration::from_secs(2), lifecycle.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        states.push(event.r#type);
+    }
+    assert_eq!(states, ["a2a.task.working", "a2a.task.completed"]);
+
+    let err = client.cancel_task(&agent_url, "task-1").await.unwrap_err();
+    assert!(matches!(err, LoomError::A2aError(ref m) if m.contains("-32002")));
+    let err = client.get_task(&agent_url, "missing").await.unwrap_err();
+    assert!(matches!(err, LoomError::A2aError(ref m) if m.contains("-32001")));
+}
+
+#[tokio::test]
+async fn rejects_unknown_methods_and_agents() {
+    let node = start_node(Duration::from_secs(1)).await;
+    let http = reqwest::Client::new();
+
+    let reply: Value = http
+        .post(format!("{}/a2a/echo", node.base_url))
+        .json(&json!({ "jsonrpc": "2.0", "id": 7, "method": "tasks/resubscribe", "params": {} }))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(reply["id"], 7);
+    assert_eq!(reply["error"]["code"], -32601);
+
+    let resp = http
+        .get(format!(
+            "{}/a2a/nobody/.well-known/agent.json",
+            node.base_url
+        ))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 404);
+
+    // Listed in the directory, but no runtime agent is subscribed
+    let task = A2aClient::new()
+        .send_task(
+            &format!("{}/a2a/offline", node.base_url),
+            &send_params("task-2", "hello"),
+        )
+        .await
+        .unwrap();
+    assert_eq!(task.status.state, TaskState::Failed);
+    assert!(task.output_text().contains("not running"));
+}
+
+#[tokio::test]
+async fn delegate_tool_runs_remote_task_on_thread() {
+    let node = start_node(Duration::from_secs(5)).await;
+    let tool = A2aDelegateTool::new()
+        .with_event_bus(Arc::clone(&node.bus))
+        .with_poll_interval(Duration::from_millis(20));
+    assert_eq!(tool.name(), "a2a:delegate");
+
+    let (_sub, mut lifecycle) = node
+        .bus
+        .subscribe(
+            "thread.thread-9.broadcast".to_string(),
+            vec!["a2a.task.completed".to_string()],
+            QoSLevel::QosBatched,
+        )
+        .await
+        .unwrap();
+
+    let out = tool
+        .call(json!({
+            "agent_url": format!("{}/a2a/echo", node.base_url),
+            "message": "delegated work",
+            "thread_id": "thread-9",
+        }))
+        .await
+        .unwrap();
+    assert_eq!(out["task_id"], "thread-9");
+    assert_eq!(out["state"], "completed");
+    assert_eq!(out["text"], "echo: delegated work");
+
+    // Published by the server and by the tool
+    for _ in 0..2 {
+        let event = tokio::time::timeout(Duration::from_secs(2), lifecycle.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            event.metadata.get("thread_id").map(String::as_str),
+            Some("thread-9")
+        );
+    }
+
+    let err = tool
+        .call(json!({ "agent_url": "http://127.0.0.1:1/a2a/x" }))
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("message"));
+}
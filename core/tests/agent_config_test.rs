@@ -0,0 +1,384 @@
+//! Tests for declarative agent configuration (`AgentsFile`, `AgentConfigLoader`, templated parameters)
+
+use async_trait::async_trait;
+use loom_core::agent::declarative::{BehaviorFactory, ReloadReport};
+use loom_core::agent::AgentBehavior;
+use loom_core::cognitive::ThinkingStrategy;
+use loom_core::proto::{Action, AgentConfig, AgentState, Event};
+use loom_core::tools::ToolResult;
+use loom_core::{
+    AgentConfigLoader, AgentRuntime, AgentSpec, AgentsFile, EventBus, ModelRouter,
+    ParameterResolver, Result, SecretProvider, Tool, ToolRegistry,
+};
+use serde_json::{json, Value};
+use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+const AGENTS_TOML: &str = r#"
+[[agents]]
+id = "support"
+topics = ["support.requests"]
+strategy = "react"
+system_prompt = "You answer product questions."
+tools = ["kb:*", "math:eval"]
+max_iterations = 4
+
+[[agents]]
+id = "triage"
+topics = ["tickets.new"]
+"#;
+
+struct NoopBehavior;
+
+#[async_trait]
+impl AgentBehavior for NoopBehavior {
+    async fn on_event(&mut self, _event: Event, _state: &mut AgentState) -> Result<Vec<Action>> {
+        Ok(vec![])
+    }
+
+    async fn on_init(&mut self, _config: &AgentConfig) -> Result<()> {
+        Ok(())
+    }
+
+    async fn on_shutdown(&mut self) -> Result<()> {
+        Ok(())
+    }
+}
+
+async fn make_runtime() -> Result<AgentRuntime> {
+    let bus = Arc::new(EventBus::new().await?);
+    bus.start().await?;
+    AgentRuntime::new(
+        bus,
+        Arc::new(ToolRegistry::new()),
+        ModelRouter::new().await?,
+    )
+    .await
+}
+
+/// Factory counting the behaviors it builds
+fn counting_factory() -> (BehaviorFactory, Arc<AtomicUsize>) {
+    let built = Arc::new(AtomicUsize::new(0));
+    let counter = Arc::clone(&built);
+    let factory: BehaviorFactory = Arc::new(move |_spec: &AgentSpec| {
+        counter.fetch_add(1, Ordering::SeqCst);
+        Ok(Box::new(NoopBehavior) as Box<dyn AgentBehavior>)
+    });
+    (factory, built)
+}
+
+fn ids(ids: &[&str]) -> Vec<String> {
+    ids.iter().map(|id| id.to_string()).collect()
+}
+
+#[test]
+fn agents_file_parses_toml_and_yaml() {
+    let file = AgentsFile::from_toml(AGENTS_TOML).unwrap();
+    assert_eq!(file.agents.len(), 2);
+    let support = &file.agents[0];
+    assert_eq!(support.strategy, ThinkingStrategy::ReAct);
+    assert_eq!(
+        support.tools,
+        Some(vec!["kb:*".to_string(), "math:eval".to_string()])
+    );
+    let config = support.cognitive_config();
+    assert_eq!(config.max_iterations, 4);
+    assert_eq!(
+        config.system_prompt.as_deref(),
+        Some("You answer product questions.")
+    );
+    // Omitted fields take defaults
+    assert_eq!(file.agents[1].strategy, ThinkingStrategy::default());
+    assert_eq!(file.agents[1].tools, None);
+
+    let yaml = r#"
+agents:
+  - id: support
+    topics: [support.requests]
+    strategy: react
+    system_prompt: You answer product questions.
+    tools: ["kb:*", "math:eval"]
+    max_iterations: 4
+  - id: triage
+    topics: [tickets.new]
+"#;
+    assert_eq!(
+        AgentsFile::parse(Path::new("agents.yaml"), yaml).unwrap(),
+        file
+    );
+    assert_eq!(
+        AgentsFile::parse(Path::new("agents.toml"), AGENTS_TOML).unwrap(),
+        file
+    );
+}
+
+#[test]
+fn invalid_agents_files_are_rejected() {
+    let duplicate = "[[agents]]\nid = \"a\"\n\n[[agents]]\nid = \"a\"\n";
+    assert!(AgentsFile::from_toml(duplicate).is_err());
+    assert!(AgentsFile::from_toml("[[agents]]\nid = \"\"\n").is_err());
+    assert!(AgentsFile::from_toml("[[agents]]\nid = \"a\"\nprompt = \"typo\"\n").is_err());
+    assert!(AgentsFile::from_toml("[[agents]]\nid = \"a\"\nstrategy = \"dream\"\n").is_err());
+}
+
+#[tokio::test]
+async fn apply_creates_updates_and_removes_agents() -> Result<()> {
+    let runtime = make_runtime().await?;
+    let (factory, built) = counting_factory();
+    let loader = AgentConfigLoader::new(runtime.clone(), factory);
+
+    let report = loader.apply(&AgentsFile::from_toml(AGENTS_TOML)?).await?;
+    assert_eq!(report.created, ids(&["support", "triage"]));
+    assert_eq!(runtime.agent_count(), 2);
+    assert!(runtime
+        .get_agent_subscriptions("support")?
+        .contains(&"support.requests".to_string()));
+
+    // Applying the same file again changes nothing
+    assert!(loader
+        .apply(&AgentsFile::from_toml(AGENTS_TOML)?)
+        .await?
+        .is_empty());
+    assert_eq!(built.load(Ordering::SeqCst), 2);
+
+    // An agent created outside the loader is never touched
+    runtime
+        .create_agent(
+            AgentSpec::new("manual").agent_config(),
+            Box::new(NoopBehavior),
+        )
+        .await?;
+
+    let mut file = AgentsFile::from_toml(AGENTS_TOML)?;
+    file.agents.retain(|spec| spec.id == "support");
+    file.agents[0].topics = vec!["support.priority".to_string()];
+    let report = loader.apply(&file).await?;
+    assert_eq!(
+        report,
+        ReloadReport {
+            updated: ids(&["support"]),
+            removed: ids(&["triage"]),
+            ..ReloadReport::default()
+        }
+    );
+    assert_eq!(runtime.agent_count(), 2);
+    let topics = runtime.get_agent_subscriptions("support")?;
+    assert!(topics.contains(&"support.priority".to_string()));
+    assert!(!topics.contains(&"support.requests".to_string()));
+    assert!(runtime.get_agent_subscriptions("triage").is_err());
+    assert!(runtime.get_agent_subscriptions("manual").is_ok());
+    assert_eq!(loader.managed_agents().await, ids(&["support"]));
+    Ok(())
+}
+
+#[tokio::test]
+async fn failed_agents_are_reported_and_retried() -> Result<()> {
+    let runtime = make_runtime().await?;
+    let (factory, _) = counting_factory();
+    let loader = AgentConfigLoader::new(runtime.clone(), factory);
+
+    let mut spec = AgentSpec::new("cron");
+    spec.parameters
+        .insert("schedule.cron".to_string(), "not a cron".to_string());
+    let report = loader.apply(&AgentsFile { agents: vec![spec] }).await?;
+    assert_eq!(report.failed.len(), 1);
+    assert!(loader.managed_agents().await.is_empty());
+
+    let fixed = AgentSpec::new("cron");
+    let report = loader
+        .apply(&AgentsFile {
+            agents: vec![fixed],
+        })
+        .await?;
+    assert_eq!(report.created, ids(&["cron"]));
+    Ok(())
+}
+
+/// Secrets that can be rotated while the loader runs
+#[derive(Default)]
+struct RotatingSecrets(std::sync::RwLock<std::collections::HashMap<String, String>>);
+
+impl SecretProvider for RotatingSecrets {
+    fn secret(&self, name: &str) -> Option<String> {
+        self.0.read().unwrap().get(name).cloned()
+    }
+}
+
+/// Records the `slack.token` parameter each behavior is initialized with
+struct TokenBehavior(Arc<std::sync::Mutex<Vec<String>>>);
+
+#[async_trait]
+impl AgentBehavior for TokenBehavior {
+    async fn on_event(&mut self, _event: Event, _state: &mut AgentState) -> Result<Vec<Action>> {
+        Ok(vec![])
+    }
+
+    async fn on_init(&mut self, config: &AgentConfig) -> Result<()> {
+        let token = config.parameters.get("slack.token").cloned();
+        self.0.lock().unwrap().extend(token);
+        Ok(())
+    }
+
+    async fn on_shutdown(&mut self) -> Result<()> {
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn templated_parameters_are_resolved_on_every_apply() -> Result<()> {
+    let runtime = make_runtime().await?;
+    let seen = Arc::new(std::sync::Mutex::new(Vec::new()));
+    let tokens = Arc::clone(&seen);
+    let factory: BehaviorFactory = Arc::new(move |_spec: &AgentSpec| {
+        Ok(Box::new(TokenBehavior(Arc::clone(&tokens))) as Box<dyn AgentBehavior>)
+    });
+    let secrets = Arc::new(RotatingSecrets::default());
+    secrets
+        .0
+        .write()
+        .unwrap()
+        .insert("SLACK_TOKEN".into(), "xoxb-1".into());
+    let loader = AgentConfigLoader::new(runtime.clone(), factory)
+        .with_parameter_resolver(ParameterResolver::new(secrets.clone()));
+    let wait_for = |n: usize| {
+        let seen = Arc::clone(&seen);
+        async move {
+            for _ in 0..100 {
+                if seen.lock().unwrap().len() >= n {
+                    break;
+                }
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+            seen.lock().unwrap().clone()
+        }
+    };
+
+    let file = AgentsFile::from_toml(
+        r#"
+[[agents]]
+id = "notifier"
+
+[agents.parameters]
+"slack.token" = "Bearer ${secrets.SLACK_TOKEN}"
+
+[[agents]]
+id = "broken"
+
+[agents.parameters]
+"slack.token" = "${secrets.MISSING_TOKEN}"
+"#,
+    )?;
+    let report = loader.apply(&file).await?;
+    assert_eq!(report.created, ids(&["notifier"]));
+    assert_eq!(report.failed.len(), 1);
+    assert_eq!(report.failed[0].0, "broken");
+    assert!(report.failed[0].1.contains("MISSING_TOKEN"));
+    assert_eq!(wait_for(1).await, ["Bearer xoxb-1"]);
+
+    // Unchanged secrets leave the agent running; a rotated one recreates it
+    assert_eq!(loader.refresh().await?.created, Vec::<String>::new());
+    secrets
+        .0
+        .write()
+        .unwrap()
+        .insert("SLACK_TOKEN".into(), "xoxb-2".into());
+    let report = loader.refresh().await?;
+    assert_eq!(report.updated, ids(&["notifier"]));
+    assert_eq!(wait_for(2).await, ["Bearer xoxb-1", "Bearer xoxb-2"]);
+    Ok(())
+}
+
+#[tokio::test]
+async fn watch_reloads_on_change_and_keeps_agents_on_bad_files() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    let path = dir.path().join("agents.toml");
+    std::fs::write(&path, AGENTS_TOML)?;
+
+    let runtime = make_runtime().await?;
+    let (factory, _) = counting_factory();
+    let loader = Arc::new(
+        AgentConfigLoader::new(runtime.clone(), factory)
+            .with_poll_interval(Duration::from_millis(20)),
+    );
+    let watcher = Arc::clone(&loader).watch(&path);
+
+    let wait_for = |expected: Vec<String>| {
+        let loader = Arc::clone(&loader);
+        async move {
+            for _ in 0..100 {
+                if loader.managed_agents().await == expected {
+                    return true;
+                }
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+            false
+        }
+    };
+    assert!(wait_for(ids(&["support", "triage"])).await);
+
+    // A broken edit leaves the running agents alone
+    std::fs::write(&path, "[[agents]\nid = ")?;
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    assert_eq!(runtime.agent_count(), 2);
+
+    std::fs::write(
+        &path,
+        "[[agents]]\nid = \"triage\"\ntopics = [\"tickets.new\"]\n",
+    )?;
+    assert!(wait_for(ids(&["triage"])).await);
+    assert_eq!(runtime.agent_count(), 1);
+
+    watcher.abort();
+    Ok(())
+}
+
+struct NamedTool(&'static str);
+
+#[async_trait]
+impl Tool for NamedTool {
+    fn name(&self) -> String {
+        self.0.to_string()
+    }
+
+    fn description(&self) -> String {
+        "test tool".to_string()
+    }
+
+    fn parameters(&self) -> Value {
+        json!({ "type": "object" })
+    }
+
+    async fn call(&self, _arguments: Value) -> ToolResult<Value> {
+        Ok(json!(self.0))
+    }
+}
+
+#[tokio::test]
+async fn scoped_registries_only_expose_allowed_tools() {
+    let tools = ToolRegistry::new();
+    for name in ["kb:search", "kb:fetch", "math:eval", "fs:write"] {
+        tools.register(Arc::new(NamedTool(name))).await;
+    }
+
+    let scoped = tools.scoped(["kb:*", "math:eval"]);
+    let mut names: Vec<String> = scoped.list_tools().into_iter().map(|t| t.name()).collect();
+    names.sort();
+    assert_eq!(names, ids(&["kb:fetch", "kb:search", "math:eval"]));
+    assert!(scoped.get("fs:write").is_none());
+    assert!(scoped.call("fs:write", json!({})).await.is_err());
+    assert_eq!(
+        scoped.call("kb:search", json!({})).await.unwrap(),
+        json!("kb:search")
+    );
+
+    // Narrowing a scoped registry never widens it
+    let narrower = scoped.scoped(["kb:search", "fs:write"]);
+    assert!(narrower.allows("kb:search"));
+    assert!(!narrower.allows("fs:write"));
+    assert!(!narrower.allows("kb:fetch"));
+
+    // An empty allowlist exposes nothing
+    assert!(tools.scoped(Vec::<String>::new()).list_tools().is_empty());
+}
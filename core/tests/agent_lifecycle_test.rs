@@ -0,0 +1,241 @@
+/// Tests for hibernation of idle agents and their reactivation on new events
+use async_trait::async_trait;
+use loom_core::agent::{AgentBehavior, AgentFactory, AgentRuntime, HibernationPolicy};
+use loom_core::proto::{Action, AgentConfig, AgentState};
+use loom_core::{Event, EventBus, ModelRouter, Result, ToolRegistry};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::mpsc;
+
+/// Counts handled events in `persistent_state` and reports the count
+struct CountingBehavior {
+    handled: mpsc::UnboundedSender<u8>,
+    shutdowns: Arc<AtomicUsize>,
+}
+
+#[async_trait]
+impl AgentBehavior for CountingBehavior {
+    async fn on_event(&mut self, _event: Event, state: &mut AgentState) -> Result<Vec<Action>> {
+        if state.persistent_state.is_empty() {
+            state.persistent_state.push(0);
+        }
+        state.persistent_state[0] += 1;
+        let _ = self.handled.send(state.persistent_state[0]);
+        Ok(vec![])
+    }
+
+    async fn on_init(&mut self, _config: &AgentConfig) -> Result<()> {
+        Ok(())
+    }
+
+    async fn on_shutdown(&mut self) -> Result<()> {
+        self.shutdowns.fetch_add(1, Ordering::SeqCst);
+        Ok(())
+    }
+}
+
+struct Harness {
+    runtime: AgentRuntime,
+    bus: Arc<EventBus>,
+    handled: mpsc::UnboundedReceiver<u8>,
+    builds: Arc<AtomicUsize>,
+    shutdowns: Arc<AtomicUsize>,
+}
+
+impl Harness {
+    async fn new() -> Result<Self> {
+        let bus = Arc::new(EventBus::new().await?);
+        bus.start().await?;
+        let runtime = AgentRuntime::new(
+            Arc::clone(&bus),
+            Arc::new(ToolRegistry::new()),
+            ModelRouter::new().await?,
+        )
+        .await?;
+        let (tx, handled) = mpsc::unbounded_channel();
+        let builds = Arc::new(AtomicUsize::new(0));
+        let shutdowns = Arc::new(AtomicUsize::new(0));
+        let harness = Self {
+            runtime,
+            bus,
+            handled,
+            builds: Arc::clone(&builds),
+            shutdowns: Arc::clone(&shutdowns),
+        };
+        let factory: AgentFactory = Arc::new(move |_config: &AgentConfig| {
+            builds.fetch_add(1, Ordering::SeqCst);
+            Ok(Box::new(CountingBehavior {
+                handled: tx.clone(),
+                shutdowns: Arc::clone(&shutdowns),
+            }) as Box<dyn AgentBehavior>)
+        });
+        harness
+            .runtime
+            .create_agent_with_factory(config("sleeper"), factory)
+            .await?;
+        Ok(harness)
+    }
+
+    async fn publish(&self) -> Result<()> {
+        self.bus.publish("jobs", event()).await?;
+        Ok(())
+    }
+
+    async fn next_count(&mut self) -> u8 {
+        tokio::time::timeout(Duration::from_secs(2), self.handled.recv())
+            .await
+            .expect("event handled in time")
+            .unwrap()
+    }
+
+    async fn wait_hibernated(&self, hibernated: bool) {
+        let deadline = tokio::time::Instant::now() + Duration::from_secs(2);
+        while self.runtime.is_agent_hibernated("sleeper").unwrap() != hibernated {
+            assert!(
+                tokio::time::Instant::now() < deadline,
+                "agent never reached hibernated = {hibernated}"
+            );
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    }
+}
+
+fn config(agent_id: &str) -> AgentConfig {
+    AgentConfig {
+        agent_id: agent_id.to_string(),
+        agent_type: "test".to_string(),
+        subscribed_topics: vec!["jobs".to_string()],
+        capabilities: vec![],
+        parameters: HashMap::new(),
+    }
+}
+
+fn event() -> Event {
+    Event {
+        id: "job".into(),
+        r#type: "job".into(),
+        timestamp_ms: 0,
+        source: "test".into(),
+        metadata: HashMap::new(),
+        payload: vec![],
+        confidence: 1.0,
+        tags: vec![],
+        priority: 50,
+    }
+}
+
+#[test]
+fn hibernation_policy_sweep_interval() {
+    let policy = HibernationPolicy::new(Duration::from_secs(60));
+    assert_eq!(policy.sweep_interval, Duration::from_secs(15));
+    let policy = HibernationPolicy::new(Duration::from_millis(20));
+    assert_eq!(policy.sweep_interval, Duration::from_millis(50));
+    let policy = HibernationPolicy::new(Duration::from_secs(3600));
+    assert_eq!(policy.sweep_interval, Duration::from_secs(30));
+    let policy = policy.with_sweep_interval(Duration::from_secs(1));
+    assert_eq!(policy.sweep_interval, Duration::from_secs(1));
+}
+
+#[tokio::test]
+async fn idle_agent_hibernates_and_wakes_with_its_state() -> Result<()> {
+    let mut harness = Harness::new().await?;
+    harness
+        .runtime
+        .set_hibernation_policy(Some(HibernationPolicy::new(Duration::from_millis(150))));
+    assert!(harness.runtime.hibernation_policy().is_some());
+
+    harness.publish().await?;
+    assert_eq!(harness.next_count().await, 1);
+    harness.wait_hibernated(true).await;
+    assert_eq!(harness.shutdowns.load(Ordering::SeqCst), 1);
+    // Still subscribed while hibernated
+    assert!(harness
+        .runtime
+        .get_agent_subscriptions("sleeper")?
+        .contains(&"jobs".to_string()));
+
+    // The next event rebuilds the behavior with the checkpointed state
+    harness.publish().await?;
+    assert_eq!(harness.next_count().await, 2);
+    assert!(!harness.runtime.is_agent_hibernated("sleeper")?);
+    assert_eq!(harness.builds.load(Ordering::SeqCst), 2);
+
+    // Events arriving together are all handled by one activation
+    harness.publish().await?;
+    harness.publish().await?;
+    assert_eq!(harness.next_count().await, 3);
+    assert_eq!(harness.next_count().await, 4);
+    harness.wait_hibernated(true).await;
+    assert_eq!(harness.builds.load(Ordering::SeqCst), 2);
+
+    harness.runtime.set_hibernation_policy(None);
+    assert!(harness.runtime.hibernation_policy().is_none());
+    harness.publish().await?;
+    assert_eq!(harness.next_count().await, 5);
+    tokio::time::sleep(Duration::from_millis(400)).await;
+    assert!(!harness.runtime.is_agent_hibernated("sleeper")?);
+    Ok(())
+}
+
+#[tokio::test]
+async fn agents_hibernate_on_request_and_delete_cleanly() -> Result<()> {
+    let mut harness = Harness::new().await?;
+    harness.runtime.hibernate_agent("sleeper")?;
+    harness.wait_hibernated(true).await;
+    harness.publish().await?;
+    assert_eq!(harness.next_count().await, 1);
+
+    // Agents created without a factory cannot hibernate
+    let (tx, _rx) = mpsc::unbounded_channel();
+    let behavior = CountingBehavior {
+        handled: tx,
+        shutdowns: Arc::new(AtomicUsize::new(0)),
+    };
+    harness
+        .runtime
+        .create_agent(config("awake"), Box::new(behavior))
+        .await?;
+    assert!(harness.runtime.hibernate_agent("awake").is_err());
+    assert!(!harness.runtime.is_agent_hibernated("awake")?);
+    assert!(harness.runtime.hibernate_agent("missing").is_err());
+
+    // A hibernated agent is deleted like any other
+    harness.runtime.hibernate_agent("sleeper")?;
+    harness.wait_hibernated(true).await;
+    harness.runtime.delete_agent("sleeper").await?;
+    harness.runtime.delete_agent("awake").await?;
+    assert_eq!(harness.runtime.agent_count(), 0);
+    Ok(())
+}
+
+#[tokio::test]
+async fn agents_wake_and_restart_on_request_keeping_their_state() -> Result<()> {
+    let mut harness = Harness::new().await?;
+    harness.publish().await?;
+    assert_eq!(harness.next_count().await, 1);
+    assert!(!harness.runtime.wake_agent("sleeper")?);
+
+    harness.runtime.hibernate_agent("sleeper")?;
+    harness.wait_hibernated(true).await;
+    assert!(harness.runtime.wake_agent("sleeper")?);
+    harness.wait_hibernated(false).await;
+    assert_eq!(harness.builds.load(Ordering::SeqCst), 2);
+
+    // A restart swaps in a fresh behavior on the same state
+    harness.runtime.restart_agent("sleeper")?;
+    let deadline = tokio::time::Instant::now() + Duration::from_secs(2);
+    while harness.builds.load(Ordering::SeqCst) < 3 {
+        assert!(
+            tokio::time::Instant::now() < deadline,
+            "agent never restarted"
+        );
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    harness.wait_hibernated(false).await;
+    assert_eq!(harness.shutdowns.load(Ordering::SeqCst), 2);
+    harness.publish().await?;
+    assert_eq!(harness.next_count().await, 2);
+    Ok(())
+}
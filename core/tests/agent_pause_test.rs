@@ -0,0 +1,214 @@
+/// Tests for pausing, resuming and draining agents
+use async_trait::async_trait;
+use loom_core::agent::{AgentBehavior, AgentRuntime, LIFECYCLE_TOPIC};
+use loom_core::proto::{Action, AgentConfig, AgentState, QoSLevel};
+use loom_core::{
+    AgentDirectory, AgentInfo, AgentStatus, Event, EventBus, ModelRouter, Result, ToolRegistry,
+};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::mpsc;
+
+/// Reports the id of every handled event
+struct RecordingBehavior {
+    handled: mpsc::UnboundedSender<String>,
+    shutdowns: Arc<AtomicUsize>,
+}
+
+#[async_trait]
+impl AgentBehavior for RecordingBehavior {
+    async fn on_event(&mut self, event: Event, _state: &mut AgentState) -> Result<Vec<Action>> {
+        let _ = self.handled.send(event.id);
+        Ok(vec![])
+    }
+
+    async fn on_init(&mut self, _config: &AgentConfig) -> Result<()> {
+        Ok(())
+    }
+
+    async fn on_shutdown(&mut self) -> Result<()> {
+        self.shutdowns.fetch_add(1, Ordering::SeqCst);
+        Ok(())
+    }
+}
+
+struct Harness {
+    runtime: AgentRuntime,
+    bus: Arc<EventBus>,
+    directory: Arc<AgentDirectory>,
+    handled: mpsc::UnboundedReceiver<String>,
+    lifecycle: mpsc::Receiver<Event>,
+    shutdowns: Arc<AtomicUsize>,
+}
+
+impl Harness {
+    async fn new() -> Result<Self> {
+        let bus = Arc::new(EventBus::new().await?);
+        bus.start().await?;
+        let directory = Arc::new(AgentDirectory::new());
+        directory.register_agent(AgentInfo {
+            agent_id: "worker".to_string(),
+            subscribed_topics: vec!["jobs".to_string()],
+            ..Default::default()
+        });
+        let runtime = AgentRuntime::new(
+            Arc::clone(&bus),
+            Arc::new(ToolRegistry::new()),
+            ModelRouter::new().await?,
+        )
+        .await?
+        .with_directory(Arc::clone(&directory));
+        let (_sub, lifecycle) = bus
+            .subscribe(LIFECYCLE_TOPIC.to_string(), vec![], QoSLevel::QosBatched)
+            .await?;
+
+        let (tx, handled) = mpsc::unbounded_channel();
+        let shutdowns = Arc::new(AtomicUsize::new(0));
+        runtime
+            .create_agent(
+                config("worker"),
+                Box::new(RecordingBehavior {
+                    handled: tx,
+                    shutdowns: Arc::clone(&shutdowns),
+                }),
+            )
+            .await?;
+        Ok(Self {
+            runtime,
+            bus,
+            directory,
+            handled,
+            lifecycle,
+            shutdowns,
+        })
+    }
+
+    async fn publish(&self, id: &str) -> Result<()> {
+        self.bus.publish("jobs", event(id)).await?;
+        Ok(())
+    }
+
+    async fn next_handled(&mut self) -> String {
+        tokio::time::timeout(Duration::from_secs(2), self.handled.recv())
+            .await
+            .expect("event handled in time")
+            .unwrap()
+    }
+
+    async fn assert_idle(&mut self) {
+        tokio::time::sleep(Duration::from_millis(150)).await;
+        assert!(self.handled.try_recv().is_err(), "unexpected event handled");
+    }
+
+    /// `state` metadata of the next lifecycle event
+    async fn next_transition(&mut self) -> String {
+        let event = tokio::time::timeout(Duration::from_secs(2), self.lifecycle.recv())
+            .await
+            .expect("lifecycle event in time")
+            .unwrap();
+        assert_eq!(event.r#type, LIFECYCLE_TOPIC);
+        assert_eq!(
+            event.metadata.get("agent_id").map(String::as_str),
+            Some("worker")
+        );
+        event.metadata["state"].clone()
+    }
+
+    fn status(&self) -> AgentStatus {
+        self.directory.get("worker").unwrap().status
+    }
+}
+
+fn config(agent_id: &str) -> AgentConfig {
+    AgentConfig {
+        agent_id: agent_id.to_string(),
+        agent_type: "test".to_string(),
+        subscribed_topics: vec!["jobs".to_string()],
+        capabilities: vec![],
+        parameters: HashMap::new(),
+    }
+}
+
+fn event(id: &str) -> Event {
+    Event {
+        id: id.into(),
+        r#type: "job".into(),
+        timestamp_ms: 0,
+        source: "test".into(),
+        metadata: HashMap::new(),
+        payload: vec![],
+        confidence: 1.0,
+        tags: vec![],
+        priority: 50,
+    }
+}
+
+#[tokio::test]
+async fn test_paused_agent_queues_events_until_resumed() -> Result<()> {
+    let mut h = Harness::new().await?;
+    h.publish("before").await?;
+    assert_eq!(h.next_handled().await, "before");
+
+    assert!(h.runtime.pause_agent("worker").await?);
+    assert!(!h.runtime.pause_agent("worker").await?);
+    assert!(h.runtime.is_agent_paused("worker")?);
+    assert_eq!(h.next_transition().await, "paused");
+    assert_eq!(h.status(), AgentStatus::Paused);
+
+    // Subscriptions stay in place while paused
+    assert!(h
+        .runtime
+        .get_agent_subscriptions("worker")?
+        .contains(&"jobs".to_string()));
+    h.publish("queued-1").await?;
+    h.publish("queued-2").await?;
+    h.assert_idle().await;
+
+    assert!(h.runtime.resume_agent("worker").await?);
+    assert!(!h.runtime.resume_agent("worker").await?);
+    assert_eq!(h.next_transition().await, "running");
+    assert_eq!(h.status(), AgentStatus::Active);
+    assert_eq!(h.next_handled().await, "queued-1");
+    assert_eq!(h.next_handled().await, "queued-2");
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_drain_handles_queued_events_then_stops() -> Result<()> {
+    let mut h = Harness::new().await?;
+    h.runtime.pause_agent("worker").await?;
+    assert_eq!(h.next_transition().await, "paused");
+    for id in ["a", "b", "c"] {
+        h.publish(id).await?;
+    }
+    h.assert_idle().await;
+
+    // Draining releases the pause, so the queue is worked off before shutdown
+    assert!(h.runtime.drain_agent("worker").await?);
+    assert_eq!(h.next_transition().await, "draining");
+    assert_eq!(h.next_transition().await, "drained");
+    for id in ["a", "b", "c"] {
+        assert_eq!(h.next_handled().await, id);
+    }
+    assert_eq!(h.shutdowns.load(Ordering::SeqCst), 1);
+    assert_eq!(h.runtime.agent_count(), 0);
+    assert_eq!(h.status(), AgentStatus::Disconnected);
+
+    // Nothing reaches the drained agent afterwards
+    h.publish("late").await?;
+    h.assert_idle().await;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_pause_resume_drain_reject_unknown_agents() -> Result<()> {
+    let h = Harness::new().await?;
+    assert!(h.runtime.pause_agent("ghost").await.is_err());
+    assert!(h.runtime.resume_agent("ghost").await.is_err());
+    assert!(h.runtime.is_agent_paused("ghost").is_err());
+    assert!(h.runtime.drain_agent("ghost").await.is_err());
+    assert!(!h.runtime.is_agent_paused("worker")?);
+    Ok(())
+}
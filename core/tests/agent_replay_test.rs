@@ -0,0 +1,322 @@
+//! Tests for trace recording and deterministic replay (TraceRecorder, ReplayRuntime)
+
+use async_trait::async_trait;
+use loom_core::agent::AgentBehavior;
+use loom_core::proto::{Action, AgentConfig, AgentState};
+use loom_core::replay::TraceEntry;
+use loom_core::tools::ToolResult;
+use loom_core::{
+    Event, EventExt, RecordingBehavior, ReplayRuntime, Result, Tool, ToolError, ToolRegistry,
+    Trace, TraceRecorder,
+};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+/// Answers differently on every call, so only a replay reproduces its results
+struct CounterTool {
+    calls: AtomicUsize,
+}
+
+#[async_trait]
+impl Tool for CounterTool {
+    fn name(&self) -> String {
+        "lookup".to_string()
+    }
+
+    fn description(&self) -> String {
+        "Looks up a query".to_string()
+    }
+
+    fn parameters(&self) -> Value {
+        json!({ "type": "object", "properties": { "q": { "type": "string" } } })
+    }
+
+    async fn call(&self, arguments: Value) -> ToolResult<Value> {
+        let n = self.calls.fetch_add(1, Ordering::SeqCst);
+        let q = arguments["q"].as_str().unwrap_or_default();
+        if q == "fail" {
+            return Err(ToolError::InvalidArguments(format!("cannot look up {q}")));
+        }
+        Ok(json!({ "answer": format!("{q}#{n}") }))
+    }
+}
+
+/// Looks up each event's payload and replies with the answer (optionally shouted)
+struct LookupBehavior {
+    tools: Arc<ToolRegistry>,
+    shout: bool,
+    initialized_as: Option<String>,
+}
+
+impl LookupBehavior {
+    fn new(tools: Arc<ToolRegistry>) -> Self {
+        Self {
+            tools,
+            shout: false,
+            initialized_as: None,
+        }
+    }
+}
+
+#[async_trait]
+impl AgentBehavior for LookupBehavior {
+    async fn on_event(&mut self, event: Event, _state: &mut AgentState) -> Result<Vec<Action>> {
+        let query = String::from_utf8_lossy(&event.payload).to_string();
+        let answer = match self.tools.call("lookup", json!({ "q": query })).await {
+            Ok(output) => output["answer"].as_str().unwrap_or_default().to_string(),
+            Err(e) => e.to_string(),
+        };
+        let answer = if self.shout {
+            answer.to_uppercase()
+        } else {
+            answer
+        };
+        Ok(vec![Action {
+            action_type: "reply".to_string(),
+            parameters: HashMap::new(),
+            payload: answer.into_bytes(),
+            priority: 50,
+        }])
+    }
+
+    async fn on_init(&mut self, config: &AgentConfig) -> Result<()> {
+        self.initialized_as = Some(config.agent_id.clone());
+        Ok(())
+    }
+
+    async fn on_shutdown(&mut self) -> Result<()> {
+        Ok(())
+    }
+}
+
+fn event(id: &str, payload: &[u8]) -> Event {
+    Event {
+        id: id.to_string(),
+        r#type: "query".to_string(),
+        timestamp_ms: 1_700_000_000_000,
+        source: "test".to_string(),
+        metadata: HashMap::new(),
+        payload: payload.to_vec(),
+        confidence: 1.0,
+        tags: vec![],
+        priority: 50,
+    }
+}
+
+fn config() -> AgentConfig {
+    AgentConfig {
+        agent_id: "searcher".to_string(),
+        agent_type: "lookup".to_string(),
+        parameters: HashMap::from([("mode".to_string(), "fast".to_string())]),
+        ..Default::default()
+    }
+}
+
+/// Record a run of LookupBehavior over `events`; returns the trace and the real tool
+async fn record(recorder: &Arc<TraceRecorder>, events: Vec<Event>) -> (Trace, Arc<CounterTool>) {
+    let tool = Arc::new(CounterTool {
+        calls: AtomicUsize::new(0),
+    });
+    let registry = ToolRegistry::new();
+    registry.register(Arc::clone(&tool) as Arc<dyn Tool>).await;
+    let tools = Arc::new(recorder.record_tools(&registry).await);
+
+    let mut behavior = RecordingBehavior::new(LookupBehavior::new(tools), Arc::clone(recorder));
+    behavior.on_init(&config()).await.unwrap();
+    let mut state = AgentState::default();
+    for event in events {
+        behavior.on_event(event, &mut state).await.unwrap();
+    }
+    behavior.on_shutdown().await.unwrap();
+    (recorder.trace().unwrap(), tool)
+}
+
+#[tokio::test]
+async fn test_records_events_actions_and_tool_results() {
+    let recorder = TraceRecorder::in_memory();
+    let (trace, _) = record(
+        &recorder,
+        vec![
+            event("e1", b"rust").with_correlation("corr-1".to_string()),
+            event("e2", b"loom"),
+        ],
+    )
+    .await;
+
+    assert_eq!(trace.agent_id(), Some("searcher"));
+    assert_eq!(trace.event_count(), 2);
+    // Tool results are tagged with the event's correlation id, or its id without one
+    let TraceEntry::ToolResult { output, .. } = trace.tool_results("corr-1")[0] else {
+        panic!("tool result expected");
+    };
+    assert_eq!(output.as_ref().unwrap()["answer"], "rust#0");
+    assert_eq!(trace.tool_results("e2").len(), 1);
+
+    let kinds: Vec<&str> = trace
+        .entries
+        .iter()
+        .map(|entry| match entry {
+            TraceEntry::Tools { .. } => "tools",
+            TraceEntry::Init { .. } => "init",
+            TraceEntry::Event { .. } => "event",
+            TraceEntry::ToolResult { .. } => "tool_result",
+            TraceEntry::Actions { .. } => "actions",
+        })
+        .collect();
+    assert_eq!(
+        kinds,
+        vec![
+            "tools",
+            "init",
+            "event",
+            "tool_result",
+            "actions",
+            "event",
+            "tool_result",
+            "actions"
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_replay_reproduces_recorded_run() {
+    let recorder = TraceRecorder::in_memory();
+    let (trace, tool) = record(
+        &recorder,
+        vec![
+            event("e1", b"rust"),
+            event("e2", b"fail"),
+            event("e3", b"rust"),
+        ],
+    )
+    .await;
+    assert_eq!(tool.calls.load(Ordering::SeqCst), 3);
+
+    let replay = ReplayRuntime::new(trace).await;
+    let tools = replay.tools();
+    // Recorded tool descriptions are offered to the replayed behavior
+    assert_eq!(
+        tools.get("lookup").unwrap().description(),
+        "Looks up a query"
+    );
+    let mut behavior = LookupBehavior::new(tools);
+    let report = replay.run(&mut behavior).await.unwrap();
+
+    assert!(report.is_faithful(), "{:?}", report);
+    assert_eq!(report.agent_id, "searcher");
+    assert_eq!(behavior.initialized_as.as_deref(), Some("searcher"));
+    assert_eq!(report.tool_calls_served, 3);
+    // Same answers as recorded, though a live call would now return rust#3
+    assert_eq!(report.events[2].actions[0].payload, "rust#2");
+    // Recorded errors come back as the same ToolError
+    assert_eq!(
+        report.events[1].actions[0].payload,
+        "Invalid arguments: cannot look up fail"
+    );
+    // The real tool was not called again
+    assert_eq!(tool.calls.load(Ordering::SeqCst), 3);
+
+    // Runs are repeatable
+    let again = replay
+        .run(&mut LookupBehavior::new(replay.tools()))
+        .await
+        .unwrap();
+    assert!(again.is_faithful());
+}
+
+/// Queries the payload twice, the second time with a suffix
+struct CuriousBehavior {
+    tools: Arc<ToolRegistry>,
+}
+
+#[async_trait]
+impl AgentBehavior for CuriousBehavior {
+    async fn on_event(&mut self, event: Event, _state: &mut AgentState) -> Result<Vec<Action>> {
+        let query = String::from_utf8_lossy(&event.payload).to_string();
+        for q in [query.clone(), format!("{query}?")] {
+            let _ = self.tools.call("lookup", json!({ "q": q })).await;
+        }
+        Ok(vec![])
+    }
+
+    async fn on_init(&mut self, _config: &AgentConfig) -> Result<()> {
+        Ok(())
+    }
+
+    async fn on_shutdown(&mut self) -> Result<()> {
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn test_replay_reports_divergence() {
+    let recorder = TraceRecorder::in_memory();
+    let (trace, _) = record(&recorder, vec![event("e1", b"rust"), event("e2", b"loom")]).await;
+    let replay = ReplayRuntime::new(trace).await;
+
+    let mut shouting = LookupBehavior::new(replay.tools());
+    shouting.shout = true;
+    let report = replay.run(&mut shouting).await.unwrap();
+    assert!(!report.is_faithful());
+    let divergences = report.divergences();
+    assert_eq!(divergences.len(), 2);
+    assert_eq!(divergences[0].seq, 1);
+    assert_eq!(divergences[0].recorded_actions[0].payload, "rust#0");
+    assert_eq!(divergences[0].actions[0].payload, "RUST#0");
+
+    // Calls the recording never made are reported and fail
+    let report = replay
+        .run(&mut CuriousBehavior {
+            tools: replay.tools(),
+        })
+        .await
+        .unwrap();
+    assert_eq!(report.tool_calls_served, 2);
+    assert_eq!(report.missing_tool_calls.len(), 2);
+    assert_eq!(
+        report.missing_tool_calls[0].arguments,
+        json!({ "q": "rust?" })
+    );
+    assert_eq!(
+        report.missing_tool_calls[0].correlation_id.as_deref(),
+        Some("e1")
+    );
+}
+
+#[tokio::test]
+async fn test_trace_file_roundtrip() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("traces").join("searcher.jsonl");
+    let recorder = TraceRecorder::create(&path).unwrap();
+    let binary = [0xff, 0x00, 0x10];
+    let (trace, _) = record(&recorder, vec![event("e1", &binary), event("e2", b"fail")]).await;
+
+    let loaded = Trace::load(&path).unwrap();
+    assert_eq!(loaded, trace);
+    let Some(TraceEntry::Event { event: traced, .. }) = loaded
+        .entries
+        .iter()
+        .find(|entry| matches!(entry, TraceEntry::Event { .. }))
+    else {
+        panic!("event expected");
+    };
+    assert!(traced.payload_base64);
+    assert_eq!(traced.to_event().payload, binary);
+
+    // A saved trace loads back the same
+    let copy = dir.path().join("copy.jsonl");
+    loaded.save(&copy).unwrap();
+    let report = ReplayRuntime::load(&copy)
+        .await
+        .unwrap()
+        .run(&mut LookupBehavior::new(Arc::new(ToolRegistry::new())))
+        .await
+        .unwrap();
+    // Built without the replay registry, the behavior cannot find the tool
+    assert_eq!(report.divergences().len(), 2);
+
+    std::fs::write(&copy, "{\"kind\": \"event\"}\n").unwrap();
+    assert!(Trace::load(&copy).is_err());
+}
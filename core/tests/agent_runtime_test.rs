@@ -1,7 +1,7 @@
 use async_trait::async_trait;
 use loom_core::agent::{AgentBehavior, AgentRuntime};
 use loom_core::proto::{Action, AgentConfig, AgentState, Event};
-use loom_core::{EventBus, ModelRouter, Result, ToolRegistry};
+use loom_core::{Envelope, EventBus, ModelRouter, Result, ToolRegistry};
 use std::sync::Arc;
 use tokio::sync::Mutex;
 
@@ -388,3 +388,45 @@ async fn create_duplicate_agent_id_replaces_previous() -> Result<()> {
     assert_eq!(c2, 1, "second agent should receive");
     Ok(())
 }
+
+#[tokio::test]
+async fn events_past_their_deadline_are_dropped() -> Result<()> {
+    let bus = Arc::new(EventBus::new().await?);
+    bus.start().await?;
+
+    let registry = Arc::new(ToolRegistry::new());
+    let router = ModelRouter::new().await?;
+    let runtime = AgentRuntime::new(Arc::clone(&bus), Arc::clone(&registry), router).await?;
+
+    let counter = Arc::new(Mutex::new(0));
+    let cfg = AgentConfig {
+        agent_id: "agent_deadline".to_string(),
+        agent_type: "test".to_string(),
+        subscribed_topics: vec!["topic.deadline".to_string()],
+        capabilities: vec![],
+        parameters: Default::default(),
+    };
+    runtime
+        .create_agent(
+            cfg,
+            Box::new(CountingBehavior {
+                counter: Arc::clone(&counter),
+            }),
+        )
+        .await?;
+
+    let mut stale = make_event("stale");
+    Envelope::new("t-stale", "agent.client")
+        .with_deadline_ms(chrono::Utc::now().timestamp_millis() - 1_000)
+        .attach_to_event(&mut stale);
+    let mut fresh = make_event("fresh");
+    Envelope::new("t-fresh", "agent.client")
+        .with_timeout(std::time::Duration::from_secs(30))
+        .attach_to_event(&mut fresh);
+    bus.publish("topic.deadline", stale).await?;
+    bus.publish("topic.deadline", fresh).await?;
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(200)).await;
+    assert_eq!(*counter.lock().await, 1, "only the fresh event is handled");
+    Ok(())
+}
@@ -0,0 +1,234 @@
+//! Tests for recurring agent self-triggers (`schedule.<name>.*` parameters)
+
+use async_trait::async_trait;
+use chrono::{DateTime, TimeZone, Utc};
+use chrono_tz::Tz;
+use loom_core::agent::schedule::{
+    parse_schedules, Cadence, CronSchedule, DEFAULT_SCHEDULE_EVENT_TYPE, SCHEDULE_EVENT_SOURCE,
+};
+use loom_core::agent::{AgentBehavior, AgentRuntime};
+use loom_core::proto::{Action, AgentConfig, AgentState, Event};
+use loom_core::{EventBus, LoomError, ModelRouter, Result, ToolRegistry};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Mutex;
+
+const BERLIN: Tz = chrono_tz::Europe::Berlin;
+
+fn next(expression: &str, after: DateTime<Tz>) -> Option<String> {
+    CronSchedule::parse(expression)
+        .unwrap()
+        .next_after(&after)
+        .map(|dt| dt.to_rfc3339())
+}
+
+fn params(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+    pairs
+        .iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect()
+}
+
+struct RecordingBehavior {
+    events: Arc<Mutex<Vec<Event>>>,
+}
+
+#[async_trait]
+impl AgentBehavior for RecordingBehavior {
+    async fn on_event(&mut self, event: Event, _state: &mut AgentState) -> Result<Vec<Action>> {
+        self.events.lock().await.push(event);
+        Ok(vec![])
+    }
+
+    async fn on_init(&mut self, _config: &AgentConfig) -> Result<()> {
+        Ok(())
+    }
+
+    async fn on_shutdown(&mut self) -> Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn cron_expressions_compute_next_fire_time() {
+    // Friday 2024-03-08 10:07:30 UTC
+    let at = Tz::UTC.with_ymd_and_hms(2024, 3, 8, 10, 7, 30).unwrap();
+    let cases = [
+        ("*/15 * * * *", "2024-03-08T10:15:00+00:00"),
+        ("0 9 * * mon-fri", "2024-03-11T09:00:00+00:00"),
+        ("30 */10 * * * *", "2024-03-08T10:10:30+00:00"),
+        ("0 0 1 */3 *", "2024-04-01T00:00:00+00:00"),
+        ("@hourly", "2024-03-08T11:00:00+00:00"),
+        ("@weekly", "2024-03-10T00:00:00+00:00"),
+        ("0 12 * jan,dec sun", "2024-12-01T12:00:00+00:00"),
+        // Both day fields restricted: either may match (the 13th or a Friday)
+        ("0 0 13 * fri", "2024-03-13T00:00:00+00:00"),
+        ("0 0 * * 7", "2024-03-10T00:00:00+00:00"),
+    ];
+    for (expression, expected) in cases {
+        assert_eq!(
+            next(expression, at).as_deref(),
+            Some(expected),
+            "{expression:?}"
+        );
+    }
+
+    // Never fires
+    assert_eq!(next("0 0 30 2 *", at), None);
+
+    for expression in [
+        "",
+        "* * * *",
+        "60 * * * *",
+        "* * 0 * *",
+        "*/0 * * * *",
+        "5-1 * * * *",
+        "* * * foo *",
+        "@sometimes",
+    ] {
+        assert!(
+            CronSchedule::parse(expression).is_err(),
+            "{expression:?} should be rejected"
+        );
+    }
+}
+
+#[test]
+fn cron_respects_dst_transitions() {
+    // 02:30 does not exist on 2024-03-31 in Berlin: skipped to the next day
+    let before_gap = BERLIN.with_ymd_and_hms(2024, 3, 31, 1, 0, 0).unwrap();
+    assert_eq!(
+        next("30 2 * * *", before_gap).as_deref(),
+        Some("2024-04-01T02:30:00+02:00")
+    );
+
+    // 02:30 happens twice on 2024-10-27: fires only at the first one
+    let before_overlap = BERLIN.with_ymd_and_hms(2024, 10, 27, 1, 0, 0).unwrap();
+    let first = next("30 2 * * *", before_overlap).unwrap();
+    assert_eq!(first, "2024-10-27T02:30:00+02:00");
+    let after_first = DateTime::parse_from_rfc3339(&first)
+        .unwrap()
+        .with_timezone(&BERLIN);
+    assert_eq!(
+        next("30 2 * * *", after_first).as_deref(),
+        Some("2024-10-28T02:30:00+01:00")
+    );
+}
+
+#[test]
+fn schedules_parse_from_agent_parameters() {
+    let schedules = parse_schedules(&params(&[
+        ("schedule.review.cron", "0 */15 * * * *"),
+        ("schedule.review.event_type", "memory.review"),
+        ("schedule.review.payload", r#"{"window":"1h"}"#),
+        ("schedule.review.timezone", "Asia/Tokyo"),
+        ("schedule.review.metadata.priority", "low"),
+        ("schedule.poll.cron", "@every 90s"),
+        ("routing.privacy", "private"),
+    ]))
+    .unwrap();
+
+    assert_eq!(schedules.len(), 2);
+    let (poll, review) = (&schedules[0], &schedules[1]);
+    assert_eq!(poll.name, "poll");
+    assert_eq!(poll.cadence, Cadence::Every(Duration::from_secs(90)));
+    assert_eq!(poll.event_type, DEFAULT_SCHEDULE_EVENT_TYPE);
+    assert_eq!(review.timezone, chrono_tz::Asia::Tokyo);
+    assert_eq!(review.metadata["priority"], "low");
+
+    let at = Utc.with_ymd_and_hms(2024, 3, 8, 10, 7, 30).unwrap();
+    assert_eq!(
+        review.next_after(at).unwrap(),
+        Utc.with_ymd_and_hms(2024, 3, 8, 10, 15, 0).unwrap()
+    );
+
+    let event = review.event("agent-1", at);
+    assert_eq!(event.r#type, "memory.review");
+    assert_eq!(event.source, SCHEDULE_EVENT_SOURCE);
+    assert_eq!(event.payload, br#"{"window":"1h"}"#);
+    assert_eq!(event.metadata["schedule"], "review");
+    assert_eq!(event.metadata["priority"], "low");
+    assert_eq!(
+        event.metadata["scheduled_at_ms"],
+        at.timestamp_millis().to_string()
+    );
+
+    for bad in [
+        params(&[("schedule.x.event_type", "tick")]),
+        params(&[("schedule.x.cron", "@daily"), ("schedule.x.colour", "red")]),
+        params(&[
+            ("schedule.x.cron", "@daily"),
+            ("schedule.x.timezone", "Mars/Base"),
+        ]),
+        params(&[("schedule.x.cron", "@every 0s")]),
+        params(&[("schedule.cron", "@daily")]),
+    ] {
+        assert!(parse_schedules(&bad).is_err(), "{bad:?} should be rejected");
+    }
+}
+
+#[tokio::test]
+async fn runtime_delivers_scheduled_events_until_deleted() -> Result<()> {
+    let bus = Arc::new(EventBus::new().await?);
+    bus.start().await?;
+    let runtime = AgentRuntime::new(
+        Arc::clone(&bus),
+        Arc::new(ToolRegistry::new()),
+        ModelRouter::new().await?,
+    )
+    .await?;
+
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let config = AgentConfig {
+        agent_id: "janitor".to_string(),
+        agent_type: "test".to_string(),
+        subscribed_topics: vec![],
+        capabilities: vec![],
+        parameters: params(&[
+            ("schedule.sweep.cron", "@every 100ms"),
+            ("schedule.sweep.event_type", "janitor.sweep"),
+        ]),
+    };
+    runtime
+        .create_agent(
+            config,
+            Box::new(RecordingBehavior {
+                events: Arc::clone(&events),
+            }),
+        )
+        .await?;
+    assert_eq!(runtime.get_agent_schedules("janitor")?, vec!["sweep"]);
+
+    tokio::time::sleep(Duration::from_millis(450)).await;
+    {
+        let events = events.lock().await;
+        assert!(events.len() >= 2, "got {} scheduled events", events.len());
+        assert!(events.iter().all(|e| e.r#type == "janitor.sweep"));
+        assert_eq!(events[0].metadata["agent_id"], "janitor");
+    }
+
+    runtime.delete_agent("janitor").await?;
+    assert!(runtime.get_agent_schedules("janitor").is_err());
+
+    // Invalid schedules reject the agent before anything is started
+    let config = AgentConfig {
+        agent_id: "broken".to_string(),
+        agent_type: "test".to_string(),
+        subscribed_topics: vec![],
+        capabilities: vec![],
+        parameters: params(&[("schedule.x.cron", "every now and then")]),
+    };
+    let err = runtime
+        .create_agent(
+            config,
+            Box::new(RecordingBehavior {
+                events: Arc::new(Mutex::new(Vec::new())),
+            }),
+        )
+        .await
+        .unwrap_err();
+    assert!(matches!(err, LoomError::AgentError(ref m) if m.contains("schedule")));
+    assert_eq!(runtime.agent_count(), 0);
+    Ok(())
+}
@@ -0,0 +1,223 @@
+//! Tests for per-subscription backpressure policies and their EventBusStats counters
+
+use async_trait::async_trait;
+use loom_core::agent::{AgentBehavior, AgentRuntime};
+use loom_core::proto::{Action, AgentConfig, AgentState, Event, QoSLevel};
+use loom_core::{BackpressurePolicy, EventBus, LoomError, ModelRouter, Result, ToolRegistry};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::mpsc;
+
+fn make_event(i: usize) -> Event {
+    Event {
+        id: i.to_string(),
+        r#type: "tick".to_string(),
+        timestamp_ms: 0,
+        source: "test".to_string(),
+        metadata: HashMap::new(),
+        payload: vec![0u8; 32],
+        confidence: 1.0,
+        tags: vec![],
+        priority: 0,
+    }
+}
+
+/// Event ids received until the subscription stays quiet for 200ms
+async fn drain(rx: &mut mpsc::Receiver<Event>) -> Vec<usize> {
+    let mut ids = Vec::new();
+    while let Ok(Some(event)) = tokio::time::timeout(Duration::from_millis(200), rx.recv()).await {
+        ids.push(event.id.parse().unwrap());
+    }
+    ids
+}
+
+struct NoopBehavior;
+
+#[async_trait]
+impl AgentBehavior for NoopBehavior {
+    async fn on_event(&mut self, _event: Event, _state: &mut AgentState) -> Result<Vec<Action>> {
+        Ok(vec![])
+    }
+
+    async fn on_init(&mut self, _config: &AgentConfig) -> Result<()> {
+        Ok(())
+    }
+
+    async fn on_shutdown(&mut self) -> Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn policies_parse_and_default_per_qos() {
+    assert_eq!(
+        BackpressurePolicy::parse("drop-oldest"),
+        Some(BackpressurePolicy::DropOldest)
+    );
+    assert_eq!(
+        BackpressurePolicy::parse(" Spill "),
+        Some(BackpressurePolicy::SpillToDisk)
+    );
+    assert_eq!(BackpressurePolicy::parse("shed"), None);
+    assert_eq!(
+        BackpressurePolicy::default_for(QoSLevel::QosRealtime),
+        BackpressurePolicy::DropNewest
+    );
+    assert_eq!(
+        BackpressurePolicy::default_for(QoSLevel::QosBackground),
+        BackpressurePolicy::Block
+    );
+    assert_eq!(BackpressurePolicy::SpillToDisk.to_string(), "spill_to_disk");
+}
+
+#[tokio::test]
+async fn drop_newest_keeps_the_queued_events() -> Result<()> {
+    let bus = EventBus::new().await?;
+    let (_id, mut rx) = bus
+        .subscribe("bp.newest".to_string(), vec![], QoSLevel::QosRealtime)
+        .await?;
+
+    for i in 0..600 {
+        bus.publish("bp.newest", make_event(i)).await?;
+    }
+    let ids = drain(&mut rx).await;
+    assert_eq!(ids, (0..512).collect::<Vec<_>>());
+
+    let stats = bus.get_stats("bp.newest").unwrap();
+    assert_eq!(stats.dropped_newest, 88);
+    assert_eq!(stats.dropped_events, 88);
+    assert_eq!(stats.dropped_oldest, 0);
+    Ok(())
+}
+
+#[tokio::test]
+async fn drop_oldest_keeps_the_newest_events() -> Result<()> {
+    let bus = EventBus::new().await?;
+    let (_id, mut rx) = bus
+        .subscribe_with_policy(
+            "bp.oldest".to_string(),
+            vec![],
+            QoSLevel::QosRealtime,
+            BackpressurePolicy::DropOldest,
+        )
+        .await?;
+
+    for i in 0..1000 {
+        bus.publish("bp.oldest", make_event(i)).await?;
+    }
+    let ids = drain(&mut rx).await;
+    assert!(ids.windows(2).all(|w| w[0] < w[1]));
+    assert_eq!(ids.last(), Some(&999));
+
+    let stats = bus.get_stats("bp.oldest").unwrap();
+    assert!(stats.dropped_oldest > 0);
+    assert_eq!(stats.dropped_oldest as usize + ids.len(), 1000);
+    assert_eq!(stats.dropped_events, stats.dropped_oldest);
+    assert_eq!(stats.dropped_newest, 0);
+    // Every publish was accepted by the subscription
+    assert_eq!(stats.total_delivered, 1000);
+    Ok(())
+}
+
+#[tokio::test]
+async fn spill_to_disk_delivers_everything_in_order() -> Result<()> {
+    let dir = tempfile::tempdir().unwrap();
+    let mut bus = EventBus::new().await?;
+    bus.set_spill_dir(dir.path());
+    let (id, mut rx) = bus
+        .subscribe_with_policy(
+            "bp.spill".to_string(),
+            vec![],
+            QoSLevel::QosRealtime,
+            BackpressurePolicy::SpillToDisk,
+        )
+        .await?;
+
+    for i in 0..700 {
+        bus.publish("bp.spill", make_event(i)).await?;
+    }
+    let stats = bus.get_stats("bp.spill").unwrap();
+    assert!(stats.spilled >= 700 - 514);
+    assert_eq!(stats.dropped_events, 0);
+    // One event may sit with the forwarder, between the queue and the channel
+    assert!(bus.queued_events(&id).unwrap() >= 699);
+    assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 1);
+
+    let ids = drain(&mut rx).await;
+    assert_eq!(ids, (0..700).collect::<Vec<_>>());
+    assert_eq!(bus.queued_events(&id), Some(0));
+
+    // The spill file goes away with the subscription
+    bus.unsubscribe(&id).await?;
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 0);
+    assert_eq!(bus.queued_events(&id), None);
+    Ok(())
+}
+
+#[tokio::test]
+async fn block_waits_for_capacity_and_counts_it() -> Result<()> {
+    let bus = Arc::new(EventBus::new().await?);
+    let (_id, mut rx) = bus
+        .subscribe_with_policy(
+            "bp.block".to_string(),
+            vec![],
+            QoSLevel::QosRealtime,
+            BackpressurePolicy::Block,
+        )
+        .await?;
+
+    let publisher = {
+        let bus = Arc::clone(&bus);
+        tokio::spawn(async move {
+            for i in 0..520 {
+                bus.publish("bp.block", make_event(i)).await.unwrap();
+            }
+        })
+    };
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    assert!(!publisher.is_finished());
+
+    let ids = drain(&mut rx).await;
+    publisher.await.unwrap();
+    assert_eq!(ids, (0..520).collect::<Vec<_>>());
+
+    let stats = bus.get_stats("bp.block").unwrap();
+    assert!(stats.blocked >= 1);
+    assert_eq!(stats.dropped_events, 0);
+    Ok(())
+}
+
+#[tokio::test]
+async fn agents_choose_their_backpressure_policy() -> Result<()> {
+    let bus = Arc::new(EventBus::new().await?);
+    let runtime = AgentRuntime::new(
+        Arc::clone(&bus),
+        Arc::new(ToolRegistry::new()),
+        ModelRouter::new().await?,
+    )
+    .await?;
+
+    let config = |id: &str, policy: &str| AgentConfig {
+        agent_id: id.to_string(),
+        agent_type: "test".to_string(),
+        subscribed_topics: vec!["bp.agent".to_string()],
+        capabilities: vec![],
+        parameters: HashMap::from([("event_bus.backpressure".to_string(), policy.to_string())]),
+    };
+
+    runtime
+        .create_agent(
+            config("keeps-newest", "drop_oldest"),
+            Box::new(NoopBehavior),
+        )
+        .await?;
+    let err = runtime
+        .create_agent(config("shedder", "shed"), Box::new(NoopBehavior))
+        .await
+        .unwrap_err();
+    assert!(matches!(err, LoomError::AgentError(ref m) if m.contains("event_bus.backpressure")));
+    assert_eq!(runtime.agent_count(), 1);
+    Ok(())
+}
@@ -0,0 +1,173 @@
+//! Tests for the blob store: references in events, access control, integrity and tools
+use loom_core::blob::{keys, BlobAccess, BlobRef, BlobStore, DiskBlobBackend};
+use loom_core::proto::Event;
+use loom_core::tools::native::blob_tools;
+use loom_core::tools::{with_caller, ToolError};
+use serde_json::json;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+fn store(dir: &tempfile::TempDir) -> BlobStore {
+    BlobStore::new(Arc::new(DiskBlobBackend::new(dir.path()))).with_inline_max_bytes(16)
+}
+
+fn make_event(payload: &[u8]) -> Event {
+    Event {
+        id: "e1".to_string(),
+        r#type: "image.captured".to_string(),
+        timestamp_ms: 0,
+        source: "camera".to_string(),
+        metadata: HashMap::new(),
+        payload: payload.to_vec(),
+        confidence: 1.0,
+        tags: vec![],
+        priority: 50,
+    }
+}
+
+#[tokio::test]
+async fn large_payloads_travel_by_reference() {
+    let dir = tempfile::tempdir().unwrap();
+    let store = store(&dir);
+    let image = vec![7u8; 1000];
+
+    let mut small = make_event(b"tiny");
+    assert!(store
+        .stash(&mut small, BlobAccess::owned_by("camera"))
+        .await
+        .unwrap()
+        .is_none());
+    assert_eq!(small.payload, b"tiny");
+
+    let mut event = make_event(&image);
+    event
+        .metadata
+        .insert(keys::CONTENT_TYPE.to_string(), "image/png".to_string());
+    let blob = store
+        .stash(&mut event, BlobAccess::owned_by("camera"))
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(event.payload.is_empty());
+    assert_eq!(BlobRef::from_event(&event), Some(blob.clone()));
+    assert_eq!(blob.size, 1000);
+    assert_eq!(blob.content_type, "image/png");
+
+    assert!(store
+        .resolve(&mut event, Some("vision"), None)
+        .await
+        .unwrap());
+    assert_eq!(event.payload, image);
+    assert!(!event.metadata.contains_key(keys::REF));
+    assert!(!store
+        .resolve(&mut event, Some("vision"), None)
+        .await
+        .unwrap());
+}
+
+#[tokio::test]
+async fn readers_and_namespaces_limit_access() {
+    let dir = tempfile::tempdir().unwrap();
+    let store = store(&dir);
+    let access = BlobAccess::owned_by("camera")
+        .with_namespace("acme")
+        .with_readers(["vision"]);
+    let blob = store.put(b"secret".to_vec(), "", access).await.unwrap();
+
+    assert!(store.get(&blob, Some("camera"), None).await.is_ok());
+    assert!(store.get(&blob, Some("vision"), Some("acme")).await.is_ok());
+    assert!(store
+        .get(&blob, Some("vision"), Some("other"))
+        .await
+        .is_err());
+    assert!(store.get(&blob, Some("audio"), Some("acme")).await.is_err());
+    assert!(store.get(&blob, None, Some("acme")).await.is_err());
+
+    let open = store
+        .put(b"public".to_vec(), "", BlobAccess::owned_by("camera"))
+        .await
+        .unwrap();
+    assert!(store.get(&open, None, None).await.is_ok());
+
+    // Readers can come from the publishing event
+    let mut event = make_event(b"");
+    event
+        .metadata
+        .insert(keys::READERS.to_string(), "vision, audio".to_string());
+    let access = BlobAccess::owned_by("camera").with_readers_of(&event);
+    assert_eq!(access.readers, ["vision", "audio"]);
+}
+
+#[tokio::test]
+async fn tampered_or_unknown_blobs_fail() {
+    let dir = tempfile::tempdir().unwrap();
+    let store = store(&dir);
+    let blob = store
+        .put(b"original".to_vec(), "", BlobAccess::default())
+        .await
+        .unwrap();
+    std::fs::write(dir.path().join(&blob.id), b"tampered").unwrap();
+    assert!(store.load(&blob).await.is_err());
+
+    let missing = BlobRef {
+        id: "0".repeat(32),
+        ..blob.clone()
+    };
+    assert!(store.load(&missing).await.is_err());
+    let traversal = BlobRef {
+        id: "../secrets".to_string(),
+        ..blob.clone()
+    };
+    assert!(store.load(&traversal).await.is_err());
+
+    assert!(store.delete(&blob.id).await.unwrap());
+    assert!(!store.delete(&blob.id).await.unwrap());
+
+    let limited = BlobStore::new(Arc::new(DiskBlobBackend::new(dir.path()))).with_max_bytes(4);
+    assert!(limited
+        .put(b"too large".to_vec(), "", BlobAccess::default())
+        .await
+        .is_err());
+}
+
+#[tokio::test]
+async fn tools_pass_blobs_between_agents() {
+    let dir = tempfile::tempdir().unwrap();
+    let tools = blob_tools(Arc::new(store(&dir)));
+    let tool = |name: &str| tools.iter().find(|t| t.name() == name).cloned().unwrap();
+    let (put, get, delete) = (tool("blob:put"), tool("blob:get"), tool("blob:delete"));
+
+    let stored = with_caller(
+        "writer",
+        put.call(json!({ "content": "/wA=", "encoding": "base64", "readers": ["reader"] })),
+    )
+    .await
+    .unwrap();
+    assert_eq!(stored["size"], 2);
+    let blob_ref = stored["blob_ref"].clone();
+
+    let read = with_caller("reader", get.call(json!({ "blob_ref": blob_ref })))
+        .await
+        .unwrap();
+    assert_eq!(read["encoding"], "base64");
+    assert_eq!(read["content"], "/wA=");
+
+    let denied = with_caller("stranger", get.call(json!({ "blob_ref": blob_ref }))).await;
+    assert!(matches!(denied, Err(ToolError::PermissionDenied(_))));
+    let denied = with_caller("reader", delete.call(json!({ "blob_ref": blob_ref }))).await;
+    assert!(matches!(denied, Err(ToolError::PermissionDenied(_))));
+
+    let deleted = with_caller("writer", delete.call(json!({ "blob_ref": blob_ref })))
+        .await
+        .unwrap();
+    assert_eq!(deleted["deleted"], true);
+
+    let text = with_caller("writer", put.call(json!({ "content": "hello" })))
+        .await
+        .unwrap();
+    let read = with_caller("other", get.call(json!({ "blob_ref": text["blob_ref"] })))
+        .await
+        .unwrap();
+    assert_eq!(read["encoding"], "text");
+    assert_eq!(read["content"], "hello");
+}
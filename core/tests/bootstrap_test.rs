@@ -0,0 +1,293 @@
+//! Tests for seeding an agent's system prompt and memory from a bootstrap directory
+
+use loom_core::agent::AgentRuntime;
+use loom_core::cognitive::llm::{LlmClient, LlmClientConfig};
+use loom_core::cognitive::{
+    relevant_seed_chunks, BootstrapSeed, CognitiveAgent, CognitiveConfig, SimpleCognitiveLoop,
+    BOOTSTRAP_DIR_PARAM, RESPONSE_EVENT,
+};
+use loom_core::context::{InMemoryStore, MemoryStore};
+use loom_core::proto::{AgentConfig, Event, QoSLevel};
+use loom_core::{AgentSpec, AgentsFile, Envelope, EventBus, ModelRouter, Result, ToolRegistry};
+use serde_json::json;
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+fn write(dir: &Path, relative: &str, text: &str) {
+    let path = dir.join(relative);
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    std::fs::write(path, text).unwrap();
+}
+
+/// A support assistant's seed directory
+fn seed_dir() -> tempfile::TempDir {
+    let dir = tempfile::tempdir().unwrap();
+    write(
+        dir.path(),
+        "persona.prompt.md",
+        "You are Ada, the Acme support assistant.",
+    );
+    write(
+        dir.path(),
+        "prompts/policies.md",
+        "Never promise refunds over $500.",
+    );
+    write(
+        dir.path(),
+        "faq.md",
+        "Shipping takes three business days.\n\nReturns are accepted within thirty days.",
+    );
+    write(
+        dir.path(),
+        "docs/warranty.txt",
+        "The warranty covers parts for two years.",
+    );
+    write(dir.path(), "docs/empty.md", "   \n");
+    write(dir.path(), ".draft.md", "Unreleased pricing");
+    write(dir.path(), "logo.png", "not text");
+    dir
+}
+
+#[test]
+fn load_splits_prompt_files_from_documents() -> Result<()> {
+    let dir = seed_dir();
+    let seed = BootstrapSeed::load(dir.path())?;
+
+    assert_eq!(
+        seed.prompts,
+        vec![
+            "You are Ada, the Acme support assistant.",
+            "Never promise refunds over $500."
+        ]
+    );
+    let paths: Vec<&str> = seed.documents.iter().map(|d| d.path.as_str()).collect();
+    assert_eq!(paths, vec!["docs/warranty.txt", "faq.md"]);
+    assert_eq!(
+        seed.system_prompt(Some("Be brief.")).as_deref(),
+        Some("Be brief.\n\nYou are Ada, the Acme support assistant.\n\nNever promise refunds over $500.")
+    );
+
+    assert!(BootstrapSeed::load(dir.path().join("missing")).is_err());
+    Ok(())
+}
+
+#[tokio::test]
+async fn seeding_is_idempotent_and_retrieval_matches_the_goal() -> Result<()> {
+    let dir = seed_dir();
+    let seed = BootstrapSeed::load(dir.path())?;
+    let store = InMemoryStore::new();
+
+    assert_eq!(seed.seed(store.as_ref(), "support").await?, 2);
+    // Already seeded with the same text
+    assert_eq!(seed.seed(store.as_ref(), "support").await?, 0);
+    assert_eq!(store.count().await?, 2);
+
+    let chunks =
+        relevant_seed_chunks(store.as_ref(), "support", "How long is the warranty?", 3).await?;
+    assert_eq!(
+        chunks,
+        vec!["[docs/warranty.txt] The warranty covers parts for two years."]
+    );
+    // Seed memory is per agent
+    assert!(
+        relevant_seed_chunks(store.as_ref(), "sales", "How long is the warranty?", 3)
+            .await?
+            .is_empty()
+    );
+
+    // A changed document rewrites its chunk
+    write(dir.path(), "faq.md", "Shipping takes five business days.");
+    let seed = BootstrapSeed::load(dir.path())?;
+    assert_eq!(seed.seed(store.as_ref(), "support").await?, 1);
+    let chunks = relevant_seed_chunks(store.as_ref(), "support", "shipping days", 3).await?;
+    assert_eq!(chunks, vec!["[faq.md] Shipping takes five business days."]);
+    Ok(())
+}
+
+#[test]
+fn agents_file_resolves_bootstrap_relative_to_the_file() -> Result<()> {
+    let file = AgentsFile::parse(
+        Path::new("/etc/loom/agents.toml"),
+        "[[agents]]\nid = \"support\"\nbootstrap = \"seeds/support\"\n",
+    )?;
+    let spec = &file.agents[0];
+    assert_eq!(
+        spec.bootstrap.as_deref(),
+        Some(Path::new("/etc/loom/seeds/support"))
+    );
+    assert_eq!(
+        spec.agent_config().parameters[BOOTSTRAP_DIR_PARAM],
+        "/etc/loom/seeds/support"
+    );
+
+    let spec = AgentSpec::new("support").with_bootstrap("/srv/seeds");
+    assert_eq!(
+        spec.agent_config().parameters[BOOTSTRAP_DIR_PARAM],
+        "/srv/seeds"
+    );
+    Ok(())
+}
+
+/// Read one request; returns its path and body
+async fn read_request(sock: &mut TcpStream) -> (String, String) {
+    let mut buf = Vec::new();
+    let mut chunk = [0u8; 4096];
+    loop {
+        let n = sock.read(&mut chunk).await.unwrap_or(0);
+        if n == 0 {
+            break;
+        }
+        buf.extend_from_slice(&chunk[..n]);
+        let text = String::from_utf8_lossy(&buf).into_owned();
+        if let Some(end) = text.find("\r\n\r\n") {
+            let length = text[..end]
+                .lines()
+                .find_map(|line| match line.split_once(':') {
+                    Some((name, value)) if name.eq_ignore_ascii_case("content-length") => {
+                        value.trim().parse::<usize>().ok()
+                    }
+                    _ => None,
+                })
+                .unwrap_or(0);
+            if buf.len() >= end + 4 + length {
+                let path = text.split_whitespace().nth(1).unwrap_or("").to_string();
+                return (path, text[end + 4..].to_string());
+            }
+        }
+    }
+    (String::new(), String::new())
+}
+
+/// Chat-completions backend recording the request bodies it answers
+async fn spawn_backend(bodies: Arc<Mutex<Vec<String>>>) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        while let Ok((mut sock, _)) = listener.accept().await {
+            let bodies = Arc::clone(&bodies);
+            tokio::spawn(async move {
+                let (path, body) = read_request(&mut sock).await;
+                if !path.ends_with("/chat/completions") {
+                    let _ = sock
+                        .write_all(b"HTTP/1.1 404 Not Found\r\ncontent-length: 0\r\nconnection: close\r\n\r\n")
+                        .await;
+                    return;
+                }
+                bodies.lock().unwrap().push(body);
+                let body = json!({
+                    "model": "test-model",
+                    "choices": [{"message": {"role": "assistant", "content": "Two years."}}],
+                })
+                .to_string();
+                let resp = format!(
+                    "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{}",
+                    body.len(),
+                    body
+                );
+                let _ = sock.write_all(resp.as_bytes()).await;
+            });
+        }
+    });
+    format!("http://{addr}/v1")
+}
+
+fn agent_config(parameters: HashMap<String, String>) -> AgentConfig {
+    AgentConfig {
+        agent_id: "support".to_string(),
+        agent_type: "cognitive".to_string(),
+        subscribed_topics: vec!["support.query".to_string()],
+        capabilities: vec![],
+        parameters,
+    }
+}
+
+#[tokio::test]
+async fn seeded_agent_prompts_with_its_persona_and_documents() -> Result<()> {
+    let dir = seed_dir();
+    let bus = Arc::new(EventBus::new().await?);
+    bus.start().await?;
+    let tools = Arc::new(ToolRegistry::new());
+    let runtime = AgentRuntime::new(
+        Arc::clone(&bus),
+        Arc::clone(&tools),
+        ModelRouter::new().await?,
+    )
+    .await?;
+    let bodies = Arc::new(Mutex::new(Vec::new()));
+    let llm = Arc::new(LlmClient::new(LlmClientConfig {
+        base_url: spawn_backend(Arc::clone(&bodies)).await,
+        model: "test-model".to_string(),
+        api_key: None,
+        request_timeout_ms: 5_000,
+        temperature: 0.0,
+    })?);
+
+    // A missing directory is rejected before the agent starts
+    let missing = HashMap::from([(
+        BOOTSTRAP_DIR_PARAM.to_string(),
+        dir.path().join("missing").to_string_lossy().into_owned(),
+    )]);
+    let behavior = CognitiveAgent::new(SimpleCognitiveLoop::new(
+        CognitiveConfig::single_shot(),
+        Arc::clone(&llm),
+        Arc::clone(&tools),
+    ));
+    assert!(runtime
+        .create_agent(agent_config(missing), Box::new(behavior))
+        .await
+        .is_err());
+
+    let parameters = HashMap::from([(
+        BOOTSTRAP_DIR_PARAM.to_string(),
+        dir.path().to_string_lossy().into_owned(),
+    )]);
+    let behavior = CognitiveAgent::new(SimpleCognitiveLoop::new(
+        CognitiveConfig::single_shot(),
+        llm,
+        tools,
+    ));
+    runtime
+        .create_agent(agent_config(parameters), Box::new(behavior))
+        .await?;
+
+    let envelope = Envelope::new("support-1", "test").with_reply_expected(true);
+    let (_sub, mut rx) = bus
+        .subscribe(
+            envelope.reply_to.clone(),
+            vec![RESPONSE_EVENT.to_string()],
+            QoSLevel::QosBatched,
+        )
+        .await?;
+    let mut query = Event {
+        id: "q1".to_string(),
+        r#type: "user.query".to_string(),
+        timestamp_ms: 0,
+        source: "test".to_string(),
+        metadata: HashMap::new(),
+        payload: b"How long is the warranty?".to_vec(),
+        confidence: 1.0,
+        tags: vec![],
+        priority: 60,
+    };
+    envelope.attach_to_event(&mut query);
+    bus.publish("support.query", query).await?;
+
+    let reply = tokio::time::timeout(Duration::from_secs(5), rx.recv())
+        .await
+        .expect("response in time")
+        .unwrap();
+    assert_eq!(String::from_utf8(reply.payload).unwrap(), "Two years.");
+
+    let bodies = bodies.lock().unwrap();
+    let request = bodies.last().expect("LLM request");
+    assert!(request.contains("You are Ada, the Acme support assistant."));
+    assert!(request.contains("Never promise refunds over $500."));
+    assert!(request.contains("The warranty covers parts for two years."));
+    assert!(!request.contains("Shipping takes"));
+    assert!(!request.contains("Unreleased pricing"));
+    Ok(())
+}
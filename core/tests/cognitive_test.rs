@@ -7,10 +7,12 @@ use loom_core::cognitive::{
     Perception, Plan, ThinkingStrategy, Thought, ThoughtStep, ToolCall,
 };
 use loom_core::proto::{AgentConfig, AgentState, Event};
-use loom_core::Result;
+use loom_core::tools::ToolResult;
+use loom_core::{LlmClient, Result, SimpleCognitiveLoop, Tool, ToolRegistry};
 use serde_json::json;
 use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
+use std::time::Duration;
 
 // ============================================================================
 // Test Helpers
@@ -147,6 +149,7 @@ fn test_cognitive_config_default() {
     assert_eq!(config.thinking_strategy, ThinkingStrategy::SingleShot);
     assert_eq!(config.memory_window_size, 20);
     assert_eq!(config.tool_timeout_ms, 30_000);
+    assert_eq!(config.max_parallel_tools, 4);
     assert!(config.refine_after_tools);
 }
 
@@ -177,9 +180,11 @@ fn test_cognitive_config_builder() {
         .with_system_prompt("You are a helpful assistant")
         .with_max_iterations(10)
         .with_reflection()
-        .with_memory_window(50);
+        .with_memory_window(50)
+        .with_max_parallel_tools(8);
 
     assert!(config.enable_reflection);
+    assert_eq!(config.max_parallel_tools, 8);
     assert_eq!(config.max_iterations, 10);
     assert_eq!(config.memory_window_size, 50);
     assert_eq!(
@@ -532,7 +537,7 @@ async fn test_cognitive_agent_multiple_events() {
     assert_eq!(perceive_count.load(Ordering::SeqCst), 3);
 
     // Memory should have accumulated event summaries
-    assert!(agent.memory_buffer().len() > 0);
+    assert!(!agent.memory_buffer().is_empty());
 }
 
 // ============================================================================
@@ -554,3 +559,107 @@ async fn test_cognitive_loop_run_cycle() {
     // Memory should be updated
     assert_eq!(mock_loop.memory_buffer().len(), 1);
 }
+
+// ============================================================================
+// SimpleCognitiveLoop act phase: parallel tool execution
+// ============================================================================
+
+/// Sleeps for `ms` and echoes `id`, tracking how many calls run at once
+struct SleepTool {
+    running: AtomicUsize,
+    max_running: AtomicUsize,
+}
+
+#[async_trait]
+impl Tool for SleepTool {
+    fn name(&self) -> String {
+        "test:sleep".to_string()
+    }
+
+    fn description(&self) -> String {
+        "Sleep, then echo the id".to_string()
+    }
+
+    fn parameters(&self) -> serde_json::Value {
+        json!({ "type": "object" })
+    }
+
+    async fn call(&self, arguments: serde_json::Value) -> ToolResult<serde_json::Value> {
+        let now = self.running.fetch_add(1, Ordering::SeqCst) + 1;
+        self.max_running.fetch_max(now, Ordering::SeqCst);
+        let ms = arguments["ms"].as_u64().unwrap_or(0);
+        tokio::time::sleep(Duration::from_millis(ms)).await;
+        self.running.fetch_sub(1, Ordering::SeqCst);
+        Ok(json!({ "id": arguments["id"] }))
+    }
+}
+
+/// Runs four sleep calls and one unknown tool; returns the ids of the observations added
+/// to working memory, the result and the peak concurrency
+async fn act_with_sleep_calls(
+    max_parallel_tools: usize,
+) -> (Vec<serde_json::Value>, ExecutionResult, usize) {
+    let sleep_tool = Arc::new(SleepTool {
+        running: AtomicUsize::new(0),
+        max_running: AtomicUsize::new(0),
+    });
+    let tools = Arc::new(ToolRegistry::new());
+    tools.register(sleep_tool.clone()).await;
+
+    let config = CognitiveConfig {
+        refine_after_tools: false,
+        ..CognitiveConfig::react().with_max_parallel_tools(max_parallel_tools)
+    };
+    let mut loop_impl =
+        SimpleCognitiveLoop::new(config, Arc::new(LlmClient::from_env().unwrap()), tools);
+
+    // Earlier steps sleep longer, so parallel calls finish in reverse order
+    let mut plan = Plan::with_goal("gather");
+    for (i, ms) in [150u64, 100, 50, 10].into_iter().enumerate() {
+        plan.add_step(ThoughtStep::with_tool(
+            i + 1,
+            format!("call {i}"),
+            ToolCall::new("test:sleep", json!({ "id": i, "ms": ms })),
+        ));
+    }
+    plan.add_step(ThoughtStep::with_tool(
+        5,
+        "missing tool",
+        ToolCall::new("test:missing", json!({})),
+    ));
+
+    let mut state = make_test_state("agent1");
+    let result = loop_impl.act(&plan, &mut state).await.unwrap();
+    let ids = loop_impl
+        .memory_buffer()
+        .recent(10)
+        .into_iter()
+        .rev()
+        .map(|item| {
+            let output = item.content.trim_start_matches("[test:sleep] ");
+            serde_json::from_str::<serde_json::Value>(output).unwrap()["id"].clone()
+        })
+        .collect();
+    (ids, result, sleep_tool.max_running.load(Ordering::SeqCst))
+}
+
+#[tokio::test]
+async fn test_simple_loop_executes_tools_in_parallel() {
+    let started = std::time::Instant::now();
+    let (_, result, max_running) = act_with_sleep_calls(4).await;
+    assert!(started.elapsed() < Duration::from_millis(300));
+    assert_eq!(max_running, 4);
+    assert!(!result.goal_achieved);
+}
+
+#[tokio::test]
+async fn test_simple_loop_parallel_observations_keep_step_order() {
+    let (ids, _, max_running) = act_with_sleep_calls(2).await;
+    assert_eq!(max_running, 2);
+    // The failed call to the unknown tool is not added to memory
+    assert_eq!(ids, [json!(0), json!(1), json!(2), json!(3)]);
+
+    let (ids, _, max_running) = act_with_sleep_calls(1).await;
+    assert_eq!(max_running, 1);
+    assert_eq!(ids, [json!(0), json!(1), json!(2), json!(3)]);
+}
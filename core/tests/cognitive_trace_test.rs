@@ -0,0 +1,186 @@
+/// Tests for the cognitive trace log: ToolTrace items per cycle and past failures in perception
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use loom_core::cognitive::trace_log::{trace_kinds, trace_tags};
+use loom_core::cognitive::{
+    past_tool_failures, trace_items, CognitiveConfig, CognitiveLoop, Plan, ThoughtStep, ToolCall,
+};
+use loom_core::context::{AgentContext, ContextItem, ContextItemType, MemoryStore};
+use loom_core::proto::{AgentState, Event};
+use loom_core::tools::{ToolError, ToolResult};
+use loom_core::{LlmClient, SimpleCognitiveLoop, Tool, ToolRegistry};
+use serde_json::{json, Value};
+
+/// Knows the weather everywhere but Atlantis
+struct WeatherTool;
+
+#[async_trait]
+impl Tool for WeatherTool {
+    fn name(&self) -> String {
+        "test:weather".to_string()
+    }
+
+    fn description(&self) -> String {
+        "Weather for a city".to_string()
+    }
+
+    fn parameters(&self) -> Value {
+        json!({ "type": "object" })
+    }
+
+    async fn call(&self, arguments: Value) -> ToolResult<Value> {
+        match arguments["city"].as_str() {
+            Some("Atlantis") => Err(ToolError::ExecutionFailed("no such city".into())),
+            Some(city) => Ok(json!({ "city": city, "sky": "clear" })),
+            None => Err(ToolError::InvalidArguments("city is required".into())),
+        }
+    }
+}
+
+fn make_event(trace_id: &str) -> Event {
+    let mut event = Event {
+        id: "e1".to_string(),
+        r#type: "test.message".to_string(),
+        timestamp_ms: 0,
+        source: "test".to_string(),
+        metadata: Default::default(),
+        payload: b"what is the weather?".to_vec(),
+        confidence: 1.0,
+        tags: vec![],
+        priority: 50,
+    };
+    event
+        .metadata
+        .insert("trace_id".to_string(), trace_id.to_string());
+    event
+}
+
+fn make_state() -> AgentState {
+    AgentState {
+        agent_id: "agent1".to_string(),
+        persistent_state: vec![],
+        ephemeral_context: vec![],
+        last_update_ms: 0,
+        metadata: Default::default(),
+    }
+}
+
+async fn make_loop() -> (SimpleCognitiveLoop, Arc<dyn MemoryStore>) {
+    let tools = Arc::new(ToolRegistry::new());
+    tools.register(Arc::new(WeatherTool)).await;
+    let context = AgentContext::with_defaults("session1", "agent1");
+    let store = Arc::clone(context.store());
+    let config = CognitiveConfig {
+        refine_after_tools: false,
+        ..CognitiveConfig::react()
+    };
+    let loop_impl =
+        SimpleCognitiveLoop::new(config, Arc::new(LlmClient::from_env().unwrap()), tools)
+            .with_context(context);
+    (loop_impl, store)
+}
+
+fn weather_plan() -> Plan {
+    let mut plan = Plan::with_goal("weather");
+    plan.add_step(ThoughtStep::with_tool(
+        1,
+        "check Atlantis",
+        ToolCall::new("test:weather", json!({ "city": "Atlantis" })),
+    ));
+    plan.add_step(ThoughtStep::with_tool(
+        2,
+        "check Paris",
+        ToolCall::new("test:weather", json!({ "city": "Paris" })),
+    ));
+    plan
+}
+
+fn kind(item: &ContextItem) -> &str {
+    item.metadata.tags[trace_tags::KIND].as_str()
+}
+
+#[tokio::test]
+async fn cycle_is_recorded_under_the_event_trace_id() {
+    let (mut loop_impl, store) = make_loop().await;
+    let mut state = make_state();
+    loop_impl
+        .perceive(make_event("trace-1"), &state)
+        .await
+        .unwrap();
+    loop_impl.act(&weather_plan(), &mut state).await.unwrap();
+
+    let items = trace_items(store.as_ref(), "trace-1").await.unwrap();
+    assert_eq!(items.len(), 5);
+    for item in &items {
+        assert_eq!(
+            item.item_type,
+            ContextItemType::ToolTrace {
+                trace_id: "trace-1".into()
+            }
+        );
+    }
+    let steps: Vec<&ContextItem> = items
+        .iter()
+        .filter(|i| kind(i) == trace_kinds::STEP)
+        .collect();
+    let observations: Vec<&ContextItem> = items
+        .iter()
+        .filter(|i| kind(i) == trace_kinds::OBSERVATION)
+        .collect();
+    let plans: Vec<&ContextItem> = items
+        .iter()
+        .filter(|i| kind(i) == trace_kinds::PLAN)
+        .collect();
+    assert_eq!((steps.len(), observations.len(), plans.len()), (2, 2, 1));
+
+    // Each observation relates to its step; the plan to everything else
+    let failed = observations
+        .iter()
+        .find(|i| i.metadata.tags[trace_tags::SUCCESS] == "false")
+        .unwrap();
+    assert_eq!(
+        failed.content.raw["arguments"],
+        json!({ "city": "Atlantis" })
+    );
+    let failed_step = steps
+        .iter()
+        .find(|i| i.metadata.tags[trace_tags::STEP] == "1")
+        .unwrap();
+    assert_eq!(failed.metadata.related_items, vec![failed_step.id.clone()]);
+    assert_eq!(plans[0].metadata.related_items.len(), 4);
+}
+
+#[tokio::test]
+async fn later_cycles_perceive_past_failures() {
+    let (mut loop_impl, store) = make_loop().await;
+    let mut state = make_state();
+    loop_impl
+        .perceive(make_event("trace-1"), &state)
+        .await
+        .unwrap();
+    loop_impl.act(&weather_plan(), &mut state).await.unwrap();
+
+    let failures = past_tool_failures(store.as_ref(), "agent1", 5)
+        .await
+        .unwrap();
+    assert_eq!(failures.len(), 1);
+    assert_eq!(failures[0].trace_id, "trace-1");
+    assert_eq!(failures[0].tool, "test:weather");
+    assert!(failures[0].error.contains("no such city"));
+
+    let perception = loop_impl
+        .perceive(make_event("trace-2"), &state)
+        .await
+        .unwrap();
+    assert!(perception.context.contains(&failures[0].describe()));
+    assert!(failures[0]
+        .describe()
+        .starts_with("You tried tool `test:weather` with {\"city\":\"Atlantis\"} and it failed"));
+
+    // Other agents' failures are not theirs to remember
+    assert!(past_tool_failures(store.as_ref(), "agent2", 5)
+        .await
+        .unwrap()
+        .is_empty());
+}
@@ -0,0 +1,201 @@
+/// Tests for fanout aggregation: majority vote, best-of judging, merge-and-summarize
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use loom_core::context::pipeline::Summarizer;
+use loom_core::messaging::aggregate::{majority_vote, AttributedReply, ReplyJudge, VoteConfig};
+use loom_core::{collab_types, Collaborator, Envelope, Event, EventBus, QoSLevel, Result};
+
+/// Responders on `service.agg.<i>` answering with `answers[i]` (and `vote` metadata)
+async fn spawn_responders(bus: &Arc<EventBus>, answers: &[&str]) -> Vec<String> {
+    let mut topics = Vec::new();
+    for (i, answer) in answers.iter().enumerate() {
+        let topic = format!("service.agg.{i}");
+        let (_sid, mut rx) = bus
+            .subscribe(
+                topic.clone(),
+                vec![collab_types::REQ.into()],
+                QoSLevel::QosBatched,
+            )
+            .await
+            .unwrap();
+        let bus_i = Arc::clone(bus);
+        let answer = answer.to_string();
+        tokio::spawn(async move {
+            while let Some(req) = rx.recv().await {
+                let env = Envelope::from_event(&req);
+                let mut md = HashMap::new();
+                md.insert("vote".to_string(), answer.to_uppercase());
+                env.apply_to_metadata(&mut md);
+                let mut reply = Event {
+                    id: format!("reply_{i}"),
+                    r#type: collab_types::REPLY.into(),
+                    timestamp_ms: chrono::Utc::now().timestamp_millis(),
+                    source: format!("agent.{i}"),
+                    metadata: md,
+                    payload: answer.clone().into_bytes(),
+                    confidence: 1.0,
+                    tags: vec![],
+                    priority: 50,
+                };
+                env.attach_to_event(&mut reply);
+                let _ = bus_i.publish(&env.reply_topic(), reply).await;
+            }
+        });
+        topics.push(topic);
+    }
+    topics
+}
+
+fn reply(source: &str, payload: &str) -> AttributedReply {
+    let event = Event {
+        id: format!("evt_{source}"),
+        r#type: collab_types::REPLY.into(),
+        timestamp_ms: 0,
+        source: source.into(),
+        metadata: HashMap::new(),
+        payload: payload.as_bytes().to_vec(),
+        confidence: 1.0,
+        tags: vec![],
+        priority: 50,
+    };
+    loom_core::messaging::aggregate::attribute(vec![event], 0).remove(0)
+}
+
+#[test]
+fn vote_needs_a_unique_leader_with_quorum() {
+    let replies = vec![
+        reply("a", "Paris"),
+        reply("b", " paris "),
+        reply("c", "Lyon"),
+        reply("d", ""),
+    ];
+    let outcome = majority_vote(&replies, &VoteConfig::default());
+    assert_eq!(outcome.winner.as_deref(), Some("paris"));
+    assert_eq!(outcome.winner_votes(), 2);
+    assert_eq!((outcome.votes_cast, outcome.abstained), (3, 1));
+    assert_eq!(outcome.tally[0].voters, vec!["a", "b"]);
+    assert_eq!(outcome.tally[1].value, "lyon");
+
+    // A tie has no winner
+    let tied = majority_vote(&replies[1..3], &VoteConfig::default());
+    assert_eq!(tied.winner, None);
+    assert_eq!(tied.tally.len(), 2);
+
+    // 2 of 3 misses a 3/4 quorum
+    let strict = majority_vote(&replies, &VoteConfig::new().with_quorum(0.75));
+    assert_eq!(strict.winner, None);
+}
+
+#[tokio::test]
+async fn fanout_vote_returns_tally_and_replies() {
+    let bus = Arc::new(EventBus::new().await.unwrap());
+    bus.start().await.unwrap();
+    let topics = spawn_responders(&bus, &["yes", "no", "yes"]).await;
+
+    let collab = Collaborator::new(Arc::clone(&bus), "agent.client");
+    let aggregate = collab
+        .fanout_vote(
+            &topics,
+            b"ship it?".to_vec(),
+            3,
+            1000,
+            &VoteConfig::new().with_metadata_key("vote"),
+        )
+        .await
+        .unwrap();
+    assert_eq!(aggregate.result.winner.as_deref(), Some("YES"));
+    assert_eq!(aggregate.replies.len(), 3);
+    let mut yes_voters = aggregate.result.tally[0].voters.clone();
+    yes_voters.sort();
+    assert_eq!(yes_voters, vec!["agent.0", "agent.2"]);
+    for reply in &aggregate.replies {
+        assert!(reply.source.starts_with("agent."));
+        assert_eq!(reply.event_id, reply.event.id);
+    }
+}
+
+/// Picks the longest reply, recording the request it saw
+struct Longest {
+    seen: Mutex<Vec<u8>>,
+}
+
+#[async_trait]
+impl ReplyJudge for Longest {
+    async fn judge(
+        &self,
+        request: &[u8],
+        candidates: &[AttributedReply],
+    ) -> Result<(usize, String)> {
+        *self.seen.lock().unwrap() = request.to_vec();
+        let (index, _) = candidates
+            .iter()
+            .enumerate()
+            .max_by_key(|(_, c)| c.event.payload.len())
+            .unwrap();
+        Ok((index, "longest".into()))
+    }
+}
+
+#[tokio::test]
+async fn fanout_best_of_reports_the_judged_reply() {
+    let bus = Arc::new(EventBus::new().await.unwrap());
+    bus.start().await.unwrap();
+    let topics = spawn_responders(&bus, &["short", "a much longer answer", "mid size"]).await;
+
+    let collab = Collaborator::new(Arc::clone(&bus), "agent.client");
+    let judge = Longest {
+        seen: Mutex::new(Vec::new()),
+    };
+    let aggregate = collab
+        .fanout_best_of(&topics, b"explain".to_vec(), 3, 1000, &judge)
+        .await
+        .unwrap();
+    let judgement = aggregate.result.unwrap();
+    assert_eq!(judgement.source, "agent.1");
+    assert_eq!(judgement.reason, "longest");
+    assert_eq!(
+        aggregate.replies[judgement.index].text(),
+        "a much longer answer"
+    );
+    assert_eq!(*judge.seen.lock().unwrap(), b"explain".to_vec());
+}
+
+/// Joins its inputs
+struct Join;
+
+#[async_trait]
+impl Summarizer for Join {
+    async fn summarize(&self, texts: &[String], _max_tokens: usize) -> Result<String> {
+        let mut texts = texts.to_vec();
+        texts.sort();
+        Ok(texts.join(" | "))
+    }
+}
+
+#[tokio::test]
+async fn fanout_summarize_attributes_each_reply() {
+    let bus = Arc::new(EventBus::new().await.unwrap());
+    bus.start().await.unwrap();
+    let topics = spawn_responders(&bus, &["alpha", "beta"]).await;
+
+    let collab = Collaborator::new(Arc::clone(&bus), "agent.client");
+    let aggregate = collab
+        .fanout_summarize(&topics, b"report".to_vec(), 2, 1000, &Join, 128)
+        .await
+        .unwrap();
+    assert_eq!(
+        aggregate.result.as_deref(),
+        Some("[agent.0] alpha | [agent.1] beta")
+    );
+    assert_eq!(aggregate.replies.len(), 2);
+
+    // Nothing to summarize without replies
+    let empty = collab
+        .fanout_summarize(&["service.nobody".to_string()], vec![], 1, 50, &Join, 128)
+        .await
+        .unwrap();
+    assert!(empty.result.is_none());
+    assert!(empty.replies.is_empty());
+}
@@ -0,0 +1,185 @@
+/// Tests for cancelling collaborations: pending waits, participants, and races with replies
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use loom_core::simulation::{Simulation, SimulationConfig};
+use loom_core::{collab_types, Collaborator, EventBus, LoomError, QoSLevel, Result};
+
+async fn slow_personas(
+    bus: &Arc<EventBus>,
+    personas: usize,
+    latency: Duration,
+) -> Result<Simulation> {
+    let config = SimulationConfig::new(personas)
+        .with_threads(vec!["tender".into()])
+        .with_reply_latency(latency, latency);
+    Simulation::spawn(Arc::clone(bus), config).await
+}
+
+fn cancelled<T: std::fmt::Debug>(result: Result<T>) -> String {
+    match result {
+        Err(LoomError::Cancelled(thread_id)) => thread_id,
+        other => panic!("expected a cancelled collaboration, got {other:?}"),
+    }
+}
+
+#[tokio::test]
+async fn cancel_resolves_a_pending_request_and_the_persona_abandons_it() -> Result<()> {
+    let bus = Arc::new(EventBus::new().await?);
+    let sim = slow_personas(&bus, 1, Duration::from_millis(300)).await?;
+    let collab = Arc::new(Collaborator::new(Arc::clone(&bus), "agent.client"));
+
+    let started = Instant::now();
+    let pending = {
+        let collab = Arc::clone(&collab);
+        let topic = sim.topics()[0].clone();
+        tokio::spawn(async move {
+            collab
+                .request_reply_in("job-1", &topic, b"work".to_vec(), 5000)
+                .await
+        })
+    };
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    assert!(collab.cancel("job-1").await?);
+
+    assert_eq!(cancelled(pending.await.unwrap()), "job-1");
+    assert!(started.elapsed() < Duration::from_millis(300));
+
+    // The persona got the cancellation on its topic and never replies
+    tokio::time::sleep(Duration::from_millis(350)).await;
+    let stats = sim.stats();
+    assert_eq!((stats.requests, stats.replies, stats.abandoned), (1, 0, 1));
+
+    // Nothing waits on the thread any more
+    assert!(!collab.cancel("job-1").await?);
+    Ok(())
+}
+
+#[tokio::test]
+async fn a_reply_that_arrives_first_wins() -> Result<()> {
+    let bus = Arc::new(EventBus::new().await?);
+    let sim = slow_personas(&bus, 1, Duration::ZERO).await?;
+    let collab = Collaborator::new(Arc::clone(&bus), "agent.client");
+
+    let reply = collab
+        .request_reply_in("job-2", &sim.topics()[0], b"work".to_vec(), 1000)
+        .await?;
+    assert_eq!(reply.unwrap().payload, b"work");
+    assert!(!collab.cancel("job-2").await?);
+    assert!(!collab.cancel("never-started").await?);
+    Ok(())
+}
+
+#[tokio::test]
+async fn cancel_and_reply_race_to_exactly_one_outcome() -> Result<()> {
+    let bus = Arc::new(EventBus::new().await?);
+    let sim = slow_personas(&bus, 1, Duration::from_millis(5)).await?;
+    let collab = Arc::new(Collaborator::new(Arc::clone(&bus), "agent.client"));
+    let topic = sim.topics()[0].clone();
+
+    let (mut replied, mut cancelled_count) = (0, 0);
+    for i in 0..20u64 {
+        let thread_id = format!("race-{i}");
+        let pending = {
+            let (collab, topic, thread_id) =
+                (Arc::clone(&collab), topic.clone(), thread_id.clone());
+            tokio::spawn(async move {
+                collab
+                    .request_reply_in(&thread_id, &topic, b"work".to_vec(), 3000)
+                    .await
+            })
+        };
+        // Cancel around the moment the reply is due
+        tokio::time::sleep(Duration::from_millis(i % 10)).await;
+        collab.cancel(&thread_id).await?;
+
+        // Either outcome, but never a timeout or a hang
+        let outcome = tokio::time::timeout(Duration::from_secs(1), pending)
+            .await
+            .expect("cancelled request should resolve at once")
+            .unwrap();
+        match outcome {
+            Ok(Some(reply)) => {
+                assert_eq!(reply.r#type, collab_types::REPLY);
+                replied += 1;
+            }
+            other => {
+                assert_eq!(cancelled(other), thread_id);
+                cancelled_count += 1;
+            }
+        }
+    }
+    assert_eq!(replied + cancelled_count, 20);
+    Ok(())
+}
+
+#[tokio::test]
+async fn cancel_resolves_fanout_and_contract_net() -> Result<()> {
+    let bus = Arc::new(EventBus::new().await?);
+    let sim = slow_personas(&bus, 5, Duration::from_millis(200)).await?;
+    let collab = Arc::new(Collaborator::new(Arc::clone(&bus), "agent.client"));
+
+    let fanout = {
+        let (collab, topics) = (Arc::clone(&collab), sim.topics());
+        tokio::spawn(async move {
+            collab
+                .fanout_fanin_in("survey", &topics, b"q".to_vec(), 5, 5000)
+                .await
+        })
+    };
+    let tender = {
+        let collab = Arc::clone(&collab);
+        tokio::spawn(async move {
+            collab
+                .contract_net("tender", b"job".to_vec(), 5000, 1)
+                .await
+        })
+    };
+    // Bidders hear about the cancellation on the thread's broadcast topic
+    let (_sid, mut broadcast) = bus
+        .subscribe(
+            "thread.tender.broadcast".into(),
+            vec![collab_types::CANCEL.into()],
+            QoSLevel::QosBatched,
+        )
+        .await?;
+
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    let started = Instant::now();
+    assert!(collab.cancel("survey").await?);
+    assert!(collab.cancel("tender").await?);
+
+    assert_eq!(cancelled(fanout.await.unwrap()), "survey");
+    assert_eq!(cancelled(tender.await.unwrap()), "tender");
+    assert!(started.elapsed() < Duration::from_secs(1));
+    let notice = broadcast.recv().await.unwrap();
+    assert_eq!(notice.source, "agent.client");
+
+    // All five requests and bids in flight were abandoned
+    tokio::time::sleep(Duration::from_millis(250)).await;
+    assert_eq!(sim.stats().abandoned, 10);
+    Ok(())
+}
+
+#[tokio::test]
+async fn another_collaborator_can_cancel_the_thread() -> Result<()> {
+    let bus = Arc::new(EventBus::new().await?);
+    let sim = slow_personas(&bus, 1, Duration::from_millis(300)).await?;
+    let requester = Arc::new(Collaborator::new(Arc::clone(&bus), "agent.client"));
+    let supervisor = Collaborator::new(Arc::clone(&bus), "agent.supervisor");
+
+    let pending = {
+        let (requester, topic) = (Arc::clone(&requester), sim.topics()[0].clone());
+        tokio::spawn(async move {
+            requester
+                .request_reply_in("job-3", &topic, b"work".to_vec(), 5000)
+                .await
+        })
+    };
+    tokio::time::sleep(Duration::from_millis(50)).await;
+
+    // Not waiting itself, the supervisor still reaches the requester's wait
+    assert!(!supervisor.cancel("job-3").await?);
+    assert_eq!(cancelled(pending.await.unwrap()), "job-3");
+    Ok(())
+}
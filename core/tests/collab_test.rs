@@ -1,6 +1,12 @@
 use std::sync::Arc;
+use std::time::Duration;
 
-use loom_core::{collab_types, Collaborator, Envelope, Event, EventBus, QoSLevel};
+use loom_core::messaging::collab::{meta, ByScore, LowestCost, PayloadSchema, WeightedScore};
+use loom_core::{
+    collab_types, AgentDirectory, AgentInfo, AgentStatus, BidConstraints, BidScorer, BidTerms,
+    CollabRetryPolicy, Collaborator, ContractNetConfig, Envelope, Event, EventBus,
+    IdempotencyCache, ProposalValidator, QoSLevel,
+};
 
 #[tokio::test]
 async fn request_reply_basic() {
@@ -155,3 +161,574 @@ async fn contract_net_selects_top_score() {
     let top = &winners[0];
     assert_eq!(top.metadata.get("score").unwrap(), "80");
 }
+
+/// Replies to requests on `topic`, ignoring the first `skip` of them
+fn spawn_responder(
+    bus: Arc<EventBus>,
+    topic: &str,
+    name: &'static str,
+    skip: usize,
+) -> Arc<std::sync::Mutex<Vec<Event>>> {
+    let seen = Arc::new(std::sync::Mutex::new(Vec::new()));
+    let seen_task = Arc::clone(&seen);
+    let topic = topic.to_string();
+    tokio::spawn(async move {
+        let (_sid, mut rx) = bus
+            .subscribe(topic, vec![collab_types::REQ.into()], QoSLevel::QosBatched)
+            .await
+            .unwrap();
+        while let Some(req) = rx.recv().await {
+            let count = {
+                let mut seen = seen_task.lock().unwrap();
+                seen.push(req.clone());
+                seen.len()
+            };
+            if count <= skip {
+                continue;
+            }
+            let env = Envelope::from_event(&req);
+            let mut md = std::collections::HashMap::new();
+            env.apply_to_metadata(&mut md);
+            let mut reply = Event {
+                id: format!("evt_{}", chrono::Utc::now().timestamp_millis()),
+                r#type: collab_types::REPLY.into(),
+                timestamp_ms: chrono::Utc::now().timestamp_millis(),
+                source: name.into(),
+                metadata: md,
+                payload: name.as_bytes().to_vec(),
+                confidence: 1.0,
+                tags: vec!["test".into()],
+                priority: 50,
+            };
+            env.attach_to_event(&mut reply);
+            let _ = bus.publish(&env.reply_topic(), reply).await;
+        }
+    });
+    seen
+}
+
+fn fast_retries(max_retries: u32) -> CollabRetryPolicy {
+    CollabRetryPolicy::new()
+        .with_retries(max_retries)
+        .with_backoff(Duration::from_millis(10), Duration::from_millis(20), 2.0)
+}
+
+#[tokio::test]
+async fn request_reply_retries_with_the_same_idempotency_key() {
+    let bus = Arc::new(EventBus::new().await.unwrap());
+    bus.start().await.unwrap();
+    let seen = spawn_responder(Arc::clone(&bus), "service.flaky", "agent.flaky", 2);
+    tokio::time::sleep(Duration::from_millis(20)).await;
+
+    // Without retries the first timeout fails the request
+    let collab = Collaborator::new(Arc::clone(&bus), "agent.client");
+    let res = collab
+        .request_reply("service.flaky", b"hi".to_vec(), 100)
+        .await
+        .unwrap();
+    assert!(res.is_none());
+
+    let collab = collab.with_retry_policy(fast_retries(2));
+    let reply = collab
+        .request_reply_with_key("service.flaky", b"hi".to_vec(), 100, "order-42")
+        .await
+        .unwrap()
+        .expect("reply after retry");
+    assert_eq!(reply.payload, b"agent.flaky");
+
+    let seen = seen.lock().unwrap();
+    assert_eq!(seen.len(), 3);
+    assert!(!seen[1].metadata.contains_key(meta::ATTEMPT));
+    let retried = &seen[2];
+    assert_eq!(
+        retried.metadata.get(meta::IDEMPOTENCY_KEY).unwrap(),
+        "order-42"
+    );
+    assert_eq!(retried.metadata.get(meta::ATTEMPT).unwrap(), "2");
+    // The retry is one more hop of the same envelope
+    assert_eq!(Envelope::from_event(retried).ttl, 15);
+}
+
+#[tokio::test]
+async fn retries_go_to_alternate_capability_providers() {
+    let bus = Arc::new(EventBus::new().await.unwrap());
+    bus.start().await.unwrap();
+    let directory = Arc::new(AgentDirectory::new());
+    for (agent_id, topic, status) in [
+        (
+            "translator-a",
+            "service.translate.a",
+            AgentStatus::Disconnected,
+        ),
+        ("translator-b", "service.translate.b", AgentStatus::Active),
+    ] {
+        directory.register_agent(AgentInfo {
+            agent_id: agent_id.into(),
+            subscribed_topics: vec![topic.into()],
+            capabilities: vec!["translate".into()],
+            ..Default::default()
+        });
+        directory.update_status(agent_id, status);
+    }
+    let seen_a = spawn_responder(Arc::clone(&bus), "service.translate.a", "agent.a", 0);
+    spawn_responder(Arc::clone(&bus), "service.translate.b", "agent.b", 0);
+    tokio::time::sleep(Duration::from_millis(20)).await;
+
+    // Nobody listens on the primary topic
+    let collab = Collaborator::new(Arc::clone(&bus), "agent.client")
+        .with_retry_policy(fast_retries(1).with_alternates("translate"))
+        .with_directory(directory);
+    let reply = collab
+        .request_reply("service.translate", b"hola".to_vec(), 100)
+        .await
+        .unwrap()
+        .expect("reply from alternate");
+    assert_eq!(reply.source, "agent.b");
+    // Disconnected providers are skipped
+    assert!(seen_a.lock().unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn retries_stop_when_the_envelope_ttl_is_spent() {
+    let bus = Arc::new(EventBus::new().await.unwrap());
+    bus.start().await.unwrap();
+    let seen = spawn_responder(
+        Arc::clone(&bus),
+        "service.silent",
+        "agent.silent",
+        usize::MAX,
+    );
+    tokio::time::sleep(Duration::from_millis(20)).await;
+
+    let collab = Collaborator::new(Arc::clone(&bus), "agent.client").with_retry_policy(
+        CollabRetryPolicy::new().with_retries(100).with_backoff(
+            Duration::from_millis(1),
+            Duration::from_millis(1),
+            1.0,
+        ),
+    );
+    let res = collab
+        .request_reply("service.silent", b"hi".to_vec(), 5)
+        .await
+        .unwrap();
+    assert!(res.is_none());
+    tokio::time::sleep(Duration::from_millis(20)).await;
+    // A fresh envelope has 16 hops: the first attempt and 15 retries
+    assert_eq!(seen.lock().unwrap().len(), 16);
+}
+
+#[tokio::test]
+async fn fanout_retries_count_each_responder_once() {
+    let bus = Arc::new(EventBus::new().await.unwrap());
+    bus.start().await.unwrap();
+    spawn_responder(Arc::clone(&bus), "service.pool.0", "agent.0", 0);
+    spawn_responder(Arc::clone(&bus), "service.pool.1", "agent.1", 0);
+    let flaky = spawn_responder(Arc::clone(&bus), "service.pool.2", "agent.2", 1);
+    tokio::time::sleep(Duration::from_millis(20)).await;
+
+    let topics: Vec<String> = (0..3).map(|i| format!("service.pool.{i}")).collect();
+    let collab =
+        Collaborator::new(Arc::clone(&bus), "agent.client").with_retry_policy(fast_retries(1));
+    let replies = collab
+        .fanout_fanin(&topics, b"go".to_vec(), 3, 150)
+        .await
+        .unwrap();
+    let mut sources: Vec<_> = replies.iter().map(|r| r.source.as_str()).collect();
+    sources.sort();
+    assert_eq!(sources, vec!["agent.0", "agent.1", "agent.2"]);
+
+    let flaky = flaky.lock().unwrap();
+    assert_eq!(flaky.len(), 2);
+    assert_eq!(
+        flaky[0].metadata.get(meta::IDEMPOTENCY_KEY),
+        flaky[1].metadata.get(meta::IDEMPOTENCY_KEY)
+    );
+}
+
+#[test]
+fn idempotency_cache_returns_recorded_replies() {
+    let cache = IdempotencyCache::new(Duration::from_secs(60));
+    let mut req = Event {
+        id: "r1".into(),
+        r#type: collab_types::REQ.into(),
+        timestamp_ms: 0,
+        source: "agent.client".into(),
+        metadata: Default::default(),
+        payload: vec![],
+        confidence: 1.0,
+        tags: vec![],
+        priority: 50,
+    };
+    // Requests without a key are never cached
+    cache.insert(&req, b"x".to_vec());
+    assert!(cache.is_empty());
+
+    req.metadata
+        .insert(meta::IDEMPOTENCY_KEY.into(), "order-42".into());
+    assert!(cache.get(&req).is_none());
+    cache.insert(&req, b"done".to_vec());
+    assert_eq!(cache.get(&req), Some(b"done".to_vec()));
+
+    let expired = IdempotencyCache::new(Duration::ZERO);
+    expired.insert(&req, b"done".to_vec());
+    assert!(expired.get(&req).is_none());
+}
+
+/// How a bidder answers an award
+#[derive(Clone, Copy)]
+enum Answer {
+    Confirm,
+    Decline,
+    Silent,
+}
+
+/// Bids `terms` on every CFP of `thread_id` and answers its awards with `answer`.
+/// Returns the control events (CFPs, awards, revocations, rejections) the bidder saw.
+async fn spawn_bidder(
+    bus: &Arc<EventBus>,
+    thread_id: &str,
+    name: &'static str,
+    terms: BidTerms,
+    answer: Answer,
+) -> Arc<std::sync::Mutex<Vec<Event>>> {
+    let (_sid, mut rx) = bus
+        .subscribe(
+            format!("thread.{}.broadcast", thread_id),
+            vec![
+                collab_types::CFP.into(),
+                collab_types::AWARD.into(),
+                collab_types::REVOKE.into(),
+                collab_types::REJECT.into(),
+            ],
+            QoSLevel::QosBatched,
+        )
+        .await
+        .unwrap();
+    let seen = Arc::new(std::sync::Mutex::new(Vec::new()));
+    let seen_task = Arc::clone(&seen);
+    let bus = Arc::clone(bus);
+    tokio::spawn(async move {
+        while let Some(ev) = rx.recv().await {
+            seen_task.lock().unwrap().push(ev.clone());
+            let reply_type = match ev.r#type.as_str() {
+                collab_types::CFP => collab_types::PROPOSAL,
+                collab_types::AWARD
+                    if ev.metadata.get(meta::AWARD_TO).map(String::as_str) == Some(name) =>
+                {
+                    match answer {
+                        Answer::Confirm => collab_types::CONFIRM,
+                        Answer::Decline => collab_types::DECLINE,
+                        Answer::Silent => continue,
+                    }
+                }
+                _ => continue,
+            };
+            let env = Envelope::from_event(&ev);
+            let mut md = std::collections::HashMap::new();
+            env.apply_to_metadata(&mut md);
+            terms.apply_to_metadata(&mut md);
+            let reply = Event {
+                id: format!("{}-{}", name, reply_type),
+                r#type: reply_type.into(),
+                timestamp_ms: chrono::Utc::now().timestamp_millis(),
+                source: name.into(),
+                metadata: md,
+                payload: vec![],
+                confidence: 1.0,
+                tags: vec!["test".into()],
+                priority: 50,
+            };
+            let _ = bus.publish(&env.reply_topic(), reply).await;
+        }
+    });
+    seen
+}
+
+#[tokio::test]
+async fn contract_net_applies_constraints_and_custom_scoring() {
+    let bus = Arc::new(EventBus::new().await.unwrap());
+    let thread_id = "cnp.constraints";
+    let constraints = BidConstraints::new()
+        .with_max_cost(10.0)
+        .with_min_capacity(2);
+
+    let fits = BidTerms::new().with_cost(5.0).with_capacity(4);
+    let seen = spawn_bidder(&bus, thread_id, "fits", fits, Answer::Silent).await;
+    let small = BidTerms::new().with_cost(3.0).with_capacity(1);
+    spawn_bidder(&bus, thread_id, "small", small, Answer::Silent).await;
+    let pricey = BidTerms::new().with_cost(12.0).with_capacity(8);
+    spawn_bidder(&bus, thread_id, "pricey", pricey, Answer::Silent).await;
+    let vague = BidTerms::new().with_score(99.0).with_capacity(8);
+    spawn_bidder(&bus, thread_id, "vague", vague, Answer::Silent).await;
+    let dearer = BidTerms::new().with_cost(9.0).with_capacity(2);
+    spawn_bidder(&bus, thread_id, "dearer", dearer, Answer::Silent).await;
+
+    let collab = Collaborator::new(Arc::clone(&bus), "agent.client");
+    let config = ContractNetConfig::new(Duration::from_millis(200), 2)
+        .with_constraints(constraints.clone())
+        .with_scorer(LowestCost);
+    let winners = collab
+        .contract_net_with(thread_id, b"task".to_vec(), config)
+        .await
+        .unwrap();
+    let names: Vec<&str> = winners.iter().map(|w| w.source.as_str()).collect();
+    assert_eq!(names, ["fits", "dearer"]);
+
+    // Bidders see the constraints on the CFP and whom each award went to
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    let seen = seen.lock().unwrap();
+    assert_eq!(BidConstraints::from_event(&seen[0]), constraints);
+    let awarded: Vec<&str> = seen[1..]
+        .iter()
+        .filter(|ev| ev.r#type == collab_types::AWARD)
+        .filter_map(|ev| ev.metadata.get(meta::AWARD_TO).map(String::as_str))
+        .collect();
+    assert_eq!(awarded, ["fits", "dearer"]);
+}
+
+#[tokio::test]
+async fn unconfirmed_awards_pass_to_the_next_bidder() {
+    let bus = Arc::new(EventBus::new().await.unwrap());
+    let thread_id = "cnp.two_phase";
+    let silent = spawn_bidder(
+        &bus,
+        thread_id,
+        "silent",
+        BidTerms::new().with_score(90.0),
+        Answer::Silent,
+    )
+    .await;
+    spawn_bidder(
+        &bus,
+        thread_id,
+        "busy",
+        BidTerms::new().with_score(70.0),
+        Answer::Decline,
+    )
+    .await;
+    spawn_bidder(
+        &bus,
+        thread_id,
+        "steady",
+        BidTerms::new().with_score(50.0),
+        Answer::Confirm,
+    )
+    .await;
+    spawn_bidder(
+        &bus,
+        thread_id,
+        "spare",
+        BidTerms::new().with_score(10.0),
+        Answer::Confirm,
+    )
+    .await;
+    let (_sid, mut summaries) = bus
+        .subscribe(
+            format!("thread.{}.broadcast", thread_id),
+            vec![collab_types::SUMMARY.into()],
+            QoSLevel::QosBatched,
+        )
+        .await
+        .unwrap();
+
+    let collab = Collaborator::new(Arc::clone(&bus), "agent.client");
+    let config = ContractNetConfig::new(Duration::from_millis(200), 1)
+        .with_confirmation(Duration::from_millis(150));
+    let winners = collab
+        .contract_net_with(thread_id, b"task".to_vec(), config)
+        .await
+        .unwrap();
+    assert_eq!(winners.len(), 1);
+    assert_eq!(winners[0].source, "steady");
+
+    let summary = summaries.recv().await.unwrap();
+    assert_eq!(summary.metadata["winners"], "1");
+    assert_eq!(summary.metadata["proposals"], "4");
+    assert_eq!(summary.metadata["revoked"], "1");
+
+    // The silent winner was told its award was withdrawn
+    let seen = silent.lock().unwrap();
+    let revoke = seen
+        .iter()
+        .find(|ev| ev.r#type == collab_types::REVOKE)
+        .expect("revocation");
+    assert_eq!(revoke.metadata[meta::AWARD_TO], "silent");
+    assert_eq!(
+        seen.iter()
+            .filter(|ev| ev.r#type == collab_types::AWARD)
+            .count(),
+        3
+    );
+}
+
+/// Reason of the rejection `seen` by a bidder, if it got one
+/// Reason of the reject addressed to `name`; every bidder sees all rejects on the
+/// thread's broadcast topic
+fn rejection(seen: &std::sync::Mutex<Vec<Event>>, name: &str) -> Option<String> {
+    seen.lock()
+        .unwrap()
+        .iter()
+        .find(|ev| {
+            ev.r#type == collab_types::REJECT
+                && ev.metadata.get(meta::REJECT_TO).map(String::as_str) == Some(name)
+        })
+        .map(|ev| ev.metadata[meta::REASON].clone())
+}
+
+#[tokio::test]
+async fn losing_and_invalid_bids_are_rejected() {
+    let bus = Arc::new(EventBus::new().await.unwrap());
+    let thread_id = "cnp.reject";
+    let best = spawn_bidder(
+        &bus,
+        thread_id,
+        "best",
+        BidTerms::new().with_score(90.0).with_eta_ms(100),
+        Answer::Silent,
+    )
+    .await;
+    let runner_up = spawn_bidder(
+        &bus,
+        thread_id,
+        "runner_up",
+        BidTerms::new().with_score(50.0).with_eta_ms(100),
+        Answer::Silent,
+    )
+    .await;
+    let no_eta = spawn_bidder(
+        &bus,
+        thread_id,
+        "no_eta",
+        BidTerms::new().with_score(99.0),
+        Answer::Silent,
+    )
+    .await;
+    let too_slow = spawn_bidder(
+        &bus,
+        thread_id,
+        "too_slow",
+        BidTerms::new().with_score(95.0).with_eta_ms(5000),
+        Answer::Silent,
+    )
+    .await;
+    let (_sid, mut summaries) = bus
+        .subscribe(
+            format!("thread.{}.broadcast", thread_id),
+            vec![collab_types::SUMMARY.into()],
+            QoSLevel::QosBatched,
+        )
+        .await
+        .unwrap();
+
+    let collab = Collaborator::new(Arc::clone(&bus), "agent.client");
+    let config = ContractNetConfig::new(Duration::from_millis(200), 1)
+        .with_constraints(BidConstraints::new().with_max_eta_ms(1000))
+        .with_validator(|terms: &BidTerms, _: &Event| match terms.eta_ms {
+            Some(_) => Ok(()),
+            None => Err("eta_ms missing".to_string()),
+        });
+    let winners = collab
+        .contract_net_with(thread_id, b"task".to_vec(), config)
+        .await
+        .unwrap();
+    assert_eq!(winners.len(), 1);
+    assert_eq!(winners[0].source, "best");
+
+    let summary = summaries.recv().await.unwrap();
+    assert_eq!(summary.metadata["proposals"], "4");
+    assert_eq!(summary.metadata["invalid"], "1");
+    assert_eq!(summary.metadata["rejected"], "1");
+
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    assert_eq!(rejection(&best, "best"), None);
+    assert_eq!(
+        rejection(&runner_up, "runner_up").as_deref(),
+        Some("outbid")
+    );
+    assert_eq!(
+        rejection(&no_eta, "no_eta").as_deref(),
+        Some("invalid: eta_ms missing")
+    );
+    assert_eq!(
+        rejection(&too_slow, "too_slow").as_deref(),
+        Some("constraints")
+    );
+}
+
+#[test]
+fn payload_schema_validates_proposals() {
+    let schema = PayloadSchema(serde_json::json!({
+        "type": "object",
+        "required": ["plan"],
+        "properties": { "plan": { "type": "string" } }
+    }));
+    let proposal = |payload: &[u8]| Event {
+        id: "p1".into(),
+        r#type: collab_types::PROPOSAL.into(),
+        timestamp_ms: 0,
+        source: "bidder".into(),
+        metadata: Default::default(),
+        payload: payload.to_vec(),
+        confidence: 1.0,
+        tags: vec![],
+        priority: 50,
+    };
+    let terms = BidTerms::new();
+    assert!(schema
+        .validate(&terms, &proposal(br#"{"plan": "two passes"}"#))
+        .is_ok());
+    assert!(schema
+        .validate(&terms, &proposal(br#"{"plan": 2}"#))
+        .unwrap_err()
+        .contains("$.plan"));
+    assert!(schema
+        .validate(&terms, &proposal(b"not json"))
+        .unwrap_err()
+        .contains("not JSON"));
+}
+
+#[test]
+fn bid_terms_and_scorers() {
+    let mut md = std::collections::HashMap::new();
+    BidTerms::new()
+        .with_score(0.5)
+        .with_cost(2.5)
+        .with_eta_ms(1000)
+        .apply_to_metadata(&mut md);
+    md.insert("capacity".into(), "lots".into());
+    let proposal = Event {
+        id: "p1".into(),
+        r#type: collab_types::PROPOSAL.into(),
+        timestamp_ms: 0,
+        source: "bidder".into(),
+        metadata: md,
+        payload: vec![],
+        confidence: 1.0,
+        tags: vec![],
+        priority: 50,
+    };
+    let terms = BidTerms::from_event(&proposal);
+    assert_eq!(
+        terms,
+        BidTerms::new()
+            .with_score(0.5)
+            .with_cost(2.5)
+            .with_eta_ms(1000)
+    );
+
+    // A constrained term that is missing or unparsable fails the constraint
+    assert!(BidConstraints::new().admits(&terms));
+    assert!(BidConstraints::new().with_max_eta_ms(1000).admits(&terms));
+    assert!(!BidConstraints::new().with_min_capacity(1).admits(&terms));
+
+    assert_eq!(ByScore.score(&terms, &proposal), 0.5);
+    assert_eq!(LowestCost.score(&terms, &proposal), -2.5);
+    let weighted = WeightedScore {
+        score_weight: 10.0,
+        cost_weight: 1.0,
+        eta_weight: 0.001,
+    };
+    assert_eq!(weighted.score(&terms, &proposal), 1.5);
+    let closure = |terms: &BidTerms, _: &Event| terms.eta_ms.map_or(0.0, |eta| 1.0 / eta as f64);
+    assert_eq!(closure.score(&terms, &proposal), 0.001);
+}
@@ -0,0 +1,278 @@
+//! Tests for composite tools: step chaining, synthesized schemas, errors and cancellation
+
+use async_trait::async_trait;
+use loom_core::tools::{CancellationToken, CompositeTool, ToolResult};
+use loom_core::{Tool, ToolError, ToolRegistry};
+use serde_json::{json, Value};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Looks up coordinates of a place
+struct GeocodeTool;
+
+#[async_trait]
+impl Tool for GeocodeTool {
+    fn name(&self) -> String {
+        "geo:lookup".to_string()
+    }
+
+    fn description(&self) -> String {
+        "Coordinates of a place".to_string()
+    }
+
+    fn parameters(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "place": { "type": "string", "description": "Place name" }
+            },
+            "required": ["place"]
+        })
+    }
+
+    async fn call(&self, arguments: Value) -> ToolResult<Value> {
+        match arguments["place"].as_str() {
+            Some("Berlin") => Ok(json!({ "name": "Berlin", "coords": [52.52, 13.41] })),
+            Some(other) => Err(ToolError::ExecutionFailed(format!("unknown place {other}"))),
+            None => Err(ToolError::InvalidArguments("Missing 'place'".to_string())),
+        }
+    }
+}
+
+/// Forecast at coordinates, in the requested units
+struct ForecastTool;
+
+#[async_trait]
+impl Tool for ForecastTool {
+    fn name(&self) -> String {
+        "weather:forecast".to_string()
+    }
+
+    fn description(&self) -> String {
+        "Forecast at coordinates".to_string()
+    }
+
+    fn parameters(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "lat": { "type": "number" },
+                "lon": { "type": "number" },
+                "units": { "type": "string", "enum": ["metric", "imperial"] }
+            },
+            "required": ["lat", "lon"]
+        })
+    }
+
+    fn output_schema(&self) -> Option<Value> {
+        Some(json!({
+            "type": "object",
+            "properties": { "summary": { "type": "string" } },
+            "required": ["summary"]
+        }))
+    }
+
+    async fn call(&self, arguments: Value) -> ToolResult<Value> {
+        let (lat, lon) = (arguments["lat"].as_f64(), arguments["lon"].as_f64());
+        let units = arguments["units"].as_str().unwrap_or("metric");
+        match (lat, lon) {
+            (Some(lat), Some(lon)) => Ok(json!({
+                "summary": format!("sunny at {lat},{lon} ({units})")
+            })),
+            _ => Err(ToolError::InvalidArguments(
+                "lat and lon must be numbers".into(),
+            )),
+        }
+    }
+}
+
+/// Sleeps for a second
+struct SlowTool;
+
+#[async_trait]
+impl Tool for SlowTool {
+    fn name(&self) -> String {
+        "test:slow".to_string()
+    }
+
+    fn description(&self) -> String {
+        "Takes a second".to_string()
+    }
+
+    fn parameters(&self) -> Value {
+        json!({ "type": "object" })
+    }
+
+    async fn call(&self, _arguments: Value) -> ToolResult<Value> {
+        tokio::time::sleep(Duration::from_secs(1)).await;
+        Ok(json!({}))
+    }
+}
+
+async fn registry() -> ToolRegistry {
+    let registry = ToolRegistry::new();
+    registry.register(Arc::new(GeocodeTool)).await;
+    registry.register(Arc::new(ForecastTool)).await;
+    registry.register(Arc::new(SlowTool)).await;
+    registry
+}
+
+fn weather_at(registry: &ToolRegistry) -> CompositeTool {
+    CompositeTool::builder("weather:at_place", "Forecast for a named place")
+        .step("geo", "geo:lookup", json!({ "place": "${input.place}" }))
+        .step(
+            "forecast",
+            "weather:forecast",
+            json!({
+                "lat": "${steps.geo.output.coords.0}",
+                "lon": "${steps.geo.output.coords.1}",
+                "units": "${input.units}"
+            }),
+        )
+        .build(registry)
+        .unwrap()
+}
+
+#[tokio::test]
+async fn composite_chains_steps_through_the_registry() {
+    let registry = registry().await;
+    registry.register(Arc::new(weather_at(&registry))).await;
+
+    let out = registry
+        .call(
+            "weather:at_place",
+            json!({ "place": "Berlin", "units": "imperial" }),
+        )
+        .await
+        .unwrap();
+    assert_eq!(out, json!({ "summary": "sunny at 52.52,13.41 (imperial)" }));
+
+    // Steps are ordinary registry calls
+    assert_eq!(registry.tool_stats("geo:lookup").unwrap().calls, 1);
+    assert_eq!(registry.tool_stats("weather:forecast").unwrap().calls, 1);
+}
+
+#[tokio::test]
+async fn schema_is_synthesized_from_the_steps() {
+    let registry = registry().await;
+    let tool = weather_at(&registry);
+
+    assert_eq!(
+        tool.parameters(),
+        json!({
+            "type": "object",
+            "properties": {
+                "place": { "type": "string", "description": "Place name" },
+                "units": { "type": "string", "enum": ["metric", "imperial"] }
+            },
+            "required": ["place", "units"]
+        })
+    );
+    // The result is the forecast's whole output, so its schema carries over
+    assert_eq!(
+        tool.output_schema(),
+        ForecastTool.output_schema(),
+        "output schema of the last step"
+    );
+
+    // Explicit inputs and output templates
+    let tool = CompositeTool::builder("weather:line", "One-line forecast")
+        .with_input(
+            "place",
+            json!({ "type": "string", "description": "City name" }),
+        )
+        .step("geo", "geo:lookup", json!({ "place": "${input.place}" }))
+        .step(
+            "forecast",
+            "weather:forecast",
+            json!({ "lat": "${steps.geo.output.coords.0}", "lon": "${steps.geo.output.coords.1}" }),
+        )
+        .with_output(json!(
+            "${steps.geo.output.name}: ${steps.forecast.output.summary}"
+        ))
+        .build(&registry)
+        .unwrap();
+    assert_eq!(
+        tool.parameters()["properties"]["place"]["description"],
+        "City name"
+    );
+    assert_eq!(tool.parameters()["required"], json!(["place"]));
+    assert_eq!(tool.output_schema(), None);
+    assert_eq!(
+        tool.call(json!({ "place": "Berlin" })).await.unwrap(),
+        json!("Berlin: sunny at 52.52,13.41 (metric)")
+    );
+}
+
+#[tokio::test]
+async fn invalid_composites_are_rejected_at_build_time() {
+    let registry = registry().await;
+    let cases = [
+        CompositeTool::builder("c:empty", "No steps"),
+        CompositeTool::builder("c:twice", "Repeated id")
+            .step("a", "geo:lookup", json!({}))
+            .step("a", "geo:lookup", json!({})),
+        CompositeTool::builder("c:forward", "References a later step")
+            .step("a", "geo:lookup", json!({ "place": "${steps.b.output}" }))
+            .step("b", "geo:lookup", json!({})),
+        CompositeTool::builder("c:self", "Calls itself").step("a", "c:self", json!({})),
+        CompositeTool::builder("c:output", "Unknown output step")
+            .step("a", "geo:lookup", json!({}))
+            .with_output(json!("${steps.z.output}")),
+    ];
+    for builder in cases {
+        let err = builder.build(&registry).err().unwrap();
+        assert!(matches!(err, ToolError::InvalidArguments(_)), "{err}");
+    }
+
+    let err = CompositeTool::builder("c:missing", "Unknown tool")
+        .step("a", "geo:nowhere", json!({}))
+        .build(&registry)
+        .err()
+        .unwrap();
+    assert!(matches!(err, ToolError::NotFound(ref name) if name == "geo:nowhere"));
+}
+
+#[tokio::test]
+async fn missing_inputs_and_failing_steps() {
+    let registry = registry().await;
+    let tool = weather_at(&registry);
+
+    let err = tool.call(json!({ "place": "Berlin" })).await.unwrap_err();
+    assert!(matches!(err, ToolError::InvalidArguments(ref m) if m.contains("units")));
+
+    // A failing step fails the composite and names the step
+    let err = tool
+        .call(json!({ "place": "Atlantis", "units": "metric" }))
+        .await
+        .unwrap_err();
+    match err {
+        ToolError::ExecutionFailed(message) => {
+            assert!(message.contains("step 'geo' (geo:lookup)"), "{message}");
+            assert!(message.contains("unknown place Atlantis"), "{message}");
+        }
+        other => panic!("expected a failed step, got {other:?}"),
+    }
+}
+
+#[tokio::test]
+async fn cancellation_stops_the_running_step() {
+    let registry = registry().await;
+    let tool = CompositeTool::builder("c:slow", "Slow, then geocode")
+        .step("wait", "test:slow", json!({}))
+        .step("geo", "geo:lookup", json!({ "place": "Berlin" }))
+        .build(&registry)
+        .unwrap();
+
+    let cancel = CancellationToken::new();
+    let trigger = cancel.clone();
+    tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        trigger.cancel();
+    });
+    let started = Instant::now();
+    let err = tool.call_with_cancel(json!({}), cancel).await.unwrap_err();
+    assert!(matches!(err, ToolError::Cancelled));
+    assert!(started.elapsed() < Duration::from_millis(500));
+    assert!(registry.tool_stats("geo:lookup").is_none());
+}
@@ -0,0 +1,135 @@
+//! Tests for the config manifest: env export, TOML round trip, apply and strict mode
+
+use loom_core::config::{env_var, is_secret, manifest_key};
+use loom_core::LoomConfig;
+use serial_test::serial;
+
+fn vars(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+    pairs
+        .iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect()
+}
+
+const MCP: &str = r#"{"fs": {"command": "npx", "args": ["-y", "server-fs", "/data"]}}"#;
+
+#[test]
+fn variables_map_to_sections_and_back() {
+    for (var, section, key) in [
+        ("LOOM_DASHBOARD", "dashboard", "enabled"),
+        ("LOOM_DASHBOARD_PORT", "dashboard", "port"),
+        ("LOOM_LLM_CACHE_TTL_SECS", "llm_cache", "ttl_secs"),
+        ("LOOM_BRIDGE_ADDR", "bridge", "addr"),
+        ("LOOM_KV_PATH", "loom", "kv_path"),
+        ("VLLM_MODEL", "llm", "model"),
+        ("REQUEST_TIMEOUT_MS", "llm", "request_timeout_ms"),
+        ("TTS_TOPIC", "tts", "topic"),
+        ("MIC_DEVICE", "mic", "device"),
+    ] {
+        assert_eq!(
+            manifest_key(var),
+            Some((section.to_string(), key.to_string())),
+            "{var}"
+        );
+        assert_eq!(env_var(section, key).as_deref(), Some(var));
+    }
+    assert_eq!(manifest_key("PATH"), None);
+    assert_eq!(manifest_key("LOOM_CONFIG"), None);
+    // Owned by [dashboard], so not reachable through [loom]
+    assert_eq!(env_var("loom", "dashboard_port"), None);
+    assert!(is_secret("VLLM_API_KEY"));
+    assert!(!is_secret("VLLM_MODEL"));
+}
+
+#[test]
+fn export_types_values_and_keeps_secrets_out() {
+    let config = LoomConfig::from_vars(
+        vars(&[
+            ("LOOM_DASHBOARD", "true"),
+            ("LOOM_DASHBOARD_PORT", "3030"),
+            ("VLLM_BASE_URL", "http://localhost:8000/v1"),
+            ("VLLM_TEMPERATURE", "0.5"),
+            ("VLLM_API_KEY", "sk-123"),
+            ("LOOM_MCP_SERVERS", MCP),
+            ("HOME", "/root"),
+        ]),
+        false,
+    )
+    .unwrap();
+
+    let dashboard = &config.sections["dashboard"];
+    assert_eq!(dashboard["enabled"], toml::Value::Boolean(true));
+    assert_eq!(dashboard["port"], toml::Value::Integer(3030));
+    assert_eq!(
+        config.sections["llm"]["temperature"],
+        toml::Value::Float(0.5)
+    );
+    assert!(!config.sections["llm"].contains_key("api_key"));
+    assert_eq!(config.env, vec!["VLLM_API_KEY"]);
+    assert_eq!(config.mcp_servers.len(), 1);
+    assert_eq!(config.mcp_servers[0].name, "fs");
+    assert_eq!(config.sections.len(), 2);
+
+    let text = config.to_toml().unwrap();
+    assert!(!text.contains("sk-123"));
+    assert!(text.contains("[[mcp_servers]]"));
+
+    // The manifest stands for the same variables
+    let reloaded = LoomConfig::from_toml(&text).unwrap();
+    let env = reloaded.env_vars().unwrap();
+    assert_eq!(env["LOOM_DASHBOARD"], "true");
+    assert_eq!(env["LOOM_DASHBOARD_PORT"], "3030");
+    assert_eq!(env["VLLM_BASE_URL"], "http://localhost:8000/v1");
+    assert_eq!(env["VLLM_TEMPERATURE"], "0.5");
+    assert!(env["LOOM_MCP_SERVERS"].contains("server-fs"));
+    assert!(!env.contains_key("VLLM_API_KEY"));
+    assert!(reloaded.declared().unwrap().contains("VLLM_API_KEY"));
+
+    let with_secrets = LoomConfig::from_vars(vars(&[("VLLM_API_KEY", "sk-123")]), true).unwrap();
+    assert!(with_secrets.env.is_empty());
+    assert_eq!(
+        with_secrets.sections["llm"]["api_key"],
+        toml::Value::String("sk-123".to_string())
+    );
+}
+
+#[test]
+fn unknown_settings_are_rejected() {
+    let err = LoomConfig::from_toml("[loom]\ndashboard_port = 1\n").unwrap_err();
+    assert!(err.to_string().contains("loom.dashboard_port"));
+    assert!(LoomConfig::from_toml("[nowhere]\nkey = 1\n").is_err());
+}
+
+#[test]
+#[serial]
+fn apply_keeps_set_variables_and_strict_mode_reports_undeclared() {
+    std::env::set_var("LOOM_CFGTEST_SET", "from-env");
+    std::env::remove_var("LOOM_CFGTEST_NEW");
+    std::env::set_var("LOOM_CFGTEST_STRAY", "1");
+
+    let config =
+        LoomConfig::from_toml("[loom]\ncfgtest_set = \"from-manifest\"\ncfgtest_new = 7\n")
+            .unwrap();
+    assert_eq!(config.apply().unwrap(), vec!["LOOM_CFGTEST_NEW"]);
+    assert_eq!(std::env::var("LOOM_CFGTEST_SET").unwrap(), "from-env");
+    assert_eq!(std::env::var("LOOM_CFGTEST_NEW").unwrap(), "7");
+
+    std::env::remove_var("LOOM_CONFIG_STRICT");
+    assert!(config.warn_undeclared().unwrap().is_empty());
+
+    std::env::set_var("LOOM_CONFIG_STRICT", "true");
+    let undeclared = config.warn_undeclared().unwrap();
+    assert!(undeclared.contains(&"LOOM_CFGTEST_STRAY".to_string()));
+    assert!(!undeclared.contains(&"LOOM_CFGTEST_SET".to_string()));
+    assert!(!undeclared.contains(&"LOOM_CFGTEST_NEW".to_string()));
+    assert!(!undeclared.contains(&"LOOM_CONFIG_STRICT".to_string()));
+
+    for var in [
+        "LOOM_CONFIG_STRICT",
+        "LOOM_CFGTEST_SET",
+        "LOOM_CFGTEST_NEW",
+        "LOOM_CFGTEST_STRAY",
+    ] {
+        std::env::remove_var(var);
+    }
+}
@@ -0,0 +1,207 @@
+//! Tests for routed context budgets: the chosen model's window reaches context assembly
+
+use async_trait::async_trait;
+use loom_core::agent::{AgentBehavior, AgentRuntime};
+use loom_core::cognitive::llm::policy::{ModelCostTable, ModelProfile};
+use loom_core::context::builder::TriggerInput;
+use loom_core::context::{AgentContext, MessageRole};
+use loom_core::proto::{Action, AgentConfig, AgentState, Event};
+use loom_core::{EventBus, ModelBudget, ModelRouter, Result, TokenBudget, ToolRegistry};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+fn make_event(event_type: &str, metadata: &[(&str, &str)]) -> Event {
+    Event {
+        id: "e1".to_string(),
+        r#type: event_type.to_string(),
+        timestamp_ms: 0,
+        source: "test".to_string(),
+        metadata: metadata
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect(),
+        payload: vec![],
+        confidence: 1.0,
+        tags: vec![],
+        priority: 0,
+    }
+}
+
+#[tokio::test]
+async fn budgets_follow_the_routed_model() -> Result<()> {
+    let router = ModelRouter::new().await?;
+
+    // Local-only chat runs on the small local model: 4K window, free
+    let decision = router
+        .route(&make_event("chat", &[("privacy", "local-only")]), None)
+        .await?;
+    let budget = router.context_budget(&decision, 256).unwrap();
+    assert_eq!(budget.model, "lightweight_llm");
+    assert_eq!(
+        budget.budget,
+        TokenBudget {
+            max_input_tokens: 3_840,
+            max_output_tokens: 256,
+        }
+    );
+    assert_eq!(budget.input_cost(10_000), 0.0);
+
+    // Background work goes to gpt-4, whose window comes from the tokenizer registry
+    let decision = router
+        .route(
+            &make_event("chat", &[("latency_class", "background")]),
+            None,
+        )
+        .await?;
+    let budget = router.context_budget(&decision, 1_000).unwrap();
+    assert_eq!(budget.model, "gpt-4");
+    assert_eq!(budget.budget.max_input_tokens, 8_192 - 1_000);
+    assert!((budget.input_cost(2_000) - 0.06).abs() < 1e-6);
+    Ok(())
+}
+
+#[tokio::test]
+async fn cost_tables_declare_context_windows() -> Result<()> {
+    let mut table = ModelCostTable::builtin();
+    table.merge(ModelCostTable::from_json(
+        r#"{"lightweight_llm": {"quality": 0.7, "context_window": 1024}}"#,
+    )?);
+    let router = ModelRouter::new().await?.with_cost_table(table);
+
+    let decision = router
+        .route(&make_event("chat", &[("privacy", "local-only")]), None)
+        .await?;
+    // The output reservation never exceeds the window
+    let budget = router.context_budget(&decision, 4_096).unwrap();
+    assert_eq!(budget.budget.max_input_tokens, 0);
+    assert_eq!(budget.budget.max_output_tokens, 1_024);
+
+    assert_eq!(
+        ModelProfile::new(0.0, 0.0, 0.5)
+            .with_context_window(2_048)
+            .context_window,
+        Some(2_048)
+    );
+    Ok(())
+}
+
+#[test]
+fn budgets_round_trip_through_event_metadata() {
+    let budget = ModelBudget {
+        model: "claude-3".to_string(),
+        budget: TokenBudget {
+            max_input_tokens: 190_000,
+            max_output_tokens: 10_000,
+        },
+        input_cost_per_1k: 0.015,
+    };
+    let mut metadata = HashMap::new();
+    budget.to_metadata(&mut metadata);
+    assert_eq!(metadata["routing_model"], "claude-3");
+    assert_eq!(ModelBudget::from_metadata(&metadata), Some(budget.clone()));
+    assert_eq!(ModelBudget::from_metadata(&HashMap::new()), None);
+
+    let trigger = TriggerInput {
+        session_id: "s".to_string(),
+        goal: None,
+        tool_hints: vec![],
+        budget: TokenBudget::default(),
+        model: None,
+    }
+    .with_model_budget(&budget);
+    assert_eq!(trigger.budget, budget.budget);
+    assert_eq!(trigger.model.as_deref(), Some("claude-3"));
+}
+
+#[tokio::test]
+async fn agent_context_fits_small_models() -> Result<()> {
+    let context = AgentContext::with_defaults("session-1", "agent-1");
+    for i in 0..10 {
+        context
+            .record_message(
+                MessageRole::User,
+                &format!("message {} {}", i, "x".repeat(200)),
+            )
+            .await?;
+    }
+
+    let all = context.get_context(None).await?;
+    assert_eq!(all.len(), 10);
+
+    let small = ModelBudget {
+        model: "lightweight_llm".to_string(),
+        budget: TokenBudget {
+            max_input_tokens: 2_000,
+            max_output_tokens: 256,
+        },
+        input_cost_per_1k: 0.0,
+    };
+    let fitted = context.get_context_for_model(None, &small).await?;
+    assert!(!fitted.is_empty());
+    assert!(fitted.len() < all.len());
+    Ok(())
+}
+
+struct CaptureBehavior {
+    seen: Arc<Mutex<Vec<HashMap<String, String>>>>,
+}
+
+#[async_trait]
+impl AgentBehavior for CaptureBehavior {
+    async fn on_event(&mut self, event: Event, _state: &mut AgentState) -> Result<Vec<Action>> {
+        self.seen.lock().unwrap().push(event.metadata);
+        Ok(vec![])
+    }
+
+    async fn on_init(&mut self, _config: &AgentConfig) -> Result<()> {
+        Ok(())
+    }
+
+    async fn on_shutdown(&mut self) -> Result<()> {
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn behaviors_receive_the_routed_budget() -> Result<()> {
+    let bus = Arc::new(EventBus::new().await?);
+    bus.start().await?;
+    let runtime = AgentRuntime::new(
+        Arc::clone(&bus),
+        Arc::new(ToolRegistry::new()),
+        ModelRouter::new().await?,
+    )
+    .await?;
+
+    let seen = Arc::new(Mutex::new(Vec::new()));
+    runtime
+        .create_agent(
+            AgentConfig {
+                agent_id: "budgeted".to_string(),
+                agent_type: "test".to_string(),
+                subscribed_topics: vec!["topic.budget".to_string()],
+                capabilities: vec![],
+                parameters: HashMap::new(),
+            },
+            Box::new(CaptureBehavior {
+                seen: Arc::clone(&seen),
+            }),
+        )
+        .await?;
+
+    bus.publish(
+        "topic.budget",
+        make_event("chat", &[("privacy", "local-only"), ("max_tokens", "512")]),
+    )
+    .await?;
+    tokio::time::sleep(Duration::from_millis(200)).await;
+
+    let seen = seen.lock().unwrap();
+    assert_eq!(seen.len(), 1);
+    let budget = ModelBudget::from_metadata(&seen[0]).expect("budget in metadata");
+    assert_eq!(budget.model, "lightweight_llm");
+    assert_eq!(budget.budget.max_output_tokens, 512);
+    assert_eq!(budget.budget.max_input_tokens, 4_096 - 512);
+    Ok(())
+}
@@ -0,0 +1,226 @@
+//! Tests for the ContextPipeline dedup and hierarchical summarization stage
+
+use async_trait::async_trait;
+use loom_core::context::pipeline::summarize::{is_summary, SUMMARY_COVERS_TAG, SUMMARY_LEVEL_TAG};
+use loom_core::context::{
+    ContextContent, ContextItem, ContextItemType, ContextMetadata, ContextPipeline, MemoryQuery,
+    MemoryStore, MessageRole, PipelineConfig, RecencyRetrieval, RetrievalTrigger,
+    SummarizationConfig, SummarizationStage, Summarizer, TemporalRanker, TiktokenCounter,
+    WindowConfig, WindowManager,
+};
+use loom_core::{InMemoryStore, Result};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+/// Counts calls; each summary is `words` words long
+struct CountingSummarizer {
+    calls: AtomicUsize,
+    words: usize,
+}
+
+impl CountingSummarizer {
+    fn new(words: usize) -> Arc<Self> {
+        Arc::new(Self {
+            calls: AtomicUsize::new(0),
+            words,
+        })
+    }
+
+    fn calls(&self) -> usize {
+        self.calls.load(Ordering::SeqCst)
+    }
+}
+
+#[async_trait]
+impl Summarizer for CountingSummarizer {
+    async fn summarize(&self, texts: &[String], _max_tokens: usize) -> Result<String> {
+        self.calls.fetch_add(1, Ordering::SeqCst);
+        Ok(format!(
+            "Summary of {} items.{}",
+            texts.len(),
+            " beta".repeat(self.words)
+        ))
+    }
+}
+
+fn message(session: &str, i: i64, text: &str) -> ContextItem {
+    let mut metadata = ContextMetadata::new(session.to_string(), "agent".to_string());
+    metadata.timestamp_ms = 1_700_000_000_000 + i * 1_000;
+    ContextItem::new(
+        ContextItemType::Message {
+            role: MessageRole::User,
+        },
+        ContextContent::from_string(text.to_string()),
+        metadata,
+    )
+}
+
+async fn store_conversation(store: &dyn MemoryStore, session: &str, turns: i64) {
+    for i in 0..turns {
+        let text = format!("Message {}:{}", i, " alpha".repeat(20));
+        store.store(message(session, i, &text)).await.unwrap();
+    }
+}
+
+/// 700 available tokens, all of which may go to any item type
+fn small_window() -> WindowManager {
+    WindowManager::new(
+        Arc::new(TiktokenCounter::gpt4()),
+        WindowConfig {
+            max_tokens: 2_700,
+            tool_results_budget: 1.0,
+            messages_budget: 1.0,
+            observations_budget: 1.0,
+            ..Default::default()
+        },
+    )
+}
+
+fn pipeline(store: Arc<dyn MemoryStore>, stage: Arc<SummarizationStage>) -> ContextPipeline {
+    ContextPipeline::new(
+        store,
+        RecencyRetrieval::new(100),
+        TemporalRanker::newest_first(),
+        small_window(),
+        PipelineConfig::default(),
+    )
+    .with_summarization(stage)
+}
+
+fn trigger(session: &str) -> RetrievalTrigger {
+    RetrievalTrigger::new(session.to_string(), "agent".to_string())
+}
+
+#[tokio::test]
+async fn overflow_is_summarized_and_originals_kept() -> Result<()> {
+    let store: Arc<dyn MemoryStore> = InMemoryStore::new();
+    store_conversation(&*store, "s1", 30).await;
+    let summarizer = CountingSummarizer::new(0);
+    let stage = Arc::new(SummarizationStage::new(
+        summarizer.clone(),
+        SummarizationConfig::default(),
+    ));
+
+    let result = pipeline(Arc::clone(&store), Arc::clone(&stage))
+        .execute(trigger("s1"))
+        .await?;
+    assert!(result.summary_count >= 1);
+    assert_eq!(
+        result.items.iter().filter(|i| is_summary(i)).count(),
+        result.summary_count
+    );
+    assert!(result.tokens_used <= result.budget);
+    assert!(!result.overflow_items.is_empty());
+
+    // Every overflowing original is covered by a summary, and none was removed
+    let covered: Vec<String> = result
+        .items
+        .iter()
+        .filter(|i| is_summary(i))
+        .flat_map(|s| {
+            s.metadata.tags[SUMMARY_COVERS_TAG]
+                .split(',')
+                .map(str::to_string)
+                .collect::<Vec<_>>()
+        })
+        .collect();
+    for item in result.overflow_items.iter() {
+        assert!(covered.contains(&item.id));
+    }
+    let calls = summarizer.calls();
+    assert_eq!(store.count().await?, 30 + calls);
+    Ok(())
+}
+
+#[tokio::test]
+async fn summaries_are_cached_by_item_hash() -> Result<()> {
+    let store: Arc<dyn MemoryStore> = InMemoryStore::new();
+    store_conversation(&*store, "s2", 30).await;
+    let summarizer = CountingSummarizer::new(0);
+    let stage = Arc::new(SummarizationStage::new(
+        summarizer.clone(),
+        SummarizationConfig::default(),
+    ));
+
+    let first = pipeline(Arc::clone(&store), Arc::clone(&stage))
+        .execute(trigger("s2"))
+        .await?;
+    let calls = summarizer.calls();
+    assert!(calls > 0);
+    assert_eq!(stage.cached_summaries(), calls);
+
+    // The stored summaries are retrieved now, and dropped as redundant with their originals
+    let second = pipeline(Arc::clone(&store), Arc::clone(&stage))
+        .execute(trigger("s2"))
+        .await?;
+    assert_eq!(summarizer.calls(), calls);
+    assert_eq!(second.deduplicated_count, calls);
+    assert_eq!(second.summary_count, first.summary_count);
+
+    // A fresh stage finds them in the store
+    let fresh = Arc::new(SummarizationStage::new(
+        summarizer.clone(),
+        SummarizationConfig::default(),
+    ));
+    pipeline(Arc::clone(&store), fresh)
+        .execute(trigger("s2"))
+        .await?;
+    assert_eq!(summarizer.calls(), calls);
+    assert_eq!(store.count().await?, 30 + calls);
+    Ok(())
+}
+
+#[tokio::test]
+async fn summaries_that_do_not_fit_are_summarized_again() -> Result<()> {
+    let store: Arc<dyn MemoryStore> = InMemoryStore::new();
+    store_conversation(&*store, "s3", 30).await;
+    let stage = Arc::new(SummarizationStage::new(
+        CountingSummarizer::new(80),
+        SummarizationConfig {
+            summary_budget: 0.4,
+            ..Default::default()
+        },
+    ));
+
+    let result = pipeline(Arc::clone(&store), stage)
+        .execute(trigger("s3"))
+        .await?;
+    let summaries: Vec<&ContextItem> = result.items.iter().filter(|i| is_summary(i)).collect();
+    assert_eq!(summaries.len(), 1);
+    assert_eq!(summaries[0].metadata.tags[SUMMARY_LEVEL_TAG], "2");
+
+    // Level-1 summaries stay in the store, covered by the level-2 one
+    let stored = store
+        .query(&MemoryQuery::new().for_session("s3".to_string()))
+        .await?;
+    let level_one: Vec<&ContextItem> = stored
+        .iter()
+        .filter(|i| is_summary(i) && i.metadata.tags[SUMMARY_LEVEL_TAG] == "1")
+        .collect();
+    assert!(level_one.len() > 1);
+    for summary in level_one {
+        assert!(summaries[0].metadata.tags[SUMMARY_COVERS_TAG].contains(&summary.id));
+    }
+    Ok(())
+}
+
+#[tokio::test]
+async fn repeated_items_are_deduplicated() -> Result<()> {
+    let store: Arc<dyn MemoryStore> = InMemoryStore::new();
+    store.store(message("s4", 0, "Where is my order?")).await?;
+    store.store(message("s4", 1, "where is  my ORDER?")).await?;
+    store.store(message("s4", 2, "It ships tomorrow.")).await?;
+    let summarizer = CountingSummarizer::new(0);
+    let stage = Arc::new(SummarizationStage::new(
+        summarizer.clone(),
+        SummarizationConfig::default(),
+    ));
+
+    let result = pipeline(store, stage).execute(trigger("s4")).await?;
+    assert_eq!(result.deduplicated_count, 1);
+    assert_eq!(result.items.len(), 2);
+    // Nothing overflowed, so nothing was summarized
+    assert_eq!(result.summary_count, 0);
+    assert_eq!(summarizer.calls(), 0);
+    Ok(())
+}
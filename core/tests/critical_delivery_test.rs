@@ -0,0 +1,242 @@
+/// Tests for delivery receipts on critical topics: acks, redelivery, dead-lettering, hand-off
+use async_trait::async_trait;
+use loom_core::messaging::receipts::{RedeliverFn, RECEIPT_ATTEMPT_KEY, RECEIPT_SUBSCRIBER_KEY};
+use loom_core::messaging::reliable::dead_letter_keys;
+use loom_core::messaging::EventHandler;
+use loom_core::proto::{Event, QoSLevel};
+use loom_core::{EventBus, LoomError, ReliableConfig, Result};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::mpsc;
+
+fn make_event(id: &str) -> Event {
+    Event {
+        id: id.to_string(),
+        r#type: "test".to_string(),
+        timestamp_ms: 0,
+        source: "test".to_string(),
+        metadata: HashMap::new(),
+        payload: vec![],
+        confidence: 1.0,
+        tags: vec![],
+        priority: 50,
+    }
+}
+
+async fn recv<T>(rx: &mut mpsc::Receiver<T>) -> T {
+    tokio::time::timeout(Duration::from_secs(1), rx.recv())
+        .await
+        .expect("timed out waiting for delivery")
+        .expect("channel closed")
+}
+
+fn fast(max_deliveries: u32) -> ReliableConfig {
+    ReliableConfig::default()
+        .with_ack_timeout(Duration::from_millis(50))
+        .with_max_deliveries(max_deliveries)
+}
+
+#[tokio::test]
+async fn acked_critical_deliveries_settle() -> Result<()> {
+    let bus = Arc::new(EventBus::new().await?);
+    bus.mark_critical("payments.*", fast(3));
+    assert!(bus.critical_config("payments.card").is_some());
+    assert!(bus.critical_config("payments").is_none());
+
+    let (sub, mut rx) = bus
+        .subscribe("payments.card".into(), vec![], QoSLevel::QosBatched)
+        .await?;
+    bus.publish("payments.card", make_event("p1")).await?;
+
+    let event = recv(&mut rx).await;
+    assert_eq!(
+        event.metadata.get(RECEIPT_SUBSCRIBER_KEY),
+        Some(&sub.to_string())
+    );
+    let outstanding = bus.outstanding_acks();
+    assert_eq!(outstanding.len(), 1);
+    assert_eq!(outstanding[0].subscriber, sub);
+    assert_eq!(outstanding[0].topic, "payments.card");
+    assert_eq!(outstanding[0].count, 1);
+
+    assert!(bus.ack_event(&event));
+    assert!(!bus.ack_event(&event));
+    assert!(bus.outstanding_acks().is_empty());
+
+    tokio::time::sleep(Duration::from_millis(150)).await;
+    assert!(rx.try_recv().is_err());
+    assert_eq!(bus.get_stats("payments.card").unwrap().redelivered, 0);
+    Ok(())
+}
+
+#[tokio::test]
+async fn other_topics_need_no_acks() -> Result<()> {
+    let bus = Arc::new(EventBus::new().await?);
+    bus.mark_critical("payments.*", fast(3));
+    let (_sub, mut rx) = bus
+        .subscribe("orders".into(), vec![], QoSLevel::QosBatched)
+        .await?;
+
+    bus.publish("orders", make_event("o1")).await?;
+    let event = recv(&mut rx).await;
+    assert!(!event.metadata.contains_key(RECEIPT_SUBSCRIBER_KEY));
+    assert_eq!(bus.receipts().outstanding_count(), 0);
+    Ok(())
+}
+
+#[tokio::test]
+async fn unacked_events_are_redelivered_then_dead_lettered() -> Result<()> {
+    let bus = Arc::new(EventBus::new().await?);
+    bus.mark_critical("payments.card", fast(2));
+    let (sub, mut rx) = bus
+        .subscribe("payments.card".into(), vec![], QoSLevel::QosBatched)
+        .await?;
+    let (_dl, mut dead_letters) = bus
+        .subscribe(
+            "dead_letter.payments.card".into(),
+            vec![],
+            QoSLevel::QosBatched,
+        )
+        .await?;
+
+    bus.publish("payments.card", make_event("p1")).await?;
+    let first = recv(&mut rx).await;
+    assert!(!first.metadata.contains_key(RECEIPT_ATTEMPT_KEY));
+
+    let second = recv(&mut rx).await;
+    assert_eq!(second.id, "p1");
+    assert_eq!(
+        second.metadata.get(RECEIPT_ATTEMPT_KEY).map(String::as_str),
+        Some("2")
+    );
+
+    let dead = recv(&mut dead_letters).await;
+    assert_eq!(dead.id, "p1");
+    let meta = |key: &str| dead.metadata.get(key).map(String::as_str);
+    assert_eq!(meta(dead_letter_keys::REASON), Some("ack_timeout"));
+    assert_eq!(meta(dead_letter_keys::ATTEMPTS), Some("2"));
+    assert_eq!(meta(dead_letter_keys::TOPIC), Some("payments.card"));
+    assert_eq!(meta(dead_letter_keys::SUBSCRIPTION), Some(sub.as_str()));
+    assert!(!dead.metadata.contains_key(RECEIPT_SUBSCRIBER_KEY));
+
+    let stats = bus.get_stats("payments.card").unwrap();
+    assert_eq!(stats.redelivered, 1);
+    assert_eq!(stats.dead_lettered, 1);
+    assert!(bus.outstanding_acks().is_empty());
+    Ok(())
+}
+
+#[tokio::test]
+async fn dropped_subscribers_are_dead_lettered() -> Result<()> {
+    let bus = Arc::new(EventBus::new().await?);
+    bus.mark_critical("payments.card", fast(5));
+    let (_sub, rx) = bus
+        .subscribe("payments.card".into(), vec![], QoSLevel::QosBatched)
+        .await?;
+    let (_dl, mut dead_letters) = bus
+        .subscribe(
+            "dead_letter.payments.card".into(),
+            vec![],
+            QoSLevel::QosBatched,
+        )
+        .await?;
+
+    bus.publish("payments.card", make_event("p1")).await?;
+    drop(rx);
+
+    let dead = recv(&mut dead_letters).await;
+    assert_eq!(
+        dead.metadata
+            .get(dead_letter_keys::REASON)
+            .map(String::as_str),
+        Some("subscriber_gone")
+    );
+    assert_eq!(
+        dead.metadata
+            .get(dead_letter_keys::ATTEMPTS)
+            .map(String::as_str),
+        Some("1")
+    );
+    Ok(())
+}
+
+/// Fails the first event it sees, then succeeds
+struct FlakyHandler {
+    calls: AtomicUsize,
+}
+
+#[async_trait]
+impl EventHandler for FlakyHandler {
+    async fn handle(&self, _event: Event) -> Result<()> {
+        if self.calls.fetch_add(1, Ordering::SeqCst) == 0 {
+            return Err(LoomError::EventBusError("not yet".into()));
+        }
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn handlers_ack_on_success() -> Result<()> {
+    let bus = Arc::new(EventBus::new().await?);
+    bus.mark_critical("payments.card", fast(3));
+    let handler = Arc::new(FlakyHandler {
+        calls: AtomicUsize::new(0),
+    });
+    let (_sub, _task) = bus
+        .subscribe_handler(
+            "payments.card".into(),
+            vec![],
+            QoSLevel::QosBatched,
+            handler.clone(),
+        )
+        .await?;
+
+    bus.publish("payments.card", make_event("p1")).await?;
+    tokio::time::sleep(Duration::from_millis(250)).await;
+
+    // The failed first attempt was redelivered; the second one was acked
+    assert_eq!(handler.calls.load(Ordering::SeqCst), 2);
+    assert_eq!(bus.get_stats("payments.card").unwrap().redelivered, 1);
+    assert!(bus.outstanding_acks().is_empty());
+    Ok(())
+}
+
+#[tokio::test]
+async fn handed_off_receipts_are_tracked_per_downstream_subscriber() -> Result<()> {
+    let bus = Arc::new(EventBus::new().await?);
+    bus.mark_critical("payments.card", fast(3));
+    let (sub, mut rx) = bus
+        .subscribe("payments.card".into(), vec![], QoSLevel::QosBatched)
+        .await?;
+
+    bus.publish("payments.card", make_event("p1")).await?;
+    let event = recv(&mut rx).await;
+
+    let (tx_b, mut rx_b) = mpsc::channel(4);
+    let redeliver_a: RedeliverFn = Arc::new(|_| true);
+    let redeliver_b: RedeliverFn = Arc::new(move |event| tx_b.try_send(event).is_ok());
+    assert!(bus.receipts().hand_off(
+        &sub,
+        &event.id,
+        vec![
+            ("agent-a".to_string(), redeliver_a),
+            ("agent-b".to_string(), redeliver_b),
+        ],
+    ));
+    assert!(!bus.receipts().is_pending(&sub, "p1"));
+    assert!(bus.ack("agent-a", "p1"));
+
+    let outstanding = bus.outstanding_acks();
+    assert_eq!(outstanding.len(), 1);
+    assert_eq!(outstanding[0].subscriber, "agent-b");
+
+    // Only the agent that never acked sees the event again
+    let again = recv(&mut rx_b).await;
+    assert_eq!(again.id, "p1");
+    assert!(bus.ack("agent-b", "p1"));
+    assert!(rx.try_recv().is_err());
+    assert_eq!(bus.receipts().outstanding_count(), 0);
+    Ok(())
+}
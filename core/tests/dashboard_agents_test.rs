@@ -0,0 +1,173 @@
+//! Tests for the dashboard agent control endpoints (`/api/agents`)
+
+use async_trait::async_trait;
+use loom_core::agent::directory::AgentDirectory;
+use loom_core::agent::{AgentBehavior, AgentFactory, AgentRuntime, HibernationPolicy};
+use loom_core::dashboard::{DashboardConfig, DashboardServer, EventBroadcaster};
+use loom_core::proto::{Action, AgentConfig, AgentState};
+use loom_core::{Event, EventBus, ModelRouter, Result, SpanCollector, ToolRegistry};
+use serde_json::Value;
+use serial_test::serial;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+const TOKEN: &str = "dashboard-secret";
+
+struct IdleBehavior;
+
+#[async_trait]
+impl AgentBehavior for IdleBehavior {
+    async fn on_event(&mut self, _event: Event, _state: &mut AgentState) -> Result<Vec<Action>> {
+        Ok(vec![])
+    }
+
+    async fn on_init(&mut self, _config: &AgentConfig) -> Result<()> {
+        Ok(())
+    }
+
+    async fn on_shutdown(&mut self) -> Result<()> {
+        Ok(())
+    }
+}
+
+fn config(agent_id: &str) -> AgentConfig {
+    AgentConfig {
+        agent_id: agent_id.to_string(),
+        agent_type: "test".to_string(),
+        subscribed_topics: vec!["jobs".to_string()],
+        capabilities: vec![],
+        parameters: HashMap::new(),
+    }
+}
+
+/// Serve a dashboard over a runtime with a hibernatable `session` and a pinned `pinned`
+async fn serve() -> (String, AgentRuntime) {
+    let bus = Arc::new(EventBus::new().await.unwrap());
+    bus.start().await.unwrap();
+    let runtime = AgentRuntime::new(
+        bus,
+        Arc::new(ToolRegistry::new()),
+        ModelRouter::new().await.unwrap(),
+    )
+    .await
+    .unwrap();
+    let factory: AgentFactory =
+        Arc::new(|_config: &AgentConfig| Ok(Box::new(IdleBehavior) as Box<dyn AgentBehavior>));
+    runtime
+        .create_agent_with_factory(config("session"), factory)
+        .await
+        .unwrap();
+    runtime
+        .create_agent(config("pinned"), Box::new(IdleBehavior))
+        .await
+        .unwrap();
+
+    let app = DashboardServer::new(
+        DashboardConfig {
+            control_token: Some(TOKEN.to_string()),
+            ..Default::default()
+        },
+        EventBroadcaster::new(16),
+        Arc::new(AgentDirectory::new()),
+        SpanCollector::new(),
+    )
+    .with_agent_runtime(runtime.clone())
+    .router();
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let base_url = format!("http://{}", listener.local_addr().unwrap());
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+    (base_url, runtime)
+}
+
+async fn agents(base_url: &str) -> Value {
+    reqwest::get(format!("{}/api/agents", base_url))
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap()
+}
+
+async fn control(base_url: &str, agent_id: &str, operation: &str) -> (u16, Value) {
+    let response = reqwest::Client::new()
+        .post(format!(
+            "{}/api/agents/{}/{}",
+            base_url, agent_id, operation
+        ))
+        .bearer_auth(TOKEN)
+        .send()
+        .await
+        .unwrap();
+    (response.status().as_u16(), response.json().await.unwrap())
+}
+
+async fn wait_hibernated(runtime: &AgentRuntime, hibernated: bool) {
+    let deadline = tokio::time::Instant::now() + Duration::from_secs(2);
+    while runtime.is_agent_hibernated("session").unwrap() != hibernated {
+        assert!(tokio::time::Instant::now() < deadline);
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+}
+
+#[tokio::test]
+#[serial]
+async fn lists_agents_and_refuses_control_by_default() {
+    std::env::remove_var("LOOM_DASHBOARD_AGENT_CONTROL");
+    let (base_url, runtime) = serve().await;
+    runtime.set_hibernation_policy(Some(HibernationPolicy::new(Duration::from_secs(60))));
+
+    let body = agents(&base_url).await;
+    assert_eq!(body["control_enabled"], false);
+    assert_eq!(body["hibernate_after_ms"], 60_000);
+    let listed = body["agents"].as_array().unwrap();
+    assert_eq!(listed.len(), 2);
+    assert_eq!(listed[0]["agent_id"], "pinned");
+    assert_eq!(listed[0]["hibernatable"], false);
+    assert_eq!(listed[1]["agent_id"], "session");
+    assert_eq!(listed[1]["hibernatable"], true);
+    assert_eq!(listed[1]["hibernated"], false);
+    assert!(listed[1]["subscriptions"]
+        .as_array()
+        .unwrap()
+        .contains(&Value::from("jobs")));
+
+    let (status, body) = control(&base_url, "session", "hibernate").await;
+    assert_eq!(status, 403);
+    assert_eq!(body["ok"], false);
+    assert!(!runtime.is_agent_hibernated("session").unwrap());
+}
+
+#[tokio::test]
+#[serial]
+async fn hibernates_wakes_and_restarts_agents() {
+    std::env::set_var("LOOM_DASHBOARD_AGENT_CONTROL", "true");
+    let (base_url, runtime) = serve().await;
+
+    let (status, body) = control(&base_url, "session", "hibernate").await;
+    assert_eq!(status, 202);
+    assert_eq!(body["ok"], true);
+    wait_hibernated(&runtime, true).await;
+    assert_eq!(agents(&base_url).await["agents"][1]["hibernated"], true);
+
+    let (status, _) = control(&base_url, "session", "wake").await;
+    assert_eq!(status, 202);
+    wait_hibernated(&runtime, false).await;
+
+    let (status, _) = control(&base_url, "session", "restart").await;
+    assert_eq!(status, 202);
+    assert_eq!(runtime.agent_count(), 2);
+
+    let (status, body) = control(&base_url, "pinned", "hibernate").await;
+    assert_eq!(status, 409);
+    assert_eq!(body["error"], "agent was not created with a factory");
+    let (status, _) = control(&base_url, "session", "reboot").await;
+    assert_eq!(status, 400);
+    let (status, _) = control(&base_url, "missing", "wake").await;
+    assert_eq!(status, 404);
+
+    std::env::remove_var("LOOM_DASHBOARD_AGENT_CONTROL");
+}
@@ -0,0 +1,221 @@
+//! Tests for the dashboard tool panel endpoints (`/api/tools`)
+
+use async_trait::async_trait;
+use loom_core::agent::directory::AgentDirectory;
+use loom_core::dashboard::{
+    DashboardConfig, DashboardEventType, DashboardServer, EventBroadcaster,
+};
+use loom_core::{SpanCollector, Tool, ToolError, ToolRegistry};
+use serde_json::{json, Value};
+use serial_test::serial;
+use std::sync::Arc;
+
+const TOKEN: &str = "dashboard-secret";
+
+struct UpperTool;
+
+#[async_trait]
+impl Tool for UpperTool {
+    fn name(&self) -> String {
+        "text:upper".to_string()
+    }
+
+    fn description(&self) -> String {
+        "Uppercases text".to_string()
+    }
+
+    fn parameters(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {"text": {"type": "string"}},
+            "required": ["text"]
+        })
+    }
+
+    async fn call(&self, arguments: Value) -> loom_core::tools::ToolResult<Value> {
+        let text = arguments["text"]
+            .as_str()
+            .ok_or_else(|| ToolError::InvalidArguments("text is required".into()))?;
+        Ok(json!({"text": text.to_uppercase()}))
+    }
+}
+
+async fn serve(broadcaster: EventBroadcaster) -> String {
+    serve_with(
+        broadcaster,
+        DashboardConfig {
+            control_token: Some(TOKEN.to_string()),
+            allowed_origins: vec!["https://ops.example".to_string()],
+            ..Default::default()
+        },
+    )
+    .await
+}
+
+async fn serve_with(broadcaster: EventBroadcaster, config: DashboardConfig) -> String {
+    let registry = Arc::new(ToolRegistry::new());
+    registry.register(Arc::new(UpperTool)).await;
+
+    let app = DashboardServer::new(
+        config,
+        broadcaster,
+        Arc::new(AgentDirectory::new()),
+        SpanCollector::new(),
+    )
+    .with_tool_registry(registry)
+    .router();
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let base_url = format!("http://{}", listener.local_addr().unwrap());
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+    base_url
+}
+
+async fn call(base_url: &str, tool: &str, body: Value) -> (u16, Value) {
+    let response = reqwest::Client::new()
+        .post(format!("{}/api/tools/{}/call", base_url, tool))
+        .bearer_auth(TOKEN)
+        .json(&body)
+        .send()
+        .await
+        .unwrap();
+    (response.status().as_u16(), response.json().await.unwrap())
+}
+
+#[tokio::test]
+#[serial]
+async fn lists_tools_with_schemas() {
+    std::env::remove_var("LOOM_DASHBOARD_TOOL_CALLS");
+    let base_url = serve(EventBroadcaster::new(16)).await;
+
+    let body: Value = reqwest::get(format!("{}/api/tools", base_url))
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(body["calls_enabled"], false);
+    let tools = body["tools"].as_array().unwrap();
+    assert_eq!(tools.len(), 1);
+    assert_eq!(tools[0]["name"], "text:upper");
+    assert_eq!(tools[0]["parameters"]["required"][0], "text");
+
+    // Listing is read-only; calling needs an explicit opt-in
+    let (status, body) = call(
+        &base_url,
+        "text:upper",
+        json!({"arguments": {"text": "hi"}}),
+    )
+    .await;
+    assert_eq!(status, 403);
+    assert_eq!(body["ok"], false);
+}
+
+#[tokio::test]
+#[serial]
+async fn calls_tools_and_reports_results() {
+    std::env::set_var("LOOM_DASHBOARD_TOOL_CALLS", "true");
+    let broadcaster = EventBroadcaster::new(16);
+    let mut events = broadcaster.subscribe();
+    let base_url = serve(broadcaster).await;
+
+    let (status, body) = call(
+        &base_url,
+        "text:upper",
+        json!({"arguments": {"text": "hi"}}),
+    )
+    .await;
+    assert_eq!(status, 200);
+    assert_eq!(body["ok"], true);
+    assert_eq!(body["result"]["text"], "HI");
+
+    let event = events.recv().await.unwrap();
+    assert!(matches!(event.event_type, DashboardEventType::ToolInvoked));
+    assert_eq!(event.topic, "tool.text:upper");
+    assert_eq!(event.sender.as_deref(), Some("dashboard"));
+
+    let (status, body) = call(&base_url, "text:upper", json!({})).await;
+    assert_eq!(status, 400);
+    assert_eq!(body["error"], "Invalid arguments: text is required");
+
+    let (status, _) = call(&base_url, "text:missing", json!({})).await;
+    assert_eq!(status, 404);
+
+    // Calls show up in the listed stats
+    let body: Value = reqwest::get(format!("{}/api/tools", base_url))
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(body["calls_enabled"], true);
+    assert_eq!(body["tools"][0]["stats"]["calls"], 2);
+    assert_eq!(body["tools"][0]["stats"]["failures"], 1);
+
+    std::env::remove_var("LOOM_DASHBOARD_TOOL_CALLS");
+}
+
+#[tokio::test]
+#[serial]
+async fn tool_calls_need_the_token_and_an_allowed_origin() {
+    std::env::set_var("LOOM_DASHBOARD_TOOL_CALLS", "true");
+    let base_url = serve(EventBroadcaster::new(16)).await;
+    let url = format!("{}/api/tools/text:upper/call", base_url);
+    let client = reqwest::Client::new();
+    let body = json!({"arguments": {"text": "hi"}});
+
+    let response = client.post(&url).json(&body).send().await.unwrap();
+    assert_eq!(response.status(), 401);
+    let response = client
+        .post(&url)
+        .bearer_auth("guess")
+        .json(&body)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 401);
+
+    // Browsers may only call control routes from the configured origins
+    let preflight = |origin: &'static str| {
+        client
+            .request(reqwest::Method::OPTIONS, &url)
+            .header("origin", origin)
+            .header("access-control-request-method", "POST")
+            .header("access-control-request-headers", "authorization")
+            .send()
+    };
+    let allowed = preflight("https://ops.example").await.unwrap();
+    assert_eq!(
+        allowed.headers()["access-control-allow-origin"],
+        "https://ops.example"
+    );
+    let foreign = preflight("https://evil.example").await.unwrap();
+    assert!(foreign
+        .headers()
+        .get("access-control-allow-origin")
+        .is_none());
+
+    // Read-only routes stay open to any origin
+    let listing = client
+        .get(format!("{}/api/tools", base_url))
+        .header("origin", "https://evil.example")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(listing.headers()["access-control-allow-origin"], "*");
+
+    // Without a configured token the control routes are refused outright
+    let base_url = serve_with(EventBroadcaster::new(16), DashboardConfig::default()).await;
+    let (status, body) = call(
+        &base_url,
+        "text:upper",
+        json!({"arguments": {"text": "hi"}}),
+    )
+    .await;
+    assert_eq!(status, 403);
+    assert_eq!(body["ok"], false);
+
+    std::env::remove_var("LOOM_DASHBOARD_TOOL_CALLS");
+}
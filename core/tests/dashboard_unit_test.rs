@@ -5,10 +5,12 @@
 //! - FlowTracker: Flow graph tracking and cleanup
 //! - TopologyBuilder: Agent topology snapshot generation
 //! - DashboardConfig: Configuration management
+//! - PreviewPolicy: Payload preview caps, redaction, topic opt-out and sampling
 
 use loom_core::agent::directory::AgentDirectory;
 use loom_core::dashboard::{
     DashboardConfig, DashboardEvent, DashboardEventType, EventBroadcaster, FlowTracker, NodeType,
+    PreviewPolicy,
 };
 use std::sync::Arc;
 use tokio::time::{sleep, Duration};
@@ -111,11 +113,13 @@ async fn broadcaster_supports_multiple_event_types() {
     let broadcaster = EventBroadcaster::new(16);
     let mut rx = broadcaster.subscribe();
 
-    let events = vec![
+    let events = [
         DashboardEventType::EventPublished,
         DashboardEventType::EventDelivered,
         DashboardEventType::AgentRegistered,
         DashboardEventType::AgentUnregistered,
+        DashboardEventType::AgentDegraded,
+        DashboardEventType::AgentRecovered,
         DashboardEventType::ToolInvoked,
         DashboardEventType::RoutingDecision,
     ];
@@ -549,3 +553,75 @@ fn dashboard_config_enabled_handles_boolean_string() {
 
     std::env::remove_var("LOOM_DASHBOARD");
 }
+
+// =============================================================================
+// PreviewPolicy Tests
+// =============================================================================
+
+fn published(topic: &str, event_id: &str, preview: &str) -> DashboardEvent {
+    DashboardEvent {
+        timestamp: chrono::Utc::now().to_rfc3339(),
+        event_type: DashboardEventType::EventPublished,
+        event_id: event_id.to_string(),
+        topic: topic.to_string(),
+        sender: None,
+        thread_id: None,
+        correlation_id: None,
+        payload_preview: preview.to_string(),
+        trace_id: String::new(),
+    }
+}
+
+#[test]
+fn preview_policy_redacts_before_capping() {
+    let policy = PreviewPolicy::default()
+        .with_max_chars(40)
+        .with_redaction(r"sk-[A-Za-z0-9]+")
+        .unwrap();
+    let text = format!("{}mail jane.doe@example.com key sk-abc123", " ".repeat(20));
+
+    let preview = policy.apply("chat.out", "evt-1", &text);
+    assert_eq!(preview.chars().count(), 40);
+    assert!(!preview.contains("jane.doe"));
+    assert!(preview.contains("[EMAIL]"));
+
+    let preview = PreviewPolicy::default()
+        .with_redaction(r"sk-[A-Za-z0-9]+")
+        .unwrap()
+        .apply("chat.out", "evt-1", &text);
+    assert!(preview.ends_with("key [REDACTED]"));
+    assert!(PreviewPolicy::default().with_redaction("(").is_err());
+}
+
+#[test]
+fn preview_policy_disables_topics_and_samples_by_id() {
+    let policy = PreviewPolicy::raw(100).with_disabled_topic("secrets.*");
+    assert_eq!(policy.apply("secrets.vault", "evt-1", "hunter2"), "");
+    assert_eq!(policy.apply("chat.out", "evt-1", "hello"), "hello");
+    assert_eq!(
+        PreviewPolicy::raw(0).apply("chat.out", "evt-1", "hello"),
+        ""
+    );
+
+    let sampled = PreviewPolicy::raw(100).with_sample_every(4);
+    let kept = (0..400)
+        .filter(|i| sampled.allows("chat.out", &format!("evt-{}", i)))
+        .count();
+    assert!((50..=150).contains(&kept), "kept {} of 400", kept);
+}
+
+#[tokio::test]
+async fn broadcaster_applies_preview_policy() {
+    let broadcaster = EventBroadcaster::new(8).with_preview_policy(
+        PreviewPolicy::default()
+            .with_max_chars(10)
+            .with_disabled_topic("audio.mic"),
+    );
+    let mut rx = broadcaster.subscribe();
+
+    broadcaster.broadcast(published("chat.out", "evt-1", "call 555-123-4567 now"));
+    broadcaster.broadcast(published("audio.mic", "evt-2", "raw audio"));
+
+    assert_eq!(rx.recv().await.unwrap().payload_preview, "call [PHON");
+    assert_eq!(rx.recv().await.unwrap().payload_preview, "");
+}
@@ -0,0 +1,287 @@
+//! Tests for the dead-letter store: recording, querying, persistence and re-publishing
+
+use loom_core::agent::directory::AgentDirectory;
+use loom_core::dashboard::{DashboardConfig, DashboardServer, EventBroadcaster};
+use loom_core::messaging::dead_letter_reasons;
+use loom_core::proto::{Event, QoSLevel};
+use loom_core::{
+    DeadLetterFilter, DeadLetterStore, EventBus, LoomError, ReliableConfig, Result, SpanCollector,
+};
+use serde_json::{json, Value};
+use serial_test::serial;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+const TOKEN: &str = "dashboard-secret";
+
+fn make_event(id: &str, payload: Value) -> Event {
+    Event {
+        id: id.to_string(),
+        r#type: "test".to_string(),
+        timestamp_ms: 0,
+        source: "test".to_string(),
+        metadata: HashMap::new(),
+        payload: payload.to_string().into_bytes(),
+        confidence: 1.0,
+        tags: vec![],
+        priority: 50,
+    }
+}
+
+async fn recv<T>(rx: &mut tokio::sync::mpsc::Receiver<T>) -> T {
+    tokio::time::timeout(Duration::from_secs(1), rx.recv())
+        .await
+        .expect("timed out waiting for delivery")
+        .expect("channel closed")
+}
+
+#[tokio::test]
+async fn exhausted_and_undeliverable_events_are_kept() -> Result<()> {
+    let bus = Arc::new(EventBus::new().await?);
+    let config = ReliableConfig::default()
+        .with_ack_timeout(Duration::from_secs(10))
+        .with_max_deliveries(2);
+    let (sub_id, mut rx) = bus
+        .subscribe_reliable("orders.*".into(), vec![], config)
+        .await?;
+    bus.publish("orders.eu", make_event("poison", json!({})))
+        .await?;
+    for _ in 0..2 {
+        recv(&mut rx).await.nack();
+    }
+    // Nacks settle on the subscription's task; wait for the dead letter so the
+    // next one is recorded after it
+    tokio::time::timeout(Duration::from_secs(1), async {
+        while bus.dead_letters(&DeadLetterFilter::default()).is_empty() {
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+    })
+    .await
+    .expect("timed out waiting for the dead letter");
+
+    let (_gone, gone_rx) = bus
+        .subscribe("alerts".into(), vec![], QoSLevel::QosBatched)
+        .await?;
+    drop(gone_rx);
+    bus.publish("alerts", make_event("lost", json!({}))).await?;
+
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    let all = bus.dead_letters(&DeadLetterFilter::default());
+    assert_eq!(all.len(), 2);
+    // Newest first
+    assert_eq!(all[0].event.id, "lost");
+    assert_eq!(all[0].reason, dead_letter_reasons::SUBSCRIBER_GONE);
+    assert_eq!(all[0].topic, "alerts");
+    assert_eq!(all[1].event.id, "poison");
+    assert_eq!(all[1].reason, dead_letter_reasons::NACKED);
+    assert_eq!(all[1].attempts, 2);
+    assert_eq!(all[1].topic, "orders.*");
+    assert_eq!(all[1].subscription.as_deref(), Some(sub_id.as_str()));
+    assert!(all[1].dead_lettered_at_ms > 0);
+    // The stored event is the one published, without dead-letter metadata
+    assert!(!all[1]
+        .event
+        .metadata
+        .keys()
+        .any(|k| k.starts_with("dead_letter.")));
+
+    let nacked = bus.dead_letters(&DeadLetterFilter::default().with_reason("nacked"));
+    assert_eq!(nacked.len(), 1);
+    let by_topic = bus.dead_letters(&DeadLetterFilter::default().with_topic("alerts"));
+    assert_eq!(by_topic[0].event.id, "lost");
+    assert_eq!(
+        bus.dead_letters(&DeadLetterFilter::default().with_limit(1))
+            .len(),
+        1
+    );
+    assert_eq!(bus.get_stats("alerts").unwrap().dead_lettered, 1);
+    Ok(())
+}
+
+#[tokio::test]
+async fn payloads_breaking_the_topic_schema_are_rejected() -> Result<()> {
+    let bus = EventBus::new().await?;
+    bus.set_topic_schema(
+        "payments.*",
+        json!({
+            "type": "object",
+            "properties": { "amount": { "type": "number" } },
+            "required": ["amount"]
+        }),
+    );
+    let (_sub, mut rx) = bus
+        .subscribe("payments.card".into(), vec![], QoSLevel::QosBatched)
+        .await?;
+
+    assert_eq!(
+        bus.publish("payments.card", make_event("ok", json!({ "amount": 5 })))
+            .await?,
+        1
+    );
+    let rejected = bus
+        .publish("payments.card", make_event("bad", json!({ "amount": "5" })))
+        .await;
+    assert!(matches!(rejected, Err(LoomError::EventBusError(_))));
+    assert_eq!(recv(&mut rx).await.id, "ok");
+    assert!(rx.try_recv().is_err());
+
+    let letters = bus.dead_letters(&DeadLetterFilter::default());
+    assert_eq!(letters.len(), 1);
+    assert_eq!(letters[0].event.id, "bad");
+    assert_eq!(letters[0].reason, dead_letter_reasons::SCHEMA_INVALID);
+    assert_eq!(letters[0].attempts, 0);
+
+    assert!(bus.remove_topic_schema("payments.*"));
+    assert_eq!(
+        bus.publish("payments.card", make_event("free", json!("anything")))
+            .await?,
+        1
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn republishing_delivers_and_removes_the_dead_letter() -> Result<()> {
+    let bus = Arc::new(EventBus::new().await?);
+    let (_gone, gone_rx) = bus
+        .subscribe("jobs.render".into(), vec![], QoSLevel::QosBatched)
+        .await?;
+    drop(gone_rx);
+    bus.publish("jobs.render", make_event("job-1", json!({})))
+        .await?;
+    let id = bus.dead_letters(&DeadLetterFilter::default())[0].id;
+
+    let (_sub, mut rx) = bus
+        .subscribe("jobs.render".into(), vec![], QoSLevel::QosBatched)
+        .await?;
+    assert_eq!(bus.republish_dead_letter(id, None).await?, 1);
+    assert_eq!(recv(&mut rx).await.id, "job-1");
+    assert!(bus.dead_letter_store().get(id).is_none());
+    assert!(bus.republish_dead_letter(id, None).await.is_err());
+    Ok(())
+}
+
+#[tokio::test]
+async fn store_is_capped_and_survives_reopening() -> Result<()> {
+    let dir = tempfile::tempdir().unwrap();
+    {
+        let mut bus = EventBus::new().await?;
+        bus.set_dead_letter_store(Arc::new(
+            DeadLetterStore::open(dir.path())?.with_capacity(2)?,
+        ));
+        let (_gone, gone_rx) = bus
+            .subscribe("metrics".into(), vec![], QoSLevel::QosBatched)
+            .await?;
+        drop(gone_rx);
+        for i in 0..3 {
+            bus.publish("metrics", make_event(&format!("m{i}"), json!(i)))
+                .await?;
+        }
+        assert_eq!(bus.dead_letter_store().len(), 2);
+    }
+
+    let store = DeadLetterStore::open(dir.path())?;
+    assert!(store.is_persistent());
+    let letters = store.list(&DeadLetterFilter::default());
+    let ids: Vec<_> = letters.iter().map(|l| l.event.id.as_str()).collect();
+    assert_eq!(ids, ["m2", "m1"]);
+    assert_eq!(letters[0].event.payload, b"2");
+    assert_eq!(letters[0].reason, dead_letter_reasons::SUBSCRIBER_GONE);
+    Ok(())
+}
+
+async fn serve_dashboard(bus: Arc<EventBus>) -> String {
+    let app = DashboardServer::new(
+        DashboardConfig {
+            control_token: Some(TOKEN.to_string()),
+            ..Default::default()
+        },
+        EventBroadcaster::new(16),
+        Arc::new(AgentDirectory::new()),
+        SpanCollector::new(),
+    )
+    .with_event_bus(bus)
+    .router();
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let base_url = format!("http://{}", listener.local_addr().unwrap());
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+    base_url
+}
+
+#[tokio::test]
+#[serial]
+async fn dashboard_lists_and_republishes_dead_letters() -> Result<()> {
+    let bus = Arc::new(EventBus::new().await?);
+    let (_gone, gone_rx) = bus
+        .subscribe("orders.eu".into(), vec![], QoSLevel::QosBatched)
+        .await?;
+    drop(gone_rx);
+    bus.publish("orders.eu", make_event("o1", json!({ "sku": "A" })))
+        .await?;
+    bus.publish("orders.eu", make_event("o2", json!({ "sku": "B" })))
+        .await?;
+    let base_url = serve_dashboard(Arc::clone(&bus)).await;
+    let client = reqwest::Client::new();
+
+    std::env::remove_var("LOOM_DASHBOARD_DEAD_LETTER_REPLAY");
+    let body: Value = client
+        .get(format!(
+            "{base_url}/api/dead_letters?topic=orders.*&limit=1"
+        ))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(body["replay_enabled"], false);
+    assert_eq!(body["total"], 2);
+    let listed = body["dead_letters"].as_array().unwrap();
+    assert_eq!(listed.len(), 1);
+    assert_eq!(listed[0]["event_id"], "o2");
+    assert_eq!(listed[0]["reason"], "subscriber_gone");
+    assert_eq!(listed[0]["payload_preview"], r#"{"sku":"B"}"#);
+    let id = listed[0]["id"].as_u64().unwrap();
+
+    let republish = format!("{base_url}/api/dead_letters/{id}/republish");
+    let response = client
+        .post(&republish)
+        .bearer_auth(TOKEN)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 403);
+    let response = client.post(&republish).send().await.unwrap();
+    assert_eq!(response.status(), 401);
+
+    std::env::set_var("LOOM_DASHBOARD_DEAD_LETTER_REPLAY", "true");
+    let (_sub, mut rx) = bus
+        .subscribe("orders.retry".into(), vec![], QoSLevel::QosBatched)
+        .await?;
+    let response = client
+        .post(&republish)
+        .bearer_auth(TOKEN)
+        .json(&json!({ "topic": "orders.retry" }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 200);
+    let body: Value = response.json().await.unwrap();
+    assert_eq!(body["ok"], true);
+    assert_eq!(body["delivered"], 1);
+    assert_eq!(recv(&mut rx).await.id, "o2");
+    assert_eq!(bus.dead_letter_store().len(), 1);
+
+    let response = client
+        .post(&republish)
+        .bearer_auth(TOKEN)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 404);
+    std::env::remove_var("LOOM_DASHBOARD_DEAD_LETTER_REPLAY");
+    Ok(())
+}
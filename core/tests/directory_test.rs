@@ -69,3 +69,158 @@ async fn capability_directory_snapshot() {
     assert_eq!(list.len(), 1);
     assert_eq!(list[0].name, "echo");
 }
+
+#[tokio::test]
+async fn persistent_agent_directory_survives_restart() {
+    let path = tempfile::tempdir().unwrap();
+
+    {
+        let dir = AgentDirectory::with_persistence(path.path()).unwrap();
+        assert!(dir.is_persistent());
+        dir.register_agent(AgentInfo {
+            agent_id: "agent.a".into(),
+            subscribed_topics: vec!["topic.a".into()],
+            capabilities: vec!["echo".into()],
+            metadata: [("region".to_string(), "eu".to_string())].into(),
+            last_heartbeat: None,
+            status: DirectoryAgentStatus::Active,
+        });
+        dir.register_agent(AgentInfo {
+            agent_id: "agent.b".into(),
+            capabilities: vec!["search".into()],
+            ..Default::default()
+        });
+        dir.update_heartbeat("agent.a");
+        dir.update_status("agent.a", DirectoryAgentStatus::Idle);
+        dir.unregister_agent("agent.b");
+    }
+
+    // Reopen: registrations, indices and last-known metadata are restored, but
+    // nothing is connected to the new process yet
+    let dir = AgentDirectory::with_persistence(path.path()).unwrap();
+    assert_eq!(dir.all().len(), 1);
+    let a = dir.get("agent.a").unwrap();
+    assert_eq!(a.status, DirectoryAgentStatus::Disconnected);
+    assert!(a.last_heartbeat.is_some());
+    assert_eq!(a.metadata.get("region").map(String::as_str), Some("eu"));
+    assert_eq!(dir.by_topic("topic.a"), vec!["agent.a".to_string()]);
+    assert_eq!(dir.by_capability("echo"), vec!["agent.a".to_string()]);
+    assert!(dir.by_capability("search").is_empty());
+}
+
+#[tokio::test]
+async fn persistent_directory_registration_before_reads_keeps_stored_agents() {
+    let path = tempfile::tempdir().unwrap();
+    {
+        let dir = AgentDirectory::with_persistence(path.path()).unwrap();
+        dir.register_agent(AgentInfo {
+            agent_id: "agent.old".into(),
+            subscribed_topics: vec!["topic.shared".into()],
+            ..Default::default()
+        });
+    }
+
+    // First access after restart is a write; stored agents are still rehydrated
+    let dir = AgentDirectory::with_persistence(path.path()).unwrap();
+    dir.register_agent(AgentInfo {
+        agent_id: "agent.new".into(),
+        subscribed_topics: vec!["topic.shared".into()],
+        ..Default::default()
+    });
+    let mut ids = dir.by_topic("topic.shared");
+    ids.sort();
+    assert_eq!(ids, vec!["agent.new".to_string(), "agent.old".to_string()]);
+    assert!(!AgentDirectory::new().is_persistent());
+}
+
+#[tokio::test]
+async fn persistent_directory_reconciles_connection_history() {
+    let path = tempfile::tempdir().unwrap();
+    {
+        let dir = AgentDirectory::with_persistence(path.path()).unwrap();
+        dir.register_agent(AgentInfo {
+            agent_id: "agent.a".into(),
+            ..Default::default()
+        });
+        dir.mark_disconnected("agent.a");
+        dir.register_agent(AgentInfo {
+            agent_id: "agent.a".into(),
+            ..Default::default()
+        });
+        dir.update_heartbeat("agent.a");
+        // Process exits with the second connection still open
+    }
+
+    let dir = AgentDirectory::with_persistence(path.path()).unwrap();
+    let history = dir.history("agent.a");
+    assert_eq!(history.len(), 2);
+    let heartbeat = dir.get("agent.a").unwrap().last_heartbeat;
+    assert_eq!(history[1].disconnected_at_ms, heartbeat);
+    assert!(history.iter().all(|r| r.disconnected_at_ms.is_some()));
+
+    // Reconnecting reactivates the agent and opens a third record
+    dir.register_agent(AgentInfo {
+        agent_id: "agent.a".into(),
+        ..Default::default()
+    });
+    assert_eq!(
+        dir.get("agent.a").unwrap().status,
+        DirectoryAgentStatus::Active
+    );
+    let history = dir.history("agent.a");
+    assert_eq!(history.len(), 3);
+    assert!(history[2].disconnected_at_ms.is_none());
+}
+
+#[tokio::test]
+async fn persistent_directory_reads_unversioned_records() {
+    let path = tempfile::tempdir().unwrap();
+    {
+        let mut opts = rocksdb::Options::default();
+        opts.create_if_missing(true);
+        opts.create_missing_column_families(true);
+        let db = rocksdb::DB::open_cf(&opts, path.path(), ["agents"]).unwrap();
+        let cf = db.cf_handle("agents").unwrap();
+        let legacy = json!({
+            "agent_id": "agent.legacy",
+            "capabilities": ["echo"],
+            "status": "Active",
+        });
+        db.put_cf(cf, "agent.legacy", legacy.to_string()).unwrap();
+        let future = json!({"version": 99, "info": {"agent_id": "agent.future"}});
+        db.put_cf(cf, "agent.future", future.to_string()).unwrap();
+    }
+
+    let dir = AgentDirectory::with_persistence(path.path()).unwrap();
+    assert_eq!(dir.all().len(), 1);
+    let legacy = dir.get("agent.legacy").unwrap();
+    assert_eq!(legacy.status, DirectoryAgentStatus::Disconnected);
+    assert!(dir.history("agent.legacy").is_empty());
+    assert_eq!(dir.by_capability("echo"), vec!["agent.legacy".to_string()]);
+}
+
+#[tokio::test]
+async fn disconnected_agents_are_evicted_beyond_limit() {
+    let dir = AgentDirectory::new().with_max_disconnected(1);
+    for id in ["agent.a", "agent.b", "agent.c"] {
+        dir.register_agent(AgentInfo {
+            agent_id: id.into(),
+            subscribed_topics: vec!["topic".into()],
+            ..Default::default()
+        });
+    }
+    dir.mark_disconnected("agent.a");
+    tokio::time::sleep(std::time::Duration::from_millis(5)).await;
+    dir.mark_disconnected("agent.b");
+
+    // agent.a disconnected first and is gone; agent.b is retained
+    assert!(dir.get("agent.a").is_none());
+    assert!(dir.history("agent.a").is_empty());
+    assert_eq!(
+        dir.get("agent.b").unwrap().status,
+        DirectoryAgentStatus::Disconnected
+    );
+    let mut ids = dir.by_topic("topic");
+    ids.sort();
+    assert_eq!(ids, vec!["agent.b".to_string(), "agent.c".to_string()]);
+}
@@ -1,6 +1,9 @@
+use loom_core::messaging::envelope::keys;
+use loom_core::proto::conventions::topics;
 use loom_core::proto::{ActionCall, Event, QoSLevel};
-use loom_core::{Envelope, ThreadTopicKind};
+use loom_core::{agent_reply_topic, Envelope, ThreadTopicKind};
 use std::collections::HashMap;
+use std::time::Duration;
 
 fn dummy_event(id: &str) -> Event {
     Event {
@@ -95,3 +98,100 @@ fn thread_topic_helpers() {
     assert_eq!(env.broadcast_topic(), "thread.abc.broadcast");
     assert_eq!(env.reply_topic(), "thread.abc.reply");
 }
+
+#[test]
+fn v2_fields_are_written_only_when_set() {
+    let mut meta = HashMap::new();
+    Envelope::new("threadV1", "agent.a").apply_to_metadata(&mut meta);
+    assert!(!meta.contains_key(keys::CAUSED_BY));
+    assert!(!meta.contains_key(keys::DEADLINE_MS));
+    assert!(!meta.contains_key(keys::REPLY_EXPECTED));
+
+    let env = Envelope::new("threadV2", "agent.a")
+        .with_caused_by("evt-0")
+        .with_deadline_ms(1_700_000_000_000)
+        .with_reply_expected(true);
+    let mut evt = dummy_event("e2");
+    env.attach_to_event(&mut evt);
+    assert_eq!(
+        evt.metadata.get(keys::CAUSED_BY),
+        Some(&"evt-0".to_string())
+    );
+    assert_eq!(
+        evt.metadata.get(keys::DEADLINE_MS),
+        Some(&"1700000000000".to_string())
+    );
+    assert_eq!(
+        evt.metadata.get(keys::REPLY_EXPECTED),
+        Some(&"true".to_string())
+    );
+    assert_eq!(Envelope::from_event(&evt), env);
+}
+
+#[test]
+fn v1_json_deserializes_with_unset_v2_fields() {
+    let json = r#"{"thread_id":"t","correlation_id":"t","sender":"agent.a",
+        "reply_to":"thread.t.reply","ttl":16,"hop":0,"timestamp_ms":1}"#;
+    let env: Envelope = serde_json::from_str(json).unwrap();
+    assert!(env.caused_by.is_empty());
+    assert_eq!(env.deadline_ms, None);
+    assert!(!env.reply_expected);
+
+    // Unset fields are not serialized either
+    let value = serde_json::to_value(&env).unwrap();
+    assert!(value.get("caused_by").is_none());
+    assert!(value.get("deadline_ms").is_none());
+    assert!(value.get("reply_expected").is_none());
+}
+
+#[test]
+fn deadlines_expire() {
+    let env = Envelope::new("threadD", "agent.a");
+    assert_eq!(env.remaining(), None);
+    assert!(!env.is_expired());
+
+    let live = env.clone().with_timeout(Duration::from_secs(60));
+    assert!(!live.is_expired());
+    assert!(live.remaining().unwrap() > Duration::from_secs(59));
+
+    let past = env.with_deadline_ms(chrono::Utc::now().timestamp_millis() - 1);
+    assert!(past.is_expired());
+    assert_eq!(past.remaining(), Some(Duration::ZERO));
+}
+
+#[test]
+fn caused_by_derives_the_response_envelope() {
+    let mut request = Envelope::new("threadC", "agent.requester")
+        .with_timeout(Duration::from_secs(5))
+        .with_reply_expected(true);
+    request.next_hop();
+    let reply = request.caused_by("evt-req", "agent.worker");
+    assert_eq!(reply.thread_id, request.thread_id);
+    assert_eq!(reply.correlation_id, request.correlation_id);
+    assert_eq!(reply.reply_to, request.reply_to);
+    assert_eq!(reply.ttl, request.ttl);
+    assert_eq!(reply.deadline_ms, request.deadline_ms);
+    assert_eq!(reply.caused_by, "evt-req");
+    assert_eq!(reply.sender, "agent.worker");
+    assert!(!reply.reply_expected);
+}
+
+#[test]
+fn topic_helpers_follow_the_shared_templates() {
+    // SDKs build these topics from the templates in loom_proto::conventions
+    assert_eq!(
+        agent_reply_topic("w1"),
+        topics::AGENT_REPLIES.replace("{agent_id}", "w1")
+    );
+    assert_eq!(
+        ThreadTopicKind::Broadcast.topic("t1"),
+        topics::THREAD_BROADCAST.replace("{thread_id}", "t1")
+    );
+    assert_eq!(
+        ThreadTopicKind::Reply.topic("t1"),
+        topics::THREAD_REPLY.replace("{thread_id}", "t1")
+    );
+    assert!(keys::ALL
+        .iter()
+        .any(|(name, value, _)| *name == "CORRELATION_ID" && *value == keys::CORRELATION_ID));
+}
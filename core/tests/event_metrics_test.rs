@@ -0,0 +1,172 @@
+//! Tests for event-to-metric extraction rules
+
+use loom_core::proto::Event;
+use loom_core::{EventBus, EventMetrics, LoomError, MetricKind, MetricRule, Result};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+const RULES: &str = r#"
+[[rules]]
+name = "tts.errors"
+topic = "tts.*"
+event_type = "tts.error"
+labels = ["voice", "topic"]
+
+[[rules]]
+name = "tts.synthesis_ms"
+kind = "histogram"
+event_type = "tts.done"
+value = "metadata.synthesis_ms"
+unit = "ms"
+where = { status = "ok" }
+
+[[rules]]
+name = "tts.characters"
+event_type = "tts.done"
+value = "payload.stats.chars"
+"#;
+
+fn make_event(event_type: &str, metadata: &[(&str, &str)], payload: &str) -> Event {
+    Event {
+        id: format!("evt-{}", event_type),
+        r#type: event_type.to_string(),
+        timestamp_ms: 0,
+        source: "tts-agent".to_string(),
+        metadata: metadata
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect::<HashMap<_, _>>(),
+        payload: payload.as_bytes().to_vec(),
+        confidence: 1.0,
+        tags: vec![],
+        priority: 50,
+    }
+}
+
+fn stats(metrics: &EventMetrics) -> HashMap<String, (u64, u64, f64)> {
+    metrics
+        .stats()
+        .into_iter()
+        .map(|s| (s.name, (s.matched, s.skipped, s.total)))
+        .collect()
+}
+
+#[test]
+fn rules_parse_from_toml() {
+    let metrics = EventMetrics::from_toml(RULES).unwrap();
+    let rules: Vec<&MetricRule> = metrics.rules().collect();
+    assert_eq!(rules.len(), 3);
+    assert_eq!(rules[0].kind, MetricKind::Counter);
+    assert_eq!(rules[1].kind, MetricKind::Histogram);
+    assert_eq!(rules[1].where_metadata["status"], "ok");
+
+    for bad in [
+        "[[rules]]\nname = \"\"",
+        "[[rules]]\nname = \"latency\"\nkind = \"histogram\"",
+        "[[rules]]\nname = \"x\"\nvalue = \"headers.foo\"",
+        "[[rules]]\nname = \"x\"\nkind = \"gauge\"",
+        "[[rules]]\nname = \"x\"\n[[rules]]\nname = \"x\"",
+        "rules = 3",
+    ] {
+        assert!(
+            matches!(
+                EventMetrics::from_toml(bad),
+                Err(LoomError::MetricsError(_))
+            ),
+            "{bad:?} should be rejected"
+        );
+    }
+}
+
+#[test]
+fn rules_extract_values_from_events() {
+    let metrics = EventMetrics::from_toml(RULES).unwrap();
+
+    metrics.observe(
+        "tts.out",
+        &make_event("tts.error", &[("voice", "alto")], ""),
+    );
+    metrics.observe("tts.out", &make_event("tts.error", &[], ""));
+    // Outside the topic pattern
+    metrics.observe("asr.out", &make_event("tts.error", &[], ""));
+
+    metrics.observe(
+        "tts.out",
+        &make_event(
+            "tts.done",
+            &[("status", "ok"), ("synthesis_ms", "120.5")],
+            r#"{"stats": {"chars": 42}}"#,
+        ),
+    );
+    metrics.observe(
+        "tts.out",
+        &make_event(
+            "tts.done",
+            &[("status", "ok"), ("synthesis_ms", "fast")],
+            r#"{"stats": {"chars": "8"}}"#,
+        ),
+    );
+    // Filtered out by `where`; payload has no stats
+    metrics.observe(
+        "tts.out",
+        &make_event(
+            "tts.done",
+            &[("status", "failed"), ("synthesis_ms", "900")],
+            "not json",
+        ),
+    );
+
+    let stats = stats(&metrics);
+    assert_eq!(stats["tts.errors"], (2, 0, 2.0));
+    assert_eq!(stats["tts.synthesis_ms"], (2, 1, 120.5));
+    assert_eq!(stats["tts.characters"], (3, 1, 50.0));
+}
+
+#[test]
+fn builders_cover_builtin_value_sources() {
+    let metrics = EventMetrics::new(vec![
+        MetricRule::histogram("agent.confidence", "confidence").with_event_type("reply"),
+        MetricRule::counter("agent.payload_bytes")
+            .with_value("payload_bytes")
+            .with_topic("agent.*"),
+        MetricRule::histogram("agent.first_score", "payload.scores.0"),
+        MetricRule::counter("agent.debits")
+            .with_value("metadata.delta")
+            .with_where("kind", "debit")
+            .with_labels(&["source"]),
+    ])
+    .unwrap();
+
+    let mut reply = make_event(
+        "reply",
+        &[("kind", "debit"), ("delta", "-3")],
+        r#"{"scores": [0.25, 0.5]}"#,
+    );
+    reply.confidence = 0.75;
+    metrics.observe("agent.bob", &reply);
+
+    let stats = stats(&metrics);
+    assert_eq!(stats["agent.confidence"], (1, 0, 0.75));
+    assert_eq!(stats["agent.payload_bytes"].2, reply.payload.len() as f64);
+    assert_eq!(stats["agent.first_score"], (1, 0, 0.25));
+    // Counters never go down
+    assert_eq!(stats["agent.debits"], (1, 1, 0.0));
+}
+
+#[tokio::test]
+async fn event_bus_evaluates_rules_on_publish() -> Result<()> {
+    let metrics = Arc::new(EventMetrics::from_toml(RULES)?);
+    let mut bus = EventBus::new().await?;
+    bus.set_event_metrics(Arc::clone(&metrics));
+    bus.start().await?;
+
+    for _ in 0..3 {
+        bus.publish("tts.out", make_event("tts.error", &[("voice", "bass")], ""))
+            .await?;
+    }
+    bus.publish("chat.out", make_event("message", &[], ""))
+        .await?;
+
+    assert_eq!(stats(&metrics)["tts.errors"], (3, 0, 3.0));
+    Ok(())
+}
@@ -10,6 +10,7 @@
 /// - `latency`: P50/P99 latency measurement tests
 /// - `filtering`: Event type filtering tests
 /// - `stats`: Statistics tracking accuracy tests
+/// - `personas`: Contract net, fanout and directory lookups against scripted personas
 ///
 /// Note: Tests use `serial_test` to avoid resource conflicts.
 /// Run with: cargo test --test event_pressure_test -- --test-threads=1
@@ -145,7 +145,7 @@ async fn qos_batched_queues_without_drop_within_capacity() -> Result<()> {
 
     // Drain all
     let mut received_count = 0;
-    while let Ok(_) = rx.try_recv() {
+    while rx.try_recv().is_ok() {
         received_count += 1;
     }
 
@@ -234,6 +234,38 @@ async fn stats_track_published_and_delivered() -> Result<()> {
     Ok(())
 }
 
+#[tokio::test]
+async fn publish_batch_delivers_in_order() -> Result<()> {
+    let bus = EventBus::new().await?;
+    let (_sub_id, mut rx) = bus
+        .subscribe("topic.batch".to_string(), vec![], QoSLevel::QosBatched)
+        .await?;
+
+    let events = (0..5)
+        .map(|i| make_event(&format!("b{}", i), "unit"))
+        .collect();
+    assert_eq!(bus.publish_batch("topic.batch", events).await?, 5);
+
+    for i in 0..5 {
+        assert_eq!(rx.try_recv().expect("delivered").id, format!("b{}", i));
+    }
+    assert_eq!(
+        bus.get_stats("topic.batch").expect("stats").total_published,
+        5
+    );
+
+    // One event from another tenant rejects the whole batch
+    let mut foreign = make_event("b5", "unit");
+    foreign.metadata.insert(
+        loom_core::tenancy::TENANT_KEY.to_string(),
+        "acme".to_string(),
+    );
+    let batch = vec![make_event("b6", "unit"), foreign];
+    assert!(bus.publish_batch("topic.batch", batch).await.is_err());
+    assert!(rx.try_recv().is_err());
+    Ok(())
+}
+
 #[tokio::test]
 async fn shutdown_clears_subscriptions() -> Result<()> {
     let bus = EventBus::new().await?;
@@ -167,3 +167,31 @@ async fn test_read_write_roundtrip() {
     let result = read_tool.call(json!({"path": "test.txt"})).await.unwrap();
     assert_eq!(result["content"].as_str().unwrap(), content);
 }
+
+#[tokio::test]
+async fn test_absolute_and_parent_paths_blocked() {
+    let workspace = create_temp_workspace();
+    let read_tool = ReadFileTool::new(workspace.path().to_path_buf());
+    let write_tool = WriteFileTool::new(workspace.path().to_path_buf());
+
+    assert!(read_tool
+        .call(json!({"path": "/etc/hostname"}))
+        .await
+        .is_err());
+    assert!(read_tool
+        .call(json!({"path": "sub/../../outside.txt"}))
+        .await
+        .is_err());
+    assert!(write_tool
+        .call(json!({"path": "../escaped.txt", "content": "x"}))
+        .await
+        .is_err());
+    assert!(!workspace.path().join("../escaped.txt").exists());
+
+    // `..` that stays inside the workspace is fine
+    write_tool
+        .call(json!({"path": "sub/../inside.txt", "content": "ok"}))
+        .await
+        .unwrap();
+    assert!(workspace.path().join("inside.txt").exists());
+}
@@ -0,0 +1,359 @@
+//! Tests for cognitive guardrails: builtin filters, config and `SimpleCognitiveLoop` wiring
+
+use async_trait::async_trait;
+use loom_core::cognitive::{
+    CognitiveConfig, CognitiveLoop, FilterAction, GuardStage, Guardrail, GuardrailConfig,
+    GuardrailKind, Guardrails, KeywordFilter, MaxLength, PiiKind, PiiRedactor, Plan, RegexFilter,
+    ThoughtStep, ToolCall, Verdict,
+};
+use loom_core::proto::{AgentState, Event};
+use loom_core::tools::ToolResult;
+use loom_core::{LlmClient, SimpleCognitiveLoop, Tool, ToolRegistry};
+use serde_json::{json, Value};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+fn make_event(payload: &str) -> Event {
+    Event {
+        id: "e1".to_string(),
+        r#type: "test.message".to_string(),
+        timestamp_ms: 0,
+        source: "test".to_string(),
+        metadata: Default::default(),
+        payload: payload.as_bytes().to_vec(),
+        confidence: 1.0,
+        tags: vec![],
+        priority: 50,
+    }
+}
+
+fn make_state() -> AgentState {
+    AgentState {
+        agent_id: "agent1".to_string(),
+        persistent_state: vec![],
+        ephemeral_context: vec![],
+        last_update_ms: 0,
+        metadata: Default::default(),
+    }
+}
+
+fn make_loop(config: CognitiveConfig, tools: Arc<ToolRegistry>) -> SimpleCognitiveLoop {
+    let config = CognitiveConfig {
+        refine_after_tools: false,
+        ..config
+    };
+    SimpleCognitiveLoop::new(config, Arc::new(LlmClient::from_env().unwrap()), tools)
+}
+
+#[tokio::test]
+async fn keyword_filter_matches_whole_words_case_insensitively() {
+    let block = KeywordFilter::new(["secret"], FilterAction::Block);
+    assert!(matches!(
+        block.inspect(GuardStage::Input, "the SECRET plan").await,
+        Verdict::Block(_)
+    ));
+    assert_eq!(
+        block.inspect(GuardStage::Input, "secretary notes").await,
+        Verdict::Allow
+    );
+
+    let mask = KeywordFilter::new(["darn", "heck"], FilterAction::Mask);
+    assert_eq!(
+        mask.inspect(GuardStage::Output, "Darn it, what the heck")
+            .await,
+        Verdict::Modify("**** it, what the ****".to_string())
+    );
+}
+
+#[tokio::test]
+async fn regex_and_length_filters() {
+    let filter = RegexFilter::new(r"sk-[A-Za-z0-9]{8,}", FilterAction::Mask).unwrap();
+    assert_eq!(
+        filter
+            .inspect(GuardStage::Output, "key sk-abcdef123456")
+            .await,
+        Verdict::Modify("key [REDACTED]".to_string())
+    );
+    assert!(RegexFilter::new("(unclosed", FilterAction::Block).is_err());
+
+    let truncate = MaxLength::new(5, true);
+    assert_eq!(
+        truncate.inspect(GuardStage::Input, "héllo world").await,
+        Verdict::Modify("héllo".to_string())
+    );
+    assert!(matches!(
+        MaxLength::new(5, false)
+            .inspect(GuardStage::Input, "héllo world")
+            .await,
+        Verdict::Block(_)
+    ));
+}
+
+#[test]
+fn pii_redactor_replaces_personal_data() {
+    let pii = PiiRedactor::all();
+    assert_eq!(
+        pii.redact("mail jo@example.com or call 555-123-4567"),
+        "mail [EMAIL] or call [PHONE]"
+    );
+    assert_eq!(pii.redact("ssn 123-45-6789"), "ssn [SSN]");
+    assert_eq!(
+        pii.redact("card 4111 1111 1111 1111 from 10.0.0.12"),
+        "card [CREDIT_CARD] from [IP_ADDRESS]"
+    );
+    // Digit runs that fail the Luhn check are not cards
+    assert_eq!(pii.redact("order 1234567890123"), "order 1234567890123");
+}
+
+#[tokio::test]
+async fn chain_applies_rewrites_in_order_and_stops_at_blocks() {
+    let config = GuardrailConfig::default()
+        .with_rule(
+            GuardrailKind::Pii {
+                kinds: PiiKind::ALL.to_vec(),
+            },
+            &[],
+        )
+        .with_rule(
+            GuardrailKind::Keywords {
+                words: vec!["drop table".to_string()],
+                action: FilterAction::Block,
+            },
+            &[GuardStage::ToolArguments],
+        );
+    let mut guardrails = Guardrails::from_config(&config).unwrap();
+    guardrails.add(
+        &[GuardStage::Output],
+        Arc::new(PiiRedactor::new(&[PiiKind::Email])),
+    );
+
+    assert_eq!(
+        guardrails
+            .check(GuardStage::Output, "reach me at jo@example.com")
+            .await
+            .unwrap(),
+        "reach me at [EMAIL]"
+    );
+    assert_eq!(
+        guardrails
+            .check(GuardStage::Input, "please drop table users")
+            .await
+            .unwrap(),
+        "please drop table users"
+    );
+
+    let violation = guardrails
+        .check_arguments(&json!({"queries": [{"sql": "DROP TABLE users"}]}))
+        .await
+        .unwrap_err();
+    assert_eq!(violation.guardrail, "keywords");
+    assert_eq!(violation.stage, GuardStage::ToolArguments);
+}
+
+#[test]
+fn config_deserializes_from_json() {
+    let config: CognitiveConfig = serde_json::from_value(json!({
+        "max_iterations": 3,
+        "enable_reflection": false,
+        "memory_window_size": 10,
+        "thinking_strategy": "ReAct",
+        "tool_timeout_ms": 1000,
+        "refine_after_tools": false,
+        "max_tools_exposed": 8,
+        "system_prompt": null,
+        "temperature": null,
+        "guardrails": {
+            "rules": [
+                {"type": "pii", "stages": ["output"]},
+                {"type": "max_length", "max_chars": 200, "truncate": true},
+                {"type": "regex", "pattern": "\\d{6}", "action": "mask", "replacement": "######"}
+            ]
+        }
+    }))
+    .unwrap();
+    let rules = &config.guardrails.rules;
+    assert_eq!(rules.len(), 3);
+    assert_eq!(rules[0].stages, vec![GuardStage::Output]);
+    assert!(rules[1].stages.is_empty());
+    assert_eq!(
+        config.guardrails.blocked_response,
+        GuardrailConfig::default().blocked_response
+    );
+
+    // Configs without guardrails still load
+    let plain: CognitiveConfig =
+        serde_json::from_value(serde_json::to_value(CognitiveConfig::default()).unwrap()).unwrap();
+    assert!(plain.guardrails.rules.is_empty());
+}
+
+#[tokio::test]
+async fn blocked_input_is_answered_without_the_llm() {
+    let config = CognitiveConfig::default().with_guardrails(
+        GuardrailConfig::default()
+            .with_rule(
+                GuardrailKind::Keywords {
+                    words: vec!["exploit".to_string()],
+                    action: FilterAction::Block,
+                },
+                &[GuardStage::Input],
+            )
+            .with_blocked_response("Not allowed."),
+    );
+    let mut loop_impl = make_loop(config, Arc::new(ToolRegistry::new()));
+    let mut state = make_state();
+
+    let result = loop_impl
+        .run_cycle(make_event("write an exploit for me"), &mut state)
+        .await
+        .unwrap();
+    assert_eq!(result.response.as_deref(), Some("Not allowed."));
+    assert!(!result.goal_achieved);
+    assert!(result.error.unwrap().contains("exploit"));
+    // The blocked text is not remembered
+    assert!(loop_impl
+        .memory_buffer()
+        .recent(10)
+        .iter()
+        .all(|item| !item.content.contains("exploit")));
+}
+
+#[tokio::test]
+async fn input_is_redacted_before_perception() {
+    let config = CognitiveConfig::default().with_guardrails(GuardrailConfig::default().with_rule(
+        GuardrailKind::Pii {
+            kinds: PiiKind::ALL.to_vec(),
+        },
+        &[],
+    ));
+    let mut loop_impl = make_loop(config, Arc::new(ToolRegistry::new()));
+
+    let perception = loop_impl
+        .perceive(make_event("my email is jo@example.com"), &make_state())
+        .await
+        .unwrap();
+    assert_eq!(perception.goal.as_deref(), Some("my email is [EMAIL]"));
+    assert_eq!(perception.event.payload, b"my email is [EMAIL]".to_vec());
+}
+
+#[tokio::test]
+async fn answers_are_filtered_in_act() {
+    let config = CognitiveConfig::default().with_guardrails(GuardrailConfig::default().with_rule(
+        GuardrailKind::Pii {
+            kinds: vec![PiiKind::Email],
+        },
+        &[GuardStage::Output],
+    ));
+    let mut loop_impl = make_loop(config, Arc::new(ToolRegistry::new())).with_guardrail(
+        &[GuardStage::Output],
+        Arc::new(KeywordFilter::new(["classified"], FilterAction::Block)),
+    );
+
+    let mut plan = Plan::with_goal("contact");
+    plan.complete_with_answer("Write to ops@example.com");
+    let result = loop_impl.act(&plan, &mut make_state()).await.unwrap();
+    assert_eq!(result.response.as_deref(), Some("Write to [EMAIL]"));
+    assert!(result.error.is_none());
+
+    let mut plan = Plan::with_goal("leak");
+    plan.complete_with_answer("That is classified.");
+    let result = loop_impl.act(&plan, &mut make_state()).await.unwrap();
+    assert_eq!(
+        result.response,
+        Some(GuardrailConfig::default().blocked_response)
+    );
+    assert!(!result.goal_achieved);
+}
+
+/// Echoes its arguments and counts calls
+struct EchoTool {
+    calls: AtomicUsize,
+}
+
+#[async_trait]
+impl Tool for EchoTool {
+    fn name(&self) -> String {
+        "test:echo".to_string()
+    }
+
+    fn description(&self) -> String {
+        "Echo the arguments".to_string()
+    }
+
+    fn parameters(&self) -> Value {
+        json!({ "type": "object" })
+    }
+
+    async fn call(&self, arguments: Value) -> ToolResult<Value> {
+        self.calls.fetch_add(1, Ordering::SeqCst);
+        Ok(arguments)
+    }
+}
+
+#[tokio::test]
+async fn tool_arguments_are_guarded_before_execution() {
+    let echo = Arc::new(EchoTool {
+        calls: AtomicUsize::new(0),
+    });
+    let tools = Arc::new(ToolRegistry::new());
+    tools.register(echo.clone()).await;
+
+    let config = CognitiveConfig::react().with_guardrails(
+        GuardrailConfig::default()
+            .with_rule(
+                GuardrailKind::Keywords {
+                    words: vec!["rm".to_string()],
+                    action: FilterAction::Block,
+                },
+                &[GuardStage::ToolArguments],
+            )
+            .with_rule(
+                GuardrailKind::Pii {
+                    kinds: vec![PiiKind::Ssn],
+                },
+                &[GuardStage::ToolArguments],
+            ),
+    );
+    let mut loop_impl = make_loop(config, tools);
+
+    let mut plan = Plan::with_goal("run");
+    plan.add_step(ThoughtStep::with_tool(
+        1,
+        "look up",
+        ToolCall::new("test:echo", json!({"ssn": "123-45-6789"})),
+    ));
+    plan.add_step(ThoughtStep::with_tool(
+        2,
+        "clean up",
+        ToolCall::new("test:echo", json!({"cmd": "rm -rf /"})),
+    ));
+    loop_impl.act(&plan, &mut make_state()).await.unwrap();
+
+    // Only the allowed call ran, with redacted arguments
+    assert_eq!(echo.calls.load(Ordering::SeqCst), 1);
+    let remembered: Vec<String> = loop_impl
+        .memory_buffer()
+        .recent(10)
+        .into_iter()
+        .map(|item| item.content.clone())
+        .collect();
+    assert!(remembered.iter().any(|c| c.contains("[SSN]")));
+    assert!(remembered.iter().all(|c| !c.contains("123-45-6789")));
+}
+
+#[tokio::test]
+async fn invalid_config_fails_closed() {
+    let config = CognitiveConfig::default().with_guardrails(GuardrailConfig::default().with_rule(
+        GuardrailKind::Regex {
+            pattern: "(".to_string(),
+            action: FilterAction::Block,
+            replacement: None,
+        },
+        &[GuardStage::Output],
+    ));
+    let mut loop_impl = make_loop(config, Arc::new(ToolRegistry::new()));
+    let result = loop_impl
+        .run_cycle(make_event("hello"), &mut make_state())
+        .await
+        .unwrap();
+    assert!(result.error.unwrap().contains("invalid guardrail config"));
+}
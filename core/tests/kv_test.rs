@@ -0,0 +1,186 @@
+//! Tests for the key-value scratch store and the `kv:*` tools
+
+use loom_core::tools::native::kv_tools;
+use loom_core::tools::{with_caller, ToolError};
+use loom_core::{KvPolicy, KvQuotas, KvStore, LoomError, Tool};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+fn tools(store: Arc<KvStore>, policy: KvPolicy) -> HashMap<String, Arc<dyn Tool>> {
+    kv_tools(store, Arc::new(policy))
+        .into_iter()
+        .map(|tool| (tool.name(), tool))
+        .collect()
+}
+
+#[test]
+fn store_persists_across_reopen() {
+    let dir = tempfile::tempdir().unwrap();
+    {
+        let store = KvStore::open(dir.path()).unwrap();
+        assert!(store.is_persistent());
+        store
+            .set("planner", "cursor", json!({ "page": 3 }), None)
+            .unwrap();
+        store.set("planner", "draft", json!("v1"), None).unwrap();
+        store.set("other", "cursor", json!(1), None).unwrap();
+    }
+
+    let store = KvStore::open(dir.path()).unwrap();
+    assert_eq!(
+        store.get("planner", "cursor").unwrap().unwrap().value,
+        json!({ "page": 3 })
+    );
+    assert_eq!(store.list("planner", "").unwrap(), ["cursor", "draft"]);
+    assert_eq!(store.list("planner", "dr").unwrap(), ["draft"]);
+
+    assert!(store.delete("planner", "draft").unwrap());
+    assert!(!store.delete("planner", "draft").unwrap());
+    assert_eq!(store.list("other", "").unwrap(), ["cursor"]);
+}
+
+#[test]
+fn expired_entries_disappear() {
+    let store = KvStore::in_memory();
+    store
+        .set("a", "short", json!(1), Some(Duration::from_millis(20)))
+        .unwrap();
+    store
+        .set("a", "long", json!(2), Some(Duration::from_secs(60)))
+        .unwrap();
+    store
+        .set("b", "short", json!(3), Some(Duration::ZERO))
+        .unwrap();
+    assert!(store.get("a", "short").unwrap().is_some());
+
+    std::thread::sleep(Duration::from_millis(40));
+    assert!(store.get("a", "short").unwrap().is_none());
+    assert_eq!(store.list("a", "").unwrap(), ["long"]);
+    assert_eq!(store.purge_expired().unwrap(), 1);
+}
+
+#[test]
+fn quotas_limit_each_namespace() {
+    let store = KvStore::in_memory().with_quotas(KvQuotas {
+        max_keys: 2,
+        max_value_bytes: 100,
+        max_namespace_bytes: 150,
+    });
+
+    let err = store
+        .set("a", "big", json!("x".repeat(200)), None)
+        .unwrap_err();
+    assert!(matches!(err, LoomError::QuotaExceeded(_)));
+
+    store.set("a", "k1", json!(1), None).unwrap();
+    store.set("a", "k2", json!(2), None).unwrap();
+    // Overwriting stays within the key quota; a third key does not
+    store.set("a", "k2", json!(3), None).unwrap();
+    let err = store.set("a", "k3", json!(3), None).unwrap_err();
+    assert!(matches!(err, LoomError::QuotaExceeded(_)));
+    store.set("b", "k3", json!(3), None).unwrap();
+
+    // Namespace bytes: entries of ~64 and ~88 bytes each fit, but not together
+    store.delete("a", "k1").unwrap();
+    store.delete("a", "k2").unwrap();
+    store.set("a", "k1", json!("x".repeat(20)), None).unwrap();
+    let err = store
+        .set("a", "k2", json!("x".repeat(44)), None)
+        .unwrap_err();
+    assert!(matches!(err, LoomError::QuotaExceeded(ref m) if m.contains("would use")));
+}
+
+#[tokio::test]
+async fn tools_use_the_calling_agents_namespace() {
+    let store = Arc::new(KvStore::in_memory());
+    let kv = tools(Arc::clone(&store), KvPolicy::new());
+    assert_eq!(kv.len(), 4);
+
+    let out = with_caller(
+        "planner",
+        kv["kv:set"].call(json!({ "key": "step", "value": [1, 2], "ttl_secs": 60 })),
+    )
+    .await
+    .unwrap();
+    assert_eq!(out["namespace"], "planner");
+    assert!(out["expires_at_ms"].as_i64().is_some());
+    assert_eq!(
+        store.get("planner", "step").unwrap().unwrap().value,
+        json!([1, 2])
+    );
+
+    let out = with_caller("planner", kv["kv:get"].call(json!({ "key": "step" })))
+        .await
+        .unwrap();
+    assert_eq!(out["found"], true);
+    assert_eq!(out["value"], json!([1, 2]));
+
+    // Another agent has its own namespace and cannot name the planner's
+    let out = with_caller("critic", kv["kv:get"].call(json!({ "key": "step" })))
+        .await
+        .unwrap();
+    assert_eq!(out["found"], false);
+    assert_eq!(out["value"], Value::Null);
+    let err = with_caller(
+        "critic",
+        kv["kv:list"].call(json!({ "namespace": "planner" })),
+    )
+    .await
+    .unwrap_err();
+    assert!(matches!(err, ToolError::PermissionDenied(_)));
+
+    // Outside an agent there is no default namespace
+    let err = kv["kv:list"].call(json!({})).await.unwrap_err();
+    assert!(matches!(err, ToolError::InvalidArguments(_)));
+
+    let out = with_caller("planner", kv["kv:delete"].call(json!({ "key": "step" })))
+        .await
+        .unwrap();
+    assert_eq!(out["deleted"], true);
+}
+
+#[tokio::test]
+async fn policy_shares_namespaces() {
+    let store = Arc::new(KvStore::in_memory());
+    let policy = KvPolicy::new()
+        .with_shared("team", ["planner", "critic"])
+        .with_shared("public", ["*"]);
+    let kv = tools(store, policy);
+
+    for agent in ["planner", "critic"] {
+        with_caller(
+            agent,
+            kv["kv:set"].call(json!({ "namespace": "team", "key": agent, "value": true })),
+        )
+        .await
+        .unwrap();
+    }
+    let out = with_caller("critic", kv["kv:list"].call(json!({ "namespace": "team" })))
+        .await
+        .unwrap();
+    assert_eq!(out["keys"], json!(["critic", "planner"]));
+    assert_eq!(out["count"], 2);
+
+    let err = with_caller(
+        "intruder",
+        kv["kv:get"].call(json!({ "namespace": "team", "key": "planner" })),
+    )
+    .await
+    .unwrap_err();
+    assert!(matches!(err, ToolError::PermissionDenied(_)));
+
+    // `*` also admits calls made outside an agent
+    kv["kv:set"]
+        .call(json!({ "namespace": "public", "key": "motd", "value": "hi" }))
+        .await
+        .unwrap();
+    let out = with_caller(
+        "intruder",
+        kv["kv:get"].call(json!({ "namespace": "public", "key": "motd" })),
+    )
+    .await
+    .unwrap();
+    assert_eq!(out["value"], "hi");
+}
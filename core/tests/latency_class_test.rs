@@ -0,0 +1,221 @@
+//! Tests for latency classes: tier selection, SLO tracking and per-agent defaults
+
+use async_trait::async_trait;
+use loom_core::agent::{AgentBehavior, AgentRuntime};
+use loom_core::cognitive::llm::router::Route;
+use loom_core::proto::{Action, AgentConfig, AgentState, Event};
+use loom_core::{
+    EventBus, LatencyClass, LoomError, ModelRouter, ModelTier, Result, TierTable, ToolRegistry,
+};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+fn make_event(metadata: &[(&str, &str)]) -> Event {
+    Event {
+        id: "e1".to_string(),
+        r#type: "test".to_string(),
+        timestamp_ms: 0,
+        source: "test".to_string(),
+        metadata: metadata
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect(),
+        payload: vec![],
+        confidence: 1.0,
+        tags: vec![],
+        priority: 0,
+    }
+}
+
+struct NoopBehavior;
+
+#[async_trait]
+impl AgentBehavior for NoopBehavior {
+    async fn on_event(&mut self, _event: Event, _state: &mut AgentState) -> Result<Vec<Action>> {
+        Ok(vec![])
+    }
+
+    async fn on_init(&mut self, _config: &AgentConfig) -> Result<()> {
+        Ok(())
+    }
+
+    async fn on_shutdown(&mut self) -> Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn latency_classes_parse_from_names_and_events() {
+    assert_eq!(LatencyClass::parse("voice"), Some(LatencyClass::Realtime));
+    assert_eq!(
+        LatencyClass::parse(" Realtime "),
+        Some(LatencyClass::Realtime)
+    );
+    assert_eq!(LatencyClass::parse("batch"), Some(LatencyClass::Background));
+    assert_eq!(LatencyClass::parse("soon"), None);
+
+    let event = make_event(&[("latency_class", "interactive")]);
+    assert_eq!(
+        LatencyClass::from_event(&event),
+        Some(LatencyClass::Interactive)
+    );
+    assert_eq!(LatencyClass::from_event(&make_event(&[])), None);
+}
+
+#[test]
+fn tier_tables_load_from_json() {
+    let table = TierTable::from_json(
+        r#"{"voice": {"models": ["tiny-llm"], "slo_ms": 250}, "background": {"models": ["big-llm"], "slo_ms": 60000}}"#,
+    )
+    .unwrap();
+    assert_eq!(
+        table.get(LatencyClass::Realtime),
+        Some(&ModelTier::new(["tiny-llm"], 250))
+    );
+    assert!(table.get(LatencyClass::Interactive).is_none());
+
+    let mut merged = TierTable::builtin();
+    merged.merge(table);
+    assert_eq!(merged.get(LatencyClass::Realtime).unwrap().slo_ms, 250);
+    assert!(merged.get(LatencyClass::Interactive).is_some());
+
+    assert!(TierTable::from_json(r#"{"soon": {"models": ["m"], "slo_ms": 1}}"#).is_err());
+    assert!(TierTable::from_json(r#"{"realtime": {"models": [], "slo_ms": 1}}"#).is_err());
+}
+
+#[tokio::test]
+async fn classes_are_served_from_their_tier() -> Result<()> {
+    let router = ModelRouter::new().await?.with_tiers(TierTable::builtin());
+
+    let decision = router
+        .route(&make_event(&[("latency_class", "realtime")]), None)
+        .await?;
+    assert_eq!(decision.route, Route::Local);
+    assert_eq!(decision.model.as_deref(), Some("lightweight_llm"));
+    assert_eq!(decision.latency_class, Some(LatencyClass::Realtime));
+
+    let decision = router
+        .route(&make_event(&[("latency_class", "background")]), None)
+        .await?;
+    assert_eq!(decision.route, Route::Cloud);
+    assert_eq!(decision.model.as_deref(), Some("gpt-4"));
+
+    // Without a hint the regular rules apply
+    let decision = router.route(&make_event(&[]), None).await?;
+    assert_eq!(decision.latency_class, None);
+
+    // Privacy still wins over the tier
+    let decision = router
+        .route(
+            &make_event(&[("latency_class", "background"), ("privacy", "local-only")]),
+            None,
+        )
+        .await?;
+    assert_eq!(decision.route, Route::Local);
+    assert_eq!(decision.latency_class, None);
+
+    // An agent-wide default applies to events without a hint
+    let voice = router.clone().with_latency_class(LatencyClass::Realtime);
+    assert_eq!(
+        voice.route(&make_event(&[]), None).await?.model.as_deref(),
+        Some("lightweight_llm")
+    );
+    assert_eq!(
+        voice
+            .route(&make_event(&[("latency_class", "background")]), None)
+            .await?
+            .model
+            .as_deref(),
+        Some("gpt-4")
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn slow_models_are_skipped_for_tight_slos() -> Result<()> {
+    let router = ModelRouter::new().await?.with_tiers(TierTable::builtin());
+    let event = make_event(&[("latency_class", "interactive")]);
+    assert_eq!(
+        router.route(&event, None).await?.model.as_deref(),
+        Some("claude-3")
+    );
+
+    // claude-3's observed p95 now exceeds the 2s SLO: the next model in the tier serves
+    for _ in 0..5 {
+        router.record_latency("claude-3", Duration::from_millis(3_000));
+    }
+    assert_eq!(
+        router.route(&event, None).await?.model.as_deref(),
+        Some("gpt-4")
+    );
+
+    // Nothing meets the SLO: the fastest model serves and the reason says so
+    for _ in 0..5 {
+        router.record_latency("gpt-4", Duration::from_millis(5_000));
+    }
+    let decision = router.route(&event, None).await?;
+    assert_eq!(decision.model.as_deref(), Some("claude-3"));
+    assert!(decision.reason.contains("no model expected within 2000ms"));
+    Ok(())
+}
+
+#[tokio::test]
+async fn outcomes_are_tracked_against_class_slos() -> Result<()> {
+    let router = ModelRouter::new().await?.with_tiers(TierTable::builtin());
+    let decision = router
+        .route(&make_event(&[("latency_class", "realtime")]), None)
+        .await?;
+
+    assert!(!router.record_outcome(&decision, Duration::from_millis(120)));
+    assert!(!router.record_outcome(&decision, Duration::from_millis(280)));
+    assert!(router.record_outcome(&decision, Duration::from_millis(900)));
+    // The model's latency is tracked too
+    assert_eq!(router.latency_tracker().sample_count("lightweight_llm"), 3);
+
+    let stats = router.slo_stats();
+    assert_eq!(stats.len(), 3);
+    let realtime = &stats[0];
+    assert_eq!(realtime.class, LatencyClass::Realtime);
+    assert_eq!(realtime.slo_ms, 300);
+    assert_eq!((realtime.requests, realtime.violations), (3, 1));
+    assert_eq!(realtime.p95_ms, Some(900));
+    assert!((realtime.attainment() - 2.0 / 3.0).abs() < 1e-9);
+    assert_eq!(stats[2].requests, 0);
+    assert_eq!(stats[2].attainment(), 1.0);
+
+    // Clones share the tracker
+    assert_eq!(router.clone().slo_stats()[0].requests, 3);
+    Ok(())
+}
+
+#[tokio::test]
+async fn agents_reject_unknown_latency_classes() -> Result<()> {
+    let bus = Arc::new(EventBus::new().await?);
+    bus.start().await?;
+    let runtime = AgentRuntime::new(
+        Arc::clone(&bus),
+        Arc::new(ToolRegistry::new()),
+        ModelRouter::new().await?,
+    )
+    .await?;
+
+    let config = |id: &str, class: &str| AgentConfig {
+        agent_id: id.to_string(),
+        agent_type: "test".to_string(),
+        subscribed_topics: vec![],
+        capabilities: vec![],
+        parameters: HashMap::from([("routing.latency_class".to_string(), class.to_string())]),
+    };
+
+    runtime
+        .create_agent(config("voice", "realtime"), Box::new(NoopBehavior))
+        .await?;
+    let err = runtime
+        .create_agent(config("hasty", "asap"), Box::new(NoopBehavior))
+        .await
+        .unwrap_err();
+    assert!(matches!(err, LoomError::AgentError(ref m) if m.contains("routing.latency_class")));
+    assert_eq!(runtime.agent_count(), 1);
+    Ok(())
+}
@@ -0,0 +1,192 @@
+//! Tests for the LLM response cache
+
+use loom_core::cognitive::llm::{
+    GenerateOptions, LlmCache, LlmCacheConfig, LlmClient, LlmClientConfig, LlmResponse,
+};
+use loom_core::cognitive::CognitiveConfig;
+use loom_core::context::PromptBundle;
+use loom_core::{AgentSpec, Result};
+use serde_json::json;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+
+fn test_config(base_url: String) -> LlmClientConfig {
+    LlmClientConfig {
+        base_url,
+        model: "test-model".to_string(),
+        api_key: None,
+        request_timeout_ms: 5000,
+        temperature: 0.7,
+    }
+}
+
+fn question_bundle(q: &str) -> PromptBundle {
+    PromptBundle {
+        system: "You are a helpful assistant".to_string(),
+        instructions: q.to_string(),
+        tools_json_schema: None,
+        context_docs: vec![],
+        history: vec![],
+    }
+}
+
+fn response(text: &str) -> LlmResponse {
+    LlmResponse {
+        text: text.to_string(),
+        ..Default::default()
+    }
+}
+
+/// Minimal HTTP server answering every request with a numbered Responses API payload.
+/// Returns the base URL and a counter of requests received.
+async fn spawn_mock_backend() -> (String, Arc<AtomicUsize>) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let hits = Arc::new(AtomicUsize::new(0));
+    let hits_srv = hits.clone();
+    tokio::spawn(async move {
+        loop {
+            let Ok((mut sock, _)) = listener.accept().await else {
+                break;
+            };
+            let hits = hits_srv.clone();
+            tokio::spawn(async move {
+                let mut buf = vec![0u8; 16 * 1024];
+                let _ = sock.read(&mut buf).await;
+                let n = hits.fetch_add(1, Ordering::SeqCst) + 1;
+                let body = json!({ "output_text": format!("answer-{n}"), "model": "test-model" })
+                    .to_string();
+                let resp = format!(
+                    "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{}",
+                    body.len(),
+                    body
+                );
+                let _ = sock.write_all(resp.as_bytes()).await;
+                let _ = sock.shutdown().await;
+            });
+        }
+    });
+    (format!("http://{addr}/v1"), hits)
+}
+
+#[tokio::test]
+async fn repeated_prompts_are_served_from_cache() -> Result<()> {
+    let (base_url, hits) = spawn_mock_backend().await;
+    let cache = Arc::new(LlmCache::new(LlmCacheConfig::default())?);
+    let client = LlmClient::new(test_config(base_url))?.with_cache(Arc::clone(&cache));
+    let bundle = question_bundle("Did you hear the wake word?");
+
+    let first = client.generate(&bundle, None).await?;
+    let second = client.generate(&bundle, None).await?;
+    assert_eq!(first.text, "answer-1");
+    assert_eq!(second.text, "answer-1");
+    assert_eq!(hits.load(Ordering::SeqCst), 1);
+
+    // A different prompt is its own entry
+    client
+        .generate(&question_bundle("Something else"), None)
+        .await?;
+    assert_eq!(hits.load(Ordering::SeqCst), 2);
+
+    let stats = cache.stats();
+    assert_eq!(stats.hits, 1);
+    assert_eq!(stats.misses, 2);
+    assert_eq!(cache.len(), 2);
+    Ok(())
+}
+
+#[tokio::test]
+async fn cache_can_be_bypassed_per_call() -> Result<()> {
+    let (base_url, hits) = spawn_mock_backend().await;
+    let cache = Arc::new(LlmCache::new(LlmCacheConfig::default())?);
+    let client = LlmClient::new(test_config(base_url))?.with_cache(Arc::clone(&cache));
+    let bundle = question_bundle("Give me a random number");
+
+    for _ in 0..2 {
+        client
+            .generate_with_options(&bundle, None, GenerateOptions::no_cache())
+            .await?;
+    }
+    assert_eq!(hits.load(Ordering::SeqCst), 2);
+    assert!(cache.is_empty());
+    Ok(())
+}
+
+#[tokio::test]
+async fn expired_entries_are_regenerated() -> Result<()> {
+    let (base_url, hits) = spawn_mock_backend().await;
+    let cache = Arc::new(LlmCache::new(
+        LlmCacheConfig::default().with_ttl(Duration::from_millis(50)),
+    )?);
+    let client = LlmClient::new(test_config(base_url))?.with_cache(Arc::clone(&cache));
+    let bundle = question_bundle("Reflect on the last answer");
+
+    client.generate(&bundle, None).await?;
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    let again = client.generate(&bundle, None).await?;
+
+    assert_eq!(again.text, "answer-2");
+    assert_eq!(hits.load(Ordering::SeqCst), 2);
+    assert_eq!(cache.stats().expirations, 1);
+    Ok(())
+}
+
+#[test]
+fn least_recently_used_entries_are_evicted() -> Result<()> {
+    let cache = LlmCache::new(LlmCacheConfig::default().with_capacity(2))?;
+    cache.put("a", &response("A"));
+    cache.put("b", &response("B"));
+    // Touch "a" so "b" is the least recently used
+    assert_eq!(cache.get("a").unwrap().text, "A");
+    cache.put("c", &response("C"));
+
+    assert_eq!(cache.len(), 2);
+    assert!(cache.get("b").is_none());
+    assert_eq!(cache.get("a").unwrap().text, "A");
+    assert_eq!(cache.get("c").unwrap().text, "C");
+    assert_eq!(cache.stats().evictions, 1);
+
+    cache.invalidate("a");
+    assert!(cache.get("a").is_none());
+    cache.clear();
+    assert!(cache.is_empty());
+    Ok(())
+}
+
+#[test]
+fn persisted_entries_survive_reopen() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    {
+        let cache = LlmCache::new(LlmCacheConfig::default().with_path(dir.path()))?;
+        cache.put("kept", &response("still here"));
+        cache.put("dropped", &response("gone"));
+        cache.invalidate("dropped");
+        cache.flush()?;
+    }
+
+    let cache = LlmCache::new(LlmCacheConfig::default().with_path(dir.path()))?;
+    assert_eq!(cache.len(), 1);
+    assert_eq!(cache.get("kept").unwrap().text, "still here");
+    assert!(cache.get("dropped").is_none());
+    Ok(())
+}
+
+#[test]
+fn invalid_config_is_rejected() {
+    assert!(LlmCache::new(LlmCacheConfig::default().with_capacity(0)).is_err());
+    assert!(LlmCache::new(LlmCacheConfig::default().with_ttl(Duration::ZERO)).is_err());
+}
+
+#[test]
+fn agents_can_opt_out() {
+    assert!(CognitiveConfig::default().llm_cache);
+    assert!(!CognitiveConfig::react().without_llm_cache().llm_cache);
+
+    let mut spec = AgentSpec::new("reflector");
+    assert!(spec.cognitive_config().llm_cache);
+    spec.llm_cache = Some(false);
+    assert!(!spec.cognitive_config().llm_cache);
+}
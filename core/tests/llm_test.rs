@@ -1,8 +1,13 @@
-use loom_core::cognitive::llm::{LlmClient, LlmClientConfig};
+use loom_core::cognitive::llm::{prompt_hash, GenerateOptions, LlmClient, LlmClientConfig};
 use loom_core::context::{PromptBundle, TokenBudget};
 use loom_core::Result;
 use serde_json::json;
 use serial_test::serial;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
 
 #[test]
 #[serial]
@@ -233,3 +238,155 @@ fn token_budget_default_values() {
     assert_eq!(budget.max_input_tokens, 2048);
     assert_eq!(budget.max_output_tokens, 512);
 }
+
+fn test_config(base_url: String) -> LlmClientConfig {
+    LlmClientConfig {
+        base_url,
+        model: "test-model".to_string(),
+        api_key: None,
+        request_timeout_ms: 5000,
+        temperature: 0.7,
+    }
+}
+
+/// Minimal HTTP server answering every request with a Responses API payload after `delay`.
+/// Returns the base URL and a counter of requests received.
+async fn spawn_mock_backend(delay: Duration) -> (String, Arc<AtomicUsize>) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let hits = Arc::new(AtomicUsize::new(0));
+    let hits_srv = hits.clone();
+    tokio::spawn(async move {
+        loop {
+            let Ok((mut sock, _)) = listener.accept().await else {
+                break;
+            };
+            let hits = hits_srv.clone();
+            tokio::spawn(async move {
+                let mut buf = vec![0u8; 16 * 1024];
+                let _ = sock.read(&mut buf).await;
+                let n = hits.fetch_add(1, Ordering::SeqCst) + 1;
+                tokio::time::sleep(delay).await;
+                let body = json!({ "output_text": format!("answer-{n}"), "model": "test-model" })
+                    .to_string();
+                let resp = format!(
+                    "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{}",
+                    body.len(),
+                    body
+                );
+                let _ = sock.write_all(resp.as_bytes()).await;
+                let _ = sock.shutdown().await;
+            });
+        }
+    });
+    (format!("http://{addr}/v1"), hits)
+}
+
+fn question_bundle(q: &str) -> PromptBundle {
+    PromptBundle {
+        system: "You are a helpful assistant".to_string(),
+        instructions: q.to_string(),
+        tools_json_schema: None,
+        context_docs: vec![],
+        history: vec![],
+    }
+}
+
+#[test]
+fn prompt_hash_is_stable_and_sensitive_to_inputs() {
+    let cfg = test_config("http://localhost:8000/v1".into());
+    let budget = TokenBudget::default();
+    let a = question_bundle("What is the weather?");
+
+    let key = prompt_hash(&cfg, &a, &budget);
+    assert_eq!(key.len(), 64);
+    assert!(key.bytes().all(|b| b.is_ascii_hexdigit()));
+    assert_eq!(
+        prompt_hash(&cfg, &a, &budget),
+        prompt_hash(&cfg, &a.clone(), &budget)
+    );
+    assert_ne!(
+        prompt_hash(&cfg, &a, &budget),
+        prompt_hash(&cfg, &question_bundle("Something else"), &budget)
+    );
+
+    let mut hotter = cfg.clone();
+    hotter.temperature = 1.0;
+    assert_ne!(
+        prompt_hash(&cfg, &a, &budget),
+        prompt_hash(&hotter, &a, &budget)
+    );
+
+    let small = TokenBudget {
+        max_input_tokens: 128,
+        max_output_tokens: 64,
+    };
+    assert_ne!(
+        prompt_hash(&cfg, &a, &budget),
+        prompt_hash(&cfg, &a, &small)
+    );
+}
+
+#[tokio::test]
+async fn identical_concurrent_calls_are_coalesced() -> Result<()> {
+    let (base_url, hits) = spawn_mock_backend(Duration::from_millis(200)).await;
+    let client = LlmClient::new(test_config(base_url))?;
+    let bundle = question_bundle("What is the weather?");
+
+    let mut handles = Vec::new();
+    for _ in 0..5 {
+        let client = client.clone();
+        let bundle = bundle.clone();
+        handles.push(tokio::spawn(
+            async move { client.generate(&bundle, None).await },
+        ));
+    }
+    let mut texts = Vec::new();
+    for h in handles {
+        texts.push(h.await.unwrap()?.text);
+    }
+
+    assert_eq!(hits.load(Ordering::SeqCst), 1);
+    assert!(texts.iter().all(|t| t == "answer-1"));
+    assert_eq!(client.inflight_requests(), 0);
+
+    // A later call after completion is not served from the finished request
+    client.generate(&bundle, None).await?;
+    assert_eq!(hits.load(Ordering::SeqCst), 2);
+    Ok(())
+}
+
+#[tokio::test]
+async fn different_prompts_are_not_coalesced() -> Result<()> {
+    let (base_url, hits) = spawn_mock_backend(Duration::from_millis(100)).await;
+    let client = LlmClient::new(test_config(base_url))?;
+
+    let a = question_bundle("first");
+    let b = question_bundle("second");
+    let (ra, rb) = tokio::join!(client.generate(&a, None), client.generate(&b, None));
+    ra?;
+    rb?;
+
+    assert_eq!(hits.load(Ordering::SeqCst), 2);
+    Ok(())
+}
+
+#[tokio::test]
+async fn coalescing_can_be_disabled_per_call() -> Result<()> {
+    let (base_url, hits) = spawn_mock_backend(Duration::from_millis(100)).await;
+    let client = LlmClient::new(test_config(base_url))?;
+    let bundle = question_bundle("Give me a random number");
+
+    let opts = GenerateOptions::no_coalesce();
+    let (r1, r2, r3) = tokio::join!(
+        client.generate_with_options(&bundle, None, opts),
+        client.generate_with_options(&bundle, None, opts),
+        client.generate_with_options(&bundle, None, opts),
+    );
+    r1?;
+    r2?;
+    r3?;
+
+    assert_eq!(hits.load(Ordering::SeqCst), 3);
+    Ok(())
+}
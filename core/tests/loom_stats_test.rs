@@ -0,0 +1,205 @@
+//! Tests for the unified stats snapshot (`StatsCollector`, `/api/stats`)
+
+use async_trait::async_trait;
+use loom_core::agent::directory::AgentDirectory;
+use loom_core::dashboard::{DashboardConfig, DashboardServer, EventBroadcaster};
+use loom_core::proto::{Action, AgentConfig, AgentState, Event, QoSLevel};
+use loom_core::{
+    AgentBehavior, AgentRuntime, EventBus, LlmResponse, LlmUsage, LoomError, McpManager,
+    MemoryComponent, MemoryGovernor, MemoryGovernorConfig, ModelRouter, Result, SpanCollector,
+    StatsCollector, StatsSource, ToolRegistry,
+};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+struct IdleBehavior;
+
+#[async_trait]
+impl AgentBehavior for IdleBehavior {
+    async fn on_event(&mut self, _event: Event, _state: &mut AgentState) -> Result<Vec<Action>> {
+        Ok(vec![])
+    }
+
+    async fn on_init(&mut self, _config: &AgentConfig) -> Result<()> {
+        Ok(())
+    }
+
+    async fn on_shutdown(&mut self) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// Report-only component holding a fixed number of bytes
+struct Ballast(usize);
+
+#[async_trait]
+impl MemoryComponent for Ballast {
+    fn name(&self) -> String {
+        "ballast".to_string()
+    }
+
+    async fn memory_usage(&self) -> usize {
+        self.0
+    }
+}
+
+struct FixedSource;
+
+#[async_trait]
+impl StatsSource for FixedSource {
+    fn name(&self) -> String {
+        "fixed".to_string()
+    }
+
+    async fn stats(&self) -> Value {
+        json!({ "connections": 3 })
+    }
+}
+
+fn event(id: &str) -> Event {
+    Event {
+        id: id.to_string(),
+        r#type: "test".to_string(),
+        timestamp_ms: 0,
+        source: "test".to_string(),
+        metadata: HashMap::new(),
+        payload: vec![],
+        confidence: 1.0,
+        tags: vec![],
+        priority: 50,
+    }
+}
+
+fn response(usage: Value) -> Result<LlmResponse> {
+    Ok(LlmResponse {
+        text: "ok".to_string(),
+        model: None,
+        provider: None,
+        usage: Some(usage),
+        raw: None,
+    })
+}
+
+async fn collector() -> (Arc<StatsCollector>, Arc<EventBus>, Arc<LlmUsage>) {
+    let bus = Arc::new(EventBus::new().await.unwrap());
+    bus.start().await.unwrap();
+    let tools = Arc::new(ToolRegistry::new());
+    let runtime = AgentRuntime::new(
+        Arc::clone(&bus),
+        Arc::clone(&tools),
+        ModelRouter::new().await.unwrap(),
+    )
+    .await
+    .unwrap();
+    runtime
+        .create_agent(
+            AgentConfig {
+                agent_id: "worker".to_string(),
+                agent_type: "test".to_string(),
+                subscribed_topics: vec!["jobs".to_string()],
+                capabilities: vec![],
+                parameters: HashMap::new(),
+            },
+            Box::new(IdleBehavior),
+        )
+        .await
+        .unwrap();
+    let governor = Arc::new(MemoryGovernor::new(
+        MemoryGovernorConfig::default().with_global_cap(1_000_000),
+    ));
+    governor.register(Arc::new(Ballast(4_096)));
+    let usage = Arc::new(LlmUsage::new());
+    let collector = Arc::new(StatsCollector::new(
+        Arc::clone(&bus),
+        runtime,
+        Arc::clone(&tools),
+        Arc::new(McpManager::new(tools)),
+        governor,
+        Arc::clone(&usage),
+    ));
+    (collector, bus, usage)
+}
+
+#[tokio::test]
+async fn snapshot_covers_every_subsystem() -> Result<()> {
+    let (collector, bus, usage) = collector().await;
+    let (_id, _rx) = bus
+        .subscribe("metrics".to_string(), vec![], QoSLevel::QosBatched)
+        .await?;
+    for i in 0..3 {
+        bus.publish("metrics", event(&format!("m{}", i))).await?;
+    }
+
+    usage.record(&response(json!({ "input_tokens": 10, "output_tokens": 5 })));
+    usage.record(&response(
+        json!({ "prompt_tokens": 7, "completion_tokens": 3, "total_tokens": 12 }),
+    ));
+    usage.record(&Err(LoomError::AgentError("backend down".to_string())));
+    collector.register(Arc::new(FixedSource));
+
+    let stats = collector.snapshot().await;
+    assert!(stats.timestamp_ms > 0);
+    assert_eq!(stats.event_bus.totals.total_published, 3);
+    assert_eq!(stats.event_bus.per_topic["metrics"].total_published, 3);
+    assert!(stats.event_bus.subscriptions >= 2);
+
+    assert_eq!(stats.agents.len(), 1);
+    assert_eq!(stats.agents[0].agent_id, "worker");
+    assert!(stats.agents[0].subscriptions >= 1);
+    assert!(!stats.agents[0].hibernated);
+
+    assert_eq!(stats.llm.usage.requests, 3);
+    assert_eq!(stats.llm.usage.failures, 1);
+    assert_eq!(stats.llm.usage.prompt_tokens, 17);
+    assert_eq!(stats.llm.usage.completion_tokens, 8);
+    assert_eq!(stats.llm.usage.total_tokens, 27);
+    assert!(stats.llm.cache.is_none());
+
+    assert!(stats.mcp_servers.is_empty());
+    assert_eq!(stats.memory.total_bytes, 4_096);
+    assert_eq!(stats.memory.global_cap_bytes, 1_000_000);
+    assert_eq!(stats.memory.components[0].name, "ballast");
+
+    assert_eq!(stats.sources["fixed"]["connections"], 3);
+    collector.unregister("fixed");
+    assert!(collector.snapshot().await.sources.is_empty());
+    Ok(())
+}
+
+#[tokio::test]
+async fn dashboard_serves_the_snapshot() {
+    let (collector, _bus, _usage) = collector().await;
+    let dashboard = || {
+        DashboardServer::new(
+            DashboardConfig::default(),
+            EventBroadcaster::new(16),
+            Arc::new(AgentDirectory::new()),
+            SpanCollector::new(),
+        )
+    };
+    let serve = |app: axum::Router| async move {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            axum::serve(listener, app).await.unwrap();
+        });
+        base_url
+    };
+
+    let base_url = serve(dashboard().with_stats(collector).router()).await;
+    let response = reqwest::get(format!("{}/api/stats", base_url))
+        .await
+        .unwrap();
+    assert_eq!(response.status().as_u16(), 200);
+    let body: Value = response.json().await.unwrap();
+    assert_eq!(body["agents"][0]["agent_id"], "worker");
+    assert_eq!(body["memory"]["level"], "normal");
+    assert!(body["event_bus"]["totals"].is_object());
+
+    let base_url = serve(dashboard().router()).await;
+    let response = reqwest::get(format!("{}/api/stats", base_url))
+        .await
+        .unwrap();
+    assert_eq!(response.status().as_u16(), 404);
+}
@@ -0,0 +1,197 @@
+//! Tests for the `math:eval` calculator tool
+
+use axum::{routing::get, Json, Router};
+use loom_core::tools::native::math::{convert_units, evaluate};
+use loom_core::tools::native::{MathConfig, MathEvalTool};
+use loom_core::{Tool, ToolError};
+use serde_json::json;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+fn approx(actual: f64, expected: f64) {
+    assert!(
+        (actual - expected).abs() < 1e-9 * expected.abs().max(1.0),
+        "{actual} != {expected}"
+    );
+}
+
+#[test]
+fn evaluates_expressions_with_precedence() {
+    let cases = [
+        ("1 + 2 * 3", 7.0),
+        ("(1 + 2) * 3", 9.0),
+        ("2 ^ 3 ^ 2", 512.0),
+        ("2 ** 10", 1024.0),
+        ("-2 ^ 2", -4.0),
+        ("10 % 4", 2.0),
+        ("5!", 120.0),
+        ("-3!", -6.0),
+        ("1_000 * 1.5e3", 1_500_000.0),
+        (".5 + .25", 0.75),
+        ("sqrt(16) + abs(-3)", 7.0),
+        ("max(1, 7, 3) - min(4, 2)", 5.0),
+        ("avg(1, 2, 3, 4)", 2.5),
+        ("log(1000) + log(8, 2) + ln(e)", 7.0),
+        ("round(2 * pi, 1)", 6.3),
+        ("cos(0) × 6 ÷ 3", 2.0),
+    ];
+    for (expression, expected) in cases {
+        approx(evaluate(expression).unwrap(), expected);
+    }
+}
+
+#[test]
+fn rejects_invalid_expressions() {
+    for expression in [
+        "",
+        "1 +",
+        "(1 + 2",
+        "1 / 0",
+        "7 % 0",
+        "2e",
+        "foo(1)",
+        "bar",
+        "1.2.3",
+        "3.5!",
+        "sqrt(1, 2)",
+        "10 ^ 400",
+        "system('ls')",
+        "1; 2",
+    ] {
+        assert!(
+            matches!(evaluate(expression), Err(ToolError::InvalidArguments(_))),
+            "{expression:?} should be rejected"
+        );
+    }
+
+    let deep = format!("{}1{}", "(".repeat(100), ")".repeat(100));
+    let err = evaluate(&deep).unwrap_err();
+    assert!(matches!(err, ToolError::InvalidArguments(ref m) if m.contains("deeply")));
+    assert!(evaluate(&"1+".repeat(600)).is_err());
+}
+
+#[test]
+fn converts_units_within_a_dimension() {
+    approx(convert_units(5.0, "km", "mi").unwrap(), 3.106_855_961_2);
+    approx(convert_units(100.0, "C", "F").unwrap(), 212.0);
+    approx(convert_units(-40.0, "°F", "celsius").unwrap(), -40.0);
+    approx(convert_units(0.0, "K", "C").unwrap(), -273.15);
+    approx(convert_units(1.0, "GiB", "MB").unwrap(), 1_073.741_824);
+    approx(convert_units(60.0, "mph", "km/h").unwrap(), 96.560_64);
+    approx(
+        convert_units(1.0, "kWh", "kcal").unwrap(),
+        860.420_650_095_6,
+    );
+    approx(
+        convert_units(180.0, "deg", "rad").unwrap(),
+        std::f64::consts::PI,
+    );
+
+    let err = convert_units(1.0, "kg", "m").unwrap_err();
+    assert!(matches!(err, ToolError::InvalidArguments(ref m) if m.contains("mass")));
+    assert!(convert_units(1.0, "furlong", "m").is_err());
+}
+
+#[tokio::test]
+async fn tool_evaluates_and_converts() {
+    let tool = MathEvalTool::new();
+    assert_eq!(tool.name(), "math:eval");
+
+    let out = tool
+        .call(json!({ "expression": "0.1 + 0.2" }))
+        .await
+        .unwrap();
+    assert_eq!(out["value"], 0.3);
+    assert_eq!(out["text"], "0.3");
+    assert_eq!(out["unit"], serde_json::Value::Null);
+
+    let out = tool
+        .call(json!({ "expression": "(20 + 5) C in F" }))
+        .await
+        .unwrap();
+    assert_eq!(out["value"], 77.0);
+    assert_eq!(out["text"], "77 F");
+    assert_eq!(out["from_value"], 25.0);
+    assert_eq!(out["kind"], "temperature");
+
+    let out = tool
+        .call(json!({ "expression": "26.2mi to km", "precision": 1 }))
+        .await
+        .unwrap();
+    assert_eq!(out["value"], 42.2);
+    assert_eq!(out["unit"], "km");
+
+    // Feet and inches: the unit `in` is not mistaken for the keyword
+    let out = tool
+        .call(json!({ "expression": "3 ft in in" }))
+        .await
+        .unwrap();
+    assert_eq!(out["value"], 36.0);
+
+    let err = tool
+        .call(json!({ "expression": "100 USD to EUR" }))
+        .await
+        .unwrap_err();
+    assert!(matches!(err, ToolError::ExecutionFailed(ref m) if m.contains("not configured")));
+    assert!(matches!(
+        tool.call(json!({})).await,
+        Err(ToolError::InvalidArguments(_))
+    ));
+}
+
+#[tokio::test]
+async fn currency_rates_are_fetched_and_cached() {
+    let hits = Arc::new(AtomicUsize::new(0));
+    let counter = Arc::clone(&hits);
+    let app = Router::new().route(
+        "/latest",
+        get(move || {
+            let counter = Arc::clone(&counter);
+            async move {
+                counter.fetch_add(1, Ordering::SeqCst);
+                Json(json!({ "result": "success", "base_code": "USD", "rates": { "USD": 1.0, "EUR": 0.5, "JPY": 150.0 } }))
+            }
+        }),
+    );
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}/latest", listener.local_addr().unwrap());
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+
+    let tool = MathEvalTool::with_config(MathConfig {
+        rates_endpoint: Some(url),
+        ..Default::default()
+    });
+    let out = tool
+        .call(json!({ "expression": "2 * 50 usd to eur" }))
+        .await
+        .unwrap();
+    assert_eq!(out["value"], 50.0);
+    assert_eq!(out["unit"], "EUR");
+    assert_eq!(out["kind"], "currency");
+
+    // Cross rate through the base currency, served from the cache
+    let out = tool
+        .call(json!({ "expression": "10 EUR to JPY" }))
+        .await
+        .unwrap();
+    assert_eq!(out["value"], 3000.0);
+    assert_eq!(out["rate"], 300.0);
+    assert_eq!(hits.load(Ordering::SeqCst), 1);
+
+    let err = tool
+        .call(json!({ "expression": "1 EUR to XYZ" }))
+        .await
+        .unwrap_err();
+    assert!(matches!(err, ToolError::InvalidArguments(ref m) if m.contains("XYZ")));
+
+    // Fixed rates need no endpoint
+    let tool = MathEvalTool::new().with_rates("eur", HashMap::from([("GBP".to_string(), 0.8)]));
+    let out = tool
+        .call(json!({ "expression": "8 GBP to EUR" }))
+        .await
+        .unwrap();
+    assert_eq!(out["value"], 10.0);
+}
@@ -0,0 +1,327 @@
+//! Tests for the MCP streamable HTTP transport against a mock server
+
+use axum::{
+    extract::State,
+    http::{HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+    routing::post,
+    Json, Router,
+};
+use loom_core::tools::mcp::types::McpServerConfig;
+use loom_core::tools::mcp::{McpClient, McpError, McpTransport, STREAMABLE_HTTP_PROTOCOL_VERSION};
+use serde_json::{json, Value};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+const TOKEN: &str = "test-token";
+
+#[derive(Default)]
+struct MockServer {
+    sessions: AtomicUsize,
+    expire_next: AtomicBool,
+    deleted: AtomicBool,
+    notifications: Mutex<Vec<Value>>,
+    client_replies: Mutex<Vec<Value>>,
+    resume_request: Mutex<Option<Value>>,
+    last_event_ids: Mutex<Vec<String>>,
+}
+
+impl MockServer {
+    fn session(&self) -> String {
+        format!("session-{}", self.sessions.load(Ordering::SeqCst))
+    }
+
+    fn notified(&self, method: &str) -> Vec<Value> {
+        self.notifications
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|n| n["method"] == method)
+            .cloned()
+            .collect()
+    }
+}
+
+fn sse(events: &[(Option<&str>, Value)]) -> Response {
+    let body: String = events
+        .iter()
+        .map(|(id, data)| match id {
+            Some(id) => format!("id: {}\ndata: {}\n\n", id, data),
+            None => format!("data: {}\n\n", data),
+        })
+        .collect();
+    ([("content-type", "text/event-stream")], body).into_response()
+}
+
+fn reply(id: &Value, result: Value) -> Value {
+    json!({ "jsonrpc": "2.0", "id": id, "result": result })
+}
+
+fn header<'a>(headers: &'a HeaderMap, name: &str) -> Option<&'a str> {
+    headers.get(name).and_then(|v| v.to_str().ok())
+}
+
+async fn handle_post(
+    State(server): State<Arc<MockServer>>,
+    headers: HeaderMap,
+    Json(message): Json<Value>,
+) -> Response {
+    let expected = format!("Bearer {}", TOKEN);
+    if header(&headers, "authorization") != Some(expected.as_str()) {
+        return StatusCode::UNAUTHORIZED.into_response();
+    }
+
+    let id = message.get("id").cloned();
+    let method = message["method"].as_str().unwrap_or_default().to_string();
+
+    if method == "initialize" {
+        let n = server.sessions.fetch_add(1, Ordering::SeqCst) + 1;
+        let result = json!({
+            "protocolVersion": message["params"]["protocolVersion"],
+            "capabilities": { "tools": {} },
+            "serverInfo": { "name": "mock", "version": "1.0" }
+        });
+        return (
+            [("mcp-session-id", format!("session-{}", n))],
+            Json(reply(id.as_ref().unwrap(), result)),
+        )
+            .into_response();
+    }
+
+    if header(&headers, "mcp-session-id") != Some(server.session().as_str())
+        || server.expire_next.swap(false, Ordering::SeqCst)
+    {
+        return StatusCode::NOT_FOUND.into_response();
+    }
+    assert_eq!(
+        header(&headers, "mcp-protocol-version"),
+        Some(STREAMABLE_HTTP_PROTOCOL_VERSION)
+    );
+
+    let Some(id) = id else {
+        server.notifications.lock().unwrap().push(message);
+        return StatusCode::ACCEPTED.into_response();
+    };
+    if method.is_empty() {
+        server.client_replies.lock().unwrap().push(message);
+        return StatusCode::ACCEPTED.into_response();
+    }
+
+    match (method.as_str(), message["params"]["name"].as_str()) {
+        ("tools/list", _) => sse(&[
+            (
+                None,
+                json!({ "jsonrpc": "2.0", "method": "notifications/progress", "params": { "progress": 1 } }),
+            ),
+            (
+                None,
+                json!({ "jsonrpc": "2.0", "id": "srv-1", "method": "sampling/createMessage" }),
+            ),
+            (
+                Some("ev-0"),
+                reply(
+                    &id,
+                    json!({ "tools": [{ "name": "echo", "inputSchema": { "type": "object" } }] }),
+                ),
+            ),
+        ]),
+        ("tools/call", Some("echo")) => Json(reply(
+            &id,
+            json!({ "content": [{ "type": "text", "text": message["params"]["arguments"]["text"] }] }),
+        ))
+        .into_response(),
+        ("tools/call", Some("resumable")) => {
+            *server.resume_request.lock().unwrap() = Some(id);
+            sse(&[(
+                Some("ev-1"),
+                json!({ "jsonrpc": "2.0", "method": "notifications/progress", "params": { "progress": 1 } }),
+            )])
+        }
+        ("tools/call", Some("slow")) => {
+            tokio::time::sleep(Duration::from_secs(10)).await;
+            Json(reply(&id, json!({ "content": [] }))).into_response()
+        }
+        _ => StatusCode::BAD_REQUEST.into_response(),
+    }
+}
+
+async fn handle_get(State(server): State<Arc<MockServer>>, headers: HeaderMap) -> Response {
+    let last_event_id = header(&headers, "last-event-id").unwrap_or_default();
+    server
+        .last_event_ids
+        .lock()
+        .unwrap()
+        .push(last_event_id.to_string());
+
+    let Some(id) = server.resume_request.lock().unwrap().take() else {
+        return StatusCode::METHOD_NOT_ALLOWED.into_response();
+    };
+    sse(&[(
+        Some("ev-2"),
+        reply(
+            &id,
+            json!({ "content": [{ "type": "text", "text": "resumed" }] }),
+        ),
+    )])
+}
+
+async fn handle_delete(State(server): State<Arc<MockServer>>) -> StatusCode {
+    server.deleted.store(true, Ordering::SeqCst);
+    StatusCode::OK
+}
+
+async fn start_mock() -> (String, Arc<MockServer>) {
+    let server = Arc::new(MockServer::default());
+    let app = Router::new()
+        .route(
+            "/mcp",
+            post(handle_post).get(handle_get).delete(handle_delete),
+        )
+        .with_state(Arc::clone(&server));
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}/mcp", listener.local_addr().unwrap());
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+    (url, server)
+}
+
+fn http_config(url: &str) -> McpServerConfig {
+    McpServerConfig {
+        name: "remote".to_string(),
+        transport: McpTransport::StreamableHttp,
+        url: Some(url.to_string()),
+        bearer_token: Some(TOKEN.to_string()),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn config_parses_streamable_http() {
+    let config: McpServerConfig = serde_json::from_value(json!({
+        "name": "remote",
+        "transport": "streamable_http",
+        "url": "https://mcp.example.com/mcp",
+        "headers": { "X-Team": "loom" },
+        "bearer_token_env": "MCP_TEST_UNSET_TOKEN"
+    }))
+    .unwrap();
+
+    assert_eq!(config.transport, McpTransport::StreamableHttp);
+    assert!(config.command.is_empty());
+    assert_eq!(config.protocol_version(), STREAMABLE_HTTP_PROTOCOL_VERSION);
+    assert!(config.validate_protocol_version().is_ok());
+    assert!(matches!(
+        config.bearer_token(),
+        Err(McpError::Unauthorized(ref m)) if m.contains("MCP_TEST_UNSET_TOKEN")
+    ));
+
+    // Stdio stays the default
+    let stdio: McpServerConfig = serde_json::from_value(json!({ "command": "npx" })).unwrap();
+    assert_eq!(stdio.transport, McpTransport::Stdio);
+}
+
+#[tokio::test]
+async fn connects_lists_and_calls_tools() {
+    let (url, server) = start_mock().await;
+    let client = McpClient::new(http_config(&url));
+    client.connect().await.unwrap();
+
+    assert_eq!(client.session_id().await.as_deref(), Some("session-1"));
+    assert_eq!(client.server_info().await.unwrap().name, "mock");
+    assert_eq!(server.notified("notifications/initialized").len(), 1);
+
+    // SSE response interleaved with a notification and a server request
+    let tools = client.list_tools().await.unwrap();
+    assert_eq!(tools.len(), 1);
+    assert_eq!(tools[0].name, "echo");
+    let replies = server.client_replies.lock().unwrap().clone();
+    assert_eq!(replies.len(), 1);
+    assert_eq!(replies[0]["id"], "srv-1");
+    assert_eq!(replies[0]["error"]["code"], -32601);
+
+    // Plain JSON response
+    let result = client
+        .call_tool("echo", Some(json!({ "text": "hi" })))
+        .await
+        .unwrap();
+    assert_eq!(result.content, "hi");
+    assert!(!result.is_error);
+
+    client.disconnect().await.unwrap();
+    assert!(server.deleted.load(Ordering::SeqCst));
+    assert_eq!(client.session_id().await, None);
+}
+
+#[tokio::test]
+async fn resumes_streams_and_renews_expired_sessions() {
+    let (url, server) = start_mock().await;
+    let client = McpClient::new(http_config(&url));
+    client.connect().await.unwrap();
+
+    // The stream ends after ev-1; the reply arrives on the resumed GET
+    let result = client.call_tool("resumable", None).await.unwrap();
+    assert_eq!(result.content, "resumed");
+    assert_eq!(*server.last_event_ids.lock().unwrap(), vec!["ev-1"]);
+
+    // A 404 on the session re-initializes and retries the request
+    server.expire_next.store(true, Ordering::SeqCst);
+    let result = client
+        .call_tool("echo", Some(json!({ "text": "again" })))
+        .await
+        .unwrap();
+    assert_eq!(result.content, "again");
+    assert_eq!(client.session_id().await.as_deref(), Some("session-2"));
+    assert_eq!(server.notified("notifications/initialized").len(), 2);
+}
+
+#[tokio::test]
+async fn dropped_requests_are_cancelled() {
+    let (url, server) = start_mock().await;
+    let client = McpClient::new(http_config(&url));
+    client.connect().await.unwrap();
+
+    let call = client.call_tool("slow", None);
+    assert!(tokio::time::timeout(Duration::from_millis(200), call)
+        .await
+        .is_err());
+
+    let mut cancelled = Vec::new();
+    for _ in 0..50 {
+        cancelled = server.notified("notifications/cancelled");
+        if !cancelled.is_empty() {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+    assert_eq!(cancelled.len(), 1);
+    assert!(cancelled[0]["params"]["requestId"].is_u64());
+}
+
+#[tokio::test]
+async fn rejects_bad_credentials_and_configs() {
+    let (url, _server) = start_mock().await;
+
+    let client = McpClient::new(McpServerConfig {
+        bearer_token: Some("wrong".to_string()),
+        ..http_config(&url)
+    });
+    assert!(matches!(
+        client.connect().await,
+        Err(McpError::Unauthorized(_))
+    ));
+
+    let client = McpClient::new(McpServerConfig {
+        url: None,
+        ..http_config(&url)
+    });
+    let err = client.connect().await.unwrap_err();
+    assert_eq!(err.code(), "TRANSPORT_ERROR");
+
+    let client = McpClient::new(McpServerConfig {
+        transport: McpTransport::Sse,
+        ..http_config(&url)
+    });
+    assert!(client.connect().await.is_err());
+}
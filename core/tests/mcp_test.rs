@@ -22,6 +22,7 @@ fn test_mcp_server_config_serialization() {
         }),
         cwd: Some("/tmp".to_string()),
         protocol_version: None,
+        ..Default::default()
     };
 
     let json = serde_json::to_string(&config).unwrap();
@@ -46,6 +47,7 @@ fn test_protocol_version_defaults() {
         env: None,
         cwd: None,
         protocol_version: None,
+        ..Default::default()
     };
 
     assert_eq!(config.protocol_version(), DEFAULT_PROTOCOL_VERSION);
@@ -120,6 +122,7 @@ async fn test_add_invalid_server() {
         env: None,
         cwd: None,
         protocol_version: None,
+        ..Default::default()
     };
 
     // This should fail because the command doesn't exist
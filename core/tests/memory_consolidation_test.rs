@@ -0,0 +1,248 @@
+//! Tests for memory consolidation: clustered summaries with provenance, boosts and demotions
+
+use async_trait::async_trait;
+use loom_core::context::consolidation::{
+    CONSOLIDATED_INTO_TAG, DEMOTED_AT_TAG, LAST_RETRIEVED_TAG, RETRIEVALS_TAG,
+};
+use loom_core::context::pipeline::summarize::{is_summary, SUMMARY_COVERS_TAG};
+use loom_core::context::{
+    ConsolidationConfig, ContextContent, ContextItem, ContextItemType, ContextMetadata,
+    ContextPipeline, MemoryConsolidator, MemoryQuery, MemoryStore, MessageRole, PipelineConfig,
+    RecencyRetrieval, RetrievalTrigger, Summarizer, TemporalRanker, TiktokenCounter, WindowConfig,
+    WindowManager,
+};
+use loom_core::{InMemoryStore, Result};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Counts calls and names how many texts it summarized
+#[derive(Default)]
+struct CountingSummarizer {
+    calls: AtomicUsize,
+}
+
+#[async_trait]
+impl Summarizer for CountingSummarizer {
+    async fn summarize(&self, texts: &[String], _max_tokens: usize) -> Result<String> {
+        self.calls.fetch_add(1, Ordering::SeqCst);
+        Ok(format!("Summary of {} items", texts.len()))
+    }
+}
+
+fn message(session: &str, i: i64) -> ContextItem {
+    let mut metadata =
+        ContextMetadata::new(session.to_string(), "agent".to_string()).with_importance(0.5);
+    metadata.timestamp_ms = 1_700_000_000_000 + i * 1_000;
+    ContextItem::new(
+        ContextItemType::Message {
+            role: MessageRole::User,
+        },
+        ContextContent::from_string(format!("Message {}", i)),
+        metadata,
+    )
+}
+
+async fn store_messages(store: &dyn MemoryStore, session: &str, range: std::ops::Range<i64>) {
+    for i in range {
+        store.store(message(session, i)).await.unwrap();
+    }
+}
+
+/// Clusters of four, no boosts or demotions
+fn clustering() -> ConsolidationConfig {
+    ConsolidationConfig {
+        min_age: Duration::ZERO,
+        cluster_size: 4,
+        stale_after: Duration::from_secs(u32::MAX as u64),
+        ..Default::default()
+    }
+}
+
+async fn session_items(store: &dyn MemoryStore, session: &str) -> Vec<ContextItem> {
+    store
+        .query(&MemoryQuery::new().for_session(session.to_string()))
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn old_items_are_clustered_into_linked_summaries() -> Result<()> {
+    let store: Arc<dyn MemoryStore> = InMemoryStore::new();
+    store_messages(&*store, "s1", 0..10).await;
+    store_messages(&*store, "s2", 0..4).await;
+    let summarizer = Arc::new(CountingSummarizer::default());
+    let consolidator =
+        MemoryConsolidator::new(Arc::clone(&store), summarizer.clone(), clustering());
+
+    let report = consolidator.run_once().await?;
+    assert_eq!(report.sessions, 2);
+    assert_eq!(report.summaries, 3);
+    assert_eq!(report.consolidated, 12);
+    assert_eq!(summarizer.calls.load(Ordering::SeqCst), 3);
+
+    // Rewritten originals are not listed twice
+    let items = session_items(&*store, "s1").await;
+    assert_eq!(items.len(), 12);
+    let summaries: Vec<&ContextItem> = items.iter().filter(|i| is_summary(i)).collect();
+    assert_eq!(summaries.len(), 2);
+    for summary in summaries {
+        let covered = store.get_related(&summary.id).await?;
+        assert_eq!(covered.len(), 4);
+        assert_eq!(
+            summary.metadata.tags[SUMMARY_COVERS_TAG],
+            summary.metadata.related_items.join(",")
+        );
+        for item in covered {
+            assert_eq!(item.metadata.tags[CONSOLIDATED_INTO_TAG], summary.id);
+            assert!(item.metadata.related_items.contains(&summary.id));
+        }
+        assert!(summary.metadata.importance >= 0.6);
+    }
+
+    // The two newest messages of s1 wait for a full cluster
+    let pending: Vec<&ContextItem> = items
+        .iter()
+        .filter(|i| !is_summary(i) && !i.metadata.tags.contains_key(CONSOLIDATED_INTO_TAG))
+        .collect();
+    assert_eq!(pending.len(), 2);
+
+    let report = consolidator.run_once().await?;
+    assert_eq!((report.summaries, report.consolidated), (0, 0));
+
+    store_messages(&*store, "s1", 10..12).await;
+    let report = consolidator.run_once().await?;
+    assert_eq!((report.summaries, report.consolidated), (1, 4));
+    // 16 originals and 4 summaries
+    assert_eq!(store.count().await?, 20);
+    Ok(())
+}
+
+#[tokio::test]
+async fn young_items_are_not_clustered() -> Result<()> {
+    let store: Arc<dyn MemoryStore> = InMemoryStore::new();
+    for i in 0..8 {
+        let item = ContextItem::new(
+            ContextItemType::Message {
+                role: MessageRole::User,
+            },
+            ContextContent::from_string(format!("Fresh {}", i)),
+            ContextMetadata::new("s3".to_string(), "agent".to_string()),
+        );
+        store.store(item).await?;
+    }
+    let consolidator = MemoryConsolidator::new(
+        Arc::clone(&store),
+        Arc::new(CountingSummarizer::default()),
+        ConsolidationConfig {
+            min_age: Duration::from_secs(3600),
+            ..clustering()
+        },
+    );
+
+    let report = consolidator.run_once().await?;
+    assert_eq!(report.summaries, 0);
+    assert_eq!(store.count().await?, 8);
+    Ok(())
+}
+
+#[tokio::test]
+async fn repeatedly_retrieved_items_are_boosted() -> Result<()> {
+    let store: Arc<dyn MemoryStore> = InMemoryStore::new();
+    store_messages(&*store, "s4", 0..3).await;
+    let items = session_items(&*store, "s4").await;
+    let consolidator = MemoryConsolidator::new(
+        Arc::clone(&store),
+        Arc::new(CountingSummarizer::default()),
+        ConsolidationConfig {
+            min_age: Duration::from_secs(u32::MAX as u64),
+            ..clustering()
+        },
+    );
+
+    for _ in 0..3 {
+        consolidator.record_retrieval(&items[..1]);
+    }
+    consolidator.record_retrieval(&items[1..2]);
+    assert_eq!(consolidator.pending_retrievals(), 2);
+
+    let report = consolidator.run_once().await?;
+    assert_eq!(report.boosted, 1);
+    assert_eq!(consolidator.pending_retrievals(), 0);
+
+    let hot = store.get(&items[0].id).await?.unwrap();
+    assert!((hot.metadata.importance - 0.6).abs() < 1e-6);
+    assert_eq!(hot.metadata.tags[RETRIEVALS_TAG], "3");
+    assert!(hot.metadata.tags.contains_key(LAST_RETRIEVED_TAG));
+
+    // Retrieved once: counted, not boosted
+    let warm = store.get(&items[1].id).await?.unwrap();
+    assert!((warm.metadata.importance - 0.5).abs() < 1e-6);
+    assert_eq!(warm.metadata.tags[RETRIEVALS_TAG], "1");
+
+    // Counts accumulate across runs
+    consolidator.record_retrieval(&items[1..2]);
+    consolidator.run_once().await?;
+    let warm = store.get(&items[1].id).await?.unwrap();
+    assert_eq!(warm.metadata.tags[RETRIEVALS_TAG], "2");
+    Ok(())
+}
+
+#[tokio::test]
+async fn stale_items_are_demoted_once_per_period() -> Result<()> {
+    let store: Arc<dyn MemoryStore> = InMemoryStore::new();
+    store_messages(&*store, "s5", 0..3).await;
+    let items = session_items(&*store, "s5").await;
+    let consolidator = MemoryConsolidator::new(
+        Arc::clone(&store),
+        Arc::new(CountingSummarizer::default()),
+        ConsolidationConfig {
+            min_age: Duration::from_secs(u32::MAX as u64),
+            stale_after: Duration::from_secs(3600),
+            demotion: 0.2,
+            importance_floor: 0.2,
+            ..Default::default()
+        },
+    );
+
+    // A recent retrieval keeps an old item fresh
+    consolidator.record_retrieval(&items[..1]);
+    let report = consolidator.run_once().await?;
+    assert_eq!(report.demoted, 2);
+    let kept = store.get(&items[0].id).await?.unwrap();
+    assert!((kept.metadata.importance - 0.5).abs() < 1e-6);
+    let demoted = store.get(&items[1].id).await?.unwrap();
+    assert!((demoted.metadata.importance - 0.3).abs() < 1e-6);
+    assert!(demoted.metadata.tags.contains_key(DEMOTED_AT_TAG));
+
+    // Demoted items stay retrievable and are not demoted again within the period
+    assert_eq!(session_items(&*store, "s5").await.len(), 3);
+    let report = consolidator.run_once().await?;
+    assert_eq!(report.demoted, 0);
+    Ok(())
+}
+
+#[tokio::test]
+async fn pipeline_reports_selected_items_to_the_consolidator() -> Result<()> {
+    let store: Arc<dyn MemoryStore> = InMemoryStore::new();
+    store_messages(&*store, "s6", 0..5).await;
+    let consolidator = Arc::new(MemoryConsolidator::new(
+        Arc::clone(&store),
+        Arc::new(CountingSummarizer::default()),
+        clustering(),
+    ));
+    let pipeline = ContextPipeline::new(
+        Arc::clone(&store),
+        RecencyRetrieval::new(100),
+        TemporalRanker::newest_first(),
+        WindowManager::new(Arc::new(TiktokenCounter::gpt4()), WindowConfig::default()),
+        PipelineConfig::default(),
+    )
+    .with_consolidator(Arc::clone(&consolidator));
+
+    let trigger = RetrievalTrigger::new("s6".to_string(), "agent".to_string());
+    let result = pipeline.execute(trigger).await?;
+    assert_eq!(result.items.len(), 5);
+    assert_eq!(consolidator.pending_retrievals(), 5);
+    Ok(())
+}
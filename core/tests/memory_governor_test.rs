@@ -0,0 +1,273 @@
+/// Tests for MemoryGovernor: caps, shedding order, pressure events, EventBus QoS shedding
+use async_trait::async_trait;
+use loom_core::dashboard::FlowTracker;
+use loom_core::governor::PRESSURE_TOPIC;
+use loom_core::proto::{Event, QoSLevel};
+use loom_core::{
+    EventBus, MemoryComponent, MemoryGovernor, MemoryGovernorConfig, PressureLevel, PressureReport,
+};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU8, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Component holding a fixed number of bytes that it frees on request
+struct Ballast {
+    name: String,
+    priority: u8,
+    bytes: AtomicUsize,
+    pressure: AtomicU8,
+}
+
+impl Ballast {
+    fn new(name: &str, priority: u8, bytes: usize) -> Arc<Self> {
+        Arc::new(Self {
+            name: name.to_string(),
+            priority,
+            bytes: AtomicUsize::new(bytes),
+            pressure: AtomicU8::new(PressureLevel::Normal.as_u8()),
+        })
+    }
+
+    fn bytes(&self) -> usize {
+        self.bytes.load(Ordering::SeqCst)
+    }
+}
+
+#[async_trait]
+impl MemoryComponent for Ballast {
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn shed_priority(&self) -> u8 {
+        self.priority
+    }
+
+    async fn memory_usage(&self) -> usize {
+        self.bytes()
+    }
+
+    async fn shed(&self, bytes: usize, _level: PressureLevel) -> usize {
+        let held = self.bytes();
+        let freed = bytes.min(held);
+        self.bytes.store(held - freed, Ordering::SeqCst);
+        freed
+    }
+
+    async fn on_pressure(&self, level: PressureLevel) {
+        self.pressure.store(level.as_u8(), Ordering::SeqCst);
+    }
+}
+
+fn make_event(id: &str, payload_len: usize) -> Event {
+    Event {
+        id: id.to_string(),
+        r#type: "test".to_string(),
+        timestamp_ms: 0,
+        source: "test".to_string(),
+        metadata: HashMap::new(),
+        payload: vec![0u8; payload_len],
+        confidence: 1.0,
+        tags: vec![],
+        priority: 50,
+    }
+}
+
+fn config(cap: usize) -> MemoryGovernorConfig {
+    MemoryGovernorConfig::default()
+        .with_global_cap(cap)
+        .with_elevated_ratio(0.8)
+}
+
+#[tokio::test]
+async fn below_threshold_is_normal_and_sheds_nothing() {
+    let governor = MemoryGovernor::new(config(1_000));
+    let a = Ballast::new("a", 10, 300);
+    governor.register(a.clone());
+
+    let report = governor.check().await;
+    assert_eq!(report.level, PressureLevel::Normal);
+    assert_eq!(report.total_bytes, 300);
+    assert_eq!(report.shed_bytes, 0);
+    assert_eq!(a.bytes(), 300);
+}
+
+#[tokio::test]
+async fn component_cap_sheds_only_that_component() {
+    let governor = MemoryGovernor::new(config(10_000).with_component_cap("a", 100));
+    let a = Ballast::new("a", 10, 250);
+    let b = Ballast::new("b", 20, 250);
+    governor.register(a.clone());
+    governor.register(b.clone());
+
+    let report = governor.check().await;
+    assert_eq!(report.level, PressureLevel::Normal);
+    assert_eq!(a.bytes(), 100);
+    assert_eq!(b.bytes(), 250);
+
+    let a_usage = report.components.iter().find(|c| c.name == "a").unwrap();
+    assert_eq!(a_usage.cap_bytes, Some(100));
+    assert_eq!(a_usage.shed_bytes, 150);
+}
+
+#[tokio::test]
+async fn global_pressure_sheds_lowest_priority_first() {
+    let governor = MemoryGovernor::new(config(1_000));
+    let first = Ballast::new("first", 10, 300);
+    let second = Ballast::new("second", 50, 600);
+    let last = Ballast::new("last", 90, 200);
+    governor.register(last.clone());
+    governor.register(second.clone());
+    governor.register(first.clone());
+
+    // 1100 bytes >= cap: critical; shed down to the 800 byte elevated threshold
+    let report = governor.check().await;
+    assert_eq!(report.level, PressureLevel::Critical);
+    assert_eq!(report.total_bytes, 800);
+    assert_eq!(first.bytes(), 0);
+    assert_eq!(second.bytes(), 600);
+    assert_eq!(last.bytes(), 200);
+    assert_eq!(governor.level(), PressureLevel::Critical);
+
+    // Components were told about the level change
+    assert_eq!(
+        PressureLevel::from_u8(last.pressure.load(Ordering::SeqCst)),
+        PressureLevel::Critical
+    );
+}
+
+#[tokio::test]
+async fn pressure_events_are_published_and_recovery_is_announced() {
+    let event_bus = Arc::new(EventBus::new().await.unwrap());
+    let (_sub, mut rx) = event_bus
+        .subscribe(PRESSURE_TOPIC.to_string(), vec![], QoSLevel::QosBatched)
+        .await
+        .unwrap();
+
+    let governor = MemoryGovernor::new(config(1_000)).with_event_bus(Arc::clone(&event_bus));
+    // Report-only component: pressure persists until it releases memory itself
+    let held = Ballast::new("held", 10, 900);
+    governor.register(Arc::new(ReportOnly(Arc::clone(&held))));
+
+    governor.check().await;
+    let ev = tokio::time::timeout(Duration::from_secs(1), rx.recv())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(ev.r#type, PRESSURE_TOPIC);
+    assert_eq!(
+        ev.metadata.get("level").map(String::as_str),
+        Some("elevated")
+    );
+    let report: PressureReport = serde_json::from_slice(&ev.payload).unwrap();
+    assert_eq!(report.level, PressureLevel::Elevated);
+    assert_eq!(report.previous_level, PressureLevel::Normal);
+    assert_eq!(report.total_bytes, 900);
+
+    // Back under the threshold: one recovery event, then silence
+    held.bytes.store(100, Ordering::SeqCst);
+    governor.check().await;
+    let ev = tokio::time::timeout(Duration::from_secs(1), rx.recv())
+        .await
+        .unwrap()
+        .unwrap();
+    let report: PressureReport = serde_json::from_slice(&ev.payload).unwrap();
+    assert_eq!(report.level, PressureLevel::Normal);
+    assert_eq!(report.previous_level, PressureLevel::Elevated);
+
+    governor.check().await;
+    assert!(rx.try_recv().is_err());
+}
+
+/// Wraps a Ballast but refuses to shed
+struct ReportOnly(Arc<Ballast>);
+
+#[async_trait]
+impl MemoryComponent for ReportOnly {
+    fn name(&self) -> String {
+        self.0.name()
+    }
+
+    async fn memory_usage(&self) -> usize {
+        self.0.bytes()
+    }
+}
+
+#[tokio::test]
+async fn event_bus_sheds_low_qos_deliveries_under_pressure() {
+    let event_bus = Arc::new(EventBus::new().await.unwrap());
+    let (_s1, mut realtime) = event_bus
+        .subscribe("t".into(), vec![], QoSLevel::QosRealtime)
+        .await
+        .unwrap();
+    let (_s2, mut batched) = event_bus
+        .subscribe("t".into(), vec![], QoSLevel::QosBatched)
+        .await
+        .unwrap();
+    let (_s3, mut background) = event_bus
+        .subscribe("t".into(), vec![], QoSLevel::QosBackground)
+        .await
+        .unwrap();
+
+    // Unconsumed events count towards the bus's usage
+    assert_eq!(
+        event_bus.publish("t", make_event("e0", 100)).await.unwrap(),
+        3
+    );
+    assert!(event_bus.memory_usage().await > 3 * 100);
+
+    event_bus.on_pressure(PressureLevel::Elevated).await;
+    assert_eq!(
+        event_bus.publish("t", make_event("e1", 10)).await.unwrap(),
+        2
+    );
+
+    event_bus.on_pressure(PressureLevel::Critical).await;
+    assert_eq!(
+        event_bus.publish("t", make_event("e2", 10)).await.unwrap(),
+        1
+    );
+
+    event_bus.on_pressure(PressureLevel::Normal).await;
+    assert_eq!(
+        event_bus.publish("t", make_event("e3", 10)).await.unwrap(),
+        3
+    );
+
+    let drain = |rx: &mut tokio::sync::mpsc::Receiver<Event>| {
+        let mut ids = Vec::new();
+        while let Ok(ev) = rx.try_recv() {
+            ids.push(ev.id);
+        }
+        ids
+    };
+    assert_eq!(drain(&mut realtime), vec!["e0", "e1", "e2", "e3"]);
+    assert_eq!(drain(&mut batched), vec!["e0", "e1", "e3"]);
+    assert_eq!(drain(&mut background), vec!["e0", "e3"]);
+    assert_eq!(event_bus.memory_usage().await, 0);
+    assert_eq!(event_bus.get_stats("t").unwrap().dropped_events, 3);
+}
+
+#[tokio::test]
+async fn flow_tracker_sheds_oldest_flows() {
+    let tracker = FlowTracker::new();
+    tracker.record_flow("agent.a", "EventBus", "old").await;
+    tokio::time::sleep(Duration::from_millis(5)).await;
+    tracker.record_flow("agent.b", "EventBus", "new").await;
+
+    let before = tracker.memory_usage().await;
+    let freed = tracker.shed(1, PressureLevel::Elevated).await;
+    assert!(freed > 0);
+    assert_eq!(tracker.memory_usage().await, before - freed);
+
+    let graph = tracker.get_graph().await;
+    assert_eq!(graph.flows.len(), 1);
+    assert_eq!(graph.flows[0].topic, "new");
+
+    // Critical pressure also drops nodes without remaining flows
+    tracker.shed(0, PressureLevel::Critical).await;
+    let graph = tracker.get_graph().await;
+    assert!(graph.nodes.iter().all(|n| n.id != "agent.a"));
+    assert!(graph.nodes.iter().any(|n| n.id == "agent.b"));
+}
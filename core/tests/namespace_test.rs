@@ -0,0 +1,284 @@
+/// Tests for namespaces: validation, EventBus isolation and bridges, tool views, agents
+use async_trait::async_trait;
+use loom_core::agent::{AgentBehavior, AgentRuntime};
+use loom_core::namespace::NAMESPACE_KEY;
+use loom_core::proto::{Action, AgentConfig, AgentState};
+use loom_core::tenancy::TENANT_KEY;
+use loom_core::{
+    Event, EventBus, LoomError, ModelRouter, Namespace, NamespaceBridge, QoSLevel, Result, Tool,
+    ToolRegistry,
+};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+fn event(namespace: Option<&str>) -> Event {
+    let mut metadata = HashMap::new();
+    if let Some(namespace) = namespace {
+        metadata.insert(TENANT_KEY.to_string(), namespace.to_string());
+    }
+    Event {
+        id: "evt".into(),
+        r#type: "test".into(),
+        timestamp_ms: 0,
+        source: "test".into(),
+        metadata,
+        payload: vec![],
+        confidence: 1.0,
+        tags: vec![],
+        priority: 50,
+    }
+}
+
+/// Tool answering with a fixed label
+struct LabelTool {
+    name: &'static str,
+    label: &'static str,
+}
+
+#[async_trait]
+impl Tool for LabelTool {
+    fn name(&self) -> String {
+        self.name.to_string()
+    }
+
+    fn description(&self) -> String {
+        "Answers with its label".to_string()
+    }
+
+    fn parameters(&self) -> Value {
+        json!({"type": "object"})
+    }
+
+    async fn call(&self, _arguments: Value) -> loom_core::tools::ToolResult<Value> {
+        Ok(json!({"label": self.label}))
+    }
+}
+
+fn label_tool(name: &'static str, label: &'static str) -> Arc<LabelTool> {
+    Arc::new(LabelTool { name, label })
+}
+
+#[test]
+fn namespace_ids_are_validated_and_scope_topics() -> Result<()> {
+    for id in ["", "a.b", "a/b"] {
+        assert!(matches!(
+            Namespace::new(id),
+            Err(LoomError::NamespaceError(_))
+        ));
+    }
+    let shop = Namespace::new("shop")?;
+    assert_eq!(shop.topic("orders"), "tenant.shop.orders");
+    assert_eq!(shop.scope("orders"), "tenant.shop.orders");
+    assert_eq!(shop.scope("tenant.shop.orders"), "tenant.shop.orders");
+    assert_eq!(shop.strip("tenant.shop.orders"), Some("orders"));
+    assert!(!shop.owns("tenant.ops.orders"));
+    assert_eq!(shop.tool_key("kb:search"), "shop/kb:search");
+
+    let parsed: Namespace = serde_json::from_str("\"ops\"").unwrap();
+    assert_eq!(parsed.as_str(), "ops");
+    assert!(serde_json::from_str::<Namespace>("\"o.ps\"").is_err());
+    Ok(())
+}
+
+#[tokio::test]
+async fn event_bus_denies_cross_namespace_publish_unless_bridged() -> Result<()> {
+    let bus = EventBus::new().await?;
+    bus.start().await?;
+    let (_sid, mut rx) = bus
+        .subscribe(
+            "tenant.ops.alerts.disk".into(),
+            vec![],
+            QoSLevel::QosBatched,
+        )
+        .await?;
+
+    let err = bus
+        .publish("tenant.ops.alerts.disk", event(Some("shop")))
+        .await
+        .unwrap_err();
+    assert!(matches!(err, LoomError::TenantError(_)));
+
+    let bridge = NamespaceBridge::new(Namespace::new("shop")?, Namespace::new("ops")?, "alerts.*");
+    bus.bridge_namespaces(bridge.clone());
+    bus.bridge_namespaces(bridge.clone());
+    assert_eq!(bus.namespace_bridges().len(), 1);
+
+    bus.publish("tenant.ops.alerts.disk", event(Some("shop")))
+        .await?;
+    tokio::time::timeout(Duration::from_secs(1), rx.recv())
+        .await
+        .unwrap()
+        .unwrap();
+    // Bridges are one-way and limited to their topics
+    assert!(bus
+        .publish("tenant.ops.deploys", event(Some("shop")))
+        .await
+        .is_err());
+    assert!(bus
+        .publish("tenant.shop.orders", event(Some("ops")))
+        .await
+        .is_err());
+    assert!(bus
+        .publish_batch("tenant.ops.alerts.cpu", vec![event(Some("globex"))])
+        .await
+        .is_err());
+
+    assert!(bus.unbridge_namespaces(&bridge));
+    assert!(!bus.unbridge_namespaces(&bridge));
+    assert!(bus
+        .publish("tenant.ops.alerts.disk", event(Some("shop")))
+        .await
+        .is_err());
+    Ok(())
+}
+
+#[tokio::test]
+async fn tool_registry_namespace_views() -> Result<()> {
+    let registry = ToolRegistry::new();
+    let shop = Namespace::new("shop")?;
+    let ops = Namespace::new("ops")?;
+    registry.register(label_tool("lookup", "global")).await;
+    registry.register(label_tool("clock", "global")).await;
+    registry
+        .register_in(&shop, label_tool("lookup", "shop"))
+        .await;
+    registry
+        .register_in(&shop, label_tool("orders", "shop"))
+        .await;
+
+    // The root view holds the global tools only
+    let mut names: Vec<String> = registry.list_tools().iter().map(|t| t.name()).collect();
+    names.sort();
+    assert_eq!(names, vec!["clock", "lookup"]);
+    assert_eq!(
+        registry.call("lookup", json!({})).await.unwrap()["label"],
+        "global"
+    );
+    assert!(registry.call("orders", json!({})).await.is_err());
+
+    // A namespace sees its own tools, shadowing global ones, by their plain names
+    let shop_tools = registry.for_namespace(&shop);
+    let mut names: Vec<String> = shop_tools.list_tools().iter().map(|t| t.name()).collect();
+    names.sort();
+    assert_eq!(names, vec!["clock", "lookup", "orders"]);
+    assert_eq!(
+        shop_tools.call("lookup", json!({})).await.unwrap()["label"],
+        "shop"
+    );
+    assert_eq!(
+        shop_tools.call("clock", json!({})).await.unwrap()["label"],
+        "global"
+    );
+    assert!(registry.tool_stats("shop/lookup").is_some());
+
+    let ops_tools = registry.for_namespace(&ops);
+    assert!(ops_tools.get("orders").is_none());
+    assert!(ops_tools.get("shop/orders").is_none());
+    assert_eq!(
+        ops_tools.call("lookup", json!({})).await.unwrap()["label"],
+        "global"
+    );
+
+    // Scoping still applies inside a namespace
+    let scoped = shop_tools.scoped(["orders"]);
+    assert!(scoped.get("lookup").is_none());
+    assert!(scoped.get("orders").is_some());
+    Ok(())
+}
+
+/// Calls `lookup` for every event
+struct LookupBehavior;
+
+#[async_trait]
+impl AgentBehavior for LookupBehavior {
+    async fn on_event(&mut self, event: Event, _state: &mut AgentState) -> Result<Vec<Action>> {
+        Ok(vec![Action {
+            action_type: "lookup".to_string(),
+            parameters: Default::default(),
+            payload: event.payload,
+            priority: 50,
+        }])
+    }
+
+    async fn on_init(&mut self, _config: &AgentConfig) -> Result<()> {
+        Ok(())
+    }
+
+    async fn on_shutdown(&mut self) -> Result<()> {
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn runtime_agents_live_in_their_namespace() -> Result<()> {
+    let bus = Arc::new(EventBus::new().await?);
+    bus.start().await?;
+    let registry = Arc::new(ToolRegistry::new());
+    let shop = Namespace::new("shop")?;
+    registry.register(label_tool("lookup", "global")).await;
+    registry
+        .register_in(&shop, label_tool("lookup", "shop"))
+        .await;
+    let runtime = AgentRuntime::new(
+        Arc::clone(&bus),
+        Arc::clone(&registry),
+        ModelRouter::new().await?,
+    )
+    .await?;
+
+    let config = |namespace: &str| AgentConfig {
+        agent_id: "clerk".to_string(),
+        agent_type: "test".to_string(),
+        subscribed_topics: vec!["orders".to_string()],
+        capabilities: vec![],
+        parameters: HashMap::from([(NAMESPACE_KEY.to_string(), namespace.to_string())]),
+    };
+    assert!(runtime
+        .create_agent(config("sh.op"), Box::new(LookupBehavior))
+        .await
+        .is_err());
+    runtime
+        .create_agent(config("shop"), Box::new(LookupBehavior))
+        .await?;
+    assert_eq!(runtime.get_agent_namespace("clerk")?, Some(shop.clone()));
+    assert!(runtime
+        .get_agent_subscriptions("clerk")?
+        .contains(&"tenant.shop.orders".to_string()));
+
+    let (_sid, mut results) = bus
+        .subscribe(
+            "tenant.shop.agent.clerk".into(),
+            vec!["action_result".into()],
+            QoSLevel::QosBatched,
+        )
+        .await?;
+    // The global topic does not reach the agent; its namespace's does
+    bus.publish("orders", event(None)).await?;
+    bus.publish("tenant.shop.orders", event(Some("shop")))
+        .await?;
+
+    let result = tokio::time::timeout(Duration::from_secs(2), results.recv())
+        .await
+        .expect("action result in time")
+        .unwrap();
+    assert_eq!(result.metadata[TENANT_KEY], "shop");
+    let payload: Value = serde_json::from_slice(&result.payload).unwrap();
+    assert_eq!(payload["label"], "shop");
+    assert!(
+        tokio::time::timeout(Duration::from_millis(200), results.recv())
+            .await
+            .is_err()
+    );
+
+    // Runtime subscriptions are namespace-relative too
+    runtime
+        .subscribe_agent("clerk", "returns".to_string())
+        .await?;
+    assert!(runtime
+        .get_agent_subscriptions("clerk")?
+        .contains(&"tenant.shop.returns".to_string()));
+    runtime.unsubscribe_agent("clerk", "returns").await?;
+    Ok(())
+}
@@ -0,0 +1,242 @@
+//! Tests for the OpenAI-compatible chat completions facade
+
+use async_trait::async_trait;
+use loom_core::agent::AgentBehavior;
+use loom_core::cognitive::{
+    CognitiveAgent, CognitiveLoop, ExecutionResult, MemoryBuffer, Perception, Plan,
+    EXPECT_REPLY_KEY, REPLY_ACTION,
+};
+use loom_core::proto::{AgentConfig, AgentState, Event};
+use loom_core::{
+    AgentRuntime, EventBus, ModelRouter, OpenAiConfig, OpenAiServer, Result, ToolRegistry,
+};
+use serde_json::{json, Value};
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Answers every goal with "echo: <goal>"
+struct EchoLoop {
+    memory: MemoryBuffer,
+}
+
+#[async_trait]
+impl CognitiveLoop for EchoLoop {
+    async fn perceive(&mut self, event: Event, _state: &AgentState) -> Result<Perception> {
+        Ok(Perception::from_event(event))
+    }
+
+    async fn think(&mut self, perception: &Perception) -> Result<Plan> {
+        let goal = perception.goal.clone().unwrap_or_default();
+        Ok(Plan::final_answer(goal.clone(), format!("echo: {goal}")))
+    }
+
+    async fn act(&mut self, plan: &Plan, _state: &mut AgentState) -> Result<ExecutionResult> {
+        Ok(ExecutionResult::with_response(
+            plan.final_answer.clone().unwrap_or_default(),
+        ))
+    }
+
+    fn memory_buffer(&self) -> &MemoryBuffer {
+        &self.memory
+    }
+
+    fn memory_buffer_mut(&mut self) -> &mut MemoryBuffer {
+        &mut self.memory
+    }
+}
+
+fn echo_agent() -> CognitiveAgent<EchoLoop> {
+    CognitiveAgent::new(EchoLoop {
+        memory: MemoryBuffer::new(10),
+    })
+}
+
+/// Event bus with an echo agent `assistant` and the facade on an ephemeral port
+async fn start_facade(config: OpenAiConfig, with_agent: bool) -> (SocketAddr, AgentRuntime) {
+    let bus = Arc::new(EventBus::new().await.unwrap());
+    bus.start().await.unwrap();
+    let runtime = AgentRuntime::new(
+        Arc::clone(&bus),
+        Arc::new(ToolRegistry::new()),
+        ModelRouter::new().await.unwrap(),
+    )
+    .await
+    .unwrap();
+    if with_agent {
+        let agent_config = AgentConfig {
+            agent_id: "assistant".to_string(),
+            agent_type: "cognitive".to_string(),
+            subscribed_topics: vec![],
+            capabilities: vec![],
+            parameters: Default::default(),
+        };
+        runtime
+            .create_agent(agent_config, Box::new(echo_agent()))
+            .await
+            .unwrap();
+    }
+
+    let app = OpenAiServer::new(config, bus).router();
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+    (addr, runtime)
+}
+
+fn request(stream: bool) -> Value {
+    json!({
+        "model": "gpt-4o",
+        "stream": stream,
+        "messages": [
+            { "role": "system", "content": "Be brief." },
+            { "role": "user", "content": "first question" },
+            { "role": "assistant", "content": "first answer" },
+            { "role": "user", "content": [{ "type": "text", "text": "hello loom" }] },
+        ],
+    })
+}
+
+#[tokio::test]
+async fn cognitive_agent_replies_when_asked() {
+    let mut agent = echo_agent();
+    let mut state = AgentState {
+        agent_id: "assistant".to_string(),
+        ..Default::default()
+    };
+    let mut event = Event {
+        id: "q1".to_string(),
+        r#type: "user.query".to_string(),
+        payload: b"ping".to_vec(),
+        ..Default::default()
+    };
+    assert!(agent
+        .on_event(event.clone(), &mut state)
+        .await
+        .unwrap()
+        .is_empty());
+
+    event
+        .metadata
+        .insert(EXPECT_REPLY_KEY.to_string(), "true".to_string());
+    let actions = agent.on_event(event, &mut state).await.unwrap();
+    assert_eq!(actions.len(), 1);
+    assert_eq!(actions[0].action_type, REPLY_ACTION);
+    assert_eq!(actions[0].payload, b"echo: ping");
+    assert_eq!(
+        actions[0].parameters.get("reply_to").map(String::as_str),
+        Some("thread.q1.reply")
+    );
+}
+
+#[tokio::test]
+async fn chat_completion_roundtrip() {
+    let (addr, _runtime) = start_facade(OpenAiConfig::default(), true).await;
+    let client = reqwest::Client::new();
+
+    let body: Value = client
+        .post(format!("http://{addr}/v1/chat/completions"))
+        .json(&request(false))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(body["object"], "chat.completion");
+    assert_eq!(body["model"], "gpt-4o");
+    assert_eq!(body["choices"][0]["message"]["role"], "assistant");
+    assert_eq!(body["choices"][0]["message"]["content"], "echo: hello loom");
+    assert_eq!(body["choices"][0]["finish_reason"], "stop");
+    assert!(body["usage"]["completion_tokens"].as_u64().unwrap() > 0);
+
+    let models: Value = client
+        .get(format!("http://{addr}/v1/models"))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(models["data"][0]["id"], "assistant");
+}
+
+#[tokio::test]
+async fn chat_completion_streams_chunks() {
+    let (addr, _runtime) = start_facade(OpenAiConfig::default(), true).await;
+    let body = reqwest::Client::new()
+        .post(format!("http://{addr}/v1/chat/completions"))
+        .json(&request(true))
+        .send()
+        .await
+        .unwrap()
+        .text()
+        .await
+        .unwrap();
+
+    let data: Vec<&str> = body
+        .lines()
+        .filter_map(|line| line.strip_prefix("data: "))
+        .collect();
+    assert_eq!(data.last(), Some(&"[DONE]"));
+    let chunks: Vec<Value> = data[..data.len() - 1]
+        .iter()
+        .map(|d| serde_json::from_str(d).unwrap())
+        .collect();
+    assert!(chunks
+        .iter()
+        .all(|c| c["object"] == "chat.completion.chunk"));
+    assert_eq!(chunks[0]["choices"][0]["delta"]["role"], "assistant");
+    let content: String = chunks
+        .iter()
+        .filter_map(|c| c["choices"][0]["delta"]["content"].as_str())
+        .collect();
+    assert_eq!(content, "echo: hello loom");
+    assert_eq!(
+        chunks.last().unwrap()["choices"][0]["finish_reason"],
+        "stop"
+    );
+}
+
+#[tokio::test]
+async fn facade_rejects_bad_requests() {
+    let config = OpenAiConfig {
+        api_key: Some("sk-loom".to_string()),
+        timeout: Duration::from_secs(2),
+        ..Default::default()
+    };
+    let (addr, _runtime) = start_facade(config, false).await;
+    let client = reqwest::Client::new();
+    let url = format!("http://{addr}/v1/chat/completions");
+
+    let resp = client
+        .post(&url)
+        .json(&request(false))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 401);
+    let body: Value = resp.json().await.unwrap();
+    assert_eq!(body["error"]["type"], "invalid_request_error");
+
+    let resp = client
+        .post(&url)
+        .bearer_auth("sk-loom")
+        .json(&json!({ "model": "x", "messages": [{ "role": "system", "content": "hi" }] }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 400);
+
+    // No agent is subscribed to agent.assistant.replies
+    let resp = client
+        .post(&url)
+        .bearer_auth("sk-loom")
+        .json(&request(false))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 503);
+}
@@ -0,0 +1,122 @@
+/// Tests for dedicated runtime pools: isolation, overload policy, queue depth
+use loom_core::pools::PooledTool;
+use loom_core::tools::native::ReadFileTool;
+use loom_core::{DedicatedPool, LoomError, OverloadPolicy, PoolConfig, Tool};
+use serde_json::json;
+use std::sync::Arc;
+use std::time::Duration;
+
+fn thread_name() -> String {
+    std::thread::current()
+        .name()
+        .unwrap_or_default()
+        .to_string()
+}
+
+#[tokio::test]
+async fn work_runs_on_dedicated_threads() {
+    let pool = DedicatedPool::new("test", PoolConfig::new(2, 4)).unwrap();
+
+    let async_thread = pool.run(async { thread_name() }).await.unwrap();
+    assert_eq!(async_thread, "loom-test");
+
+    let blocking_thread = pool.run_blocking(thread_name).await.unwrap();
+    assert_eq!(blocking_thread, "loom-test");
+
+    assert_eq!(pool.inflight(), 0);
+}
+
+#[tokio::test]
+async fn full_pool_rejects_work() {
+    let pool = Arc::new(DedicatedPool::new("reject", PoolConfig::new(1, 1)).unwrap());
+    let (release_tx, release_rx) = std::sync::mpsc::channel::<()>();
+    let release_rx = Arc::new(std::sync::Mutex::new(release_rx));
+
+    // Occupy the worker and the single queue slot
+    let mut busy = Vec::new();
+    for _ in 0..2 {
+        let pool = Arc::clone(&pool);
+        let rx = Arc::clone(&release_rx);
+        busy.push(tokio::spawn(async move {
+            pool.run_blocking(move || {
+                let _ = rx.lock().unwrap().recv();
+            })
+            .await
+        }));
+    }
+    while pool.inflight() < 2 {
+        tokio::time::sleep(Duration::from_millis(5)).await;
+    }
+    assert_eq!(pool.queue_depth(), 1);
+
+    let rejected = pool.run_blocking(|| ()).await;
+    assert!(matches!(rejected, Err(LoomError::Overloaded(_))));
+
+    release_tx.send(()).unwrap();
+    release_tx.send(()).unwrap();
+    for h in busy {
+        h.await.unwrap().unwrap();
+    }
+    assert_eq!(pool.inflight(), 0);
+
+    // Capacity is available again
+    assert!(pool.run_blocking(|| ()).await.is_ok());
+}
+
+#[tokio::test]
+async fn caller_runs_policy_falls_back_to_caller_runtime() {
+    let pool = Arc::new(
+        DedicatedPool::new(
+            "caller",
+            PoolConfig::new(1, 0).with_overload(OverloadPolicy::CallerRuns),
+        )
+        .unwrap(),
+    );
+    let (release_tx, release_rx) = std::sync::mpsc::channel::<()>();
+
+    let busy = {
+        let pool = Arc::clone(&pool);
+        tokio::spawn(async move {
+            pool.run_blocking(move || {
+                let _ = release_rx.recv();
+            })
+            .await
+        })
+    };
+    while pool.inflight() < 1 {
+        tokio::time::sleep(Duration::from_millis(5)).await;
+    }
+
+    // Overflow work still completes, just not on the pool's threads
+    let name = pool.run(async { thread_name() }).await.unwrap();
+    assert_ne!(name, "loom-caller");
+
+    release_tx.send(()).unwrap();
+    busy.await.unwrap().unwrap();
+}
+
+#[tokio::test]
+async fn shut_down_pool_rejects_work() {
+    let pool = DedicatedPool::new("closed", PoolConfig::new(1, 1)).unwrap();
+    pool.shutdown();
+    assert!(matches!(
+        pool.run(async {}).await,
+        Err(LoomError::Overloaded(_))
+    ));
+}
+
+#[tokio::test]
+async fn pooled_tool_delegates_to_inner_tool() {
+    let workspace = tempfile::tempdir().unwrap();
+    std::fs::write(workspace.path().join("hello.txt"), "hi").unwrap();
+
+    let pool = Arc::new(DedicatedPool::new("tools", PoolConfig::new(1, 4)).unwrap());
+    let tool = PooledTool::new(
+        Arc::new(ReadFileTool::new(workspace.path().to_path_buf())),
+        pool,
+    );
+
+    assert_eq!(tool.name(), "fs:read_file");
+    let result = tool.call(json!({"path": "hello.txt"})).await.unwrap();
+    assert_eq!(result["content"], "hi");
+}
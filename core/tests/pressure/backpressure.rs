@@ -39,7 +39,7 @@ pub async fn threshold_enforcement() -> Result<()> {
         stats.dropped_events > 0,
         "Should drop events when over backpressure threshold"
     );
-    assert_eq!(stats.total_published, event_count as u64);
+    assert_eq!(stats.total_published, event_count);
 
     Ok(())
 }
@@ -21,6 +21,7 @@ pub fn make_event(id: u64, event_type: &str) -> Event {
 pub mod backpressure;
 pub mod filtering;
 pub mod latency;
+pub mod personas;
 pub mod qos_behavior;
 pub mod stats;
 pub mod throughput;
@@ -0,0 +1,115 @@
+//! Collaboration protocols against many scripted personas
+//!
+//! Tests covering contract-net, fanout and directory lookups at scale, with personas
+//! standing in for LLM-backed agents.
+
+use loom_core::messaging::collab::ContractNetConfig;
+use loom_core::simulation::{Simulation, SimulationConfig};
+use loom_core::{AgentDirectory, Collaborator, EventBus, Result};
+use serial_test::serial;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Test: Contract net awards the best of 200 bidders, who confirm
+#[tokio::test]
+#[serial]
+pub async fn contract_net_with_many_bidders() -> Result<()> {
+    let bus = Arc::new(EventBus::new().await?);
+    let personas = 200;
+    let config = SimulationConfig::new(personas)
+        .with_threads(vec!["pressure.cnp".into()])
+        .with_reply_latency(Duration::ZERO, Duration::from_millis(20));
+    let sim = Simulation::spawn(Arc::clone(&bus), config).await?;
+
+    let collab = Collaborator::new(Arc::clone(&bus), "agent.coordinator");
+    let config = ContractNetConfig::new(Duration::from_millis(300), 3)
+        .with_confirmation(Duration::from_millis(200));
+    let start = Instant::now();
+    let winners = collab
+        .contract_net_with("pressure.cnp", b"job".to_vec(), config)
+        .await?;
+    let elapsed = start.elapsed();
+    // Let the last confirmations be counted
+    tokio::time::sleep(Duration::from_millis(50)).await;
+
+    let stats = sim.stats();
+    println!(
+        "Contract net: {} proposals, {} winners in {:?}",
+        stats.proposals,
+        winners.len(),
+        elapsed
+    );
+
+    assert_eq!(stats.proposals, personas as u64);
+    assert_eq!(winners.len(), 3);
+    assert_eq!(winners[0].source, sim.best_bidder().unwrap().id);
+    assert_eq!((stats.awards, stats.confirms), (3, 3));
+    sim.stop().await;
+    Ok(())
+}
+
+/// Test: Fanout to 100 slow personas, a fifth of which fail
+#[tokio::test]
+#[serial]
+pub async fn fanout_with_slow_and_failing_personas() -> Result<()> {
+    let bus = Arc::new(EventBus::new().await?);
+    let personas = 100;
+    let config = SimulationConfig::new(personas)
+        .with_reply_latency(Duration::from_millis(1), Duration::from_millis(20))
+        .with_error_rate(0.2);
+    let sim = Simulation::spawn(Arc::clone(&bus), config).await?;
+
+    let collab = Collaborator::new(Arc::clone(&bus), "agent.coordinator");
+    let replies = collab
+        .fanout_fanin(&sim.topics(), b"ping".to_vec(), personas, 500)
+        .await?;
+
+    let stats = sim.stats();
+    println!(
+        "Fanout: {} requests, {} replies, {} failures",
+        stats.requests,
+        replies.len(),
+        stats.errors
+    );
+
+    assert_eq!(stats.requests, personas as u64);
+    assert_eq!(stats.replies + stats.errors, personas as u64);
+    assert_eq!(replies.len() as u64, stats.replies);
+    assert!(stats.errors > 0 && stats.errors < 50);
+    assert!(replies.iter().all(|r| r.payload == b"ping"));
+    sim.stop().await;
+    Ok(())
+}
+
+/// Test: Directory lookups over 5k registered personas
+#[tokio::test]
+#[serial]
+pub async fn directory_with_many_personas() -> Result<()> {
+    let bus = Arc::new(EventBus::new().await?);
+    let personas = 5_000;
+    let config = SimulationConfig::new(personas).with_capabilities(vec!["translate".into()]);
+    let sim = Simulation::spawn(Arc::clone(&bus), config).await?;
+    let directory = AgentDirectory::new();
+
+    let start = Instant::now();
+    sim.register(&directory);
+    let registered = start.elapsed();
+
+    let start = Instant::now();
+    let lookups = 1_000;
+    for i in 0..lookups {
+        assert_eq!(directory.by_topic(&sim.personas()[i].topic).len(), 1);
+    }
+    let translators = directory.by_capability("translate");
+    let looked_up = start.elapsed();
+
+    println!(
+        "Directory: registered {} personas in {:?}, {} lookups in {:?}",
+        personas, registered, lookups, looked_up
+    );
+
+    assert_eq!(translators.len(), personas);
+    assert_eq!(directory.all().len(), personas);
+    sim.stop().await;
+    Ok(())
+}
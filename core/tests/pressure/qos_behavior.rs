@@ -49,7 +49,7 @@ pub async fn realtime_qos_drops_under_load() -> Result<()> {
         "Not all events should be received due to drops"
     );
     assert_eq!(
-        stats.total_published, event_count as u64,
+        stats.total_published, event_count,
         "All events should be counted as published"
     );
 
@@ -92,7 +92,7 @@ pub async fn batched_qos_buffers_without_drop() -> Result<()> {
         "Batched should not drop within capacity"
     );
     assert_eq!(received, event_count);
-    assert_eq!(stats.total_delivered, event_count as u64);
+    assert_eq!(stats.total_delivered, event_count);
 
     Ok(())
 }
@@ -39,8 +39,8 @@ pub async fn stats_accuracy() -> Result<()> {
         stats.total_published, stats.total_delivered, received, stats.dropped_events
     );
 
-    assert_eq!(stats.total_published, event_count as u64);
-    assert_eq!(stats.total_delivered, received as u64);
+    assert_eq!(stats.total_published, event_count);
+    assert_eq!(stats.total_delivered, received);
     assert_eq!(stats.dropped_events, 0);
     assert_eq!(received, event_count);
 
@@ -28,7 +28,7 @@ pub async fn single_publisher_single_consumer() -> Result<()> {
     let received = Arc::new(AtomicU64::new(0));
     let received_clone = received.clone();
     let consumer = tokio::spawn(async move {
-        while received_clone.load(Ordering::Relaxed) < event_count as u64 {
+        while received_clone.load(Ordering::Relaxed) < event_count {
             if rx.recv().await.is_none() {
                 break;
             }
@@ -218,7 +218,7 @@ pub async fn multiple_subscribers_delivery() -> Result<()> {
         received_counters.push(counter.clone());
 
         let consumer = tokio::spawn(async move {
-            while counter.load(Ordering::Relaxed) < event_count as u64 {
+            while counter.load(Ordering::Relaxed) < event_count {
                 if rx.recv().await.is_none() {
                     break;
                 }
@@ -247,7 +247,7 @@ pub async fn multiple_subscribers_delivery() -> Result<()> {
         let count = counter.load(Ordering::Relaxed);
         println!("Subscriber {}: received {}", idx, count);
         assert_eq!(
-            count, event_count as u64,
+            count, event_count,
             "Each subscriber should receive all events"
         );
     }
@@ -0,0 +1,273 @@
+//! Property-based tests for envelope metadata, topic matching, collab correlation and
+//! the parsers that process untrusted LLM output.
+//!
+//! Coverage-guided fuzz targets for the same parsers live in `core/fuzz/`.
+
+use loom_core::cognitive::extract_tool_call;
+use loom_core::cognitive::llm::{parse_tool_calls_from_chat, parse_tool_calls_from_responses};
+use loom_core::messaging::collab::is_correlated;
+use loom_core::proto::Event;
+use loom_core::{topic_matches, Envelope};
+use proptest::prelude::*;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+
+fn event(id: &str) -> Event {
+    Event {
+        id: id.to_string(),
+        r#type: "collab.reply".to_string(),
+        timestamp_ms: 0,
+        source: "responder".to_string(),
+        metadata: HashMap::new(),
+        payload: vec![],
+        confidence: 1.0,
+        tags: vec![],
+        priority: 50,
+    }
+}
+
+fn arb_envelope() -> impl Strategy<Value = Envelope> {
+    (
+        (".*", ".*", ".*", ".*"),
+        (any::<i32>(), any::<u32>(), any::<i64>()),
+        ("[0-9a-f]{0,32}", "[0-9a-f]{0,16}", "[0-9a-f]{0,2}"),
+        (".*", prop::option::of(any::<i64>()), any::<bool>()),
+    )
+        .prop_map(
+            |(
+                (thread_id, correlation_id, sender, reply_to),
+                (ttl, hop, timestamp_ms),
+                (trace_id, span_id, trace_flags),
+                (caused_by, deadline_ms, reply_expected),
+            )| Envelope {
+                thread_id,
+                correlation_id,
+                sender,
+                reply_to,
+                ttl,
+                hop,
+                timestamp_ms,
+                trace_id,
+                span_id,
+                trace_flags,
+                caused_by,
+                deadline_ms,
+                reply_expected,
+            },
+        )
+}
+
+fn arb_json() -> impl Strategy<Value = Value> {
+    let leaf = prop_oneof![
+        Just(Value::Null),
+        any::<bool>().prop_map(Value::Bool),
+        any::<i64>().prop_map(|n| json!(n)),
+        ".*".prop_map(Value::String),
+        // Bias towards the keys the parsers look for
+        prop_oneof![
+            Just("tool_use"),
+            Just("function"),
+            Just("arguments"),
+            Just("{\"k\":1}")
+        ]
+        .prop_map(|s| Value::String(s.to_string())),
+    ];
+    leaf.prop_recursive(5, 64, 6, |inner| {
+        let key = prop_oneof![
+            Just("output".to_string()),
+            Just("content".to_string()),
+            Just("type".to_string()),
+            Just("name".to_string()),
+            Just("id".to_string()),
+            Just("input".to_string()),
+            Just("choices".to_string()),
+            Just("message".to_string()),
+            Just("tool_calls".to_string()),
+            Just("function".to_string()),
+            Just("arguments".to_string()),
+            "[a-z]{1,6}",
+        ];
+        prop_oneof![
+            prop::collection::vec(inner.clone(), 0..4).prop_map(Value::Array),
+            prop::collection::hash_map(key, inner, 0..4)
+                .prop_map(|m| Value::Object(m.into_iter().collect())),
+        ]
+    })
+}
+
+/// Reference matcher mirroring the documented wildcard semantics
+fn reference_match(pattern: &str, topic: &str) -> bool {
+    if pattern == topic {
+        return true;
+    }
+    match pattern.strip_suffix(".*") {
+        Some(prefix) => {
+            topic.len() > prefix.len()
+                && topic.starts_with(prefix)
+                && topic.as_bytes()[prefix.len()] == b'.'
+        }
+        None => false,
+    }
+}
+
+proptest! {
+    // ===== Envelope =====
+
+    #[test]
+    fn envelope_metadata_roundtrip(env in arb_envelope()) {
+        let mut meta = HashMap::new();
+        env.apply_to_metadata(&mut meta);
+        prop_assert_eq!(Envelope::from_metadata(&meta, "fallback"), env);
+    }
+
+    #[test]
+    fn envelope_event_roundtrip(env in arb_envelope(), id in ".*") {
+        let mut evt = event(&id);
+        env.attach_to_event(&mut evt);
+        prop_assert_eq!(Envelope::from_event(&evt), env);
+    }
+
+    #[test]
+    fn envelope_from_arbitrary_metadata_falls_back(
+        meta in prop::collection::hash_map(
+            prop_oneof![
+                Just("thread_id".to_string()),
+                Just("correlation_id".to_string()),
+                Just("ttl".to_string()),
+                Just("hop".to_string()),
+                Just("ts".to_string()),
+                ".*",
+            ],
+            ".*",
+            0..8,
+        ),
+        fallback in ".*",
+    ) {
+        let env = Envelope::from_metadata(&meta, &fallback);
+        let expected_thread = meta.get("thread_id").unwrap_or(&fallback);
+        prop_assert_eq!(&env.thread_id, expected_thread);
+        let expected_corr = meta.get("correlation_id").unwrap_or(&env.thread_id);
+        prop_assert_eq!(&env.correlation_id, expected_corr);
+        if meta.get("ttl").and_then(|s| s.parse::<i32>().ok()).is_none() {
+            prop_assert_eq!(env.ttl, 16);
+        }
+    }
+
+    #[test]
+    fn envelope_next_hop_never_increases_ttl(ttl in any::<i32>(), hop in any::<u32>()) {
+        let mut env = Envelope::new("t", "agent.a");
+        env.ttl = ttl;
+        env.hop = hop;
+        let alive = env.next_hop();
+        prop_assert_eq!(alive, env.ttl > 0);
+        prop_assert!(env.ttl <= ttl);
+        prop_assert!(env.ttl >= ttl.min(0));
+        prop_assert_eq!(env.hop, hop.saturating_add(1));
+    }
+
+    // ===== Topic matcher =====
+
+    #[test]
+    fn topic_matches_agrees_with_reference(pattern in "[ab.*]{0,8}", topic in "[ab.*]{0,8}") {
+        prop_assert_eq!(topic_matches(&pattern, &topic), reference_match(&pattern, &topic));
+    }
+
+    #[test]
+    fn topic_matches_exact(topic in ".*") {
+        prop_assert!(topic_matches(&topic, &topic));
+    }
+
+    #[test]
+    fn wildcard_matches_children_only(prefix in "[a-z]{1,6}(\\.[a-z]{1,6}){0,2}", child in "[a-zA-Z0-9.]{1,12}") {
+        let pattern = format!("{prefix}.*");
+        prop_assert!(topic_matches(&pattern, &format!("{prefix}.{child}")));
+        prop_assert!(!topic_matches(&pattern, &prefix));
+        prop_assert!(!topic_matches(&pattern, &format!("{prefix}{child}")) || child.starts_with('.'));
+    }
+
+    // ===== Collab correlation =====
+
+    #[test]
+    fn replies_built_from_request_envelope_correlate(
+        request in arb_envelope(),
+        other in ".*",
+        hops in 0usize..8,
+    ) {
+        let mut req_evt = event("req");
+        request.attach_to_event(&mut req_evt);
+
+        // Responders (possibly forwarding through several agents) copy the envelope
+        let mut current = req_evt;
+        for i in 0..hops {
+            let mut env = Envelope::from_event(&current);
+            env.next_hop();
+            let mut next = event(&format!("hop-{i}"));
+            env.attach_to_event(&mut next);
+            current = next;
+        }
+
+        prop_assert!(is_correlated(&current, &request.correlation_id));
+        prop_assume!(other != request.correlation_id);
+        prop_assert!(!is_correlated(&current, &other));
+    }
+
+    #[test]
+    fn events_without_correlation_never_match(corr in ".*") {
+        prop_assert!(!is_correlated(&event("bare"), &corr));
+    }
+
+    // ===== LLM output parsers =====
+
+    #[test]
+    fn parse_tool_calls_never_yield_unnamed_calls(v in arb_json()) {
+        for call in parse_tool_calls_from_responses(&v)
+            .into_iter()
+            .chain(parse_tool_calls_from_chat(&v))
+        {
+            prop_assert!(!call.name.is_empty());
+        }
+    }
+
+    #[test]
+    fn parse_tool_calls_from_chat_finds_embedded_call(
+        name in "[a-z_.]{1,16}",
+        args in prop::collection::hash_map("[a-z]{1,6}", any::<i64>(), 0..4),
+        as_string in any::<bool>(),
+    ) {
+        let args = json!(args);
+        let arguments = if as_string { json!(args.to_string()) } else { args.clone() };
+        let v = json!({
+            "choices": [{"message": {"tool_calls": [
+                {"id": "call_1", "function": {"name": name, "arguments": arguments}}
+            ]}}]
+        });
+        let calls = parse_tool_calls_from_chat(&v);
+        prop_assert_eq!(calls.len(), 1);
+        prop_assert_eq!(&calls[0].name, &name);
+        prop_assert_eq!(&calls[0].arguments, &args);
+    }
+
+    #[test]
+    fn extract_tool_call_never_panics(text in ".*") {
+        let _ = extract_tool_call(&text);
+    }
+
+    #[test]
+    fn extract_tool_call_finds_call_in_prose(
+        prefix in "[^{]*",
+        suffix in ".*",
+        name in "[a-z_.]{1,16}",
+        key in prop_oneof![Just(("tool", "args")), Just(("action", "arguments")), Just(("name", "parameters"))],
+        arg in any::<i64>(),
+    ) {
+        // Trailing text may itself open a new object; only closed calls are guaranteed
+        prop_assume!(!suffix.contains('{'));
+        let call = json!({ (key.0): name, (key.1): {"x": arg} });
+        let text = format!("{prefix}{call}{suffix}");
+        let tc = extract_tool_call(&text);
+        prop_assert!(tc.is_some(), "no call found in {:?}", text);
+        let tc = tc.unwrap();
+        prop_assert_eq!(tc.name, name);
+        prop_assert_eq!(tc.arguments, json!({"x": arg}));
+    }
+}
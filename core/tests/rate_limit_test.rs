@@ -0,0 +1,203 @@
+/// Tests for EventBus rate limits: per-publisher and per-topic token buckets
+use loom_core::messaging::rate_limit::OTHERS;
+use loom_core::messaging::{RateLimitScope, PUBLISHER_KEY};
+use loom_core::proto::{Event, QoSLevel};
+use loom_core::{EventBus, EventExt, LoomError, RateLimit, Result};
+use std::collections::HashMap;
+use std::time::Duration;
+
+fn make_event(id: &str, source: &str) -> Event {
+    Event {
+        id: id.to_string(),
+        r#type: "test".to_string(),
+        timestamp_ms: 0,
+        source: source.to_string(),
+        metadata: HashMap::new(),
+        payload: vec![],
+        confidence: 1.0,
+        tags: vec![],
+        priority: 50,
+    }
+}
+
+fn limited(result: Result<u64>) -> loom_core::RateLimited {
+    match result {
+        Err(LoomError::RateLimited(limited)) => limited,
+        other => panic!("expected a rate-limited publish, got {other:?}"),
+    }
+}
+
+#[tokio::test]
+async fn runaway_publisher_only_throttles_itself() -> Result<()> {
+    let bus = EventBus::new().await?;
+    bus.rate_limits()
+        .set_default_publisher_limit(Some(RateLimit::per_second(1.0).with_burst(3)));
+    let (_sid, mut rx) = bus
+        .subscribe("chat".into(), vec![], QoSLevel::QosBatched)
+        .await?;
+
+    for i in 0..3 {
+        bus.publish("chat", make_event(&format!("r{i}"), "agent.runaway"))
+            .await?;
+    }
+    let rejected = limited(bus.publish("chat", make_event("r3", "agent.runaway")).await);
+    assert_eq!(rejected.scope, RateLimitScope::Publisher);
+    assert_eq!(rejected.key, "agent.runaway");
+    assert!(rejected.retry_after > Duration::ZERO);
+    assert!(rejected.retry_after <= Duration::from_secs(1));
+
+    // Others keep their own budget; the envelope sender wins over the source
+    bus.publish("chat", make_event("c0", "agent.calm")).await?;
+    let relayed = make_event("c1", "agent.runaway").with_sender("agent.calm".into());
+    bus.publish("chat", relayed).await?;
+
+    let mut received = Vec::new();
+    while let Ok(event) = rx.try_recv() {
+        received.push(event.id);
+    }
+    assert_eq!(received, ["r0", "r1", "r2", "c0", "c1"]);
+    assert_eq!(bus.get_stats("chat").unwrap().rate_limited, 1);
+    let stats = bus.rate_limits().stats();
+    assert_eq!(stats.len(), 1);
+    assert_eq!(
+        (stats[0].scope, stats[0].key.as_str(), stats[0].rejected),
+        (RateLimitScope::Publisher, "agent.runaway", 1)
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn topic_limits_apply_per_topic_and_take_nothing_on_rejection() -> Result<()> {
+    let bus = EventBus::new().await?;
+    bus.rate_limits()
+        .set_topic_limit("audio.*", RateLimit::per_second(1.0).with_burst(2));
+    bus.rate_limits()
+        .set_publisher_limit("mic", RateLimit::per_second(1.0).with_burst(4));
+
+    bus.publish("audio.mic", make_event("a", "mic")).await?;
+    bus.publish("audio.mic", make_event("b", "mic")).await?;
+    let rejected = limited(bus.publish("audio.mic", make_event("c", "mic")).await);
+    assert_eq!(rejected.scope, RateLimitScope::Topic);
+    assert_eq!(rejected.key, "audio.mic");
+
+    // audio.line has a bucket of its own, and the rejection above cost "mic" nothing
+    bus.publish("audio.line", make_event("d", "mic")).await?;
+    bus.publish("audio.line", make_event("e", "mic")).await?;
+    let rejected = limited(bus.publish("other", make_event("f", "mic")).await);
+    assert_eq!(rejected.scope, RateLimitScope::Publisher);
+
+    // Unlimited topics and publishers are unaffected
+    bus.publish("other", make_event("g", "speaker")).await?;
+
+    assert!(bus.rate_limits().remove_topic_limit("audio.*"));
+    assert!(bus.rate_limits().remove_publisher_limit("mic"));
+    bus.publish("audio.mic", make_event("h", "mic")).await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn buckets_refill_over_time() -> Result<()> {
+    let bus = EventBus::new().await?;
+    bus.rate_limits()
+        .set_topic_limit("ticks", RateLimit::per_second(20.0).with_burst(1));
+
+    bus.publish("ticks", make_event("t0", "clock")).await?;
+    let rejected = limited(bus.publish("ticks", make_event("t1", "clock")).await);
+    tokio::time::sleep(rejected.retry_after + Duration::from_millis(20)).await;
+    bus.publish("ticks", make_event("t2", "clock")).await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn batches_stop_at_the_first_rate_limited_event() -> Result<()> {
+    let bus = EventBus::new().await?;
+    bus.rate_limits()
+        .set_topic_limit("jobs", RateLimit::per_second(1.0).with_burst(2));
+    let (_sid, mut rx) = bus
+        .subscribe("jobs".into(), vec![], QoSLevel::QosBatched)
+        .await?;
+
+    let batch = ["j0", "j1", "j2", "j3"]
+        .iter()
+        .map(|id| make_event(id, "producer"))
+        .collect();
+    limited(bus.publish_batch("jobs", batch).await);
+
+    let mut received = Vec::new();
+    while let Ok(event) = rx.try_recv() {
+        received.push(event.id);
+    }
+    assert_eq!(received, ["j0", "j1"]);
+    Ok(())
+}
+
+#[tokio::test]
+async fn ingress_publisher_wins_over_the_claimed_sender() -> Result<()> {
+    let bus = EventBus::new().await?;
+    bus.rate_limits()
+        .set_default_publisher_limit(Some(RateLimit::per_second(1.0).with_burst(2)));
+    let (_sid, mut rx) = bus
+        .subscribe("chat".into(), vec![], QoSLevel::QosBatched)
+        .await?;
+
+    // Rotating the sender does not refill the connection's bucket, nor drain the
+    // bucket of the agent it names
+    let from_ingress = |id: &str, sender: &str| {
+        let mut event = make_event(id, "agent.sly").with_sender(sender.into());
+        event
+            .metadata
+            .insert(PUBLISHER_KEY.to_string(), "agent.sly".to_string());
+        event
+    };
+    bus.publish("chat", from_ingress("s0", "fake.0")).await?;
+    bus.publish("chat", from_ingress("s1", "agent.victim"))
+        .await?;
+    let rejected = limited(bus.publish("chat", from_ingress("s2", "fake.2")).await);
+    assert_eq!(rejected.key, "agent.sly");
+    bus.publish("chat", make_event("v0", "agent.victim"))
+        .await?;
+    bus.publish("chat", make_event("v1", "agent.victim"))
+        .await?;
+
+    let mut received = Vec::new();
+    while let Ok(event) = rx.try_recv() {
+        assert!(!event.metadata.contains_key(PUBLISHER_KEY));
+        received.push(event.id);
+    }
+    assert_eq!(received, ["s0", "s1", "v0", "v1"]);
+    Ok(())
+}
+
+#[tokio::test]
+async fn zero_rate_rejects_the_burst_too() -> Result<()> {
+    let bus = EventBus::new().await?;
+    bus.rate_limits()
+        .set_publisher_limit("muted", RateLimit::per_second(0.0).with_burst(5));
+
+    let rejected = limited(bus.publish("chat", make_event("m0", "muted")).await);
+    assert_eq!(rejected.scope, RateLimitScope::Publisher);
+    assert_eq!(rejected.retry_after, Duration::MAX);
+    limited(bus.publish("chat", make_event("m1", "muted")).await);
+    bus.publish("chat", make_event("o0", "other")).await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn rejection_stats_stay_bounded() -> Result<()> {
+    let bus = EventBus::new().await?;
+    bus.rate_limits()
+        .set_default_publisher_limit(Some(RateLimit::per_second(0.0)));
+
+    for i in 0..1_200 {
+        limited(
+            bus.publish("chat", make_event("e", &format!("agent.{i}")))
+                .await,
+        );
+    }
+    let stats = bus.rate_limits().stats();
+    assert_eq!(stats.len(), 1_001);
+    assert_eq!(stats[0].key, OTHERS);
+    assert_eq!(stats[0].rejected, 200);
+    assert_eq!(stats.iter().map(|s| s.rejected).sum::<u64>(), 1_200);
+    Ok(())
+}
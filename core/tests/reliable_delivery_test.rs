@@ -0,0 +1,172 @@
+/// Tests for QosReliable subscriptions: ack, redelivery, nack, dead-lettering
+use loom_core::messaging::reliable::dead_letter_keys;
+use loom_core::proto::{Event, QoSLevel};
+use loom_core::{EventBus, LoomError, MemoryComponent, PressureLevel, ReliableConfig, Result};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+fn make_event(id: &str) -> Event {
+    Event {
+        id: id.to_string(),
+        r#type: "test".to_string(),
+        timestamp_ms: 0,
+        source: "test".to_string(),
+        metadata: HashMap::new(),
+        payload: vec![],
+        confidence: 1.0,
+        tags: vec![],
+        priority: 50,
+    }
+}
+
+async fn recv<T>(rx: &mut tokio::sync::mpsc::Receiver<T>) -> T {
+    tokio::time::timeout(Duration::from_secs(1), rx.recv())
+        .await
+        .expect("timed out waiting for delivery")
+        .expect("channel closed")
+}
+
+#[tokio::test]
+async fn acked_events_are_not_redelivered() -> Result<()> {
+    let bus = Arc::new(EventBus::new().await?);
+    let config = ReliableConfig::default().with_ack_timeout(Duration::from_millis(50));
+    let (_sub, mut rx) = bus
+        .subscribe_reliable("orders".into(), vec![], config)
+        .await?;
+
+    assert_eq!(bus.publish("orders", make_event("o1")).await?, 1);
+    let delivered = recv(&mut rx).await;
+    assert_eq!(delivered.id, "o1");
+    assert_eq!(delivered.attempt, 1);
+    delivered.ack();
+
+    tokio::time::sleep(Duration::from_millis(150)).await;
+    assert!(rx.try_recv().is_err());
+    assert_eq!(bus.get_stats("orders").unwrap().redelivered, 0);
+    Ok(())
+}
+
+#[tokio::test]
+async fn unacked_events_are_redelivered_after_timeout() -> Result<()> {
+    let bus = Arc::new(EventBus::new().await?);
+    let config = ReliableConfig::default().with_ack_timeout(Duration::from_millis(50));
+    let (_sub, mut rx) = bus
+        .subscribe_reliable("orders".into(), vec![], config)
+        .await?;
+
+    bus.publish("orders", make_event("o1")).await?;
+    let first = recv(&mut rx).await;
+    assert_eq!(first.attempt, 1);
+    drop(first);
+
+    let second = recv(&mut rx).await;
+    assert_eq!(second.id, "o1");
+    assert_eq!(second.attempt, 2);
+    second.ack();
+
+    tokio::time::sleep(Duration::from_millis(150)).await;
+    assert!(rx.try_recv().is_err());
+    assert_eq!(bus.get_stats("orders").unwrap().redelivered, 1);
+    Ok(())
+}
+
+#[tokio::test]
+async fn repeatedly_nacked_events_go_to_dead_letter_topic() -> Result<()> {
+    let bus = Arc::new(EventBus::new().await?);
+    let config = ReliableConfig::default()
+        .with_ack_timeout(Duration::from_secs(10))
+        .with_max_deliveries(3);
+    assert_eq!(config.dead_letter_topic_for("orders"), "dead_letter.orders");
+    let (sub_id, mut rx) = bus
+        .subscribe_reliable("orders".into(), vec![], config)
+        .await?;
+    let (_dlq, mut dead_letters) = bus
+        .subscribe("dead_letter.orders".into(), vec![], QoSLevel::QosBatched)
+        .await?;
+
+    bus.publish("orders", make_event("poison")).await?;
+    for attempt in 1..=3 {
+        let delivered = recv(&mut rx).await;
+        assert_eq!(delivered.attempt, attempt);
+        delivered.nack();
+    }
+
+    let dead = recv(&mut dead_letters).await;
+    assert_eq!(dead.id, "poison");
+    assert_eq!(
+        dead.metadata
+            .get(dead_letter_keys::REASON)
+            .map(String::as_str),
+        Some("nacked")
+    );
+    assert_eq!(
+        dead.metadata
+            .get(dead_letter_keys::ATTEMPTS)
+            .map(String::as_str),
+        Some("3")
+    );
+    assert_eq!(
+        dead.metadata
+            .get(dead_letter_keys::TOPIC)
+            .map(String::as_str),
+        Some("orders")
+    );
+    assert_eq!(
+        dead.metadata.get(dead_letter_keys::SUBSCRIPTION),
+        Some(&sub_id)
+    );
+    assert!(rx.try_recv().is_err());
+
+    let stats = bus.get_stats("orders").unwrap();
+    assert_eq!(stats.redelivered, 2);
+    assert_eq!(stats.dead_lettered, 1);
+    Ok(())
+}
+
+#[tokio::test]
+async fn stale_nack_does_not_cause_extra_redelivery() -> Result<()> {
+    let bus = Arc::new(EventBus::new().await?);
+    let config = ReliableConfig::default().with_ack_timeout(Duration::from_millis(50));
+    let (_sub, mut rx) = bus
+        .subscribe_reliable("orders".into(), vec![], config)
+        .await?;
+
+    bus.publish("orders", make_event("o1")).await?;
+    let first = recv(&mut rx).await;
+    // Let the first attempt time out, then nack it after the redelivery
+    let second = recv(&mut rx).await;
+    assert_eq!(second.attempt, 2);
+    first.nack();
+    second.ack();
+
+    tokio::time::sleep(Duration::from_millis(150)).await;
+    assert!(rx.try_recv().is_err());
+    Ok(())
+}
+
+#[tokio::test]
+async fn reliable_qos_requires_subscribe_reliable() -> Result<()> {
+    let bus = EventBus::new().await?;
+    let result = bus
+        .subscribe("orders".into(), vec![], QoSLevel::QosReliable)
+        .await;
+    assert!(matches!(result, Err(LoomError::EventBusError(_))));
+    Ok(())
+}
+
+#[tokio::test]
+async fn reliable_deliveries_survive_memory_pressure() -> Result<()> {
+    let bus = Arc::new(EventBus::new().await?);
+    let (_sub, mut rx) = bus
+        .subscribe_reliable("orders".into(), vec![], ReliableConfig::default())
+        .await?;
+    let (_bg, _bg_rx) = bus
+        .subscribe("orders".into(), vec![], QoSLevel::QosBackground)
+        .await?;
+
+    bus.on_pressure(PressureLevel::Critical).await;
+    assert_eq!(bus.publish("orders", make_event("o1")).await?, 1);
+    recv(&mut rx).await.ack();
+    Ok(())
+}
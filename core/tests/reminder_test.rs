@@ -0,0 +1,285 @@
+//! Tests for agent reminders (`ReminderScheduler`) and the `schedule:*` tools
+
+use async_trait::async_trait;
+use chrono::Utc;
+use chrono_tz::Tz;
+use loom_core::agent::reminders::{Reminder, DEFAULT_REMINDER_EVENT_TYPE};
+use loom_core::agent::{AgentBehavior, AgentRuntime};
+use loom_core::proto::{Action, AgentConfig, AgentState, Event, QoSLevel};
+use loom_core::tools::native::schedule_tools;
+use loom_core::tools::{with_caller, ToolError};
+use loom_core::{EventBus, LoomError, ModelRouter, ReminderScheduler, Result, Tool, ToolRegistry};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{mpsc, Mutex};
+
+fn tools(reminders: &Arc<ReminderScheduler>) -> HashMap<String, Arc<dyn Tool>> {
+    schedule_tools(Arc::clone(reminders), Tz::UTC)
+        .into_iter()
+        .map(|tool| (tool.name(), tool))
+        .collect()
+}
+
+fn in_ms(ms: i64) -> chrono::DateTime<Utc> {
+    Utc::now() + chrono::Duration::milliseconds(ms)
+}
+
+async fn reply_topic(bus: &EventBus, agent_id: &str) -> Result<mpsc::Receiver<Event>> {
+    let (_sid, rx) = bus
+        .subscribe(
+            loom_core::agent_reply_topic(agent_id),
+            vec![],
+            QoSLevel::QosBatched,
+        )
+        .await?;
+    Ok(rx)
+}
+
+struct RecordingBehavior {
+    events: Arc<Mutex<Vec<Event>>>,
+}
+
+#[async_trait]
+impl AgentBehavior for RecordingBehavior {
+    async fn on_event(&mut self, event: Event, _state: &mut AgentState) -> Result<Vec<Action>> {
+        self.events.lock().await.push(event);
+        Ok(vec![])
+    }
+
+    async fn on_init(&mut self, _config: &AgentConfig) -> Result<()> {
+        Ok(())
+    }
+
+    async fn on_shutdown(&mut self) -> Result<()> {
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn remind_delivers_an_event_to_the_calling_agent() -> Result<()> {
+    let bus = Arc::new(EventBus::new().await?);
+    let reminders = ReminderScheduler::new(Arc::clone(&bus));
+    let schedule = tools(&reminders);
+    let mut rx = reply_topic(&bus, "planner").await?;
+
+    let out = with_caller(
+        "planner",
+        schedule["schedule:remind"].call(json!({
+            "message": "check the build",
+            "when": in_ms(200).to_rfc3339(),
+            "data": { "build": 42 }
+        })),
+    )
+    .await
+    .unwrap();
+    let id = out["reminder_id"].as_str().unwrap().to_string();
+    assert!(out["now"]["unix_ms"].as_i64().unwrap() < out["fire_at"]["unix_ms"].as_i64().unwrap());
+    assert_eq!(reminders.pending("planner").len(), 1);
+
+    let event = tokio::time::timeout(Duration::from_secs(2), rx.recv())
+        .await
+        .expect("reminder should fire")
+        .unwrap();
+    assert_eq!(event.r#type, DEFAULT_REMINDER_EVENT_TYPE);
+    assert_eq!(event.source, "scheduler");
+    assert_eq!(event.metadata["reminder"], id);
+    let payload: Value = serde_json::from_slice(&event.payload).unwrap();
+    assert_eq!(payload["message"], "check the build");
+    assert_eq!(payload["data"], json!({ "build": 42 }));
+
+    // Fired reminders are no longer pending
+    assert!(reminders.pending("planner").is_empty());
+    Ok(())
+}
+
+#[tokio::test]
+async fn list_and_cancel_only_see_the_callers_reminders() -> Result<()> {
+    let bus = Arc::new(EventBus::new().await?);
+    let reminders = ReminderScheduler::new(Arc::clone(&bus));
+    let schedule = tools(&reminders);
+    let mut rx = reply_topic(&bus, "planner").await?;
+
+    let mut ids = Vec::new();
+    for (message, when) in [("later", "in 2 hours"), ("sooner", "in 20 minutes")] {
+        let out = with_caller(
+            "planner",
+            schedule["schedule:remind"].call(json!({ "message": message, "when": when })),
+        )
+        .await
+        .unwrap();
+        ids.push(out["reminder_id"].as_str().unwrap().to_string());
+    }
+    with_caller(
+        "critic",
+        schedule["schedule:remind"].call(json!({ "message": "mine", "in_secs": 60 })),
+    )
+    .await
+    .unwrap();
+
+    let out = with_caller("planner", schedule["schedule:list"].call(json!({})))
+        .await
+        .unwrap();
+    assert_eq!(out["count"], 2);
+    assert_eq!(out["reminders"][0]["message"], "sooner");
+    assert_eq!(out["reminders"][1]["message"], "later");
+    let in_secs = out["reminders"][0]["in_secs"].as_i64().unwrap();
+    assert!((1195..=1200).contains(&in_secs), "{in_secs}");
+
+    // Another agent cannot cancel the planner's reminders
+    let out = with_caller(
+        "critic",
+        schedule["schedule:cancel"].call(json!({ "reminder_id": ids[0] })),
+    )
+    .await
+    .unwrap();
+    assert_eq!(out["cancelled"], false);
+
+    let out = with_caller(
+        "planner",
+        schedule["schedule:cancel"].call(json!({ "reminder_id": ids[0] })),
+    )
+    .await
+    .unwrap();
+    assert_eq!(out["cancelled"], true);
+    assert_eq!(reminders.pending("planner").len(), 1);
+    assert_eq!(reminders.pending("critic").len(), 1);
+
+    // A cancelled reminder never fires
+    reminders.schedule(Reminder::new("planner", in_ms(50), "short-lived"))?;
+    let id = reminders.pending("planner")[0].id.clone();
+    assert!(reminders.cancel("planner", &id)?);
+    tokio::time::sleep(Duration::from_millis(150)).await;
+    assert!(rx.try_recv().is_err());
+    Ok(())
+}
+
+#[tokio::test]
+async fn invalid_reminders_are_rejected() -> Result<()> {
+    let bus = Arc::new(EventBus::new().await?);
+    let reminders = ReminderScheduler::new(Arc::clone(&bus));
+    reminders.set_max_per_agent(2);
+    let schedule = tools(&reminders);
+    let remind = |args: Value| with_caller("planner", schedule["schedule:remind"].call(args));
+
+    for args in [
+        json!({ "message": "no time" }),
+        json!({ "message": "both", "when": "in 5 minutes", "in_secs": 300 }),
+        json!({ "message": "past", "when": "2 hours ago" }),
+        json!({ "message": "gibberish", "when": "whenever" }),
+        json!({ "message": "zero", "in_secs": 0 }),
+        json!({ "when": "in 5 minutes" }),
+    ] {
+        let err = remind(args.clone()).await.unwrap_err();
+        assert!(matches!(err, ToolError::InvalidArguments(_)), "{args}");
+    }
+
+    // Outside an agent there are no reminders to manage
+    let err = schedule["schedule:list"].call(json!({})).await.unwrap_err();
+    assert!(matches!(err, ToolError::InvalidArguments(_)));
+
+    remind(json!({ "message": "one", "in_secs": 60 }))
+        .await
+        .unwrap();
+    remind(json!({ "message": "two", "in_secs": 60 }))
+        .await
+        .unwrap();
+    let err = remind(json!({ "message": "three", "in_secs": 60 }))
+        .await
+        .unwrap_err();
+    assert!(matches!(err, ToolError::ExecutionFailed(ref m) if m.contains("pending reminders")));
+    let err = reminders
+        .schedule(Reminder::new("planner", in_ms(60_000), "direct"))
+        .unwrap_err();
+    assert!(matches!(err, LoomError::QuotaExceeded(_)));
+    Ok(())
+}
+
+#[tokio::test]
+async fn persisted_reminders_wait_for_their_agent() -> Result<()> {
+    let dir = tempfile::tempdir().unwrap();
+    let bus = Arc::new(EventBus::new().await?);
+    let mut rx = reply_topic(&bus, "planner").await?;
+    {
+        let reminders = ReminderScheduler::open(Arc::clone(&bus), dir.path())?;
+        assert!(reminders.is_persistent());
+        reminders.schedule(
+            Reminder::new("planner", in_ms(100), "stand-up").with_event_type("planner.standup"),
+        )?;
+        reminders.schedule(Reminder::new("planner", in_ms(3_600_000), "review"))?;
+    }
+
+    // Dropping the scheduler stopped its timers
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    assert!(rx.try_recv().is_err());
+
+    // Reloaded reminders are suspended until resumed, then fire if already due
+    let reminders = ReminderScheduler::open(Arc::clone(&bus), dir.path())?;
+    let pending = reminders.pending("planner");
+    assert_eq!(pending.len(), 2);
+    assert_eq!(pending[0].message, "stand-up");
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    assert!(rx.try_recv().is_err());
+
+    assert_eq!(reminders.resume("planner"), 2);
+    let event = tokio::time::timeout(Duration::from_secs(1), rx.recv())
+        .await
+        .expect("overdue reminder should fire on resume")
+        .unwrap();
+    assert_eq!(event.r#type, "planner.standup");
+    assert_eq!(reminders.pending("planner").len(), 1);
+    drop(reminders);
+
+    // The fired reminder is gone from the store too
+    let reminders = ReminderScheduler::open(Arc::clone(&bus), dir.path())?;
+    let pending = reminders.pending("planner");
+    assert_eq!(pending.len(), 1);
+    assert_eq!(pending[0].message, "review");
+    Ok(())
+}
+
+#[tokio::test]
+async fn runtime_suspends_and_resumes_reminders_with_the_agent() -> Result<()> {
+    let bus = Arc::new(EventBus::new().await?);
+    let reminders = ReminderScheduler::new(Arc::clone(&bus));
+    let runtime = AgentRuntime::new(
+        Arc::clone(&bus),
+        Arc::new(ToolRegistry::new()),
+        ModelRouter::new().await?,
+    )
+    .await?
+    .with_reminders(Arc::clone(&reminders));
+
+    let config = AgentConfig {
+        agent_id: "assistant".to_string(),
+        agent_type: "test".to_string(),
+        subscribed_topics: vec![],
+        capabilities: vec![],
+        parameters: HashMap::new(),
+    };
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let behavior = |events: &Arc<Mutex<Vec<Event>>>| {
+        Box::new(RecordingBehavior {
+            events: Arc::clone(events),
+        })
+    };
+    runtime
+        .create_agent(config.clone(), behavior(&events))
+        .await?;
+    reminders.schedule(Reminder::new("assistant", in_ms(150), "tea"))?;
+
+    // Deleted before it is due: the reminder is kept but does not fire
+    runtime.delete_agent("assistant").await?;
+    tokio::time::sleep(Duration::from_millis(250)).await;
+    assert_eq!(reminders.pending("assistant").len(), 1);
+
+    // Re-created, the agent gets the overdue reminder
+    runtime.create_agent(config, behavior(&events)).await?;
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    let events = events.lock().await;
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].metadata["agent_id"], "assistant");
+    assert!(reminders.pending("assistant").is_empty());
+    Ok(())
+}
@@ -0,0 +1,156 @@
+//! Tests for the `report:render` tool: templating, charts, and HTML/PDF blobs
+
+use loom_core::blob::{BlobStore, DiskBlobBackend};
+use loom_core::tools::native::ReportRenderTool;
+use loom_core::tools::{with_caller, ToolError};
+use loom_core::Tool;
+use serde_json::{json, Value};
+use std::sync::Arc;
+
+fn setup() -> (tempfile::TempDir, Arc<BlobStore>, ReportRenderTool) {
+    let dir = tempfile::tempdir().unwrap();
+    let blobs = Arc::new(BlobStore::new(Arc::new(DiskBlobBackend::new(dir.path()))));
+    let tool = ReportRenderTool::new(Arc::clone(&blobs));
+    (dir, blobs, tool)
+}
+
+async fn artifact(blobs: &BlobStore, out: &Value, n: usize, reader: &str) -> Vec<u8> {
+    let blob = blobs
+        .blob_ref(out["artifacts"][n]["blob_ref"].as_str().unwrap())
+        .await
+        .unwrap()
+        .unwrap();
+    blobs.get(&blob, Some(reader), None).await.unwrap()
+}
+
+fn sales_args() -> Value {
+    json!({
+        "title": "{{region}} sales",
+        "metadata": { "Prepared by": "analyst", "Period": "{{period}}" },
+        "variables": { "region": "EMEA", "period": "Q3", "total": 4200 },
+        "markdown": "## Summary\n\nRevenue reached **{{total}}** <script>alert(1)</script>.\n\n\
+                     {{chart:monthly}}\n\n| Month | Revenue |\n|---|---:|\n| July | 1200 |\n\n\
+                     - see [details](https://example.com/q3)\n- [bad](javascript:alert(1))",
+        "charts": [
+            {
+                "id": "monthly", "type": "bar", "title": "Revenue by month",
+                "labels": ["Jul", "Aug", "Sep"],
+                "series": [
+                    { "name": "2024", "values": [1200, 1400, 1600] },
+                    { "name": "2023", "values": [900, -100, 1300] }
+                ]
+            },
+            {
+                "id": "trend", "type": "line",
+                "labels": ["Jul", "Aug", "Sep"],
+                "series": [{ "values": [1.5, 2.25, 3] }]
+            }
+        ],
+        "formats": ["html", "pdf"],
+        "readers": ["publisher"]
+    })
+}
+
+#[tokio::test]
+async fn renders_html_and_pdf_blobs_owned_by_the_caller() {
+    let (_dir, blobs, tool) = setup();
+    let out = with_caller("analyst", tool.call(sales_args()))
+        .await
+        .unwrap();
+    assert_eq!(out["title"], "EMEA sales");
+    assert_eq!(out["artifacts"][0]["format"], "html");
+    assert_eq!(
+        out["artifacts"][0]["content_type"],
+        "text/html; charset=utf-8"
+    );
+    assert_eq!(out["artifacts"][1]["format"], "pdf");
+    assert_eq!(out["artifacts"][1]["content_type"], "application/pdf");
+
+    let html = String::from_utf8(artifact(&blobs, &out, 0, "publisher").await).unwrap();
+    assert!(html.starts_with("<!DOCTYPE html>"));
+    assert!(html.contains("<title>EMEA sales</title>"));
+    assert!(html.contains("<dt>Period</dt><dd>Q3</dd>"));
+    assert!(html.contains("<h2>Summary</h2>"));
+    assert!(html.contains("<strong>4200</strong> &lt;script&gt;"));
+    assert!(!html.contains("<script>"));
+    assert!(html.contains("<td>1200</td>"));
+    assert!(html.contains("<a href=\"https://example.com/q3\">details</a>"));
+    assert!(!html.contains("javascript:"));
+    // The placed chart comes before the table, the unplaced one is appended
+    let monthly = html.find("aria-label=\"Revenue by month\"").unwrap();
+    let trend = html.find("aria-label=\"trend\"").unwrap();
+    assert!(monthly < html.find("<table>").unwrap());
+    assert!(trend > html.find("</table>").unwrap());
+    assert!(html.matches("<polyline points=\"").count() > 2);
+
+    let pdf = artifact(&blobs, &out, 1, "analyst").await;
+    let text = String::from_utf8_lossy(&pdf);
+    assert!(text.starts_with("%PDF-1.4"));
+    assert!(text.ends_with("%%EOF\n"));
+    assert!(text.contains("/Title (EMEA sales)"));
+    assert!(text.contains("(Summary) Tj"));
+    assert!(text.contains("/BaseFont /Helvetica-Bold"));
+    // startxref points at the cross-reference table
+    let start: usize = text
+        .rsplit("startxref\n")
+        .next()
+        .unwrap()
+        .lines()
+        .next()
+        .unwrap()
+        .parse()
+        .unwrap();
+    assert!(pdf[start..].starts_with(b"xref\n"));
+
+    // Only the readers and the owner may fetch the artifacts
+    let blob = blobs
+        .blob_ref(out["artifacts"][0]["blob_ref"].as_str().unwrap())
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(blobs.get(&blob, Some("stranger"), None).await.is_err());
+}
+
+#[tokio::test]
+async fn long_reports_break_across_pdf_pages() {
+    let (_dir, blobs, tool) = setup();
+    let paragraph = "Lorem ipsum dolor sit amet, consectetur adipiscing elit. ".repeat(20);
+    let markdown = format!(
+        "# Long\n\n{}\n```\n{}\n```",
+        format!("{paragraph}\n\n").repeat(12),
+        "x".repeat(300)
+    );
+    let out = tool
+        .call(json!({ "markdown": markdown, "formats": ["pdf"] }))
+        .await
+        .unwrap();
+    assert_eq!(out["title"], Value::Null);
+    assert_eq!(out["artifacts"].as_array().unwrap().len(), 1);
+    let pdf = artifact(&blobs, &out, 0, "anyone").await;
+    let text = String::from_utf8_lossy(&pdf);
+    let pages = text.matches("/Type /Page ").count();
+    assert!(pages >= 3, "expected several pages, got {pages}");
+    assert!(text.contains(&format!("/Count {pages}")));
+    assert!(text.contains(&format!("({pages} / {pages}) Tj")));
+}
+
+#[tokio::test]
+async fn rejects_bad_templates_and_charts() {
+    let (_dir, _blobs, tool) = setup();
+    let cases = [
+        json!({ "markdown": "Hello {{name}}" }),
+        json!({ "markdown": "{{chart:missing}}" }),
+        json!({ "markdown": "x", "formats": ["docx"] }),
+        json!({ "markdown": "x", "charts": [{ "id": "c", "labels": ["a", "b"], "series": [{ "values": [1] }] }] }),
+        json!({ "markdown": "x", "charts": [{ "id": "c", "labels": ["a"], "series": [{ "values": ["1"] }] }] }),
+        json!({ "markdown": "x", "charts": [{ "id": "c", "type": "pie", "labels": ["a"], "series": [{ "values": [1] }] }] }),
+        json!({ "title": "no body" }),
+    ];
+    for args in cases {
+        let result = tool.call(args.clone()).await;
+        assert!(
+            matches!(result, Err(ToolError::InvalidArguments(_))),
+            "{args} gave {result:?}"
+        );
+    }
+}
@@ -0,0 +1,345 @@
+//! Tests for streaming agent responses to the requester as `response.partial` events
+
+use async_trait::async_trait;
+use loom_core::agent::{AgentBehavior, AgentRuntime};
+use loom_core::cognitive::llm::{LlmClient, LlmClientConfig};
+use loom_core::cognitive::{
+    CognitiveAgent, CognitiveConfig, CognitiveLoop, ExecutionResult, MemoryBuffer, Perception,
+    Plan, ResponseSink, SimpleCognitiveLoop, REPLY_ACTION, REPLY_EVENT_TYPE_PARAM, RESPONSE_EVENT,
+    RESPONSE_FINAL_EVENT, RESPONSE_PARTIAL_EVENT, STREAM_SEQ_KEY,
+};
+use loom_core::context::PromptBundle;
+use loom_core::proto::{AgentConfig, AgentState, Event, QoSLevel};
+use loom_core::{Envelope, EventBus, ModelRouter, Result, ToolRegistry};
+use serde_json::json;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+const CHUNKS: [&str; 3] = ["The sky", " is blue.", " Mostly."];
+
+/// Read one request; returns its path and body
+async fn read_request(sock: &mut TcpStream) -> (String, String) {
+    let mut buf = Vec::new();
+    let mut chunk = [0u8; 4096];
+    loop {
+        let n = sock.read(&mut chunk).await.unwrap_or(0);
+        if n == 0 {
+            break;
+        }
+        buf.extend_from_slice(&chunk[..n]);
+        let text = String::from_utf8_lossy(&buf).into_owned();
+        if let Some(end) = text.find("\r\n\r\n") {
+            let length = text[..end]
+                .lines()
+                .find_map(|line| match line.split_once(':') {
+                    Some((name, value)) if name.eq_ignore_ascii_case("content-length") => {
+                        value.trim().parse::<usize>().ok()
+                    }
+                    _ => None,
+                })
+                .unwrap_or(0);
+            if buf.len() >= end + 4 + length {
+                let path = text.split_whitespace().nth(1).unwrap_or("").to_string();
+                return (path, text[end + 4..].to_string());
+            }
+        }
+    }
+    (String::new(), String::new())
+}
+
+/// OpenAI-compatible backend without the Responses API. Streaming chat requests get
+/// `CHUNKS` as server-sent events (unless `plain`), others one JSON completion.
+async fn spawn_backend(plain: bool) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        while let Ok((mut sock, _)) = listener.accept().await {
+            tokio::spawn(async move {
+                let (path, body) = read_request(&mut sock).await;
+                if !path.ends_with("/chat/completions") {
+                    let _ = sock
+                        .write_all(b"HTTP/1.1 404 Not Found\r\ncontent-length: 0\r\nconnection: close\r\n\r\n")
+                        .await;
+                    return;
+                }
+                let stream = serde_json::from_str::<serde_json::Value>(&body)
+                    .ok()
+                    .and_then(|v| v.get("stream").and_then(|s| s.as_bool()))
+                    .unwrap_or(false);
+                if !stream || plain {
+                    let body = json!({
+                        "model": "test-model",
+                        "choices": [{"message": {"role": "assistant", "content": CHUNKS.concat()}}],
+                    })
+                    .to_string();
+                    let resp = format!(
+                        "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{}",
+                        body.len(),
+                        body
+                    );
+                    let _ = sock.write_all(resp.as_bytes()).await;
+                    return;
+                }
+
+                let _ = sock
+                    .write_all(b"HTTP/1.1 200 OK\r\ncontent-type: text/event-stream\r\nconnection: close\r\n\r\n")
+                    .await;
+                for chunk in CHUNKS {
+                    let data = json!({
+                        "model": "test-model",
+                        "choices": [{"index": 0, "delta": {"content": chunk}}],
+                    });
+                    let _ = sock
+                        .write_all(format!("data: {}\n\n", data).as_bytes())
+                        .await;
+                    tokio::time::sleep(Duration::from_millis(20)).await;
+                }
+                let usage = json!({"choices": [], "usage": {"total_tokens": 42}});
+                let _ = sock
+                    .write_all(format!("data: {}\n\ndata: [DONE]\n\n", usage).as_bytes())
+                    .await;
+                let _ = sock.shutdown().await;
+            });
+        }
+    });
+    format!("http://{addr}/v1")
+}
+
+fn client(base_url: String) -> Arc<LlmClient> {
+    Arc::new(
+        LlmClient::new(LlmClientConfig {
+            base_url,
+            model: "test-model".to_string(),
+            api_key: None,
+            request_timeout_ms: 5_000,
+            temperature: 0.0,
+        })
+        .unwrap(),
+    )
+}
+
+fn bundle() -> PromptBundle {
+    PromptBundle {
+        system: "You are terse".to_string(),
+        instructions: "What color is the sky?".to_string(),
+        tools_json_schema: None,
+        context_docs: vec![],
+        history: vec![],
+    }
+}
+
+fn drain(rx: &mut tokio::sync::mpsc::UnboundedReceiver<String>) -> Vec<String> {
+    let mut chunks = Vec::new();
+    while let Ok(chunk) = rx.try_recv() {
+        chunks.push(chunk);
+    }
+    chunks
+}
+
+#[tokio::test]
+async fn generate_stream_forwards_content_deltas() -> Result<()> {
+    let llm = client(spawn_backend(false).await);
+    let (sink, mut rx) = ResponseSink::channel();
+
+    let response = llm.generate_stream(&bundle(), None, &sink).await?;
+    assert_eq!(response.text, CHUNKS.concat());
+    assert_eq!(response.model.as_deref(), Some("test-model"));
+    assert_eq!(response.usage, Some(json!({"total_tokens": 42})));
+    assert_eq!(drain(&mut rx), CHUNKS);
+    Ok(())
+}
+
+#[tokio::test]
+async fn backends_that_do_not_stream_send_one_chunk() -> Result<()> {
+    let llm = client(spawn_backend(true).await);
+    let (sink, mut rx) = ResponseSink::channel();
+
+    let response = llm.generate_stream(&bundle(), None, &sink).await?;
+    assert_eq!(response.text, CHUNKS.concat());
+    assert_eq!(drain(&mut rx), vec![CHUNKS.concat()]);
+    Ok(())
+}
+
+#[tokio::test]
+async fn queries_stream_partials_before_the_final_response() -> Result<()> {
+    let bus = Arc::new(EventBus::new().await?);
+    bus.start().await?;
+    let tools = Arc::new(ToolRegistry::new());
+    let runtime = AgentRuntime::new(
+        Arc::clone(&bus),
+        Arc::clone(&tools),
+        ModelRouter::new().await?,
+    )
+    .await?;
+
+    let loop_impl = SimpleCognitiveLoop::new(
+        CognitiveConfig::single_shot(),
+        client(spawn_backend(false).await),
+        tools,
+    );
+    let agent = CognitiveAgent::new(loop_impl).with_response_streaming(Arc::clone(&bus));
+    runtime
+        .create_agent(
+            AgentConfig {
+                agent_id: "streamer".to_string(),
+                agent_type: "cognitive".to_string(),
+                subscribed_topics: vec!["stream.query".to_string()],
+                capabilities: vec![],
+                parameters: HashMap::new(),
+            },
+            Box::new(agent),
+        )
+        .await?;
+
+    let envelope = Envelope::new("voice-1", "test").with_reply_expected(true);
+    let (_sub, mut rx) = bus
+        .subscribe(
+            envelope.reply_to.clone(),
+            vec![
+                RESPONSE_PARTIAL_EVENT.to_string(),
+                RESPONSE_FINAL_EVENT.to_string(),
+                RESPONSE_EVENT.to_string(),
+            ],
+            QoSLevel::QosBatched,
+        )
+        .await?;
+    let mut query = Event {
+        id: "q1".to_string(),
+        r#type: "user.query".to_string(),
+        timestamp_ms: 0,
+        source: "test".to_string(),
+        metadata: HashMap::new(),
+        payload: b"What color is the sky?".to_vec(),
+        confidence: 1.0,
+        tags: vec![],
+        priority: 60,
+    };
+    envelope.attach_to_event(&mut query);
+    bus.publish("stream.query", query).await?;
+
+    let mut partials = Vec::new();
+    let last = loop {
+        let event = tokio::time::timeout(Duration::from_secs(5), rx.recv())
+            .await
+            .expect("response in time")
+            .unwrap();
+        assert_eq!(event.metadata["correlation_id"], "voice-1");
+        assert_eq!(event.metadata["caused_by"], "q1");
+        if event.r#type != RESPONSE_PARTIAL_EVENT {
+            break event;
+        }
+        assert_eq!(event.metadata[STREAM_SEQ_KEY], partials.len().to_string());
+        partials.push(String::from_utf8(event.payload).unwrap());
+    };
+    assert_eq!(partials, CHUNKS);
+    assert_eq!(last.r#type, RESPONSE_FINAL_EVENT);
+    assert_eq!(String::from_utf8(last.payload).unwrap(), CHUNKS.concat());
+    Ok(())
+}
+
+/// Streams `CHUNKS` into the sink when one is set
+struct ScriptedLoop {
+    memory: MemoryBuffer,
+    sink: Option<ResponseSink>,
+    streamed: Arc<AtomicBool>,
+}
+
+#[async_trait]
+impl CognitiveLoop for ScriptedLoop {
+    async fn perceive(&mut self, event: Event, _state: &AgentState) -> Result<Perception> {
+        Ok(Perception::from_event(event))
+    }
+
+    async fn think(&mut self, perception: &Perception) -> Result<Plan> {
+        if let Some(ref sink) = self.sink {
+            self.streamed.store(true, Ordering::SeqCst);
+            for chunk in CHUNKS {
+                sink.send(chunk);
+            }
+        }
+        Ok(Plan::final_answer(
+            perception.goal.clone().unwrap_or_default(),
+            CHUNKS.concat(),
+        ))
+    }
+
+    async fn act(&mut self, plan: &Plan, _state: &mut AgentState) -> Result<ExecutionResult> {
+        Ok(ExecutionResult::with_response(
+            plan.final_answer.clone().unwrap_or_default(),
+        ))
+    }
+
+    fn memory_buffer(&self) -> &MemoryBuffer {
+        &self.memory
+    }
+
+    fn memory_buffer_mut(&mut self) -> &mut MemoryBuffer {
+        &mut self.memory
+    }
+
+    fn set_response_sink(&mut self, sink: Option<ResponseSink>) {
+        self.sink = sink;
+    }
+}
+
+#[tokio::test]
+async fn only_queries_expecting_a_reply_are_streamed() -> Result<()> {
+    let bus = Arc::new(EventBus::new().await?);
+    let streamed = Arc::new(AtomicBool::new(false));
+    let mut agent = CognitiveAgent::new(ScriptedLoop {
+        memory: MemoryBuffer::new(10),
+        sink: None,
+        streamed: Arc::clone(&streamed),
+    })
+    .with_response_streaming(bus);
+    let mut state = AgentState {
+        agent_id: "scripted".to_string(),
+        persistent_state: vec![],
+        ephemeral_context: vec![],
+        last_update_ms: 0,
+        metadata: Default::default(),
+    };
+    let event = |event_type: &str, expect_reply: bool| Event {
+        id: "e1".to_string(),
+        r#type: event_type.to_string(),
+        timestamp_ms: 0,
+        source: "test".to_string(),
+        metadata: HashMap::from([("expect_reply".to_string(), expect_reply.to_string())]),
+        payload: b"hello".to_vec(),
+        confidence: 1.0,
+        tags: vec![],
+        priority: 0,
+    };
+
+    // Other requests get a plain agent.response
+    let actions = agent
+        .on_event(event("user.message", true), &mut state)
+        .await?;
+    assert!(!streamed.load(Ordering::SeqCst));
+    assert_eq!(actions.len(), 1);
+    assert_eq!(actions[0].action_type, REPLY_ACTION);
+    assert!(!actions[0].parameters.contains_key(REPLY_EVENT_TYPE_PARAM));
+
+    // Queries nobody waits for are neither streamed nor answered
+    let actions = agent
+        .on_event(event("user.query", false), &mut state)
+        .await?;
+    assert!(!streamed.load(Ordering::SeqCst));
+    assert!(actions.is_empty());
+
+    // A query expecting a reply streams, and its reply closes the stream
+    let actions = agent
+        .on_event(event("user.query", true), &mut state)
+        .await?;
+    assert!(streamed.load(Ordering::SeqCst));
+    assert_eq!(
+        actions[0].parameters[REPLY_EVENT_TYPE_PARAM],
+        RESPONSE_FINAL_EVENT
+    );
+    assert!(agent.inner().sink.is_none());
+    Ok(())
+}
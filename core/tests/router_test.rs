@@ -77,6 +77,7 @@ async fn route_low_confidence_local_model_chooses_cloud() -> Result<()> {
     let evt = make_event("e3");
     let decision = router.route(&evt, None).await?;
     assert_eq!(decision.route, Route::Cloud);
+    assert_eq!(decision.model.as_deref(), Some("gpt-4"));
     Ok(())
 }
 
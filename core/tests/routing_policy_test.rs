@@ -0,0 +1,290 @@
+//! Tests for cost- and latency-aware routing policies
+
+use async_trait::async_trait;
+use loom_core::agent::{AgentBehavior, AgentRuntime};
+use loom_core::cognitive::llm::policy::{
+    estimate_tokens, policy_from_name, Candidate, LatencyTracker, DEFAULT_OUTPUT_TOKENS,
+};
+use loom_core::cognitive::llm::router::{
+    ConfidenceEstimator, ModelRouter, PrivacyLevel, Route, RoutingConstraints,
+};
+use loom_core::proto::{Action, AgentConfig, AgentState, Event};
+use loom_core::{
+    CheapestFirst, EventBus, FastestFirst, LoomError, ModelCostTable, ModelProfile, QualityFirst,
+    Result, RoutingPolicy, ToolRegistry,
+};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+struct FixedConfidence(f32);
+
+#[async_trait]
+impl ConfidenceEstimator for FixedConfidence {
+    fn name(&self) -> &'static str {
+        "fixed"
+    }
+
+    fn supports_event_type(&self, _event_type: &str) -> bool {
+        true
+    }
+
+    async fn estimate_confidence(&self, _event: &Event) -> Result<f32> {
+        Ok(self.0)
+    }
+}
+
+struct NoopBehavior;
+
+#[async_trait]
+impl AgentBehavior for NoopBehavior {
+    async fn on_event(&mut self, _event: Event, _state: &mut AgentState) -> Result<Vec<Action>> {
+        Ok(vec![])
+    }
+
+    async fn on_init(&mut self, _config: &AgentConfig) -> Result<()> {
+        Ok(())
+    }
+
+    async fn on_shutdown(&mut self) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// Defers every event
+struct NeverPolicy;
+
+impl RoutingPolicy for NeverPolicy {
+    fn name(&self) -> &'static str {
+        "never"
+    }
+
+    fn select(
+        &self,
+        _candidates: &[Candidate],
+        _constraints: &RoutingConstraints,
+    ) -> Option<usize> {
+        None
+    }
+}
+
+fn make_event(metadata: &[(&str, &str)]) -> Event {
+    Event {
+        id: "e1".to_string(),
+        r#type: "test".to_string(),
+        timestamp_ms: 0,
+        source: "test".to_string(),
+        metadata: metadata
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect(),
+        payload: vec![],
+        confidence: 1.0,
+        tags: vec![],
+        priority: 0,
+    }
+}
+
+fn loose() -> RoutingConstraints {
+    RoutingConstraints {
+        privacy_level: PrivacyLevel::Sensitive,
+        latency_budget_ms: 2000,
+        cost_cap_per_event: 1.0,
+        quality_threshold: 0.0,
+    }
+}
+
+async fn router(confidence: f32, policy: Arc<dyn RoutingPolicy>) -> Result<ModelRouter> {
+    Ok(ModelRouter::new()
+        .await?
+        .with_confidence_estimator(Arc::new(FixedConfidence(confidence)))
+        .with_routing_policy(policy))
+}
+
+#[test]
+fn latency_percentiles_use_a_rolling_window() {
+    let tracker = LatencyTracker::new(4);
+    assert_eq!(tracker.p95("gpt-4"), None);
+
+    for ms in [100, 10, 40, 30, 20] {
+        tracker.record("gpt-4", Duration::from_millis(ms));
+    }
+    // 100 fell out of the window
+    assert_eq!(tracker.sample_count("gpt-4"), 4);
+    assert_eq!(tracker.p50("gpt-4"), Some(20));
+    assert_eq!(tracker.p95("gpt-4"), Some(40));
+    assert_eq!(tracker.percentile("gpt-4", 0.0), Some(10));
+}
+
+#[test]
+fn tokens_and_costs_are_estimated_per_event() {
+    let event = Event {
+        payload: vec![b'x'; 400],
+        ..make_event(&[])
+    };
+    assert_eq!(estimate_tokens(&event), (100, DEFAULT_OUTPUT_TOKENS));
+    assert_eq!(
+        estimate_tokens(&make_event(&[
+            ("estimated_input_tokens", "2000"),
+            ("max_tokens", "10")
+        ])),
+        (2000, 10)
+    );
+
+    let profile = ModelProfile::new(0.01, 0.02, 0.9);
+    assert!((profile.cost(1000, 500) - 0.02).abs() < 1e-6);
+
+    let table =
+        ModelCostTable::from_json(r#"{"local-llama": {"quality": 0.6, "typical_latency_ms": 20}}"#)
+            .unwrap();
+    let llama = table.get("local-llama").unwrap();
+    assert_eq!(llama.input_cost_per_1k, 0.0);
+    assert_eq!(llama.typical_latency_ms, 20);
+
+    assert!(ModelCostTable::from_json(r#"{"bad": {"quality": 1.5}}"#).is_err());
+    assert!(ModelCostTable::from_json("[]").is_err());
+}
+
+#[tokio::test]
+async fn builtin_policies_rank_candidates() -> Result<()> {
+    let event = make_event(&[]);
+
+    let cheapest = router(0.6, Arc::new(CheapestFirst))
+        .await?
+        .with_constraints(loose());
+    let decision = cheapest.route(&event, None).await?;
+    assert_eq!(decision.route, Route::Local);
+    assert_eq!(decision.model.as_deref(), Some("face_detector"));
+    assert_eq!(decision.estimated_cost, 0.0);
+    assert!(decision.reason.contains("cheapest"));
+
+    let quality = router(0.6, Arc::new(QualityFirst))
+        .await?
+        .with_constraints(loose());
+    let decision = quality.route(&event, None).await?;
+    assert_eq!(decision.route, Route::Cloud);
+    assert_eq!(decision.model.as_deref(), Some("gpt-4"));
+    assert!(decision.estimated_cost > 0.0);
+
+    // Typical latencies favor the local model until observations say otherwise
+    let fastest = router(0.6, Arc::new(FastestFirst))
+        .await?
+        .with_constraints(loose());
+    assert_eq!(
+        fastest.route(&event, None).await?.model.as_deref(),
+        Some("face_detector")
+    );
+    for _ in 0..5 {
+        fastest.record_latency("claude-3", Duration::from_millis(10));
+        fastest.record_latency("face_detector", Duration::from_millis(120));
+    }
+    let decision = fastest.route(&event, None).await?;
+    assert_eq!(decision.model.as_deref(), Some("claude-3"));
+    assert_eq!(decision.estimated_latency_ms, 10);
+    Ok(())
+}
+
+#[tokio::test]
+async fn cheapest_cloud_depends_on_token_estimate() -> Result<()> {
+    // The default estimator does not support "test" events: cloud candidates only
+    let router = ModelRouter::new()
+        .await?
+        .with_routing_policy(Arc::new(CheapestFirst))
+        .with_constraints(loose());
+
+    // gpt-4 has the cheaper output tokens
+    let short = make_event(&[]);
+    assert_eq!(
+        router.route(&short, None).await?.model.as_deref(),
+        Some("gpt-4")
+    );
+
+    // claude-3 has the cheaper input tokens
+    let long_prompt = make_event(&[("estimated_input_tokens", "2000"), ("max_tokens", "10")]);
+    assert_eq!(
+        router.route(&long_prompt, None).await?.model.as_deref(),
+        Some("claude-3")
+    );
+
+    // A custom cost table changes the ranking
+    let mut table = ModelCostTable::builtin();
+    table.insert("gpt-4", ModelProfile::new(0.0, 0.0, 0.95));
+    let router = router.with_cost_table(table);
+    assert_eq!(
+        router.route(&long_prompt, None).await?.model.as_deref(),
+        Some("gpt-4")
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn constraints_filter_candidates_before_ranking() -> Result<()> {
+    let event = make_event(&[]);
+
+    // Default constraints (200ms, 0.01, 0.85) leave only the confident local model
+    let decision = router(0.9, Arc::new(QualityFirst))
+        .await?
+        .route(&event, None)
+        .await?;
+    assert_eq!(decision.route, Route::Local);
+    assert!(!decision.reason.contains("no model within constraints"));
+
+    // Nothing qualifies: the policy still ranks every candidate
+    let decision = router(0.5, Arc::new(QualityFirst))
+        .await?
+        .route(&event, None)
+        .await?;
+    assert_eq!(decision.model.as_deref(), Some("gpt-4"));
+    assert!(decision.reason.contains("no model within constraints"));
+
+    // Privacy still wins over the policy
+    let decision = router(0.5, Arc::new(QualityFirst))
+        .await?
+        .route(&make_event(&[("privacy", "local-only")]), None)
+        .await?;
+    assert_eq!(decision.route, Route::Local);
+
+    let decision = router(0.5, Arc::new(NeverPolicy))
+        .await?
+        .route(&event, None)
+        .await?;
+    assert_eq!(decision.route, Route::Defer);
+    assert_eq!(decision.model, None);
+    Ok(())
+}
+
+#[tokio::test]
+async fn agents_select_policies_by_name() -> Result<()> {
+    assert_eq!(policy_from_name("cheapest").unwrap().name(), "cheapest");
+    assert_eq!(policy_from_name("Fastest-First").unwrap().name(), "fastest");
+    assert_eq!(policy_from_name("quality_first").unwrap().name(), "quality");
+    assert!(policy_from_name("random").is_none());
+
+    let bus = Arc::new(EventBus::new().await?);
+    bus.start().await?;
+    let runtime = AgentRuntime::new(
+        Arc::clone(&bus),
+        Arc::new(ToolRegistry::new()),
+        ModelRouter::new().await?,
+    )
+    .await?;
+
+    let config = |id: &str, policy: &str| AgentConfig {
+        agent_id: id.to_string(),
+        agent_type: "test".to_string(),
+        subscribed_topics: vec![],
+        capabilities: vec![],
+        parameters: HashMap::from([("routing.policy".to_string(), policy.to_string())]),
+    };
+
+    runtime
+        .create_agent(config("frugal", "cheapest"), Box::new(NoopBehavior))
+        .await?;
+    let err = runtime
+        .create_agent(config("confused", "random"), Box::new(NoopBehavior))
+        .await
+        .unwrap_err();
+    assert!(matches!(err, LoomError::AgentError(ref m) if m.contains("routing.policy")));
+    assert_eq!(runtime.agent_count(), 1);
+    Ok(())
+}
@@ -0,0 +1,180 @@
+//! Tests for the built-in SentinelAgent and its detectors
+
+use async_trait::async_trait;
+use loom_core::agent::sentinel::{Detector, EwmaDetector, ZScoreDetector, ANOMALY_TOPIC};
+use loom_core::cognitive::llm::router::ModelRouter;
+use loom_core::tools::ToolPolicy;
+use loom_core::{Anomaly, EventBus, QoSLevel, SentinelAgent, SentinelConfig, Tool, ToolError};
+use loom_core::{Result, ToolRegistry};
+use serde_json::{json, Value};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Fails whenever called with `{"fail": true}`
+struct SometimesFails;
+
+#[async_trait]
+impl Tool for SometimesFails {
+    fn name(&self) -> String {
+        "test:sometimes".to_string()
+    }
+
+    fn description(&self) -> String {
+        "Fails on request".to_string()
+    }
+
+    fn parameters(&self) -> Value {
+        json!({"type": "object"})
+    }
+
+    async fn call(&self, arguments: Value) -> loom_core::tools::ToolResult<Value> {
+        if arguments["fail"] == true {
+            Err(ToolError::ExecutionFailed("upstream unavailable".into()))
+        } else {
+            Ok(json!({"ok": true}))
+        }
+    }
+}
+
+fn config() -> SentinelConfig {
+    SentinelConfig::default()
+        .with_zscore(20, 3.0, 5)
+        .with_cooldown(Duration::from_secs(60))
+}
+
+#[test]
+fn zscore_fires_only_after_min_samples() {
+    let mut detector = ZScoreDetector::new(20, 3.0, 5);
+    // Too few samples to judge
+    assert!(detector.observe(10.0).is_none());
+    assert!(detector.observe(500.0).is_none());
+
+    let mut detector = ZScoreDetector::new(20, 3.0, 5);
+    for v in [10.0, 12.0, 10.0, 12.0, 10.0, 12.0] {
+        assert!(detector.observe(v).is_none());
+    }
+    assert!(detector.observe(12.5).is_none());
+    let detection = detector.observe(50.0).unwrap();
+    assert_eq!(detection.detector, "zscore");
+    assert!(detection.score > 3.0);
+    assert!((detection.baseline - 11.21).abs() < 0.1);
+
+    // Drops are anomalies too
+    assert!(detector.observe(-40.0).unwrap().score < -3.0);
+}
+
+#[test]
+fn ewma_fires_once_per_excursion() {
+    let mut detector = EwmaDetector::new(0.5, 0.5);
+    assert!(detector.observe(0.0).is_none());
+    // 0.5: at the ceiling is not above it
+    assert!(detector.observe(1.0).is_none());
+    let detection = detector.observe(1.0).unwrap();
+    assert_eq!(detection.detector, "ewma");
+    assert_eq!(detection.score, 0.75);
+    assert_eq!(detection.baseline, 0.5);
+    assert!(detector.observe(1.0).is_none());
+
+    // Recover, then a new excursion re-arms the detector
+    for _ in 0..4 {
+        detector.observe(0.0);
+    }
+    assert!(detector.observe(0.4).is_none());
+    assert!(detector.observe(1.0).is_some());
+}
+
+#[tokio::test]
+async fn tool_error_rate_anomaly_is_published() -> Result<()> {
+    let bus = Arc::new(EventBus::new().await?);
+    let (_sub, mut rx) = bus
+        .subscribe(ANOMALY_TOPIC.to_string(), vec![], QoSLevel::QosBatched)
+        .await?;
+
+    let registry = Arc::new(ToolRegistry::new());
+    registry.register(Arc::new(SometimesFails)).await;
+    let mut policy = ToolPolicy::new();
+    policy.circuit_breaker = None;
+    registry.set_policy("test:sometimes", policy);
+
+    let sentinel = SentinelAgent::new(config())
+        .with_event_bus(Arc::clone(&bus))
+        .with_tool_registry(Arc::clone(&registry));
+
+    let call = |fail: bool| {
+        let registry = Arc::clone(&registry);
+        async move {
+            for _ in 0..4 {
+                let _ = registry.call("test:sometimes", json!({"fail": fail})).await;
+            }
+        }
+    };
+
+    call(false).await;
+    assert!(sentinel.check().await.is_empty());
+    // Idle interval: no sample
+    assert!(sentinel.check().await.is_empty());
+
+    // Smoothed error rate: 0.3, then 0.51
+    call(true).await;
+    assert!(sentinel.check().await.is_empty());
+    call(true).await;
+    let anomalies = sentinel.check().await;
+    assert_eq!(anomalies.len(), 1);
+    assert_eq!(anomalies[0].signal, "tool.error_rate");
+    assert_eq!(anomalies[0].subject, "test:sometimes");
+    assert_eq!(anomalies[0].value, 1.0);
+    assert_eq!(anomalies[0].recent, vec![0.0, 1.0]);
+
+    let ev = tokio::time::timeout(Duration::from_secs(1), rx.recv())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(ev.r#type, ANOMALY_TOPIC);
+    assert_eq!(ev.source, "sentinel");
+    assert_eq!(
+        ev.metadata.get("signal").map(String::as_str),
+        Some("tool.error_rate")
+    );
+    assert_eq!(
+        ev.metadata.get("severity").map(String::as_str),
+        Some("warning")
+    );
+    let anomaly: Anomaly = serde_json::from_slice(&ev.payload).unwrap();
+    assert_eq!(
+        anomaly.context["last_error"],
+        "Execution failed: upstream unavailable"
+    );
+
+    // Still failing, but the excursion was already reported
+    call(true).await;
+    assert!(sentinel.check().await.is_empty());
+    Ok(())
+}
+
+#[tokio::test]
+async fn llm_latency_spike_is_detected_with_cooldown() -> Result<()> {
+    let router = ModelRouter::new().await?;
+    let sentinel = SentinelAgent::new(config()).with_model_router(router.clone());
+
+    for _ in 0..12 {
+        router.record_latency("gpt-4", Duration::from_millis(100));
+        assert!(sentinel.check().await.is_empty());
+    }
+
+    for _ in 0..20 {
+        router.record_latency("gpt-4", Duration::from_millis(2000));
+    }
+    let anomalies = sentinel.check().await;
+    assert_eq!(anomalies.len(), 1);
+    let anomaly = &anomalies[0];
+    assert_eq!(anomaly.signal, "llm.latency_p95_ms");
+    assert_eq!(anomaly.subject, "gpt-4");
+    assert_eq!(anomaly.value, 2000.0);
+    assert_eq!(anomaly.detection.baseline, 100.0);
+    assert_eq!(anomaly.severity, "critical");
+    assert_eq!(anomaly.context["samples"], 32);
+
+    // Still far from the baseline, but inside the cooldown
+    assert!(sentinel.check().await.is_empty());
+    Ok(())
+}
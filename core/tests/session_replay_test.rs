@@ -0,0 +1,186 @@
+//! Tests for what-if replays of forked sessions (SessionReplay).
+
+use async_trait::async_trait;
+use loom_core::cognitive::{
+    CognitiveAgent, CognitiveLoop, ExecutionResult, ForkPoint, MemoryBuffer, MemoryItemType,
+    Perception, Plan, SessionManager, SessionReplay, REPLAY_SOURCE_KEY,
+};
+use loom_core::context::{InMemoryStore, MessageRole};
+use loom_core::proto::{AgentState, Event};
+use loom_core::{LoomError, Result};
+use std::sync::Arc;
+
+/// Answers `<style>: <request>`, mentioning how many requests it remembers; fails on "boom"
+struct StyleLoop {
+    style: &'static str,
+    memory: MemoryBuffer,
+    sources: Vec<String>,
+}
+
+impl StyleLoop {
+    fn agent(style: &'static str) -> CognitiveAgent<Self> {
+        CognitiveAgent::new(Self {
+            style,
+            memory: MemoryBuffer::new(50),
+            sources: vec![],
+        })
+    }
+}
+
+#[async_trait]
+impl CognitiveLoop for StyleLoop {
+    async fn perceive(&mut self, event: Event, _state: &AgentState) -> Result<Perception> {
+        self.sources
+            .extend(event.metadata.get(REPLAY_SOURCE_KEY).cloned());
+        Ok(Perception::from_event(event))
+    }
+
+    async fn think(&mut self, perception: &Perception) -> Result<Plan> {
+        let goal = perception.goal.clone().unwrap_or_default();
+        if goal == "boom" {
+            return Err(LoomError::AgentError("model unavailable".into()));
+        }
+        let remembered = self
+            .memory
+            .recent(50)
+            .iter()
+            .filter(|item| item.item_type == MemoryItemType::UserMessage)
+            .count();
+        Ok(Plan::final_answer(
+            goal.clone(),
+            format!("{}: {goal} ({remembered} earlier)", self.style),
+        ))
+    }
+
+    async fn act(&mut self, plan: &Plan, _state: &mut AgentState) -> Result<ExecutionResult> {
+        let answer = plan.final_answer.clone().unwrap_or_default();
+        self.memory.add_user_message(&plan.goal);
+        self.memory.add_agent_response(&answer);
+        Ok(ExecutionResult::with_response(answer))
+    }
+
+    fn memory_buffer(&self) -> &MemoryBuffer {
+        &self.memory
+    }
+
+    fn memory_buffer_mut(&mut self) -> &mut MemoryBuffer {
+        &mut self.memory
+    }
+}
+
+/// Source session recorded by the "plain" variant
+async fn recorded_session(manager: &SessionManager, requests: &[&str]) -> String {
+    let session = manager.start_session("assistant", vec![]).await.unwrap();
+    for (i, request) in requests.iter().enumerate() {
+        manager
+            .record_turn(&session.session_id, "alice", MessageRole::User, *request)
+            .await
+            .unwrap();
+        manager
+            .record_turn(
+                &session.session_id,
+                "assistant",
+                MessageRole::Assistant,
+                format!("plain: {request} ({i} earlier)"),
+            )
+            .await
+            .unwrap();
+    }
+    session.session_id
+}
+
+#[tokio::test]
+async fn test_replay_with_same_behavior_matches() {
+    let manager = Arc::new(SessionManager::new(InMemoryStore::new()));
+    let source = recorded_session(&manager, &["one", "two", "three"]).await;
+
+    let replay = SessionReplay::new(Arc::clone(&manager), &source, ForkPoint::AfterTurns(2));
+    let mut agent = StyleLoop::agent("plain");
+    let run = replay.run("baseline", &mut agent).await.unwrap();
+
+    assert_eq!(run.label, "baseline");
+    assert_eq!(run.source_session_id, source);
+    assert_eq!(run.forked_after_turns, 2);
+    assert_eq!(run.steps.len(), 2);
+    assert_eq!(run.steps[0].request, "two");
+    assert_eq!(run.steps[0].participant, "alice");
+    // The forked history was loaded into the agent's memory
+    assert_eq!(
+        run.steps[0].replayed.as_deref(),
+        Some("plain: two (1 earlier)")
+    );
+    assert!(run.changed_steps().is_empty());
+    assert!(run.first_divergence().is_none());
+    assert_eq!(run.mean_similarity(), 1.0);
+    assert_eq!(agent.session_id(), Some(run.fork_session_id.as_str()));
+    assert_eq!(agent.inner().sources, vec![source.clone(), source.clone()]);
+
+    // Replayed turns are recorded to the fork
+    let (_, turns) = manager.resume_session(&run.fork_session_id).await.unwrap();
+    let contents: Vec<&str> = turns.iter().map(|t| t.content.as_str()).collect();
+    assert_eq!(
+        contents,
+        vec![
+            "one",
+            "plain: one (0 earlier)",
+            "two",
+            "plain: two (1 earlier)",
+            "three",
+            "plain: three (2 earlier)"
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_replay_compares_variants() {
+    let manager = Arc::new(SessionManager::new(InMemoryStore::new()));
+    let source = recorded_session(&manager, &["one", "boom", "three"]).await;
+    let replay = SessionReplay::new(Arc::clone(&manager), &source, ForkPoint::AfterTurns(0));
+
+    let terse = replay
+        .run("terse", &mut StyleLoop::agent("terse"))
+        .await
+        .unwrap();
+    let plain = replay
+        .run("plain", &mut StyleLoop::agent("plain"))
+        .await
+        .unwrap();
+    // Each run gets its own fork
+    assert_ne!(terse.fork_session_id, plain.fork_session_id);
+
+    assert_eq!(terse.changed_steps().len(), 3);
+    let first = terse.first_divergence().unwrap();
+    assert_eq!(first.original.as_deref(), Some("plain: one (0 earlier)"));
+    assert_eq!(first.replayed.as_deref(), Some("terse: one (0 earlier)"));
+    assert!(first.similarity > 0.5 && first.similarity < 1.0);
+
+    // A failing cycle is kept as a step and the replay continues
+    let failed = &plain.steps[1];
+    assert!(failed.replayed.is_none());
+    assert!(failed
+        .error
+        .as_deref()
+        .unwrap()
+        .contains("model unavailable"));
+    assert_eq!(failed.similarity, 0.0);
+    // The failed request never reached memory, so the next answer drifts too
+    assert_eq!(
+        plain.steps[2].replayed.as_deref(),
+        Some("plain: three (1 earlier)")
+    );
+    assert_eq!(plain.changed_steps().len(), 2);
+    assert!(plain.mean_similarity() > terse.mean_similarity());
+
+    let json = serde_json::to_value(&plain).unwrap();
+    assert_eq!(json["steps"][1]["error"], "Agent error: model unavailable");
+}
+
+#[tokio::test]
+async fn test_replay_of_unknown_session_fails() {
+    let manager = Arc::new(SessionManager::new(InMemoryStore::new()));
+    let replay = SessionReplay::new(manager, "missing", ForkPoint::End);
+    assert!(replay
+        .run("any", &mut StyleLoop::agent("plain"))
+        .await
+        .is_err());
+}
@@ -0,0 +1,330 @@
+//! Tests for conversation sessions (SessionManager + CognitiveAgent resume).
+
+use async_trait::async_trait;
+use loom_core::agent::AgentBehavior;
+use loom_core::cognitive::{
+    CognitiveAgent, CognitiveLoop, ExecutionResult, ForkPoint, MemoryBuffer, MemoryItemType,
+    Perception, Plan, SessionManager, FORKED_FROM_TAG,
+};
+use loom_core::context::{InMemoryStore, MemoryQuery, MemoryStore, MessageRole, RocksDbStore};
+use loom_core::proto::{AgentState, Event};
+use loom_core::Result;
+use std::sync::Arc;
+
+/// Echoes the request back as the answer
+struct EchoLoop {
+    memory: MemoryBuffer,
+}
+
+impl EchoLoop {
+    fn new() -> Self {
+        Self {
+            memory: MemoryBuffer::new(20),
+        }
+    }
+}
+
+#[async_trait]
+impl CognitiveLoop for EchoLoop {
+    async fn perceive(&mut self, event: Event, _state: &AgentState) -> Result<Perception> {
+        Ok(Perception::from_event(event))
+    }
+
+    async fn think(&mut self, perception: &Perception) -> Result<Plan> {
+        let goal = perception.goal.clone().unwrap_or_default();
+        Ok(Plan::final_answer(goal.clone(), format!("echo: {goal}")))
+    }
+
+    async fn act(&mut self, plan: &Plan, _state: &mut AgentState) -> Result<ExecutionResult> {
+        let answer = plan.final_answer.clone().unwrap_or_default();
+        self.memory.add_agent_response(&answer);
+        Ok(ExecutionResult::with_response(answer))
+    }
+
+    fn memory_buffer(&self) -> &MemoryBuffer {
+        &self.memory
+    }
+
+    fn memory_buffer_mut(&mut self) -> &mut MemoryBuffer {
+        &mut self.memory
+    }
+}
+
+fn user_event(id: &str, source: &str, text: &str) -> Event {
+    Event {
+        id: id.to_string(),
+        r#type: "user.message".to_string(),
+        timestamp_ms: chrono::Utc::now().timestamp_millis(),
+        source: source.to_string(),
+        metadata: Default::default(),
+        payload: text.as_bytes().to_vec(),
+        confidence: 1.0,
+        tags: vec![],
+        priority: 50,
+    }
+}
+
+fn agent_state(agent_id: &str) -> AgentState {
+    AgentState {
+        agent_id: agent_id.to_string(),
+        persistent_state: vec![],
+        ephemeral_context: vec![],
+        last_update_ms: 0,
+        metadata: Default::default(),
+    }
+}
+
+#[tokio::test]
+async fn test_session_lifecycle_and_transcript() {
+    let manager = SessionManager::new(InMemoryStore::new());
+    let session = manager
+        .start_session("assistant", vec!["alice".to_string()])
+        .await
+        .unwrap();
+    assert!(session.is_active());
+    assert_eq!(session.participants, vec!["alice"]);
+
+    manager
+        .record_turn(&session.session_id, "alice", MessageRole::User, "hi")
+        .await
+        .unwrap();
+    manager
+        .record_turn(
+            &session.session_id,
+            "assistant",
+            MessageRole::Assistant,
+            "hello",
+        )
+        .await
+        .unwrap();
+    manager
+        .record_turn(&session.session_id, "bob", MessageRole::User, "me too")
+        .await
+        .unwrap();
+    assert_eq!(manager.active_sessions().len(), 1);
+
+    let ended = manager.end_session(&session.session_id).await.unwrap();
+    assert!(!ended.is_active());
+    assert_eq!(ended.participants, vec!["alice", "assistant", "bob"]);
+    assert!(manager.active_sessions().is_empty());
+
+    // Ended sessions reject new turns
+    assert!(manager
+        .record_turn(&session.session_id, "alice", MessageRole::User, "late")
+        .await
+        .is_err());
+
+    let (resumed, turns) = manager.resume_session(&session.session_id).await.unwrap();
+    assert_eq!(resumed.ended_at_ms, ended.ended_at_ms);
+    let contents: Vec<&str> = turns.iter().map(|t| t.content.as_str()).collect();
+    assert_eq!(contents, vec!["hi", "hello", "me too"]);
+    assert_eq!(turns[2].participant, "bob");
+}
+
+#[tokio::test]
+async fn test_resume_unknown_session_fails() {
+    let manager = SessionManager::new(InMemoryStore::new());
+    assert!(manager.resume_session("missing").await.is_err());
+    assert!(manager
+        .record_turn("missing", "alice", MessageRole::User, "hi")
+        .await
+        .is_err());
+}
+
+#[tokio::test]
+async fn test_cognitive_agent_records_cycles_to_session() {
+    let store: Arc<dyn MemoryStore> = InMemoryStore::new();
+    let manager = Arc::new(SessionManager::new(Arc::clone(&store)));
+    let session = manager.start_session("assistant", vec![]).await.unwrap();
+
+    let mut agent = CognitiveAgent::new(EchoLoop::new())
+        .with_session(Arc::clone(&manager), &session.session_id);
+    let mut state = agent_state("assistant");
+    agent
+        .on_event(user_event("e1", "alice", "what time is it"), &mut state)
+        .await
+        .unwrap();
+
+    let (resumed, turns) = manager.resume_session(&session.session_id).await.unwrap();
+    assert_eq!(turns.len(), 2);
+    assert_eq!(turns[0].role, MessageRole::User);
+    assert_eq!(turns[0].participant, "alice");
+    assert_eq!(turns[1].role, MessageRole::Assistant);
+    assert_eq!(turns[1].content, "echo: what time is it");
+    assert_eq!(resumed.participants, vec!["alice", "assistant"]);
+}
+
+#[tokio::test]
+async fn test_cognitive_agent_reloads_memory_after_restart() {
+    let dir = tempfile::tempdir().unwrap();
+    let session_id = {
+        let store: Arc<dyn MemoryStore> = RocksDbStore::new(dir.path()).unwrap();
+        let manager = Arc::new(SessionManager::new(store));
+        let session = manager
+            .start_session("assistant", vec!["alice".to_string()])
+            .await
+            .unwrap();
+        let mut agent = CognitiveAgent::new(EchoLoop::new())
+            .with_session(Arc::clone(&manager), &session.session_id);
+        let mut state = agent_state("assistant");
+        agent
+            .on_event(
+                user_event("e1", "alice", "remember the blue door"),
+                &mut state,
+            )
+            .await
+            .unwrap();
+        session.session_id
+    };
+
+    // New process: fresh store handle, manager and agent
+    let store: Arc<dyn MemoryStore> = RocksDbStore::new(dir.path()).unwrap();
+    let manager = Arc::new(SessionManager::new(store));
+    let mut agent = CognitiveAgent::new(EchoLoop::new());
+    let session = agent
+        .resume_session(Arc::clone(&manager), &session_id)
+        .await
+        .unwrap();
+    assert!(session.is_active());
+    assert_eq!(agent.session_id(), Some(session_id.as_str()));
+
+    let items = agent.memory_buffer().recent(10);
+    assert_eq!(items.len(), 2);
+    assert_eq!(items[1].item_type, MemoryItemType::UserMessage);
+    assert_eq!(items[1].content, "remember the blue door");
+    assert_eq!(items[0].item_type, MemoryItemType::AgentResponse);
+
+    // The resumed session keeps recording
+    let mut state = agent_state("assistant");
+    agent
+        .on_event(user_event("e2", "alice", "which door?"), &mut state)
+        .await
+        .unwrap();
+    let (_, turns) = manager.resume_session(&session_id).await.unwrap();
+    assert_eq!(turns.len(), 4);
+}
+
+/// Session with four turns: two user requests and their answers
+async fn recorded_session(manager: &SessionManager) -> (String, Vec<String>) {
+    let session = manager
+        .start_session("assistant", vec!["alice".to_string()])
+        .await
+        .unwrap();
+    let mut ids = Vec::new();
+    for (participant, role, text) in [
+        ("alice", MessageRole::User, "pick a colour"),
+        ("assistant", MessageRole::Assistant, "blue"),
+        ("alice", MessageRole::User, "why?"),
+        ("assistant", MessageRole::Assistant, "it is calm"),
+    ] {
+        ids.push(
+            manager
+                .record_turn(&session.session_id, participant, role, text)
+                .await
+                .unwrap(),
+        );
+    }
+    (session.session_id, ids)
+}
+
+#[tokio::test]
+async fn test_fork_copies_history_up_to_fork_point() {
+    let manager = SessionManager::new(InMemoryStore::new());
+    let (source, ids) = recorded_session(&manager).await;
+    manager.end_session(&source).await.unwrap();
+
+    let fork = manager
+        .fork_session(&source, ForkPoint::AfterTurns(2))
+        .await
+        .unwrap();
+    assert_ne!(fork.session.session_id, source);
+    assert!(fork.session.is_active());
+    assert_eq!(fork.session.agent_id, "assistant");
+    assert_eq!(fork.copied_items, 2);
+    let origin = fork.session.forked_from.clone().unwrap();
+    assert_eq!(origin.session_id, source);
+    assert_eq!(origin.turns, 2);
+    assert_eq!(origin.item_id.as_deref(), Some(ids[1].as_str()));
+    let remaining: Vec<&str> = fork.remaining.iter().map(|t| t.content.as_str()).collect();
+    assert_eq!(remaining, vec!["why?", "it is calm"]);
+
+    // The fork resumes with its own transcript and keeps its origin
+    let (resumed, turns) = manager
+        .resume_session(&fork.session.session_id)
+        .await
+        .unwrap();
+    assert_eq!(resumed.forked_from, Some(origin));
+    let contents: Vec<&str> = turns.iter().map(|t| t.content.as_str()).collect();
+    assert_eq!(contents, vec!["pick a colour", "blue"]);
+    assert_eq!(turns[0].participant, "alice");
+
+    // Copies are new items tagged with their source; the source is untouched
+    let copied = manager
+        .store()
+        .query(&MemoryQuery::new().for_session(fork.session.session_id.clone()))
+        .await
+        .unwrap();
+    assert!(copied.iter().all(|item| !ids.contains(&item.id)));
+    assert!(copied
+        .iter()
+        .filter(|item| item.metadata.tags.contains_key(FORKED_FROM_TAG))
+        .all(|item| item.metadata.tags[FORKED_FROM_TAG] == source));
+    let (_, source_turns) = manager.resume_session(&source).await.unwrap();
+    assert_eq!(source_turns.len(), 4);
+
+    // Forks accept new turns even though the source has ended
+    manager
+        .record_turn(
+            &fork.session.session_id,
+            "alice",
+            MessageRole::User,
+            "and green?",
+        )
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn test_fork_points() {
+    let manager = SessionManager::new(InMemoryStore::new());
+    let (source, ids) = recorded_session(&manager).await;
+
+    let fork = manager
+        .fork_session(&source, ForkPoint::AfterItem(ids[2].clone()))
+        .await
+        .unwrap();
+    assert_eq!(fork.copied_items, 3);
+    assert_eq!(fork.remaining.len(), 1);
+
+    let fork = manager
+        .fork_session(&source, ForkPoint::AfterTurns(0))
+        .await
+        .unwrap();
+    assert_eq!(fork.copied_items, 0);
+    assert_eq!(fork.remaining.len(), 4);
+
+    let fork = manager
+        .fork_session_with_id(&source, ForkPoint::End, "full-copy")
+        .await
+        .unwrap();
+    assert_eq!(fork.session.session_id, "full-copy");
+    assert_eq!(fork.copied_items, 4);
+    assert!(fork.remaining.is_empty());
+    assert!(manager
+        .fork_session_with_id(&source, ForkPoint::End, "full-copy")
+        .await
+        .is_err());
+
+    assert!(manager
+        .fork_session(&source, ForkPoint::AfterTurns(5))
+        .await
+        .is_err());
+    assert!(manager
+        .fork_session(&source, ForkPoint::AfterItem("missing".into()))
+        .await
+        .is_err());
+    assert!(manager
+        .fork_session("missing", ForkPoint::End)
+        .await
+        .is_err());
+}
@@ -0,0 +1,184 @@
+//! Unit tests for the sandboxed shell tool (Unix only: they run real commands)
+#![cfg(unix)]
+
+use loom_core::tools::native::{ShellSandbox, ShellTool};
+use loom_core::tools::{Tool, ToolError};
+use serde_json::json;
+use tempfile::TempDir;
+
+fn shell(sandbox: ShellSandbox) -> ShellTool {
+    let allowed = ["echo", "pwd", "cat", "env", "sh", "sleep"];
+    ShellTool::new(allowed.iter().map(|c| c.to_string()).collect()).with_sandbox(sandbox)
+}
+
+fn jailed(workspace: &TempDir) -> ShellSandbox {
+    ShellSandbox {
+        working_dir: Some(workspace.path().to_path_buf()),
+        ..Default::default()
+    }
+}
+
+#[tokio::test]
+async fn test_result_is_structured() {
+    let tool = shell(ShellSandbox::default());
+    let result = tool
+        .call(json!({"command": "sh", "args": ["-c", "echo out; echo err >&2; exit 3"]}))
+        .await
+        .unwrap();
+    assert_eq!(result["stdout"], "out\n");
+    assert_eq!(result["stderr"], "err\n");
+    assert_eq!(result["exit_code"], 3);
+    assert_eq!(result["timed_out"], false);
+    assert_eq!(result["stdout_truncated"], false);
+
+    let err = tool.call(json!({"command": "rm"})).await.unwrap_err();
+    assert!(matches!(err, ToolError::PermissionDenied(_)));
+}
+
+#[tokio::test]
+async fn test_commands_are_confined_to_the_working_dir() {
+    let workspace = tempfile::tempdir().unwrap();
+    std::fs::create_dir(workspace.path().join("sub")).unwrap();
+    std::fs::write(workspace.path().join("notes.txt"), "inside").unwrap();
+    let tool = shell(jailed(&workspace));
+
+    let result = tool.call(json!({"command": "pwd"})).await.unwrap();
+    let jail = workspace.path().canonicalize().unwrap();
+    assert_eq!(
+        std::path::Path::new(result["stdout"].as_str().unwrap().trim())
+            .canonicalize()
+            .unwrap(),
+        jail
+    );
+    let result = tool
+        .call(json!({"command": "cat", "args": ["../notes.txt"], "cwd": "sub"}))
+        .await
+        .unwrap();
+    assert_eq!(result["stdout"], "inside");
+
+    for args in [
+        json!({"command": "cat", "args": ["/etc/hostname"]}),
+        json!({"command": "cat", "args": ["../../etc/hostname"], "cwd": "sub"}),
+        json!({"command": "cat", "args": ["--file=/etc/hostname"]}),
+        json!({"command": "cat", "args": ["-C/etc"]}),
+        json!({"command": "cat", "args": ["-o../x"]}),
+        json!({"command": "pwd", "cwd": ".."}),
+    ] {
+        let err = tool.call(args).await.unwrap_err();
+        assert!(matches!(err, ToolError::PermissionDenied(_)), "{err}");
+    }
+}
+
+#[tokio::test]
+async fn test_symlinks_out_of_the_working_dir_are_rejected() {
+    let workspace = tempfile::tempdir().unwrap();
+    let outside = tempfile::tempdir().unwrap();
+    std::fs::write(outside.path().join("secret.txt"), "outside").unwrap();
+    std::fs::write(workspace.path().join("notes.txt"), "inside").unwrap();
+    let link = |target: &std::path::Path, name: &str| {
+        std::os::unix::fs::symlink(target, workspace.path().join(name)).unwrap()
+    };
+    link(outside.path(), "out");
+    link(&outside.path().join("missing.txt"), "dangling");
+    link(&workspace.path().join("notes.txt"), "alias.txt");
+    let tool = shell(jailed(&workspace));
+
+    for args in [
+        json!({"command": "cat", "args": ["out/secret.txt"]}),
+        json!({"command": "cat", "args": ["--file=out/secret.txt"]}),
+        json!({"command": "cat", "args": ["dangling"]}),
+        json!({"command": "pwd", "cwd": "out"}),
+    ] {
+        let err = tool.call(args).await.unwrap_err();
+        assert!(matches!(err, ToolError::PermissionDenied(_)), "{err}");
+    }
+    let result = tool
+        .call(json!({"command": "cat", "args": ["alias.txt"]}))
+        .await
+        .unwrap();
+    assert_eq!(result["stdout"], "inside");
+}
+
+#[tokio::test]
+async fn test_environment_is_scrubbed() {
+    std::env::set_var("LOOM_SHELL_TEST_SECRET", "hunter2");
+    let tool = shell(ShellSandbox {
+        scrub_env: true,
+        ..Default::default()
+    });
+    let result = tool.call(json!({"command": "env"})).await.unwrap();
+    let env = result["stdout"].as_str().unwrap();
+    assert!(!env.contains("LOOM_SHELL_TEST_SECRET"));
+    assert!(env.lines().any(|line| line.starts_with("PATH=")));
+
+    let result = shell(ShellSandbox::default())
+        .call(json!({"command": "env"}))
+        .await
+        .unwrap();
+    assert!(result["stdout"]
+        .as_str()
+        .unwrap()
+        .contains("LOOM_SHELL_TEST_SECRET=hunter2"));
+}
+
+#[tokio::test]
+async fn test_wall_clock_limit_kills_the_command() {
+    let tool = shell(ShellSandbox {
+        timeout_ms: 200,
+        ..Default::default()
+    });
+    let started = std::time::Instant::now();
+    let result = tool
+        .call(json!({"command": "sleep", "args": ["5"]}))
+        .await
+        .unwrap();
+    assert!(started.elapsed() < std::time::Duration::from_secs(3));
+    assert_eq!(result["timed_out"], true);
+    assert!(result["exit_code"].is_null());
+}
+
+#[tokio::test]
+async fn test_timeout_kills_the_whole_process_group() {
+    let tool = shell(ShellSandbox {
+        timeout_ms: 300,
+        ..Default::default()
+    });
+    // The background `sleep` keeps stdout open unless it is killed with its parent
+    let result = tool
+        .call(json!({"command": "sh", "args": ["-c", "sleep 30 & echo started; wait"]}))
+        .await
+        .unwrap();
+    assert_eq!(result["timed_out"], true);
+    assert_eq!(result["stdout"], "started\n");
+    assert_eq!(result["stdout_truncated"], false);
+}
+
+#[tokio::test]
+async fn test_output_is_capped() {
+    let tool = shell(ShellSandbox {
+        max_output_bytes: 10,
+        ..Default::default()
+    });
+    let result = tool
+        .call(json!({"command": "echo", "args": ["0123456789abcdef"]}))
+        .await
+        .unwrap();
+    assert_eq!(result["stdout"], "0123456789");
+    assert_eq!(result["stdout_truncated"], true);
+    assert_eq!(result["exit_code"], 0);
+}
+
+#[tokio::test]
+async fn test_cpu_limit_stops_busy_commands() {
+    let tool = shell(ShellSandbox {
+        cpu_secs: Some(1),
+        timeout_ms: 20_000,
+        ..Default::default()
+    });
+    let result = tool
+        .call(json!({"command": "sh", "args": ["-c", "while :; do :; done"]}))
+        .await
+        .unwrap();
+    assert_eq!(result["timed_out"], false);
+    assert!(result["exit_code"].is_null() || result["exit_code"] != 0);
+}
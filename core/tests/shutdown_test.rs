@@ -0,0 +1,255 @@
+/// Tests for ShutdownRegistry ordering and deadlines, ToolRegistry and AgentRuntime draining
+use async_trait::async_trait;
+use loom_core::agent::{AgentBehavior, AgentRuntime};
+use loom_core::proto::{Action, AgentConfig, AgentState, Event};
+use loom_core::{
+    EventBus, LoomError, ModelRouter, Result, ShutdownRegistry, StopOutcome, Tool, ToolError,
+    ToolRegistry,
+};
+use serde_json::{json, Value};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::time::Instant;
+
+/// Registers a hook that appends `name` to `log` when stopped
+fn record(registry: &ShutdownRegistry, log: &Arc<Mutex<Vec<String>>>, name: &str, deps: &[&str]) {
+    let log = Arc::clone(log);
+    let label = name.to_string();
+    registry
+        .register_fn(name, deps, move |_| {
+            log.lock().unwrap().push(label.clone());
+            async { Ok(()) }
+        })
+        .unwrap();
+}
+
+#[tokio::test]
+async fn dependents_stop_before_their_dependencies() {
+    let registry = ShutdownRegistry::new();
+    let log = Arc::new(Mutex::new(Vec::new()));
+    // Registration order deliberately differs from stop order
+    record(&registry, &log, "event_bus", &[]);
+    record(&registry, &log, "tools", &["pools"]);
+    record(&registry, &log, "agents", &["tools", "event_bus"]);
+    record(&registry, &log, "pools", &[]);
+
+    assert_eq!(
+        registry.stop_order(),
+        vec!["agents", "event_bus", "tools", "pools"]
+    );
+
+    let report = registry.shutdown(Duration::from_secs(1)).await;
+    assert!(report.is_clean());
+    assert_eq!(
+        *log.lock().unwrap(),
+        vec!["agents", "event_bus", "tools", "pools"]
+    );
+
+    // Components are consumed by the first shutdown
+    assert!(registry
+        .shutdown(Duration::from_secs(1))
+        .await
+        .components
+        .is_empty());
+}
+
+#[tokio::test]
+async fn duplicates_and_cycles_are_rejected() {
+    let registry = ShutdownRegistry::new();
+    let log = Arc::new(Mutex::new(Vec::new()));
+    record(&registry, &log, "a", &["b"]);
+    record(&registry, &log, "b", &["c"]);
+
+    let dup = registry.register_fn("a", &[], |_| async { Ok(()) });
+    assert!(matches!(dup, Err(LoomError::AgentError(_))));
+
+    let cycle = registry.register_fn("c", &["a"], |_| async { Ok(()) });
+    assert!(matches!(cycle, Err(LoomError::AgentError(_))));
+
+    // The rejected component left the registry unchanged
+    assert_eq!(registry.stop_order(), vec!["a", "b"]);
+}
+
+#[tokio::test]
+async fn timed_out_and_failed_hooks_do_not_block_the_rest() {
+    let registry = ShutdownRegistry::new();
+    let log = Arc::new(Mutex::new(Vec::new()));
+    registry
+        .register_with_timeout(
+            "stuck",
+            &["failing"],
+            Duration::from_millis(50),
+            Arc::new(Stuck),
+        )
+        .unwrap();
+    registry
+        .register_fn("failing", &["last"], |_| async {
+            Err(LoomError::StorageError("flush failed".into()))
+        })
+        .unwrap();
+    record(&registry, &log, "last", &[]);
+
+    let started = Instant::now();
+    let report = registry.shutdown(Duration::from_secs(5)).await;
+    assert!(started.elapsed() < Duration::from_secs(1));
+
+    assert!(!report.is_clean());
+    assert_eq!(report.outcome("stuck"), Some(&StopOutcome::TimedOut));
+    assert!(matches!(
+        report.outcome("failing"),
+        Some(StopOutcome::Failed(msg)) if msg.contains("flush failed")
+    ));
+    assert_eq!(report.outcome("last"), Some(&StopOutcome::Stopped));
+    assert_eq!(*log.lock().unwrap(), vec!["last"]);
+}
+
+/// Hook that never returns
+struct Stuck;
+
+#[async_trait]
+impl loom_core::ShutdownHook for Stuck {
+    async fn stop(&self, _deadline: Instant) -> Result<()> {
+        std::future::pending().await
+    }
+}
+
+/// Tool that holds its call open until released
+struct GateTool {
+    release: Arc<tokio::sync::Notify>,
+}
+
+#[async_trait]
+impl Tool for GateTool {
+    fn name(&self) -> String {
+        "test:gate".to_string()
+    }
+
+    fn description(&self) -> String {
+        "Returns once released".to_string()
+    }
+
+    fn parameters(&self) -> Value {
+        json!({"type": "object"})
+    }
+
+    async fn call(&self, _arguments: Value) -> loom_core::tools::ToolResult<Value> {
+        self.release.notified().await;
+        Ok(json!({"ok": true}))
+    }
+}
+
+#[tokio::test]
+async fn tool_registry_drain_waits_for_inflight_calls() {
+    let registry = Arc::new(ToolRegistry::new());
+    let release = Arc::new(tokio::sync::Notify::new());
+    registry
+        .register(Arc::new(GateTool {
+            release: Arc::clone(&release),
+        }))
+        .await;
+
+    let call = {
+        let registry = Arc::clone(&registry);
+        tokio::spawn(async move { registry.call("test:gate", json!({})).await })
+    };
+    while registry.inflight_calls() == 0 {
+        tokio::task::yield_now().await;
+    }
+
+    // Deadline passes while the call is still running
+    assert!(
+        !registry
+            .drain(Instant::now() + Duration::from_millis(20))
+            .await
+    );
+    assert_eq!(registry.inflight_calls(), 1);
+
+    // New calls are refused once draining started
+    assert!(matches!(
+        registry.call("test:gate", json!({})).await,
+        Err(ToolError::Internal(_))
+    ));
+
+    let drained = {
+        let registry = Arc::clone(&registry);
+        tokio::spawn(async move {
+            registry
+                .drain(Instant::now() + Duration::from_secs(1))
+                .await
+        })
+    };
+    tokio::time::sleep(Duration::from_millis(10)).await;
+    release.notify_one();
+
+    assert!(call.await.unwrap().is_ok());
+    assert!(drained.await.unwrap());
+    assert_eq!(registry.inflight_calls(), 0);
+}
+
+struct ShutdownFlag {
+    stopped: Arc<AtomicBool>,
+}
+
+#[async_trait]
+impl AgentBehavior for ShutdownFlag {
+    async fn on_event(&mut self, _event: Event, _state: &mut AgentState) -> Result<Vec<Action>> {
+        Ok(vec![])
+    }
+
+    async fn on_init(&mut self, _config: &AgentConfig) -> Result<()> {
+        Ok(())
+    }
+
+    async fn on_shutdown(&mut self) -> Result<()> {
+        self.stopped.store(true, Ordering::SeqCst);
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn agent_runtime_drain_runs_on_shutdown() -> Result<()> {
+    let bus = Arc::new(EventBus::new().await?);
+    bus.start().await?;
+    let runtime = AgentRuntime::new(
+        Arc::clone(&bus),
+        Arc::new(ToolRegistry::new()),
+        ModelRouter::new().await?,
+    )
+    .await?;
+
+    let stopped = Arc::new(AtomicBool::new(false));
+    let cfg = AgentConfig {
+        agent_id: "drained".to_string(),
+        agent_type: "test".to_string(),
+        subscribed_topics: vec!["topic.drain".to_string()],
+        capabilities: vec![],
+        parameters: Default::default(),
+    };
+    runtime
+        .create_agent(
+            cfg,
+            Box::new(ShutdownFlag {
+                stopped: Arc::clone(&stopped),
+            }),
+        )
+        .await?;
+
+    let aborted = runtime.drain(Instant::now() + Duration::from_secs(1)).await;
+    assert_eq!(aborted, 0);
+    assert!(stopped.load(Ordering::SeqCst));
+    // Subscriptions are gone with the agent
+    let ev = Event {
+        id: "late".to_string(),
+        r#type: "test".to_string(),
+        timestamp_ms: 0,
+        source: "test".to_string(),
+        metadata: Default::default(),
+        payload: vec![],
+        confidence: 1.0,
+        tags: vec![],
+        priority: 0,
+    };
+    assert_eq!(bus.publish("topic.drain", ev).await?, 0);
+    Ok(())
+}
@@ -0,0 +1,228 @@
+/// Tests for envelope signing: HMAC signatures verified on Bridge publishes
+use loom_core::messaging::envelope::keys;
+use loom_core::messaging::{BRIDGE_INGRESS, INGRESS_KEY};
+use loom_core::proto::{Event, QoSLevel};
+use loom_core::{EnvelopeSigner, EventBus, EventExt, LoomError, Result};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+fn make_event(id: &str, sender: &str) -> Event {
+    Event {
+        id: id.to_string(),
+        r#type: "system.shutdown".to_string(),
+        timestamp_ms: 0,
+        source: "bridge".to_string(),
+        metadata: HashMap::new(),
+        payload: b"now".to_vec(),
+        confidence: 1.0,
+        tags: vec![],
+        priority: 50,
+    }
+    .with_sender(sender.to_string())
+}
+
+/// The event as the Bridge hands it to the EventBus
+fn via_bridge(mut event: Event) -> Event {
+    event
+        .metadata
+        .insert(INGRESS_KEY.to_string(), BRIDGE_INGRESS.to_string());
+    event
+}
+
+fn signature_error(result: Result<u64>) -> String {
+    match result {
+        Err(LoomError::SignatureError(reason)) => reason,
+        other => panic!("expected a signature error, got {other:?}"),
+    }
+}
+
+const TOPIC: &str = "system.control";
+
+#[test]
+fn signature_covers_topic_envelope_and_payload() {
+    let signer = EnvelopeSigner::new("deployment-key");
+    let mut event = make_event("e1", "agent.ops");
+    signer.sign(TOPIC, &mut event);
+    assert_eq!(event.metadata[keys::SIGNATURE].len(), 64);
+    assert!(event.metadata.contains_key(keys::TIMESTAMP_MS));
+
+    // Signed for one topic only
+    let reason = signer.verify("system.other", &event).unwrap_err();
+    assert!(reason.to_string().contains("does not match"), "{reason}");
+
+    let spoofed = event.clone().with_sender("agent.admin".to_string());
+    assert!(signer.verify(TOPIC, &spoofed).is_err());
+
+    let mut tampered = event.clone();
+    tampered.payload = b"later".to_vec();
+    assert!(signer.verify(TOPIC, &tampered).is_err());
+
+    // Another deployment's key does not verify
+    assert!(EnvelopeSigner::new("other-key")
+        .verify(TOPIC, &event)
+        .is_err());
+
+    let mut garbled = event.clone();
+    garbled
+        .metadata
+        .insert(keys::SIGNATURE.to_string(), "not-hex".to_string());
+    assert!(signer.verify(TOPIC, &garbled).is_err());
+    assert!(signer
+        .verify(TOPIC, &make_event("e2", "agent.ops"))
+        .is_err());
+
+    // Trace context is re-injected per hop and left out of the signature
+    let mut traced = event.clone();
+    traced
+        .metadata
+        .insert(keys::SPAN_ID.to_string(), "00f067aa0ba902b7".to_string());
+    signer.verify(TOPIC, &traced).unwrap();
+}
+
+#[test]
+fn replayed_and_stale_events_are_rejected() {
+    let signer = EnvelopeSigner::new("deployment-key").with_max_clock_skew(Duration::from_secs(60));
+    let mut event = make_event("e1", "agent.ops");
+    signer.sign(TOPIC, &mut event);
+    signer.verify(TOPIC, &event).unwrap();
+    let reason = signer.verify(TOPIC, &event).unwrap_err();
+    assert!(reason.to_string().contains("already seen"), "{reason}");
+
+    let now = chrono::Utc::now().timestamp_millis();
+    for (id, signed_at) in [("old", now - 120_000), ("future", now + 120_000)] {
+        let mut event = make_event(id, "agent.ops");
+        event
+            .metadata
+            .insert(keys::TIMESTAMP_MS.to_string(), signed_at.to_string());
+        signer.sign(TOPIC, &mut event);
+        let reason = signer.verify(TOPIC, &event).unwrap_err();
+        assert!(reason.to_string().contains("clock skew"), "{reason}");
+    }
+
+    // A small skew is tolerated
+    let mut event = make_event("late", "agent.ops");
+    event
+        .metadata
+        .insert(keys::TIMESTAMP_MS.to_string(), (now - 30_000).to_string());
+    signer.sign(TOPIC, &mut event);
+    signer.verify(TOPIC, &event).unwrap();
+}
+
+#[test]
+fn required_topics_default_to_system() {
+    let signer = EnvelopeSigner::new("k");
+    assert!(signer.requires_signature("system.shutdown"));
+    assert!(!signer.requires_signature("chat"));
+
+    let signer = signer.with_required_topics(["ops.*"]);
+    assert!(signer.requires_signature("ops.restart"));
+    assert!(!signer.requires_signature("system.shutdown"));
+}
+
+#[tokio::test]
+async fn bus_verifies_bridge_publishes() -> Result<()> {
+    let signer = Arc::new(EnvelopeSigner::new("deployment-key"));
+    let mut bus = EventBus::new().await?;
+    bus.set_signer(Arc::clone(&signer));
+    let (_sys, mut sys_rx) = bus
+        .subscribe("system.*".into(), vec![], QoSLevel::QosBatched)
+        .await?;
+    let (_chat, mut chat_rx) = bus
+        .subscribe("chat".into(), vec![], QoSLevel::QosBatched)
+        .await?;
+
+    // Unsigned and forged Bridge publishes on system topics are rejected
+    let unsigned = via_bridge(make_event("u1", "agent.ops"));
+    let reason = signature_error(bus.publish("system.control", unsigned).await);
+    assert!(reason.contains("not signed"));
+    let mut forged = make_event("f1", "agent.ops");
+    signer.sign("system.control", &mut forged);
+    let forged = via_bridge(forged.with_sender("agent.admin".to_string()));
+    signature_error(bus.publish("system.control", forged).await);
+    assert_eq!(
+        bus.get_stats("system.control").unwrap().signature_rejected,
+        2
+    );
+
+    // A signed one is delivered marked verified, without the ingress marker
+    let mut signed = make_event("s1", "agent.ops");
+    signer.sign("system.control", &mut signed);
+    bus.publish("system.control", via_bridge(signed.clone()))
+        .await?;
+    let delivered = sys_rx.try_recv().unwrap();
+    assert_eq!(delivered.id, "s1");
+    assert_eq!(delivered.metadata[keys::SIGNATURE_VERIFIED], "true");
+    assert!(!delivered.metadata.contains_key(INGRESS_KEY));
+
+    // It cannot be replayed, on its topic or another one
+    let reason = signature_error(
+        bus.publish("system.control", via_bridge(signed.clone()))
+            .await,
+    );
+    assert!(reason.contains("already seen"));
+    signature_error(bus.publish("system.config", via_bridge(signed)).await);
+    assert!(sys_rx.try_recv().is_err());
+
+    // In-process publishers are trusted without a signature
+    bus.publish("system.control", make_event("i1", "agent.sentinel"))
+        .await?;
+    assert_eq!(sys_rx.try_recv().unwrap().id, "i1");
+    assert!(sys_rx.try_recv().is_err());
+
+    // Elsewhere unsigned Bridge publishes pass, but cannot claim to be verified
+    let mut claimed = via_bridge(make_event("c1", "agent.ops"));
+    claimed
+        .metadata
+        .insert(keys::SIGNATURE_VERIFIED.to_string(), "true".to_string());
+    bus.publish("chat", claimed).await?;
+    let delivered = chat_rx.try_recv().unwrap();
+    assert!(!delivered.metadata.contains_key(keys::SIGNATURE_VERIFIED));
+    Ok(())
+}
+
+#[tokio::test]
+async fn bus_without_signer_accepts_bridge_publishes() -> Result<()> {
+    let bus = EventBus::new().await?;
+    let (_sid, mut rx) = bus
+        .subscribe("system.control".into(), vec![], QoSLevel::QosBatched)
+        .await?;
+    bus.publish("system.control", via_bridge(make_event("u1", "agent.ops")))
+        .await?;
+    let delivered = rx.try_recv().unwrap();
+    assert!(!delivered.metadata.contains_key(INGRESS_KEY));
+    assert!(!delivered.metadata.contains_key(keys::SIGNATURE_VERIFIED));
+    Ok(())
+}
+
+#[tokio::test]
+async fn batch_with_a_bad_signature_partway_delivers_nothing() -> Result<()> {
+    let signer = Arc::new(EnvelopeSigner::new("deployment-key"));
+    let mut bus = EventBus::new().await?;
+    bus.set_signer(Arc::clone(&signer));
+    let (_sid, mut rx) = bus
+        .subscribe("system.control".into(), vec![], QoSLevel::QosBatched)
+        .await?;
+    let signed = |id: &str| {
+        let mut event = make_event(id, "agent.ops");
+        signer.sign(TOPIC, &mut event);
+        event
+    };
+    let (first, second) = (signed("b1"), signed("b2"));
+
+    let batch = vec![
+        via_bridge(first.clone()),
+        via_bridge(second.clone()),
+        via_bridge(make_event("b3", "agent.ops")),
+    ];
+    let reason = signature_error(bus.publish_batch(TOPIC, batch).await);
+    assert!(reason.contains("not signed"));
+    assert!(rx.try_recv().is_err());
+
+    // The events that were not published can be sent again
+    let batch = vec![via_bridge(first), via_bridge(second)];
+    assert_eq!(bus.publish_batch(TOPIC, batch).await?, 2);
+    assert_eq!(rx.try_recv().unwrap().id, "b1");
+    assert_eq!(rx.try_recv().unwrap().id, "b2");
+    Ok(())
+}
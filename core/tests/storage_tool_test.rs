@@ -0,0 +1,271 @@
+//! Tests for the `storage:*` tools against a mock S3-compatible server
+
+use axum::body::Bytes;
+use axum::extract::{Path, Query, State};
+use axum::http::{HeaderMap, Method, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::{routing::any, Router};
+use loom_core::blob::{BlobAccess, BlobStore, DiskBlobBackend};
+use loom_core::tools::native::{storage_tools, ObjectStorage, StorageConfig};
+use loom_core::tools::{with_caller, ToolError};
+use loom_core::Tool;
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, Mutex};
+
+/// Objects by `bucket/key`, with their content type
+type Objects = Arc<Mutex<BTreeMap<String, (Vec<u8>, String)>>>;
+
+async fn object(
+    State(objects): State<Objects>,
+    Path((bucket, key)): Path<(String, String)>,
+    method: Method,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Response {
+    assert!(headers["authorization"]
+        .to_str()
+        .unwrap()
+        .starts_with("AWS4-HMAC-SHA256 Credential=minio/"));
+    let path = format!("{bucket}/{key}");
+    match method {
+        Method::PUT => {
+            let content_type = headers
+                .get("content-type")
+                .map(|v| v.to_str().unwrap().to_string())
+                .unwrap_or_default();
+            objects
+                .lock()
+                .unwrap()
+                .insert(path, (body.to_vec(), content_type));
+            StatusCode::OK.into_response()
+        }
+        Method::GET => match objects.lock().unwrap().get(&path) {
+            Some((content, content_type)) => {
+                ([("content-type", content_type.clone())], content.clone()).into_response()
+            }
+            None => StatusCode::NOT_FOUND.into_response(),
+        },
+        _ => StatusCode::METHOD_NOT_ALLOWED.into_response(),
+    }
+}
+
+/// ListObjectsV2, one object per page when `max-keys` is 1
+async fn list(
+    State(objects): State<Objects>,
+    Path(bucket): Path<String>,
+    Query(q): Query<HashMap<String, String>>,
+) -> String {
+    assert_eq!(q["list-type"], "2");
+    let prefix = format!("{bucket}/{}", q.get("prefix").cloned().unwrap_or_default());
+    let max_keys: usize = q["max-keys"].parse().unwrap();
+    let start: usize = q
+        .get("continuation-token")
+        .map(|t| t.parse().unwrap())
+        .unwrap_or(0);
+    let objects = objects.lock().unwrap();
+    let matching: Vec<_> = objects
+        .iter()
+        .filter(|(path, _)| path.starts_with(&prefix))
+        .collect();
+    let page: String = matching
+        .iter()
+        .skip(start)
+        .take(max_keys)
+        .map(|(path, (content, _))| {
+            format!(
+                "<Contents><Key>{}</Key><LastModified>2024-06-01T12:00:00.000Z</LastModified><Size>{}</Size></Contents>",
+                path[bucket.len() + 1..].replace('&', "&amp;"),
+                content.len()
+            )
+        })
+        .collect();
+    let next = start + max_keys;
+    let truncated = next < matching.len();
+    format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?><ListBucketResult><Name>{bucket}</Name>\
+         <IsTruncated>{truncated}</IsTruncated>{page}<NextContinuationToken>{next}</NextContinuationToken></ListBucketResult>"
+    )
+}
+
+/// Serve a mock S3 on a random local port and return its base URL
+async fn serve_s3() -> String {
+    let app = Router::new()
+        .route("/:bucket", any(list))
+        .route("/:bucket/*key", any(object))
+        .with_state(Objects::default());
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+    url
+}
+
+fn storage(endpoint: String) -> Arc<ObjectStorage> {
+    let mut config =
+        StorageConfig::new(endpoint, "minio", "minio-secret").with_buckets(["reports", "audio"]);
+    config.max_bytes = 16;
+    Arc::new(ObjectStorage::new(config).unwrap())
+}
+
+fn tools(
+    storage: Arc<ObjectStorage>,
+    blobs: Option<Arc<BlobStore>>,
+) -> HashMap<String, Arc<dyn Tool>> {
+    storage_tools(storage, blobs)
+        .into_iter()
+        .map(|t| (t.name(), t))
+        .collect()
+}
+
+#[tokio::test]
+async fn objects_round_trip_within_allowlist_and_limits() {
+    let tools = tools(storage(serve_s3().await), None);
+    let (put, get, list) = (
+        &tools["storage:put"],
+        &tools["storage:get"],
+        &tools["storage:list"],
+    );
+
+    let out = put
+        .call(json!({
+            "bucket": "reports", "key": "2024/june.md", "content": "# June",
+            "content_type": "text/markdown"
+        }))
+        .await
+        .unwrap();
+    assert_eq!(out["size"], 6);
+    put.call(json!({ "bucket": "reports", "key": "2024/a&b.bin", "content": "/wA=", "encoding": "base64" }))
+        .await
+        .unwrap();
+    put.call(json!({ "bucket": "reports", "key": "2023/may.md", "content": "# May" }))
+        .await
+        .unwrap();
+
+    let out = get
+        .call(json!({ "bucket": "reports", "key": "2024/june.md" }))
+        .await
+        .unwrap();
+    assert_eq!(out["content"], "# June");
+    assert_eq!(out["encoding"], "text");
+    assert_eq!(out["content_type"], "text/markdown");
+    let out = get
+        .call(json!({ "bucket": "reports", "key": "2024/a&b.bin" }))
+        .await
+        .unwrap();
+    assert_eq!(out["encoding"], "base64");
+    assert_eq!(out["content"], "/wA=");
+    let missing = get
+        .call(json!({ "bucket": "reports", "key": "2024/july.md" }))
+        .await;
+    assert!(matches!(missing, Err(ToolError::ExecutionFailed(_))));
+
+    // Pages follow the continuation token; keys are unescaped
+    let page = list
+        .call(json!({ "bucket": "reports", "prefix": "2024/", "max_keys": 1 }))
+        .await
+        .unwrap();
+    assert_eq!(page["count"], 1);
+    assert_eq!(page["objects"][0]["key"], "2024/a&b.bin");
+    assert_eq!(page["objects"][0]["size"], 2);
+    let page = list
+        .call(json!({
+            "bucket": "reports", "prefix": "2024/", "max_keys": 1,
+            "next_token": page["next_token"]
+        }))
+        .await
+        .unwrap();
+    assert_eq!(page["objects"][0]["key"], "2024/june.md");
+    assert_eq!(page["next_token"], Value::Null);
+
+    let denied = get
+        .call(json!({ "bucket": "secrets", "key": "keys.txt" }))
+        .await;
+    assert!(matches!(denied, Err(ToolError::PermissionDenied(_))));
+    let too_large = put
+        .call(json!({ "bucket": "audio", "key": "clip.wav", "content": "x".repeat(17) }))
+        .await;
+    assert!(matches!(too_large, Err(ToolError::ExecutionFailed(_))));
+    let bad_key = put
+        .call(json!({ "bucket": "audio", "key": "/clip.wav", "content": "x" }))
+        .await;
+    assert!(matches!(bad_key, Err(ToolError::InvalidArguments(_))));
+}
+
+#[tokio::test]
+async fn objects_move_to_and_from_blobs() {
+    let dir = tempfile::tempdir().unwrap();
+    let blobs = Arc::new(BlobStore::new(Arc::new(DiskBlobBackend::new(dir.path()))));
+    let tools = tools(storage(serve_s3().await), Some(Arc::clone(&blobs)));
+    let (put, get) = (&tools["storage:put"], &tools["storage:get"]);
+
+    let blob = blobs
+        .put(
+            b"RIFF-wave".to_vec(),
+            "audio/wav",
+            BlobAccess::owned_by("tts").with_readers(["publisher"]),
+        )
+        .await
+        .unwrap();
+    let args = json!({ "bucket": "audio", "key": "greeting.wav", "blob_ref": blob.id });
+    let denied = with_caller("stranger", put.call(args.clone())).await;
+    assert!(matches!(denied, Err(ToolError::PermissionDenied(_))));
+    let out = with_caller("publisher", put.call(args)).await.unwrap();
+    assert_eq!(out["size"], 9);
+    assert_eq!(out["content_type"], "audio/wav");
+
+    let out = with_caller(
+        "publisher",
+        get.call(json!({
+            "bucket": "audio", "key": "greeting.wav", "as_blob": true, "readers": ["player"]
+        })),
+    )
+    .await
+    .unwrap();
+    assert!(out.get("content").is_none());
+    let copy = blobs
+        .blob_ref(out["blob_ref"].as_str().unwrap())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(copy.content_type, "audio/wav");
+    assert_eq!(
+        blobs.get(&copy, Some("player"), None).await.unwrap(),
+        b"RIFF-wave"
+    );
+    assert!(blobs.get(&copy, Some("tts"), None).await.is_err());
+}
+
+#[tokio::test]
+async fn presigned_links_are_capped_and_use_public_endpoint() {
+    let mut config =
+        StorageConfig::new("http://minio:9000", "minio", "minio-secret").with_buckets(["reports"]);
+    config.public_endpoint = Some("https://files.example.com".to_string());
+    config.max_presign_secs = 600;
+    let tools = tools(Arc::new(ObjectStorage::new(config).unwrap()), None);
+    let presign = &tools["storage:presign"];
+
+    let out = presign
+        .call(json!({ "bucket": "reports", "key": "2024/june report.md", "expires_secs": 86400 }))
+        .await
+        .unwrap();
+    assert_eq!(out["method"], "GET");
+    assert_eq!(out["expires_secs"], 600);
+    let url = out["url"].as_str().unwrap();
+    assert!(url.starts_with("https://files.example.com/reports/2024/june%20report.md?"));
+    assert!(url.contains("X-Amz-Expires=600"));
+    assert!(url.contains("X-Amz-Credential=minio%2F"));
+    assert!(url.contains("X-Amz-Signature="));
+
+    let out = presign
+        .call(json!({ "bucket": "reports", "key": "upload.csv", "method": "put" }))
+        .await
+        .unwrap();
+    assert_eq!(out["method"], "PUT");
+    assert_eq!(out["expires_secs"], 600);
+    let bad = presign
+        .call(json!({ "bucket": "reports", "key": "x", "method": "DELETE" }))
+        .await;
+    assert!(matches!(bad, Err(ToolError::InvalidArguments(_))));
+}
@@ -0,0 +1,196 @@
+//! Tests for structured agent results published on `result.<agent_id>`
+
+use async_trait::async_trait;
+use loom_core::agent::{AgentBehavior, AgentRuntime};
+use loom_core::cognitive::{
+    result_keys, result_topic, CognitiveAgent, CognitiveLoop, ExecutionResult, MemoryBuffer,
+    Perception, Plan, StructuredOutput, RESULT_ACTION, RESULT_EVENT,
+};
+use loom_core::proto::{AgentConfig, AgentState, Event, QoSLevel};
+use loom_core::{EventBus, ModelRouter, Result, ToolRegistry};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct TradePlan {
+    symbol: String,
+    action: String,
+    size_usdt: f64,
+}
+
+/// Answers in text and attaches the plan as a structured result
+struct PlannerLoop {
+    memory: MemoryBuffer,
+}
+
+#[async_trait]
+impl CognitiveLoop for PlannerLoop {
+    async fn perceive(&mut self, event: Event, _state: &AgentState) -> Result<Perception> {
+        Ok(Perception::from_event(event))
+    }
+
+    async fn think(&mut self, perception: &Perception) -> Result<Plan> {
+        Ok(Plan::final_answer(
+            perception.goal.clone().unwrap_or_default(),
+            "Buying 100 USDT of BTC",
+        ))
+    }
+
+    async fn act(&mut self, plan: &Plan, _state: &mut AgentState) -> Result<ExecutionResult> {
+        let trade = TradePlan {
+            symbol: "BTCUSDT".to_string(),
+            action: "buy".to_string(),
+            size_usdt: 100.0,
+        };
+        Ok(
+            ExecutionResult::with_response(plan.final_answer.clone().unwrap_or_default())
+                .with_structured(
+                    StructuredOutput::from_value("trade.plan", &trade)?
+                        .with_schema("loom://schemas/trade.plan/v1"),
+                ),
+        )
+    }
+
+    fn memory_buffer(&self) -> &MemoryBuffer {
+        &self.memory
+    }
+
+    fn memory_buffer_mut(&mut self) -> &mut MemoryBuffer {
+        &mut self.memory
+    }
+}
+
+fn planner() -> CognitiveAgent<PlannerLoop> {
+    CognitiveAgent::new(PlannerLoop {
+        memory: MemoryBuffer::new(10),
+    })
+}
+
+fn request(id: &str, metadata: &[(&str, &str)]) -> Event {
+    Event {
+        id: id.to_string(),
+        r#type: "user.message".to_string(),
+        timestamp_ms: 0,
+        source: "test".to_string(),
+        metadata: metadata
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect(),
+        payload: b"buy some BTC".to_vec(),
+        confidence: 1.0,
+        tags: vec![],
+        priority: 50,
+    }
+}
+
+#[test]
+fn outputs_round_trip_through_result_events() {
+    let output = StructuredOutput::new("score", json!({"value": 0.9})).with_schema("score/v1");
+    let metadata = output.metadata();
+    assert_eq!(metadata[result_keys::KIND], "score");
+    assert_eq!(metadata[result_keys::SCHEMA], "score/v1");
+    assert_eq!(metadata[result_keys::CONTENT_TYPE], "application/json");
+
+    let event = Event {
+        id: "r1".to_string(),
+        r#type: RESULT_EVENT.to_string(),
+        timestamp_ms: 0,
+        source: "agent.scorer".to_string(),
+        metadata,
+        payload: serde_json::to_vec(&output.payload).unwrap(),
+        confidence: 1.0,
+        tags: vec![],
+        priority: 0,
+    };
+    assert_eq!(StructuredOutput::from_event(&event), Some(output));
+
+    let mut text = event.clone();
+    text.r#type = "agent.response".to_string();
+    assert_eq!(StructuredOutput::from_event(&text), None);
+    assert_eq!(result_topic("scorer"), "result.scorer");
+}
+
+#[tokio::test]
+async fn adapter_emits_result_actions_beside_the_reply() -> Result<()> {
+    let mut agent = planner();
+    let mut state = AgentState {
+        agent_id: "planner".to_string(),
+        persistent_state: vec![],
+        ephemeral_context: vec![],
+        last_update_ms: 0,
+        metadata: Default::default(),
+    };
+
+    let actions = agent
+        .on_event(
+            request("e1", &[("expect_reply", "true"), ("correlation_id", "c-1")]),
+            &mut state,
+        )
+        .await?;
+    let kinds: Vec<&str> = actions.iter().map(|a| a.action_type.as_str()).collect();
+    assert_eq!(kinds, vec!["agent.reply", RESULT_ACTION]);
+
+    let result = &actions[1];
+    assert_eq!(result.parameters[result_keys::KIND], "trade.plan");
+    assert_eq!(
+        result.parameters[result_keys::SCHEMA],
+        "loom://schemas/trade.plan/v1"
+    );
+    assert_eq!(result.parameters["correlation_id"], "c-1");
+    assert_eq!(result.parameters["caused_by"], "e1");
+    let payload: serde_json::Value = serde_json::from_slice(&result.payload).unwrap();
+    assert_eq!(payload["symbol"], "BTCUSDT");
+    Ok(())
+}
+
+#[tokio::test]
+async fn downstream_agents_receive_typed_results() -> Result<()> {
+    let bus = Arc::new(EventBus::new().await?);
+    bus.start().await?;
+    let runtime = AgentRuntime::new(
+        Arc::clone(&bus),
+        Arc::new(ToolRegistry::new()),
+        ModelRouter::new().await?,
+    )
+    .await?;
+    runtime
+        .create_agent(
+            AgentConfig {
+                agent_id: "planner".to_string(),
+                agent_type: "cognitive".to_string(),
+                subscribed_topics: vec!["planner.requests".to_string()],
+                capabilities: vec![],
+                parameters: HashMap::new(),
+            },
+            Box::new(planner()),
+        )
+        .await?;
+
+    let (_id, mut results) = bus
+        .subscribe(result_topic("planner"), vec![], QoSLevel::QosBatched)
+        .await?;
+    bus.publish("planner.requests", request("e2", &[])).await?;
+
+    let event = tokio::time::timeout(Duration::from_secs(2), results.recv())
+        .await
+        .expect("no structured result")
+        .unwrap();
+    assert_eq!(event.r#type, RESULT_EVENT);
+    assert_eq!(event.source, "agent.planner");
+    assert_eq!(event.metadata["caused_by"], "e2");
+
+    let output = StructuredOutput::from_event(&event).unwrap();
+    assert_eq!(output.kind, "trade.plan");
+    assert_eq!(
+        output.parse::<TradePlan>()?,
+        TradePlan {
+            symbol: "BTCUSDT".to_string(),
+            action: "buy".to_string(),
+            size_usdt: 100.0,
+        }
+    );
+    Ok(())
+}
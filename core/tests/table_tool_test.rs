@@ -0,0 +1,338 @@
+//! Tests for the `table:query` tool: CSV/TSV/Parquet loading, filters, aggregates, sorting, limits
+
+use loom_core::blob::{BlobAccess, BlobStore, DiskBlobBackend};
+use loom_core::tools::native::TableQueryTool;
+use loom_core::tools::{with_caller, ToolError};
+use loom_core::Tool;
+use serde_json::{json, Value};
+use std::sync::Arc;
+
+const SALES: &str = "\
+region,product,units,price,returned
+EMEA,widget,10,2.5,false
+APAC,widget,4,2.5,true
+EMEA,gadget,3,10,false
+AMER,widget,7,2.5,false
+EMEA,widget,1,,true
+APAC,gizmo,12,1.25,false
+";
+
+fn setup() -> (tempfile::TempDir, Arc<BlobStore>, TableQueryTool) {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::create_dir(dir.path().join("data")).unwrap();
+    std::fs::write(dir.path().join("data/sales.csv"), SALES).unwrap();
+    let blobs = Arc::new(BlobStore::new(Arc::new(DiskBlobBackend::new(
+        dir.path().join("blobs"),
+    ))));
+    let tool = TableQueryTool::new(dir.path().to_path_buf(), Some(Arc::clone(&blobs)));
+    (dir, blobs, tool)
+}
+
+fn query(arguments: Value) -> Value {
+    let mut arguments = arguments;
+    arguments["path"] = json!("data/sales.csv");
+    arguments
+}
+
+#[tokio::test]
+async fn returns_rows_with_the_inferred_schema() {
+    let (_dir, _blobs, tool) = setup();
+    let out = tool.call(query(json!({}))).await.unwrap();
+    assert_eq!(
+        out["schema"],
+        json!([
+            { "name": "region", "type": "string" },
+            { "name": "product", "type": "string" },
+            { "name": "units", "type": "integer" },
+            { "name": "price", "type": "float" },
+            { "name": "returned", "type": "boolean" }
+        ])
+    );
+    assert_eq!(out["total_rows"], 6);
+    assert_eq!(out["matched_rows"], 6);
+    assert_eq!(out["truncated"], false);
+    assert_eq!(out["rows"][0], json!(["EMEA", "widget", 10, 2.5, false]));
+    assert_eq!(out["rows"][4][3], Value::Null);
+}
+
+#[tokio::test]
+async fn filters_selects_sorts_and_pages() {
+    let (_dir, _blobs, tool) = setup();
+    let out = tool
+        .call(query(json!({
+            "filter": [
+                { "column": "product", "value": "widget" },
+                { "column": "units", "op": "gte", "value": "4" },
+                { "column": "returned", "op": "ne", "value": true }
+            ],
+            "select": ["region", "units"],
+            "sort": [{ "column": "units", "desc": true }]
+        })))
+        .await
+        .unwrap();
+    assert_eq!(
+        out["columns"],
+        json!([{ "name": "region", "type": "string" }, { "name": "units", "type": "integer" }])
+    );
+    assert_eq!(out["rows"], json!([["EMEA", 10], ["AMER", 7]]));
+
+    let out = tool
+        .call(query(json!({
+            "filter": { "column": "region", "op": "in", "value": ["APAC", "AMER"] },
+            "sort": [{ "column": "price" }, { "column": "units" }],
+            "select": "units",
+            "offset": 1,
+            "limit": 1
+        })))
+        .await
+        .unwrap();
+    assert_eq!(out["rows"], json!([[4]]));
+    assert_eq!(out["matched_rows"], 3);
+    assert_eq!(out["truncated"], true);
+
+    // Nulls sort last and never match comparisons
+    let out = tool
+        .call(query(json!({
+            "filter": [{ "column": "price", "op": "lt", "value": 100 }],
+            "select": ["price"],
+            "sort": [{ "column": "price" }]
+        })))
+        .await
+        .unwrap();
+    assert_eq!(out["matched_rows"], 5);
+    let out = tool
+        .call(query(
+            json!({ "select": ["price"], "sort": [{ "column": "price" }] }),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(out["rows"][5], json!([null]));
+}
+
+#[tokio::test]
+async fn groups_and_aggregates() {
+    let (_dir, _blobs, tool) = setup();
+    let out = tool
+        .call(query(json!({
+            "group_by": ["region"],
+            "aggregates": [
+                { "fn": "count" },
+                { "fn": "sum", "column": "units", "as": "units" },
+                { "fn": "avg", "column": "price" },
+                { "fn": "count_distinct", "column": "product" },
+                { "fn": "max", "column": "product" }
+            ],
+            "sort": [{ "column": "units", "desc": true }]
+        })))
+        .await
+        .unwrap();
+    let names: Vec<&str> = out["columns"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|c| c["name"].as_str().unwrap())
+        .collect();
+    assert_eq!(
+        names,
+        [
+            "region",
+            "count",
+            "units",
+            "avg_price",
+            "count_distinct_product",
+            "max_product"
+        ]
+    );
+    assert_eq!(
+        out["rows"],
+        json!([
+            ["APAC", 2, 16, 1.875, 2, "widget"],
+            ["EMEA", 3, 14, 6.25, 2, "widget"],
+            ["AMER", 1, 7, 2.5, 1, "widget"]
+        ])
+    );
+
+    // Without group_by the aggregates cover every matching row
+    let out = tool
+        .call(query(json!({
+            "filter": [{ "column": "region", "value": "nowhere" }],
+            "aggregates": [{ "fn": "count" }, { "fn": "sum", "column": "units" }]
+        })))
+        .await
+        .unwrap();
+    assert_eq!(out["rows"], json!([[0, null]]));
+}
+
+#[tokio::test]
+async fn reads_tsv_blobs_the_caller_may_read() {
+    let (_dir, blobs, tool) = setup();
+    let tsv = "name\tscore\nada\t9\nbob\t7\n";
+    let blob = blobs
+        .put(
+            tsv.as_bytes().to_vec(),
+            "text/tab-separated-values",
+            BlobAccess::owned_by("analyst").with_readers(["reviewer"]),
+        )
+        .await
+        .unwrap();
+    let arguments = json!({
+        "blob_ref": blob.id,
+        "aggregates": [{ "fn": "min", "column": "score" }]
+    });
+    let out = with_caller("reviewer", tool.call(arguments.clone()))
+        .await
+        .unwrap();
+    assert_eq!(out["rows"], json!([[7]]));
+    assert!(matches!(
+        with_caller("stranger", tool.call(arguments)).await,
+        Err(ToolError::PermissionDenied(_))
+    ));
+}
+
+#[tokio::test]
+async fn rejects_bad_queries_and_files() {
+    let (dir, _blobs, tool) = setup();
+    std::fs::write(dir.path().join("data/big.csv"), "a\n".repeat(64)).unwrap();
+    std::fs::write(dir.path().join("data/sales.parquet"), b"PAR1").unwrap();
+    let small = TableQueryTool::new(dir.path().to_path_buf(), None).with_max_bytes(32);
+    assert!(matches!(
+        small.call(json!({ "path": "data/big.csv" })).await,
+        Err(ToolError::InvalidArguments(_))
+    ));
+    assert!(matches!(
+        tool.call(json!({ "path": "../outside.csv" })).await,
+        Err(ToolError::PermissionDenied(_))
+    ));
+    assert!(matches!(
+        tool.call(json!({ "path": "data/missing.csv" })).await,
+        Err(ToolError::NotFound(_))
+    ));
+
+    let cases = [
+        json!({ "path": "data/sales.parquet" }),
+        query(json!({ "filter": [{ "column": "nope", "value": 1 }] })),
+        query(json!({ "filter": [{ "column": "units", "op": "like", "value": 1 }] })),
+        query(json!({ "aggregates": [{ "fn": "sum", "column": "region" }] })),
+        query(json!({ "aggregates": [{ "fn": "median", "column": "units" }] })),
+        query(json!({ "group_by": "region", "select": ["units"] })),
+        query(json!({ "group_by": "region", "sort": [{ "column": "units" }] })),
+        query(json!({ "limit": 5000 })),
+        json!({}),
+    ];
+    for args in cases {
+        let result = tool.call(args.clone()).await;
+        assert!(
+            matches!(result, Err(ToolError::InvalidArguments(_))),
+            "{args} gave {result:?}"
+        );
+    }
+}
+
+/// The sales table written as snappy-compressed Parquet
+#[cfg(feature = "parquet")]
+fn write_sales_parquet(path: &std::path::Path) {
+    use parquet::basic::Compression;
+    use parquet::data_type::{BoolType, ByteArray, ByteArrayType, DoubleType, Int64Type};
+    use parquet::file::properties::WriterProperties;
+    use parquet::file::writer::SerializedFileWriter;
+    use parquet::schema::parser::parse_message_type;
+
+    let schema = parse_message_type(
+        "message sales {
+            required binary region (UTF8);
+            required binary product (UTF8);
+            required int64 units;
+            optional double price;
+            required boolean returned;
+        }",
+    )
+    .unwrap();
+    let props = WriterProperties::builder()
+        .set_compression(Compression::SNAPPY)
+        .build();
+    let file = std::fs::File::create(path).unwrap();
+    let mut writer = SerializedFileWriter::new(file, Arc::new(schema), Arc::new(props)).unwrap();
+    let mut group = writer.next_row_group().unwrap();
+    let text = |values: &[&str]| {
+        values
+            .iter()
+            .map(|v| ByteArray::from(*v))
+            .collect::<Vec<_>>()
+    };
+
+    let mut column = group.next_column().unwrap().unwrap();
+    column
+        .typed::<ByteArrayType>()
+        .write_batch(
+            &text(&["EMEA", "APAC", "EMEA", "AMER", "EMEA", "APAC"]),
+            None,
+            None,
+        )
+        .unwrap();
+    column.close().unwrap();
+    let mut column = group.next_column().unwrap().unwrap();
+    column
+        .typed::<ByteArrayType>()
+        .write_batch(
+            &text(&["widget", "widget", "gadget", "widget", "widget", "gizmo"]),
+            None,
+            None,
+        )
+        .unwrap();
+    column.close().unwrap();
+    let mut column = group.next_column().unwrap().unwrap();
+    column
+        .typed::<Int64Type>()
+        .write_batch(&[10, 4, 3, 7, 1, 12], None, None)
+        .unwrap();
+    column.close().unwrap();
+    let mut column = group.next_column().unwrap().unwrap();
+    column
+        .typed::<DoubleType>()
+        .write_batch(
+            &[2.5, 2.5, 10.0, 2.5, 1.25],
+            Some(&[1, 1, 1, 1, 0, 1]),
+            None,
+        )
+        .unwrap();
+    column.close().unwrap();
+    let mut column = group.next_column().unwrap().unwrap();
+    column
+        .typed::<BoolType>()
+        .write_batch(&[false, true, false, false, true, false], None, None)
+        .unwrap();
+    column.close().unwrap();
+
+    group.close().unwrap();
+    writer.close().unwrap();
+}
+
+#[cfg(feature = "parquet")]
+#[tokio::test]
+async fn queries_parquet_files() {
+    let (dir, _blobs, tool) = setup();
+    write_sales_parquet(&dir.path().join("data/sales.parquet"));
+
+    let out = tool
+        .call(json!({ "path": "data/sales.parquet", "limit": 1 }))
+        .await
+        .unwrap();
+    let csv = tool.call(query(json!({ "limit": 1 }))).await.unwrap();
+    assert_eq!(out["schema"], csv["schema"]);
+    assert_eq!(out["total_rows"], 6);
+    assert_eq!(out["rows"], json!([["EMEA", "widget", 10, 2.5, false]]));
+
+    let out = tool
+        .call(json!({
+            "path": "data/sales.parquet",
+            "group_by": "region",
+            "aggregates": [{ "fn": "sum", "column": "units" }, { "fn": "count", "column": "price" }],
+            "sort": [{ "column": "region" }]
+        }))
+        .await
+        .unwrap();
+    assert_eq!(
+        out["rows"],
+        json!([["AMER", 7, 1], ["APAC", 16, 2], ["EMEA", 14, 2]])
+    );
+}
@@ -0,0 +1,357 @@
+/// Tests for TaskQueue priorities, leases, retries, dead tasks, persistence and workers
+use async_trait::async_trait;
+use loom_core::messaging::envelope::keys;
+use loom_core::messaging::reliable::dead_letter_keys;
+use loom_core::proto::{Action, AgentConfig, AgentState, Event, QoSLevel};
+use loom_core::task_queue::{dead_reasons, task_keys};
+use loom_core::{
+    AgentBehavior, CancellationToken, EventBus, LoomError, Result, TaskQueue, TaskQueueConfig,
+    TaskWorker,
+};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+fn make_task(id: &str, priority: i32) -> Event {
+    Event {
+        id: id.to_string(),
+        r#type: "job".to_string(),
+        timestamp_ms: 0,
+        source: "test".to_string(),
+        metadata: HashMap::new(),
+        payload: id.as_bytes().to_vec(),
+        confidence: 1.0,
+        tags: vec![],
+        priority,
+    }
+}
+
+fn short_leases(max_attempts: u32) -> TaskQueueConfig {
+    TaskQueueConfig::default()
+        .with_visibility_timeout(Duration::from_millis(50))
+        .with_max_attempts(max_attempts)
+}
+
+#[tokio::test]
+async fn tasks_are_leased_by_priority_then_enqueue_order() -> Result<()> {
+    let queue = TaskQueue::new("jobs", TaskQueueConfig::default());
+    queue.enqueue(make_task("low", 10)).await?;
+    queue.enqueue(make_task("high-1", 90)).await?;
+    queue.enqueue(make_task("high-2", 90)).await?;
+
+    let mut order = Vec::new();
+    while let Some(lease) = queue.lease("w").await? {
+        order.push(lease.task.event.id.clone());
+        assert_eq!(lease.attempt(), 1);
+        assert_eq!(
+            lease.task.event.metadata.get(task_keys::QUEUE).unwrap(),
+            "jobs"
+        );
+        assert_eq!(
+            lease.task.event.metadata.get(task_keys::ATTEMPT).unwrap(),
+            "1"
+        );
+        assert!(queue.ack(&lease).await?);
+    }
+    assert_eq!(order, vec!["high-1", "high-2", "low"]);
+
+    let stats = queue.stats();
+    assert_eq!(stats.enqueued, 3);
+    assert_eq!(stats.acked, 3);
+    assert_eq!((stats.ready, stats.leased, stats.dead), (0, 0, 0));
+    Ok(())
+}
+
+#[tokio::test]
+async fn leased_tasks_are_invisible_until_the_lease_expires() -> Result<()> {
+    let queue = TaskQueue::new("jobs", short_leases(5));
+    queue.enqueue(make_task("t1", 50)).await?;
+
+    let first = queue.lease("w1").await?.unwrap();
+    assert!(queue.lease("w2").await?.is_none());
+    assert_eq!(queue.stats().leased, 1);
+
+    tokio::time::sleep(Duration::from_millis(80)).await;
+    let second = queue.lease("w2").await?.unwrap();
+    assert_eq!(second.task.id, first.task.id);
+    assert_eq!(second.attempt(), 2);
+    assert_eq!(second.worker, "w2");
+
+    // The first lease is stale now
+    assert!(!queue.ack(&first).await?);
+    assert!(queue.ack(&second).await?);
+    assert_eq!(queue.stats().lease_expired, 1);
+    Ok(())
+}
+
+#[tokio::test]
+async fn extended_leases_stay_invisible() -> Result<()> {
+    let queue = TaskQueue::new("jobs", short_leases(5));
+    queue.enqueue(make_task("t1", 50)).await?;
+
+    let mut lease = queue.lease("w1").await?.unwrap();
+    for _ in 0..4 {
+        tokio::time::sleep(Duration::from_millis(30)).await;
+        assert!(queue.extend(&mut lease));
+    }
+    assert!(queue.lease("w2").await?.is_none());
+    assert!(queue.ack(&lease).await?);
+    assert!(!queue.extend(&mut lease));
+    Ok(())
+}
+
+#[tokio::test]
+async fn failed_tasks_are_retried_then_dead_lettered() -> Result<()> {
+    let bus = Arc::new(EventBus::new().await?);
+    let (_dlq, mut dead_letters) = bus
+        .subscribe("dead_letter.task.jobs".into(), vec![], QoSLevel::QosBatched)
+        .await?;
+    let queue = TaskQueue::new("jobs", short_leases(2)).with_event_bus(Arc::clone(&bus));
+    let id = queue.enqueue(make_task("t1", 50)).await?;
+
+    let first = queue.lease("w").await?.unwrap();
+    assert!(queue.fail(&first, "boom").await?);
+    assert_eq!(queue.stats().ready, 1);
+    let second = queue.lease("w").await?.unwrap();
+    assert_eq!(second.task.last_error.as_deref(), Some("boom"));
+    assert!(queue.fail(&second, "boom again").await?);
+    assert!(queue.lease("w").await?.is_none());
+
+    let dead = queue.dead_tasks();
+    assert_eq!(dead.len(), 1);
+    assert_eq!(dead[0].task.id, id);
+    assert_eq!(dead[0].reason, dead_reasons::FAILED);
+
+    let letter = tokio::time::timeout(Duration::from_secs(1), dead_letters.recv())
+        .await
+        .expect("no dead letter")
+        .unwrap();
+    assert_eq!(letter.id, "t1");
+    assert_eq!(
+        letter.metadata.get(dead_letter_keys::REASON).unwrap(),
+        dead_reasons::FAILED
+    );
+    assert_eq!(
+        letter.metadata.get(dead_letter_keys::ATTEMPTS).unwrap(),
+        "2"
+    );
+    assert_eq!(
+        letter.metadata.get(dead_letter_keys::TOPIC).unwrap(),
+        "task.jobs"
+    );
+    assert_eq!(letter.metadata.get(task_keys::ERROR).unwrap(), "boom again");
+
+    let stats = queue.stats();
+    assert_eq!((stats.failed, stats.died, stats.dead), (2, 1, 1));
+
+    // Requeueing a dead task gives it a fresh set of attempts
+    assert!(queue.requeue_dead(&id).await?);
+    let again = queue.lease("w").await?.unwrap();
+    assert_eq!(again.attempt(), 1);
+    assert!(queue.ack(&again).await?);
+    assert!(!queue.requeue_dead(&id).await?);
+    Ok(())
+}
+
+#[tokio::test]
+async fn expired_leases_use_up_attempts() -> Result<()> {
+    let queue = TaskQueue::new("jobs", short_leases(1));
+    queue.enqueue(make_task("t1", 50)).await?;
+    let _abandoned = queue.lease("w").await?.unwrap();
+
+    tokio::time::sleep(Duration::from_millis(80)).await;
+    assert_eq!(queue.sweep().await?, 1);
+    let dead = queue.dead_tasks();
+    assert_eq!(dead.len(), 1);
+    assert_eq!(dead[0].reason, dead_reasons::VISIBILITY_TIMEOUT);
+    assert_eq!(queue.purge_dead()?, 1);
+    assert_eq!(queue.stats().dead, 0);
+    Ok(())
+}
+
+#[tokio::test]
+async fn tasks_past_their_deadline_are_not_leased() -> Result<()> {
+    let queue = TaskQueue::new("jobs", TaskQueueConfig::default());
+    let mut overdue = make_task("overdue", 90);
+    overdue.metadata.insert(
+        keys::DEADLINE_MS.into(),
+        (chrono::Utc::now().timestamp_millis() - 1_000).to_string(),
+    );
+    let mut timely = make_task("timely", 10);
+    timely.metadata.insert(
+        keys::DEADLINE_MS.into(),
+        (chrono::Utc::now().timestamp_millis() + 60_000).to_string(),
+    );
+    queue.enqueue(overdue).await?;
+    queue.enqueue(timely).await?;
+
+    let lease = queue.lease("w").await?.unwrap();
+    assert_eq!(lease.task.event.id, "timely");
+    assert!(lease.task.deadline_ms.is_some());
+    let dead = queue.dead_tasks();
+    assert_eq!(dead.len(), 1);
+    assert_eq!(dead[0].reason, dead_reasons::DEADLINE_EXPIRED);
+    Ok(())
+}
+
+#[tokio::test]
+async fn lease_wait_wakes_on_enqueue() -> Result<()> {
+    let queue = Arc::new(TaskQueue::new("jobs", TaskQueueConfig::default()));
+    let waiter = {
+        let queue = Arc::clone(&queue);
+        tokio::spawn(async move { queue.lease_wait("w", Duration::from_secs(2)).await })
+    };
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    queue.enqueue(make_task("t1", 50)).await?;
+
+    let lease = tokio::time::timeout(Duration::from_millis(500), waiter)
+        .await
+        .expect("waiter not woken")
+        .unwrap()?
+        .unwrap();
+    assert_eq!(lease.task.event.id, "t1");
+    assert!(queue
+        .lease_wait("w", Duration::from_millis(20))
+        .await?
+        .is_none());
+    Ok(())
+}
+
+#[tokio::test]
+async fn persistent_queue_survives_restart() -> Result<()> {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("tasks");
+    let (acked_id, leased_id, dead_id) = {
+        let queue = TaskQueue::with_persistence("jobs", short_leases(1), &path)?;
+        let acked = queue.enqueue(make_task("acked", 90)).await?;
+        let leased = queue.enqueue(make_task("leased", 50)).await?;
+        let dead = queue.enqueue(make_task("dead", 10)).await?;
+        let lease = queue.lease("w").await?.unwrap();
+        queue.ack(&lease).await?;
+        let _in_flight = queue.lease("w").await?.unwrap();
+        let doomed = queue.lease("w").await?.unwrap();
+        queue.fail(&doomed, "boom").await?;
+        (acked, leased, dead)
+    };
+
+    let queue = TaskQueue::with_persistence("jobs", short_leases(1), &path)?;
+    let stats = queue.stats();
+    assert_eq!((stats.ready, stats.leased, stats.dead), (1, 0, 1));
+    let dead = queue.dead_tasks();
+    assert_eq!(dead[0].task.id, dead_id);
+    assert_eq!(dead[0].task.last_error.as_deref(), Some("boom"));
+
+    // The task leased at shutdown is back, with its attempt still counted
+    let lease = queue.lease("w").await?.unwrap();
+    assert_eq!(lease.task.id, leased_id);
+    assert_eq!(lease.task.event.payload, b"leased".to_vec());
+    assert_eq!(lease.attempt(), 2);
+    assert_ne!(lease.task.id, acked_id);
+
+    // New ids do not collide with reloaded ones
+    let fresh = queue.enqueue(make_task("fresh", 50)).await?;
+    assert!(![acked_id, leased_id, dead_id].contains(&fresh));
+    Ok(())
+}
+
+/// Fails events whose payload is "bad"; counts what it handled
+struct Worker {
+    handled: Arc<AtomicUsize>,
+}
+
+#[async_trait]
+impl AgentBehavior for Worker {
+    async fn on_event(&mut self, event: Event, _state: &mut AgentState) -> Result<Vec<Action>> {
+        self.handled.fetch_add(1, Ordering::SeqCst);
+        if event.payload == b"bad" {
+            return Err(LoomError::AgentError("bad task".into()));
+        }
+        Ok(vec![Action {
+            action_type: "job.done".into(),
+            parameters: HashMap::new(),
+            payload: event.payload,
+            priority: 0,
+        }])
+    }
+
+    async fn on_init(&mut self, _config: &AgentConfig) -> Result<()> {
+        Ok(())
+    }
+
+    async fn on_shutdown(&mut self) -> Result<()> {
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn worker_pool_drains_the_queue() -> Result<()> {
+    let queue = Arc::new(TaskQueue::new("jobs", short_leases(1)));
+    for i in 0..6 {
+        queue.enqueue(make_task(&format!("t{i}"), 50)).await?;
+    }
+    queue.enqueue(make_task("bad", 50)).await?;
+
+    let handled = Arc::new(AtomicUsize::new(0));
+    let cancel = CancellationToken::new();
+    let workers = TaskWorker::spawn_pool(
+        Arc::clone(&queue),
+        "worker",
+        3,
+        |_| Worker {
+            handled: Arc::clone(&handled),
+        },
+        cancel.clone(),
+    );
+
+    for _ in 0..100 {
+        if queue.depth() == 0 {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+    cancel.cancel();
+    let mut acked = 0;
+    let mut failed = 0;
+    for worker in workers {
+        let report = worker.await.unwrap()?;
+        acked += report.acked;
+        failed += report.failed;
+    }
+    assert_eq!((acked, failed), (6, 1));
+    assert_eq!(handled.load(Ordering::SeqCst), 7);
+    assert_eq!(queue.dead_tasks().len(), 1);
+    Ok(())
+}
+
+#[tokio::test]
+async fn worker_publishes_actions_as_results() -> Result<()> {
+    let bus = Arc::new(EventBus::new().await?);
+    let (_sub, mut results) = bus
+        .subscribe("jobs.results".into(), vec![], QoSLevel::QosBatched)
+        .await?;
+    let queue = Arc::new(TaskQueue::new("jobs", TaskQueueConfig::default()));
+    let id = queue.enqueue(make_task("t1", 50)).await?;
+
+    let cancel = CancellationToken::new();
+    let worker = TaskWorker::new(
+        Arc::clone(&queue),
+        "solo",
+        Worker {
+            handled: Arc::new(AtomicUsize::new(0)),
+        },
+    )
+    .with_results(Arc::clone(&bus), "jobs.results")
+    .with_poll_interval(Duration::from_millis(20));
+    let handle = tokio::spawn(worker.run(cancel.clone()));
+
+    let result = tokio::time::timeout(Duration::from_secs(1), results.recv())
+        .await
+        .expect("no result")
+        .unwrap();
+    assert_eq!(result.r#type, "job.done");
+    assert_eq!(result.metadata.get(task_keys::ID).unwrap(), &id);
+    cancel.cancel();
+    assert_eq!(handle.await.unwrap()?.acked, 1);
+    Ok(())
+}
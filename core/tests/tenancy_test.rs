@@ -0,0 +1,143 @@
+/// Tests for tenancy: topic namespaces, EventBus isolation, credentials and quotas
+use loom_core::tenancy::{namespace_topic, strip_namespace, topic_tenant, TENANT_KEY};
+use loom_core::{Event, EventBus, LoomError, QoSLevel, TenantConfig, TenantQuotas, TenantRegistry};
+use std::collections::HashMap;
+use std::time::Duration;
+
+fn event(tenant: Option<&str>) -> Event {
+    let mut metadata = HashMap::new();
+    if let Some(tenant) = tenant {
+        metadata.insert(TENANT_KEY.to_string(), tenant.to_string());
+    }
+    Event {
+        id: "evt".into(),
+        r#type: "test".into(),
+        timestamp_ms: 0,
+        source: "test".into(),
+        metadata,
+        payload: vec![],
+        confidence: 1.0,
+        tags: vec![],
+        priority: 50,
+    }
+}
+
+#[test]
+fn namespace_helpers_roundtrip() {
+    let topic = namespace_topic("acme", "chat.requests");
+    assert_eq!(topic, "tenant.acme.chat.requests");
+    assert_eq!(topic_tenant(&topic), Some("acme"));
+    assert_eq!(strip_namespace("acme", &topic), Some("chat.requests"));
+    assert_eq!(strip_namespace("acmecorp", &topic), None);
+    assert_eq!(topic_tenant("chat.requests"), None);
+    assert_eq!(topic_tenant("tenant.acme"), None);
+}
+
+#[tokio::test]
+async fn event_bus_rejects_cross_tenant_publish() {
+    let bus = EventBus::new().await.unwrap();
+    bus.start().await.unwrap();
+    let (_sid, mut rx) = bus
+        .subscribe("tenant.acme.chat".into(), vec![], QoSLevel::QosBatched)
+        .await
+        .unwrap();
+
+    let err = bus
+        .publish("tenant.acme.chat", event(Some("globex")))
+        .await
+        .unwrap_err();
+    assert!(matches!(err, LoomError::TenantError(_)));
+    assert!(bus.publish("chat", event(Some("acme"))).await.is_err());
+
+    // Own namespace and untenanted publishers are allowed
+    bus.publish("tenant.acme.chat", event(Some("acme")))
+        .await
+        .unwrap();
+    bus.publish("tenant.acme.chat", event(None)).await.unwrap();
+    for _ in 0..2 {
+        tokio::time::timeout(Duration::from_secs(1), rx.recv())
+            .await
+            .unwrap()
+            .unwrap();
+    }
+}
+
+#[test]
+fn registry_authenticates_and_enforces_quotas() {
+    let tenants = TenantRegistry::from_toml(
+        r#"
+        [[tenants]]
+        id = "acme"
+        tokens = ["acme-secret"]
+        max_agents = 1
+        max_events_per_sec = 2
+        max_llm_tokens_per_min = 100
+
+        [[tenants]]
+        id = "globex"
+        tokens = ["globex-secret"]
+        "#,
+    )
+    .unwrap();
+    assert_eq!(tenants.authenticate("acme-secret").as_deref(), Some("acme"));
+    assert_eq!(tenants.authenticate("nope"), None);
+    assert_eq!(tenants.tenant_ids(), vec!["acme", "globex"]);
+
+    // Agents: re-admitting is a no-op, release frees the slot
+    tenants.admit_agent("acme", "a1").unwrap();
+    tenants.admit_agent("acme", "a1").unwrap();
+    assert!(matches!(
+        tenants.admit_agent("acme", "a2"),
+        Err(LoomError::QuotaExceeded(_))
+    ));
+    tenants.release_agent("acme", "a1");
+    tenants.admit_agent("acme", "a2").unwrap();
+
+    // Events per second
+    tenants.admit_event("acme").unwrap();
+    tenants.admit_event("acme").unwrap();
+    assert!(tenants.admit_event("acme").is_err());
+
+    // LLM tokens per minute
+    tenants.check_llm_budget("acme").unwrap();
+    tenants.record_llm_tokens("acme", 150);
+    assert!(tenants.check_llm_budget("acme").is_err());
+
+    let usage = tenants.usage("acme").unwrap();
+    assert_eq!(usage.agents, 1);
+    assert_eq!(usage.agents_rejected, 1);
+    assert_eq!(usage.events_total, 2);
+    assert_eq!(usage.events_rejected, 1);
+    assert_eq!(usage.llm_tokens_this_minute, 150);
+    assert_eq!(usage.llm_calls_rejected, 1);
+
+    // Tenants without quotas are unlimited
+    for _ in 0..100 {
+        tenants.admit_event("globex").unwrap();
+    }
+    assert!(tenants.admit_event("unknown").is_err());
+}
+
+#[test]
+fn invalid_tenants_are_rejected() {
+    let tenants = TenantRegistry::new();
+    tenants
+        .register(TenantConfig::new("acme").with_token("shared"))
+        .unwrap();
+    assert!(tenants.register(TenantConfig::new("acme")).is_err());
+    assert!(tenants.register(TenantConfig::new("a.b")).is_err());
+    assert!(tenants
+        .register(TenantConfig::new("globex").with_token("shared"))
+        .is_err());
+    assert!(!tenants.contains("globex"));
+
+    let quotas = TenantQuotas {
+        max_agents: Some(3),
+        ..Default::default()
+    };
+    tenants
+        .register(TenantConfig::new("initech").with_quotas(quotas.clone()))
+        .unwrap();
+    assert_eq!(tenants.quotas("initech"), Some(quotas));
+    assert!(TenantRegistry::from_toml("[[tenants]]\ntokens = []\n").is_err());
+}
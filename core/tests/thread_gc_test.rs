@@ -0,0 +1,176 @@
+/// Tests for thread lifecycle: explicit close, idle TTL expiry and `thread.closed` events
+use loom_core::messaging::threads::{close_reasons, thread_id_of, CLOSE_REASON_KEY};
+use loom_core::proto::{Event, QoSLevel};
+use loom_core::{EventBus, EventExt, Result, ThreadTopicKind, THREAD_CLOSED};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::mpsc;
+
+fn make_event(id: &str) -> Event {
+    Event {
+        id: id.to_string(),
+        r#type: "test".to_string(),
+        timestamp_ms: 0,
+        source: "test".to_string(),
+        metadata: HashMap::new(),
+        payload: vec![],
+        confidence: 1.0,
+        tags: vec![],
+        priority: 50,
+    }
+}
+
+async fn recv(rx: &mut mpsc::Receiver<Event>) -> Option<Event> {
+    tokio::time::timeout(Duration::from_secs(1), rx.recv())
+        .await
+        .expect("timed out waiting for delivery")
+}
+
+#[test]
+fn thread_ids_are_parsed_from_thread_topics() {
+    assert_eq!(thread_id_of("thread.task-1.broadcast"), Some("task-1"));
+    assert_eq!(thread_id_of("thread.a.b.reply"), Some("a.b"));
+    assert_eq!(thread_id_of("thread..reply"), None);
+    assert_eq!(thread_id_of("thread.task-1.other"), None);
+    assert_eq!(thread_id_of("orders.reply"), None);
+}
+
+#[tokio::test]
+async fn closing_a_thread_drops_its_subscriptions() -> Result<()> {
+    let bus = Arc::new(EventBus::new().await?);
+    let broadcast = ThreadTopicKind::Broadcast.topic("t1");
+    let reply = ThreadTopicKind::Reply.topic("t1");
+    let (_b, mut broadcast_rx) = bus
+        .subscribe(broadcast.clone(), vec![], QoSLevel::QosBatched)
+        .await?;
+    let (_r, mut reply_rx) = bus
+        .subscribe(reply.clone(), vec![], QoSLevel::QosBatched)
+        .await?;
+    let (_o, mut other_rx) = bus
+        .subscribe("orders".into(), vec![], QoSLevel::QosBatched)
+        .await?;
+    bus.publish(&broadcast, make_event("m1")).await?;
+
+    assert_eq!(bus.close_thread("t1").await?, 2);
+
+    // Queued events are still delivered, followed by the close announcement
+    assert_eq!(recv(&mut broadcast_rx).await.unwrap().id, "m1");
+    let closed = recv(&mut broadcast_rx).await.unwrap();
+    assert_eq!(closed.r#type, THREAD_CLOSED);
+    assert_eq!(closed.thread_id(), Some("t1"));
+    assert_eq!(
+        closed.metadata.get(CLOSE_REASON_KEY).map(String::as_str),
+        Some(close_reasons::CLOSED)
+    );
+    assert!(recv(&mut broadcast_rx).await.is_none());
+    assert!(recv(&mut reply_rx).await.is_none());
+
+    assert_eq!(bus.subscription_count(), 1);
+    assert!(bus.get_stats(&broadcast).is_none());
+    assert!(bus.get_stats(&reply).is_none());
+
+    // Other topics are untouched
+    bus.publish("orders", make_event("o1")).await?;
+    assert_eq!(recv(&mut other_rx).await.unwrap().id, "o1");
+    Ok(())
+}
+
+#[tokio::test]
+async fn idle_threads_expire() -> Result<()> {
+    let bus = Arc::new(EventBus::new().await?);
+    bus.set_thread_ttl(Duration::from_millis(150));
+    assert!(!bus.threads().is_open("orders"));
+
+    let (_idle, mut idle_rx) = bus
+        .subscribe(
+            ThreadTopicKind::Reply.topic("idle"),
+            vec![],
+            QoSLevel::QosBatched,
+        )
+        .await?;
+    let (_busy, mut busy_rx) = bus
+        .subscribe(
+            ThreadTopicKind::Broadcast.topic("busy"),
+            vec![],
+            QoSLevel::QosBatched,
+        )
+        .await?;
+    bus.publish("orders", make_event("o1")).await?;
+    let open: Vec<String> = bus
+        .threads()
+        .open_threads()
+        .into_iter()
+        .map(|t| t.thread_id)
+        .collect();
+    assert_eq!(open, ["busy", "idle"]);
+
+    // Activity keeps a thread alive
+    for i in 0..6 {
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        bus.publish(
+            &ThreadTopicKind::Broadcast.topic("busy"),
+            make_event(&format!("b{i}")),
+        )
+        .await?;
+    }
+    assert!(recv(&mut idle_rx).await.is_none());
+    assert!(!bus.threads().is_open("idle"));
+    assert!(bus.threads().is_open("busy"));
+    for i in 0..6 {
+        assert_eq!(recv(&mut busy_rx).await.unwrap().id, format!("b{i}"));
+    }
+
+    // Once quiet, it expires too and says why
+    let closed = recv(&mut busy_rx).await.unwrap();
+    assert_eq!(closed.r#type, THREAD_CLOSED);
+    assert_eq!(
+        closed.metadata.get(CLOSE_REASON_KEY).map(String::as_str),
+        Some(close_reasons::EXPIRED)
+    );
+    assert!(recv(&mut busy_rx).await.is_none());
+    assert!(bus.threads().open_threads().is_empty());
+    assert_eq!(bus.subscription_count(), 0);
+    Ok(())
+}
+
+#[tokio::test]
+async fn opened_threads_keep_their_own_ttl() -> Result<()> {
+    let bus = Arc::new(EventBus::new().await?);
+    bus.set_thread_ttl(Duration::from_millis(50));
+    bus.open_thread("pinned", None);
+    bus.open_thread("short", Some(Duration::from_millis(50)));
+    let (_sub, mut rx) = bus
+        .subscribe(
+            ThreadTopicKind::Broadcast.topic("pinned"),
+            vec![],
+            QoSLevel::QosBatched,
+        )
+        .await?;
+
+    tokio::time::sleep(Duration::from_millis(250)).await;
+    let open = bus.threads().open_threads();
+    assert_eq!(open.len(), 1);
+    assert_eq!(open[0].thread_id, "pinned");
+    assert_eq!(open[0].ttl, None);
+    assert!(rx.try_recv().is_err());
+
+    bus.close_thread("pinned").await?;
+    assert!(!bus.threads().is_open("pinned"));
+    assert_eq!(recv(&mut rx).await.unwrap().r#type, THREAD_CLOSED);
+    Ok(())
+}
+
+#[tokio::test]
+async fn threads_are_not_tracked_without_a_ttl() -> Result<()> {
+    let bus = Arc::new(EventBus::new().await?);
+    bus.publish(&ThreadTopicKind::Broadcast.topic("t1"), make_event("m1"))
+        .await?;
+    assert!(bus.threads().open_threads().is_empty());
+    // Closing still cleans up
+    assert_eq!(bus.close_thread("t1").await?, 0);
+    assert!(bus
+        .get_stats(&ThreadTopicKind::Broadcast.topic("t1"))
+        .is_none());
+    Ok(())
+}
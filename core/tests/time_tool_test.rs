@@ -0,0 +1,151 @@
+//! Tests for the `time:*` tools and their date/time parser
+
+use chrono::{DateTime, TimeZone};
+use chrono_tz::Tz;
+use loom_core::tools::native::time::{parse_time, parse_timezone};
+use loom_core::tools::native::time_tools;
+use loom_core::{Tool, ToolError};
+use serde_json::json;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+const BERLIN: Tz = chrono_tz::Europe::Berlin;
+
+/// Wednesday 2024-03-06 10:00 in Berlin (CET, before the DST switch on March 31)
+fn reference() -> DateTime<Tz> {
+    BERLIN.with_ymd_and_hms(2024, 3, 6, 10, 0, 0).unwrap()
+}
+
+fn parsed(text: &str) -> Option<String> {
+    parse_time(text, &reference()).map(|dt| dt.to_rfc3339())
+}
+
+fn tools() -> HashMap<String, Arc<dyn Tool>> {
+    time_tools(Tz::UTC)
+        .into_iter()
+        .map(|tool| (tool.name(), tool))
+        .collect()
+}
+
+#[test]
+fn parses_natural_language_relative_to_reference() {
+    let cases = [
+        ("now", "2024-03-06T10:00:00+01:00"),
+        ("tomorrow at 5pm", "2024-03-07T17:00:00+01:00"),
+        ("Tomorrow, 9:30 am", "2024-03-07T09:30:00+01:00"),
+        ("yesterday", "2024-03-05T00:00:00+01:00"),
+        ("noon", "2024-03-06T12:00:00+01:00"),
+        ("18:45", "2024-03-06T18:45:00+01:00"),
+        ("in 3 hours", "2024-03-06T13:00:00+01:00"),
+        ("in an hour", "2024-03-06T11:00:00+01:00"),
+        ("2 days ago", "2024-03-04T10:00:00+01:00"),
+        ("90 minutes from now", "2024-03-06T11:30:00+01:00"),
+        ("wednesday", "2024-03-06T00:00:00+01:00"),
+        ("next wednesday", "2024-03-13T00:00:00+01:00"),
+        ("next friday 3pm", "2024-03-08T15:00:00+01:00"),
+        ("last fri", "2024-03-01T00:00:00+01:00"),
+        ("last week", "2024-02-28T10:00:00+01:00"),
+        // Crosses the DST switch: same wall-clock time, new offset
+        ("next month", "2024-04-06T10:00:00+02:00"),
+        ("in 1 year", "2025-03-06T10:00:00+01:00"),
+    ];
+    for (text, expected) in cases {
+        assert_eq!(parsed(text).as_deref(), Some(expected), "parsing {text:?}");
+    }
+
+    for text in [
+        "",
+        "5",
+        "the day after",
+        "next fortnight",
+        "13pm",
+        "in 3 parsecs",
+    ] {
+        assert_eq!(parsed(text), None, "parsing {text:?}");
+    }
+}
+
+#[test]
+fn parses_absolute_times_and_resolves_dst() {
+    let cases = [
+        ("2024-06-01T12:00:00Z", "2024-06-01T14:00:00+02:00"),
+        ("2024-05-01", "2024-05-01T00:00:00+02:00"),
+        ("2024-05-01 08:15", "2024-05-01T08:15:00+02:00"),
+        ("1 May 2024", "2024-05-01T00:00:00+02:00"),
+        (
+            "Tue, 1 Oct 2024 12:00:00 +0000",
+            "2024-10-01T14:00:00+02:00",
+        ),
+        ("1700000000", "2023-11-14T23:13:20+01:00"),
+        ("1700000000000", "2023-11-14T23:13:20+01:00"),
+        // Skipped by spring-forward: moves past the gap
+        ("2024-03-31 02:30", "2024-03-31T03:30:00+02:00"),
+        // Repeated by fall-back: the earlier instant
+        ("2024-10-27 02:30", "2024-10-27T02:30:00+02:00"),
+    ];
+    for (text, expected) in cases {
+        assert_eq!(parsed(text).as_deref(), Some(expected), "parsing {text:?}");
+    }
+
+    assert_eq!(parse_timezone("utc").unwrap(), Tz::UTC);
+    assert_eq!(
+        parse_timezone("Asia/Tokyo").unwrap(),
+        chrono_tz::Asia::Tokyo
+    );
+    assert!(matches!(
+        parse_timezone("Mars/Olympus"),
+        Err(ToolError::InvalidArguments(_))
+    ));
+}
+
+#[tokio::test]
+async fn tools_convert_diff_and_parse() {
+    let tools = tools();
+    assert_eq!(tools.len(), 4);
+
+    let now = tools["time:now"]
+        .call(json!({ "timezone": "America/New_York" }))
+        .await
+        .unwrap();
+    assert_eq!(now["timezone"], "America/New_York");
+    assert!(now["unix"].as_i64().unwrap() > 1_700_000_000);
+
+    let out = tools["time:convert"]
+        .call(json!({ "time": "2024-07-01 09:00", "from_timezone": "Europe/London", "to_timezone": "Asia/Tokyo" }))
+        .await
+        .unwrap();
+    assert_eq!(out["from"]["iso"], "2024-07-01T09:00:00+01:00");
+    assert_eq!(out["from"]["dst"], true);
+    assert_eq!(out["from"]["abbreviation"], "BST");
+    assert_eq!(out["to"]["iso"], "2024-07-01T17:00:00+09:00");
+    assert_eq!(out["to"]["dst"], false);
+    assert_eq!(out["to"]["weekday"], "Monday");
+
+    let out = tools["time:diff"]
+        .call(json!({ "start": "2024-03-30 12:00", "end": "2024-04-01 13:30:05", "timezone": "Europe/Berlin" }))
+        .await
+        .unwrap();
+    // One hour is lost to the DST switch
+    assert_eq!(out["seconds"], 2 * 86_400 + 1_805);
+    assert_eq!(out["calendar_days"], 2);
+    assert_eq!(out["human"], "2 days, 30 minutes, 5 seconds");
+    assert_eq!(out["end_is_after_start"], true);
+
+    let out = tools["time:parse"]
+        .call(json!({ "text": "next monday 9am", "timezone": "Europe/Berlin", "reference": "2024-03-06T10:00:00+01:00" }))
+        .await
+        .unwrap();
+    assert_eq!(out["iso"], "2024-03-11T09:00:00+01:00");
+    assert_eq!(out["input"], "next monday 9am");
+
+    let err = tools["time:parse"]
+        .call(json!({ "text": "whenever" }))
+        .await
+        .unwrap_err();
+    assert!(matches!(err, ToolError::InvalidArguments(ref m) if m.contains("whenever")));
+    let err = tools["time:convert"]
+        .call(json!({ "to_timezone": "Nowhere/City" }))
+        .await
+        .unwrap_err();
+    assert!(matches!(err, ToolError::InvalidArguments(_)));
+}
@@ -0,0 +1,128 @@
+/// Tests for per-model tokenizers: registry lookup, exact BPE counts, budgets and
+/// model-aware prompt assembly
+use loom_core::context::builder::{ContextBuilder, TriggerInput};
+use loom_core::context::window::tokenizer::DEFAULT_CONTEXT_WINDOW;
+use loom_core::context::window::{
+    BpeCounter, ModelTokenizer, TokenCounter, TokenizerKind, TokenizerRegistry,
+};
+use loom_core::context::{MemoryReader, MemoryWriter, WindowConfig, WindowManager};
+use loom_core::{Event, Result, TokenBudget};
+use std::io::Write;
+use std::sync::Arc;
+
+#[test]
+fn registry_resolves_models_by_longest_prefix() {
+    let tokenizers = TokenizerRegistry::with_defaults();
+
+    assert_eq!(tokenizers.get("gpt-4").kind, TokenizerKind::Cl100k);
+    assert_eq!(tokenizers.context_window("gpt-4-0613"), 8_192);
+    assert_eq!(tokenizers.context_window("gpt-4-turbo-preview"), 128_000);
+    assert_eq!(tokenizers.get("GPT-4o-mini").kind, TokenizerKind::O200k);
+    assert_eq!(tokenizers.context_window("openai/gpt-4o"), 128_000);
+    assert_eq!(
+        tokenizers.context_window("meta-llama/Llama-3.1-8B-Instruct"),
+        131_072
+    );
+    assert_eq!(tokenizers.context_window("llama3:8b"), 8_192);
+
+    let unknown = tokenizers.get("qwen2.5-0.5b-instruct");
+    assert_eq!(unknown.kind, TokenizerKind::Estimate);
+    assert_eq!(unknown.context_window, DEFAULT_CONTEXT_WINDOW);
+}
+
+#[test]
+fn bpe_counters_are_exact() {
+    let cl100k = BpeCounter::cl100k().unwrap();
+    let o200k = BpeCounter::o200k().unwrap();
+    assert_eq!(cl100k.count_text("hello world"), 2);
+    assert_eq!(o200k.count_text("hello world"), 2);
+
+    // Tiny tiktoken-format ranks file: "a", "b" and the merge "ab"
+    let mut ranks = tempfile::NamedTempFile::new().unwrap();
+    writeln!(ranks, "YQ== 0\nYg== 1\nYWI= 2").unwrap();
+    let mut tokenizers = TokenizerRegistry::new();
+    tokenizers
+        .register_llama_bpe("llama-test", ranks.path(), 4_096)
+        .unwrap();
+    let llama = tokenizers.get("llama-test-1b");
+    assert_eq!(llama.kind, TokenizerKind::LlamaBpe);
+    assert_eq!(llama.counter.count_text("abab"), 2);
+    assert_eq!(llama.counter.count_text("aab"), 2);
+
+    let mut broken = tempfile::NamedTempFile::new().unwrap();
+    writeln!(broken, "not-a-rank-line").unwrap();
+    assert!(BpeCounter::from_tiktoken_file(broken.path(), "\\w+").is_err());
+}
+
+#[test]
+fn budgets_follow_the_model_context_window() {
+    let mut tokenizers = TokenizerRegistry::with_defaults();
+    let budget = tokenizers.budget("gpt-4", 1_000);
+    assert_eq!(budget.max_input_tokens, 7_192);
+    assert_eq!(budget.max_output_tokens, 1_000);
+
+    let mut window = WindowManager::for_model(&tokenizers, "gpt-4o", WindowConfig::default());
+    assert_eq!(window.config().max_tokens, 128_000);
+    assert_eq!(window.config().response_reserve, 1_000);
+
+    tokenizers.register(
+        "tiny",
+        ModelTokenizer::builtin(TokenizerKind::Cl100k, 1_024),
+    );
+    window.set_model(&tokenizers, "tiny-model");
+    assert_eq!(window.config().max_tokens, 1_024);
+}
+
+struct Docs(Vec<String>);
+
+#[async_trait::async_trait]
+impl MemoryReader for Docs {
+    async fn retrieve(
+        &self,
+        _query: &str,
+        _k: usize,
+        _filters: Option<serde_json::Value>,
+    ) -> Result<Vec<String>> {
+        Ok(self.0.clone())
+    }
+}
+
+#[async_trait::async_trait]
+impl MemoryWriter for Docs {
+    async fn append_event(&self, _session: &str, _event: Event) -> Result<()> {
+        Ok(())
+    }
+
+    async fn summarize_episode(&self, _session: &str) -> Result<Option<String>> {
+        Ok(None)
+    }
+}
+
+#[tokio::test]
+async fn context_builder_fits_docs_into_the_model_window() {
+    // Each doc is ~500 cl100k tokens
+    let docs = Arc::new(Docs(vec!["hello ".repeat(500); 4]));
+    let mut tokenizers = TokenizerRegistry::new();
+    tokenizers.register(
+        "small",
+        ModelTokenizer::builtin(TokenizerKind::Cl100k, 1_800),
+    );
+    let builder =
+        ContextBuilder::new(Arc::clone(&docs), docs).with_tokenizers(Arc::new(tokenizers));
+
+    let trigger = |model: &str| TriggerInput {
+        session_id: "s1".into(),
+        goal: Some("summarize".into()),
+        tool_hints: vec![],
+        budget: TokenBudget {
+            max_input_tokens: 100_000,
+            max_output_tokens: 512,
+        },
+        model: Some(model.into()),
+    };
+
+    // 1800 - 512 = 1288 input tokens: the header and two docs fit
+    let bundle = builder.build(trigger("small-1")).await.unwrap();
+    assert_eq!(bundle.context_docs.len(), 3);
+    assert_eq!(bundle.context_docs[0], "Retrieved context:");
+}
@@ -0,0 +1,249 @@
+//! Tests for per-agent tool allow/deny lists in `CognitiveConfig` and `SimpleCognitiveLoop`
+
+use async_trait::async_trait;
+use loom_core::cognitive::{
+    CognitiveAgent, CognitiveConfig, CognitiveLoop, Plan, ThoughtStep, ToolCall,
+    ALLOWED_TOOLS_PARAM, DENIED_TOOLS_PARAM,
+};
+use loom_core::proto::{AgentConfig, AgentState, Event};
+use loom_core::tools::ToolResult;
+use loom_core::{AgentBehavior, AgentSpec, LlmClient, SimpleCognitiveLoop, Tool, ToolRegistry};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+fn make_event(payload: &str) -> Event {
+    Event {
+        id: "e1".to_string(),
+        r#type: "test.message".to_string(),
+        timestamp_ms: 0,
+        source: "test".to_string(),
+        metadata: Default::default(),
+        payload: payload.as_bytes().to_vec(),
+        confidence: 1.0,
+        tags: vec![],
+        priority: 50,
+    }
+}
+
+fn make_state() -> AgentState {
+    AgentState {
+        agent_id: "trader".to_string(),
+        persistent_state: vec![],
+        ephemeral_context: vec![],
+        last_update_ms: 0,
+        metadata: Default::default(),
+    }
+}
+
+/// Returns its arguments and counts calls
+struct CountingTool {
+    name: &'static str,
+    calls: AtomicUsize,
+}
+
+#[async_trait]
+impl Tool for CountingTool {
+    fn name(&self) -> String {
+        self.name.to_string()
+    }
+
+    fn description(&self) -> String {
+        "Return the arguments".to_string()
+    }
+
+    fn parameters(&self) -> Value {
+        json!({ "type": "object" })
+    }
+
+    async fn call(&self, arguments: Value) -> ToolResult<Value> {
+        self.calls.fetch_add(1, Ordering::SeqCst);
+        Ok(arguments)
+    }
+}
+
+async fn registry(names: &[&'static str]) -> (Arc<ToolRegistry>, Vec<Arc<CountingTool>>) {
+    let registry = Arc::new(ToolRegistry::new());
+    let mut tools = Vec::new();
+    for &name in names {
+        let tool = Arc::new(CountingTool {
+            name,
+            calls: AtomicUsize::new(0),
+        });
+        registry.register(tool.clone()).await;
+        tools.push(tool);
+    }
+    (registry, tools)
+}
+
+fn make_loop(config: CognitiveConfig, tools: Arc<ToolRegistry>) -> SimpleCognitiveLoop {
+    let config = CognitiveConfig {
+        refine_after_tools: false,
+        ..config
+    };
+    SimpleCognitiveLoop::new(config, Arc::new(LlmClient::from_env().unwrap()), tools)
+}
+
+fn sorted(mut names: Vec<String>) -> Vec<String> {
+    names.sort();
+    names
+}
+
+#[test]
+fn allow_and_deny_patterns_match_tool_names() {
+    let config = CognitiveConfig::default()
+        .with_allowed_tools(["market:*", "math:eval", "report:?ender"])
+        .with_denied_tools(["market:*order*"]);
+    assert!(config.allows_tool("market:quote"));
+    assert!(config.allows_tool("math:eval"));
+    assert!(config.allows_tool("report:render"));
+    assert!(!config.allows_tool("market:place_order"));
+    assert!(!config.allows_tool("market:order"));
+    assert!(!config.allows_tool("math:evaluate"));
+    assert!(!config.allows_tool("shell:exec"));
+
+    // No allowlist allows everything not denied; an empty one nothing
+    let open = CognitiveConfig::default().with_denied_tools(["shell:*"]);
+    assert!(open.allows_tool("fs:read_file"));
+    assert!(!open.allows_tool("shell:exec"));
+    let closed = CognitiveConfig::default().with_allowed_tools(Vec::<String>::new());
+    assert!(!closed.allows_tool("fs:read_file"));
+}
+
+#[test]
+fn parameters_only_take_tools_away() {
+    let mut config = CognitiveConfig::default().with_allowed_tools(["market:*", "math:eval"]);
+    config.apply_tool_parameters(&HashMap::from([
+        (
+            ALLOWED_TOOLS_PARAM.to_string(),
+            "market:quote, shell:*, math:*".to_string(),
+        ),
+        (DENIED_TOOLS_PARAM.to_string(), "math:eval".to_string()),
+    ]));
+    assert_eq!(
+        sorted(config.allowed_tools.clone().unwrap()),
+        vec!["market:quote".to_string(), "math:eval".to_string()]
+    );
+    assert!(config.allows_tool("market:quote"));
+    assert!(!config.allows_tool("market:history"));
+    assert!(!config.allows_tool("shell:exec"));
+    assert!(!config.allows_tool("math:eval"));
+
+    // Without a configured allowlist the parameter becomes the allowlist
+    let mut config = CognitiveConfig::default();
+    config.apply_tool_parameters(&HashMap::from([(
+        ALLOWED_TOOLS_PARAM.to_string(),
+        "fs:*".to_string(),
+    )]));
+    assert!(config.allows_tool("fs:read_file"));
+    assert!(!config.allows_tool("shell:exec"));
+}
+
+#[test]
+fn config_deserializes_without_tool_lists() {
+    let config: CognitiveConfig = serde_json::from_value(json!({
+        "max_iterations": 3,
+        "enable_reflection": false,
+        "memory_window_size": 10,
+        "thinking_strategy": "ReAct",
+        "tool_timeout_ms": 1000,
+        "refine_after_tools": false,
+        "max_tools_exposed": 8,
+        "system_prompt": null,
+        "temperature": null
+    }))
+    .unwrap();
+    assert_eq!(config.allowed_tools, None);
+    assert!(config.denied_tools.is_empty());
+
+    let config: CognitiveConfig = serde_json::from_value(json!({
+        "max_iterations": 3,
+        "enable_reflection": false,
+        "memory_window_size": 10,
+        "thinking_strategy": "ReAct",
+        "tool_timeout_ms": 1000,
+        "refine_after_tools": false,
+        "max_tools_exposed": 8,
+        "system_prompt": null,
+        "temperature": null,
+        "allowed_tools": ["market:*"],
+        "denied_tools": ["market:place_order"]
+    }))
+    .unwrap();
+    assert!(config.allows_tool("market:quote"));
+    assert!(!config.allows_tool("market:place_order"));
+}
+
+#[tokio::test]
+async fn perception_lists_only_allowed_tools() {
+    let (tools, _) = registry(&["market:quote", "market:place_order", "shell:exec"]).await;
+    let config = CognitiveConfig::react()
+        .with_allowed_tools(["market:*"])
+        .with_denied_tools(["*:place_order"]);
+    let mut loop_impl = make_loop(config, tools);
+
+    let perception = loop_impl
+        .perceive(make_event("what is AAPL at?"), &make_state())
+        .await
+        .unwrap();
+    assert_eq!(perception.available_tools, vec!["market:quote".to_string()]);
+}
+
+#[tokio::test]
+async fn act_refuses_tools_the_agent_may_not_use() {
+    let (tools, counters) = registry(&["market:quote", "shell:exec"]).await;
+    let config = CognitiveConfig::react().with_denied_tools(["shell:*"]);
+    let mut loop_impl = make_loop(config, tools);
+
+    let mut plan = Plan::with_goal("trade");
+    plan.add_step(ThoughtStep::with_tool(
+        1,
+        "check the price",
+        ToolCall::new("market:quote", json!({"symbol": "AAPL"})),
+    ));
+    plan.add_step(ThoughtStep::with_tool(
+        2,
+        "clean up",
+        ToolCall::new("shell:exec", json!({"cmd": "rm -rf /"})),
+    ));
+    loop_impl.act(&plan, &mut make_state()).await.unwrap();
+
+    assert_eq!(counters[0].calls.load(Ordering::SeqCst), 1);
+    assert_eq!(counters[1].calls.load(Ordering::SeqCst), 0);
+}
+
+#[tokio::test]
+async fn agent_parameters_restrict_tools_on_init() {
+    let (tools, _) = registry(&["fs:read_file", "market:quote", "shell:exec"]).await;
+    let mut agent = CognitiveAgent::new(make_loop(CognitiveConfig::react(), tools));
+    let config = AgentConfig {
+        agent_id: "trader".to_string(),
+        agent_type: "cognitive".to_string(),
+        subscribed_topics: vec![],
+        capabilities: vec![],
+        parameters: HashMap::from([
+            (ALLOWED_TOOLS_PARAM.to_string(), "market:*,fs:*".to_string()),
+            (DENIED_TOOLS_PARAM.to_string(), "fs:*".to_string()),
+        ]),
+    };
+    agent.on_init(&config).await.unwrap();
+
+    let perception = agent
+        .inner_mut()
+        .perceive(make_event("buy"), &make_state())
+        .await
+        .unwrap();
+    assert_eq!(perception.available_tools, vec!["market:quote".to_string()]);
+}
+
+#[test]
+fn agent_spec_carries_tool_lists_into_the_config() {
+    let spec = AgentSpec::new("trader")
+        .with_tools(["market:*"])
+        .with_denied_tools(["market:place_order"]);
+    let config = spec.cognitive_config();
+    assert!(config.allows_tool("market:quote"));
+    assert!(!config.allows_tool("market:place_order"));
+    assert!(!config.allows_tool("shell:exec"));
+}
@@ -0,0 +1,161 @@
+//! Tests for cancelling in-flight tool calls through the registry and orchestrator
+
+use async_trait::async_trait;
+use loom_core::tools::{CircuitState, ToolPolicy};
+use loom_core::{CancellationToken, Tool, ToolError, ToolRegistry};
+use serde_json::{json, Value};
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Sleeps for a minute unless its token fires first
+struct SlowTool {
+    calls: AtomicU32,
+    saw_cancel: AtomicBool,
+}
+
+impl SlowTool {
+    fn new() -> Arc<Self> {
+        Arc::new(Self {
+            calls: AtomicU32::new(0),
+            saw_cancel: AtomicBool::new(false),
+        })
+    }
+}
+
+#[async_trait]
+impl Tool for SlowTool {
+    fn name(&self) -> String {
+        "test:slow".to_string()
+    }
+
+    fn description(&self) -> String {
+        "Takes a minute".to_string()
+    }
+
+    fn parameters(&self) -> Value {
+        json!({"type": "object"})
+    }
+
+    async fn call(&self, _arguments: Value) -> loom_core::tools::ToolResult<Value> {
+        self.calls.fetch_add(1, Ordering::SeqCst);
+        tokio::time::sleep(Duration::from_secs(60)).await;
+        Ok(json!("done"))
+    }
+
+    async fn call_with_cancel(
+        &self,
+        arguments: Value,
+        cancel: CancellationToken,
+    ) -> loom_core::tools::ToolResult<Value> {
+        tokio::select! {
+            result = self.call(arguments) => result,
+            _ = cancel.cancelled() => {
+                self.saw_cancel.store(true, Ordering::SeqCst);
+                Err(ToolError::Cancelled)
+            }
+        }
+    }
+}
+
+async fn registry_with(tool: Arc<SlowTool>) -> ToolRegistry {
+    let registry = ToolRegistry::new();
+    registry.register(tool).await;
+    registry.set_policy(
+        "test:slow",
+        ToolPolicy::new()
+            .with_timeout(Duration::from_secs(120))
+            .with_retries(2),
+    );
+    registry
+}
+
+#[tokio::test]
+async fn cancelling_stops_the_call_and_reaches_the_tool() {
+    let tool = SlowTool::new();
+    let registry = registry_with(Arc::clone(&tool)).await;
+
+    let cancel = CancellationToken::new();
+    let trigger = cancel.clone();
+    tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        trigger.cancel();
+    });
+
+    let start = Instant::now();
+    let result = registry
+        .call_with_cancel("test:slow", json!({}), cancel)
+        .await;
+    assert!(matches!(result, Err(ToolError::Cancelled)));
+    assert!(start.elapsed() < Duration::from_secs(5));
+    assert!(tool.saw_cancel.load(Ordering::SeqCst));
+    // Cancelled calls are not retried
+    assert_eq!(tool.calls.load(Ordering::SeqCst), 1);
+
+    let stats = registry.tool_stats("test:slow").unwrap();
+    assert_eq!(stats.cancelled, 1);
+    assert_eq!(stats.failures, 0);
+    assert_eq!(stats.retries, 0);
+    assert_eq!(stats.consecutive_failures, 0);
+    assert_eq!(stats.circuit_state, CircuitState::Closed);
+}
+
+#[tokio::test]
+async fn cancelled_token_never_invokes_the_tool() {
+    let tool = SlowTool::new();
+    let registry = registry_with(Arc::clone(&tool)).await;
+
+    let cancel = CancellationToken::new();
+    cancel.cancel();
+    let result = registry
+        .call_with_cancel("test:slow", json!({}), cancel)
+        .await;
+    assert!(matches!(result, Err(ToolError::Cancelled)));
+    assert_eq!(tool.calls.load(Ordering::SeqCst), 0);
+}
+
+#[tokio::test]
+async fn tools_without_cancel_support_are_dropped() {
+    struct Sleeper;
+
+    #[async_trait]
+    impl Tool for Sleeper {
+        fn name(&self) -> String {
+            "test:sleeper".to_string()
+        }
+
+        fn description(&self) -> String {
+            "Ignores its token".to_string()
+        }
+
+        fn parameters(&self) -> Value {
+            json!({"type": "object"})
+        }
+
+        async fn call(&self, _arguments: Value) -> loom_core::tools::ToolResult<Value> {
+            tokio::time::sleep(Duration::from_secs(60)).await;
+            Ok(Value::Null)
+        }
+    }
+
+    let registry = ToolRegistry::new();
+    registry.register(Arc::new(Sleeper)).await;
+
+    let cancel = CancellationToken::new();
+    let call = {
+        let cancel = cancel.clone();
+        tokio::spawn(async move {
+            registry
+                .call_with_cancel("test:sleeper", json!({}), cancel)
+                .await
+        })
+    };
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    cancel.cancel();
+
+    let result = tokio::time::timeout(Duration::from_secs(1), call)
+        .await
+        .expect("call not cancelled")
+        .unwrap();
+    assert!(matches!(result, Err(ToolError::Cancelled)));
+}
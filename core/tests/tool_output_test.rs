@@ -0,0 +1,145 @@
+//! Tests for tool output schemas: registry validation (lenient and strict) and
+//! the result contract exposed to the LLM
+use async_trait::async_trait;
+use loom_core::cognitive::llm::describe_tool;
+use loom_core::tools::native::WeatherTool;
+use loom_core::tools::{CircuitBreakerConfig, CircuitState, ToolPolicy};
+use loom_core::{Tool, ToolError, ToolRegistry};
+use serde_json::{json, Value};
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Returns `arguments["output"]` verbatim and declares a `{ "temp": number }` result
+struct EchoOutputTool {
+    calls: AtomicU32,
+}
+
+impl EchoOutputTool {
+    fn new() -> Arc<Self> {
+        Arc::new(Self {
+            calls: AtomicU32::new(0),
+        })
+    }
+}
+
+#[async_trait]
+impl Tool for EchoOutputTool {
+    fn name(&self) -> String {
+        "test:echo_output".to_string()
+    }
+
+    fn description(&self) -> String {
+        "Returns the given output".to_string()
+    }
+
+    fn parameters(&self) -> Value {
+        json!({"type": "object"})
+    }
+
+    fn output_schema(&self) -> Option<Value> {
+        Some(json!({
+            "type": "object",
+            "properties": { "temp": { "type": "number" } },
+            "required": ["temp"]
+        }))
+    }
+
+    async fn call(&self, arguments: Value) -> loom_core::tools::ToolResult<Value> {
+        self.calls.fetch_add(1, Ordering::SeqCst);
+        Ok(arguments["output"].clone())
+    }
+}
+
+#[tokio::test]
+async fn mismatched_output_passes_through_when_not_strict() {
+    let registry = ToolRegistry::new();
+    registry.register(EchoOutputTool::new()).await;
+    assert!(!registry.strict_outputs());
+
+    let result = registry
+        .call("test:echo_output", json!({"output": {"temp": "warm"}}))
+        .await
+        .unwrap();
+    assert_eq!(result, json!({"temp": "warm"}));
+}
+
+#[tokio::test]
+async fn strict_mode_rejects_malformed_output_without_retrying() {
+    let registry = ToolRegistry::new();
+    let tool = EchoOutputTool::new();
+    registry.register(tool.clone()).await;
+    registry.set_strict_outputs(true);
+    registry.set_policy(
+        "test:echo_output",
+        ToolPolicy::default()
+            .with_retries(3)
+            .with_backoff(Duration::from_millis(1), Duration::from_millis(5), 2.0)
+            .with_circuit_breaker(CircuitBreakerConfig {
+                failure_threshold: 1,
+                ..Default::default()
+            }),
+    );
+
+    let ok = registry
+        .call("test:echo_output", json!({"output": {"temp": 21.5}}))
+        .await
+        .unwrap();
+    assert_eq!(ok, json!({"temp": 21.5}));
+
+    let err = registry
+        .call("test:echo_output", json!({"output": {"temperature": 21.5}}))
+        .await
+        .unwrap_err();
+    match err {
+        ToolError::InvalidOutput(reason) => {
+            assert_eq!(reason, "$: missing required field 'temp'")
+        }
+        other => panic!("expected InvalidOutput, got {other:?}"),
+    }
+    // Deterministic contract violations are neither retried nor held against the circuit
+    assert_eq!(tool.calls.load(Ordering::SeqCst), 2);
+    let stats = registry.tool_stats("test:echo_output").unwrap();
+    assert_eq!(stats.circuit_state, CircuitState::Closed);
+}
+
+#[tokio::test]
+async fn tools_without_schema_are_never_validated() {
+    struct Untyped;
+
+    #[async_trait]
+    impl Tool for Untyped {
+        fn name(&self) -> String {
+            "test:untyped".to_string()
+        }
+        fn description(&self) -> String {
+            "No declared output".to_string()
+        }
+        fn parameters(&self) -> Value {
+            json!({"type": "object"})
+        }
+        async fn call(&self, _arguments: Value) -> loom_core::tools::ToolResult<Value> {
+            Ok(json!("anything"))
+        }
+    }
+
+    let registry = ToolRegistry::new();
+    registry.register(Arc::new(Untyped)).await;
+    registry.set_strict_outputs(true);
+    assert_eq!(
+        registry.call("test:untyped", json!({})).await.unwrap(),
+        json!("anything")
+    );
+    assert_eq!(describe_tool(&Untyped), "No declared output");
+}
+
+#[test]
+fn llm_description_carries_output_contract() {
+    let weather = WeatherTool::new();
+    let schema = weather
+        .output_schema()
+        .expect("weather declares an output schema");
+    let description = describe_tool(&weather);
+    assert!(description.starts_with(&weather.description()));
+    assert!(description.contains(&schema.to_string()));
+}
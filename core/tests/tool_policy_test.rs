@@ -0,0 +1,236 @@
+/// Tests for ToolRegistry policies: timeout, retries with backoff, circuit breaker
+use async_trait::async_trait;
+use loom_core::tools::{CircuitBreakerConfig, CircuitState, ToolPolicy};
+use loom_core::{Tool, ToolError, ToolRegistry};
+use serde_json::{json, Value};
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Tool that fails with `ExecutionFailed` for the first `fail_times` calls
+struct FlakyTool {
+    name: String,
+    fail_times: u32,
+    calls: AtomicU32,
+}
+
+impl FlakyTool {
+    fn new(name: &str, fail_times: u32) -> Arc<Self> {
+        Arc::new(Self {
+            name: name.to_string(),
+            fail_times,
+            calls: AtomicU32::new(0),
+        })
+    }
+
+    fn calls(&self) -> u32 {
+        self.calls.load(Ordering::SeqCst)
+    }
+}
+
+#[async_trait]
+impl Tool for FlakyTool {
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn description(&self) -> String {
+        "Fails a fixed number of times, then succeeds".to_string()
+    }
+
+    fn parameters(&self) -> Value {
+        json!({"type": "object"})
+    }
+
+    async fn call(&self, arguments: Value) -> loom_core::tools::ToolResult<Value> {
+        let n = self.calls.fetch_add(1, Ordering::SeqCst);
+        if arguments.get("invalid").is_some() {
+            return Err(ToolError::InvalidArguments("bad input".into()));
+        }
+        if n < self.fail_times {
+            Err(ToolError::ExecutionFailed(format!("failure #{}", n + 1)))
+        } else {
+            Ok(json!({"ok": true}))
+        }
+    }
+}
+
+/// Tool that sleeps longer than any sensible test timeout
+struct SlowTool;
+
+#[async_trait]
+impl Tool for SlowTool {
+    fn name(&self) -> String {
+        "test:slow".to_string()
+    }
+
+    fn description(&self) -> String {
+        "Never finishes in time".to_string()
+    }
+
+    fn parameters(&self) -> Value {
+        json!({"type": "object"})
+    }
+
+    async fn call(&self, _arguments: Value) -> loom_core::tools::ToolResult<Value> {
+        tokio::time::sleep(Duration::from_secs(10)).await;
+        Ok(json!({}))
+    }
+}
+
+fn fast_backoff(policy: ToolPolicy) -> ToolPolicy {
+    policy.with_backoff(Duration::from_millis(1), Duration::from_millis(5), 2.0)
+}
+
+#[test]
+fn backoff_grows_exponentially_and_is_capped() {
+    let policy = ToolPolicy::default().with_backoff(
+        Duration::from_millis(100),
+        Duration::from_millis(350),
+        2.0,
+    );
+    assert_eq!(policy.backoff_for(0), Duration::from_millis(100));
+    assert_eq!(policy.backoff_for(1), Duration::from_millis(200));
+    assert_eq!(policy.backoff_for(2), Duration::from_millis(350));
+    assert_eq!(policy.backoff_for(10), Duration::from_millis(350));
+}
+
+#[tokio::test]
+async fn per_tool_timeout_overrides_default() {
+    let registry = ToolRegistry::new();
+    registry.register(Arc::new(SlowTool)).await;
+    registry.set_policy(
+        "test:slow",
+        ToolPolicy::default().with_timeout(Duration::from_millis(20)),
+    );
+
+    let started = std::time::Instant::now();
+    let result = registry.call("test:slow", json!({})).await;
+    assert!(matches!(result, Err(ToolError::Timeout)));
+    assert!(started.elapsed() < Duration::from_secs(2));
+
+    let stats = registry.tool_stats("test:slow").unwrap();
+    assert_eq!(stats.timeouts, 1);
+    assert_eq!(stats.failures, 1);
+}
+
+#[tokio::test]
+async fn transient_failures_are_retried() {
+    let registry = ToolRegistry::new();
+    let tool = FlakyTool::new("test:flaky", 2);
+    registry.register(tool.clone()).await;
+    registry.set_policy(
+        "test:flaky",
+        fast_backoff(ToolPolicy::new().with_retries(3)),
+    );
+
+    let result = registry.call("test:flaky", json!({})).await.unwrap();
+    assert_eq!(result["ok"], true);
+    assert_eq!(tool.calls(), 3);
+
+    let stats = registry.tool_stats("test:flaky").unwrap();
+    assert_eq!(stats.retries, 2);
+    assert_eq!(stats.successes, 1);
+    assert_eq!(stats.failures, 0);
+    assert_eq!(stats.circuit_state, CircuitState::Closed);
+}
+
+#[tokio::test]
+async fn invalid_arguments_are_not_retried() {
+    let registry = ToolRegistry::new();
+    let tool = FlakyTool::new("test:flaky", 0);
+    registry.register(tool.clone()).await;
+    registry.set_policy(
+        "test:flaky",
+        fast_backoff(ToolPolicy::new().with_retries(3)),
+    );
+
+    let result = registry.call("test:flaky", json!({"invalid": true})).await;
+    assert!(matches!(result, Err(ToolError::InvalidArguments(_))));
+    assert_eq!(tool.calls(), 1);
+}
+
+#[tokio::test]
+async fn circuit_opens_and_recovers_through_half_open_probe() {
+    let registry = ToolRegistry::new();
+    let tool = FlakyTool::new("test:flaky", 3);
+    registry.register(tool.clone()).await;
+    registry.set_policy(
+        "test:flaky",
+        ToolPolicy::new().with_circuit_breaker(CircuitBreakerConfig {
+            failure_threshold: 2,
+            open_duration: Duration::from_millis(50),
+            half_open_max_calls: 1,
+        }),
+    );
+
+    // Two failures open the circuit
+    assert!(registry.call("test:flaky", json!({})).await.is_err());
+    assert!(registry.call("test:flaky", json!({})).await.is_err());
+    assert_eq!(
+        registry.tool_stats("test:flaky").unwrap().circuit_state,
+        CircuitState::Open
+    );
+
+    // Calls are rejected without reaching the tool
+    let result = registry.call("test:flaky", json!({})).await;
+    assert!(matches!(result, Err(ToolError::CircuitOpen(_))));
+    assert_eq!(tool.calls(), 2);
+
+    // After open_duration a failing probe reopens the circuit
+    tokio::time::sleep(Duration::from_millis(60)).await;
+    assert!(matches!(
+        registry.call("test:flaky", json!({})).await,
+        Err(ToolError::ExecutionFailed(_))
+    ));
+    assert_eq!(
+        registry.tool_stats("test:flaky").unwrap().circuit_state,
+        CircuitState::Open
+    );
+
+    // A successful probe closes it again
+    tokio::time::sleep(Duration::from_millis(60)).await;
+    assert!(registry.call("test:flaky", json!({})).await.is_ok());
+
+    let stats = registry.tool_stats("test:flaky").unwrap();
+    assert_eq!(stats.circuit_state, CircuitState::Closed);
+    assert_eq!(stats.rejected, 1);
+    assert_eq!(stats.failures, 3);
+    assert_eq!(stats.successes, 1);
+    assert_eq!(stats.consecutive_failures, 0);
+}
+
+#[tokio::test]
+async fn circuit_breaker_can_be_disabled() {
+    let registry = ToolRegistry::new();
+    let tool = FlakyTool::new("test:flaky", 100);
+    registry.register(tool.clone()).await;
+    registry.set_default_policy(ToolPolicy::new().without_circuit_breaker());
+
+    for _ in 0..10 {
+        assert!(matches!(
+            registry.call("test:flaky", json!({})).await,
+            Err(ToolError::ExecutionFailed(_))
+        ));
+    }
+    assert_eq!(tool.calls(), 10);
+    assert_eq!(
+        registry.tool_stats("test:flaky").unwrap().circuit_state,
+        CircuitState::Closed
+    );
+}
+
+#[tokio::test]
+async fn stats_lists_called_tools_sorted() {
+    let registry = ToolRegistry::new();
+    registry.register(FlakyTool::new("b:tool", 0)).await;
+    registry.register(FlakyTool::new("a:tool", 0)).await;
+    registry.register(FlakyTool::new("c:unused", 0)).await;
+
+    registry.call("b:tool", json!({})).await.unwrap();
+    registry.call("a:tool", json!({})).await.unwrap();
+
+    let names: Vec<String> = registry.stats().into_iter().map(|s| s.name).collect();
+    assert_eq!(names, vec!["a:tool", "b:tool"]);
+    assert!(registry.tool_stats("c:unused").is_none());
+}
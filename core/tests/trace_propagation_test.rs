@@ -0,0 +1,247 @@
+/// Tests for trace context propagation into in-process EventHandlers and agents
+use async_trait::async_trait;
+use loom_core::agent::{AgentBehavior, AgentRuntime};
+use loom_core::proto::{Action, AgentConfig, AgentState, Event, QoSLevel};
+use loom_core::{
+    Envelope, EventBus, EventHandler, ModelRouter, Result, SpanCollector, SpanData, ToolRegistry,
+};
+use opentelemetry::trace::TracerProvider as _;
+use opentelemetry_sdk::trace::TracerProvider;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tracing::Instrument;
+use tracing_subscriber::layer::SubscriberExt;
+
+const TRACE_ID: &str = "4bf92f3577b34da6a3ce929d0e0e4736";
+const PARENT_SPAN_ID: &str = "00f067aa0ba902b7";
+
+/// Keeps the tracer provider and thread-local subscriber alive for a test
+struct Tracing {
+    _provider: TracerProvider,
+    _guard: tracing::subscriber::DefaultGuard,
+}
+
+/// Installs an OpenTelemetry layer feeding `SpanCollector` for the current thread
+fn collect_spans() -> (SpanCollector, Tracing) {
+    let collector = SpanCollector::new();
+    let provider = TracerProvider::builder()
+        .with_span_processor(collector.clone())
+        .build();
+    let subscriber = tracing_subscriber::registry()
+        .with(tracing_opentelemetry::layer().with_tracer(provider.tracer("test")));
+    let guard = tracing::subscriber::set_default(subscriber);
+    (
+        collector,
+        Tracing {
+            _provider: provider,
+            _guard: guard,
+        },
+    )
+}
+
+fn traced_event(id: &str) -> Event {
+    let mut event = Event {
+        id: id.to_string(),
+        r#type: "test".to_string(),
+        timestamp_ms: 0,
+        source: "test".to_string(),
+        metadata: Default::default(),
+        payload: vec![],
+        confidence: 1.0,
+        tags: vec![],
+        priority: 50,
+    };
+    let mut env = Envelope::new("thread-1", "agent.publisher");
+    env.trace_id = TRACE_ID.to_string();
+    env.span_id = PARENT_SPAN_ID.to_string();
+    env.trace_flags = "01".to_string();
+    env.attach_to_event(&mut event);
+    event
+}
+
+/// Waits for the span named `name` recorded for `event_id` to reach the collector
+async fn wait_for_span(collector: &SpanCollector, name: &str, event_id: &str) -> SpanData {
+    for _ in 0..100 {
+        if let Some(span) = collector
+            .get_recent(usize::MAX)
+            .await
+            .into_iter()
+            .find(|s| {
+                s.name == name && s.attributes.get("event_id").map(String::as_str) == Some(event_id)
+            })
+        {
+            return span;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    panic!("span {name} for {event_id} was not recorded");
+}
+
+/// Forwards handled events, re-publishing trace context from inside the handler span
+struct Relay {
+    tx: mpsc::UnboundedSender<Envelope>,
+}
+
+#[async_trait]
+impl EventHandler for Relay {
+    async fn handle(&self, event: Event) -> Result<()> {
+        let mut env = Envelope::from_event(&event);
+        env.inject_trace_context();
+        let _ = self.tx.send(env);
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn event_handler_span_is_child_of_envelope_context() -> Result<()> {
+    let (collector, _guard) = collect_spans();
+    let bus = Arc::new(EventBus::new().await?);
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    let (_sub, _task) = bus
+        .subscribe_handler(
+            "topic.traced".to_string(),
+            vec![],
+            QoSLevel::QosRealtime,
+            Arc::new(Relay { tx }),
+        )
+        .await?;
+
+    bus.publish("topic.traced", traced_event("e1")).await?;
+    let relayed = tokio::time::timeout(Duration::from_secs(1), rx.recv())
+        .await
+        .unwrap()
+        .unwrap();
+
+    // envelope context -> publish -> event.handle
+    let publish = wait_for_span(&collector, "publish", "e1").await;
+    assert_eq!(publish.trace_id, TRACE_ID);
+    assert_eq!(publish.parent_span_id.as_deref(), Some(PARENT_SPAN_ID));
+
+    let span = wait_for_span(&collector, "event.handle", "e1").await;
+    assert_eq!(span.trace_id, TRACE_ID);
+    assert_eq!(span.parent_span_id, Some(publish.span_id));
+
+    // Context injected inside the handler points at the handler's own span
+    assert_eq!(relayed.trace_id, TRACE_ID);
+    assert_eq!(relayed.span_id, span.span_id);
+    Ok(())
+}
+
+#[tokio::test]
+async fn event_handler_joins_in_process_publisher_trace() -> Result<()> {
+    let (collector, _guard) = collect_spans();
+    let bus = Arc::new(EventBus::new().await?);
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    let (_sub, _task) = bus
+        .subscribe_handler(
+            "topic.plain".to_string(),
+            vec![],
+            QoSLevel::QosRealtime,
+            Arc::new(Relay { tx }),
+        )
+        .await?;
+
+    let mut event = traced_event("e2");
+    event.metadata.clear();
+    bus.publish("topic.plain", event).await?;
+    tokio::time::timeout(Duration::from_secs(1), rx.recv())
+        .await
+        .unwrap()
+        .unwrap();
+
+    // Without envelope context the publish span roots a new trace
+    let publish = wait_for_span(&collector, "publish", "e2").await;
+    assert_ne!(publish.trace_id, TRACE_ID);
+    assert_eq!(publish.parent_span_id, None);
+
+    let span = wait_for_span(&collector, "event.handle", "e2").await;
+    assert_eq!(span.trace_id, publish.trace_id);
+    assert_eq!(span.parent_span_id, Some(publish.span_id));
+    Ok(())
+}
+
+#[tokio::test]
+async fn publish_inside_span_of_same_trace_keeps_its_parent() -> Result<()> {
+    let (collector, _guard) = collect_spans();
+    let bus = Arc::new(EventBus::new().await?);
+    let (_sub, _rx) = bus
+        .subscribe("topic.nested".to_string(), vec![], QoSLevel::QosRealtime)
+        .await?;
+
+    // Like `bridge.publish`: a span parented from the envelope wraps the publish
+    let event = traced_event("e4");
+    let caller = tracing::info_span!("caller", event_id = "e4");
+    Envelope::from_event(&event).set_span_parent(&caller);
+    bus.publish("topic.nested", event)
+        .instrument(caller)
+        .await?;
+
+    let caller = wait_for_span(&collector, "caller", "e4").await;
+    let publish = wait_for_span(&collector, "publish", "e4").await;
+    assert_eq!(caller.parent_span_id.as_deref(), Some(PARENT_SPAN_ID));
+    assert_eq!(publish.trace_id, TRACE_ID);
+    assert_eq!(publish.parent_span_id, Some(caller.span_id));
+    Ok(())
+}
+
+struct Notifying {
+    tx: mpsc::UnboundedSender<String>,
+}
+
+#[async_trait]
+impl AgentBehavior for Notifying {
+    async fn on_event(&mut self, event: Event, _state: &mut AgentState) -> Result<Vec<Action>> {
+        let _ = self.tx.send(event.id);
+        Ok(vec![])
+    }
+
+    async fn on_init(&mut self, _config: &AgentConfig) -> Result<()> {
+        Ok(())
+    }
+
+    async fn on_shutdown(&mut self) -> Result<()> {
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn agent_on_event_span_is_child_of_envelope_context() -> Result<()> {
+    let (collector, _guard) = collect_spans();
+    let bus = Arc::new(EventBus::new().await?);
+    let runtime = AgentRuntime::new(
+        Arc::clone(&bus),
+        Arc::new(ToolRegistry::new()),
+        ModelRouter::new().await?,
+    )
+    .await?;
+
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    let cfg = AgentConfig {
+        agent_id: "traced".to_string(),
+        agent_type: "test".to_string(),
+        subscribed_topics: vec!["topic.agent".to_string()],
+        capabilities: vec![],
+        parameters: Default::default(),
+    };
+    runtime
+        .create_agent(cfg, Box::new(Notifying { tx }))
+        .await?;
+
+    bus.publish("topic.agent", traced_event("e3")).await?;
+    let handled = tokio::time::timeout(Duration::from_secs(1), rx.recv())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(handled, "e3");
+
+    let publish = wait_for_span(&collector, "publish", "e3").await;
+    let span = wait_for_span(&collector, "agent.on_event", "e3").await;
+    assert_eq!(span.trace_id, TRACE_ID);
+    assert_eq!(span.parent_span_id, Some(publish.span_id));
+    assert_eq!(
+        span.attributes.get("agent_id").map(String::as_str),
+        Some("traced")
+    );
+    Ok(())
+}
@@ -0,0 +1,162 @@
+//! Tests for the differentially private usage export
+
+use loom_core::proto::Event;
+use loom_core::{
+    EventBus, Result, UsageExportConfig, UsageExporter, UsageMetric, UsageReport, USAGE_SCHEMA,
+};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// Noise this small never moves a count across a rounding boundary
+fn exact_config() -> UsageExportConfig {
+    UsageExportConfig::new("log")
+        .with_epsilon(1e9)
+        .with_bucket(1)
+        .with_min_count(0)
+        .with_salt("test-salt")
+        .with_event_types(&["message"])
+}
+
+fn make_event(event_type: &str, sender: Option<&str>) -> Event {
+    let mut metadata = HashMap::new();
+    if let Some(sender) = sender {
+        metadata.insert("sender".to_string(), sender.to_string());
+    }
+    Event {
+        id: format!("evt-{}", event_type),
+        r#type: event_type.to_string(),
+        timestamp_ms: 0,
+        source: "test".to_string(),
+        metadata,
+        payload: b"my secret conversation".to_vec(),
+        confidence: 1.0,
+        tags: vec![],
+        priority: 50,
+    }
+}
+
+fn counts(report: &UsageReport) -> HashMap<(UsageMetric, String), u64> {
+    report
+        .rows
+        .iter()
+        .map(|row| ((row.metric, row.key.clone()), row.count))
+        .collect()
+}
+
+#[test]
+fn report_counts_types_and_hashed_agents() {
+    let exporter = UsageExporter::new(exact_config()).unwrap();
+    for _ in 0..4 {
+        exporter.observe(&make_event("message", Some("alice")));
+    }
+    exporter.observe(&make_event("internal.secret_type", Some("bob")));
+    exporter.observe(&make_event("message", None));
+
+    let report = exporter.take_report();
+    assert_eq!(report.schema, USAGE_SCHEMA);
+    let counts = counts(&report);
+    assert_eq!(counts[&(UsageMetric::EventsPublished, "message".into())], 5);
+    assert_eq!(counts[&(UsageMetric::EventsPublished, "other".into())], 1);
+    let alice = exporter.hash_agent_id("alice");
+    assert_eq!(counts[&(UsageMetric::AgentEvents, alice)], 4);
+    assert_eq!(counts[&(UsageMetric::ActiveAgents, "all".into())], 2);
+
+    // The next window starts empty
+    assert!(exporter.take_report().rows.is_empty());
+}
+
+#[test]
+fn report_leaks_no_ids_types_or_payloads() {
+    let exporter = UsageExporter::new(exact_config()).unwrap();
+    exporter.observe(&make_event("internal.secret_type", Some("alice")));
+    let json = serde_json::to_string(&exporter.take_report()).unwrap();
+    for leaked in ["alice", "secret", "conversation", "evt-"] {
+        assert!(!json.contains(leaked), "{} in {}", leaked, json);
+    }
+}
+
+#[test]
+fn agent_hashes_depend_on_salt() {
+    let a = UsageExporter::new(exact_config()).unwrap();
+    let b = UsageExporter::new(exact_config().with_salt("other-salt")).unwrap();
+    assert_eq!(a.hash_agent_id("alice"), a.hash_agent_id("alice"));
+    assert_ne!(a.hash_agent_id("alice"), a.hash_agent_id("bob"));
+    assert_ne!(a.hash_agent_id("alice"), b.hash_agent_id("alice"));
+    assert!(a.hash_agent_id("alice").starts_with("agent-"));
+    // HMAC-SHA256 keyed by the salt, so it cannot be recomputed without it
+    assert_eq!(
+        b.hash_agent_id("alice"),
+        "agent-0d1f1e82e9713ed704abe0af273ea1a1"
+    );
+}
+
+#[test]
+fn small_counts_are_suppressed_and_large_ones_bucketed() {
+    let exporter = UsageExporter::new(
+        UsageExportConfig::new("log")
+            .with_epsilon(1.0)
+            .with_bucket(10)
+            .with_min_count(50)
+            .with_event_types(&["message"]),
+    )
+    .unwrap();
+    for _ in 0..1_000 {
+        exporter.observe(&make_event("message", Some("busy-agent")));
+    }
+    exporter.observe(&make_event("rare", Some("quiet-agent")));
+
+    let report = exporter.take_report();
+    assert!(report.rows.iter().all(|row| row.count % 10 == 0));
+    assert!(report.rows.iter().all(|row| row.count >= 50));
+    let counts = counts(&report);
+    let busy = counts[&(UsageMetric::EventsPublished, "message".into())];
+    assert!((900..=1_100).contains(&busy), "noisy count {}", busy);
+    assert!(!counts.contains_key(&(UsageMetric::EventsPublished, "other".into())));
+    assert!(!counts.contains_key(&(
+        UsageMetric::AgentEvents,
+        exporter.hash_agent_id("quiet-agent")
+    )));
+    // Two agents are far below the threshold
+    assert!(!counts.contains_key(&(UsageMetric::ActiveAgents, "all".into())));
+}
+
+#[test]
+fn schema_is_strict() {
+    let report: UsageReport = serde_json::from_str(
+        r#"{"schema":"loom.usage.v1","window_start_ms":0,"window_end_ms":1,"epsilon":1.0,
+            "rows":[{"metric":"events_published","key":"message","count":10}]}"#,
+    )
+    .unwrap();
+    assert_eq!(report.rows[0].metric, UsageMetric::EventsPublished);
+
+    let extra = r#"{"schema":"loom.usage.v1","window_start_ms":0,"window_end_ms":1,"epsilon":1.0,
+        "rows":[{"metric":"events_published","key":"message","count":10,"payload":"hi"}]}"#;
+    assert!(serde_json::from_str::<UsageReport>(extra).is_err());
+    let unknown_metric = r#"{"schema":"loom.usage.v1","window_start_ms":0,"window_end_ms":1,
+        "epsilon":1.0,"rows":[{"metric":"payload_bytes","key":"all","count":10}]}"#;
+    assert!(serde_json::from_str::<UsageReport>(unknown_metric).is_err());
+}
+
+#[test]
+fn invalid_config_is_rejected() {
+    assert!(UsageExporter::new(exact_config().with_epsilon(0.0)).is_err());
+    assert!(UsageExporter::new(exact_config().with_bucket(0)).is_err());
+    assert!(UsageExporter::new(UsageExportConfig::new("")).is_err());
+}
+
+#[tokio::test]
+async fn event_bus_feeds_exporter_on_publish() -> Result<()> {
+    let exporter = Arc::new(UsageExporter::new(exact_config())?);
+    let mut bus = EventBus::new().await?;
+    bus.set_usage_exporter(Arc::clone(&exporter));
+    bus.start().await?;
+
+    for _ in 0..3 {
+        bus.publish("chat.out", make_event("message", Some("alice")))
+            .await?;
+    }
+
+    let counts = counts(&exporter.take_report());
+    assert_eq!(counts[&(UsageMetric::EventsPublished, "message".into())], 3);
+    Ok(())
+}
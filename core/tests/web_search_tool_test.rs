@@ -0,0 +1,307 @@
+//! Tests for the `web:search` tool and its providers
+
+use async_trait::async_trait;
+use axum::extract::Query;
+use axum::http::HeaderMap;
+use axum::{routing::get, Json, Router};
+use loom_core::tools::native::web_search::{normalize_results, parse_duckduckgo_html};
+use loom_core::tools::native::{
+    SearchProvider, SearchProviderKind, SearchResult, WebSearchConfig, WebSearchTool,
+};
+use loom_core::tools::ToolResult;
+use loom_core::{Tool, ToolError};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Serve `app` on a random local port and return its base URL
+async fn serve(app: Router) -> String {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+    url
+}
+
+fn config(provider: SearchProviderKind, endpoint: String) -> WebSearchConfig {
+    WebSearchConfig::for_provider(provider)
+        .with_endpoint(endpoint)
+        .with_min_interval_ms(0)
+}
+
+fn result(title: &str, url: &str) -> SearchResult {
+    SearchResult {
+        title: title.to_string(),
+        url: url.to_string(),
+        snippet: None,
+        published_at: None,
+    }
+}
+
+#[tokio::test]
+async fn brave_results_are_normalized() {
+    let app = Router::new().route(
+        "/search",
+        get(|headers: HeaderMap, Query(q): Query<HashMap<String, String>>| async move {
+            assert_eq!(headers["x-subscription-token"], "brave-key");
+            assert_eq!(q["q"], "rust async");
+            Json(json!({ "web": { "results": [
+                { "title": "Async <strong>Rust</strong>", "url": "https://rust-lang.org/async",
+                  "description": "Learn <strong>async</strong> &amp; await", "page_age": "2024-03-01T08:30:00" },
+                { "title": "Duplicate", "url": "https://rust-lang.org/async/" },
+                { "title": "Not web", "url": "ftp://example.com/file" }
+            ] } }))
+        }),
+    );
+    let url = serve(app).await;
+    let tool = WebSearchTool::with_config(
+        config(SearchProviderKind::Brave, format!("{}/search", url)).with_api_key("brave-key"),
+    );
+
+    let out = tool.call(json!({ "query": "rust async" })).await.unwrap();
+    assert_eq!(out["provider"], "brave");
+    assert_eq!(out["count"], 1);
+    assert_eq!(
+        out["results"][0],
+        json!({
+            "title": "Async Rust",
+            "url": "https://rust-lang.org/async",
+            "snippet": "Learn async & await",
+            "published_at": "2024-03-01T08:30:00Z"
+        })
+    );
+}
+
+#[tokio::test]
+async fn bing_uses_its_own_key_and_dates() {
+    let app = Router::new().route(
+        "/v7.0/search",
+        get(
+            |headers: HeaderMap, Query(q): Query<HashMap<String, String>>| async move {
+                assert_eq!(headers["ocp-apim-subscription-key"], "bing-key");
+                assert_eq!(q["count"], "2");
+                Json(json!({ "webPages": { "value": [
+                { "name": "First", "url": "https://a.example/", "snippet": "one",
+                  "datePublished": "2024-05-02T10:00:00.0000000Z" },
+                { "name": "Second", "url": "https://b.example/", "snippet": "two",
+                  "dateLastCrawled": "2024-05-03T00:00:00+02:00" },
+                { "name": "Third", "url": "https://c.example/" }
+            ] } }))
+            },
+        ),
+    );
+    let url = serve(app).await;
+    let tool = WebSearchTool::with_config(
+        config(SearchProviderKind::Bing, format!("{}/v7.0/search", url)).with_api_key("bing-key"),
+    );
+
+    let results = tool.search("news", 2).await.unwrap();
+    assert_eq!(results.len(), 2);
+    assert_eq!(
+        results[0].published_at.as_deref(),
+        Some("2024-05-02T10:00:00Z")
+    );
+    // Converted to UTC
+    assert_eq!(
+        results[1].published_at.as_deref(),
+        Some("2024-05-02T22:00:00Z")
+    );
+}
+
+#[tokio::test]
+async fn searxng_reads_json_api() {
+    let app = Router::new().route(
+        "/search",
+        get(|Query(q): Query<HashMap<String, String>>| async move {
+            assert_eq!(q["format"], "json");
+            Json(json!({ "results": [
+                { "title": "Loom", "url": "https://loom.example", "content": "Agent runtime",
+                  "publishedDate": "2023-11-20" },
+                { "title": "  ", "url": "https://empty-title.example" }
+            ] }))
+        }),
+    );
+    let url = serve(app).await;
+    // A trailing slash on the instance URL is fine
+    let tool = WebSearchTool::with_config(config(SearchProviderKind::Searxng, format!("{}/", url)));
+    assert_eq!(tool.provider_name(), "searxng");
+
+    let results = tool.search("loom", 5).await.unwrap();
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].snippet.as_deref(), Some("Agent runtime"));
+    assert_eq!(
+        results[0].published_at.as_deref(),
+        Some("2023-11-20T00:00:00Z")
+    );
+}
+
+const DDG_HTML: &str = r#"
+<div class="result results_links results_links_deep web-result">
+  <h2 class="result__title">
+    <a rel="nofollow" class="result__a" href="//duckduckgo.com/l/?uddg=https%3A%2F%2Fwww.rust-lang.org%2F&amp;rut=abc">Rust <b>Programming</b> Language</a>
+  </h2>
+  <a class="result__snippet" href="//duckduckgo.com/l/?uddg=https%3A%2F%2Fwww.rust-lang.org%2F">A language empowering <b>everyone</b> to build reliable software.</a>
+</div>
+<div class="result results_links web-result">
+  <h2 class="result__title">
+    <a href="https://doc.rust-lang.org/book/" class="result__a">The Rust Book</a>
+  </h2>
+</div>
+"#;
+
+#[test]
+fn duckduckgo_html_is_parsed() {
+    let results = normalize_results(parse_duckduckgo_html(DDG_HTML), 10);
+    assert_eq!(results.len(), 2);
+    assert_eq!(results[0].title, "Rust Programming Language");
+    assert_eq!(results[0].url, "https://www.rust-lang.org/");
+    assert_eq!(
+        results[0].snippet.as_deref(),
+        Some("A language empowering everyone to build reliable software.")
+    );
+    assert_eq!(results[1].url, "https://doc.rust-lang.org/book/");
+    assert!(results[1].snippet.is_none());
+}
+
+#[tokio::test]
+async fn duckduckgo_needs_no_key() {
+    let app = Router::new().route("/html/", get(|| async { axum::response::Html(DDG_HTML) }));
+    let url = serve(app).await;
+    let tool = WebSearchTool::with_config(config(
+        SearchProviderKind::DuckDuckGo,
+        format!("{}/html/", url),
+    ));
+    let out = tool
+        .call(json!({ "query": "rust", "limit": 1 }))
+        .await
+        .unwrap();
+    assert_eq!(out["provider"], "duckduckgo");
+    assert_eq!(out["count"], 1);
+    assert_eq!(out["results"][0]["url"], "https://www.rust-lang.org/");
+}
+
+#[tokio::test]
+async fn missing_key_or_endpoint_fails_with_hint() {
+    let err = WebSearchTool::with_config(WebSearchConfig::for_provider(SearchProviderKind::Bing))
+        .search("q", 5)
+        .await
+        .unwrap_err();
+    assert!(matches!(err, ToolError::ExecutionFailed(ref m) if m.contains("BING_API_KEY")));
+
+    let err =
+        WebSearchTool::with_config(WebSearchConfig::for_provider(SearchProviderKind::Searxng))
+            .search("q", 5)
+            .await
+            .unwrap_err();
+    assert!(matches!(err, ToolError::ExecutionFailed(ref m) if m.contains("LOOM_SEARCH_ENDPOINT")));
+}
+
+#[tokio::test]
+async fn provider_errors_are_reported() {
+    let app = Router::new().route(
+        "/search",
+        get(|| async { (axum::http::StatusCode::TOO_MANY_REQUESTS, "slow down") }),
+    );
+    let url = serve(app).await;
+    let tool = WebSearchTool::with_config(
+        config(SearchProviderKind::Brave, format!("{}/search", url)).with_api_key("k"),
+    );
+    let err = tool.search("q", 5).await.unwrap_err();
+    assert!(
+        matches!(err, ToolError::ExecutionFailed(ref m) if m.contains("429") && m.contains("slow down"))
+    );
+}
+
+/// Counts calls and returns one fixed result
+struct CountingProvider {
+    calls: AtomicUsize,
+}
+
+#[async_trait]
+impl SearchProvider for CountingProvider {
+    fn name(&self) -> &str {
+        "counting"
+    }
+
+    async fn search(
+        &self,
+        _http: &reqwest::Client,
+        query: &str,
+        _count: usize,
+    ) -> ToolResult<Vec<SearchResult>> {
+        self.calls.fetch_add(1, Ordering::SeqCst);
+        Ok(vec![result(query, "https://example.com/")])
+    }
+}
+
+#[tokio::test]
+async fn requests_are_spaced_by_min_interval() {
+    let provider = Arc::new(CountingProvider {
+        calls: AtomicUsize::new(0),
+    });
+    let tool = Arc::new(
+        WebSearchTool::with_config(
+            WebSearchConfig::for_provider(SearchProviderKind::DuckDuckGo).with_min_interval_ms(100),
+        )
+        .with_provider(Arc::clone(&provider) as Arc<dyn SearchProvider>),
+    );
+    assert_eq!(tool.provider_name(), "counting");
+    assert!(tool.description().contains("counting"));
+
+    let started = Instant::now();
+    let calls: Vec<_> = (0..3)
+        .map(|i| {
+            let tool = Arc::clone(&tool);
+            tokio::spawn(async move { tool.search(&format!("q{}", i), 5).await })
+        })
+        .collect();
+    for call in calls {
+        assert_eq!(call.await.unwrap().unwrap().len(), 1);
+    }
+    // Three requests need two full intervals
+    assert!(started.elapsed() >= Duration::from_millis(200));
+    assert_eq!(provider.calls.load(Ordering::SeqCst), 3);
+}
+
+#[test]
+fn normalization_dedups_and_truncates() {
+    let results = normalize_results(
+        vec![
+            result("One", "https://a.example/page"),
+            result("One again", "https://A.example/page#section"),
+            result("Two", " https://b.example "),
+            result("Three", "https://c.example"),
+        ],
+        2,
+    );
+    let urls: Vec<_> = results.iter().map(|r| r.url.as_str()).collect();
+    assert_eq!(urls, ["https://a.example/page", "https://b.example"]);
+}
+
+#[test]
+fn provider_kinds_parse_and_config_redacts_key() {
+    assert_eq!(
+        SearchProviderKind::parse("DDG"),
+        Some(SearchProviderKind::DuckDuckGo)
+    );
+    assert_eq!(
+        SearchProviderKind::parse("searx"),
+        Some(SearchProviderKind::Searxng)
+    );
+    assert_eq!(SearchProviderKind::parse("altavista"), None);
+    assert_eq!(SearchProviderKind::Bing.api_key_env(), Some("BING_API_KEY"));
+    assert!(!SearchProviderKind::DuckDuckGo.requires_api_key());
+
+    let config = WebSearchConfig::for_provider(SearchProviderKind::Bing).with_api_key("secret");
+    assert!(!format!("{:?}", config).contains("secret"));
+    let serialized: Value = serde_json::to_value(&config).unwrap();
+    assert!(serialized.get("api_key").is_none());
+    assert_eq!(serialized["provider"], "bing");
+
+    let parsed: WebSearchConfig =
+        serde_json::from_value(json!({ "provider": "duckduckgo" })).unwrap();
+    assert_eq!(parsed.provider, SearchProviderKind::DuckDuckGo);
+}
@@ -0,0 +1,236 @@
+/// Tests for the workflow engine: DAG ordering, data passing, retries, timeouts,
+/// agent steps and the `workflow.completed` summary
+use async_trait::async_trait;
+use loom_core::workflow::{types, Step, StepStatus, Workflow, WorkflowEngine, WorkflowReport};
+use loom_core::{collab_types, Envelope, Event, EventBus, QoSLevel, Tool, ToolError, ToolRegistry};
+use serde_json::{json, Value};
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Adds `a` and `b`; fails with `ExecutionFailed` for the first `fail_times` calls
+struct AddTool {
+    fail_times: u32,
+    calls: AtomicU32,
+}
+
+#[async_trait]
+impl Tool for AddTool {
+    fn name(&self) -> String {
+        "math:add".to_string()
+    }
+
+    fn description(&self) -> String {
+        "Add two numbers".to_string()
+    }
+
+    fn parameters(&self) -> Value {
+        json!({"type": "object"})
+    }
+
+    async fn call(&self, arguments: Value) -> loom_core::tools::ToolResult<Value> {
+        if self.calls.fetch_add(1, Ordering::SeqCst) < self.fail_times {
+            return Err(ToolError::ExecutionFailed("flaky".into()));
+        }
+        let a = arguments["a"].as_i64().unwrap_or_default();
+        let b = arguments["b"].as_i64().unwrap_or_default();
+        Ok(json!({"sum": a + b}))
+    }
+}
+
+struct SlowTool;
+
+#[async_trait]
+impl Tool for SlowTool {
+    fn name(&self) -> String {
+        "slow".to_string()
+    }
+
+    fn description(&self) -> String {
+        "Never finishes in time".to_string()
+    }
+
+    fn parameters(&self) -> Value {
+        json!({"type": "object"})
+    }
+
+    async fn call(&self, _arguments: Value) -> loom_core::tools::ToolResult<Value> {
+        tokio::time::sleep(Duration::from_secs(10)).await;
+        Ok(json!({}))
+    }
+}
+
+async fn setup(fail_times: u32) -> (Arc<EventBus>, WorkflowEngine, Arc<AddTool>) {
+    let bus = Arc::new(EventBus::new().await.unwrap());
+    bus.start().await.unwrap();
+    let registry = Arc::new(ToolRegistry::new());
+    let add = Arc::new(AddTool {
+        fail_times,
+        calls: AtomicU32::new(0),
+    });
+    registry.register(add.clone()).await;
+    registry.register(Arc::new(SlowTool)).await;
+    let engine = WorkflowEngine::new(Arc::clone(&bus), registry);
+    (bus, engine, add)
+}
+
+#[tokio::test]
+async fn toml_diamond_passes_outputs_between_steps() {
+    let (bus, engine, _) = setup(0).await;
+    let workflow = Workflow::from_toml(
+        r#"
+        name = "diamond"
+
+        [[steps]]
+        id = "total"
+        tool = "math:add"
+        arguments = { a = "${steps.left.output.sum}", b = "${steps.right.output.sum}" }
+
+        [[steps]]
+        id = "left"
+        tool = "math:add"
+        arguments = { a = "${input.x}", b = 1 }
+
+        [[steps]]
+        id = "right"
+        tool = "math:add"
+        arguments = { a = "${input.x}", b = 2 }
+        "#,
+    )
+    .unwrap();
+    assert_eq!(
+        workflow.execution_order().unwrap(),
+        vec!["left", "right", "total"]
+    );
+
+    let (_sid, mut rx) = bus
+        .subscribe(
+            workflow.topic(),
+            vec![types::COMPLETED.into()],
+            QoSLevel::QosBatched,
+        )
+        .await
+        .unwrap();
+
+    let report = engine.run(&workflow, json!({"x": 10})).await.unwrap();
+    assert!(report.succeeded());
+    assert_eq!(report.output("total"), Some(&json!({"sum": 23})));
+    assert_eq!(report.steps.last().unwrap().id, "total");
+
+    let completed = tokio::time::timeout(Duration::from_secs(1), rx.recv())
+        .await
+        .unwrap()
+        .unwrap();
+    let summary: WorkflowReport = serde_json::from_slice(&completed.payload).unwrap();
+    assert_eq!(summary.run_id, report.run_id);
+    assert_eq!(summary.steps.len(), 3);
+    assert_eq!(
+        completed.metadata.get("workflow").map(String::as_str),
+        Some("diamond")
+    );
+}
+
+#[tokio::test]
+async fn failed_step_is_retried_then_skips_dependents() {
+    let (_bus, engine, add) = setup(2).await;
+    let retried = Workflow::new("retried").with_step(
+        Step::tool("add", "math:add", json!({"a": 1, "b": 1}))
+            .with_retries(2)
+            .with_retry_backoff(Duration::from_millis(5)),
+    );
+    let report = engine.run(&retried, Value::Null).await.unwrap();
+    assert!(report.succeeded());
+    assert_eq!(report.step("add").unwrap().attempts, 3);
+    assert_eq!(add.calls.load(Ordering::SeqCst), 3);
+
+    let workflow = Workflow::new("timeouts")
+        .with_step(Step::tool("slow", "slow", json!({})).with_timeout(Duration::from_millis(50)))
+        .with_step(Step::tool(
+            "after",
+            "math:add",
+            json!({"a": "${steps.slow.output.n}"}),
+        ))
+        .with_step(Step::tool(
+            "independent",
+            "math:add",
+            json!({"a": 2, "b": 3}),
+        ));
+    let report = engine.run(&workflow, Value::Null).await.unwrap();
+    assert!(!report.succeeded());
+    let slow = report.step("slow").unwrap();
+    assert_eq!(slow.status, StepStatus::Failed);
+    assert!(slow.error.as_deref().unwrap().contains("timed out"));
+    assert_eq!(report.step("after").unwrap().status, StepStatus::Skipped);
+    assert_eq!(report.output("independent"), Some(&json!({"sum": 5})));
+}
+
+#[tokio::test]
+async fn agent_step_uses_request_reply() {
+    let (bus, engine, _) = setup(0).await;
+
+    // Agent that doubles `n`
+    let (_sid, mut rx) = bus
+        .subscribe(
+            "agent.doubler".into(),
+            vec![collab_types::REQ.into()],
+            QoSLevel::QosBatched,
+        )
+        .await
+        .unwrap();
+    let responder_bus = Arc::clone(&bus);
+    tokio::spawn(async move {
+        while let Some(req) = rx.recv().await {
+            let request: Value = serde_json::from_slice(&req.payload).unwrap();
+            let env = Envelope::from_event(&req);
+            let mut metadata = std::collections::HashMap::new();
+            env.apply_to_metadata(&mut metadata);
+            let mut reply = Event {
+                id: "reply".into(),
+                r#type: collab_types::REPLY.into(),
+                timestamp_ms: chrono::Utc::now().timestamp_millis(),
+                source: "agent.doubler".into(),
+                metadata,
+                payload: serde_json::to_vec(&json!({"n": request["n"].as_i64().unwrap() * 2}))
+                    .unwrap(),
+                confidence: 1.0,
+                tags: vec![],
+                priority: 50,
+            };
+            env.attach_to_event(&mut reply);
+            let _ = responder_bus.publish(&env.reply_topic(), reply).await;
+        }
+    });
+
+    let workflow = Workflow::new("agents")
+        .with_step(Step::tool("sum", "math:add", json!({"a": 2, "b": 3})))
+        .with_step(Step::agent(
+            "double",
+            "agent.doubler",
+            json!({"n": "${steps.sum.output.sum}"}),
+        ));
+    let report = engine.run(&workflow, Value::Null).await.unwrap();
+    assert!(report.succeeded(), "{report:?}");
+    assert_eq!(report.output("double"), Some(&json!({"n": 10})));
+}
+
+#[test]
+fn invalid_definitions_are_rejected() {
+    let cycle = Workflow::new("cycle")
+        .with_step(Step::tool("a", "math:add", json!({})).with_depends_on(["b"]))
+        .with_step(Step::tool(
+            "b",
+            "math:add",
+            json!({"a": "${steps.a.output}"}),
+        ));
+    assert!(cycle.validate().unwrap_err().to_string().contains("cycle"));
+
+    let unknown = Workflow::new("unknown")
+        .with_step(Step::tool("a", "math:add", json!({})).with_depends_on(["missing"]));
+    assert!(unknown.validate().is_err());
+
+    assert!(Workflow::from_toml("name = \"x\"\n[[steps]]\nid = \"a\"\n").is_err());
+    assert!(Workflow::from_toml(
+        "name = \"x\"\n[[steps]]\nid = \"a\"\ntool = \"t\"\nagent = \"topic\"\n"
+    )
+    .is_err());
+}
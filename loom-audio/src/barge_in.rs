@@ -0,0 +1,298 @@
+//! Barge-in detection for full-duplex voice agents
+//!
+//! With the agent's own speech captured on `audio.playback` (see `playback_capture`),
+//! `BargeInDetector` tells the echo of that speech in the microphone apart from the user
+//! talking over it:
+//! - While playback is audible it tracks how loud the echo is relative to the playback
+//!   (the speaker-to-mic coupling), learning from mic chunks that are mostly echo.
+//! - Mic energy well above the expected echo for `min_speech_ms` publishes one
+//!   `barge_in` event; `ResponseSpeaker` answers it by stopping TTS.
+//! - Optionally it republishes the mic on a clean topic with echo-only chunks silenced,
+//!   so VAD/STT downstream do not transcribe the agent's own voice.
+//!
+//! This is an energy gate, not a full acoustic echo canceller: it assumes the echo is
+//! quieter than the user's voice at the microphone, which holds for laptops and
+//! speakerphones at normal volume.
+//!
+//! Env overrides:
+//! - BARGE_IN_MIC_TOPIC (default `audio.mic`), BARGE_IN_PLAYBACK_TOPIC (default `audio.playback`)
+//! - BARGE_IN_TOPIC (default `voice.barge_in`), BARGE_IN_CLEAN_TOPIC (unset: no clean stream)
+//! - BARGE_IN_MIN_SPEECH_MS (default 200), BARGE_IN_SPEECH_RMS (default 800),
+//!   BARGE_IN_ECHO_MARGIN (default 2.0)
+
+use crate::utils::{decode_pcm16le, gen_id, now_ms};
+use loom_core::{messaging::EventBus, proto::Event, QoSLevel, Result};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::task::JoinHandle;
+use tracing::{debug, error, info, warn};
+
+/// Event type published when the user speaks over playback
+pub const BARGE_IN_EVENT: &str = "barge_in";
+
+/// Coupling assumed before any echo has been observed
+const INITIAL_COUPLING: f32 = 0.5;
+/// Weight of each echo observation in the coupling estimate
+const COUPLING_LEARNING_RATE: f32 = 0.1;
+
+#[derive(Clone, Debug)]
+pub struct BargeInConfig {
+    /// Microphone topic (expects `audio_chunk` events)
+    pub mic_topic: String,
+    /// Playback capture topic (expects `audio_chunk` events)
+    pub playback_topic: String,
+    /// Topic `barge_in` events are published on
+    pub topic: String,
+    /// Republish the mic here with echo-only chunks silenced
+    pub clean_topic: Option<String>,
+    /// Playback RMS below which the speaker counts as silent
+    pub playback_rms: f32,
+    /// Mic RMS a barge-in must exceed regardless of the echo estimate
+    pub speech_rms: f32,
+    /// How many times louder than the expected echo the mic must be to count as speech
+    pub echo_margin: f32,
+    /// Speech over playback lasting this long is a barge-in
+    pub min_speech_ms: u32,
+    /// Playback counts as audible for this long after its last loud chunk
+    pub hold_ms: u32,
+}
+
+impl Default for BargeInConfig {
+    fn default() -> Self {
+        fn env_or<T: std::str::FromStr>(key: &str, default: T) -> T {
+            std::env::var(key)
+                .ok()
+                .and_then(|v| v.parse::<T>().ok())
+                .unwrap_or(default)
+        }
+        Self {
+            mic_topic: std::env::var("BARGE_IN_MIC_TOPIC").unwrap_or_else(|_| "audio.mic".into()),
+            playback_topic: std::env::var("BARGE_IN_PLAYBACK_TOPIC")
+                .unwrap_or_else(|_| "audio.playback".into()),
+            topic: std::env::var("BARGE_IN_TOPIC").unwrap_or_else(|_| "voice.barge_in".into()),
+            clean_topic: std::env::var("BARGE_IN_CLEAN_TOPIC").ok(),
+            playback_rms: 300.0,
+            speech_rms: env_or("BARGE_IN_SPEECH_RMS", 800.0),
+            echo_margin: env_or("BARGE_IN_ECHO_MARGIN", 2.0),
+            min_speech_ms: env_or("BARGE_IN_MIN_SPEECH_MS", 200),
+            hold_ms: 300,
+        }
+    }
+}
+
+/// What a mic chunk holds, relative to the playback
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MicActivity {
+    /// Nothing is playing
+    Clear,
+    /// Playback is audible and the mic hears only its echo
+    Echo,
+    /// The user is speaking over playback
+    Speech,
+    /// Speech over playback just lasted `min_speech_ms`; reported once per playback
+    BargeIn,
+}
+
+/// Echo/speech classification of mic chunks against the playback reference
+#[derive(Clone, Debug)]
+pub struct EchoGate {
+    cfg: BargeInConfig,
+    /// Echo RMS at the mic per unit of playback RMS
+    coupling: f32,
+    /// Recent playback level, decaying between loud chunks
+    playback_level: f32,
+    last_playback_ms: Option<i64>,
+    speech_ms: u32,
+    fired: bool,
+}
+
+impl EchoGate {
+    pub fn new(cfg: BargeInConfig) -> Self {
+        Self {
+            cfg,
+            coupling: INITIAL_COUPLING,
+            playback_level: 0.0,
+            last_playback_ms: None,
+            speech_ms: 0,
+            fired: false,
+        }
+    }
+
+    /// Learned echo RMS per unit of playback RMS
+    pub fn coupling(&self) -> f32 {
+        self.coupling
+    }
+
+    /// Mic RMS expected from echo alone right now
+    pub fn expected_echo(&self) -> f32 {
+        self.coupling * self.playback_level
+    }
+
+    /// Whether playback was audible at `now_ms`
+    pub fn is_playing(&self, now_ms: i64) -> bool {
+        self.last_playback_ms
+            .is_some_and(|at| now_ms - at <= self.cfg.hold_ms as i64)
+    }
+
+    /// Record a playback chunk captured at `now_ms`
+    pub fn observe_playback(&mut self, samples: &[i16], now_ms: i64) {
+        let level = rms(samples);
+        if level < self.cfg.playback_rms {
+            return;
+        }
+        // Hold the peak briefly so the echo estimate covers the room's reverb tail
+        self.playback_level = if self.is_playing(now_ms) {
+            level.max(self.playback_level * 0.8)
+        } else {
+            level
+        };
+        self.last_playback_ms = Some(now_ms);
+    }
+
+    /// Classify a mic chunk of `chunk_ms` captured at `now_ms`
+    pub fn process_mic(&mut self, samples: &[i16], chunk_ms: u32, now_ms: i64) -> MicActivity {
+        if !self.is_playing(now_ms) {
+            self.speech_ms = 0;
+            self.fired = false;
+            return MicActivity::Clear;
+        }
+        let level = rms(samples);
+        let threshold = self
+            .cfg
+            .speech_rms
+            .max(self.cfg.echo_margin * self.expected_echo());
+        if level > threshold {
+            self.speech_ms += chunk_ms;
+            if self.speech_ms >= self.cfg.min_speech_ms && !self.fired {
+                self.fired = true;
+                return MicActivity::BargeIn;
+            }
+            return MicActivity::Speech;
+        }
+        self.speech_ms = 0;
+        if self.playback_level > 0.0 {
+            let observed = (level / self.playback_level).clamp(0.01, 4.0);
+            self.coupling += COUPLING_LEARNING_RATE * (observed - self.coupling);
+        }
+        MicActivity::Echo
+    }
+}
+
+/// Root mean square of PCM16 samples
+pub fn rms(samples: &[i16]) -> f32 {
+    if samples.is_empty() {
+        return 0.0;
+    }
+    let sum: f64 = samples.iter().map(|&s| (s as f64) * (s as f64)).sum();
+    (sum / samples.len() as f64).sqrt() as f32
+}
+
+/// Publishes `barge_in` when the user speaks over the agent's playback
+pub struct BargeInDetector {
+    bus: Arc<EventBus>,
+    cfg: BargeInConfig,
+}
+
+impl BargeInDetector {
+    pub fn new(bus: Arc<EventBus>, cfg: BargeInConfig) -> Self {
+        Self { bus, cfg }
+    }
+
+    pub async fn start(self) -> Result<JoinHandle<()>> {
+        let types = vec!["audio_chunk".to_string()];
+        let (_mic_sub, mut mic_rx) = self
+            .bus
+            .subscribe(
+                self.cfg.mic_topic.clone(),
+                types.clone(),
+                QoSLevel::QosRealtime,
+            )
+            .await?;
+        let (_playback_sub, mut playback_rx) = self
+            .bus
+            .subscribe(
+                self.cfg.playback_topic.clone(),
+                types,
+                QoSLevel::QosRealtime,
+            )
+            .await?;
+        info!(
+            target: "barge_in",
+            mic = %self.cfg.mic_topic,
+            playback = %self.cfg.playback_topic,
+            "Listening for barge-in"
+        );
+
+        let handle = tokio::spawn(async move {
+            let mut gate = EchoGate::new(self.cfg.clone());
+            loop {
+                tokio::select! {
+                    Some(ev) = playback_rx.recv() => {
+                        gate.observe_playback(&decode_pcm16le(&ev.payload), ev.timestamp_ms);
+                    }
+                    Some(ev) = mic_rx.recv() => {
+                        if let Err(e) = self.on_mic(&mut gate, ev).await {
+                            error!(target: "barge_in", error = %e, "Failed to publish");
+                        }
+                    }
+                    else => break,
+                }
+            }
+        });
+        Ok(handle)
+    }
+
+    async fn on_mic(&self, gate: &mut EchoGate, mut ev: Event) -> Result<()> {
+        let samples = decode_pcm16le(&ev.payload);
+        let activity = gate.process_mic(&samples, chunk_ms(&ev, samples.len()), ev.timestamp_ms);
+        if activity == MicActivity::BargeIn {
+            info!(target: "barge_in", "User spoke over playback");
+            let mut metadata = HashMap::new();
+            metadata.insert("mic_rms".to_string(), format!("{:.0}", rms(&samples)));
+            metadata.insert(
+                "expected_echo_rms".to_string(),
+                format!("{:.0}", gate.expected_echo()),
+            );
+            metadata.insert("mic_event_id".to_string(), ev.id.clone());
+            let barge_in = Event {
+                id: gen_id(),
+                r#type: BARGE_IN_EVENT.to_string(),
+                timestamp_ms: now_ms(),
+                source: "barge_in".to_string(),
+                metadata,
+                payload: Vec::new(),
+                confidence: 1.0,
+                tags: vec![],
+                priority: 95,
+            };
+            self.bus.publish(&self.cfg.topic, barge_in).await?;
+        }
+
+        if let Some(clean_topic) = &self.cfg.clean_topic {
+            if activity == MicActivity::Echo {
+                debug!(target: "barge_in", "Silencing echo-only mic chunk");
+                ev.payload.fill(0);
+                ev.metadata.insert("echo_suppressed".into(), "true".into());
+            }
+            if let Err(e) = self.bus.publish(clean_topic, ev).await {
+                warn!(target: "barge_in", error = %e, "Failed to republish mic chunk");
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Duration of an `audio_chunk` holding `samples` interleaved samples
+fn chunk_ms(ev: &Event, samples: usize) -> u32 {
+    let rate: u64 = ev
+        .metadata
+        .get("sample_rate")
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(16_000);
+    let channels: u64 = ev
+        .metadata
+        .get("channels")
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(1);
+    (samples as u64 * 1000 / (rate * channels).max(1)) as u32
+}
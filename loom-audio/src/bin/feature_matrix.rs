@@ -0,0 +1,327 @@
+//! Feature-combination test matrix for loom-audio
+//!
+//! Single-feature CI misses breakage that only shows up when features are combined
+//! (e.g. `barge-in` without `mic`, or `wake-onnx` next to `vad`). This runner builds and
+//! tests a curated set of combinations with `--no-default-features`, one after another.
+//!
+//! Capture runs against the synthetic device (`LOOM_AUDIO_MOCK_DEVICE=silence`) so no test
+//! touches real hardware. Combinations whose native prerequisites are missing on this
+//! machine (ALSA headers on Linux, ONNX Runtime) are skipped unless `--strict` is given.
+//!
+//! Usage:
+//!   cargo run -p loom-audio --bin loom-audio-matrix -- [--list] [--only a,b] [--check]
+//!                                                      [--fail-fast] [--strict]
+
+use std::path::{Path, PathBuf};
+use std::process::{Command, ExitCode, Stdio};
+use std::time::{Duration, Instant};
+
+/// Native prerequisite of a combination
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Requirement {
+    /// cpal's platform audio backend (ALSA development headers on Linux)
+    AudioHost,
+    /// ONNX Runtime binaries for `ort`; opt in with `LOOM_MATRIX_ONNX=1`
+    OnnxRuntime,
+}
+
+struct Combo {
+    name: &'static str,
+    features: &'static [&'static str],
+    requires: &'static [Requirement],
+}
+
+const MATRIX: &[Combo] = &[
+    Combo {
+        name: "none",
+        features: &[],
+        requires: &[],
+    },
+    Combo {
+        name: "mic",
+        features: &["mic"],
+        requires: &[Requirement::AudioHost],
+    },
+    Combo {
+        name: "playback-capture",
+        features: &["playback-capture"],
+        requires: &[Requirement::AudioHost],
+    },
+    Combo {
+        name: "audio-mock",
+        features: &["audio-mock"],
+        requires: &[],
+    },
+    Combo {
+        name: "vad",
+        features: &["vad"],
+        requires: &[],
+    },
+    Combo {
+        name: "stt",
+        features: &["stt"],
+        requires: &[],
+    },
+    Combo {
+        name: "wake",
+        features: &["wake"],
+        requires: &[],
+    },
+    Combo {
+        name: "wake-onnx",
+        features: &["wake-onnx"],
+        requires: &[Requirement::OnnxRuntime],
+    },
+    Combo {
+        name: "tts",
+        features: &["tts"],
+        requires: &[],
+    },
+    Combo {
+        name: "barge-in",
+        features: &["barge-in"],
+        requires: &[],
+    },
+    Combo {
+        name: "tts+barge-in",
+        features: &["tts", "barge-in"],
+        requires: &[],
+    },
+    Combo {
+        name: "vad+stt",
+        features: &["vad", "stt"],
+        requires: &[],
+    },
+    // Headless fixture → VAD → STT pipeline
+    Combo {
+        name: "audio-mock+vad+stt",
+        features: &["audio-mock", "vad", "stt"],
+        requires: &[],
+    },
+    Combo {
+        name: "mic+vad+stt",
+        features: &["mic", "vad", "stt"],
+        requires: &[Requirement::AudioHost],
+    },
+    Combo {
+        name: "wake+stt+tts",
+        features: &["wake", "stt", "tts"],
+        requires: &[],
+    },
+    // What apps/voice_agent enables
+    Combo {
+        name: "voice-agent",
+        features: &[
+            "mic",
+            "playback-capture",
+            "vad",
+            "stt",
+            "wake",
+            "tts",
+            "barge-in",
+        ],
+        requires: &[Requirement::AudioHost],
+    },
+    Combo {
+        name: "full",
+        features: &[
+            "mic",
+            "audio-mock",
+            "playback-capture",
+            "vad",
+            "stt",
+            "wake",
+            "wake-onnx",
+            "tts",
+            "barge-in",
+        ],
+        requires: &[Requirement::AudioHost, Requirement::OnnxRuntime],
+    },
+];
+
+struct Options {
+    list: bool,
+    only: Option<Vec<String>>,
+    check: bool,
+    fail_fast: bool,
+    strict: bool,
+}
+
+impl Options {
+    fn parse() -> Result<Self, String> {
+        let mut options = Options {
+            list: false,
+            only: None,
+            check: false,
+            fail_fast: false,
+            strict: false,
+        };
+        let mut args = std::env::args().skip(1);
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--list" => options.list = true,
+                "--check" => options.check = true,
+                "--fail-fast" => options.fail_fast = true,
+                "--strict" => options.strict = true,
+                "--only" => {
+                    let names = args.next().ok_or("--only needs a comma-separated list")?;
+                    options.only = Some(names.split(',').map(|n| n.trim().to_string()).collect());
+                }
+                "-h" | "--help" => {
+                    return Err(
+                        "usage: loom-audio-matrix [--list] [--only a,b] [--check] [--fail-fast] [--strict]"
+                            .to_string(),
+                    )
+                }
+                other => return Err(format!("unknown argument '{}'", other)),
+            }
+        }
+        if let Some(only) = &options.only {
+            for name in only {
+                if !MATRIX.iter().any(|c| c.name == name) {
+                    return Err(format!("unknown combination '{}' (see --list)", name));
+                }
+            }
+        }
+        Ok(options)
+    }
+}
+
+enum Outcome {
+    Passed(Duration),
+    Failed(Duration),
+    Skipped(String),
+}
+
+/// Why `requirement` cannot be met here, if it cannot
+fn missing(requirement: Requirement) -> Option<String> {
+    match requirement {
+        Requirement::AudioHost => {
+            if !cfg!(target_os = "linux") {
+                // CoreAudio and WASAPI ship with the OS
+                return None;
+            }
+            let found = Command::new("pkg-config")
+                .args(["--exists", "alsa"])
+                .status()
+                .map(|s| s.success())
+                .unwrap_or(false);
+            (!found).then(|| "ALSA headers not found (install libasound2-dev)".to_string())
+        }
+        Requirement::OnnxRuntime => {
+            let enabled = std::env::var("LOOM_MATRIX_ONNX").is_ok_and(|v| v == "1")
+                || std::env::var_os("ORT_LIB_LOCATION").is_some();
+            (!enabled).then(|| "ONNX Runtime not enabled (set LOOM_MATRIX_ONNX=1)".to_string())
+        }
+    }
+}
+
+fn run_combo(combo: &Combo, manifest: &Path, check: bool) -> Outcome {
+    let cargo = std::env::var_os("CARGO").unwrap_or_else(|| "cargo".into());
+    let mut command = Command::new(cargo);
+    if check {
+        command.args(["check", "--tests"]);
+    } else {
+        command.arg("test");
+    }
+    command
+        .arg("--manifest-path")
+        .arg(manifest)
+        .arg("--no-default-features");
+    if !combo.features.is_empty() {
+        command.args(["--features", &combo.features.join(",")]);
+    }
+    command
+        .env("LOOM_AUDIO_MOCK_DEVICE", "silence")
+        .stdin(Stdio::null());
+
+    let started = Instant::now();
+    let status = command.status();
+    let elapsed = started.elapsed();
+    match status {
+        Ok(status) if status.success() => Outcome::Passed(elapsed),
+        Ok(_) => Outcome::Failed(elapsed),
+        Err(e) => Outcome::Skipped(format!("could not run cargo: {}", e)),
+    }
+}
+
+fn main() -> ExitCode {
+    let options = match Options::parse() {
+        Ok(options) => options,
+        Err(message) => {
+            eprintln!("{}", message);
+            return ExitCode::from(2);
+        }
+    };
+
+    let selected: Vec<&Combo> = MATRIX
+        .iter()
+        .filter(|c| match &options.only {
+            Some(only) => only.iter().any(|n| n == c.name),
+            None => true,
+        })
+        .collect();
+
+    if options.list {
+        for combo in &selected {
+            println!("{:<18} [{}]", combo.name, combo.features.join(","));
+        }
+        return ExitCode::SUCCESS;
+    }
+
+    let manifest: PathBuf = Path::new(env!("CARGO_MANIFEST_DIR")).join("Cargo.toml");
+    let mut results: Vec<(&Combo, Outcome)> = Vec::new();
+    for combo in selected {
+        let skip = combo.requires.iter().find_map(|r| missing(*r));
+        let outcome = match skip {
+            Some(reason) if !options.strict => Outcome::Skipped(reason),
+            _ => {
+                eprintln!(
+                    "==> {} [{}]",
+                    combo.name,
+                    if combo.features.is_empty() {
+                        "no features".to_string()
+                    } else {
+                        combo.features.join(",")
+                    }
+                );
+                run_combo(combo, &manifest, options.check)
+            }
+        };
+        let failed = matches!(outcome, Outcome::Failed(_));
+        results.push((combo, outcome));
+        if failed && options.fail_fast {
+            break;
+        }
+    }
+
+    println!();
+    println!(
+        "loom-audio feature matrix ({})",
+        if options.check { "check" } else { "test" }
+    );
+    let (mut passed, mut failed, mut skipped) = (0, 0, 0);
+    for (combo, outcome) in &results {
+        match outcome {
+            Outcome::Passed(t) => {
+                passed += 1;
+                println!("  ok    {:<18} {:>6.1}s", combo.name, t.as_secs_f64());
+            }
+            Outcome::Failed(t) => {
+                failed += 1;
+                println!("  FAIL  {:<18} {:>6.1}s", combo.name, t.as_secs_f64());
+            }
+            Outcome::Skipped(reason) => {
+                skipped += 1;
+                println!("  skip  {:<18} {}", combo.name, reason);
+            }
+        }
+    }
+    println!("{} passed, {} failed, {} skipped", passed, failed, skipped);
+
+    if failed > 0 || (options.strict && skipped > 0) {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
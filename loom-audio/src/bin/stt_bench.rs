@@ -0,0 +1,108 @@
+//! STT accuracy benchmark
+//!
+//! Runs a labeled dataset through one or more whisper.cpp configurations and prints a
+//! Markdown comparison of word error rate and latency. Without `--configs`, a single
+//! configuration built from the `STT_*` environment (as `SttEngine` would use) is run.
+//!
+//! Usage:
+//!   cargo run -p loom-audio --features stt --bin loom-stt-bench -- \
+//!       --dataset <dir|manifest.jsonl> [--configs configs.json] [--json report.json]
+
+use loom_audio::{BenchConfig, BenchConfigSpec, BenchDataset, SttBenchmark, SttConfig};
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+const USAGE: &str =
+    "usage: loom-stt-bench --dataset <dir|manifest.jsonl> [--configs configs.json] [--json report.json]";
+
+struct Options {
+    dataset: PathBuf,
+    configs: Option<PathBuf>,
+    json: Option<PathBuf>,
+}
+
+impl Options {
+    fn parse() -> Result<Self, String> {
+        let mut dataset = None;
+        let mut configs = None;
+        let mut json = None;
+        let mut args = std::env::args().skip(1);
+        while let Some(arg) = args.next() {
+            let mut value = || args.next().ok_or_else(|| format!("{} needs a path", arg));
+            match arg.as_str() {
+                "--dataset" => dataset = Some(PathBuf::from(value()?)),
+                "--configs" => configs = Some(PathBuf::from(value()?)),
+                "--json" => json = Some(PathBuf::from(value()?)),
+                "-h" | "--help" => return Err(USAGE.to_string()),
+                other => return Err(format!("unknown argument '{}'\n{}", other, USAGE)),
+            }
+        }
+        Ok(Self {
+            dataset: dataset.ok_or(USAGE)?,
+            configs,
+            json,
+        })
+    }
+}
+
+fn main() -> ExitCode {
+    let options = match Options::parse() {
+        Ok(options) => options,
+        Err(message) => {
+            eprintln!("{}", message);
+            return ExitCode::from(2);
+        }
+    };
+
+    let dataset = match BenchDataset::load(&options.dataset) {
+        Ok(dataset) if !dataset.is_empty() => dataset,
+        Ok(_) => {
+            eprintln!("no labeled samples in {:?}", options.dataset);
+            return ExitCode::FAILURE;
+        }
+        Err(e) => {
+            eprintln!("failed to load dataset {:?}: {}", options.dataset, e);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let base = SttConfig::default();
+    let configs = match &options.configs {
+        Some(path) => match BenchConfigSpec::load(path) {
+            Ok(specs) => specs.into_iter().map(|s| s.into_config(&base)).collect(),
+            Err(e) => {
+                eprintln!("failed to load configs {:?}: {}", path, e);
+                return ExitCode::FAILURE;
+            }
+        },
+        None => vec![BenchConfig::new("default", base)],
+    };
+    let benchmark = configs
+        .into_iter()
+        .fold(SttBenchmark::new(dataset), SttBenchmark::with_config);
+
+    let runtime = match tokio::runtime::Builder::new_multi_thread()
+        .enable_all()
+        .build()
+    {
+        Ok(runtime) => runtime,
+        Err(e) => {
+            eprintln!("failed to start runtime: {}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+    let report = runtime.block_on(benchmark.run());
+
+    print!("{}", report.to_markdown());
+    if let Some(path) = &options.json {
+        if let Err(e) = std::fs::write(path, report.to_json()) {
+            eprintln!("failed to write {:?}: {}", path, e);
+            return ExitCode::FAILURE;
+        }
+    }
+    if report.configs.iter().all(|c| c.failed == c.files.len()) {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
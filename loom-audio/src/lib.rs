@@ -3,6 +3,18 @@
 
 #![allow(clippy::all)]
 
+#[cfg_attr(
+    not(any(
+        feature = "mic",
+        feature = "audio-mock",
+        feature = "vad",
+        feature = "stt",
+        feature = "wake",
+        feature = "tts",
+        feature = "barge-in"
+    )),
+    allow(dead_code)
+)]
 pub(crate) mod utils;
 
 #[cfg(feature = "mic")]
@@ -10,6 +22,26 @@ pub mod mic;
 #[cfg(feature = "mic")]
 pub use mic::{MicConfig, MicSource};
 
+#[cfg(any(feature = "audio-mock", feature = "stt"))]
+mod wav;
+#[cfg(any(feature = "audio-mock", feature = "stt"))]
+pub use wav::{read_wav, WavClip};
+
+#[cfg(feature = "audio-mock")]
+pub mod mock;
+#[cfg(feature = "audio-mock")]
+pub use mock::{MockMicConfig, MockMicSource};
+
+#[cfg(feature = "playback-capture")]
+pub mod playback_capture;
+#[cfg(feature = "playback-capture")]
+pub use playback_capture::{PlaybackCaptureConfig, PlaybackCaptureSource};
+
+#[cfg(feature = "barge-in")]
+pub mod barge_in;
+#[cfg(feature = "barge-in")]
+pub use barge_in::{BargeInConfig, BargeInDetector, EchoGate, MicActivity};
+
 #[cfg(feature = "vad")]
 pub mod vad;
 #[cfg(feature = "vad")]
@@ -18,14 +50,32 @@ pub use vad::{VadConfig, VadGate};
 #[cfg(feature = "stt")]
 pub mod stt;
 #[cfg(feature = "stt")]
-pub use stt::{SttConfig, SttEngine};
+pub use stt::{PartialScore, PartialStability, SttConfig, SttEngine};
+
+#[cfg(feature = "stt")]
+pub mod stt_bench;
+#[cfg(feature = "stt")]
+pub use stt_bench::{
+    word_errors, BenchConfig, BenchConfigSpec, BenchDataset, BenchReport, BenchSample, BenchVad,
+    ConfigReport, FileResult, SttBenchmark,
+};
 
 #[cfg(feature = "wake")]
 pub mod wake;
 #[cfg(feature = "wake")]
-pub use wake::{WakeWordConfig, WakeWordDetector};
+pub use wake::{WakeMode, WakeModel, WakeWordConfig, WakeWordDetector};
+
+#[cfg(feature = "wake-onnx")]
+pub mod wake_onnx;
+#[cfg(feature = "wake-onnx")]
+pub use wake_onnx::OnnxWakeModel;
 
 #[cfg(feature = "tts")]
 pub mod tts;
 #[cfg(feature = "tts")]
-pub use tts::{TtsSpeakProvider, TtsSpeakProviderConfig};
+pub use tts::{TtsPlayback, TtsSpeakProvider, TtsSpeakProviderConfig, TtsStopTool};
+
+#[cfg(feature = "tts")]
+pub mod speaker;
+#[cfg(feature = "tts")]
+pub use speaker::{ResponseSpeaker, ResponseSpeakerConfig, SentenceChunker};
@@ -5,10 +5,13 @@
 //!   sudo apt-get update && sudo apt-get install -y libasound2-dev pkg-config
 //! Then run the example with:
 //!   cargo run -p loom-core --example mic_capture --features mic
-use crate::utils::{gen_id, now_ms};
+//!
+//! `LOOM_AUDIO_MOCK_DEVICE=silence|tone` replaces the device with a synthetic one that
+//! produces chunks in real time without touching cpal, so capture can be exercised on
+//! machines (and CI runners) without audio hardware.
+use crate::utils::audio_chunk_event;
 use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
-use loom_core::{messaging::EventBus, proto::Event, LoomError, Result};
-use std::collections::HashMap;
+use loom_core::{messaging::EventBus, LoomError, Result};
 use std::sync::Arc;
 use tokio::sync::mpsc;
 use tokio::task::JoinHandle;
@@ -69,7 +72,7 @@ impl MicSource {
 
         // Spawn an async task to run capture and publish
         let handle = tokio::spawn(async move {
-            if let Err(e) = run_capture_loop(event_bus, cfg).await {
+            if let Err(e) = run_capture_loop(event_bus, cfg, select_input_device).await {
                 error!("MicSource stopped with error: {}", e);
             }
         });
@@ -77,7 +80,7 @@ impl MicSource {
     }
 }
 
-// now_ms and gen_id are provided by audio::utils
+// audio_chunk events are built by audio::utils
 
 struct AudioPacket {
     samples: Vec<i16>,
@@ -86,12 +89,93 @@ struct AudioPacket {
     device_name: String,
 }
 
-async fn run_capture_loop(event_bus: Arc<EventBus>, config: MicConfig) -> Result<()> {
+/// Picks the capture device given an optional name substring
+pub(crate) type DeviceSelector = fn(&cpal::Host, Option<&str>) -> Option<cpal::Device>;
+
+/// Microphone selection: first input device matching `MIC_DEVICE`, else the default input
+fn select_input_device(host: &cpal::Host, needle: Option<&str>) -> Option<cpal::Device> {
+    if let Some(needle) = needle {
+        if let Some((dev, name)) = find_device(host.input_devices(), needle) {
+            info!("Selected input device by MIC_DEVICE='{}': {}", needle, name);
+            return Some(dev);
+        }
+    }
+    host.default_input_device()
+}
+
+/// First device whose name contains `needle`, case-insensitively
+pub(crate) fn find_device<I>(
+    devices: std::result::Result<I, cpal::DevicesError>,
+    needle: &str,
+) -> Option<(cpal::Device, String)>
+where
+    I: Iterator<Item = cpal::Device>,
+{
+    let needle = needle.to_lowercase();
+    match devices {
+        Ok(devices) => devices.into_iter().find_map(|dev| {
+            let name = dev.name().ok()?;
+            name.to_lowercase().contains(&needle).then_some((dev, name))
+        }),
+        Err(e) => {
+            warn!("Failed to list devices: {}", e);
+            None
+        }
+    }
+}
+
+/// Capture from the device chosen by `select_device` and publish `audio_chunk` events
+/// to `config.topic` until the process exits
+pub(crate) async fn run_capture_loop(
+    event_bus: Arc<EventBus>,
+    config: MicConfig,
+    select_device: DeviceSelector,
+) -> Result<()> {
     // Channel to receive audio chunks (with metadata) from the producer thread
     let (tx, mut rx) = mpsc::channel::<AudioPacket>(64);
 
-    // Spawn a producer thread that owns the CPAL stream (non-Send)
-    let cfg_for_thread = config.clone();
+    if let Some(mock) = MockDevice::from_env() {
+        info!(
+            "Capture started: source={} device=\"{}\" chunk={}ms rate={}Hz ch={}",
+            config.source,
+            mock.name(),
+            config.chunk_ms,
+            config.sample_rate_hz,
+            config.channels
+        );
+        std::thread::spawn({
+            let config = config.clone();
+            move || mock.run(&config, tx)
+        });
+    } else {
+        spawn_cpal_producer(config.clone(), select_device, tx);
+    }
+
+    // Consumer: assemble and publish events
+    while let Some(pkt) = rx.recv().await {
+        let event = audio_chunk_event(
+            &pkt.samples,
+            pkt.sample_rate_hz,
+            pkt.channels,
+            &pkt.device_name,
+            &config.source,
+        );
+
+        if let Err(e) = event_bus.publish(&config.topic, event).await {
+            warn!("Failed to publish audio_chunk: {}", e);
+        }
+    }
+
+    Ok(())
+}
+
+/// Spawn the thread owning the cpal stream (non-Send); it sends chunks through `tx`
+fn spawn_cpal_producer(
+    config: MicConfig,
+    select_device: DeviceSelector,
+    tx: mpsc::Sender<AudioPacket>,
+) {
+    let cfg_for_thread = config;
     std::thread::spawn(move || {
         // Choose host and input device
         let host = cpal::default_host();
@@ -141,27 +225,7 @@ async fn run_capture_loop(event_bus: Arc<EventBus>, config: MicConfig) -> Result
             }
         }
 
-        // Enumerate devices and optionally filter by name
-        let input_device = if let Some(ref needle) = cfg_for_thread.device_name {
-            let mut found: Option<cpal::Device> = None;
-            match host.input_devices() {
-                Ok(devices) => {
-                    for dev in devices {
-                        if let Ok(name) = dev.name() {
-                            if name.to_lowercase().contains(&needle.to_lowercase()) {
-                                found = Some(dev);
-                                info!("Selected input device by MIC_DEVICE='{}': {}", needle, name);
-                                break;
-                            }
-                        }
-                    }
-                }
-                Err(e) => warn!("Failed to list input devices: {}", e),
-            }
-            found.or_else(|| host.default_input_device())
-        } else {
-            host.default_input_device()
-        };
+        let input_device = select_device(&host, cfg_for_thread.device_name.as_deref());
 
         let input_device = match input_device {
             Some(d) => d,
@@ -172,14 +236,16 @@ async fn run_capture_loop(event_bus: Arc<EventBus>, config: MicConfig) -> Result
         };
         let device_name = input_device.name().unwrap_or_else(|_| "unknown".into());
 
-        // Resolve supported configs and pick the best matching one
-        let supported_configs = match input_device.supported_input_configs() {
-            Ok(c) => c,
-            Err(e) => {
-                error!("failed to query supported input configs: {}", e);
-                return;
-            }
-        };
+        // Resolve supported configs and pick the best matching one. Loopback captures
+        // of an output device may not report any, in which case the defaults below apply.
+        let supported_configs: Vec<cpal::SupportedStreamConfigRange> =
+            match input_device.supported_input_configs() {
+                Ok(c) => c.collect(),
+                Err(e) => {
+                    warn!("failed to query supported input configs: {}", e);
+                    Vec::new()
+                }
+            };
 
         // Build candidate list across supported configs, preferring:
         // 1) Requested sample rate if available, otherwise 48k, 32k, 16k, 8k
@@ -274,7 +340,10 @@ async fn run_capture_loop(event_bus: Arc<EventBus>, config: MicConfig) -> Result
         let chosen_config = if let Some(best) = candidates.first() {
             best.cfg.clone()
         } else {
-            match input_device.default_input_config() {
+            match input_device
+                .default_input_config()
+                .or_else(|_| input_device.default_output_config())
+            {
                 Ok(c) => c,
                 Err(e) => {
                     error!("failed to get default input config: {}", e);
@@ -429,8 +498,12 @@ async fn run_capture_loop(event_bus: Arc<EventBus>, config: MicConfig) -> Result
         }
 
         info!(
-            "MicSource started: device=\"{}\" chunk={}ms rate={}Hz ch={}",
-            device_name, cfg_for_thread.chunk_ms, actual_rate, actual_channels
+            "Capture started: source={} device=\"{}\" chunk={}ms rate={}Hz ch={}",
+            cfg_for_thread.source,
+            device_name,
+            cfg_for_thread.chunk_ms,
+            actual_rate,
+            actual_channels
         );
 
         // Keep thread alive while stream runs; callbacks send packets via mpsc
@@ -438,43 +511,67 @@ async fn run_capture_loop(event_bus: Arc<EventBus>, config: MicConfig) -> Result
             std::thread::sleep(std::time::Duration::from_secs(60));
         }
     });
+}
 
-    // Consumer: assemble and publish events
-    while let Some(pkt) = rx.recv().await {
-        // Serialize to little-endian bytes
-        let mut payload = Vec::with_capacity(pkt.samples.len() * 2);
-        for sample in pkt.samples.iter() {
-            payload.extend_from_slice(&sample.to_le_bytes());
-        }
-
-        let mut metadata: HashMap<String, String> = HashMap::new();
-        metadata.insert("sample_rate".into(), pkt.sample_rate_hz.to_string());
-        metadata.insert("channels".into(), pkt.channels.to_string());
-        metadata.insert("device".into(), pkt.device_name.clone());
-        metadata.insert("encoding".into(), "pcm_s16le".into());
-        metadata.insert(
-            "frame_samples".into(),
-            (payload.len() as u32 / 2).to_string(),
-        );
+/// Synthetic capture device selected by `LOOM_AUDIO_MOCK_DEVICE`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum MockDevice {
+    Silence,
+    /// 440 Hz sine at a third of full scale
+    Tone,
+}
 
-        let event = Event {
-            id: gen_id(),
-            r#type: "audio_chunk".into(),
-            timestamp_ms: now_ms(),
-            source: config.source.clone(),
-            metadata,
-            payload,
-            confidence: 1.0,
-            tags: vec![],
-            priority: 90,
-        };
+impl MockDevice {
+    fn from_env() -> Option<Self> {
+        match std::env::var("LOOM_AUDIO_MOCK_DEVICE").ok()?.as_str() {
+            "silence" => Some(Self::Silence),
+            "tone" => Some(Self::Tone),
+            other => {
+                warn!("Unknown LOOM_AUDIO_MOCK_DEVICE '{}'; using silence", other);
+                Some(Self::Silence)
+            }
+        }
+    }
 
-        if let Err(e) = event_bus.publish(&config.topic, event).await {
-            warn!("Failed to publish audio_chunk: {}", e);
+    fn name(&self) -> &'static str {
+        match self {
+            Self::Silence => "mock:silence",
+            Self::Tone => "mock:tone",
         }
     }
 
-    Ok(())
+    /// Produce one chunk every `chunk_ms` until the consumer goes away
+    fn run(self, config: &MicConfig, tx: mpsc::Sender<AudioPacket>) {
+        let channels = config.channels.max(1);
+        let frames = (config.sample_rate_hz as u64 * config.chunk_ms as u64 / 1000) as usize;
+        let period = std::time::Duration::from_millis(config.chunk_ms.max(1) as u64);
+        let mut t = 0u64;
+        loop {
+            let mut samples = Vec::with_capacity(frames * channels as usize);
+            for _ in 0..frames {
+                let value = match self {
+                    Self::Silence => 0,
+                    Self::Tone => {
+                        let phase = 2.0 * std::f32::consts::PI * 440.0 * t as f32
+                            / config.sample_rate_hz as f32;
+                        (phase.sin() * i16::MAX as f32 / 3.0) as i16
+                    }
+                };
+                samples.extend(std::iter::repeat(value).take(channels as usize));
+                t += 1;
+            }
+            let packet = AudioPacket {
+                samples,
+                sample_rate_hz: config.sample_rate_hz,
+                channels,
+                device_name: self.name().to_string(),
+            };
+            if tx.blocking_send(packet).is_err() {
+                return;
+            }
+            std::thread::sleep(period);
+        }
+    }
 }
 
 fn build_input_stream<T, F>(
@@ -0,0 +1,144 @@
+//! Mock audio backend: plays WAV fixtures as microphone input.
+//!
+//! `MockMicSource` publishes the same `audio_chunk` events as `MicSource` (same topic,
+//! metadata and chunking) from 16-bit PCM WAV files instead of a capture device, so the
+//! MicSource → VadGate → SttEngine chain can run in containers and CI without audio
+//! hardware or cpal. Playback is deterministic: every fixture is cut into the same
+//! chunks on every run, and each is followed by `gap_ms` of silence so VAD closes the
+//! utterance before the next one starts.
+//!
+//! Enable with the `audio-mock` feature.
+
+use crate::utils::audio_chunk_event;
+pub use crate::wav::{read_wav, WavClip};
+use loom_core::{messaging::EventBus, Result};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::task::JoinHandle;
+use tracing::{error, info, warn};
+
+/// Configuration for WAV fixture playback
+#[derive(Clone, Debug)]
+pub struct MockMicConfig {
+    /// WAV files played in order (16-bit PCM, any rate/channel count)
+    pub fixtures: Vec<PathBuf>,
+    /// Chunk size in milliseconds for emitted audio_chunk events
+    pub chunk_ms: u32,
+    /// Silence appended after each fixture, in milliseconds
+    pub gap_ms: u32,
+    /// Playback speed: 1.0 paces chunks in real time, 4.0 four times faster, 0 sends them
+    /// back to back (yielding between chunks)
+    pub speed: f32,
+    /// Event topic to publish to (e.g., "audio.mic")
+    pub topic: String,
+    /// Event source name (e.g., "mic.mock")
+    pub source: String,
+    /// Reported as the `device` metadata of every chunk
+    pub device_name: String,
+}
+
+impl Default for MockMicConfig {
+    fn default() -> Self {
+        Self {
+            fixtures: Vec::new(),
+            chunk_ms: 20,
+            gap_ms: 500,
+            speed: 1.0,
+            topic: "audio.mic".into(),
+            source: "mic.mock".into(),
+            device_name: "mock".into(),
+        }
+    }
+}
+
+impl MockMicConfig {
+    pub fn with_fixture(mut self, path: impl Into<PathBuf>) -> Self {
+        self.fixtures.push(path.into());
+        self
+    }
+
+    pub fn with_speed(mut self, speed: f32) -> Self {
+        self.speed = speed;
+        self
+    }
+
+    pub fn with_gap_ms(mut self, gap_ms: u32) -> Self {
+        self.gap_ms = gap_ms;
+        self
+    }
+
+    pub fn with_topic(mut self, topic: impl Into<String>) -> Self {
+        self.topic = topic.into();
+        self
+    }
+}
+
+/// Headless stand-in for `MicSource` that plays WAV fixtures
+pub struct MockMicSource {
+    event_bus: Arc<EventBus>,
+    config: MockMicConfig,
+}
+
+impl MockMicSource {
+    pub fn new(event_bus: Arc<EventBus>, config: MockMicConfig) -> Self {
+        Self { event_bus, config }
+    }
+
+    /// Decode every fixture, then play them in the background.
+    ///
+    /// Fails up front on an unreadable fixture. The returned task finishes once the
+    /// last chunk (including the trailing gap) has been published.
+    pub async fn start(self) -> Result<JoinHandle<()>> {
+        let mut clips = Vec::with_capacity(self.config.fixtures.len());
+        for path in &self.config.fixtures {
+            clips.push(read_wav(path)?);
+        }
+        let handle = tokio::spawn(async move {
+            if let Err(e) = play(&self.event_bus, &self.config, &clips).await {
+                error!("MockMicSource stopped with error: {}", e);
+            }
+        });
+        Ok(handle)
+    }
+}
+
+async fn play(event_bus: &EventBus, config: &MockMicConfig, clips: &[WavClip]) -> Result<()> {
+    let pause = (config.speed > 0.0)
+        .then(|| Duration::from_secs_f32(config.chunk_ms.max(1) as f32 / 1000.0 / config.speed));
+    for (path, clip) in config.fixtures.iter().zip(clips) {
+        info!(
+            "Mock capture: playing {:?} ({}ms @ {}Hz, ch={})",
+            path,
+            clip.duration_ms(),
+            clip.sample_rate_hz,
+            clip.channels
+        );
+        let channels = clip.channels as usize;
+        let chunk_len = (clip.sample_rate_hz as usize * config.chunk_ms as usize / 1000) * channels;
+        let gap_len = (clip.sample_rate_hz as usize * config.gap_ms as usize / 1000) * channels;
+        let mut samples = clip.samples.clone();
+        samples.resize(samples.len() + gap_len, 0);
+        // Pad the tail to a whole chunk so every chunk has the same size
+        let padded = samples.len().div_ceil(chunk_len.max(1)) * chunk_len.max(1);
+        samples.resize(padded, 0);
+
+        for chunk in samples.chunks(chunk_len.max(1)) {
+            let event = audio_chunk_event(
+                chunk,
+                clip.sample_rate_hz,
+                clip.channels,
+                &config.device_name,
+                &config.source,
+            );
+            if let Err(e) = event_bus.publish(&config.topic, event).await {
+                warn!("Failed to publish audio_chunk: {}", e);
+            }
+            match pause {
+                Some(pause) => tokio::time::sleep(pause).await,
+                None => tokio::task::yield_now().await,
+            }
+        }
+    }
+    Ok(())
+}
@@ -9,6 +9,18 @@ pub mod mic;
 #[cfg(feature = "mic")]
 pub use mic::{MicConfig, MicSource};
 
+#[cfg(feature = "playback-capture")]
+pub mod playback_capture;
+
+#[cfg(feature = "playback-capture")]
+pub use playback_capture::{PlaybackCaptureConfig, PlaybackCaptureSource};
+
+#[cfg(feature = "barge-in")]
+pub mod barge_in;
+
+#[cfg(feature = "barge-in")]
+pub use barge_in::{BargeInConfig, BargeInDetector, EchoGate, MicActivity};
+
 #[cfg(feature = "vad")]
 pub mod vad;
 
@@ -25,11 +37,23 @@ pub use stt::{SttConfig, SttEngine};
 pub mod wake;
 
 #[cfg(feature = "wake")]
-pub use wake::{WakeWordConfig, WakeWordDetector};
+pub use wake::{WakeMode, WakeModel, WakeWordConfig, WakeWordDetector};
+
+#[cfg(feature = "wake-onnx")]
+pub mod wake_onnx;
+
+#[cfg(feature = "wake-onnx")]
+pub use wake_onnx::OnnxWakeModel;
 
 #[cfg(feature = "tts")]
 pub mod tts;
 
 #[cfg(feature = "tts")]
-pub use tts::{TtsSpeakProvider, TtsSpeakProviderConfig};
+pub use tts::{TtsSpeakProvider, TtsSpeakProviderConfig, TtsStopTool};
+
+#[cfg(feature = "tts")]
+pub mod speaker;
+
+#[cfg(feature = "tts")]
+pub use speaker::{ResponseSpeaker, ResponseSpeakerConfig, SentenceChunker};
 // (utils re-export intentionally crate-visible only)
@@ -0,0 +1,160 @@
+//! Playback capture EventSource: records what the system is playing (a loopback device)
+//! and publishes it as `audio_chunk` events on `audio.playback`.
+//!
+//! Voice agents use this as the far-end reference for echo suppression and barge-in
+//! detection (see `barge_in`). Capture runs through the same cpal pipeline as `mic`; only
+//! the device differs:
+//! - `PLAYBACK_DEVICE` picks the first input or output device whose name contains it.
+//! - Otherwise the first input device that looks like a loopback: a PulseAudio/PipeWire
+//!   `Monitor of ...` source, Windows `Stereo Mix`, or a macOS BlackHole/Soundflower
+//!   virtual device.
+//! - On Windows, the default output device, which WASAPI records in loopback mode.
+//!
+//! Linux: `pactl list short sources | grep monitor` lists the available monitors.
+use crate::mic::{find_device, run_capture_loop, MicConfig};
+use cpal::traits::{DeviceTrait, HostTrait};
+use loom_core::{messaging::EventBus, Result};
+use std::sync::Arc;
+use tokio::task::JoinHandle;
+use tracing::{error, info, warn};
+
+/// Substrings identifying loopback input devices, matched case-insensitively
+pub const LOOPBACK_DEVICE_HINTS: &[&str] = &[
+    "monitor",
+    "loopback",
+    "stereo mix",
+    "blackhole",
+    "soundflower",
+];
+
+/// Configuration for playback capture
+#[derive(Clone, Debug)]
+pub struct PlaybackCaptureConfig {
+    /// Desired sample rate; falls back to common rates like the mic
+    pub sample_rate_hz: u32,
+    /// Desired channels; default mono
+    pub channels: u16,
+    /// Chunk size in milliseconds; keep it equal to the mic's so chunks line up
+    pub chunk_ms: u32,
+    /// Optional loopback device name substring to match
+    pub device_name: Option<String>,
+    /// Event topic to publish to (e.g., "audio.playback")
+    pub topic: String,
+    /// Event source name (e.g., "playback.loopback")
+    pub source: String,
+}
+
+impl Default for PlaybackCaptureConfig {
+    fn default() -> Self {
+        let chunk_ms = std::env::var("PLAYBACK_CHUNK_MS")
+            .ok()
+            .and_then(|v| v.parse::<u32>().ok())
+            .or_else(|| {
+                std::env::var("MIC_CHUNK_MS")
+                    .ok()
+                    .and_then(|v| v.parse::<u32>().ok())
+            })
+            .unwrap_or(20);
+        let topic =
+            std::env::var("PLAYBACK_TOPIC").unwrap_or_else(|_| "audio.playback".to_string());
+        let source =
+            std::env::var("PLAYBACK_SOURCE").unwrap_or_else(|_| "playback.loopback".to_string());
+        let device_name = std::env::var("PLAYBACK_DEVICE").ok();
+        Self {
+            sample_rate_hz: 16_000,
+            channels: 1,
+            chunk_ms,
+            device_name,
+            topic,
+            source,
+        }
+    }
+}
+
+impl From<PlaybackCaptureConfig> for MicConfig {
+    fn from(cfg: PlaybackCaptureConfig) -> Self {
+        MicConfig {
+            sample_rate_hz: cfg.sample_rate_hz,
+            channels: cfg.channels,
+            chunk_ms: cfg.chunk_ms,
+            device_name: cfg.device_name,
+            topic: cfg.topic,
+            source: cfg.source,
+        }
+    }
+}
+
+/// Playback capture event source: publishes the system output as `audio_chunk` events
+pub struct PlaybackCaptureSource {
+    event_bus: Arc<EventBus>,
+    config: PlaybackCaptureConfig,
+}
+
+impl PlaybackCaptureSource {
+    pub fn new(event_bus: Arc<EventBus>, config: PlaybackCaptureConfig) -> Self {
+        Self { event_bus, config }
+    }
+
+    /// Start the loopback capture loop. Returns a handle to the background task.
+    pub async fn start(self) -> Result<JoinHandle<()>> {
+        let cfg: MicConfig = self.config.into();
+        let event_bus = Arc::clone(&self.event_bus);
+
+        let handle = tokio::spawn(async move {
+            if let Err(e) = run_capture_loop(event_bus, cfg, select_loopback_device).await {
+                error!("PlaybackCaptureSource stopped with error: {}", e);
+            }
+        });
+        Ok(handle)
+    }
+}
+
+/// True if `name` looks like a loopback input device
+pub fn is_loopback_device_name(name: &str) -> bool {
+    let name = name.to_lowercase();
+    LOOPBACK_DEVICE_HINTS.iter().any(|hint| name.contains(hint))
+}
+
+fn select_loopback_device(host: &cpal::Host, needle: Option<&str>) -> Option<cpal::Device> {
+    if let Some(needle) = needle {
+        let found = find_device(host.input_devices(), needle)
+            .or_else(|| find_device(host.output_devices(), needle));
+        match found {
+            Some((dev, name)) => {
+                info!(
+                    "Selected playback device by PLAYBACK_DEVICE='{}': {}",
+                    needle, name
+                );
+                return Some(dev);
+            }
+            None => warn!("No device matches PLAYBACK_DEVICE='{}'", needle),
+        }
+    }
+
+    if let Ok(devices) = host.input_devices() {
+        for dev in devices {
+            if let Ok(name) = dev.name() {
+                if is_loopback_device_name(&name) {
+                    info!("Selected loopback device: {}", name);
+                    return Some(dev);
+                }
+            }
+        }
+    }
+
+    // WASAPI captures an output device in loopback mode
+    #[cfg(target_os = "windows")]
+    if let Some(dev) = host.default_output_device() {
+        info!(
+            "Capturing default output device in loopback mode: {}",
+            dev.name().unwrap_or_else(|_| "unknown".into())
+        );
+        return Some(dev);
+    }
+
+    error!(
+        "No loopback device found; set PLAYBACK_DEVICE to a monitor source \
+         (Linux: `pactl list short sources`, macOS: BlackHole, Windows: Stereo Mix)"
+    );
+    None
+}
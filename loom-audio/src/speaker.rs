@@ -0,0 +1,256 @@
+//! Sentence-chunked speech of streamed agent responses
+//!
+//! `ResponseSpeaker` listens on a reply topic for the `response.partial` events an agent
+//! streams while it generates (see `CognitiveAgent::with_response_streaming`) and calls
+//! `tts.speak` once per complete sentence, so speech starts after the first sentence
+//! instead of the whole answer. The closing `response.final` (or a plain
+//! `agent.response`) speaks whatever is left; a response that was not streamed is
+//! spoken sentence by sentence on arrival.
+//!
+//! Streams are told apart by their `correlation_id`.
+//!
+//! With `barge_in_topic` set, a `barge_in` event (see `BargeInDetector`) cuts the current
+//! sentence short through `tts.stop` and mutes the rest of every response started before
+//! it; responses arriving afterwards are spoken as usual.
+//!
+//! Env overrides:
+//! - VOICE_REPLY_TOPIC (default `voice.reply`)
+//! - TTS_MIN_SENTENCE_CHARS (default 12)
+//! - VOICE_BARGE_IN_TOPIC (unset: speech is never interrupted)
+
+use loom_core::cognitive::{RESPONSE_EVENT, RESPONSE_FINAL_EVENT, RESPONSE_PARTIAL_EVENT};
+use loom_core::messaging::envelope::keys;
+use loom_core::{messaging::EventBus, Event, QoSLevel, Result, ToolRegistry};
+use serde_json::json;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+use tracing::{debug, info, warn};
+
+/// Configuration of the response speaker
+#[derive(Clone, Debug)]
+pub struct ResponseSpeakerConfig {
+    /// Topic responses are published on (the queries' `reply_to`)
+    pub topic: String,
+    /// Shortest text spoken as a sentence; shorter ones ("Dr.", "1.") join the next
+    pub min_sentence_chars: usize,
+    /// `tts.speak` voice
+    pub voice: Option<String>,
+    /// `tts.speak` rate
+    pub rate: Option<f32>,
+    /// `tts.speak` volume
+    pub volume: Option<f32>,
+    /// Topic of `barge_in` events that interrupt speech
+    pub barge_in_topic: Option<String>,
+}
+
+impl Default for ResponseSpeakerConfig {
+    fn default() -> Self {
+        Self {
+            topic: std::env::var("VOICE_REPLY_TOPIC").unwrap_or_else(|_| "voice.reply".into()),
+            min_sentence_chars: std::env::var("TTS_MIN_SENTENCE_CHARS")
+                .ok()
+                .and_then(|s| s.parse::<usize>().ok())
+                .unwrap_or(12),
+            voice: None,
+            rate: None,
+            volume: None,
+            barge_in_topic: std::env::var("VOICE_BARGE_IN_TOPIC").ok(),
+        }
+    }
+}
+
+/// Splits streamed text into sentences
+#[derive(Debug, Clone)]
+pub struct SentenceChunker {
+    buffer: String,
+    min_chars: usize,
+}
+
+impl SentenceChunker {
+    pub fn new(min_chars: usize) -> Self {
+        Self {
+            buffer: String::new(),
+            min_chars,
+        }
+    }
+
+    /// Append `text`; returns the sentences it completed, trimmed
+    ///
+    /// A sentence ends at a line break, or at `.`, `!`, `?` or `…` followed by
+    /// whitespace, so the end of the buffered text is never a boundary until more
+    /// text arrives.
+    pub fn push(&mut self, text: &str) -> Vec<String> {
+        self.buffer.push_str(text);
+        let mut sentences = Vec::new();
+        let mut start = 0;
+        let mut chars = self.buffer.char_indices().peekable();
+        while let Some((index, c)) = chars.next() {
+            let ends = match c {
+                '\n' => true,
+                '.' | '!' | '?' | '…' => {
+                    matches!(chars.peek(), Some((_, next)) if next.is_whitespace())
+                }
+                _ => false,
+            };
+            if !ends {
+                continue;
+            }
+            let end = index + c.len_utf8();
+            let sentence = self.buffer[start..end].trim();
+            if sentence.chars().count() >= self.min_chars {
+                sentences.push(sentence.to_string());
+                start = end;
+            }
+        }
+        self.buffer.drain(..start);
+        sentences
+    }
+
+    /// The buffered text that did not end a sentence, if any; empties the chunker
+    pub fn finish(&mut self) -> Option<String> {
+        let rest = std::mem::take(&mut self.buffer);
+        let rest = rest.trim();
+        if rest.is_empty() {
+            None
+        } else {
+            Some(rest.to_string())
+        }
+    }
+}
+
+pub struct ResponseSpeaker {
+    bus: Arc<EventBus>,
+    registry: Arc<ToolRegistry>,
+    cfg: ResponseSpeakerConfig,
+}
+
+impl ResponseSpeaker {
+    /// Speaker calling `tts.speak` through `registry`
+    pub fn new(
+        bus: Arc<EventBus>,
+        registry: Arc<ToolRegistry>,
+        cfg: ResponseSpeakerConfig,
+    ) -> Self {
+        Self { bus, registry, cfg }
+    }
+
+    pub async fn start(self) -> Result<JoinHandle<()>> {
+        let (_sub_id, mut rx) = self
+            .bus
+            .subscribe(
+                self.cfg.topic.clone(),
+                vec![
+                    RESPONSE_PARTIAL_EVENT.to_string(),
+                    RESPONSE_FINAL_EVENT.to_string(),
+                    RESPONSE_EVENT.to_string(),
+                ],
+                QoSLevel::QosBatched,
+            )
+            .await?;
+        let mut barge_in = match &self.cfg.barge_in_topic {
+            Some(topic) => {
+                let (_sub_id, rx) = self
+                    .bus
+                    .subscribe(
+                        topic.clone(),
+                        vec!["barge_in".to_string()],
+                        QoSLevel::QosRealtime,
+                    )
+                    .await?;
+                Some(rx)
+            }
+            None => None,
+        };
+        info!(target: "speaker", topic = %self.cfg.topic, "Speaking responses");
+
+        let handle = tokio::spawn(async move {
+            // Streams in progress by correlation id, with the barge-in count at their start
+            let mut streams: HashMap<String, (SentenceChunker, u64)> = HashMap::new();
+            let mut barge_ins = 0u64;
+            loop {
+                let ev = tokio::select! {
+                    ev = rx.recv() => match ev {
+                        Some(ev) => ev,
+                        None => break,
+                    },
+                    Some(_) = next_barge_in(&mut barge_in) => {
+                        barge_ins += 1;
+                        self.stop().await;
+                        continue;
+                    }
+                };
+                let correlation_id = ev
+                    .metadata
+                    .get(keys::CORRELATION_ID)
+                    .cloned()
+                    .unwrap_or_default();
+                let text = String::from_utf8_lossy(&ev.payload);
+                let (sentences, started_at) = if ev.r#type == RESPONSE_PARTIAL_EVENT {
+                    let (chunker, started_at) =
+                        streams.entry(correlation_id).or_insert_with(|| {
+                            (SentenceChunker::new(self.cfg.min_sentence_chars), barge_ins)
+                        });
+                    (chunker.push(&text), *started_at)
+                } else {
+                    match streams.remove(&correlation_id) {
+                        // The streamed sentences were spoken already
+                        Some((mut chunker, started_at)) => {
+                            (chunker.finish().into_iter().collect(), started_at)
+                        }
+                        None => {
+                            let mut chunker = SentenceChunker::new(self.cfg.min_sentence_chars);
+                            let mut sentences = chunker.push(&text);
+                            sentences.extend(chunker.finish());
+                            (sentences, barge_ins)
+                        }
+                    }
+                };
+                for sentence in sentences {
+                    // The user interrupted this response
+                    if started_at != barge_ins {
+                        debug!(target: "speaker", sentence = %sentence, "Muted after barge-in");
+                        continue;
+                    }
+                    tokio::select! {
+                        _ = self.speak(&sentence) => {}
+                        Some(_) = next_barge_in(&mut barge_in) => {
+                            barge_ins += 1;
+                            self.stop().await;
+                        }
+                    }
+                }
+            }
+        });
+        Ok(handle)
+    }
+
+    async fn stop(&self) {
+        info!(target: "speaker", "Barge-in; stopping speech");
+        if let Err(e) = self.registry.call("tts.stop", json!({})).await {
+            warn!(target: "speaker", error = %e, "TTS stop failed");
+        }
+    }
+
+    async fn speak(&self, sentence: &str) {
+        debug!(target: "speaker", sentence = %sentence, "Speaking sentence");
+        let args = json!({
+            "text": sentence,
+            "voice": self.cfg.voice.clone().unwrap_or_default(),
+            "rate": self.cfg.rate,
+            "volume": self.cfg.volume,
+        });
+        if let Err(e) = self.registry.call("tts.speak", args).await {
+            warn!(target: "speaker", error = %e, "TTS invocation failed");
+        }
+    }
+}
+
+/// Next barge-in event; pending forever without a barge-in subscription
+async fn next_barge_in(rx: &mut Option<mpsc::Receiver<Event>>) -> Option<Event> {
+    match rx {
+        Some(rx) => rx.recv().await,
+        None => std::future::pending().await,
+    }
+}
@@ -1,5 +1,5 @@
 use crate::utils::{gen_id, now_ms};
-use loom_core::{messaging::EventBus, proto::Event, QoSLevel, Result};
+use loom_core::{messaging::EventBus, proto::Event, DedicatedPool, QoSLevel, Result};
 use std::collections::HashMap;
 use std::io::Write;
 use std::path::PathBuf;
@@ -16,7 +16,7 @@ pub struct SttConfig {
     pub vad_topic: String,
     /// Input topic to subscribe to voiced audio frames (`audio_voiced`)
     pub voiced_topic: String,
-    /// Output topic to publish transcript events (`transcript.final`)
+    /// Output topic to publish transcript events (`transcript.final`, `transcript.partial`)
     pub transcript_topic: String,
     /// Path to whisper.cpp executable (e.g., "./whisper.cpp/main")
     pub whisper_bin: PathBuf,
@@ -28,6 +28,18 @@ pub struct SttConfig {
     pub temp_dir: PathBuf,
     /// Additional whisper.cpp arguments (e.g., ["--threads", "4"])
     pub extra_args: Vec<String>,
+    /// Dedicated pool for running whisper (defaults to the global blocking pool)
+    pub pool: Option<Arc<DedicatedPool>>,
+    /// When set, only transcribe utterances that start within `wake_window_ms` of a
+    /// `wake_word_detected` event on this topic. Use with acoustic wake detection only:
+    /// transcript-based wake needs every utterance transcribed.
+    pub wake_topic: Option<String>,
+    /// How long a wake keeps STT transcribing (ms)
+    pub wake_window_ms: u64,
+    /// Re-decode the utterance so far this often while the user speaks, publishing
+    /// `transcript.partial` events (ms); 0 only transcribes finished utterances. Each
+    /// re-decode runs whisper on the whole utterance, so keep it well above the decode time.
+    pub partial_interval_ms: u64,
 }
 
 impl Default for SttConfig {
@@ -68,6 +80,16 @@ impl Default for SttConfig {
             language,
             temp_dir,
             extra_args,
+            pool: None,
+            wake_topic: std::env::var("STT_WAKE_TOPIC").ok(),
+            wake_window_ms: std::env::var("STT_WAKE_WINDOW_MS")
+                .ok()
+                .and_then(|s| s.parse::<u64>().ok())
+                .unwrap_or(8000),
+            partial_interval_ms: std::env::var("STT_PARTIAL_INTERVAL_MS")
+                .ok()
+                .and_then(|s| s.parse::<u64>().ok())
+                .unwrap_or(0),
         }
     }
 }
@@ -119,9 +141,99 @@ impl SttEngine {
 
 // now_ms and gen_id are provided by audio::utils
 
+/// Utterances shorter than this are not transcribed (ms)
+pub(crate) const MIN_UTTERANCE_MS: u64 = 200;
+
+/// Consecutive hypotheses a word must survive unchanged to count as stable
+pub const PARTIAL_STABLE_AFTER: u32 = 2;
+
+/// Stability of one partial hypothesis (see `PartialStability`)
+#[derive(Clone, Debug, PartialEq)]
+pub struct PartialScore {
+    /// Hypotheses of the utterance so far, this one included
+    pub revision: u32,
+    /// 0.0 (every word is new) to 1.0 (every word is stable)
+    pub stability: f32,
+    /// Leading words that are stable
+    pub stable_text: String,
+}
+
+/// Scores successive partial hypotheses of one utterance
+///
+/// A word is stable once it, and every word before it, has kept its place across
+/// `stable_after` consecutive hypotheses. Case and punctuation are ignored, since
+/// re-decoding a longer utterance often only changes those.
+#[derive(Clone, Debug)]
+pub struct PartialStability {
+    stable_after: u32,
+    /// Normalized words of the last hypothesis and how many hypotheses each has held
+    words: Vec<(String, u32)>,
+    revision: u32,
+}
+
+impl Default for PartialStability {
+    fn default() -> Self {
+        Self::new(PARTIAL_STABLE_AFTER)
+    }
+}
+
+impl PartialStability {
+    pub fn new(stable_after: u32) -> Self {
+        Self {
+            stable_after: stable_after.max(1),
+            words: Vec::new(),
+            revision: 0,
+        }
+    }
+
+    /// Score `text`, the latest hypothesis
+    pub fn update(&mut self, text: &str) -> PartialScore {
+        self.revision += 1;
+        let tokens: Vec<&str> = text.split_whitespace().collect();
+        let mut agreeing = true;
+        let words: Vec<(String, u32)> = tokens
+            .iter()
+            .enumerate()
+            .map(|(i, token)| {
+                let word: String = token
+                    .chars()
+                    .filter(|c| c.is_alphanumeric())
+                    .flat_map(char::to_lowercase)
+                    .collect();
+                agreeing = agreeing && self.words.get(i).is_some_and(|(w, _)| *w == word);
+                let held = if agreeing { self.words[i].1 + 1 } else { 1 };
+                (word, held)
+            })
+            .collect();
+        self.words = words;
+
+        let stable_words = self
+            .words
+            .iter()
+            .take_while(|(_, held)| *held >= self.stable_after)
+            .count();
+        let stability = if self.words.is_empty() {
+            0.0
+        } else {
+            self.words
+                .iter()
+                .map(|(_, held)| (*held).min(self.stable_after) as f32 / self.stable_after as f32)
+                .sum::<f32>()
+                / self.words.len() as f32
+        };
+        PartialScore {
+            revision: self.revision,
+            stability,
+            stable_text: tokens[..stable_words].join(" "),
+        }
+    }
+}
+
 /// Utterance buffer for audio frames between speech_start and speech_end
 #[derive(Debug)]
 struct Utterance {
+    /// Shared by the utterance's partial and final transcripts
+    id: String,
     frames: Vec<Vec<i16>>,
     sample_rate: u32,
     start_time_ms: i64,
@@ -130,6 +242,7 @@ struct Utterance {
 impl Utterance {
     fn new(sample_rate: u32) -> Self {
         Self {
+            id: gen_id(),
             frames: Vec::new(),
             sample_rate,
             start_time_ms: now_ms(),
@@ -190,10 +303,56 @@ async fn run_stt(bus: Arc<EventBus>, cfg: SttConfig) -> Result<()> {
     // State: current utterance being recorded
     let utterance = Arc::new(Mutex::new(Option::<Utterance>::None));
 
+    // Wake gate: utterances starting after this timestamp are not transcribed
+    let awake_until_ms = Arc::new(std::sync::atomic::AtomicI64::new(
+        if cfg.wake_topic.is_some() {
+            i64::MIN
+        } else {
+            i64::MAX
+        },
+    ));
+    let wake_task = match cfg.wake_topic.clone() {
+        Some(wake_topic) => {
+            let (_wake_sub_id, mut wake_rx) = bus
+                .subscribe(
+                    wake_topic,
+                    vec!["wake_word_detected".to_string()],
+                    QoSLevel::QosRealtime,
+                )
+                .await?;
+            let awake_until_ms = Arc::clone(&awake_until_ms);
+            let window_ms = cfg.wake_window_ms as i64;
+            Some(tokio::spawn(async move {
+                while let Some(ev) = wake_rx.recv().await {
+                    debug!(
+                        "Wake word detected ({}), transcribing for {}ms",
+                        ev.id, window_ms
+                    );
+                    awake_until_ms.store(
+                        now_ms().saturating_add(window_ms),
+                        std::sync::atomic::Ordering::SeqCst,
+                    );
+                }
+            }))
+        }
+        None => None,
+    };
+
+    // Re-decode the utterance so far for partial transcripts
+    let partial_task = (has_whisper && cfg.partial_interval_ms > 0).then(|| {
+        tokio::spawn(run_partials(
+            Arc::clone(&bus),
+            cfg.clone(),
+            Arc::clone(&utterance),
+            Arc::clone(&awake_until_ms),
+        ))
+    });
+
     // Spawn a task to handle VAD events
     let bus_vad = Arc::clone(&bus);
     let cfg_vad = cfg.clone();
     let utterance_vad = Arc::clone(&utterance);
+    let awake_until_vad = Arc::clone(&awake_until_ms);
     let vad_task = tokio::spawn(async move {
         while let Some(ev) = vad_rx.recv().await {
             match ev.r#type.as_str() {
@@ -212,8 +371,15 @@ async fn run_stt(bus: Arc<EventBus>, cfg: SttConfig) -> Result<()> {
                     info!("🎤 Speech ended, processing utterance...");
                     let mut utt = utterance_vad.lock().await;
                     if let Some(utterance) = utt.take() {
+                        let awake = utterance.start_time_ms
+                            <= awake_until_vad.load(std::sync::atomic::Ordering::SeqCst);
                         // Process the utterance
-                        if has_whisper {
+                        if !awake {
+                            debug!(
+                                "Skipping STT processing (no wake word), {} samples buffered",
+                                utterance.total_samples()
+                            );
+                        } else if has_whisper {
                             if let Err(e) = process_utterance(&bus_vad, &cfg_vad, utterance).await {
                                 error!("Failed to process utterance: {}", e);
                             }
@@ -263,10 +429,111 @@ async fn run_stt(bus: Arc<EventBus>, cfg: SttConfig) -> Result<()> {
 
     // Wait for both tasks
     let _ = tokio::try_join!(vad_task, voiced_task);
+    if let Some(wake_task) = wake_task {
+        wake_task.abort();
+    }
+    if let Some(partial_task) = partial_task {
+        partial_task.abort();
+    }
 
     Ok(())
 }
 
+/// Every `partial_interval_ms`, transcribe the current utterance if it grew and publish
+/// a `transcript.partial` scored against the previous hypotheses
+async fn run_partials(
+    bus: Arc<EventBus>,
+    cfg: SttConfig,
+    utterance: Arc<Mutex<Option<Utterance>>>,
+    awake_until_ms: Arc<std::sync::atomic::AtomicI64>,
+) {
+    let mut ticker =
+        tokio::time::interval(std::time::Duration::from_millis(cfg.partial_interval_ms));
+    ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+    // Utterance being decoded, its scores so far and how many samples were decoded last
+    let mut current: Option<(String, PartialStability, usize)> = None;
+
+    loop {
+        ticker.tick().await;
+        let snapshot = {
+            let utt = utterance.lock().await;
+            match utt.as_ref() {
+                Some(u)
+                    if u.duration_ms() >= MIN_UTTERANCE_MS
+                        && u.start_time_ms
+                            <= awake_until_ms.load(std::sync::atomic::Ordering::SeqCst) =>
+                {
+                    Some((u.id.clone(), u.to_pcm(), u.sample_rate, u.duration_ms()))
+                }
+                _ => None,
+            }
+        };
+        let Some((id, pcm, sample_rate, duration)) = snapshot else {
+            current = None;
+            continue;
+        };
+        if current
+            .as_ref()
+            .map_or(true, |(current_id, ..)| *current_id != id)
+        {
+            current = Some((id.clone(), PartialStability::default(), 0));
+        }
+        let Some((_, stability, decoded)) = current.as_mut() else {
+            continue;
+        };
+        if pcm.len() == *decoded {
+            continue;
+        }
+        *decoded = pcm.len();
+
+        let text = match transcribe_pcm(&cfg, &pcm, sample_rate).await {
+            Ok(text) => text,
+            Err(e) => {
+                warn!("Partial transcription failed: {}", e);
+                continue;
+            }
+        };
+        // Publish under the lock speech_end takes: once the utterance ended, its final
+        // transcript supersedes this one
+        let current_utterance = utterance.lock().await;
+        if text.is_empty() || !current_utterance.as_ref().is_some_and(|u| u.id == id) {
+            continue;
+        }
+
+        let score = stability.update(&text);
+        debug!(
+            "📝 Partial transcript #{} (stability {:.2}): {}",
+            score.revision, score.stability, text
+        );
+        let mut metadata = HashMap::new();
+        metadata.insert("utterance_id".to_string(), id);
+        metadata.insert("sample_rate".to_string(), sample_rate.to_string());
+        metadata.insert("duration_ms".to_string(), duration.to_string());
+        metadata.insert("language".to_string(), cfg.language.clone());
+        metadata.insert("text".to_string(), text.clone());
+        metadata.insert("stable_text".to_string(), score.stable_text);
+        metadata.insert("stability".to_string(), format!("{:.2}", score.stability));
+        metadata.insert("revision".to_string(), score.revision.to_string());
+
+        let event = Event {
+            id: gen_id(),
+            r#type: "transcript.partial".to_string(),
+            timestamp_ms: now_ms(),
+            source: "stt".to_string(),
+            metadata,
+            payload: text.into_bytes(),
+            confidence: score.stability,
+            tags: vec![],
+            // Below finals: a late partial is worth less than the final
+            priority: 65,
+        };
+        if let Err(e) = bus.publish(&cfg.transcript_topic, event).await {
+            error!("Failed to publish partial transcript event: {}", e);
+        }
+        drop(current_utterance);
+    }
+}
+
 async fn process_utterance(
     bus: &Arc<EventBus>,
     cfg: &SttConfig,
@@ -280,50 +547,26 @@ async fn process_utterance(
         samples, duration, utterance.sample_rate
     );
 
-    // Skip very short utterances (< 200ms)
-    if duration < 200 {
+    // Skip very short utterances
+    if duration < MIN_UTTERANCE_MS {
         info!("Utterance too short, skipping transcription");
         return Ok(());
     }
 
-    // Convert to PCM
-    let pcm = utterance.to_pcm();
-
-    // Write to temporary WAV file
-    let wav_path = cfg.temp_dir.join(format!("utterance_{}.wav", gen_id()));
-    if let Err(e) = write_wav_file(&wav_path, &pcm, utterance.sample_rate, 1) {
-        error!("Failed to write WAV file: {}", e);
-        return Ok(());
-    }
-
-    info!("💾 Wrote WAV file: {:?} ({} samples)", wav_path, pcm.len());
-
-    // Call whisper.cpp
-    let transcript = match transcribe_with_whisper(cfg, &wav_path).await {
+    let transcript = match transcribe_pcm(cfg, &utterance.to_pcm(), utterance.sample_rate).await {
         Ok(text) => text,
         Err(e) => {
             error!("Transcription failed: {}", e);
-            // Clean up WAV file (unless debug mode)
-            if std::env::var("STT_KEEP_WAV").is_err() {
-                let _ = std::fs::remove_file(&wav_path);
-            }
             return Ok(());
         }
     };
 
-    // Clean up WAV file (unless debug mode)
-    let keep_wav = std::env::var("STT_KEEP_WAV").is_ok();
-    if keep_wav {
-        info!("🔍 Kept WAV file for debugging: {:?}", wav_path);
-    } else {
-        let _ = std::fs::remove_file(&wav_path);
-    }
-
     // Publish transcript event
     if !transcript.is_empty() {
         info!("📝 Transcript: {}", transcript);
 
         let mut metadata = HashMap::new();
+        metadata.insert("utterance_id".to_string(), utterance.id.clone());
         metadata.insert("sample_rate".to_string(), utterance.sample_rate.to_string());
         metadata.insert("duration_ms".to_string(), duration.to_string());
         metadata.insert("language".to_string(), cfg.language.clone());
@@ -351,6 +594,27 @@ async fn process_utterance(
     Ok(())
 }
 
+/// Transcribe mono PCM with whisper.cpp through a temporary WAV file
+pub(crate) async fn transcribe_pcm(
+    cfg: &SttConfig,
+    pcm: &[i16],
+    sample_rate: u32,
+) -> Result<String> {
+    let wav_path = cfg.temp_dir.join(format!("utterance_{}.wav", gen_id()));
+    write_wav_file(&wav_path, pcm, sample_rate, 1)?;
+    info!("💾 Wrote WAV file: {:?} ({} samples)", wav_path, pcm.len());
+
+    let transcript = transcribe_with_whisper(cfg, &wav_path).await;
+
+    // Clean up WAV file (unless debug mode)
+    if std::env::var("STT_KEEP_WAV").is_ok() {
+        info!("🔍 Kept WAV file for debugging: {:?}", wav_path);
+    } else {
+        let _ = std::fs::remove_file(&wav_path);
+    }
+    transcript
+}
+
 async fn transcribe_with_whisper(cfg: &SttConfig, wav_path: &PathBuf) -> Result<String> {
     // Build whisper command
     // Example: ./whisper.cpp/main -m ./models/ggml-base.en.bin -f input.wav -l en --no-timestamps
@@ -412,12 +676,15 @@ async fn transcribe_with_whisper(cfg: &SttConfig, wav_path: &PathBuf) -> Result<
     debug!("Running whisper command: {:?}", cmd);
 
     // Run command and capture output
-    let output = tokio::task::spawn_blocking(move || cmd.output())
-        .await
-        .map_err(|e| {
-            loom_core::LoomError::IoError(std::io::Error::new(std::io::ErrorKind::Other, e))
-        })?
-        .map_err(|e| loom_core::LoomError::IoError(e))?;
+    let output = match &cfg.pool {
+        Some(pool) => pool.run_blocking(move || cmd.output()).await?,
+        None => tokio::task::spawn_blocking(move || cmd.output())
+            .await
+            .map_err(|e| {
+                loom_core::LoomError::IoError(std::io::Error::new(std::io::ErrorKind::Other, e))
+            })?,
+    }
+    .map_err(|e| loom_core::LoomError::IoError(e))?;
 
     if !output.status.success() {
         let stderr = String::from_utf8_lossy(&output.stderr);
@@ -0,0 +1,644 @@
+//! Speech-to-text accuracy benchmark.
+//!
+//! Runs a labeled dataset through one or more STT configurations and reports word error
+//! rate (WER) and latency per file and per configuration, so model size, beam width and
+//! VAD settings can be compared on the same recordings.
+//!
+//! A dataset is either a directory of `name.wav` files with `name.txt` references next
+//! to them, or a JSONL manifest with one `{"audio": "clips/a.wav", "text": "..."}` per
+//! line (relative paths are resolved against the manifest's directory).
+//!
+//! Without VAD settings a configuration transcribes each file whole. With them (and the
+//! `vad` feature) the file is first cut into utterances by `VadGate`, exactly as in the
+//! live pipeline, and the utterance transcripts are joined; utterances shorter than
+//! 200ms are skipped like `SttEngine` does.
+//!
+//! ```rust,ignore
+//! let report = SttBenchmark::new(BenchDataset::load(Path::new("data/manifest.jsonl"))?)
+//!     .with_config(BenchConfig::new("base", SttConfig::default()))
+//!     .with_config(BenchConfig::new("small-beam5", SttConfig::default())
+//!         .with_model("models/ggml-small.en.bin")
+//!         .with_beam_size(5))
+//!     .run()
+//!     .await;
+//! println!("{}", report.to_markdown());
+//! ```
+//!
+//! The `loom-stt-bench` binary wraps this for the command line.
+
+use crate::stt::{transcribe_pcm, SttConfig, MIN_UTTERANCE_MS};
+use crate::wav::{invalid, read_wav};
+use loom_core::Result;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+use tracing::{info, warn};
+
+/// One labeled recording
+#[derive(Clone, Debug, PartialEq)]
+pub struct BenchSample {
+    /// 16-bit PCM WAV file
+    pub audio: PathBuf,
+    /// What is said in it
+    pub reference: String,
+}
+
+/// Labeled recordings to benchmark against
+#[derive(Clone, Debug, Default)]
+pub struct BenchDataset {
+    pub samples: Vec<BenchSample>,
+}
+
+#[derive(Deserialize)]
+struct ManifestLine {
+    audio: PathBuf,
+    text: String,
+}
+
+impl BenchDataset {
+    /// Load a directory of WAV/TXT pairs or a JSONL manifest
+    pub fn load(path: &Path) -> Result<Self> {
+        if path.is_dir() {
+            Self::from_dir(path)
+        } else {
+            Self::from_manifest(path)
+        }
+    }
+
+    /// Every `*.wav` in `dir` (sorted) with the `*.txt` of the same name as reference
+    pub fn from_dir(dir: &Path) -> Result<Self> {
+        let mut wavs: Vec<PathBuf> = std::fs::read_dir(dir)?
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|p| p.extension().is_some_and(|ext| ext == "wav"))
+            .collect();
+        wavs.sort();
+        let mut samples = Vec::with_capacity(wavs.len());
+        for audio in wavs {
+            let txt = audio.with_extension("txt");
+            match std::fs::read_to_string(&txt) {
+                Ok(reference) => samples.push(BenchSample {
+                    audio,
+                    reference: reference.trim().to_string(),
+                }),
+                Err(_) => warn!("Skipping {:?}: no reference at {:?}", audio, txt),
+            }
+        }
+        Ok(Self { samples })
+    }
+
+    /// JSONL manifest; blank lines and lines starting with `#` are ignored
+    pub fn from_manifest(path: &Path) -> Result<Self> {
+        let base = path.parent().unwrap_or(Path::new("."));
+        let mut samples = Vec::new();
+        for (n, line) in std::fs::read_to_string(path)?.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let entry: ManifestLine = serde_json::from_str(line)
+                .map_err(|e| invalid(format!("{}:{}: {}", path.display(), n + 1, e)))?;
+            samples.push(BenchSample {
+                audio: base.join(entry.audio),
+                reference: entry.text,
+            });
+        }
+        Ok(Self { samples })
+    }
+
+    pub fn len(&self) -> usize {
+        self.samples.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.samples.is_empty()
+    }
+}
+
+/// VAD segmentation applied before transcription; mirrors `VadConfig`
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BenchVad {
+    /// Aggressiveness 0..=3
+    pub mode: i32,
+    /// 10, 20 or 30
+    pub frame_ms: u32,
+    pub min_start_ms: u32,
+    pub hangover_ms: u32,
+}
+
+impl Default for BenchVad {
+    fn default() -> Self {
+        Self {
+            mode: 2,
+            frame_ms: 20,
+            min_start_ms: 60,
+            hangover_ms: 200,
+        }
+    }
+}
+
+/// One STT setup to benchmark
+#[derive(Clone, Debug)]
+pub struct BenchConfig {
+    /// Label in the report
+    pub name: String,
+    pub stt: SttConfig,
+    /// Segment files with VAD first; None transcribes each file whole
+    pub vad: Option<BenchVad>,
+}
+
+impl BenchConfig {
+    pub fn new(name: impl Into<String>, stt: SttConfig) -> Self {
+        Self {
+            name: name.into(),
+            stt,
+            vad: None,
+        }
+    }
+
+    pub fn with_model(mut self, model: impl Into<PathBuf>) -> Self {
+        self.stt.whisper_model = model.into();
+        self
+    }
+
+    /// Pass `--beam-size` to whisper.cpp
+    pub fn with_beam_size(mut self, beam_size: u32) -> Self {
+        self.stt.extra_args.push("--beam-size".into());
+        self.stt.extra_args.push(beam_size.to_string());
+        self
+    }
+
+    pub fn with_vad(mut self, vad: BenchVad) -> Self {
+        self.vad = Some(vad);
+        self
+    }
+
+    /// Model file, extra whisper arguments and VAD settings, for the report
+    pub fn describe(&self) -> String {
+        let model = self
+            .stt
+            .whisper_model
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let mut parts = vec![model];
+        if !self.stt.extra_args.is_empty() {
+            parts.push(self.stt.extra_args.join(" "));
+        }
+        parts.push(match &self.vad {
+            Some(vad) => format!(
+                "vad mode={} frame={}ms start={}ms hangover={}ms",
+                vad.mode, vad.frame_ms, vad.min_start_ms, vad.hangover_ms
+            ),
+            None => "whole file".to_string(),
+        });
+        parts.join(", ")
+    }
+}
+
+/// A `BenchConfig` as written in a JSON configs file; unset fields keep the base
+/// `SttConfig` (from the environment)
+///
+/// ```json
+/// [
+///   { "name": "base", "model": "models/ggml-base.en.bin" },
+///   { "name": "small-beam5", "model": "models/ggml-small.en.bin", "beam_size": 5 },
+///   { "name": "base-vad3", "model": "models/ggml-base.en.bin", "vad": { "mode": 3 } }
+/// ]
+/// ```
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BenchConfigSpec {
+    pub name: String,
+    pub whisper_bin: Option<PathBuf>,
+    pub model: Option<PathBuf>,
+    pub language: Option<String>,
+    pub beam_size: Option<u32>,
+    pub threads: Option<u32>,
+    pub extra_args: Vec<String>,
+    pub vad: Option<BenchVad>,
+}
+
+impl BenchConfigSpec {
+    /// Read a JSON array of specs
+    pub fn load(path: &Path) -> Result<Vec<Self>> {
+        let text = std::fs::read_to_string(path)?;
+        serde_json::from_str(&text).map_err(|e| invalid(format!("{}: {}", path.display(), e)))
+    }
+
+    pub fn into_config(self, base: &SttConfig) -> BenchConfig {
+        let mut stt = base.clone();
+        if let Some(bin) = self.whisper_bin {
+            stt.whisper_bin = bin;
+        }
+        if let Some(model) = self.model {
+            stt.whisper_model = model;
+        }
+        if let Some(language) = self.language {
+            stt.language = language;
+        }
+        if let Some(threads) = self.threads {
+            stt.extra_args.push("--threads".into());
+            stt.extra_args.push(threads.to_string());
+        }
+        stt.extra_args.extend(self.extra_args);
+        let mut config = BenchConfig::new(self.name, stt);
+        if let Some(beam_size) = self.beam_size {
+            config = config.with_beam_size(beam_size);
+        }
+        config.vad = self.vad;
+        config
+    }
+}
+
+/// Outcome for one file under one configuration
+#[derive(Clone, Debug, Serialize)]
+pub struct FileResult {
+    pub audio: String,
+    pub reference: String,
+    pub hypothesis: String,
+    /// Words in the normalized reference
+    pub reference_words: usize,
+    /// Substitutions, deletions and insertions
+    pub word_errors: usize,
+    pub wer: f64,
+    pub audio_ms: u64,
+    /// Wall time to produce the transcript
+    pub latency_ms: u64,
+    /// Utterances transcribed (1 without VAD)
+    pub segments: usize,
+    /// Why the file could not be transcribed; it then counts as all deletions
+    pub error: Option<String>,
+}
+
+/// Aggregates for one configuration
+#[derive(Clone, Debug, Serialize)]
+pub struct ConfigReport {
+    pub name: String,
+    pub description: String,
+    pub files: Vec<FileResult>,
+    /// Total word errors over total reference words
+    pub wer: f64,
+    pub failed: usize,
+    pub mean_latency_ms: u64,
+    pub p50_latency_ms: u64,
+    pub p95_latency_ms: u64,
+    /// Total latency over total audio duration
+    pub real_time_factor: f64,
+}
+
+impl ConfigReport {
+    fn new(config: &BenchConfig, files: Vec<FileResult>) -> Self {
+        let reference_words: usize = files.iter().map(|f| f.reference_words).sum();
+        let word_errors: usize = files.iter().map(|f| f.word_errors).sum();
+        let mut latencies: Vec<u64> = files
+            .iter()
+            .filter(|f| f.error.is_none())
+            .map(|f| f.latency_ms)
+            .collect();
+        latencies.sort_unstable();
+        let total_latency: u64 = latencies.iter().sum();
+        let audio_ms: u64 = files
+            .iter()
+            .filter(|f| f.error.is_none())
+            .map(|f| f.audio_ms)
+            .sum();
+        Self {
+            name: config.name.clone(),
+            description: config.describe(),
+            failed: files.iter().filter(|f| f.error.is_some()).count(),
+            wer: ratio(word_errors, reference_words),
+            mean_latency_ms: total_latency / (latencies.len().max(1) as u64),
+            p50_latency_ms: percentile(&latencies, 50),
+            p95_latency_ms: percentile(&latencies, 95),
+            real_time_factor: if audio_ms == 0 {
+                0.0
+            } else {
+                total_latency as f64 / audio_ms as f64
+            },
+            files,
+        }
+    }
+}
+
+/// Results of every configuration on the same dataset
+#[derive(Clone, Debug, Serialize)]
+pub struct BenchReport {
+    pub samples: usize,
+    pub configs: Vec<ConfigReport>,
+}
+
+impl BenchReport {
+    /// Configuration with the lowest WER, then the lowest mean latency
+    pub fn best(&self) -> Option<&ConfigReport> {
+        self.configs.iter().min_by(|a, b| {
+            a.wer
+                .total_cmp(&b.wer)
+                .then(a.mean_latency_ms.cmp(&b.mean_latency_ms))
+        })
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap_or_default()
+    }
+
+    /// Comparison table followed by the files each configuration got wrong
+    pub fn to_markdown(&self) -> String {
+        let mut out = format!("# STT benchmark ({} files)\n\n", self.samples);
+        out.push_str("| Config | Setup | WER | Failed | Mean ms | p50 ms | p95 ms | RTF |\n");
+        out.push_str("|---|---|---|---|---|---|---|---|\n");
+        for c in &self.configs {
+            out.push_str(&format!(
+                "| {} | {} | {:.1}% | {} | {} | {} | {} | {:.2} |\n",
+                c.name,
+                c.description,
+                c.wer * 100.0,
+                c.failed,
+                c.mean_latency_ms,
+                c.p50_latency_ms,
+                c.p95_latency_ms,
+                c.real_time_factor
+            ));
+        }
+        if let Some(best) = self.best() {
+            out.push_str(&format!(
+                "\nBest: {} ({:.1}% WER)\n",
+                best.name,
+                best.wer * 100.0
+            ));
+        }
+        for c in &self.configs {
+            let misses: Vec<&FileResult> = c.files.iter().filter(|f| f.word_errors > 0).collect();
+            if misses.is_empty() {
+                continue;
+            }
+            out.push_str(&format!("\n## {}\n\n", c.name));
+            for f in misses {
+                match &f.error {
+                    Some(error) => out.push_str(&format!("- {}: error: {}\n", f.audio, error)),
+                    None => out.push_str(&format!(
+                        "- {} ({:.1}% WER): \"{}\" → \"{}\"\n",
+                        f.audio,
+                        f.wer * 100.0,
+                        f.reference,
+                        f.hypothesis
+                    )),
+                }
+            }
+        }
+        out
+    }
+}
+
+/// Runs a dataset through each configuration in turn
+pub struct SttBenchmark {
+    dataset: BenchDataset,
+    configs: Vec<BenchConfig>,
+}
+
+impl SttBenchmark {
+    pub fn new(dataset: BenchDataset) -> Self {
+        Self {
+            dataset,
+            configs: Vec::new(),
+        }
+    }
+
+    pub fn with_config(mut self, config: BenchConfig) -> Self {
+        self.configs.push(config);
+        self
+    }
+
+    /// Benchmark every configuration; files are transcribed one at a time so latencies
+    /// are comparable
+    pub async fn run(&self) -> BenchReport {
+        let mut configs = Vec::with_capacity(self.configs.len());
+        for config in &self.configs {
+            info!(
+                "Benchmarking STT config {} ({})",
+                config.name,
+                config.describe()
+            );
+            let mut files = Vec::with_capacity(self.dataset.len());
+            for sample in &self.dataset.samples {
+                files.push(run_file(config, sample).await);
+            }
+            configs.push(ConfigReport::new(config, files));
+        }
+        BenchReport {
+            samples: self.dataset.len(),
+            configs,
+        }
+    }
+}
+
+async fn run_file(config: &BenchConfig, sample: &BenchSample) -> FileResult {
+    let reference_words = normalize(&sample.reference).len();
+    let mut result = FileResult {
+        audio: sample.audio.display().to_string(),
+        reference: sample.reference.clone(),
+        hypothesis: String::new(),
+        reference_words,
+        word_errors: reference_words,
+        wer: ratio(reference_words, reference_words),
+        audio_ms: 0,
+        latency_ms: 0,
+        segments: 0,
+        error: None,
+    };
+    match transcribe_file(config, &sample.audio, &mut result).await {
+        Ok(hypothesis) => {
+            let (errors, words) = word_errors(&sample.reference, &hypothesis);
+            result.word_errors = errors;
+            result.wer = ratio(errors, words);
+            result.hypothesis = hypothesis;
+        }
+        Err(e) => {
+            warn!("{}: {:?} failed: {}", config.name, sample.audio, e);
+            result.error = Some(e.to_string());
+        }
+    }
+    result
+}
+
+/// Transcript of one file, filling in its duration, latency and segment count
+async fn transcribe_file(
+    config: &BenchConfig,
+    audio: &Path,
+    result: &mut FileResult,
+) -> Result<String> {
+    let cfg = &config.stt;
+    if !cfg.whisper_bin.exists() || !cfg.whisper_model.exists() {
+        return Err(invalid(format!(
+            "whisper binary {:?} or model {:?} not found",
+            cfg.whisper_bin, cfg.whisper_model
+        )));
+    }
+    let clip = read_wav(audio)?;
+    result.audio_ms = clip.duration_ms();
+    let pcm = clip.mono();
+
+    let (segments, segmenting) = match &config.vad {
+        Some(vad) => segment(vad, &pcm, clip.sample_rate_hz).await?,
+        None => (vec![pcm], Duration::ZERO),
+    };
+
+    let transcribing = Instant::now();
+    let mut texts = Vec::new();
+    for segment in &segments {
+        let ms = segment.len() as u64 * 1000 / clip.sample_rate_hz.max(1) as u64;
+        if config.vad.is_some() && ms < MIN_UTTERANCE_MS {
+            continue;
+        }
+        let text = transcribe_pcm(cfg, segment, clip.sample_rate_hz).await?;
+        result.segments += 1;
+        if !text.is_empty() {
+            texts.push(text);
+        }
+    }
+    result.latency_ms = (segmenting + transcribing.elapsed()).as_millis() as u64;
+    Ok(texts.join(" "))
+}
+
+/// Cut `pcm` into utterances with `VadGate` on a private EventBus. Returns them with the
+/// time VAD took, excluding the quiet period used to detect that it is done.
+#[cfg(feature = "vad")]
+async fn segment(
+    settings: &BenchVad,
+    pcm: &[i16],
+    sample_rate: u32,
+) -> Result<(Vec<Vec<i16>>, Duration)> {
+    use crate::utils::{audio_chunk_event, decode_pcm16le};
+    use crate::vad::{VadConfig, VadGate};
+    use loom_core::{messaging::EventBus, QoSLevel};
+    use std::sync::Arc;
+
+    const INPUT_TOPIC: &str = "bench.audio";
+    // Boundaries and frames share a topic so they arrive in order
+    const OUTPUT_TOPIC: &str = "bench.vad";
+    const CHUNK_MS: usize = 100;
+    const QUIET: Duration = Duration::from_millis(150);
+
+    let bus = Arc::new(EventBus::new().await?);
+    bus.start().await?;
+    let (_sub, mut rx) = bus
+        .subscribe(OUTPUT_TOPIC.into(), vec![], QoSLevel::QosBatched)
+        .await?;
+    let subscribed = bus.subscription_count();
+    let gate = VadGate::new(
+        Arc::clone(&bus),
+        VadConfig {
+            input_topic: INPUT_TOPIC.into(),
+            voiced_topic: OUTPUT_TOPIC.into(),
+            vad_topic: OUTPUT_TOPIC.into(),
+            mode: settings.mode,
+            frame_ms: settings.frame_ms,
+            min_start_ms: settings.min_start_ms,
+            hangover_ms: settings.hangover_ms,
+        },
+    )
+    .start()
+    .await?;
+    while bus.subscription_count() == subscribed && !gate.is_finished() {
+        tokio::time::sleep(Duration::from_millis(5)).await;
+    }
+
+    let started = Instant::now();
+    // Trailing silence lets the hangover close an utterance running to the end
+    let mut audio = pcm.to_vec();
+    let tail_ms = (settings.hangover_ms + 2 * settings.frame_ms) as usize;
+    audio.resize(audio.len() + sample_rate as usize * tail_ms / 1000, 0);
+    let chunk_len = (sample_rate as usize * CHUNK_MS / 1000).max(1);
+    for chunk in audio.chunks(chunk_len) {
+        let event = audio_chunk_event(chunk, sample_rate, 1, "bench", "stt.bench");
+        bus.publish(INPUT_TOPIC, event).await?;
+        tokio::task::yield_now().await;
+    }
+
+    let mut segments = Vec::new();
+    let mut current: Option<Vec<i16>> = None;
+    let mut last_event = Instant::now();
+    while let Ok(Some(event)) = tokio::time::timeout(QUIET, rx.recv()).await {
+        last_event = Instant::now();
+        match event.r#type.as_str() {
+            "vad.speech_start" => current = Some(Vec::new()),
+            "audio_voiced" => {
+                if let Some(utterance) = current.as_mut() {
+                    utterance.extend(decode_pcm16le(&event.payload));
+                }
+            }
+            "vad.speech_end" => segments.extend(current.take()),
+            _ => {}
+        }
+    }
+    segments.extend(current.take());
+
+    gate.abort();
+    let _ = bus.shutdown().await;
+    Ok((segments, last_event.duration_since(started)))
+}
+
+#[cfg(not(feature = "vad"))]
+async fn segment(
+    _settings: &BenchVad,
+    _pcm: &[i16],
+    _sample_rate: u32,
+) -> Result<(Vec<Vec<i16>>, Duration)> {
+    Err(invalid(
+        "VAD settings need loom-audio's `vad` feature".to_string(),
+    ))
+}
+
+/// Word-level edit distance between `reference` and `hypothesis` after normalization,
+/// and the number of reference words: `(errors, reference_words)`
+pub fn word_errors(reference: &str, hypothesis: &str) -> (usize, usize) {
+    let reference = normalize(reference);
+    let hypothesis = normalize(hypothesis);
+    // Levenshtein over words with a single rolling row
+    let mut row: Vec<usize> = (0..=hypothesis.len()).collect();
+    for (i, r) in reference.iter().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, h) in hypothesis.iter().enumerate() {
+            let substitution = diagonal + usize::from(r != h);
+            diagonal = row[j + 1];
+            row[j + 1] = substitution.min(row[j] + 1).min(diagonal + 1);
+        }
+    }
+    (row[hypothesis.len()], reference.len())
+}
+
+/// Lowercased words with punctuation dropped (apostrophes kept)
+fn normalize(text: &str) -> Vec<String> {
+    text.to_lowercase()
+        .chars()
+        .map(|c| {
+            if c.is_alphanumeric() || c == '\'' {
+                c
+            } else {
+                ' '
+            }
+        })
+        .collect::<String>()
+        .split_whitespace()
+        .map(str::to_string)
+        .collect()
+}
+
+fn ratio(errors: usize, words: usize) -> f64 {
+    match (errors, words) {
+        (0, _) => 0.0,
+        // Anything said over an empty reference is fully wrong
+        (_, 0) => 1.0,
+        _ => errors as f64 / words as f64,
+    }
+}
+
+/// Nearest-rank percentile of sorted values; 0 when empty
+fn percentile(sorted: &[u64], p: usize) -> u64 {
+    if sorted.is_empty() {
+        return 0;
+    }
+    let rank = (p * sorted.len()).div_ceil(100).max(1);
+    sorted[rank - 1]
+}
@@ -6,6 +6,12 @@
 //! - Fallback to espeak-ng (widely available)
 //! - If neither present, logs the text and returns OK
 //!
+//! With `TtsPlayback::Buffer` (`TTS_PLAYBACK=buffer`) nothing is played: the rendered WAV
+//! is returned base64-encoded in the tool result and published as a `tts.audio` event,
+//! so servers without audio output and tests can check synthesis programmatically. When
+//! no engine is installed, buffer mode renders a deterministic synthetic waveform (one
+//! tone burst per word) instead of only logging the text.
+//!
 //! Headers supported on ActionCall:
 //! - voice: string (piper voice model path or name; espeak voice code)
 //! - rate:  float (0.5–2.0, default 1.0)
@@ -17,26 +23,66 @@
 //! - PIPER_BIN, PIPER_VOICE, PIPER_VOICE_DIR
 //! - ESPEAK_BIN
 //! - TTS_TIMEOUT_MS, TTS_TEMP_DIR, TTS_TOPIC
+//! - TTS_PLAYBACK (player|buffer)
+//!
+//! Blocking work runs on `TtsSpeakProviderConfig::pool` when set (e.g. `Loom::pools.audio`).
+//!
+//! Playback can be cut short with the companion `tts.stop` tool (see
+//! `TtsSpeakProvider::stop_tool`), e.g. when the user barges in: the player is killed and
+//! the interrupted `tts.speak` call reports `interrupted: true`.
 //!
 //! Emits observability events on `tts` topic by default:
-//! - tts.start, tts.done, tts.error
+//! - tts.start, tts.done, tts.error, tts.stop
+//! - tts.audio (buffer playback only; payload is the WAV file)
 
 use crate::utils::{gen_id, now_ms};
 use async_trait::async_trait;
 use loom_core::messaging::EventBus;
 use loom_core::proto::Event;
 use loom_core::tools::{Tool, ToolResult};
+use loom_core::DedicatedPool;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fs::File;
+use std::future::Future;
 use std::io::{Read, Write};
 use std::path::{Path, PathBuf};
+use std::pin::Pin;
 use std::process::{Command, Stdio};
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 use tokio::task;
 use tokio::time::{timeout, Duration};
 use tracing::{debug, info, warn};
 
+/// What happens to synthesized speech
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TtsPlayback {
+    /// Play the WAV with aplay, paplay or ffplay and keep it on disk
+    #[default]
+    Player,
+    /// Keep the WAV in memory: return it in the tool result and publish it as `tts.audio`
+    Buffer,
+}
+
+impl TtsPlayback {
+    /// Parse `player` or `buffer` (`null` is accepted for the latter)
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "player" => Some(Self::Player),
+            "buffer" | "null" => Some(Self::Buffer),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Player => "player",
+            Self::Buffer => "buffer",
+        }
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct TtsSpeakProviderConfig {
     pub temp_dir: PathBuf,
@@ -47,6 +93,10 @@ pub struct TtsSpeakProviderConfig {
     pub piper_voice: Option<PathBuf>,
     pub piper_voice_dir: Option<PathBuf>,
     pub espeak_bin: Option<PathBuf>,
+    /// Play speech or only render it (see `TtsPlayback`)
+    pub playback: TtsPlayback,
+    /// Dedicated pool for synthesis + playback (defaults to the global blocking pool)
+    pub pool: Option<Arc<DedicatedPool>>,
 }
 
 impl Default for TtsSpeakProviderConfig {
@@ -66,6 +116,10 @@ impl Default for TtsSpeakProviderConfig {
         let piper_voice_dir = std::env::var("PIPER_VOICE_DIR").ok().map(PathBuf::from);
         let espeak_bin =
             get_from_env_or_path("ESPEAK_BIN", "espeak-ng").or_else(|| get_from_path("espeak"));
+        let playback = std::env::var("TTS_PLAYBACK")
+            .ok()
+            .and_then(|s| TtsPlayback::parse(&s))
+            .unwrap_or_default();
 
         Self {
             temp_dir,
@@ -76,6 +130,8 @@ impl Default for TtsSpeakProviderConfig {
             piper_voice,
             piper_voice_dir,
             espeak_bin,
+            playback,
+            pool: None,
         }
     }
 }
@@ -112,6 +168,8 @@ fn get_from_path(bin: &str) -> Option<PathBuf> {
 pub struct TtsSpeakProvider {
     bus: Arc<EventBus>,
     cfg: TtsSpeakProviderConfig,
+    /// Bumped by `tts.stop`; speech started under an older value is interrupted
+    stops: Arc<AtomicU64>,
 }
 
 impl TtsSpeakProvider {
@@ -124,7 +182,65 @@ impl TtsSpeakProvider {
         if let Some(ref e) = cfg.espeak_bin {
             info!(target = "tts", bin = ?e, "Detected espeak-ng binary");
         }
-        Self { bus, cfg }
+        if cfg.playback == TtsPlayback::Buffer {
+            info!(
+                target = "tts",
+                "Rendering speech to buffers; playback disabled"
+            );
+        }
+        Self {
+            bus,
+            cfg,
+            stops: Arc::new(AtomicU64::new(0)),
+        }
+    }
+
+    /// The `tts.stop` tool interrupting this provider's speech; register it alongside
+    pub fn stop_tool(&self) -> TtsStopTool {
+        TtsStopTool {
+            bus: Arc::clone(&self.bus),
+            topic: self.cfg.topic.clone(),
+            stops: Arc::clone(&self.stops),
+        }
+    }
+}
+
+/// Native `tts.stop` capability: interrupts speech in progress or being synthesized
+pub struct TtsStopTool {
+    bus: Arc<EventBus>,
+    topic: String,
+    stops: Arc<AtomicU64>,
+}
+
+#[async_trait]
+impl Tool for TtsStopTool {
+    fn name(&self) -> String {
+        "tts.stop".to_string()
+    }
+
+    fn description(&self) -> String {
+        "Stops speech started by tts.speak".to_string()
+    }
+
+    fn parameters(&self) -> serde_json::Value {
+        serde_json::json!({ "type": "object", "properties": {} })
+    }
+
+    async fn call(&self, _arguments: serde_json::Value) -> ToolResult<serde_json::Value> {
+        self.stops.fetch_add(1, Ordering::SeqCst);
+        let ev = Event {
+            id: gen_id(),
+            r#type: "tts.stop".to_string(),
+            timestamp_ms: now_ms(),
+            source: "tts".to_string(),
+            metadata: HashMap::new(),
+            payload: Vec::new(),
+            confidence: 1.0,
+            tags: vec![],
+            priority: 50,
+        };
+        let _ = self.bus.publish(&self.topic, ev).await;
+        Ok(serde_json::json!({ "stopped": true }))
     }
 }
 
@@ -184,8 +300,13 @@ impl Tool for TtsSpeakProvider {
         let sample_rate = payload.sample_rate.unwrap_or(self.cfg.default_sample_rate);
         let player_pref = payload.player;
 
+        let stops = Arc::clone(&self.stops);
+        let stops_at_start = stops.load(Ordering::SeqCst);
         let engine = select_engine(&self.cfg, &voice);
-        let player = select_player(player_pref.as_deref());
+        let player = match self.cfg.playback {
+            TtsPlayback::Player => select_player(player_pref.as_deref()),
+            TtsPlayback::Buffer => None,
+        };
 
         // start event
         let mut meta = HashMap::new();
@@ -195,6 +316,10 @@ impl Tool for TtsSpeakProvider {
         meta.insert("volume".to_string(), volume.to_string());
         meta.insert("sample_rate".to_string(), sample_rate.to_string());
         meta.insert("player".to_string(), player.clone().unwrap_or_default());
+        meta.insert(
+            "playback".to_string(),
+            self.cfg.playback.as_str().to_string(),
+        );
 
         let start_event = Event {
             id: gen_id(),
@@ -245,7 +370,7 @@ impl Tool for TtsSpeakProvider {
         let t0 = now_ms();
         let meta_for_timeout = meta.clone();
 
-        let join = task::spawn_blocking(move || {
+        let work = move || {
             let wav_path = cfg.temp_dir.join(format!("tts_{}.wav", gen_id()));
             let synthesis_ms: i64;
             let playback_ms: i64;
@@ -256,6 +381,10 @@ impl Tool for TtsSpeakProvider {
                 "espeak-ng" => {
                     synth_with_espeak(&cfg, &voice, rate, volume, sample_rate, &text, &wav_path)
                 }
+                "synthetic" => {
+                    std::fs::write(&wav_path, synthetic_wav(&text, rate, volume, sample_rate))
+                        .map_err(loom_core::LoomError::IoError)
+                }
                 _ => Ok(()),
             };
             synthesis_ms = now_ms() - synth_start;
@@ -285,17 +414,67 @@ impl Tool for TtsSpeakProvider {
                 }
             }
 
-            // Playback
+            // Playback, unless stopped during synthesis
+            let stopped = || stops.load(Ordering::SeqCst) != stops_at_start;
+            let mut interrupted = stopped();
+            if cfg.playback == TtsPlayback::Buffer {
+                let wav = std::fs::read(&wav_path)
+                    .map_err(|e| loom_core::ToolError::ExecutionFailed(e.to_string()))?;
+                let _ = std::fs::remove_file(&wav_path);
+                let audio_ms = wav_duration_ms(&wav);
+                let publish =
+                    |r#type: &str, metadata: HashMap<String, String>, payload: Vec<u8>| {
+                        let ev = Event {
+                            id: gen_id(),
+                            r#type: r#type.to_string(),
+                            timestamp_ms: now_ms(),
+                            source: "tts".to_string(),
+                            metadata,
+                            payload,
+                            confidence: 1.0,
+                            tags: vec![],
+                            priority: 50,
+                        };
+                        let _ = tokio::runtime::Handle::current().block_on(bus.publish(&topic, ev));
+                    };
+
+                // Stopped speech is not handed to downstream sinks
+                if !interrupted {
+                    let mut meta_audio = meta.clone();
+                    meta_audio.insert("encoding".into(), "wav".into());
+                    meta_audio.insert("audio_ms".into(), audio_ms.to_string());
+                    publish("tts.audio", meta_audio, wav.clone());
+                }
+                let mut meta_done = meta.clone();
+                meta_done.insert("synthesis_ms".into(), synthesis_ms.to_string());
+                meta_done.insert("total_ms".into(), (now_ms() - t0).to_string());
+                meta_done.insert("audio_ms".into(), audio_ms.to_string());
+                meta_done.insert("audio_bytes".into(), wav.len().to_string());
+                meta_done.insert("interrupted".into(), interrupted.to_string());
+                publish("tts.done", meta_done, Vec::new());
+
+                return Ok(serde_json::json!({
+                    "engine": engine,
+                    "voice": voice,
+                    "rate": rate,
+                    "volume": volume,
+                    "sample_rate": sample_rate,
+                    "playback": "buffer",
+                    "audio_ms": audio_ms,
+                    "wav_base64": (!interrupted).then(|| base64_encode(&wav)),
+                    "interrupted": interrupted,
+                }));
+            }
             let play_start = now_ms();
-            if wav_path.exists() {
+            if wav_path.exists() && !interrupted {
                 if let Some(bin) = player.as_ref().and_then(|name| get_from_path(name)) {
-                    let _ = play_wav_with(&bin, &wav_path);
+                    interrupted = play_wav_with(&bin, &wav_path, &stopped).unwrap_or(false);
                 } else {
                     if let Some(bin) = get_from_path("aplay")
                         .or_else(|| get_from_path("paplay"))
                         .or_else(|| get_from_path("ffplay"))
                     {
-                        let _ = play_wav_with(&bin, &wav_path);
+                        interrupted = play_wav_with(&bin, &wav_path, &stopped).unwrap_or(false);
                     } else {
                         info!(target = "tts", path = ?wav_path, "No audio player found; kept WAV on disk");
                     }
@@ -309,6 +488,7 @@ impl Tool for TtsSpeakProvider {
             meta_done.insert("playback_ms".into(), playback_ms.to_string());
             meta_done.insert("total_ms".into(), duration_ms.to_string());
             meta_done.insert("wav_path".into(), wav_path.to_string_lossy().to_string());
+            meta_done.insert("interrupted".into(), interrupted.to_string());
 
             let ev = Event {
                 id: gen_id(),
@@ -331,12 +511,31 @@ impl Tool for TtsSpeakProvider {
                 "sample_rate": sample_rate,
                 "player": player,
                 "wav_path": wav_path.to_string_lossy(),
+                "interrupted": interrupted,
             }))
-        });
+        };
+
+        // Run on the dedicated audio pool when configured, else the global blocking pool
+        let join: Pin<Box<dyn Future<Output = ToolResult<serde_json::Value>> + Send>> =
+            match &self.cfg.pool {
+                Some(pool) => {
+                    let pool = Arc::clone(pool);
+                    Box::pin(async move {
+                        pool.run_blocking(work)
+                            .await
+                            .map_err(|e| loom_core::ToolError::Internal(e.to_string()))?
+                    })
+                }
+                None => Box::pin(async move {
+                    task::spawn_blocking(work)
+                        .await
+                        .map_err(|e| loom_core::ToolError::Internal(e.to_string()))?
+                }),
+            };
 
         // Apply internal timeout
         match timeout(Duration::from_millis(self.cfg.timeout_ms), join).await {
-            Ok(join_res) => join_res.map_err(|e| loom_core::ToolError::Internal(e.to_string()))?,
+            Ok(res) => res,
             Err(_) => {
                 let ev = Event {
                     id: gen_id(),
@@ -367,6 +566,9 @@ fn select_engine(cfg: &TtsSpeakProviderConfig, _voice_header: &str) -> String {
     if cfg.espeak_bin.is_some() {
         return "espeak-ng".into();
     }
+    if cfg.playback == TtsPlayback::Buffer {
+        return "synthetic".into();
+    }
     "none".into()
 }
 
@@ -481,30 +683,32 @@ fn synth_with_espeak(
     Ok(())
 }
 
-fn play_wav_with(player_bin: &Path, wav_path: &Path) -> std::io::Result<()> {
+/// Play `wav_path`, killing the player once `stopped` returns true; Ok(true) if it did
+fn play_wav_with(
+    player_bin: &Path,
+    wav_path: &Path,
+    stopped: &dyn Fn() -> bool,
+) -> std::io::Result<bool> {
     let name = player_bin
         .file_name()
         .and_then(|s| s.to_str())
         .unwrap_or("");
-    match name {
-        "aplay" => {
-            Command::new(player_bin).arg(wav_path).status()?;
-        }
-        "paplay" => {
-            Command::new(player_bin).arg(wav_path).status()?;
-        }
-        "ffplay" => {
-            Command::new(player_bin)
-                .arg("-autoexit")
-                .arg("-nodisp")
-                .arg(wav_path)
-                .status()?;
+    let mut cmd = Command::new(player_bin);
+    if name == "ffplay" {
+        cmd.arg("-autoexit").arg("-nodisp");
+    }
+    let mut child = cmd.arg(wav_path).spawn()?;
+    loop {
+        if child.try_wait()?.is_some() {
+            return Ok(false);
         }
-        _ => {
-            Command::new(player_bin).arg(wav_path).status()?;
+        if stopped() {
+            let _ = child.kill();
+            let _ = child.wait();
+            return Ok(true);
         }
+        std::thread::sleep(std::time::Duration::from_millis(10));
     }
-    Ok(())
 }
 
 fn scale_wav_pcm16_inplace(path: &Path, gain: f32) -> std::io::Result<()> {
@@ -545,6 +749,106 @@ fn scale_wav_pcm16_inplace(path: &Path, gain: f32) -> std::io::Result<()> {
     Ok(())
 }
 
+/// Stand-in speech for buffer playback without an engine: one tone burst per word, its
+/// length following the word's, separated by short pauses. Deterministic for a given
+/// text and settings.
+fn synthetic_wav(text: &str, rate: f32, volume: f32, sample_rate: u32) -> Vec<u8> {
+    let rate_hz = sample_rate.max(8_000) as f32;
+    let samples_for = |ms: f32| (rate_hz * ms / 1000.0 / rate) as usize;
+    let amplitude = (i16::MAX as f32 * 0.3 * volume).min(i16::MAX as f32);
+    let fade = samples_for(10.0).max(1);
+
+    let mut samples: Vec<i16> = Vec::new();
+    for word in text.split_whitespace() {
+        let chars = word.chars().count() as f32;
+        let len = samples_for((60.0 * chars).clamp(120.0, 600.0));
+        let freq = 180.0 + 20.0 * (word.len() % 5) as f32;
+        for i in 0..len {
+            let envelope = (i.min(len - 1 - i) as f32 / fade as f32).min(1.0);
+            let phase = 2.0 * std::f32::consts::PI * freq * i as f32 / rate_hz;
+            samples.push((phase.sin() * amplitude * envelope) as i16);
+        }
+        samples.resize(samples.len() + samples_for(80.0), 0);
+    }
+    encode_wav(&samples, sample_rate.max(8_000), 1)
+}
+
+/// Mono or interleaved PCM16 samples as a WAV file
+fn encode_wav(samples: &[i16], sample_rate: u32, channels: u16) -> Vec<u8> {
+    let data_len = (samples.len() * 2) as u32;
+    let block_align = channels * 2;
+    let mut wav = Vec::with_capacity(44 + data_len as usize);
+    wav.extend_from_slice(b"RIFF");
+    wav.extend_from_slice(&(36 + data_len).to_le_bytes());
+    wav.extend_from_slice(b"WAVEfmt ");
+    wav.extend_from_slice(&16u32.to_le_bytes());
+    wav.extend_from_slice(&1u16.to_le_bytes());
+    wav.extend_from_slice(&channels.to_le_bytes());
+    wav.extend_from_slice(&sample_rate.to_le_bytes());
+    wav.extend_from_slice(&(sample_rate * block_align as u32).to_le_bytes());
+    wav.extend_from_slice(&block_align.to_le_bytes());
+    wav.extend_from_slice(&16u16.to_le_bytes());
+    wav.extend_from_slice(b"data");
+    wav.extend_from_slice(&data_len.to_le_bytes());
+    for sample in samples {
+        wav.extend_from_slice(&sample.to_le_bytes());
+    }
+    wav
+}
+
+/// Playing time of a PCM WAV file from its `fmt ` and `data` chunks; 0 if unreadable
+fn wav_duration_ms(wav: &[u8]) -> u64 {
+    if wav.len() < 12 || &wav[0..4] != b"RIFF" || &wav[8..12] != b"WAVE" {
+        return 0;
+    }
+    let mut byte_rate = 0u64;
+    let mut idx = 12;
+    while idx + 8 <= wav.len() {
+        let sz =
+            u32::from_le_bytes([wav[idx + 4], wav[idx + 5], wav[idx + 6], wav[idx + 7]]) as usize;
+        match &wav[idx..idx + 4] {
+            b"fmt " if idx + 20 <= wav.len() => {
+                byte_rate = u32::from_le_bytes([
+                    wav[idx + 16],
+                    wav[idx + 17],
+                    wav[idx + 18],
+                    wav[idx + 19],
+                ]) as u64;
+            }
+            // Streaming writers (piper to a pipe) leave the size at 0 or u32::MAX
+            b"data" if byte_rate > 0 => {
+                let len = sz.min(wav.len() - idx - 8) as u64;
+                return len * 1000 / byte_rate;
+            }
+            _ => {}
+        }
+        idx += 8 + sz + (sz & 1);
+    }
+    0
+}
+
+/// Standard base64 with padding
+fn base64_encode(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut out = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let b = [
+            chunk[0],
+            *chunk.get(1).unwrap_or(&0),
+            *chunk.get(2).unwrap_or(&0),
+        ];
+        let n = (b[0] as u32) << 16 | (b[1] as u32) << 8 | b[2] as u32;
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(ALPHABET[(n >> (18 - 6 * i) & 63) as usize] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
+
 fn select_player(pref: Option<&str>) -> Option<String> {
     if let Some(p) = pref {
         if get_from_path(p).is_some() {
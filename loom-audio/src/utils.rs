@@ -1,5 +1,7 @@
 //! Shared audio utilities.
 
+use loom_core::proto::Event;
+use std::collections::HashMap;
 use std::time::{SystemTime, UNIX_EPOCH};
 
 /// Monotonic-ish timestamp in milliseconds since UNIX epoch.
@@ -22,3 +24,46 @@ pub(crate) fn gen_id() -> String {
         .unwrap_or(0);
     format!("{:x}", nanos)
 }
+
+/// Decode little-endian PCM16 bytes; a trailing odd byte is ignored.
+#[inline]
+pub(crate) fn decode_pcm16le(payload: &[u8]) -> Vec<i16> {
+    payload
+        .chunks_exact(2)
+        .map(|b| i16::from_le_bytes([b[0], b[1]]))
+        .collect()
+}
+
+/// Build the `audio_chunk` event published by capture sources (real or mock).
+pub(crate) fn audio_chunk_event(
+    samples: &[i16],
+    sample_rate_hz: u32,
+    channels: u16,
+    device: &str,
+    source: &str,
+) -> Event {
+    // Serialize to little-endian bytes
+    let mut payload = Vec::with_capacity(samples.len() * 2);
+    for sample in samples {
+        payload.extend_from_slice(&sample.to_le_bytes());
+    }
+
+    let mut metadata: HashMap<String, String> = HashMap::new();
+    metadata.insert("sample_rate".into(), sample_rate_hz.to_string());
+    metadata.insert("channels".into(), channels.to_string());
+    metadata.insert("device".into(), device.to_string());
+    metadata.insert("encoding".into(), "pcm_s16le".into());
+    metadata.insert("frame_samples".into(), samples.len().to_string());
+
+    Event {
+        id: gen_id(),
+        r#type: "audio_chunk".into(),
+        timestamp_ms: now_ms(),
+        source: source.to_string(),
+        metadata,
+        payload,
+        confidence: 1.0,
+        tags: vec![],
+        priority: 90,
+    }
+}
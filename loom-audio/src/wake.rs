@@ -1,15 +1,52 @@
 use crate::utils::{gen_id, now_ms};
-use loom_core::{messaging::EventBus, proto::Event, QoSLevel, Result};
-use std::collections::HashMap;
+use loom_core::cognitive::llm::tiers::LATENCY_CLASS_KEY;
+use loom_core::{messaging::EventBus, proto::Event, Envelope, QoSLevel, Result};
+use std::collections::{HashMap, VecDeque};
+use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 use tokio::sync::Mutex;
 use tokio::task::JoinHandle;
-use tracing::{debug, info, warn};
+use tracing::{debug, error, info, warn};
 
-/// Configuration for wake word detection driven by transcripts
+/// Where wake decisions come from
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WakeMode {
+    /// Match wake phrases in final transcripts (no extra model)
+    Transcript,
+    /// Score `audio_voiced` frames with a keyword-spotting model; transcripts only carry
+    /// the query. Falls back to `Transcript` when no model can be loaded.
+    Acoustic,
+}
+
+impl WakeMode {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "transcript" | "text" => Some(Self::Transcript),
+            "acoustic" | "audio" => Some(Self::Acoustic),
+            _ => None,
+        }
+    }
+}
+
+/// Keyword-spotting model scoring raw audio windows
+pub trait WakeModel: Send {
+    /// Reported as the `phrase` of acoustic wake events
+    fn name(&self) -> &str;
+
+    /// Sample rate the model expects; chunks at other rates are skipped
+    fn sample_rate(&self) -> u32 {
+        16_000
+    }
+
+    /// Wake probability (0.0-1.0) for one window of mono samples in -1.0..1.0
+    fn score(&mut self, window: &[f32]) -> Result<f32>;
+}
+
+/// Configuration for wake word detection (transcript or acoustic)
 #[derive(Clone, Debug)]
 pub struct WakeWordConfig {
-    /// Topic to listen for final transcripts
+    /// Topic to listen for final (and partial) transcripts
     pub transcript_topic: String,
     /// Topic to publish wake events
     pub wake_topic: String,
@@ -25,6 +62,27 @@ pub struct WakeWordConfig {
     pub jaro_winkler_threshold: f64,
     /// Optional: minimum characters in query after removing wake phrase to consider same-utterance query
     pub min_query_chars: usize,
+    /// Transcript matching or acoustic model (`WAKE_MODE`)
+    pub mode: WakeMode,
+    /// Topic with voiced audio frames for acoustic mode
+    pub voiced_topic: String,
+    /// ONNX keyword-spotting model (requires the `wake-onnx` feature)
+    pub model_path: Option<PathBuf>,
+    /// Minimum model score that counts as a wake
+    pub acoustic_threshold: f32,
+    /// Audio window scored by the model (ms)
+    pub window_ms: u32,
+    /// New audio between two scores (ms)
+    pub hop_ms: u32,
+    /// Quiet period after an acoustic wake (ms)
+    pub refractory_ms: u64,
+    /// Topic the answer to each query is requested on (`WAKE_REPLY_TOPIC`); when set,
+    /// queries carry an envelope expecting a reply there, correlated by session id
+    pub reply_topic: Option<String>,
+    /// Also match wake phrases in the stable words of `transcript.partial` events
+    /// (`WAKE_ON_PARTIAL`), waking before the user finishes the utterance; the query is
+    /// still taken from the final transcript. Transcript mode only.
+    pub wake_on_partial: bool,
 }
 
 impl Default for WakeWordConfig {
@@ -58,8 +116,74 @@ impl Default for WakeWordConfig {
                 .ok()
                 .and_then(|s| s.parse::<usize>().ok())
                 .unwrap_or(4),
+            mode: std::env::var("WAKE_MODE")
+                .ok()
+                .and_then(|s| WakeMode::parse(&s))
+                .unwrap_or(WakeMode::Transcript),
+            voiced_topic: std::env::var("WAKE_VOICED_TOPIC")
+                .unwrap_or_else(|_| "audio.voiced".into()),
+            model_path: std::env::var("WAKE_MODEL_PATH").ok().map(PathBuf::from),
+            acoustic_threshold: std::env::var("WAKE_THRESHOLD")
+                .ok()
+                .and_then(|s| s.parse::<f32>().ok())
+                .unwrap_or(0.5),
+            window_ms: std::env::var("WAKE_WINDOW_MS")
+                .ok()
+                .and_then(|s| s.parse::<u32>().ok())
+                .unwrap_or(1280),
+            hop_ms: std::env::var("WAKE_HOP_MS")
+                .ok()
+                .and_then(|s| s.parse::<u32>().ok())
+                .unwrap_or(80),
+            refractory_ms: std::env::var("WAKE_REFRACTORY_MS")
+                .ok()
+                .and_then(|s| s.parse::<u64>().ok())
+                .unwrap_or(2000),
+            reply_topic: std::env::var("WAKE_REPLY_TOPIC")
+                .ok()
+                .filter(|s| !s.is_empty()),
+            wake_on_partial: std::env::var("WAKE_ON_PARTIAL")
+                .ok()
+                .map(|s| matches!(s.as_str(), "1" | "true" | "TRUE" | "yes" | "on"))
+                .unwrap_or(false),
+        }
+    }
+}
+
+/// Ask for the answer to `query` on `cfg.reply_topic`, if set
+fn request_reply(cfg: &WakeWordConfig, session_id: &str, query: &mut Event) {
+    if let Some(ref reply_topic) = cfg.reply_topic {
+        let mut envelope = Envelope::new(session_id, "wake").with_reply_expected(true);
+        envelope.reply_to = reply_topic.clone();
+        envelope.attach_to_event(query);
+    }
+}
+
+/// Load the acoustic model named by `cfg.model_path`, if any
+///
+/// Returns `None` (after logging why) when no model is configured, the `wake-onnx`
+/// feature is disabled, or the model fails to load.
+pub fn load_model(cfg: &WakeWordConfig) -> Option<Box<dyn WakeModel>> {
+    let path = cfg.model_path.as_ref()?;
+    #[cfg(feature = "wake-onnx")]
+    {
+        match crate::wake_onnx::OnnxWakeModel::load(path) {
+            Ok(model) => Some(Box::new(model)),
+            Err(e) => {
+                warn!(target: "wake", "Failed to load wake model {:?}: {}", path, e);
+                None
+            }
         }
     }
+    #[cfg(not(feature = "wake-onnx"))]
+    {
+        warn!(
+            target: "wake",
+            "WAKE_MODEL_PATH={:?} ignored: loom-audio built without the `wake-onnx` feature",
+            path
+        );
+        None
+    }
 }
 
 pub struct WakeWordDetector {
@@ -67,6 +191,7 @@ pub struct WakeWordDetector {
     cfg: WakeWordConfig,
     // When Some(session_id), the next transcript will be treated as user query
     armed_session: Arc<Mutex<Option<String>>>,
+    model: Option<Box<dyn WakeModel>>,
 }
 
 impl WakeWordDetector {
@@ -75,25 +200,81 @@ impl WakeWordDetector {
             bus,
             cfg,
             armed_session: Arc::new(Mutex::new(None)),
+            model: None,
         }
     }
 
+    /// Use this model in acoustic mode instead of loading `cfg.model_path`
+    pub fn with_model(mut self, model: Box<dyn WakeModel>) -> Self {
+        self.model = Some(model);
+        self
+    }
+
     pub async fn start(self) -> Result<JoinHandle<()>> {
         let bus = Arc::clone(&self.bus);
         let cfg = self.cfg.clone();
         let armed = Arc::clone(&self.armed_session);
 
+        // Acoustic mode needs a model; without one, keep matching transcripts
+        let model = match cfg.mode {
+            WakeMode::Acoustic => self.model.or_else(|| load_model(&cfg)),
+            WakeMode::Transcript => None,
+        };
+        let acoustic = model.is_some();
+        if cfg.mode == WakeMode::Acoustic && !acoustic {
+            warn!(target: "wake", "No acoustic wake model available; falling back to transcripts");
+        }
+        let acoustic_task = match model {
+            Some(model) => {
+                let (_id, voiced_rx) = bus
+                    .subscribe(
+                        cfg.voiced_topic.clone(),
+                        vec!["audio_voiced".into()],
+                        QoSLevel::QosRealtime,
+                    )
+                    .await?;
+                let bus = Arc::clone(&bus);
+                let cfg = cfg.clone();
+                let armed = Arc::clone(&armed);
+                Some(async move {
+                    if let Err(e) = run_acoustic(bus, cfg, model, armed, voiced_rx).await {
+                        error!("Acoustic wake detector stopped with error: {}", e);
+                    }
+                })
+            }
+            None => None,
+        };
+
         // Subscribe to transcripts
+        let partials = cfg.wake_on_partial && !acoustic;
+        let mut event_types = vec!["transcript.final".to_string()];
+        if partials {
+            event_types.push("transcript.partial".into());
+        }
         let (_id, mut rx) = bus
             .subscribe(
                 cfg.transcript_topic.clone(),
-                vec!["transcript.final".into()],
+                event_types,
                 QoSLevel::QosRealtime,
             )
             .await?;
 
-        let handle = tokio::spawn(async move {
+        let transcript_task = async move {
+            // Utterance whose partial transcript woke us; its final transcript still
+            // contains the wake phrase
+            let mut woken_utterance: Option<String> = None;
             while let Some(ev) = rx.recv().await {
+                let utterance_id = ev.metadata.get("utterance_id").cloned();
+                if ev.r#type == "transcript.partial" {
+                    if partials && armed.lock().await.is_none() {
+                        if let Some(session_id) = wake_on_partial(&bus, &cfg, &ev).await {
+                            *armed.lock().await = Some(session_id);
+                            woken_utterance = utterance_id;
+                        }
+                    }
+                    continue;
+                }
+
                 // Extract transcript text
                 let text = ev
                     .metadata
@@ -114,12 +295,38 @@ impl WakeWordDetector {
                     guard.take()
                 };
                 if let Some(session_id) = armed_session {
+                    let woke_mid_utterance =
+                        utterance_id.is_some() && woken_utterance.take() == utterance_id;
+                    // An acoustic wake, or one on this utterance's partial transcript,
+                    // fires mid-utterance, so the transcript usually still holds the
+                    // wake phrase
+                    let text = if acoustic || woke_mid_utterance {
+                        let remainder = if acoustic {
+                            detect_wake_prefix(&cfg, &text_norm)
+                        } else {
+                            detect_wake(&cfg, &text_norm).map(|(_, remainder)| remainder)
+                        };
+                        match remainder {
+                            Some(remainder) if !is_query(&cfg, &remainder) => {
+                                // Only the wake phrase: the query is the next utterance
+                                *armed.lock().await = Some(session_id);
+                                continue;
+                            }
+                            Some(remainder) => remainder,
+                            None => text,
+                        }
+                    } else {
+                        text
+                    };
+
                     // Treat this transcript as the user's query
                     let mut md = HashMap::new();
                     md.insert("session_id".into(), session_id.clone());
                     md.insert("text".into(), text.clone());
+                    // A person is waiting on the spoken answer
+                    md.insert(LATENCY_CLASS_KEY.into(), "realtime".into());
 
-                    let query_event = Event {
+                    let mut query_event = Event {
                         id: gen_id(),
                         r#type: "user.query".into(),
                         timestamp_ms: now_ms(),
@@ -130,6 +337,7 @@ impl WakeWordDetector {
                         tags: vec![],
                         priority: 60,
                     };
+                    request_reply(&cfg, &session_id, &mut query_event);
 
                     if let Err(e) = bus.publish(&cfg.query_topic, query_event).await {
                         warn!("Failed to publish user.query: {}", e);
@@ -141,6 +349,12 @@ impl WakeWordDetector {
                     continue;
                 }
 
+                // The acoustic model owns wake decisions
+                if acoustic {
+                    debug!(target: "wake", "Ignoring transcript while not awake");
+                    continue;
+                }
+
                 // Not armed: check for wake phrase
                 if let Some((matched, remainder)) = detect_wake(&cfg, &text_norm) {
                     let session_id = format!("sess_{}", gen_id());
@@ -150,6 +364,7 @@ impl WakeWordDetector {
                     md.insert("phrase".into(), matched.clone());
                     md.insert("text".into(), text.clone());
                     md.insert("session_id".into(), session_id.clone());
+                    md.insert("detector".into(), "transcript".into());
 
                     let wake_event = Event {
                         id: gen_id(),
@@ -170,14 +385,13 @@ impl WakeWordDetector {
                     }
 
                     // If there's meaningful remainder, treat it as immediate query; otherwise arm
-                    if remainder.chars().filter(|c| !c.is_whitespace()).count()
-                        >= cfg.min_query_chars
-                    {
+                    if is_query(&cfg, &remainder) {
                         let mut qmd = HashMap::new();
                         qmd.insert("session_id".into(), session_id.clone());
                         qmd.insert("text".into(), remainder.clone());
+                        qmd.insert(LATENCY_CLASS_KEY.into(), "realtime".into());
 
-                        let query_event = Event {
+                        let mut query_event = Event {
                             id: gen_id(),
                             r#type: "user.query".into(),
                             timestamp_ms: now_ms(),
@@ -188,6 +402,7 @@ impl WakeWordDetector {
                             tags: vec![],
                             priority: 60,
                         };
+                        request_reply(&cfg, &session_id, &mut query_event);
 
                         if let Err(e) = bus.publish(&cfg.query_topic, query_event).await {
                             warn!("Failed to publish user.query: {}", e);
@@ -204,12 +419,168 @@ impl WakeWordDetector {
                     }
                 }
             }
+        };
+
+        // One task, so aborting the handle stops both detectors
+        let handle = tokio::spawn(async move {
+            match acoustic_task {
+                Some(acoustic_task) => {
+                    tokio::join!(transcript_task, acoustic_task);
+                }
+                None => transcript_task.await,
+            }
         });
 
         Ok(handle)
     }
 }
 
+/// Publish `wake_word_detected` if the stable words of a partial transcript hold a wake
+/// phrase; returns the new session id
+async fn wake_on_partial(bus: &EventBus, cfg: &WakeWordConfig, ev: &Event) -> Option<String> {
+    let stable = ev.metadata.get("stable_text")?;
+    let (matched, _) = detect_wake(cfg, &normalize(stable))?;
+    let session_id = format!("sess_{}", gen_id());
+
+    let mut md = HashMap::new();
+    md.insert("phrase".into(), matched.clone());
+    md.insert("text".into(), stable.clone());
+    md.insert("session_id".into(), session_id.clone());
+    md.insert("detector".into(), "partial".into());
+    if let Some(utterance_id) = ev.metadata.get("utterance_id") {
+        md.insert("utterance_id".into(), utterance_id.clone());
+    }
+
+    let wake_event = Event {
+        id: gen_id(),
+        r#type: "wake_word_detected".into(),
+        timestamp_ms: now_ms(),
+        source: "wake".into(),
+        metadata: md,
+        payload: vec![],
+        confidence: ev.confidence,
+        tags: vec![],
+        priority: 65,
+    };
+    if let Err(e) = bus.publish(&cfg.wake_topic, wake_event).await {
+        warn!("Failed to publish wake_word_detected: {}", e);
+    } else {
+        info!(target: "wake", "🔔 Wake word detected in partial transcript: {}", matched);
+    }
+    Some(session_id)
+}
+
+/// Score voiced audio in overlapping windows and wake on the first score over threshold
+async fn run_acoustic(
+    bus: Arc<EventBus>,
+    cfg: WakeWordConfig,
+    mut model: Box<dyn WakeModel>,
+    armed: Arc<Mutex<Option<String>>>,
+    mut rx: tokio::sync::mpsc::Receiver<Event>,
+) -> Result<()> {
+    let rate = model.sample_rate();
+    let window_len = (rate as usize * cfg.window_ms.max(1) as usize / 1000).max(1);
+    let hop_len = (rate as usize * cfg.hop_ms.max(1) as usize / 1000).max(1);
+    let refractory = Duration::from_millis(cfg.refractory_ms);
+
+    // Pre-filled with silence so short first utterances are scored too
+    let mut window: VecDeque<f32> = std::iter::repeat(0.0).take(window_len).collect();
+    let mut since_score = 0usize;
+    let mut last_wake: Option<Instant> = None;
+    let mut warned_rate = false;
+
+    while let Some(ev) = rx.recv().await {
+        let chunk_rate: u32 = ev
+            .metadata
+            .get("sample_rate")
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(16_000);
+        if chunk_rate != rate {
+            if !warned_rate {
+                warn!(
+                    target: "wake",
+                    "Wake model {} expects {}Hz audio, got {}Hz; skipping",
+                    model.name(),
+                    rate,
+                    chunk_rate
+                );
+                warned_rate = true;
+            }
+            continue;
+        }
+        if ev.payload.len() % 2 != 0 {
+            continue;
+        }
+
+        for b in ev.payload.chunks_exact(2) {
+            if window.len() == window_len {
+                window.pop_front();
+            }
+            window.push_back(i16::from_le_bytes([b[0], b[1]]) as f32 / 32768.0);
+            since_score += 1;
+        }
+        if since_score < hop_len {
+            continue;
+        }
+        since_score = 0;
+        if last_wake.is_some_and(|at| at.elapsed() < refractory) {
+            continue;
+        }
+
+        // Inference is CPU-bound; keep it off the async workers
+        let samples: Vec<f32> = window.iter().copied().collect();
+        let (returned, score) = tokio::task::spawn_blocking(move || {
+            let score = model.score(&samples);
+            (model, score)
+        })
+        .await
+        .map_err(|e| loom_core::LoomError::AgentError(format!("wake model panicked: {}", e)))?;
+        model = returned;
+        let score = match score {
+            Ok(score) => score,
+            Err(e) => {
+                warn!(target: "wake", "Wake model scoring failed: {}", e);
+                continue;
+            }
+        };
+        if score < cfg.acoustic_threshold {
+            continue;
+        }
+
+        last_wake = Some(Instant::now());
+        window.iter_mut().for_each(|s| *s = 0.0);
+        let session_id = format!("sess_{}", gen_id());
+
+        let mut md = HashMap::new();
+        md.insert("phrase".into(), model.name().to_string());
+        md.insert("session_id".into(), session_id.clone());
+        md.insert("score".into(), format!("{:.3}", score));
+        md.insert("detector".into(), "acoustic".into());
+
+        let wake_event = Event {
+            id: gen_id(),
+            r#type: "wake_word_detected".into(),
+            timestamp_ms: now_ms(),
+            source: "wake".into(),
+            metadata: md,
+            payload: vec![],
+            confidence: score,
+            tags: vec![],
+            priority: 65,
+        };
+
+        // Arm before publishing so the utterance's transcript finds the session
+        *armed.lock().await = Some(session_id);
+        if let Err(e) = bus.publish(&cfg.wake_topic, wake_event).await {
+            warn!("Failed to publish wake_word_detected: {}", e);
+        } else {
+            info!(target: "wake", "🔔 Acoustic wake ({}): score={:.3}", model.name(), score);
+        }
+    }
+
+    Ok(())
+}
+
 fn normalize(s: &str) -> String {
     s.to_lowercase()
         .chars()
@@ -220,6 +591,19 @@ fn normalize(s: &str) -> String {
         .join(" ")
 }
 
+fn is_query(cfg: &WakeWordConfig, text: &str) -> bool {
+    text.chars().filter(|c| !c.is_whitespace()).count() >= cfg.min_query_chars
+}
+
+/// Remainder after a wake phrase at the start of the utterance, if there is one
+fn detect_wake_prefix(cfg: &WakeWordConfig, text_norm: &str) -> Option<String> {
+    let prefix_only = WakeWordConfig {
+        match_anywhere: false,
+        ..cfg.clone()
+    };
+    detect_wake(&prefix_only, text_norm).map(|(_, remainder)| remainder)
+}
+
 /// Detect wake phrase and return (matched_phrase, remainder_after_phrase)
 fn detect_wake(cfg: &WakeWordConfig, text_norm: &str) -> Option<(String, String)> {
     use strsim::{jaro_winkler, levenshtein};
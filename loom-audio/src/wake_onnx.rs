@@ -0,0 +1,49 @@
+//! ONNX keyword-spotting model for acoustic wake detection.
+//!
+//! Expects a single end-to-end graph (openWakeWord-style export with the
+//! melspectrogram/embedding stages folded in): input `[1, samples]` float PCM at
+//! 16 kHz in -1.0..1.0, output one or more wake probabilities. The highest output
+//! is the window's score.
+
+use crate::wake::WakeModel;
+use loom_core::{LoomError, Result};
+use ort::{GraphOptimizationLevel, Session};
+use std::path::Path;
+
+pub struct OnnxWakeModel {
+    name: String,
+    session: Session,
+}
+
+impl OnnxWakeModel {
+    pub fn load(path: &Path) -> Result<Self> {
+        let session = Session::builder()
+            .and_then(|b| b.with_optimization_level(GraphOptimizationLevel::Level3))
+            .and_then(|b| b.with_intra_threads(1))
+            .and_then(|b| b.commit_from_file(path))
+            .map_err(|e| LoomError::AgentError(format!("wake model {:?}: {}", path, e)))?;
+        let name = path
+            .file_stem()
+            .map(|s| s.to_string_lossy().to_string())
+            .unwrap_or_else(|| "onnx".to_string());
+        Ok(Self { name, session })
+    }
+}
+
+impl WakeModel for OnnxWakeModel {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn score(&mut self, window: &[f32]) -> Result<f32> {
+        let err = |e: ort::Error| LoomError::AgentError(format!("wake model: {}", e));
+        let input = ndarray::Array2::from_shape_vec((1, window.len()), window.to_vec())
+            .map_err(|e| LoomError::AgentError(format!("wake model input: {}", e)))?;
+        let outputs = self
+            .session
+            .run(ort::inputs![input.view()].map_err(err)?)
+            .map_err(err)?;
+        let scores = outputs[0].try_extract_tensor::<f32>().map_err(err)?;
+        Ok(scores.iter().copied().fold(0.0, f32::max))
+    }
+}
@@ -0,0 +1,93 @@
+//! 16-bit PCM WAV decoding shared by the fixture player and the STT benchmark.
+
+use loom_core::{LoomError, Result};
+use std::path::Path;
+
+/// Decoded WAV file
+#[derive(Clone, Debug, PartialEq)]
+pub struct WavClip {
+    /// Interleaved samples
+    pub samples: Vec<i16>,
+    pub sample_rate_hz: u32,
+    pub channels: u16,
+}
+
+impl WavClip {
+    pub fn duration_ms(&self) -> u64 {
+        let frames = self.samples.len() as u64 / self.channels.max(1) as u64;
+        frames * 1000 / self.sample_rate_hz.max(1) as u64
+    }
+
+    /// Samples averaged across channels
+    pub fn mono(&self) -> Vec<i16> {
+        let channels = self.channels.max(1) as usize;
+        if channels == 1 {
+            return self.samples.clone();
+        }
+        self.samples
+            .chunks_exact(channels)
+            .map(|frame| (frame.iter().map(|s| *s as i32).sum::<i32>() / channels as i32) as i16)
+            .collect()
+    }
+}
+
+/// Read a 16-bit PCM WAV file (plain or WAVE_FORMAT_EXTENSIBLE)
+pub fn read_wav(path: &Path) -> Result<WavClip> {
+    let bytes = std::fs::read(path)?;
+    parse_wav(&bytes).map_err(|e| invalid(format!("{}: {}", path.display(), e)))
+}
+
+fn parse_wav(bytes: &[u8]) -> std::result::Result<WavClip, String> {
+    if bytes.len() < 12 || &bytes[0..4] != b"RIFF" || &bytes[8..12] != b"WAVE" {
+        return Err("not a RIFF/WAVE file".into());
+    }
+    let mut format: Option<(u16, u16, u32, u16)> = None;
+    let mut pos = 12;
+    while pos + 8 <= bytes.len() {
+        let id = &bytes[pos..pos + 4];
+        let size = u32::from_le_bytes([
+            bytes[pos + 4],
+            bytes[pos + 5],
+            bytes[pos + 6],
+            bytes[pos + 7],
+        ]) as usize;
+        let body = &bytes[pos + 8..(pos + 8 + size).min(bytes.len())];
+        match id {
+            b"fmt " if body.len() >= 16 => {
+                let u16_at = |i: usize| u16::from_le_bytes([body[i], body[i + 1]]);
+                let rate = u32::from_le_bytes([body[4], body[5], body[6], body[7]]);
+                format = Some((u16_at(0), u16_at(2), rate, u16_at(14)));
+            }
+            b"data" => {
+                let (tag, channels, rate, bits) = format.ok_or("data chunk before fmt chunk")?;
+                // 1 = PCM, 0xFFFE = WAVE_FORMAT_EXTENSIBLE
+                if !matches!(tag, 1 | 0xFFFE) || bits != 16 {
+                    return Err(format!(
+                        "unsupported format tag={} bits={}; expected 16-bit PCM",
+                        tag, bits
+                    ));
+                }
+                if channels == 0 || rate == 0 {
+                    return Err("zero channels or sample rate".into());
+                }
+                return Ok(WavClip {
+                    samples: crate::utils::decode_pcm16le(body),
+                    sample_rate_hz: rate,
+                    channels,
+                });
+            }
+            _ => {}
+        }
+        // Chunks are padded to an even size
+        pos += 8 + size + (size & 1);
+    }
+    Err("no data chunk".into())
+}
+
+/// An `InvalidData` I/O error carrying `message`
+pub(crate) fn invalid(message: String) -> LoomError {
+    LoomError::IoError(std::io::Error::new(
+        std::io::ErrorKind::InvalidData,
+        message,
+    ))
+}
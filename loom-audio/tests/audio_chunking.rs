@@ -97,7 +97,7 @@ async fn test_push_and_ship_chunking() {
         chunks.push(chunk);
     }
     assert_eq!(chunks.len(), 3);
-    assert!(acc.len() > 0 && acc.len() < chunk_samples); // remainder buffered
+    assert!(!acc.is_empty() && acc.len() < chunk_samples); // remainder buffered
 
     // Verify the concatenation of delivered chunks equals the first 3*chunk_samples inputs
     let delivered: Vec<i16> = chunks.into_iter().flatten().collect();
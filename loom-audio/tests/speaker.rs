@@ -0,0 +1,275 @@
+//! Integration tests for sentence-chunked speech of streamed responses
+
+// When the 'tts' feature is enabled, run the real tests
+#[cfg(feature = "tts")]
+mod speaker_tests {
+    use async_trait::async_trait;
+    use loom_audio::{ResponseSpeaker, ResponseSpeakerConfig, SentenceChunker};
+    use loom_core::cognitive::{RESPONSE_EVENT, RESPONSE_FINAL_EVENT, RESPONSE_PARTIAL_EVENT};
+    use loom_core::tools::{Tool, ToolResult};
+    use loom_core::{Event, EventBus, ToolRegistry};
+    use std::collections::HashMap;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::{Arc, Mutex};
+    use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+    fn gen_id() -> String {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos())
+            .unwrap_or(0);
+        format!("{:x}", nanos)
+    }
+
+    /// `tts.speak` stand-in recording the text it is asked to speak
+    struct RecordingTts {
+        spoken: Arc<Mutex<Vec<String>>>,
+    }
+
+    #[async_trait]
+    impl Tool for RecordingTts {
+        fn name(&self) -> String {
+            "tts.speak".to_string()
+        }
+
+        fn description(&self) -> String {
+            "Records spoken text".to_string()
+        }
+
+        fn parameters(&self) -> serde_json::Value {
+            serde_json::json!({"type": "object"})
+        }
+
+        async fn call(&self, arguments: serde_json::Value) -> ToolResult<serde_json::Value> {
+            let text = arguments["text"].as_str().unwrap_or_default().to_string();
+            self.spoken.lock().unwrap().push(text);
+            Ok(serde_json::json!({"status": "ok"}))
+        }
+    }
+
+    /// `tts.speak` stand-in taking 100ms per sentence, cut short by `tts.stop`
+    struct SlowTts {
+        spoken: Arc<Mutex<Vec<String>>>,
+    }
+
+    #[async_trait]
+    impl Tool for SlowTts {
+        fn name(&self) -> String {
+            "tts.speak".to_string()
+        }
+
+        fn description(&self) -> String {
+            "Records spoken text slowly".to_string()
+        }
+
+        fn parameters(&self) -> serde_json::Value {
+            serde_json::json!({"type": "object"})
+        }
+
+        async fn call(&self, arguments: serde_json::Value) -> ToolResult<serde_json::Value> {
+            let text = arguments["text"].as_str().unwrap_or_default().to_string();
+            self.spoken.lock().unwrap().push(text);
+            tokio::time::sleep(Duration::from_millis(100)).await;
+            Ok(serde_json::json!({"status": "ok"}))
+        }
+    }
+
+    /// `tts.stop` stand-in counting stops
+    struct CountingStop {
+        stops: Arc<AtomicUsize>,
+    }
+
+    #[async_trait]
+    impl Tool for CountingStop {
+        fn name(&self) -> String {
+            "tts.stop".to_string()
+        }
+
+        fn description(&self) -> String {
+            "Counts stops".to_string()
+        }
+
+        fn parameters(&self) -> serde_json::Value {
+            serde_json::json!({"type": "object"})
+        }
+
+        async fn call(&self, _arguments: serde_json::Value) -> ToolResult<serde_json::Value> {
+            self.stops.fetch_add(1, Ordering::SeqCst);
+            Ok(serde_json::json!({"stopped": true}))
+        }
+    }
+
+    fn response(event_type: &str, correlation_id: &str, text: &str) -> Event {
+        Event {
+            id: gen_id(),
+            r#type: event_type.to_string(),
+            timestamp_ms: 0,
+            source: "agent.assistant".to_string(),
+            metadata: HashMap::from([("correlation_id".to_string(), correlation_id.to_string())]),
+            payload: text.as_bytes().to_vec(),
+            confidence: 1.0,
+            tags: vec![],
+            priority: 60,
+        }
+    }
+
+    #[test]
+    fn test_chunker_splits_on_sentence_ends() {
+        let mut chunker = SentenceChunker::new(8);
+        assert!(chunker.push("The sky is blue").is_empty());
+        // A period is not a boundary until whitespace follows it
+        assert!(chunker.push(".").is_empty());
+        assert_eq!(
+            chunker.push(" It costs 3.50 today"),
+            vec!["The sky is blue."]
+        );
+        // Short fragments join the following sentence
+        assert_eq!(
+            chunker.push("! Ok. Dr. Smith agrees? Yes\n"),
+            vec!["It costs 3.50 today!", "Ok. Dr. Smith agrees?"]
+        );
+        assert_eq!(chunker.finish().as_deref(), Some("Yes"));
+        assert_eq!(chunker.finish(), None);
+    }
+
+    #[tokio::test]
+    async fn test_speaker_speaks_streamed_sentences_as_they_complete() {
+        let bus = Arc::new(EventBus::new().await.unwrap());
+        bus.start().await.unwrap();
+        let registry = Arc::new(ToolRegistry::new());
+        let spoken = Arc::new(Mutex::new(Vec::new()));
+        registry
+            .register(Arc::new(RecordingTts {
+                spoken: Arc::clone(&spoken),
+            }))
+            .await;
+
+        let cfg = ResponseSpeakerConfig {
+            topic: format!("test.reply.{}", gen_id()),
+            min_sentence_chars: 4,
+            ..Default::default()
+        };
+        let topic = cfg.topic.clone();
+        let handle = ResponseSpeaker::new(Arc::clone(&bus), registry, cfg)
+            .start()
+            .await
+            .unwrap();
+
+        for delta in [
+            "Hello there",
+            ". The weather",
+            " is sunny. Enjoy",
+            " the day",
+        ] {
+            bus.publish(&topic, response(RESPONSE_PARTIAL_EVENT, "s1", delta))
+                .await
+                .unwrap();
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        // The unfinished sentence waits for the rest of the stream
+        assert_eq!(
+            *spoken.lock().unwrap(),
+            vec!["Hello there.", "The weather is sunny."]
+        );
+
+        bus.publish(
+            &topic,
+            response(
+                RESPONSE_FINAL_EVENT,
+                "s1",
+                "Hello there. The weather is sunny. Enjoy the day",
+            ),
+        )
+        .await
+        .unwrap();
+        // Unstreamed responses are spoken sentence by sentence
+        bus.publish(&topic, response(RESPONSE_EVENT, "s2", "Done. Bye now."))
+            .await
+            .unwrap();
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert_eq!(
+            *spoken.lock().unwrap(),
+            vec![
+                "Hello there.",
+                "The weather is sunny.",
+                "Enjoy the day",
+                "Done.",
+                "Bye now."
+            ]
+        );
+
+        handle.abort();
+        bus.shutdown().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_speaker_stops_and_mutes_interrupted_response_on_barge_in() {
+        let bus = Arc::new(EventBus::new().await.unwrap());
+        bus.start().await.unwrap();
+        let registry = Arc::new(ToolRegistry::new());
+        let spoken = Arc::new(Mutex::new(Vec::new()));
+        let stops = Arc::new(AtomicUsize::new(0));
+        registry
+            .register(Arc::new(SlowTts {
+                spoken: Arc::clone(&spoken),
+            }))
+            .await;
+        registry
+            .register(Arc::new(CountingStop {
+                stops: Arc::clone(&stops),
+            }))
+            .await;
+
+        let cfg = ResponseSpeakerConfig {
+            topic: format!("test.reply.{}", gen_id()),
+            min_sentence_chars: 4,
+            barge_in_topic: Some(format!("test.barge_in.{}", gen_id())),
+            ..Default::default()
+        };
+        let topic = cfg.topic.clone();
+        let barge_in_topic = cfg.barge_in_topic.clone().unwrap();
+        let handle = ResponseSpeaker::new(Arc::clone(&bus), registry, cfg)
+            .start()
+            .await
+            .unwrap();
+
+        bus.publish(
+            &topic,
+            response(RESPONSE_PARTIAL_EVENT, "s1", "First one. Second one. "),
+        )
+        .await
+        .unwrap();
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        let mut barge_in = response("barge_in", "", "");
+        barge_in.source = "barge_in".to_string();
+        bus.publish(&barge_in_topic, barge_in).await.unwrap();
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert_eq!(stops.load(Ordering::SeqCst), 1);
+
+        // The rest of the interrupted stream stays silent; the next reply is spoken
+        bus.publish(
+            &topic,
+            response(RESPONSE_FINAL_EVENT, "s1", "First one. Second one. Third"),
+        )
+        .await
+        .unwrap();
+        bus.publish(&topic, response(RESPONSE_EVENT, "s2", "Okay, stopping."))
+            .await
+            .unwrap();
+        tokio::time::sleep(Duration::from_millis(300)).await;
+        assert_eq!(
+            *spoken.lock().unwrap(),
+            vec!["First one.", "Okay, stopping."]
+        );
+
+        handle.abort();
+        bus.shutdown().await.unwrap();
+    }
+}
+
+// Placeholder test when the feature is disabled, so `cargo test` still passes
+#[cfg(not(feature = "tts"))]
+#[test]
+fn speaker_tests_require_feature() {
+    println!("Speaker tests require 'tts' feature. Run: cargo test --features tts");
+}
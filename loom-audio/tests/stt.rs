@@ -7,7 +7,7 @@
 
 #![cfg(feature = "stt")]
 
-use loom_audio::{SttConfig, SttEngine};
+use loom_audio::{PartialStability, SttConfig, SttEngine};
 use loom_core::{Event, EventBus, QoSLevel};
 use std::collections::HashMap;
 use std::sync::Arc;
@@ -44,6 +44,10 @@ async fn test_stt_engine_starts() {
         language: "en".to_string(),
         temp_dir: std::env::temp_dir(),
         extra_args: vec![],
+        pool: None,
+        wake_topic: None,
+        wake_window_ms: 8000,
+        partial_interval_ms: 0,
     };
 
     let stt_engine = SttEngine::new(Arc::clone(&bus), stt_config);
@@ -75,6 +79,10 @@ async fn test_stt_receives_vad_events() {
         language: "en".to_string(),
         temp_dir: std::env::temp_dir(),
         extra_args: vec![],
+        pool: None,
+        wake_topic: None,
+        wake_window_ms: 8000,
+        partial_interval_ms: 0,
     };
 
     // Subscribe to transcript events (even though whisper won't run)
@@ -182,6 +190,10 @@ async fn test_stt_ignores_short_utterances() {
         language: "en".to_string(),
         temp_dir: std::env::temp_dir(),
         extra_args: vec![],
+        pool: None,
+        wake_topic: None,
+        wake_window_ms: 8000,
+        partial_interval_ms: 0,
     };
 
     let (_sub_id, mut transcript_rx) = bus
@@ -266,3 +278,159 @@ async fn test_stt_ignores_short_utterances() {
 
     bus.shutdown().await.unwrap();
 }
+
+/// Words become stable once they hold across consecutive hypotheses
+#[test]
+fn test_partial_stability_scores_agreeing_words() {
+    let mut stability = PartialStability::default();
+
+    let first = stability.update("turn on");
+    assert_eq!(first.revision, 1);
+    assert_eq!(first.stability, 0.5);
+    assert_eq!(first.stable_text, "");
+
+    // Case and punctuation changes keep a word stable
+    let second = stability.update("Turn on, the");
+    assert_eq!(second.stable_text, "Turn on,");
+    assert!((second.stability - 5.0 / 6.0).abs() < 1e-6);
+
+    // A changed word resets it and every word after it
+    let third = stability.update("turn off the lights");
+    assert_eq!(third.revision, 3);
+    assert_eq!(third.stable_text, "turn");
+    assert_eq!(third.stability, 0.625);
+
+    let fourth = stability.update("turn off the lights");
+    assert_eq!(fourth.stable_text, "turn off the lights");
+    assert_eq!(fourth.stability, 1.0);
+}
+
+/// Stand-in whisper binary that always prints `transcript`
+#[cfg(unix)]
+fn fake_whisper(dir: &std::path::Path, transcript: &str) -> std::path::PathBuf {
+    use std::os::unix::fs::PermissionsExt;
+    let path = dir.join("whisper");
+    std::fs::write(&path, format!("#!/bin/sh\necho \"{}\"\n", transcript)).unwrap();
+    std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+    path
+}
+
+/// Test that partial transcripts stream while speaking and share the final's utterance id
+#[cfg(unix)]
+#[tokio::test]
+async fn test_stt_streams_partial_transcripts() {
+    let bus = Arc::new(EventBus::new().await.unwrap());
+    bus.start().await.unwrap();
+
+    let dir = std::env::temp_dir().join(format!("loom_stt_partial_{}", gen_id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let whisper_bin = fake_whisper(&dir, "turn on the lights");
+    let stt_config = SttConfig {
+        vad_topic: format!("vad.{}", gen_id()),
+        voiced_topic: format!("audio.voiced.{}", gen_id()),
+        transcript_topic: format!("transcript.{}", gen_id()),
+        // Only checked for existence by the stand-in
+        whisper_model: whisper_bin.clone(),
+        whisper_bin,
+        language: "en".to_string(),
+        temp_dir: dir.clone(),
+        extra_args: vec![],
+        pool: None,
+        wake_topic: None,
+        wake_window_ms: 8000,
+        partial_interval_ms: 50,
+    };
+
+    let (_sub_id, mut transcript_rx) = bus
+        .subscribe(
+            stt_config.transcript_topic.clone(),
+            vec![
+                "transcript.partial".to_string(),
+                "transcript.final".to_string(),
+            ],
+            QoSLevel::QosBatched,
+        )
+        .await
+        .unwrap();
+
+    let (vad_topic, voiced_topic) = (
+        stt_config.vad_topic.clone(),
+        stt_config.voiced_topic.clone(),
+    );
+    let stt_engine = SttEngine::new(Arc::clone(&bus), stt_config);
+    let handle = stt_engine.start().await.unwrap();
+    sleep(Duration::from_millis(100)).await;
+
+    let vad_event = |kind: &str| Event {
+        id: gen_id(),
+        r#type: kind.to_string(),
+        timestamp_ms: now_ms(),
+        source: "test".to_string(),
+        metadata: HashMap::from([("sample_rate".to_string(), "16000".to_string())]),
+        payload: vec![],
+        confidence: 1.0,
+        tags: vec![],
+        priority: 70,
+    };
+    bus.publish(&vad_topic, vad_event("vad.speech_start"))
+        .await
+        .unwrap();
+
+    // 100ms of audio per 20ms of wall time, so the utterance grows between decodes
+    let mut partials = Vec::new();
+    for _ in 0..30 {
+        let voiced_event = Event {
+            id: gen_id(),
+            r#type: "audio_voiced".to_string(),
+            timestamp_ms: now_ms(),
+            source: "test".to_string(),
+            metadata: HashMap::from([("sample_rate".to_string(), "16000".to_string())]),
+            payload: vec![0u8; 3200],
+            confidence: 1.0,
+            tags: vec![],
+            priority: 80,
+        };
+        bus.publish(&voiced_topic, voiced_event).await.unwrap();
+        sleep(Duration::from_millis(20)).await;
+        while let Ok(ev) = transcript_rx.try_recv() {
+            partials.push(ev);
+        }
+    }
+    bus.publish(&vad_topic, vad_event("vad.speech_end"))
+        .await
+        .unwrap();
+
+    let final_event = loop {
+        let ev = tokio::time::timeout(Duration::from_secs(2), transcript_rx.recv())
+            .await
+            .expect("no final transcript")
+            .unwrap();
+        if ev.r#type == "transcript.final" {
+            break ev;
+        }
+        partials.push(ev);
+    };
+
+    assert!(
+        partials.len() >= 2,
+        "expected several partial transcripts, got {}",
+        partials.len()
+    );
+    assert!(partials.iter().all(|p| p.r#type == "transcript.partial"));
+    let (first, last) = (&partials[0], &partials[partials.len() - 1]);
+    assert_eq!(first.metadata["text"], "turn on the lights");
+    assert_eq!(first.metadata["revision"], "1");
+    assert_eq!(first.metadata["stability"], "0.50");
+    assert_eq!(first.metadata["stable_text"], "");
+    assert_eq!(last.metadata["stability"], "1.00");
+    assert_eq!(last.metadata["stable_text"], "turn on the lights");
+    assert_eq!(last.confidence, 1.0);
+    assert!(partials
+        .iter()
+        .all(|p| p.metadata["utterance_id"] == final_event.metadata["utterance_id"]));
+    assert_eq!(final_event.metadata["text"], "turn on the lights");
+
+    handle.abort();
+    bus.shutdown().await.unwrap();
+    let _ = std::fs::remove_dir_all(dir);
+}
@@ -0,0 +1,220 @@
+//! STT benchmark tests: WER scoring, dataset loading and config comparison
+
+// When the 'stt' feature is enabled, run the real tests
+#[cfg(feature = "stt")]
+mod stt_bench_tests {
+    use loom_audio::{
+        word_errors, BenchConfig, BenchConfigSpec, BenchDataset, SttBenchmark, SttConfig,
+    };
+    use std::path::{Path, PathBuf};
+
+    /// 300ms silence, 600ms of a 200 Hz tone, 400ms silence; 16 kHz mono
+    fn utterance_fixture() -> PathBuf {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/utterance_16k_mono.wav")
+    }
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("loom_stt_bench_{}_{}", name, std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn stt_config(whisper_bin: PathBuf, temp_dir: &Path) -> SttConfig {
+        SttConfig {
+            vad_topic: "vad".into(),
+            voiced_topic: "audio.voiced".into(),
+            transcript_topic: "transcript".into(),
+            whisper_bin,
+            // Only checked for existence by the stand-ins
+            whisper_model: utterance_fixture(),
+            language: "en".into(),
+            temp_dir: temp_dir.to_path_buf(),
+            extra_args: vec![],
+            pool: None,
+            wake_topic: None,
+            wake_window_ms: 8000,
+            partial_interval_ms: 0,
+        }
+    }
+
+    /// Stand-in whisper binary that always prints `transcript`
+    #[cfg(unix)]
+    fn fake_whisper(dir: &Path, name: &str, transcript: &str) -> PathBuf {
+        use std::os::unix::fs::PermissionsExt;
+        let path = dir.join(name);
+        std::fs::write(&path, format!("#!/bin/sh\necho \"{}\"\n", transcript)).unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+        path
+    }
+
+    #[test]
+    fn wer_counts_substitutions_deletions_and_insertions() {
+        assert_eq!(word_errors("hello world", "hello world"), (0, 2));
+        assert_eq!(word_errors("hello world", "hello there world"), (1, 2));
+        assert_eq!(word_errors("the quick brown fox", "the quack fox"), (2, 4));
+        assert_eq!(word_errors("turn on the lights", ""), (4, 4));
+        assert_eq!(word_errors("", "noise"), (1, 0));
+    }
+
+    #[test]
+    fn wer_ignores_case_and_punctuation() {
+        assert_eq!(
+            word_errors("Hello, World! It's me.", "hello world it's me"),
+            (0, 4)
+        );
+        assert_eq!(word_errors("it's", "its"), (1, 1));
+    }
+
+    #[test]
+    fn dataset_loads_from_manifest_and_directory() {
+        let dir = temp_dir("dataset");
+        std::fs::write(
+            dir.join("manifest.jsonl"),
+            "# comment\n{\"audio\": \"a.wav\", \"text\": \"first\"}\n\n{\"audio\": \"/abs/b.wav\", \"text\": \"second\"}\n",
+        )
+        .unwrap();
+        let dataset = BenchDataset::load(&dir.join("manifest.jsonl")).unwrap();
+        assert_eq!(dataset.len(), 2);
+        assert_eq!(dataset.samples[0].audio, dir.join("a.wav"));
+        assert_eq!(dataset.samples[0].reference, "first");
+        assert_eq!(dataset.samples[1].audio, PathBuf::from("/abs/b.wav"));
+
+        std::fs::write(dir.join("bad.jsonl"), "{\"audio\": \"a.wav\"}\n").unwrap();
+        assert!(BenchDataset::load(&dir.join("bad.jsonl")).is_err());
+
+        // Directory: WAVs with a sibling transcript; unlabeled ones are skipped
+        let clips = dir.join("clips");
+        std::fs::create_dir_all(&clips).unwrap();
+        std::fs::copy(utterance_fixture(), clips.join("one.wav")).unwrap();
+        std::fs::write(clips.join("one.txt"), "hello from the fixture\n").unwrap();
+        std::fs::copy(utterance_fixture(), clips.join("two.wav")).unwrap();
+        let dataset = BenchDataset::load(&clips).unwrap();
+        assert_eq!(dataset.len(), 1);
+        assert_eq!(dataset.samples[0].audio, clips.join("one.wav"));
+        assert_eq!(dataset.samples[0].reference, "hello from the fixture");
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn config_spec_overrides_base() {
+        let specs: Vec<BenchConfigSpec> = serde_json::from_str(
+            r#"[{ "name": "small", "model": "ggml-small.en.bin", "beam_size": 5, "threads": 2 },
+                { "name": "vad", "vad": { "mode": 3 } }]"#,
+        )
+        .unwrap();
+        let base = stt_config("/usr/bin/whisper".into(), &std::env::temp_dir());
+        let mut configs = specs.into_iter().map(|s| s.into_config(&base));
+
+        let small = configs.next().unwrap();
+        assert_eq!(small.name, "small");
+        assert_eq!(small.stt.whisper_model, PathBuf::from("ggml-small.en.bin"));
+        assert_eq!(small.stt.whisper_bin, base.whisper_bin);
+        assert_eq!(
+            small.stt.extra_args,
+            vec!["--threads", "2", "--beam-size", "5"]
+        );
+        assert!(small.vad.is_none());
+        assert!(small.describe().contains("--beam-size 5"));
+
+        let vad = configs.next().unwrap();
+        let settings = vad.vad.clone().unwrap();
+        assert_eq!(settings.mode, 3);
+        // Unset fields keep the VadConfig defaults
+        assert_eq!(settings.hangover_ms, 200);
+        assert!(vad.describe().contains("vad mode=3"));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test(flavor = "multi_thread")]
+    async fn configs_are_compared_on_the_same_dataset() {
+        let dir = temp_dir("compare");
+        let dataset = BenchDataset {
+            samples: vec![loom_audio::BenchSample {
+                audio: utterance_fixture(),
+                reference: "Hello from the fixture.".into(),
+            }],
+        };
+        let good = fake_whisper(&dir, "good", "hello from the fixture");
+        let poor = fake_whisper(&dir, "poor", "yellow from a fixture today");
+
+        let report = SttBenchmark::new(dataset)
+            .with_config(BenchConfig::new("poor", stt_config(poor, &dir)))
+            .with_config(BenchConfig::new("good", stt_config(good, &dir)).with_beam_size(5))
+            .with_config(BenchConfig::new(
+                "missing",
+                stt_config("/nonexistent/whisper".into(), &dir),
+            ))
+            .run()
+            .await;
+
+        assert_eq!(report.samples, 1);
+        let [poor, good, missing] = &report.configs[..] else {
+            panic!("three configs expected");
+        };
+        assert_eq!(good.wer, 0.0);
+        assert_eq!(good.failed, 0);
+        assert_eq!(good.files[0].hypothesis, "hello from the fixture");
+        assert_eq!(good.files[0].audio_ms, 1_300);
+        assert_eq!(good.files[0].segments, 1);
+        // yellow→hello, a→the, +today
+        assert_eq!(poor.files[0].word_errors, 3);
+        assert_eq!(poor.wer, 0.75);
+        assert_eq!(missing.failed, 1);
+        assert_eq!(missing.wer, 1.0);
+        assert!(missing.files[0].error.is_some());
+        assert_eq!(report.best().unwrap().name, "good");
+
+        let markdown = report.to_markdown();
+        assert!(markdown.contains("| good |"));
+        assert!(markdown.contains("75.0%"));
+        assert!(markdown.contains("Best: good (0.0% WER)"));
+        assert!(markdown.contains("\"yellow from a fixture today\""));
+        let json: serde_json::Value = serde_json::from_str(&report.to_json()).unwrap();
+        assert_eq!(json["configs"][1]["name"], "good");
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    /// The fixture's tone is cut out by VadGate and transcribed as one utterance
+    #[cfg(all(unix, feature = "vad"))]
+    #[tokio::test(flavor = "multi_thread")]
+    async fn vad_mode_transcribes_each_utterance() {
+        use loom_audio::BenchVad;
+
+        let dir = temp_dir("vad");
+        let whisper = fake_whisper(&dir, "whisper", "hello from the fixture");
+        let dataset = BenchDataset {
+            samples: vec![loom_audio::BenchSample {
+                audio: utterance_fixture(),
+                reference: "hello from the fixture".into(),
+            }],
+        };
+        let vad = BenchVad {
+            mode: 1,
+            frame_ms: 20,
+            min_start_ms: 40,
+            hangover_ms: 100,
+        };
+
+        let report = SttBenchmark::new(dataset)
+            .with_config(BenchConfig::new("vad", stt_config(whisper, &dir)).with_vad(vad))
+            .run()
+            .await;
+
+        let file = &report.configs[0].files[0];
+        assert!(file.error.is_none(), "{:?}", file.error);
+        assert_eq!(file.segments, 1);
+        assert_eq!(file.wer, 0.0);
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
+
+// Placeholder test when the feature is disabled, so `cargo test` still passes
+#[cfg(not(feature = "stt"))]
+#[test]
+fn stt_bench_tests_require_feature() {
+    println!("STT benchmark tests require 'stt' feature. Run: cargo test --features stt");
+}
@@ -0,0 +1,145 @@
+//! Integration tests for buffer playback of `tts.speak` (no engine or player needed)
+
+// When the 'tts' feature is enabled, run the real tests
+#[cfg(feature = "tts")]
+mod tts_tests {
+    use loom_audio::{TtsPlayback, TtsSpeakProvider, TtsSpeakProviderConfig};
+    use loom_core::proto::QoSLevel;
+    use loom_core::tools::Tool;
+    use loom_core::{Event, EventBus};
+    use std::sync::Arc;
+    use std::time::Duration;
+    use tokio::sync::mpsc;
+
+    /// Buffer playback with every engine hidden, so the synthetic one is used
+    fn synthetic_config(topic: &str) -> TtsSpeakProviderConfig {
+        TtsSpeakProviderConfig {
+            topic: topic.to_string(),
+            piper_bin: None,
+            piper_voice: None,
+            espeak_bin: None,
+            playback: TtsPlayback::Buffer,
+            ..Default::default()
+        }
+    }
+
+    async fn setup(topic: &str) -> (Arc<EventBus>, mpsc::Receiver<Event>, TtsSpeakProvider) {
+        let bus = Arc::new(EventBus::new().await.unwrap());
+        bus.start().await.unwrap();
+        let (_sub, rx) = bus
+            .subscribe(topic.to_string(), vec![], QoSLevel::QosBatched)
+            .await
+            .unwrap();
+        let tts = TtsSpeakProvider::new(Arc::clone(&bus), Some(synthetic_config(topic)));
+        (bus, rx, tts)
+    }
+
+    async fn next_of_type(rx: &mut mpsc::Receiver<Event>, r#type: &str) -> Event {
+        loop {
+            let event = tokio::time::timeout(Duration::from_secs(2), rx.recv())
+                .await
+                .expect("tts event in time")
+                .unwrap();
+            if event.r#type == r#type {
+                return event;
+            }
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_buffer_playback_returns_and_publishes_wav() {
+        let (bus, mut rx, tts) = setup("tts.test.buffer").await;
+
+        let result = tts
+            .call(serde_json::json!({ "text": "hello from the synthetic sink" }))
+            .await
+            .unwrap();
+        assert_eq!(result["engine"], "synthetic");
+        assert_eq!(result["playback"], "buffer");
+        assert_eq!(result["interrupted"], false);
+        assert!(result.get("wav_path").is_none());
+        // "RIFF" encodes to "UklGR"
+        let wav_base64 = result["wav_base64"].as_str().unwrap();
+        assert!(wav_base64.starts_with("UklGR"));
+        let audio_ms = result["audio_ms"].as_u64().unwrap();
+        assert!(audio_ms > 500, "five words last {}ms", audio_ms);
+
+        let audio = next_of_type(&mut rx, "tts.audio").await;
+        assert_eq!(&audio.payload[0..4], b"RIFF");
+        assert_eq!(&audio.payload[8..12], b"WAVE");
+        assert_eq!(audio.metadata["encoding"], "wav");
+        assert_eq!(audio.metadata["audio_ms"], audio_ms.to_string());
+        // 44-byte header, 16 kHz mono PCM16
+        let data_bytes = (audio.payload.len() - 44) as u64;
+        assert_eq!(data_bytes * 1000 / 32_000, audio_ms);
+
+        let done = next_of_type(&mut rx, "tts.done").await;
+        assert_eq!(done.metadata["playback"], "buffer");
+        assert_eq!(
+            done.metadata["audio_bytes"],
+            audio.payload.len().to_string()
+        );
+        assert!(!done.metadata.contains_key("wav_path"));
+
+        bus.shutdown().await.unwrap();
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_synthetic_speech_is_deterministic() {
+        let (bus, _rx, tts) = setup("tts.test.deterministic").await;
+        let speak = |text: &'static str| tts.call(serde_json::json!({ "text": text }));
+
+        let first = speak("same words twice").await.unwrap();
+        let second = speak("same words twice").await.unwrap();
+        assert_eq!(first["wav_base64"], second["wav_base64"]);
+
+        let longer = speak("same words twice and then some more").await.unwrap();
+        assert!(longer["audio_ms"].as_u64() > first["audio_ms"].as_u64());
+
+        // A faster rate renders shorter audio
+        let fast = tts
+            .call(serde_json::json!({ "text": "same words twice", "rate": 2.0 }))
+            .await
+            .unwrap();
+        assert!(fast["audio_ms"].as_u64() < first["audio_ms"].as_u64());
+
+        bus.shutdown().await.unwrap();
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_player_mode_without_engine_only_prints() {
+        let bus = Arc::new(EventBus::new().await.unwrap());
+        bus.start().await.unwrap();
+        let config = TtsSpeakProviderConfig {
+            playback: TtsPlayback::Player,
+            ..synthetic_config("tts.test.player")
+        };
+        let tts = TtsSpeakProvider::new(Arc::clone(&bus), Some(config));
+
+        let result = tts
+            .call(serde_json::json!({ "text": "nobody hears this" }))
+            .await
+            .unwrap();
+        assert_eq!(result["engine"], "none");
+        assert_eq!(result["printed"], true);
+        assert!(result.get("wav_base64").is_none());
+
+        bus.shutdown().await.unwrap();
+    }
+
+    #[test]
+    fn test_playback_parse() {
+        assert_eq!(TtsPlayback::parse("buffer"), Some(TtsPlayback::Buffer));
+        assert_eq!(TtsPlayback::parse(" NULL "), Some(TtsPlayback::Buffer));
+        assert_eq!(TtsPlayback::parse("player"), Some(TtsPlayback::Player));
+        assert_eq!(TtsPlayback::parse("speakers"), None);
+        assert_eq!(TtsPlayback::default(), TtsPlayback::Player);
+    }
+}
+
+// Placeholder test when the feature is disabled, so `cargo test` still passes
+#[cfg(not(feature = "tts"))]
+#[test]
+fn tts_tests_require_feature() {
+    println!("TTS tests require 'tts' feature. Run: cargo test --features tts");
+}
@@ -1,9 +1,9 @@
-//! Integration tests for transcript-based Wake Word detection
+//! Integration tests for transcript-based and acoustic Wake Word detection
 
 // When the 'wake' feature is enabled, run the real tests
 #[cfg(feature = "wake")]
 mod wake_tests {
-    use loom_audio::{WakeWordConfig, WakeWordDetector};
+    use loom_audio::{WakeMode, WakeModel, WakeWordConfig, WakeWordDetector};
     use loom_core::{Event, EventBus, QoSLevel};
     use std::collections::HashMap;
     use std::sync::Arc;
@@ -215,6 +215,263 @@ mod wake_tests {
 
         bus.shutdown().await.unwrap();
     }
+
+    /// A transcript event of one utterance, as published by SttEngine
+    fn utterance_event(kind: &str, utterance_id: &str, text: &str, stable: &str) -> Event {
+        let mut ev = transcript_event(text);
+        ev.r#type = format!("transcript.{}", kind);
+        ev.metadata
+            .insert("utterance_id".to_string(), utterance_id.to_string());
+        ev.metadata
+            .insert("stable_text".to_string(), stable.to_string());
+        ev
+    }
+
+    #[tokio::test]
+    async fn test_wake_on_stable_partial_takes_query_from_final() {
+        let bus = Arc::new(EventBus::new().await.unwrap());
+        bus.start().await.unwrap();
+
+        let ns = gen_id();
+        let cfg = WakeWordConfig {
+            transcript_topic: format!("test.transcript.{}", ns),
+            wake_topic: format!("test.wake.{}", ns),
+            query_topic: format!("test.query.{}", ns),
+            phrases: vec!["hey loom".into()],
+            wake_on_partial: true,
+            ..Default::default()
+        };
+        let (_w_id, mut wake_rx) = bus
+            .subscribe(
+                cfg.wake_topic.clone(),
+                vec!["wake_word_detected".into()],
+                QoSLevel::QosRealtime,
+            )
+            .await
+            .unwrap();
+        let (_q_id, mut query_rx) = bus
+            .subscribe(
+                cfg.query_topic.clone(),
+                vec!["user.query".into()],
+                QoSLevel::QosRealtime,
+            )
+            .await
+            .unwrap();
+
+        let detector = WakeWordDetector::new(Arc::clone(&bus), cfg.clone());
+        let _handle = detector.start().await.unwrap();
+        tokio::time::sleep(tokio::time::Duration::from_millis(20)).await;
+
+        // Unstable words do not wake
+        for ev in [
+            utterance_event("partial", "utt-1", "hey loom", ""),
+            utterance_event("partial", "utt-1", "hey loom turn", "hey loom"),
+            utterance_event("partial", "utt-1", "hey loom turn on", "hey loom turn"),
+        ] {
+            bus.publish(&cfg.transcript_topic, ev).await.unwrap();
+        }
+        let wake = tokio::time::timeout(tokio::time::Duration::from_millis(500), wake_rx.recv())
+            .await
+            .expect("no wake from partial transcript")
+            .unwrap();
+        assert_eq!(wake.metadata["detector"], "partial");
+        assert_eq!(wake.metadata["utterance_id"], "utt-1");
+        assert_eq!(wake.metadata["text"], "hey loom");
+
+        // One wake per utterance; the query comes from the final transcript
+        bus.publish(
+            &cfg.transcript_topic,
+            utterance_event("final", "utt-1", "Hey Loom, turn on the lights.", ""),
+        )
+        .await
+        .unwrap();
+        let query = tokio::time::timeout(tokio::time::Duration::from_millis(500), query_rx.recv())
+            .await
+            .expect("no query from final transcript")
+            .unwrap();
+        assert_eq!(query.metadata["text"], "turn on the lights");
+        assert_eq!(query.metadata["session_id"], wake.metadata["session_id"]);
+        assert!(wake_rx.try_recv().is_err());
+
+        bus.shutdown().await.unwrap();
+    }
+
+    /// Scores a window by its peak amplitude
+    struct PeakModel;
+
+    impl WakeModel for PeakModel {
+        fn name(&self) -> &str {
+            "peak"
+        }
+
+        fn score(&mut self, window: &[f32]) -> loom_core::Result<f32> {
+            Ok(window.iter().fold(0.0f32, |m, s| m.max(s.abs())))
+        }
+    }
+
+    /// 20ms of 16kHz mono PCM at a constant amplitude
+    fn voiced_event(amplitude: i16) -> Event {
+        let mut metadata = HashMap::new();
+        metadata.insert("sample_rate".to_string(), "16000".to_string());
+        metadata.insert("channels".to_string(), "1".to_string());
+
+        Event {
+            id: gen_id(),
+            r#type: "audio_voiced".to_string(),
+            timestamp_ms: now_ms(),
+            source: "test_vad".to_string(),
+            metadata,
+            payload: std::iter::repeat(amplitude.to_le_bytes())
+                .take(320)
+                .flatten()
+                .collect(),
+            confidence: 1.0,
+            tags: vec![],
+            priority: 80,
+        }
+    }
+
+    fn acoustic_config(ns: &str) -> WakeWordConfig {
+        WakeWordConfig {
+            transcript_topic: format!("test.transcript.{}", ns),
+            wake_topic: format!("test.wake.{}", ns),
+            query_topic: format!("test.query.{}", ns),
+            phrases: vec!["hey loom".into()],
+            mode: WakeMode::Acoustic,
+            voiced_topic: format!("test.voiced.{}", ns),
+            model_path: None,
+            acoustic_threshold: 0.5,
+            window_ms: 320,
+            hop_ms: 20,
+            refractory_ms: 60_000,
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_acoustic_wake_arms_transcript_query() {
+        let bus = Arc::new(EventBus::new().await.unwrap());
+        bus.start().await.unwrap();
+
+        let cfg = acoustic_config(&gen_id());
+        let (_w_id, mut wake_rx) = bus
+            .subscribe(
+                cfg.wake_topic.clone(),
+                vec!["wake_word_detected".into()],
+                QoSLevel::QosRealtime,
+            )
+            .await
+            .unwrap();
+        let (_q_id, mut query_rx) = bus
+            .subscribe(
+                cfg.query_topic.clone(),
+                vec!["user.query".into()],
+                QoSLevel::QosRealtime,
+            )
+            .await
+            .unwrap();
+
+        let detector =
+            WakeWordDetector::new(Arc::clone(&bus), cfg.clone()).with_model(Box::new(PeakModel));
+        let _handle = detector.start().await.unwrap();
+        tokio::time::sleep(tokio::time::Duration::from_millis(20)).await;
+
+        // The model owns wake decisions: phrases in transcripts are ignored
+        bus.publish(
+            &cfg.transcript_topic,
+            transcript_event("hey loom what's up"),
+        )
+        .await
+        .unwrap();
+        // Quiet audio stays under the threshold
+        for _ in 0..5 {
+            bus.publish(&cfg.voiced_topic, voiced_event(300))
+                .await
+                .unwrap();
+        }
+        tokio::time::sleep(tokio::time::Duration::from_millis(200)).await;
+        assert!(wake_rx.try_recv().is_err());
+        assert!(query_rx.try_recv().is_err());
+
+        // Loud audio wakes, once per refractory period
+        for _ in 0..5 {
+            bus.publish(&cfg.voiced_topic, voiced_event(30_000))
+                .await
+                .unwrap();
+        }
+        let ev = tokio::time::timeout(tokio::time::Duration::from_secs(1), wake_rx.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            ev.metadata.get("detector").map(String::as_str),
+            Some("acoustic")
+        );
+        assert_eq!(ev.metadata.get("phrase").map(String::as_str), Some("peak"));
+        let session_id = ev.metadata.get("session_id").cloned();
+        tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+        assert!(wake_rx.try_recv().is_err());
+
+        // The waking utterance's transcript: wake phrase stripped
+        bus.publish(
+            &cfg.transcript_topic,
+            transcript_event("Hey loom, what's the weather"),
+        )
+        .await
+        .unwrap();
+        let ev = tokio::time::timeout(tokio::time::Duration::from_secs(1), query_rx.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            ev.metadata.get("text").map(String::as_str),
+            Some("whats the weather")
+        );
+        assert_eq!(ev.metadata.get("session_id").cloned(), session_id);
+
+        bus.shutdown().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_acoustic_mode_falls_back_to_transcripts_without_model() {
+        let bus = Arc::new(EventBus::new().await.unwrap());
+        bus.start().await.unwrap();
+
+        let cfg = acoustic_config(&gen_id());
+        let (_w_id, mut wake_rx) = bus
+            .subscribe(
+                cfg.wake_topic.clone(),
+                vec!["wake_word_detected".into()],
+                QoSLevel::QosRealtime,
+            )
+            .await
+            .unwrap();
+
+        let detector = WakeWordDetector::new(Arc::clone(&bus), cfg.clone());
+        let _handle = detector.start().await.unwrap();
+        tokio::time::sleep(tokio::time::Duration::from_millis(20)).await;
+
+        bus.publish(
+            &cfg.transcript_topic,
+            transcript_event("hey loom what's up"),
+        )
+        .await
+        .unwrap();
+        let ev = tokio::time::timeout(tokio::time::Duration::from_secs(1), wake_rx.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            ev.metadata.get("phrase").map(String::as_str),
+            Some("hey loom")
+        );
+        assert_eq!(
+            ev.metadata.get("detector").map(String::as_str),
+            Some("transcript")
+        );
+
+        bus.shutdown().await.unwrap();
+    }
 }
 
 // Placeholder test when the feature is disabled, so `cargo test` still passes
@@ -0,0 +1,831 @@
+//! `LoomAgent`: a Bridge session that stays up.
+//!
+//! `connect` registers the agent and opens its event stream (Ack handshake first). A
+//! background task then owns the stream: it forwards queued publishes and tool results,
+//! dispatches deliveries to subscriptions and tool calls to the registered tools, and
+//! sends an inline ping every `heartbeat_interval`. When the stream fails or a pong does
+//! not arrive within `heartbeat_timeout`, it re-registers and reopens the stream with
+//! exponential backoff. Publishes made while disconnected wait in the outbound queue.
+//! A `ToolCancel` from the Bridge aborts the matching tool call; no result is sent for it.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::Value;
+use tokio::sync::{mpsc, watch};
+use tokio::task::{AbortHandle, JoinHandle};
+use tokio::time::{sleep, sleep_until, Instant, MissedTickBehavior};
+use tokio_stream::wrappers::ReceiverStream;
+use tonic::metadata::{Ascii, MetadataValue};
+use tonic::service::interceptor::InterceptedService;
+use tonic::service::Interceptor;
+use tonic::transport::{Channel, Endpoint};
+use tracing::{debug, info, warn};
+
+use loom_proto::{
+    bridge_client::BridgeClient, client_event, server_event, Ack, AgentDescriptor,
+    AgentRegisterRequest, ClientEvent, Event, HeartbeatRequest, ListAgentsRequest,
+    ListToolsRequest, Publish, PublishBatch, ServerEvent, ToolCall, ToolDescriptor, ToolResult,
+    ToolStatus,
+};
+
+use crate::delivery::{topic_matches, Delivery, Subscription};
+use crate::tool::{descriptor, Tool};
+use crate::{ClientError, Result};
+
+/// Address used when `LOOM_BRIDGE_ADDR` is not set
+pub const DEFAULT_BRIDGE_ADDR: &str = "127.0.0.1:50051";
+
+/// Publishes and tool results queued for the stream
+const OUTBOUND_CAPACITY: usize = 1024;
+
+/// Deliveries buffered per subscription before new ones are dropped
+const SUBSCRIPTION_CAPACITY: usize = 256;
+
+/// Bridge address from `LOOM_BRIDGE_ADDR`, or `DEFAULT_BRIDGE_ADDR`
+pub fn bridge_addr() -> String {
+    std::env::var("LOOM_BRIDGE_ADDR").unwrap_or_else(|_| DEFAULT_BRIDGE_ADDR.to_string())
+}
+
+type Client = BridgeClient<InterceptedService<Channel, BearerToken>>;
+
+/// Adds `authorization: Bearer <token>` to every RPC when a token is set
+#[derive(Clone)]
+struct BearerToken(Option<MetadataValue<Ascii>>);
+
+impl Interceptor for BearerToken {
+    fn call(
+        &mut self,
+        mut request: tonic::Request<()>,
+    ) -> std::result::Result<tonic::Request<()>, tonic::Status> {
+        if let Some(ref value) = self.0 {
+            request
+                .metadata_mut()
+                .insert("authorization", value.clone());
+        }
+        Ok(request)
+    }
+}
+
+/// Backoff between reconnect attempts
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReconnectPolicy {
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+    /// Give up after this many failed attempts in a row (`None` = never)
+    pub max_attempts: Option<u32>,
+}
+
+impl Default for ReconnectPolicy {
+    fn default() -> Self {
+        Self {
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(10),
+            max_attempts: None,
+        }
+    }
+}
+
+/// Builder for `LoomAgent`
+pub struct LoomAgentBuilder {
+    agent_id: String,
+    topics: Vec<String>,
+    tools: Vec<Arc<dyn Tool>>,
+    metadata: HashMap<String, String>,
+    token: Option<String>,
+    heartbeat_interval: Duration,
+    heartbeat_timeout: Duration,
+    reconnect: ReconnectPolicy,
+}
+
+impl Default for LoomAgentBuilder {
+    fn default() -> Self {
+        Self {
+            agent_id: format!("rust-agent-{}", std::process::id()),
+            topics: Vec::new(),
+            tools: Vec::new(),
+            metadata: HashMap::new(),
+            token: std::env::var("LOOM_BRIDGE_TOKEN")
+                .ok()
+                .filter(|t| !t.is_empty()),
+            heartbeat_interval: Duration::from_secs(15),
+            heartbeat_timeout: Duration::from_secs(5),
+            reconnect: ReconnectPolicy::default(),
+        }
+    }
+}
+
+impl LoomAgentBuilder {
+    /// Agent id (defaults to `rust-agent-<pid>`)
+    pub fn agent_id(mut self, agent_id: impl Into<String>) -> Self {
+        self.agent_id = agent_id.into();
+        self
+    }
+
+    /// Subscribe to `topics`. The agent's reply topic `agent.<id>.replies` is always added.
+    pub fn topics<I, S>(mut self, topics: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.topics.extend(topics.into_iter().map(Into::into));
+        self
+    }
+
+    /// Serve `tool` to Loom
+    pub fn tool(mut self, tool: impl Tool + 'static) -> Self {
+        self.tools.push(Arc::new(tool));
+        self
+    }
+
+    /// Serve an already shared tool
+    pub fn tool_arc(mut self, tool: Arc<dyn Tool>) -> Self {
+        self.tools.push(tool);
+        self
+    }
+
+    /// Registration metadata
+    pub fn metadata(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.metadata.insert(key.into(), value.into());
+        self
+    }
+
+    /// Accept up to `max_events` deliveries per stream frame. The Bridge packs events
+    /// that are already waiting on a topic into one `DeliveryBatch`; they are still
+    /// dispatched to subscriptions one at a time.
+    pub fn batching(self, max_events: usize) -> Self {
+        self.metadata("accept-batch", max_events.to_string())
+    }
+
+    /// Bearer token identifying the agent's tenant on a multi-tenant Bridge (defaults
+    /// to `LOOM_BRIDGE_TOKEN`)
+    pub fn token(mut self, token: impl Into<String>) -> Self {
+        self.token = Some(token.into());
+        self
+    }
+
+    /// How often to ping the Bridge, and how long to wait for the pong before reconnecting
+    pub fn heartbeat(mut self, interval: Duration, timeout: Duration) -> Self {
+        self.heartbeat_interval = interval;
+        self.heartbeat_timeout = timeout;
+        self
+    }
+
+    pub fn reconnect(mut self, policy: ReconnectPolicy) -> Self {
+        self.reconnect = policy;
+        self
+    }
+
+    /// Register with the Bridge at `addr` (`host:port` or a full `http://` URL) and
+    /// open the event stream. Fails if the first registration does not succeed.
+    pub async fn connect(self, addr: impl AsRef<str>) -> Result<LoomAgent> {
+        let addr = addr.as_ref();
+        let endpoint = if addr.contains("://") {
+            addr.to_string()
+        } else {
+            format!("http://{addr}")
+        };
+        if self.agent_id.is_empty() {
+            return Err(ClientError::Registration("agent_id cannot be empty".into()));
+        }
+
+        let mut topics = self.topics;
+        let reply_topic = format!("agent.{}.replies", self.agent_id);
+        if !topics.contains(&reply_topic) {
+            topics.push(reply_topic);
+        }
+        let registration = AgentRegisterRequest {
+            agent_id: self.agent_id.clone(),
+            subscribed_topics: topics,
+            tools: self.tools.iter().map(|t| descriptor(t.as_ref())).collect(),
+            metadata: self.metadata,
+        };
+
+        let token = match self.token {
+            Some(token) => Some(
+                MetadataValue::try_from(format!("Bearer {token}"))
+                    .map_err(|_| ClientError::Registration("invalid token".into()))?,
+            ),
+            None => None,
+        };
+
+        // The channel re-establishes its connection on its own; reconnects reuse it
+        let channel = Endpoint::from_shared(endpoint)?.connect().await?;
+        let client = BridgeClient::with_interceptor(channel, BearerToken(token));
+        let session = Session::open(client.clone(), &registration).await?;
+        info!(agent_id = %self.agent_id, tools = registration.tools.len(), "Connected to Bridge");
+
+        let shared = Arc::new(Shared {
+            agent_id: self.agent_id,
+            tools: self.tools.into_iter().map(|t| (t.name(), t)).collect(),
+            subscribers: Mutex::new(Vec::new()),
+            running_tools: Mutex::new(HashMap::new()),
+            dropped_deliveries: AtomicU64::new(0),
+            reconnects: AtomicU64::new(0),
+            event_seq: AtomicU64::new(0),
+        });
+        let (outbound_tx, outbound_rx) = mpsc::channel(OUTBOUND_CAPACITY);
+        let (shutdown_tx, shutdown_rx) = watch::channel(false);
+        let (connected_tx, connected_rx) = watch::channel(true);
+        let supervisor = Supervisor {
+            client: client.clone(),
+            registration,
+            shared: Arc::clone(&shared),
+            outbound_rx,
+            results_tx: outbound_tx.downgrade(),
+            shutdown_rx,
+            connected_tx,
+            heartbeat_interval: self.heartbeat_interval,
+            heartbeat_timeout: self.heartbeat_timeout,
+            reconnect: self.reconnect,
+        };
+        let task = tokio::spawn(supervisor.run(session));
+
+        Ok(LoomAgent {
+            client,
+            shared,
+            outbound_tx,
+            shutdown_tx,
+            connected_rx,
+            task: Mutex::new(Some(task)),
+        })
+    }
+}
+
+/// State shared by the agent handle and its background task
+struct Shared {
+    agent_id: String,
+    tools: HashMap<String, Arc<dyn Tool>>,
+    subscribers: Mutex<Vec<(String, mpsc::Sender<Delivery>)>>,
+    // tool call id -> task running it, until it finishes or is cancelled
+    running_tools: Mutex<HashMap<String, AbortHandle>>,
+    dropped_deliveries: AtomicU64,
+    reconnects: AtomicU64,
+    event_seq: AtomicU64,
+}
+
+impl Shared {
+    fn dispatch(&self, delivery: Delivery) {
+        let mut subscribers = self.subscribers.lock().unwrap();
+        subscribers.retain(|(pattern, tx)| {
+            if !topic_matches(pattern, &delivery.topic) {
+                return !tx.is_closed();
+            }
+            match tx.try_send(delivery.clone()) {
+                Ok(()) => true,
+                Err(mpsc::error::TrySendError::Full(_)) => {
+                    self.dropped_deliveries.fetch_add(1, Ordering::Relaxed);
+                    warn!(agent_id = %self.agent_id, topic = %delivery.topic, %pattern, "Subscription full; dropping delivery");
+                    true
+                }
+                Err(mpsc::error::TrySendError::Closed(_)) => false,
+            }
+        });
+    }
+}
+
+/// A Rust agent connected to Loom through the Bridge
+///
+/// ```ignore
+/// let agent = LoomAgent::builder()
+///     .agent_id("weather-agent")
+///     .topics(["weather.requests"])
+///     .tool(weather_tool)
+///     .connect("127.0.0.1:50051")
+///     .await?;
+///
+/// let mut requests = agent.subscribe("weather.requests");
+/// while let Some(Ok((delivery, req))) = requests.recv_json::<WeatherRequest>().await {
+///     agent.publish_json("weather.updates", "weather.updated", &lookup(&req).await).await?;
+/// }
+/// ```
+pub struct LoomAgent {
+    client: Client,
+    shared: Arc<Shared>,
+    outbound_tx: mpsc::Sender<ClientEvent>,
+    shutdown_tx: watch::Sender<bool>,
+    connected_rx: watch::Receiver<bool>,
+    task: Mutex<Option<JoinHandle<()>>>,
+}
+
+impl LoomAgent {
+    pub fn builder() -> LoomAgentBuilder {
+        LoomAgentBuilder::default()
+    }
+
+    pub fn agent_id(&self) -> &str {
+        &self.shared.agent_id
+    }
+
+    /// Whether the event stream is currently up
+    pub fn is_connected(&self) -> bool {
+        *self.connected_rx.borrow()
+    }
+
+    /// Wait until the event stream is up. Fails if the agent gave up reconnecting.
+    pub async fn wait_connected(&self) -> Result<()> {
+        let mut rx = self.connected_rx.clone();
+        rx.wait_for(|up| *up)
+            .await
+            .map_err(|_| ClientError::Closed)?;
+        Ok(())
+    }
+
+    /// Times the stream was re-established after a failure
+    pub fn reconnects(&self) -> u64 {
+        self.shared.reconnects.load(Ordering::Relaxed)
+    }
+
+    /// Deliveries dropped because a subscription was not drained fast enough
+    pub fn dropped_deliveries(&self) -> u64 {
+        self.shared.dropped_deliveries.load(Ordering::Relaxed)
+    }
+
+    /// Deliveries on topics matching `pattern` (exact, or `prefix.*`). Only topics the
+    /// agent registered are delivered; deliveries arriving before this call are not seen.
+    pub fn subscribe(&self, pattern: impl Into<String>) -> Subscription {
+        let pattern = pattern.into();
+        let (tx, rx) = mpsc::channel(SUBSCRIPTION_CAPACITY);
+        self.shared
+            .subscribers
+            .lock()
+            .unwrap()
+            .push((pattern.clone(), tx));
+        Subscription::new(pattern, rx)
+    }
+
+    /// An event from this agent with a fresh id and the current timestamp
+    pub fn event(&self, event_type: impl Into<String>, payload: Vec<u8>) -> Event {
+        let seq = self.shared.event_seq.fetch_add(1, Ordering::Relaxed);
+        let timestamp_ms = now_ms();
+        Event {
+            id: format!("{}-{timestamp_ms}-{seq}", self.shared.agent_id),
+            r#type: event_type.into(),
+            timestamp_ms,
+            source: self.shared.agent_id.clone(),
+            metadata: HashMap::new(),
+            payload,
+            confidence: 1.0,
+            tags: vec![],
+            priority: 50,
+        }
+    }
+
+    /// Publish `event` on `topic`. Waits for room when the outbound queue is full.
+    pub async fn publish(&self, topic: impl Into<String>, event: Event) -> Result<()> {
+        self.outbound_tx
+            .send(ClientEvent {
+                msg: Some(client_event::Msg::Publish(Publish {
+                    topic: topic.into(),
+                    event: Some(event),
+                })),
+            })
+            .await
+            .map_err(|_| ClientError::Closed)
+    }
+
+    /// Publish `events` on `topic` in one stream frame, in order
+    pub async fn publish_batch(&self, topic: impl Into<String>, events: Vec<Event>) -> Result<()> {
+        if events.is_empty() {
+            return Ok(());
+        }
+        self.outbound_tx
+            .send(ClientEvent {
+                msg: Some(client_event::Msg::PublishBatch(PublishBatch {
+                    topic: topic.into(),
+                    events,
+                })),
+            })
+            .await
+            .map_err(|_| ClientError::Closed)
+    }
+
+    /// Publish `payload` as a JSON event of type `event_type`
+    pub async fn publish_json<T: Serialize + ?Sized>(
+        &self,
+        topic: impl Into<String>,
+        event_type: impl Into<String>,
+        payload: &T,
+    ) -> Result<()> {
+        let event = self.event(event_type, serde_json::to_vec(payload)?);
+        self.publish(topic, event).await
+    }
+
+    /// Acknowledge processing of `delivery`. Deliveries on critical and guaranteed
+    /// topics (`Delivery::needs_ack`) are redelivered until acked; acking any other
+    /// delivery is a no-op on the server.
+    pub async fn ack(&self, delivery: &Delivery) -> Result<()> {
+        self.outbound_tx
+            .send(ClientEvent {
+                msg: Some(client_event::Msg::Ack(Ack {
+                    message_id: delivery.event.id.clone(),
+                })),
+            })
+            .await
+            .map_err(|_| ClientError::Closed)
+    }
+
+    /// Invoke a tool in Loom's ToolRegistry through `ForwardToolCall`
+    pub async fn call_tool<R: DeserializeOwned>(
+        &self,
+        name: &str,
+        arguments: &impl Serialize,
+    ) -> Result<R> {
+        let seq = self.shared.event_seq.fetch_add(1, Ordering::Relaxed);
+        let call = ToolCall {
+            id: format!("{}-call-{seq}", self.shared.agent_id),
+            name: name.to_string(),
+            arguments: serde_json::to_string(arguments)?,
+            headers: Default::default(),
+            timeout_ms: 0,
+            correlation_id: String::new(),
+            qos: 0,
+        };
+        let result = self
+            .client
+            .clone()
+            .forward_tool_call(call)
+            .await?
+            .into_inner();
+        if result.status != ToolStatus::ToolOk as i32 {
+            let message = result
+                .error
+                .map(|e| format!("{}: {}", e.code, e.message))
+                .unwrap_or_else(|| format!("status {}", result.status));
+            return Err(ClientError::Tool(message));
+        }
+        let output = if result.output.is_empty() {
+            "null"
+        } else {
+            &result.output
+        };
+        Ok(serde_json::from_str(output)?)
+    }
+
+    /// Tools callable with `call_tool` whose name starts with `prefix` (all if empty),
+    /// sorted by name
+    pub async fn list_tools(&self, prefix: &str) -> Result<Vec<ToolDescriptor>> {
+        let response = self
+            .client
+            .clone()
+            .list_tools(ListToolsRequest {
+                name_prefix: prefix.to_string(),
+            })
+            .await?;
+        Ok(response.into_inner().tools)
+    }
+
+    /// Connected agents providing `capability` (all if empty), sorted by agent id. On a
+    /// multi-tenant Bridge only agents of this agent's tenant are listed.
+    pub async fn list_agents(&self, capability: &str) -> Result<Vec<AgentDescriptor>> {
+        let response = self
+            .client
+            .clone()
+            .list_agents(ListAgentsRequest {
+                capability: capability.to_string(),
+                ..Default::default()
+            })
+            .await?;
+        Ok(response.into_inner().agents)
+    }
+
+    /// Flush queued publishes and tool results, then close the stream
+    pub async fn shutdown(&self) {
+        let _ = self.shutdown_tx.send(true);
+        let task = self.task.lock().unwrap().take();
+        if let Some(task) = task {
+            let _ = task.await;
+        }
+    }
+}
+
+impl Drop for LoomAgent {
+    fn drop(&mut self) {
+        let _ = self.shutdown_tx.send(true);
+    }
+}
+
+/// One registered agent with an open event stream
+struct Session {
+    stream_tx: mpsc::Sender<ClientEvent>,
+    inbound: tonic::Streaming<ServerEvent>,
+}
+
+impl Session {
+    async fn open(mut client: Client, registration: &AgentRegisterRequest) -> Result<Self> {
+        let resp = client
+            .register_agent(registration.clone())
+            .await?
+            .into_inner();
+        if !resp.success {
+            return Err(ClientError::Registration(resp.error_message));
+        }
+
+        // The Ack must be queued before awaiting the RPC, or the handshake deadlocks
+        let (stream_tx, stream_rx) = mpsc::channel(OUTBOUND_CAPACITY);
+        stream_tx
+            .send(ClientEvent {
+                msg: Some(client_event::Msg::Ack(Ack {
+                    message_id: registration.agent_id.clone(),
+                })),
+            })
+            .await
+            .map_err(|_| ClientError::Closed)?;
+        let inbound = client
+            .event_stream(ReceiverStream::new(stream_rx))
+            .await?
+            .into_inner();
+        Ok(Self { stream_tx, inbound })
+    }
+}
+
+/// Why a session ended
+enum SessionEnd {
+    Shutdown,
+    Disconnected(String),
+}
+
+/// Background task owning the stream
+struct Supervisor {
+    client: Client,
+    registration: AgentRegisterRequest,
+    shared: Arc<Shared>,
+    outbound_rx: mpsc::Receiver<ClientEvent>,
+    // Weak so that dropping the `LoomAgent` closes the queue
+    results_tx: mpsc::WeakSender<ClientEvent>,
+    shutdown_rx: watch::Receiver<bool>,
+    connected_tx: watch::Sender<bool>,
+    heartbeat_interval: Duration,
+    heartbeat_timeout: Duration,
+    reconnect: ReconnectPolicy,
+}
+
+impl Supervisor {
+    async fn run(mut self, mut session: Session) {
+        let agent_id = self.shared.agent_id.clone();
+        loop {
+            let _ = self.connected_tx.send(true);
+            let end = self.run_session(&mut session).await;
+            let _ = self.connected_tx.send(false);
+            match end {
+                SessionEnd::Shutdown => {
+                    info!(agent_id = %agent_id, "Agent shut down");
+                    return;
+                }
+                SessionEnd::Disconnected(reason) => {
+                    warn!(agent_id = %agent_id, %reason, "Bridge stream lost; reconnecting");
+                }
+            }
+            match self.reopen().await {
+                Some(next) => {
+                    self.shared.reconnects.fetch_add(1, Ordering::Relaxed);
+                    info!(agent_id = %agent_id, "Reconnected to Bridge");
+                    session = next;
+                }
+                None => return,
+            }
+        }
+    }
+
+    /// Re-register and reopen the stream with backoff. `None` on shutdown or when
+    /// `max_attempts` is exhausted.
+    async fn reopen(&mut self) -> Option<Session> {
+        let mut backoff = self.reconnect.initial_backoff;
+        let mut attempt = 0u32;
+        loop {
+            if self
+                .reconnect
+                .max_attempts
+                .is_some_and(|max| attempt >= max)
+            {
+                warn!(agent_id = %self.shared.agent_id, attempts = attempt, "Giving up reconnecting to Bridge");
+                return None;
+            }
+            tokio::select! {
+                _ = stopped(&mut self.shutdown_rx) => return None,
+                _ = sleep(backoff) => {}
+            }
+            attempt += 1;
+            match Session::open(self.client.clone(), &self.registration).await {
+                Ok(session) => return Some(session),
+                Err(e) => {
+                    debug!(agent_id = %self.shared.agent_id, attempt, error = %e, "Reconnect failed");
+                    backoff = (backoff * 2).min(self.reconnect.max_backoff);
+                }
+            }
+        }
+    }
+
+    async fn run_session(&mut self, session: &mut Session) -> SessionEnd {
+        let mut heartbeat = tokio::time::interval(self.heartbeat_interval);
+        heartbeat.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        heartbeat.tick().await;
+        let mut pong_deadline: Option<Instant> = None;
+
+        loop {
+            tokio::select! {
+                _ = stopped(&mut self.shutdown_rx) => {
+                    // Flush what was queued before the shutdown
+                    while let Ok(msg) = self.outbound_rx.try_recv() {
+                        if session.stream_tx.send(msg).await.is_err() {
+                            break;
+                        }
+                    }
+                    return SessionEnd::Shutdown;
+                }
+                msg = self.outbound_rx.recv() => {
+                    let Some(msg) = msg else {
+                        return SessionEnd::Shutdown;
+                    };
+                    if session.stream_tx.send(msg).await.is_err() {
+                        return SessionEnd::Disconnected("outbound stream closed".into());
+                    }
+                }
+                msg = session.inbound.message() => match msg {
+                    Ok(Some(msg)) => {
+                        if let Some(server_event::Msg::Pong(_)) = msg.msg {
+                            pong_deadline = None;
+                        } else {
+                            handle(&self.shared, &self.results_tx, msg);
+                        }
+                    }
+                    Ok(None) => return SessionEnd::Disconnected("stream closed by server".into()),
+                    Err(status) => return SessionEnd::Disconnected(status.to_string()),
+                },
+                _ = heartbeat.tick() => {
+                    let ping = ClientEvent {
+                        msg: Some(client_event::Msg::Ping(HeartbeatRequest {
+                            timestamp_ms: now_ms(),
+                        })),
+                    };
+                    if session.stream_tx.send(ping).await.is_err() {
+                        return SessionEnd::Disconnected("outbound stream closed".into());
+                    }
+                    pong_deadline.get_or_insert(Instant::now() + self.heartbeat_timeout);
+                }
+                _ = sleep_until(pong_deadline.unwrap_or_else(Instant::now)), if pong_deadline.is_some() => {
+                    return SessionEnd::Disconnected("heartbeat timed out".into());
+                }
+            }
+        }
+    }
+}
+
+/// Act on a message from the Bridge other than a pong
+fn handle(shared: &Arc<Shared>, results_tx: &mpsc::WeakSender<ClientEvent>, msg: ServerEvent) {
+    match msg.msg {
+        Some(server_event::Msg::Delivery(d)) => {
+            if let Some(event) = d.event {
+                shared.dispatch(Delivery {
+                    topic: d.topic,
+                    event,
+                });
+            }
+        }
+        Some(server_event::Msg::DeliveryBatch(batch)) => {
+            for event in batch.events {
+                shared.dispatch(Delivery {
+                    topic: batch.topic.clone(),
+                    event,
+                });
+            }
+        }
+        Some(server_event::Msg::ToolCall(call)) => {
+            let tool = shared.tools.get(&call.name).cloned();
+            let results_tx = results_tx.clone();
+            let task_shared = Arc::clone(shared);
+            let call_id = call.id.clone();
+            // Held until the handle is recorded so a fast call cannot finish first
+            let mut running = shared.running_tools.lock().unwrap();
+            let task = tokio::spawn(async move {
+                let result = run_tool(tool, call).await;
+                task_shared.running_tools.lock().unwrap().remove(&result.id);
+                // Dropped if the agent shut down while the tool ran
+                if let Some(tx) = results_tx.upgrade() {
+                    let _ = tx
+                        .send(ClientEvent {
+                            msg: Some(client_event::Msg::ToolResult(result)),
+                        })
+                        .await;
+                }
+            });
+            running.insert(call_id, task.abort_handle());
+        }
+        Some(server_event::Msg::ToolCancel(cancel)) => {
+            let task = shared.running_tools.lock().unwrap().remove(&cancel.id);
+            match task {
+                Some(task) => {
+                    task.abort();
+                    info!(agent_id = %shared.agent_id, call_id = %cancel.id, reason = %cancel.reason, "Tool call cancelled");
+                }
+                None => {
+                    debug!(agent_id = %shared.agent_id, call_id = %cancel.id, "Cancel for a tool call not running");
+                }
+            }
+        }
+        Some(server_event::Msg::Err(err)) => {
+            warn!(agent_id = %shared.agent_id, code = %err.code, message = %err.message, "Server error on stream");
+        }
+        Some(server_event::Msg::Pong(_)) | None => {}
+    }
+}
+
+/// Run a pushed tool call and build its result
+async fn run_tool(tool: Option<Arc<dyn Tool>>, call: ToolCall) -> ToolResult {
+    let Some(tool) = tool else {
+        return error_result(
+            call.id,
+            ToolStatus::ToolNotFound,
+            "NOT_FOUND",
+            format!("Tool '{}' not found", call.name),
+        );
+    };
+    let arguments = if call.arguments.is_empty() {
+        Value::Object(Default::default())
+    } else {
+        match serde_json::from_str(&call.arguments) {
+            Ok(v) => v,
+            Err(e) => {
+                return error_result(
+                    call.id,
+                    ToolStatus::ToolInvalidArguments,
+                    "INVALID_ARGUMENTS",
+                    format!("Failed to parse arguments JSON: {e}"),
+                )
+            }
+        }
+    };
+
+    let outcome = if call.timeout_ms > 0 {
+        match tokio::time::timeout(
+            Duration::from_millis(call.timeout_ms as u64),
+            tool.call(arguments),
+        )
+        .await
+        {
+            Ok(outcome) => outcome,
+            Err(_) => {
+                return error_result(
+                    call.id,
+                    ToolStatus::ToolTimeout,
+                    "TIMEOUT",
+                    format!("Tool '{}' timed out", call.name),
+                )
+            }
+        }
+    } else {
+        tool.call(arguments).await
+    };
+
+    match outcome {
+        Ok(output) => ToolResult {
+            id: call.id,
+            status: ToolStatus::ToolOk as i32,
+            output: output.to_string(),
+            error: None,
+        },
+        Err(e) => {
+            let (status, code) = e.status();
+            error_result(call.id, status, code, e.to_string())
+        }
+    }
+}
+
+fn error_result(id: String, status: ToolStatus, code: &str, message: String) -> ToolResult {
+    ToolResult {
+        id,
+        status: status as i32,
+        output: String::new(),
+        error: Some(loom_proto::ToolError {
+            code: code.into(),
+            message,
+            details: Default::default(),
+        }),
+    }
+}
+
+/// Resolves once shutdown is requested or the agent is dropped. Unlike `wait_for` it
+/// holds no watch guard, so the select arm that follows may await.
+async fn stopped(shutdown_rx: &mut watch::Receiver<bool>) {
+    let _ = shutdown_rx.wait_for(|stop| *stop).await;
+}
+
+fn now_ms() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as i64)
+        .unwrap_or_default()
+}
+
+impl std::fmt::Debug for LoomAgent {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LoomAgent")
+            .field("agent_id", &self.shared.agent_id)
+            .field("connected", &self.is_connected())
+            .finish()
+    }
+}
@@ -0,0 +1,91 @@
+//! Events delivered to an agent on its subscribed topics.
+
+use serde::de::DeserializeOwned;
+use tokio::sync::mpsc;
+
+use loom_proto::Event;
+
+use crate::Result;
+
+/// Metadata keys the core EventBus sets on deliveries that must be acked
+const RECEIPT_SUBSCRIBER_KEY: &str = "receipt.subscriber";
+const RECEIPT_ATTEMPT_KEY: &str = "receipt.attempt";
+
+/// An event received on one of the agent's topics
+#[derive(Debug, Clone)]
+pub struct Delivery {
+    pub topic: String,
+    pub event: Event,
+}
+
+impl Delivery {
+    /// The event's type (e.g. "weather.updated")
+    pub fn event_type(&self) -> &str {
+        &self.event.r#type
+    }
+
+    /// Metadata value, e.g. an envelope key such as `thread_id`
+    pub fn metadata(&self, key: &str) -> Option<&str> {
+        self.event.metadata.get(key).map(String::as_str)
+    }
+
+    /// Whether the delivery is on a critical or guaranteed topic and must be acked
+    /// with `LoomAgent::ack` once processed
+    pub fn needs_ack(&self) -> bool {
+        self.event.metadata.contains_key(RECEIPT_SUBSCRIBER_KEY)
+    }
+
+    /// Delivery attempt: 1, or higher when redelivered for a missing ack
+    pub fn attempt(&self) -> u32 {
+        self.metadata(RECEIPT_ATTEMPT_KEY)
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(1)
+    }
+
+    /// Decode the JSON payload
+    pub fn json<T: DeserializeOwned>(&self) -> Result<T> {
+        Ok(serde_json::from_slice(&self.event.payload)?)
+    }
+}
+
+/// Deliveries whose topic matches a pattern, from `LoomAgent::subscribe`
+pub struct Subscription {
+    pattern: String,
+    rx: mpsc::Receiver<Delivery>,
+}
+
+impl Subscription {
+    pub(crate) fn new(pattern: String, rx: mpsc::Receiver<Delivery>) -> Self {
+        Self { pattern, rx }
+    }
+
+    /// The topic pattern this subscription filters on
+    pub fn pattern(&self) -> &str {
+        &self.pattern
+    }
+
+    /// Next delivery, or `None` once the agent has shut down
+    pub async fn recv(&mut self) -> Option<Delivery> {
+        self.rx.recv().await
+    }
+
+    /// Next delivery with its payload decoded as `T`
+    pub async fn recv_json<T: DeserializeOwned>(&mut self) -> Option<Result<(Delivery, T)>> {
+        let delivery = self.rx.recv().await?;
+        Some(delivery.json().map(|value| (delivery, value)))
+    }
+}
+
+/// Same wildcard semantics as the core EventBus: exact match, or `prefix.*`
+/// matching any topic below `prefix`
+pub(crate) fn topic_matches(pattern: &str, topic: &str) -> bool {
+    if pattern == topic {
+        return true;
+    }
+    match pattern.strip_suffix(".*") {
+        Some(prefix) => topic
+            .strip_prefix(prefix)
+            .is_some_and(|rest| rest.starts_with('.')),
+        None => false,
+    }
+}
@@ -0,0 +1,25 @@
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum ClientError {
+    #[error("transport error: {0}")]
+    Transport(#[from] tonic::transport::Error),
+    #[error("rpc failed: {0}")]
+    Rpc(Box<tonic::Status>),
+    #[error("registration failed: {0}")]
+    Registration(String),
+    #[error("invalid payload: {0}")]
+    Codec(#[from] serde_json::Error),
+    #[error("tool call failed: {0}")]
+    Tool(String),
+    #[error("agent is shut down")]
+    Closed,
+}
+
+impl From<tonic::Status> for ClientError {
+    fn from(status: tonic::Status) -> Self {
+        ClientError::Rpc(Box::new(status))
+    }
+}
+
+pub type Result<T> = std::result::Result<T, ClientError>;
@@ -0,0 +1,36 @@
+//! Loom Client - Rust SDK for agents running outside the Loom runtime
+//!
+//! Wraps the Bridge gRPC protocol (registration, Ack handshake, bidirectional event
+//! stream, heartbeats, server-push tool calls) behind `LoomAgent`:
+//!
+//! ```ignore
+//! use loom_client::{FnTool, LoomAgent};
+//! use serde_json::json;
+//!
+//! let agent = LoomAgent::builder()
+//!     .agent_id("echo-agent")
+//!     .topics(["chat.requests"])
+//!     .tool(FnTool::new("echo:say", "Echo the input", |args| async move {
+//!         Ok(json!({ "echo": args }))
+//!     }))
+//!     .connect("127.0.0.1:50051")
+//!     .await?;
+//!
+//! let mut requests = agent.subscribe("chat.requests");
+//! while let Some(delivery) = requests.recv().await {
+//!     agent.publish_json("chat.replies", "chat.reply", &json!({"seen": delivery.event.id})).await?;
+//! }
+//! ```
+
+pub mod agent;
+pub mod delivery;
+pub mod error;
+pub mod tool;
+
+pub use agent::{bridge_addr, LoomAgent, LoomAgentBuilder, ReconnectPolicy, DEFAULT_BRIDGE_ADDR};
+pub use delivery::{Delivery, Subscription};
+pub use error::{ClientError, Result};
+pub use tool::{FnTool, Tool, ToolError};
+
+// Re-export proto types used in the public API
+pub use loom_proto::{AgentDescriptor, Event, ToolDescriptor};
@@ -0,0 +1,161 @@
+//! Tools an agent exposes to Loom.
+//!
+//! The Bridge pushes a `ToolCall` down the agent's stream; `LoomAgent` looks the tool up
+//! by name, runs it on its own task and sends the `ToolResult` back.
+
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::{json, Value};
+use thiserror::Error;
+
+use loom_proto::{ToolDescriptor, ToolStatus};
+
+/// Failure returned by a tool callback
+#[derive(Error, Debug)]
+pub enum ToolError {
+    #[error("Invalid arguments: {0}")]
+    InvalidArguments(String),
+
+    #[error("Execution failed: {0}")]
+    ExecutionFailed(String),
+}
+
+impl ToolError {
+    /// Status and error code reported to the Bridge
+    pub(crate) fn status(&self) -> (ToolStatus, &'static str) {
+        match self {
+            ToolError::InvalidArguments(_) => {
+                (ToolStatus::ToolInvalidArguments, "INVALID_ARGUMENTS")
+            }
+            ToolError::ExecutionFailed(_) => (ToolStatus::ToolError, "TOOL_ERROR"),
+        }
+    }
+}
+
+/// A tool served by this agent
+#[async_trait]
+pub trait Tool: Send + Sync {
+    /// The unique name of the tool (e.g., "weather:get")
+    fn name(&self) -> String;
+
+    /// A human-readable description of what the tool does
+    fn description(&self) -> String;
+
+    /// The JSON Schema for the tool's arguments
+    fn parameters(&self) -> Value {
+        json!({"type": "object"})
+    }
+
+    /// Execute the tool with the given arguments
+    async fn call(&self, arguments: Value) -> Result<Value, ToolError>;
+}
+
+pub(crate) fn descriptor(tool: &dyn Tool) -> ToolDescriptor {
+    ToolDescriptor {
+        name: tool.name(),
+        description: tool.description(),
+        parameters_schema: tool.parameters().to_string(),
+        provider: loom_proto::ProviderKind::ProviderGrpc as i32,
+        metadata: Default::default(),
+    }
+}
+
+type Handler = Arc<
+    dyn Fn(Value) -> Pin<Box<dyn Future<Output = Result<Value, ToolError>> + Send>> + Send + Sync,
+>;
+
+/// A `Tool` backed by an async closure
+///
+/// ```ignore
+/// #[derive(Deserialize)]
+/// struct Args { city: String }
+///
+/// let weather = FnTool::typed("weather:get", "Current weather", |args: Args| async move {
+///     Ok(json!({ "city": args.city, "temperature": 21.5 }))
+/// })
+/// .with_parameters(json!({
+///     "type": "object",
+///     "properties": { "city": { "type": "string" } },
+///     "required": ["city"]
+/// }));
+/// ```
+#[derive(Clone)]
+pub struct FnTool {
+    name: String,
+    description: String,
+    parameters: Value,
+    handler: Handler,
+}
+
+impl FnTool {
+    /// Tool taking and returning raw JSON
+    pub fn new<F, Fut>(name: impl Into<String>, description: impl Into<String>, f: F) -> Self
+    where
+        F: Fn(Value) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<Value, ToolError>> + Send + 'static,
+    {
+        Self {
+            name: name.into(),
+            description: description.into(),
+            parameters: json!({"type": "object"}),
+            handler: Arc::new(move |args| Box::pin(f(args))),
+        }
+    }
+
+    /// Tool whose arguments are deserialized into `A` and whose result is serialized from `R`.
+    /// Arguments that do not deserialize fail with `ToolError::InvalidArguments`.
+    pub fn typed<A, R, F, Fut>(
+        name: impl Into<String>,
+        description: impl Into<String>,
+        f: F,
+    ) -> Self
+    where
+        A: DeserializeOwned + Send + 'static,
+        R: Serialize + Send + 'static,
+        F: Fn(A) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<R, ToolError>> + Send + 'static,
+    {
+        let f = Arc::new(f);
+        Self::new(name, description, move |args| {
+            let f = Arc::clone(&f);
+            async move {
+                let args: A = serde_json::from_value(args)
+                    .map_err(|e| ToolError::InvalidArguments(e.to_string()))?;
+                let result = f(args).await?;
+                serde_json::to_value(result).map_err(|e| {
+                    ToolError::ExecutionFailed(format!("failed to serialize result: {e}"))
+                })
+            }
+        })
+    }
+
+    /// Set the JSON Schema advertised for the arguments
+    pub fn with_parameters(mut self, schema: Value) -> Self {
+        self.parameters = schema;
+        self
+    }
+}
+
+#[async_trait]
+impl Tool for FnTool {
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn description(&self) -> String {
+        self.description.clone()
+    }
+
+    fn parameters(&self) -> Value {
+        self.parameters.clone()
+    }
+
+    async fn call(&self, arguments: Value) -> Result<Value, ToolError> {
+        (self.handler)(arguments).await
+    }
+}
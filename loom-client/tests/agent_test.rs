@@ -0,0 +1,365 @@
+//! LoomAgent against an in-process Bridge server.
+
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+
+use loom_client::{ClientError, FnTool, LoomAgent, ReconnectPolicy, ToolError};
+use loom_core::{AgentDirectory, EventBus, TenantConfig, TenantRegistry, ToolRegistry};
+use loom_proto::bridge_server::BridgeServer;
+use loom_proto::{ToolCall, ToolStatus};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::watch;
+use tokio_stream::wrappers::TcpListenerStream;
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+struct Order {
+    id: u32,
+    item: String,
+}
+
+#[derive(Deserialize)]
+struct AddArgs {
+    a: i64,
+    b: i64,
+}
+
+async fn bridge_state() -> loom_bridge::BridgeState {
+    let event_bus = Arc::new(EventBus::new().await.unwrap());
+    event_bus.start().await.unwrap();
+    loom_bridge::BridgeState::new(
+        event_bus,
+        Arc::new(ToolRegistry::new()),
+        Arc::new(AgentDirectory::new()),
+    )
+}
+
+async fn start_bridge() -> (SocketAddr, loom_bridge::BridgeService) {
+    start_bridge_with_state(bridge_state().await).await
+}
+
+async fn start_bridge_with_state(
+    state: loom_bridge::BridgeState,
+) -> (SocketAddr, loom_bridge::BridgeService) {
+    let svc = loom_bridge::BridgeService::new(state);
+
+    let listener = TcpListener::bind((std::net::Ipv4Addr::LOCALHOST, 0))
+        .await
+        .unwrap();
+    let addr = listener.local_addr().unwrap();
+    let server = svc.clone();
+    tokio::spawn(async move {
+        tonic::transport::Server::builder()
+            .add_service(BridgeServer::new(server))
+            .serve_with_incoming(TcpListenerStream::new(listener))
+            .await
+            .unwrap();
+    });
+    (addr, svc)
+}
+
+/// TCP proxy in front of `target`; bumping the returned watch drops every proxied
+/// connection, as a network failure would
+async fn start_proxy(target: SocketAddr) -> (SocketAddr, watch::Sender<u64>) {
+    let listener = TcpListener::bind((std::net::Ipv4Addr::LOCALHOST, 0))
+        .await
+        .unwrap();
+    let addr = listener.local_addr().unwrap();
+    let (kill_tx, kill_rx) = watch::channel(0u64);
+    tokio::spawn(async move {
+        while let Ok((mut inbound, _)) = listener.accept().await {
+            let mut kill = kill_rx.clone();
+            kill.borrow_and_update();
+            tokio::spawn(async move {
+                let Ok(mut outbound) = TcpStream::connect(target).await else {
+                    return;
+                };
+                tokio::select! {
+                    _ = tokio::io::copy_bidirectional(&mut inbound, &mut outbound) => {}
+                    _ = kill.changed() => {}
+                }
+            });
+        }
+    });
+    (addr, kill_tx)
+}
+
+fn tool_call(id: &str, name: &str, arguments: &str) -> ToolCall {
+    ToolCall {
+        id: id.into(),
+        name: name.into(),
+        arguments: arguments.into(),
+        headers: Default::default(),
+        timeout_ms: 1000,
+        correlation_id: String::new(),
+        qos: 0,
+    }
+}
+
+fn add_tool() -> FnTool {
+    FnTool::typed("math:add", "Add two integers", |args: AddArgs| async move {
+        if args.a < 0 {
+            return Err(ToolError::ExecutionFailed("negative input".into()));
+        }
+        Ok(json!({ "sum": args.a + args.b }))
+    })
+}
+
+async fn push_and_wait(
+    svc: &loom_bridge::BridgeService,
+    agent_id: &str,
+    call: ToolCall,
+) -> loom_proto::ToolResult {
+    let id = call.id.clone();
+    assert!(svc.push_tool_call(agent_id, call).await.unwrap());
+    svc.await_tool_result(&id, Duration::from_secs(2))
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_pushed_tool_calls_dispatch_to_callbacks() {
+    let (addr, svc) = start_bridge().await;
+    let agent = LoomAgent::builder()
+        .agent_id("calc")
+        .tool(add_tool())
+        .connect(addr.to_string())
+        .await
+        .unwrap();
+    assert!(agent.is_connected());
+
+    let ok = push_and_wait(
+        &svc,
+        "calc",
+        tool_call("c1", "math:add", r#"{"a":2,"b":3}"#),
+    )
+    .await;
+    assert_eq!(ok.status, ToolStatus::ToolOk as i32);
+    assert_eq!(
+        serde_json::from_str::<Value>(&ok.output).unwrap(),
+        json!({"sum": 5})
+    );
+
+    let bad_args = push_and_wait(&svc, "calc", tool_call("c2", "math:add", r#"{"a":"x"}"#)).await;
+    assert_eq!(bad_args.status, ToolStatus::ToolInvalidArguments as i32);
+
+    let failed = push_and_wait(
+        &svc,
+        "calc",
+        tool_call("c3", "math:add", r#"{"a":-1,"b":0}"#),
+    )
+    .await;
+    assert_eq!(failed.status, ToolStatus::ToolError as i32);
+    assert!(failed.error.unwrap().message.contains("negative input"));
+
+    let missing = push_and_wait(&svc, "calc", tool_call("c4", "math:mul", "{}")).await;
+    assert_eq!(missing.status, ToolStatus::ToolNotFound as i32);
+
+    agent.shutdown().await;
+}
+
+#[tokio::test]
+async fn test_typed_publish_and_subscribe() {
+    let (addr, _svc) = start_bridge().await;
+    let consumer = LoomAgent::builder()
+        .agent_id("consumer")
+        .topics(["orders.created", "orders.cancelled"])
+        .connect(addr.to_string())
+        .await
+        .unwrap();
+    let mut created = consumer.subscribe("orders.created");
+    let mut all = consumer.subscribe("orders.*");
+    let producer = LoomAgent::builder()
+        .agent_id("producer")
+        .connect(addr.to_string())
+        .await
+        .unwrap();
+
+    let order = Order {
+        id: 7,
+        item: "tea".into(),
+    };
+    producer
+        .publish_json("orders.created", "order.created", &order)
+        .await
+        .unwrap();
+    producer
+        .publish_json("orders.cancelled", "order.cancelled", &json!({"id": 7}))
+        .await
+        .unwrap();
+
+    let (delivery, received) =
+        tokio::time::timeout(Duration::from_secs(2), created.recv_json::<Order>())
+            .await
+            .unwrap()
+            .unwrap()
+            .unwrap();
+    assert_eq!(received, order);
+    assert_eq!(delivery.event_type(), "order.created");
+    assert_eq!(delivery.event.source, "producer");
+
+    let mut topics = Vec::new();
+    for _ in 0..2 {
+        let d = tokio::time::timeout(Duration::from_secs(2), all.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        topics.push(d.topic);
+    }
+    topics.sort();
+    assert_eq!(topics, vec!["orders.cancelled", "orders.created"]);
+
+    producer.shutdown().await;
+    consumer.shutdown().await;
+    assert!(matches!(
+        producer
+            .publish_json("orders.created", "order.created", &order)
+            .await,
+        Err(ClientError::Closed)
+    ));
+}
+
+#[tokio::test]
+async fn test_batched_publish_and_delivery() {
+    let state = bridge_state().await;
+    let fanout = Arc::clone(&state.fanout);
+    let (addr, _svc) = start_bridge_with_state(state).await;
+    let consumer = LoomAgent::builder()
+        .agent_id("batch-consumer")
+        .topics(["ticks"])
+        .batching(16)
+        .connect(addr.to_string())
+        .await
+        .unwrap();
+    let mut ticks = consumer.subscribe("ticks");
+    let producer = LoomAgent::builder()
+        .agent_id("batch-producer")
+        .connect(addr.to_string())
+        .await
+        .unwrap();
+
+    let events = (0..40)
+        .map(|i| producer.event("tick", i.to_string().into_bytes()))
+        .collect();
+    producer.publish_batch("ticks", events).await.unwrap();
+
+    for i in 0..40 {
+        let d = tokio::time::timeout(Duration::from_secs(2), ticks.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(d.event.payload, i.to_string().into_bytes());
+    }
+    assert_eq!(fanout.batch_limit_of("batch-consumer"), 16);
+
+    producer.shutdown().await;
+    consumer.shutdown().await;
+}
+
+#[tokio::test]
+async fn test_token_selects_tenant() {
+    let mut state = bridge_state().await;
+    let tenants = TenantRegistry::new();
+    tenants
+        .register(TenantConfig::new("acme").with_token("acme-secret"))
+        .unwrap();
+    state.set_tenants(Arc::new(tenants));
+    let (addr, _svc) = start_bridge_with_state(state).await;
+
+    let err = LoomAgent::builder()
+        .agent_id("anonymous")
+        .connect(addr.to_string())
+        .await
+        .unwrap_err();
+    assert!(
+        matches!(err, ClientError::Rpc(ref status) if status.code() == tonic::Code::Unauthenticated)
+    );
+
+    let agent = LoomAgent::builder()
+        .agent_id("tenant-agent")
+        .token("acme-secret")
+        .topics(["notes"])
+        .connect(addr.to_string())
+        .await
+        .unwrap();
+    let mut notes = agent.subscribe("notes");
+    agent
+        .publish_json("notes", "note.added", &json!({"text": "hi"}))
+        .await
+        .unwrap();
+    let delivery = tokio::time::timeout(Duration::from_secs(2), notes.recv())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(delivery.topic, "notes");
+    assert_eq!(delivery.metadata("tenant"), Some("acme"));
+
+    agent.shutdown().await;
+}
+
+#[tokio::test]
+async fn test_reconnects_and_reregisters_after_connection_loss() {
+    let (bridge_addr, svc) = start_bridge().await;
+    let (proxy_addr, kill) = start_proxy(bridge_addr).await;
+    let agent = LoomAgent::builder()
+        .agent_id("resilient")
+        .tool(add_tool())
+        .heartbeat(Duration::from_millis(200), Duration::from_millis(500))
+        .reconnect(ReconnectPolicy {
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_millis(500),
+            max_attempts: None,
+        })
+        .connect(proxy_addr.to_string())
+        .await
+        .unwrap();
+
+    let before = push_and_wait(
+        &svc,
+        "resilient",
+        tool_call("r1", "math:add", r#"{"a":1,"b":1}"#),
+    )
+    .await;
+    assert_eq!(before.status, ToolStatus::ToolOk as i32);
+
+    kill.send_modify(|generation| *generation += 1);
+    tokio::time::timeout(Duration::from_secs(5), async {
+        while agent.reconnects() == 0 {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+    })
+    .await
+    .expect("agent reconnected");
+    agent.wait_connected().await.unwrap();
+
+    let after = push_and_wait(
+        &svc,
+        "resilient",
+        tool_call("r2", "math:add", r#"{"a":2,"b":2}"#),
+    )
+    .await;
+    assert_eq!(
+        serde_json::from_str::<Value>(&after.output).unwrap(),
+        json!({"sum": 4})
+    );
+
+    agent.shutdown().await;
+}
+
+#[tokio::test]
+async fn test_connect_fails_without_bridge() {
+    let listener = TcpListener::bind((std::net::Ipv4Addr::LOCALHOST, 0))
+        .await
+        .unwrap();
+    let addr = listener.local_addr().unwrap();
+    drop(listener);
+
+    let err = LoomAgent::builder()
+        .agent_id("lonely")
+        .connect(addr.to_string())
+        .await
+        .unwrap_err();
+    assert!(matches!(err, ClientError::Transport(_)));
+}
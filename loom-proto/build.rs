@@ -1,3 +1,18 @@
+#[path = "src/conventions.rs"]
+#[allow(dead_code)]
+mod conventions;
+#[path = "build/sdk.rs"]
+mod sdk;
+
+const PROTOS: &[&str] = &[
+    "proto/event.proto",
+    "proto/agent.proto",
+    "proto/plugin.proto",
+    "proto/action.proto",
+    "proto/bridge.proto",
+    "proto/memory.proto",
+];
+
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Ensure protoc is available via vendored binary for reproducible builds
     if let Ok(path) = protoc_bin_vendored::protoc_bin_path() {
@@ -5,20 +20,24 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     }
 
     // Generate combined Rust file for package `loom.v1` to keep include path stable
+    let descriptors = std::env::var("OUT_DIR").unwrap() + "/loom.v1.bin";
     tonic_build::configure()
         .build_server(true)
         .build_client(true)
-        .file_descriptor_set_path(std::env::var("OUT_DIR").unwrap() + "/loom.v1.bin")
-        .compile(
-            &[
-                "proto/event.proto",
-                "proto/agent.proto",
-                "proto/plugin.proto",
-                "proto/action.proto",
-                "proto/bridge.proto",
-                "proto/memory.proto",
-            ],
+        .file_descriptor_set_path(&descriptors)
+        .compile(PROTOS, &["proto"])?;
+
+    // Optional TypeScript/Python SDK sources, see the README
+    println!("cargo:rerun-if-env-changed=LOOM_PROTO_SDK_OUT");
+    println!("cargo:rerun-if-changed=src/conventions.rs");
+    println!("cargo:rerun-if-changed=build/sdk.rs");
+    if let Some(out) = std::env::var_os("LOOM_PROTO_SDK_OUT") {
+        sdk::generate(
+            std::path::Path::new(&out),
+            std::path::Path::new(&descriptors),
+            PROTOS,
             &["proto"],
         )?;
+    }
     Ok(())
 }
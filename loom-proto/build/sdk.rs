@@ -0,0 +1,598 @@
+//! SDK sources emitted when `LOOM_PROTO_SDK_OUT` is set (see the README):
+//!
+//! - `conventions.json`: the constants of `src/conventions.rs` as a language-neutral spec
+//! - `typescript/loom.ts`: message interfaces, enums and service client interfaces
+//! - `typescript/conventions.ts`: keys, event-type enums and topic helpers
+//! - `python/*_pb2.py(i)`: protoc's Python messages
+//! - `python/*_pb2_grpc.py`: gRPC client stubs
+//! - `python/loom_conventions.py`: keys, event-type enums and topic helpers
+
+use std::collections::{BTreeSet, HashMap};
+use std::fmt::Write as _;
+use std::path::Path;
+use std::process::Command;
+
+use prost::Message;
+use prost_types::field_descriptor_proto::{Label, Type};
+use prost_types::{
+    DescriptorProto, EnumDescriptorProto, FieldDescriptorProto, FileDescriptorProto,
+    FileDescriptorSet, ServiceDescriptorProto,
+};
+
+use crate::conventions::{blob, collab, keys, topics};
+
+type Result<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
+const HEADER: &str = "Generated by the loom-proto build script. DO NOT EDIT!";
+
+/// How a group of constants is rendered
+#[derive(Clone, Copy, PartialEq)]
+enum Kind {
+    /// Plain constants (metadata keys)
+    Constants,
+    /// String enum (event types)
+    Enum,
+    /// Constants plus a function per `{placeholder}` template
+    Topics,
+}
+
+struct Group {
+    /// Path in `conventions.json`
+    path: &'static str,
+    /// Class / object name in the SDKs
+    name: &'static str,
+    kind: Kind,
+    doc: &'static str,
+    items: &'static [(&'static str, &'static str, &'static str)],
+}
+
+const GROUPS: &[Group] = &[
+    Group {
+        path: "keys",
+        name: "EnvelopeKeys",
+        kind: Kind::Constants,
+        doc: keys::DOC,
+        items: keys::ALL,
+    },
+    Group {
+        path: "collab.types",
+        name: "CollabEventType",
+        kind: Kind::Enum,
+        doc: collab::types::DOC,
+        items: collab::types::ALL,
+    },
+    Group {
+        path: "collab.meta",
+        name: "CollabMetaKeys",
+        kind: Kind::Constants,
+        doc: collab::meta::DOC,
+        items: collab::meta::ALL,
+    },
+    Group {
+        path: "collab.proposal",
+        name: "ProposalKeys",
+        kind: Kind::Constants,
+        doc: collab::proposal::DOC,
+        items: collab::proposal::ALL,
+    },
+    Group {
+        path: "blob",
+        name: "BlobKeys",
+        kind: Kind::Constants,
+        doc: blob::DOC,
+        items: blob::ALL,
+    },
+    Group {
+        path: "topics",
+        name: "Topics",
+        kind: Kind::Topics,
+        doc: topics::DOC,
+        items: topics::ALL,
+    },
+];
+
+/// Write every SDK source into `out`
+pub fn generate(out: &Path, descriptors: &Path, protos: &[&str], includes: &[&str]) -> Result<()> {
+    let set = FileDescriptorSet::decode(std::fs::read(descriptors)?.as_slice())?;
+    let index = Index::new(&set);
+
+    std::fs::create_dir_all(out)?;
+    std::fs::write(out.join("conventions.json"), conventions_json())?;
+
+    let ts = out.join("typescript");
+    std::fs::create_dir_all(&ts)?;
+    std::fs::write(ts.join("loom.ts"), typescript_protos(&set, &index))?;
+    std::fs::write(ts.join("conventions.ts"), typescript_conventions())?;
+
+    let py = out.join("python");
+    std::fs::create_dir_all(&py)?;
+    let protoc = std::env::var("PROTOC").unwrap_or_else(|_| "protoc".to_string());
+    let mut cmd = Command::new(protoc);
+    cmd.arg(format!("--python_out={}", py.display()))
+        .arg(format!("--pyi_out={}", py.display()));
+    for include in includes {
+        cmd.arg(format!("-I{}", include));
+    }
+    let status = cmd.args(protos).status()?;
+    if !status.success() {
+        return Err(format!("protoc failed to generate Python sources: {status}").into());
+    }
+    for file in &set.file {
+        if !file.service.is_empty() {
+            let name = format!("{}_pb2_grpc.py", module_stem(file.name()));
+            std::fs::write(py.join(name), python_stubs(file, &index))?;
+        }
+    }
+    std::fs::write(py.join("loom_conventions.py"), python_conventions())?;
+    Ok(())
+}
+
+/// Fully qualified type name (`.loom.v1.Outer.Inner`) → defining file and map entries
+struct Index {
+    package: String,
+    files: HashMap<String, String>,
+    /// Map entry type → (key field, value field)
+    maps: HashMap<String, (FieldDescriptorProto, FieldDescriptorProto)>,
+}
+
+impl Index {
+    fn new(set: &FileDescriptorSet) -> Self {
+        let mut index = Self {
+            package: set
+                .file
+                .first()
+                .map(|f| f.package().to_string())
+                .unwrap_or_default(),
+            files: HashMap::new(),
+            maps: HashMap::new(),
+        };
+        for file in &set.file {
+            let prefix = format!(".{}", file.package());
+            for message in &file.message_type {
+                index.add_message(file.name(), &prefix, message);
+            }
+            for enumeration in &file.enum_type {
+                index.files.insert(
+                    format!("{}.{}", prefix, enumeration.name()),
+                    file.name().to_string(),
+                );
+            }
+        }
+        index
+    }
+
+    fn add_message(&mut self, file: &str, prefix: &str, message: &DescriptorProto) {
+        let full = format!("{}.{}", prefix, message.name());
+        if message.options.as_ref().is_some_and(|o| o.map_entry()) {
+            let key = message.field.iter().find(|f| f.number() == 1).cloned();
+            let value = message.field.iter().find(|f| f.number() == 2).cloned();
+            if let (Some(key), Some(value)) = (key, value) {
+                self.maps.insert(full.clone(), (key, value));
+            }
+        }
+        for nested in &message.nested_type {
+            self.add_message(file, &full, nested);
+        }
+        for enumeration in &message.enum_type {
+            self.files
+                .insert(format!("{}.{}", full, enumeration.name()), file.to_string());
+        }
+        self.files.insert(full, file.to_string());
+    }
+
+    /// `.loom.v1.Outer.Inner` → `Outer_Inner`
+    fn local_name(&self, full: &str) -> String {
+        let prefix = format!(".{}.", self.package);
+        full.strip_prefix(&prefix)
+            .unwrap_or(full.trim_start_matches('.'))
+            .replace('.', "_")
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Spec
+
+fn conventions_json() -> String {
+    let mut out = String::from("{\n");
+    for (i, group) in GROUPS.iter().enumerate() {
+        let _ = writeln!(out, "  {}: {{", quote(group.path));
+        let _ = writeln!(out, "    \"doc\": {},", quote(group.doc.trim()));
+        let _ = writeln!(out, "    \"constants\": {{");
+        for (j, (name, value, doc)) in group.items.iter().enumerate() {
+            let _ = write!(
+                out,
+                "      {}: {{ \"value\": {}, \"doc\": {} }}",
+                quote(name),
+                quote(value),
+                quote(doc.trim())
+            );
+            out.push_str(if j + 1 < group.items.len() {
+                ",\n"
+            } else {
+                "\n"
+            });
+        }
+        out.push_str("    }\n  }");
+        out.push_str(if i + 1 < GROUPS.len() { ",\n" } else { "\n" });
+    }
+    out.push_str("}\n");
+    out
+}
+
+// ---------------------------------------------------------------------------
+// TypeScript
+
+fn typescript_protos(set: &FileDescriptorSet, index: &Index) -> String {
+    let mut out = format!(
+        "// {HEADER}\n//\n\
+         // Messages use the field names of @grpc/proto-loader with `keepCase: false`,\n\
+         // `longs: String` and `enums: Number`.\n\n\
+         export interface MethodDescriptor {{\n  path: string;\n  requestType: string;\n  \
+         responseType: string;\n  requestStream: boolean;\n  responseStream: boolean;\n}}\n"
+    );
+    for file in &set.file {
+        let _ = write!(out, "\n// {}\n", file.name());
+        for enumeration in &file.enum_type {
+            ts_enum(&mut out, enumeration, enumeration.name());
+        }
+        for message in &file.message_type {
+            ts_message(&mut out, index, message, message.name());
+        }
+        for service in &file.service {
+            ts_service(&mut out, index, file.package(), service);
+        }
+    }
+    out
+}
+
+fn ts_enum(out: &mut String, enumeration: &EnumDescriptorProto, name: &str) {
+    let _ = writeln!(out, "\nexport enum {} {{", name);
+    for value in &enumeration.value {
+        let _ = writeln!(out, "  {} = {},", value.name(), value.number());
+    }
+    out.push_str("}\n");
+}
+
+fn ts_message(out: &mut String, index: &Index, message: &DescriptorProto, name: &str) {
+    if message.options.as_ref().is_some_and(|o| o.map_entry()) {
+        return;
+    }
+    let _ = writeln!(out, "\nexport interface {} {{", name);
+    for field in &message.field {
+        let _ = writeln!(
+            out,
+            "  {}?: {};",
+            camel_case(field.name()),
+            ts_field_type(index, field)
+        );
+    }
+    out.push_str("}\n");
+    for enumeration in &message.enum_type {
+        ts_enum(
+            out,
+            enumeration,
+            &format!("{}_{}", name, enumeration.name()),
+        );
+    }
+    for nested in &message.nested_type {
+        ts_message(out, index, nested, &format!("{}_{}", name, nested.name()));
+    }
+}
+
+fn ts_field_type(index: &Index, field: &FieldDescriptorProto) -> String {
+    if let Some((key, value)) = index.maps.get(field.type_name()) {
+        return format!(
+            "Record<{}, {}>",
+            ts_scalar_type(index, key),
+            ts_scalar_type(index, value)
+        );
+    }
+    let single = ts_scalar_type(index, field);
+    if field.label() == Label::Repeated {
+        format!("{}[]", single)
+    } else {
+        single
+    }
+}
+
+fn ts_scalar_type(index: &Index, field: &FieldDescriptorProto) -> String {
+    match field.r#type() {
+        Type::Double
+        | Type::Float
+        | Type::Int32
+        | Type::Uint32
+        | Type::Sint32
+        | Type::Fixed32
+        | Type::Sfixed32 => "number".to_string(),
+        Type::Int64 | Type::Uint64 | Type::Sint64 | Type::Fixed64 | Type::Sfixed64 => {
+            "string".to_string()
+        }
+        Type::Bool => "boolean".to_string(),
+        Type::String => "string".to_string(),
+        Type::Bytes => "Uint8Array".to_string(),
+        Type::Enum | Type::Message | Type::Group => index.local_name(field.type_name()),
+    }
+}
+
+fn ts_service(out: &mut String, index: &Index, package: &str, service: &ServiceDescriptorProto) {
+    let name = service.name();
+    let _ = writeln!(out, "\n/** Methods of `{}.{}` */", package, name);
+    let _ = writeln!(out, "export const {}Service = {{", name);
+    for method in &service.method {
+        let _ = writeln!(
+            out,
+            "  {}: {{\n    path: \"/{}.{}/{}\",\n    requestType: \"{}\",\n    \
+             responseType: \"{}\",\n    requestStream: {},\n    responseStream: {},\n  }},",
+            camel_case(method.name()),
+            package,
+            name,
+            method.name(),
+            method.input_type().trim_start_matches('.'),
+            method.output_type().trim_start_matches('.'),
+            method.client_streaming(),
+            method.server_streaming()
+        );
+    }
+    out.push_str("} as const satisfies Record<string, MethodDescriptor>;\n");
+
+    let _ = writeln!(out, "\n/** Client of `{}.{}` */", package, name);
+    let _ = writeln!(out, "export interface {}Client {{", name);
+    for method in &service.method {
+        let input = index.local_name(method.input_type());
+        let output = index.local_name(method.output_type());
+        let request = if method.client_streaming() {
+            format!("requests: AsyncIterable<{}>", input)
+        } else {
+            format!("request: {}", input)
+        };
+        let response = if method.server_streaming() {
+            format!("AsyncIterable<{}>", output)
+        } else {
+            format!("Promise<{}>", output)
+        };
+        let _ = writeln!(
+            out,
+            "  {}({}): {};",
+            camel_case(method.name()),
+            request,
+            response
+        );
+    }
+    out.push_str("}\n");
+}
+
+fn typescript_conventions() -> String {
+    let mut out = format!("// {HEADER}\n");
+    for group in GROUPS {
+        let _ = write!(out, "\n/** {} */\n", group.doc.trim());
+        if group.kind == Kind::Enum {
+            let _ = writeln!(out, "export enum {} {{", group.name);
+            for (name, value, doc) in group.items {
+                let _ = writeln!(
+                    out,
+                    "  /** {} */\n  {} = {},",
+                    doc.trim(),
+                    name,
+                    quote(value)
+                );
+            }
+            out.push_str("}\n");
+            continue;
+        }
+        let _ = writeln!(out, "export const {} = {{", group.name);
+        for (name, value, doc) in group.items {
+            let _ = writeln!(
+                out,
+                "  /** {} */\n  {}: {},",
+                doc.trim(),
+                name,
+                quote(value)
+            );
+        }
+        out.push_str("} as const;\n");
+        if group.kind == Kind::Topics {
+            for (name, value, doc) in group.items {
+                let params = placeholders(value);
+                if params.is_empty() {
+                    continue;
+                }
+                let args: Vec<String> = params
+                    .iter()
+                    .map(|p| format!("{}: string", camel_case(p)))
+                    .collect();
+                let mut body = value.replace('`', "\\`");
+                for param in &params {
+                    body = body.replace(
+                        &format!("{{{}}}", param),
+                        &format!("${{{}}}", camel_case(param)),
+                    );
+                }
+                let _ = write!(
+                    out,
+                    "\n/** {} */\nexport function {}Topic({}): string {{\n  return `{}`;\n}}\n",
+                    doc.trim(),
+                    camel_case(&name.to_ascii_lowercase()),
+                    args.join(", "),
+                    body
+                );
+            }
+        }
+    }
+    out
+}
+
+// ---------------------------------------------------------------------------
+// Python
+
+fn python_stubs(file: &FileDescriptorProto, index: &Index) -> String {
+    let mut modules = BTreeSet::new();
+    for service in &file.service {
+        for method in &service.method {
+            for ty in [method.input_type(), method.output_type()] {
+                if let Some(defining) = index.files.get(ty) {
+                    modules.insert(module_stem(defining));
+                }
+            }
+        }
+    }
+
+    let mut out = format!(
+        "# {HEADER}\n\"\"\"gRPC client stubs for {}\"\"\"\n\nimport grpc\n\n",
+        file.name()
+    );
+    for module in &modules {
+        let _ = writeln!(out, "import {}_pb2 as {}__pb2", module, module);
+    }
+    for service in &file.service {
+        let _ = write!(
+            out,
+            "\n\nclass {}Stub:\n    \"\"\"Client of {}.{}; works with grpc and grpc.aio channels\"\"\"\n\n    \
+             def __init__(self, channel):\n",
+            service.name(),
+            file.package(),
+            service.name()
+        );
+        for method in &service.method {
+            let kind = match (method.client_streaming(), method.server_streaming()) {
+                (false, false) => "unary_unary",
+                (false, true) => "unary_stream",
+                (true, false) => "stream_unary",
+                (true, true) => "stream_stream",
+            };
+            let _ = write!(
+                out,
+                "        self.{} = channel.{}(\n            \"/{}.{}/{}\",\n            \
+                 request_serializer={}.SerializeToString,\n            \
+                 response_deserializer={}.FromString,\n        )\n",
+                method.name(),
+                kind,
+                file.package(),
+                service.name(),
+                method.name(),
+                python_type(index, method.input_type()),
+                python_type(index, method.output_type())
+            );
+        }
+    }
+    out
+}
+
+/// `.loom.v1.Outer.Inner` → `module__pb2.Outer.Inner`
+fn python_type(index: &Index, full: &str) -> String {
+    let module = index
+        .files
+        .get(full)
+        .map(|f| module_stem(f))
+        .unwrap_or_default();
+    let prefix = format!(".{}.", index.package);
+    format!(
+        "{}__pb2.{}",
+        module,
+        full.strip_prefix(&prefix).unwrap_or(full)
+    )
+}
+
+fn python_conventions() -> String {
+    let mut out =
+        format!("# {HEADER}\n\"\"\"Loom envelope conventions\"\"\"\n\nfrom enum import Enum\n");
+    for group in GROUPS {
+        let base = if group.kind == Kind::Enum {
+            "(str, Enum)"
+        } else {
+            ""
+        };
+        let _ = write!(
+            out,
+            "\n\nclass {}{}:\n    {}\n",
+            group.name,
+            base,
+            quote(group.doc.trim())
+        );
+        for (name, value, doc) in group.items {
+            let _ = write!(
+                out,
+                "\n    #: {}\n    {} = {}\n",
+                doc.trim(),
+                name,
+                quote(value)
+            );
+        }
+        if group.kind != Kind::Topics {
+            continue;
+        }
+        for (name, value, doc) in group.items {
+            let params = placeholders(value);
+            if params.is_empty() {
+                continue;
+            }
+            let args: Vec<String> = params.iter().map(|p| format!("{}: str", p)).collect();
+            let kwargs: Vec<String> = params.iter().map(|p| format!("{p}={p}")).collect();
+            let _ = write!(
+                out,
+                "\n\ndef {}_topic({}) -> str:\n    {}\n    return {}.{}.format({})\n",
+                name.to_ascii_lowercase(),
+                args.join(", "),
+                quote(doc.trim()),
+                group.name,
+                name,
+                kwargs.join(", ")
+            );
+        }
+    }
+    out
+}
+
+// ---------------------------------------------------------------------------
+// Helpers
+
+/// `bridge.proto` → `bridge`
+fn module_stem(file: &str) -> String {
+    file.trim_end_matches(".proto")
+        .replace(['/', '-', '.'], "_")
+}
+
+/// `register_agent` / `RegisterAgent` → `registerAgent`
+fn camel_case(name: &str) -> String {
+    let mut out = String::new();
+    let mut upper = false;
+    for (i, c) in name.chars().enumerate() {
+        if c == '_' {
+            upper = true;
+        } else if upper {
+            out.extend(c.to_uppercase());
+            upper = false;
+        } else if i == 0 {
+            out.extend(c.to_lowercase());
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+/// `{agent_id}` placeholders of a topic template
+fn placeholders(template: &str) -> Vec<String> {
+    template
+        .split('{')
+        .skip(1)
+        .filter_map(|part| part.split_once('}').map(|(name, _)| name.to_string()))
+        .collect()
+}
+
+/// JSON string literal; also valid in TypeScript and Python
+fn quote(s: &str) -> String {
+    let mut out = String::from("\"");
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            c if (c as u32) < 0x20 => {
+                let _ = write!(out, "\\u{:04x}", c as u32);
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
@@ -0,0 +1,180 @@
+//! Envelope conventions shared by every Loom client: metadata keys, event types and
+//! topic names that ride in `Event.metadata`, `Event.type` and topic strings.
+//!
+//! `loom-core` re-exports these constants, and the build script emits them for the
+//! TypeScript and Python SDKs (see `LOOM_PROTO_SDK_OUT` in the README), so external
+//! agents do not hand-copy strings such as `keys::CORRELATION_ID`.
+//!
+//! Each module also lists its constants in `ALL` as `(name, value, doc)`. Topic values
+//! containing `{placeholder}`s are templates; SDKs get a function per template.
+
+macro_rules! conventions {
+    ($(#[doc = $mod_doc:literal])* pub mod $name:ident {
+        $($(#[doc = $doc:literal])+ $konst:ident = $value:literal;)*
+    }) => {
+        $(#[doc = $mod_doc])*
+        pub mod $name {
+            $($(#[doc = $doc])+ pub const $konst: &str = $value;)*
+
+            /// Documentation of this module, for the generated SDK sources
+            pub const DOC: &str = concat!($($mod_doc),*);
+
+            /// Every constant of this module as `(name, value, doc)`
+            pub const ALL: &[(&str, &str, &str)] = &[
+                $((stringify!($konst), $value, concat!($($doc),+)),)*
+            ];
+        }
+    };
+}
+
+conventions! {
+    /// Reserved metadata/header keys for thread & correlation semantics.
+    ///
+    /// These keys are used in `Event.metadata` and `ActionCall.headers` to carry
+    /// envelope information for multi-agent coordination.
+    pub mod keys {
+        /// Thread identifier for grouping related messages
+        THREAD_ID = "thread_id";
+        /// Correlation identifier linking replies to requests
+        CORRELATION_ID = "correlation_id";
+        /// Logical identity of the message sender (e.g., "agent.foo")
+        SENDER = "sender";
+        /// Canonical reply topic for responses
+        REPLY_TO = "reply_to";
+        /// Remaining time-to-live (hops budget)
+        TTL = "ttl";
+        /// Current hop count (incremented each forwarding)
+        HOP_COUNT = "hop";
+        /// Timestamp in milliseconds since epoch
+        TIMESTAMP_MS = "ts";
+        /// OpenTelemetry trace identifier (128-bit hex string)
+        TRACE_ID = "trace_id";
+        /// OpenTelemetry span identifier (64-bit hex string)
+        SPAN_ID = "span_id";
+        /// OpenTelemetry trace flags (8-bit hex string, typically "01" for sampled)
+        TRACE_FLAGS = "trace_flags";
+        /// Id of the event this message was caused by (lineage)
+        CAUSED_BY = "caused_by";
+        /// Absolute deadline in milliseconds since epoch; handlers drop the message after it
+        DEADLINE_MS = "deadline_ms";
+        /// "true" when the sender waits for a reply on `reply_to`
+        REPLY_EXPECTED = "expect_reply";
+        /// Hex HMAC-SHA256 of the envelope and payload, keyed with the deployment's
+        /// signing key (see `EnvelopeSigner` in loom-core)
+        SIGNATURE = "signature";
+        /// "true" on events whose signature the EventBus verified; never trusted from agents
+        SIGNATURE_VERIFIED = "signature_verified";
+    }
+}
+
+/// Collaboration protocols: request-reply, fanout-fanin, contract-net
+pub mod collab {
+    conventions! {
+        /// Control event type names used on `Event.type` for collaboration protocols
+        pub mod types {
+            /// Request event in request-reply or fanout-fanin patterns
+            REQ = "collab.request";
+            /// Reply event in request-reply or fanout-fanin patterns
+            REPLY = "collab.reply";
+            /// Call for proposals in contract-net protocol
+            CFP = "collab.cfp";
+            /// Proposal response in contract-net protocol
+            PROPOSAL = "collab.proposal";
+            /// Award announcement in contract-net protocol
+            AWARD = "collab.award";
+            /// Winner accepts a two-phase award
+            CONFIRM = "collab.confirm";
+            /// Winner turns down a two-phase award
+            DECLINE = "collab.decline";
+            /// Award withdrawn after its confirmation window lapsed
+            REVOKE = "collab.revoke";
+            /// Tells a bidder its proposal was not awarded
+            REJECT = "collab.reject";
+            /// Optional heartbeat for barrier synchronization
+            BARRIER_TICK = "collab.barrier";
+            /// Timeout notification when collaboration fails to complete
+            TIMEOUT = "collab.timeout";
+            /// Summary event with collaboration results and statistics
+            SUMMARY = "collab.summary";
+            /// The collaboration was called off; waits resolve and participants abandon work
+            CANCEL = "collab.cancel";
+        }
+    }
+
+    conventions! {
+        /// Metadata keys set on collaboration requests
+        pub mod meta {
+            /// Stable per logical request, identical on every retry; responders use it to
+            /// avoid doing the same work twice (see `IdempotencyCache`)
+            IDEMPOTENCY_KEY = "idempotency_key";
+            /// Attempt number, set on retried requests only (2 for the first retry)
+            ATTEMPT = "collab.attempt";
+            /// Bidder an award (or revocation) is addressed to
+            AWARD_TO = "award_to";
+            /// Bidder a rejection is addressed to
+            REJECT_TO = "reject_to";
+            /// Why an award was revoked or a proposal rejected
+            REASON = "reason";
+            /// On awards and CFPs of a two-phase contract net: how long a winner has to confirm
+            CONFIRM_WITHIN_MS = "confirm_within_ms";
+            /// CFP constraint: highest acceptable `cost`
+            CFP_MAX_COST = "cfp.max_cost";
+            /// CFP constraint: lowest acceptable `capacity`
+            CFP_MIN_CAPACITY = "cfp.min_capacity";
+            /// CFP constraint: highest acceptable `eta_ms`
+            CFP_MAX_ETA_MS = "cfp.max_eta_ms";
+        }
+    }
+
+    conventions! {
+        /// Standard bid terms carried in `collab.proposal` metadata (see `BidTerms`)
+        pub mod proposal {
+            /// Bidder's own rating of its fit for the task; higher is better
+            SCORE = "score";
+            /// Price the bidder asks for the task
+            COST = "cost";
+            /// Units of work the bidder can take on
+            CAPACITY = "capacity";
+            /// Bidder's estimate of time to completion
+            ETA_MS = "eta_ms";
+        }
+    }
+}
+
+conventions! {
+    /// Metadata keys of an event whose payload is kept in the blob store; the event's own
+    /// payload is then empty
+    pub mod blob {
+        /// Id of the blob holding the payload
+        REF = "blob.ref";
+        /// Payload size in bytes
+        SIZE = "blob.size";
+        /// Hex SHA-256 of the payload
+        SHA256 = "blob.sha256";
+        /// Media type of the payload, if known
+        CONTENT_TYPE = "blob.content_type";
+        /// Comma-separated agent ids allowed to read the blob; set by the publisher
+        READERS = "blob.readers";
+    }
+}
+
+conventions! {
+    /// Well-known topics; `{placeholder}`s are filled in by the publisher
+    pub mod topics {
+        /// Private mailbox of an agent for point-to-point replies
+        AGENT_REPLIES = "agent.{agent_id}.replies";
+        /// Broadcast to all participants of a thread
+        THREAD_BROADCAST = "thread.{thread_id}.broadcast";
+        /// Replies targeted to the requester(s) of a thread
+        THREAD_REPLY = "thread.{thread_id}.reply";
+        /// Agent lifecycle transitions (pause, resume, drain)
+        AGENT_LIFECYCLE = "agent.lifecycle";
+        /// Memory pressure level changes
+        SYSTEM_PRESSURE = "system.pressure";
+        /// Anomalies reported by the sentinel agent
+        ANOMALY = "anomaly.detected";
+        /// Prefix of the topics receiving events that exhausted their deliveries
+        /// (`dead_letter.<topic>`)
+        DEAD_LETTER_PREFIX = "dead_letter.";
+    }
+}
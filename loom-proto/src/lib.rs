@@ -4,3 +4,9 @@
 #![allow(warnings)]
 
 include!(concat!(env!("OUT_DIR"), "/loom.v1.rs"));
+
+/// Metadata keys, event types and topics shared with the SDKs
+pub mod conventions;
+
+/// Encoded `FileDescriptorSet` of the `loom.v1` protos, for reflection and transcoding
+pub const FILE_DESCRIPTOR_SET: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/loom.v1.bin"));
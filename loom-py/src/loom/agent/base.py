@@ -251,7 +251,7 @@ class Agent:
             while not self._stopped.is_set():
                 await asyncio.sleep(15)
                 try:
-                    await asyncio.wait_for(self.client.heartbeat(), timeout=5)
+                    await asyncio.wait_for(self.client.heartbeat(self.agent_id), timeout=5)
                 except Exception as e:
                     logging.warning("[loom] Heartbeat failed: %s", e)
                     await self._reconnect()
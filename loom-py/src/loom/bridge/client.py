@@ -16,9 +16,11 @@ DEFAULT_ADDR = "127.0.0.1:50051"  # resolved at construction time
 
 
 class BridgeClient:
-    def __init__(self, address: Optional[str] = None):
+    def __init__(self, address: Optional[str] = None, token: Optional[str] = None):
         # Resolve default lazily to avoid import-time env read
         self.address = address or os.environ.get("LOOM_BRIDGE_ADDR", DEFAULT_ADDR)
+        # Bearer token selecting the tenant on a multi-tenant Bridge
+        self.token = token or os.environ.get("LOOM_BRIDGE_TOKEN") or None
         self._channel: Optional[grpc.aio.Channel] = None
         self._stub: Optional[pb_bridge_grpc.BridgeStub] = None
         self._memory_stub: Optional[pb_memory_grpc.MemoryServiceStub] = None
@@ -36,6 +38,11 @@ class BridgeClient:
             self._stub = None
             self._memory_stub = None
 
+    def _auth(self) -> Optional[tuple[tuple[str, str], ...]]:
+        if not self.token:
+            return None
+        return (("authorization", f"Bearer {self.token}"),)
+
     async def register_agent(
         self,
         agent_id: str,
@@ -50,7 +57,7 @@ class BridgeClient:
             tools=tools,
             metadata=metadata or {},
         )
-        resp = await self._stub.RegisterAgent(req)
+        resp = await self._stub.RegisterAgent(req, metadata=self._auth())
         if not resp.success:
             raise RuntimeError(f"RegisterAgent failed: {resp.error_message}")
         return True
@@ -65,15 +72,25 @@ class BridgeClient:
             async for item in outbound:
                 yield item
 
-        return self._stub.EventStream(_with_handshake())
+        return self._stub.EventStream(_with_handshake(), metadata=self._auth())
 
     async def forward_tool_call(self, call: pb_action.ToolCall) -> pb_action.ToolResult:
         assert self._stub is not None
-        return await self._stub.ForwardToolCall(call)
+        return await self._stub.ForwardToolCall(call, metadata=self._auth())
+
+    async def heartbeat(self, agent_id: Optional[str] = None) -> pb_bridge.HeartbeatResponse:
+        """Ping the Bridge; with `agent_id` the heartbeat also keeps that agent alive.
 
-    async def heartbeat(self) -> pb_bridge.HeartbeatResponse:
+        Raises `grpc.aio.AioRpcError` (NOT_FOUND) if the Bridge no longer knows the agent,
+        e.g. after declaring it dead for missed heartbeats, so the caller can re-register.
+        """
         assert self._stub is not None
-        return await self._stub.Heartbeat(pb_bridge.HeartbeatRequest())
+        metadata = list(self._auth() or ())
+        if agent_id:
+            metadata.append(("x-loom-agent-id", agent_id))
+        return await self._stub.Heartbeat(
+            pb_bridge.HeartbeatRequest(), metadata=tuple(metadata) or None
+        )
 
     # Memory service methods
     async def save_plan(self, req: pb_memory.SavePlanRequest) -> pb_memory.SavePlanResponse:
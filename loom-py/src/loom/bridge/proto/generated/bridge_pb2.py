@@ -26,7 +26,7 @@ from . import event_pb2 as event__pb2
 from . import action_pb2 as action__pb2
 
 
-DESCRIPTOR = _descriptor_pool.Default().AddSerializedFile(b'\n\x0c\x62ridge.proto\x12\x07loom.v1\x1a\x0b\x65vent.proto\x1a\x0c\x61\x63tion.proto\"\xdb\x01\n\x14\x41gentRegisterRequest\x12\x10\n\x08\x61gent_id\x18\x01 \x01(\t\x12\x19\n\x11subscribed_topics\x18\x02 \x03(\t\x12&\n\x05tools\x18\x03 \x03(\x0b\x32\x17.loom.v1.ToolDescriptor\x12=\n\x08metadata\x18\x04 \x03(\x0b\x32+.loom.v1.AgentRegisterRequest.MetadataEntry\x1a/\n\rMetadataEntry\x12\x0b\n\x03key\x18\x01 \x01(\t\x12\r\n\x05value\x18\x02 \x01(\t:\x02\x38\x01\"?\n\x15\x41gentRegisterResponse\x12\x0f\n\x07success\x18\x01 \x01(\x08\x12\x15\n\rerror_message\x18\x02 \x01(\t\"\xad\x01\n\x0b\x43lientEvent\x12#\n\x07publish\x18\x01 \x01(\x0b\x32\x10.loom.v1.PublishH\x00\x12\x1b\n\x03\x61\x63k\x18\x02 \x01(\x0b\x32\x0c.loom.v1.AckH\x00\x12)\n\x04ping\x18\x03 \x01(\x0b\x32\x19.loom.v1.HeartbeatRequestH\x00\x12*\n\x0btool_result\x18\x04 \x01(\x0b\x32\x13.loom.v1.ToolResultH\x00\x42\x05\n\x03msg\"7\n\x07Publish\x12\r\n\x05topic\x18\x01 \x01(\t\x12\x1d\n\x05\x65vent\x18\x02 \x01(\x0b\x32\x0e.loom.v1.Event\"\x19\n\x03\x41\x63k\x12\x12\n\nmessage_id\x18\x01 \x01(\t\"\xae\x01\n\x0bServerEvent\x12%\n\x08\x64\x65livery\x18\x01 \x01(\x0b\x32\x11.loom.v1.DeliveryH\x00\x12*\n\x04pong\x18\x02 \x01(\x0b\x32\x1a.loom.v1.HeartbeatResponseH\x00\x12\x1d\n\x03\x65rr\x18\x03 \x01(\x0b\x32\x0e.loom.v1.ErrorH\x00\x12&\n\ttool_call\x18\x04 \x01(\x0b\x32\x11.loom.v1.ToolCallH\x00\x42\x05\n\x03msg\"8\n\x08\x44\x65livery\x12\r\n\x05topic\x18\x01 \x01(\t\x12\x1d\n\x05\x65vent\x18\x02 \x01(\x0b\x32\x0e.loom.v1.Event\"&\n\x05\x45rror\x12\x0c\n\x04\x63ode\x18\x01 \x01(\t\x12\x0f\n\x07message\x18\x02 \x01(\t\"(\n\x10HeartbeatRequest\x12\x14\n\x0ctimestamp_ms\x18\x01 \x01(\x03\"9\n\x11HeartbeatResponse\x12\x14\n\x0ctimestamp_ms\x18\x01 \x01(\x03\x12\x0e\n\x06status\x18\x02 \x01(\t2\x96\x02\n\x06\x42ridge\x12N\n\rRegisterAgent\x12\x1d.loom.v1.AgentRegisterRequest\x1a\x1e.loom.v1.AgentRegisterResponse\x12=\n\x0b\x45ventStream\x12\x14.loom.v1.ClientEvent\x1a\x14.loom.v1.ServerEvent(\x01\x30\x01\x12\x39\n\x0f\x46orwardToolCall\x12\x11.loom.v1.ToolCall\x1a\x13.loom.v1.ToolResult\x12\x42\n\tHeartbeat\x12\x19.loom.v1.HeartbeatRequest\x1a\x1a.loom.v1.HeartbeatResponseb\x06proto3')
+DESCRIPTOR = _descriptor_pool.Default().AddSerializedFile(b'\n\x0c\x62ridge.proto\x12\x07loom.v1\x1a\x0b\x65vent.proto\x1a\x0c\x61\x63tion.proto\"\xdb\x01\n\x14\x41gentRegisterRequest\x12\x10\n\x08\x61gent_id\x18\x01 \x01(\t\x12\x19\n\x11subscribed_topics\x18\x02 \x03(\t\x12&\n\x05tools\x18\x03 \x03(\x0b\x32\x17.loom.v1.ToolDescriptor\x12=\n\x08metadata\x18\x04 \x03(\x0b\x32+.loom.v1.AgentRegisterRequest.MetadataEntry\x1a/\n\rMetadataEntry\x12\x0b\n\x03key\x18\x01 \x01(\t\x12\r\n\x05value\x18\x02 \x01(\t:\x02\x38\x01\"?\n\x15\x41gentRegisterResponse\x12\x0f\n\x07success\x18\x01 \x01(\x08\x12\x15\n\rerror_message\x18\x02 \x01(\t\"\xdd\x01\n\x0b\x43lientEvent\x12#\n\x07publish\x18\x01 \x01(\x0b\x32\x10.loom.v1.PublishH\x00\x12\x1b\n\x03\x61\x63k\x18\x02 \x01(\x0b\x32\x0c.loom.v1.AckH\x00\x12)\n\x04ping\x18\x03 \x01(\x0b\x32\x19.loom.v1.HeartbeatRequestH\x00\x12*\n\x0btool_result\x18\x04 \x01(\x0b\x32\x13.loom.v1.ToolResultH\x00\x12.\n\rpublish_batch\x18\x05 \x01(\x0b\x32\x15.loom.v1.PublishBatchH\x00\x42\x05\n\x03msg\"7\n\x07Publish\x12\r\n\x05topic\x18\x01 \x01(\t\x12\x1d\n\x05\x65vent\x18\x02 \x01(\x0b\x32\x0e.loom.v1.Event\"=\n\x0cPublishBatch\x12\r\n\x05topic\x18\x01 \x01(\t\x12\x1e\n\x06\x65vents\x18\x02 \x03(\x0b\x32\x0e.loom.v1.Event\"\x19\n\x03\x41\x63k\x12\x12\n\nmessage_id\x18\x01 \x01(\t\"\x8c\x02\n\x0bServerEvent\x12%\n\x08\x64\x65livery\x18\x01 \x01(\x0b\x32\x11.loom.v1.DeliveryH\x00\x12*\n\x04pong\x18\x02 \x01(\x0b\x32\x1a.loom.v1.HeartbeatResponseH\x00\x12\x1d\n\x03\x65rr\x18\x03 \x01(\x0b\x32\x0e.loom.v1.ErrorH\x00\x12&\n\ttool_call\x18\x04 \x01(\x0b\x32\x11.loom.v1.ToolCallH\x00\x12\x30\n\x0e\x64\x65livery_batch\x18\x05 \x01(\x0b\x32\x16.loom.v1.DeliveryBatchH\x00\x12*\n\x0btool_cancel\x18\x06 \x01(\x0b\x32\x13.loom.v1.ToolCancelH\x00\x42\x05\n\x03msg\"(\n\nToolCancel\x12\n\n\x02id\x18\x01 \x01(\t\x12\x0e\n\x06reason\x18\x02 \x01(\t\"8\n\x08\x44\x65livery\x12\r\n\x05topic\x18\x01 \x01(\t\x12\x1d\n\x05\x65vent\x18\x02 \x01(\x0b\x32\x0e.loom.v1.Event\">\n\rDeliveryBatch\x12\r\n\x05topic\x18\x01 \x01(\t\x12\x1e\n\x06\x65vents\x18\x02 \x03(\x0b\x32\x0e.loom.v1.Event\"&\n\x05\x45rror\x12\x0c\n\x04\x63ode\x18\x01 \x01(\t\x12\x0f\n\x07message\x18\x02 \x01(\t\"(\n\x10HeartbeatRequest\x12\x14\n\x0ctimestamp_ms\x18\x01 \x01(\x03\"9\n\x11HeartbeatResponse\x12\x14\n\x0ctimestamp_ms\x18\x01 \x01(\x03\x12\x0e\n\x06status\x18\x02 \x01(\t\"T\n\x11ListAgentsRequest\x12\x12\n\ncapability\x18\x01 \x01(\t\x12\r\n\x05topic\x18\x02 \x01(\t\x12\x1c\n\x14include_disconnected\x18\x03 \x01(\x08\">\n\x12ListAgentsResponse\x12(\n\x06\x61gents\x18\x01 \x03(\x0b\x32\x18.loom.v1.AgentDescriptor\"\xea\x01\n\x0f\x41gentDescriptor\x12\x10\n\x08\x61gent_id\x18\x01 \x01(\t\x12\x19\n\x11subscribed_topics\x18\x02 \x03(\t\x12\x14\n\x0c\x63\x61pabilities\x18\x03 \x03(\t\x12\x38\n\x08metadata\x18\x04 \x03(\x0b\x32&.loom.v1.AgentDescriptor.MetadataEntry\x12\x19\n\x11last_heartbeat_ms\x18\x05 \x01(\x03\x12\x0e\n\x06status\x18\x06 \x01(\t\x1a/\n\rMetadataEntry\x12\x0b\n\x03key\x18\x01 \x01(\t\x12\r\n\x05value\x18\x02 \x01(\t:\x02\x38\x01\x32\xa1\x03\n\x06\x42ridge\x12N\n\rRegisterAgent\x12\x1d.loom.v1.AgentRegisterRequest\x1a\x1e.loom.v1.AgentRegisterResponse\x12=\n\x0b\x45ventStream\x12\x14.loom.v1.ClientEvent\x1a\x14.loom.v1.ServerEvent(\x01\x30\x01\x12\x39\n\x0f\x46orwardToolCall\x12\x11.loom.v1.ToolCall\x1a\x13.loom.v1.ToolResult\x12\x42\n\tHeartbeat\x12\x19.loom.v1.HeartbeatRequest\x1a\x1a.loom.v1.HeartbeatResponse\x12\x42\n\tListTools\x12\x19.loom.v1.ListToolsRequest\x1a\x1a.loom.v1.ListToolsResponse\x12\x45\n\nListAgents\x12\x1a.loom.v1.ListAgentsRequest\x1a\x1b.loom.v1.ListAgentsResponseb\x06proto3')
 
 _globals = globals()
 _builder.BuildMessageAndEnumDescriptors(DESCRIPTOR, _globals)
@@ -35,6 +35,8 @@ if not _descriptor._USE_C_DESCRIPTORS:
   DESCRIPTOR._loaded_options = None
   _globals['_AGENTREGISTERREQUEST_METADATAENTRY']._loaded_options = None
   _globals['_AGENTREGISTERREQUEST_METADATAENTRY']._serialized_options = b'8\001'
+  _globals['_AGENTDESCRIPTOR_METADATAENTRY']._loaded_options = None
+  _globals['_AGENTDESCRIPTOR_METADATAENTRY']._serialized_options = b'8\001'
   _globals['_AGENTREGISTERREQUEST']._serialized_start=53
   _globals['_AGENTREGISTERREQUEST']._serialized_end=272
   _globals['_AGENTREGISTERREQUEST_METADATAENTRY']._serialized_start=225
@@ -42,21 +44,35 @@ if not _descriptor._USE_C_DESCRIPTORS:
   _globals['_AGENTREGISTERRESPONSE']._serialized_start=274
   _globals['_AGENTREGISTERRESPONSE']._serialized_end=337
   _globals['_CLIENTEVENT']._serialized_start=340
-  _globals['_CLIENTEVENT']._serialized_end=513
-  _globals['_PUBLISH']._serialized_start=515
-  _globals['_PUBLISH']._serialized_end=570
-  _globals['_ACK']._serialized_start=572
-  _globals['_ACK']._serialized_end=597
-  _globals['_SERVEREVENT']._serialized_start=600
-  _globals['_SERVEREVENT']._serialized_end=774
-  _globals['_DELIVERY']._serialized_start=776
-  _globals['_DELIVERY']._serialized_end=832
-  _globals['_ERROR']._serialized_start=834
-  _globals['_ERROR']._serialized_end=872
-  _globals['_HEARTBEATREQUEST']._serialized_start=874
-  _globals['_HEARTBEATREQUEST']._serialized_end=914
-  _globals['_HEARTBEATRESPONSE']._serialized_start=916
-  _globals['_HEARTBEATRESPONSE']._serialized_end=973
-  _globals['_BRIDGE']._serialized_start=976
-  _globals['_BRIDGE']._serialized_end=1254
+  _globals['_CLIENTEVENT']._serialized_end=561
+  _globals['_PUBLISH']._serialized_start=563
+  _globals['_PUBLISH']._serialized_end=618
+  _globals['_PUBLISHBATCH']._serialized_start=620
+  _globals['_PUBLISHBATCH']._serialized_end=681
+  _globals['_ACK']._serialized_start=683
+  _globals['_ACK']._serialized_end=708
+  _globals['_SERVEREVENT']._serialized_start=711
+  _globals['_SERVEREVENT']._serialized_end=979
+  _globals['_TOOLCANCEL']._serialized_start=981
+  _globals['_TOOLCANCEL']._serialized_end=1021
+  _globals['_DELIVERY']._serialized_start=1023
+  _globals['_DELIVERY']._serialized_end=1079
+  _globals['_DELIVERYBATCH']._serialized_start=1081
+  _globals['_DELIVERYBATCH']._serialized_end=1143
+  _globals['_ERROR']._serialized_start=1145
+  _globals['_ERROR']._serialized_end=1183
+  _globals['_HEARTBEATREQUEST']._serialized_start=1185
+  _globals['_HEARTBEATREQUEST']._serialized_end=1225
+  _globals['_HEARTBEATRESPONSE']._serialized_start=1227
+  _globals['_HEARTBEATRESPONSE']._serialized_end=1284
+  _globals['_LISTAGENTSREQUEST']._serialized_start=1286
+  _globals['_LISTAGENTSREQUEST']._serialized_end=1370
+  _globals['_LISTAGENTSRESPONSE']._serialized_start=1372
+  _globals['_LISTAGENTSRESPONSE']._serialized_end=1434
+  _globals['_AGENTDESCRIPTOR']._serialized_start=1437
+  _globals['_AGENTDESCRIPTOR']._serialized_end=1671
+  _globals['_AGENTDESCRIPTOR_METADATAENTRY']._serialized_start=225
+  _globals['_AGENTDESCRIPTOR_METADATAENTRY']._serialized_end=272
+  _globals['_BRIDGE']._serialized_start=1674
+  _globals['_BRIDGE']._serialized_end=2091
 # @@protoc_insertion_point(module_scope)
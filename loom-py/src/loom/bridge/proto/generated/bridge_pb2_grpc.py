@@ -57,6 +57,16 @@ class BridgeStub(object):
                 request_serializer=bridge__pb2.HeartbeatRequest.SerializeToString,
                 response_deserializer=bridge__pb2.HeartbeatResponse.FromString,
                 _registered_method=True)
+        self.ListTools = channel.unary_unary(
+                '/loom.v1.Bridge/ListTools',
+                request_serializer=action__pb2.ListToolsRequest.SerializeToString,
+                response_deserializer=action__pb2.ListToolsResponse.FromString,
+                _registered_method=True)
+        self.ListAgents = channel.unary_unary(
+                '/loom.v1.Bridge/ListAgents',
+                request_serializer=bridge__pb2.ListAgentsRequest.SerializeToString,
+                response_deserializer=bridge__pb2.ListAgentsResponse.FromString,
+                _registered_method=True)
 
 
 class BridgeServicer(object):
@@ -92,6 +102,20 @@ class BridgeServicer(object):
         context.set_details('Method not implemented!')
         raise NotImplementedError('Method not implemented!')
 
+    def ListTools(self, request, context):
+        """List the tools callable through ForwardToolCall.
+        """
+        context.set_code(grpc.StatusCode.UNIMPLEMENTED)
+        context.set_details('Method not implemented!')
+        raise NotImplementedError('Method not implemented!')
+
+    def ListAgents(self, request, context):
+        """List agents in the directory, optionally filtered by capability or topic.
+        """
+        context.set_code(grpc.StatusCode.UNIMPLEMENTED)
+        context.set_details('Method not implemented!')
+        raise NotImplementedError('Method not implemented!')
+
 
 def add_BridgeServicer_to_server(servicer, server):
     rpc_method_handlers = {
@@ -115,6 +139,16 @@ def add_BridgeServicer_to_server(servicer, server):
                     request_deserializer=bridge__pb2.HeartbeatRequest.FromString,
                     response_serializer=bridge__pb2.HeartbeatResponse.SerializeToString,
             ),
+            'ListTools': grpc.unary_unary_rpc_method_handler(
+                    servicer.ListTools,
+                    request_deserializer=action__pb2.ListToolsRequest.FromString,
+                    response_serializer=action__pb2.ListToolsResponse.SerializeToString,
+            ),
+            'ListAgents': grpc.unary_unary_rpc_method_handler(
+                    servicer.ListAgents,
+                    request_deserializer=bridge__pb2.ListAgentsRequest.FromString,
+                    response_serializer=bridge__pb2.ListAgentsResponse.SerializeToString,
+            ),
     }
     generic_handler = grpc.method_handlers_generic_handler(
             'loom.v1.Bridge', rpc_method_handlers)
@@ -235,3 +269,57 @@ class Bridge(object):
             timeout,
             metadata,
             _registered_method=True)
+
+    @staticmethod
+    def ListTools(request,
+            target,
+            options=(),
+            channel_credentials=None,
+            call_credentials=None,
+            insecure=False,
+            compression=None,
+            wait_for_ready=None,
+            timeout=None,
+            metadata=None):
+        return grpc.experimental.unary_unary(
+            request,
+            target,
+            '/loom.v1.Bridge/ListTools',
+            action__pb2.ListToolsRequest.SerializeToString,
+            action__pb2.ListToolsResponse.FromString,
+            options,
+            channel_credentials,
+            insecure,
+            call_credentials,
+            compression,
+            wait_for_ready,
+            timeout,
+            metadata,
+            _registered_method=True)
+
+    @staticmethod
+    def ListAgents(request,
+            target,
+            options=(),
+            channel_credentials=None,
+            call_credentials=None,
+            insecure=False,
+            compression=None,
+            wait_for_ready=None,
+            timeout=None,
+            metadata=None):
+        return grpc.experimental.unary_unary(
+            request,
+            target,
+            '/loom.v1.Bridge/ListAgents',
+            bridge__pb2.ListAgentsRequest.SerializeToString,
+            bridge__pb2.ListAgentsResponse.FromString,
+            options,
+            channel_credentials,
+            insecure,
+            call_credentials,
+            compression,
+            wait_for_ready,
+            timeout,
+            metadata,
+            _registered_method=True)
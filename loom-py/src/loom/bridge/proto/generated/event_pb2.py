@@ -24,7 +24,7 @@ _sym_db = _symbol_database.Default()
 
 
 
-DESCRIPTOR = _descriptor_pool.Default().AddSerializedFile(b'\n\x0b\x65vent.proto\x12\x07loom.v1\"\xed\x01\n\x05\x45vent\x12\n\n\x02id\x18\x01 \x01(\t\x12\x0c\n\x04type\x18\x02 \x01(\t\x12\x14\n\x0ctimestamp_ms\x18\x03 \x01(\x03\x12\x0e\n\x06source\x18\x04 \x01(\t\x12.\n\x08metadata\x18\x05 \x03(\x0b\x32\x1c.loom.v1.Event.MetadataEntry\x12\x0f\n\x07payload\x18\x06 \x01(\x0c\x12\x12\n\nconfidence\x18\x07 \x01(\x02\x12\x0c\n\x04tags\x18\x08 \x03(\t\x12\x10\n\x08priority\x18\t \x01(\x05\x1a/\n\rMetadataEntry\x12\x0b\n\x03key\x18\x01 \x01(\t\x12\r\n\x05value\x18\x02 \x01(\t:\x02\x38\x01\"A\n\x0b\x45ventStream\x12\x1e\n\x06\x65vents\x18\x01 \x03(\x0b\x32\x0e.loom.v1.Event\x12\x12\n\nsession_id\x18\x02 \x01(\t\"\xbf\x01\n\x10SubscribeRequest\x12\r\n\x05topic\x18\x01 \x01(\t\x12\x13\n\x0b\x65vent_types\x18\x02 \x03(\t\x12\x1e\n\x03qos\x18\x03 \x01(\x0e\x32\x11.loom.v1.QoSLevel\x12\x37\n\x07\x66ilters\x18\x04 \x03(\x0b\x32&.loom.v1.SubscribeRequest.FiltersEntry\x1a.\n\x0c\x46iltersEntry\x12\x0b\n\x03key\x18\x01 \x01(\t\x12\r\n\x05value\x18\x02 \x01(\t:\x02\x38\x01\"T\n\x11SubscribeResponse\x12\x17\n\x0fsubscription_id\x18\x01 \x01(\t\x12\x0f\n\x07success\x18\x02 \x01(\x08\x12\x15\n\rerror_message\x18\x03 \x01(\t\">\n\x0ePublishRequest\x12\r\n\x05topic\x18\x01 \x01(\t\x12\x1d\n\x05\x65vent\x18\x02 \x01(\x0b\x32\x0e.loom.v1.Event\"R\n\x0fPublishResponse\x12\x0f\n\x07success\x18\x01 \x01(\x08\x12\x15\n\rerror_message\x18\x02 \x01(\t\x12\x17\n\x0fsequence_number\x18\x03 \x01(\x03\"-\n\x12UnsubscribeRequest\x12\x17\n\x0fsubscription_id\x18\x01 \x01(\t\"&\n\x13UnsubscribeResponse\x12\x0f\n\x07success\x18\x01 \x01(\x08\"\x1d\n\x0cStatsRequest\x12\r\n\x05topic\x18\x01 \x01(\t\"u\n\rStatsResponse\x12\x14\n\x0ctotal_events\x18\x01 \x01(\x03\x12\x1c\n\x14\x61\x63tive_subscriptions\x18\x02 \x01(\x03\x12\x1a\n\x12throughput_per_sec\x18\x03 \x01(\x01\x12\x14\n\x0c\x62\x61\x63klog_size\x18\x04 \x01(\x03*A\n\x08QoSLevel\x12\x10\n\x0cQOS_REALTIME\x10\x00\x12\x0f\n\x0bQOS_BATCHED\x10\x01\x12\x12\n\x0eQOS_BACKGROUND\x10\x02\x32\x87\x02\n\x08\x45ventBus\x12<\n\x07Publish\x12\x17.loom.v1.PublishRequest\x1a\x18.loom.v1.PublishResponse\x12\x38\n\tSubscribe\x12\x19.loom.v1.SubscribeRequest\x1a\x0e.loom.v1.Event0\x01\x12H\n\x0bUnsubscribe\x12\x1b.loom.v1.UnsubscribeRequest\x1a\x1c.loom.v1.UnsubscribeResponse\x12\x39\n\x08GetStats\x12\x15.loom.v1.StatsRequest\x1a\x16.loom.v1.StatsResponseb\x06proto3')
+DESCRIPTOR = _descriptor_pool.Default().AddSerializedFile(b'\n\x0b\x65vent.proto\x12\x07loom.v1\"\xed\x01\n\x05\x45vent\x12\n\n\x02id\x18\x01 \x01(\t\x12\x0c\n\x04type\x18\x02 \x01(\t\x12\x14\n\x0ctimestamp_ms\x18\x03 \x01(\x03\x12\x0e\n\x06source\x18\x04 \x01(\t\x12.\n\x08metadata\x18\x05 \x03(\x0b\x32\x1c.loom.v1.Event.MetadataEntry\x12\x0f\n\x07payload\x18\x06 \x01(\x0c\x12\x12\n\nconfidence\x18\x07 \x01(\x02\x12\x0c\n\x04tags\x18\x08 \x03(\t\x12\x10\n\x08priority\x18\t \x01(\x05\x1a/\n\rMetadataEntry\x12\x0b\n\x03key\x18\x01 \x01(\t\x12\r\n\x05value\x18\x02 \x01(\t:\x02\x38\x01\"A\n\x0b\x45ventStream\x12\x1e\n\x06\x65vents\x18\x01 \x03(\x0b\x32\x0e.loom.v1.Event\x12\x12\n\nsession_id\x18\x02 \x01(\t\"\xbf\x01\n\x10SubscribeRequest\x12\r\n\x05topic\x18\x01 \x01(\t\x12\x13\n\x0b\x65vent_types\x18\x02 \x03(\t\x12\x1e\n\x03qos\x18\x03 \x01(\x0e\x32\x11.loom.v1.QoSLevel\x12\x37\n\x07\x66ilters\x18\x04 \x03(\x0b\x32&.loom.v1.SubscribeRequest.FiltersEntry\x1a.\n\x0c\x46iltersEntry\x12\x0b\n\x03key\x18\x01 \x01(\t\x12\r\n\x05value\x18\x02 \x01(\t:\x02\x38\x01\"T\n\x11SubscribeResponse\x12\x17\n\x0fsubscription_id\x18\x01 \x01(\t\x12\x0f\n\x07success\x18\x02 \x01(\x08\x12\x15\n\rerror_message\x18\x03 \x01(\t\">\n\x0ePublishRequest\x12\r\n\x05topic\x18\x01 \x01(\t\x12\x1d\n\x05\x65vent\x18\x02 \x01(\x0b\x32\x0e.loom.v1.Event\"R\n\x0fPublishResponse\x12\x0f\n\x07success\x18\x01 \x01(\x08\x12\x15\n\rerror_message\x18\x02 \x01(\t\x12\x17\n\x0fsequence_number\x18\x03 \x01(\x03\"-\n\x12UnsubscribeRequest\x12\x17\n\x0fsubscription_id\x18\x01 \x01(\t\"&\n\x13UnsubscribeResponse\x12\x0f\n\x07success\x18\x01 \x01(\x08\"\x1d\n\x0cStatsRequest\x12\r\n\x05topic\x18\x01 \x01(\t\"u\n\rStatsResponse\x12\x14\n\x0ctotal_events\x18\x01 \x01(\x03\x12\x1c\n\x14\x61\x63tive_subscriptions\x18\x02 \x01(\x03\x12\x1a\n\x12throughput_per_sec\x18\x03 \x01(\x01\x12\x14\n\x0c\x62\x61\x63klog_size\x18\x04 \x01(\x03*g\n\x08QoSLevel\x12\x10\n\x0cQOS_REALTIME\x10\x00\x12\x0f\n\x0bQOS_BATCHED\x10\x01\x12\x12\n\x0eQOS_BACKGROUND\x10\x02\x12\x10\n\x0cQOS_RELIABLE\x10\x03\x12\x12\n\x0eQOS_GUARANTEED\x10\x04\x32\x87\x02\n\x08\x45ventBus\x12<\n\x07Publish\x12\x17.loom.v1.PublishRequest\x1a\x18.loom.v1.PublishResponse\x12\x38\n\tSubscribe\x12\x19.loom.v1.SubscribeRequest\x1a\x0e.loom.v1.Event0\x01\x12H\n\x0bUnsubscribe\x12\x1b.loom.v1.UnsubscribeRequest\x1a\x1c.loom.v1.UnsubscribeResponse\x12\x39\n\x08GetStats\x12\x15.loom.v1.StatsRequest\x1a\x16.loom.v1.StatsResponseb\x06proto3')
 
 _globals = globals()
 _builder.BuildMessageAndEnumDescriptors(DESCRIPTOR, _globals)
@@ -36,7 +36,7 @@ if not _descriptor._USE_C_DESCRIPTORS:
   _globals['_SUBSCRIBEREQUEST_FILTERSENTRY']._loaded_options = None
   _globals['_SUBSCRIBEREQUEST_FILTERSENTRY']._serialized_options = b'8\001'
   _globals['_QOSLEVEL']._serialized_start=996
-  _globals['_QOSLEVEL']._serialized_end=1061
+  _globals['_QOSLEVEL']._serialized_end=1099
   _globals['_EVENT']._serialized_start=25
   _globals['_EVENT']._serialized_end=262
   _globals['_EVENT_METADATAENTRY']._serialized_start=215
@@ -61,6 +61,6 @@ if not _descriptor._USE_C_DESCRIPTORS:
   _globals['_STATSREQUEST']._serialized_end=875
   _globals['_STATSRESPONSE']._serialized_start=877
   _globals['_STATSRESPONSE']._serialized_end=994
-  _globals['_EVENTBUS']._serialized_start=1064
-  _globals['_EVENTBUS']._serialized_end=1327
+  _globals['_EVENTBUS']._serialized_start=1102
+  _globals['_EVENTBUS']._serialized_end=1365
 # @@protoc_insertion_point(module_scope)
@@ -25,7 +25,7 @@ _sym_db = _symbol_database.Default()
 from . import event_pb2 as event__pb2
 
 
-DESCRIPTOR = _descriptor_pool.Default().AddSerializedFile(b'\n\x0cmemory.proto\x12\x07loom.v1\x1a\x0b\x65vent.proto\"\xf2\x01\n\nPlanRecord\x12\x14\n\x0ctimestamp_ms\x18\x01 \x01(\x03\x12\x0e\n\x06symbol\x18\x02 \x01(\t\x12\x0e\n\x06\x61\x63tion\x18\x03 \x01(\t\x12\x12\n\nconfidence\x18\x04 \x01(\x02\x12\x11\n\treasoning\x18\x05 \x01(\t\x12\x11\n\tplan_hash\x18\x06 \x01(\t\x12\x0e\n\x06method\x18\x07 \x01(\t\x12\x33\n\x08metadata\x18\x08 \x03(\x0b\x32!.loom.v1.PlanRecord.MetadataEntry\x1a/\n\rMetadataEntry\x12\x0b\n\x03key\x18\x01 \x01(\t\x12\r\n\x05value\x18\x02 \x01(\t:\x02\x38\x01\"\xd2\x01\n\x0f\x45xecutionRecord\x12\x14\n\x0ctimestamp_ms\x18\x01 \x01(\x03\x12\x11\n\tplan_hash\x18\x02 \x01(\t\x12\x0e\n\x06symbol\x18\x03 \x01(\t\x12\x0e\n\x06\x61\x63tion\x18\x04 \x01(\t\x12\x12\n\nconfidence\x18\x05 \x01(\x02\x12\x0e\n\x06status\x18\x06 \x01(\t\x12\x10\n\x08\x65xecuted\x18\x07 \x01(\x08\x12\x10\n\x08order_id\x18\x08 \x01(\t\x12\x17\n\x0forder_size_usdt\x18\t \x01(\x02\x12\x15\n\rerror_message\x18\n \x01(\t\"H\n\x0fSavePlanRequest\x12\x12\n\nsession_id\x18\x01 \x01(\t\x12!\n\x04plan\x18\x02 \x01(\x0b\x32\x13.loom.v1.PlanRecord\"M\n\x10SavePlanResponse\x12\x0f\n\x07success\x18\x01 \x01(\x08\x12\x11\n\tplan_hash\x18\x02 \x01(\t\x12\x15\n\rerror_message\x18\x03 \x01(\t\"J\n\x15GetRecentPlansRequest\x12\x12\n\nsession_id\x18\x01 \x01(\t\x12\x0e\n\x06symbol\x18\x02 \x01(\t\x12\r\n\x05limit\x18\x03 \x01(\x05\"d\n\x16GetRecentPlansResponse\x12\"\n\x05plans\x18\x01 \x03(\x0b\x32\x13.loom.v1.PlanRecord\x12\x0f\n\x07success\x18\x02 \x01(\x08\x12\x15\n\rerror_message\x18\x03 \x01(\t\"g\n\x15\x43heckDuplicateRequest\x12\x12\n\nsession_id\x18\x01 \x01(\t\x12!\n\x04plan\x18\x02 \x01(\x0b\x32\x13.loom.v1.PlanRecord\x12\x17\n\x0ftime_window_sec\x18\x03 \x01(\x05\"|\n\x16\x43heckDuplicateResponse\x12\x14\n\x0cis_duplicate\x18\x01 \x01(\x08\x12+\n\x0e\x64uplicate_plan\x18\x02 \x01(\x0b\x32\x13.loom.v1.PlanRecord\x12\x1f\n\x17time_since_duplicate_ms\x18\x03 \x01(\x03\"i\n\x13MarkExecutedRequest\x12\x12\n\nsession_id\x18\x01 \x01(\t\x12\x11\n\tplan_hash\x18\x02 \x01(\t\x12+\n\texecution\x18\x03 \x01(\x0b\x32\x18.loom.v1.ExecutionRecord\">\n\x14MarkExecutedResponse\x12\x0f\n\x07success\x18\x01 \x01(\x08\x12\x15\n\rerror_message\x18\x02 \x01(\t\">\n\x18GetExecutionStatsRequest\x12\x12\n\nsession_id\x18\x01 \x01(\t\x12\x0e\n\x06symbol\x18\x02 \x01(\t\"\xd3\x01\n\x19GetExecutionStatsResponse\x12\x18\n\x10total_executions\x18\x01 \x01(\x05\x12\x1d\n\x15successful_executions\x18\x02 \x01(\x05\x12\x19\n\x11\x66\x61iled_executions\x18\x03 \x01(\x05\x12\x10\n\x08win_rate\x18\x04 \x01(\x02\x12\x1b\n\x13\x64uplicate_prevented\x18\x05 \x01(\x05\x12\x33\n\x11recent_executions\x18\x06 \x03(\x0b\x32\x18.loom.v1.ExecutionRecord\"\xb5\x01\n\x12MemoryWriteRequest\x12\x12\n\nsession_id\x18\x01 \x01(\t\x12\x1d\n\x05\x65vent\x18\x02 \x01(\x0b\x32\x0e.loom.v1.Event\x12;\n\x08metadata\x18\x03 \x03(\x0b\x32).loom.v1.MemoryWriteRequest.MetadataEntry\x1a/\n\rMetadataEntry\x12\x0b\n\x03key\x18\x01 \x01(\t\x12\r\n\x05value\x18\x02 \x01(\t:\x02\x38\x01\"=\n\x13MemoryWriteResponse\x12\x0f\n\x07success\x18\x01 \x01(\x08\x12\x15\n\rerror_message\x18\x02 \x01(\t\"\xb3\x01\n\x15MemoryRetrieveRequest\x12\x12\n\nsession_id\x18\x01 \x01(\t\x12\r\n\x05query\x18\x02 \x01(\t\x12\t\n\x01k\x18\x03 \x01(\x05\x12<\n\x07\x66ilters\x18\x04 \x03(\x0b\x32+.loom.v1.MemoryRetrieveRequest.FiltersEntry\x1a.\n\x0c\x46iltersEntry\x12\x0b\n\x03key\x18\x01 \x01(\t\x12\r\n\x05value\x18\x02 \x01(\t:\x02\x38\x01\"Q\n\x16MemoryRetrieveResponse\x12\x0f\n\x07results\x18\x01 \x03(\t\x12\x0f\n\x07success\x18\x02 \x01(\x08\x12\x15\n\rerror_message\x18\x03 \x01(\t\"@\n\x16MemorySummarizeRequest\x12\x12\n\nsession_id\x18\x01 \x01(\t\x12\x12\n\nmax_events\x18\x02 \x01(\x05\"g\n\x17MemorySummarizeResponse\x12\x0f\n\x07summary\x18\x01 \x01(\t\x12\x13\n\x0b\x65vent_count\x18\x02 \x01(\x05\x12\x0f\n\x07success\x18\x03 \x01(\x08\x12\x15\n\rerror_message\x18\x04 \x01(\t\"=\n\x14\x43heckExecutedRequest\x12\x12\n\nsession_id\x18\x01 \x01(\t\x12\x11\n\tplan_hash\x18\x02 \x01(\t\"Y\n\x15\x43heckExecutedResponse\x12\x13\n\x0bis_executed\x18\x01 \x01(\x08\x12+\n\texecution\x18\x02 \x01(\x0b\x32\x18.loom.v1.ExecutionRecord*\xf3\x01\n\x0cMemoryOpType\x12\x17\n\x13MEMORY_OP_SAVE_PLAN\x10\x00\x12\x1e\n\x1aMEMORY_OP_GET_RECENT_PLANS\x10\x01\x12\x1d\n\x19MEMORY_OP_CHECK_DUPLICATE\x10\x02\x12\x1b\n\x17MEMORY_OP_MARK_EXECUTED\x10\x03\x12!\n\x1dMEMORY_OP_GET_EXECUTION_STATS\x10\x04\x12\x1a\n\x16MEMORY_OP_APPEND_EVENT\x10\x05\x12\x16\n\x12MEMORY_OP_RETRIEVE\x10\x06\x12\x17\n\x13MEMORY_OP_SUMMARIZE\x10\x07\x32\xdd\x05\n\rMemoryService\x12?\n\x08SavePlan\x12\x18.loom.v1.SavePlanRequest\x1a\x19.loom.v1.SavePlanResponse\x12Q\n\x0eGetRecentPlans\x12\x1e.loom.v1.GetRecentPlansRequest\x1a\x1f.loom.v1.GetRecentPlansResponse\x12Q\n\x0e\x43heckDuplicate\x12\x1e.loom.v1.CheckDuplicateRequest\x1a\x1f.loom.v1.CheckDuplicateResponse\x12K\n\x0cMarkExecuted\x12\x1c.loom.v1.MarkExecutedRequest\x1a\x1d.loom.v1.MarkExecutedResponse\x12N\n\rCheckExecuted\x12\x1d.loom.v1.CheckExecutedRequest\x1a\x1e.loom.v1.CheckExecutedResponse\x12Z\n\x11GetExecutionStats\x12!.loom.v1.GetExecutionStatsRequest\x1a\".loom.v1.GetExecutionStatsResponse\x12H\n\x0b\x41ppendEvent\x12\x1b.loom.v1.MemoryWriteRequest\x1a\x1c.loom.v1.MemoryWriteResponse\x12K\n\x08Retrieve\x12\x1e.loom.v1.MemoryRetrieveRequest\x1a\x1f.loom.v1.MemoryRetrieveResponse\x12U\n\x10SummarizeEpisode\x12\x1f.loom.v1.MemorySummarizeRequest\x1a .loom.v1.MemorySummarizeResponseb\x06proto3')
+DESCRIPTOR = _descriptor_pool.Default().AddSerializedFile(b'\n\x0cmemory.proto\x12\x07loom.v1\x1a\x0b\x65vent.proto\"\xf2\x01\n\nPlanRecord\x12\x14\n\x0ctimestamp_ms\x18\x01 \x01(\x03\x12\x0e\n\x06symbol\x18\x02 \x01(\t\x12\x0e\n\x06\x61\x63tion\x18\x03 \x01(\t\x12\x12\n\nconfidence\x18\x04 \x01(\x02\x12\x11\n\treasoning\x18\x05 \x01(\t\x12\x11\n\tplan_hash\x18\x06 \x01(\t\x12\x0e\n\x06method\x18\x07 \x01(\t\x12\x33\n\x08metadata\x18\x08 \x03(\x0b\x32!.loom.v1.PlanRecord.MetadataEntry\x1a/\n\rMetadataEntry\x12\x0b\n\x03key\x18\x01 \x01(\t\x12\r\n\x05value\x18\x02 \x01(\t:\x02\x38\x01\"\xf6\x01\n\x0f\x45xecutionRecord\x12\x14\n\x0ctimestamp_ms\x18\x01 \x01(\x03\x12\x11\n\tplan_hash\x18\x02 \x01(\t\x12\x0e\n\x06symbol\x18\x03 \x01(\t\x12\x0e\n\x06\x61\x63tion\x18\x04 \x01(\t\x12\x12\n\nconfidence\x18\x05 \x01(\x02\x12\x0e\n\x06status\x18\x06 \x01(\t\x12\x10\n\x08\x65xecuted\x18\x07 \x01(\x08\x12\x10\n\x08order_id\x18\x08 \x01(\t\x12\x17\n\x0forder_size_usdt\x18\t \x01(\x02\x12\x15\n\rerror_message\x18\n \x01(\t\x12\x10\n\x08pnl_usdt\x18\x0b \x01(\x01\x12\x10\n\x08strategy\x18\x0c \x01(\t\"H\n\x0fSavePlanRequest\x12\x12\n\nsession_id\x18\x01 \x01(\t\x12!\n\x04plan\x18\x02 \x01(\x0b\x32\x13.loom.v1.PlanRecord\"M\n\x10SavePlanResponse\x12\x0f\n\x07success\x18\x01 \x01(\x08\x12\x11\n\tplan_hash\x18\x02 \x01(\t\x12\x15\n\rerror_message\x18\x03 \x01(\t\"J\n\x15GetRecentPlansRequest\x12\x12\n\nsession_id\x18\x01 \x01(\t\x12\x0e\n\x06symbol\x18\x02 \x01(\t\x12\r\n\x05limit\x18\x03 \x01(\x05\"d\n\x16GetRecentPlansResponse\x12\"\n\x05plans\x18\x01 \x03(\x0b\x32\x13.loom.v1.PlanRecord\x12\x0f\n\x07success\x18\x02 \x01(\x08\x12\x15\n\rerror_message\x18\x03 \x01(\t\"g\n\x15\x43heckDuplicateRequest\x12\x12\n\nsession_id\x18\x01 \x01(\t\x12!\n\x04plan\x18\x02 \x01(\x0b\x32\x13.loom.v1.PlanRecord\x12\x17\n\x0ftime_window_sec\x18\x03 \x01(\x05\"|\n\x16\x43heckDuplicateResponse\x12\x14\n\x0cis_duplicate\x18\x01 \x01(\x08\x12+\n\x0e\x64uplicate_plan\x18\x02 \x01(\x0b\x32\x13.loom.v1.PlanRecord\x12\x1f\n\x17time_since_duplicate_ms\x18\x03 \x01(\x03\"i\n\x13MarkExecutedRequest\x12\x12\n\nsession_id\x18\x01 \x01(\t\x12\x11\n\tplan_hash\x18\x02 \x01(\t\x12+\n\texecution\x18\x03 \x01(\x0b\x32\x18.loom.v1.ExecutionRecord\">\n\x14MarkExecutedResponse\x12\x0f\n\x07success\x18\x01 \x01(\x08\x12\x15\n\rerror_message\x18\x02 \x01(\t\">\n\x18GetExecutionStatsRequest\x12\x12\n\nsession_id\x18\x01 \x01(\t\x12\x0e\n\x06symbol\x18\x02 \x01(\t\"\xd3\x01\n\x19GetExecutionStatsResponse\x12\x18\n\x10total_executions\x18\x01 \x01(\x05\x12\x1d\n\x15successful_executions\x18\x02 \x01(\x05\x12\x19\n\x11\x66\x61iled_executions\x18\x03 \x01(\x05\x12\x10\n\x08win_rate\x18\x04 \x01(\x02\x12\x1b\n\x13\x64uplicate_prevented\x18\x05 \x01(\x05\x12\x33\n\x11recent_executions\x18\x06 \x03(\x0b\x32\x18.loom.v1.ExecutionRecord\"\xb5\x01\n\x12MemoryWriteRequest\x12\x12\n\nsession_id\x18\x01 \x01(\t\x12\x1d\n\x05\x65vent\x18\x02 \x01(\x0b\x32\x0e.loom.v1.Event\x12;\n\x08metadata\x18\x03 \x03(\x0b\x32).loom.v1.MemoryWriteRequest.MetadataEntry\x1a/\n\rMetadataEntry\x12\x0b\n\x03key\x18\x01 \x01(\t\x12\r\n\x05value\x18\x02 \x01(\t:\x02\x38\x01\"=\n\x13MemoryWriteResponse\x12\x0f\n\x07success\x18\x01 \x01(\x08\x12\x15\n\rerror_message\x18\x02 \x01(\t\"\xb3\x01\n\x15MemoryRetrieveRequest\x12\x12\n\nsession_id\x18\x01 \x01(\t\x12\r\n\x05query\x18\x02 \x01(\t\x12\t\n\x01k\x18\x03 \x01(\x05\x12<\n\x07\x66ilters\x18\x04 \x03(\x0b\x32+.loom.v1.MemoryRetrieveRequest.FiltersEntry\x1a.\n\x0c\x46iltersEntry\x12\x0b\n\x03key\x18\x01 \x01(\t\x12\r\n\x05value\x18\x02 \x01(\t:\x02\x38\x01\"Q\n\x16MemoryRetrieveResponse\x12\x0f\n\x07results\x18\x01 \x03(\t\x12\x0f\n\x07success\x18\x02 \x01(\x08\x12\x15\n\rerror_message\x18\x03 \x01(\t\"@\n\x16MemorySummarizeRequest\x12\x12\n\nsession_id\x18\x01 \x01(\t\x12\x12\n\nmax_events\x18\x02 \x01(\x05\"g\n\x17MemorySummarizeResponse\x12\x0f\n\x07summary\x18\x01 \x01(\t\x12\x13\n\x0b\x65vent_count\x18\x02 \x01(\x05\x12\x0f\n\x07success\x18\x03 \x01(\x08\x12\x15\n\rerror_message\x18\x04 \x01(\t\"=\n\x14\x43heckExecutedRequest\x12\x12\n\nsession_id\x18\x01 \x01(\t\x12\x11\n\tplan_hash\x18\x02 \x01(\t\"Y\n\x15\x43heckExecutedResponse\x12\x13\n\x0bis_executed\x18\x01 \x01(\x08\x12+\n\texecution\x18\x02 \x01(\x0b\x32\x18.loom.v1.ExecutionRecord\"\xc0\x01\n\x0cTradingQuery\x12\x12\n\nsession_id\x18\x01 \x01(\t\x12\x0f\n\x07symbols\x18\x02 \x03(\t\x12\x0f\n\x07\x61\x63tions\x18\x03 \x03(\t\x12\x10\n\x08statuses\x18\x04 \x03(\t\x12\x12\n\nstrategies\x18\x05 \x03(\t\x12\x10\n\x08start_ms\x18\x06 \x01(\x03\x12\x0e\n\x06\x65nd_ms\x18\x07 \x01(\x03\x12\r\n\x05limit\x18\x08 \x01(\x05\x12\x14\n\x0coldest_first\x18\t \x01(\x08\x12\r\n\x05query\x18\n \x01(\t\"`\n\x12QueryPlansResponse\x12\"\n\x05plans\x18\x01 \x03(\x0b\x32\x13.loom.v1.PlanRecord\x12\x0f\n\x07success\x18\x02 \x01(\x08\x12\x15\n\rerror_message\x18\x03 \x01(\t\"o\n\x17QueryExecutionsResponse\x12,\n\nexecutions\x18\x01 \x03(\x0b\x32\x18.loom.v1.ExecutionRecord\x12\x0f\n\x07success\x18\x02 \x01(\x08\x12\x15\n\rerror_message\x18\x03 \x01(\t\"T\n\x1a\x41ggregateExecutionsRequest\x12$\n\x05query\x18\x01 \x01(\x0b\x32\x15.loom.v1.TradingQuery\x12\x10\n\x08group_by\x18\x02 \x01(\t\"\xb7\x01\n\x0e\x45xecutionGroup\x12\x0b\n\x03key\x18\x01 \x01(\t\x12\x12\n\nexecutions\x18\x02 \x01(\x05\x12\x12\n\nsuccessful\x18\x03 \x01(\x05\x12\x0e\n\x06\x66\x61iled\x18\x04 \x01(\x05\x12\x10\n\x08pnl_usdt\x18\x05 \x01(\x01\x12\x13\n\x0bvolume_usdt\x18\x06 \x01(\x01\x12\x16\n\x0e\x61vg_confidence\x18\x07 \x01(\x02\x12\x10\n\x08\x66irst_ms\x18\x08 \x01(\x03\x12\x0f\n\x07last_ms\x18\t \x01(\x03\"\x96\x01\n\x1b\x41ggregateExecutionsResponse\x12\'\n\x06groups\x18\x01 \x03(\x0b\x32\x17.loom.v1.ExecutionGroup\x12&\n\x05total\x18\x02 \x01(\x0b\x32\x17.loom.v1.ExecutionGroup\x12\x0f\n\x07success\x18\x03 \x01(\x08\x12\x15\n\rerror_message\x18\x04 \x01(\t*\xf3\x01\n\x0cMemoryOpType\x12\x17\n\x13MEMORY_OP_SAVE_PLAN\x10\x00\x12\x1e\n\x1aMEMORY_OP_GET_RECENT_PLANS\x10\x01\x12\x1d\n\x19MEMORY_OP_CHECK_DUPLICATE\x10\x02\x12\x1b\n\x17MEMORY_OP_MARK_EXECUTED\x10\x03\x12!\n\x1dMEMORY_OP_GET_EXECUTION_STATS\x10\x04\x12\x1a\n\x16MEMORY_OP_APPEND_EVENT\x10\x05\x12\x16\n\x12MEMORY_OP_RETRIEVE\x10\x06\x12\x17\n\x13MEMORY_OP_SUMMARIZE\x10\x07\x32\xcd\x07\n\rMemoryService\x12?\n\x08SavePlan\x12\x18.loom.v1.SavePlanRequest\x1a\x19.loom.v1.SavePlanResponse\x12Q\n\x0eGetRecentPlans\x12\x1e.loom.v1.GetRecentPlansRequest\x1a\x1f.loom.v1.GetRecentPlansResponse\x12Q\n\x0e\x43heckDuplicate\x12\x1e.loom.v1.CheckDuplicateRequest\x1a\x1f.loom.v1.CheckDuplicateResponse\x12K\n\x0cMarkExecuted\x12\x1c.loom.v1.MarkExecutedRequest\x1a\x1d.loom.v1.MarkExecutedResponse\x12N\n\rCheckExecuted\x12\x1d.loom.v1.CheckExecutedRequest\x1a\x1e.loom.v1.CheckExecutedResponse\x12Z\n\x11GetExecutionStats\x12!.loom.v1.GetExecutionStatsRequest\x1a\".loom.v1.GetExecutionStatsResponse\x12@\n\nQueryPlans\x12\x15.loom.v1.TradingQuery\x1a\x1b.loom.v1.QueryPlansResponse\x12J\n\x0fQueryExecutions\x12\x15.loom.v1.TradingQuery\x1a .loom.v1.QueryExecutionsResponse\x12`\n\x13\x41ggregateExecutions\x12#.loom.v1.AggregateExecutionsRequest\x1a$.loom.v1.AggregateExecutionsResponse\x12H\n\x0b\x41ppendEvent\x12\x1b.loom.v1.MemoryWriteRequest\x1a\x1c.loom.v1.MemoryWriteResponse\x12K\n\x08Retrieve\x12\x1e.loom.v1.MemoryRetrieveRequest\x1a\x1f.loom.v1.MemoryRetrieveResponse\x12U\n\x10SummarizeEpisode\x12\x1f.loom.v1.MemorySummarizeRequest\x1a .loom.v1.MemorySummarizeResponseb\x06proto3')
 
 _globals = globals()
 _builder.BuildMessageAndEnumDescriptors(DESCRIPTOR, _globals)
@@ -38,54 +38,66 @@ if not _descriptor._USE_C_DESCRIPTORS:
   _globals['_MEMORYWRITEREQUEST_METADATAENTRY']._serialized_options = b'8\001'
   _globals['_MEMORYRETRIEVEREQUEST_FILTERSENTRY']._loaded_options = None
   _globals['_MEMORYRETRIEVEREQUEST_FILTERSENTRY']._serialized_options = b'8\001'
-  _globals['_MEMORYOPTYPE']._serialized_start=2345
-  _globals['_MEMORYOPTYPE']._serialized_end=2588
+  _globals['_MEMORYOPTYPE']._serialized_start=3212
+  _globals['_MEMORYOPTYPE']._serialized_end=3455
   _globals['_PLANRECORD']._serialized_start=39
   _globals['_PLANRECORD']._serialized_end=281
   _globals['_PLANRECORD_METADATAENTRY']._serialized_start=234
   _globals['_PLANRECORD_METADATAENTRY']._serialized_end=281
   _globals['_EXECUTIONRECORD']._serialized_start=284
-  _globals['_EXECUTIONRECORD']._serialized_end=494
-  _globals['_SAVEPLANREQUEST']._serialized_start=496
-  _globals['_SAVEPLANREQUEST']._serialized_end=568
-  _globals['_SAVEPLANRESPONSE']._serialized_start=570
-  _globals['_SAVEPLANRESPONSE']._serialized_end=647
-  _globals['_GETRECENTPLANSREQUEST']._serialized_start=649
-  _globals['_GETRECENTPLANSREQUEST']._serialized_end=723
-  _globals['_GETRECENTPLANSRESPONSE']._serialized_start=725
-  _globals['_GETRECENTPLANSRESPONSE']._serialized_end=825
-  _globals['_CHECKDUPLICATEREQUEST']._serialized_start=827
-  _globals['_CHECKDUPLICATEREQUEST']._serialized_end=930
-  _globals['_CHECKDUPLICATERESPONSE']._serialized_start=932
-  _globals['_CHECKDUPLICATERESPONSE']._serialized_end=1056
-  _globals['_MARKEXECUTEDREQUEST']._serialized_start=1058
-  _globals['_MARKEXECUTEDREQUEST']._serialized_end=1163
-  _globals['_MARKEXECUTEDRESPONSE']._serialized_start=1165
-  _globals['_MARKEXECUTEDRESPONSE']._serialized_end=1227
-  _globals['_GETEXECUTIONSTATSREQUEST']._serialized_start=1229
-  _globals['_GETEXECUTIONSTATSREQUEST']._serialized_end=1291
-  _globals['_GETEXECUTIONSTATSRESPONSE']._serialized_start=1294
-  _globals['_GETEXECUTIONSTATSRESPONSE']._serialized_end=1505
-  _globals['_MEMORYWRITEREQUEST']._serialized_start=1508
-  _globals['_MEMORYWRITEREQUEST']._serialized_end=1689
+  _globals['_EXECUTIONRECORD']._serialized_end=530
+  _globals['_SAVEPLANREQUEST']._serialized_start=532
+  _globals['_SAVEPLANREQUEST']._serialized_end=604
+  _globals['_SAVEPLANRESPONSE']._serialized_start=606
+  _globals['_SAVEPLANRESPONSE']._serialized_end=683
+  _globals['_GETRECENTPLANSREQUEST']._serialized_start=685
+  _globals['_GETRECENTPLANSREQUEST']._serialized_end=759
+  _globals['_GETRECENTPLANSRESPONSE']._serialized_start=761
+  _globals['_GETRECENTPLANSRESPONSE']._serialized_end=861
+  _globals['_CHECKDUPLICATEREQUEST']._serialized_start=863
+  _globals['_CHECKDUPLICATEREQUEST']._serialized_end=966
+  _globals['_CHECKDUPLICATERESPONSE']._serialized_start=968
+  _globals['_CHECKDUPLICATERESPONSE']._serialized_end=1092
+  _globals['_MARKEXECUTEDREQUEST']._serialized_start=1094
+  _globals['_MARKEXECUTEDREQUEST']._serialized_end=1199
+  _globals['_MARKEXECUTEDRESPONSE']._serialized_start=1201
+  _globals['_MARKEXECUTEDRESPONSE']._serialized_end=1263
+  _globals['_GETEXECUTIONSTATSREQUEST']._serialized_start=1265
+  _globals['_GETEXECUTIONSTATSREQUEST']._serialized_end=1327
+  _globals['_GETEXECUTIONSTATSRESPONSE']._serialized_start=1330
+  _globals['_GETEXECUTIONSTATSRESPONSE']._serialized_end=1541
+  _globals['_MEMORYWRITEREQUEST']._serialized_start=1544
+  _globals['_MEMORYWRITEREQUEST']._serialized_end=1725
   _globals['_MEMORYWRITEREQUEST_METADATAENTRY']._serialized_start=234
   _globals['_MEMORYWRITEREQUEST_METADATAENTRY']._serialized_end=281
-  _globals['_MEMORYWRITERESPONSE']._serialized_start=1691
-  _globals['_MEMORYWRITERESPONSE']._serialized_end=1752
-  _globals['_MEMORYRETRIEVEREQUEST']._serialized_start=1755
-  _globals['_MEMORYRETRIEVEREQUEST']._serialized_end=1934
-  _globals['_MEMORYRETRIEVEREQUEST_FILTERSENTRY']._serialized_start=1888
-  _globals['_MEMORYRETRIEVEREQUEST_FILTERSENTRY']._serialized_end=1934
-  _globals['_MEMORYRETRIEVERESPONSE']._serialized_start=1936
-  _globals['_MEMORYRETRIEVERESPONSE']._serialized_end=2017
-  _globals['_MEMORYSUMMARIZEREQUEST']._serialized_start=2019
-  _globals['_MEMORYSUMMARIZEREQUEST']._serialized_end=2083
-  _globals['_MEMORYSUMMARIZERESPONSE']._serialized_start=2085
-  _globals['_MEMORYSUMMARIZERESPONSE']._serialized_end=2188
-  _globals['_CHECKEXECUTEDREQUEST']._serialized_start=2190
-  _globals['_CHECKEXECUTEDREQUEST']._serialized_end=2251
-  _globals['_CHECKEXECUTEDRESPONSE']._serialized_start=2253
-  _globals['_CHECKEXECUTEDRESPONSE']._serialized_end=2342
-  _globals['_MEMORYSERVICE']._serialized_start=2591
-  _globals['_MEMORYSERVICE']._serialized_end=3324
+  _globals['_MEMORYWRITERESPONSE']._serialized_start=1727
+  _globals['_MEMORYWRITERESPONSE']._serialized_end=1788
+  _globals['_MEMORYRETRIEVEREQUEST']._serialized_start=1791
+  _globals['_MEMORYRETRIEVEREQUEST']._serialized_end=1970
+  _globals['_MEMORYRETRIEVEREQUEST_FILTERSENTRY']._serialized_start=1924
+  _globals['_MEMORYRETRIEVEREQUEST_FILTERSENTRY']._serialized_end=1970
+  _globals['_MEMORYRETRIEVERESPONSE']._serialized_start=1972
+  _globals['_MEMORYRETRIEVERESPONSE']._serialized_end=2053
+  _globals['_MEMORYSUMMARIZEREQUEST']._serialized_start=2055
+  _globals['_MEMORYSUMMARIZEREQUEST']._serialized_end=2119
+  _globals['_MEMORYSUMMARIZERESPONSE']._serialized_start=2121
+  _globals['_MEMORYSUMMARIZERESPONSE']._serialized_end=2224
+  _globals['_CHECKEXECUTEDREQUEST']._serialized_start=2226
+  _globals['_CHECKEXECUTEDREQUEST']._serialized_end=2287
+  _globals['_CHECKEXECUTEDRESPONSE']._serialized_start=2289
+  _globals['_CHECKEXECUTEDRESPONSE']._serialized_end=2378
+  _globals['_TRADINGQUERY']._serialized_start=2381
+  _globals['_TRADINGQUERY']._serialized_end=2573
+  _globals['_QUERYPLANSRESPONSE']._serialized_start=2575
+  _globals['_QUERYPLANSRESPONSE']._serialized_end=2671
+  _globals['_QUERYEXECUTIONSRESPONSE']._serialized_start=2673
+  _globals['_QUERYEXECUTIONSRESPONSE']._serialized_end=2784
+  _globals['_AGGREGATEEXECUTIONSREQUEST']._serialized_start=2786
+  _globals['_AGGREGATEEXECUTIONSREQUEST']._serialized_end=2870
+  _globals['_EXECUTIONGROUP']._serialized_start=2873
+  _globals['_EXECUTIONGROUP']._serialized_end=3056
+  _globals['_AGGREGATEEXECUTIONSRESPONSE']._serialized_start=3059
+  _globals['_AGGREGATEEXECUTIONSRESPONSE']._serialized_end=3209
+  _globals['_MEMORYSERVICE']._serialized_start=3458
+  _globals['_MEMORYSERVICE']._serialized_end=4431
 # @@protoc_insertion_point(module_scope)
@@ -65,6 +65,21 @@ class MemoryServiceStub(object):
                 request_serializer=memory__pb2.GetExecutionStatsRequest.SerializeToString,
                 response_deserializer=memory__pb2.GetExecutionStatsResponse.FromString,
                 _registered_method=True)
+        self.QueryPlans = channel.unary_unary(
+                '/loom.v1.MemoryService/QueryPlans',
+                request_serializer=memory__pb2.TradingQuery.SerializeToString,
+                response_deserializer=memory__pb2.QueryPlansResponse.FromString,
+                _registered_method=True)
+        self.QueryExecutions = channel.unary_unary(
+                '/loom.v1.MemoryService/QueryExecutions',
+                request_serializer=memory__pb2.TradingQuery.SerializeToString,
+                response_deserializer=memory__pb2.QueryExecutionsResponse.FromString,
+                _registered_method=True)
+        self.AggregateExecutions = channel.unary_unary(
+                '/loom.v1.MemoryService/AggregateExecutions',
+                request_serializer=memory__pb2.AggregateExecutionsRequest.SerializeToString,
+                response_deserializer=memory__pb2.AggregateExecutionsResponse.FromString,
+                _registered_method=True)
         self.AppendEvent = channel.unary_unary(
                 '/loom.v1.MemoryService/AppendEvent',
                 request_serializer=memory__pb2.MemoryWriteRequest.SerializeToString,
@@ -124,6 +139,25 @@ class MemoryServiceServicer(object):
         context.set_details('Method not implemented!')
         raise NotImplementedError('Method not implemented!')
 
+    def QueryPlans(self, request, context):
+        """Queries over history
+        """
+        context.set_code(grpc.StatusCode.UNIMPLEMENTED)
+        context.set_details('Method not implemented!')
+        raise NotImplementedError('Method not implemented!')
+
+    def QueryExecutions(self, request, context):
+        """Missing associated documentation comment in .proto file."""
+        context.set_code(grpc.StatusCode.UNIMPLEMENTED)
+        context.set_details('Method not implemented!')
+        raise NotImplementedError('Method not implemented!')
+
+    def AggregateExecutions(self, request, context):
+        """Missing associated documentation comment in .proto file."""
+        context.set_code(grpc.StatusCode.UNIMPLEMENTED)
+        context.set_details('Method not implemented!')
+        raise NotImplementedError('Method not implemented!')
+
     def AppendEvent(self, request, context):
         """Generic memory operations
         """
@@ -176,6 +210,21 @@ def add_MemoryServiceServicer_to_server(servicer, server):
                     request_deserializer=memory__pb2.GetExecutionStatsRequest.FromString,
                     response_serializer=memory__pb2.GetExecutionStatsResponse.SerializeToString,
             ),
+            'QueryPlans': grpc.unary_unary_rpc_method_handler(
+                    servicer.QueryPlans,
+                    request_deserializer=memory__pb2.TradingQuery.FromString,
+                    response_serializer=memory__pb2.QueryPlansResponse.SerializeToString,
+            ),
+            'QueryExecutions': grpc.unary_unary_rpc_method_handler(
+                    servicer.QueryExecutions,
+                    request_deserializer=memory__pb2.TradingQuery.FromString,
+                    response_serializer=memory__pb2.QueryExecutionsResponse.SerializeToString,
+            ),
+            'AggregateExecutions': grpc.unary_unary_rpc_method_handler(
+                    servicer.AggregateExecutions,
+                    request_deserializer=memory__pb2.AggregateExecutionsRequest.FromString,
+                    response_serializer=memory__pb2.AggregateExecutionsResponse.SerializeToString,
+            ),
             'AppendEvent': grpc.unary_unary_rpc_method_handler(
                     servicer.AppendEvent,
                     request_deserializer=memory__pb2.MemoryWriteRequest.FromString,
@@ -365,6 +414,87 @@ class MemoryService(object):
             metadata,
             _registered_method=True)
 
+    @staticmethod
+    def QueryPlans(request,
+            target,
+            options=(),
+            channel_credentials=None,
+            call_credentials=None,
+            insecure=False,
+            compression=None,
+            wait_for_ready=None,
+            timeout=None,
+            metadata=None):
+        return grpc.experimental.unary_unary(
+            request,
+            target,
+            '/loom.v1.MemoryService/QueryPlans',
+            memory__pb2.TradingQuery.SerializeToString,
+            memory__pb2.QueryPlansResponse.FromString,
+            options,
+            channel_credentials,
+            insecure,
+            call_credentials,
+            compression,
+            wait_for_ready,
+            timeout,
+            metadata,
+            _registered_method=True)
+
+    @staticmethod
+    def QueryExecutions(request,
+            target,
+            options=(),
+            channel_credentials=None,
+            call_credentials=None,
+            insecure=False,
+            compression=None,
+            wait_for_ready=None,
+            timeout=None,
+            metadata=None):
+        return grpc.experimental.unary_unary(
+            request,
+            target,
+            '/loom.v1.MemoryService/QueryExecutions',
+            memory__pb2.TradingQuery.SerializeToString,
+            memory__pb2.QueryExecutionsResponse.FromString,
+            options,
+            channel_credentials,
+            insecure,
+            call_credentials,
+            compression,
+            wait_for_ready,
+            timeout,
+            metadata,
+            _registered_method=True)
+
+    @staticmethod
+    def AggregateExecutions(request,
+            target,
+            options=(),
+            channel_credentials=None,
+            call_credentials=None,
+            insecure=False,
+            compression=None,
+            wait_for_ready=None,
+            timeout=None,
+            metadata=None):
+        return grpc.experimental.unary_unary(
+            request,
+            target,
+            '/loom.v1.MemoryService/AggregateExecutions',
+            memory__pb2.AggregateExecutionsRequest.SerializeToString,
+            memory__pb2.AggregateExecutionsResponse.FromString,
+            options,
+            channel_credentials,
+            insecure,
+            call_credentials,
+            compression,
+            wait_for_ready,
+            timeout,
+            metadata,
+            _registered_method=True)
+
     @staticmethod
     def AppendEvent(request,
             target,
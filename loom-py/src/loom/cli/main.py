@@ -52,6 +52,33 @@ def cmd_dev(args):
             proc.kill()
 
 
+def cmd_loadgen(args):
+    """Run the Rust load generator (loom-loadgen) against an EventBus or a running bridge."""
+    cargo = shutil.which("cargo")
+    if not cargo:
+        print("[loom] 'cargo' not found. loadgen runs the loom-loadgen binary from this repo.")
+        print("      cargo run -p loom-bridge --release --bin loom-loadgen")
+        sys.exit(2)
+    env = os.environ.copy()
+    env["LOOM_LOADGEN_TARGET"] = args.target
+    if args.address:
+        env["LOOM_LOADGEN_BRIDGE_ADDR"] = args.address
+    env["LOOM_LOADGEN_TOPICS"] = str(args.topics)
+    env["LOOM_LOADGEN_RATE"] = str(args.rate)
+    env["LOOM_LOADGEN_DURATION_SECS"] = str(args.duration)
+    env["LOOM_LOADGEN_PUBLISHERS"] = str(args.publishers)
+    env["LOOM_LOADGEN_PAYLOAD_BYTES"] = str(args.payload_bytes)
+    env["LOOM_LOADGEN_PATTERN"] = args.pattern.replace("-", "_")
+    env["LOOM_LOADGEN_THREADS"] = str(args.threads)
+    if args.burst_size and args.burst_every_ms:
+        env["LOOM_LOADGEN_BURST_SIZE"] = str(args.burst_size)
+        env["LOOM_LOADGEN_BURST_EVERY_MS"] = str(args.burst_every_ms)
+    cmd = [cargo, "run", "-p", "loom-bridge", "--bin", "loom-loadgen"]
+    if not args.debug:
+        cmd.insert(2, "--release")
+    sys.exit(subprocess.call(cmd, env=env))
+
+
 TEMPLATE_AGENT = """from loom import Agent, capability
 import asyncio
 
@@ -418,6 +445,37 @@ def main():
     sdown = sub.add_parser("down", help="Shutdown all Loom processes (runtime + agents)")
     sdown.set_defaults(func=cmd_down)
 
+    sl = sub.add_parser(
+        "loadgen", help="Generate synthetic event load and report throughput/latency"
+    )
+    sl.add_argument(
+        "--target",
+        choices=["bus", "bridge"],
+        default="bus",
+        help="In-process EventBus or a running bridge (default: bus)",
+    )
+    sl.add_argument(
+        "--address", "-a", default=None, help="Bridge address (default: LOOM_BRIDGE_ADDR)"
+    )
+    sl.add_argument("--topics", type=int, default=4, help="Number of topics (default: 4)")
+    sl.add_argument(
+        "--rate", type=int, default=1000, help="Events/s across publishers, 0 = unthrottled"
+    )
+    sl.add_argument("--duration", type=int, default=10, help="Seconds to publish (default: 10)")
+    sl.add_argument("--publishers", type=int, default=4, help="Concurrent publishers")
+    sl.add_argument("--payload-bytes", type=int, default=256, help="Payload size in bytes")
+    sl.add_argument("--burst-size", type=int, default=0, help="Extra events per burst")
+    sl.add_argument("--burst-every-ms", type=int, default=0, help="Burst interval in ms")
+    sl.add_argument(
+        "--pattern",
+        choices=["none", "threads", "request-reply"],
+        default="none",
+        help="Correlation pattern (default: none)",
+    )
+    sl.add_argument("--threads", type=int, default=16, help="Thread ids for threads/request-reply")
+    sl.add_argument("--debug", action="store_true", help="Use a debug build instead of release")
+    sl.set_defaults(func=cmd_loadgen)
+
     schat = sub.add_parser("chat", help="Start interactive chat with a cognitive agent")
     schat.add_argument(
         "--address",